@@ -458,7 +458,7 @@ fn resolve_account_npub(data_dir: &std::path::Path) -> Result<String, String> {
         .filter_map(|e| e.ok())
         .filter(|e| e.path().is_dir())
         .filter_map(|e| e.file_name().into_string().ok())
-        .filter(|n| n.starts_with("npub1"))
+        .filter(|n| vector_core::ids::is_npub(n))
         .collect();
     match npubs.len() {
         1 => Ok(npubs.into_iter().next().unwrap()),
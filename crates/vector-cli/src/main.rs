@@ -58,6 +58,24 @@ async fn main() {
     }
 
     match args[1].as_str() {
+        "login-bunker" => {
+            if args.len() < 3 {
+                eprintln!("Usage: vector-cli login-bunker <bunker://...>");
+                std::process::exit(1);
+            }
+            println!("Waiting for your signer to approve...");
+            match core.login_with_bunker(&args[2], None).await {
+                Ok(result) => {
+                    println!("Logged in as {} (remote signer)", result.npub);
+                    println!("Ready.");
+                }
+                Err(e) => {
+                    eprintln!("Login failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
         "--login" | "login" => {
             if args.len() < 3 {
                 eprintln!("Usage: vector-cli login <nsec|seed>");
@@ -180,6 +198,22 @@ async fn auto_login(core: &VectorCore) {
     vector_core::db::set_current_account(npub.clone()).ok();
     vector_core::db::init_database(npub).ok();
 
+    // Remote-signer account: reconnect through the stored pairing
+    if vector_core::db::get_signer_type().as_deref() == Ok("bunker") {
+        let url = match vector_core::db::get_bunker_url().await {
+            Ok(Some(url)) => url,
+            _ => {
+                eprintln!("No stored bunker for {}. Run: vector-cli login-bunker <bunker://...>", npub);
+                std::process::exit(1);
+            }
+        };
+        if let Err(e) = core.login_with_bunker(&url, None).await {
+            eprintln!("Auto-login failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let pkey = match vector_core::db::get_pkey() {
         Ok(Some(k)) => k,
         _ => {
@@ -204,6 +238,7 @@ fn print_usage() {
     println!();
     println!("Usage:");
     println!("  vector-cli login <nsec|seed>       Login / create account");
+    println!("  vector-cli login-bunker <uri>       Login via a NIP-46 remote signer");
     println!("  vector-cli send <npub> <message>    Send a text DM");
     println!("  vector-cli send-file <npub> <path>  Send a file DM");
     println!("  vector-cli accounts                 List stored accounts");
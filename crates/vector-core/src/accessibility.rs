@@ -0,0 +1,94 @@
+//! Accessibility preferences: message font scale, reduced motion and high contrast.
+//!
+//! Stored as plain keys in the account settings KV, all of them in `settings_sync::SYNCED_KEYS`, so
+//! they follow the user to a new device. The boot payload carries them so the UI can apply them
+//! before the first paint instead of flashing the defaults.
+
+const FONT_SCALE_KEY: &str = "a11y_font_scale";
+const REDUCED_MOTION_KEY: &str = "a11y_reduced_motion";
+const HIGH_CONTRAST_KEY: &str = "a11y_high_contrast";
+
+/// Smallest and largest message font scale, in percent.
+pub const MIN_FONT_SCALE: u16 = 80;
+pub const MAX_FONT_SCALE: u16 = 200;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccessibilitySettings {
+    /// Message text size, in percent of the default.
+    pub font_scale: u16,
+    pub reduced_motion: bool,
+    pub high_contrast: bool,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self { font_scale: 100, reduced_motion: false, high_contrast: false }
+    }
+}
+
+fn flag(key: &str) -> bool {
+    matches!(crate::db::settings::get_sql_setting(key.to_string()), Ok(Some(v)) if v == "true")
+}
+
+/// The stored preferences (defaults for anything unset or unreadable).
+pub fn load() -> AccessibilitySettings {
+    let font_scale = crate::db::settings::get_sql_setting(FONT_SCALE_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<u16>().ok())
+        .map(|s| s.clamp(MIN_FONT_SCALE, MAX_FONT_SCALE))
+        .unwrap_or(100);
+    AccessibilitySettings {
+        font_scale,
+        reduced_motion: flag(REDUCED_MOTION_KEY),
+        high_contrast: flag(HIGH_CONTRAST_KEY),
+    }
+}
+
+/// Store the preferences; an out-of-range font scale is an error. Only changed keys are written,
+/// so an untouched toggle doesn't win a sync merge against another device's edit.
+pub fn save(settings: &AccessibilitySettings) -> Result<(), String> {
+    if !(MIN_FONT_SCALE..=MAX_FONT_SCALE).contains(&settings.font_scale) {
+        return Err(format!("Font scale must be between {}% and {}%", MIN_FONT_SCALE, MAX_FONT_SCALE));
+    }
+    let current = load();
+    if current.font_scale != settings.font_scale {
+        crate::db::settings::set_sql_setting(FONT_SCALE_KEY.to_string(), settings.font_scale.to_string())?;
+    }
+    if current.reduced_motion != settings.reduced_motion {
+        crate::db::settings::set_sql_setting(REDUCED_MOTION_KEY.to_string(), settings.reduced_motion.to_string())?;
+    }
+    if current.high_contrast != settings.high_contrast {
+        crate::db::settings::set_sql_setting(HIGH_CONTRAST_KEY.to_string(), settings.high_contrast.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefs_roundtrip_and_are_synced() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        crate::db::clear_id_caches();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+
+        assert_eq!(load(), AccessibilitySettings::default());
+        let prefs = AccessibilitySettings { font_scale: 150, reduced_motion: true, high_contrast: false };
+        save(&prefs).unwrap();
+        assert_eq!(load(), prefs);
+        assert!(save(&AccessibilitySettings { font_scale: 20, ..prefs }).is_err());
+
+        for key in [FONT_SCALE_KEY, REDUCED_MOTION_KEY, HIGH_CONTRAST_KEY] {
+            assert!(crate::settings_sync::is_synced_key(key));
+        }
+    }
+}
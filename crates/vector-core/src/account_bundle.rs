@@ -0,0 +1,486 @@
+//! Account bundles — one password-encrypted file holding everything needed to move an account to
+//! another machine.
+//!
+//! A bundle carries a consistent snapshot of the account's `vector.db`: messages, keys, settings,
+//! Community group state and every other per-account table. It also carries a manifest of
+//! downloaded files. The files themselves stay behind (they can be large, and most can be
+//! re-downloaded). On import, each one the user has copied into the new download folder is
+//! relinked; the rest are marked not-downloaded.
+//!
+//! File layout: `MAGIC | format (u8) | salt (16) | nonce (12) | ciphertext`. The key is Argon2id
+//! over the bundle password with the per-bundle salt. The plaintext is
+//! `manifest length (u32 LE) | manifest JSON | database`, and the manifest records the database's
+//! size and SHA-256. Import checks the AEAD tag, that checksum, the bundle format, that the
+//! database's schema isn't newer than this build understands and that the database belongs to the
+//! manifest's account.
+//!
+//! [`create_backup`] is the same bundle written to a timestamped file in the download folder's
+//! `Backups` directory, for when the user just wants "a backup" rather than picking a path.
+
+use std::path::{Path, PathBuf};
+
+use argon2::Argon2;
+use chacha20poly1305::{aead::Aead, ChaCha20Poly1305, KeyInit};
+use rand::RngCore;
+use zeroize::Zeroizing;
+use crate::clock::now_secs;
+
+const MAGIC: &[u8; 10] = b"VECTORBNDL";
+/// Bundle format written by this build; older formats are still read.
+const FORMAT_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + SALT_LEN;
+
+/// A downloaded file at export time.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct BundleDownload {
+    pub event_id: String,
+    pub hash: String,
+    /// Relative to the download folder when it lived there (archived files: their original
+    /// location), otherwise absolute.
+    pub path: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct BundleManifest {
+    pub format: u8,
+    /// The vector-core version that wrote the bundle.
+    pub app_version: String,
+    /// Highest database migration applied in the exported account.
+    pub schema_version: u32,
+    pub npub: String,
+    pub created_at: u64,
+    pub db_size: u64,
+    pub db_sha256: String,
+    pub downloads: Vec<BundleDownload>,
+}
+
+/// What an export wrote or an import restored.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct BundleSummary {
+    pub npub: String,
+    pub app_version: String,
+    pub created_at: u64,
+    /// Bundle size (export) or restored database size (import), in bytes.
+    pub bytes: u64,
+    /// Downloaded files listed in the manifest.
+    pub downloads: usize,
+    /// Of those, files found in this machine's download folder and relinked (import only).
+    pub downloads_relinked: usize,
+    /// Where the bundle was written (export only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// Argon2id key for a bundle. Same cost as the at-rest key (150 MB, 10 passes), but salted per
+/// bundle.
+async fn derive_key(password: &str, salt: [u8; SALT_LEN]) -> Result<Zeroizing<[u8; 32]>, String> {
+    let password = Zeroizing::new(password.to_string());
+    tokio::task::spawn_blocking(move || {
+        let params = argon2::Params::new(150_000, 10, 1, Some(32)).map_err(|e| e.to_string())?;
+        let argon2 = Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+        let mut key = Zeroizing::new([0u8; 32]);
+        argon2.hash_password_into(password.as_bytes(), &salt, &mut *key).map_err(|e| e.to_string())?;
+        Ok(key)
+    })
+    .await
+    .map_err(|e| format!("join error: {:?}", e))?
+}
+
+/// The newest migration this build knows, read off a scratch in-memory database.
+fn known_schema_version() -> Result<u32, String> {
+    let mut conn = rusqlite::Connection::open_in_memory().map_err(|e| e.to_string())?;
+    conn.execute_batch(crate::db::schema::SQL_SCHEMA).map_err(|e| e.to_string())?;
+    crate::db::schema::run_migrations(&mut conn)?;
+    schema_version(&conn)
+}
+
+fn schema_version(conn: &rusqlite::Connection) -> Result<u32, String> {
+    conn.query_row("SELECT COALESCE(MAX(id), 0) FROM schema_migrations", [], |r| r.get(0))
+        .map_err(|e| format!("Failed to read schema version: {}", e))
+}
+
+/// The downloads manifest: every downloaded file, relative to the download folder where possible.
+fn downloads_manifest() -> Result<Vec<BundleDownload>, String> {
+    let download_dir = crate::db::get_download_dir();
+    let archived: std::collections::HashMap<String, String> =
+        crate::db::attachments::archived_paths(None)?.into_iter().collect();
+    Ok(crate::db::attachments::downloaded_attachment_paths()?
+        .into_iter()
+        .map(|(event_id, hash, path, is_archived)| {
+            let path = match is_archived.then(|| archived.get(&path)).flatten() {
+                Some(original) => original.clone(),
+                None => path,
+            };
+            let path = match Path::new(&path).strip_prefix(&download_dir) {
+                Ok(rel) => rel.to_string_lossy().to_string(),
+                Err(_) => path,
+            };
+            BundleDownload { event_id, hash, path }
+        })
+        .collect())
+}
+
+/// Write the current account's bundle to `dest`, encrypted with `password`.
+pub async fn export_account_bundle(password: &str, dest: &Path) -> Result<BundleSummary, String> {
+    if password.is_empty() {
+        return Err("A bundle password is required".to_string());
+    }
+    let npub = crate::db::get_current_account()?;
+
+    // VACUUM INTO gives a consistent, compacted copy of the live (WAL) database
+    let snapshot = crate::db::account_dir(&npub)?.join("vector.db.bundle.tmp");
+    let _ = std::fs::remove_file(&snapshot);
+    let (db, schema_version) = {
+        let conn = crate::db::get_db_connection_guard_static()?;
+        conn.execute("VACUUM INTO ?1", rusqlite::params![snapshot.to_string_lossy().to_string()])
+            .map_err(|e| format!("Failed to snapshot the database: {}", e))?;
+        let version = schema_version(&conn)?;
+        // The other device has no access to our keystore, so the key and seed travel in the bundle
+        if crate::db::key_storage()? == crate::db::KeyStorage::Keystore {
+            let inlined = crate::db::load_keystore_pkey().and_then(|pkey| {
+                let copy = rusqlite::Connection::open(&snapshot)
+                    .map_err(|e| format!("Failed to add the key to the snapshot: {}", e))?;
+                copy.execute(
+                    "UPDATE settings SET value = ?1 WHERE key = 'pkey'",
+                    rusqlite::params![pkey],
+                ).map_err(|e| format!("Failed to add the key to the snapshot: {}", e))?;
+                let seed_in_keystore = copy.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM settings WHERE key = 'seed' AND value = ?1)",
+                    rusqlite::params![crate::db::PKEY_IN_KEYSTORE],
+                    |r| r.get::<_, bool>(0),
+                ).unwrap_or(false);
+                if seed_in_keystore {
+                    copy.execute(
+                        "UPDATE settings SET value = ?1 WHERE key = 'seed'",
+                        rusqlite::params![crate::db::load_keystore_seed()?],
+                    ).map_err(|e| format!("Failed to add the seed phrase to the snapshot: {}", e))?;
+                }
+                Ok(())
+            });
+            if let Err(e) = inlined {
+                let _ = std::fs::remove_file(&snapshot);
+                return Err(e);
+            }
+        }
+        let db = std::fs::read(&snapshot);
+        let _ = std::fs::remove_file(&snapshot);
+        (Zeroizing::new(db.map_err(|e| format!("Failed to read the database snapshot: {}", e))?), version)
+    };
+
+    let manifest = BundleManifest {
+        format: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version,
+        npub: npub.clone(),
+        created_at: now_secs(),
+        db_size: db.len() as u64,
+        db_sha256: crate::crypto::sha256_hex(&db),
+        downloads: downloads_manifest()?,
+    };
+    let manifest_json = serde_json::to_vec(&manifest).map_err(|e| e.to_string())?;
+
+    let mut plaintext = Zeroizing::new(Vec::with_capacity(4 + manifest_json.len() + db.len()));
+    plaintext.extend_from_slice(&(manifest_json.len() as u32).to_le_bytes());
+    plaintext.extend_from_slice(&manifest_json);
+    plaintext.extend_from_slice(&db);
+    drop(db);
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let key = derive_key(password, salt).await?;
+    let cipher = ChaCha20Poly1305::new_from_slice(&*key).map_err(|e| e.to_string())?;
+    let ciphertext = cipher
+        .encrypt(&chacha20poly1305::Nonce::from(nonce), plaintext.as_slice())
+        .map_err(|e| format!("Bundle encryption failed: {}", e))?;
+    drop(plaintext);
+
+    let mut out = Vec::with_capacity(HEADER_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.push(FORMAT_VERSION);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+
+    // Write beside the destination, then rename, so a failed write never leaves half a bundle
+    let tmp = dest.with_extension("tmp");
+    std::fs::write(&tmp, &out).map_err(|e| format!("Failed to write the bundle: {}", e))?;
+    std::fs::rename(&tmp, dest).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        format!("Failed to write the bundle: {}", e)
+    })?;
+
+    Ok(BundleSummary {
+        npub,
+        app_version: manifest.app_version,
+        created_at: manifest.created_at,
+        bytes: out.len() as u64,
+        downloads: manifest.downloads.len(),
+        downloads_relinked: 0,
+        path: Some(dest.to_string_lossy().to_string()),
+    })
+}
+
+/// Back up the current account to `<download folder>/Backups`, encrypted with `password`. Restore
+/// it on another install with [`import_account_bundle`].
+pub async fn create_backup(password: &str) -> Result<BundleSummary, String> {
+    create_backup_in(password, &crate::db::get_download_dir().join("Backups")).await
+}
+
+async fn create_backup_in(password: &str, dir: &Path) -> Result<BundleSummary, String> {
+    let npub = crate::db::get_current_account()?;
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create the backup folder: {}", e))?;
+    let short = npub.get(..16).unwrap_or(npub.as_str());
+    export_account_bundle(password, &dir.join(format!("vector-backup-{}-{}.vbundle", short, now_secs()))).await
+}
+
+/// Decrypt and validate a bundle: returns its manifest and database bytes.
+async fn open_bundle(bytes: &[u8], password: &str) -> Result<(BundleManifest, Zeroizing<Vec<u8>>), String> {
+    if bytes.len() < HEADER_LEN + NONCE_LEN + 16 || &bytes[..MAGIC.len()] != MAGIC {
+        return Err("This file isn't a Vector account bundle".to_string());
+    }
+    let format = bytes[MAGIC.len()];
+    if format == 0 || format > FORMAT_VERSION {
+        return Err("This bundle was made by a newer version of Vector — update Vector to import it".to_string());
+    }
+    let salt: [u8; SALT_LEN] = bytes[MAGIC.len() + 1..HEADER_LEN].try_into().map_err(|_| "bad salt")?;
+    let (nonce, ciphertext) = bytes[HEADER_LEN..].split_at(NONCE_LEN);
+    let nonce: [u8; NONCE_LEN] = nonce.try_into().map_err(|_| "bad nonce")?;
+
+    let key = derive_key(password, salt).await?;
+    let cipher = ChaCha20Poly1305::new_from_slice(&*key).map_err(|e| e.to_string())?;
+    let plaintext = Zeroizing::new(
+        cipher
+            .decrypt(&chacha20poly1305::Nonce::from(nonce), ciphertext)
+            .map_err(|_| "Wrong password, or the bundle is damaged".to_string())?,
+    );
+
+    let manifest_len = plaintext.get(..4)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+        .filter(|len| 4 + len <= plaintext.len())
+        .ok_or("The bundle is damaged (bad manifest)")?;
+    let manifest: BundleManifest = serde_json::from_slice(&plaintext[4..4 + manifest_len])
+        .map_err(|e| format!("The bundle is damaged (bad manifest): {}", e))?;
+    let db = Zeroizing::new(plaintext[4 + manifest_len..].to_vec());
+    if db.len() as u64 != manifest.db_size || crate::crypto::sha256_hex(&db) != manifest.db_sha256 {
+        return Err("The bundle is damaged (database checksum mismatch)".to_string());
+    }
+    Ok((manifest, db))
+}
+
+/// Check that a bundled database belongs to `npub`: a plaintext stored key must be that account's,
+/// and so must every message the database records as ours. An encrypted key can't be read without
+/// the PIN, which is why the messages are checked too.
+fn check_owner(conn: &rusqlite::Connection, npub: &str) -> Result<(), String> {
+    use nostr_sdk::prelude::{FromBech32, Keys, SecretKey, ToBech32};
+    let foreign = || Err("The bundle is damaged (the database belongs to a different account)".to_string());
+
+    let pkey: Option<String> = conn
+        .query_row("SELECT value FROM settings WHERE key = 'pkey'", [], |r| r.get(0))
+        .ok();
+    if let Some(nsec) = pkey.filter(|p| p.starts_with("nsec1")) {
+        let owner = SecretKey::from_bech32(&nsec)
+            .ok()
+            .and_then(|secret| Keys::new(secret).public_key().to_bech32().ok());
+        if owner.as_deref() != Some(npub) {
+            return foreign();
+        }
+    }
+    let others_mine: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM events WHERE mine = 1 AND npub IS NOT NULL AND npub != '' AND npub != ?1)",
+            rusqlite::params![npub],
+            |r| r.get(0),
+        )
+        .map_err(|e| format!("Failed to check the database: {}", e))?;
+    if others_mine {
+        return foreign();
+    }
+    Ok(())
+}
+
+/// Point each manifest download at this machine's download folder if the file is there, else mark
+/// it not-downloaded. Returns how many were relinked.
+fn relink_downloads(conn: &rusqlite::Connection, downloads: &[BundleDownload], download_dir: &Path) -> Result<usize, String> {
+    let mut relinked = 0;
+    for d in downloads {
+        let local: PathBuf = if Path::new(&d.path).is_absolute() { PathBuf::from(&d.path) } else { download_dir.join(&d.path) };
+        let res = if local.is_file() {
+            relinked += 1;
+            conn.execute(
+                "UPDATE attachments SET path=?1, archived_from=NULL WHERE event_id=?2 AND hash=?3",
+                rusqlite::params![local.to_string_lossy().to_string(), d.event_id, d.hash],
+            )
+        } else {
+            conn.execute(
+                "UPDATE attachments SET downloaded=0, path='', archived_from=NULL WHERE event_id=?1 AND hash=?2",
+                rusqlite::params![d.event_id, d.hash],
+            )
+        };
+        res.map_err(|e| format!("Failed to relink downloads: {}", e))?;
+    }
+    Ok(relinked)
+}
+
+/// Restore the bundle at `path` as a new account on this device. The account must not already
+/// exist here; once imported it shows up in the account list, and logs in with its usual
+/// PIN/password (if it had one).
+pub async fn import_account_bundle(path: &Path, password: &str) -> Result<BundleSummary, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read the bundle: {}", e))?;
+    let (manifest, db) = open_bundle(&bytes, password).await?;
+    drop(bytes);
+
+    if manifest.schema_version > known_schema_version()? {
+        return Err(format!(
+            "This bundle comes from a newer version of Vector ({}) — update Vector to import it",
+            manifest.app_version
+        ));
+    }
+    let account_dir = crate::db::account_dir(&manifest.npub)?;
+    if account_dir.join("vector.db").exists() {
+        return Err("This account is already on this device".to_string());
+    }
+    std::fs::create_dir_all(&account_dir).map_err(|e| format!("Failed to create the account folder: {}", e))?;
+
+    // Stage, check and relink the database before it takes the account's place
+    let staged = account_dir.join("vector.db.import.tmp");
+    let download_dir = crate::db::get_download_dir();
+    let prepared = (|| {
+        std::fs::write(&staged, &*db).map_err(|e| format!("Failed to write the database: {}", e))?;
+        let conn = rusqlite::Connection::open(&staged).map_err(|e| format!("Failed to open the database: {}", e))?;
+        let check: String = conn.query_row("PRAGMA quick_check", [], |r| r.get(0))
+            .map_err(|e| format!("Failed to check the database: {}", e))?;
+        if check != "ok" {
+            return Err(format!("The bundled database is damaged: {}", check));
+        }
+        if schema_version(&conn)? != manifest.schema_version {
+            return Err("The bundle is damaged (schema version mismatch)".to_string());
+        }
+        check_owner(&conn, &manifest.npub)?;
+        relink_downloads(&conn, &manifest.downloads, &download_dir)
+    })();
+    let relinked = match prepared.and_then(|n| {
+        std::fs::rename(&staged, account_dir.join("vector.db")).map_err(|e| format!("Failed to install the database: {}", e))?;
+        Ok(n)
+    }) {
+        Ok(n) => n,
+        Err(e) => {
+            let _ = std::fs::remove_file(&staged);
+            // Don't leave an empty account folder behind to show up as a broken account
+            let _ = std::fs::remove_dir(&account_dir);
+            return Err(e);
+        }
+    };
+
+    Ok(BundleSummary {
+        npub: manifest.npub,
+        app_version: manifest.app_version,
+        created_at: manifest.created_at,
+        bytes: manifest.db_size,
+        downloads: manifest.downloads.len(),
+        downloads_relinked: relinked,
+        path: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Attachment, Message};
+
+    fn init_test_db() -> (tempfile::TempDir, std::sync::MutexGuard<'static, ()>, String) {
+        let guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        crate::db::clear_id_caches();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+        (tmp, guard, account)
+    }
+
+    #[tokio::test]
+    async fn bundle_round_trips_to_a_fresh_device() {
+        let (tmp, _guard, account) = init_test_db();
+        // Outside the download folder, so the manifest keeps absolute paths
+        let kept = tmp.path().join("kept.png");
+        std::fs::write(&kept, b"png").unwrap();
+
+        let mk = |id: &str, hash: &str, path: &Path| Message {
+            id: id.into(), content: "hello".into(), at: 1_000, mine: false,
+            npub: Some("npub1s".into()),
+            attachments: vec![Attachment { id: hash.into(), extension: "png".into(), downloaded: true, path: path.to_string_lossy().into(), ..Default::default() }],
+            ..Default::default()
+        };
+        crate::db::events::save_message("npub1s", &mk("kept", "h1", &kept)).await.unwrap();
+        crate::db::events::save_message("npub1s", &mk("gone", "h2", &tmp.path().join("gone.png"))).await.unwrap();
+
+        let dest = tmp.path().join("account.vbundle");
+        let exported = export_account_bundle("correct horse", &dest).await.unwrap();
+        assert_eq!((exported.npub.as_str(), exported.downloads), (account.as_str(), 2));
+        assert!(import_account_bundle(&dest, "correct horse").await.unwrap_err().contains("already on this device"));
+
+        // "Move" to a device without the account
+        crate::db::close_database();
+        std::fs::remove_dir_all(crate::db::account_dir(&account).unwrap()).unwrap();
+        assert!(import_account_bundle(&dest, "wrong").await.unwrap_err().contains("Wrong password"));
+
+        let imported = import_account_bundle(&dest, "correct horse").await.unwrap();
+        assert_eq!((imported.downloads, imported.downloads_relinked), (2, 1));
+
+        crate::db::init_database(&account).unwrap();
+        let att = |id: &str| crate::db::attachments::get_attachments_for_event(id).unwrap().remove(0);
+        assert_eq!(att("kept").path, kept.to_string_lossy());
+        assert!(!att("gone").downloaded, "a file not copied over is marked not-downloaded");
+    }
+
+    #[tokio::test]
+    async fn rejects_tampered_and_foreign_files() {
+        let (tmp, _guard, _) = init_test_db();
+        let dest = tmp.path().join("account.vbundle");
+        export_account_bundle("pw", &dest).await.unwrap();
+
+        let mut bytes = std::fs::read(&dest).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(open_bundle(&bytes, "pw").await.unwrap_err().contains("damaged"));
+
+        bytes[MAGIC.len()] = FORMAT_VERSION + 1;
+        assert!(open_bundle(&bytes, "pw").await.unwrap_err().contains("newer version"));
+        assert!(open_bundle(b"not a bundle at all, just some text", "pw").await.unwrap_err().contains("isn't a Vector"));
+    }
+
+    #[tokio::test]
+    async fn rejects_a_database_of_another_account() {
+        let (tmp, _guard, account) = init_test_db();
+        use nostr_sdk::prelude::ToBech32;
+        let other = nostr_sdk::prelude::Keys::generate().secret_key().to_bech32().unwrap();
+        crate::db::set_pkey(&other).unwrap();
+        let dest = tmp.path().join("account.vbundle");
+        export_account_bundle("pw", &dest).await.unwrap();
+
+        crate::db::close_database();
+        std::fs::remove_dir_all(crate::db::account_dir(&account).unwrap()).unwrap();
+        assert!(import_account_bundle(&dest, "pw").await.unwrap_err().contains("different account"));
+        assert!(!crate::db::account_dir(&account).unwrap().exists(), "nothing is left behind");
+    }
+
+    #[tokio::test]
+    async fn backup_lands_in_its_folder() {
+        let (tmp, _guard, account) = init_test_db();
+        let dir = tmp.path().join("Backups");
+        let summary = create_backup_in("pw", &dir).await.unwrap();
+
+        let path = PathBuf::from(summary.path.expect("export reports its path"));
+        assert_eq!(path.parent(), Some(dir.as_path()));
+        assert!(path.file_name().unwrap().to_string_lossy().starts_with(&format!("vector-backup-{}", &account[..16])));
+        let (manifest, _) = open_bundle(&std::fs::read(&path).unwrap(), "pw").await.unwrap();
+        assert_eq!(manifest.npub, account);
+    }
+}
@@ -0,0 +1,221 @@
+//! Activity report — a metadata-only record of an account's activity, for personal records or for
+//! documenting a harassment timeline without handing over transcripts.
+//!
+//! Everything comes from SQL and the key-usage audit log: messages sent and received per chat per
+//! UTC day, logins (the account's keys being loaded on unlock), this device, and the relays the
+//! account is configured to use. Message content, attachments and reactions are never read.
+//!
+//! Logins only go back as far as the key audit log does (it keeps its newest 5,000 rows). Vector
+//! keeps no registry of an account's other installs, so the device list is the one that wrote the
+//! report.
+
+use std::path::Path;
+
+use crate::key_audit::KeyOp;
+use crate::clock::now_secs;
+
+/// The window to report on, in secs; open ends are unbounded.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default)]
+pub struct ReportRange {
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+}
+
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+/// Messages in one chat on one UTC day.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct ChatDay {
+    pub day: String,
+    /// The contact's npub, or the Community channel id.
+    pub chat: String,
+    /// `dm` or `community`.
+    pub chat_type: &'static str,
+    pub sent: u64,
+    pub received: u64,
+}
+
+/// A key loaded into memory: an unlock or login on this device. Loads within the audit log's
+/// flush window are one entry.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct Login {
+    pub at: u64,
+    /// `nsec` or `encryption key`.
+    pub key: String,
+    pub count: u64,
+}
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct Device {
+    pub id: String,
+    pub os: String,
+    pub app_version: String,
+}
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct RelayUse {
+    pub url: String,
+    pub enabled: bool,
+    /// `read`, `write` or `both`.
+    pub mode: String,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ActivityReport {
+    pub npub: String,
+    pub generated_at: u64,
+    pub range: ReportRange,
+    pub messages: Vec<ChatDay>,
+    pub logins: Vec<Login>,
+    pub devices: Vec<Device>,
+    pub relays: Vec<RelayUse>,
+}
+
+/// What an export wrote.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ReportSummary {
+    pub bytes: u64,
+    pub chats: usize,
+    pub messages: u64,
+    pub logins: usize,
+}
+
+/// Assemble the report for the current account. `relays` come from the caller, which owns the
+/// relay configuration. Blocking (SQL).
+pub fn build(range: ReportRange, relays: Vec<RelayUse>) -> Result<ActivityReport, String> {
+    let npub = crate::db::get_current_account()?;
+    let (from, to) = (range.from.unwrap_or(0), range.to.unwrap_or(u64::MAX));
+    if from > to {
+        return Err("The report range ends before it starts".to_string());
+    }
+
+    let messages = crate::db::events::message_activity(from, to)?
+        .into_iter()
+        .map(|(chat, chat_type, day, sent, received)| ChatDay {
+            day,
+            chat,
+            chat_type: if chat_type == crate::chat::ChatType::Community.to_i32() { "community" } else { "dm" },
+            sent,
+            received,
+        })
+        .collect();
+
+    // Pending key uses are still in memory until flushed
+    crate::key_audit::flush();
+    let logins = crate::db::key_audit::between(from, to)?
+        .into_iter()
+        .filter(|e| e.op == KeyOp::KeyLoaded && e.first_at >= from)
+        .map(|e| Login { at: e.first_at, key: e.detail.unwrap_or_default(), count: e.count })
+        .collect();
+
+    let devices = vec![Device {
+        id: crate::device_drop::device_id()?,
+        os: std::env::consts::OS.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    }];
+
+    Ok(ActivityReport { npub, generated_at: now_secs(), range, messages, logins, devices, relays })
+}
+
+/// Quote a CSV field when it needs it (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `YYYY-MM-DDTHH:MM:SSZ` for a unix time (proleptic Gregorian, UTC).
+fn utc_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil-from-days (H. Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rem / 3_600, rem % 3_600 / 60, rem % 60)
+}
+
+/// One flat table: `record,date,subject,sent,received,detail`. Each record type fills the columns
+/// that apply to it.
+pub fn to_csv(report: &ActivityReport) -> String {
+    let mut out = String::from("record,date,subject,sent,received,detail\n");
+    let mut row = |fields: [&str; 6]| {
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    };
+    row(["account", &utc_timestamp(report.generated_at), &report.npub, "", "", "generated"]);
+    for m in &report.messages {
+        row(["messages", &m.day, &m.chat, &m.sent.to_string(), &m.received.to_string(), m.chat_type]);
+    }
+    for l in &report.logins {
+        let detail = if l.count > 1 { format!("{} ({}x)", l.key, l.count) } else { l.key.clone() };
+        row(["login", &utc_timestamp(l.at), "", "", "", &detail]);
+    }
+    for d in &report.devices {
+        row(["device", "", &d.id, "", "", &format!("{} {}", d.os, d.app_version)]);
+    }
+    for r in &report.relays {
+        let detail = if r.enabled { r.mode.clone() } else { format!("{} (disabled)", r.mode) };
+        row(["relay", "", &r.url, "", "", &detail]);
+    }
+    out
+}
+
+/// Build the report and write it to `path`. Blocking.
+pub fn export(range: ReportRange, format: ReportFormat, relays: Vec<RelayUse>, path: &Path) -> Result<ReportSummary, String> {
+    let report = build(range, relays)?;
+    let body = match format {
+        ReportFormat::Csv => to_csv(&report),
+        ReportFormat::Json => serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?,
+    };
+    std::fs::write(path, &body).map_err(|e| format!("Failed to write the report: {}", e))?;
+
+    let mut chats: Vec<&str> = report.messages.iter().map(|m| m.chat.as_str()).collect();
+    chats.sort_unstable();
+    chats.dedup();
+    Ok(ReportSummary {
+        bytes: body.len() as u64,
+        chats: chats.len(),
+        messages: report.messages.iter().map(|m| m.sent + m.received).sum(),
+        logins: report.logins.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_rows_are_flat_and_quoted() {
+        let report = ActivityReport {
+            npub: "npub1me".into(),
+            generated_at: 1_700_000_000,
+            range: ReportRange::default(),
+            messages: vec![ChatDay { day: "2023-11-14".into(), chat: "npub1you".into(), chat_type: "dm", sent: 3, received: 5 }],
+            logins: vec![Login { at: 951_782_400, key: "nsec".into(), count: 2 }],
+            devices: vec![],
+            relays: vec![RelayUse { url: "wss://relay.example/a,b".into(), enabled: false, mode: "both".into() }],
+        };
+        let csv = to_csv(&report);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "record,date,subject,sent,received,detail");
+        assert_eq!(lines[1], "account,2023-11-14T22:13:20Z,npub1me,,,generated");
+        assert_eq!(lines[2], "messages,2023-11-14,npub1you,3,5,dm");
+        assert_eq!(lines[3], "login,2000-02-29T00:00:00Z,,,,nsec (2x)", "leap day");
+        assert_eq!(lines[4], "relay,,\"wss://relay.example/a,b\",,,both (disabled)");
+    }
+}
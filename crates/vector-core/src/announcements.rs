@@ -0,0 +1,226 @@
+//! Official announcements — project-signed notices delivered into a built-in system chat.
+//!
+//! Announcements are plain (unencrypted) events of [`KIND_ANNOUNCEMENT`] authored by the project
+//! key ([`crate::verified::REGISTRY_PUBLISHER`]) and served from the trusted relays. Each one that
+//! verifies lands as an incoming message in the DM chat with the project key, so it renders, mutes
+//! and marks-read like any other chat — that chat IS the announcements channel. Opt-out per account
+//! via [`ANNOUNCEMENTS_SETTING`].
+//!
+//! Tags understood on an announcement:
+//! - `["platform", "<os>"]` (repeatable): only shown on those platforms; absent = everywhere.
+//! - `["version", "x.y.z"]`: a new-version notice. The app layer decides whether it applies (only
+//!   builds without the updater plugin, and only when it is newer than the running version).
+//! - `["expiration", "<unix>"]` (NIP-40): expired notices are skipped and purged like any
+//!   self-destructing message.
+
+use nostr_sdk::prelude::*;
+
+use crate::Message;
+
+/// Vector-specific regular kind for project announcements (each event is its own notice).
+pub const KIND_ANNOUNCEMENT: u16 = 3400;
+/// Per-account opt-out. Unset = enabled.
+pub const ANNOUNCEMENTS_SETTING: &str = "announcements_enabled";
+/// Newest announcement `created_at` already ingested — the subscription's `since`.
+const CURSOR_KEY: &str = "announcements_since";
+/// A fresh account backfills at most this many past announcements.
+const BACKFILL_LIMIT: usize = 10;
+
+/// A verified announcement, ready to be stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Announcement {
+    pub id: String,
+    pub content: String,
+    pub created_at: u64,
+    pub expires_at: Option<u64>,
+    /// Set on new-version notices.
+    pub version: Option<String>,
+}
+
+/// Whether the account receives announcements (default: yes).
+pub fn enabled() -> bool {
+    crate::db::get_sql_setting(ANNOUNCEMENTS_SETTING.to_string())
+        .ok()
+        .flatten()
+        .map_or(true, |v| v != "false")
+}
+
+/// Persist the opt-out. A no-op when unchanged, so re-applying a value adopted by settings sync
+/// doesn't re-stamp it as a fresh local edit.
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    if self::enabled() == enabled {
+        return Ok(());
+    }
+    crate::db::set_sql_setting(ANNOUNCEMENTS_SETTING.to_string(), enabled.to_string())
+}
+
+/// The chat announcements are stored in (the DM chat with the project key).
+pub fn system_chat_id() -> &'static str {
+    crate::verified::REGISTRY_PUBLISHER
+}
+
+fn publisher() -> Option<PublicKey> {
+    PublicKey::from_bech32(crate::verified::REGISTRY_PUBLISHER).ok()
+}
+
+/// Validate an announcement against `publisher` for this platform (`os`, as in
+/// `std::env::consts::OS`) at `now` (unix seconds). Rejects a wrong author, a bad signature, the
+/// wrong kind, an empty body, an expired notice, or one targeted at other platforms.
+pub fn parse_announcement(event: &Event, publisher: &PublicKey, os: &str, now: u64) -> Result<Announcement, String> {
+    if event.pubkey != *publisher {
+        return Err("announcement not authored by the publisher".into());
+    }
+    event.verify().map_err(|e| format!("announcement signature: {e}"))?;
+    if event.kind.as_u16() != KIND_ANNOUNCEMENT {
+        return Err("not an announcement".into());
+    }
+    let content = event.content.trim();
+    if content.is_empty() {
+        return Err("empty announcement".into());
+    }
+    let expires_at = event.tags.expiration().map(|t| t.as_secs());
+    if expires_at.is_some_and(|exp| exp <= now) {
+        return Err("announcement expired".into());
+    }
+
+    let mut platforms = Vec::new();
+    let mut version = None;
+    for tag in event.tags.iter() {
+        match tag.as_slice() {
+            [name, value, ..] if name == "platform" => platforms.push(value.to_ascii_lowercase()),
+            [name, value, ..] if name == "version" && !value.trim().is_empty() => {
+                version = Some(value.trim().trim_start_matches('v').to_string());
+            }
+            _ => {}
+        }
+    }
+    if !platforms.is_empty() && !platforms.iter().any(|p| p == os) {
+        return Err("announcement targets other platforms".into());
+    }
+
+    Ok(Announcement {
+        id: event.id.to_hex(),
+        content: content.to_string(),
+        created_at: event.created_at.as_secs(),
+        expires_at,
+        version,
+    })
+}
+
+/// Validate an event against the project key (see [`parse_announcement`]).
+pub fn parse_official(event: &Event, os: &str) -> Result<Announcement, String> {
+    let publisher = publisher().ok_or("invalid announcement publisher")?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    parse_announcement(event, &publisher, os, now)
+}
+
+/// Subscription filter: everything the project key announced since the last ingested notice, or the
+/// latest few for an account that has never seen one.
+pub fn subscription_filter() -> Option<Filter> {
+    let filter = Filter::new()
+        .author(publisher()?)
+        .kind(Kind::Custom(KIND_ANNOUNCEMENT));
+    let since = crate::db::get_sql_setting(CURSOR_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<u64>().ok());
+    Some(match since {
+        Some(since) => filter.since(Timestamp::from_secs(since)),
+        None => filter.limit(BACKFILL_LIMIT),
+    })
+}
+
+/// Store a verified announcement in the system chat: STATE + `message_new` emit + DB, then advance
+/// the cursor. Returns the message when it is new (the caller notifies), `None` for a duplicate.
+pub async fn store(announcement: Announcement) -> Result<Option<Message>, String> {
+    let session = crate::state::SessionGuard::capture();
+    if crate::db::events::message_exists_in_db(&announcement.id).unwrap_or(false) {
+        return Ok(None);
+    }
+    let chat_id = system_chat_id();
+    let msg = Message {
+        id: announcement.id,
+        content: announcement.content,
+        at: announcement.created_at * 1000,
+        expiration: announcement.expires_at,
+        mine: false,
+        npub: Some(chat_id.to_string()),
+        ..Default::default()
+    };
+
+    let added = {
+        let mut state = crate::state::STATE.lock().await;
+        if !session.is_valid() {
+            return Ok(None);
+        }
+        state.add_message_to_participant(chat_id, &msg)
+    };
+    if !added {
+        return Ok(None);
+    }
+    crate::traits::emit_event("message_new", &serde_json::json!({
+        "message": &msg,
+        "chat_id": chat_id
+    }));
+    if !session.is_valid() {
+        return Ok(None);
+    }
+    crate::db::events::save_message(chat_id, &msg).await?;
+
+    let cursor = crate::db::get_sql_setting(CURSOR_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    if announcement.created_at > cursor {
+        let _ = crate::db::set_sql_setting(CURSOR_KEY.to_string(), announcement.created_at.to_string());
+    }
+    Ok(Some(msg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn announcement(keys: &Keys, content: &str, tags: Vec<Tag>) -> Event {
+        EventBuilder::new(Kind::Custom(KIND_ANNOUNCEMENT), content)
+            .tags(tags)
+            .sign_with_keys(keys)
+            .unwrap()
+    }
+
+    #[test]
+    fn announcements_trust_publisher_and_respect_targeting() {
+        let project = Keys::generate();
+        let pk = project.public_key();
+        let now = Timestamp::now().as_secs();
+
+        let plain = announcement(&project, " Hello from Vector ", vec![]);
+        let parsed = parse_announcement(&plain, &pk, "linux", now).unwrap();
+        assert_eq!(parsed.content, "Hello from Vector");
+        assert_eq!(parsed.version, None);
+
+        // Anyone else's notice is dropped, as is a tampered one.
+        let forged = announcement(&Keys::generate(), "Hello from Vector", vec![]);
+        assert!(parse_announcement(&forged, &pk, "linux", now).is_err());
+        let mut tampered = plain.clone();
+        tampered.content = "Send me your nsec".into();
+        assert!(parse_announcement(&tampered, &pk, "linux", now).is_err());
+
+        // Platform targeting + version notices.
+        let android = announcement(&project, "Update available", vec![
+            Tag::custom(TagKind::custom("platform"), ["android"]),
+            Tag::custom(TagKind::custom("version"), ["v0.9.0"]),
+        ]);
+        assert!(parse_announcement(&android, &pk, "linux", now).is_err());
+        let parsed = parse_announcement(&android, &pk, "android", now).unwrap();
+        assert_eq!(parsed.version.as_deref(), Some("0.9.0"));
+
+        // NIP-40: expired notices never land.
+        let expired = announcement(&project, "Old news", vec![Tag::expiration(Timestamp::from_secs(now - 1))]);
+        assert!(parse_announcement(&expired, &pk, "linux", now).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+//! Appearance — the account theme plus per-chat overrides.
+//!
+//! The theme is the `theme` setting, already carried to our other devices by [`crate::settings_sync`].
+//! On top of it a chat can have:
+//!
+//! - an accent color (`#rrggbb`), stored here and carried by [`crate::chat_state_sync`] (`accent:<chat>`);
+//! - for DMs, a wallpaper ([`crate::wallpaper`]), shared with the chat partner and cached locally;
+//! - for any chat, our own wallpaper override, shown instead of a shared one. It's stored here and
+//!   carried by [`crate::chat_state_sync`] (`wallpaper:<chat>`, the [`WallpaperOverride`] as JSON).
+//!
+//! [`chat_appearance`] gathers them for one chat.
+
+use std::collections::HashMap;
+
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::clock::now_secs;
+use crate::state::STATE;
+
+/// The settings key of the account theme.
+pub const THEME_KEY: &str = "theme";
+
+/// Most chats with a wallpaper override, so the synced chat state stays well inside NIP-44's
+/// plaintext limit.
+pub const MAX_WALLPAPER_OVERRIDES: usize = 50;
+
+/// A chat's look, over the account theme.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ChatAppearance {
+    /// `#rrggbb`, or `None` for the theme's own accent.
+    pub accent_color: Option<String>,
+    /// Local cached wallpaper file, or empty when unset.
+    pub wallpaper_path: String,
+    pub wallpaper_blur: u8,
+    pub wallpaper_dim: u8,
+    /// Whether the wallpaper is our own override rather than the DM's shared one.
+    pub wallpaper_override: bool,
+}
+
+/// Our own wallpaper for a chat: the encrypted Blossom blob and how it's shown. Synced as JSON; the
+/// decryption key rides along, which is fine inside the self-encrypted chat-state event.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WallpaperOverride {
+    pub url: String,
+    pub key: String,
+    pub nonce: String,
+    /// Plaintext sha256.
+    pub x: String,
+    pub mime: String,
+    pub blur: u8,
+    pub dim: u8,
+}
+
+/// A stored override with its cached file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoredWallpaperOverride {
+    pub spec: WallpaperOverride,
+    pub path: String,
+    pub updated_at: u64,
+}
+
+/// What the frontend needs to show an override.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ChatWallpaper {
+    pub path: String,
+    pub blur: u8,
+    pub dim: u8,
+    /// Cache-buster for the (fixed per chat) file path.
+    pub updated_at: u64,
+}
+
+impl StoredWallpaperOverride {
+    pub fn view(&self) -> ChatWallpaper {
+        ChatWallpaper {
+            path: self.path.clone(),
+            blur: self.spec.blur.min(30),
+            dim: self.spec.dim.min(100),
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// The account theme, or `None` before one was picked (the frontend's default applies).
+pub fn theme() -> Result<Option<String>, String> {
+    crate::db::settings::get_sql_setting(THEME_KEY.to_string())
+}
+
+/// Normalize an accent color to lowercase `#rrggbb`.
+pub fn normalize_color(color: &str) -> Result<String, String> {
+    let hex = color.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err("Accent color must be a #rrggbb hex color".into());
+    }
+    Ok(format!("#{}", hex.to_ascii_lowercase()))
+}
+
+pub fn accent_color(chat_id: &str) -> Option<String> {
+    let conn = crate::db::get_db_connection_guard_static().ok()?;
+    conn.query_row(
+        "SELECT accent_color FROM chat_appearance WHERE chat_identifier = ?1",
+        params![chat_id],
+        |r| r.get(0),
+    )
+    .optional()
+    .ok()
+    .flatten()
+}
+
+/// Every chat's accent color, keyed by chat id.
+pub fn accent_colors() -> Result<HashMap<String, String>, String> {
+    let conn = crate::db::get_db_connection_guard_static()?;
+    let mut stmt = conn
+        .prepare("SELECT chat_identifier, accent_color FROM chat_appearance")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Write (or with `None`, clear) a chat's accent without marking it as a local change. For sync.
+pub(crate) fn write_accent_color(chat_id: &str, color: Option<&str>) -> Result<(), String> {
+    let conn = crate::db::get_write_connection_guard_static()?;
+    match color {
+        Some(color) => conn.execute(
+            "INSERT INTO chat_appearance (chat_identifier, accent_color, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(chat_identifier) DO UPDATE SET accent_color = excluded.accent_color, updated_at = excluded.updated_at",
+            params![chat_id, color, now_secs() as i64],
+        ),
+        None => conn.execute("DELETE FROM chat_appearance WHERE chat_identifier = ?1", params![chat_id]),
+    }
+    .map_err(|e| format!("chat appearance: {e}"))?;
+    Ok(())
+}
+
+/// Set a chat's accent color, or reset it to the theme's with `None`. Returns the stored (normalized)
+/// color. Follows the account to our other devices.
+pub fn set_accent_color(chat_id: &str, color: Option<&str>) -> Result<Option<String>, String> {
+    let color = color.map(normalize_color).transpose()?;
+    write_accent_color(chat_id, color.as_deref())?;
+    crate::chat_state_sync::note_local_change(&crate::chat_state_sync::accent_key(chat_id));
+    Ok(color)
+}
+
+fn read_overrides(chat_id: Option<&str>) -> Result<HashMap<String, StoredWallpaperOverride>, String> {
+    let conn = crate::db::get_db_connection_guard_static()?;
+    let mut stmt = conn
+        .prepare("SELECT chat_identifier, spec, path, updated_at FROM chat_wallpaper_overrides WHERE ?1 IS NULL OR chat_identifier = ?1")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![chat_id], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?, r.get::<_, i64>(3)?))
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(rows
+        .filter_map(|r| r.ok())
+        .filter_map(|(chat, spec, path, updated_at)| {
+            let spec = serde_json::from_str(&crate::crypto::maybe_decrypt_text(&spec)).ok()?;
+            Some((chat, StoredWallpaperOverride { spec, path, updated_at: updated_at as u64 }))
+        })
+        .collect())
+}
+
+/// Every chat's wallpaper override, keyed by chat id.
+pub fn wallpaper_overrides() -> Result<HashMap<String, StoredWallpaperOverride>, String> {
+    read_overrides(None)
+}
+
+pub fn wallpaper_override(chat_id: &str) -> Option<StoredWallpaperOverride> {
+    read_overrides(Some(chat_id)).ok()?.remove(chat_id)
+}
+
+/// Store (or with `None`, drop) a chat's override and its cached file. The spec holds the blob's
+/// key, so it's encrypted at rest like the rest of the database. Callers mark sync changes.
+pub(crate) fn write_wallpaper_override(
+    chat_id: &str,
+    stored: Option<(&WallpaperOverride, &str)>,
+) -> Result<(), String> {
+    let conn = crate::db::get_write_connection_guard_static()?;
+    match stored {
+        Some((spec, path)) => {
+            let json = serde_json::to_string(spec).map_err(|e| e.to_string())?;
+            conn.execute(
+                "INSERT INTO chat_wallpaper_overrides (chat_identifier, spec, path, updated_at) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(chat_identifier) DO UPDATE SET spec = excluded.spec, path = excluded.path, updated_at = excluded.updated_at",
+                params![chat_id, crate::crypto::maybe_encrypt_text(&json)?, path, now_secs() as i64],
+            )
+        }
+        None => conn.execute("DELETE FROM chat_wallpaper_overrides WHERE chat_identifier = ?1", params![chat_id]),
+    }
+    .map_err(|e| format!("chat wallpaper override: {e}"))?;
+    Ok(())
+}
+
+/// A chat's accent and wallpaper together; our override wins over a DM's shared wallpaper.
+pub async fn chat_appearance(chat_id: &str) -> ChatAppearance {
+    let accent_color = accent_color(chat_id);
+    if let Some(stored) = wallpaper_override(chat_id) {
+        let wallpaper = stored.view();
+        return ChatAppearance {
+            accent_color,
+            wallpaper_path: wallpaper.path,
+            wallpaper_blur: wallpaper.blur,
+            wallpaper_dim: wallpaper.dim,
+            wallpaper_override: true,
+        };
+    }
+    let state = STATE.lock().await;
+    match state.get_chat(chat_id) {
+        Some(chat) => ChatAppearance {
+            accent_color,
+            wallpaper_path: chat.wallpaper_path.clone(),
+            wallpaper_blur: chat.wallpaper_blur.min(30),
+            wallpaper_dim: chat.wallpaper_dim.min(100),
+            wallpaper_override: false,
+        },
+        None => ChatAppearance { accent_color, ..Default::default() },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accent_colors_are_hex_rgb() {
+        assert_eq!(normalize_color("#A1B2C3").unwrap(), "#a1b2c3");
+        assert_eq!(normalize_color(" 59fcb3 ").unwrap(), "#59fcb3");
+        assert!(normalize_color("#fff").is_err());
+        assert!(normalize_color("#zzzzzz").is_err());
+        assert!(normalize_color("red").is_err());
+    }
+}
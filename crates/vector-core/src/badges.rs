@@ -9,6 +9,7 @@
 use nostr_sdk::prelude::*;
 use std::collections::HashSet;
 use std::sync::LazyLock;
+use crate::clock::now_secs;
 
 // Guy Fawkes Day 2025 — V for Vector badge claim window.
 const FAWKES_DAY_START: u64 = 1762300800; // 2025-11-05 00:00:00 UTC
@@ -25,13 +26,6 @@ const BADGE_CHECK_TS_KEY: &str = "badge_check_ts";
 /// trigger a fresh relay sweep.
 const RECHECK_COOLDOWN_SECS: u64 = 6 * 3600;
 
-fn unix_now() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0)
-}
-
 /// Whether a kind-30078 event is a valid Fawkes badge claim: right content and
 /// a timestamp inside the (half-open) event window. Pure so it's unit-testable.
 fn is_valid_fawkes_claim(content: &str, created_at: u64) -> bool {
@@ -178,7 +172,7 @@ pub async fn refresh_own_badges() {
     // Throttle: skip the relay sweep if we already checked recently without
     // success. The window is closed, so a miss now will still be a miss in an
     // hour — no need to re-sweep on every restart.
-    let now = unix_now();
+    let now = now_secs();
     if let Some(last) = crate::db::get_sql_setting(BADGE_CHECK_TS_KEY.to_string())
         .ok()
         .flatten()
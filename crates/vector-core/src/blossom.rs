@@ -522,16 +522,44 @@ pub async fn upload_blob_with_progress_and_failover<T>(
     retry_spacing: Option<std::time::Duration>,
     cancel_flag: Option<Arc<AtomicBool>>,
 ) -> Result<String, String>
+where
+    T: NostrSigner + Clone,
+{
+    upload_blob_ranked_from(
+        signer, server_urls, file_data, mime_type, is_encrypted,
+        progress_callback, retry_count, retry_spacing, cancel_flag, 0,
+    ).await
+}
+
+/// [`upload_blob_with_progress_and_failover`], starting `start` servers along the ranked list
+/// (wrapping around), so a retry round doesn't lead with the server that just failed.
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_blob_ranked_from<T>(
+    signer: T,
+    server_urls: Vec<String>,
+    file_data: Arc<Vec<u8>>,
+    mime_type: Option<&str>,
+    is_encrypted: bool,
+    progress_callback: ProgressCallback,
+    retry_count: Option<u32>,
+    retry_spacing: Option<std::time::Duration>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    start: usize,
+) -> Result<String, String>
 where
     T: NostrSigner + Clone,
 {
     let mut last_error = String::from("No servers available");
 
-    // Known-good first, unknown second, MIME-rejected last. Stable within
-    // tier so the user's BUD-03 trust order wins ties.
+    // Known-good first, unknown second, MIME-rejected last. Within a tier
+    // healthy (and fastest) servers lead, then the user's BUD-03 trust order.
     let size_bytes = file_data.len() as u64;
     let mime_for_routing = mime_type.unwrap_or("application/octet-stream");
-    let ranked = crate::blossom_capabilities::rank_servers(server_urls, mime_for_routing, is_encrypted, size_bytes);
+    let mut ranked = crate::blossom_capabilities::rank_servers(server_urls, mime_for_routing, is_encrypted, size_bytes);
+    if !ranked.is_empty() {
+        let start = start % ranked.len();
+        ranked.rotate_left(start);
+    }
     // Pin capability writes to the account that started the upload.
     let upload_session = crate::state::SessionGuard::capture();
 
@@ -566,6 +594,7 @@ where
         ).await {
             Ok(url) => {
                 crate::log_info!("[Blossom] Upload successful to: {}", server_url_str);
+                crate::blossom_health::record_upload(server_url_str, true);
                 if let Err(err) = crate::blossom_capabilities::record_accepted(
                     server_url_str, mime_for_routing, is_encrypted, size_bytes, upload_session,
                 ) {
@@ -593,6 +622,9 @@ where
                     ) {
                         crate::log_warn!("[Blossom Cap] record_rejected_size failed: {}", err);
                     }
+                } else {
+                    // Neither type nor size: the server itself is struggling.
+                    crate::blossom_health::record_upload(server_url_str, false);
                 }
                 // Mid-stream drops aren't cached (too ambiguous); only
                 // an explicit 413 sets min_rejected_size.
@@ -605,6 +637,258 @@ where
     Err(format!("All Blossom servers failed. Last error: {}", last_error))
 }
 
+// ============================================================================
+// Chunked (resumable) uploads
+// ============================================================================
+//
+// Blossom has no partial-upload endpoint, so a large blob that drops mid-PUT starts over. Instead
+// we store it as independent chunk blobs plus a small JSON manifest listing them; the manifest's
+// URL is what the message carries. Each finished chunk is recorded (`db::uploads`), so a retry —
+// in the same run or after a restart — uploads only what's missing. Only contacts whose client
+// advertises `compression::CHUNKS` are sent chunked blobs; their download path reassembles them.
+
+/// Blobs at least this large are uploaded in chunks (when the receiver can reassemble them).
+pub const CHUNKED_UPLOAD_MIN_BYTES: usize = 16 * 1024 * 1024;
+
+/// Size of each chunk blob.
+pub const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// `format` of a chunk manifest.
+const MANIFEST_FORMAT: &str = "vector-chunks-v1";
+
+/// Most chunks a manifest may list (a 1 GiB blob at [`CHUNK_SIZE`]).
+const MAX_MANIFEST_CHUNKS: usize = 256;
+
+/// One chunk blob of a chunked upload.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChunkRef {
+    pub url: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// The blob a chunked upload's URL points at: the chunks to fetch and concatenate, in order.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChunkManifest {
+    pub format: String,
+    /// Size of the reassembled blob.
+    pub size: u64,
+    pub chunks: Vec<ChunkRef>,
+}
+
+/// Read a downloaded blob as a chunk manifest. Anything else (i.e. every ordinary attachment,
+/// which is ciphertext) is `None`.
+pub fn parse_chunk_manifest(bytes: &[u8]) -> Option<ChunkManifest> {
+    // Cheap reject before any JSON parsing: a manifest is small and starts with an object
+    if bytes.len() > 64 * 1024 || bytes.first() != Some(&b'{') {
+        return None;
+    }
+    let manifest: ChunkManifest = serde_json::from_slice(bytes).ok()?;
+    let consistent = manifest.format == MANIFEST_FORMAT
+        && !manifest.chunks.is_empty()
+        && manifest.chunks.len() <= MAX_MANIFEST_CHUNKS
+        && manifest.chunks.iter().map(|c| c.size).sum::<u64>() == manifest.size;
+    consistent.then_some(manifest)
+}
+
+/// Whether a blob we stored earlier is still on its server.
+async fn blob_exists(url: &str) -> bool {
+    let Ok(client) = crate::net::build_http_client(std::time::Duration::from_secs(10)) else { return false };
+    matches!(client.head(url).send().await, Ok(resp) if resp.status().is_success())
+}
+
+/// Upload `file_data` as chunk blobs plus a manifest, skipping chunks a previous attempt already
+/// stored. `upload_id` keys the progress records (the plaintext file hash), and the records are
+/// cleared once the manifest is up. Progress is reported across the whole blob, chunk by chunk.
+/// Each blob starts `start` servers along the ranked list (see [`upload_blob_ranked_from`]).
+pub async fn upload_chunked_with_failover<T>(
+    signer: T,
+    server_urls: Vec<String>,
+    upload_id: &str,
+    file_data: Arc<Vec<u8>>,
+    mime_type: Option<&str>,
+    progress_callback: ProgressCallback,
+    retry_count: Option<u32>,
+    retry_spacing: Option<std::time::Duration>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    start: usize,
+) -> Result<String, String>
+where
+    T: NostrSigner + Clone,
+{
+    let total = file_data.len() as u64;
+    let done_before = crate::db::uploads::uploaded_chunks(upload_id);
+    let mut chunks = Vec::with_capacity(file_data.len().div_ceil(CHUNK_SIZE));
+    let mut sent: u64 = 0;
+
+    for (idx, piece) in file_data.chunks(CHUNK_SIZE).enumerate() {
+        let idx = idx as u32;
+        let size = piece.len() as u64;
+        let sha256 = crate::crypto::sha256_hex(piece);
+
+        let stored = match done_before.get(&idx) {
+            Some((hash, url)) if *hash == sha256 && blob_exists(url).await => Some(url.clone()),
+            _ => None,
+        };
+        let url = match stored {
+            Some(url) => url,
+            None => {
+                let outer = progress_callback.clone();
+                let base = sent;
+                let chunk_progress: ProgressCallback = Arc::new(move |_, bytes| {
+                    let now = base + bytes.unwrap_or(0).min(size);
+                    outer(Some((now * 100 / total.max(1)) as u8), Some(now))
+                });
+                let url = upload_blob_ranked_from(
+                    signer.clone(), server_urls.clone(), Arc::new(piece.to_vec()), mime_type,
+                    /* is_encrypted */ true,
+                    chunk_progress, retry_count, retry_spacing, cancel_flag.clone(), start,
+                ).await?;
+                if let Err(e) = crate::db::uploads::record_chunk(upload_id, idx, &sha256, &url) {
+                    crate::log_warn!("[Blossom Chunks] {}", e);
+                }
+                url
+            }
+        };
+        sent += size;
+        progress_callback(Some((sent * 100 / total.max(1)) as u8), Some(sent))?;
+        chunks.push(ChunkRef { url, sha256, size });
+    }
+
+    let manifest = ChunkManifest { format: MANIFEST_FORMAT.to_string(), size: total, chunks };
+    let manifest_bytes = serde_json::to_vec(&manifest).map_err(|e| e.to_string())?;
+    // The manifest is small; progress already reads 100%
+    let quiet: ProgressCallback = Arc::new(|_, _| Ok(()));
+    let url = upload_blob_ranked_from(
+        signer, server_urls, Arc::new(manifest_bytes), Some("application/json"),
+        /* is_encrypted */ false,
+        quiet, retry_count, retry_spacing, cancel_flag, start,
+    ).await?;
+    if let Err(e) = crate::db::uploads::finish(upload_id) {
+        crate::log_warn!("[Blossom Chunks] {}", e);
+    }
+    crate::log_info!("[Blossom Chunks] Uploaded {} bytes in {} chunks", total, manifest.chunks.len());
+    Ok(url)
+}
+
+// ============================================================================
+// Mirroring — extra copies on other servers
+// ============================================================================
+//
+// With mirroring on (`blossom_servers::load_mirror_count`), a finished upload is copied to that
+// many more enabled servers and the message lists the copies as NIP-94 `fallback` URLs, so an
+// attachment outlives the server it was first stored on. Each server is first asked to fetch the
+// blob itself (BUD-04 `PUT /mirror`, which costs the sender no bandwidth); one that can't gets a
+// plain upload. Best effort: the message goes out with whichever copies landed. Chunked uploads
+// aren't mirrored, since their manifest points at chunks on specific servers.
+
+/// Most mirror URLs read from one attachment (they're author-controlled).
+pub const MAX_MIRROR_URLS: usize = 4;
+
+/// Whether two URLs are on the same host (a blob URL and a server URL differ in path).
+fn same_host(a: &str, b: &str) -> bool {
+    match (Url::parse(a), Url::parse(b)) {
+        (Ok(a), Ok(b)) => a.host_str() == b.host_str() && a.port_or_known_default() == b.port_or_known_default(),
+        _ => false,
+    }
+}
+
+/// Ask `server_url` to fetch `source_url` itself (BUD-04 `PUT /mirror`).
+async fn mirror_from<T>(signer: &T, server_url: &Url, source_url: &str, hash: Sha256Hash) -> Result<String, String>
+where
+    T: NostrSigner,
+{
+    let mirror_url = server_url.join("mirror")
+        .map_err(|e| format!("Invalid server URL: {}", e))?;
+    let auth_header = build_auth_header(signer, hash).await?;
+    let client = crate::net::build_http_client_with_options(
+        std::time::Duration::from_secs(120),
+        None,
+        false,
+    )?;
+    let response = client
+        .put(mirror_url)
+        .header(AUTHORIZATION, auth_header)
+        .json(&serde_json::json!({ "url": source_url }))
+        .send()
+        .await
+        .map_err(|e| format!("Mirror request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Mirror failed with status {}", status));
+    }
+    let descriptor: BlobDescriptor = response.json().await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    if descriptor.sha256 != hash {
+        return Err(format!(
+            "[INTEGRITY] {} mirrored a different blob (returned {}, expected {})",
+            server_url, descriptor.sha256, hash,
+        ));
+    }
+    Ok(descriptor.url.to_string())
+}
+
+/// Copy a blob already stored at `primary_url` to up to `count` more of `servers`, in order,
+/// skipping the server that holds it. Returns the URLs of the copies that landed.
+pub async fn mirror_blob<T>(
+    signer: T,
+    servers: Vec<String>,
+    primary_url: &str,
+    file_data: Arc<Vec<u8>>,
+    mime_type: Option<&str>,
+    count: usize,
+) -> Vec<String>
+where
+    T: NostrSigner + Clone,
+{
+    let mut mirrors: Vec<String> = Vec::new();
+    if count == 0 {
+        return mirrors;
+    }
+    let hash = Sha256Hash::hash(&*file_data);
+    for server in servers.iter().filter(|s| !same_host(s, primary_url)) {
+        if mirrors.len() >= count {
+            break;
+        }
+        let Ok(server_url) = Url::parse(server) else { continue };
+        let copied = match mirror_from(&signer, &server_url, primary_url, hash).await {
+            Ok(url) => Ok(url),
+            Err(e) => {
+                crate::log_info!("[Blossom Mirror] {} didn't mirror ({}), uploading instead", server, e);
+                upload_blob(signer.clone(), &server_url, file_data.clone(), mime_type, None).await
+            }
+        };
+        match copied {
+            Ok(url) => {
+                crate::blossom_health::record_upload(server, true);
+                if url != primary_url && !mirrors.contains(&url) {
+                    mirrors.push(url);
+                }
+            }
+            Err(e) => crate::log_warn!("[Blossom Mirror] Copy to {} failed: {}", server, e),
+        }
+    }
+    crate::log_info!("[Blossom Mirror] {} of {} copies stored", mirrors.len(), count);
+    mirrors
+}
+
+/// The usable mirror URLs an attachment carries: http(s) only, without repeats of `primary_url`,
+/// at most [`MAX_MIRROR_URLS`].
+pub fn mirror_urls<'a>(urls: impl Iterator<Item = &'a str>, primary_url: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for url in urls.map(str::trim) {
+        if out.len() >= MAX_MIRROR_URLS {
+            break;
+        }
+        let usable = (url.starts_with("https://") || url.starts_with("http://")) && url.len() <= 2048;
+        if usable && url != primary_url && !out.iter().any(|u| u == url) {
+            out.push(url.to_string());
+        }
+    }
+    out
+}
+
 // ============================================================================
 // Blossom DELETE — paired with NIP-17 message deletion
 // ============================================================================
@@ -969,3 +1253,62 @@ mod hash_extract_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod chunk_manifest_tests {
+    use super::*;
+
+    fn manifest(sizes: &[u64]) -> ChunkManifest {
+        ChunkManifest {
+            format: MANIFEST_FORMAT.to_string(),
+            size: sizes.iter().sum(),
+            chunks: sizes.iter().enumerate().map(|(i, &size)| ChunkRef {
+                url: format!("https://srv.example/{}", i),
+                sha256: "00".repeat(32),
+                size,
+            }).collect(),
+        }
+    }
+
+    #[test]
+    fn manifests_roundtrip_and_ciphertext_is_not_one() {
+        let m = manifest(&[CHUNK_SIZE as u64, 10]);
+        let bytes = serde_json::to_vec(&m).unwrap();
+        assert_eq!(parse_chunk_manifest(&bytes), Some(m.clone()));
+
+        // Sizes that don't add up, or an unknown format, aren't trusted
+        let mut lying = m.clone();
+        lying.size += 1;
+        assert_eq!(parse_chunk_manifest(&serde_json::to_vec(&lying).unwrap()), None);
+        let mut other = m;
+        other.format = "something-else".into();
+        assert_eq!(parse_chunk_manifest(&serde_json::to_vec(&other).unwrap()), None);
+
+        assert_eq!(parse_chunk_manifest(&[0x8f, 0x12, 0x00, 0x7b]), None);
+        assert_eq!(parse_chunk_manifest(b"{\"not\":\"a manifest\"}"), None);
+    }
+}
+
+#[cfg(test)]
+mod mirror_tests {
+    use super::*;
+
+    #[test]
+    fn mirror_urls_are_sanitized() {
+        let primary = "https://a.example/abc";
+        let urls = [
+            "https://b.example/abc", primary, "javascript:alert(1)", "https://b.example/abc",
+            " http://c.example/abc ", "https://d.example/abc", "https://e.example/abc", "https://f.example/abc",
+        ];
+        assert_eq!(
+            mirror_urls(urls.into_iter(), primary),
+            vec!["https://b.example/abc", "http://c.example/abc", "https://d.example/abc", "https://e.example/abc"],
+        );
+    }
+
+    #[test]
+    fn the_primary_server_is_not_a_mirror() {
+        assert!(same_host("https://blossom.primal.net/", "https://blossom.primal.net/abc.png"));
+        assert!(!same_host("https://nostr.download", "https://blossom.primal.net/abc.png"));
+    }
+}
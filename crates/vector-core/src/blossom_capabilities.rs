@@ -1,7 +1,8 @@
 //! Per-(server, mime, encrypted) Blossom capability cache and routing.
 //!
 //! Uploads report outcomes here; `rank_servers()` reorders the enabled
-//! list so known-good servers are tried first.
+//! list so known-good servers are tried first (healthiest first within
+//! a tier, see `blossom_health`).
 
 use std::collections::HashMap;
 use serde::Serialize;
@@ -31,9 +32,7 @@ pub struct CapabilityEntry {
 }
 
 fn now_secs() -> i64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs() as i64).unwrap_or(0)
+    crate::clock::now_secs() as i64
 }
 
 fn norm_url(url: &str) -> String {
@@ -254,7 +253,8 @@ pub fn classify(
 pub fn rank_servers(servers: Vec<String>, mime: &str, is_encrypted: bool, size_bytes: u64) -> Vec<String> {
     if servers.is_empty() { return servers; }
     let cache = load_cache_for(&servers, mime, is_encrypted).unwrap_or_default();
-    classify(&cache, servers, size_bytes)
+    // `classify` is stable within a tier, so health order breaks its ties.
+    classify(&cache, crate::blossom_health::order(servers), size_bytes)
 }
 
 /// Pre-flight check: is there any enabled server we haven't already
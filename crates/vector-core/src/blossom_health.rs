@@ -0,0 +1,254 @@
+//! Blossom server health: reachability checks and upload success rates.
+//!
+//! Every [`CHECK_INTERVAL`] each enabled server gets an unauthenticated `HEAD /upload` and we time
+//! the answer. Any status below 500 counts as up (BUD-06 servers turn a bare preflight away with
+//! 401/405/411, which is still a live server); a 5xx, refused connection or timeout counts as down.
+//! On top of that, every upload through the failover path reports whether it landed. Counters are
+//! halved every [`WINDOW`] uploads, so a server that recovers climbs back on its own.
+//!
+//! [`order`] feeds `blossom_capabilities::rank_servers`: within each capability tier, healthy
+//! servers go first, the fastest of them first unless the user turned that off (then the list
+//! order they chose wins). RAM-only like `relay_health`; a fresh session starts from the next check.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use crate::clock::now_secs;
+
+const PREFER_FASTEST_KEY: &str = "blossom_prefer_fastest";
+
+/// How often the enabled servers are checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// A check slower than this counts as down.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Uploads seen before a server's success rate is judged.
+const MIN_UPLOADS: u64 = 3;
+
+/// Upload counters are halved once they cover this many uploads.
+const WINDOW: u64 = 20;
+
+/// Share of failed uploads that marks a server unhealthy.
+const FAILURE_RATIO: f64 = 0.5;
+
+/// Latencies within the same bucket tie, so jitter doesn't reshuffle close servers.
+const LATENCY_BUCKET_MS: u64 = 100;
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Health {
+    /// Last check's round trip; `None` until checked or when it failed.
+    latency_ms: Option<u64>,
+    /// Last check's verdict; `None` until checked.
+    reachable: Option<bool>,
+    checked_at: u64,
+    uploads_ok: u64,
+    uploads_failed: u64,
+}
+
+impl Health {
+    fn add_upload(&mut self, ok: bool) {
+        if ok {
+            self.uploads_ok += 1;
+        } else {
+            self.uploads_failed += 1;
+        }
+        if self.uploads_ok + self.uploads_failed >= WINDOW {
+            self.uploads_ok /= 2;
+            self.uploads_failed /= 2;
+        }
+    }
+
+    /// Why this server is unhealthy, if it is.
+    fn unhealthy_reason(&self) -> Option<&'static str> {
+        if self.reachable == Some(false) {
+            return Some("unreachable");
+        }
+        let total = self.uploads_ok + self.uploads_failed;
+        if total >= MIN_UPLOADS && self.uploads_failed as f64 >= total as f64 * FAILURE_RATIO {
+            return Some("failing");
+        }
+        None
+    }
+
+    /// Sort key: healthy first, then (when preferring speed) measured before unmeasured, fastest
+    /// bucket first.
+    fn rank_key(&self, prefer_fastest: bool) -> (bool, u64) {
+        let speed = match (prefer_fastest, self.latency_ms) {
+            (false, _) => 0,
+            (true, Some(ms)) => ms / LATENCY_BUCKET_MS,
+            (true, None) => u64::MAX,
+        };
+        (self.unhealthy_reason().is_some(), speed)
+    }
+}
+
+/// One server's health as the Media Servers list shows it.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct BlossomServerHealth {
+    pub url: String,
+    pub latency_ms: Option<u64>,
+    /// When the last check ran (secs); 0 when never.
+    pub checked_at: u64,
+    pub uploads_ok: u64,
+    pub uploads_failed: u64,
+    pub healthy: bool,
+    /// "unreachable" or "failing" when unhealthy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+}
+
+static REGISTRY: LazyLock<Mutex<HashMap<String, Health>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn norm_url(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_lowercase()
+}
+
+/// Whether uploads try the fastest healthy server first (on unless the user turned it off).
+pub fn prefer_fastest() -> bool {
+    !matches!(crate::db::settings::get_sql_setting(PREFER_FASTEST_KEY.to_string()), Ok(Some(v)) if v == "false")
+}
+
+pub fn set_prefer_fastest(enabled: bool) -> Result<(), String> {
+    crate::db::settings::set_sql_setting(PREFER_FASTEST_KEY.to_string(), enabled.to_string())
+}
+
+/// Report whether an upload to `server_url` landed. Only outages count as failures: a server that
+/// refused the blob's type or size is healthy, just not for that blob (the capability cache
+/// handles those).
+pub fn record_upload(server_url: &str, ok: bool) {
+    REGISTRY.lock().unwrap().entry(norm_url(server_url)).or_default().add_upload(ok);
+}
+
+fn record_check(server_url: &str, latency_ms: Option<u64>) {
+    let mut registry = REGISTRY.lock().unwrap();
+    let health = registry.entry(norm_url(server_url)).or_default();
+    health.latency_ms = latency_ms;
+    health.reachable = Some(latency_ms.is_some());
+    health.checked_at = now_secs();
+}
+
+fn order_with(registry: &HashMap<String, Health>, mut servers: Vec<String>, prefer_fastest: bool) -> Vec<String> {
+    servers.sort_by_key(|s| registry.get(&norm_url(s)).copied().unwrap_or_default().rank_key(prefer_fastest));
+    servers
+}
+
+/// Reorder `servers` by health (stable, so ties keep the user's order).
+pub fn order(servers: Vec<String>) -> Vec<String> {
+    let prefer_fastest = prefer_fastest();
+    order_with(&REGISTRY.lock().unwrap(), servers, prefer_fastest)
+}
+
+/// Health of each of `servers`, in the given order.
+pub fn stats(servers: &[String]) -> Vec<BlossomServerHealth> {
+    let registry = REGISTRY.lock().unwrap();
+    servers.iter()
+        .map(|url| {
+            let h = registry.get(&norm_url(url)).copied().unwrap_or_default();
+            BlossomServerHealth {
+                url: url.clone(),
+                latency_ms: h.latency_ms,
+                checked_at: h.checked_at,
+                uploads_ok: h.uploads_ok,
+                uploads_failed: h.uploads_failed,
+                healthy: h.unhealthy_reason().is_none(),
+                reason: h.unhealthy_reason(),
+            }
+        })
+        .collect()
+}
+
+/// Forget a server's scores (hard remove, so a re-add starts clean).
+pub fn forget(server_url: &str) {
+    REGISTRY.lock().unwrap().remove(&norm_url(server_url));
+}
+
+/// Forget every score (account swap).
+pub fn clear_session() {
+    REGISTRY.lock().unwrap().clear();
+}
+
+/// Time one server's answer to a bare upload preflight; `None` when it's down.
+async fn check_server(client: &reqwest::Client, server_url: &str) -> Option<u64> {
+    let url = format!("{}/upload", server_url.trim_end_matches('/'));
+    let started = Instant::now();
+    match tokio::time::timeout(CHECK_TIMEOUT, client.head(&url).send()).await {
+        Ok(Ok(resp)) if !resp.status().is_server_error() => Some(started.elapsed().as_millis() as u64),
+        _ => None,
+    }
+}
+
+/// Check every enabled server now (concurrently) and emit `blossom_health_updated`.
+pub async fn check_all() -> Result<(), String> {
+    let servers = crate::state::get_blossom_servers();
+    let client = crate::net::build_http_client(CHECK_TIMEOUT)?;
+    let results = futures_util::future::join_all(servers.iter().map(|s| check_server(&client, s))).await;
+    for (server, latency) in servers.iter().zip(results) {
+        if latency.is_none() {
+            crate::log_warn!("[BlossomHealth] {} is unreachable", server);
+        }
+        record_check(server, latency);
+    }
+    crate::traits::emit_event("blossom_health_updated", &());
+    Ok(())
+}
+
+/// Session generation whose check loop is running, so a re-sync doesn't start a second one.
+static LOOP_GENERATION: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// Check the enabled servers now and every [`CHECK_INTERVAL`] for the rest of this session.
+pub fn spawn_health_checks() {
+    let generation = crate::state::current_session_generation();
+    if LOOP_GENERATION.swap(generation, Ordering::SeqCst) == generation {
+        return;
+    }
+    let session = crate::state::SessionGuard::capture();
+    tokio::spawn(async move {
+        loop {
+            if !session.is_valid() {
+                return;
+            }
+            if let Err(e) = check_all().await {
+                crate::log_warn!("[BlossomHealth] Check pass failed: {}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn servers(urls: &[&str]) -> Vec<String> {
+        urls.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn healthy_fast_servers_go_first() {
+        let mut registry = HashMap::new();
+        registry.insert("https://a".to_string(), Health { latency_ms: Some(900), reachable: Some(true), ..Default::default() });
+        registry.insert("https://b".to_string(), Health { reachable: Some(false), ..Default::default() });
+        registry.insert("https://c".to_string(), Health { latency_ms: Some(120), reachable: Some(true), ..Default::default() });
+        let list = servers(&["https://a", "https://b", "https://c", "https://d"]);
+
+        assert_eq!(order_with(&registry, list.clone(), true), servers(&["https://c", "https://a", "https://d", "https://b"]));
+        // Without the speed preference only the down server moves
+        assert_eq!(order_with(&registry, list, false), servers(&["https://a", "https://c", "https://d", "https://b"]));
+    }
+
+    #[test]
+    fn failing_uploads_mark_a_server_unhealthy_until_it_recovers() {
+        let mut h = Health { latency_ms: Some(50), reachable: Some(true), ..Default::default() };
+        h.add_upload(false);
+        h.add_upload(false);
+        assert_eq!(h.unhealthy_reason(), None, "not judged on a small sample");
+        h.add_upload(false);
+        assert_eq!(h.unhealthy_reason(), Some("failing"));
+
+        (0..WINDOW * 2).for_each(|_| h.add_upload(true));
+        assert_eq!(h.unhealthy_reason(), None);
+        assert!(h.uploads_ok + h.uploads_failed < WINDOW);
+    }
+}
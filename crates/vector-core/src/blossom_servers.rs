@@ -4,7 +4,11 @@
 //!   * `custom_blossom_servers`             — `Vec<CustomBlossomServer>` JSON
 //!   * `disabled_default_blossom_servers`   — `Vec<String>` JSON
 //!
-//! Resolution order (BUD-03 trust order): enabled defaults, then enabled customs.
+//!   * `blossom_server_order`               — `Vec<String>` JSON, the user's manual order
+//!
+//! Resolution order (BUD-03 trust order): enabled customs, then enabled defaults, unless the user
+//! reordered the list, in which case their order wins. Uploads further prefer healthy servers
+//! (`blossom_health`).
 
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::collections::HashSet;
@@ -98,6 +102,54 @@ pub fn save_disabled_default_blossom_servers(urls: &[String]) -> Result<(), Stri
     crate::db::set_sql_setting("disabled_default_blossom_servers".to_string(), json)
 }
 
+pub fn load_server_order() -> Vec<String> {
+    crate::db::get_sql_setting("blossom_server_order".to_string())
+        .ok().flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_server_order(urls: &[String]) -> Result<(), String> {
+    let json = serde_json::to_string(urls)
+        .map_err(|e| format!("Failed to serialize blossom server order: {}", e))?;
+    crate::db::set_sql_setting("blossom_server_order".to_string(), json)
+}
+
+/// Most extra copies of an attachment [`crate::blossom::mirror_blob`] makes.
+pub const MAX_MIRRORS: usize = 3;
+
+/// How many other servers each attachment is mirrored to; 0 (the default) is off.
+pub fn load_mirror_count() -> usize {
+    crate::db::get_sql_setting("blossom_mirror_count".to_string())
+        .ok().flatten()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(MAX_MIRRORS)
+}
+
+pub fn save_mirror_count(count: usize) -> Result<(), String> {
+    if count > MAX_MIRRORS {
+        return Err(format!("At most {} mirrors", MAX_MIRRORS));
+    }
+    crate::db::set_sql_setting("blossom_mirror_count".to_string(), count.to_string())
+}
+
+/// Stable-sort `items` by their URL's position in `order`; URLs the order doesn't mention (added
+/// since) keep their relative place after the ordered ones.
+pub fn apply_order<T>(mut items: Vec<T>, order: &[String], url_of: impl Fn(&T) -> &str) -> Vec<T> {
+    if order.is_empty() {
+        return items;
+    }
+    let position = |url: &str| {
+        let key = url.trim().trim_end_matches('/').to_lowercase();
+        order.iter()
+            .position(|o| o.trim().trim_end_matches('/').to_lowercase() == key)
+            .unwrap_or(usize::MAX)
+    };
+    items.sort_by_key(|item| position(url_of(item)));
+    items
+}
+
 // ============================================================================
 // Resolver — enabled customs (first) + defaults (minus disabled)
 // ============================================================================
@@ -140,10 +192,10 @@ pub fn compute_enabled_servers() -> Vec<String> {
             out.push((*d).to_string());
         }
     }
-    out
+    apply_order(out, &load_server_order(), |s| s.as_str())
 }
 
-/// All rows for the frontend (defaults then customs, including disabled).
+/// All rows for the frontend (defaults then customs, or the user's order; including disabled).
 pub fn list_all_servers() -> Vec<BlossomServerInfo> {
     let disabled = load_disabled_default_blossom_servers().unwrap_or_default();
     let disabled_lower: HashSet<String> = disabled.iter()
@@ -168,7 +220,7 @@ pub fn list_all_servers() -> Vec<BlossomServerInfo> {
             enabled: c.enabled,
         });
     }
-    out
+    apply_order(out, &load_server_order(), |s| s.url.as_str())
 }
 
 /// Refresh the in-memory `BLOSSOM_SERVERS` cache. Call after edits + on login.
@@ -390,6 +442,22 @@ mod tests {
         assert!(!is_default_server("https://other.example.com"));
     }
 
+    #[test]
+    fn manual_order_wins_and_unlisted_servers_trail() {
+        let servers = vec![
+            "https://a.example.com".to_string(),
+            "https://b.example.com".to_string(),
+            "https://c.example.com".to_string(),
+            "https://d.example.com".to_string(),
+        ];
+        let order = vec!["https://C.example.com/".to_string(), "https://a.example.com".to_string()];
+        assert_eq!(
+            apply_order(servers.clone(), &order, |s| s.as_str()),
+            vec!["https://c.example.com", "https://a.example.com", "https://b.example.com", "https://d.example.com"],
+        );
+        assert_eq!(apply_order(servers.clone(), &[], |s| s.as_str()), servers);
+    }
+
     #[test]
     fn merge_appends_new_urls_normalized() {
         let incoming = vec![
@@ -432,7 +432,7 @@ pub fn typed_args(spec: &CommandSpec, parsed: &ParsedCommand) -> Result<HashMap<
                 // mention as the NIP-21 `nostr:npub1…` URI — accept it and normalize
                 // back to the bare npub. Parsing also rejects a bad bech32 checksum.
                 let raw = v.strip_prefix("nostr:").unwrap_or(v);
-                if !raw.starts_with("npub1") {
+                if !crate::ids::is_npub(raw) {
                     return Err(format!("{k}: not an npub"));
                 }
                 let pk = nostr_sdk::prelude::PublicKey::parse(raw).map_err(|_| format!("{k}: not an npub"))?;
@@ -0,0 +1,95 @@
+//! Blind Diffie-Hellman key exchange (NUT-00): how a mint signs ecash without seeing it.
+//!
+//! The wallet maps a proof's secret to a curve point Y and blinds it with a random factor r
+//! (`B_ = Y + rG`). The mint signs the blinded point with its key k for that amount (`C_ = kB_`),
+//! and the wallet unblinds the signature with the mint's public key K (`C = C_ - rK = kY`). The
+//! pair (secret, C) is a proof the mint accepts once, without being able to link it to the
+//! signing.
+
+use std::sync::LazyLock;
+
+use nostr::secp256k1::{All, PublicKey, Scalar, Secp256k1, SecretKey};
+use sha2::{Digest, Sha256};
+
+static SECP: LazyLock<Secp256k1<All>> = LazyLock::new(Secp256k1::new);
+
+const DOMAIN_SEPARATOR: &[u8] = b"Secp256k1_HashToCurve_Cashu_";
+
+/// A blinded secret: the point the mint signs, and the factor that unblinds its signature.
+pub struct Blinded {
+    /// `B_`
+    pub point: PublicKey,
+    /// `r`
+    pub factor: SecretKey,
+}
+
+/// Map `message` (a proof's secret) to the curve point Y.
+pub fn hash_to_curve(message: &[u8]) -> Result<PublicKey, String> {
+    let msg_hash = Sha256::new().chain_update(DOMAIN_SEPARATOR).chain_update(message).finalize();
+    for counter in 0u32..(1 << 16) {
+        let hash = Sha256::new().chain_update(msg_hash).chain_update(counter.to_le_bytes()).finalize();
+        let mut candidate = [0u8; 33];
+        candidate[0] = 0x02;
+        candidate[1..].copy_from_slice(&hash);
+        if let Ok(point) = PublicKey::from_slice(&candidate) {
+            return Ok(point);
+        }
+    }
+    Err("No curve point for this secret".to_string())
+}
+
+/// Blind `secret` with a fresh random factor.
+pub fn blind(secret: &[u8]) -> Result<Blinded, String> {
+    blind_with(secret, random_secret_key())
+}
+
+fn blind_with(secret: &[u8], factor: SecretKey) -> Result<Blinded, String> {
+    let y = hash_to_curve(secret)?;
+    let r_g = PublicKey::from_secret_key(&SECP, &factor);
+    let point = y.combine(&r_g).map_err(|e| e.to_string())?;
+    Ok(Blinded { point, factor })
+}
+
+/// Unblind the mint's signature `C_` into `C`, given the mint's public key for the amount.
+pub fn unblind(signature: &PublicKey, factor: &SecretKey, mint_key: &PublicKey) -> Result<PublicKey, String> {
+    let r_k = mint_key.mul_tweak(&SECP, &Scalar::from(*factor)).map_err(|e| e.to_string())?;
+    signature.combine(&r_k.negate(&SECP)).map_err(|e| e.to_string())
+}
+
+fn random_secret_key() -> SecretKey {
+    loop {
+        let bytes: [u8; 32] = rand::random();
+        if let Ok(key) = SecretKey::from_slice(&bytes) {
+            return key;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_to_curve_matches_spec_vector() {
+        // NUT-00 test vector: the all-zero 32-byte message
+        let y = hash_to_curve(&[0u8; 32]).unwrap();
+        assert_eq!(
+            crate::simd::hex::bytes_to_hex_string(&y.serialize()),
+            "024cce997d3b518f739663b757deaec95bcd9473c30a14ac2fd04023a739d1a725"
+        );
+    }
+
+    #[test]
+    fn unblinded_signature_is_mint_key_times_y() {
+        let mint_secret = SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let mint_key = PublicKey::from_secret_key(&SECP, &mint_secret);
+        let secret = b"407915bc212be61a77e3e6d2aeb4c727980bda51cd06a6afc29e2861768a7837";
+
+        let blinded = blind(secret).unwrap();
+        let signature = blinded.point.mul_tweak(&SECP, &Scalar::from(mint_secret)).unwrap();
+        let c = unblind(&signature, &blinded.factor, &mint_key).unwrap();
+
+        let expected = hash_to_curve(secret).unwrap().mul_tweak(&SECP, &Scalar::from(mint_secret)).unwrap();
+        assert_eq!(c, expected);
+    }
+}
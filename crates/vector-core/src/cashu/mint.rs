@@ -0,0 +1,305 @@
+//! A mint's HTTP API (NUT-01 to NUT-09), over the shared proxy-aware client. Mint URLs come from
+//! tokens other people send, so every request is held to public hosts and a capped response.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::token::Proof;
+
+/// Cap per mint request; a melt waits on a Lightning payment, so it gets longer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const MELT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Largest mint response read; keysets, signatures and states are a few KiB.
+const MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// A blinded secret for the mint to sign.
+#[derive(Serialize, Clone, Debug)]
+pub struct BlindedMessage {
+    pub amount: u64,
+    /// Keyset id to sign with.
+    pub id: String,
+    #[serde(rename = "B_")]
+    pub b: String,
+}
+
+/// The mint's signature on a [`BlindedMessage`], still blinded.
+#[derive(Deserialize, Clone, Debug)]
+pub struct BlindSignature {
+    pub amount: u64,
+    pub id: String,
+    #[serde(rename = "C_")]
+    pub c: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct KeysetInfo {
+    pub id: String,
+    pub unit: String,
+    pub active: bool,
+    /// Fee per input spent from this keyset, in parts per thousand of a sat.
+    #[serde(default)]
+    pub input_fee_ppk: u64,
+}
+
+#[derive(Deserialize)]
+struct Keyset {
+    /// Public key (hex) per amount.
+    keys: HashMap<String, String>,
+}
+
+/// A Lightning invoice that mints ecash once paid.
+#[derive(Deserialize, Clone, Debug)]
+pub struct MintQuote {
+    pub quote: String,
+    /// The BOLT11 invoice.
+    pub request: String,
+    /// `UNPAID`, `PAID` or `ISSUED` (older mints send `paid` instead).
+    #[serde(default)]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub paid: Option<bool>,
+    #[serde(default)]
+    pub expiry: Option<u64>,
+}
+
+impl MintQuote {
+    pub fn is_paid(&self) -> bool {
+        match self.state.as_deref() {
+            Some(state) => state == "PAID",
+            None => self.paid == Some(true),
+        }
+    }
+
+    pub fn is_issued(&self) -> bool {
+        self.state.as_deref() == Some("ISSUED")
+    }
+}
+
+/// What paying a Lightning invoice from ecash costs.
+#[derive(Deserialize, Clone, Debug)]
+pub struct MeltQuote {
+    pub quote: String,
+    pub amount: u64,
+    /// Most the routing fee can be; the unused part comes back as change.
+    pub fee_reserve: u64,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct MeltResponse {
+    #[serde(default)]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub paid: Option<bool>,
+    #[serde(default)]
+    pub payment_preimage: Option<String>,
+    #[serde(default)]
+    pub change: Option<Vec<BlindSignature>>,
+}
+
+impl MeltResponse {
+    pub fn is_paid(&self) -> bool {
+        match self.state.as_deref() {
+            Some(state) => state == "PAID",
+            None => self.paid == Some(true),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ProofState {
+    #[serde(rename = "Y")]
+    pub y: String,
+    /// `UNSPENT`, `PENDING` or `SPENT`.
+    pub state: String,
+}
+
+#[derive(Deserialize)]
+struct KeysetsResponse<T> {
+    keysets: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct SignaturesResponse {
+    signatures: Vec<BlindSignature>,
+}
+
+#[derive(Deserialize)]
+struct StatesResponse {
+    states: Vec<ProofState>,
+}
+
+#[derive(Deserialize)]
+struct RestoreResponse {
+    outputs: Vec<RestoredOutput>,
+    /// Named `promises` by older mints.
+    #[serde(alias = "promises")]
+    signatures: Vec<BlindSignature>,
+}
+
+#[derive(Deserialize)]
+struct RestoredOutput {
+    #[serde(rename = "B_")]
+    b: String,
+}
+
+/// A mint URL in canonical form: https (or http to a .onion) on a public host, no trailing slash.
+pub fn normalize_url(url: &str) -> Result<String, String> {
+    let parsed = url::Url::parse(url.trim()).map_err(|_| "Invalid mint URL".to_string())?;
+    crate::net::validate_url_not_private(parsed.as_str()).map_err(|e| format!("Invalid mint URL: {}", e))?;
+    let onion = parsed.host_str().is_some_and(|h| h.ends_with(".onion"));
+    if parsed.scheme() != "https" && !(parsed.scheme() == "http" && onion) {
+        return Err("Mints must use https".to_string());
+    }
+    if parsed.query().is_some() || parsed.fragment().is_some() || !parsed.username().is_empty() {
+        return Err("Invalid mint URL".to_string());
+    }
+    Ok(parsed.as_str().trim_end_matches('/').to_string())
+}
+
+pub async fn keysets(mint: &str) -> Result<Vec<KeysetInfo>, String> {
+    let response: KeysetsResponse<KeysetInfo> = get(mint, "/v1/keysets").await?;
+    Ok(response.keysets)
+}
+
+/// A keyset's public keys by amount.
+pub async fn keys(mint: &str, keyset_id: &str) -> Result<HashMap<u64, String>, String> {
+    let response: KeysetsResponse<Keyset> = get(mint, &format!("/v1/keys/{}", path_segment(keyset_id)?)).await?;
+    let keyset = response.keysets.into_iter().next().ok_or("The mint returned no keys")?;
+    Ok(keyset.keys.into_iter().filter_map(|(amount, key)| Some((amount.parse().ok()?, key))).collect())
+}
+
+/// Spend `inputs` for fresh signatures on `outputs` (NUT-03).
+pub async fn swap(mint: &str, inputs: &[Proof], outputs: &[BlindedMessage]) -> Result<Vec<BlindSignature>, String> {
+    let body = serde_json::json!({ "inputs": inputs, "outputs": outputs });
+    let response: SignaturesResponse = post(mint, "/v1/swap", &body, REQUEST_TIMEOUT).await?;
+    Ok(response.signatures)
+}
+
+pub async fn mint_quote(mint: &str, amount: u64) -> Result<MintQuote, String> {
+    let body = serde_json::json!({ "amount": amount, "unit": "sat" });
+    post(mint, "/v1/mint/quote/bolt11", &body, REQUEST_TIMEOUT).await
+}
+
+pub async fn mint_quote_state(mint: &str, quote: &str) -> Result<MintQuote, String> {
+    get(mint, &format!("/v1/mint/quote/bolt11/{}", path_segment(quote)?)).await
+}
+
+/// Collect the signatures for a paid mint quote (NUT-04).
+pub async fn mint(mint: &str, quote: &str, outputs: &[BlindedMessage]) -> Result<Vec<BlindSignature>, String> {
+    let body = serde_json::json!({ "quote": quote, "outputs": outputs });
+    let response: SignaturesResponse = post(mint, "/v1/mint/bolt11", &body, REQUEST_TIMEOUT).await?;
+    Ok(response.signatures)
+}
+
+pub async fn melt_quote(mint: &str, invoice: &str) -> Result<MeltQuote, String> {
+    let body = serde_json::json!({ "request": invoice, "unit": "sat" });
+    post(mint, "/v1/melt/quote/bolt11", &body, REQUEST_TIMEOUT).await
+}
+
+/// Pay a melt quote with `inputs`; `outputs` are blank, for the fee change (NUT-05, NUT-08).
+pub async fn melt(mint: &str, quote: &str, inputs: &[Proof], outputs: &[BlindedMessage]) -> Result<MeltResponse, String> {
+    let body = serde_json::json!({ "quote": quote, "inputs": inputs, "outputs": outputs });
+    post(mint, "/v1/melt/bolt11", &body, MELT_TIMEOUT).await
+}
+
+/// The mint's signatures on whichever of `outputs` it signed before, by blinded message (NUT-09).
+pub async fn restore(mint: &str, outputs: &[BlindedMessage]) -> Result<HashMap<String, BlindSignature>, String> {
+    let body = serde_json::json!({ "outputs": outputs });
+    let response: RestoreResponse = post(mint, "/v1/restore", &body, REQUEST_TIMEOUT).await?;
+    if response.outputs.len() != response.signatures.len() {
+        return Err("The mint returned an invalid response".to_string());
+    }
+    Ok(response.outputs.into_iter().map(|o| o.b).zip(response.signatures).collect())
+}
+
+/// Spent state of proofs by their Y (NUT-07).
+pub async fn check_state(mint: &str, ys: &[String]) -> Result<Vec<ProofState>, String> {
+    let body = serde_json::json!({ "Ys": ys });
+    let response: StatesResponse = post(mint, "/v1/checkstate", &body, REQUEST_TIMEOUT).await?;
+    Ok(response.states)
+}
+
+/// `path` on `mint`, checked again here since it's what the request goes to (redirects are
+/// checked by the client).
+fn endpoint(mint: &str, path: &str) -> Result<String, String> {
+    let url = format!("{}{}", mint, path);
+    crate::net::validate_url_not_private(&url).map_err(|e| format!("Invalid mint URL: {}", e))?;
+    Ok(url)
+}
+
+async fn get<T: DeserializeOwned>(mint: &str, path: &str) -> Result<T, String> {
+    let response = crate::net::shared_http_client()
+        .get(endpoint(mint, path)?)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Couldn't reach the mint: {}", e))?;
+    read(response).await
+}
+
+async fn post<T: DeserializeOwned>(mint: &str, path: &str, body: &serde_json::Value, timeout: Duration) -> Result<T, String> {
+    let response = crate::net::shared_http_client()
+        .post(endpoint(mint, path)?)
+        .timeout(timeout)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| format!("Couldn't reach the mint: {}", e))?;
+    read(response).await
+}
+
+/// Decode a mint response, surfacing its `detail` message on errors. Reads at most
+/// [`MAX_RESPONSE_BYTES`].
+async fn read<T: DeserializeOwned>(response: reqwest::Response) -> Result<T, String> {
+    use futures_util::StreamExt;
+    let status = response.status();
+    if response.content_length().is_some_and(|len| len > MAX_RESPONSE_BYTES as u64) {
+        return Err("The mint's response is too large".to_string());
+    }
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Couldn't reach the mint: {}", e))?;
+        if body.len() + chunk.len() > MAX_RESPONSE_BYTES {
+            return Err("The mint's response is too large".to_string());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    if !status.is_success() {
+        let detail = serde_json::from_slice::<serde_json::Value>(&body).ok()
+            .and_then(|v| v.get("detail").and_then(|d| d.as_str()).map(str::to_string))
+            .unwrap_or_else(|| status.to_string());
+        return Err(format!("The mint refused: {}", detail));
+    }
+    serde_json::from_slice(&body).map_err(|_| "The mint returned an invalid response".to_string())
+}
+
+/// Ids the mint hands us go into URL paths; they're alphanumeric (plus `-`/`_`) in practice.
+fn path_segment(id: &str) -> Result<&str, String> {
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        Ok(id)
+    } else {
+        Err("The mint returned an invalid id".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mint_urls_are_canonical() {
+        assert_eq!(normalize_url("https://mint.example.com/").unwrap(), "https://mint.example.com");
+        assert_eq!(normalize_url(" https://example.com/cashu/api/ ").unwrap(), "https://example.com/cashu/api");
+        assert!(normalize_url("http://mint.example.com").is_err());
+        assert!(normalize_url("http://abcdefghijklmnop.onion").is_ok());
+        assert!(normalize_url("https://mint.example.com/?x=1").is_err());
+        assert!(normalize_url("https://127.0.0.1:3338").is_err());
+        assert!(normalize_url("https://192.168.1.5").is_err());
+        assert!(normalize_url("https://localhost/mint").is_err());
+    }
+}
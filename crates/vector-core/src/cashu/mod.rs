@@ -0,0 +1,1027 @@
+//! Cashu ecash wallet — in-chat payments with bearer tokens.
+//!
+//! A mint issues ecash against Lightning sats. The wallet holds proofs (blind-signed notes, see
+//! [`dhke`]) and pays by handing them to someone, whose wallet swaps them at the mint for fresh
+//! ones before the sender could spend them again. In chat a token travels inside a gift-wrapped
+//! rumor tagged `d` = [`D_TAG`], like a PIVX payment, and the recipient's client redeems it on
+//! receipt if it's from a mint the wallet already uses ([`from_known_mint`]); others wait for a
+//! tap. The mint sees amounts and timing but, thanks to the blinding, not who paid whom.
+//!
+//! - Deposit: a mint quote yields a Lightning invoice; once it's paid, [`complete_deposit`] mints
+//!   the proofs (NUT-04).
+//! - Withdraw: [`withdraw`] pays a Lightning invoice from one mint's proofs (NUT-05), with the
+//!   unused fee reserve returned as change (NUT-08).
+//! - Send / receive: [`create_token`] splits proofs for the amount off at the mint (NUT-03);
+//!   [`receive_token`] swaps a token's proofs into ours.
+//!
+//! Proofs are money: each is stored encrypted at rest in `cashu_proofs`, keyed by its public
+//! point Y so spent checks (NUT-07) never need the secret. Operations run one at a time, so two
+//! sends can't pick the same proofs. Sent tokens stay in the history, encrypted, so one that
+//! never arrives can be taken back.
+//!
+//! The secrets and blinding factors of new outputs are saved (`cashu_pending`, encrypted) before
+//! the mint sees them, and dropped with the write that stores the proofs. A row left behind (a
+//! crash, an account swap or a failed write after the mint signed) is settled by
+//! [`recover_pending`]: the mint hands back its signatures (NUT-09) and the proofs land in the
+//! account that started the operation, at its next login if not before.
+
+pub mod dhke;
+pub mod mint;
+pub mod token;
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{LazyLock, Mutex};
+
+use nostr::secp256k1::{PublicKey as CurvePoint, SecretKey};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use crate::simd::hex::{bytes_to_hex_string, hex_string_to_bytes_checked};
+use mint::{BlindSignature, BlindedMessage, KeysetInfo};
+use token::{Proof, Token};
+
+/// The `d` tag of a chat rumor carrying an ecash token.
+pub const D_TAG: &str = "cashu-token";
+
+/// Largest deposit, send or withdrawal, in sats. Ecash is only as safe as the mint holding it,
+/// so this is a wallet for pocket money.
+pub const MAX_AMOUNT: u64 = 1_000_000;
+
+/// Longest memo kept on a sent token, in characters.
+const MAX_MEMO_CHARS: usize = 140;
+
+/// The only unit we hold.
+const UNIT: &str = "sat";
+
+/// Serializes wallet operations (see the module docs).
+static WALLET_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Mint public keys by (mint, keyset id); a published keyset never changes. Keyed by mint too, so a
+/// mint can't plant keys under another mint's keyset id.
+static KEYS: LazyLock<Mutex<HashMap<(String, String), HashMap<u64, CurvePoint>>>> = LazyLock::new(Default::default);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MintBalance {
+    pub mint: String,
+    pub amount: u64,
+}
+
+/// Sats held, per mint (largest first).
+#[derive(Serialize, Clone, Debug)]
+pub struct WalletBalance {
+    pub total: u64,
+    pub mints: Vec<MintBalance>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HistoryEntry {
+    pub id: i64,
+    /// `deposit`, `withdraw`, `send` or `receive`.
+    pub kind: String,
+    pub amount: u64,
+    /// Mint and Lightning fees paid.
+    pub fee: u64,
+    pub mint: String,
+    pub chat_id: Option<String>,
+    pub message_id: Option<String>,
+    /// Sends only: the token handed over.
+    pub token: Option<String>,
+    pub created_at: u64,
+}
+
+/// A deposit awaiting payment of its invoice.
+#[derive(Serialize, Clone, Debug)]
+pub struct Deposit {
+    pub quote: String,
+    pub invoice: String,
+    pub amount: u64,
+    pub mint: String,
+    pub expiry: Option<u64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Withdrawal {
+    pub amount: u64,
+    pub fee: u64,
+    pub preimage: Option<String>,
+}
+
+/// A token split off for sending, and its history row.
+pub struct SentToken {
+    pub token: Token,
+    pub encoded: String,
+    pub history_id: i64,
+}
+
+/// A proof held by the wallet.
+#[derive(Clone)]
+struct StoredProof {
+    y: String,
+    proof: Proof,
+}
+
+/// An output awaiting the mint's signature, with what unblinds it.
+struct PendingOutput {
+    message: BlindedMessage,
+    secret: String,
+    factor: SecretKey,
+}
+
+/// A [`PendingOutput`] as saved in `cashu_pending`.
+#[derive(Serialize, Deserialize)]
+struct SavedOutput {
+    amount: u64,
+    id: String,
+    #[serde(rename = "B_")]
+    b: String,
+    secret: String,
+    r: String,
+}
+
+/// An operation whose outputs went to the mint, saved until its proofs are stored.
+struct PendingOp<'a> {
+    kind: &'a str,
+    amount: u64,
+    fee: u64,
+    /// Our proofs it spends (none for a deposit or a received token).
+    inputs: &'a [StoredProof],
+    chat_id: Option<&'a str>,
+    message_id: Option<&'a str>,
+    quote_id: Option<&'a str>,
+}
+
+/// A `cashu_pending` row, loaded for recovery.
+struct PendingRow {
+    id: String,
+    mint: String,
+    kind: String,
+    amount: u64,
+    fee: u64,
+    inputs: Vec<String>,
+    input_total: u64,
+    outputs: Vec<PendingOutput>,
+    chat_id: Option<String>,
+    message_id: Option<String>,
+    quote_id: Option<String>,
+}
+
+struct ActiveKeyset {
+    id: String,
+    keys: HashMap<u64, CurvePoint>,
+}
+
+/// A history row to write alongside a change of proofs.
+struct Record<'a> {
+    kind: &'a str,
+    amount: u64,
+    fee: u64,
+    chat_id: Option<&'a str>,
+    message_id: Option<&'a str>,
+    token: Option<&'a str>,
+}
+
+// ============================================================================
+// Queries
+// ============================================================================
+
+pub fn balance() -> Result<WalletBalance, String> {
+    let conn = crate::db::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT mint_url, SUM(amount) AS total FROM cashu_proofs GROUP BY mint_url ORDER BY total DESC",
+    ).map_err(|e| format!("prepare ecash balance: {e}"))?;
+    let mints: Vec<MintBalance> = stmt
+        .query_map([], |r| Ok(MintBalance { mint: r.get(0)?, amount: r.get::<_, i64>(1)?.max(0) as u64 }))
+        .map_err(|e| format!("query ecash balance: {e}"))?
+        .flatten()
+        .collect();
+    Ok(WalletBalance { total: mints.iter().map(|m| m.amount).sum(), mints })
+}
+
+/// The most recent `limit` history entries, newest first.
+pub fn history(limit: u32) -> Result<Vec<HistoryEntry>, String> {
+    let conn = crate::db::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, amount, fee, mint_url, chat_id, message_id, token, created_at
+         FROM cashu_history ORDER BY created_at DESC, id DESC LIMIT ?1",
+    ).map_err(|e| format!("prepare ecash history: {e}"))?;
+    let entries = stmt
+        .query_map(params![limit as i64], |r| Ok(HistoryEntry {
+            id: r.get(0)?,
+            kind: r.get(1)?,
+            amount: r.get::<_, i64>(2)?.max(0) as u64,
+            fee: r.get::<_, i64>(3)?.max(0) as u64,
+            mint: r.get(4)?,
+            chat_id: r.get(5)?,
+            message_id: r.get(6)?,
+            token: r.get::<_, Option<String>>(7)?.map(|t| crate::crypto::maybe_decrypt_text(&t)),
+            created_at: r.get::<_, i64>(8)?.max(0) as u64,
+        }))
+        .map_err(|e| format!("query ecash history: {e}"))?
+        .flatten()
+        .collect();
+    Ok(entries)
+}
+
+/// Whether `encoded` is from a mint the wallet already uses: one it holds ecash at or has history
+/// with. Only those tokens are redeemed without asking, so a stranger can't have the app call out
+/// to a host of their choosing.
+pub fn from_known_mint(encoded: &str) -> bool {
+    let Some(mint) = Token::decode(encoded).ok().and_then(|t| mint::normalize_url(&t.mint).ok()) else {
+        return false;
+    };
+    let Ok(conn) = crate::db::get_db_connection_guard_static() else { return false };
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM cashu_proofs WHERE mint_url = ?1)
+             OR EXISTS(SELECT 1 FROM cashu_history WHERE mint_url = ?1)",
+        params![mint],
+        |r| r.get::<_, bool>(0),
+    ).unwrap_or(false)
+}
+
+/// What the token in chat message `message_id` brought in, once redeemed.
+pub fn redeemed_amount(message_id: &str) -> Option<u64> {
+    let conn = crate::db::get_db_connection_guard_static().ok()?;
+    conn.query_row(
+        "SELECT amount FROM cashu_history WHERE kind = 'receive' AND message_id = ?1",
+        params![message_id],
+        |r| r.get::<_, i64>(0),
+    ).ok().map(|a| a.max(0) as u64)
+}
+
+/// Deposits whose invoices may still be paid.
+pub fn pending_deposits() -> Result<Vec<Deposit>, String> {
+    let conn = crate::db::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT quote_id, invoice, amount, mint_url, expiry FROM cashu_quotes ORDER BY created_at DESC",
+    ).map_err(|e| format!("prepare ecash deposits: {e}"))?;
+    let deposits = stmt
+        .query_map([], |r| Ok(Deposit {
+            quote: r.get(0)?,
+            invoice: r.get(1)?,
+            amount: r.get::<_, i64>(2)?.max(0) as u64,
+            mint: r.get(3)?,
+            expiry: r.get::<_, Option<i64>>(4)?.map(|e| e.max(0) as u64),
+        }))
+        .map_err(|e| format!("query ecash deposits: {e}"))?
+        .flatten()
+        .collect();
+    Ok(deposits)
+}
+
+// ============================================================================
+// Deposit / withdraw (Lightning in and out)
+// ============================================================================
+
+/// Ask `mint_url` for an invoice that mints `amount` sats of ecash once paid.
+pub async fn deposit(mint_url: &str, amount: u64) -> Result<Deposit, String> {
+    check_amount(amount)?;
+    let session = crate::state::SessionGuard::capture();
+    let mint = mint::normalize_url(mint_url)?;
+    let quote = mint::mint_quote(&mint, amount).await?;
+    if crate::zaps::bolt11_amount_msat(&quote.request) != Some(amount * 1000) {
+        return Err("The mint returned an invoice for the wrong amount".to_string());
+    }
+    check_session(&session)?;
+    let conn = crate::db::get_write_connection_guard_static()?;
+    conn.execute(
+        "INSERT OR REPLACE INTO cashu_quotes (quote_id, mint_url, amount, invoice, expiry, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![quote.quote, mint, amount as i64, quote.request, quote.expiry.map(|e| e as i64), crate::clock::now_secs() as i64],
+    ).map_err(|e| format!("Failed to save deposit: {}", e))?;
+    Ok(Deposit { quote: quote.quote, invoice: quote.request, amount, mint, expiry: quote.expiry })
+}
+
+/// Mint the ecash for a deposit whose invoice has been paid. Errs while it's still unpaid.
+pub async fn complete_deposit(quote_id: &str) -> Result<u64, String> {
+    let _wallet = WALLET_LOCK.lock().await;
+    let session = crate::state::SessionGuard::capture();
+    recover_locked(&session).await;
+    let (mint, amount) = {
+        let conn = crate::db::get_db_connection_guard_static()?;
+        conn.query_row(
+            "SELECT mint_url, amount FROM cashu_quotes WHERE quote_id = ?1",
+            params![quote_id],
+            |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?.max(0) as u64)),
+        ).map_err(|_| "Deposit not found".to_string())?
+    };
+    let state = mint::mint_quote_state(&mint, quote_id).await?;
+    if state.is_issued() {
+        check_session(&session)?;
+        forget_quote(quote_id)?;
+        return Err("This deposit was already collected".to_string());
+    }
+    if !state.is_paid() {
+        return Err("The invoice hasn't been paid yet".to_string());
+    }
+
+    let keyset = active_keyset(&mint, &mint::keysets(&mint).await?).await?;
+    let outputs = new_outputs(&split_amount(amount), &keyset.id)?;
+    let pending = save_pending(&session, &mint, PendingOp {
+        kind: "deposit", amount, fee: 0, inputs: &[], chat_id: None, message_id: None, quote_id: Some(quote_id),
+    }, &[&outputs])?;
+    let signatures = mint::mint(&mint, quote_id, &messages(&outputs)).await?;
+    let proofs = unblind_all(&outputs, &signatures, &keyset.keys)?;
+    commit(&session, &mint, &[], &proofs, Some(Record {
+        kind: "deposit", amount, fee: 0, chat_id: None, message_id: None, token: None,
+    }), &pending)?;
+    forget_quote(quote_id)?;
+    Ok(amount)
+}
+
+/// Pay a Lightning invoice from the mint holding the most ecash.
+pub async fn withdraw(invoice: &str) -> Result<Withdrawal, String> {
+    let invoice = invoice.trim();
+    let invoice = invoice.strip_prefix("lightning:").unwrap_or(invoice);
+    let amount_msat = crate::zaps::bolt11_amount_msat(invoice)
+        .ok_or("Enter an invoice with an amount")?;
+    check_amount(amount_msat.div_ceil(1000))?;
+
+    let _wallet = WALLET_LOCK.lock().await;
+    let session = crate::state::SessionGuard::capture();
+    recover_locked(&session).await;
+    let mint = balance()?.mints.into_iter().next().ok_or("Your ecash wallet is empty")?.mint;
+    let quote = mint::melt_quote(&mint, invoice).await?;
+    if quote.amount != amount_msat.div_ceil(1000) {
+        return Err("The mint quoted the wrong amount for this invoice".to_string());
+    }
+    let keysets = mint::keysets(&mint).await?;
+    let (selected, input_fee) = select(&load_proofs(&mint)?, quote.amount + quote.fee_reserve, &keysets)?;
+    let total: u64 = selected.iter().map(|p| p.proof.amount).sum();
+    let keyset = active_keyset(&mint, &keysets).await?;
+
+    // Blank outputs for whatever of the fee reserve (and overshoot) isn't used
+    let overpaid = total - quote.amount - input_fee;
+    let blanks = (0..blank_output_count(overpaid))
+        .map(|_| new_output(1, &keyset.id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let pending = save_pending(&session, &mint, PendingOp {
+        kind: "withdraw", amount: quote.amount, fee: 0, inputs: &selected, chat_id: None, message_id: None, quote_id: None,
+    }, &[&blanks])?;
+
+    let inputs: Vec<Proof> = selected.iter().map(|p| p.proof.clone()).collect();
+    let response = match mint::melt(&mint, &quote.quote, &inputs, &messages(&blanks)).await {
+        Ok(response) => response,
+        Err(e) => {
+            prune_spent(&session, &mint).await;
+            return Err(e);
+        }
+    };
+    if !response.is_paid() {
+        if response.state.as_deref() == Some("PENDING") {
+            return Err("The payment is still in flight; your balance will update once it settles".to_string());
+        }
+        return Err("The mint couldn't pay this invoice".to_string());
+    }
+
+    // The mint signs only as many blanks as the change needs
+    let change_sigs = response.change.unwrap_or_default();
+    if change_sigs.len() > blanks.len() {
+        return Err("The mint returned too much change".to_string());
+    }
+    let change = unblind_all(&blanks[..change_sigs.len()], &change_sigs, &keyset.keys)?;
+    let fee = total.saturating_sub(quote.amount + change.iter().map(|p| p.amount).sum::<u64>());
+    let spent: Vec<String> = selected.into_iter().map(|p| p.y).collect();
+    commit(&session, &mint, &spent, &change, Some(Record {
+        kind: "withdraw", amount: quote.amount, fee, chat_id: None, message_id: None, token: None,
+    }), &pending)?;
+    Ok(Withdrawal { amount: quote.amount, fee, preimage: response.payment_preimage })
+}
+
+// ============================================================================
+// Send / receive (tokens)
+// ============================================================================
+
+/// Split `amount` off into a token for someone else. The proofs leave the wallet; the token is
+/// kept in the history.
+pub async fn create_token(amount: u64, memo: Option<&str>) -> Result<SentToken, String> {
+    check_amount(amount)?;
+    let _wallet = WALLET_LOCK.lock().await;
+    let session = crate::state::SessionGuard::capture();
+    recover_locked(&session).await;
+    let mint = balance()?.mints.into_iter()
+        .find(|m| m.amount >= amount)
+        .ok_or("Not enough ecash at any one mint")?
+        .mint;
+    let keysets = mint::keysets(&mint).await?;
+    let (selected, fee) = select(&load_proofs(&mint)?, amount, &keysets)?;
+    let total: u64 = selected.iter().map(|p| p.proof.amount).sum();
+    let keyset = active_keyset(&mint, &keysets).await?;
+
+    let send_outputs = new_outputs(&split_amount(amount), &keyset.id)?;
+    let keep_outputs = new_outputs(&split_amount(total - amount - fee), &keyset.id)?;
+    let mut outputs = messages(&send_outputs);
+    outputs.extend(messages(&keep_outputs));
+    let pending = save_pending(&session, &mint, PendingOp {
+        kind: "send", amount, fee, inputs: &selected, chat_id: None, message_id: None, quote_id: None,
+    }, &[&send_outputs, &keep_outputs])?;
+
+    let inputs: Vec<Proof> = selected.iter().map(|p| p.proof.clone()).collect();
+    let signatures = match mint::swap(&mint, &inputs, &outputs).await {
+        Ok(signatures) if signatures.len() == outputs.len() => signatures,
+        Ok(_) => return Err("The mint returned the wrong number of signatures".to_string()),
+        Err(e) => {
+            prune_spent(&session, &mint).await;
+            return Err(e);
+        }
+    };
+    let (send_sigs, keep_sigs) = signatures.split_at(send_outputs.len());
+    let send = unblind_all(&send_outputs, send_sigs, &keyset.keys)?;
+    let keep = unblind_all(&keep_outputs, keep_sigs, &keyset.keys)?;
+
+    let memo = memo.map(|m| m.trim().chars().take(MAX_MEMO_CHARS).collect::<String>()).filter(|m| !m.is_empty());
+    let token = Token { mint: mint.clone(), unit: UNIT.to_string(), memo, proofs: send };
+    let encoded = token.encode()?;
+    let spent: Vec<String> = selected.into_iter().map(|p| p.y).collect();
+    let history_id = commit(&session, &mint, &spent, &keep, Some(Record {
+        kind: "send", amount, fee, chat_id: None, message_id: None, token: Some(&encoded),
+    }), &pending)?.ok_or("Failed to update ecash history")?;
+    Ok(SentToken { token, encoded, history_id })
+}
+
+/// Note which chat message carried a sent token.
+pub fn link_sent_token(history_id: i64, chat_id: &str, message_id: &str) -> Result<(), String> {
+    let conn = crate::db::get_write_connection_guard_static()?;
+    conn.execute(
+        "UPDATE cashu_history SET chat_id = ?1, message_id = ?2 WHERE id = ?3",
+        params![chat_id, message_id, history_id],
+    ).map_err(|e| format!("Failed to update ecash history: {}", e))?;
+    Ok(())
+}
+
+/// Swap a token's proofs into the wallet, so the sender can no longer spend them. `message_id`
+/// is the chat message it came in, redeemed at most once. Returns the sats received after the
+/// mint's fee.
+pub async fn receive_token(encoded: &str, chat_id: Option<&str>, message_id: Option<&str>) -> Result<u64, String> {
+    let token = Token::decode(encoded)?;
+    if token.unit != UNIT {
+        return Err("Only sat tokens are supported".to_string());
+    }
+    if token.proofs.is_empty() {
+        return Err("This token is empty".to_string());
+    }
+    let mint = mint::normalize_url(&token.mint)?;
+
+    let _wallet = WALLET_LOCK.lock().await;
+    let session = crate::state::SessionGuard::capture();
+    recover_locked(&session).await;
+    if message_id.is_some_and(|id| redeemed_amount(id).is_some()) {
+        return Err("This token was already redeemed".to_string());
+    }
+    let keysets = mint::keysets(&mint).await?;
+    let fee = input_fee(&token.proofs, &keysets);
+    let amount = token.amount().checked_sub(fee).filter(|&a| a > 0)
+        .ok_or("This token is worth less than the mint's fee")?;
+    let keyset = active_keyset(&mint, &keysets).await?;
+    let outputs = new_outputs(&split_amount(amount), &keyset.id)?;
+    let pending = save_pending(&session, &mint, PendingOp {
+        kind: "receive", amount, fee, inputs: &[], chat_id, message_id, quote_id: None,
+    }, &[&outputs])?;
+    let signatures = match mint::swap(&mint, &token.proofs, &messages(&outputs)).await {
+        Ok(signatures) => signatures,
+        Err(e) => {
+            if all_spent(&mint, &token.proofs).await.unwrap_or(false) {
+                return Err("This token was already claimed".to_string());
+            }
+            return Err(e);
+        }
+    };
+    let proofs = unblind_all(&outputs, &signatures, &keyset.keys)?;
+    commit(&session, &mint, &[], &proofs, Some(Record {
+        kind: "receive", amount, fee, chat_id, message_id, token: None,
+    }), &pending)?;
+    Ok(amount)
+}
+
+/// Whether every proof in `encoded` has been spent, e.g. to show a sent token as claimed.
+pub async fn token_spent(encoded: &str) -> Result<bool, String> {
+    let token = Token::decode(encoded)?;
+    all_spent(&mint::normalize_url(&token.mint)?, &token.proofs).await
+}
+
+async fn all_spent(mint: &str, proofs: &[Proof]) -> Result<bool, String> {
+    let ys = proofs.iter().map(proof_y).collect::<Result<Vec<_>, _>>()?;
+    let states = mint::check_state(mint, &ys).await?;
+    Ok(!states.is_empty() && states.iter().all(|s| s.state == "SPENT"))
+}
+
+// ============================================================================
+// Mint plumbing
+// ============================================================================
+
+/// The mint's cheapest active sat keyset, with its keys.
+async fn active_keyset(mint: &str, keysets: &[KeysetInfo]) -> Result<ActiveKeyset, String> {
+    // Tokens carry keyset ids as bytes, so legacy (base64) ids can't be used for new proofs
+    let id = keysets.iter()
+        .filter(|k| k.active && k.unit == UNIT && hex_string_to_bytes_checked(&k.id).is_some())
+        .min_by_key(|k| k.input_fee_ppk)
+        .map(|k| k.id.clone())
+        .ok_or("This mint has no active sat keyset")?;
+    let keys = keyset_keys(mint, &id).await?;
+    Ok(ActiveKeyset { id, keys })
+}
+
+/// A keyset's public keys by amount, checked against its id and cached.
+async fn keyset_keys(mint: &str, id: &str) -> Result<HashMap<u64, CurvePoint>, String> {
+    let cache_key = (mint.to_string(), id.to_string());
+    let cached = KEYS.lock().unwrap_or_else(|e| e.into_inner()).get(&cache_key).cloned();
+    if let Some(keys) = cached {
+        return Ok(keys);
+    }
+    let keys: HashMap<u64, CurvePoint> = mint::keys(mint, id).await?
+        .into_iter()
+        .filter_map(|(amount, key)| Some((amount, CurvePoint::from_str(&key).ok()?)))
+        .collect();
+    if keys.is_empty() {
+        return Err("The mint returned no keys".to_string());
+    }
+    if !keyset_id_matches(id, &keys) {
+        return Err("The mint's keys don't match their keyset id".to_string());
+    }
+    KEYS.lock().unwrap_or_else(|e| e.into_inner()).insert(cache_key, keys.clone());
+    Ok(keys)
+}
+
+/// Whether `id` is the keyset id of `keys` (NUT-02): the keys sorted by amount and concatenated,
+/// hashed, and prefixed with the version byte. Version 00 keeps the first 7 bytes of the hash;
+/// version 01 hashes the unit in too and keeps all of it. Other versions can't be checked.
+fn keyset_id_matches(id: &str, keys: &HashMap<u64, CurvePoint>) -> bool {
+    use sha2::{Digest, Sha256};
+    let mut amounts: Vec<&u64> = keys.keys().collect();
+    amounts.sort_unstable();
+    let mut hasher = Sha256::new();
+    for amount in amounts {
+        hasher.update(keys[amount].serialize());
+    }
+    let id = id.to_ascii_lowercase();
+    if let Some(short) = id.strip_prefix("00") {
+        let hash = hasher.finalize();
+        short == bytes_to_hex_string(&hash[..7])
+    } else if let Some(full) = id.strip_prefix("01") {
+        hasher.update(format!("unit:{UNIT}").as_bytes());
+        full == bytes_to_hex_string(&hasher.finalize())
+    } else {
+        false
+    }
+}
+
+/// Fee for spending `proofs`: each keyset's per-input fee, summed and rounded up (NUT-02).
+fn input_fee(proofs: &[Proof], keysets: &[KeysetInfo]) -> u64 {
+    let ppk: u64 = proofs.iter()
+        .map(|p| keysets.iter().find(|k| k.id == p.id).map_or(0, |k| k.input_fee_ppk))
+        .sum();
+    ppk.div_ceil(1000)
+}
+
+/// Proofs covering `target` plus the fee for spending them, largest first. Returns them and the
+/// fee.
+fn select(proofs: &[StoredProof], target: u64, keysets: &[KeysetInfo]) -> Result<(Vec<StoredProof>, u64), String> {
+    let mut sorted: Vec<&StoredProof> = proofs.iter().collect();
+    sorted.sort_by(|a, b| b.proof.amount.cmp(&a.proof.amount));
+    let mut selected: Vec<StoredProof> = Vec::new();
+    let mut total = 0u64;
+    for proof in sorted {
+        let fee = input_fee(&selected.iter().map(|p| p.proof.clone()).collect::<Vec<_>>(), keysets);
+        if total >= target + fee {
+            break;
+        }
+        total += proof.proof.amount;
+        selected.push(proof.clone());
+    }
+    let fee = input_fee(&selected.iter().map(|p| p.proof.clone()).collect::<Vec<_>>(), keysets);
+    if total < target + fee {
+        return Err(format!("Not enough ecash: {} sats needed including fees", target + fee));
+    }
+    Ok((selected, fee))
+}
+
+/// `amount` as powers of two, the denominations mints sign.
+fn split_amount(amount: u64) -> Vec<u64> {
+    (0..64).map(|bit| 1u64 << bit).filter(|v| amount & v != 0).collect()
+}
+
+/// Blank outputs enough to return `overpaid` sats of change (NUT-08).
+fn blank_output_count(overpaid: u64) -> usize {
+    match overpaid {
+        0 => 0,
+        n => (64 - (n - 1).leading_zeros()).max(1) as usize,
+    }
+}
+
+fn new_outputs(amounts: &[u64], keyset_id: &str) -> Result<Vec<PendingOutput>, String> {
+    amounts.iter().map(|&amount| new_output(amount, keyset_id)).collect()
+}
+
+fn new_output(amount: u64, keyset_id: &str) -> Result<PendingOutput, String> {
+    let secret = bytes_to_hex_string(&rand::random::<[u8; 32]>());
+    let blinded = dhke::blind(secret.as_bytes())?;
+    Ok(PendingOutput {
+        message: BlindedMessage {
+            amount,
+            id: keyset_id.to_string(),
+            b: bytes_to_hex_string(&blinded.point.serialize()),
+        },
+        secret,
+        factor: blinded.factor,
+    })
+}
+
+fn messages(outputs: &[PendingOutput]) -> Vec<BlindedMessage> {
+    outputs.iter().map(|o| o.message.clone()).collect()
+}
+
+/// Unblind the mint's signatures, which come in output order, into proofs.
+fn unblind_all(
+    outputs: &[PendingOutput],
+    signatures: &[BlindSignature],
+    keys: &HashMap<u64, CurvePoint>,
+) -> Result<Vec<Proof>, String> {
+    if signatures.len() != outputs.len() {
+        return Err("The mint returned the wrong number of signatures".to_string());
+    }
+    outputs.iter().zip(signatures).map(|(output, signature)| unblind(output, signature, keys)).collect()
+}
+
+fn unblind(output: &PendingOutput, signature: &BlindSignature, keys: &HashMap<u64, CurvePoint>) -> Result<Proof, String> {
+    if signature.id != output.message.id {
+        return Err("The mint signed with an unexpected keyset".to_string());
+    }
+    let key = keys.get(&signature.amount).ok_or("The mint signed an unknown amount")?;
+    let blind_c = CurvePoint::from_str(&signature.c).map_err(|_| "The mint returned an invalid signature")?;
+    let c = dhke::unblind(&blind_c, &output.factor, key)?;
+    Ok(Proof {
+        amount: signature.amount,
+        id: signature.id.clone(),
+        secret: output.secret.clone(),
+        c: bytes_to_hex_string(&c.serialize()),
+    })
+}
+
+// ============================================================================
+// Recovery
+// ============================================================================
+
+/// Settle the operations interrupted after their outputs went to the mint (see the module docs).
+/// Run once the account's database is open; every wallet operation also runs it first.
+pub async fn recover_pending() {
+    let _wallet = WALLET_LOCK.lock().await;
+    recover_locked(&crate::state::SessionGuard::capture()).await;
+}
+
+/// [`recover_pending`], under the wallet lock. A row the mint can't settle yet stays for next time.
+async fn recover_locked(session: &crate::state::SessionGuard) {
+    let rows = match load_pending() {
+        Ok(rows) => rows,
+        Err(e) => {
+            crate::log_warn!("[ecash] Couldn't read interrupted operations: {}", e);
+            return;
+        }
+    };
+    for row in rows {
+        if !session.is_valid() {
+            return;
+        }
+        let kind = row.kind.clone();
+        if let Err(e) = settle(session, row).await {
+            crate::log_warn!("[ecash] Couldn't recover an interrupted {} yet: {}", kind, e);
+        }
+    }
+}
+
+/// Store whatever the mint signed for `row` and drop the spent inputs, with the history row the
+/// operation would have written. A send's token never went out, so its value simply returns.
+async fn settle(session: &crate::state::SessionGuard, row: PendingRow) -> Result<(), String> {
+    let mut spent = Vec::new();
+    if !row.inputs.is_empty() {
+        let states = mint::check_state(&row.mint, &row.inputs).await?;
+        // A melt still in flight: its change is signed once the payment settles
+        if states.iter().any(|s| s.state == "PENDING") {
+            return Ok(());
+        }
+        spent = states.into_iter().filter(|s| s.state == "SPENT").map(|s| s.y).collect();
+    }
+
+    let signed = mint::restore(&row.mint, &messages(&row.outputs)).await?;
+    let mut keys: HashMap<String, HashMap<u64, CurvePoint>> = HashMap::new();
+    let mut proofs = Vec::new();
+    for output in &row.outputs {
+        let Some(signature) = signed.get(&output.message.b) else { continue };
+        if !keys.contains_key(&signature.id) {
+            keys.insert(signature.id.clone(), keyset_keys(&row.mint, &signature.id).await?);
+        }
+        match unblind(output, signature, &keys[&signature.id]) {
+            Ok(proof) => proofs.push(proof),
+            Err(e) => crate::log_warn!("[ecash] Skipping an unusable restored signature: {}", e),
+        }
+    }
+    // Restored elsewhere and spent since: not ours to add
+    if !proofs.is_empty() {
+        let ys = proofs.iter().map(proof_y).collect::<Result<Vec<_>, _>>()?;
+        let unspent: HashSet<String> = mint::check_state(&row.mint, &ys).await?
+            .into_iter()
+            .filter(|s| s.state == "UNSPENT")
+            .map(|s| s.y)
+            .collect();
+        proofs.retain(|p| proof_y(p).is_ok_and(|y| unspent.contains(&y)));
+    }
+
+    let received: u64 = proofs.iter().map(|p| p.amount).sum();
+    let record = match row.kind.as_str() {
+        "deposit" | "receive" if !proofs.is_empty() => Some(Record {
+            kind: &row.kind,
+            amount: received,
+            fee: row.fee,
+            chat_id: row.chat_id.as_deref(),
+            message_id: row.message_id.as_deref(),
+            token: None,
+        }),
+        "withdraw" if !spent.is_empty() => Some(Record {
+            kind: "withdraw",
+            amount: row.amount,
+            fee: row.input_total.saturating_sub(row.amount + received),
+            chat_id: None,
+            message_id: None,
+            token: None,
+        }),
+        _ => None,
+    };
+    commit(session, &row.mint, &spent, &proofs, record, &row.id)?;
+    if let Some(quote_id) = row.quote_id.as_deref().filter(|_| !proofs.is_empty()) {
+        forget_quote(quote_id)?;
+    }
+    if received > 0 {
+        crate::log_info!("[ecash] Recovered {} sats from an interrupted {}", received, row.kind);
+    }
+    Ok(())
+}
+
+/// Drop proofs the mint reports spent, after an operation failed on them.
+async fn prune_spent(session: &crate::state::SessionGuard, mint: &str) {
+    let Ok(stored) = load_proofs(mint) else { return };
+    let ys: Vec<String> = stored.into_iter().map(|p| p.y).collect();
+    let Ok(states) = mint::check_state(mint, &ys).await else { return };
+    let spent: Vec<String> = states.into_iter().filter(|s| s.state == "SPENT").map(|s| s.y).collect();
+    if !spent.is_empty() && session.is_valid() {
+        let _ = commit_removal(&spent);
+    }
+}
+
+/// The mint calls straddle awaits; an account swap meanwhile means the result isn't this account's
+/// to write.
+fn check_session(session: &crate::state::SessionGuard) -> Result<(), String> {
+    if !session.is_valid() {
+        return Err("The account changed during this ecash operation".to_string());
+    }
+    Ok(())
+}
+
+fn check_amount(amount: u64) -> Result<(), String> {
+    if amount == 0 || amount > MAX_AMOUNT {
+        return Err(format!("Amounts must be between 1 and {} sats", MAX_AMOUNT));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// A proof's Y (hex): its public identity at the mint.
+fn proof_y(proof: &Proof) -> Result<String, String> {
+    Ok(bytes_to_hex_string(&dhke::hash_to_curve(proof.secret.as_bytes())?.serialize()))
+}
+
+/// Save `op` with its outputs, before they go to the mint. Returns the row id for [`commit`].
+fn save_pending(
+    session: &crate::state::SessionGuard,
+    mint: &str,
+    op: PendingOp,
+    outputs: &[&[PendingOutput]],
+) -> Result<String, String> {
+    check_session(session)?;
+    let saved: Vec<SavedOutput> = outputs.iter().flat_map(|o| o.iter()).map(|o| SavedOutput {
+        amount: o.message.amount,
+        id: o.message.id.clone(),
+        b: o.message.b.clone(),
+        secret: o.secret.clone(),
+        r: bytes_to_hex_string(&o.factor.secret_bytes()),
+    }).collect();
+    let outputs = serde_json::to_string(&saved).map_err(|e| e.to_string())?;
+    let inputs: Vec<&str> = op.inputs.iter().map(|p| p.y.as_str()).collect();
+    let input_total: u64 = op.inputs.iter().map(|p| p.proof.amount).sum();
+    let id = bytes_to_hex_string(&rand::random::<[u8; 16]>());
+
+    let conn = crate::db::get_write_connection_guard_static()?;
+    conn.execute(
+        "INSERT INTO cashu_pending (id, mint_url, kind, amount, fee, inputs, input_total, outputs, chat_id, message_id, quote_id, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            id, mint, op.kind, op.amount as i64, op.fee as i64,
+            serde_json::to_string(&inputs).map_err(|e| e.to_string())?, input_total as i64,
+            crate::crypto::maybe_encrypt_text(&outputs)?,
+            op.chat_id, op.message_id, op.quote_id, crate::clock::now_secs() as i64,
+        ],
+    ).map_err(|e| format!("Failed to save ecash operation: {}", e))?;
+    Ok(id)
+}
+
+fn load_pending() -> Result<Vec<PendingRow>, String> {
+    let conn = crate::db::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT id, mint_url, kind, amount, fee, inputs, input_total, outputs, chat_id, message_id, quote_id
+         FROM cashu_pending ORDER BY created_at",
+    ).map_err(|e| format!("prepare ecash pending: {e}"))?;
+    let rows: Vec<(PendingRow, String, String)> = stmt
+        .query_map([], |r| Ok((PendingRow {
+            id: r.get(0)?,
+            mint: r.get(1)?,
+            kind: r.get(2)?,
+            amount: r.get::<_, i64>(3)?.max(0) as u64,
+            fee: r.get::<_, i64>(4)?.max(0) as u64,
+            inputs: Vec::new(),
+            input_total: r.get::<_, i64>(6)?.max(0) as u64,
+            outputs: Vec::new(),
+            chat_id: r.get(8)?,
+            message_id: r.get(9)?,
+            quote_id: r.get(10)?,
+        }, r.get(5)?, r.get(7)?)))
+        .map_err(|e| format!("query ecash pending: {e}"))?
+        .flatten()
+        .collect();
+    rows.into_iter().map(|(mut row, inputs, outputs)| {
+        let unreadable = || "Couldn't read the ecash wallet".to_string();
+        row.inputs = serde_json::from_str(&inputs).map_err(|_| unreadable())?;
+        let saved: Vec<SavedOutput> = serde_json::from_str(&crate::crypto::maybe_decrypt_text(&outputs))
+            .map_err(|_| unreadable())?;
+        row.outputs = saved.into_iter().map(|o| {
+            let factor = hex_string_to_bytes_checked(&o.r)
+                .and_then(|r| SecretKey::from_slice(&r).ok())
+                .ok_or_else(unreadable)?;
+            Ok(PendingOutput {
+                message: BlindedMessage { amount: o.amount, id: o.id, b: o.b },
+                secret: o.secret,
+                factor,
+            })
+        }).collect::<Result<_, String>>()?;
+        Ok(row)
+    }).collect()
+}
+
+fn load_proofs(mint: &str) -> Result<Vec<StoredProof>, String> {
+    let conn = crate::db::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare("SELECT y, proof FROM cashu_proofs WHERE mint_url = ?1")
+        .map_err(|e| format!("prepare ecash proofs: {e}"))?;
+    let rows: Vec<(String, String)> = stmt
+        .query_map(params![mint], |r| Ok((r.get(0)?, r.get(1)?)))
+        .map_err(|e| format!("query ecash proofs: {e}"))?
+        .flatten()
+        .collect();
+    rows.into_iter().map(|(y, stored)| {
+        let proof = serde_json::from_str(&crate::crypto::maybe_decrypt_text(&stored))
+            .map_err(|_| "Couldn't read the ecash wallet".to_string())?;
+        Ok(StoredProof { y, proof })
+    }).collect()
+}
+
+/// Swap `spent` proofs for `received` ones, write the history row (if any) and drop the
+/// operation's `pending` row, atomically. Returns the history row id. Refuses once `session` is
+/// stale, so proofs never land in another account's wallet; the pending row, in the database of
+/// the account that started the operation, gets them there instead.
+fn commit(
+    session: &crate::state::SessionGuard,
+    mint: &str,
+    spent: &[String],
+    received: &[Proof],
+    record: Option<Record>,
+    pending: &str,
+) -> Result<Option<i64>, String> {
+    check_session(session)?;
+    let mut rows = Vec::with_capacity(received.len());
+    for proof in received {
+        let json = serde_json::to_string(proof).map_err(|e| e.to_string())?;
+        rows.push((proof_y(proof)?, proof.amount, crate::crypto::maybe_encrypt_text(&json)?));
+    }
+    let token = record.as_ref().and_then(|r| r.token).map(crate::crypto::maybe_encrypt_text).transpose()?;
+    let now = crate::clock::now_secs() as i64;
+
+    let mut conn = crate::db::get_write_connection_guard_static()?;
+    let tx = conn.transaction().map_err(|e| format!("ecash tx: {e}"))?;
+    for y in spent {
+        tx.execute("DELETE FROM cashu_proofs WHERE y = ?1", params![y])
+            .map_err(|e| format!("Failed to update ecash wallet: {}", e))?;
+    }
+    for (y, amount, proof) in rows {
+        tx.execute(
+            "INSERT OR REPLACE INTO cashu_proofs (y, mint_url, amount, proof, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![y, mint, amount as i64, proof, now],
+        ).map_err(|e| format!("Failed to update ecash wallet: {}", e))?;
+    }
+    let id = match record {
+        Some(record) => {
+            tx.execute(
+                "INSERT INTO cashu_history (kind, amount, fee, mint_url, chat_id, message_id, token, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![record.kind, record.amount as i64, record.fee as i64, mint, record.chat_id, record.message_id, token, now],
+            ).map_err(|e| format!("Failed to update ecash history: {}", e))?;
+            Some(tx.last_insert_rowid())
+        }
+        None => None,
+    };
+    tx.execute("DELETE FROM cashu_pending WHERE id = ?1", params![pending])
+        .map_err(|e| format!("Failed to update ecash wallet: {}", e))?;
+    tx.commit().map_err(|e| format!("ecash commit: {e}"))?;
+    Ok(id)
+}
+
+fn commit_removal(spent: &[String]) -> Result<(), String> {
+    let mut conn = crate::db::get_write_connection_guard_static()?;
+    let tx = conn.transaction().map_err(|e| format!("ecash tx: {e}"))?;
+    for y in spent {
+        tx.execute("DELETE FROM cashu_proofs WHERE y = ?1", params![y])
+            .map_err(|e| format!("Failed to update ecash wallet: {}", e))?;
+    }
+    tx.commit().map_err(|e| format!("ecash commit: {e}"))
+}
+
+fn forget_quote(quote_id: &str) -> Result<(), String> {
+    let conn = crate::db::get_write_connection_guard_static()?;
+    conn.execute("DELETE FROM cashu_quotes WHERE quote_id = ?1", params![quote_id])
+        .map_err(|e| format!("Failed to update deposits: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(amount: u64, id: &str) -> StoredProof {
+        StoredProof {
+            y: format!("y{}", amount),
+            proof: Proof { amount, id: id.to_string(), secret: String::new(), c: String::new() },
+        }
+    }
+
+    fn keyset(id: &str, input_fee_ppk: u64) -> KeysetInfo {
+        KeysetInfo { id: id.to_string(), unit: "sat".to_string(), active: true, input_fee_ppk }
+    }
+
+    #[test]
+    fn amounts_split_into_powers_of_two() {
+        assert_eq!(split_amount(13), vec![1, 4, 8]);
+        assert_eq!(split_amount(64), vec![64]);
+        assert!(split_amount(0).is_empty());
+    }
+
+    #[test]
+    fn blank_outputs_cover_the_overpayment() {
+        assert_eq!(blank_output_count(0), 0);
+        assert_eq!(blank_output_count(1), 1);
+        assert_eq!(blank_output_count(1000), 10);
+        assert_eq!(blank_output_count(1024), 10);
+        assert_eq!(blank_output_count(1025), 11);
+    }
+
+    #[test]
+    fn selection_covers_amount_and_fees() {
+        let proofs = vec![stored(1, "00aa"), stored(4, "00aa"), stored(8, "00aa"), stored(32, "00aa")];
+        let free = [keyset("00aa", 0)];
+        let (picked, fee) = select(&proofs, 10, &free).unwrap();
+        assert_eq!((picked.iter().map(|p| p.proof.amount).collect::<Vec<_>>(), fee), (vec![32], 0));
+
+        // 1 sat per input: three inputs for 40 plus their 3 sat fee
+        let costly = [keyset("00aa", 1000)];
+        let (picked, fee) = select(&proofs, 40, &costly).unwrap();
+        assert_eq!((picked.iter().map(|p| p.proof.amount).sum::<u64>(), fee), (44, 3));
+
+        assert!(select(&proofs, 46, &free).is_err());
+    }
+
+    #[test]
+    fn keyset_ids_are_checked_against_the_keys() {
+        let keys: HashMap<u64, CurvePoint> = [1u64, 2, 4].iter().map(|&amount| {
+            let secret = SecretKey::from_slice(&[amount as u8; 32]).unwrap();
+            (amount, CurvePoint::from_secret_key(&nostr::secp256k1::Secp256k1::new(), &secret))
+        }).collect();
+        let mut hasher = <sha2::Sha256 as sha2::Digest>::new();
+        for amount in [1u64, 2, 4] {
+            sha2::Digest::update(&mut hasher, keys[&amount].serialize());
+        }
+        let hash = sha2::Digest::finalize(hasher);
+        let id = format!("00{}", bytes_to_hex_string(&hash[..7]));
+
+        assert!(keyset_id_matches(&id, &keys));
+        assert!(!keyset_id_matches("00ffffffffffffff", &keys), "keys planted under another id");
+        let mut other = keys.clone();
+        other.remove(&4);
+        assert!(!keyset_id_matches(&id, &other));
+        assert!(!keyset_id_matches("I2yN+iRYfkzT", &keys), "unversioned ids can't be checked");
+    }
+
+    #[test]
+    fn input_fees_round_up() {
+        let proofs: Vec<Proof> = (0..3).map(|_| stored(1, "00aa").proof).collect();
+        assert_eq!(input_fee(&proofs, &[keyset("00aa", 100)]), 1);
+        assert_eq!(input_fee(&proofs, &[keyset("00aa", 0)]), 0);
+        assert_eq!(input_fee(&proofs, &[keyset("00bb", 500)]), 0);
+    }
+}
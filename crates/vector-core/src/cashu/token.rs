@@ -0,0 +1,232 @@
+//! Cashu token strings (NUT-00): a mint URL plus the proofs it issued, serialized for sharing.
+//!
+//! We send `cashuB` (V4, CBOR); `cashuA` (V3, JSON) is still accepted on receipt, since older
+//! wallets produce it.
+
+use ciborium::value::{Integer, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::simd::hex::{bytes_to_hex_string, hex_string_to_bytes_checked};
+
+const PREFIX_V3: &str = "cashuA";
+const PREFIX_V4: &str = "cashuB";
+
+/// A spendable ecash note of one denomination.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Proof {
+    pub amount: u64,
+    /// Keyset id (hex) of the key that signed it.
+    pub id: String,
+    pub secret: String,
+    /// The unblinded signature (hex, compressed point).
+    #[serde(rename = "C")]
+    pub c: String,
+}
+
+/// Proofs from one mint, ready to hand to someone else.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub mint: String,
+    pub unit: String,
+    pub memo: Option<String>,
+    pub proofs: Vec<Proof>,
+}
+
+#[derive(Deserialize)]
+struct TokenV3 {
+    token: Vec<TokenV3Entry>,
+    #[serde(default)]
+    unit: Option<String>,
+    #[serde(default)]
+    memo: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenV3Entry {
+    mint: String,
+    proofs: Vec<Proof>,
+}
+
+impl Token {
+    /// Total value of the proofs.
+    pub fn amount(&self) -> u64 {
+        self.proofs.iter().map(|p| p.amount).sum()
+    }
+
+    /// Serialize as a V4 (`cashuB`) token.
+    pub fn encode(&self) -> Result<String, String> {
+        // Proofs grouped by keyset, keeping their order
+        let mut keysets: Vec<(&str, Vec<Value>)> = Vec::new();
+        for proof in &self.proofs {
+            let entry = Value::Map(vec![
+                (text("a"), Value::Integer(Integer::from(proof.amount))),
+                (text("s"), text(&proof.secret)),
+                (text("c"), Value::Bytes(hex(&proof.c)?)),
+            ]);
+            match keysets.iter_mut().find(|(id, _)| *id == proof.id) {
+                Some((_, proofs)) => proofs.push(entry),
+                None => keysets.push((proof.id.as_str(), vec![entry])),
+            }
+        }
+        let mut keyset_values = Vec::with_capacity(keysets.len());
+        for (id, proofs) in keysets {
+            keyset_values.push(Value::Map(vec![
+                (text("i"), Value::Bytes(hex(id)?)),
+                (text("p"), Value::Array(proofs)),
+            ]));
+        }
+
+        let mut root = vec![(text("m"), text(&self.mint)), (text("u"), text(&self.unit))];
+        if let Some(memo) = &self.memo {
+            root.push((text("d"), text(memo)));
+        }
+        root.push((text("t"), Value::Array(keyset_values)));
+
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&Value::Map(root), &mut bytes).map_err(|e| e.to_string())?;
+        Ok(format!("{}{}", PREFIX_V4, base64_simd::URL_SAFE_NO_PAD.encode_to_string(&bytes)))
+    }
+
+    /// Parse a `cashuA` or `cashuB` token (a `cashu:` URI prefix is accepted).
+    pub fn decode(input: &str) -> Result<Token, String> {
+        let input = input.trim();
+        let input = input.strip_prefix("cashu:").unwrap_or(input);
+        if let Some(data) = input.strip_prefix(PREFIX_V4) {
+            return decode_v4(&base64_decode(data)?).ok_or_else(|| "Invalid ecash token".to_string());
+        }
+        if let Some(data) = input.strip_prefix(PREFIX_V3) {
+            let v3: TokenV3 = serde_json::from_slice(&base64_decode(data)?)
+                .map_err(|_| "Invalid ecash token".to_string())?;
+            let mut entries = v3.token.into_iter();
+            let (Some(entry), None) = (entries.next(), entries.next()) else {
+                return Err("Tokens spanning several mints aren't supported".to_string());
+            };
+            return Ok(Token {
+                mint: entry.mint,
+                unit: v3.unit.unwrap_or_else(|| "sat".to_string()),
+                memo: v3.memo,
+                proofs: entry.proofs,
+            });
+        }
+        Err("Not an ecash token".to_string())
+    }
+}
+
+fn decode_v4(bytes: &[u8]) -> Option<Token> {
+    let root: Value = ciborium::from_reader(bytes).ok()?;
+    let root = as_map(&root)?;
+    let mint = as_text(get(root, "m")?)?.to_string();
+    let unit = as_text(get(root, "u")?)?.to_string();
+    let memo = get(root, "d").and_then(as_text).map(str::to_string);
+
+    let mut proofs = Vec::new();
+    let Value::Array(keysets) = get(root, "t")? else { return None };
+    for keyset in keysets {
+        let keyset = as_map(keyset)?;
+        let Value::Bytes(id) = get(keyset, "i")? else { return None };
+        let id = bytes_to_hex_string(id);
+        let Value::Array(entries) = get(keyset, "p")? else { return None };
+        for entry in entries {
+            let entry = as_map(entry)?;
+            let Value::Integer(amount) = get(entry, "a")? else { return None };
+            let Value::Bytes(c) = get(entry, "c")? else { return None };
+            proofs.push(Proof {
+                amount: u64::try_from(*amount).ok()?,
+                id: id.clone(),
+                secret: as_text(get(entry, "s")?)?.to_string(),
+                c: bytes_to_hex_string(c),
+            });
+        }
+    }
+    Some(Token { mint, unit, memo, proofs })
+}
+
+fn text(s: &str) -> Value {
+    Value::Text(s.to_string())
+}
+
+fn hex(s: &str) -> Result<Vec<u8>, String> {
+    hex_string_to_bytes_checked(s).ok_or_else(|| "Invalid hex in ecash proof".to_string())
+}
+
+fn get<'a>(map: &'a [(Value, Value)], key: &str) -> Option<&'a Value> {
+    map.iter().find(|(k, _)| matches!(k, Value::Text(k) if k == key)).map(|(_, v)| v)
+}
+
+fn as_map(value: &Value) -> Option<&[(Value, Value)]> {
+    match value {
+        Value::Map(map) => Some(map.as_slice()),
+        _ => None,
+    }
+}
+
+fn as_text(value: &Value) -> Option<&str> {
+    match value {
+        Value::Text(s) => Some(s.as_str()),
+        _ => None,
+    }
+}
+
+/// Tokens are base64url, but some wallets emit standard base64 or keep the padding.
+fn base64_decode(data: &str) -> Result<Vec<u8>, String> {
+    let normalized: String = data.trim_end_matches('=')
+        .chars()
+        .map(|c| match c { '+' => '-', '/' => '_', c => c })
+        .collect();
+    base64_simd::URL_SAFE_NO_PAD
+        .decode_to_vec(normalized.as_bytes())
+        .map_err(|_| "Invalid ecash token".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Token {
+        Token {
+            mint: "https://mint.example.com".to_string(),
+            unit: "sat".to_string(),
+            memo: Some("lunch".to_string()),
+            proofs: vec![
+                Proof {
+                    amount: 2,
+                    id: "009a1f293253e41e".to_string(),
+                    secret: "407915bc212be61a77e3e6d2aeb4c727980bda51cd06a6afc29e2861768a7837".to_string(),
+                    c: "02bc9097997d81afb2cc7346b5e4345a9346bd2a506eb7958598a72f0cf85163ea".to_string(),
+                },
+                Proof {
+                    amount: 8,
+                    id: "009a1f293253e41e".to_string(),
+                    secret: "fe15109314e61d7756b0f8ee0f23a624acaa3f4e042f61433c728c7057b931be".to_string(),
+                    c: "029e8e5050b890a7d6c0968db16bc1d5d5fa040ea1de284f6ec69d61299f671059".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn v4_round_trip() {
+        let token = sample();
+        let encoded = token.encode().unwrap();
+        assert!(encoded.starts_with("cashuB"));
+        assert_eq!(Token::decode(&encoded).unwrap(), token);
+        assert_eq!(token.amount(), 10);
+    }
+
+    #[test]
+    fn decodes_v3() {
+        let json = serde_json::json!({
+            "token": [{ "mint": "https://mint.example.com", "proofs": sample().proofs }],
+            "memo": "lunch",
+        });
+        let encoded = format!("cashuA{}", base64_simd::URL_SAFE.encode_to_string(json.to_string()));
+        let token = Token::decode(&encoded).unwrap();
+        assert_eq!(token, Token { unit: "sat".to_string(), ..sample() });
+    }
+
+    #[test]
+    fn rejects_other_strings() {
+        assert!(Token::decode("hello").is_err());
+        assert!(Token::decode("cashuBnotbase64!").is_err());
+    }
+}
@@ -241,8 +241,7 @@ impl Chat {
     }
 
     pub fn get_active_typers(&self, interner: &NpubInterner) -> Vec<String> {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        let now = crate::clock::now_secs();
         self.typing_participants.iter()
             .filter(|(_, exp)| *exp > now)
             .filter_map(|(h, _)| interner.resolve(*h).map(|s| s.to_string()))
@@ -255,8 +254,7 @@ impl Chat {
         } else {
             self.typing_participants.push((handle, expires_at));
         }
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        let now = crate::clock::now_secs();
         self.typing_participants.retain(|(_, exp)| *exp > now);
     }
 
@@ -783,6 +781,26 @@ mod tests {
         assert_eq!(active.len(), 1, "refreshed typer should not duplicate");
     }
 
+    #[test]
+    fn typing_indicator_lapses_after_its_expiry() {
+        let mut interner = NpubInterner::new();
+        let mut chat = Chat::new_dm("npub1peer".to_string(), &mut interner);
+        let clock = crate::clock::freeze(1_700_000_000_000);
+        let handle = interner.intern("npub1typer");
+
+        // A 30s indicator, as `send_typing` stamps it
+        chat.update_typing_participant(handle, crate::clock::now_secs() + 30);
+        clock.advance_secs(29);
+        assert_eq!(chat.get_active_typers(&interner).len(), 1);
+        clock.advance_secs(1);
+        assert!(chat.get_active_typers(&interner).is_empty(), "expiry is exclusive");
+
+        // The next update sweeps the lapsed entry
+        let other = interner.intern("npub1other");
+        chat.update_typing_participant(other, crate::clock::now_secs() + 30);
+        assert_eq!(chat.typing_participants.len(), 1);
+    }
+
     #[test]
     fn typing_participants_empty_initially() {
         let interner = NpubInterner::new();
@@ -0,0 +1,364 @@
+//! Cross-device chat state — read markers, chat mutes, accent colors, wallpaper overrides and contact
+//! nicknames between our own installs.
+//!
+//! Gift wraps already reach every device, but what the user did with them didn't: reading a chat on the
+//! phone left it unread on the desktop. This rides the same transport as [`crate::settings_sync`] — a
+//! NIP-44-self-encrypted kind 30078 under its own `d=vector/chat-state`, so read markers (which change
+//! constantly) never churn the settings event — and the same per-key last-writer-wins merge:
+//!
+//! - `read:<chat>` — the chat's read marker (message id). Only markers this device moved (or adopted) are
+//!   carried, capped to the [`MAX_READ_MARKERS`] most recently changed — locally and in every snapshot we
+//!   publish, sibling devices' markers included — so the snapshot stays well inside NIP-44's plaintext
+//!   limit.
+//! - `muted:<chat>` — `"true"`/`"false"`, so an unmute is a stamped entry rather than an absence.
+//! - `accent:<chat>` — the chat's accent color ([`crate::appearance`]; empty = reset to the theme's).
+//! - `wallpaper:<chat>` — our wallpaper override for the chat, as its JSON spec (empty = removed).
+//! - `nickname:<npub>` — the local nickname (empty = cleared).
+//!
+//! Entries for chats or profiles this device doesn't have are left for later: they stay in the relay copy
+//! and apply once the chat shows up.
+
+use nostr_sdk::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::settings_sync::{SettingsSnapshot, SettingsSyncReport, SyncedValue};
+use crate::state::{SessionGuard, STATE};
+use crate::sync_store::{StampedStore, StoreEntries};
+
+pub const CHAT_STATE_D_TAG: &str = "vector/chat-state";
+/// Per-key local write stamps (JSON `{key: ms}`).
+const STAMPS_KEY: &str = "chat_state_sync_stamps";
+pub const READ_PREFIX: &str = "read:";
+const MUTED_PREFIX: &str = "muted:";
+const ACCENT_PREFIX: &str = "accent:";
+const WALLPAPER_PREFIX: &str = "wallpaper:";
+const NICKNAME_PREFIX: &str = "nickname:";
+/// Most read markers carried (the most recently moved win).
+const MAX_READ_MARKERS: usize = 150;
+/// Read markers move on every chat open, so bursts coalesce for longer than settings edits do.
+const REPUBLISH_DEBOUNCE_SECS: u64 = 5;
+
+pub fn read_key(chat_id: &str) -> String {
+    format!("{READ_PREFIX}{chat_id}")
+}
+
+pub fn muted_key(chat_id: &str) -> String {
+    format!("{MUTED_PREFIX}{chat_id}")
+}
+
+pub fn accent_key(chat_id: &str) -> String {
+    format!("{ACCENT_PREFIX}{chat_id}")
+}
+
+pub fn wallpaper_key(chat_id: &str) -> String {
+    format!("{WALLPAPER_PREFIX}{chat_id}")
+}
+
+pub fn nickname_key(npub: &str) -> String {
+    format!("{NICKNAME_PREFIX}{npub}")
+}
+
+// ============================================================================
+// Local stamps + snapshot
+// ============================================================================
+
+static STORE: StampedStore = StampedStore::new(
+    CHAT_STATE_D_TAG,
+    STAMPS_KEY,
+    "[ChatStateSync]",
+    REPUBLISH_DEBOUNCE_SECS,
+    &ChatState,
+);
+
+/// The read-marker keys past the [`MAX_READ_MARKERS`] newest, given each key's stamp.
+fn stale_read_keys<'a>(stamps: impl Iterator<Item = (&'a String, u64)>) -> Vec<String> {
+    let mut reads: Vec<(u64, &String)> = stamps
+        .filter(|(key, _)| key.starts_with(READ_PREFIX))
+        .map(|(key, at)| (at, key))
+        .collect();
+    if reads.len() <= MAX_READ_MARKERS {
+        return Vec::new();
+    }
+    reads.sort_unstable_by(|a, b| b.cmp(a));
+    reads.into_iter().skip(MAX_READ_MARKERS).map(|(_, key)| key.clone()).collect()
+}
+
+/// Drop all but the [`MAX_READ_MARKERS`] newest read-marker stamps.
+fn prune_read_stamps(stamps: &mut HashMap<String, u64>) {
+    for key in stale_read_keys(stamps.iter().map(|(key, at)| (key, *at))) {
+        stamps.remove(&key);
+    }
+}
+
+/// Drop all but the [`MAX_READ_MARKERS`] newest read markers from a snapshot about to be published. The
+/// merge unions every device's markers, so without this the relay copy only ever grows.
+fn prune_read_entries(snapshot: &mut SettingsSnapshot) {
+    for key in stale_read_keys(snapshot.entries.iter().map(|(key, v)| (key, v.updated_at))) {
+        snapshot.entries.remove(&key);
+    }
+}
+
+/// Record a local change to a synced key (`read:`, `muted:`, `accent:`, `wallpaper:` or `nickname:`) and push
+/// it out shortly.
+/// Called from the write paths themselves.
+pub fn note_local_change(key: &str) {
+    STORE.note_local_change(key);
+}
+
+/// A read marker worth carrying: a real event id, not a never-read (zeroed) or pending one.
+fn is_event_id(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit()) && value.bytes().any(|b| b != b'0')
+}
+
+/// This device's view: every stamped read marker, every muted chat and stamped unmute, and every accent
+/// color, wallpaper override and nickname (set or stamped-cleared).
+pub async fn local_snapshot() -> SettingsSnapshot {
+    STORE.local_snapshot().await
+}
+
+struct ChatState;
+
+#[async_trait::async_trait]
+impl StoreEntries for ChatState {
+    async fn local_snapshot(&self, stamps: &HashMap<String, u64>) -> SettingsSnapshot {
+        let at = |key: &str| stamps.get(key).copied().unwrap_or(0);
+        let mut entries = BTreeMap::new();
+        let accents = crate::appearance::accent_colors().unwrap_or_default();
+        for (key, &updated_at) in stamps {
+            if let Some(chat_id) = key.strip_prefix(ACCENT_PREFIX) {
+                let value = accents.get(chat_id).cloned().unwrap_or_default();
+                entries.insert(key.clone(), SyncedValue { value, updated_at });
+            }
+        }
+        for (chat_id, color) in accents {
+            let key = accent_key(&chat_id);
+            entries.entry(key).or_insert(SyncedValue { value: color, updated_at: 0 });
+        }
+        let wallpapers: HashMap<String, String> = crate::appearance::wallpaper_overrides()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(chat_id, stored)| Some((chat_id, serde_json::to_string(&stored.spec).ok()?)))
+            .collect();
+        for (key, &updated_at) in stamps {
+            if let Some(chat_id) = key.strip_prefix(WALLPAPER_PREFIX) {
+                let value = wallpapers.get(chat_id).cloned().unwrap_or_default();
+                entries.insert(key.clone(), SyncedValue { value, updated_at });
+            }
+        }
+        for (chat_id, spec) in wallpapers {
+            entries.entry(wallpaper_key(&chat_id)).or_insert(SyncedValue { value: spec, updated_at: 0 });
+        }
+        let state = STATE.lock().await;
+        for chat in &state.chats {
+            let key = read_key(&chat.id);
+            if stamps.contains_key(&key) {
+                let value = crate::compact::decode_message_id(&chat.last_read);
+                if is_event_id(&value) {
+                    let updated_at = at(&key);
+                    entries.insert(key, SyncedValue { value, updated_at });
+                }
+            }
+            let key = muted_key(&chat.id);
+            if chat.muted || stamps.contains_key(&key) {
+                let updated_at = at(&key);
+                entries.insert(key, SyncedValue { value: chat.muted.to_string(), updated_at });
+            }
+        }
+        for profile in &state.profiles {
+            let Some(npub) = state.interner.resolve(profile.id) else { continue };
+            let key = nickname_key(npub);
+            if !profile.nickname().is_empty() || stamps.contains_key(&key) {
+                let updated_at = at(&key);
+                entries.insert(key, SyncedValue { value: profile.nickname().to_string(), updated_at });
+            }
+        }
+        SettingsSnapshot { entries }
+    }
+
+    async fn apply(
+        &self,
+        session: &SessionGuard,
+        key: &str,
+        entry: &SyncedValue,
+        already: bool,
+        handler: &dyn crate::profile::sync::ProfileSyncHandler,
+    ) -> bool {
+        if let Some(chat_id) = key.strip_prefix(READ_PREFIX) {
+            is_event_id(&entry.value) && (already || apply_read(chat_id, &entry.value).await)
+        } else if let Some(chat_id) = key.strip_prefix(MUTED_PREFIX) {
+            already || apply_muted(chat_id, entry.value == "true").await
+        } else if let Some(chat_id) = key.strip_prefix(ACCENT_PREFIX) {
+            already || apply_accent(session, chat_id, &entry.value)
+        } else if let Some(chat_id) = key.strip_prefix(WALLPAPER_PREFIX) {
+            already || crate::wallpaper::apply_synced_override(session, chat_id, &entry.value).await
+        } else if let Some(npub) = key.strip_prefix(NICKNAME_PREFIX) {
+            PublicKey::parse(npub).is_ok()
+                && (already || crate::profile::sync::set_nickname(npub.to_string(), entry.value.clone(), handler).await)
+        } else {
+            // A key from a newer client this build doesn't know — ignore it.
+            false
+        }
+    }
+
+    fn prune_stamps(&self, stamps: &mut HashMap<String, u64>) {
+        prune_read_stamps(stamps);
+    }
+
+    fn prune_published(&self, snapshot: &mut SettingsSnapshot) {
+        prune_read_entries(snapshot);
+    }
+}
+
+/// Move a chat's read marker to `message_id`, as if it was read here. Only forward, and only onto a
+/// message this device has: a marker for one still on its way stays unstamped, so a later round offers it
+/// again once the message arrived.
+async fn apply_read(chat_id: &str, message_id: &str) -> bool {
+    let session = crate::state::SessionGuard::capture();
+    let Ok(Some(target_at)) = crate::db::events::event_created_at_in_chat(chat_id, message_id) else { return false };
+    let current = {
+        let state = STATE.lock().await;
+        let Some(chat) = state.get_chat(chat_id) else { return false };
+        crate::compact::decode_message_id(&chat.last_read)
+    };
+    if is_event_id(&current) {
+        let current_at = crate::db::events::event_created_at_in_chat(chat_id, &current).ok().flatten();
+        if current_at.is_some_and(|at| at >= target_at) {
+            return false;
+        }
+    }
+    let slim = {
+        let mut state = STATE.lock().await;
+        if !session.is_valid() {
+            return false;
+        }
+        let Some(chat) = state.get_chat_mut(chat_id) else { return false };
+        chat.last_read = crate::compact::encode_message_id(message_id);
+        let Some(chat) = state.get_chat(chat_id) else { return false };
+        crate::db::chats::SlimChatDB::from_chat(chat, &state.interner)
+    };
+    if crate::db::chats::save_slim_chat(&slim).is_err() {
+        return false;
+    }
+    // The marker may land short of the newest message, so recount rather than clear.
+    let unread = crate::db::events::unread_summary_for_chat(chat_id).await.unwrap_or_default();
+    {
+        let mut state = STATE.lock().await;
+        if !session.is_valid() {
+            return false;
+        }
+        if state.unread_seeded {
+            state.unread_set(chat_id, unread);
+        }
+    }
+    crate::traits::emit_event("chat_mark_read", &serde_json::json!({
+        "chat_id": chat_id,
+        "last_read": message_id,
+    }));
+    true
+}
+
+async fn apply_muted(chat_id: &str, muted: bool) -> bool {
+    let session = crate::state::SessionGuard::capture();
+    let slim = {
+        let mut state = STATE.lock().await;
+        if !session.is_valid() {
+            return false;
+        }
+        let Some(chat) = state.get_chat_mut(chat_id) else { return false };
+        chat.muted = muted;
+        let Some(chat) = state.get_chat(chat_id) else { return false };
+        crate::db::chats::SlimChatDB::from_chat(chat, &state.interner)
+    };
+    if crate::db::chats::save_slim_chat(&slim).is_err() {
+        return false;
+    }
+    crate::traits::emit_event("chat_muted", &serde_json::json!({
+        "chat_id": chat_id,
+        "value": muted,
+    }));
+    true
+}
+
+/// Set (or with an empty value, reset) a chat's accent color. Unlike the other keys this doesn't need the
+/// chat to be here yet: accents are keyed by chat id alone.
+fn apply_accent(session: &SessionGuard, chat_id: &str, value: &str) -> bool {
+    let color = if value.is_empty() {
+        None
+    } else {
+        match crate::appearance::normalize_color(value) {
+            Ok(color) => Some(color),
+            Err(_) => return false,
+        }
+    };
+    if !session.is_valid() {
+        return false;
+    }
+    if crate::appearance::write_accent_color(chat_id, color.as_deref()).is_err() {
+        return false;
+    }
+    crate::traits::emit_event("chat_appearance_changed", &serde_json::json!({
+        "chat_id": chat_id,
+        "accent_color": color,
+    }));
+    true
+}
+
+// ============================================================================
+// Sync rounds
+// ============================================================================
+
+/// Full round: fetch the relay snapshot, adopt its newer entries, and publish the merge when this device
+/// holds anything newer. Read markers from other devices arrive as `chat_mark_read`, mutes as `chat_muted`,
+/// accents and wallpaper overrides as `chat_appearance_changed` and nicknames as `profile_nick_changed`, exactly as if they'd
+/// changed here.
+pub async fn sync_now(handler: &dyn crate::profile::sync::ProfileSyncHandler) -> Result<SettingsSyncReport, String> {
+    STORE.sync_now(handler).await
+}
+
+/// Live cross-device path: adopt a snapshot another device published. Returns the adopted keys. Never
+/// republishes (the relay echoes our own publishes back on the same subscription).
+pub async fn ingest_remote_event(event: &Event, handler: &dyn crate::profile::sync::ProfileSyncHandler) -> Vec<String> {
+    STORE.ingest_remote_event(event, handler).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_stamps_keep_only_the_newest() {
+        let mut stamps: HashMap<String, u64> = (0..MAX_READ_MARKERS as u64 + 10)
+            .map(|i| (read_key(&format!("npub1chat{i}")), i))
+            .collect();
+        stamps.insert(muted_key("npub1chat0"), 0);
+        prune_read_stamps(&mut stamps);
+
+        assert_eq!(stamps.keys().filter(|k| k.starts_with(READ_PREFIX)).count(), MAX_READ_MARKERS);
+        assert!(!stamps.contains_key(&read_key("npub1chat0")), "the oldest marker goes first");
+        assert!(stamps.contains_key(&read_key(&format!("npub1chat{}", MAX_READ_MARKERS + 9))));
+        assert!(stamps.contains_key(&muted_key("npub1chat0")), "mutes are never pruned");
+    }
+
+    #[test]
+    fn published_snapshots_keep_only_the_newest_read_markers() {
+        let mut snapshot = SettingsSnapshot::default();
+        for i in 0..MAX_READ_MARKERS as u64 * 2 {
+            snapshot.entries.insert(read_key(&format!("npub1chat{i}")), SyncedValue { value: "ab".repeat(32), updated_at: i });
+        }
+        snapshot.entries.insert(nickname_key("npub1old"), SyncedValue { value: "Old".into(), updated_at: 0 });
+        prune_read_entries(&mut snapshot);
+
+        assert_eq!(snapshot.entries.keys().filter(|k| k.starts_with(READ_PREFIX)).count(), MAX_READ_MARKERS);
+        assert!(!snapshot.entries.contains_key(&read_key(&format!("npub1chat{}", MAX_READ_MARKERS - 1))));
+        assert!(snapshot.entries.contains_key(&read_key(&format!("npub1chat{}", MAX_READ_MARKERS))));
+        assert!(snapshot.entries.contains_key(&nickname_key("npub1old")), "only read markers are pruned");
+    }
+
+    #[test]
+    fn only_real_event_ids_are_read_markers() {
+        assert!(is_event_id(&"ab".repeat(32)));
+        assert!(!is_event_id(&"0".repeat(64)), "never-read");
+        assert!(!is_event_id("pending-1700000000000"));
+        assert!(!is_event_id("zz"));
+    }
+}
@@ -0,0 +1,124 @@
+//! Wall-clock time and non-secret randomness, behind providers a test can swap out.
+//!
+//! Behaviour that hangs on the clock (sync windows, typing expiry, cooldowns) reads it through
+//! [`now_secs`] / [`now_ms`], and code that draws throwaway randomness (invite codes and labels,
+//! pack identifiers) draws it through [`with_rng`]. In a running app these are the system clock
+//! and `thread_rng`. A test pins them for its own thread with [`freeze`] and [`seed_rng`]; the
+//! returned guards restore the real providers when dropped.
+//!
+//! The overrides are thread-local, so tests running in parallel never see each other's time. An
+//! async test relying on them runs on a current-thread runtime (`#[tokio::test]`'s default).
+//!
+//! Secrets never come from here: keys, nonces and tokens stay on `OsRng`, out of any test hook's
+//! reach.
+
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+thread_local! {
+    /// Frozen time (ms) on this thread, when a test set one.
+    static FROZEN_MS: Cell<Option<u64>> = const { Cell::new(None) };
+    /// Seeded RNG on this thread, when a test set one.
+    static SEEDED: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Unix time in milliseconds.
+pub fn now_ms() -> u64 {
+    FROZEN_MS.with(|f| f.get()).unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    })
+}
+
+/// Unix time in seconds.
+pub fn now_secs() -> u64 {
+    now_ms() / 1000
+}
+
+/// Run `f` with this thread's RNG: `thread_rng`, or the seeded one a test installed.
+pub fn with_rng<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+    SEEDED.with(|seeded| {
+        if seeded.borrow().is_none() {
+            return f(&mut rand::thread_rng());
+        }
+        f(seeded.borrow_mut().as_mut().expect("checked above"))
+    })
+}
+
+/// A frozen clock on the current thread; time stands still until [`Frozen::advance`]. Dropping it
+/// restores the system clock.
+pub struct Frozen {
+    // Thread-local state: the guard must be dropped on the thread that froze it
+    _thread: PhantomData<*const ()>,
+}
+
+/// Freeze this thread's clock at `at_ms`.
+pub fn freeze(at_ms: u64) -> Frozen {
+    FROZEN_MS.with(|f| f.set(Some(at_ms)));
+    Frozen { _thread: PhantomData }
+}
+
+impl Frozen {
+    /// Move the frozen clock forward by `ms`.
+    pub fn advance(&self, ms: u64) {
+        FROZEN_MS.with(|f| f.set(Some(f.get().unwrap_or(0).saturating_add(ms))));
+    }
+
+    /// Move the frozen clock forward by `secs`.
+    pub fn advance_secs(&self, secs: u64) {
+        self.advance(secs.saturating_mul(1000));
+    }
+}
+
+impl Drop for Frozen {
+    fn drop(&mut self) {
+        FROZEN_MS.with(|f| f.set(None));
+    }
+}
+
+/// A seeded RNG on the current thread. Dropping it restores `thread_rng`.
+pub struct Seeded {
+    _thread: PhantomData<*const ()>,
+}
+
+/// Make this thread's [`with_rng`] draws reproducible from `seed`.
+pub fn seed_rng(seed: u64) -> Seeded {
+    SEEDED.with(|s| *s.borrow_mut() = Some(StdRng::seed_from_u64(seed)));
+    Seeded { _thread: PhantomData }
+}
+
+impl Drop for Seeded {
+    fn drop(&mut self) {
+        SEEDED.with(|s| *s.borrow_mut() = None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn frozen_time_and_seeded_draws_are_reproducible() {
+        {
+            let clock = freeze(1_700_000_000_500);
+            assert_eq!(now_secs(), 1_700_000_000);
+            clock.advance_secs(30);
+            assert_eq!(now_ms(), 1_700_000_030_500);
+            // Other threads keep the real clock
+            let elsewhere = std::thread::spawn(now_secs).join().unwrap();
+            assert!(elsewhere > 1_700_000_030);
+        }
+        assert!(now_secs() > 1_700_000_030, "the guard restores the system clock");
+
+        let draw = || with_rng(|rng| (0..4).map(|_| rng.gen_range(0..1000u32)).collect::<Vec<_>>());
+        let first = { let _rng = seed_rng(7); draw() };
+        let again = { let _rng = seed_rng(7); draw() };
+        assert_eq!(first, again);
+    }
+}
@@ -0,0 +1,219 @@
+//! Code in message text — fenced blocks (with their language hints) and inline spans.
+//!
+//! Mirrors the renderer's markdown rules closely enough that everything it shows as code is
+//! found here: a fence is 3+ backticks or tildes on their own line (indented at most three
+//! spaces), closed by a matching fence or the end of the text. Link previews and mention
+//! parsing use [`strip_code`] so a URL or npub pasted inside code stays literal.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// One fenced code block.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct CodeBlock {
+    /// The first word of the fence's info string (` ```rust ` → `rust`), if any.
+    pub language: Option<String>,
+    /// The block's text, without the fence lines.
+    pub code: String,
+}
+
+struct Fence {
+    /// The whole block, fence lines included.
+    range: Range<usize>,
+    language: Option<String>,
+    code: Range<usize>,
+}
+
+/// An opening or closing fence line: (fence char, run length, info string).
+fn fence_line(line: &str) -> Option<(u8, usize, &str)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let ch = *trimmed.as_bytes().first()?;
+    if ch != b'`' && ch != b'~' {
+        return None;
+    }
+    let run = trimmed.bytes().take_while(|&b| b == ch).count();
+    if run < 3 {
+        return None;
+    }
+    let info = trimmed[run..].trim();
+    // A backtick fence's info string can't itself contain backticks (that's inline code)
+    if ch == b'`' && info.contains('`') {
+        return None;
+    }
+    Some((ch, run, info))
+}
+
+fn fences(content: &str) -> Vec<Fence> {
+    let mut out = Vec::new();
+    let mut open: Option<(u8, usize, usize, Option<String>, usize)> = None;
+    let mut pos = 0;
+    for line in content.split_inclusive('\n') {
+        let start = pos;
+        pos += line.len();
+        let text = line.trim_end_matches(['\n', '\r']);
+        match open.take() {
+            None => {
+                if let Some((ch, run, info)) = fence_line(text) {
+                    let language = info.split_whitespace().next().map(str::to_string);
+                    open = Some((ch, run, start, language, pos));
+                }
+            }
+            Some((ch, run, block_start, language, code_start)) => {
+                let closes = fence_line(text)
+                    .is_some_and(|(c, r, info)| c == ch && r >= run && info.is_empty());
+                if closes {
+                    out.push(Fence { range: block_start..pos, language, code: code_start..start });
+                } else {
+                    open = Some((ch, run, block_start, language, code_start));
+                }
+            }
+        }
+    }
+    // An unclosed fence runs to the end of the text
+    if let Some((_, _, block_start, language, code_start)) = open {
+        out.push(Fence { range: block_start..content.len(), language, code: code_start.min(content.len())..content.len() });
+    }
+    out
+}
+
+/// Every fenced code block, in order.
+pub fn code_blocks(content: &str) -> Vec<CodeBlock> {
+    fences(content)
+        .into_iter()
+        .map(|f| CodeBlock {
+            language: f.language,
+            code: content[f.code].trim_end_matches(['\n', '\r']).to_string(),
+        })
+        .collect()
+}
+
+/// The language hint of each fenced block, in order (empty string when the fence has none).
+pub fn code_languages(content: &str) -> Vec<String> {
+    if !content.contains("```") && !content.contains("~~~") {
+        return Vec::new();
+    }
+    fences(content).into_iter().map(|f| f.language.unwrap_or_default()).collect()
+}
+
+/// Byte ranges of all code: fenced blocks (fence lines included) and inline `code` spans.
+pub fn code_ranges(content: &str) -> Vec<Range<usize>> {
+    let blocks: Vec<Range<usize>> = fences(content).into_iter().map(|f| f.range).collect();
+    let mut ranges = Vec::new();
+    let mut gap_start = 0;
+    for block in blocks.iter().chain(std::iter::once(&(content.len()..content.len()))) {
+        ranges.extend(inline_spans(content, gap_start..block.start));
+        if block.start < block.end {
+            ranges.push(block.clone());
+        }
+        gap_start = block.end;
+    }
+    ranges
+}
+
+/// Inline code spans within `within`: a run of N backticks closed by the next run of exactly N.
+fn inline_spans(content: &str, within: Range<usize>) -> Vec<Range<usize>> {
+    let bytes = &content.as_bytes()[within.clone()];
+    let run_at = |i: usize| bytes[i..].iter().take_while(|&&b| b == b'`').count();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let run = run_at(i);
+        let mut j = i + run;
+        let mut close = None;
+        while j < bytes.len() {
+            if bytes[j] == b'`' {
+                let r = run_at(j);
+                if r == run {
+                    close = Some(j + r);
+                    break;
+                }
+                j += r;
+            } else {
+                j += 1;
+            }
+        }
+        match close {
+            Some(end) => {
+                out.push(within.start + i..within.start + end);
+                i = end;
+            }
+            // No closer: the backticks are literal
+            None => i += run,
+        }
+    }
+    out
+}
+
+/// `content` with every code range replaced by a single space (so words either side stay
+/// apart). Borrowed when there's no code at all.
+pub fn strip_code(content: &str) -> Cow<'_, str> {
+    if !content.contains('`') && !content.contains("~~~") {
+        return Cow::Borrowed(content);
+    }
+    let ranges = code_ranges(content);
+    if ranges.is_empty() {
+        return Cow::Borrowed(content);
+    }
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for r in ranges {
+        out.push_str(&content[last..r.start]);
+        out.push(' ');
+        last = r.end;
+    }
+    out.push_str(&content[last..]);
+    Cow::Owned(out)
+}
+
+/// Whether byte offset `at` falls inside code.
+pub fn in_code(ranges: &[Range<usize>], at: usize) -> bool {
+    ranges.iter().any(|r| r.contains(&at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fenced_blocks_with_languages() {
+        let text = "look:\n```rust\nfn main() {}\n```\nand\n~~~\nplain\n~~~\n";
+        assert_eq!(code_blocks(text), vec![
+            CodeBlock { language: Some("rust".into()), code: "fn main() {}".into() },
+            CodeBlock { language: None, code: "plain".into() },
+        ]);
+        assert_eq!(code_languages(text), vec!["rust".to_string(), String::new()]);
+        assert!(code_languages("no code here").is_empty());
+    }
+
+    #[test]
+    fn fences_need_a_matching_closer() {
+        // A shorter or different fence doesn't close; an unclosed block runs to the end
+        let text = "````js\nconst a = \"```\";\n~~~~\nmore\n";
+        let blocks = code_blocks(text);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].language.as_deref(), Some("js"));
+        assert_eq!(blocks[0].code, "const a = \"```\";\n~~~~\nmore");
+    }
+
+    #[test]
+    fn strip_code_hides_urls_and_mentions_in_code() {
+        let text = "see https://a.example and `https://b.example`\n```\nhttps://c.example\n```\n``x `y` z`` done";
+        let stripped = strip_code(text);
+        assert!(stripped.contains("https://a.example"));
+        assert!(!stripped.contains("b.example"));
+        assert!(!stripped.contains("c.example"));
+        assert!(!stripped.contains('y'), "double-backtick span swallows the single-backtick pair");
+        assert!(stripped.ends_with(" done"));
+
+        // Unpaired backticks are literal text
+        assert_eq!(strip_code("it`s fine"), "it`s fine");
+        assert!(matches!(strip_code("no code"), Cow::Borrowed(_)));
+    }
+}
@@ -1,8 +1,9 @@
 //! Community message attachments (NIP-92 `imeta`).
 //!
-//! Unlike NIP-17 DMs (one media item per event), a Community message event carries its
-//! caption in `content` plus one `imeta` tag per attachment — so a single message can
-//! mix text and N files. Each `imeta` holds the per-file AES-GCM key+nonce (the NIP-17
+//! A Community message event carries its caption in `content` plus one `imeta` tag per
+//! attachment — so a single message can mix text and N files. Gallery DMs reuse the same
+//! encoding: their Kind 15 rumor adds one `imeta` per file next to the legacy flat tags
+//! (see `sending::send_files_dm`). Each `imeta` holds the per-file AES-GCM key+nonce (the NIP-17
 //! attachment technique: fresh random key per file), so the Blossom ciphertext is only
 //! decryptable by members who can open the event.
 
@@ -42,6 +43,13 @@ pub fn attachment_to_imeta(att: &Attachment) -> Tag {
     if let Some(topic) = att.webxdc_topic.as_deref().filter(|t| !t.is_empty()) {
         fields.push(format!("webxdc-topic {}", topic));
     }
+    if att.view_once {
+        fields.push("view-once 1".to_string());
+    }
+    // NIP-94 `fallback`, once per mirror (see `blossom::mirror_blob`)
+    for mirror in &att.mirrors {
+        fields.push(format!("fallback {}", mirror));
+    }
     Tag::custom(TagKind::Custom(IMETA.into()), fields)
 }
 
@@ -54,6 +62,11 @@ fn field<'a>(entries: &'a [String], key: &str) -> Option<&'a str> {
     })
 }
 
+/// Every value of a field that may repeat (e.g. `fallback`).
+fn fields<'a>(entries: &'a [String], key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    entries.iter().filter_map(move |e| e.strip_prefix(key).and_then(|rest| rest.strip_prefix(' ')))
+}
+
 /// Parse a single `imeta` tag into an [`Attachment`]. `None` if the tag isn't an `imeta`
 /// or is missing the required url / decryption fields. `download_dir` computes the
 /// (not-yet-downloaded) local target path, mirroring the DM file-attachment path.
@@ -138,6 +151,8 @@ pub fn attachment_from_imeta(tag: &Tag, download_dir: &Path) -> Option<Attachmen
         .filter(|t| t.len() == 52 && t.bytes().all(|b| b.is_ascii_uppercase() || (b'2'..=b'7').contains(&b)))
         .map(|t| t.to_string());
 
+    let mirrors = crate::blossom::mirror_urls(fields(body, "fallback"), &url);
+
     Some(Attachment {
         id: basis,
         key,
@@ -153,6 +168,11 @@ pub fn attachment_from_imeta(tag: &Tag, download_dir: &Path) -> Option<Attachmen
         webxdc_topic,
         group_id: None, // Community attachments use explicit key/nonce (NIP-17 technique).
         original_hash,
+        mirrors,
+        view_once: field(body, "view-once") == Some("1"),
+        video_note: false,
+        viewed: false,
+        transcript: None,
     })
 }
 
@@ -219,6 +239,11 @@ mod tests {
             webxdc_topic: None,
             group_id: None,
             original_hash: Some("a".repeat(64)),
+            mirrors: Vec::new(),
+            view_once: false,
+            video_note: false,
+            viewed: false,
+            transcript: None,
         }
     }
 
@@ -289,6 +314,16 @@ mod tests {
         assert!(!parsed.downloaded, "a digest-named file is never proof of download");
     }
 
+    #[test]
+    fn mirrors_ride_the_imeta_as_fallbacks() {
+        let dir = std::env::temp_dir();
+        let mut att = sample("pic.png", "png", false);
+        att.mirrors = vec!["https://mirror-1.example/abc".into(), "https://mirror-2.example/abc".into()];
+        let tag = attachment_to_imeta(&att);
+        assert!(tag.as_slice().iter().any(|e| e == "fallback https://mirror-1.example/abc"));
+        assert_eq!(attachment_from_imeta(&tag, &dir).unwrap().mirrors, att.mirrors);
+    }
+
     #[test]
     fn imeta_round_trip_preserves_crypto_and_meta() {
         let dir = std::env::temp_dir();
@@ -390,6 +425,11 @@ mod tests {
             webxdc_topic: None,
             group_id: None,
             original_hash: Some("c".repeat(64)),
+            mirrors: Vec::new(),
+            view_once: false,
+            video_note: false,
+            viewed: false,
+            transcript: None,
         };
         let parsed = attachment_from_imeta(&attachment_to_imeta(&att), &dir).expect("parses");
         // The parsed key/nonce (straight off the imeta) must decrypt the ciphertext.
@@ -214,6 +214,23 @@ pub fn take_ready_preload(community_id: &str) -> Option<Vec<Event>> {
     }
 }
 
+/// Activity in a community's warmed page, without taking it (the invite preview's estimate): how
+/// many of its events were sent at or after `since_secs`, and the newest send time. `None` unless a
+/// fresh page is Ready.
+pub fn peek_preload_activity(community_id: &str, since_secs: u64) -> Option<(usize, Option<u64>)> {
+    let generation = crate::state::current_session_generation();
+    let map = preload_locked();
+    match map.get(community_id) {
+        Some(Preload { state: PreloadState::Ready(page), fetched_at, generation: g })
+            if *g == generation && fetched_at.elapsed() < PRELOAD_TTL =>
+        {
+            let recent = page.iter().filter(|e| e.created_at.as_secs() >= since_secs).count();
+            Some((recent, page.iter().map(|e| e.created_at.as_secs()).max()))
+        }
+        _ => None,
+    }
+}
+
 /// Adopt a community's warm-up as this sync's page: Ready → take it; Pending → await it (the
 /// in-flight fetch IS the page, so this waits only the request's remaining time, never firing a
 /// second); absent/stale/failed → `None` so the caller fetches normally. Polls at coarse granularity
@@ -0,0 +1,191 @@
+//! Diagnostics for a Community that "stopped decrypting".
+//!
+//! A snapshot of what this device holds: the epoch each scope is on, every epoch key it has (and
+//! when it arrived — the observable rekey history), the folded control state, how far channel sync
+//! has paged, and how much is stored. Enough to tell a missed rekey from a sync stall without
+//! shipping logs. Key material never leaves the DB; only epochs and timestamps are reported.
+
+use super::{CommunityId, ConcordProtocol, SERVER_ROOT_SCOPE_HEX};
+
+/// Entries of the rekey history kept in a snapshot (newest).
+const HISTORY_LIMIT: usize = 50;
+
+/// One epoch key arriving for a scope.
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct EpochRecord {
+    /// Channel name, or `@everyone` for the server root.
+    pub scope: String,
+    pub epoch: u64,
+    /// When the key was stored here (secs).
+    pub received_at: u64,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ChannelDebugInfo {
+    pub id: String,
+    pub name: String,
+    /// The epoch new messages are read and sent at.
+    pub epoch: u64,
+    /// Every epoch we hold a key for; a gap below `epoch` is history we can't open.
+    pub held_epochs: Vec<u64>,
+    pub messages: usize,
+    /// Sync cursors this session (secs): the oldest and newest relay page fetched.
+    pub oldest_cursor: Option<u64>,
+    pub newest_cursor: Option<u64>,
+    pub at_history_start: bool,
+}
+
+/// An edition (versioned control document) we've folded.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct EditionHeadInfo {
+    pub entity_id: String,
+    pub version: u64,
+    pub epoch: u64,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CommunityDebugInfo {
+    pub id: String,
+    pub protocol: &'static str,
+    pub root_epoch: u64,
+    pub held_root_epochs: Vec<u64>,
+    pub dissolved: bool,
+    /// A base rotation is waiting for every channel to follow (and the epoch it's cutting to).
+    pub read_cut_pending: bool,
+    pub read_cut_target_epoch: u64,
+    pub relays: Vec<String>,
+    pub channels: Vec<ChannelDebugInfo>,
+    /// Newest first.
+    pub epoch_history: Vec<EpochRecord>,
+    pub edition_heads: Vec<EditionHeadInfo>,
+    /// `created_at` (secs) of the banlist and role editions in force; 0 when none.
+    pub banlist_at: i64,
+    pub roles_at: i64,
+    pub banned: usize,
+    pub stored_epoch_keys: usize,
+    pub stored_messages: usize,
+}
+
+/// Snapshot a Community's state for a debug report.
+pub fn debug_info(community_id: &str) -> Result<CommunityDebugInfo, String> {
+    let id = CommunityId(crate::db::community::hex_id_to_32(community_id)?);
+    let protocol = crate::db::community::community_protocol(&id)?.ok_or("Community not found")?;
+    let (root_epoch, dissolved, relays, channels): (u64, bool, Vec<String>, Vec<(String, String, u64)>) = match protocol {
+        ConcordProtocol::V2 => {
+            let c = crate::db::community::load_community_v2(&id)?.ok_or("Community not found")?;
+            let channels = c.channels.iter().map(|ch| (ch.id.to_hex(), ch.name.clone(), ch.epoch.0)).collect();
+            (c.root_epoch.0, c.dissolved, c.relays, channels)
+        }
+        ConcordProtocol::V1 => {
+            let c = crate::db::community::load_community(&id)?.ok_or("Community not found")?;
+            let channels = c.channels.iter().map(|ch| (ch.id.to_hex(), ch.name.clone(), ch.epoch.0)).collect();
+            (c.server_root_epoch.0, c.dissolved, c.relays, channels)
+        }
+    };
+
+    let history = crate::db::community::epoch_key_history(community_id)?;
+    let held = |scope: &str| -> Vec<u64> {
+        let mut epochs: Vec<u64> = history.iter().filter(|(s, _, _)| s == scope).map(|(_, e, _)| *e).collect();
+        epochs.sort_unstable();
+        epochs
+    };
+    let scope_name = |scope: &str| -> String {
+        if scope == SERVER_ROOT_SCOPE_HEX {
+            return "@everyone".to_string();
+        }
+        channels.iter().find(|(id, _, _)| id == scope).map(|(_, name, _)| format!("#{}", name))
+            .unwrap_or_else(|| scope.chars().take(12).collect())
+    };
+
+    let channel_infos: Vec<ChannelDebugInfo> = channels.iter()
+        .map(|(ch_id, name, epoch)| ChannelDebugInfo {
+            id: ch_id.clone(),
+            name: name.clone(),
+            epoch: *epoch,
+            held_epochs: held(ch_id),
+            messages: crate::db::id_cache::get_chat_id_by_identifier(ch_id)
+                .and_then(crate::db::events::get_chat_message_count)
+                .unwrap_or(0),
+            oldest_cursor: super::cache::oldest_cursor(ch_id),
+            newest_cursor: super::cache::newest_cursor(ch_id),
+            at_history_start: super::cache::is_at_history_start(ch_id),
+        })
+        .collect();
+
+    let mut edition_heads: Vec<EditionHeadInfo> = crate::db::community::get_all_edition_heads_epoched(community_id)?
+        .into_iter()
+        .map(|(entity_id, (epoch, version, _))| EditionHeadInfo { entity_id, version, epoch })
+        .collect();
+    edition_heads.sort_by(|a, b| a.entity_id.cmp(&b.entity_id));
+
+    let epoch_history = history.iter().rev().take(HISTORY_LIMIT)
+        .map(|(scope, epoch, at)| EpochRecord { scope: scope_name(scope), epoch: *epoch, received_at: *at })
+        .collect();
+
+    Ok(CommunityDebugInfo {
+        id: community_id.to_string(),
+        protocol: match protocol {
+            ConcordProtocol::V1 => "v1",
+            ConcordProtocol::V2 => "v2",
+        },
+        root_epoch,
+        held_root_epochs: held(SERVER_ROOT_SCOPE_HEX),
+        dissolved,
+        read_cut_pending: crate::db::community::get_read_cut_pending(community_id)?,
+        read_cut_target_epoch: crate::db::community::get_read_cut_target_epoch(community_id)?,
+        relays,
+        stored_messages: channel_infos.iter().map(|c| c.messages).sum(),
+        channels: channel_infos,
+        epoch_history,
+        edition_heads,
+        banlist_at: crate::db::community::get_community_banlist_at(community_id)?,
+        roles_at: crate::db::community::get_community_roles_at(community_id)?,
+        banned: crate::db::community::get_community_banlist(community_id)?.len(),
+        stored_epoch_keys: history.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_reports_epochs_without_keys() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        crate::db::clear_id_caches();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+
+        let owner = nostr_sdk::prelude::Keys::generate();
+        let g = crate::community::v2::control::genesis(
+            &owner,
+            crate::community::v2::control::CommunityMetadata { name: "Debug".into(), ..Default::default() },
+            1_000,
+        )
+        .unwrap();
+        let c = crate::community::v2::community::CommunityV2::from_genesis(&g, "Debug", None, vec!["wss://r".into()], 1_000);
+        crate::db::community::save_community_v2(&c).unwrap();
+        let cid = c.id().to_hex();
+        let general = c.channels[0].id.to_hex();
+        crate::db::community::store_epoch_key(&cid, SERVER_ROOT_SCOPE_HEX, 0, &c.community_root).unwrap();
+        crate::db::community::store_epoch_key(&cid, &general, 1, &[9u8; 32]).unwrap();
+
+        let info = debug_info(&cid).unwrap();
+        assert_eq!(info.protocol, "v2");
+        assert_eq!(info.held_root_epochs, vec![0]);
+        assert_eq!(info.channels[0].name, "general");
+        assert_eq!(info.channels[0].held_epochs, vec![1]);
+        assert_eq!(info.epoch_history.len(), info.stored_epoch_keys);
+        assert!(info.epoch_history.iter().any(|r| r.scope == "#general" && r.epoch == 1));
+
+        let json = serde_json::to_string(&info).unwrap();
+        assert!(!json.contains(&crate::simd::hex::bytes_to_hex_32(&[9u8; 32])), "no key material");
+        assert!(debug_info(&"0".repeat(64)).is_err());
+    }
+}
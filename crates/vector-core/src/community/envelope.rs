@@ -565,6 +565,11 @@ mod tests {
             webxdc_topic: None,
             group_id: None,
             original_hash: Some("a".repeat(64)),
+            mirrors: Vec::new(),
+            view_once: false,
+            video_note: false,
+            viewed: false,
+            transcript: None,
         };
         let imetas = vec![
             super::super::attachments::attachment_to_imeta(&mk("photo.png", "png", true)),
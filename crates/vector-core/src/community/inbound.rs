@@ -859,7 +859,7 @@ mod tests {
             id: "x".into(), key: "0".repeat(64), nonce: format!("{:0<24}", crate::simd::hex::bytes_to_hex_string(n.as_bytes())),
             extension: ext.into(), name: n.into(), url: format!("https://b/{n}"),
             path: String::new(), size: 9, img_meta: None, downloading: false, downloaded: false,
-            webxdc_topic: None, group_id: None, original_hash: Some("a".repeat(64)),
+            webxdc_topic: None, group_id: None, original_hash: Some("a".repeat(64)), mirrors: Vec::new(), view_once: false, video_note: false, viewed: false, transcript: None,
         };
         let imetas = vec![attachment_to_imeta(&mk("a.png", "png")), attachment_to_imeta(&mk("b.txt", "txt"))];
         let inner = build_inner_full(
@@ -0,0 +1,240 @@
+//! Delivery tracking for the Community invites we send.
+//!
+//! An invite is a one-shot gift wrap to the invitee's DM relays; if every relay hiccups, the
+//! invitee never learns they were invited and nobody notices. Each send is recorded per invitee
+//! (`db::community::record_invite_attempt`): delivered once a relay OKs it, joined once they show up
+//! in the member list (their join presence is the acceptance receipt). [`run_retry_loop`]
+//! re-sends the undelivered ones with backoff. [`invite_status`] and [`list_outgoing_invites`]
+//! report it all to the inviter, and [`revoke_invite`] withdraws one that hasn't been acted on.
+
+use std::time::Duration;
+
+use crate::db::community::InviteDeliveryRow;
+use crate::ids::UserId;
+
+/// Sends per invite before we give up on it.
+pub const MAX_ATTEMPTS: u32 = 6;
+
+/// How often the retry loop looks for undelivered invites.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Wait before the first re-send; doubles per attempt.
+const BASE_BACKOFF_SECS: i64 = 60;
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InviteDeliveryState {
+    /// No relay has accepted it yet; a re-send is scheduled.
+    Pending,
+    /// A relay accepted it.
+    Delivered,
+    /// The invitee has joined.
+    Joined,
+    /// Every attempt failed; re-invite to try again.
+    Failed,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct InviteDelivery {
+    pub community_id: String,
+    pub invitee: String,
+    pub state: InviteDeliveryState,
+    pub attempts: u32,
+    pub first_sent_at: i64,
+    pub last_attempt_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub joined_at: Option<i64>,
+}
+
+fn state_of(row: &InviteDeliveryRow) -> InviteDeliveryState {
+    if row.joined_at.is_some() {
+        InviteDeliveryState::Joined
+    } else if row.delivered_at.is_some() {
+        InviteDeliveryState::Delivered
+    } else if row.attempts >= MAX_ATTEMPTS {
+        InviteDeliveryState::Failed
+    } else {
+        InviteDeliveryState::Pending
+    }
+}
+
+/// Whether an undelivered invite is due another send at `now`.
+fn retry_due(row: &InviteDeliveryRow, now: i64) -> bool {
+    let backoff = BASE_BACKOFF_SECS << row.attempts.saturating_sub(1).min(10);
+    row.attempts < MAX_ATTEMPTS && now - row.last_attempt_at >= backoff
+}
+
+fn now_secs() -> i64 {
+    crate::clock::now_secs() as i64
+}
+
+/// Record one send of an invite to `invitee_npub`; `delivered` = at least one relay OK'd it.
+/// Tracking is best-effort and never fails the invite itself.
+pub fn record_attempt(community_id: &str, invitee_npub: &str, delivered: bool) {
+    let Ok(invitee) = UserId::parse(invitee_npub) else { return };
+    if let Err(e) = crate::db::community::record_invite_attempt(community_id, &invitee.hex(), delivered) {
+        crate::log_warn!("[Invites] Failed to record invite delivery: {}", e);
+    }
+}
+
+/// Stamp every tracked invitee of `community_id` who is now a member as joined.
+async fn refresh_joined(community_id: &str, rows: &mut [InviteDeliveryRow]) {
+    if rows.iter().all(|r| r.joined_at.is_some()) {
+        return;
+    }
+    let members: std::collections::HashSet<String> = crate::VectorCore
+        .get_community_members(community_id)
+        .await
+        .iter()
+        .filter_map(|m| m.get("npub").and_then(|n| n.as_str()))
+        .filter_map(|n| UserId::parse(n).ok())
+        .map(|user| user.hex())
+        .collect();
+    for row in rows.iter_mut().filter(|r| r.joined_at.is_none() && members.contains(&r.invitee)) {
+        if crate::db::community::mark_invite_joined(community_id, &row.invitee).is_ok() {
+            row.joined_at = Some(now_secs());
+        }
+    }
+}
+
+fn to_delivery(row: &InviteDeliveryRow) -> InviteDelivery {
+    InviteDelivery {
+        community_id: row.community_id.clone(),
+        invitee: UserId::parse(&row.invitee).map(|user| user.npub()).unwrap_or_else(|_| row.invitee.clone()),
+        state: state_of(row),
+        attempts: row.attempts,
+        first_sent_at: row.first_sent_at,
+        last_attempt_at: row.last_attempt_at,
+        delivered_at: row.delivered_at,
+        joined_at: row.joined_at,
+    }
+}
+
+/// Delivery state of every invite we've sent for a Community. Only this account's own sends are
+/// tracked, so there's nothing to gate: it's the inviter's record of their invites.
+pub async fn invite_status(community_id: &str) -> Result<Vec<InviteDelivery>, String> {
+    let mut rows = crate::db::community::list_invite_deliveries(community_id)?;
+    refresh_joined(community_id, &mut rows).await;
+    Ok(rows.iter().map(to_delivery).collect())
+}
+
+/// Every invite we've sent, across all communities, newest first; joins are detected from each
+/// community's member list like [`invite_status`].
+pub async fn list_outgoing_invites() -> Result<Vec<InviteDelivery>, String> {
+    let mut rows = crate::db::community::list_all_invite_deliveries()?;
+    let mut communities: Vec<String> = rows.iter().map(|r| r.community_id.clone()).collect();
+    communities.sort();
+    communities.dedup();
+    for community_id in communities {
+        let mut community_rows: Vec<InviteDeliveryRow> =
+            rows.iter().filter(|r| r.community_id == community_id).cloned().collect();
+        refresh_joined(&community_id, &mut community_rows).await;
+        for joined in community_rows.into_iter().filter(|r| r.joined_at.is_some()) {
+            if let Some(row) = rows.iter_mut().find(|r| r.community_id == joined.community_id && r.invitee == joined.invitee) {
+                row.joined_at = joined.joined_at;
+            }
+        }
+    }
+    Ok(rows.iter().map(to_delivery).collect())
+}
+
+/// Withdraw an invite the invitee hasn't acted on: stops its re-sends and drops it from the
+/// list. An invite a relay already accepted can't be unsent (the bundle carries the keys), so
+/// fully cutting that invitee off still takes a rekey; one that's joined must be removed as a
+/// member instead.
+pub async fn revoke_invite(community_id: &str, invitee_npub: &str) -> Result<(), String> {
+    let invitee = UserId::parse(invitee_npub).map_err(|_| "invalid npub".to_string())?.hex();
+    let mut rows: Vec<InviteDeliveryRow> = crate::db::community::list_invite_deliveries(community_id)?
+        .into_iter()
+        .filter(|r| r.invitee == invitee)
+        .collect();
+    if rows.is_empty() {
+        return Err("No invite to revoke".to_string());
+    }
+    refresh_joined(community_id, &mut rows).await;
+    if rows[0].joined_at.is_some() {
+        return Err("They've already joined; remove them from the community instead".to_string());
+    }
+    crate::db::community::delete_invite_delivery(community_id, &invitee)?;
+    Ok(())
+}
+
+/// Re-send every undelivered invite whose backoff has elapsed. Invitees who joined anyway (a
+/// relay stored it without an OK) are stamped and skipped. Each re-send is recorded once, here.
+pub async fn retry_undelivered() {
+    let session = crate::state::SessionGuard::capture();
+    if crate::state::nostr_client().is_none() {
+        return;
+    }
+    let Ok(mut rows) = crate::db::community::undelivered_invites(MAX_ATTEMPTS) else { return };
+    let now = now_secs();
+    rows.retain(|r| retry_due(r, now));
+    let mut communities: Vec<String> = rows.iter().map(|r| r.community_id.clone()).collect();
+    communities.sort();
+    communities.dedup();
+    for community_id in communities {
+        let mut community_rows: Vec<InviteDeliveryRow> =
+            rows.iter().filter(|r| r.community_id == community_id).cloned().collect();
+        refresh_joined(&community_id, &mut community_rows).await;
+        for row in community_rows.iter().filter(|r| r.joined_at.is_none()) {
+            if !session.is_valid() {
+                return;
+            }
+            let Ok(npub) = UserId::parse(&row.invitee).map(|user| user.npub()) else {
+                continue;
+            };
+            let mut published = None;
+            let result = crate::VectorCore.send_community_invite(&community_id, &npub, &mut published).await;
+            // Failing before publishing (lost permission, banned since) still counts, so the
+            // invite runs out of attempts instead of retrying forever.
+            record_attempt(&community_id, &npub, published.unwrap_or(false));
+            match result {
+                Ok(_) => crate::log_info!("[Invites] Re-sent undelivered invite (attempt {})", row.attempts + 1),
+                Err(e) => crate::log_warn!("[Invites] Re-sending invite failed: {}", e),
+            }
+        }
+    }
+}
+
+/// The retry loop. Hosts with their own async runtime (e.g. Tauri) should spawn this directly.
+pub async fn run_retry_loop() {
+    loop {
+        tokio::time::sleep(RETRY_INTERVAL).await;
+        retry_undelivered().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(attempts: u32, last_attempt_at: i64) -> InviteDeliveryRow {
+        InviteDeliveryRow {
+            community_id: "c".into(),
+            invitee: "i".into(),
+            attempts,
+            first_sent_at: 0,
+            last_attempt_at,
+            delivered_at: None,
+            joined_at: None,
+        }
+    }
+
+    #[test]
+    fn retries_back_off_and_give_up() {
+        assert!(!retry_due(&row(1, 1_000), 1_000 + 59));
+        assert!(retry_due(&row(1, 1_000), 1_000 + 60));
+        assert!(!retry_due(&row(3, 1_000), 1_000 + 239));
+        assert!(retry_due(&row(3, 1_000), 1_000 + 240));
+        assert!(!retry_due(&row(MAX_ATTEMPTS, 0), i64::MAX / 2));
+
+        assert_eq!(state_of(&row(MAX_ATTEMPTS, 0)), InviteDeliveryState::Failed);
+        assert_eq!(state_of(&InviteDeliveryRow { delivered_at: Some(5), ..row(1, 0) }), InviteDeliveryState::Delivered);
+        assert_eq!(
+            state_of(&InviteDeliveryRow { delivered_at: Some(5), joined_at: Some(9), ..row(1, 0) }),
+            InviteDeliveryState::Joined
+        );
+    }
+}
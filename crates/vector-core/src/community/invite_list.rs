@@ -16,6 +16,7 @@ use std::collections::HashMap;
 
 use super::list::{CommunityList, COMMUNITY_LIST_D_TAG};
 use crate::stored_event::event_kind;
+use crate::clock::now_secs;
 
 /// Rides the same NIP-78 (kind 30078) parameterized-replaceable, NIP-44-self-encrypted machinery as the
 /// Community List, distinguished only by this `d`-tag — so a single REQ fetches both.
@@ -28,13 +29,6 @@ const LOCAL_INVITE_LIST_KEY: &str = "invite_list_json";
 const INVITE_LIST_PUBLISHED_AT_KEY: &str = "invite_list_published_at";
 const FETCH_TIMEOUT_SECS: u64 = 20;
 
-fn now_secs() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0)
-}
-
 /// One minted public-invite link. Immutable once minted (the token is the whole secret), so the merge can
 /// treat any copy as interchangeable. Timestamps are UNIX seconds, matching `community_public_invites`.
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
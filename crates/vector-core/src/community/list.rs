@@ -15,6 +15,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::stored_event::event_kind;
+use crate::clock::now_ms;
 
 /// The synced list rides a NIP-78 parameterized-replaceable event (kind 30078) addressed to ourselves,
 /// distinguished from the wallpaper/badge 30078s by this `d`-tag. NIP-44-self-encrypted content — the
@@ -28,14 +29,6 @@ const LOCAL_LIST_KEY: &str = "community_list_json";
 const LIST_PUBLISHED_AT_KEY: &str = "community_list_published_at";
 const FETCH_TIMEOUT_SECS: u64 = 20;
 
-/// Milliseconds since the epoch — the clock the merge tiebreaks on (`added_at`/`removed_at`).
-fn now_ms() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0)
-}
-
 /// One joined community in the synced list. Carries TWO full bundles: the stable `seed` (earliest/join
 /// — the full-history backfill anchor) and a refreshed `current` snapshot (latest root + latest channel
 /// keys + latest name). A fresh device reconstructs DIRECTLY from `current` → instant-latest with NO
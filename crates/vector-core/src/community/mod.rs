@@ -9,10 +9,12 @@
 pub mod attachments;
 pub mod cache;
 pub mod cipher;
+pub mod debug;
 pub mod derive;
 pub mod envelope;
 pub mod inbound;
 pub mod invite;
+pub mod invite_delivery;
 pub mod invite_list;
 pub mod list;
 pub mod metadata;
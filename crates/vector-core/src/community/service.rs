@@ -1488,21 +1488,22 @@ pub async fn republish_channel_metadata<T: Transport + ?Sized>(
 // Public (link) invites
 // ============================================================================
 
-/// Mint a public invite link for a Community the local user owns: snapshot its preview,
-/// build + publish the token-encrypted bundle to the Community relays, retain the token
-/// locally (for list/revoke), and return `(hex token, shareable URL)`.
-///
-/// Owner-only: the bundle grants the @everyone base (server-root) key, and minting the
-/// canonical link is an owner action. `SessionGuard`-gated around the token persist.
 /// A short, human-typable label for an unlabeled invite link. Crockford-ish base32 (no 0/1/I/O)
 /// so it's unambiguous to read and share aloud; 6 chars ≈ 1B combinations (collision-improbable).
 fn generate_invite_label() -> String {
     use rand::Rng;
     const ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
-    let mut rng = rand::thread_rng();
-    (0..6).map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())] as char).collect()
+    crate::clock::with_rng(|rng| {
+        (0..6).map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())] as char).collect()
+    })
 }
 
+/// Mint a public invite link for a Community the local user owns: snapshot its preview,
+/// build + publish the token-encrypted bundle to the Community relays, retain the token
+/// locally (for list/revoke), and return `(hex token, shareable URL)`.
+///
+/// Owner-only: the bundle grants the @everyone base (server-root) key, and minting the
+/// canonical link is an owner action. `SessionGuard`-gated around the token persist.
 pub async fn create_public_invite<T: Transport + ?Sized>(
     transport: &T,
     community: &Community,
@@ -507,18 +507,12 @@ fn plane_pool_insert(generation: u64, key: String, client: Client) -> Vec<Client
     evicted
 }
 
-/// Whether Full-drain timeout demotion may apply. Under Tor EVERY relay is
-/// legitimately slow — a first congested pass must not cascade into pool-wide
-/// starvation, so demotion is disabled entirely.
+/// Whether Full-drain timeout demotion may apply. Under Tor (or the user's
+/// proxy, commonly a Tor daemon) EVERY relay is legitimately slow — a first
+/// congested pass must not cascade into pool-wide starvation, so demotion is
+/// disabled entirely.
 fn demotion_allowed() -> bool {
-    #[cfg(feature = "tor")]
-    {
-        matches!(crate::tor::transport_state(), crate::tor::TorTransportState::Disabled)
-    }
-    #[cfg(not(feature = "tor"))]
-    {
-        true
-    }
+    matches!(crate::proxy::transport(), crate::proxy::Transport::Direct)
 }
 
 /// Fetch one relay to GENUINE EOSE, or fail. `Client::fetch_events_from` (and
@@ -16,6 +16,7 @@ use super::chat::{self, ChatEvent};
 use super::community::CommunityV2;
 use super::guestbook::{self, GuestbookEntry};
 use super::stream;
+use crate::context::lock_state;
 use crate::event_handler::InboundEventHandler;
 use crate::state::ChatState;
 use crate::types::{EmojiTag, Message, Reaction};
@@ -207,7 +208,7 @@ pub async fn persist_chat_event(
     session: &crate::state::SessionGuard,
 ) -> Option<ChatPersist> {
     let outcome = {
-        let mut st = crate::state::STATE.lock().await;
+        let mut st = lock_state().await;
         // A swap can land on the lock await: only mutate THIS account's STATE.
         if !session.is_valid() {
             return None;
@@ -846,6 +847,11 @@ mod tests {
             webxdc_topic: None,
             group_id: None,
             original_hash: Some("b".repeat(64)),
+            mirrors: Vec::new(),
+            view_once: false,
+            video_note: false,
+            viewed: false,
+            transcript: None,
         };
         let imeta = crate::community::attachments::attachment_to_imeta(&attachment);
         let member = Keys::generate();
@@ -24,6 +24,7 @@ use super::rekey::{self, Continuity, RekeyScope};
 use super::{guestbook, stream, vsk};
 use crate::community::edition::ParsedEdition;
 use crate::state::SessionGuard;
+use crate::clock::now_ms;
 
 /// The active signer for v2 authority actions: the live client's signer — which
 /// covers a NIP-46 bunker / NIP-55 offline signer — falling back to the local
@@ -61,13 +62,6 @@ fn me_pk() -> Result<PublicKey, String> {
     crate::state::my_public_key().ok_or_else(|| "no active identity".to_string())
 }
 
-fn now_ms() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0)
-}
-
 /// Create a fresh v2 community owned by the local identity: mint the genesis
 /// (self-certifying id + the two owner editions), persist, publish the genesis
 /// control editions, and announce the owner's Guestbook Join. Returns the saved
@@ -662,7 +656,9 @@ pub async fn mint_public_link<T: Transport + ?Sized>(
     if session.is_valid() {
         let cid_hex = crate::simd::hex::bytes_to_hex_32(&community.id().0);
         let token_hex = crate::simd::hex::bytes_to_hex_16(&minted.token);
-        let _ = crate::db::community::save_public_invite(&token_hex, &cid_hex, &minted.url, expires_at_ms.map(|e| e as i64), label.as_deref());
+        // The mirror keeps v1's unix seconds (the bundle carries ms).
+        let expires_at = expires_at_ms.map(|ms| (ms / 1000) as i64);
+        let _ = crate::db::community::save_public_invite(&token_hex, &cid_hex, &minted.url, expires_at, label.as_deref());
     }
     Ok(minted)
 }
@@ -10,7 +10,7 @@
 //! - Boxed optional fields (replied_to, wrapper_id) to save inline space
 //! - Compact timestamp (u32 seconds since 2020 epoch)
 
-use crate::types::{Attachment, EditEntry, ImageMetadata, Reaction, SiteMetadata};
+use crate::types::{Attachment, EditEntry, ImageMetadata, Reaction, SiteMetadata, Transcript};
 use crate::simd::hex::{bytes_to_hex_32, bytes_to_hex_string, hex_to_bytes_32};
 
 /// Decode a hex string of up to 32 hex chars into [u8; 16], left-aligned.
@@ -603,6 +603,9 @@ impl AttachmentFlags {
     const DOWNLOADING: u8 = 0b0001;
     const DOWNLOADED: u8  = 0b0010;
     const SHORT_NONCE: u8 = 0b0100; // 12-byte nonce (legacy) vs 16-byte (DM)
+    const VIEW_ONCE: u8   = 0b1000;
+    const VIEWED: u8      = 0b1_0000;
+    const VIDEO_NOTE: u8  = 0b10_0000;
 
     #[inline]
     pub fn is_downloading(self) -> bool { self.0 & Self::DOWNLOADING != 0 }
@@ -610,6 +613,12 @@ impl AttachmentFlags {
     pub fn is_downloaded(self) -> bool { self.0 & Self::DOWNLOADED != 0 }
     #[inline]
     pub fn is_short_nonce(self) -> bool { self.0 & Self::SHORT_NONCE != 0 }
+    #[inline]
+    pub fn is_view_once(self) -> bool { self.0 & Self::VIEW_ONCE != 0 }
+    #[inline]
+    pub fn is_viewed(self) -> bool { self.0 & Self::VIEWED != 0 }
+    #[inline]
+    pub fn is_video_note(self) -> bool { self.0 & Self::VIDEO_NOTE != 0 }
 
     #[inline]
     pub fn set_downloading(&mut self, value: bool) {
@@ -623,6 +632,18 @@ impl AttachmentFlags {
     pub fn set_short_nonce(&mut self, value: bool) {
         if value { self.0 |= Self::SHORT_NONCE; } else { self.0 &= !Self::SHORT_NONCE; }
     }
+    #[inline]
+    pub fn set_view_once(&mut self, value: bool) {
+        if value { self.0 |= Self::VIEW_ONCE; } else { self.0 &= !Self::VIEW_ONCE; }
+    }
+    #[inline]
+    pub fn set_viewed(&mut self, value: bool) {
+        if value { self.0 |= Self::VIEWED; } else { self.0 &= !Self::VIEWED; }
+    }
+    #[inline]
+    pub fn set_video_note(&mut self, value: bool) {
+        if value { self.0 |= Self::VIDEO_NOTE; } else { self.0 &= !Self::VIDEO_NOTE; }
+    }
 
     pub fn from_bools(downloading: bool, downloaded: bool) -> Self {
         let mut flags = Self::NONE;
@@ -673,6 +694,10 @@ pub struct CompactAttachment {
     pub original_hash: Option<Box<[u8; 32]>>,
     /// WebXDC topic (Mini Apps only - very rare)
     pub webxdc_topic: Option<Box<str>>,
+    /// Mirror URLs of the blob (only when the sender mirrored it)
+    pub mirrors: Option<Box<[Box<str>]>>,
+    /// Voice message transcript (only once transcribed)
+    pub transcript: Option<Box<Transcript>>,
     /// Original filename (e.g. "memories.zip"). Empty = fallback to {hash}.{ext}
     pub name: Box<str>,
 }
@@ -687,6 +712,15 @@ impl CompactAttachment {
     pub fn set_downloaded(&mut self, value: bool) { self.flags.set_downloaded(value); }
     #[inline]
     pub fn set_downloading(&mut self, value: bool) { self.flags.set_downloading(value); }
+    #[inline]
+    pub fn view_once(&self) -> bool { self.flags.is_view_once() }
+    #[inline]
+    pub fn viewed(&self) -> bool { self.flags.is_viewed() }
+
+    /// Replace the mirror URLs (e.g. once a mirrored upload finishes).
+    pub fn set_mirrors(&mut self, mirrors: &[String]) {
+        self.mirrors = box_mirrors(mirrors.iter().cloned());
+    }
 
     /// Check if this attachment's ID matches a hex string
     #[inline]
@@ -728,6 +762,9 @@ impl CompactAttachment {
         let is_short_nonce = att.nonce.len() == 24;
         let mut flags = AttachmentFlags::from_bools(att.downloading, att.downloaded);
         flags.set_short_nonce(is_short_nonce);
+        flags.set_view_once(att.view_once);
+        flags.set_viewed(att.viewed);
+        flags.set_video_note(att.video_note);
 
         Self {
             id: hex_to_bytes_32(&att.id),
@@ -742,6 +779,8 @@ impl CompactAttachment {
             group_id: att.group_id.as_ref().map(|s| Box::new(hex_to_bytes_32(s))),
             original_hash: att.original_hash.as_ref().map(|s| Box::new(hex_to_bytes_32(s))),
             webxdc_topic: att.webxdc_topic.clone().map(|s| s.into_boxed_str()),
+            mirrors: box_mirrors(att.mirrors.iter().cloned()),
+            transcript: att.transcript.clone().map(Box::new),
             name: att.name.clone().into_boxed_str(),
         }
     }
@@ -752,6 +791,9 @@ impl CompactAttachment {
         let is_short_nonce = att.nonce.len() == 24;
         let mut flags = AttachmentFlags::from_bools(att.downloading, att.downloaded);
         flags.set_short_nonce(is_short_nonce);
+        flags.set_view_once(att.view_once);
+        flags.set_viewed(att.viewed);
+        flags.set_video_note(att.video_note);
 
         Self {
             id: hex_to_bytes_32(&att.id),
@@ -766,6 +808,8 @@ impl CompactAttachment {
             group_id: att.group_id.map(|s| Box::new(hex_to_bytes_32(&s))),
             original_hash: att.original_hash.map(|s| Box::new(hex_to_bytes_32(&s))),
             webxdc_topic: att.webxdc_topic.map(|s| s.into_boxed_str()),
+            mirrors: box_mirrors(att.mirrors.into_iter()),
+            transcript: att.transcript.map(Box::new),
             name: att.name.into_boxed_str(),
         }
     }
@@ -787,10 +831,21 @@ impl CompactAttachment {
             webxdc_topic: self.webxdc_topic.as_ref().map(|s| s.to_string()),
             group_id: self.group_id.as_ref().map(|b| bytes_to_hex_32(b)),
             original_hash: self.original_hash.as_ref().map(|b| bytes_to_hex_32(b)),
+            mirrors: self.mirrors.as_deref().unwrap_or_default().iter().map(|s| s.to_string()).collect(),
+            view_once: self.flags.is_view_once(),
+            video_note: self.flags.is_video_note(),
+            viewed: self.flags.is_viewed(),
+            transcript: self.transcript.as_ref().map(|b| (**b).clone()),
         }
     }
 }
 
+/// Box an attachment's mirror URLs; `None` when it has none (the common case).
+fn box_mirrors(mirrors: impl Iterator<Item = String>) -> Option<Box<[Box<str>]>> {
+    let boxed: Box<[Box<str>]> = mirrors.map(String::into_boxed_str).collect();
+    (!boxed.is_empty()).then_some(boxed)
+}
+
 /// Parse a hex nonce string into [u8; 16], left-aligned, zero-allocation.
 /// Both DM (32 hex chars) and legacy (24 hex chars) nonces are decoded.
 /// Short nonces are right-padded with '0' to reach 32 chars before decode.
@@ -961,6 +1016,8 @@ pub struct CompactMessage {
     pub edit_history: Option<Box<Vec<EditEntry>>>,
     /// Link preview metadata - boxed since ~216 bytes but rare (saves ~208 bytes)
     pub preview_metadata: Option<Box<SiteMetadata>>,
+    /// Shared location - boxed since only location messages carry one
+    pub location: Option<Box<crate::location::Location>>,
     /// NIP-30 emoji tags travelling with this rumor — boxed because the
     /// vast majority of messages have none, so the cold path stays cheap.
     #[allow(clippy::box_collection)]
@@ -1523,6 +1580,7 @@ impl CompactMessage {
             // Box rare fields to save inline space
             edit_history: msg.edit_history.clone().map(Box::new),
             preview_metadata: msg.preview_metadata.clone().map(Box::new),
+            location: msg.location.map(Box::new),
             emoji_tags: if msg.emoji_tags.is_empty() {
                 None
             } else {
@@ -1574,6 +1632,7 @@ impl CompactMessage {
             // Box rare fields to save inline space
             edit_history: msg.edit_history.map(Box::new),
             preview_metadata: msg.preview_metadata.map(Box::new),
+            location: msg.location.map(Box::new),
             emoji_tags: if msg.emoji_tags.is_empty() {
                 None
             } else {
@@ -1618,12 +1677,17 @@ impl CompactMessage {
             // Unbox rare fields
             edit_history: self.edit_history.as_ref().map(|b| (**b).clone()),
             preview_metadata: self.preview_metadata.as_ref().map(|b| (**b).clone()),
+            location: self.location.as_deref().copied(),
             emoji_tags: self.emoji_tags.as_ref().map(|b| (**b).clone()).unwrap_or_default(),
             addressed_bots: self
                 .addressed_bots
                 .as_ref()
                 .map(|b| b.iter().filter_map(|&i| interner.resolve(i).map(|s| s.to_string())).collect())
                 .unwrap_or_default(),
+            code_languages: crate::code_blocks::code_languages(&self.content),
+            nostr_previews: crate::nostr_uri::cached_previews(&self.content),
+            zap_msat: crate::zaps::message_total(&self.id_hex()),
+            starred: crate::starred::is_starred(&self.id_hex()),
         }
     }
 }
@@ -1715,6 +1779,7 @@ mod tests {
             reactions: TinyVec::new(),
             edit_history: None,
             preview_metadata: None,  // Boxed, but None = 8 bytes
+            location: None,
             emoji_tags: None,
             addressed_bots: None,
         };
@@ -1734,6 +1799,7 @@ mod tests {
             reactions: TinyVec::new(),
             edit_history: None,
             preview_metadata: None,  // Boxed, but None = 8 bytes
+            location: None,
             emoji_tags: None,
             addressed_bots: None,
         };
@@ -1771,6 +1837,7 @@ mod tests {
             reactions: TinyVec::new(),
             edit_history: None,
             preview_metadata: None,  // Boxed
+            location: None,
             emoji_tags: None,
             addressed_bots: None,
         };
@@ -1834,8 +1901,13 @@ mod tests {
                     reactions: vec![],
                     edit_history: None,
                     preview_metadata: None,
+                    location: None,
                     emoji_tags: Vec::new(),
                     addressed_bots: Vec::new(),
+                    code_languages: Vec::new(),
+                    nostr_previews: Vec::new(),
+                    zap_msat: 0,
+                    starred: false,
                 }
             })
             .collect();
@@ -2732,6 +2804,7 @@ mod tests {
             reactions: TinyVec::new(),
             edit_history: None,
             preview_metadata: None,
+            location: None,
             emoji_tags: None,
             addressed_bots: None,
         }
@@ -3091,6 +3164,7 @@ mod tests {
                 description: Some("Desc".into()),
                 favicon: Some("https://example.com/favicon.ico".into()),
             }),
+            location: Some(crate::location::Location { lat: 52.37, lon: 4.9, accuracy: 10.0, live_until: 1893456000 }),
             attachments: vec![Attachment {
                 id: "aaaa000000000000000000000000000000000000000000000000000000000000".into(),
                 key: "bbbb000000000000000000000000000000000000000000000000000000000000".into(),
@@ -3110,6 +3184,11 @@ mod tests {
                 webxdc_topic: None,
                 group_id: None,
                 original_hash: None,
+                mirrors: Vec::new(),
+                view_once: false,
+                video_note: false,
+                viewed: false,
+                transcript: None,
             }],
             reactions: vec![Reaction {
                 id: "dddd000000000000000000000000000000000000000000000000000000000000".into(),
@@ -3131,6 +3210,10 @@ mod tests {
             ]),
             emoji_tags: Vec::new(),
             addressed_bots: vec!["npub1botrouting0000000000000000000000000000000000000000000000".into()],
+            code_languages: Vec::new(),
+            nostr_previews: Vec::new(),
+            zap_msat: 0,
+            starred: false,
         }
     }
 
@@ -3155,6 +3238,7 @@ mod tests {
         assert_eq!(restored.edited, msg.edited, "edited mismatch");
         assert_eq!(restored.edit_history, msg.edit_history, "edit_history mismatch");
         assert_eq!(restored.preview_metadata, msg.preview_metadata, "preview_metadata mismatch");
+        assert_eq!(restored.location, msg.location, "location mismatch");
         // Timestamp loses sub-second precision but seconds should match
         assert_eq!(restored.at / 1000, msg.at / 1000, "timestamp seconds mismatch");
         // Attachments
@@ -3410,6 +3494,11 @@ mod tests {
             webxdc_topic: None,
             group_id: None,
             original_hash: None,
+            mirrors: Vec::new(),
+            view_once: false,
+            video_note: false,
+            viewed: false,
+            transcript: None,
         };
 
         let compact = CompactAttachment::from_attachment(&att);
@@ -3444,6 +3533,11 @@ mod tests {
             webxdc_topic: None,
             group_id: None,
             original_hash: None,
+            mirrors: Vec::new(),
+            view_once: false,
+            video_note: false,
+            viewed: false,
+            transcript: None,
         };
         let att_clone = att.clone();
 
@@ -3554,6 +3648,11 @@ mod tests {
             webxdc_topic: Some("game-state".into()),
             group_id: Some("cccc000000000000000000000000000000000000000000000000000000000000".into()),
             original_hash: Some("dddd000000000000000000000000000000000000000000000000000000000000".into()),
+            mirrors: Vec::new(),
+            view_once: false,
+            video_note: false,
+            viewed: false,
+            transcript: None,
         };
 
         let compact = CompactAttachment::from_attachment(&att);
@@ -0,0 +1,215 @@
+//! Compressed DM content.
+//!
+//! Long texts are zstd-compressed inside the rumor, so the seal and gift wrap around them (and
+//! every relay copy) shrink with it. A client that can read compressed content says so with an
+//! `["accept-encoding", "zstd"]` tag on the DMs it sends, next to a [`DEVICE_TAG`] naming the
+//! sending install; we remember that per contact device (`db::peer_capabilities`) and only compress
+//! texts to contacts whose every active device has advertised it. A
+//! compressed rumor carries `["encoding", "zstd"]` and base64 content, and `rumor::process_rumor`
+//! restores the text before anything else reads it.
+//!
+//! The rumor id covers the compressed content, so sender and receiver agree on the message id.
+//!
+//! The same tag advertises [`CHUNKS`]: the client reassembles attachments uploaded as chunk blobs
+//! (`blossom::upload_chunked_with_failover`); and [`SEALED_CHUNKS`]: it opens attachments sealed in
+//! records (`crypto::stream`), which voice messages to it are, so they can play as they download.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use nostr_sdk::prelude::*;
+
+/// Tag advertising which encodings the sender's client can read.
+pub const ACCEPT_ENCODING_TAG: &str = "accept-encoding";
+
+/// Tag marking the rumor's content as encoded.
+pub const ENCODING_TAG: &str = "encoding";
+
+pub const ZSTD: &str = "zstd";
+
+/// Advertised by clients that can download chunked attachment uploads.
+pub const CHUNKS: &str = "chunks";
+
+/// Advertised by clients that open sealed-chunk attachments (`crypto::stream`).
+pub const SEALED_CHUNKS: &str = "sealed-chunks";
+
+/// Tag naming the install a DM was sent from, so capabilities are tracked per device. The value is
+/// the device id hashed with the receiver, so contacts can't match it up between them.
+pub const DEVICE_TAG: &str = "client-device";
+
+/// Every capability we advertise, and so record from others.
+const CAPABILITIES: [&str; 3] = [ZSTD, CHUNKS, SEALED_CHUNKS];
+
+/// Texts shorter than this aren't worth the framing.
+pub const COMPRESS_THRESHOLD: usize = 1024;
+
+/// Largest decompressed content we accept, so a tiny payload can't expand without bound.
+const MAX_DECOMPRESSED_BYTES: usize = 256 * 1024;
+
+const ZSTD_LEVEL: i32 = 9;
+
+/// Contact devices whose capabilities were already stored this session, so a busy chat isn't a
+/// write per message.
+static RECORDED: LazyLock<Mutex<HashSet<(String, String)>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+static SAVINGS_MESSAGES: AtomicU64 = AtomicU64::new(0);
+static SAVINGS_RAW_BYTES: AtomicU64 = AtomicU64::new(0);
+static SAVINGS_WIRE_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Content bytes saved by compression this session.
+#[derive(serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompressionSavings {
+    pub messages: u64,
+    /// Size of the texts as written.
+    pub raw_bytes: u64,
+    /// Size of the content that went into the rumors instead.
+    pub wire_bytes: u64,
+}
+
+pub fn savings() -> CompressionSavings {
+    CompressionSavings {
+        messages: SAVINGS_MESSAGES.load(Ordering::Relaxed),
+        raw_bytes: SAVINGS_RAW_BYTES.load(Ordering::Relaxed),
+        wire_bytes: SAVINGS_WIRE_BYTES.load(Ordering::Relaxed),
+    }
+}
+
+/// The tag every DM we send carries.
+pub fn accept_encoding_tag() -> Tag {
+    Tag::custom(TagKind::custom(ACCEPT_ENCODING_TAG), CAPABILITIES)
+}
+
+/// The [`DEVICE_TAG`] for a DM to `receiver_npub`, or `None` when the device id is unavailable.
+pub fn device_tag(receiver_npub: &str) -> Option<Tag> {
+    use nostr_sdk::hashes::{sha256::Hash as Sha256Hash, Hash};
+    let id = crate::device_drop::device_id().ok()?;
+    let hash = Sha256Hash::hash(format!("{}:{}", id, receiver_npub).as_bytes());
+    let marker = crate::simd::hex::bytes_to_hex_string(&hash.to_byte_array()[..8]);
+    Some(Tag::custom(TagKind::custom(DEVICE_TAG), [marker]))
+}
+
+/// Whether `receiver_npub`'s client can reassemble a chunked upload. Our own devices always can.
+pub fn accepts_chunks(receiver_npub: &str) -> bool {
+    is_me(receiver_npub) || crate::db::peer_capabilities::has(receiver_npub, CHUNKS)
+}
+
+/// Whether `receiver_npub`'s client opens sealed-chunk attachments. Our own devices always can.
+pub fn accepts_sealed_chunks(receiver_npub: &str) -> bool {
+    is_me(receiver_npub) || crate::db::peer_capabilities::has(receiver_npub, SEALED_CHUNKS)
+}
+
+fn is_me(npub: &str) -> bool {
+    crate::state::my_public_key()
+        .and_then(|pk| pk.to_bech32().ok())
+        .is_some_and(|me| me == npub)
+}
+
+/// Compress `content` for a rumor: base64 zstd, or `None` when it's under the threshold or
+/// doesn't get smaller.
+pub fn compress(content: &str) -> Option<String> {
+    if content.len() < COMPRESS_THRESHOLD {
+        return None;
+    }
+    let compressed = zstd::bulk::compress(content.as_bytes(), ZSTD_LEVEL).ok()?;
+    let encoded = base64_simd::STANDARD.encode_to_string(&compressed);
+    (encoded.len() < content.len()).then_some(encoded)
+}
+
+/// Reverse [`compress`].
+pub fn decompress(encoded: &str) -> Result<String, String> {
+    let compressed = base64_simd::STANDARD
+        .decode_to_vec(encoded.as_bytes())
+        .map_err(|_| "Compressed content is not base64".to_string())?;
+    let bytes = zstd::bulk::decompress(&compressed, MAX_DECOMPRESSED_BYTES)
+        .map_err(|e| format!("Failed to decompress content: {}", e))?;
+    String::from_utf8(bytes).map_err(|_| "Decompressed content is not UTF-8".to_string())
+}
+
+/// The content to send `receiver_npub`, plus the tag marking it compressed when it is.
+/// Compresses only for contacts whose client advertised zstd.
+pub fn encode_for(receiver_npub: &str, content: &str) -> (String, Option<Tag>) {
+    if content.len() < COMPRESS_THRESHOLD || !crate::db::peer_capabilities::has(receiver_npub, ZSTD) {
+        return (content.to_string(), None);
+    }
+    let Some(encoded) = compress(content) else {
+        return (content.to_string(), None);
+    };
+    SAVINGS_MESSAGES.fetch_add(1, Ordering::Relaxed);
+    SAVINGS_RAW_BYTES.fetch_add(content.len() as u64, Ordering::Relaxed);
+    SAVINGS_WIRE_BYTES.fetch_add(encoded.len() as u64, Ordering::Relaxed);
+    crate::log_info!("[Compression] {} -> {} bytes", content.len(), encoded.len());
+    (encoded, Some(Tag::custom(TagKind::custom(ENCODING_TAG), [ZSTD])))
+}
+
+/// Restore compressed content in place. Content without an encoding tag is left alone; an
+/// encoding we don't know is an error rather than garbage in the chat.
+pub fn decode_content(content: &mut String, tags: &Tags) -> Result<(), String> {
+    let Some(encoding) = tag_value(tags, ENCODING_TAG) else { return Ok(()) };
+    if encoding != ZSTD {
+        return Err(format!("Unsupported content encoding: {}", encoding));
+    }
+    *content = decompress(content)?;
+    Ok(())
+}
+
+/// Note a contact's advertised encodings from a rumor they sent. Recorded even when it advertises
+/// none, so their older clients hold back what they can't read.
+pub fn note_capabilities(sender_npub: &str, tags: &Tags, created_at: u64) {
+    let advertised: Vec<&str> = tags.iter()
+        .map(|t| t.as_slice())
+        .filter(|s| s.first().map(String::as_str) == Some(ACCEPT_ENCODING_TAG))
+        .flat_map(|s| s[1..].iter().map(String::as_str))
+        .filter(|v| CAPABILITIES.contains(v))
+        .collect();
+    let device = (sender_npub.to_string(), tag_value(tags, DEVICE_TAG).unwrap_or_default().to_string());
+    if RECORDED.lock().unwrap().contains(&device) {
+        return;
+    }
+    if let Err(e) = crate::db::peer_capabilities::record(sender_npub, &device.1, &advertised, created_at) {
+        crate::log_warn!("[Compression] {}", e);
+        return;
+    }
+    RECORDED.lock().unwrap().insert(device);
+}
+
+/// Forget the per-session record cache (account swap).
+pub fn clear_session() {
+    RECORDED.lock().unwrap().clear();
+}
+
+fn tag_value<'a>(tags: &'a Tags, name: &str) -> Option<&'a str> {
+    tags.iter()
+        .map(|t| t.as_slice())
+        .find(|s| s.first().map(String::as_str) == Some(name))
+        .and_then(|s| s.get(1))
+        .map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_text_roundtrips_and_short_text_is_left_alone() {
+        assert_eq!(compress("hello"), None);
+
+        let text = "The quick brown fox jumps over the lazy dog. ".repeat(100);
+        let encoded = compress(&text).expect("repetitive text compresses");
+        assert!(encoded.len() < text.len() / 4);
+
+        let mut tags = Tags::new();
+        tags.push(Tag::custom(TagKind::custom(ENCODING_TAG), [ZSTD]));
+        let mut content = encoded;
+        decode_content(&mut content, &tags).unwrap();
+        assert_eq!(content, text);
+
+        let mut plain = "not compressed".to_string();
+        decode_content(&mut plain, &Tags::new()).unwrap();
+        assert_eq!(plain, "not compressed");
+
+        let mut brotli = Tags::new();
+        brotli.push(Tag::custom(TagKind::custom(ENCODING_TAG), ["br"]));
+        assert!(decode_content(&mut plain, &brotli).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! Consent for expensive operations: a large history sync, a transcription model download.
+//!
+//! Before one of these runs, the app gets an [`Estimate`] (events to fetch, bytes to download,
+//! rough minutes) carrying a one-time token, and the operation only starts when that token comes
+//! back, so a stray tap can't pull gigabytes over mobile data. "Don't ask again" is remembered per
+//! operation in the account settings; a remembered operation gets no token and needs none.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long an unused token stays valid.
+const TOKEN_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Assumed throughput for the minutes estimate: a middling mobile connection, and how fast a
+/// sync fetches and decrypts gift wraps.
+const ASSUMED_BYTES_PER_SEC: u64 = 1_000_000;
+const ASSUMED_EVENTS_PER_SEC: u64 = 150;
+
+/// Typical gift wrap on the wire, for the size of a history sync.
+pub const AVG_WRAP_BYTES: u64 = 2_048;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpensiveOp {
+    /// Fetching a large DM history backlog from relays.
+    HistorySync,
+    /// Downloading a transcription model.
+    ModelDownload,
+}
+
+impl ExpensiveOp {
+    fn setting_key(self) -> &'static str {
+        match self {
+            ExpensiveOp::HistorySync => "consent_history_sync",
+            ExpensiveOp::ModelDownload => "consent_model_download",
+        }
+    }
+}
+
+/// What an operation will cost, for the confirmation prompt.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct Estimate {
+    pub op: ExpensiveOp,
+    /// What it runs on (the model name; empty for a history sync).
+    pub subject: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    pub minutes: u64,
+    /// Hand back to proceed; `None` when the user chose not to be asked again.
+    pub token: Option<String>,
+}
+
+struct Grant {
+    op: ExpensiveOp,
+    subject: String,
+    issued_at: Instant,
+}
+
+static GRANTS: LazyLock<Mutex<HashMap<String, Grant>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Whether the user said not to ask again for `op`.
+pub fn is_remembered(op: ExpensiveOp) -> bool {
+    matches!(crate::db::settings::get_sql_setting(op.setting_key().to_string()), Ok(Some(v)) if v == "allow")
+}
+
+pub fn set_remembered(op: ExpensiveOp, remembered: bool) -> Result<(), String> {
+    if remembered {
+        crate::db::settings::set_sql_setting(op.setting_key().to_string(), "allow".to_string())
+    } else {
+        crate::db::settings::remove_setting(op.setting_key())
+    }
+}
+
+/// Rough wall-clock minutes for a transfer of `bytes` plus `events` to process (at least 1).
+fn minutes_for(events: u64, bytes: u64) -> u64 {
+    let secs = bytes / ASSUMED_BYTES_PER_SEC + events / ASSUMED_EVENTS_PER_SEC;
+    secs.div_ceil(60).max(1)
+}
+
+/// Price an operation on `subject`, with a token to proceed unless it's remembered.
+pub fn estimate(op: ExpensiveOp, subject: &str, events: Option<u64>, bytes: Option<u64>) -> Estimate {
+    let token = (!is_remembered(op)).then(|| {
+        let mut raw = [0u8; 16];
+        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut raw);
+        let token = crate::simd::hex::bytes_to_hex_string(&raw);
+        let mut grants = GRANTS.lock().unwrap();
+        grants.retain(|_, g| g.issued_at.elapsed() < TOKEN_TTL);
+        grants.insert(token.clone(), Grant { op, subject: subject.to_string(), issued_at: Instant::now() });
+        token
+    });
+    Estimate {
+        op,
+        subject: subject.to_string(),
+        events,
+        bytes,
+        minutes: minutes_for(events.unwrap_or(0), bytes.unwrap_or(0)),
+        token,
+    }
+}
+
+/// Check consent for `op` on `subject`: fine when remembered, otherwise `token` must be a live one
+/// issued for exactly this operation (it's used up either way).
+pub fn redeem(op: ExpensiveOp, subject: &str, token: Option<&str>) -> Result<(), String> {
+    let grant = token.and_then(|t| GRANTS.lock().unwrap().remove(t));
+    if is_remembered(op) {
+        return Ok(());
+    }
+    match grant {
+        Some(g) if g.op == op && g.subject == subject && g.issued_at.elapsed() < TOKEN_TTL => Ok(()),
+        _ => Err("Confirmation required".to_string()),
+    }
+}
+
+/// Drop outstanding tokens (account swap).
+pub fn clear_session() {
+    GRANTS.lock().unwrap().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_single_use_and_bound_to_their_operation() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        crate::db::clear_id_caches();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+
+        let est = estimate(ExpensiveOp::ModelDownload, "small", None, Some(244_000_000));
+        assert_eq!(est.minutes, 5);
+        let token = est.token.expect("asks first");
+        assert!(redeem(ExpensiveOp::ModelDownload, "large-v3", Some(&token)).is_err(), "other subject");
+        assert!(redeem(ExpensiveOp::ModelDownload, "small", Some(&token)).is_err(), "used up by the miss");
+
+        let token = estimate(ExpensiveOp::ModelDownload, "small", None, None).token.unwrap();
+        assert!(redeem(ExpensiveOp::HistorySync, "small", Some(&token)).is_err());
+        let token = estimate(ExpensiveOp::ModelDownload, "small", None, None).token.unwrap();
+        assert!(redeem(ExpensiveOp::ModelDownload, "small", Some(&token)).is_ok());
+        assert!(redeem(ExpensiveOp::ModelDownload, "small", Some(&token)).is_err(), "single use");
+        assert!(redeem(ExpensiveOp::ModelDownload, "small", None).is_err());
+
+        set_remembered(ExpensiveOp::ModelDownload, true).unwrap();
+        assert!(estimate(ExpensiveOp::ModelDownload, "small", None, None).token.is_none());
+        assert!(redeem(ExpensiveOp::ModelDownload, "small", None).is_ok());
+        assert!(redeem(ExpensiveOp::HistorySync, "", None).is_err(), "remembered per operation");
+        set_remembered(ExpensiveOp::ModelDownload, false).unwrap();
+        assert!(redeem(ExpensiveOp::ModelDownload, "small", None).is_err());
+    }
+}
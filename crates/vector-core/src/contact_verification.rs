@@ -0,0 +1,214 @@
+//! Contact verification — safety numbers, compared in person or over a trusted channel, to prove
+//! a chat isn't with an impostor.
+//!
+//! A safety number is 60 digits derived from both parties' public keys, identical on both sides.
+//! It can be read aloud or scanned: the QR payload is a `vector://verify/<npub>/<digits>` link, and
+//! scanning it compares the digits against our own. A scan never marks anyone verified by itself (a
+//! link can arrive from anywhere): the user compares the numbers on screen and confirms.
+//!
+//! In Nostr the npub *is* the key, so a verified contact's key can't change under them. What a key
+//! change looks like in practice is the contact "moving" to a new npub, which is also what an
+//! impersonator looks like. So the warning is raised when an unverified profile presents the same
+//! name, display name or NIP-05 as a verified one (`verification_warning`) — a contact we verified,
+//! or an account in the signed [`crate::verified`] registry. Once per npub per session.
+//!
+//! (The MLS credential that used to feed group verification is gone with MLS; Community members are
+//! plain npubs and verify the same way.)
+
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+
+use nostr_sdk::prelude::*;
+use sha2::{Digest, Sha512};
+
+use crate::state::STATE;
+use crate::traits::emit_event;
+
+const DOMAIN: &[u8] = b"vector/safety-number/v1";
+/// 12 groups of 5 digits.
+const GROUPS: usize = 12;
+const QR_PREFIX: &str = "vector://verify/";
+
+/// Npubs already warned about this session.
+static WARNED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// What the verification screen shows for one contact.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct VerificationCode {
+    pub npub: String,
+    /// 60 digits in groups of 5, space-separated.
+    pub safety_number: String,
+    /// For the contact to scan: our npub and the digits.
+    pub qr_payload: String,
+    pub verified: bool,
+}
+
+/// A scanned verification code, checked against our own safety number with its sender.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ScannedCode {
+    pub npub: String,
+    /// Our safety number with them, grouped like [`VerificationCode::safety_number`].
+    pub safety_number: String,
+    /// Whether the scanned digits are the same.
+    pub matches: bool,
+    /// Whether they're already marked verified.
+    pub verified: bool,
+}
+
+/// Raised when a profile may be impersonating (or be the new key of) a verified contact.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct VerificationWarning {
+    pub npub: String,
+    pub verified_npub: String,
+    /// `name`, `display_name` or `nip05`: what they share.
+    pub matched: &'static str,
+}
+
+/// The safety number for a pair of keys, the same whichever side computes it.
+pub fn safety_number(a: &PublicKey, b: &PublicKey) -> String {
+    let (lo, hi) = if a.to_bytes() <= b.to_bytes() { (a, b) } else { (b, a) };
+    let mut hasher = Sha512::new();
+    hasher.update(DOMAIN);
+    hasher.update(lo.to_bytes());
+    hasher.update(hi.to_bytes());
+    let digest = hasher.finalize();
+    digest
+        .chunks_exact(5)
+        .take(GROUPS)
+        .map(|chunk| {
+            let n = chunk.iter().fold(0u64, |acc, b| (acc << 8) | u64::from(*b));
+            format!("{:05}", n % 100_000)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_npub(npub: &str) -> Result<PublicKey, String> {
+    PublicKey::parse(npub.trim()).map_err(|_| "Invalid npub".to_string())
+}
+
+/// Our safety number with `npub`, and whether they're marked verified.
+pub async fn code_for(npub: &str) -> Result<VerificationCode, String> {
+    let my_pk = crate::state::my_public_key().ok_or("Not logged in")?;
+    let their_pk = parse_npub(npub)?;
+    if their_pk == my_pk {
+        return Err("That's your own key".to_string());
+    }
+    let npub = their_pk.to_bech32().map_err(|e| e.to_string())?;
+    let safety_number = safety_number(&my_pk, &their_pk);
+    let qr_payload = format!(
+        "{}{}/{}",
+        QR_PREFIX,
+        my_pk.to_bech32().map_err(|e| e.to_string())?,
+        safety_number.replace(' ', "")
+    );
+    let verified = STATE.lock().await
+        .get_profile(&npub)
+        .is_some_and(|p| p.flags.is_contact_verified());
+    Ok(VerificationCode { npub, safety_number, qr_payload, verified })
+}
+
+/// Mark `npub` verified (or not). Persists through `handler` like any profile change.
+pub async fn set_verified(
+    npub: &str,
+    verified: bool,
+    handler: &dyn crate::profile::sync::ProfileSyncHandler,
+) -> Result<(), String> {
+    let npub = parse_npub(npub)?.to_bech32().map_err(|e| e.to_string())?;
+    let mut state = STATE.lock().await;
+    if state.interner.lookup(&npub).is_none() {
+        state.insert_or_replace_profile(&npub, crate::profile::Profile::new());
+    }
+    let id = state.interner.lookup(&npub).ok_or("Profile not found")?;
+    state.get_profile_mut_by_id(id).ok_or("Profile not found")?.flags.set_contact_verified(verified);
+    let slim = state.serialize_profile(id).ok_or("Profile not found")?;
+    drop(state);
+    emit_event("profile_update", &slim);
+    handler.on_profile_fetched(&slim, "", "");
+    Ok(())
+}
+
+/// Check a scanned QR payload against our own safety number with its sender. Marks nothing: the
+/// user confirms the match, then [`set_verified`] records it.
+pub async fn check_scanned(payload: &str) -> Result<ScannedCode, String> {
+    let rest = payload.trim().strip_prefix(QR_PREFIX).ok_or("Not a Vector verification code")?;
+    let (npub, digits) = rest.split_once('/').ok_or("Not a Vector verification code")?;
+    let code = code_for(npub).await?;
+    let matches = code.safety_number.replace(' ', "") == digits.trim_end_matches('/');
+    Ok(ScannedCode { npub: code.npub, safety_number: code.safety_number, matches, verified: code.verified })
+}
+
+fn normalize(s: &str) -> String {
+    s.trim().to_lowercase()
+}
+
+/// What, if anything, `candidate` (name, display name, NIP-05) shares with a verified profile.
+fn shared_identity(candidate: [&str; 3], verified: [&str; 3]) -> Option<&'static str> {
+    const FIELDS: [&str; 3] = ["name", "display_name", "nip05"];
+    (0..3).find_map(|i| {
+        let c = normalize(candidate[i]);
+        (!c.is_empty() && c == normalize(verified[i])).then_some(FIELDS[i])
+    })
+}
+
+/// Warn (once per session) when the freshly updated `profile` looks like a verified contact or
+/// registry account it isn't.
+pub async fn check_lookalike(profile: &crate::SlimProfile) {
+    if profile.contact_verified || profile.mine || crate::verified::is_verified(&profile.id) {
+        return;
+    }
+    if WARNED.lock().map(|w| w.contains(&profile.id)).unwrap_or(true) {
+        return;
+    }
+    let candidate = [profile.name.as_str(), profile.display_name.as_str(), profile.nip05.as_str()];
+    let warning = {
+        let state = STATE.lock().await;
+        state.profiles.iter()
+            .find_map(|p| {
+                let verified_npub = state.interner.resolve(p.id)?;
+                if !p.flags.is_contact_verified() && !crate::verified::is_verified(verified_npub) {
+                    return None;
+                }
+                let matched = shared_identity(candidate, [&*p.name, &*p.display_name, p.nip05()])?;
+                let verified_npub = verified_npub.to_string();
+                (verified_npub != profile.id).then(|| VerificationWarning {
+                    npub: profile.id.clone(),
+                    verified_npub,
+                    matched,
+                })
+            })
+    };
+    if let Some(warning) = warning {
+        if let Ok(mut warned) = WARNED.lock() {
+            warned.insert(profile.id.clone());
+        }
+        crate::log_warn!("[Verification] {} shares a {} with verified {}", warning.npub, warning.matched, warning.verified_npub);
+        emit_event("verification_warning", &warning);
+    }
+}
+
+pub fn clear_session() {
+    if let Ok(mut warned) = WARNED.lock() {
+        warned.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safety_number_is_symmetric_and_pair_specific() {
+        let (a, b, c) = (Keys::generate().public_key(), Keys::generate().public_key(), Keys::generate().public_key());
+        let ab = safety_number(&a, &b);
+        assert_eq!(ab, safety_number(&b, &a));
+        assert_ne!(ab, safety_number(&a, &c));
+        let groups: Vec<&str> = ab.split(' ').collect();
+        assert_eq!(groups.len(), 12);
+        assert!(groups.iter().all(|g| g.len() == 5 && g.chars().all(|c| c.is_ascii_digit())));
+
+        assert_eq!(shared_identity(["Alice", "", ""], ["alice ", "Al", ""]), Some("name"));
+        assert_eq!(shared_identity(["", "", "a@x.io"], ["", "", "A@X.io"]), Some("nip05"));
+        assert_eq!(shared_identity(["", "", ""], ["", "", ""]), None, "empty fields never match");
+    }
+}
@@ -0,0 +1,305 @@
+//! Contact list — NIP-02 (kind 3), shared with every other Nostr client the user signs in to.
+//!
+//! The list lives in `db::contacts` and is kept in step with the relays both ways: the newest kind 3
+//! is fetched at boot and streamed live on the self-sync subscription, and local adds and removals
+//! are published (debounced) as a new kind 3.
+//!
+//! A kind 3 is a whole-list replace, so a careless publish wipes contacts another client added.
+//! Merging is per npub instead, last-writer-wins: a list entry (or its absence) carries the list's
+//! `created_at`, a local edit carries its own time, and the newer one decides. Removals are kept as
+//! tombstones for that. A publish always starts from the relay's current list and keeps its
+//! content and non-`p` tags (older clients keep their relay map there) — and never happens unless
+//! the relays actually answered ([`crate::settings_sync::fetch_confirmed`]): a timed-out fetch
+//! looks just like an empty list.
+
+use std::collections::{HashMap, HashSet};
+
+use nostr_sdk::prelude::*;
+
+use crate::db::contacts::ContactRow;
+use crate::clock::now_secs;
+
+const FETCH_TIMEOUT_SECS: u64 = 15;
+
+/// Longest accepted petname, in characters.
+pub const MAX_PETNAME_LEN: usize = 40;
+
+/// One entry on the contact list.
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Contact {
+    pub npub: String,
+    /// Relay hint, or empty.
+    pub relay: String,
+    /// Local name ("petname"), or empty.
+    pub petname: String,
+}
+
+/// The newest kind 3 on the relays, parsed.
+struct RemoteList {
+    created_at: u64,
+    contacts: Vec<Contact>,
+    content: String,
+    /// Everything that isn't a `p` tag, republished untouched.
+    other_tags: Vec<Tag>,
+}
+
+fn parse_list(event: &Event) -> RemoteList {
+    let mut contacts = Vec::new();
+    let mut seen = HashSet::new();
+    let mut other_tags = Vec::new();
+    for tag in event.tags.iter() {
+        let parts = tag.as_slice();
+        if parts.first().map(String::as_str) != Some("p") {
+            other_tags.push(tag.clone());
+            continue;
+        }
+        let Some(npub) = parts.get(1).and_then(|pk| PublicKey::parse(pk).ok()).and_then(|pk| pk.to_bech32().ok()) else {
+            continue;
+        };
+        if seen.insert(npub.clone()) {
+            contacts.push(Contact {
+                npub,
+                relay: parts.get(2).cloned().unwrap_or_default(),
+                petname: parts.get(3).cloned().unwrap_or_default(),
+            });
+        }
+    }
+    RemoteList { created_at: event.created_at.as_secs(), contacts, content: event.content.clone(), other_tags }
+}
+
+/// The rows `remote` (listed at `remote_at`) changes: entries it lists or drops win over local rows
+/// last edited at or before `remote_at`; newer local edits stand.
+fn merge(local: &[ContactRow], remote: &[Contact], remote_at: u64) -> Vec<ContactRow> {
+    let by_npub: HashMap<&str, &ContactRow> = local.iter().map(|r| (r.npub.as_str(), r)).collect();
+    let mut changed = Vec::new();
+    for c in remote {
+        match by_npub.get(c.npub.as_str()) {
+            Some(row) if row.updated_at > remote_at => {}
+            Some(row) if !row.removed && row.relay == c.relay && row.petname == c.petname => {}
+            _ => changed.push(ContactRow {
+                npub: c.npub.clone(),
+                relay: c.relay.clone(),
+                petname: c.petname.clone(),
+                removed: false,
+                updated_at: remote_at,
+            }),
+        }
+    }
+    let listed: HashSet<&str> = remote.iter().map(|c| c.npub.as_str()).collect();
+    for row in local {
+        if !row.removed && row.updated_at <= remote_at && !listed.contains(row.npub.as_str()) {
+            changed.push(ContactRow { removed: true, updated_at: remote_at, ..row.clone() });
+        }
+    }
+    changed
+}
+
+/// The contacts `rows` amount to once `changed` is applied, sorted by npub.
+fn present(rows: &[ContactRow], changed: &[ContactRow]) -> Vec<Contact> {
+    let mut by_npub: HashMap<&str, &ContactRow> = rows.iter().map(|r| (r.npub.as_str(), r)).collect();
+    for row in changed {
+        by_npub.insert(row.npub.as_str(), row);
+    }
+    let mut contacts: Vec<Contact> = by_npub
+        .into_values()
+        .filter(|r| !r.removed)
+        .map(|r| Contact { npub: r.npub.clone(), relay: r.relay.clone(), petname: r.petname.clone() })
+        .collect();
+    contacts.sort();
+    contacts
+}
+
+/// The contact list, sorted by npub.
+pub fn list() -> Result<Vec<Contact>, String> {
+    Ok(present(&crate::db::contacts::all_rows()?, &[]))
+}
+
+/// `npub` (or hex) as a bech32 npub.
+fn normalize(npub: &str) -> Result<String, String> {
+    PublicKey::parse(npub.trim())
+        .ok()
+        .and_then(|pk| pk.to_bech32().ok())
+        .ok_or_else(|| "Invalid npub".to_string())
+}
+
+fn write_local(
+    session: &crate::state::SessionGuard,
+    npub: String,
+    relay: String,
+    petname: String,
+    removed: bool,
+) -> Result<Contact, String> {
+    if !session.is_valid() {
+        return Err("The account changed".to_string());
+    }
+    crate::db::contacts::upsert_rows(&[ContactRow {
+        npub: npub.clone(),
+        relay: relay.clone(),
+        petname: petname.clone(),
+        removed,
+        updated_at: now_secs(),
+    }])?;
+    if tokio::runtime::Handle::try_current().is_ok() {
+        republish_debounced();
+    }
+    Ok(Contact { npub, relay, petname })
+}
+
+/// Add `npub` to the contact list (or update its petname), and publish.
+pub fn add(npub: &str, petname: Option<&str>) -> Result<Contact, String> {
+    let session = crate::state::SessionGuard::capture();
+    let petname = petname.unwrap_or_default().trim().to_string();
+    if petname.chars().count() > MAX_PETNAME_LEN {
+        return Err(format!("Contact names are limited to {} characters", MAX_PETNAME_LEN));
+    }
+    let npub = normalize(npub)?;
+    // Keep a relay hint another client gave the contact
+    let relay = crate::db::contacts::all_rows()?
+        .into_iter()
+        .find(|r| r.npub == npub)
+        .map(|r| r.relay)
+        .unwrap_or_default();
+    write_local(&session, npub, relay, petname, false)
+}
+
+/// Take `npub` off the contact list, and publish.
+pub fn remove(npub: &str) -> Result<(), String> {
+    let session = crate::state::SessionGuard::capture();
+    write_local(&session, normalize(npub)?, String::new(), String::new(), true).map(|_| ())
+}
+
+// ============================================================================
+// Transport (kind 3, replaceable)
+// ============================================================================
+
+/// The newest kind 3 on the relays. `Ok(None)` only when every relay answered that there's none.
+async fn fetch_remote(client: &Client, my_pk: PublicKey) -> Result<Option<RemoteList>, String> {
+    let filter = Filter::new().author(my_pk).kind(Kind::ContactList).limit(1);
+    let events = crate::settings_sync::fetch_confirmed(client, filter, FETCH_TIMEOUT_SECS)
+        .await
+        .map_err(|e| format!("fetch contact list (kind 3): {}", e))?;
+    Ok(events.into_iter().max_by_key(|e| e.created_at).map(|e| parse_list(&e)))
+}
+
+async fn publish(client: &Client, remote: Option<RemoteList>, contacts: &[Contact]) -> Result<(), String> {
+    let (content, mut tags) = remote.map(|r| (r.content, r.other_tags)).unwrap_or_default();
+    for c in contacts {
+        let Ok(pk) = PublicKey::parse(&c.npub) else { continue };
+        let mut values = vec![pk.to_hex()];
+        if !c.relay.is_empty() || !c.petname.is_empty() {
+            values.push(c.relay.clone());
+        }
+        if !c.petname.is_empty() {
+            values.push(c.petname.clone());
+        }
+        tags.push(Tag::custom(TagKind::p(), values));
+    }
+    client
+        .send_event_builder(EventBuilder::new(Kind::ContactList, content).tags(tags))
+        .await
+        .map_err(|e| format!("Failed to publish contact list (kind 3): {}", e))?;
+    Ok(())
+}
+
+/// Adopt what `remote` changes, and tell the UI. Returns the resulting list.
+fn apply_remote(remote: Option<&RemoteList>) -> Result<Vec<Contact>, String> {
+    let rows = crate::db::contacts::all_rows()?;
+    let changed = match remote {
+        Some(r) => merge(&rows, &r.contacts, r.created_at),
+        None => Vec::new(),
+    };
+    crate::db::contacts::upsert_rows(&changed)?;
+    let contacts = present(&rows, &changed);
+    if !changed.is_empty() {
+        crate::traits::emit_event("contacts_updated", &contacts);
+    }
+    Ok(contacts)
+}
+
+/// Full round: fetch the relay list, adopt its newer entries, and publish when this device holds
+/// anything the relay copy doesn't. A fetch the relays didn't answer fails the round, publishing
+/// nothing.
+pub async fn sync_now() -> Result<(), String> {
+    let session = crate::state::SessionGuard::capture();
+    let client = crate::state::nostr_client().ok_or("Nostr client not initialized")?;
+    let my_pk = crate::state::my_public_key().ok_or("Not logged in")?;
+
+    let remote = fetch_remote(&client, my_pk).await?;
+    if !session.is_valid() {
+        return Ok(());
+    }
+    let contacts = apply_remote(remote.as_ref())?;
+    let mut listed = remote.as_ref().map(|r| r.contacts.clone()).unwrap_or_default();
+    listed.sort();
+    if contacts != listed && session.is_valid() {
+        publish(&client, remote, &contacts).await?;
+        crate::log_info!("[Contacts] Published {} contact(s)", contacts.len());
+    }
+    Ok(())
+}
+
+/// Live path: adopt a kind 3 another client published. Never republishes (the relay echoes our own
+/// publishes back on the same subscription).
+pub async fn ingest_remote_event(event: &Event) {
+    let session = crate::state::SessionGuard::capture();
+    if crate::state::my_public_key() != Some(event.pubkey) || !session.is_valid() {
+        return;
+    }
+    if let Err(e) = apply_remote(Some(&parse_list(event))) {
+        crate::log_warn!("[Contacts] Failed to adopt contact list: {}", e);
+    }
+}
+
+static REPUBLISH_GEN: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Coalesce a burst of edits into one [`sync_now`] (read-merge-write, so another client's newer
+/// entries are never overwritten).
+fn republish_debounced() {
+    use std::sync::atomic::Ordering;
+    let gen = REPUBLISH_GEN.fetch_add(1, Ordering::SeqCst) + 1;
+    let session = crate::state::SessionGuard::capture();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        if REPUBLISH_GEN.load(Ordering::SeqCst) != gen || !session.is_valid() {
+            return;
+        }
+        if let Err(e) = sync_now().await {
+            crate::log_warn!("[Contacts] Republish failed: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(npub: &str, petname: &str, removed: bool, at: u64) -> ContactRow {
+        ContactRow { npub: npub.into(), relay: String::new(), petname: petname.into(), removed, updated_at: at }
+    }
+
+    fn contact(npub: &str, petname: &str) -> Contact {
+        Contact { npub: npub.into(), relay: String::new(), petname: petname.into() }
+    }
+
+    #[test]
+    fn merge_is_last_writer_wins_per_npub() {
+        let local = vec![
+            row("npub1old", "", false, 10),      // dropped by the newer list
+            row("npub1fresh", "", false, 50),    // added here after the list
+            row("npub1gone", "", true, 40),      // removed here after the list
+            row("npub1stale", "", true, 5),      // removed here before the list re-added it
+            row("npub1same", "amy", false, 10),
+        ];
+        let remote = vec![contact("npub1gone", ""), contact("npub1stale", ""), contact("npub1same", "amy"), contact("npub1new", "bo")];
+        let changed = merge(&local, &remote, 30);
+
+        let mut npubs: Vec<(&str, bool)> = changed.iter().map(|r| (r.npub.as_str(), r.removed)).collect();
+        npubs.sort();
+        assert_eq!(npubs, vec![("npub1new", false), ("npub1old", true), ("npub1stale", false)]);
+        assert!(changed.iter().all(|r| r.updated_at == 30), "adopted rows carry the list's time");
+
+        let after: Vec<String> = present(&local, &changed).into_iter().map(|c| c.npub).collect();
+        assert_eq!(after, vec!["npub1fresh", "npub1new", "npub1same", "npub1stale"]);
+        assert!(merge(&local, &[], 0).is_empty(), "no list on the relays changes nothing");
+    }
+}
@@ -0,0 +1,178 @@
+//! The per-session handles a subsystem works against: Nostr client, our public key, chat state.
+//!
+//! In the app these are the process-wide globals in [`crate::state`], and
+//! [`AppContext::current`] is a snapshot of them. Code run inside [`AppContext::scope`] sees the
+//! scoped context instead, through `current()` and through `state::nostr_client()` /
+//! `state::my_public_key()`. A test can hand a subsystem an offline client and a private
+//! `ChatState` without installing anything globally, and parallel tests don't trample each other.
+//!
+//! What a scope covers: `VectorCore`'s methods, and sending, event handling, profile sync and
+//! Community inbound below it — they reach chat state through [`lock_state`] — plus anything that
+//! gets the client or key through the `state` accessors. The remaining modules that lock
+//! `state::STATE` directly (Community services, wallpapers, zaps and other leaf features) still
+//! touch the global chat state inside a scope.
+//!
+//! The scope is task-local: it covers the future passed to `scope` but not tasks that future
+//! `tokio::spawn`s. The database is still reached through `crate::db` (tests pin it with
+//! `DB_TEST_GUARD`).
+
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use nostr_sdk::prelude::*;
+use tokio::sync::{Mutex, MutexGuard, OwnedMutexGuard};
+
+use crate::state::ChatState;
+
+tokio::task_local! {
+    static SCOPED: AppContext;
+}
+
+/// Where chat state lives for a context.
+#[derive(Clone)]
+pub enum StateHandle {
+    /// The process-wide [`crate::state::STATE`].
+    Global,
+    /// State owned by this context alone.
+    Owned(Arc<Mutex<ChatState>>),
+}
+
+impl StateHandle {
+    pub async fn lock(&self) -> MutexGuard<'_, ChatState> {
+        match self {
+            StateHandle::Global => crate::state::STATE.lock().await,
+            StateHandle::Owned(state) => state.lock().await,
+        }
+    }
+
+    /// [`lock`](Self::lock), with a guard that doesn't borrow the handle.
+    pub async fn lock_owned(&self) -> StateGuard {
+        match self {
+            StateHandle::Global => StateGuard::Global(crate::state::STATE.lock().await),
+            StateHandle::Owned(state) => StateGuard::Owned(state.clone().lock_owned().await),
+        }
+    }
+}
+
+/// A locked [`StateHandle`].
+pub enum StateGuard {
+    Global(MutexGuard<'static, ChatState>),
+    Owned(OwnedMutexGuard<ChatState>),
+}
+
+impl Deref for StateGuard {
+    type Target = ChatState;
+
+    fn deref(&self) -> &ChatState {
+        match self {
+            StateGuard::Global(guard) => guard,
+            StateGuard::Owned(guard) => guard,
+        }
+    }
+}
+
+impl DerefMut for StateGuard {
+    fn deref_mut(&mut self) -> &mut ChatState {
+        match self {
+            StateGuard::Global(guard) => guard,
+            StateGuard::Owned(guard) => guard,
+        }
+    }
+}
+
+/// Lock the current context's chat state: the scoped one inside [`AppContext::scope`], otherwise
+/// `state::STATE`.
+pub async fn lock_state() -> StateGuard {
+    AppContext::current().state.lock_owned().await
+}
+
+#[derive(Clone)]
+pub struct AppContext {
+    pub client: Option<Client>,
+    pub my_public_key: Option<PublicKey>,
+    pub state: StateHandle,
+}
+
+impl AppContext {
+    /// The scoped context when inside [`scope`](Self::scope), otherwise the active session's
+    /// globals.
+    pub fn current() -> Self {
+        SCOPED.try_with(Clone::clone).unwrap_or_else(|_| Self::global())
+    }
+
+    /// The active session's globals, ignoring any scope.
+    pub fn global() -> Self {
+        Self {
+            client: crate::state::NOSTR_CLIENT.read().unwrap().as_ref().cloned(),
+            my_public_key: *crate::state::MY_PUBLIC_KEY.read().unwrap(),
+            state: StateHandle::Global,
+        }
+    }
+
+    /// A self-contained context: `client` signing as `keys` and an empty `ChatState`. The client
+    /// has no relays until some are added, so nothing leaves the process.
+    pub fn detached(keys: Keys) -> Self {
+        let my_public_key = keys.public_key();
+        Self {
+            client: Some(Client::builder().signer(keys).build()),
+            my_public_key: Some(my_public_key),
+            state: StateHandle::Owned(Arc::new(Mutex::new(ChatState::new()))),
+        }
+    }
+
+    /// Run `fut` with this context in place of the globals.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        SCOPED.scope(self, fut).await
+    }
+
+    /// Our npub, if a key is set.
+    pub fn my_npub(&self) -> Option<String> {
+        self.my_public_key.and_then(|pk| pk.to_bech32().ok())
+    }
+}
+
+/// The scoped context's client and key, when a scope is active. `None` means "use the globals".
+pub(crate) fn scoped_session() -> Option<(Option<Client>, Option<PublicKey>)> {
+    SCOPED.try_with(|ctx| (ctx.client.clone(), ctx.my_public_key)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::Profile;
+
+    #[tokio::test]
+    async fn scope_isolates_from_globals() {
+        let keys = Keys::generate();
+        let my_npub = keys.public_key().to_bech32().unwrap();
+        let ctx = AppContext::detached(keys);
+        let state = ctx.state.clone();
+
+        ctx.scope(async {
+            assert_eq!(crate::VectorCore.my_npub().as_deref(), Some(my_npub.as_str()));
+            assert!(crate::state::nostr_client().is_some());
+            AppContext::current().state.lock().await.insert_or_replace_profile(&my_npub, Profile::new());
+            assert!(crate::VectorCore.get_profile(&my_npub).await.is_some());
+        }).await;
+
+        assert!(state.lock().await.get_profile(&my_npub).is_some());
+        assert!(crate::state::STATE.lock().await.get_profile(&my_npub).is_none());
+        assert!(scoped_session().is_none());
+    }
+
+    #[tokio::test]
+    async fn lock_state_follows_the_scope() {
+        let keys = Keys::generate();
+        let npub = keys.public_key().to_bech32().unwrap();
+        let ctx = AppContext::detached(keys);
+        let state = ctx.state.clone();
+
+        ctx.scope(async {
+            lock_state().await.insert_or_replace_profile(&npub, Profile::new());
+        }).await;
+
+        assert!(state.lock().await.get_profile(&npub).is_some());
+        assert!(lock_state().await.get_profile(&npub).is_none(), "outside a scope it's the global state");
+    }
+}
@@ -0,0 +1,160 @@
+//! Decrypted message content in RAM — an opt-in, per-account cache.
+//!
+//! With encryption on, message content stays ChaCha20 ciphertext in SQL and every read decrypts it
+//! on demand, so plaintext lives only as long as the view it was read for. That's the default. An
+//! account that pages through a lot of history can trade it for speed with the
+//! `message_plaintext_cache` setting: the most recently decrypted bodies are kept in an LRU, and
+//! re-reads (scrolling back, search, reply quotes) skip the cipher. Nothing decrypted is written
+//! to disk either way, and evicted or cleared plaintext is zeroized.
+//!
+//! Entries are keyed by the ciphertext's nonce and tag rather than the event id. A stored value
+//! never changes under its key (a rekey or edit writes new ciphertext), so nothing needs evicting
+//! on writes, and an edit's content can't be served for its original message.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+
+use zeroize::Zeroize;
+
+/// Per-account setting: `"true"` keeps decrypted content cached.
+pub const SETTING_KEY: &str = "message_plaintext_cache";
+
+/// Most entries held; past this the least recently used quarter is dropped.
+const CAPACITY: usize = 4096;
+/// Longer bodies are always decrypted on demand (they'd crowd out many short ones).
+const MAX_CACHED_LEN: usize = 16 * 1024;
+
+const UNKNOWN: u8 = 0;
+const OFF: u8 = 1;
+const ON: u8 = 2;
+
+/// The account's setting, read once per session.
+static MODE: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+struct Lru {
+    entries: HashMap<String, (String, u64)>,
+    tick: u64,
+}
+
+static CACHE: Mutex<Option<Lru>> = Mutex::new(None);
+
+/// Whether this account keeps decrypted content in RAM.
+pub fn enabled() -> bool {
+    match MODE.load(Ordering::Acquire) {
+        ON => true,
+        OFF => false,
+        _ => {
+            let on = crate::db::settings::get_sql_setting(SETTING_KEY.to_string())
+                .ok()
+                .flatten()
+                .is_some_and(|v| v == "true");
+            MODE.store(if on { ON } else { OFF }, Ordering::Release);
+            on
+        }
+    }
+}
+
+/// Persist the account's choice. Turning it off drops what's cached.
+pub fn set_enabled(on: bool) -> Result<(), String> {
+    crate::db::settings::set_sql_setting(SETTING_KEY.to_string(), on.to_string())?;
+    MODE.store(if on { ON } else { OFF }, Ordering::Release);
+    if !on {
+        clear();
+    }
+    Ok(())
+}
+
+/// Nonce + tag of a hex ciphertext: unique per encryption.
+fn cache_key(stored: &str) -> String {
+    let tag_start = stored.len().saturating_sub(32);
+    format!("{}{}", &stored[..24.min(stored.len())], &stored[tag_start..])
+}
+
+fn lookup(key: &str) -> Option<String> {
+    let mut guard = CACHE.lock().ok()?;
+    let lru = guard.as_mut()?;
+    lru.tick += 1;
+    let tick = lru.tick;
+    lru.entries.get_mut(key).map(|(text, used)| {
+        *used = tick;
+        text.clone()
+    })
+}
+
+fn insert(key: String, text: &str) {
+    let Ok(mut guard) = CACHE.lock() else { return };
+    let lru = guard.get_or_insert_with(|| Lru { entries: HashMap::new(), tick: 0 });
+    lru.tick += 1;
+    lru.entries.insert(key, (text.to_string(), lru.tick));
+    if lru.entries.len() > CAPACITY {
+        let mut ticks: Vec<u64> = lru.entries.values().map(|(_, t)| *t).collect();
+        ticks.sort_unstable();
+        let cutoff = ticks[CAPACITY / 4];
+        lru.entries.retain(|_, (text, used)| {
+            let keep = *used >= cutoff;
+            if !keep {
+                text.zeroize();
+            }
+            keep
+        });
+    }
+}
+
+/// Decrypt stored message content — from the cache when the account has it on, otherwise (and on
+/// a miss) via [`super::maybe_decrypt`].
+pub async fn decrypt(stored: String) -> Result<String, ()> {
+    if !super::looks_encrypted(&stored) || !enabled() {
+        return super::maybe_decrypt(stored).await;
+    }
+    let key = cache_key(&stored);
+    if let Some(text) = lookup(&key) {
+        return Ok(text);
+    }
+    let text = super::maybe_decrypt(stored).await?;
+    if text.len() <= MAX_CACHED_LEN {
+        insert(key, &text);
+    }
+    Ok(text)
+}
+
+/// Drop (and zeroize) every cached body.
+pub fn clear() {
+    if let Ok(mut guard) = CACHE.lock() {
+        if let Some(mut lru) = guard.take() {
+            for (text, _) in lru.entries.values_mut() {
+                text.zeroize();
+            }
+        }
+    }
+}
+
+/// Account swap: forget the cache and the previous account's setting.
+pub fn clear_session() {
+    clear();
+    MODE.store(UNKNOWN, Ordering::Release);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lru_drops_the_least_recently_used_quarter() {
+        clear();
+        for i in 0..CAPACITY {
+            insert(format!("k{}", i), "body");
+        }
+        // Touch the oldest entry so it survives the sweep
+        assert_eq!(lookup("k0").as_deref(), Some("body"));
+        insert("overflow".to_string(), "body");
+
+        let len = CACHE.lock().unwrap().as_ref().unwrap().entries.len();
+        assert_eq!(len, CAPACITY + 1 - CAPACITY / 4);
+        assert!(lookup("k0").is_some(), "recently read entries stay");
+        assert!(lookup("k1").is_none(), "the oldest go");
+        assert!(lookup("overflow").is_some());
+        clear();
+        assert!(lookup("overflow").is_none());
+    }
+}
@@ -309,6 +309,7 @@ impl GuardedKey {
     /// `others` is a slice of references to other active GuardedKey instances
     /// for cross-key protection during decoy writes.
     #[inline]
+    #[track_caller]
     pub fn store_from_keys(&self, keys: &nostr_sdk::Keys, others: &[&GuardedKey]) {
         let mut sk_bytes = keys.secret_key().secret_bytes();
         self.set(sk_bytes, others);
@@ -323,7 +324,11 @@ impl GuardedKey {
     ///
     /// INVARIANT: pass EVERY other live key. A key's lane is chosen by excluding only `others`'
     /// lanes, so an omitted live key can collide and clobber it.
+    #[track_caller]
     pub fn set(&self, mut key: [u8; 32], others: &[&GuardedKey]) {
+        if let Some(which) = self.audit_label() {
+            crate::key_audit::record_caller(crate::key_audit::KeyOp::KeyLoaded, Some(which.to_string()));
+        }
 
         let mut rng = rand::rngs::OsRng;
         ensure_vaults();
@@ -449,11 +454,33 @@ impl GuardedKey {
         }
     }
 
+    /// Which of the app's two keys this is, for the audit log (`None` for any other instance).
+    fn audit_label(&self) -> Option<&'static str> {
+        if std::ptr::eq(self, &crate::state::MY_SECRET_KEY) {
+            Some("nsec")
+        } else if std::ptr::eq(self, &crate::state::ENCRYPTION_KEY) {
+            Some("encryption key")
+        } else {
+            None
+        }
+    }
+
     pub fn has_key(&self) -> bool {
         self.active.load(Ordering::Acquire) != 0
     }
 
+    /// The stored key as `Keys`. Reads of the account nsec are recorded in the key-usage audit
+    /// log, attributed to the caller.
+    #[track_caller]
     pub fn to_keys(&self) -> Option<nostr_sdk::Keys> {
+        if self.audit_label() == Some("nsec") {
+            crate::key_audit::record_caller(crate::key_audit::KeyOp::KeyAccess, None);
+        }
+        self.to_keys_unaudited()
+    }
+
+    /// [`Self::to_keys`] for callers that record their own, more specific audit entry.
+    pub(crate) fn to_keys_unaudited(&self) -> Option<nostr_sdk::Keys> {
         let mut bytes = self.get()?;
         let result = nostr_sdk::SecretKey::from_slice(&bytes);
         bytes.zeroize();
@@ -1,4 +1,6 @@
+pub mod content_cache;
 pub mod guarded_key;
+pub mod stream;
 pub use guarded_key::GuardedKey;
 
 mod signer;
@@ -126,12 +128,16 @@ pub fn generate_encryption_params() -> EncryptionParams {
     params
 }
 
-/// Encrypt data with AES-256-GCM using a 16-byte nonce (0xChat-compatible).
+/// Encrypt data with AES-256-GCM using a 16-byte nonce (0xChat-compatible), or in sealed records
+/// when `params` carry a 7-byte nonce ([`stream`]).
 pub fn encrypt_data(data: &[u8], params: &EncryptionParams) -> Result<Vec<u8>, String> {
     use aes::Aes256;
     use aes::cipher::typenum::U16;
     use aes_gcm::{AesGcm, AeadInPlace, KeyInit as AesKeyInit};
 
+    if stream::is_sealed_nonce(&params.nonce) {
+        return stream::encrypt(data, &params.key, &params.nonce);
+    }
     let key_bytes = hex::decode(&params.key).map_err(|e| format!("Invalid key: {}", e))?;
     let nonce_bytes = hex::decode(&params.nonce).map_err(|e| format!("Invalid nonce: {}", e))?;
 
@@ -151,12 +157,16 @@ pub fn encrypt_data(data: &[u8], params: &EncryptionParams) -> Result<Vec<u8>, S
 }
 
 /// Decrypt data with AES-256-GCM using a 16-byte nonce (0xChat-compatible).
-/// Input format: ciphertext || 16-byte auth tag.
+/// Input format: ciphertext || 16-byte auth tag. A 7-byte nonce marks a sealed-chunk blob ([`stream`]).
 pub fn decrypt_data(encrypted_data: &[u8], key_hex: &str, nonce_hex: &str) -> Result<Vec<u8>, String> {
     use aes::Aes256;
     use aes::cipher::typenum::U16;
     use aes_gcm::{AesGcm, AeadInPlace, KeyInit as AesKeyInit};
 
+    if stream::is_sealed_nonce(nonce_hex) {
+        return stream::decrypt(encrypted_data, key_hex, nonce_hex);
+    }
+
     if encrypted_data.len() < 16 {
         return Err(format!("Invalid Input: encrypted data too small ({} bytes, minimum 16 bytes required for authentication tag)", encrypted_data.len()));
     }
@@ -2,10 +2,13 @@
 //!
 //! Any client using nostr-sdk needs a signer. This implementation reads the
 //! secret key from the memory-hardened vault on every operation — the key
-//! exists in plaintext only for microseconds during signing.
+//! exists in plaintext only for microseconds during signing. Every operation is recorded in the
+//! key-usage audit log.
 
 use nostr_sdk::prelude::*;
 
+use crate::key_audit::{record, KeyOp};
+
 /// A `NostrSigner` backed by the `MY_SECRET_KEY` vault.
 ///
 /// The secret key is never stored in this struct — it's fetched from the
@@ -20,8 +23,9 @@ impl GuardedSigner {
         Self { public_key }
     }
 
-    fn temp_keys(&self) -> Result<Keys, SignerError> {
-        crate::state::MY_SECRET_KEY.to_keys()
+    fn temp_keys(&self, op: KeyOp, detail: String) -> Result<Keys, SignerError> {
+        record(op, "signer", Some(detail));
+        crate::state::MY_SECRET_KEY.to_keys_unaudited()
             .ok_or_else(|| SignerError::from("Secret key not available"))
     }
 }
@@ -37,7 +41,7 @@ impl NostrSigner for GuardedSigner {
     }
 
     fn sign_event(&self, unsigned: UnsignedEvent) -> BoxedFuture<'_, Result<Event, SignerError>> {
-        let keys = self.temp_keys();
+        let keys = self.temp_keys(KeyOp::SignEvent, format!("kind {}", unsigned.kind.as_u16()));
         Box::pin(async move {
             let keys = keys?;
             unsigned.sign_with_keys(&keys).map_err(SignerError::backend)
@@ -47,28 +51,28 @@ impl NostrSigner for GuardedSigner {
     fn nip04_encrypt<'a>(
         &'a self, public_key: &'a PublicKey, content: &'a str,
     ) -> BoxedFuture<'a, Result<String, SignerError>> {
-        let keys = self.temp_keys();
+        let keys = self.temp_keys(KeyOp::Encrypt, "nip04".into());
         Box::pin(async move { let keys = keys?; keys.nip04_encrypt(public_key, content).await })
     }
 
     fn nip04_decrypt<'a>(
         &'a self, public_key: &'a PublicKey, encrypted_content: &'a str,
     ) -> BoxedFuture<'a, Result<String, SignerError>> {
-        let keys = self.temp_keys();
+        let keys = self.temp_keys(KeyOp::Decrypt, "nip04".into());
         Box::pin(async move { let keys = keys?; keys.nip04_decrypt(public_key, encrypted_content).await })
     }
 
     fn nip44_encrypt<'a>(
         &'a self, public_key: &'a PublicKey, content: &'a str,
     ) -> BoxedFuture<'a, Result<String, SignerError>> {
-        let keys = self.temp_keys();
+        let keys = self.temp_keys(KeyOp::Encrypt, "nip44".into());
         Box::pin(async move { let keys = keys?; keys.nip44_encrypt(public_key, content).await })
     }
 
     fn nip44_decrypt<'a>(
         &'a self, public_key: &'a PublicKey, payload: &'a str,
     ) -> BoxedFuture<'a, Result<String, SignerError>> {
-        let keys = self.temp_keys();
+        let keys = self.temp_keys(KeyOp::Decrypt, "nip44".into());
         Box::pin(async move { let keys = keys?; keys.nip44_decrypt(public_key, payload).await })
     }
 }
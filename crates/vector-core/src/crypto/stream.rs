@@ -0,0 +1,196 @@
+//! Sealed-chunk attachment encryption, for playing media while it downloads.
+//!
+//! A plain AES-GCM blob has one tag over the whole ciphertext, so none of it can be trusted before
+//! the last byte is in. Voice messages to clients that advertise
+//! [`crate::compression::SEALED_CHUNKS`] are sealed in records instead (the STREAM construction):
+//! the plaintext is cut into [`RECORD_PLAINTEXT`]-byte records, each AES-256-GCM-sealed under its
+//! own nonce — the attachment's random 7-byte `decryption-nonce`, the record's index and a
+//! last-record flag. Every record authenticates on its own, and records can't be reordered,
+//! dropped or cut off at a record boundary. [`SealedStream`] opens them as they arrive and yields
+//! only authenticated plaintext.
+//!
+//! The 7-byte nonce is what marks a blob as sealed ([`is_sealed_nonce`]): [`super::encrypt_data`]
+//! and [`super::decrypt_data`] dispatch on it, so every other path reads these blobs unchanged.
+
+use aes::cipher::typenum::U12;
+use aes_gcm::{AeadInPlace, Aes256Gcm, KeyInit};
+use zeroize::Zeroize;
+
+/// Length of the nonce prefix a sealed blob's `decryption-nonce` carries.
+pub const NONCE_PREFIX_LEN: usize = 7;
+
+/// Plaintext bytes per record (the last one may be shorter).
+pub const RECORD_PLAINTEXT: usize = 64 * 1024;
+
+const TAG_LEN: usize = 16;
+const RECORD_LEN: usize = RECORD_PLAINTEXT + TAG_LEN;
+
+/// Whether an attachment's hex nonce marks a sealed-chunk blob.
+pub fn is_sealed_nonce(nonce_hex: &str) -> bool {
+    nonce_hex.len() == NONCE_PREFIX_LEN * 2
+}
+
+/// Fresh key and nonce prefix for a sealed-chunk blob.
+pub fn generate_params() -> super::EncryptionParams {
+    let mut params = super::generate_encryption_params();
+    params.nonce.truncate(NONCE_PREFIX_LEN * 2);
+    params
+}
+
+fn cipher_and_prefix(key_hex: &str, nonce_hex: &str) -> Result<(Aes256Gcm, [u8; NONCE_PREFIX_LEN]), String> {
+    let mut key = super::hex::decode(key_hex).map_err(|e| format!("Invalid key: {}", e))?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| "Invalid encryption key".to_string());
+    key.zeroize();
+    let prefix = super::hex::decode(nonce_hex)
+        .map_err(|e| format!("Invalid nonce: {}", e))?
+        .try_into()
+        .map_err(|_| "Invalid nonce length".to_string())?;
+    Ok((cipher?, prefix))
+}
+
+fn record_nonce(prefix: &[u8; NONCE_PREFIX_LEN], index: u32, last: bool) -> aes_gcm::Nonce<U12> {
+    let mut nonce = [0u8; 12];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..11].copy_from_slice(&index.to_be_bytes());
+    nonce[11] = u8::from(last);
+    aes_gcm::Nonce::<U12>::from(nonce)
+}
+
+/// Seal `data` in records.
+pub fn encrypt(data: &[u8], key_hex: &str, nonce_hex: &str) -> Result<Vec<u8>, String> {
+    let (cipher, prefix) = cipher_and_prefix(key_hex, nonce_hex)?;
+    let records = data.len().div_ceil(RECORD_PLAINTEXT).max(1);
+    if records > u32::MAX as usize {
+        return Err("File too large to seal".to_string());
+    }
+    let mut out = Vec::with_capacity(data.len() + records * TAG_LEN);
+    for index in 0..records {
+        let start = index * RECORD_PLAINTEXT;
+        let mut record = data[start..(start + RECORD_PLAINTEXT).min(data.len())].to_vec();
+        let nonce = record_nonce(&prefix, index as u32, index + 1 == records);
+        let tag = cipher.encrypt_in_place_detached(&nonce, &[], &mut record)
+            .map_err(|_| "Encryption failed".to_string())?;
+        out.extend_from_slice(&record);
+        out.extend_from_slice(&tag);
+    }
+    Ok(out)
+}
+
+/// Open a whole sealed blob.
+pub fn decrypt(blob: &[u8], key_hex: &str, nonce_hex: &str) -> Result<Vec<u8>, String> {
+    let mut stream = SealedStream::new(key_hex, nonce_hex)?;
+    let mut out = stream.feed(blob)?;
+    out.extend(stream.finish()?);
+    Ok(out)
+}
+
+/// Opens one sealed blob fed to it in arbitrary pieces.
+pub struct SealedStream {
+    cipher: Aes256Gcm,
+    prefix: [u8; NONCE_PREFIX_LEN],
+    /// Index of the next record.
+    index: u32,
+    /// Ciphertext of records not opened yet.
+    held: Vec<u8>,
+}
+
+impl SealedStream {
+    /// Start opening a blob with the attachment's hex key and 7-byte hex nonce.
+    pub fn new(key_hex: &str, nonce_hex: &str) -> Result<Self, String> {
+        let (cipher, prefix) = cipher_and_prefix(key_hex, nonce_hex)?;
+        Ok(Self { cipher, prefix, index: 0, held: Vec::with_capacity(2 * RECORD_LEN) })
+    }
+
+    /// Feed the next piece of the blob. Returns the plaintext of every record it completes, all of
+    /// it authenticated; a record that fails is an error. A full record is only known not to be
+    /// the last once bytes past it arrive, so the newest one waits for the next piece or
+    /// [`Self::finish`].
+    pub fn feed(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+        self.held.extend_from_slice(ciphertext);
+        let mut out = Vec::new();
+        while self.held.len() > RECORD_LEN {
+            let record: Vec<u8> = self.held.drain(..RECORD_LEN).collect();
+            out.extend(self.open(record, false)?);
+        }
+        Ok(out)
+    }
+
+    /// Open the last record, once the whole blob was fed. Fails when the blob was cut short.
+    pub fn finish(mut self) -> Result<Vec<u8>, String> {
+        let record = std::mem::take(&mut self.held);
+        self.open(record, true)
+    }
+
+    fn open(&mut self, mut record: Vec<u8>, last: bool) -> Result<Vec<u8>, String> {
+        if record.len() < TAG_LEN {
+            return Err("Attachment was cut short".to_string());
+        }
+        let tag_start = record.len() - TAG_LEN;
+        let tag = aes_gcm::Tag::clone_from_slice(&record[tag_start..]);
+        record.truncate(tag_start);
+        self.cipher
+            .decrypt_in_place_detached(&record_nonce(&self.prefix, self.index, last), &[], &mut record, &tag)
+            .map_err(|_| "Attachment failed authentication".to_string())?;
+        self.index = self.index.checked_add(1).ok_or("Attachment too large")?;
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn sealed_blobs_open_in_any_piece_size() {
+        let params = generate_params();
+        for len in [0, 1, RECORD_PLAINTEXT, RECORD_PLAINTEXT + 1, 3 * RECORD_PLAINTEXT + 5] {
+            let data = plain(len);
+            let blob = super::super::encrypt_data(&data, &params).unwrap();
+            assert_eq!(super::super::decrypt_data(&blob, &params.key, &params.nonce).unwrap(), data);
+            for step in [1000, RECORD_LEN, RECORD_LEN + 1, blob.len().max(1)] {
+                let mut stream = SealedStream::new(&params.key, &params.nonce).unwrap();
+                let mut out = Vec::new();
+                for piece in blob.chunks(step) {
+                    out.extend(stream.feed(piece).unwrap());
+                }
+                out.extend(stream.finish().unwrap());
+                assert_eq!(out, data, "{} bytes in pieces of {}", len, step);
+            }
+        }
+    }
+
+    #[test]
+    fn tampered_records_are_never_yielded() {
+        let params = generate_params();
+        let data = plain(3 * RECORD_PLAINTEXT);
+        let mut blob = encrypt(&data, &params.key, &params.nonce).unwrap();
+        blob[RECORD_LEN + 10] ^= 1;
+
+        let mut stream = SealedStream::new(&params.key, &params.nonce).unwrap();
+        let first = stream.feed(&blob[..RECORD_LEN + 1]).unwrap();
+        assert_eq!(first, data[..RECORD_PLAINTEXT], "the intact first record opens");
+        assert!(stream.feed(&blob[RECORD_LEN + 1..]).is_err(), "the tampered second one doesn't");
+    }
+
+    #[test]
+    fn truncated_or_reordered_blobs_fail() {
+        let params = generate_params();
+        let blob = encrypt(&plain(2 * RECORD_PLAINTEXT + 5), &params.key, &params.nonce).unwrap();
+        // Cut at a record boundary: the last record there wasn't sealed as the last
+        assert!(decrypt(&blob[..2 * RECORD_LEN], &params.key, &params.nonce).is_err());
+        let mut swapped = blob[RECORD_LEN..2 * RECORD_LEN].to_vec();
+        swapped.extend_from_slice(&blob[..RECORD_LEN]);
+        swapped.extend_from_slice(&blob[2 * RECORD_LEN..]);
+        assert!(decrypt(&swapped, &params.key, &params.nonce).is_err());
+    }
+
+    #[test]
+    fn only_seven_byte_nonces_are_sealed() {
+        assert!(is_sealed_nonce(&generate_params().nonce));
+        assert!(!is_sealed_nonce(&super::super::generate_encryption_params().nonce));
+    }
+}
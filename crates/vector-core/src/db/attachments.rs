@@ -7,16 +7,72 @@
 
 use std::collections::HashMap;
 
-use crate::types::Attachment;
+use crate::types::{Attachment, SiteMetadata, Transcript};
+
+/// Gallery bucket for an attachment, persisted as `attachments.media_kind` (migration 77).
+/// `Link` never appears in the table — it is the "shared links" view over `events.preview_metadata`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[repr(i64)]
+pub enum MediaKind {
+    Image = 0,
+    Video = 1,
+    Voice = 2,
+    File = 3,
+    Link = 4,
+    VideoNote = 5,
+}
+
+impl MediaKind {
+    /// Classify by extension. Voice notes are the nameless audio attachments the recorder sends (a
+    /// named audio file is a shared file, not a voice note); video notes are the videos the sender
+    /// marked as one (`Attachment::video_note`).
+    pub fn classify(extension: &str, name: &str, video_note: bool) -> Self {
+        let mime = crate::crypto::mime_from_extension(extension);
+        if mime.starts_with("image/") {
+            MediaKind::Image
+        } else if mime.starts_with("video/") && video_note {
+            MediaKind::VideoNote
+        } else if mime.starts_with("video/") {
+            MediaKind::Video
+        } else if mime.starts_with("audio/") && name.is_empty() {
+            MediaKind::Voice
+        } else {
+            MediaKind::File
+        }
+    }
+}
+
+/// One gallery entry. Attachment kinds carry the attachment (thumbhash in `img_meta`, download state
+/// in `downloaded`/`path`); `Link` carries the message's preview metadata instead.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ChatMediaItem {
+    pub message_id: String,
+    /// Milliseconds, matching `Message::at`.
+    pub at: u64,
+    pub mine: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment: Option<Attachment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<SiteMetadata>,
+}
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct ChatMediaPage {
+    pub items: Vec<ChatMediaItem>,
+    pub has_more: bool,
+}
 
 const SELECT_COLS: &str = "event_id, att_index, hash, key, nonce, extension, name, url, \
-    path, size, img_meta, downloaded, webxdc_topic, group_id, original_hash";
+    path, size, img_meta, downloaded, webxdc_topic, group_id, original_hash, view_once, viewed_at, mirrors, transcript, media_kind";
 
 /// Rebuild `(event_id, Attachment)` from a row selecting `SELECT_COLS`. `downloading` is transient
 /// runtime state and is never persisted (always false on load).
 fn row_to_attachment(row: &rusqlite::Row) -> rusqlite::Result<(String, Attachment)> {
     let event_id: String = row.get(0)?;
     let img_meta_json: Option<String> = row.get(10)?;
+    let mirrors_json: Option<String> = row.get(17)?;
+    let transcript: Option<String> = row.get(18)?;
     let att = Attachment {
         id: row.get(2)?,
         key: row.get(3)?,
@@ -32,6 +88,11 @@ fn row_to_attachment(row: &rusqlite::Row) -> rusqlite::Result<(String, Attachmen
         webxdc_topic: row.get(12)?,
         group_id: row.get(13)?,
         original_hash: row.get(14)?,
+        mirrors: mirrors_json.and_then(|j| serde_json::from_str(&j).ok()).unwrap_or_default(),
+        view_once: row.get::<_, i64>(15)? != 0,
+        video_note: row.get::<_, i64>(19)? == MediaKind::VideoNote as i64,
+        viewed: row.get::<_, Option<i64>>(16)?.is_some(),
+        transcript: transcript.and_then(|t| decode_transcript(&t)),
     };
     Ok((event_id, att))
 }
@@ -44,7 +105,8 @@ fn row_to_attachment(row: &rusqlite::Row) -> rusqlite::Result<(String, Attachmen
 /// carries a completed download (`downloaded=1`) — the nonce→content-hash rewrite the download path
 /// performs. So a relay re-delivery (downloaded=0) preserves the downloaded file, its content-hash
 /// key, and its path; a completed download persists all three in one pass. Explicit un-download goes
-/// through `clear_attachment_download`, never here.
+/// through `clear_attachment_download`, never here. An opened view-once attachment (`viewed_at`) stays
+/// not-downloaded whatever comes in. A download landing here stamps `last_used` for the media cache.
 pub fn insert_attachment_rows(conn: &rusqlite::Connection, event_id: &str, attachments: &[Attachment]) -> Result<(), String> {
     if attachments.is_empty() {
         return Ok(());
@@ -53,24 +115,34 @@ pub fn insert_attachment_rows(conn: &rusqlite::Connection, event_id: &str, attac
     // so bulk-sync batches don't re-parse the SQL per message.
     let mut stmt = conn.prepare_cached(
         "INSERT INTO attachments (event_id, att_index, hash, key, nonce, extension, name, url, \
-         path, size, img_meta, downloaded, webxdc_topic, group_id, original_hash) \
-         VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13,?14,?15) \
+         path, size, img_meta, downloaded, webxdc_topic, group_id, original_hash, media_kind, view_once, mirrors, \
+         last_used) \
+         VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13,?14,?15,?16,?17,?18,CASE WHEN ?12=1 THEN ?19 ELSE 0 END) \
          ON CONFLICT(event_id, att_index) DO UPDATE SET \
             key=excluded.key, nonce=excluded.nonce, extension=excluded.extension, \
             name=excluded.name, url=excluded.url, size=excluded.size, img_meta=excluded.img_meta, \
+            media_kind=excluded.media_kind, \
             webxdc_topic=excluded.webxdc_topic, group_id=excluded.group_id, \
-            original_hash=excluded.original_hash, \
-            downloaded=MAX(downloaded, excluded.downloaded), \
-            hash=CASE WHEN excluded.downloaded=1 THEN excluded.hash ELSE hash END, \
-            path=CASE WHEN excluded.downloaded=1 THEN excluded.path ELSE path END",
+            original_hash=excluded.original_hash, mirrors=excluded.mirrors, \
+            view_once=MAX(view_once, excluded.view_once), \
+            downloaded=CASE WHEN viewed_at IS NOT NULL THEN 0 ELSE MAX(downloaded, excluded.downloaded) END, \
+            hash=CASE WHEN excluded.downloaded=1 AND viewed_at IS NULL THEN excluded.hash ELSE hash END, \
+            path=CASE WHEN viewed_at IS NOT NULL THEN '' WHEN excluded.downloaded=1 THEN excluded.path ELSE path END, \
+            last_used=CASE WHEN downloaded=0 AND excluded.downloaded=1 THEN ?19 ELSE last_used END",
     ).map_err(|e| format!("prepare insert attachment: {e}"))?;
+    let now = now_secs();
     for (i, a) in attachments.iter().enumerate() {
         let img_meta_json = a.img_meta.as_ref().and_then(|m| serde_json::to_string(m).ok());
+        let mirrors_json = (!a.mirrors.is_empty()).then(|| serde_json::to_string(&a.mirrors).ok()).flatten();
         stmt.execute(
             rusqlite::params![
                 event_id, i as i64, a.id, a.key, a.nonce, a.extension, a.name, a.url,
                 a.path, a.size as i64, img_meta_json, a.downloaded as i64,
                 a.webxdc_topic, a.group_id, a.original_hash,
+                MediaKind::classify(&a.extension, &a.name, a.video_note) as i64,
+                a.view_once as i64,
+                mirrors_json,
+                now,
             ],
         ).map_err(|e| format!("insert attachment: {e}"))?;
     }
@@ -105,17 +177,168 @@ pub fn get_attachments_for_event(event_id: &str) -> Result<Vec<Attachment>, Stri
     Ok(map.into_values().next().unwrap_or_default())
 }
 
+/// One page of a chat's shared media, newest first. Attachment kinds are an indexed
+/// `media_kind` filter joined onto the chat's events; `Link` lists messages with a stored preview.
+/// Fetches one extra row to report `has_more` without a COUNT.
+pub fn get_chat_media(chat_id: i64, kind: MediaKind, page: usize, page_size: usize) -> Result<ChatMediaPage, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let limit = page_size as i64 + 1;
+    let offset = (page * page_size) as i64;
+    let mut items: Vec<ChatMediaItem> = if kind == MediaKind::Link {
+        let mut stmt = conn.prepare(
+            "SELECT id, created_at, mine, preview_metadata FROM events \
+             WHERE chat_id = ?1 AND preview_metadata IS NOT NULL AND preview_metadata != '' \
+             ORDER BY created_at DESC, received_at DESC LIMIT ?2 OFFSET ?3"
+        ).map_err(|e| format!("prepare chat links: {e}"))?;
+        let rows = stmt.query_map(rusqlite::params![chat_id, limit, offset], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?, r.get::<_, i64>(2)?, r.get::<_, String>(3)?))
+        }).map_err(|e| format!("query chat links: {e}"))?;
+        rows.flatten()
+            .filter_map(|(id, at, mine, meta)| {
+                let link: SiteMetadata = serde_json::from_str(&meta).ok()?;
+                Some(ChatMediaItem { message_id: id, at: at as u64 * 1000, mine: mine != 0, attachment: None, link: Some(link) })
+            })
+            .collect()
+    } else {
+        // SELECT_COLS are all attachments-only names, so they resolve unqualified across the join.
+        let sql = format!(
+            "SELECT {SELECT_COLS}, e.created_at, e.mine FROM attachments a JOIN events e ON e.id = a.event_id \
+             WHERE e.chat_id = ?1 AND a.media_kind = ?2 \
+             ORDER BY e.created_at DESC, e.received_at DESC, a.att_index ASC LIMIT ?3 OFFSET ?4"
+        );
+        let mut stmt = conn.prepare(&sql).map_err(|e| format!("prepare chat media: {e}"))?;
+        let rows = stmt.query_map(rusqlite::params![chat_id, kind as i64, limit, offset], |r| {
+            let (event_id, att) = row_to_attachment(r)?;
+            Ok(ChatMediaItem {
+                message_id: event_id,
+                at: r.get::<_, i64>(20)? as u64 * 1000,
+                mine: r.get::<_, i64>(21)? != 0,
+                attachment: Some(att),
+                link: None,
+            })
+        }).map_err(|e| format!("query chat media: {e}"))?;
+        rows.flatten().collect()
+    };
+    let has_more = items.len() > page_size;
+    items.truncate(page_size);
+    Ok(ChatMediaPage { items, has_more })
+}
+
 /// Flip one attachment's downloaded state — a single-row UPDATE keyed by (event_id, content hash),
 /// replacing the old read-modify-write of the whole tags blob.
 pub fn set_attachment_downloaded(event_id: &str, hash: &str, downloaded: bool, path: &str) -> Result<(), String> {
     let conn = super::get_write_connection_guard_static()?;
     conn.execute(
-        "UPDATE attachments SET downloaded=?1, path=?2 WHERE event_id=?3 AND hash=?4",
-        rusqlite::params![downloaded as i64, path, event_id, hash],
+        "UPDATE attachments SET downloaded=?1, path=?2, archived_from=NULL, \
+         last_used=CASE WHEN ?1=1 THEN ?5 ELSE last_used END \
+         WHERE event_id=?3 AND hash=?4 AND (?1=0 OR viewed_at IS NULL)",
+        rusqlite::params![downloaded as i64, path, event_id, hash, now_secs()],
     ).map_err(|e| format!("set_attachment_downloaded: {e}"))?;
     Ok(())
 }
 
+/// Store a voice attachment's transcript, keyed like `set_attachment_downloaded`. Encrypted at rest
+/// like message content; re-saving the message never touches it. View-once media keeps none.
+pub fn set_attachment_transcript(event_id: &str, hash: &str, transcript: &Transcript) -> Result<(), String> {
+    let json = serde_json::to_string(transcript).map_err(|e| format!("serialize transcript: {e}"))?;
+    let stored = crate::crypto::maybe_encrypt_text(&json)?;
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute(
+        "UPDATE attachments SET transcript=?1 WHERE event_id=?2 AND hash=?3 AND view_once=0",
+        rusqlite::params![stored, event_id, hash],
+    ).map_err(|e| format!("set_attachment_transcript: {e}"))?;
+    Ok(())
+}
+
+/// Transcripts of the given events' attachments, as `event_id → text` (one message has at most one
+/// voice note, so the first per event wins).
+pub fn get_transcript_texts(event_ids: &[String]) -> Result<HashMap<String, String>, String> {
+    let mut out = HashMap::new();
+    if event_ids.is_empty() {
+        return Ok(out);
+    }
+    let conn = super::get_db_connection_guard_static()?;
+    let placeholders = event_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let sql = format!(
+        "SELECT event_id, transcript FROM attachments \
+         WHERE transcript IS NOT NULL AND event_id IN ({placeholders}) ORDER BY att_index"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| format!("prepare transcripts: {e}"))?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(event_ids.iter()), |r| {
+        Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?))
+    }).map_err(|e| format!("query transcripts: {e}"))?;
+    for (event_id, stored) in rows.flatten() {
+        if let Some(t) = decode_transcript(&stored) {
+            out.entry(event_id).or_insert_with(|| t.text());
+        }
+    }
+    Ok(out)
+}
+
+fn decode_transcript(stored: &str) -> Option<Transcript> {
+    serde_json::from_str(&crate::crypto::maybe_decrypt_text(stored)).ok()
+}
+
+fn now_secs() -> i64 {
+    crate::clock::now_secs() as i64
+}
+
+/// Newest messages of a chat whose files count as used when the chat is opened.
+const TOUCH_RECENT_MESSAGES: i64 = 200;
+
+/// Mark the downloaded files of a chat's recent messages as just used (the chat was opened), so
+/// the media cache evicts them last.
+pub fn touch_chat_media(chat_id: i64) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute(
+        "UPDATE attachments SET last_used=?3 WHERE downloaded=1 AND event_id IN (
+             SELECT id FROM events WHERE chat_id=?1 ORDER BY created_at DESC LIMIT ?2
+         )",
+        rusqlite::params![chat_id, TOUCH_RECENT_MESSAGES, now_secs()],
+    ).map_err(|e| format!("touch_chat_media: {e}"))?;
+    Ok(())
+}
+
+/// Downloaded files in least-recently-used order, as (path, last used secs). A file several
+/// attachments share counts as used when any of them was; one never touched counts from its
+/// newest message. Archived files are out of the cache's hands.
+pub fn cached_files_lru() -> Result<Vec<(String, u64)>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT a.path, MAX(MAX(a.last_used, e.created_at)) AS used
+         FROM attachments a JOIN events e ON e.id = a.event_id
+         WHERE a.downloaded=1 AND a.path!='' AND a.archived_from IS NULL
+         GROUP BY a.path ORDER BY used ASC"
+    ).map_err(|e| format!("prepare cached_files_lru: {e}"))?;
+    let rows = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?.max(0) as u64)))
+        .map_err(|e| format!("query cached_files_lru: {e}"))?;
+    Ok(rows.flatten().collect())
+}
+
+/// Cached files that a starred message points at, which the media cache never evicts.
+pub fn starred_paths() -> Result<std::collections::HashSet<String>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT a.path FROM attachments a JOIN starred_messages s ON s.message_id = a.event_id
+         WHERE a.downloaded=1 AND a.path!=''"
+    ).map_err(|e| format!("prepare starred_paths: {e}"))?;
+    let rows = stmt.query_map([], |r| r.get::<_, String>(0))
+        .map_err(|e| format!("query starred_paths: {e}"))?;
+    Ok(rows.flatten().collect())
+}
+
+/// Record that the cached file at `path` was evicted: every attachment pointing at it goes back
+/// to not-downloaded (re-downloadable from its URL). Returns the affected event ids.
+pub fn mark_evicted(path: &str) -> Result<Vec<String>, String> {
+    let conn = super::get_write_connection_guard_static()?;
+    let affected = events_at_path(&conn, path)?;
+    conn.execute(
+        "UPDATE attachments SET downloaded=0, path='' WHERE downloaded=1 AND path=?1",
+        rusqlite::params![path],
+    ).map_err(|e| format!("mark_evicted: {e}"))?;
+    Ok(affected)
+}
+
 /// Mark every OTHER attachment sharing this content hash as downloaded to the same path — the
 /// download-sharing dedup, now an indexed `WHERE hash = ?` instead of a `LIKE '%hash%'` table scan.
 /// Returns the affected event ids so the caller can reconcile in-memory STATE.
@@ -123,28 +346,28 @@ pub fn backfill_downloaded_by_hash(hash: &str, path: &str, exclude_event_id: &st
     let conn = super::get_write_connection_guard_static()?;
     let affected: Vec<String> = {
         let mut stmt = conn.prepare(
-            "SELECT DISTINCT event_id FROM attachments WHERE hash=?1 AND event_id!=?2 AND downloaded=0"
+            "SELECT DISTINCT event_id FROM attachments WHERE hash=?1 AND event_id!=?2 AND downloaded=0 AND viewed_at IS NULL"
         ).map_err(|e| format!("prepare backfill_by_hash: {e}"))?;
         let rows = stmt.query_map(rusqlite::params![hash, exclude_event_id], |r| r.get::<_, String>(0))
             .map_err(|e| format!("query backfill_by_hash: {e}"))?;
         rows.flatten().collect()
     };
     conn.execute(
-        "UPDATE attachments SET downloaded=1, path=?1 WHERE hash=?2 AND event_id!=?3 AND downloaded=0",
+        "UPDATE attachments SET downloaded=1, path=?1 WHERE hash=?2 AND event_id!=?3 AND downloaded=0 AND viewed_at IS NULL",
         rusqlite::params![path, hash, exclude_event_id],
     ).map_err(|e| format!("backfill_by_hash update: {e}"))?;
     Ok(affected)
 }
 
-/// A downloaded attachment's on-disk path, for the integrity sweep. Returns (event_id, hash, path)
-/// for every attachment claiming `downloaded=1` with a non-empty path — an indexed read, no per-event
-/// JSON parse.
-pub fn downloaded_attachment_paths() -> Result<Vec<(String, String, String)>, String> {
+/// A downloaded attachment's on-disk path, for the integrity sweep. Returns (event_id, hash, path,
+/// archived) for every attachment claiming `downloaded=1` with a non-empty path — an indexed read, no
+/// per-event JSON parse.
+pub fn downloaded_attachment_paths() -> Result<Vec<(String, String, String, bool)>, String> {
     let conn = super::get_db_connection_guard_static()?;
     let mut stmt = conn.prepare(
-        "SELECT event_id, hash, path FROM attachments WHERE downloaded=1 AND path!=''"
+        "SELECT event_id, hash, path, archived_from IS NOT NULL FROM attachments WHERE downloaded=1 AND path!=''"
     ).map_err(|e| format!("prepare downloaded_paths: {e}"))?;
-    let rows = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?)))
+    let rows = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?, r.get::<_, bool>(3)?)))
         .map_err(|e| format!("query downloaded_paths: {e}"))?;
     Ok(rows.flatten().collect())
 }
@@ -153,12 +376,97 @@ pub fn downloaded_attachment_paths() -> Result<Vec<(String, String, String)>, St
 pub fn clear_attachment_download(event_id: &str, hash: &str) -> Result<(), String> {
     let conn = super::get_write_connection_guard_static()?;
     conn.execute(
-        "UPDATE attachments SET downloaded=0, path='' WHERE event_id=?1 AND hash=?2",
+        "UPDATE attachments SET downloaded=0, path='', archived_from=NULL WHERE event_id=?1 AND hash=?2",
         rusqlite::params![event_id, hash],
     ).map_err(|e| format!("clear_attachment_download: {e}"))?;
     Ok(())
 }
 
+/// Mark a view-once attachment opened: it's no longer downloaded and never will be again, and any
+/// transcript of it goes too. Returns
+/// its former path, and whether any other attachment still points at that file (a content-hash
+/// shared download), so the caller only deletes a file nothing else uses.
+pub fn mark_attachment_viewed(event_id: &str, hash: &str) -> Result<Option<(String, bool)>, String> {
+    let conn = super::get_write_connection_guard_static()?;
+    let path: Option<String> = conn.query_row(
+        "SELECT path FROM attachments WHERE event_id=?1 AND hash=?2 AND view_once=1",
+        rusqlite::params![event_id, hash],
+        |r| r.get(0),
+    ).ok();
+    let Some(path) = path else { return Ok(None) };
+    let now = now_secs();
+    conn.execute(
+        "UPDATE attachments SET viewed_at=COALESCE(viewed_at, ?3), downloaded=0, path='', archived_from=NULL, transcript=NULL \
+         WHERE event_id=?1 AND hash=?2",
+        rusqlite::params![event_id, hash, now],
+    ).map_err(|e| format!("mark_attachment_viewed: {e}"))?;
+    let shared = !path.is_empty() && !events_at_path(&conn, &path)?.is_empty();
+    Ok(Some((path, shared)))
+}
+
+/// Downloaded files that only messages older than `cutoff_secs` point at — the candidates for
+/// archiving. A file shared (by content hash) with a newer message stays put.
+pub fn archivable_paths(cutoff_secs: u64) -> Result<Vec<String>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT a.path FROM attachments a JOIN events e ON e.id = a.event_id
+         WHERE a.downloaded=1 AND a.path!='' AND a.archived_from IS NULL AND e.created_at < ?1
+           AND a.path NOT IN (
+               SELECT a2.path FROM attachments a2 JOIN events e2 ON e2.id = a2.event_id
+               WHERE a2.downloaded=1 AND e2.created_at >= ?1
+           )"
+    ).map_err(|e| format!("prepare archivable_paths: {e}"))?;
+    let rows = stmt.query_map(rusqlite::params![cutoff_secs as i64], |r| r.get::<_, String>(0))
+        .map_err(|e| format!("query archivable_paths: {e}"))?;
+    Ok(rows.flatten().collect())
+}
+
+/// Event ids of the attachments pointing at `path`.
+fn events_at_path(conn: &rusqlite::Connection, path: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn.prepare("SELECT DISTINCT event_id FROM attachments WHERE downloaded=1 AND path=?1")
+        .map_err(|e| format!("prepare events_at_path: {e}"))?;
+    let rows = stmt.query_map(rusqlite::params![path], |r| r.get::<_, String>(0))
+        .map_err(|e| format!("query events_at_path: {e}"))?;
+    Ok(rows.flatten().collect())
+}
+
+/// Record that the file at `from` was moved to the archive at `to`: every attachment pointing at it
+/// is repointed, remembering `from` for [`mark_restored`]. Returns the affected event ids.
+pub fn mark_archived(from: &str, to: &str) -> Result<Vec<String>, String> {
+    let conn = super::get_write_connection_guard_static()?;
+    let affected = events_at_path(&conn, from)?;
+    conn.execute(
+        "UPDATE attachments SET path=?2, archived_from=?1 WHERE downloaded=1 AND path=?1",
+        rusqlite::params![from, to],
+    ).map_err(|e| format!("mark_archived: {e}"))?;
+    Ok(affected)
+}
+
+/// Archived files as (archived path, original path) — all of them, or just the ones `event_id`'s
+/// attachments point at.
+pub fn archived_paths(event_id: Option<&str>) -> Result<Vec<(String, String)>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT path, archived_from FROM attachments
+         WHERE archived_from IS NOT NULL AND downloaded=1 AND (?1 IS NULL OR event_id=?1)"
+    ).map_err(|e| format!("prepare archived_paths: {e}"))?;
+    let rows = stmt.query_map(rusqlite::params![event_id], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))
+        .map_err(|e| format!("query archived_paths: {e}"))?;
+    Ok(rows.flatten().collect())
+}
+
+/// Record that the archived file at `path` was moved back to its original location. Returns the
+/// affected event ids.
+pub fn mark_restored(path: &str) -> Result<Vec<String>, String> {
+    let conn = super::get_write_connection_guard_static()?;
+    let affected = events_at_path(&conn, path)?;
+    conn.execute(
+        "UPDATE attachments SET path=archived_from, archived_from=NULL WHERE path=?1 AND archived_from IS NOT NULL",
+        rusqlite::params![path],
+    ).map_err(|e| format!("mark_restored: {e}"))?;
+    Ok(affected)
+}
+
 /// Repoint downloaded-file paths from old download directories to a new one (Android download-dir
 /// migration). For each downloaded attachment whose path starts with an old prefix: move it to
 /// `new_dir/<filename>` if that exists, else mark it not-downloaded. Returns the affected event ids.
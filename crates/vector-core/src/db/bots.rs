@@ -4,13 +4,7 @@
 //! Manifests are PUBLIC replaceable events: rows are plaintext by design.
 
 use rusqlite::{params, OptionalExtension};
-
-fn now_secs() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0)
-}
+use crate::clock::now_secs;
 
 /// Store a validated manifest for `pubkey_hex`, keeping whichever edition is
 /// newest (`event_created_at` = the manifest event's timestamp). An equal-time
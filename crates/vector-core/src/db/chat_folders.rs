@@ -0,0 +1,174 @@
+//! Chat folders — user-defined folders ("Work", "Family") for organizing the chat list.
+//!
+//! Unlike contact groups (`contact_groups`), a folder holds any chat, DMs and communities alike, and a
+//! chat sits in at most one folder. Purely local: nothing is published. Names are unique
+//! case-insensitively. Folder unread counts come from `ChatState`, not from here.
+
+use rusqlite::{params, OptionalExtension};
+use crate::clock::now_secs;
+
+/// Longest accepted folder name, in characters.
+pub const MAX_FOLDER_NAME_LEN: usize = 40;
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct ChatFolder {
+    pub id: i64,
+    pub name: String,
+    /// Member chat identifiers, sorted.
+    pub chats: Vec<String>,
+    /// Unread across the folder's chats, filled in from `ChatState` by the caller (0 from the DB).
+    pub unread: u32,
+}
+
+fn clean_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Folder name can't be empty".into());
+    }
+    if name.chars().count() > MAX_FOLDER_NAME_LEN {
+        return Err(format!("Folder name is limited to {} characters", MAX_FOLDER_NAME_LEN));
+    }
+    Ok(name.to_string())
+}
+
+fn map_unique(e: rusqlite::Error) -> String {
+    match e {
+        rusqlite::Error::SqliteFailure(err, _) if err.code == rusqlite::ErrorCode::ConstraintViolation => {
+            "A folder with that name already exists".to_string()
+        }
+        e => format!("chat folder: {e}"),
+    }
+}
+
+/// Create a folder and return it (empty).
+pub fn create_folder(name: &str) -> Result<ChatFolder, String> {
+    let name = clean_name(name)?;
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute(
+        "INSERT INTO chat_folders (name, created_at) VALUES (?1, ?2)",
+        params![name, now_secs() as i64],
+    ).map_err(map_unique)?;
+    Ok(ChatFolder { id: conn.last_insert_rowid(), name, chats: Vec::new(), unread: 0 })
+}
+
+pub fn rename_folder(id: i64, name: &str) -> Result<(), String> {
+    let name = clean_name(name)?;
+    let conn = super::get_write_connection_guard_static()?;
+    let changed = conn.execute("UPDATE chat_folders SET name = ?1 WHERE id = ?2", params![name, id])
+        .map_err(map_unique)?;
+    if changed == 0 {
+        return Err("Folder not found".into());
+    }
+    Ok(())
+}
+
+/// Delete a folder; its chats go back to being unfiled (the chats themselves are untouched).
+pub fn delete_folder(id: i64) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    let tx = conn.unchecked_transaction().map_err(|e| format!("delete chat folder tx: {e}"))?;
+    tx.execute("DELETE FROM chat_folder_members WHERE folder_id = ?1", params![id])
+        .map_err(|e| format!("delete chat folder: {e}"))?;
+    tx.execute("DELETE FROM chat_folders WHERE id = ?1", params![id])
+        .map_err(|e| format!("delete chat folder: {e}"))?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Move a chat into a folder (out of any other), or out of its folder with `None`. Idempotent.
+pub fn assign_chat(chat_id: &str, folder_id: Option<i64>) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    match folder_id {
+        Some(folder_id) => {
+            let exists: Option<i64> = conn
+                .query_row("SELECT id FROM chat_folders WHERE id = ?1", params![folder_id], |r| r.get(0))
+                .optional()
+                .map_err(|e| e.to_string())?;
+            if exists.is_none() {
+                return Err("Folder not found".into());
+            }
+            conn.execute(
+                "INSERT INTO chat_folder_members (chat_identifier, folder_id) VALUES (?1, ?2)
+                 ON CONFLICT(chat_identifier) DO UPDATE SET folder_id = excluded.folder_id",
+                params![chat_id, folder_id],
+            )
+        }
+        None => conn.execute("DELETE FROM chat_folder_members WHERE chat_identifier = ?1", params![chat_id]),
+    }
+    .map_err(|e| format!("chat folder membership: {e}"))?;
+    Ok(())
+}
+
+/// Every folder with its chats, ordered by name.
+pub fn list_folders() -> Result<Vec<ChatFolder>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT f.id, f.name, m.chat_identifier FROM chat_folders f
+             LEFT JOIN chat_folder_members m ON m.folder_id = f.id
+             ORDER BY f.name COLLATE NOCASE, f.id, m.chat_identifier",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?, r.get::<_, Option<String>>(2)?)))
+        .map_err(|e| e.to_string())?;
+
+    let mut folders: Vec<ChatFolder> = Vec::new();
+    for (id, name, chat) in rows.filter_map(|r| r.ok()) {
+        if folders.last().map(|f| f.id) != Some(id) {
+            folders.push(ChatFolder { id, name, chats: Vec::new(), unread: 0 });
+        }
+        if let (Some(chat), Some(folder)) = (chat, folders.last_mut()) {
+            folder.chats.push(chat);
+        }
+    }
+    Ok(folders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_test_db() -> (tempfile::TempDir, std::sync::MutexGuard<'static, ()>) {
+        let guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        crate::db::clear_id_caches();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+        (tmp, guard)
+    }
+
+    #[test]
+    fn a_chat_sits_in_one_folder() {
+        let (_tmp, _guard) = init_test_db();
+
+        let work = create_folder(" Work ").unwrap();
+        assert_eq!(work.name, "Work");
+        let family = create_folder("Family").unwrap();
+        assert!(create_folder("work").is_err(), "names are unique case-insensitively");
+        assert!(create_folder("   ").is_err());
+
+        assign_chat("npub1alice", Some(work.id)).unwrap();
+        assign_chat("community1", Some(work.id)).unwrap();
+        assign_chat("npub1alice", Some(family.id)).unwrap();
+        assert!(assign_chat("npub1bob", Some(9999)).is_err());
+
+        let folders = list_folders().unwrap();
+        assert_eq!(folders.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), vec!["Family", "Work"]);
+        assert_eq!(folders[0].chats, vec!["npub1alice"], "assigning moves the chat");
+        assert_eq!(folders[1].chats, vec!["community1"]);
+
+        assign_chat("community1", None).unwrap();
+        rename_folder(work.id, "Office").unwrap();
+        assert!(rename_folder(work.id, "family").is_err());
+
+        delete_folder(family.id).unwrap();
+        let folders = list_folders().unwrap();
+        assert_eq!(folders, vec![ChatFolder { id: work.id, name: "Office".into(), chats: Vec::new(), unread: 0 }]);
+        assign_chat("npub1alice", Some(work.id)).unwrap();
+        assert_eq!(list_folders().unwrap()[0].chats, vec!["npub1alice"], "memberships went with the folder");
+    }
+}
@@ -0,0 +1,71 @@
+//! Chat-list snapshot — the last boot's serialized chat list (light profiles plus the last
+//! message per chat), stored as one encrypted row.
+//!
+//! A cold start paints from it before the full load has hydrated STATE; the full load then
+//! replaces it in the UI and rewrites it. Staleness is bounded by that one boot.
+
+use rusqlite::params;
+
+/// Store `payload` (the serialized chat-list JSON), replacing the previous snapshot.
+pub async fn save(payload: String) -> Result<(), String> {
+    let encrypted = crate::crypto::maybe_encrypt(payload).await;
+    let written_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute(
+        "INSERT OR REPLACE INTO chat_list_snapshot (id, payload, written_at) VALUES (1, ?1, ?2)",
+        params![encrypted, written_at as i64],
+    ).map_err(|e| format!("Failed to save chat list snapshot: {}", e))?;
+    Ok(())
+}
+
+/// The stored snapshot JSON, if there is one and it decrypts.
+pub async fn load() -> Option<String> {
+    let stored: String = {
+        let conn = super::get_db_connection_guard_static().ok()?;
+        conn.query_row("SELECT payload FROM chat_list_snapshot WHERE id = 1", [], |row| row.get(0)).ok()?
+    };
+    crate::crypto::maybe_decrypt(stored).await.ok()
+}
+
+/// Drop the snapshot (the next boot falls back to the full load).
+pub fn clear() -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute("DELETE FROM chat_list_snapshot", [])
+        .map_err(|e| format!("Failed to clear chat list snapshot: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_test_db() -> (tempfile::TempDir, std::sync::MutexGuard<'static, ()>) {
+        let guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        crate::db::clear_id_caches();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+        (tmp, guard)
+    }
+
+    #[tokio::test]
+    async fn snapshot_round_trips_and_clears() {
+        let (_tmp, _guard) = init_test_db();
+
+        assert_eq!(load().await, None);
+        save(r#"{"profiles":[],"chats":[1]}"#.into()).await.unwrap();
+        save(r#"{"profiles":[],"chats":[2]}"#.into()).await.unwrap();
+        assert_eq!(load().await.as_deref(), Some(r#"{"profiles":[],"chats":[2]}"#), "a save replaces the single row");
+
+        clear().unwrap();
+        assert_eq!(load().await, None);
+    }
+}
@@ -83,12 +83,13 @@ impl SlimChatDB {
 pub fn get_all_chats() -> Result<Vec<SlimChatDB>, String> {
     let conn = super::get_db_connection_guard_static()?;
 
-    // chat_type 1 was the removed MLS group variant — legacy rows are dropped at load.
+    // chat_type 1 was the removed MLS group variant — legacy rows are dropped at load. Soft-deleted
+    // chats stay out of the list until restored or purged.
     let mut stmt = conn.prepare(
         "SELECT chat_identifier, chat_type, participants, last_read, created_at, metadata, muted, \
                 wallpaper_path, wallpaper_ts, wallpaper_blur, wallpaper_dim, \
                 wallpaper_url, wallpaper_uploader \
-         FROM chats WHERE chat_type != 1 ORDER BY created_at DESC"
+         FROM chats WHERE chat_type != 1 AND deleted_at IS NULL ORDER BY created_at DESC"
     ).map_err(|e| format!("Failed to prepare statement: {}", e))?;
 
     let rows = stmt.query_map([], |row| {
@@ -137,7 +138,8 @@ pub fn save_slim_chat(slim_chat: &SlimChatDB) -> Result<(), String> {
         // predate marker hydration (realtime-created, partial boot), and persisting its
         // empty marker would wipe the stored read position — resurrecting every message
         // since as phantom unread. Marker clears go through the dedicated
-        // `UPDATE chats SET last_read` paths, not this upsert.
+        // `UPDATE chats SET last_read` paths, not this upsert. A save also lifts a soft delete: a
+        // chat re-saved from STATE (a new message in it) is live again.
         "INSERT INTO chats (chat_identifier, chat_type, participants, last_read, created_at, metadata, muted, wallpaper_path, wallpaper_ts, wallpaper_blur, wallpaper_dim, wallpaper_url, wallpaper_uploader) \
          VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13) \
          ON CONFLICT(chat_identifier) DO UPDATE SET \
@@ -146,7 +148,8 @@ pub fn save_slim_chat(slim_chat: &SlimChatDB) -> Result<(), String> {
             metadata = excluded.metadata, muted = excluded.muted, \
             wallpaper_path = excluded.wallpaper_path, wallpaper_ts = excluded.wallpaper_ts, \
             wallpaper_blur = excluded.wallpaper_blur, wallpaper_dim = excluded.wallpaper_dim, \
-            wallpaper_url = excluded.wallpaper_url, wallpaper_uploader = excluded.wallpaper_uploader",
+            wallpaper_url = excluded.wallpaper_url, wallpaper_uploader = excluded.wallpaper_uploader, \
+            deleted_at = NULL",
         rusqlite::params![
             slim_chat.id,
             chat_type_int,
@@ -180,10 +183,103 @@ pub fn delete_chat(chat_identifier: &str) -> Result<(), String> {
         "DELETE FROM chats WHERE chat_identifier = ?1",
         rusqlite::params![chat_identifier],
     ).map_err(|e| format!("Failed to delete chat: {}", e))?;
+    let _ = conn.execute(
+        "DELETE FROM chat_notification_prefs WHERE chat_identifier = ?1",
+        rusqlite::params![chat_identifier],
+    );
+    let _ = conn.execute(
+        "DELETE FROM chat_typing_off WHERE chat_identifier = ?1",
+        rusqlite::params![chat_identifier],
+    );
     super::id_cache::forget_chat_id(chat_identifier);
+    // The boot snapshot may still carry this chat's last message; the next boot rewrites it.
+    let _ = conn.execute("DELETE FROM chat_list_snapshot", []);
     Ok(())
 }
 
+/// How long a soft-deleted chat can be restored before [`purge_deleted_chats`] removes it.
+pub const DELETED_CHAT_RETENTION_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// A soft-deleted chat, as listed for restore.
+#[derive(Serialize, Clone, Debug)]
+pub struct DeletedChat {
+    pub id: String,
+    pub chat_type: ChatType,
+    /// Secs.
+    pub deleted_at: u64,
+    /// Secs; after this the chat is gone for good.
+    pub purge_at: u64,
+}
+
+/// Hide a chat from the chat list, keeping it and its messages for [`restore_chat`]. Returns
+/// whether a live chat was hidden.
+pub fn soft_delete_chat(chat_identifier: &str, at: u64) -> Result<bool, String> {
+    let conn = super::get_write_connection_guard_static()?;
+    let n = conn.execute(
+        "UPDATE chats SET deleted_at = ?2 WHERE chat_identifier = ?1 AND deleted_at IS NULL",
+        rusqlite::params![chat_identifier, at as i64],
+    ).map_err(|e| format!("Failed to soft-delete chat: {}", e))?;
+    // The boot snapshot would otherwise show the chat again until the next boot rewrites it.
+    let _ = conn.execute("DELETE FROM chat_list_snapshot", []);
+    Ok(n > 0)
+}
+
+/// Undo [`soft_delete_chat`], returning the chat to list. `None` when it isn't soft-deleted (never
+/// was, or already purged).
+pub fn restore_chat(chat_identifier: &str) -> Result<Option<SlimChatDB>, String> {
+    {
+        let conn = super::get_write_connection_guard_static()?;
+        let n = conn.execute(
+            "UPDATE chats SET deleted_at = NULL WHERE chat_identifier = ?1 AND deleted_at IS NOT NULL",
+            rusqlite::params![chat_identifier],
+        ).map_err(|e| format!("Failed to restore chat: {}", e))?;
+        if n == 0 {
+            return Ok(None);
+        }
+        let _ = conn.execute("DELETE FROM chat_list_snapshot", []);
+    }
+    Ok(get_all_chats()?.into_iter().find(|c| c.id == chat_identifier))
+}
+
+/// Soft-deleted chats still inside the restore window, most recently deleted first.
+pub fn list_recently_deleted() -> Result<Vec<DeletedChat>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT chat_identifier, chat_type, deleted_at FROM chats \
+         WHERE deleted_at IS NOT NULL AND chat_type != 1 ORDER BY deleted_at DESC"
+    ).map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let rows = stmt.query_map([], |row| {
+        let deleted_at = row.get::<_, i64>(2)? as u64;
+        Ok(DeletedChat {
+            id: row.get(0)?,
+            chat_type: ChatType::from_i32(row.get(1)?),
+            deleted_at,
+            purge_at: deleted_at + DELETED_CHAT_RETENTION_SECS,
+        })
+    }).map_err(|e| format!("Failed to query deleted chats: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect deleted chats: {}", e))
+}
+
+/// Permanently remove chats soft-deleted before `before` (secs), with their messages. Returns how
+/// many went.
+pub fn purge_deleted_chats(before: u64) -> Result<usize, String> {
+    let expired: Vec<String> = {
+        let conn = super::get_db_connection_guard_static()?;
+        let mut stmt = conn.prepare(
+            "SELECT chat_identifier FROM chats WHERE deleted_at IS NOT NULL AND deleted_at < ?1"
+        ).map_err(|e| format!("Failed to prepare statement: {}", e))?;
+        let rows = stmt.query_map(rusqlite::params![before as i64], |row| row.get(0))
+            .map_err(|e| format!("Failed to query expired chats: {}", e))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect expired chats: {}", e))?
+    };
+    for id in &expired {
+        delete_chat(id)?;
+    }
+    Ok(expired.len())
+}
+
 #[cfg(test)]
 mod tests {
     static TEST_COUNTER: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(900);
@@ -272,4 +368,51 @@ mod tests {
             .expect("stub-created non-npub chat must survive get_all_chats");
         assert_eq!(found.chat_type, crate::ChatType::Community);
     }
+
+    // A soft delete hides the chat but keeps its row for restore until the purge, and a re-save
+    // (a new message) brings it back on its own.
+    #[test]
+    fn soft_deleted_chat_restores_until_purged() {
+        let (_tmp, _guard) = init_test_db();
+        let listed = |id: &str| super::get_all_chats().unwrap().iter().any(|c| c.id == id);
+        let mut slim = super::SlimChatDB {
+            id: make_test_npub(1),
+            chat_type: crate::ChatType::DirectMessage,
+            participants: vec![],
+            last_read: String::new(),
+            created_at: 1000,
+            metadata: crate::chat::ChatMetadata::default(),
+            muted: false,
+            wallpaper_path: String::new(),
+            wallpaper_ts: 0,
+            wallpaper_blur: 0,
+            wallpaper_dim: 50,
+            wallpaper_url: String::new(),
+            wallpaper_uploader: String::new(),
+        };
+        super::save_slim_chat(&slim).unwrap();
+
+        assert!(super::soft_delete_chat(&slim.id, 5_000).unwrap());
+        assert!(!super::soft_delete_chat(&slim.id, 6_000).unwrap(), "already deleted");
+        assert!(!listed(&slim.id));
+        let deleted = super::list_recently_deleted().unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].purge_at, 5_000 + super::DELETED_CHAT_RETENTION_SECS);
+
+        assert!(super::restore_chat(&slim.id).unwrap().is_some());
+        assert!(listed(&slim.id));
+        assert!(super::restore_chat(&slim.id).unwrap().is_none(), "nothing to restore");
+
+        super::soft_delete_chat(&slim.id, 5_000).unwrap();
+        super::save_slim_chat(&slim).unwrap();
+        assert!(listed(&slim.id), "a re-save lifts the soft delete");
+
+        slim.id = make_test_npub(2);
+        super::save_slim_chat(&slim).unwrap();
+        super::soft_delete_chat(&slim.id, 5_000).unwrap();
+        assert_eq!(super::purge_deleted_chats(5_000).unwrap(), 0, "inside the window");
+        assert_eq!(super::purge_deleted_chats(5_001).unwrap(), 1);
+        assert!(super::list_recently_deleted().unwrap().is_empty());
+        assert!(super::restore_chat(&slim.id).unwrap().is_none(), "purged chats are gone");
+    }
 }
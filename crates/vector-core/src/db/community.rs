@@ -18,10 +18,7 @@ use rusqlite::{params, OptionalExtension};
 use crate::community::{Channel, ChannelId, ChannelKey, Community, CommunityId, Epoch, ServerRootKey};
 
 fn now_secs() -> i64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs() as i64)
-        .unwrap_or(0)
+    crate::clock::now_secs() as i64
 }
 
 fn to_32(bytes: &[u8]) -> Result<[u8; 32], String> {
@@ -143,6 +140,7 @@ pub fn save_community(community: &Community) -> Result<(), String> {
         store_epoch_key_tx(&tx, &community_id, &channel.id.to_hex(), channel.epoch.0, channel.key.as_bytes())?;
     }
     tx.commit().map_err(|e| format!("save community commit: {e}"))?;
+    forget_warm_community(&community_id);
     Ok(())
 }
 
@@ -154,7 +152,9 @@ pub fn save_community(community: &Community) -> Result<(), String> {
 /// overwrite; an epoch key is otherwise immutable).
 pub fn store_epoch_key(community_id: &str, scope_id: &str, epoch: u64, key: &[u8; 32]) -> Result<(), String> {
     let conn = super::get_write_connection_guard_static()?;
-    store_epoch_key_tx(&conn, community_id, scope_id, epoch, key)
+    store_epoch_key_tx(&conn, community_id, scope_id, epoch, key)?;
+    forget_warm_community(community_id);
+    Ok(())
 }
 
 /// Shared INSERT body so `save_community` can archive keys inside its own transaction and the
@@ -215,6 +215,7 @@ pub fn advance_channel_epoch(
         .map_err(|e| format!("advance channel head: {e}"))?;
     }
     tx.commit().map_err(|e| format!("advance channel epoch commit: {e}"))?;
+    forget_warm_community(community_id);
     Ok(advanced)
 }
 
@@ -248,6 +249,7 @@ pub fn advance_server_root_epoch(community_id: &str, new_epoch: u64, new_root: &
         .map_err(|e| format!("advance server-root head: {e}"))?;
     }
     tx.commit().map_err(|e| format!("advance server root commit: {e}"))?;
+    forget_warm_community(community_id);
     Ok(advanced)
 }
 
@@ -270,6 +272,7 @@ pub fn converge_server_root_epoch(community_id: &str, epoch: u64, new_root: &[u8
         .map_err(|e| format!("converge server-root head: {e}"))?
         > 0;
     tx.commit().map_err(|e| format!("converge server root commit: {e}"))?;
+    forget_warm_community(community_id);
     Ok(switched)
 }
 
@@ -290,6 +293,7 @@ pub fn converge_channel_epoch(community_id: &str, channel_id: &str, epoch: u64,
         .map_err(|e| format!("converge channel head: {e}"))?
         > 0;
     tx.commit().map_err(|e| format!("converge channel commit: {e}"))?;
+    forget_warm_community(community_id);
     Ok(switched)
 }
 
@@ -330,6 +334,24 @@ pub fn held_epoch_key(community_id: &str, scope_id: &str, epoch: u64) -> Result<
     blob.map(|b| dec_key(&b)).transpose()
 }
 
+/// When each held epoch key arrived: `(scope_id, epoch, received_at secs)`, oldest first. The
+/// observable rekey history of a Community; no key material is read.
+pub fn epoch_key_history(community_id: &str) -> Result<Vec<(String, u64, u64)>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn
+        .prepare("SELECT scope_id, epoch, created_at FROM community_epoch_keys WHERE community_id = ?1")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![community_id], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)? as u64, r.get::<_, i64>(2)? as u64))
+        })
+        .map_err(|e| e.to_string())?;
+    let mut out = rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?;
+    // Sorted in Rust for the same u64-as-i64 reason as `held_epoch_keys`
+    out.sort_by_key(|(_, epoch, at)| (*at, *epoch));
+    Ok(out)
+}
+
 /// Local first-save time of a community (≈ when this account joined or created it), in ms.
 /// `created_at` is set on the first save and preserved across metadata re-saves, so it tracks
 /// the join moment. Used to sort a not-yet-active community by join time. `None` if unknown.
@@ -346,8 +368,53 @@ pub fn community_created_at_ms(id: &CommunityId) -> Option<u64> {
     .map(|secs| (secs.max(0) as u64) * 1000)
 }
 
-/// Load a Community and its channels by id. Returns `None` if not stored locally.
+// ── Warm community cache ─────────────────────────────────────────────────────
+// Every send resolves its channel through `load_community`: a row read + channel scan +
+// one epoch-key query per channel, each key and text field vault-decrypted, plus the
+// roster parse. None of it changes between sends. Keep the decoded Community warm,
+// keyed by community_id — evicted by every write to the rows it's built from (head +
+// archive writes, banlist/roles/dissolve, delete, v2 save) and cleared wholesale on
+// account swap via `clear_id_caches`. A load that raced a write must not re-warm the
+// pre-write copy: `WARM_GEN` is bumped on every eviction and a fill only lands if it
+// hasn't moved since the load began.
+static WARM_COMMUNITIES: std::sync::LazyLock<
+    std::sync::RwLock<std::collections::HashMap<String, std::sync::Arc<Community>>>,
+> = std::sync::LazyLock::new(|| std::sync::RwLock::new(std::collections::HashMap::new()));
+static WARM_GEN: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Drop every warm Community (account swap).
+pub fn clear_warm_communities() {
+    let mut warm = WARM_COMMUNITIES.write().unwrap();
+    WARM_GEN.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    warm.clear();
+}
+
+/// Evict one Community after a write to its rows; the next load re-reads the DB.
+fn forget_warm_community(community_id: &str) {
+    let mut warm = WARM_COMMUNITIES.write().unwrap();
+    WARM_GEN.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    warm.remove(community_id);
+}
+
+/// Load a Community and its channels by id. Returns `None` if not stored locally. Served from
+/// the warm cache when nothing has written the Community since it was last read.
 pub fn load_community(id: &CommunityId) -> Result<Option<Community>, String> {
+    let id_hex = id.to_hex();
+    if let Some(c) = WARM_COMMUNITIES.read().unwrap().get(&id_hex) {
+        return Ok(Some(Community::clone(c)));
+    }
+    let gen = WARM_GEN.load(std::sync::atomic::Ordering::SeqCst);
+    let loaded = load_community_uncached(id)?;
+    if let Some(ref c) = loaded {
+        let mut warm = WARM_COMMUNITIES.write().unwrap();
+        if WARM_GEN.load(std::sync::atomic::Ordering::SeqCst) == gen {
+            warm.insert(id_hex, std::sync::Arc::new(c.clone()));
+        }
+    }
+    Ok(loaded)
+}
+
+fn load_community_uncached(id: &CommunityId) -> Result<Option<Community>, String> {
     let conn = super::get_db_connection_guard_static()?;
     let id_hex = id.to_hex();
 
@@ -640,6 +707,110 @@ pub fn community_exists(id: &CommunityId) -> Result<bool, String> {
     Ok(found.is_some())
 }
 
+/// One invite we sent, as tracked in `community_invite_deliveries`. Times are unix seconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InviteDeliveryRow {
+    pub community_id: String,
+    /// Invitee pubkey, hex.
+    pub invitee: String,
+    pub attempts: u32,
+    pub first_sent_at: i64,
+    pub last_attempt_at: i64,
+    /// When a relay first accepted the invite (`None` = no relay has yet).
+    pub delivered_at: Option<i64>,
+    /// When the invitee first showed up as a member.
+    pub joined_at: Option<i64>,
+}
+
+/// Record one publish of an invite to `invitee_hex`: bumps the attempt count, and stamps
+/// `delivered_at` the first time a relay accepts it.
+pub fn record_invite_attempt(community_id: &str, invitee_hex: &str, delivered: bool) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    let now = now_secs();
+    let delivered_at = delivered.then_some(now);
+    conn.execute(
+        "INSERT INTO community_invite_deliveries
+            (community_id, invitee, attempts, first_sent_at, last_attempt_at, delivered_at)
+         VALUES (?1, ?2, 1, ?3, ?3, ?4)
+         ON CONFLICT(community_id, invitee) DO UPDATE SET
+            attempts = attempts + 1,
+            last_attempt_at = excluded.last_attempt_at,
+            delivered_at = COALESCE(delivered_at, excluded.delivered_at)",
+        params![community_id, invitee_hex, now, delivered_at],
+    ).map_err(|e| format!("record invite attempt: {e}"))?;
+    Ok(())
+}
+
+/// Stamp an invitee as joined (first time only).
+pub fn mark_invite_joined(community_id: &str, invitee_hex: &str) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute(
+        "UPDATE community_invite_deliveries SET joined_at = ?3
+         WHERE community_id = ?1 AND invitee = ?2 AND joined_at IS NULL",
+        params![community_id, invitee_hex, now_secs()],
+    ).map_err(|e| format!("mark invite joined: {e}"))?;
+    Ok(())
+}
+
+/// Every invite we sent for a community, oldest first.
+pub fn list_invite_deliveries(community_id: &str) -> Result<Vec<InviteDeliveryRow>, String> {
+    query_invite_deliveries(
+        "SELECT community_id, invitee, attempts, first_sent_at, last_attempt_at, delivered_at, joined_at
+         FROM community_invite_deliveries WHERE community_id = ?1 ORDER BY first_sent_at",
+        params![community_id],
+    )
+}
+
+/// Every invite we sent, across all communities, newest first.
+pub fn list_all_invite_deliveries() -> Result<Vec<InviteDeliveryRow>, String> {
+    query_invite_deliveries(
+        "SELECT community_id, invitee, attempts, first_sent_at, last_attempt_at, delivered_at, joined_at
+         FROM community_invite_deliveries ORDER BY first_sent_at DESC",
+        [],
+    )
+}
+
+/// Stop tracking an invite. Returns whether one was tracked.
+pub fn delete_invite_delivery(community_id: &str, invitee_hex: &str) -> Result<bool, String> {
+    let conn = super::get_write_connection_guard_static()?;
+    let changed = conn.execute(
+        "DELETE FROM community_invite_deliveries WHERE community_id = ?1 AND invitee = ?2",
+        params![community_id, invitee_hex],
+    ).map_err(|e| format!("delete invite delivery: {e}"))?;
+    Ok(changed > 0)
+}
+
+/// Invites no relay has accepted yet and that have had fewer than `max_attempts` tries, across
+/// every community.
+pub fn undelivered_invites(max_attempts: u32) -> Result<Vec<InviteDeliveryRow>, String> {
+    query_invite_deliveries(
+        "SELECT community_id, invitee, attempts, first_sent_at, last_attempt_at, delivered_at, joined_at
+         FROM community_invite_deliveries
+         WHERE delivered_at IS NULL AND joined_at IS NULL AND attempts < ?1
+         ORDER BY last_attempt_at",
+        params![max_attempts],
+    )
+}
+
+fn query_invite_deliveries(sql: &str, args: impl rusqlite::Params) -> Result<Vec<InviteDeliveryRow>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(sql).map_err(|e| format!("prepare invite deliveries: {e}"))?;
+    let rows = stmt
+        .query_map(args, |r| {
+            Ok(InviteDeliveryRow {
+                community_id: r.get(0)?,
+                invitee: r.get(1)?,
+                attempts: r.get::<_, i64>(2)?.max(0) as u32,
+                first_sent_at: r.get(3)?,
+                last_attempt_at: r.get(4)?,
+                delivered_at: r.get(5)?,
+                joined_at: r.get(6)?,
+            })
+        })
+        .map_err(|e| format!("query invite deliveries: {e}"))?;
+    Ok(rows.flatten().collect())
+}
+
 /// A parked invite awaiting the user's accept/decline decision.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct PendingCommunityInvite {
@@ -982,6 +1153,7 @@ fn delete_community_inner(community_id: &str, retain_keys: bool) -> Result<(), S
         Some("DELETE FROM community_public_invites WHERE community_id = ?1"),
         Some("DELETE FROM community_invite_link_sets WHERE community_id = ?1"),
         Some("DELETE FROM pending_community_invites WHERE community_id = ?1"),
+        Some("DELETE FROM community_invite_deliveries WHERE community_id = ?1"),
         // Per-entity edition heads (keyless model) — else stale refuse-downgrade floors + self_hash
         // anchors survive a leave/re-join and reject a legitimately reset chain.
         Some("DELETE FROM community_edition_heads WHERE community_id = ?1"),
@@ -995,6 +1167,7 @@ fn delete_community_inner(community_id: &str, retain_keys: bool) -> Result<(), S
     tx.commit().map_err(|e| format!("delete community commit: {e}"))?;
     BANLIST_CACHE.write().unwrap().remove(community_id);
     forget_community_channels(community_id);
+    forget_warm_community(community_id);
     // `community_message_keys` is INTENTIONALLY left intact: those are our OWN ephemeral signing keys for
     // NIP-09-deleting our own messages. The right to erase our own content from relays outlives membership
     // — even after a ban or leave we must keep the ability to purge what we sent — so they survive a
@@ -1276,6 +1449,7 @@ pub fn set_community_banlist(community_id: &str, banned_hex: &[String], at: i64)
         .write()
         .unwrap()
         .insert(community_id.to_string(), std::sync::Arc::new(banlist_set_from_hexes(banned_hex)));
+    forget_warm_community(community_id);
     Ok(())
 }
 
@@ -1310,6 +1484,7 @@ pub fn set_community_roles(
         params![json, at, community_id],
     )
     .map_err(|e| format!("set roles: {e}"))?;
+    forget_warm_community(community_id);
     Ok(())
 }
 
@@ -1753,6 +1928,7 @@ pub fn set_community_dissolved(community_id: &str) -> Result<bool, String> {
             params![community_id],
         )
         .map_err(|e| format!("set dissolved: {e}"))?;
+    forget_warm_community(community_id);
     Ok(changed > 0)
 }
 
@@ -2008,6 +2184,7 @@ pub fn save_community_v2(c: &crate::community::v2::community::CommunityV2) -> Re
     // The save may have pruned channels (DELETE ... NOT IN the new set); evict so a
     // pruned channel stops resolving to this community.
     forget_community_channels(&id_hex);
+    forget_warm_community(&id_hex);
     Ok(())
 }
 
@@ -2340,6 +2517,28 @@ mod tests {
         assert_eq!(loaded.server_root_key.as_bytes(), &[0x42u8; 32]);
     }
 
+    #[test]
+    fn warm_community_is_evicted_by_epoch_writes() {
+        // A rekey landing between two sends must be seen by the second: the warm copy can't
+        // outlive a head advance.
+        let (_tmp, _guard) = init_test_db();
+        let c = Community::create("HQ", "general", vec![]);
+        save_community(&c).unwrap();
+        let (cid, chan) = (c.id.to_hex(), c.channels[0].id.to_hex());
+        assert_eq!(load_community(&c.id).unwrap().unwrap().channels[0].epoch, Epoch(0));
+        assert!(WARM_COMMUNITIES.read().unwrap().contains_key(&cid), "first load warms");
+
+        assert!(advance_channel_epoch(&cid, &chan, 1, &[0x11u8; 32]).unwrap());
+        let loaded = load_community(&c.id).unwrap().unwrap();
+        assert_eq!(loaded.channels[0].epoch, Epoch(1));
+        assert_eq!(loaded.channels[0].key.as_bytes(), &[0x11u8; 32]);
+
+        set_community_dissolved(&cid).unwrap();
+        assert!(load_community(&c.id).unwrap().unwrap().dissolved);
+        delete_community(&cid).unwrap();
+        assert!(load_community(&c.id).unwrap().is_none(), "a deleted Community doesn't linger warm");
+    }
+
     #[test]
     fn epoch_key_archive_retains_every_epoch() {
         // a member who lived through a rotation must keep OLD epoch keys. Storing a new
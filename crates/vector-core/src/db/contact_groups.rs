@@ -0,0 +1,184 @@
+//! Contact groups — user-defined labels ("Family", "Work") for organizing DM contacts.
+//!
+//! Purely local and organizational: nothing is published, and a contact group has nothing to do
+//! with group chats. A contact can sit in any number of groups; names are unique case-insensitively.
+
+use rusqlite::{params, OptionalExtension};
+use crate::clock::now_secs;
+
+/// Longest accepted group name, in characters.
+pub const MAX_GROUP_NAME_LEN: usize = 40;
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct ContactGroup {
+    pub id: i64,
+    pub name: String,
+    /// Member npubs, sorted.
+    pub members: Vec<String>,
+}
+
+fn clean_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Group name can't be empty".into());
+    }
+    if name.chars().count() > MAX_GROUP_NAME_LEN {
+        return Err(format!("Group name is limited to {} characters", MAX_GROUP_NAME_LEN));
+    }
+    Ok(name.to_string())
+}
+
+fn map_unique(e: rusqlite::Error) -> String {
+    match e {
+        rusqlite::Error::SqliteFailure(err, _) if err.code == rusqlite::ErrorCode::ConstraintViolation => {
+            "A group with that name already exists".to_string()
+        }
+        e => format!("contact group: {e}"),
+    }
+}
+
+/// Create a group and return it (empty).
+pub fn create_group(name: &str) -> Result<ContactGroup, String> {
+    let name = clean_name(name)?;
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute(
+        "INSERT INTO contact_groups (name, created_at) VALUES (?1, ?2)",
+        params![name, now_secs() as i64],
+    ).map_err(map_unique)?;
+    Ok(ContactGroup { id: conn.last_insert_rowid(), name, members: Vec::new() })
+}
+
+pub fn rename_group(id: i64, name: &str) -> Result<(), String> {
+    let name = clean_name(name)?;
+    let conn = super::get_write_connection_guard_static()?;
+    let changed = conn.execute("UPDATE contact_groups SET name = ?1 WHERE id = ?2", params![name, id])
+        .map_err(map_unique)?;
+    if changed == 0 {
+        return Err("Group not found".into());
+    }
+    Ok(())
+}
+
+/// Delete a group and its memberships (the contacts themselves are untouched).
+pub fn delete_group(id: i64) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    let tx = conn.unchecked_transaction().map_err(|e| format!("delete contact group tx: {e}"))?;
+    tx.execute("DELETE FROM contact_group_members WHERE group_id = ?1", params![id])
+        .map_err(|e| format!("delete contact group: {e}"))?;
+    tx.execute("DELETE FROM contact_groups WHERE id = ?1", params![id])
+        .map_err(|e| format!("delete contact group: {e}"))?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Add (`member = true`) or remove a contact from a group. Idempotent.
+pub fn set_member(group_id: i64, npub: &str, member: bool) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    if member {
+        let exists: Option<i64> = conn
+            .query_row("SELECT id FROM contact_groups WHERE id = ?1", params![group_id], |r| r.get(0))
+            .optional()
+            .map_err(|e| e.to_string())?;
+        if exists.is_none() {
+            return Err("Group not found".into());
+        }
+        conn.execute(
+            "INSERT OR IGNORE INTO contact_group_members (group_id, npub) VALUES (?1, ?2)",
+            params![group_id, npub],
+        )
+    } else {
+        conn.execute(
+            "DELETE FROM contact_group_members WHERE group_id = ?1 AND npub = ?2",
+            params![group_id, npub],
+        )
+    }
+    .map_err(|e| format!("contact group membership: {e}"))?;
+    Ok(())
+}
+
+/// Every group with its members, ordered by name.
+pub fn list_groups() -> Result<Vec<ContactGroup>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT g.id, g.name, m.npub FROM contact_groups g
+             LEFT JOIN contact_group_members m ON m.group_id = g.id
+             ORDER BY g.name COLLATE NOCASE, g.id, m.npub",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?, r.get::<_, Option<String>>(2)?)))
+        .map_err(|e| e.to_string())?;
+
+    let mut groups: Vec<ContactGroup> = Vec::new();
+    for (id, name, npub) in rows.filter_map(|r| r.ok()) {
+        if groups.last().map(|g| g.id) != Some(id) {
+            groups.push(ContactGroup { id, name, members: Vec::new() });
+        }
+        if let (Some(npub), Some(group)) = (npub, groups.last_mut()) {
+            group.members.push(npub);
+        }
+    }
+    Ok(groups)
+}
+
+/// Member npubs of one group (empty for an unknown group).
+pub fn group_members(group_id: i64) -> Result<Vec<String>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn
+        .prepare("SELECT npub FROM contact_group_members WHERE group_id = ?1 ORDER BY npub")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![group_id], |r| r.get::<_, String>(0))
+        .map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_test_db() -> (tempfile::TempDir, std::sync::MutexGuard<'static, ()>) {
+        let guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        crate::db::clear_id_caches();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+        (tmp, guard)
+    }
+
+    #[test]
+    fn groups_track_membership_and_unique_names() {
+        let (_tmp, _guard) = init_test_db();
+
+        let work = create_group(" Work ").unwrap();
+        assert_eq!(work.name, "Work");
+        let family = create_group("Family").unwrap();
+        assert!(create_group("work").is_err(), "names are unique case-insensitively");
+        assert!(create_group("   ").is_err());
+
+        set_member(work.id, "npub1alice", true).unwrap();
+        set_member(work.id, "npub1alice", true).unwrap();
+        set_member(work.id, "npub1bob", true).unwrap();
+        set_member(family.id, "npub1alice", true).unwrap();
+        assert!(set_member(9999, "npub1alice", true).is_err());
+        assert_eq!(group_members(work.id).unwrap(), vec!["npub1alice", "npub1bob"]);
+
+        let groups = list_groups().unwrap();
+        assert_eq!(groups.iter().map(|g| g.name.as_str()).collect::<Vec<_>>(), vec!["Family", "Work"]);
+        assert_eq!(groups[0].members, vec!["npub1alice"]);
+
+        set_member(work.id, "npub1bob", false).unwrap();
+        rename_group(work.id, "Office").unwrap();
+        assert!(rename_group(work.id, "family").is_err());
+
+        delete_group(family.id).unwrap();
+        let groups = list_groups().unwrap();
+        assert_eq!(groups, vec![ContactGroup { id: work.id, name: "Office".into(), members: vec!["npub1alice".into()] }]);
+        assert!(group_members(family.id).unwrap().is_empty(), "memberships go with the group");
+    }
+}
@@ -0,0 +1,50 @@
+//! NIP-02 contact list storage (see `crate::contacts`). Removed contacts keep their row as a
+//! tombstone so the removal's time survives for merging.
+
+use rusqlite::params;
+
+/// One npub's state on the contact list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContactRow {
+    pub npub: String,
+    /// Relay hint for the contact, or empty.
+    pub relay: String,
+    /// Local name for the contact ("petname"), or empty.
+    pub petname: String,
+    pub removed: bool,
+    /// Secs of the last local edit, or of the kind 3 this state was adopted from.
+    pub updated_at: u64,
+}
+
+/// Every row, tombstones included, by npub.
+pub fn all_rows() -> Result<Vec<ContactRow>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare("SELECT npub, relay, petname, removed, updated_at FROM contacts ORDER BY npub")
+        .map_err(|e| format!("prepare contacts: {e}"))?;
+    let rows = stmt.query_map([], |r| Ok(ContactRow {
+        npub: r.get(0)?,
+        relay: r.get(1)?,
+        petname: r.get(2)?,
+        removed: r.get::<_, i64>(3)? != 0,
+        updated_at: r.get::<_, i64>(4)?.max(0) as u64,
+    })).map_err(|e| format!("query contacts: {e}"))?;
+    Ok(rows.flatten().collect())
+}
+
+/// Insert or overwrite `rows` in one transaction.
+pub fn upsert_rows(rows: &[ContactRow]) -> Result<(), String> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let mut conn = super::get_write_connection_guard_static()?;
+    let tx = conn.transaction().map_err(|e| format!("contacts tx: {e}"))?;
+    for row in rows {
+        tx.execute(
+            "INSERT INTO contacts (npub, relay, petname, removed, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(npub) DO UPDATE SET relay = excluded.relay, petname = excluded.petname,
+                 removed = excluded.removed, updated_at = excluded.updated_at",
+            params![row.npub, row.relay, row.petname, row.removed as i64, row.updated_at as i64],
+        ).map_err(|e| format!("Failed to save contact: {}", e))?;
+    }
+    tx.commit().map_err(|e| format!("contacts commit: {e}"))
+}
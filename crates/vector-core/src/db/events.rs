@@ -88,6 +88,20 @@ pub fn event_exists(event_id: &str) -> Result<bool, String> {
     event_exists_on(&conn, event_id)
 }
 
+/// When `event_id` was created, if it's stored in `chat_identifier`.
+pub fn event_created_at_in_chat(chat_identifier: &str, event_id: &str) -> Result<Option<u64>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    conn.query_row(
+        "SELECT e.created_at FROM events e JOIN chats c ON e.chat_id = c.id \
+         WHERE e.id = ?1 AND c.chat_identifier = ?2",
+        rusqlite::params![event_id, chat_identifier],
+        |row| row.get::<_, i64>(0),
+    )
+    .optional()
+    .map(|at| at.map(|at| at.max(0) as u64))
+    .map_err(|e| format!("query event created_at: {e}"))
+}
+
 /// `event_exists` against a caller-held connection or transaction — an in-transaction check
 /// sees the batch's own uncommitted rows, which the pooled read connection cannot.
 fn event_exists_on(conn: &rusqlite::Connection, event_id: &str) -> Result<bool, String> {
@@ -184,6 +198,10 @@ pub async fn save_message(chat_id: &str, message: &Message) -> Result<(), String
         let tx = conn.unchecked_transaction().map_err(|e| format!("save_message tx: {e}"))?;
         insert_event_row(&tx, &event, &content, &tags_json)?;
         super::attachments::insert_attachment_rows(&tx, &message.id, &message.attachments)?;
+        let my_npub = super::get_current_account().ok();
+        super::mentions::index_message(
+            &tx, &message.id, chat_int_id, event.created_at, &message.content, message.mine, my_npub.as_deref(),
+        )?;
         tx.commit().map_err(|e| format!("save_message commit: {e}"))?;
     }
 
@@ -255,6 +273,7 @@ async fn prepare_batch_rows<'a>(
 fn write_batch_rows(rows: &[BatchRow<'_>]) -> Result<usize, String> {
     let conn = super::get_write_connection_guard_static()?;
     let tx = conn.unchecked_transaction().map_err(|e| format!("batch tx: {e}"))?;
+    let my_npub = super::get_current_account().ok();
     let mut saved = 0usize;
     for row in rows {
         // Per-row savepoint = save_message's per-message atomicity inside the batch: a
@@ -263,7 +282,11 @@ fn write_batch_rows(rows: &[BatchRow<'_>]) -> Result<usize, String> {
         // (a re-saved old file message must keep its download record on a transient error).
         tx.execute_batch("SAVEPOINT batch_row").map_err(|e| format!("batch savepoint: {e}"))?;
         let row_written = insert_event_row(&tx, &row.event, &row.content, &row.tags_json)
-            .and_then(|_| super::attachments::insert_attachment_rows(&tx, &row.message.id, &row.message.attachments));
+            .and_then(|_| super::attachments::insert_attachment_rows(&tx, &row.message.id, &row.message.attachments))
+            .and_then(|_| super::mentions::index_message(
+                &tx, &row.message.id, row.event.chat_id, row.event.created_at, &row.message.content,
+                row.message.mine, my_npub.as_deref(),
+            ));
         if let Err(e) = row_written {
             crate::log_warn!("[DB] batch skip {}: {}", &row.message.id[..8.min(row.message.id.len())], e);
             let _ = tx.execute_batch("ROLLBACK TO batch_row; RELEASE batch_row");
@@ -395,6 +418,11 @@ fn message_to_stored_event(message: &Message, chat_id: i64, user_id: Option<i64>
         tags.push(vec!["expiration".to_string(), exp.to_string()]);
     }
 
+    // Shared location, including how far a live share has got
+    if let Some(location) = &message.location {
+        tags.push(location.stored_tag());
+    }
+
     let preview_metadata = message.preview_metadata.as_ref()
         .and_then(|m| serde_json::to_string(m).ok());
 
@@ -671,8 +699,41 @@ pub fn get_chat_message_count(chat_id: i64) -> Result<usize, String> {
     Ok(count as usize)
 }
 
+/// Messages per chat per UTC day within `[from, to]` (secs), for the activity report: rows of
+/// (chat identifier, chat type, day as `YYYY-MM-DD`, sent, received), oldest day first. Counts the
+/// same kinds as [`get_chat_message_count`]; never reads content.
+pub fn message_activity(from: u64, to: u64) -> Result<Vec<(String, i32, String, u64, u64)>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT c.chat_identifier, c.chat_type, date(e.created_at, 'unixepoch') AS day,
+                SUM(e.mine), COUNT(*) - SUM(e.mine)
+         FROM events e JOIN chats c ON c.id = e.chat_id
+         WHERE e.kind IN ({}, {}, {}) AND e.created_at BETWEEN ?1 AND ?2
+         GROUP BY c.id, day ORDER BY day ASC, c.chat_identifier ASC",
+        event_kind::CHAT_MESSAGE, event_kind::PRIVATE_DIRECT_MESSAGE, event_kind::FILE_ATTACHMENT
+    )).map_err(|e| format!("prepare message_activity: {e}"))?;
+    let rows = stmt.query_map(rusqlite::params![from as i64, to.min(i64::MAX as u64) as i64], |r| Ok((
+        r.get::<_, String>(0)?,
+        r.get::<_, i32>(1)?,
+        r.get::<_, String>(2)?,
+        r.get::<_, i64>(3)?.max(0) as u64,
+        r.get::<_, i64>(4)?.max(0) as u64,
+    ))).map_err(|e| format!("query message_activity: {e}"))?;
+    Ok(rows.flatten().collect())
+}
+
 /// Get PIVX payment events for a chat.
 pub fn get_pivx_payments_for_chat(conversation_id: &str) -> Result<Vec<StoredEvent>, String> {
+    get_application_events_for_chat(conversation_id, "pivx-payment")
+}
+
+/// Get ecash token events for a chat.
+pub fn get_ecash_tokens_for_chat(conversation_id: &str) -> Result<Vec<StoredEvent>, String> {
+    get_application_events_for_chat(conversation_id, crate::cashu::D_TAG)
+}
+
+/// Application-specific events in a chat whose `d` tag is `d_tag`.
+fn get_application_events_for_chat(conversation_id: &str, d_tag: &str) -> Result<Vec<StoredEvent>, String> {
     let conn = super::get_db_connection_guard_static()?;
     let chat_id: i64 = conn.query_row(
         "SELECT id FROM chats WHERE chat_identifier = ?1",
@@ -702,14 +763,14 @@ pub fn get_pivx_payments_for_chat(conversation_id: &str) -> Result<Vec<StoredEve
         }
     ).map_err(|e| format!("Failed to query: {}", e))?;
 
-    let mut payments = Vec::new();
+    let mut events = Vec::new();
     for row in rows {
         let event = row.map_err(|e| format!("Failed to read event: {}", e))?;
-        if event.tags.iter().any(|t| t.len() >= 2 && t[0] == "d" && t[1] == "pivx-payment") {
-            payments.push(event);
+        if event.tags.iter().any(|t| t.len() >= 2 && t[0] == "d" && t[1] == d_tag) {
+            events.push(event);
         }
     }
-    Ok(payments)
+    Ok(events)
 }
 
 /// Get system events (member joined/left) for a chat.
@@ -857,7 +918,7 @@ pub async fn get_events(
     let mut decrypted = Vec::with_capacity(events.len());
     for mut event in events {
         if event.kind == event_kind::CHAT_MESSAGE || event.kind == event_kind::PRIVATE_DIRECT_MESSAGE {
-            event.content = crate::crypto::maybe_decrypt(event.content).await
+            event.content = crate::crypto::content_cache::decrypt(event.content).await
                 .unwrap_or_else(|_| "[Decryption failed]".to_string());
         }
         decrypted.push(event);
@@ -900,6 +961,122 @@ pub async fn get_related_events(
     Ok(events)
 }
 
+// ============================================================================
+// Reaction details — "who reacted with what"
+// ============================================================================
+
+/// One person's reaction to a message, resolved against the local profile cache. `name`/`avatar`
+/// are empty for strangers we hold no profile for; the frontend falls back to the npub.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct ReactorEntry {
+    pub author_id: String,
+    pub emoji: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji_url: Option<String>,
+    /// Milliseconds, matching `Message::at`.
+    pub at: u64,
+    pub mine: bool,
+    pub name: String,
+    pub avatar: String,
+}
+
+/// Per-emoji tally for the sheet's tab strip, most popular first.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct ReactionTally {
+    pub emoji: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji_url: Option<String>,
+    pub count: u32,
+    pub reacted_by_me: bool,
+}
+
+#[derive(serde::Serialize, Clone, Debug, Default, PartialEq)]
+pub struct MessageReactions {
+    pub tallies: Vec<ReactionTally>,
+    /// Earliest first, one entry per (author, emoji).
+    pub reactors: Vec<ReactorEntry>,
+}
+
+/// Every reaction on `message_id`, one entry per (author, emoji). The same reaction can land more
+/// than once (relay re-delivery, a re-sent wrapper) under different event ids — the earliest copy
+/// wins. A reactor may still hold several DIFFERENT emojis, each listed.
+pub async fn get_message_reactions(message_id: &str) -> Result<MessageReactions, String> {
+    use std::collections::{HashMap, HashSet};
+
+    let me = super::get_current_account().ok();
+    let events = get_related_events(&[message_id.to_string()]).await?;
+
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    let mut reactors: Vec<ReactorEntry> = Vec::new();
+    for event in events.into_iter().filter(|e| e.kind == event_kind::REACTION) {
+        let author_id = normalize_reaction_author(event.npub.clone().unwrap_or_default());
+        if author_id.is_empty() || !seen.insert((author_id.clone(), event.content.clone())) {
+            continue;
+        }
+        let mine = event.mine || me.as_deref() == Some(author_id.as_str());
+        reactors.push(ReactorEntry {
+            emoji_url: extract_reaction_emoji_url(&event.tags, &event.content),
+            emoji: event.content,
+            at: event.created_at * 1000,
+            mine,
+            author_id,
+            name: String::new(),
+            avatar: String::new(),
+        });
+    }
+    if reactors.is_empty() {
+        return Ok(MessageReactions::default());
+    }
+
+    // Resolve names/avatars in one query over the distinct reactors.
+    let npubs: Vec<String> = reactors.iter().map(|r| r.author_id.clone())
+        .collect::<HashSet<_>>().into_iter().collect();
+    let profiles: HashMap<String, (String, String)> = {
+        let conn = super::get_db_connection_guard_static()?;
+        let placeholders = npubs.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT npub, nickname, display_name, name, avatar_cached, avatar FROM profiles WHERE npub IN ({})",
+            placeholders
+        );
+        let mut stmt = conn.prepare(&sql).map_err(|e| format!("prepare reactor profiles: {}", e))?;
+        let params: Vec<&dyn rusqlite::ToSql> = npubs.iter().map(|s| s as &dyn rusqlite::ToSql).collect();
+        let rows = stmt.query_map(params.as_slice(), |r| {
+            let pick = |v: [String; 3]| v.into_iter().find(|s| !s.is_empty()).unwrap_or_default();
+            let name = pick([r.get(1)?, r.get(2)?, r.get(3)?]);
+            let cached: String = r.get(4)?;
+            let avatar = if !cached.is_empty() && std::path::Path::new(&cached).exists() { cached } else { r.get(5)? };
+            Ok((r.get::<_, String>(0)?, (name, avatar)))
+        }).map_err(|e| format!("query reactor profiles: {}", e))?;
+        rows.flatten().collect()
+    };
+    for r in &mut reactors {
+        if let Some((name, avatar)) = profiles.get(&r.author_id) {
+            r.name = name.clone();
+            r.avatar = avatar.clone();
+        }
+    }
+
+    let mut tallies: Vec<ReactionTally> = Vec::new();
+    for r in &reactors {
+        match tallies.iter_mut().find(|t| t.emoji == r.emoji) {
+            Some(t) => {
+                t.count += 1;
+                t.reacted_by_me |= r.mine;
+                if t.emoji_url.is_none() {
+                    t.emoji_url = r.emoji_url.clone();
+                }
+            }
+            None => tallies.push(ReactionTally {
+                emoji: r.emoji.clone(), emoji_url: r.emoji_url.clone(), count: 1, reacted_by_me: r.mine,
+            }),
+        }
+    }
+    // Stable sort: equal counts keep first-reacted order.
+    tallies.sort_by(|a, b| b.count.cmp(&a.count));
+
+    Ok(MessageReactions { tallies, reactors })
+}
+
 /// Context data for a replied-to message.
 pub struct ReplyContext {
     pub content: String,
@@ -986,7 +1163,7 @@ pub async fn get_reply_contexts(
         let decrypted_content = if kind == event_kind::CHAT_MESSAGE as i32
             || kind == event_kind::PRIVATE_DIRECT_MESSAGE as i32
         {
-            crate::crypto::maybe_decrypt(content_to_decrypt).await
+            crate::crypto::content_cache::decrypt(content_to_decrypt).await
                 .unwrap_or_else(|_| "[Decryption failed]".to_string())
         } else {
             String::new()
@@ -1162,7 +1339,7 @@ async fn compose_message_views(message_events: Vec<StoredEvent>) -> Result<Vec<M
                     });
                 }
                 k if k == event_kind::MESSAGE_EDIT => {
-                    let decrypted = crate::crypto::maybe_decrypt(event.content.clone()).await
+                    let decrypted = crate::crypto::content_cache::decrypt(event.content.clone()).await
                         .unwrap_or_else(|_| event.content.clone());
                     let edit_emoji = crate::types::EmojiTag::extract_from_stored(&event.tags);
                     edits_by_msg.entry(ref_id.clone()).or_default().push((event.created_at * 1000, decrypted, edit_emoji));
@@ -1236,17 +1413,26 @@ async fn compose_message_views(message_events: Vec<StoredEvent>) -> Result<Vec<M
 
         let addressed_bots = extract_bot_tags(&event.tags);
         let expiration = extract_expiration_tag(&event.tags);
+        let location = crate::location::from_stored_tags(&event.tags);
+        let code_languages = crate::code_blocks::code_languages(&content);
+        let nostr_previews = crate::nostr_uri::cached_previews(&content);
+        let zap_msat = crate::zaps::message_total(&event.id);
+        let starred = crate::starred::is_starred(&event.id);
         messages.push(Message {
             expiration,
             id: event.id, content, replied_to,
             replied_to_content: None, replied_to_npub: None, replied_to_has_attachment: None,
             replied_to_attachment_extension: None,
-            preview_metadata, attachments, reactions, at,
+            preview_metadata, location, attachments, reactions, at,
             pending: event.pending, failed: event.failed, mine: event.mine,
             npub: event.npub, wrapper_event_id: event.wrapper_event_id,
             edited, edit_history,
             emoji_tags,
             addressed_bots,
+            code_languages,
+            nostr_previews,
+            zap_msat,
+            starred,
         });
     }
 
@@ -1356,7 +1542,7 @@ pub async fn get_messages_around(
     let mut decrypted = Vec::with_capacity(message_events.len());
     for mut event in message_events {
         if event.kind == event_kind::CHAT_MESSAGE || event.kind == event_kind::PRIVATE_DIRECT_MESSAGE {
-            event.content = crate::crypto::maybe_decrypt(event.content).await
+            event.content = crate::crypto::content_cache::decrypt(event.content).await
                 .unwrap_or_else(|_| "[Decryption failed]".to_string());
         }
         decrypted.push(event);
@@ -1365,6 +1551,319 @@ pub async fn get_messages_around(
     compose_message_views(decrypted).await
 }
 
+/// Keyset page of history: up to `limit` messages strictly older than `before_id`, by the same
+/// (created_at, received_at, rowid) key as [`get_messages_around`], so paging walks through
+/// same-second bursts and isn't thrown off by messages arriving at the tail meanwhile (unlike the
+/// offset pager). Returns ASC (oldest first), composed like the other views; empty at the start of
+/// history. Errs if `before_id` isn't in the DB.
+pub async fn get_messages_before(chat_id: i64, before_id: &str, limit: usize) -> Result<Vec<Message>, String> {
+    let message_kinds = [event_kind::CHAT_MESSAGE, event_kind::PRIVATE_DIRECT_MESSAGE, event_kind::FILE_ATTACHMENT];
+
+    let message_events: Vec<StoredEvent> = {
+        let conn = super::get_db_connection_guard_static()?;
+        let (cursor_at, cursor_rt, cursor_rowid): (i64, i64, i64) = conn.query_row(
+            "SELECT created_at, received_at, rowid FROM events WHERE id = ?1",
+            rusqlite::params![before_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).map_err(|e| format!("Cursor message not found: {}", e))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, chat_id, user_id, content, tags, reference_id, \
+             created_at, received_at, mine, pending, failed, wrapper_event_id, npub, preview_metadata \
+             FROM events WHERE chat_id = ?1 AND kind IN (?2, ?3, ?4) \
+             AND (created_at < ?5 OR (created_at = ?5 AND (received_at < ?6 \
+                  OR (received_at = ?6 AND rowid < ?7)))) \
+             ORDER BY created_at DESC, received_at DESC, rowid DESC LIMIT ?8"
+        ).map_err(|e| format!("Failed to prepare older page query: {}", e))?;
+        let rows = stmt.query_map(
+            rusqlite::params![
+                chat_id,
+                message_kinds[0] as i32, message_kinds[1] as i32, message_kinds[2] as i32,
+                cursor_at, cursor_rt, cursor_rowid, limit as i64
+            ],
+            parse_event_row,
+        ).map_err(|e| format!("Failed to query older page: {}", e))?;
+        let mut older: Vec<StoredEvent> = rows.filter_map(|r| r.ok()).collect();
+        older.reverse(); // DESC -> ASC
+        older
+    };
+
+    // Decrypt message content (mirror get_events).
+    let mut decrypted = Vec::with_capacity(message_events.len());
+    for mut event in message_events {
+        if event.kind == event_kind::CHAT_MESSAGE || event.kind == event_kind::PRIVATE_DIRECT_MESSAGE {
+            event.content = crate::crypto::content_cache::decrypt(event.content).await
+                .unwrap_or_else(|_| "[Decryption failed]".to_string());
+        }
+        decrypted.push(event);
+    }
+
+    compose_message_views(decrypted).await
+}
+
+/// Starred messages across every chat, most recently starred first, each with its chat's
+/// identifier and when it was starred (secs). Backs the Saved Messages view.
+pub async fn get_starred_message_views(limit: usize) -> Result<Vec<(String, u64, Message)>, String> {
+    use std::collections::HashMap;
+
+    let message_kinds = [event_kind::CHAT_MESSAGE, event_kind::PRIVATE_DIRECT_MESSAGE, event_kind::FILE_ATTACHMENT];
+    let (message_events, starred): (Vec<StoredEvent>, HashMap<String, (String, u64)>) = {
+        let conn = super::get_db_connection_guard_static()?;
+        let mut stmt = conn.prepare(
+            "SELECT e.id, e.kind, e.chat_id, e.user_id, e.content, e.tags, e.reference_id, \
+             e.created_at, e.received_at, e.mine, e.pending, e.failed, e.wrapper_event_id, e.npub, e.preview_metadata, \
+             c.chat_identifier, s.starred_at \
+             FROM starred_messages s JOIN events e ON e.id = s.message_id JOIN chats c ON c.id = e.chat_id \
+             WHERE e.kind IN (?1, ?2, ?3) \
+             ORDER BY s.starred_at DESC LIMIT ?4"
+        ).map_err(|e| format!("Failed to prepare starred query: {}", e))?;
+        let rows = stmt.query_map(
+            rusqlite::params![
+                message_kinds[0] as i32, message_kinds[1] as i32, message_kinds[2] as i32, limit as i64
+            ],
+            |row| Ok((parse_event_row(row)?, row.get::<_, String>(15)?, row.get::<_, i64>(16)?.max(0) as u64)),
+        ).map_err(|e| format!("Failed to query starred messages: {}", e))?;
+        let mut events = Vec::new();
+        let mut starred = HashMap::new();
+        for (event, chat_identifier, starred_at) in rows.flatten() {
+            starred.insert(event.id.clone(), (chat_identifier, starred_at));
+            events.push(event);
+        }
+        (events, starred)
+    };
+
+    // Decrypt message content (mirror get_events).
+    let mut decrypted = Vec::with_capacity(message_events.len());
+    for mut event in message_events {
+        if event.kind == event_kind::CHAT_MESSAGE || event.kind == event_kind::PRIVATE_DIRECT_MESSAGE {
+            event.content = crate::crypto::content_cache::decrypt(event.content).await
+                .unwrap_or_else(|_| "[Decryption failed]".to_string());
+        }
+        decrypted.push(event);
+    }
+
+    Ok(compose_message_views(decrypted).await?
+        .into_iter()
+        .filter_map(|message| {
+            let (chat_identifier, starred_at) = starred.get(&message.id)?.clone();
+            Some((chat_identifier, starred_at, message))
+        })
+        .collect())
+}
+
+// ============================================================================
+// In-Chat Search
+// ============================================================================
+
+/// One message matching an in-chat search. `ranges` are `(start, len)` pairs in
+/// UTF-16 code units, so the frontend can slice/highlight with plain JS string indices.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct ChatSearchMatch {
+    pub message_id: String,
+    /// Milliseconds, matching `Message::at`.
+    pub at: u64,
+    pub ranges: Vec<(usize, usize)>,
+    /// The match is in the message's voice transcript, and `ranges` index into its text.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub in_transcript: bool,
+}
+
+/// A page of in-chat search matches, newest first. Either cursor feeds straight
+/// back into [`search_in_chat`] with the matching direction.
+#[derive(serde::Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ChatSearchPage {
+    pub matches: Vec<ChatSearchMatch>,
+    /// Continue toward older messages (`Older`); `None` once history is exhausted.
+    pub older_cursor: Option<String>,
+    /// Continue toward newer messages (`Newer`); `None` when nothing newer can match.
+    pub newer_cursor: Option<String>,
+}
+
+/// Which side of the cursor [`search_in_chat`] scans.
+#[derive(serde::Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchDirection {
+    #[default]
+    Older,
+    Newer,
+}
+
+/// Rows decrypted per scan step. Bounds peak memory without one query per message.
+const SEARCH_SCAN_BATCH: usize = 256;
+
+/// Case-insensitive, non-overlapping occurrences of `needle` in `haystack`, as
+/// `(start, len)` UTF-16 ranges. Folds per char so offsets stay aligned with the original text.
+pub fn find_match_ranges(haystack: &str, needle: &str) -> Vec<(usize, usize)> {
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let needle: Vec<char> = needle.chars().map(fold).collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    // (folded char, utf16 offset) with a trailing sentinel offset for the end.
+    let mut hay: Vec<(char, usize)> = Vec::with_capacity(haystack.len());
+    let mut off = 0usize;
+    for c in haystack.chars() {
+        hay.push((fold(c), off));
+        off += c.len_utf16();
+    }
+    let end = off;
+
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i + needle.len() <= hay.len() {
+        if hay[i..i + needle.len()].iter().zip(&needle).all(|((h, _), n)| h == n) {
+            let start = hay[i].1;
+            let stop = hay.get(i + needle.len()).map_or(end, |(_, o)| *o);
+            ranges.push((start, stop - start));
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    ranges
+}
+
+/// Search a single conversation's text messages for `query`, returning up to
+/// `limit` matches on one side of `cursor` (a message id; `None` = from the newest).
+///
+/// Content is encrypted at rest, so there is no plaintext index to query — a
+/// shadow FTS table would leak every message. Rows are instead decrypted in
+/// [`SEARCH_SCAN_BATCH`] steps along the (created_at, received_at, rowid) key and
+/// matched in memory; an edited message is matched on its latest edit, and a voice message
+/// on its transcript when its text doesn't match.
+pub async fn search_in_chat(
+    chat_id: i64,
+    query: &str,
+    cursor: Option<&str>,
+    direction: SearchDirection,
+    limit: usize,
+) -> Result<ChatSearchPage, String> {
+    use std::collections::HashMap;
+
+    let query = query.trim();
+    if query.is_empty() || limit == 0 {
+        return Ok(ChatSearchPage::default());
+    }
+
+    let older = direction == SearchDirection::Older;
+    // Scan position: the last (created_at, received_at, rowid) key examined. No cursor starts
+    // past the chat edge on the scan side, so the first batch includes the edge row.
+    let mut key: (i64, i64, i64) = match cursor {
+        Some(id) => {
+            let conn = super::get_db_connection_guard_static()?;
+            conn.query_row(
+                "SELECT created_at, received_at, rowid FROM events WHERE id = ?1 AND chat_id = ?2",
+                rusqlite::params![id, chat_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            ).map_err(|e| format!("Search cursor not found: {}", e))?
+        }
+        None if older => (i64::MAX, i64::MAX, i64::MAX),
+        None => (i64::MIN, i64::MIN, i64::MIN),
+    };
+
+    // ?1 chat, ?2/?3 text kinds, ?8 file kind (its content isn't shown, only a transcript is
+    // searched), ?4..?6 key, ?7 batch size.
+    let (cmp, order) = if older { ("<", "DESC") } else { (">", "ASC") };
+    let sql = format!(
+        "SELECT id, CASE WHEN kind = ?8 THEN '' ELSE content END, created_at, received_at, rowid FROM events \
+         WHERE chat_id = ?1 AND kind IN (?2, ?3, ?8) \
+         AND (created_at {cmp} ?4 OR (created_at = ?4 AND (received_at {cmp} ?5 \
+              OR (received_at = ?5 AND rowid {cmp} ?6)))) \
+         ORDER BY created_at {order}, received_at {order}, rowid {order} LIMIT ?7"
+    );
+
+    let mut matches: Vec<ChatSearchMatch> = Vec::new();
+    let mut exhausted = false;
+    while matches.len() < limit {
+        let (rows, edits): (Vec<(String, String, i64, i64, i64)>, HashMap<String, String>) = {
+            let conn = super::get_db_connection_guard_static()?;
+            let mut stmt = conn.prepare_cached(&sql)
+                .map_err(|e| format!("Failed to prepare search query: {}", e))?;
+            let rows: Vec<(String, String, i64, i64, i64)> = stmt.query_map(
+                rusqlite::params![
+                    chat_id,
+                    event_kind::CHAT_MESSAGE as i32, event_kind::PRIVATE_DIRECT_MESSAGE as i32,
+                    key.0, key.1, key.2, SEARCH_SCAN_BATCH as i64,
+                    event_kind::FILE_ATTACHMENT as i32
+                ],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            ).map_err(|e| format!("Failed to query search batch: {}", e))?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            // Latest edit per message in this batch (first row wins: ordered newest first).
+            let mut edits: HashMap<String, String> = HashMap::new();
+            if !rows.is_empty() {
+                let placeholders = (0..rows.len()).map(|i| format!("?{}", i + 2)).collect::<Vec<_>>().join(",");
+                let edit_sql = format!(
+                    "SELECT reference_id, content FROM events WHERE kind = ?1 AND reference_id IN ({}) \
+                     ORDER BY created_at DESC, received_at DESC",
+                    placeholders
+                );
+                let mut edit_stmt = conn.prepare(&edit_sql)
+                    .map_err(|e| format!("Failed to prepare search edit query: {}", e))?;
+                let mut params: Vec<&dyn rusqlite::ToSql> = Vec::with_capacity(rows.len() + 1);
+                let edit_kind = event_kind::MESSAGE_EDIT as i32;
+                params.push(&edit_kind);
+                for row in &rows {
+                    params.push(&row.0);
+                }
+                let edit_rows = edit_stmt.query_map(params.as_slice(), |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                }).map_err(|e| format!("Failed to query search edits: {}", e))?;
+                for (ref_id, content) in edit_rows.filter_map(|r| r.ok()) {
+                    edits.entry(ref_id).or_insert(content);
+                }
+            }
+            (rows, edits)
+        };
+
+        if rows.len() < SEARCH_SCAN_BATCH {
+            exhausted = true;
+        }
+        let Some(last) = rows.last() else { break };
+        key = (last.2, last.3, last.4);
+        let ids: Vec<String> = rows.iter().map(|r| r.0.clone()).collect();
+        let transcripts = super::attachments::get_transcript_texts(&ids)?;
+
+        for (id, content, created_at, _, _) in rows {
+            let content = edits.get(&id).cloned().unwrap_or(content);
+            let Ok(text) = crate::crypto::content_cache::decrypt(content).await else { continue };
+            let mut ranges = find_match_ranges(&text, query);
+            let mut in_transcript = false;
+            if ranges.is_empty() {
+                if let Some(transcript) = transcripts.get(&id) {
+                    ranges = find_match_ranges(transcript, query);
+                    in_transcript = true;
+                }
+            }
+            if !ranges.is_empty() {
+                matches.push(ChatSearchMatch { message_id: id, at: created_at as u64 * 1000, ranges, in_transcript });
+                if matches.len() == limit {
+                    exhausted = false;
+                    break;
+                }
+            }
+        }
+        if exhausted {
+            break;
+        }
+    }
+
+    // Scanning newer yields ASC; pages are always newest first.
+    if !older {
+        matches.reverse();
+    }
+    let first = matches.first().map(|m| m.message_id.clone());
+    let last = matches.last().map(|m| m.message_id.clone());
+    let (older_cursor, newer_cursor) = if older {
+        (if exhausted { None } else { last }, cursor.map(|c| first.unwrap_or_else(|| c.to_string())))
+    } else {
+        (last.or_else(|| cursor.map(str::to_string)), if exhausted { None } else { first })
+    };
+    Ok(ChatSearchPage { matches, older_cursor, newer_cursor })
+}
+
 /// Get the last message for ALL chats in a single batch query.
 /// Optimized for app startup (chat list sidebar).
 pub async fn get_all_chats_last_messages() -> Result<std::collections::HashMap<String, Vec<Message>>, String> {
@@ -1381,7 +1880,7 @@ pub async fn get_all_chats_last_messages() -> Result<std::collections::HashMap<S
                  SELECT e2.rowid FROM events e2 WHERE e2.chat_id = c.id \
                  AND e2.kind IN (?1, ?2, ?3) \
                  ORDER BY e2.created_at DESC, e2.received_at DESC LIMIT 1) \
-             WHERE c.chat_type != 1"
+             WHERE c.chat_type != 1 AND c.deleted_at IS NULL"
         ).map_err(|e| format!("Failed to prepare: {}", e))?;
 
         let rows = stmt.query_map(
@@ -1433,7 +1932,7 @@ pub async fn get_all_chats_last_messages() -> Result<std::collections::HashMap<S
                     });
                 }
                 k if k == event_kind::MESSAGE_EDIT => {
-                    let decrypted = crate::crypto::maybe_decrypt(event.content.clone()).await
+                    let decrypted = crate::crypto::content_cache::decrypt(event.content.clone()).await
                         .unwrap_or_else(|_| event.content.clone());
                     let edit_emoji = crate::types::EmojiTag::extract_from_stored(&event.tags);
                     edits_by_msg.entry(ref_id.clone()).or_default().push((event.created_at * 1000, decrypted, edit_emoji));
@@ -1481,7 +1980,7 @@ pub async fn get_all_chats_last_messages() -> Result<std::collections::HashMap<S
         let original_content = if event.kind == event_kind::CHAT_MESSAGE
             || event.kind == event_kind::PRIVATE_DIRECT_MESSAGE
         {
-            crate::crypto::maybe_decrypt(event.content.clone()).await
+            crate::crypto::content_cache::decrypt(event.content.clone()).await
                 .unwrap_or_else(|_| "[Decryption failed]".to_string())
         } else {
             String::new()
@@ -1491,6 +1990,7 @@ pub async fn get_all_chats_last_messages() -> Result<std::collections::HashMap<S
         let original_emoji = crate::types::EmojiTag::extract_from_stored(&stored_tags);
         let addressed_bots = extract_bot_tags(&stored_tags);
         let expiration = extract_expiration_tag(&stored_tags);
+        let location = crate::location::from_stored_tags(&stored_tags);
         // Newest edit's emoji tags win so the latest content renders correctly.
         let (content, edited, edit_history, emoji_tags) = if let Some(edits) = edits_by_msg.remove(&event.id) {
             let (latest, latest_emoji) = edits.last()
@@ -1507,18 +2007,26 @@ pub async fn get_all_chats_last_messages() -> Result<std::collections::HashMap<S
 
         let preview_metadata = event.preview_metadata
             .and_then(|json| serde_json::from_str(&json).ok());
+        let code_languages = crate::code_blocks::code_languages(&content);
+        let nostr_previews = crate::nostr_uri::cached_previews(&content);
+        let zap_msat = crate::zaps::message_total(&event.id);
+        let starred = crate::starred::is_starred(&event.id);
 
         result.entry(chat_identifier).or_default().push(Message {
             expiration,
             id: event.id, content, replied_to,
             replied_to_content: None, replied_to_npub: None, replied_to_has_attachment: None,
             replied_to_attachment_extension: None,
-            preview_metadata, attachments, reactions, at: event.created_at * 1000,
+            preview_metadata, location, attachments, reactions, at: event.created_at * 1000,
             pending: event.pending, failed: event.failed, mine: event.mine,
             npub: event.npub, wrapper_event_id: event.wrapper_event_id,
             edited, edit_history,
             emoji_tags,
             addressed_bots,
+            code_languages,
+            nostr_previews,
+            zap_msat,
+            starred,
         });
     }
 
@@ -1567,6 +2075,7 @@ pub async fn unread_counts() -> Result<std::collections::HashMap<String, u32>, S
                 FROM chats c \
                 LEFT JOIN events e ON e.chat_id = c.id \
                   AND ((e.mine = 1 AND e.kind IN (?1, ?2, ?3)) OR e.id = c.last_read) \
+                WHERE c.deleted_at IS NULL \
                 GROUP BY c.id \
              ) \
              SELECT a.chat_identifier, COUNT(*) AS unread \
@@ -1616,6 +2125,96 @@ pub async fn unread_count_for_chat(chat_identifier: &str) -> Result<u32, String>
     Ok(count as u32)
 }
 
+/// One chat's unread as the chat list badges it.
+#[derive(serde::Serialize, Clone, Debug, Default, PartialEq)]
+pub struct ChatUnread {
+    pub count: u32,
+    /// How many of those mention us (by npub or `@everyone`).
+    pub mentions: u32,
+    /// The oldest unread message — where the chat opens to ("jump to first unread").
+    pub oldest_unread: Option<String>,
+}
+
+/// [`ChatUnread`] for every chat with unread, same semantics as [`unread_counts`]. The one full scan
+/// behind the `ChatState` unread cache, which is then kept current per chat with
+/// [`unread_summary_for_chat`]. Chats with 0 unread are omitted.
+pub async fn unread_summaries() -> Result<std::collections::HashMap<String, ChatUnread>, String> {
+    let mentions = super::mentions::unread_mention_counts().unwrap_or_default();
+    let conn = super::get_db_connection_guard_static()?;
+    // Same anchors as `unread_counts`; the window numbers each chat's unread oldest-first so one row
+    // per chat carries both its count and its oldest message. Same-second ties go by arrival, like the
+    // message cursor.
+    let mut stmt = conn
+        .prepare(
+            "WITH anchors AS ( \
+                SELECT c.id AS chat_id, c.chat_identifier AS chat_identifier, \
+                       COALESCE(MAX(e.created_at), 0) AS anchor_ts \
+                FROM chats c \
+                LEFT JOIN events e ON e.chat_id = c.id \
+                  AND ((e.mine = 1 AND e.kind IN (?1, ?2, ?3)) OR e.id = c.last_read) \
+                WHERE c.deleted_at IS NULL \
+                GROUP BY c.id \
+             ), unread AS ( \
+                SELECT a.chat_identifier AS chat_identifier, e.id AS id, \
+                       ROW_NUMBER() OVER (PARTITION BY e.chat_id ORDER BY e.created_at, e.received_at, e.rowid) AS rn, \
+                       COUNT(*) OVER (PARTITION BY e.chat_id) AS unread \
+                FROM events e JOIN anchors a ON a.chat_id = e.chat_id \
+                WHERE e.kind IN (?1, ?2, ?3) AND e.mine = 0 AND e.created_at > a.anchor_ts \
+             ) \
+             SELECT chat_identifier, unread, id FROM unread WHERE rn = 1",
+        )
+        .map_err(|e| format!("prepare unread_summaries: {e}"))?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![
+                event_kind::CHAT_MESSAGE as i32,
+                event_kind::PRIVATE_DIRECT_MESSAGE as i32,
+                event_kind::FILE_ATTACHMENT as i32
+            ],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u32, row.get::<_, String>(2)?)),
+        )
+        .map_err(|e| format!("query unread_summaries: {e}"))?;
+    let mut out = std::collections::HashMap::new();
+    for (chat, count, oldest) in rows.flatten() {
+        let mentions = mentions.get(&chat).copied().unwrap_or(0);
+        out.insert(chat, ChatUnread { count, mentions, oldest_unread: Some(oldest) });
+    }
+    Ok(out)
+}
+
+/// [`ChatUnread`] for a SINGLE chat (indexed per-chat queries), for reconciling one cache entry on
+/// inbound / delete / retreat.
+pub async fn unread_summary_for_chat(chat_identifier: &str) -> Result<ChatUnread, String> {
+    let row: Option<(String, i64)> = {
+        let conn = super::get_db_connection_guard_static()?;
+        // The window count runs before LIMIT, so the oldest row carries the whole count.
+        conn.query_row(
+            "SELECT e.id, COUNT(*) OVER () FROM events e JOIN chats c ON e.chat_id = c.id \
+             WHERE c.chat_identifier = ?4 AND e.kind IN (?1, ?2, ?3) AND e.mine = 0 \
+               AND e.created_at > COALESCE(( \
+                     SELECT MAX(e2.created_at) FROM events e2 \
+                     WHERE e2.chat_id = c.id \
+                       AND ((e2.mine = 1 AND e2.kind IN (?1, ?2, ?3)) OR e2.id = c.last_read)), 0) \
+             ORDER BY e.created_at, e.received_at, e.rowid LIMIT 1",
+            rusqlite::params![
+                event_kind::CHAT_MESSAGE as i32,
+                event_kind::PRIVATE_DIRECT_MESSAGE as i32,
+                event_kind::FILE_ATTACHMENT as i32,
+                chat_identifier
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("query unread_summary_for_chat: {e}"))?
+    };
+    let Some((oldest, count)) = row else { return Ok(ChatUnread::default()) };
+    Ok(ChatUnread {
+        count: count as u32,
+        mentions: super::mentions::unread_mention_count_for_chat(chat_identifier).unwrap_or(0),
+        oldest_unread: Some(oldest),
+    })
+}
+
 /// What [`compute_unread_anchor`] decided a chat's read marker should become to surface its newest
 /// contact message as unread. Computed from the full DB history (RAM may hold only a preview
 /// message for an unopened community).
@@ -1879,6 +2478,148 @@ mod tests {
         assert_eq!(unread_count_for_chat("npub1nonexistent").await.unwrap(), 0);
     }
 
+    // The per-chat summary (the cache's reconcile path) agrees with the full seed, and its oldest
+    // unread moves past what the read anchor covers.
+    #[tokio::test]
+    async fn unread_summary_for_chat_matches_the_seed() {
+        let (_tmp, _guard) = init_test_db();
+        let me = crate::db::get_current_account().unwrap();
+        let chat = "npub1summary";
+        let mk = |id: &str, secs: u64, mine: bool, content: String| Message {
+            id: id.into(), content, at: secs * 1000, mine,
+            npub: (!mine).then(|| "npub1sender".to_string()),
+            ..Default::default()
+        };
+        let agree = || async {
+            let seed = unread_summaries().await.unwrap().remove(chat).unwrap_or_default();
+            let one = unread_summary_for_chat(chat).await.unwrap();
+            assert_eq!(seed, one, "single-chat summary diverged from the seed");
+            one
+        };
+
+        save_message(chat, &mk("m0", 1000, false, "x".into())).await.unwrap();
+        save_message(chat, &mk("m1", 1001, false, format!("hi {me}"))).await.unwrap();
+        save_message(chat, &mk("m2", 1002, false, "x".into())).await.unwrap();
+        assert_eq!(agree().await, ChatUnread { count: 3, mentions: 1, oldest_unread: Some("m0".into()) });
+
+        save_message(chat, &mk("mine", 1010, true, "ok".into())).await.unwrap();
+        assert_eq!(agree().await, ChatUnread::default(), "our reply reads everything before it");
+        save_message(chat, &mk("m3", 1011, false, "x".into())).await.unwrap();
+        save_message(chat, &mk("m4", 1011, false, "x".into())).await.unwrap();
+        assert_eq!(agree().await, ChatUnread { count: 2, mentions: 0, oldest_unread: Some("m3".into()) }, "same-second ties go by arrival");
+    }
+
+    // The keyset pager walks all the way back through a same-second burst, one strict step at a
+    // time, and new arrivals at the tail don't shift its pages.
+    #[tokio::test]
+    async fn messages_before_pages_through_a_burst() {
+        let (_tmp, _guard) = init_test_db();
+        let chat = "npub1pager";
+        let mk = |id: &str, secs: u64| Message {
+            id: id.into(), content: "x".into(), at: secs * 1000, mine: false,
+            npub: Some("npub1sender".to_string()), ..Default::default()
+        };
+        save_message(chat, &mk("old", 900)).await.unwrap();
+        for i in 0..5 {
+            save_message(chat, &mk(&format!("b{i}"), 1000)).await.unwrap();
+        }
+        save_message(chat, &mk("new", 1100)).await.unwrap();
+        let chat_int = crate::db::id_cache::get_chat_id_by_identifier(chat).unwrap();
+
+        let ids = |page: &[Message]| page.iter().map(|m| m.id.clone()).collect::<Vec<_>>();
+        let page = get_messages_before(chat_int, "new", 2).await.unwrap();
+        assert_eq!(ids(&page), ["b3", "b4"], "ASC, strictly older than the cursor");
+        save_message(chat, &mk("newer", 1200)).await.unwrap();
+        let page = get_messages_before(chat_int, &page[0].id, 2).await.unwrap();
+        assert_eq!(ids(&page), ["b1", "b2"]);
+        let page = get_messages_before(chat_int, &page[0].id, 5).await.unwrap();
+        assert_eq!(ids(&page), ["old", "b0"]);
+        assert!(get_messages_before(chat_int, "old", 5).await.unwrap().is_empty(), "start of history");
+        assert!(get_messages_before(chat_int, "missing", 5).await.is_err());
+    }
+
+    #[test]
+    fn match_ranges_are_case_insensitive_utf16_offsets() {
+        assert_eq!(find_match_ranges("Hello hello HELLO", "hello"), vec![(0, 5), (6, 5), (12, 5)]);
+        // 🎉 is two UTF-16 units, so the match after it starts at 3, not 2.
+        assert_eq!(find_match_ranges("🎉 Café", "café"), vec![(3, 4)]);
+        assert_eq!(find_match_ranges("aaaa", "aa"), vec![(0, 2), (2, 2)], "non-overlapping");
+        assert!(find_match_ranges("anything", "").is_empty());
+        assert!(find_match_ranges("short", "much longer").is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_in_chat_pages_both_ways_and_prefers_latest_edit() {
+        let (_tmp, _guard) = init_test_db();
+        let chat = "npub1searchchat";
+        let chat_int = crate::db::id_cache::get_or_create_chat_id(chat).unwrap();
+        let mk = |id: &str, secs: u64, content: &str| Message {
+            id: id.into(), content: content.into(), at: secs * 1000, mine: false,
+            npub: Some("npub1sender".to_string()),
+            ..Default::default()
+        };
+        for i in 0..5u64 {
+            save_message(chat, &mk(&format!("hit{i}"), 1000 + i * 10, "meet at the Cafe")).await.unwrap();
+            save_message(chat, &mk(&format!("miss{i}"), 1005 + i * 10, "nothing here")).await.unwrap();
+        }
+        // Edited away from the query: must no longer match.
+        save_edit_event("edit0", "hit4", "rescheduled", &[], chat_int, None, "npub1sender").await.unwrap();
+        // Edited into the query: must match.
+        save_edit_event("edit1", "miss0", "cafe instead", &[], chat_int, None, "npub1sender").await.unwrap();
+
+        let page = search_in_chat(chat_int, "cafe", None, SearchDirection::Older, 2).await.unwrap();
+        let ids: Vec<_> = page.matches.iter().map(|m| m.message_id.as_str()).collect();
+        assert_eq!(ids, ["hit3", "hit2"], "newest first, edited-away hit4 skipped");
+        assert_eq!(page.matches[0].ranges, vec![(12, 4)]);
+        assert!(page.newer_cursor.is_none(), "first page from the newest has nothing newer");
+
+        let older = search_in_chat(chat_int, "cafe", page.older_cursor.as_deref(), SearchDirection::Older, 10).await.unwrap();
+        let ids: Vec<_> = older.matches.iter().map(|m| m.message_id.as_str()).collect();
+        assert_eq!(ids, ["hit1", "miss0", "hit0"]);
+        assert!(older.older_cursor.is_none(), "history exhausted");
+
+        let back = search_in_chat(chat_int, "cafe", older.newer_cursor.as_deref(), SearchDirection::Newer, 10).await.unwrap();
+        let ids: Vec<_> = back.matches.iter().map(|m| m.message_id.as_str()).collect();
+        assert_eq!(ids, ["hit3", "hit2"], "stepping back returns the previous page");
+        assert!(back.newer_cursor.is_none());
+
+        assert!(search_in_chat(chat_int, "   ", None, SearchDirection::Older, 10).await.unwrap().matches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_in_chat_matches_voice_transcripts() {
+        use crate::types::{Transcript, TranscriptSection};
+        let (_tmp, _guard) = init_test_db();
+        let chat = "npub1voicechat";
+        let chat_int = crate::db::id_cache::get_or_create_chat_id(chat).unwrap();
+        let voice = Attachment {
+            id: "hashV".into(), extension: "wav".into(), downloaded: false, ..Default::default()
+        };
+        save_message(chat, &Message {
+            id: "voice".into(), at: 1_000_000, npub: Some("npub1sender".into()),
+            attachments: vec![voice], ..Default::default()
+        }).await.unwrap();
+
+        assert!(search_in_chat(chat_int, "cafe", None, SearchDirection::Older, 10).await.unwrap().matches.is_empty());
+
+        let transcript = Transcript {
+            lang: "en".into(),
+            sections: vec![
+                TranscriptSection { text: " Meet me".into(), at: 0 },
+                TranscriptSection { text: " at the cafe".into(), at: 1200 },
+            ],
+        };
+        crate::db::attachments::set_attachment_transcript("voice", "hashV", &transcript).unwrap();
+        let stored = crate::db::attachments::get_attachments_for_event("voice").unwrap();
+        assert_eq!(stored[0].transcript.as_ref(), Some(&transcript));
+
+        let page = search_in_chat(chat_int, "cafe", None, SearchDirection::Older, 10).await.unwrap();
+        assert_eq!(page.matches.len(), 1);
+        assert_eq!(page.matches[0].message_id, "voice");
+        assert!(page.matches[0].in_transcript);
+        assert_eq!(page.matches[0].ranges, vec![(15, 4)], "offsets into \"Meet me at the cafe\"");
+    }
+
     #[tokio::test]
     async fn attachments_table_round_trip_dedup_and_cascade() {
         let (_tmp, _guard) = init_test_db();
@@ -1925,6 +2666,130 @@ mod tests {
         assert!(crate::db::attachments::get_attachments_for_event("m1").unwrap().is_empty(), "ON DELETE CASCADE");
     }
 
+    #[tokio::test]
+    async fn chat_media_filters_by_kind_and_pages_newest_first() {
+        use crate::db::attachments::{get_chat_media, MediaKind};
+        let (_tmp, _guard) = init_test_db();
+        let chat = "npub1gallery";
+        let chat_int = crate::db::id_cache::get_or_create_chat_id(chat).unwrap();
+        let att = |id: &str, ext: &str, name: &str| Attachment {
+            id: id.into(), url: format!("https://blossom/{id}"), name: name.into(),
+            extension: ext.into(), size: 1, downloaded: false, ..Default::default()
+        };
+        let msg = |mid: &str, secs: u64, atts: Vec<Attachment>| Message {
+            id: mid.into(), content: String::new(), at: secs * 1000, mine: false,
+            npub: Some("npub1sender".into()), attachments: atts, ..Default::default()
+        };
+
+        save_message(chat, &msg("img1", 1000, vec![att("h1", "png", "a.png")])).await.unwrap();
+        save_message(chat, &msg("mixed", 1001, vec![att("h2", "jpg", "b.jpg"), att("h3", "mp4", "c.mp4")])).await.unwrap();
+        save_message(chat, &msg("voice", 1002, vec![att("h4", "wav", "")])).await.unwrap();
+        save_message(chat, &msg("clip", 1002, vec![Attachment { video_note: true, ..att("h8", "webm", "") }])).await.unwrap();
+        save_message(chat, &msg("screen", 1002, vec![att("h9", "webm", "")])).await.unwrap();
+        save_message(chat, &msg("song", 1003, vec![att("h5", "mp3", "song.mp3"), att("h6", "pdf", "doc.pdf")])).await.unwrap();
+        let mut linked = msg("link", 1004, vec![]);
+        linked.content = "https://example.com".into();
+        linked.preview_metadata = Some(crate::types::SiteMetadata {
+            domain: "example.com".into(), og_title: None, og_description: None, og_image: None,
+            og_url: Some("https://example.com".into()), og_type: None, title: None, description: None, favicon: None,
+        });
+        save_message(chat, &linked).await.unwrap();
+        // Another chat's image must never leak into this gallery.
+        save_message("npub1other", &msg("elsewhere", 1005, vec![att("h7", "png", "x.png")])).await.unwrap();
+
+        let images = get_chat_media(chat_int, MediaKind::Image, 0, 1).unwrap();
+        assert_eq!(images.items.len(), 1);
+        assert_eq!(images.items[0].message_id, "mixed", "newest first");
+        assert!(images.has_more);
+        let images = get_chat_media(chat_int, MediaKind::Image, 1, 1).unwrap();
+        assert_eq!(images.items[0].message_id, "img1");
+        assert!(!images.has_more);
+
+        let ids = |k| get_chat_media(chat_int, k, 0, 10).unwrap().items.into_iter()
+            .map(|i| i.attachment.map(|a| a.id).unwrap_or(i.message_id)).collect::<Vec<_>>();
+        assert_eq!(ids(MediaKind::Video), ["h9", "h3"], "a nameless video isn't a video note unless marked");
+        assert_eq!(ids(MediaKind::Voice), ["h4"], "nameless audio is a voice note");
+        assert_eq!(ids(MediaKind::VideoNote), ["h8"]);
+        let note = get_chat_media(chat_int, MediaKind::VideoNote, 0, 10).unwrap();
+        assert!(note.items[0].attachment.as_ref().unwrap().video_note, "the mark survives a reload");
+        assert_eq!(ids(MediaKind::File), ["h5", "h6"], "named audio is a plain file");
+        assert_eq!(ids(MediaKind::Link), ["link"]);
+    }
+
+    // Only pings of the account owner are indexed (never own messages, never other people's
+    // mentions); the per-chat unread count drops once we reply past them.
+    #[tokio::test]
+    async fn mentions_index_pages_and_counts_unread() {
+        use crate::db::mentions::{get_my_mentions, unread_mention_counts};
+        let (_tmp, _guard) = init_test_db();
+        let me = crate::db::get_current_account().unwrap();
+        let other = make_test_npub(7);
+        let mk = |id: &str, secs: u64, mine: bool, content: String| Message {
+            id: id.into(), content, at: secs * 1000, mine,
+            npub: (!mine).then(|| "npub1sender".to_string()),
+            ..Default::default()
+        };
+
+        save_message("npub1dm", &mk("direct", 1000, false, format!("hey {me} look"))).await.unwrap();
+        save_message("group", &mk("all", 1001, false, "@everyone standup".into())).await.unwrap();
+        save_message("group", &mk("someone", 1002, false, format!("ping {other}"))).await.unwrap();
+        save_message("group", &mk("self", 1003, true, format!("note to {me}"))).await.unwrap();
+        let batch = mk("batched", 1004, false, format!("{me}!"));
+        save_messages_batch("group", &[&batch], None).await.unwrap();
+
+        let page = get_my_mentions(0, 10).unwrap();
+        let ids: Vec<_> = page.iter().map(|m| m.message_id.as_str()).collect();
+        assert_eq!(ids, ["batched", "all", "direct"], "newest first, own + foreign mentions excluded");
+        assert!(page[1].everyone && !page[0].everyone);
+        assert_eq!(page[2].chat_id, "npub1dm");
+        assert_eq!(page[2].at, 1_000_000);
+        assert_eq!(get_my_mentions(1, 2).unwrap().len(), 1);
+
+        // Our own message at 1003 anchors "group": only the later batched mention stays unread.
+        let counts = unread_mention_counts().unwrap();
+        assert_eq!(counts.get("group"), Some(&1));
+        assert_eq!(counts.get("npub1dm"), Some(&1));
+    }
+
+    // The "who reacted" sheet: a relay re-delivery of the same reaction under a new event id is
+    // collapsed, distinct emojis from one person are kept, and tallies sort by popularity.
+    #[tokio::test]
+    async fn message_reactions_dedup_and_tally() {
+        let (_tmp, _guard) = init_test_db();
+        let me = crate::db::get_current_account().unwrap();
+        let alice = make_test_npub(11);
+        let bob = make_test_npub(12);
+        let chat = "npub1reactions";
+        save_message(chat, &Message {
+            id: "target".into(), content: "hi".into(), at: 1_000_000, mine: true, ..Default::default()
+        }).await.unwrap();
+        let chat_int = crate::db::id_cache::get_or_create_chat_id(chat).unwrap();
+        let react = |id: &str, author: &str, emoji: &str| Reaction {
+            id: id.into(), reference_id: "target".into(), author_id: author.into(), emoji: emoji.into(), emoji_url: None,
+        };
+        save_reaction_event(&react("r1", &alice, "👍"), chat_int, None, false, None).await.unwrap();
+        save_reaction_event(&react("r1-dup", &alice, "👍"), chat_int, None, false, None).await.unwrap();
+        save_reaction_event(&react("r2", &alice, "🔥"), chat_int, None, false, None).await.unwrap();
+        save_reaction_event(&react("r3", &bob, "👍"), chat_int, None, false, None).await.unwrap();
+        save_reaction_event(&react("r4", &me, "👍"), chat_int, None, true, None).await.unwrap();
+        crate::db::profiles::set_profile(&crate::profile::SlimProfile {
+            id: alice.clone(), name: "alice".into(), nickname: "Al".into(), ..Default::default()
+        }).unwrap();
+
+        let out = get_message_reactions("target").await.unwrap();
+        assert_eq!(out.reactors.len(), 4, "duplicate delivery collapsed");
+        assert_eq!(out.tallies[0].emoji, "👍");
+        assert_eq!(out.tallies[0].count, 3);
+        assert!(out.tallies[0].reacted_by_me);
+        assert_eq!(out.tallies[1].count, 1);
+        assert!(!out.tallies[1].reacted_by_me);
+        let al = out.reactors.iter().find(|r| r.author_id == alice).unwrap();
+        assert_eq!(al.name, "Al", "nickname wins");
+        assert!(out.reactors.iter().find(|r| r.author_id == bob).unwrap().name.is_empty());
+
+        assert_eq!(get_message_reactions("nope").await.unwrap(), MessageReactions::default());
+    }
+
     // The download persist path: a re-save must never DOWNGRADE download state (relay re-delivery),
     // but a re-save carrying a completed download (+ the nonce→content-hash id rewrite) must persist.
     #[tokio::test]
@@ -2420,4 +3285,72 @@ mod tests {
         assert!(event_exists("evicted_ok").unwrap(), "evicted-but-not-deleted message persisted");
         assert!(ledgered(wrap_evicted.0), "evicted message's wrapper ledgered with it");
     }
+
+    // Archiving moves only files no newer message shares, remembers where they came from, and
+    // restores them there; a re-download drops the archive record.
+    #[tokio::test]
+    async fn attachment_archive_round_trip() {
+        let (_tmp, _guard) = init_test_db();
+        let mk = |id: &str, secs: u64, hash: &str, path: &str| Message {
+            id: id.into(), content: String::new(), at: secs * 1000, mine: false,
+            npub: Some("npub1s".into()),
+            attachments: vec![Attachment { id: hash.into(), extension: "png".into(), downloaded: true, path: path.into(), ..Default::default() }],
+            ..Default::default()
+        };
+        save_message("npub1s", &mk("old", 1_000, "h1", "/dl/a.png")).await.unwrap();
+        save_message("npub1s", &mk("old_shared", 1_000, "h2", "/dl/b.png")).await.unwrap();
+        save_message("npub1s", &mk("new_shared", 9_000, "h2", "/dl/b.png")).await.unwrap();
+
+        use crate::db::attachments as att;
+        assert_eq!(att::archivable_paths(5_000).unwrap(), vec!["/dl/a.png".to_string()], "b.png is still used by a newer message");
+
+        assert_eq!(att::mark_archived("/dl/a.png", "/nas/a.png").unwrap(), vec!["old".to_string()]);
+        assert_eq!(att::get_attachments_for_event("old").unwrap()[0].path, "/nas/a.png");
+        assert!(att::archivable_paths(5_000).unwrap().is_empty(), "already archived");
+        assert_eq!(att::archived_paths(None).unwrap(), vec![("/nas/a.png".to_string(), "/dl/a.png".to_string())]);
+        assert!(att::archived_paths(Some("new_shared")).unwrap().is_empty());
+        assert!(att::downloaded_attachment_paths().unwrap().iter().any(|(id, _, _, archived)| id == "old" && *archived));
+
+        assert_eq!(att::mark_restored("/nas/a.png").unwrap(), vec!["old".to_string()]);
+        assert_eq!(att::get_attachments_for_event("old").unwrap()[0].path, "/dl/a.png");
+        assert!(att::archived_paths(None).unwrap().is_empty());
+
+        att::mark_archived("/dl/a.png", "/nas/a.png").unwrap();
+        att::set_attachment_downloaded("old", "h1", true, "/dl/a2.png").unwrap();
+        assert!(att::archived_paths(None).unwrap().is_empty(), "a re-download is no longer archived");
+    }
+
+    // The media cache evicts by last use (message age until touched), and an eviction un-downloads
+    // every attachment sharing the file.
+    #[tokio::test]
+    async fn media_cache_lru_order_and_eviction() {
+        let (_tmp, _guard) = init_test_db();
+        let mk = |id: &str, secs: u64, hash: &str, path: &str| Message {
+            id: id.into(), content: String::new(), at: secs * 1000, mine: false,
+            npub: Some("npub1s".into()),
+            attachments: vec![Attachment { id: hash.into(), extension: "png".into(), downloaded: true, path: path.into(), ..Default::default() }],
+            ..Default::default()
+        };
+        save_message("npub1s", &mk("oldest", 1_000, "h1", "/dl/a.png")).await.unwrap();
+        save_message("npub1s", &mk("middle", 2_000, "h2", "/dl/b.png")).await.unwrap();
+        save_message("npub1s", &mk("newest", 3_000, "h3", "/dl/c.png")).await.unwrap();
+        save_message("npub1s", &mk("middle_dup", 1_500, "h2", "/dl/b.png")).await.unwrap();
+
+        use crate::db::attachments as att;
+        let paths = |files: Vec<(String, u64)>| files.into_iter().map(|(p, _)| p).collect::<Vec<_>>();
+        // As if downloaded before migration 93: no last-use yet
+        crate::db::get_write_connection_guard_static().unwrap()
+            .execute("UPDATE attachments SET last_used=0", []).unwrap();
+        assert_eq!(paths(att::cached_files_lru().unwrap()), vec!["/dl/a.png", "/dl/b.png", "/dl/c.png"]);
+
+        // Re-downloading the oldest makes it the most recently used
+        att::set_attachment_downloaded("oldest", "h1", true, "/dl/a.png").unwrap();
+        assert_eq!(paths(att::cached_files_lru().unwrap()), vec!["/dl/b.png", "/dl/c.png", "/dl/a.png"]);
+
+        let mut affected = att::mark_evicted("/dl/b.png").unwrap();
+        affected.sort();
+        assert_eq!(affected, vec!["middle".to_string(), "middle_dup".to_string()]);
+        assert!(!att::get_attachments_for_event("middle_dup").unwrap()[0].downloaded);
+        assert_eq!(paths(att::cached_files_lru().unwrap()), vec!["/dl/c.png", "/dl/a.png"]);
+    }
 }
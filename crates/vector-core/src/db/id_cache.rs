@@ -67,14 +67,13 @@ pub fn get_or_create_chat_id(chat_identifier: &str) -> Result<i64, String> {
     let id = if let Some(id) = existing {
         id
     } else {
-        // Create stub chat entry. Discriminant must match ChatType::to_i32:
-        // 0 = DirectMessage (npub), 2 = Community (non-npub). Value 1 was the
-        // retired MlsGroup variant and is dropped by the get_all_chats load filter,
-        // so a non-npub stub MUST be 2 or the chat vanishes on reload.
+        // Create stub chat entry: 0 = DirectMessage (npub), 2 = Community (non-npub).
+        // Value 1 was the retired MlsGroup variant and is dropped by the get_all_chats
+        // load filter, so a non-npub stub MUST be 2 or the chat vanishes on reload.
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH).unwrap()
             .as_secs() as i64;
-        let chat_type: i32 = if chat_identifier.starts_with("npub1") { 0 } else { 2 };
+        let chat_type: i32 = crate::ids::chat_type_of(chat_identifier).to_i32();
 
         conn.execute(
             "INSERT INTO chats (chat_identifier, chat_type, participants, created_at) VALUES (?1, ?2, '[]', ?3)",
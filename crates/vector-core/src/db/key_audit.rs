@@ -0,0 +1,56 @@
+//! Key-usage audit storage. Each row is one coalesced [`crate::key_audit::KeyAuditEntry`],
+//! encrypted at rest like message content; only its last-use time is plaintext (for paging and
+//! pruning).
+
+use rusqlite::params;
+
+use crate::key_audit::KeyAuditEntry;
+
+/// Rows kept; older ones are pruned on each write.
+const MAX_ROWS: i64 = 5_000;
+
+/// Append entries, then prune beyond [`MAX_ROWS`].
+pub fn insert_entries(entries: &[KeyAuditEntry]) -> Result<(), String> {
+    let mut rows = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let json = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+        rows.push((entry.last_at, crate::crypto::maybe_encrypt_text(&json)?));
+    }
+    let mut conn = super::get_write_connection_guard_static()?;
+    let tx = conn.transaction().map_err(|e| format!("key audit tx: {e}"))?;
+    for (last_at, payload) in rows {
+        tx.execute("INSERT INTO key_audit (last_at, payload) VALUES (?1, ?2)", params![last_at as i64, payload])
+            .map_err(|e| format!("Failed to save key audit entry: {}", e))?;
+    }
+    tx.execute(
+        "DELETE FROM key_audit WHERE id NOT IN (SELECT id FROM key_audit ORDER BY last_at DESC, id DESC LIMIT ?1)",
+        params![MAX_ROWS],
+    ).map_err(|e| format!("Failed to prune key audit log: {}", e))?;
+    tx.commit().map_err(|e| format!("key audit commit: {e}"))
+}
+
+/// One page of entries, most recent first.
+pub fn page(page: u32, page_size: u32) -> Result<Vec<KeyAuditEntry>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare("SELECT payload FROM key_audit ORDER BY last_at DESC, id DESC LIMIT ?1 OFFSET ?2")
+        .map_err(|e| format!("prepare key audit page: {e}"))?;
+    let rows = stmt.query_map(params![page_size as i64, page as i64 * page_size as i64], |r| r.get::<_, String>(0))
+        .map_err(|e| format!("query key audit page: {e}"))?;
+    Ok(rows
+        .flatten()
+        .filter_map(|stored| serde_json::from_str(&crate::crypto::maybe_decrypt_text(&stored)).ok())
+        .collect())
+}
+
+/// Every entry last used within `[from, to]` (secs), oldest first.
+pub fn between(from: u64, to: u64) -> Result<Vec<KeyAuditEntry>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare("SELECT payload FROM key_audit WHERE last_at BETWEEN ?1 AND ?2 ORDER BY last_at ASC, id ASC")
+        .map_err(|e| format!("prepare key audit range: {e}"))?;
+    let rows = stmt.query_map(params![from as i64, to.min(i64::MAX as u64) as i64], |r| r.get::<_, String>(0))
+        .map_err(|e| format!("query key audit range: {e}"))?;
+    Ok(rows
+        .flatten()
+        .filter_map(|stored| serde_json::from_str(&crate::crypto::maybe_decrypt_text(&stored)).ok())
+        .collect())
+}
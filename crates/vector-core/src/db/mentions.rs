@@ -0,0 +1,179 @@
+//! Mentions index — which messages mention the account owner, for the "mentions" view and the
+//! per-chat unread-mention badge. Only self-mentions (and `@everyone`) are indexed: content is
+//! encrypted at rest, and a plaintext index of who-mentions-whom would leak far more than we need.
+
+use std::collections::HashMap;
+
+use rusqlite::params;
+
+use crate::stored_event::event_kind;
+use crate::types::mentions_npub;
+
+/// Settings flag marking the one-shot backfill of pre-index history as done.
+const BACKFILL_FLAG: &str = "mentions_backfilled";
+
+/// One message that mentions the account owner.
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct MentionEntry {
+    pub message_id: String,
+    /// `chat_identifier` (npub for DMs, channel id for Communities).
+    pub chat_id: String,
+    /// Milliseconds, matching `Message::at`.
+    pub at: u64,
+    /// True when the message pinged `@everyone` rather than naming us.
+    pub everyone: bool,
+}
+
+/// Whether `content` pings `my_npub`: `Some(false)` for a direct mention, `Some(true)` for an
+/// `@everyone`-only ping, `None` otherwise.
+pub fn classify(content: &str, my_npub: &str) -> Option<bool> {
+    if mentions_npub(content, my_npub) {
+        Some(false)
+    } else if content.contains("@everyone") {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Index one inbound message on the caller's connection or transaction, so it commits atomically
+/// with the event row. Own messages are never indexed.
+pub(crate) fn index_message(
+    conn: &rusqlite::Connection,
+    event_id: &str,
+    chat_id: i64,
+    created_at: u64,
+    content: &str,
+    mine: bool,
+    my_npub: Option<&str>,
+) -> Result<(), String> {
+    let Some(me) = my_npub else { return Ok(()) };
+    if mine {
+        return Ok(());
+    }
+    let Some(everyone) = classify(content, me) else { return Ok(()) };
+    let mut stmt = conn.prepare_cached(
+        "INSERT OR REPLACE INTO mentions (event_id, chat_id, created_at, everyone) VALUES (?1, ?2, ?3, ?4)",
+    ).map_err(|e| format!("prepare index mention: {e}"))?;
+    stmt.execute(params![event_id, chat_id, created_at as i64, everyone as i64])
+        .map_err(|e| format!("index mention: {e}"))?;
+    Ok(())
+}
+
+/// Messages mentioning me across every chat, newest first, one page at a time.
+pub fn get_my_mentions(page: usize, page_size: usize) -> Result<Vec<MentionEntry>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT m.event_id, c.chat_identifier, m.created_at, m.everyone \
+         FROM mentions m JOIN chats c ON c.id = m.chat_id \
+         WHERE c.deleted_at IS NULL \
+         ORDER BY m.created_at DESC LIMIT ?1 OFFSET ?2",
+    ).map_err(|e| format!("prepare get_my_mentions: {e}"))?;
+    let rows = stmt.query_map(params![page_size as i64, (page * page_size) as i64], |r| {
+        Ok(MentionEntry {
+            message_id: r.get(0)?,
+            chat_id: r.get(1)?,
+            at: r.get::<_, i64>(2)? as u64 * 1000,
+            everyone: r.get::<_, i64>(3)? != 0,
+        })
+    }).map_err(|e| format!("query get_my_mentions: {e}"))?;
+    Ok(rows.flatten().collect())
+}
+
+/// Per-chat count of UNREAD mentions (`chat_identifier` → count), using the same read anchor as
+/// [`super::events::unread_counts`]. Chats with none are omitted.
+pub fn unread_mention_counts() -> Result<HashMap<String, u32>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "WITH anchors AS ( \
+            SELECT c.id AS chat_id, c.chat_identifier AS chat_identifier, \
+                   COALESCE(MAX(e.created_at), 0) AS anchor_ts \
+            FROM chats c \
+            LEFT JOIN events e ON e.chat_id = c.id \
+              AND ((e.mine = 1 AND e.kind IN (?1, ?2, ?3)) OR e.id = c.last_read) \
+            WHERE c.deleted_at IS NULL \
+            GROUP BY c.id \
+         ) \
+         SELECT a.chat_identifier, COUNT(*) FROM mentions m JOIN anchors a ON a.chat_id = m.chat_id \
+         WHERE m.created_at > a.anchor_ts GROUP BY a.chat_identifier",
+    ).map_err(|e| format!("prepare unread_mention_counts: {e}"))?;
+    let rows = stmt.query_map(
+        params![
+            event_kind::CHAT_MESSAGE as i32,
+            event_kind::PRIVATE_DIRECT_MESSAGE as i32,
+            event_kind::FILE_ATTACHMENT as i32
+        ],
+        |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)? as u32)),
+    ).map_err(|e| format!("query unread_mention_counts: {e}"))?;
+    Ok(rows.flatten().collect())
+}
+
+/// Unread mentions in ONE chat, same anchor as [`unread_mention_counts`]. Reconciles the `ChatState`
+/// unread cache one chat at a time.
+pub fn unread_mention_count_for_chat(chat_identifier: &str) -> Result<u32, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM mentions m JOIN chats c ON c.id = m.chat_id \
+         WHERE c.chat_identifier = ?4 AND m.created_at > COALESCE(( \
+               SELECT MAX(e.created_at) FROM events e \
+               WHERE e.chat_id = c.id \
+                 AND ((e.mine = 1 AND e.kind IN (?1, ?2, ?3)) OR e.id = c.last_read)), 0)",
+        params![
+            event_kind::CHAT_MESSAGE as i32,
+            event_kind::PRIVATE_DIRECT_MESSAGE as i32,
+            event_kind::FILE_ATTACHMENT as i32,
+            chat_identifier
+        ],
+        |r| r.get(0),
+    ).map_err(|e| format!("query unread_mention_count_for_chat: {e}"))?;
+    Ok(count as u32)
+}
+
+/// Index history saved before the mentions table existed. Runs once per account (flagged in
+/// settings); decryption is async, so it can't live in the sync migration.
+pub async fn backfill_if_needed() -> Result<(), String> {
+    if super::get_sql_setting(BACKFILL_FLAG.to_string())?.is_some() {
+        return Ok(());
+    }
+    let session = crate::state::SessionGuard::capture();
+    let Ok(me) = super::get_current_account() else { return Ok(()) };
+    let rows: Vec<(String, i64, i64, String)> = {
+        let conn = super::get_db_connection_guard_static()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, chat_id, created_at, content FROM events WHERE mine = 0 AND kind IN (?1, ?2)",
+        ).map_err(|e| format!("prepare mention backfill: {e}"))?;
+        let mapped = stmt.query_map(
+            params![event_kind::CHAT_MESSAGE as i32, event_kind::PRIVATE_DIRECT_MESSAGE as i32],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+        ).map_err(|e| format!("query mention backfill: {e}"))?;
+        mapped.flatten().collect()
+    };
+
+    let mut hits: Vec<(String, i64, i64, bool)> = Vec::new();
+    for (id, chat_id, created_at, content) in rows {
+        let Ok(text) = crate::crypto::maybe_decrypt(content).await else { continue };
+        if let Some(everyone) = classify(&text, &me) {
+            hits.push((id, chat_id, created_at, everyone));
+        }
+    }
+    // Decrypting yields, so the account may have changed: these rows (and the done flag) belong to
+    // the old one
+    if !session.is_valid() {
+        return Ok(());
+    }
+
+    let conn = super::get_write_connection_guard_static()?;
+    let tx = conn.unchecked_transaction().map_err(|e| format!("mention backfill tx: {e}"))?;
+    for (id, chat_id, created_at, everyone) in &hits {
+        tx.execute(
+            "INSERT OR IGNORE INTO mentions (event_id, chat_id, created_at, everyone) VALUES (?1, ?2, ?3, ?4)",
+            params![id, chat_id, created_at, *everyone as i64],
+        ).map_err(|e| format!("mention backfill insert: {e}"))?;
+    }
+    tx.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, '1')",
+        params![BACKFILL_FLAG],
+    ).map_err(|e| format!("mention backfill flag: {e}"))?;
+    tx.commit().map_err(|e| format!("mention backfill commit: {e}"))?;
+    Ok(())
+}
@@ -14,6 +14,8 @@ use std::ops::{Deref, DerefMut};
 
 use serde::{Deserialize, Serialize};
 
+use crate::ids::is_npub;
+
 pub mod settings;
 pub mod schema;
 pub mod profiles;
@@ -25,13 +27,27 @@ pub mod wrappers;
 pub mod nip17_keys;
 pub mod community;
 pub mod bots;
+pub mod mentions;
+pub mod social_backup;
+pub mod contact_groups;
+pub mod chat_folders;
+pub mod contacts;
+pub mod chat_list_snapshot;
+pub mod key_audit;
+pub mod profile_relays;
+pub mod peer_capabilities;
+pub mod uploads;
+pub mod pruning;
+pub mod notification_prefs;
 
 pub use settings::{
     get_sql_setting, set_sql_setting, get_pkey, set_pkey, get_seed, set_seed, remove_setting,
+    KeyStorage, PKEY_IN_KEYSTORE, key_storage, set_key_storage, load_keystore_pkey,
+    load_keystore_seed, seed_entry, KEYSTORE_UNSEALABLE,
     get_signer_type, set_signer_type,
     get_bunker_url, set_bunker_url,
     get_bunker_remote_pubkey, set_bunker_remote_pubkey,
-    commit_bunker_account_setup,
+    commit_bunker_account_setup, BunkerPairing, find_bunker_pairing,
     get_nip55_user_pubkey, set_nip55_user_pubkey,
     get_nip55_signer_package, set_nip55_signer_package,
     commit_nip55_account_setup,
@@ -104,19 +120,6 @@ static CURRENT_ACCOUNT: LazyLock<Arc<RwLock<Option<String>>>> = LazyLock::new(||
 /// Filename for the persistent active-account marker. Plain text, just the npub.
 const ACTIVE_ACCOUNT_FILE: &str = "active_account";
 
-/// npub bech32 form: `npub1` + 58 chars from the bech32 alphabet (no `1`, `b`, `i`, `o`).
-fn is_valid_npub(s: &str) -> bool {
-    if s.len() != 63 || !s.starts_with("npub1") {
-        return false;
-    }
-    s.bytes().skip(5).all(|c| matches!(c,
-        b'q' | b'p' | b'z' | b'r' | b'y' | b'9' | b'x' | b'8' |
-        b'g' | b'f' | b'2' | b't' | b'v' | b'd' | b'w' | b'0' |
-        b's' | b'3' | b'j' | b'n' | b'5' | b'4' | b'k' | b'h' |
-        b'c' | b'e' | b'6' | b'm' | b'u' | b'a' | b'7' | b'l'
-    ))
-}
-
 pub fn get_current_account() -> Result<String, String> {
     CURRENT_ACCOUNT.read().unwrap()
         .as_ref().cloned()
@@ -207,7 +210,7 @@ fn read_active_account_file_in(app_data: &std::path::Path) -> Result<Option<Stri
         return Ok(None);
     }
     let npub = buf.trim().to_string();
-    if !is_valid_npub(&npub) {
+    if !is_npub(&npub) {
         return Ok(None);
     }
     // `symlink_metadata` instead of `is_dir()` (which follows links): a
@@ -224,7 +227,7 @@ fn read_active_account_file_in(app_data: &std::path::Path) -> Result<Option<Stri
 }
 
 fn write_active_account_file_in(app_data: &std::path::Path, npub: &str) -> Result<(), String> {
-    if !is_valid_npub(npub) {
+    if !is_npub(npub) {
         return Err(format!("Invalid npub format: {}", npub));
     }
     if !app_data.exists() {
@@ -292,7 +295,7 @@ fn list_account_npubs_in(app_data: &std::path::Path) -> Vec<String> {
         for entry in entries.flatten() {
             if entry.file_type().map_or(false, |ft| ft.is_dir()) {
                 let name = entry.file_name().to_string_lossy().to_string();
-                if is_valid_npub(&name) {
+                if is_npub(&name) {
                     out.push(name);
                 }
             }
@@ -317,22 +320,22 @@ mod active_account_tests {
 
     #[test]
     fn npub_validator_accepts_canonical_form() {
-        assert!(is_valid_npub(VALID_A));
-        assert!(is_valid_npub(VALID_B));
+        assert!(is_npub(VALID_A));
+        assert!(is_npub(VALID_B));
     }
 
     #[test]
     fn npub_validator_rejects_wrong_length() {
-        assert!(!is_valid_npub("npub1abc"));
-        assert!(!is_valid_npub(&format!("{}x", VALID_A)));
-        assert!(!is_valid_npub(""));
+        assert!(!is_npub("npub1abc"));
+        assert!(!is_npub(&format!("{}x", VALID_A)));
+        assert!(!is_npub(""));
     }
 
     #[test]
     fn npub_validator_rejects_missing_prefix() {
         let body = &VALID_A[5..];
-        assert!(!is_valid_npub(&format!("nsec1{}", body)));
-        assert!(!is_valid_npub(&format!("xxxx1{}", body)));
+        assert!(!is_npub(&format!("nsec1{}", body)));
+        assert!(!is_npub(&format!("xxxx1{}", body)));
     }
 
     #[test]
@@ -341,7 +344,7 @@ mod active_account_tests {
         for bad in ['1', 'b', 'i', 'o', 'B', 'I', 'O', '!', '*', ' '] {
             let mut s = String::from(VALID_A);
             s.replace_range(10..11, &bad.to_string());
-            assert!(!is_valid_npub(&s), "should reject character {:?}", bad);
+            assert!(!is_npub(&s), "should reject character {:?}", bad);
         }
     }
 
@@ -467,7 +470,7 @@ mod active_account_tests {
     #[test]
     fn npub_validator_rejects_uppercase_prefix() {
         let upper = format!("NPUB1{}", &VALID_A[5..]);
-        assert!(!is_valid_npub(&upper));
+        assert!(!is_npub(&upper));
     }
 
     #[test]
@@ -689,7 +692,7 @@ impl Drop for WriteConnectionGuard {
 /// otherwise yield `<app_data>/../../etc` and downstream
 /// `remove_dir_all` (delete_account, logout) would walk arbitrary dirs.
 pub fn account_dir(npub: &str) -> Result<PathBuf, String> {
-    if !is_valid_npub(npub) {
+    if !is_npub(npub) {
         return Err(format!("Invalid npub format: {}", npub));
     }
     Ok(get_app_data_dir()?.join(npub))
@@ -831,6 +834,12 @@ pub fn init_database(npub: &str) -> Result<(), String> {
         crate::tor::set_tor_enabled_pref(enabled);
     }
 
+    // Same for the user's SOCKS proxy, for the same reason.
+    match create_connection(&db_path) {
+        Ok(conn) => crate::proxy::init_from_conn(&conn),
+        Err(_) => crate::proxy::set_config(None),
+    }
+
     Ok(())
 }
 
@@ -839,6 +848,8 @@ pub fn init_database(npub: &str) -> Result<(), String> {
 /// check and discard the connection instead of returning it to the
 /// (now-cleared) pool.
 pub fn close_database() {
+    // Write out pending key-audit entries while this account's DB is still the current one
+    crate::key_audit::flush();
     bump_pool_generation();
     if let Ok(mut pool) = DB_READ_POOL.lock() {
         pool.clear();
@@ -867,7 +878,7 @@ pub fn get_accounts() -> Result<Vec<String>, String> {
         for entry in entries.flatten() {
             if entry.file_type().map_or(false, |ft| ft.is_dir()) {
                 let name = entry.file_name().to_string_lossy().to_string();
-                if name.starts_with("npub1") {
+                if is_npub(&name) {
                     // Check if vector.db exists
                     if entry.path().join("vector.db").exists() {
                         accounts.push(name);
@@ -882,7 +893,7 @@ pub fn get_accounts() -> Result<Vec<String>, String> {
 
 /// Get the profile directory path for a given npub.
 pub fn get_profile_directory(npub: &str) -> Result<PathBuf, String> {
-    if !npub.starts_with("npub1") {
+    if !is_npub(npub) {
         return Err(format!("Invalid npub format: {}", npub));
     }
     let dir = account_dir(npub)?;
@@ -910,6 +921,7 @@ pub fn clear_id_caches() {
     id_cache::clear_id_caches();
     community::clear_banlist_cache();
     community::clear_channel_community_cache();
+    community::clear_warm_communities();
 }
 
 // ============================================================================
@@ -0,0 +1,101 @@
+//! Per-chat notification preferences, finer than the chat's all-or-nothing `muted` flag.
+//!
+//! A chat can notify only when it pings us (mentions-only), be silenced until a point in time
+//! (mute for 1h / 8h / a week, which lapses by itself), and post without sound. None of these
+//! touch the unread badge; `muted` still owns that. A chat with every default has no row.
+
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChatNotificationPrefs {
+    /// Notify only for a direct @mention, a reply to us, or an authorized @everyone.
+    pub mentions_only: bool,
+    /// Secs; no notifications before this. 0 = not muted.
+    pub muted_until: u64,
+    /// Play the notification sound.
+    pub sound: bool,
+}
+
+impl Default for ChatNotificationPrefs {
+    fn default() -> Self {
+        Self { mentions_only: false, muted_until: 0, sound: true }
+    }
+}
+
+impl ChatNotificationPrefs {
+    /// Whether a notification is shown at `now` (secs), and if so with sound. `ping` is true when
+    /// the message is addressed to us (every DM is).
+    pub fn decide(&self, ping: bool, now: u64) -> Option<bool> {
+        if now < self.muted_until || (self.mentions_only && !ping) {
+            return None;
+        }
+        Some(self.sound)
+    }
+}
+
+/// `chat_identifier`'s preferences (the defaults when none are set).
+pub fn get(chat_identifier: &str) -> ChatNotificationPrefs {
+    let Ok(conn) = super::get_db_connection_guard_static() else { return ChatNotificationPrefs::default() };
+    conn.query_row(
+        "SELECT mentions_only, muted_until, sound FROM chat_notification_prefs WHERE chat_identifier = ?1",
+        params![chat_identifier],
+        |r| Ok(ChatNotificationPrefs {
+            mentions_only: r.get::<_, i32>(0)? != 0,
+            muted_until: r.get::<_, i64>(1)?.max(0) as u64,
+            sound: r.get::<_, i32>(2)? != 0,
+        }),
+    ).optional().ok().flatten().unwrap_or_default()
+}
+
+/// Store `chat_identifier`'s preferences. Setting the defaults removes the row.
+pub fn set(chat_identifier: &str, prefs: &ChatNotificationPrefs) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    if *prefs == ChatNotificationPrefs::default() {
+        conn.execute("DELETE FROM chat_notification_prefs WHERE chat_identifier = ?1", params![chat_identifier])
+            .map_err(|e| format!("Failed to clear notification preferences: {}", e))?;
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT INTO chat_notification_prefs (chat_identifier, mentions_only, muted_until, sound) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(chat_identifier) DO UPDATE SET
+            mentions_only = excluded.mentions_only, muted_until = excluded.muted_until, sound = excluded.sound",
+        params![chat_identifier, prefs.mentions_only as i32, prefs.muted_until as i64, prefs.sound as i32],
+    ).map_err(|e| format!("Failed to save notification preferences: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timed_mute_lapses_and_mentions_only_lets_pings_through() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        crate::db::clear_id_caches();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+
+        assert_eq!(get("npub1a"), ChatNotificationPrefs::default());
+        assert_eq!(ChatNotificationPrefs::default().decide(false, 0), Some(true));
+
+        let prefs = ChatNotificationPrefs { mentions_only: true, muted_until: 1_000 + 3_600, sound: false };
+        set("npub1a", &prefs).unwrap();
+        let stored = get("npub1a");
+        assert_eq!(stored, prefs);
+        assert_eq!(stored.decide(true, 1_000), None, "muted for the hour, pings included");
+        assert_eq!(stored.decide(true, 1_000 + 3_600), Some(false), "the mute lapses; silently");
+        assert_eq!(stored.decide(false, 1_000 + 3_600), None, "mentions-only");
+
+        set("npub1a", &ChatNotificationPrefs::default()).unwrap();
+        let rows: i64 = crate::db::get_db_connection_guard_static().unwrap()
+            .query_row("SELECT COUNT(*) FROM chat_notification_prefs", [], |r| r.get(0)).unwrap();
+        assert_eq!(rows, 0, "defaults keep no row");
+    }
+}
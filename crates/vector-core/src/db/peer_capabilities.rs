@@ -0,0 +1,50 @@
+//! Capabilities contacts' clients advertise in their DM rumors (see `compression`).
+//!
+//! A contact can run several clients, and one that advertises a capability says nothing about the
+//! others, so rows are kept per device (`""` for clients that don't name theirs). Each sighting
+//! also stamps a [`SEEN`] row, so a device that stops advertising something stops counting as
+//! supporting it.
+
+use rusqlite::params;
+
+/// The per-device row stamped on every sighting, advertised capabilities or not.
+const SEEN: &str = "";
+
+/// Devices not heard from within this long of the contact's latest are assumed retired.
+const ACTIVE_WINDOW_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// Remember that `npub`'s `device` was seen at `seen_at` advertising `capabilities`. Returns
+/// whether anything was written.
+pub fn record(npub: &str, device: &str, capabilities: &[&str], seen_at: u64) -> Result<bool, String> {
+    let conn = super::get_write_connection_guard_static()?;
+    let mut changed = 0;
+    for capability in std::iter::once(SEEN).chain(capabilities.iter().copied()) {
+        changed += conn.execute(
+            "INSERT INTO peer_capabilities (npub, device, capability, seen_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(npub, device, capability) DO UPDATE SET seen_at = excluded.seen_at
+             WHERE excluded.seen_at > peer_capabilities.seen_at",
+            params![npub, device, capability, seen_at as i64],
+        ).map_err(|e| format!("Failed to save peer capability: {}", e))?;
+    }
+    Ok(changed > 0)
+}
+
+/// Whether every active device of `npub` advertised `capability` the last time it was seen.
+pub fn has(npub: &str, capability: &str) -> bool {
+    let Ok(conn) = super::get_db_connection_guard_static() else { return false };
+    conn.query_row(
+        "WITH devices AS (
+             SELECT device, MAX(seen_at) AS seen FROM peer_capabilities WHERE npub = ?1 GROUP BY device
+         ),
+         active AS (
+             SELECT device, seen FROM devices WHERE seen >= (SELECT MAX(seen) FROM devices) - ?3
+         )
+         SELECT COUNT(*) > 0 AND COUNT(*) = SUM(EXISTS(
+             SELECT 1 FROM peer_capabilities p
+             WHERE p.npub = ?1 AND p.device = active.device AND p.capability = ?2 AND p.seen_at >= active.seen
+         ))
+         FROM active",
+        params![npub, capability, ACTIVE_WINDOW_SECS as i64],
+        |row| row.get::<_, bool>(0),
+    ).unwrap_or(false)
+}
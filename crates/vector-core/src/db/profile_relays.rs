@@ -0,0 +1,170 @@
+//! Contacts' NIP-65 relay lists — the read relays each profile advertises in its kind 10002,
+//! kept so outgoing gift wraps can reach them (see `inbox_relays::resolve_gift_wrap_targets`) —
+//! and the per-contact override of where their gift wraps go ([`ContactRelays`]).
+
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Most relays a manual delivery list may hold.
+pub const MAX_MANUAL_RELAYS: usize = 8;
+
+/// Where gift wraps (DMs, typing) to a contact are published.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(tag = "mode", content = "relays", rename_all = "snake_case")]
+pub enum ContactRelays {
+    /// Their 10050 inbox, else our write relays plus their NIP-65 read relays.
+    #[default]
+    Auto,
+    /// Exactly these relays, whatever the contact advertises.
+    Manual(Vec<String>),
+    /// Vector's trusted relays only.
+    Trusted,
+}
+
+impl ContactRelays {
+    fn to_columns(&self) -> (&'static str, String) {
+        match self {
+            Self::Auto => ("auto", "[]".to_string()),
+            Self::Manual(relays) => ("manual", serde_json::to_string(relays).unwrap_or_else(|_| "[]".to_string())),
+            Self::Trusted => ("trusted", "[]".to_string()),
+        }
+    }
+
+    fn from_columns(mode: &str, relays: &str) -> Self {
+        match mode {
+            "manual" => match serde_json::from_str::<Vec<String>>(relays) {
+                Ok(list) if !list.is_empty() => Self::Manual(list),
+                _ => Self::Auto,
+            },
+            "trusted" => Self::Trusted,
+            _ => Self::Auto,
+        }
+    }
+}
+
+/// Store `npub`'s read relays from a list created at `created_at`. An older revision than the one
+/// already stored (a lagging relay) is ignored. Returns whether anything was written.
+pub fn set_read_relays(npub: &str, relays: &[String], created_at: u64) -> Result<bool, String> {
+    let json = serde_json::to_string(relays).map_err(|e| e.to_string())?;
+    let conn = super::get_write_connection_guard_static()?;
+    let changed = conn.execute(
+        "INSERT INTO profile_relays (npub, read_relays, list_created_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(npub) DO UPDATE SET read_relays = excluded.read_relays, list_created_at = excluded.list_created_at
+         WHERE excluded.list_created_at > profile_relays.list_created_at",
+        params![npub, json, created_at as i64],
+    ).map_err(|e| format!("Failed to save relay list: {}", e))?;
+    Ok(changed > 0)
+}
+
+/// `npub`'s stored read relays (empty when they haven't published a list we've seen).
+pub fn get_read_relays(npub: &str) -> Result<Vec<String>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let json: Option<String> = conn.query_row(
+        "SELECT read_relays FROM profile_relays WHERE npub = ?1",
+        params![npub],
+        |row| row.get(0),
+    ).ok();
+    Ok(json.and_then(|j| serde_json::from_str(&j).ok()).unwrap_or_default())
+}
+
+/// `npub`'s delivery preference ([`ContactRelays::Auto`] unless one was set).
+pub fn get_delivery(npub: &str) -> ContactRelays {
+    let Ok(conn) = super::get_db_connection_guard_static() else { return ContactRelays::default() };
+    conn.query_row(
+        "SELECT delivery_mode, manual_relays FROM profile_relays WHERE npub = ?1",
+        params![npub],
+        |row| Ok(ContactRelays::from_columns(&row.get::<_, String>(0)?, &row.get::<_, String>(1)?)),
+    ).optional().ok().flatten().unwrap_or_default()
+}
+
+/// Store `npub`'s delivery preference. A manual list must be non-empty, at most
+/// [`MAX_MANUAL_RELAYS`] long, and `wss://` (or `ws://`) URLs; duplicates are dropped.
+pub fn set_delivery(npub: &str, delivery: &ContactRelays) -> Result<ContactRelays, String> {
+    let delivery = match delivery {
+        ContactRelays::Manual(relays) => {
+            let mut list: Vec<String> = Vec::new();
+            for relay in relays {
+                let url = nostr_sdk::RelayUrl::parse(relay.trim())
+                    .map_err(|_| format!("Invalid relay URL: {}", relay))?
+                    .to_string();
+                let norm = crate::inbox_relays::normalize_relay_url(&url);
+                if !list.iter().any(|u| crate::inbox_relays::normalize_relay_url(u) == norm) {
+                    list.push(url);
+                }
+            }
+            if list.is_empty() {
+                return Err("A manual relay list needs at least one relay".to_string());
+            }
+            if list.len() > MAX_MANUAL_RELAYS {
+                return Err(format!("At most {} relays per contact", MAX_MANUAL_RELAYS));
+            }
+            ContactRelays::Manual(list)
+        }
+        other => other.clone(),
+    };
+    let (mode, relays) = delivery.to_columns();
+    let conn = super::get_write_connection_guard_static()?;
+    // A contact we hold no NIP-65 list for gets a row at revision 0, so their first list still lands
+    conn.execute(
+        "INSERT INTO profile_relays (npub, list_created_at, delivery_mode, manual_relays) VALUES (?1, 0, ?2, ?3)
+         ON CONFLICT(npub) DO UPDATE SET delivery_mode = excluded.delivery_mode, manual_relays = excluded.manual_relays",
+        params![npub, mode, relays],
+    ).map_err(|e| format!("Failed to save contact relays: {}", e))?;
+    Ok(delivery)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newest_list_wins() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+
+        let contact = "npub1contact";
+        assert!(get_read_relays(contact).unwrap().is_empty());
+        let newer = vec!["wss://b.example".to_string()];
+        assert!(set_read_relays(contact, &newer, 200).unwrap());
+        assert!(!set_read_relays(contact, &["wss://a.example".to_string()], 100).unwrap());
+        assert_eq!(get_read_relays(contact).unwrap(), newer);
+    }
+
+    #[test]
+    fn delivery_preference_survives_list_updates() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+
+        let contact = "npub1contact";
+        assert_eq!(get_delivery(contact), ContactRelays::Auto);
+        assert!(set_delivery(contact, &ContactRelays::Manual(vec![])).is_err());
+        assert!(set_delivery(contact, &ContactRelays::Manual(vec!["https://nope".to_string()])).is_err());
+
+        let manual = set_delivery(contact, &ContactRelays::Manual(vec![
+            "wss://a.example".to_string(),
+            "wss://a.example/".to_string(),
+        ])).unwrap();
+        assert!(matches!(&manual, ContactRelays::Manual(list) if list.len() == 1), "duplicates collapse");
+        assert_eq!(get_delivery(contact), manual);
+
+        // Their first NIP-65 list still lands after the override created the row, and keeps it
+        assert!(set_read_relays(contact, &["wss://b.example".to_string()], 100).unwrap());
+        assert_eq!(get_delivery(contact), manual);
+
+        set_delivery(contact, &ContactRelays::Trusted).unwrap();
+        assert_eq!(get_delivery(contact), ContactRelays::Trusted);
+        assert_eq!(get_read_relays(contact).unwrap(), vec!["wss://b.example".to_string()]);
+    }
+}
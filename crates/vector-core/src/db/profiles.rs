@@ -9,10 +9,16 @@ pub fn get_all_profiles() -> Result<Vec<SlimProfile>, String> {
     let mut stmt = conn.prepare(
         "SELECT npub, name, display_name, nickname, lud06, lud16, banner, avatar, \
          about, website, nip05, status_content, status_url, bot, avatar_cached, \
-         banner_cached, is_blocked FROM profiles"
+         banner_cached, is_blocked, status_emoji, status_expires_at, last_active, contact_verified FROM profiles"
     ).map_err(|e| format!("Failed to prepare statement: {}", e))?;
 
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs()).unwrap_or(0);
     let profiles = stmt.query_map([], |row| {
+        // A status that lapsed while we were offline loads as cleared.
+        let expires_at = row.get::<_, i64>(18).unwrap_or(0).max(0) as u64;
+        let expired = Status::is_expired_at(expires_at, now);
         Ok(SlimProfile {
             id: row.get(0)?,
             name: row.get(1)?,
@@ -25,12 +31,19 @@ pub fn get_all_profiles() -> Result<Vec<SlimProfile>, String> {
             about: row.get(8)?,
             website: row.get(9)?,
             nip05: row.get(10)?,
-            status: Status {
-                title: row.get(11)?,
-                purpose: String::new(),
-                url: row.get(12)?,
+            status: if expired { Status::new() } else {
+                Status {
+                    title: row.get(11)?,
+                    purpose: String::new(),
+                    url: row.get(12)?,
+                    emoji: row.get(17).unwrap_or_default(),
+                    expires_at,
+                }
             },
             last_updated: 0,
+            last_active: row.get::<_, i64>(19).unwrap_or(0).max(0) as u64,
+            last_seen: 0,
+            online: false,
             mine: false,
             bot: row.get::<_, i32>(13)? != 0,
             avatar_cached: {
@@ -42,6 +55,10 @@ pub fn get_all_profiles() -> Result<Vec<SlimProfile>, String> {
                 if !p.is_empty() && !std::path::Path::new(&p).exists() { String::new() } else { p }
             },
             is_blocked: row.get::<_, i32>(16).unwrap_or(0) != 0,
+            contact_verified: row.get::<_, i32>(20).unwrap_or(0) != 0,
+            verified: None,
+            zaps: Default::default(),
+            details_deferred: false,
         })
     })
     .map_err(|e| format!("Failed to query profiles: {}", e))?
@@ -57,8 +74,9 @@ pub fn set_profile(profile: &SlimProfile) -> Result<(), String> {
 
     conn.execute(
         "INSERT INTO profiles (npub, name, display_name, nickname, lud06, lud16, banner, avatar, \
-         about, website, nip05, status_content, status_url, bot, avatar_cached, banner_cached, is_blocked) \
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17) \
+         about, website, nip05, status_content, status_url, bot, avatar_cached, banner_cached, is_blocked, \
+         status_emoji, status_expires_at, last_active, contact_verified) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21) \
          ON CONFLICT(npub) DO UPDATE SET \
             name = excluded.name, display_name = excluded.display_name, \
             nickname = excluded.nickname, lud06 = excluded.lud06, lud16 = excluded.lud16, \
@@ -66,7 +84,9 @@ pub fn set_profile(profile: &SlimProfile) -> Result<(), String> {
             website = excluded.website, nip05 = excluded.nip05, \
             status_content = excluded.status_content, status_url = excluded.status_url, \
             bot = excluded.bot, avatar_cached = excluded.avatar_cached, \
-            banner_cached = excluded.banner_cached, is_blocked = excluded.is_blocked",
+            banner_cached = excluded.banner_cached, is_blocked = excluded.is_blocked, \
+            status_emoji = excluded.status_emoji, status_expires_at = excluded.status_expires_at, \
+            last_active = excluded.last_active, contact_verified = excluded.contact_verified",
         rusqlite::params![
             profile.id,
             profile.name,
@@ -85,6 +105,10 @@ pub fn set_profile(profile: &SlimProfile) -> Result<(), String> {
             profile.avatar_cached,
             profile.banner_cached,
             profile.is_blocked as i32,
+            profile.status.emoji,
+            profile.status.expires_at as i64,
+            profile.last_active as i64,
+            profile.contact_verified as i32,
         ],
     ).map_err(|e| format!("Failed to insert profile: {}", e))?;
 
@@ -0,0 +1,231 @@
+//! Stale-data pruning — one TTL sweep over the tables that would otherwise grow without bound.
+//!
+//! Each [`Policy`] names a table, what makes a row stale, and how long a row is kept. The daily
+//! maintenance pass ([`prune_if_due`]) applies them all and records what it removed, which
+//! `get_database_stats` reports alongside the table sizes.
+//!
+//! Deliberately not covered: the `processed_wrappers` ledger (its NIP-17 rows are the negentropy
+//! fingerprint, so pruning them re-downloads history; its Concord rows are the only replay guard
+//! for control envelopes), and tables already bounded where they're written (`emoji_usage`,
+//! `key_audit`, `marketplace_cache`, the retained resend bodies in `nip17_wrap_keys`).
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use crate::clock::now_secs;
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Settings key holding the last run's [`PruneStats`].
+const STATS_KEY: &str = "pruning_stats";
+
+/// How often the sweep runs.
+const INTERVAL_SECS: u64 = DAY_SECS;
+
+/// One cache's retention rule. `stale` is a `WHERE` clause over the table with the cutoff (secs)
+/// as `?1`; `before` runs first, for dependent rows keyed off the stale ones.
+pub struct Policy {
+    pub name: &'static str,
+    table: &'static str,
+    stale: &'static str,
+    before: Option<&'static str>,
+    pub ttl_secs: u64,
+}
+
+pub const POLICIES: &[Policy] = &[
+    // A contact's client is re-learned from their next message
+    Policy {
+        name: "peer_capabilities",
+        table: "peer_capabilities",
+        stale: "seen_at < ?1",
+        before: None,
+        ttl_secs: 180 * DAY_SECS,
+    },
+    // Rows go stale (re-probed) after 4 days; servers since removed or never used again linger
+    Policy {
+        name: "blossom_capabilities",
+        table: "blossom_server_capabilities",
+        stale: "updated_at < ?1",
+        before: None,
+        ttl_secs: 30 * DAY_SECS,
+    },
+    // Settled invites: the invitee joined, so there's nothing left to re-send
+    Policy {
+        name: "invite_deliveries",
+        table: "community_invite_deliveries",
+        stale: "joined_at IS NOT NULL AND joined_at < ?1",
+        before: None,
+        ttl_secs: 90 * DAY_SECS,
+    },
+    // Interrupted uploads past resuming (also swept when the next upload starts)
+    Policy {
+        name: "upload_sessions",
+        table: "upload_sessions",
+        stale: "started_at < ?1",
+        before: Some("DELETE FROM upload_chunks WHERE file_hash IN (SELECT file_hash FROM upload_sessions WHERE started_at < ?1)"),
+        ttl_secs: 7 * DAY_SECS,
+    },
+    // Past their TTL a preview is refetched rather than reused, so the row is dead weight
+    Policy {
+        name: "link_previews",
+        table: "link_previews",
+        stale: "fetched_at < ?1",
+        before: None,
+        ttl_secs: crate::link_preview::CACHE_TTL_SECS,
+    },
+];
+
+/// One policy's numbers, as of the last run.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PolicyStats {
+    pub name: String,
+    pub ttl_days: u64,
+    /// Rows removed by the last run.
+    pub pruned_last: u64,
+    /// Rows removed since the stats began.
+    pub pruned_total: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PruneStats {
+    /// Secs; 0 = never.
+    pub last_run_at: u64,
+    pub policies: Vec<PolicyStats>,
+}
+
+/// The last run's stats (empty before the first).
+pub fn load_stats() -> PruneStats {
+    super::settings::get_sql_setting(STATS_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Apply every policy as of `now` (secs), in one transaction, and record the result.
+pub fn prune_at(now: u64) -> Result<PruneStats, String> {
+    let mut stats = load_stats();
+    {
+        let mut conn = super::get_write_connection_guard_static()?;
+        let tx = conn.transaction().map_err(|e| format!("Failed to begin prune: {}", e))?;
+        for policy in POLICIES {
+            let cutoff = now.saturating_sub(policy.ttl_secs) as i64;
+            if let Some(sql) = policy.before {
+                tx.execute(sql, params![cutoff])
+                    .map_err(|e| format!("Failed to prune {}: {}", policy.name, e))?;
+            }
+            let pruned = tx
+                .execute(&format!("DELETE FROM {} WHERE {}", policy.table, policy.stale), params![cutoff])
+                .map_err(|e| format!("Failed to prune {}: {}", policy.name, e))? as u64;
+
+            let entry = match stats.policies.iter_mut().position(|p| p.name == policy.name) {
+                Some(i) => &mut stats.policies[i],
+                None => {
+                    stats.policies.push(PolicyStats { name: policy.name.to_string(), ..Default::default() });
+                    stats.policies.last_mut().expect("just pushed")
+                }
+            };
+            entry.ttl_days = policy.ttl_secs / DAY_SECS;
+            entry.pruned_last = pruned;
+            entry.pruned_total += pruned;
+        }
+        tx.commit().map_err(|e| format!("Failed to commit prune: {}", e))?;
+    }
+    stats.policies.retain(|p| POLICIES.iter().any(|policy| policy.name == p.name));
+    stats.last_run_at = now;
+    let json = serde_json::to_string(&stats).map_err(|e| e.to_string())?;
+    super::settings::set_sql_setting(STATS_KEY.to_string(), json)?;
+    Ok(stats)
+}
+
+/// Run the sweep if the last one was over a day ago. Returns the stats when it ran.
+pub fn prune_if_due() -> Result<Option<PruneStats>, String> {
+    let now = now_secs();
+    if now.saturating_sub(load_stats().last_run_at) < INTERVAL_SECS {
+        return Ok(None);
+    }
+    prune_at(now).map(Some)
+}
+
+/// Row counts per policy table, for the stats view.
+pub fn table_rows() -> Result<Vec<(&'static str, u64)>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    POLICIES
+        .iter()
+        .map(|p| {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", p.table), [], |r| r.get::<_, i64>(0))
+                .map(|n| (p.name, n as u64))
+                .map_err(|e| format!("Failed to count {}: {}", p.table, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_year_of_accumulation_is_cut_back_to_each_ttl() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        crate::db::clear_id_caches();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+
+        // One row per table per day, 365 days back from `now` (day 0 is today)
+        let now = 400 * DAY_SECS;
+        {
+            let conn = crate::db::get_write_connection_guard_static().unwrap();
+            for day in 0..365u64 {
+                let at = (now - day * DAY_SECS) as i64;
+                conn.execute(
+                    "INSERT INTO peer_capabilities (npub, capability, seen_at) VALUES (?1, 'zstd', ?2)",
+                    params![format!("npub{}", day), at],
+                ).unwrap();
+                conn.execute(
+                    "INSERT INTO blossom_server_capabilities (server_url, mime_type, outcome, updated_at) VALUES (?1, 'image/png', 1, ?2)",
+                    params![format!("https://s{}.example", day), at],
+                ).unwrap();
+                // Every other invitee joined; pending ones are never pruned
+                conn.execute(
+                    "INSERT INTO community_invite_deliveries (community_id, invitee, first_sent_at, last_attempt_at, joined_at) VALUES ('c', ?1, ?2, ?2, ?3)",
+                    params![format!("{:064x}", day), at, (day % 2 == 0).then_some(at)],
+                ).unwrap();
+                conn.execute(
+                    "INSERT INTO upload_sessions (file_hash, key, nonce, started_at) VALUES (?1, 'k', 'n', ?2)",
+                    params![format!("h{}", day), at],
+                ).unwrap();
+                conn.execute(
+                    "INSERT INTO upload_chunks (file_hash, idx, sha256, url) VALUES (?1, 0, 's', 'u')",
+                    params![format!("h{}", day)],
+                ).unwrap();
+            }
+        }
+
+        let stats = prune_at(now).unwrap();
+        let pruned = |name: &str| stats.policies.iter().find(|p| p.name == name).unwrap().pruned_last;
+        // Rows exactly at the cutoff stay: day N is kept for a TTL of N days
+        assert_eq!(pruned("peer_capabilities"), 365 - 181);
+        assert_eq!(pruned("blossom_capabilities"), 365 - 31);
+        assert_eq!(pruned("invite_deliveries"), (91..365).filter(|d| d % 2 == 0).count() as u64);
+        assert_eq!(pruned("upload_sessions"), 365 - 8);
+
+        let rows: std::collections::HashMap<_, _> = table_rows().unwrap().into_iter().collect();
+        assert_eq!(rows["peer_capabilities"], 181);
+        assert_eq!(rows["invite_deliveries"], 365 - pruned("invite_deliveries"));
+        let chunks: i64 = crate::db::get_db_connection_guard_static().unwrap()
+            .query_row("SELECT COUNT(*) FROM upload_chunks", [], |r| r.get(0)).unwrap();
+        assert_eq!(chunks, 8, "chunks go with their session");
+
+        // A month later only that month's worth ages out, and the totals add up
+        let again = prune_at(now + 30 * DAY_SECS).unwrap();
+        let peer = again.policies.iter().find(|p| p.name == "peer_capabilities").unwrap();
+        assert_eq!((peer.pruned_last, peer.pruned_total), (30, 365 - 181 + 30));
+        assert_eq!(load_stats(), again);
+        assert_eq!(again.last_run_at, now + 30 * DAY_SECS);
+    }
+}
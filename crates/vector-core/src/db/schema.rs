@@ -1018,5 +1018,483 @@ pub fn run_migrations(conn: &mut rusqlite::Connection) -> Result<(), String> {
         Ok(())
     })?;
 
+    // Per-chat media gallery: a persisted media_kind so "all images in this chat" is an indexed
+    // filter instead of a per-row extension parse. Backfilled with the same classifier the insert
+    // path uses.
+    run_atomic_migration(conn, 77, "Add media_kind to attachments", |tx| {
+        tx.execute_batch(
+            "ALTER TABLE attachments ADD COLUMN media_kind INTEGER NOT NULL DEFAULT 3;
+             CREATE INDEX IF NOT EXISTS idx_attachments_kind ON attachments(media_kind, event_id);"
+        ).map_err(|e| format!("add media_kind: {}", e))?;
+        let rows: Vec<(i64, String, String)> = {
+            let mut stmt = tx.prepare("SELECT id, extension, name FROM attachments")
+                .map_err(|e| format!("prepare media_kind backfill: {}", e))?;
+            let mapped = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+                .map_err(|e| format!("query media_kind backfill: {}", e))?;
+            mapped.filter_map(|r| r.ok()).collect()
+        };
+        for (id, extension, name) in rows {
+            let kind = super::attachments::MediaKind::classify(&extension, &name, false);
+            tx.execute("UPDATE attachments SET media_kind=?1 WHERE id=?2", rusqlite::params![kind as i64, id])
+                .map_err(|e| format!("backfill media_kind: {}", e))?;
+        }
+        Ok(())
+    })?;
+
+    // Mentions index: only messages pinging the account owner (by npub or @everyone) are recorded —
+    // a full who-mentions-whom table would be a plaintext leak of encrypted content. Existing history
+    // is indexed lazily by `mentions::backfill_if_needed`, since decryption can't run in a migration.
+    run_atomic_migration(conn, 78, "Add mentions index", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS mentions (
+                event_id   TEXT PRIMARY KEY,
+                chat_id    INTEGER NOT NULL,
+                created_at INTEGER NOT NULL,
+                everyone   INTEGER NOT NULL DEFAULT 0,
+                FOREIGN KEY (event_id) REFERENCES events(id) ON DELETE CASCADE,
+                FOREIGN KEY (chat_id) REFERENCES chats(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_mentions_chat_time ON mentions(chat_id, created_at DESC);
+            CREATE INDEX IF NOT EXISTS idx_mentions_time      ON mentions(created_at DESC);"
+        ).map_err(|e| format!("create mentions: {}", e))?;
+        Ok(())
+    })?;
+
+    // Live-status emoji + NIP-40 expiry, so an expiring status survives restarts and a lapsed one
+    // loads as cleared instead of lingering until the next relay fetch.
+    run_atomic_migration(conn, 79, "Add status emoji and expiry to profiles", |tx| {
+        tx.execute_batch(
+            "ALTER TABLE profiles ADD COLUMN status_emoji TEXT NOT NULL DEFAULT '';
+             ALTER TABLE profiles ADD COLUMN status_expires_at INTEGER NOT NULL DEFAULT 0;"
+        ).map_err(|e| format!("add status expiry: {}", e))?;
+        Ok(())
+    })?;
+
+    // Approximate per-contact "last active", cached so it shows before the next profile sync.
+    run_atomic_migration(conn, 80, "Add last_active to profiles", |tx| {
+        tx.execute_batch("ALTER TABLE profiles ADD COLUMN last_active INTEGER NOT NULL DEFAULT 0;")
+            .map_err(|e| format!("add last_active: {}", e))?;
+        Ok(())
+    })?;
+
+    // Social backup (guardian side): seed shares held for contacts + recovery requests awaiting approval.
+    run_atomic_migration(conn, 81, "Add social backup tables", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS social_backup_shares (
+                owner_npub TEXT PRIMARY KEY,
+                backup_id TEXT NOT NULL,
+                share TEXT NOT NULL,
+                received_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS social_backup_requests (
+                requester_npub TEXT PRIMARY KEY,
+                owner_npub TEXT NOT NULL,
+                code TEXT NOT NULL,
+                requested_at INTEGER NOT NULL
+            );"
+        ).map_err(|e| format!("create social backup tables: {}", e))?;
+        Ok(())
+    })?;
+
+    // Contact groups: user-defined labels ("Family", "Work") for organizing DM contacts.
+    // Purely local and unrelated to group chats.
+    run_atomic_migration(conn, 82, "Add contact groups", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS contact_groups (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                created_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS contact_group_members (
+                group_id INTEGER NOT NULL,
+                npub TEXT NOT NULL,
+                PRIMARY KEY (group_id, npub)
+            );
+            CREATE INDEX IF NOT EXISTS idx_contact_group_members_npub ON contact_group_members(npub);"
+        ).map_err(|e| format!("create contact groups: {}", e))?;
+        Ok(())
+    })?;
+
+    // Chat-list snapshot: the last boot's chat list (light profiles + last message per chat),
+    // encrypted like message content, so a cold start can paint before the full load finishes.
+    run_atomic_migration(conn, 83, "Add chat list snapshot", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS chat_list_snapshot (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                payload TEXT NOT NULL,
+                written_at INTEGER NOT NULL
+            );"
+        ).map_err(|e| format!("create chat list snapshot: {}", e))?;
+        Ok(())
+    })?;
+
+    // Attachment archiving: a downloaded file moved out to a user-chosen folder keeps its original
+    // download path here so it can be restored (NULL = not archived).
+    run_atomic_migration(conn, 84, "Add attachment archived_from", |tx| {
+        tx.execute_batch("ALTER TABLE attachments ADD COLUMN archived_from TEXT;")
+            .map_err(|e| format!("add archived_from: {}", e))?;
+        Ok(())
+    })?;
+
+    // Key-usage audit log: coalesced uses of the nsec / encryption key. The payload (op, subsystem,
+    // counts) is encrypted at rest; last_at stays plaintext for paging and pruning.
+    run_atomic_migration(conn, 85, "Add key usage audit log", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS key_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                last_at INTEGER NOT NULL,
+                payload TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_key_audit_last_at ON key_audit(last_at);"
+        ).map_err(|e| format!("create key audit: {}", e))?;
+        Ok(())
+    })?;
+
+    // Contacts' NIP-65 (kind 10002) read relays, learned during profile sync, so gift wraps can
+    // reach a recipient who hasn't published a 10050 inbox list. Newest list wins.
+    run_atomic_migration(conn, 86, "Add profile relay lists", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS profile_relays (
+                npub TEXT PRIMARY KEY,
+                read_relays TEXT NOT NULL DEFAULT '[]',
+                list_created_at INTEGER NOT NULL
+            );"
+        ).map_err(|e| format!("create profile relays: {}", e))?;
+        Ok(())
+    })?;
+
+    // Community invites we sent: per invitee, how many times the invite was published, whether a
+    // relay accepted it, and when they showed up as a member. Undelivered invites are re-sent on a
+    // timer. Invitees are hex pubkeys, like the banlist.
+    run_atomic_migration(conn, 87, "Add community invite deliveries", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS community_invite_deliveries (
+                community_id TEXT NOT NULL,
+                invitee TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                first_sent_at INTEGER NOT NULL,
+                last_attempt_at INTEGER NOT NULL,
+                delivered_at INTEGER,
+                joined_at INTEGER,
+                PRIMARY KEY (community_id, invitee)
+            );"
+        ).map_err(|e| format!("create community invite deliveries: {}", e))?;
+        Ok(())
+    })?;
+
+    // Capabilities contacts' clients advertise in their DM rumors (e.g. `zstd`: they can read
+    // compressed content), so we only send what the recipient can decode.
+    run_atomic_migration(conn, 88, "Add peer capabilities", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS peer_capabilities (
+                npub TEXT NOT NULL,
+                capability TEXT NOT NULL,
+                seen_at INTEGER NOT NULL,
+                PRIMARY KEY (npub, capability)
+            );"
+        ).map_err(|e| format!("create peer capabilities: {}", e))?;
+        Ok(())
+    })?;
+
+    // View-once attachments: flagged by the sender, and stamped `viewed_at` once the receiver has
+    // opened (and so deleted) their copy.
+    run_atomic_migration(conn, 89, "Add view-once attachments", |tx| {
+        tx.execute_batch(
+            "ALTER TABLE attachments ADD COLUMN view_once INTEGER NOT NULL DEFAULT 0;
+             ALTER TABLE attachments ADD COLUMN viewed_at INTEGER;"
+        ).map_err(|e| format!("add view-once columns: {}", e))?;
+        Ok(())
+    })?;
+
+    // Formerly reclassified every nameless video as a video note. Video notes are now marked by
+    // their sender (`Attachment::video_note`), and a nameless video from before that is just a
+    // video, so existing rows keep the kind they were stored with. Kept as a no-op so the version
+    // still advances.
+    run_atomic_migration(conn, 90, "Reclassify nameless videos as video notes (retired)", |_tx| Ok(()))?;
+
+    // Resumable attachment uploads (`blossom::upload_chunked_with_failover`): the encryption
+    // params of a large upload in flight, so a retry re-encrypts to the same bytes, and the chunks
+    // already on a server.
+    run_atomic_migration(conn, 91, "Add resumable upload state", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS upload_sessions (
+                file_hash TEXT PRIMARY KEY,
+                key TEXT NOT NULL,
+                nonce TEXT NOT NULL,
+                started_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS upload_chunks (
+                file_hash TEXT NOT NULL,
+                idx INTEGER NOT NULL,
+                sha256 TEXT NOT NULL,
+                url TEXT NOT NULL,
+                PRIMARY KEY (file_hash, idx)
+            );"
+        ).map_err(|e| format!("create upload state: {}", e))?;
+        Ok(())
+    })?;
+
+    // Mirrored attachments (`blossom::mirror_blob`): the blob's other copies, as a JSON array of
+    // URLs, for the download path to fail over to. NULL when there are none.
+    run_atomic_migration(conn, 92, "Add attachment mirrors", |tx| {
+        tx.execute_batch("ALTER TABLE attachments ADD COLUMN mirrors TEXT;")
+            .map_err(|e| format!("add attachment mirrors: {}", e))?;
+        Ok(())
+    })?;
+
+    // Media cache LRU (`media_cache`): when a downloaded file was last fetched or shown, in secs.
+    // 0 until then; eviction falls back to the message's age.
+    run_atomic_migration(conn, 93, "Add attachment last-used time", |tx| {
+        tx.execute_batch("ALTER TABLE attachments ADD COLUMN last_used INTEGER NOT NULL DEFAULT 0;")
+            .map_err(|e| format!("add attachment last_used: {}", e))?;
+        Ok(())
+    })?;
+
+    // NIP-02 contact list (`contacts`): one row per npub ever on the list. A removal keeps its row
+    // (`removed=1`) so it can win against an older kind 3 from another client; `updated_at` is the
+    // secs of the last local edit or of the kind 3 the row was adopted from.
+    run_atomic_migration(conn, 94, "Add contact list", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS contacts (
+                npub TEXT PRIMARY KEY,
+                relay TEXT NOT NULL DEFAULT '',
+                petname TEXT NOT NULL DEFAULT '',
+                removed INTEGER NOT NULL DEFAULT 0,
+                updated_at INTEGER NOT NULL
+            );"
+        ).map_err(|e| format!("create contacts: {}", e))?;
+        Ok(())
+    })?;
+
+    // Contact verification (`contact_verification`): the user compared safety numbers with them.
+    run_atomic_migration(conn, 95, "Add contact verification flag", |tx| {
+        tx.execute_batch("ALTER TABLE profiles ADD COLUMN contact_verified INTEGER NOT NULL DEFAULT 0;")
+            .map_err(|e| format!("add contact_verified: {}", e))?;
+        Ok(())
+    })?;
+
+    // Soft-deleted chats (`db::chats::soft_delete_chat`): secs of the delete, NULL while live. The
+    // row and its messages stay for restore until the retention purge.
+    run_atomic_migration(conn, 96, "Add chat soft-delete tombstone", |tx| {
+        tx.execute_batch("ALTER TABLE chats ADD COLUMN deleted_at INTEGER;")
+            .map_err(|e| format!("add chats.deleted_at: {}", e))?;
+        Ok(())
+    })?;
+
+    // Per-chat notification preferences (`db::notification_prefs`); a row only where they differ
+    // from the defaults.
+    run_atomic_migration(conn, 97, "Add chat notification preferences", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS chat_notification_prefs (
+                chat_identifier TEXT PRIMARY KEY,
+                mentions_only INTEGER NOT NULL DEFAULT 0,
+                muted_until INTEGER NOT NULL DEFAULT 0,
+                sound INTEGER NOT NULL DEFAULT 1
+            );"
+        ).map_err(|e| format!("create chat_notification_prefs: {}", e))?;
+        Ok(())
+    })?;
+
+    // Per-contact gift-wrap delivery (`db::profile_relays::ContactRelays`): 'auto', 'manual' (to
+    // `manual_relays`) or 'trusted'. Kept beside the contact's NIP-65 read relays.
+    run_atomic_migration(conn, 98, "Add contact relay delivery preference", |tx| {
+        tx.execute_batch(
+            "ALTER TABLE profile_relays ADD COLUMN delivery_mode TEXT NOT NULL DEFAULT 'auto';
+             ALTER TABLE profile_relays ADD COLUMN manual_relays TEXT NOT NULL DEFAULT '[]';"
+        ).map_err(|e| format!("add profile_relays delivery: {}", e))?;
+        Ok(())
+    })?;
+
+    // Chats with typing indicators switched off (`typing::set_chat_enabled`); on is the default.
+    run_atomic_migration(conn, 99, "Add per-chat typing indicator opt-out", |tx| {
+        tx.execute_batch("CREATE TABLE IF NOT EXISTS chat_typing_off (chat_identifier TEXT PRIMARY KEY);")
+            .map_err(|e| format!("create chat_typing_off: {}", e))?;
+        Ok(())
+    })?;
+
+    // Link previews fetched by this device (`link_preview`), keyed by URL and reused for a week.
+    run_atomic_migration(conn, 100, "Add link preview cache", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS link_previews (
+                url TEXT PRIMARY KEY,
+                metadata TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL
+            );"
+        ).map_err(|e| format!("create link_previews: {}", e))?;
+        Ok(())
+    })?;
+
+    // Voice message transcripts (`Attachment::transcript`): JSON, encrypted at rest like message
+    // content. NULL until the attachment is transcribed.
+    run_atomic_migration(conn, 101, "Add attachment transcripts", |tx| {
+        tx.execute_batch("ALTER TABLE attachments ADD COLUMN transcript TEXT;")
+            .map_err(|e| format!("add attachment transcript: {}", e))?;
+        Ok(())
+    })?;
+
+    // NIP-57 zap receipts that involve us (`zaps`), summed per message and per contact.
+    run_atomic_migration(conn, 102, "Add zap receipts", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS zaps (
+                receipt_id TEXT PRIMARY KEY,
+                sender TEXT NOT NULL,
+                recipient TEXT NOT NULL,
+                event_id TEXT,
+                amount_msat INTEGER NOT NULL,
+                comment TEXT NOT NULL DEFAULT '',
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_zaps_event ON zaps(event_id);"
+        ).map_err(|e| format!("create zaps: {}", e))?;
+        Ok(())
+    })?;
+
+    // Cashu ecash wallet (`cashu`): proofs (JSON, encrypted at rest) keyed by their public point Y,
+    // deposits awaiting payment, and the wallet history. A sent token stays in `token`, encrypted.
+    run_atomic_migration(conn, 103, "Add ecash wallet", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS cashu_proofs (
+                y TEXT PRIMARY KEY,
+                mint_url TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                proof TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_cashu_proofs_mint ON cashu_proofs(mint_url);
+            CREATE TABLE IF NOT EXISTS cashu_quotes (
+                quote_id TEXT PRIMARY KEY,
+                mint_url TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                invoice TEXT NOT NULL,
+                expiry INTEGER,
+                created_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS cashu_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                fee INTEGER NOT NULL DEFAULT 0,
+                mint_url TEXT NOT NULL,
+                chat_id TEXT,
+                message_id TEXT,
+                token TEXT,
+                created_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_cashu_history_message ON cashu_history(message_id);"
+        ).map_err(|e| format!("create cashu tables: {}", e))?;
+        Ok(())
+    })?;
+
+    // Starred messages (`starred`): kept past their Self-Destruct Timer and out of media-cache
+    // eviction. Goes with the message when it's deleted.
+    run_atomic_migration(conn, 104, "Add starred messages", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS starred_messages (
+                message_id TEXT PRIMARY KEY,
+                starred_at INTEGER NOT NULL,
+                FOREIGN KEY (message_id) REFERENCES events(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_starred_messages_at ON starred_messages(starred_at);"
+        ).map_err(|e| format!("create starred_messages: {}", e))?;
+        Ok(())
+    })?;
+
+    // Chat folders: user-defined folders for organizing the chat list (DMs and communities alike).
+    // A chat sits in at most one folder; purely local.
+    run_atomic_migration(conn, 105, "Add chat folders", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS chat_folders (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                created_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS chat_folder_members (
+                chat_identifier TEXT PRIMARY KEY,
+                folder_id INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_chat_folder_members_folder ON chat_folder_members(folder_id);"
+        ).map_err(|e| format!("create chat folders: {}", e))?;
+        Ok(())
+    })?;
+
+    // Per-chat appearance (`appearance`): an accent color over the account theme. Cleared = no row.
+    run_atomic_migration(conn, 106, "Add per-chat appearance", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS chat_appearance (
+                chat_identifier TEXT PRIMARY KEY,
+                accent_color TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );"
+        ).map_err(|e| format!("create chat_appearance: {}", e))?;
+        Ok(())
+    })?;
+
+    // Zap requests we sent, with the zap server that must sign their receipts.
+    run_atomic_migration(conn, 107, "Add zap requests", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS zap_requests (
+                request_id TEXT PRIMARY KEY,
+                provider TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            );"
+        ).map_err(|e| format!("create zap_requests: {}", e))?;
+        Ok(())
+    })?;
+
+    // Peer capabilities per device: a contact's other clients may not read what one advertised.
+    // Existing rows become the unnamed device.
+    run_atomic_migration(conn, 108, "Track peer capabilities per device", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE peer_capabilities_new (
+                npub TEXT NOT NULL,
+                device TEXT NOT NULL DEFAULT '',
+                capability TEXT NOT NULL,
+                seen_at INTEGER NOT NULL,
+                PRIMARY KEY (npub, device, capability)
+            );
+            INSERT INTO peer_capabilities_new (npub, device, capability, seen_at)
+                SELECT npub, '', capability, seen_at FROM peer_capabilities;
+            DROP TABLE peer_capabilities;
+            ALTER TABLE peer_capabilities_new RENAME TO peer_capabilities;"
+        ).map_err(|e| format!("migrate peer capabilities: {}", e))?;
+        Ok(())
+    })?;
+
+    // Our own per-chat wallpaper overrides (`appearance`): the synced spec (encrypted, it holds the
+    // blob's key) and the locally cached file. Cleared = no row.
+    run_atomic_migration(conn, 109, "Add chat wallpaper overrides", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS chat_wallpaper_overrides (
+                chat_identifier TEXT PRIMARY KEY,
+                spec TEXT NOT NULL,
+                path TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            );"
+        ).map_err(|e| format!("create chat_wallpaper_overrides: {}", e))?;
+        Ok(())
+    })?;
+
+    // Ecash operations whose outputs went to the mint but whose proofs aren't stored yet
+    // (`cashu::recover_pending`). `outputs` holds the secrets and blinding factors, encrypted.
+    run_atomic_migration(conn, 110, "Add pending ecash operations", |tx| {
+        tx.execute_batch(
+            "CREATE TABLE IF NOT EXISTS cashu_pending (
+                id TEXT PRIMARY KEY,
+                mint_url TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                fee INTEGER NOT NULL DEFAULT 0,
+                inputs TEXT NOT NULL,
+                input_total INTEGER NOT NULL DEFAULT 0,
+                outputs TEXT NOT NULL,
+                chat_id TEXT,
+                message_id TEXT,
+                quote_id TEXT,
+                created_at INTEGER NOT NULL
+            );"
+        ).map_err(|e| format!("create cashu_pending: {}", e))?;
+        Ok(())
+    })?;
+
     Ok(())
 }
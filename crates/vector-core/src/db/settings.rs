@@ -11,8 +11,17 @@ pub fn get_sql_setting(key: String) -> Result<Option<String>, String> {
     Ok(result)
 }
 
-/// Set a SQL setting key-value pair.
+/// Set a SQL setting key-value pair. Cross-device keys are stamped for settings sync.
 pub fn set_sql_setting(key: String, value: String) -> Result<(), String> {
+    write_setting_unsynced(&key, &value)?;
+    if crate::settings_sync::is_synced_key(&key) {
+        crate::settings_sync::note_local_change(&key);
+    }
+    Ok(())
+}
+
+/// Write a setting WITHOUT stamping it as a local change — for values adopted from another device.
+pub fn write_setting_unsynced(key: &str, value: &str) -> Result<(), String> {
     let conn = super::get_write_connection_guard_static()?;
     conn.execute(
         "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
@@ -29,18 +38,43 @@ pub fn remove_setting(key: &str) -> Result<(), String> {
     Ok(())
 }
 
-/// Get the stored private key (bech32 nsec).
+/// `pkey` (and `seed`) row value meaning the secret itself is held by the system
+/// keystore ([`crate::traits::SecretStore`]): the key under the account's npub,
+/// the seed phrase under [`seed_entry`].
+pub const PKEY_IN_KEYSTORE: &str = "keystore";
+
+/// Error for a keystore entry that exists but can no longer be read back (its
+/// platform key was reset). The secret is gone from this device; the frontend
+/// matches this text to send the user to recovery rather than a PIN retry.
+pub const KEYSTORE_UNSEALABLE: &str = "The system keystore can no longer unseal this account's key";
+
+/// Where the account's private key and seed phrase are kept.
+///
+/// Either way the values are the same (a bech32 nsec and a mnemonic,
+/// PIN-encrypted when local encryption is on); the keystore just keeps them out
+/// of the database file, so a copied `vector.db` doesn't carry the account's
+/// secrets. Everything else in it stays where it is.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyStorage {
+    Database,
+    Keystore,
+}
+
+/// Get the stored private key (bech32 nsec, may be encrypted), wherever it's kept.
 pub fn get_pkey() -> Result<Option<String>, String> {
-    let conn = super::get_db_connection_guard_static()?;
-    Ok(conn.query_row(
-        "SELECT value FROM settings WHERE key = 'pkey'",
-        [],
-        |row| row.get(0),
-    ).ok())
+    match get_pkey_row()? {
+        Some(row) if row == PKEY_IN_KEYSTORE => load_keystore_pkey().map(Some),
+        row => Ok(row),
+    }
 }
 
-/// Set the stored private key.
+/// Set the stored private key, in whichever backend currently holds it.
 pub fn set_pkey(pkey: &str) -> Result<(), String> {
+    if get_pkey_row()?.as_deref() == Some(PKEY_IN_KEYSTORE) {
+        let account = super::get_current_account()?;
+        return crate::traits::secret_store()?.store(&account, pkey);
+    }
     let conn = super::get_write_connection_guard_static()?;
     conn.execute(
         "INSERT OR REPLACE INTO settings (key, value) VALUES ('pkey', ?1)",
@@ -49,8 +83,127 @@ pub fn set_pkey(pkey: &str) -> Result<(), String> {
     Ok(())
 }
 
-/// Get the stored seed phrase (may be encrypted).
-pub fn get_seed() -> Result<Option<String>, String> {
+/// The raw `pkey` row: the key itself, or [`PKEY_IN_KEYSTORE`].
+fn get_pkey_row() -> Result<Option<String>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    Ok(conn.query_row(
+        "SELECT value FROM settings WHERE key = 'pkey'",
+        [],
+        |row| row.get(0),
+    ).ok())
+}
+
+/// The active account's private key as held by the system keystore.
+pub fn load_keystore_pkey() -> Result<String, String> {
+    let account = super::get_current_account()?;
+    crate::traits::secret_store()?
+        .load(&account)?
+        .ok_or_else(|| "The private key is missing from the system keystore".to_string())
+}
+
+/// Where the active account's private key is kept.
+pub fn key_storage() -> Result<KeyStorage, String> {
+    Ok(match get_pkey_row()? {
+        Some(row) if row == PKEY_IN_KEYSTORE => KeyStorage::Keystore,
+        _ => KeyStorage::Database,
+    })
+}
+
+/// Keystore entry holding `account`'s seed phrase while its `seed` row is [`PKEY_IN_KEYSTORE`].
+pub fn seed_entry(account: &str) -> String {
+    format!("{}/seed", account)
+}
+
+/// Move the active account's private key and seed phrase to `target`.
+///
+/// Into the keystore: each secret is stored and read back before the database
+/// rows are replaced by the sentinel (both in one statement), so a keystore that
+/// silently drops writes can't cost either. Back to the database: the rows are
+/// rewritten first, and only then are the keystore entries deleted (best-effort
+/// — a leftover entry is harmless once the rows hold the secrets again).
+pub fn set_key_storage(target: KeyStorage) -> Result<(), String> {
+    let account = super::get_current_account()?;
+    let store = crate::traits::secret_store()?;
+    let mut conn = super::get_write_connection_guard_static()?;
+    let row: String = conn.query_row(
+        "SELECT value FROM settings WHERE key = 'pkey'",
+        [],
+        |row| row.get(0),
+    ).map_err(|_| "This account has no private key stored on this device".to_string())?;
+    let seed: Option<String> = conn.query_row(
+        "SELECT value FROM settings WHERE key = 'seed'",
+        [],
+        |row| row.get(0),
+    ).ok();
+    let seed_account = seed_entry(&account);
+
+    match target {
+        KeyStorage::Keystore => {
+            let mut moving = Vec::new();
+            if row != PKEY_IN_KEYSTORE {
+                moving.push((account.as_str(), row.as_str()));
+            }
+            if let Some(seed) = seed.as_deref().filter(|s| *s != PKEY_IN_KEYSTORE) {
+                moving.push((seed_account.as_str(), seed));
+            }
+            if moving.is_empty() {
+                return Ok(());
+            }
+            for (i, (entry, value)) in moving.iter().enumerate() {
+                let kept = store.store(entry, value)
+                    .and_then(|_| store.load(entry))
+                    .map(|loaded| loaded.as_deref() == Some(*value));
+                if kept != Ok(true) {
+                    for (stored, _) in &moving[..=i] {
+                        let _ = store.delete(stored);
+                    }
+                    return Err(kept.err().unwrap_or_else(|| "The system keystore didn't keep the key".to_string()));
+                }
+            }
+            conn.execute(
+                "UPDATE settings SET value = ?1 WHERE key IN ('pkey', 'seed')",
+                rusqlite::params![PKEY_IN_KEYSTORE],
+            ).map_err(|e| format!("Failed to set pkey: {}", e))?;
+        }
+        KeyStorage::Database => {
+            let pkey = match row == PKEY_IN_KEYSTORE {
+                true => Some(store.load(&account)?
+                    .ok_or_else(|| "The private key is missing from the system keystore".to_string())?),
+                false => None,
+            };
+            let seed = match seed.as_deref() == Some(PKEY_IN_KEYSTORE) {
+                true => Some(store.load(&seed_account)?
+                    .ok_or_else(|| "The seed phrase is missing from the system keystore".to_string())?),
+                false => None,
+            };
+            if pkey.is_none() && seed.is_none() {
+                return Ok(());
+            }
+            let tx = conn.transaction()
+                .map_err(|e| format!("Failed to begin tx: {}", e))?;
+            for (key, value) in [("pkey", &pkey), ("seed", &seed)] {
+                if let Some(value) = value {
+                    tx.execute(
+                        "UPDATE settings SET value = ?1 WHERE key = ?2",
+                        rusqlite::params![value, key],
+                    ).map_err(|e| format!("Failed to set {}: {}", key, e))?;
+                }
+            }
+            tx.commit().map_err(|e| format!("Failed to commit tx: {}", e))?;
+            for (entry, moved) in [(account.as_str(), pkey.is_some()), (seed_account.as_str(), seed.is_some())] {
+                if moved {
+                    if let Err(e) = store.delete(entry) {
+                        eprintln!("[Keystore] Failed to delete the moved secret: {}", e);
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The raw `seed` row: the seed phrase itself, or [`PKEY_IN_KEYSTORE`].
+fn get_seed_row() -> Result<Option<String>, String> {
     let conn = super::get_db_connection_guard_static()?;
     Ok(conn.query_row(
         "SELECT value FROM settings WHERE key = 'seed'",
@@ -59,8 +212,29 @@ pub fn get_seed() -> Result<Option<String>, String> {
     ).ok())
 }
 
-/// Set the seed phrase (should be encrypted before calling).
+/// The active account's seed phrase as held by the system keystore.
+pub fn load_keystore_seed() -> Result<String, String> {
+    let account = super::get_current_account()?;
+    crate::traits::secret_store()?
+        .load(&seed_entry(&account))?
+        .ok_or_else(|| "The seed phrase is missing from the system keystore".to_string())
+}
+
+/// Get the stored seed phrase (may be encrypted), wherever it's kept.
+pub fn get_seed() -> Result<Option<String>, String> {
+    match get_seed_row()? {
+        Some(row) if row == PKEY_IN_KEYSTORE => load_keystore_seed().map(Some),
+        row => Ok(row),
+    }
+}
+
+/// Set the seed phrase (should be encrypted before calling), in whichever
+/// backend currently holds it.
 pub fn set_seed(seed: &str) -> Result<(), String> {
+    if get_seed_row()?.as_deref() == Some(PKEY_IN_KEYSTORE) {
+        let account = super::get_current_account()?;
+        return crate::traits::secret_store()?.store(&seed_entry(&account), seed);
+    }
     let conn = super::get_write_connection_guard_static()?;
     conn.execute(
         "INSERT OR REPLACE INTO settings (key, value) VALUES ('seed', ?1)",
@@ -227,6 +401,35 @@ pub fn set_bunker_remote_pubkey(pubkey_hex: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// This device's pairing with a remote signer, as one account holds it.
+pub struct BunkerPairing {
+    pub npub: String,
+    /// The client keypair's `pkey` row — an nsec, or encrypted when the account is.
+    pub pkey: String,
+}
+
+/// Find the account paired with the remote signer `remote_hex` (its stored
+/// `bunker_remote_pubkey`). Each account's settings are read read-only
+/// straight from its vector.db, so nothing has to be opened or made current
+/// before the signer has answered. A pkey held in the OS keystore isn't
+/// reachable from here, so that account pairs afresh.
+pub fn find_bunker_pairing(remote_hex: &str) -> Option<BunkerPairing> {
+    super::get_accounts().ok()?.into_iter().find_map(|npub| {
+        let path = super::account_dir(&npub).ok()?.join("vector.db");
+        let conn = rusqlite::Connection::open_with_flags(&path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).ok()?;
+        let get = |key: &str| -> Option<String> {
+            conn.query_row("SELECT value FROM settings WHERE key = ?1", rusqlite::params![key], |r| r.get(0)).ok()
+        };
+        if get("signer_type").as_deref() != Some("bunker")
+            || !get("bunker_remote_pubkey")?.eq_ignore_ascii_case(remote_hex)
+        {
+            return None;
+        }
+        let pkey = get("pkey").filter(|p| p != PKEY_IN_KEYSTORE)?;
+        Some(BunkerPairing { npub, pkey })
+    })
+}
+
 /// Atomically commit the four settings written during *bunker* new-account
 /// setup: the (possibly-encrypted) client keypair pkey, `encryption_enabled`,
 /// `security_type`, plus `signer_type='bunker'`, the (possibly-encrypted)
@@ -397,3 +600,68 @@ pub fn commit_nip55_account_setup(
     tx.commit().map_err(|e| format!("Failed to commit tx: {}", e))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::SecretStore;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    static ENTRIES: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+    struct MemoryStore;
+    impl SecretStore for MemoryStore {
+        fn store(&self, account: &str, secret: &str) -> Result<(), String> {
+            ENTRIES.lock().unwrap().get_or_insert_with(HashMap::new).insert(account.to_string(), secret.to_string());
+            Ok(())
+        }
+        fn load(&self, account: &str) -> Result<Option<String>, String> {
+            Ok(ENTRIES.lock().unwrap().as_ref().and_then(|e| e.get(account).cloned()))
+        }
+        fn delete(&self, account: &str) -> Result<(), String> {
+            if let Some(entries) = ENTRIES.lock().unwrap().as_mut() {
+                entries.remove(account);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn secrets_move_between_database_and_keystore() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+        crate::traits::set_secret_store(Box::new(MemoryStore));
+
+        set_pkey("nsec1first").unwrap();
+        set_seed("first seed words").unwrap();
+        set_key_storage(KeyStorage::Keystore).unwrap();
+        assert_eq!(key_storage().unwrap(), KeyStorage::Keystore);
+        assert_eq!(get_pkey_row().unwrap().as_deref(), Some(PKEY_IN_KEYSTORE));
+        assert_eq!(get_seed_row().unwrap().as_deref(), Some(PKEY_IN_KEYSTORE));
+        assert_eq!(get_pkey().unwrap().as_deref(), Some("nsec1first"));
+        assert_eq!(get_seed().unwrap().as_deref(), Some("first seed words"));
+
+        // Writes follow the secrets into the keystore
+        set_pkey("nsec1second").unwrap();
+        set_seed("second seed words").unwrap();
+        assert_eq!(get_pkey_row().unwrap().as_deref(), Some(PKEY_IN_KEYSTORE));
+        assert_eq!(get_seed_row().unwrap().as_deref(), Some(PKEY_IN_KEYSTORE));
+        assert_eq!(get_pkey().unwrap().as_deref(), Some("nsec1second"));
+        assert_eq!(get_seed().unwrap().as_deref(), Some("second seed words"));
+
+        set_key_storage(KeyStorage::Database).unwrap();
+        assert_eq!(key_storage().unwrap(), KeyStorage::Database);
+        assert_eq!(get_pkey_row().unwrap().as_deref(), Some("nsec1second"));
+        assert_eq!(get_seed_row().unwrap().as_deref(), Some("second seed words"));
+        assert_eq!(MemoryStore.load(&account).unwrap(), None);
+        assert_eq!(MemoryStore.load(&seed_entry(&account)).unwrap(), None);
+    }
+}
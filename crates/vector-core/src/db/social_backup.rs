@@ -0,0 +1,108 @@
+//! Social-backup storage (guardian side): the seed shares we hold for contacts and the recovery
+//! requests waiting on our approval. Shares are encrypted at rest like message content.
+
+use rusqlite::params;
+
+/// A share we hold for `owner_npub` (only the latest backup per owner is kept).
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct HeldShare {
+    pub owner_npub: String,
+    pub backup_id: String,
+    pub received_at: u64,
+}
+
+/// A pending request to release a held share to `requester_npub` (a recovering device's key).
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RecoveryRequest {
+    pub requester_npub: String,
+    pub owner_npub: String,
+    /// Verification code shown on the recovering device — the guardian checks it out-of-band.
+    pub code: String,
+    pub requested_at: u64,
+}
+
+/// Store (or replace) the share `owner_npub` entrusted to us.
+pub async fn save_held_share(owner_npub: &str, backup_id: &str, share: &str, received_at: u64) -> Result<(), String> {
+    let encrypted = crate::crypto::maybe_encrypt(share.to_string()).await;
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute(
+        "INSERT OR REPLACE INTO social_backup_shares (owner_npub, backup_id, share, received_at) VALUES (?1, ?2, ?3, ?4)",
+        params![owner_npub, backup_id, encrypted, received_at as i64],
+    ).map_err(|e| format!("Failed to save backup share: {}", e))?;
+    Ok(())
+}
+
+/// The (decrypted, encoded) share held for `owner_npub`.
+pub async fn get_held_share(owner_npub: &str) -> Result<Option<String>, String> {
+    let stored: Option<String> = {
+        let conn = super::get_db_connection_guard_static()?;
+        conn.query_row(
+            "SELECT share FROM social_backup_shares WHERE owner_npub = ?1",
+            params![owner_npub],
+            |row| row.get(0),
+        ).ok()
+    };
+    match stored {
+        Some(s) => crate::crypto::maybe_decrypt(s).await
+            .map(Some)
+            .map_err(|_| "Failed to decrypt backup share".to_string()),
+        None => Ok(None),
+    }
+}
+
+pub fn list_held_shares() -> Result<Vec<HeldShare>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT owner_npub, backup_id, received_at FROM social_backup_shares ORDER BY received_at DESC",
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| Ok(HeldShare {
+        owner_npub: row.get(0)?,
+        backup_id: row.get(1)?,
+        received_at: row.get::<_, i64>(2)? as u64,
+    })).map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+pub fn save_request(req: &RecoveryRequest) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute(
+        "INSERT OR REPLACE INTO social_backup_requests (requester_npub, owner_npub, code, requested_at) VALUES (?1, ?2, ?3, ?4)",
+        params![req.requester_npub, req.owner_npub, req.code, req.requested_at as i64],
+    ).map_err(|e| format!("Failed to save recovery request: {}", e))?;
+    Ok(())
+}
+
+pub fn get_request(requester_npub: &str) -> Result<Option<RecoveryRequest>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    Ok(conn.query_row(
+        "SELECT requester_npub, owner_npub, code, requested_at FROM social_backup_requests WHERE requester_npub = ?1",
+        params![requester_npub],
+        |row| Ok(RecoveryRequest {
+            requester_npub: row.get(0)?,
+            owner_npub: row.get(1)?,
+            code: row.get(2)?,
+            requested_at: row.get::<_, i64>(3)? as u64,
+        }),
+    ).ok())
+}
+
+pub fn list_requests() -> Result<Vec<RecoveryRequest>, String> {
+    let conn = super::get_db_connection_guard_static()?;
+    let mut stmt = conn.prepare(
+        "SELECT requester_npub, owner_npub, code, requested_at FROM social_backup_requests ORDER BY requested_at DESC",
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| Ok(RecoveryRequest {
+        requester_npub: row.get(0)?,
+        owner_npub: row.get(1)?,
+        code: row.get(2)?,
+        requested_at: row.get::<_, i64>(3)? as u64,
+    })).map_err(|e| e.to_string())?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+pub fn remove_request(requester_npub: &str) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute("DELETE FROM social_backup_requests WHERE requester_npub = ?1", params![requester_npub])
+        .map_err(|e| format!("Failed to remove recovery request: {}", e))?;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! Progress of resumable attachment uploads (see `blossom::upload_chunked_with_failover`).
+//!
+//! A session is keyed by the plaintext file hash and holds the encryption params, so retrying the
+//! same file (even after a restart) encrypts to the same ciphertext and the chunks already stored
+//! on a server still match. The params are stored encrypted like the rest of the database. Sessions
+//! are dropped once the upload completes, or after a week.
+
+use std::collections::HashMap;
+
+use rusqlite::params;
+
+use crate::crypto::EncryptionParams;
+use crate::clock::now_secs;
+
+/// Sessions older than this are abandoned; servers may have pruned their chunks anyway.
+const SESSION_TTL_SECS: u64 = 7 * 24 * 3600;
+
+/// The encryption params of `file_hash`'s upload: the stored ones when an earlier attempt was
+/// interrupted, otherwise `fresh`, which are stored for the next attempt.
+pub fn session_params(file_hash: &str, fresh: EncryptionParams) -> Result<EncryptionParams, String> {
+    let conn = super::get_write_connection_guard_static()?;
+    let cutoff = now_secs().saturating_sub(SESSION_TTL_SECS) as i64;
+    conn.execute(
+        "DELETE FROM upload_chunks WHERE file_hash IN (SELECT file_hash FROM upload_sessions WHERE started_at < ?1)",
+        params![cutoff],
+    ).map_err(|e| format!("Failed to prune upload chunks: {}", e))?;
+    conn.execute("DELETE FROM upload_sessions WHERE started_at < ?1", params![cutoff])
+        .map_err(|e| format!("Failed to prune upload sessions: {}", e))?;
+
+    let existing = conn.query_row(
+        "SELECT key, nonce FROM upload_sessions WHERE file_hash = ?1",
+        params![file_hash],
+        |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+    ).ok();
+    if let Some((key, nonce)) = existing {
+        return Ok(EncryptionParams {
+            key: crate::crypto::maybe_decrypt_text(&key),
+            nonce: crate::crypto::maybe_decrypt_text(&nonce),
+        });
+    }
+    let key = crate::crypto::maybe_encrypt_text(&fresh.key)?;
+    let nonce = crate::crypto::maybe_encrypt_text(&fresh.nonce)?;
+    conn.execute(
+        "INSERT INTO upload_sessions (file_hash, key, nonce, started_at) VALUES (?1, ?2, ?3, ?4)",
+        params![file_hash, key, nonce, now_secs() as i64],
+    ).map_err(|e| format!("Failed to save upload session: {}", e))?;
+    Ok(fresh)
+}
+
+/// Chunks of `file_hash` already uploaded: index → (sha256, url).
+pub fn uploaded_chunks(file_hash: &str) -> HashMap<u32, (String, String)> {
+    let Ok(conn) = super::get_db_connection_guard_static() else { return HashMap::new() };
+    let Ok(mut stmt) = conn.prepare("SELECT idx, sha256, url FROM upload_chunks WHERE file_hash = ?1") else {
+        return HashMap::new();
+    };
+    stmt.query_map(params![file_hash], |row| Ok((row.get::<_, u32>(0)?, (row.get(1)?, row.get(2)?))))
+        .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Remember that chunk `idx` of `file_hash` is stored at `url`.
+pub fn record_chunk(file_hash: &str, idx: u32, sha256: &str, url: &str) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute(
+        "INSERT OR REPLACE INTO upload_chunks (file_hash, idx, sha256, url) VALUES (?1, ?2, ?3, ?4)",
+        params![file_hash, idx, sha256, url],
+    ).map_err(|e| format!("Failed to save upload chunk: {}", e))?;
+    Ok(())
+}
+
+/// Drop a finished upload's session and chunk records.
+pub fn finish(file_hash: &str) -> Result<(), String> {
+    let conn = super::get_write_connection_guard_static()?;
+    conn.execute("DELETE FROM upload_chunks WHERE file_hash = ?1", params![file_hash])
+        .map_err(|e| format!("Failed to clear upload chunks: {}", e))?;
+    conn.execute("DELETE FROM upload_sessions WHERE file_hash = ?1", params![file_hash])
+        .map_err(|e| format!("Failed to clear upload session: {}", e))?;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! Device drop — moving files between our own devices.
+//!
+//! A drop is an ordinary file DM gift-wrapped to our own npub, so every device signed in to the
+//! account receives it from its inbox relays. The rumor carries a `["device", <id>, <os>]` tag
+//! naming the sending install; that tag is what keeps drops out of Notes: both the sender and the
+//! receiving devices file them under the [`CHAT_ID`] chat, which never counts towards unread.
+//!
+//! The device id is per install (a file next to the account folders), so it isn't carried over by
+//! an account bundle.
+
+use std::sync::Arc;
+
+use nostr_sdk::prelude::*;
+use rand::RngCore;
+
+use crate::sending::{OutgoingFile, SendCallback, SendConfig, SendResult};
+
+/// Chat id of the device-drop chat.
+pub const CHAT_ID: &str = "devices";
+
+/// Tag naming the device a drop was sent from.
+pub const DEVICE_TAG: &str = "device";
+
+const DEVICE_ID_FILE: &str = "device_id";
+
+/// This install's device id, created on first use.
+pub fn device_id() -> Result<String, String> {
+    let path = crate::db::get_app_data_dir()?.join(DEVICE_ID_FILE);
+    if let Ok(id) = std::fs::read_to_string(&path) {
+        let id = id.trim();
+        if !id.is_empty() {
+            return Ok(id.to_string());
+        }
+    }
+    let mut bytes = [0u8; 8];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    let id = crate::simd::hex::bytes_to_hex_string(&bytes);
+    std::fs::write(&path, &id).map_err(|e| format!("Failed to save device id: {}", e))?;
+    Ok(id)
+}
+
+/// The routing tag stamped on a drop. Fails rather than naming no device.
+pub fn device_tag() -> Result<Tag, String> {
+    let id = device_id()?;
+    Ok(Tag::custom(TagKind::custom(DEVICE_TAG), [id, std::env::consts::OS.to_string()]))
+}
+
+/// Whether a rumor's tags mark it as a device drop.
+pub fn is_device_drop(tags: &Tags) -> bool {
+    tags.iter().any(|t| t.as_slice().first().map(String::as_str) == Some(DEVICE_TAG))
+}
+
+/// Drop `files` to our other devices. The message lands in the device-drop chat here too.
+pub async fn send_files(
+    files: Vec<OutgoingFile>,
+    caption: Option<&str>,
+    mut config: SendConfig,
+    callback: Arc<dyn SendCallback>,
+) -> Result<SendResult, String> {
+    let my_npub = crate::state::my_public_key()
+        .ok_or("Not logged in")?
+        .to_bech32()
+        .map_err(|e| e.to_string())?;
+    // Before anything shows as pending: without a device id the drop can't be tagged
+    device_id()?;
+    // The wrap already goes to our own inbox; a second copy would be a duplicate
+    config.self_send = false;
+    config.device_drop = true;
+    crate::sending::send_files_dm(&my_npub, files, caption, &config, callback).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_are_tagged_and_recognised() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        let tmp = tempfile::tempdir().unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        // Set once per process, so it may be another test's (already removed) folder
+        std::fs::create_dir_all(crate::db::get_app_data_dir().unwrap()).unwrap();
+
+        let id = device_id().unwrap();
+        assert_eq!(id.len(), 16);
+        assert_eq!(device_id().unwrap(), id, "the id sticks to the install");
+
+        let mut tags = Tags::new();
+        assert!(!is_device_drop(&tags));
+        tags.push(device_tag().unwrap());
+        assert!(is_device_drop(&tags));
+        assert_eq!(tags.first().unwrap().as_slice()[1], id);
+    }
+}
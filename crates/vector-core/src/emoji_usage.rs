@@ -192,6 +192,63 @@ pub fn ranked(limit: Option<usize>) -> Vec<EmojiUsageEntry> {
     }
 }
 
+// ============================================================================
+// Reaction quick-picks
+// ============================================================================
+
+/// Settings key holding the user's pinned quick-picks (a JSON array of emoji); synced across devices.
+const QUICKPICKS_KEY: &str = "reaction_quickpicks";
+/// Emojis shown in the long-press reaction bar.
+pub const QUICKPICK_COUNT: usize = 6;
+/// What a new account (no usage yet) sees, and what fills any remaining slots.
+pub const DEFAULT_QUICKPICKS: [&str; QUICKPICK_COUNT] = ["👍", "❤️", "😂", "😮", "😢", "🔥"];
+
+/// Pinned first, then the most-used unicode emojis, then the defaults — deduplicated and cut to
+/// [`QUICKPICK_COUNT`].
+fn merge_quickpicks(pinned: &[String], used: &[EmojiUsageEntry]) -> Vec<String> {
+    let mut picks: Vec<String> = Vec::with_capacity(QUICKPICK_COUNT);
+    let used = used.iter().filter(|e| e.kind == "unicode").map(|e| e.id.as_str());
+    for emoji in pinned.iter().map(String::as_str).chain(used).chain(DEFAULT_QUICKPICKS) {
+        if picks.len() == QUICKPICK_COUNT {
+            break;
+        }
+        if !emoji.is_empty() && !picks.iter().any(|p| p == emoji) {
+            picks.push(emoji.to_string());
+        }
+    }
+    picks
+}
+
+/// The user's pinned quick-picks (empty when none are set).
+pub fn pinned_quickpicks() -> Vec<String> {
+    crate::db::settings::get_sql_setting(QUICKPICKS_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default()
+}
+
+/// The reaction bar's emojis, following usage around whatever the user pinned.
+pub fn quickpicks() -> Vec<String> {
+    merge_quickpicks(&pinned_quickpicks(), &ranked(Some(QUICKPICK_COUNT * 2)))
+}
+
+/// Pin `overrides` to the front of the reaction bar (an empty list unpins all).
+pub fn set_quickpicks(overrides: Vec<String>) -> Result<(), String> {
+    let mut pinned: Vec<String> = Vec::new();
+    for emoji in overrides.into_iter().map(|e| e.trim().to_string()).filter(|e| !e.is_empty()) {
+        if !pinned.contains(&emoji) {
+            pinned.push(emoji);
+        }
+    }
+    if pinned.len() > QUICKPICK_COUNT {
+        return Err(format!("At most {} quick reactions can be pinned", QUICKPICK_COUNT));
+    }
+    // An empty list is stored rather than removed, so unpinning syncs like any other edit
+    let json = serde_json::to_string(&pinned).map_err(|e| e.to_string())?;
+    crate::db::settings::set_sql_setting(QUICKPICKS_KEY.to_string(), json)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,4 +323,16 @@ mod tests {
         let newer = simulate(&[T0 + 10.0 * DAY, T0 + 11.0 * DAY, T0 + 12.0 * DAY]);
         assert!(newer > older, "same use count, more recent should rank higher");
     }
+
+    #[test]
+    fn quickpicks_put_pins_then_usage_then_defaults() {
+        let entry = |kind: &str, id: &str| EmojiUsageEntry { kind: kind.into(), id: id.into(), url: None, score: 1.0 };
+
+        // A new account gets the defaults
+        assert_eq!(merge_quickpicks(&[], &[]), DEFAULT_QUICKPICKS.map(String::from).to_vec());
+
+        let used = [entry("unicode", "🎉"), entry("custom", "pepe"), entry("unicode", "👍"), entry("unicode", "🙏")];
+        let picks = merge_quickpicks(&["🙏".to_string()], &used);
+        assert_eq!(picks, ["🙏", "🎉", "👍", "❤️", "😂", "😮"], "custom emojis are skipped, nothing repeats");
+    }
 }
@@ -8,6 +8,7 @@
 
 use nostr_sdk::prelude::*;
 
+use crate::context::lock_state;
 use crate::rumor::{RumorProcessingResult, RumorEvent, RumorContext, ConversationType, process_rumor};
 use crate::types::Message;
 use crate::state::WRAPPER_ID_CACHE;
@@ -300,13 +301,40 @@ pub enum PreparedEvent {
         wrapper_id_bytes: [u8; 32],
         wrapper_created_at: u64,
     },
+    /// From a blocked sender — dropped before the rumor is parsed. Wrapper persisted so it
+    /// never re-syncs.
+    BlockedSkip {
+        wrapper_id_bytes: [u8; 32],
+        wrapper_created_at: u64,
+    },
+    /// A NIP-17 group message (several recipients) — well-formed, but Vector DMs are 1:1.
+    /// Wrapper persisted so it never re-syncs.
+    GroupSkip {
+        wrapper_id_bytes: [u8; 32],
+        wrapper_created_at: u64,
+    },
     /// Error during unwrap/processing — persist wrapper for negentropy.
     ErrorSkip {
         wrapper_id_bytes: [u8; 32],
         wrapper_created_at: u64,
+        /// The wrap itself didn't decrypt (vs. decrypting to something we reject).
+        undecryptable: bool,
     },
 }
 
+impl PreparedEvent {
+    /// How this event reflects on the relay that delivered it (see `relay_health`).
+    pub fn relay_verdict(&self) -> crate::relay_health::Verdict {
+        use crate::relay_health::Verdict;
+        match self {
+            PreparedEvent::DedupSkip { .. } => Verdict::Duplicate,
+            PreparedEvent::ErrorSkip { undecryptable: true, .. } => Verdict::UnwrapFailed,
+            PreparedEvent::ErrorSkip { .. } => Verdict::Malformed,
+            _ => Verdict::Accepted,
+        }
+    }
+}
+
 /// Phase 1: Prepare an event for commit (parallel-safe, no state mutation).
 ///
 /// Performs dedup check, gift wrap decryption, and rumor parsing.
@@ -337,7 +365,7 @@ pub async fn prepare_event(
     let (rumor, sender) = match client.unwrap_gift_wrap(&event).await {
         Ok(UnwrappedGift { rumor, sender }) => (rumor, sender),
         Err(_) => return PreparedEvent::ErrorSkip {
-            wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at,
+            wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at, undecryptable: true,
         },
     };
 
@@ -355,12 +383,28 @@ pub async fn prepare_event(
     } else {
         sender.to_bech32().unwrap_or_default()
     };
+    // Files dropped between our own devices live in their own chat, not Notes
+    let contact = if is_mine && crate::device_drop::is_device_drop(&rumor.tags) {
+        crate::device_drop::CHAT_ID.to_string()
+    } else {
+        contact
+    };
+
+    // Blocked sender: the seal had to be opened to learn who it is, but nothing past that —
+    // no rumor parse, invite parking or capability notes
+    if !is_mine {
+        let blocked = {
+            let state = lock_state().await;
+            state.get_profile(&contact).is_some_and(|p| p.flags.is_blocked())
+        };
+        if blocked {
+            return PreparedEvent::BlockedSkip { wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at };
+        }
+    }
 
     // Skip NIP-17 group messages (multiple p-tags) — Vector DMs are 1:1
     if rumor.tags.public_keys().count() > 1 {
-        return PreparedEvent::ErrorSkip {
-            wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at,
-        };
+        return PreparedEvent::GroupSkip { wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at };
     }
 
     // Community invite (carrier) — a join, not a chat message. Recognized before
@@ -370,7 +414,7 @@ pub async fn prepare_event(
             Some(invite) => PreparedEvent::CommunityInvite {
                 invite, inviter: contact.clone(), is_mine, wrapper_event_id_bytes, wrapper_created_at, rumor_created_at,
             },
-            None => PreparedEvent::ErrorSkip { wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at },
+            None => PreparedEvent::ErrorSkip { wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at, undecryptable: false },
         };
     }
 
@@ -390,14 +434,19 @@ pub async fn prepare_event(
                 wrapper_created_at,
                 rumor_created_at,
             },
-            None => PreparedEvent::ErrorSkip { wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at },
+            None => PreparedEvent::ErrorSkip { wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at, undecryptable: false },
         };
     }
 
+    // Remember whether their client reads compressed texts (see `compression`)
+    if !is_mine && rumor.kind == Kind::PrivateDirectMessage {
+        crate::compression::note_capabilities(&contact, &rumor.tags, rumor.created_at.as_secs());
+    }
+
     // Build RumorEvent for processing
     let Some(rumor_id) = rumor.id else {
         return PreparedEvent::ErrorSkip {
-            wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at,
+            wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at, undecryptable: false,
         };
     };
 
@@ -430,7 +479,7 @@ pub async fn prepare_event(
         Err(e) => {
             log_warn!("[EventHandler] Failed to process rumor: {}", e);
             PreparedEvent::ErrorSkip {
-                wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at,
+                wrapper_id_bytes: wrapper_event_id_bytes, wrapper_created_at, undecryptable: false,
             }
         }
     }
@@ -470,7 +519,7 @@ pub async fn commit_prepared_event(
             // the dropped content never re-syncs)
             if !is_mine {
                 let blocked = {
-                    let state = crate::state::STATE.lock().await;
+                    let state = lock_state().await;
                     state.get_profile(&contact).map_or(false, |p| p.flags.is_blocked())
                 };
                 if blocked {
@@ -484,7 +533,8 @@ pub async fn commit_prepared_event(
             // message's save, so its wrapper must never be ledgered before its row lands (a
             // ledgered-but-unpersisted message reads as "have" and is never re-delivered).
             // Message wrappers ledger inside commit_dm_message / the batch-flush transaction.
-            if !matches!(result, RumorProcessingResult::TextMessage(_) | RumorProcessingResult::FileAttachment(_)) {
+            // Presence pings are live-only and frequent: their wrappers expire relay-side instead.
+            if !matches!(result, RumorProcessingResult::TextMessage(_) | RumorProcessingResult::FileAttachment(_) | RumorProcessingResult::Presence { .. }) {
                 let _ = crate::db::wrappers::save_processed_wrapper(&wrapper_event_id_bytes, wrapper_created_at, crate::db::wrappers::TRANSPORT_NIP17);
             }
 
@@ -533,7 +583,7 @@ pub async fn commit_prepared_event(
                 }
                 RumorProcessingResult::TypingIndicator { profile_id, until } => {
                     let active_typers = {
-                        let mut state = crate::state::STATE.lock().await;
+                        let mut state = lock_state().await;
                         state.update_typing_and_get_active(&contact, &profile_id, until)
                     };
                     crate::traits::emit_event("typing-update", &serde_json::json!({
@@ -542,6 +592,14 @@ pub async fn commit_prepared_event(
                     }));
                     false
                 }
+                RumorProcessingResult::Presence { profile_id, until } => {
+                    crate::profile::sync::apply_presence(&profile_id, until).await;
+                    false
+                }
+                RumorProcessingResult::LocationUpdate { message_id, location } => {
+                    crate::location::apply_update(&contact, &message_id, location, is_mine).await;
+                    false
+                }
                 RumorProcessingResult::PivxPayment { gift_code, amount_piv, address, message_id, mut event } => {
                     if crate::db::events::event_exists(&event.id).unwrap_or(false) {
                         return false;
@@ -558,6 +616,44 @@ pub async fn commit_prepared_event(
                     }));
                     true
                 }
+                RumorProcessingResult::EcashToken { token, amount_sat, mint, message_id, mut event } => {
+                    if crate::db::events::event_exists(&event.id).unwrap_or(false) {
+                        return false;
+                    }
+                    event.wrapper_event_id = Some(wrapper_event_id.clone());
+                    let ts = event.created_at;
+                    let memo = serde_json::from_str::<serde_json::Value>(&event.content).ok()
+                        .and_then(|v| v.get("memo").and_then(|m| m.as_str().map(String::from)));
+                    let _ = crate::db::events::save_pivx_payment_event(&contact, event).await;
+                    crate::traits::emit_event("ecash_received", &serde_json::json!({
+                        "conversation_id": contact,
+                        "amount_sat": amount_sat, "mint": mint, "memo": memo,
+                        "message_id": message_id,
+                        "sender": sender.to_hex(), "is_mine": is_mine,
+                        "at": ts * 1000,
+                    }));
+                    // Claim it before the sender (or anyone the token leaked to) can spend it again. Redeeming
+                    // contacts the token's mint, so it's held to the chat's remote-content policy, and only
+                    // done unasked for a mint the wallet already uses; the bubble offers the rest by hand.
+                    if !is_mine
+                        && crate::remote_content::gate(&contact, false).is_ok()
+                        && crate::cashu::from_known_mint(&token)
+                    {
+                        let bg = crate::state::SessionGuard::capture();
+                        tokio::spawn(async move {
+                            if !bg.is_valid() {
+                                return;
+                            }
+                            match crate::cashu::receive_token(&token, Some(&contact), Some(&message_id)).await {
+                                Ok(amount) => crate::traits::emit_event("ecash_redeemed", &serde_json::json!({
+                                    "conversation_id": contact, "message_id": message_id, "amount": amount,
+                                })),
+                                Err(e) => log_warn!("[ecash] auto-redeem failed: {}", e),
+                            }
+                        });
+                    }
+                    true
+                }
                 RumorProcessingResult::UnknownEvent(mut event) => {
                     event.wrapper_event_id = Some(wrapper_event_id.clone());
                     // Store unknown events for forward compatibility
@@ -598,6 +694,10 @@ pub async fn commit_prepared_event(
                         commit_reaction_deletion(&target_event_id, &sender).await
                     }
                 }
+                RumorProcessingResult::SocialBackup { d_tag, content, sender_npub, created_at } => {
+                    crate::social_backup::handle_incoming(&d_tag, &content, &sender_npub, created_at).await;
+                    false
+                }
                 RumorProcessingResult::Ignored => false,
             }
         }
@@ -734,7 +834,13 @@ pub async fn commit_prepared_event(
             }
             false
         }
-        PreparedEvent::ErrorSkip { wrapper_id_bytes, wrapper_created_at } => {
+        PreparedEvent::BlockedSkip { wrapper_id_bytes, wrapper_created_at }
+        | PreparedEvent::GroupSkip { wrapper_id_bytes, wrapper_created_at } => {
+            WRAPPER_ID_CACHE.lock().await.insert(wrapper_id_bytes);
+            let _ = crate::db::wrappers::save_processed_wrapper(&wrapper_id_bytes, wrapper_created_at, crate::db::wrappers::TRANSPORT_NIP17);
+            false
+        }
+        PreparedEvent::ErrorSkip { wrapper_id_bytes, wrapper_created_at, .. } => {
             let _ = crate::db::wrappers::save_processed_wrapper(&wrapper_id_bytes, wrapper_created_at, crate::db::wrappers::TRANSPORT_NIP17);
             false
         }
@@ -785,7 +891,7 @@ async fn commit_dm_message(
 
     // Add to STATE (+ clear typing indicator for file senders)
     let added = {
-        let mut state = crate::state::STATE.lock().await;
+        let mut state = lock_state().await;
         let added = state.add_message_to_participant(contact, &msg);
         if is_file && added {
             state.update_typing_and_get_active(contact, contact, 0);
@@ -800,6 +906,12 @@ async fn commit_dm_message(
             "chat_id": contact
         }));
 
+        // Live messages resolve their nostr: references in the background; history synced in
+        // bulk resolves on demand when rendered.
+        if is_new {
+            crate::nostr_uri::resolve_in_background(contact, &msg.id, &msg.content);
+        }
+
         // Platform callback (notifications, badge, etc.)
         if is_file {
             handler.on_file_received(contact, &msg, is_new);
@@ -835,7 +947,7 @@ async fn commit_reaction(
 ) -> bool {
     // Add to STATE
     let msg_for_emit = {
-        let mut state = crate::state::STATE.lock().await;
+        let mut state = lock_state().await;
         if let Some((chat_id, was_added)) = state.add_reaction_to_message(&reaction.reference_id, reaction.clone()) {
             if was_added {
                 state.find_message(&reaction.reference_id)
@@ -884,7 +996,7 @@ async fn commit_edit(
     let _ = crate::db::events::save_event(event).await;
 
     let msg_for_emit = {
-        let mut state = crate::state::STATE.lock().await;
+        let mut state = lock_state().await;
         state.update_message_in_chat(contact, message_id, |msg| {
             msg.apply_edit(new_content.to_string(), edited_at, emoji_tags.clone());
         })
@@ -935,7 +1047,7 @@ async fn commit_deletion(
     // (me if true, chat counterpart if false). We derive the original
     // author from that, since the rumor pubkey isn't stored.
     let (mine, chat_id) = {
-        let state = crate::state::STATE.lock().await;
+        let state = lock_state().await;
         match state.find_message(target_event_id) {
             Some((chat, msg)) => (msg.mine, chat.id.clone()),
             None => return false,
@@ -973,7 +1085,7 @@ async fn commit_deletion(
 
     // Drop from in-memory state.
     let removed = {
-        let mut state = crate::state::STATE.lock().await;
+        let mut state = lock_state().await;
         state.remove_message(target_event_id)
     };
     let removed_msg = match removed {
@@ -1029,7 +1141,7 @@ async fn commit_deletion(
 /// the reaction or the sender isn't its author.
 async fn commit_reaction_deletion(target_reaction_id: &str, sender: &PublicKey) -> bool {
     let found = {
-        let state = crate::state::STATE.lock().await;
+        let state = lock_state().await;
         state.find_reaction(target_reaction_id)
     };
     let (chat_id, message_id, author_npub, _is_community) = match found {
@@ -1050,7 +1162,7 @@ async fn commit_reaction_deletion(target_reaction_id: &str, sender: &PublicKey)
     }
 
     let updated = {
-        let mut state = crate::state::STATE.lock().await;
+        let mut state = lock_state().await;
         state.remove_reaction_from_message(&message_id, target_reaction_id)
     };
     let message = match updated {
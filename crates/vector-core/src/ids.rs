@@ -0,0 +1,199 @@
+//! Typed user and chat identifiers.
+//!
+//! Three string shapes travel through the app: bech32 `npub`s, 64-char hex pubkeys, and 64-char
+//! hex Community channel ids. A hex pubkey and a channel id are indistinguishable by shape, and
+//! comparing an `npub` against the hex form of the same key silently fails, so raw strings are
+//! converted here, at the boundaries (relay events, DB rows, frontend arguments), and compared as
+//! [`UserId`] / [`ChatId`] rather than as text.
+//!
+//! The canonical string forms are unchanged: users and DM chats are keyed by `npub`, channels by
+//! lowercase hex.
+
+use std::fmt;
+
+use nostr_sdk::prelude::*;
+
+use crate::chat::ChatType;
+
+/// A user, whichever form their key arrived in.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct UserId(PublicKey);
+
+impl UserId {
+    /// Accepts `npub1…`, `nostr:npub1…` or 64-char hex.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let s = s.strip_prefix("nostr:").unwrap_or(s);
+        PublicKey::parse(s).map(Self).map_err(|_| format!("Invalid user id: {}", s))
+    }
+
+    pub fn pubkey(&self) -> PublicKey {
+        self.0
+    }
+
+    /// The canonical form: how profiles, DM chats and `mine` checks key a user.
+    pub fn npub(&self) -> String {
+        self.0.to_bech32().unwrap_or_else(|_| self.0.to_hex())
+    }
+
+    /// The form used in event tags and Community tables.
+    pub fn hex(&self) -> String {
+        self.0.to_hex()
+    }
+}
+
+impl From<PublicKey> for UserId {
+    fn from(pk: PublicKey) -> Self {
+        Self(pk)
+    }
+}
+
+impl fmt::Display for UserId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.npub())
+    }
+}
+
+impl serde::Serialize for UserId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.npub())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for UserId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A chat: a DM keyed by the other party, a Community channel keyed by its random id, or the
+/// device-drop chat (a DM with ourselves, see `device_drop`).
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ChatId {
+    Dm(UserId),
+    Channel(String),
+    Devices,
+}
+
+impl ChatId {
+    /// Parse a stored or frontend chat identifier. DM chats are always keyed by `npub`, so a
+    /// 64-char hex id is a channel, never a user.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        if s == crate::device_drop::CHAT_ID {
+            return Ok(ChatId::Devices);
+        }
+        if is_dm_chat_id(s) {
+            return UserId::parse(s).map(ChatId::Dm);
+        }
+        if s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Ok(ChatId::Channel(s.to_ascii_lowercase()));
+        }
+        Err(format!("Invalid chat id: {}", s))
+    }
+
+    /// The string the chat is stored and emitted under.
+    pub fn as_identifier(&self) -> String {
+        match self {
+            ChatId::Dm(user) => user.npub(),
+            ChatId::Channel(id) => id.clone(),
+            ChatId::Devices => crate::device_drop::CHAT_ID.to_string(),
+        }
+    }
+
+    pub fn chat_type(&self) -> ChatType {
+        match self {
+            ChatId::Dm(_) | ChatId::Devices => ChatType::DirectMessage,
+            ChatId::Channel(_) => ChatType::Community,
+        }
+    }
+
+    pub fn is_dm(&self) -> bool {
+        matches!(self, ChatId::Dm(_) | ChatId::Devices)
+    }
+}
+
+impl fmt::Display for ChatId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.as_identifier())
+    }
+}
+
+/// Whether `s` has the shape of a bech32 `npub`: `npub1` + 58 chars from the bech32 alphabet (no
+/// `1`, `b`, `i`, `o`). Cheap and checksum-free, for telling an npub from other ids (hex, account
+/// folder names); [`UserId::parse`] is what validates one.
+pub fn is_npub(s: &str) -> bool {
+    if s.len() != 63 || !s.starts_with("npub1") {
+        return false;
+    }
+    s.bytes().skip(5).all(|c| matches!(c,
+        b'q' | b'p' | b'z' | b'r' | b'y' | b'9' | b'x' | b'8' |
+        b'g' | b'f' | b'2' | b't' | b'v' | b'd' | b'w' | b'0' |
+        b's' | b'3' | b'j' | b'n' | b'5' | b'4' | b'k' | b'h' |
+        b'c' | b'e' | b'6' | b'm' | b'u' | b'a' | b'7' | b'l'
+    ))
+}
+
+/// Whether a stored chat identifier names a DM. Shape-only, so it also classifies ids that
+/// [`ChatId::parse`] would reject; use it where an unparseable id must still land somewhere.
+/// The device-drop chat (a DM with ourselves, see `device_drop`) counts as one.
+pub fn is_dm_chat_id(chat_id: &str) -> bool {
+    chat_id.starts_with("npub1") || chat_id == crate::device_drop::CHAT_ID
+}
+
+/// The chat type a stored chat identifier belongs to (see [`is_dm_chat_id`]).
+pub fn chat_type_of(chat_id: &str) -> ChatType {
+    if is_dm_chat_id(chat_id) { ChatType::DirectMessage } else { ChatType::Community }
+}
+
+/// Whether two user ids name the same key, in any mix of `npub` and hex. Ids that don't parse
+/// only match themselves.
+pub fn same_user(a: &str, b: &str) -> bool {
+    match (UserId::parse(a), UserId::parse(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_ids_compare_across_forms() {
+        let pk = Keys::generate().public_key();
+        let npub = pk.to_bech32().unwrap();
+        let hex = pk.to_hex();
+
+        assert_eq!(UserId::parse(&npub).unwrap(), UserId::parse(&hex).unwrap());
+        assert_eq!(UserId::parse(&format!("nostr:{}", npub)).unwrap().npub(), npub);
+        assert_eq!(UserId::parse(&npub).unwrap().hex(), hex);
+        assert!(same_user(&npub, &hex));
+        assert!(!same_user(&npub, &Keys::generate().public_key().to_hex()));
+        assert!(same_user("npub1me", "npub1me"));
+        assert!(UserId::parse("npub1me").is_err());
+    }
+
+    #[test]
+    fn chat_ids_classify_by_shape() {
+        let pk = Keys::generate().public_key();
+        let npub = pk.to_bech32().unwrap();
+
+        let dm = ChatId::parse(&npub).unwrap();
+        assert!(dm.is_dm());
+        assert_eq!(dm.as_identifier(), npub);
+        assert_eq!(dm.chat_type(), ChatType::DirectMessage);
+
+        // A hex pubkey is shaped like a channel id and is read as one
+        let channel = ChatId::parse(&pk.to_hex().to_ascii_uppercase()).unwrap();
+        assert_eq!(channel, ChatId::Channel(pk.to_hex()));
+        assert_eq!(channel.chat_type(), ChatType::Community);
+
+        assert!(ChatId::parse("not-a-chat").is_err());
+        let devices = ChatId::parse(crate::device_drop::CHAT_ID).unwrap();
+        assert_eq!((devices.as_identifier().as_str(), devices.chat_type()), (crate::device_drop::CHAT_ID, ChatType::DirectMessage));
+        assert_eq!(chat_type_of(&npub), ChatType::DirectMessage);
+        assert_eq!(chat_type_of("not-a-chat"), ChatType::Community);
+    }
+}
@@ -376,6 +376,32 @@ fn parse_relay_tags(tags: &Tags) -> Vec<String> {
         .collect()
 }
 
+/// Most NIP-65 read relays a single gift wrap is sent to, beyond our own write relays. Each one
+/// not already pooled costs an on-demand connection.
+const MAX_NIP65_READ_RELAYS: usize = 3;
+
+/// Extract the read relays from a kind-10002 (NIP-65) event: where the author reads, so where
+/// messages for them should go. A missing marker means read+write; "write"-only entries are
+/// dropped. Only `wss://` relays are kept, capped at [`MAX_NIP65_READ_RELAYS`].
+pub fn extract_read_relays(ev: &Event) -> Vec<String> {
+    let mut relays: Vec<String> = Vec::new();
+    for (url, marker) in nostr_sdk::nips::nip65::extract_relay_list(ev) {
+        if matches!(marker, Some(nostr_sdk::nips::nip65::RelayMetadata::Write)) {
+            continue;
+        }
+        let url = url.to_string();
+        if url.starts_with("wss://")
+            && !relays.iter().any(|r| normalize_relay_url(r) == normalize_relay_url(&url))
+        {
+            relays.push(url);
+        }
+        if relays.len() == MAX_NIP65_READ_RELAYS {
+            break;
+        }
+    }
+    relays
+}
+
 /// Generic cache-with-lock implementation used by both production and test code.
 /// Uses double-checked locking to prevent cache stampede: rapid requests to the
 /// same pubkey serialize through a per-key lock, so only one fetch happens.
@@ -492,6 +518,29 @@ pub fn trusted_relay_urls() -> Vec<RelayUrl> {
     TRUSTED_RELAY_URLS.clone()
 }
 
+/// Where a typing indicator to `npub` goes: Vector's trusted relays, unless the
+/// contact has a manual relay list with pooled members, which then take it
+/// instead. Indicators fire per keystroke burst and expire in seconds, so
+/// unlike a message they never on-demand connect a relay.
+pub async fn typing_relays(client: &Client, npub: &str) -> Vec<RelayUrl> {
+    if let crate::db::profile_relays::ContactRelays::Manual(relays) = crate::db::profile_relays::get_delivery(npub) {
+        let pooled: Vec<RelayUrl> = client.pool().all_relays().await
+            .into_keys()
+            .filter(|url| {
+                let norm = normalize_relay_url(url.as_str());
+                relays.iter().any(|r| normalize_relay_url(r) == norm)
+            })
+            .collect();
+        if !pooled.is_empty() {
+            return pooled;
+        }
+    }
+    crate::state::active_trusted_relays().await
+        .into_iter()
+        .filter_map(|s| RelayUrl::parse(s).ok())
+        .collect()
+}
+
 /// Send an event to specific relays, returning as soon as the **first** relay
 /// acknowledges success. Remaining relays continue sending in the background.
 ///
@@ -698,23 +747,55 @@ pub async fn send_gift_wrap_retained(
 }
 
 /// Resolve where a gift wrap for `recipient` should be published:
-/// their kind-10050 inbox relays when advertised (on-demand connecting
-/// any that are not already pooled, as transient members), otherwise
-/// our pool's write-relays.
+/// their kind-10050 inbox relays when advertised, otherwise our pool's
+/// write-relays plus the read relays from their NIP-65 list (stored by
+/// profile sync). Recipient relays not already pooled are on-demand
+/// connected as transient members.
+///
+/// A per-contact override (`db::profile_relays::ContactRelays`) replaces
+/// all of that: a manual list is targeted as-is (connected on demand like
+/// an inbox), trusted-only targets Vector's trusted relays in the pool.
 pub async fn resolve_gift_wrap_targets(
     client: &Client,
     recipient: &PublicKey,
 ) -> GiftWrapTargets {
-    let inbox_strs = get_or_fetch_inbox_relays(client, recipient).await;
+    use crate::db::profile_relays::ContactRelays;
+    let delivery = recipient.to_bech32().ok()
+        .map(|npub| crate::db::profile_relays::get_delivery(&npub))
+        .unwrap_or_default();
+    let inbox_strs = match &delivery {
+        ContactRelays::Auto => get_or_fetch_inbox_relays(client, recipient).await,
+        ContactRelays::Manual(relays) => relays.clone(),
+        ContactRelays::Trusted => crate::state::active_trusted_relays().await
+            .into_iter().map(str::to_string).collect(),
+    };
+    // The recipient's own relays (10050 inbox, else NIP-65 read) — the
+    // ones that may need an on-demand connection.
+    let recipient_strs: Vec<String> = if !inbox_strs.is_empty() {
+        inbox_strs.clone()
+    } else if delivery != ContactRelays::Auto {
+        Vec::new()
+    } else {
+        recipient.to_bech32().ok()
+            .and_then(|npub| crate::db::profile_relays::get_read_relays(&npub).ok())
+            .unwrap_or_default()
+    };
     let targeted_strs: Vec<String> = if !inbox_strs.is_empty() {
         inbox_strs.clone()
     } else {
         let pool = client.pool();
         let relays = pool.relays().await;
-        relays.iter()
+        let mut strs: Vec<String> = relays.iter()
             .filter(|(_, r)| r.flags().has_write())
             .map(|(url, _)| url.to_string())
-            .collect()
+            .collect();
+        for s in &recipient_strs {
+            let norm = normalize_relay_url(s);
+            if !strs.iter().any(|t| normalize_relay_url(t) == norm) {
+                strs.push(s.clone());
+            }
+        }
+        strs
     };
     // Resolve to live Relay handles in the pool. Strict HashMap lookup by
     // `RelayUrl` was missing visually-identical URLs because nostr-sdk
@@ -745,14 +826,14 @@ pub async fn resolve_gift_wrap_targets(
         })
         .collect();
 
-    // On-demand connect: inbox relays not already in the pool are added +
+    // On-demand connect: recipient relays not already in the pool are added +
     // connected just for this send, then removed afterwards (transient_added).
-    // The recipient's inbox relays are theirs, not ours — keeping them would
-    // pollute the pool, which the reconcile loop owns. Only for real inbox
-    // relays; the pool-write fallback already targets live pool members.
+    // The recipient's relays are theirs, not ours — keeping them would
+    // pollute the pool, which the reconcile loop owns. Only for the
+    // recipient's own relays; the pool-write set already targets live pool members.
     let mut transient_added: Vec<RelayUrl> = Vec::new();
-    if !inbox_strs.is_empty() {
-        for s in &targeted_strs {
+    if !recipient_strs.is_empty() {
+        for s in &recipient_strs {
             let norm = normalize_url_for_match(s);
             let in_pool = pool_norm.iter().any(|(p, _, _)| p == &norm);
             let already_added = transient_added.iter()
@@ -770,19 +851,31 @@ pub async fn resolve_gift_wrap_targets(
         }
         if !transient_added.is_empty() {
             crate::log_info!(
-                "[InboxRelays] on-demand connected {} inbox relay(s) for {} (transient)",
+                "[InboxRelays] on-demand connected {} recipient relay(s) for {} (transient)",
                 transient_added.len(),
                 recipient,
             );
         }
     }
 
-    if !inbox_strs.is_empty() {
-        println!(
+    if delivery != ContactRelays::Auto && !inbox_strs.is_empty() {
+        crate::log_debug!(
+            "[InboxRelays] Routing gift-wrap to {} contact-chosen relays for {}",
+            resolved.len(),
+            recipient
+        );
+    } else if !inbox_strs.is_empty() {
+        crate::log_debug!(
             "[InboxRelays] Routing gift-wrap to {} inbox relays for {}",
             resolved.len(),
             recipient
         );
+    } else if !recipient_strs.is_empty() {
+        crate::log_debug!(
+            "[InboxRelays] No 10050 for {}; adding {} NIP-65 read relay(s) to our write relays",
+            recipient,
+            recipient_strs.len()
+        );
     }
 
     GiftWrapTargets {
@@ -1388,6 +1481,146 @@ pub fn republish_inbox_relays_debounced() {
     });
 }
 
+// ============================================================================
+// Own-list health — can senders find our inbox?
+// ============================================================================
+//
+// Every DM and Community invite reaches us through our 10050: a sender
+// looks it up on whatever relays they share with us (or the Discovery
+// Relays), and with no list they fall back to guessing. A list that was
+// published once but has since been pruned from a relay, or lost a
+// replaceable-event race there, leaves us un-invitable from that relay's
+// side without any local symptom. The boot check reads each relay's copy
+// on its own and rebroadcasts the newest signed event (verbatim, no
+// re-sign) wherever it's missing or behind.
+
+/// One relay's copy of our list.
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelayListState {
+    /// Holds the newest list.
+    Current,
+    /// Holds an older list.
+    Stale,
+    /// Answered, but holds no list.
+    Missing,
+    /// Didn't answer.
+    Unreachable,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RelayListCheck {
+    pub url: String,
+    pub state: RelayListState,
+}
+
+/// Outcome of [`check_own_list_health`], emitted as `inbox_list_health`.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct InboxListHealth {
+    /// `healthy`, `healed`, `degraded` (some relays are behind and wouldn't
+    /// take the rebroadcast), `unusable` (the newest list names no usable
+    /// relay) or `missing` (no relay holds one).
+    pub status: &'static str,
+    pub relays: Vec<RelayListCheck>,
+    /// `wss://` relays the newest list names.
+    pub usable_relays: usize,
+    /// Relays the newest list was rebroadcast to and accepted it.
+    pub healed: Vec<String>,
+}
+
+fn classify(newest_at: Option<u64>, held_at: Option<Option<u64>>) -> RelayListState {
+    match (held_at, newest_at) {
+        (None, _) => RelayListState::Unreachable,
+        (Some(None), _) => RelayListState::Missing,
+        (Some(Some(at)), Some(newest)) if at < newest => RelayListState::Stale,
+        (Some(Some(_)), _) => RelayListState::Current,
+    }
+}
+
+/// Read our 10050 from each connected read + Discovery relay separately,
+/// and rebroadcast the newest copy to those missing it or holding an older
+/// one. `Err` when no relay could be asked.
+pub async fn check_own_list_health(client: &Client) -> Result<InboxListHealth, String> {
+    let me = crate::state::my_public_key().ok_or("no active pubkey")?;
+    let session = crate::state::SessionGuard::capture();
+    let relays = client.pool().all_relays().await;
+    let targets: Vec<RelayUrl> = inbox_query_targets(client)
+        .await
+        .into_iter()
+        .filter(|url| relays.get(url).is_some_and(|r| r.status() == RelayStatus::Connected))
+        .collect();
+    if targets.is_empty() {
+        return Err("no query target connected".to_string());
+    }
+
+    let filter = Filter::new().author(me).kind(Kind::Custom(10050)).limit(1);
+    let answers = futures_util::future::join_all(targets.iter().map(|url| {
+        let filter = filter.clone();
+        async move {
+            client
+                .fetch_events_from(vec![url.clone()], filter, std::time::Duration::from_secs(6))
+                .await
+                .ok()
+                .map(|events| events.into_iter().max_by_key(|e| e.created_at))
+        }
+    }))
+    .await;
+    if !session.is_valid() {
+        return Err("session changed".to_string());
+    }
+
+    let newest: Option<Event> = answers
+        .iter()
+        .flatten()
+        .flatten()
+        .max_by(|a, b| a.created_at.cmp(&b.created_at).then(b.id.cmp(&a.id)))
+        .cloned();
+    let newest_at = newest.as_ref().map(|e| e.created_at.as_secs());
+    let checks: Vec<RelayListCheck> = targets
+        .iter()
+        .zip(&answers)
+        .map(|(url, answer)| RelayListCheck {
+            url: url.to_string(),
+            state: classify(newest_at, answer.as_ref().map(|held| held.as_ref().map(|e| e.created_at.as_secs()))),
+        })
+        .collect();
+
+    let Some(newest) = newest else {
+        return Ok(InboxListHealth { status: "missing", relays: checks, usable_relays: 0, healed: Vec::new() });
+    };
+    let usable_relays = parse_relay_tags(&newest.tags)
+        .iter()
+        .filter(|u| u.starts_with("wss://") && RelayUrl::parse(u).is_ok())
+        .count();
+
+    let behind: Vec<RelayUrl> = targets
+        .iter()
+        .zip(&checks)
+        .filter(|(_, c)| matches!(c.state, RelayListState::Missing | RelayListState::Stale))
+        .map(|(url, _)| url.clone())
+        .collect();
+    let any_behind = !behind.is_empty();
+    let mut healed = Vec::new();
+    if any_behind && usable_relays > 0 {
+        match client.send_event_to(behind, &newest).await {
+            Ok(out) => healed = out.success.iter().map(|u| u.to_string()).collect(),
+            Err(e) => crate::log_warn!("[InboxRelays] List rebroadcast failed: {}", e),
+        }
+        healed.sort();
+    }
+
+    let status = if usable_relays == 0 {
+        "unusable"
+    } else if !any_behind {
+        "healthy"
+    } else if healed.is_empty() {
+        "degraded"
+    } else {
+        "healed"
+    };
+    Ok(InboxListHealth { status, relays: checks, usable_relays, healed })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1692,6 +1925,42 @@ mod tests {
         assert_eq!(result, vec!["wss://good.example.com".to_string()]);
     }
 
+    #[test]
+    fn extract_read_relays_skips_write_only_and_caps() {
+        let r = |url: &str, marker: Option<&str>| {
+            let mut values = vec![url.to_string()];
+            values.extend(marker.map(str::to_string));
+            Tag::custom(TagKind::custom("r"), values)
+        };
+        let event = EventBuilder::new(Kind::RelayList, "")
+            .tags([
+                r("wss://write.example.com", Some("write")),
+                r("wss://both.example.com", None),
+                r("wss://both.example.com/", Some("read")),
+                r("ws://plain.example.com", Some("read")),
+                r("wss://read.example.com", Some("read")),
+                r("wss://third.example.com", None),
+                r("wss://over-cap.example.com", None),
+            ])
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+        let relays: Vec<String> = extract_read_relays(&event).iter().map(|u| normalize_relay_url(u)).collect();
+        assert_eq!(relays, vec![
+            "wss://both.example.com",
+            "wss://read.example.com",
+            "wss://third.example.com",
+        ]);
+    }
+
+    #[test]
+    fn list_health_classifies_each_relay_against_the_newest() {
+        assert_eq!(classify(Some(20), None), RelayListState::Unreachable);
+        assert_eq!(classify(Some(20), Some(None)), RelayListState::Missing);
+        assert_eq!(classify(Some(20), Some(Some(10))), RelayListState::Stale);
+        assert_eq!(classify(Some(20), Some(Some(20))), RelayListState::Current);
+        assert_eq!(classify(None, Some(None)), RelayListState::Missing);
+    }
+
     #[test]
     fn parse_relay_tags_empty() {
         let tags = Tags::new();
@@ -0,0 +1,179 @@
+//! Key-usage audit log — which subsystems touched the decrypted nsec or the at-rest encryption key,
+//! and when.
+//!
+//! Hooks sit where the keys surface: [`crate::crypto::GuardedSigner`] records signs and
+//! encrypt/decrypt calls, `GuardedKey::to_keys` records direct nsec reads (attributed to the
+//! calling source file), and `GuardedKey::set` records a key being loaded. Key exports are recorded
+//! by the export command. The at-rest key's per-row reads are not logged: they happen on every
+//! database access and would drown out everything else.
+//!
+//! Uses are coalesced in memory per (operation, subsystem, detail) and flushed to the encrypted
+//! `key_audit` table every [`FLUSH_SECS`], on read, and before the database closes. A sync that
+//! unwraps a thousand gift wraps is one row with a count, not a thousand rows.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use crate::clock::now_secs;
+
+/// Entries per page of [`get_key_audit_log`].
+pub const PAGE_SIZE: u32 = 50;
+
+/// How long uses coalesce in memory before they're written out.
+const FLUSH_SECS: u64 = 30;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyOp {
+    /// An event signed with the nsec.
+    SignEvent,
+    /// NIP-44/NIP-04 encryption with the nsec.
+    Encrypt,
+    /// NIP-44/NIP-04 decryption with the nsec (gift-wrap unwraps land here).
+    Decrypt,
+    /// The nsec read out of the vault directly.
+    KeyAccess,
+    /// The nsec or seed phrase shown to the user for backup.
+    ExportKeys,
+    /// A key (nsec or encryption key) loaded into its vault.
+    KeyLoaded,
+}
+
+/// Uses of one (operation, subsystem, detail) within a flush window.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct KeyAuditEntry {
+    pub op: KeyOp,
+    /// Where the use came from: a source path like `community/v2/service`, or a named
+    /// component (`signer`).
+    pub subsystem: String,
+    /// Extra context, e.g. `kind 1059` or `nip44`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub count: u64,
+    pub first_at: u64,
+    pub last_at: u64,
+}
+
+type Key = (KeyOp, String, Option<String>);
+
+static PENDING: LazyLock<Mutex<HashMap<Key, KeyAuditEntry>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+static LAST_FLUSH: AtomicU64 = AtomicU64::new(0);
+static FLUSH_SCHEDULED: AtomicBool = AtomicBool::new(false);
+
+/// `crates/vector-core/src/community/v2/service.rs` → `community/v2/service`.
+fn subsystem_of(file: &str) -> String {
+    let file = file.replace('\\', "/");
+    let path = file.rsplit_once("src/").map(|(_, p)| p).unwrap_or(&file);
+    path.trim_end_matches(".rs").to_string()
+}
+
+/// Record one use of a key.
+pub fn record(op: KeyOp, subsystem: &str, detail: Option<String>) {
+    let now = now_secs();
+    {
+        let Ok(mut pending) = PENDING.lock() else { return };
+        pending
+            .entry((op, subsystem.to_string(), detail.clone()))
+            .and_modify(|e| {
+                e.count += 1;
+                e.last_at = now;
+            })
+            .or_insert_with(|| KeyAuditEntry {
+                op,
+                subsystem: subsystem.to_string(),
+                detail,
+                count: 1,
+                first_at: now,
+                last_at: now,
+            });
+    }
+
+    // Write out in the background once the window has passed. Never inline: a key can be used
+    // while the caller holds the database's write connection.
+    if now.saturating_sub(LAST_FLUSH.load(Ordering::Relaxed)) >= FLUSH_SECS
+        && !FLUSH_SCHEDULED.swap(true, Ordering::AcqRel)
+    {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let session = crate::state::SessionGuard::capture();
+                handle.spawn_blocking(move || {
+                    flush_session(Some(&session));
+                    FLUSH_SCHEDULED.store(false, Ordering::Release);
+                });
+            }
+            Err(_) => FLUSH_SCHEDULED.store(false, Ordering::Release),
+        }
+    }
+}
+
+/// Record one use of a key, attributed to the caller's source file.
+#[track_caller]
+pub fn record_caller(op: KeyOp, detail: Option<String>) {
+    record(op, &subsystem_of(std::panic::Location::caller().file()), detail);
+}
+
+/// Write pending uses to the current account's log. Called before the database closes, so an
+/// account's uses never spill into the next account's log; entries that can't be written are
+/// dropped.
+pub fn flush() {
+    flush_session(None);
+}
+
+/// [`flush`], for the background write: when the account swapped since it was scheduled (the swap
+/// flushed on its way out), what's pending can't be told apart by account, so it's dropped rather
+/// than written into the next account's log.
+fn flush_session(session: Option<&crate::state::SessionGuard>) {
+    LAST_FLUSH.store(now_secs(), Ordering::Relaxed);
+    let entries: Vec<KeyAuditEntry> = match PENDING.lock() {
+        Ok(mut pending) if !pending.is_empty() => pending.drain().map(|(_, e)| e).collect(),
+        _ => return,
+    };
+    if session.is_some_and(|s| !s.is_valid()) {
+        return;
+    }
+    if let Err(e) = crate::db::key_audit::insert_entries(&entries) {
+        crate::log_warn!("[KeyAudit] Failed to write {} entries: {}", entries.len(), e);
+    }
+}
+
+/// One page of the audit log (0 = most recent), including uses not yet written out.
+pub fn get_key_audit_log(page: u32) -> Result<Vec<KeyAuditEntry>, String> {
+    flush();
+    crate::db::key_audit::page(page, PAGE_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subsystem_from_source_path() {
+        assert_eq!(subsystem_of("crates/vector-core/src/community/v2/service.rs"), "community/v2/service");
+        assert_eq!(subsystem_of("src-tauri\\src\\commands\\account.rs"), "commands/account");
+        assert_eq!(subsystem_of("lib.rs"), "lib");
+    }
+
+    #[tokio::test]
+    async fn uses_coalesce_and_page_newest_first() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+        PENDING.lock().unwrap().clear();
+
+        for _ in 0..3 {
+            record(KeyOp::Decrypt, "signer", Some("nip44".into()));
+        }
+        record(KeyOp::ExportKeys, "commands/account", None);
+        let log = get_key_audit_log(0).unwrap();
+        let decrypts = log.iter().find(|e| e.op == KeyOp::Decrypt).unwrap();
+        assert_eq!((decrypts.count, decrypts.subsystem.as_str(), decrypts.detail.as_deref()), (3, "signer", Some("nip44")));
+        assert!(log.iter().any(|e| e.op == KeyOp::ExportKeys));
+        assert!(PENDING.lock().unwrap().is_empty(), "reading flushes");
+        assert!(get_key_audit_log(1).unwrap().is_empty());
+    }
+}
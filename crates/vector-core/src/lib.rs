@@ -32,6 +32,7 @@ mod macros;
 pub mod logging;
 pub mod error;
 pub mod traits;
+pub mod clock;
 
 // Nostr SDK trait imports needed for bech32 operations
 use nostr_sdk::prelude::ToBech32;
@@ -41,14 +42,21 @@ pub mod types;
 pub mod profile;
 pub mod chat;
 pub mod compact;
+pub mod code_blocks;
+pub mod nostr_uri;
+pub mod ids;
 
 // === State ===
 pub mod state;
+pub mod context;
 
 // === Debug Stats ===
 #[cfg(debug_assertions)]
 pub mod stats;
 
+// === Startup Timing (diagnostics) ===
+pub mod startup;
+
 // === Crypto ===
 pub mod crypto;
 
@@ -63,22 +71,49 @@ pub mod db;
 
 // === Network ===
 pub mod net;
+pub mod proxy;
+pub mod remote_content;
+pub mod relay_health;
 pub mod negentropy;
 pub mod blossom;
 pub mod blossom_servers;
 pub mod blossom_capabilities;
+pub mod blossom_health;
+pub mod consent;
+pub mod contact_verification;
+pub mod contacts;
+pub mod media_cache;
 pub mod inbox_relays;
 pub mod emoji_packs;
 pub mod emoji_usage;
 pub mod badges;
+pub mod verified;
+pub mod announcements;
+pub mod zaps;
+pub mod cashu;
+pub mod starred;
+pub mod settings_sync;
+pub mod chat_state_sync;
+pub(crate) mod sync_store;
+pub mod appearance;
+pub mod mute_list;
+pub mod accessibility;
+pub mod social_backup;
+pub mod typing;
+pub mod presence;
+pub mod link_preview;
+pub mod location;
+pub mod account_bundle;
+pub mod activity_report;
+pub mod key_audit;
 pub mod bot_interface;
 pub mod webxdc;
 #[cfg(feature = "tor")]
 pub mod tor;
 
-/// Build a `nostr_sdk::ClientOptions` with the embedded-Tor SOCKS proxy
-/// applied if (and only if) the `tor` feature is on AND `tor::TorService` is
-/// currently active. When Tor is off, returns the default options unchanged.
+/// Build a `nostr_sdk::ClientOptions` with the active SOCKS proxy applied —
+/// the embedded Tor service when it's enabled, else the user's own proxy (see
+/// [`proxy::transport`]). With neither, returns the default options unchanged.
 ///
 /// Note: nostr-sdk's `proxy()` lives on `Connection`, not `ClientOptions`
 /// directly — we build a `Connection` with the proxy mode and pass it via
@@ -86,7 +121,7 @@ pub mod tor;
 /// `#[cfg(not(target_arch = "wasm32"))]`, but Vector targets are all native.
 ///
 /// Callers should use this rather than `ClientOptions::new()` directly so the
-/// Tor toggle automatically covers their relay traffic.
+/// Tor toggle and proxy settings automatically cover their relay traffic.
 pub fn nostr_client_options() -> nostr_sdk::ClientOptions {
     // NIP-42: authenticate to relays that challenge, using the account signer. Many
     // Concord/Armada communities live on AUTH-gating relays (Ditto's default gates
@@ -95,49 +130,50 @@ pub fn nostr_client_options() -> nostr_sdk::ClientOptions {
     // up empty. Auto-auth unlocks those reads; a relay that doesn't challenge is
     // unaffected.
     let opts = nostr_sdk::ClientOptions::new().automatic_authentication(true);
-    #[cfg(all(feature = "tor", not(target_arch = "wasm32")))]
+    #[cfg(not(target_arch = "wasm32"))]
     {
-        match tor::transport_state() {
-            tor::TorTransportState::Active(addr) => {
+        match proxy::transport() {
+            proxy::Transport::Proxy(addr) => {
                 return opts.connection(nostr_sdk::client::Connection::new().proxy(addr));
             }
-            tor::TorTransportState::RequiredButInactive => {
-                // Tor failsafe: route to a blackhole so the relay socket can't
-                // accidentally come up direct while Tor is mid-bootstrap.
+            proxy::Transport::Blocked => {
+                // Failsafe: route to a blackhole so the relay socket can't
+                // accidentally come up direct while Tor is mid-bootstrap or
+                // the kill-switched proxy is down.
                 return opts.connection(
-                    nostr_sdk::client::Connection::new().proxy(tor::blackhole_proxy_addr()),
+                    nostr_sdk::client::Connection::new().proxy(proxy::blackhole_addr()),
                 );
             }
-            tor::TorTransportState::Disabled => {}
+            proxy::Transport::Direct => {}
         }
     }
     opts
 }
 
-/// Augment a `RelayOptions` with the Tor connection mode when active. Used
-/// by every site that adds a relay to the pool — default relays at boot,
+/// Augment a `RelayOptions` with the proxy connection mode when one applies.
+/// Used by every site that adds a relay to the pool — default relays at boot,
 /// custom user relays, community relays, NIP-17 inbox relays — so they all
-/// come up through Tor when the toggle is on.
+/// come up through Tor (or the user's proxy) when it's on.
 ///
 /// Without this, `RelayOptions::new()` (or the per-mode helper) defaults to
 /// `ConnectionMode::Direct`, and relays added at boot would stay direct even
 /// after Tor is bootstrapped — `switch_relay_transport()` covers existing
 /// relays on toggle, but not freshly-added ones.
 pub fn tor_aware_relay_options(opts: nostr_sdk::RelayOptions) -> nostr_sdk::RelayOptions {
-    #[cfg(all(feature = "tor", not(target_arch = "wasm32")))]
+    #[cfg(not(target_arch = "wasm32"))]
     {
-        match tor::transport_state() {
-            tor::TorTransportState::Active(addr) => {
+        match proxy::transport() {
+            proxy::Transport::Proxy(addr) => {
                 return opts.connection_mode(nostr_sdk::pool::ConnectionMode::proxy(addr));
             }
-            tor::TorTransportState::RequiredButInactive => {
-                // Tor failsafe: pin to blackhole so this relay can never come
-                // up direct while Tor isn't running.
+            proxy::Transport::Blocked => {
+                // Failsafe: pin to blackhole so this relay can never come
+                // up direct while the required proxy isn't running.
                 return opts.connection_mode(
-                    nostr_sdk::pool::ConnectionMode::proxy(tor::blackhole_proxy_addr()),
+                    nostr_sdk::pool::ConnectionMode::proxy(proxy::blackhole_addr()),
                 );
             }
-            tor::TorTransportState::Disabled => {}
+            proxy::Transport::Direct => {}
         }
     }
     opts
@@ -176,6 +212,7 @@ pub mod rumor;
 
 // === Messaging ===
 pub mod sending;
+pub mod compression;
 
 // === Per-DM Wallpapers ===
 pub mod wallpaper;
@@ -183,6 +220,8 @@ pub mod wallpaper;
 // === Message Deletion (NIP-09 against retained gift-wraps) ===
 pub mod deletion;
 pub mod self_destruct;
+pub mod view_once;
+pub mod device_drop;
 
 // === SIMD Operations ===
 pub mod simd;
@@ -224,7 +263,7 @@ pub use nip55::{
     VECTOR_NIP55_SIGN_KINDS, VECTOR_NIP55_ENCRYPT_TYPES,
 };
 pub use error::{VectorError, Result};
-pub use traits::{EventEmitter, NoOpEmitter, set_event_emitter, emit_event};
+pub use traits::{EventEmitter, NoOpEmitter, set_event_emitter, emit_event, SecretStore, set_secret_store};
 pub use db::{set_app_data_dir, get_app_data_dir};
 pub use sending::{SendCallback, NoOpSendCallback, SendConfig, SendResult};
 pub use deletion::{delete_own_dm, DeleteOutcome};
@@ -377,6 +416,79 @@ impl VectorCore {
         Ok(LoginResult { npub, has_encryption })
     }
 
+    /// Login through a NIP-46 remote signer (`bunker://…`), keeping the user's nsec off this device.
+    ///
+    /// The account is whichever identity the signer answers for; nothing is opened until it has. The
+    /// first login pairs a fresh client keypair with the signer and persists it with the bunker URL
+    /// (same rows the app writes), so later logins — with the same URI, or the stored one from
+    /// [`db::get_bunker_url`] — find that pairing by the signer's pubkey and reuse it instead of
+    /// asking the signer to approve a new device.
+    /// Every signature then goes through [`WatchedBunkerSigner`], which tracks online/offline and lets
+    /// the next call retry after a connection blip.
+    pub async fn login_with_bunker(&self, bunker_uri: &str, password: Option<&str>) -> Result<LoginResult> {
+        use nostr_sdk::prelude::*;
+
+        let remote_hex = signer::parse_bunker_remote_pubkey(bunker_uri).map_err(VectorError::Nostr)?;
+
+        // Reuse this device's pairing with that signer when an account already holds one
+        let pairing = db::find_bunker_pairing(&remote_hex);
+        let mut password_key = None;
+        let client_keys = match &pairing {
+            Some(pairing) => {
+                let nsec = if pairing.pkey.starts_with("nsec1") {
+                    pairing.pkey.clone()
+                } else {
+                    let pwd = password.ok_or_else(|| VectorError::Crypto("This account is encrypted — a password is required".to_string()))?;
+                    let key = crate::crypto::hash_pass(pwd).await;
+                    let nsec = crate::crypto::decrypt_with_key(&pairing.pkey, &key)
+                        .map_err(|_| VectorError::Crypto("Incorrect password".to_string()))?;
+                    password_key = Some(key);
+                    nsec
+                };
+                Keys::parse(&nsec).map_err(|_| VectorError::Crypto("Invalid stored key".to_string()))?
+            }
+            None => Keys::generate(),
+        };
+
+        // 60s: a first pairing waits on the user approving it in their signer app
+        let signer_pk = signer::attempt_bunker_login(bunker_uri, client_keys.clone(), std::time::Duration::from_secs(60))
+            .await
+            .map_err(VectorError::Nostr)?;
+        let npub = signer_pk.to_bech32()
+            .map_err(|e| VectorError::Nostr(format!("Failed to encode npub: {}", e)))?;
+        let opened = open_bunker_account(bunker_uri, &remote_hex, &npub, pairing.as_ref(), password, password_key, &client_keys).await;
+        let has_encryption = match opened {
+            Ok(has_encryption) => has_encryption,
+            Err(e) => {
+                if let Some(bunker) = signer::drain_bunker_state() {
+                    let _ = bunker.shutdown().await;
+                }
+                return Err(e);
+            }
+        };
+
+        state::MY_SECRET_KEY.set(client_keys.secret_key().to_secret_bytes(), &[&state::ENCRYPTION_KEY]);
+        state::set_my_public_key(signer_pk);
+        signer::set_signer_kind(signer::SignerKind::Bunker);
+
+        let bunker = signer::bunker_signer()
+            .ok_or_else(|| VectorError::NotInitialized("Bunker signer not installed".to_string()))?;
+        let client = ClientBuilder::new()
+            .signer(signer::WatchedBunkerSigner::new(bunker))
+            .opts(nostr_client_options())
+            .monitor(Monitor::new(1024))
+            .build();
+        for relay in state::TRUSTED_RELAYS {
+            let opts = tor_aware_relay_options(nostr_sdk::RelayOptions::default());
+            client.pool().add_relay(*relay, opts).await.ok();
+        }
+        // Wait for the relays here, so callers can use the client as soon as this returns
+        let _ = client.try_connect(std::time::Duration::from_secs(10)).await;
+        state::set_nostr_client(client);
+
+        Ok(LoginResult { npub, has_encryption })
+    }
+
     /// Generate a fresh random account secret key (bech32 nsec). Lets a headless client spin up a
     /// brand-new identity (`add_account` with no key) without depending on nostr-sdk directly.
     pub fn generate_nsec(&self) -> Result<String> {
@@ -403,36 +515,38 @@ impl VectorCore {
     }
 
     /// Download a received attachment and decrypt it to plaintext bytes. Fetches the encrypted blob
-    /// from its Blossom URL (SSRF/Tor-aware client, size-capped) and AES-decrypts with the
-    /// attachment's embedded key + nonce.
+    /// from its Blossom URL (SSRF/Tor-aware client, size-capped), falling back to its mirrors, and
+    /// AES-decrypts with the attachment's embedded key + nonce. A chunked upload is reassembled
+    /// from its manifest.
     pub async fn download_attachment(&self, attachment: &Attachment) -> Result<Vec<u8>> {
-        use futures_util::StreamExt;
         const MAX_DOWNLOAD: usize = 256 * 1024 * 1024;
         if attachment.url.is_empty() {
             return Err(VectorError::Other("attachment has no URL".into()));
         }
-        // SSRF guard: the URL is attacker-controlled (off an inbound message). build_http_client only
-        // validates redirect HOPS, not the initial request — so validate it here (matches the native
-        // download path). With Tor off this is the only egress guard.
-        crate::net::validate_url_not_private(&attachment.url)
-            .map_err(|e| VectorError::Other(e.to_string()))?;
-        let client = crate::net::build_http_client(std::time::Duration::from_secs(120)).map_err(VectorError::Other)?;
-        let resp = client.get(&attachment.url).send().await
-            .map_err(|e| VectorError::Other(format!("download: {e}")))?;
-        if !resp.status().is_success() {
-            return Err(VectorError::Other(format!("download failed: HTTP {}", resp.status())));
-        }
-        // Stream with a cap so a hostile/oversized blob can't OOM the process.
-        let mut encrypted: Vec<u8> = Vec::with_capacity(
-            resp.content_length().map(|l| (l as usize).min(MAX_DOWNLOAD)).unwrap_or(64 * 1024),
-        );
-        let mut stream = resp.bytes_stream();
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|e| VectorError::Other(format!("read body: {e}")))?;
-            if encrypted.len() + chunk.len() > MAX_DOWNLOAD {
+        if attachment.viewed {
+            return Err(VectorError::Other("view-once attachment was already viewed".into()));
+        }
+        // The server the sender uploaded to first, then each mirror they made
+        let mut fetched = Err(VectorError::Other("attachment has no URL".into()));
+        for url in std::iter::once(&attachment.url).chain(&attachment.mirrors) {
+            fetched = fetch_capped(url, MAX_DOWNLOAD).await;
+            if fetched.is_ok() {
+                break;
+            }
+        }
+        let mut encrypted = fetched?;
+        if let Some(manifest) = blossom::parse_chunk_manifest(&encrypted) {
+            if manifest.size > MAX_DOWNLOAD as u64 {
                 return Err(VectorError::Other("attachment exceeds 256 MiB cap".into()));
             }
-            encrypted.extend_from_slice(&chunk);
+            encrypted = Vec::with_capacity(manifest.size as usize);
+            for chunk in &manifest.chunks {
+                let bytes = fetch_capped(&chunk.url, chunk.size as usize).await?;
+                if bytes.len() as u64 != chunk.size || crate::crypto::sha256_hex(&bytes) != chunk.sha256 {
+                    return Err(VectorError::Other("chunk does not match its manifest".into()));
+                }
+                encrypted.extend_from_slice(&bytes);
+            }
         }
         crate::crypto::decrypt_data(&encrypted, &attachment.key, &attachment.nonce).map_err(VectorError::Other)
     }
@@ -554,7 +668,8 @@ impl VectorCore {
             emoji_url: emoji_url.map(|s| s.to_string()),
         };
         let msg_for_save = {
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             match st.add_reaction_to_message(reference_id, reaction) {
                 Some((cid, true)) => st.find_message(reference_id).map(|(_, m)| (cid, m)),
                 _ => None,
@@ -579,7 +694,7 @@ impl VectorCore {
         let my_public_key = state::my_public_key().ok_or(VectorError::Other("Not logged in".into()))?;
         let pubkey = PublicKey::from_bech32(to_npub).map_err(|e| VectorError::Nostr(e.to_string()))?;
 
-        let expiry = Timestamp::from_secs(Timestamp::now().as_secs() + 30);
+        let expiry = Timestamp::from_secs(crate::clock::now_secs() + 30);
         let rumor = EventBuilder::new(Kind::ApplicationSpecificData, "typing")
             .tag(Tag::public_key(pubkey))
             .tag(Tag::custom(TagKind::d(), vec!["vector"]))
@@ -587,7 +702,7 @@ impl VectorCore {
             .build(my_public_key);
 
         client.gift_wrap_to(
-            state::active_trusted_relays().await,
+            inbox_relays::typing_relays(&client, to_npub).await,
             &pubkey,
             rumor,
             [Tag::expiration(expiry)],
@@ -626,7 +741,8 @@ impl VectorCore {
 
         // Optimistic local echo + best-effort persistence.
         let msg_for_emit = {
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             st.update_message_in_chat(to_npub, message_id, |msg| {
                 msg.apply_edit(new_content.to_string(), edit_ts_ms, emoji_tags.clone());
                 msg.preview_metadata = None;
@@ -665,15 +781,28 @@ impl VectorCore {
 
     /// Get chats from the in-memory state.
     pub async fn get_chats(&self) -> Vec<SerializableChat> {
-        let state = state::STATE.lock().await;
+        let ctx = context::AppContext::current();
+        let state = ctx.state.lock().await;
         state.chats.iter()
             .map(|c| c.to_serializable_with_last_n(1, &state.interner))
             .collect()
     }
 
+    /// Get the DM chats with members of a contact group (see [`db::contact_groups`]).
+    pub async fn get_chats_in_contact_group(&self, group_id: i64) -> Result<Vec<SerializableChat>> {
+        let members = db::contact_groups::group_members(group_id).map_err(VectorError::Other)?;
+        let ctx = context::AppContext::current();
+        let state = ctx.state.lock().await;
+        Ok(state.chats.iter()
+            .filter(|c| *c.chat_type() == ChatType::DirectMessage && members.contains(c.id()))
+            .map(|c| c.to_serializable_with_last_n(1, &state.interner))
+            .collect())
+    }
+
     /// Get messages for a chat (paginated).
     pub async fn get_messages(&self, chat_id: &str, limit: usize, offset: usize) -> Vec<Message> {
-        let state = state::STATE.lock().await;
+        let ctx = context::AppContext::current();
+        let state = ctx.state.lock().await;
         if let Some(chat) = state.get_chat(chat_id) {
             let msgs = chat.get_all_messages(&state.interner);
             let start = offset.min(msgs.len());
@@ -686,7 +815,8 @@ impl VectorCore {
 
     /// Get a profile by npub.
     pub async fn get_profile(&self, npub: &str) -> Option<SlimProfile> {
-        let state = state::STATE.lock().await;
+        let ctx = context::AppContext::current();
+        let state = ctx.state.lock().await;
         state.get_profile(npub)
             .map(|p| SlimProfile::from_profile(p, &state.interner))
     }
@@ -715,7 +845,13 @@ impl VectorCore {
 
     /// Update the current user's status and broadcast to relays.
     pub async fn update_status(&self, status: &str) -> bool {
-        profile::sync::update_status(status.to_string()).await
+        profile::sync::update_status(status.to_string(), None, None).await
+    }
+
+    /// Like [`update_status`](Self::update_status) with an explicit emoji and an optional
+    /// expiry (unix secs) after which relays drop the status and it clears locally.
+    pub async fn update_status_with_expiry(&self, status: &str, emoji: &str, expires_at: Option<u64>) -> bool {
+        profile::sync::update_status(status.to_string(), Some(emoji.to_string()), expires_at).await
     }
 
     /// Upload an image file to Blossom **unencrypted** and return its public URL — for avatars,
@@ -780,8 +916,7 @@ impl VectorCore {
 
     /// Get the current user's npub.
     pub fn my_npub(&self) -> Option<String> {
-        state::my_public_key()
-            .and_then(|pk| ToBech32::to_bech32(&pk).ok())
+        context::AppContext::current().my_npub()
     }
 
     // === Communities (headless) ===
@@ -913,7 +1048,8 @@ impl VectorCore {
             .map(|c| crate::simd::hex::bytes_to_hex_32(&c.id.0))
             .collect();
         let slim = {
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             if !session.is_valid() {
                 return; // account swapped during the join/create — don't write into the new one.
             }
@@ -1129,7 +1265,8 @@ impl VectorCore {
             .and_then(|pk| ToBech32::to_bech32(&pk).ok());
         {
             let created_at_ms = crate::db::community::community_created_at_ms(&community.id);
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             for ch in &community.channels {
                 st.upsert_community_chat(
                     &ch.id.to_hex(),
@@ -1184,7 +1321,8 @@ impl VectorCore {
             .and_then(|pk| ToBech32::to_bech32(&pk).ok());
         {
             let created_at_ms = crate::db::community::community_created_at_ms(&community.id);
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             for ch in &community.channels {
                 st.upsert_community_chat(
                     &ch.id.to_hex(),
@@ -1212,6 +1350,17 @@ impl VectorCore {
 
     /// Mint a public invite link for a Community this identity owns. Returns the shareable URL.
     pub async fn create_public_invite(&self, community_id: &str) -> Result<String> {
+        self.create_public_invite_with(community_id, None, None).await
+    }
+
+    /// [`Self::create_public_invite`] with an optional expiry (unix secs, client-enforced: joiners
+    /// refuse an expired link) and attribution label ("Reddit", "Conf").
+    pub async fn create_public_invite_with(
+        &self,
+        community_id: &str,
+        expires_at: Option<u64>,
+        label: Option<String>,
+    ) -> Result<String> {
         use crate::community::{service, transport::LiveTransport, CommunityId};
         if community_id.len() != 64 {
             return Err(VectorError::Other("malformed community id".into()));
@@ -1228,7 +1377,7 @@ impl VectorCore {
             // v2 `build_invite_url` appends its own `/invite/<naddr>`, so pass the
             // bare domain (strip the `/invite` the v1 constant carries).
             let base = crate::community::public_invite::INVITE_URL_BASE.trim_end_matches("/invite");
-            let minted = crate::community::v2::service::mint_public_link(&transport, &community, base, None, None)
+            let minted = crate::community::v2::service::mint_public_link(&transport, &community, base, expires_at.map(|secs| secs.saturating_mul(1000)), label)
                 .await
                 .map_err(VectorError::Other)?;
             return Ok(minted.url);
@@ -1239,7 +1388,7 @@ impl VectorCore {
         .map_err(VectorError::Other)?
         .ok_or_else(|| VectorError::Other("community not found".into()))?;
         let transport = LiveTransport::with_timeout(std::time::Duration::from_secs(12));
-        let (_token, url) = service::create_public_invite(&transport, &community, None, None)
+        let (_token, url) = service::create_public_invite(&transport, &community, expires_at, label)
             .await
             .map_err(VectorError::Other)?;
         Ok(url)
@@ -1248,7 +1397,24 @@ impl VectorCore {
     /// Send a PRIVATE invite: gift-wrap this Community's invite bundle directly to an npub over a NIP-17
     /// DM (the same transport as a regular DM). The invitee parks it pending consent (accept_pending_invite).
     /// Requires CREATE_INVITE; a banned npub can't be re-invited. Returns the wrap's event id + relays.
+    /// A send that got as far as publishing is recorded for delivery tracking.
     pub async fn invite_to_community(&self, community_id: &str, invitee_npub: &str) -> Result<serde_json::Value> {
+        let mut published = None;
+        let result = self.send_community_invite(community_id, invitee_npub, &mut published).await;
+        if let Some(delivered) = published {
+            crate::community::invite_delivery::record_attempt(community_id, invitee_npub, delivered);
+        }
+        result
+    }
+
+    /// [`Self::invite_to_community`] without the delivery tracking: `published` is set once the
+    /// invite was handed to the relays, to whether any accepted it.
+    pub(crate) async fn send_community_invite(
+        &self,
+        community_id: &str,
+        invitee_npub: &str,
+        published: &mut Option<bool>,
+    ) -> Result<serde_json::Value> {
         use crate::community::{service, CommunityId};
         use crate::sending::{send_rumor_dm, NoOpSendCallback, SendCallback, SendConfig};
 
@@ -1291,9 +1457,9 @@ impl VectorCore {
             if !session.is_valid() {
                 return Err(VectorError::Other("account changed".into()));
             }
-            crate::inbox_relays::send_gift_wrap(&client, &recipient, rumor, [k_tag])
-                .await
-                .map_err(VectorError::Other)?;
+            let sent = crate::inbox_relays::send_gift_wrap(&client, &recipient, rumor, [k_tag]).await;
+            *published = Some(matches!(&sent, Ok(output) if !output.success.is_empty()));
+            sent.map_err(VectorError::Other)?;
             return Ok(serde_json::json!({ "invited": invitee_npub, "version": 2 }));
         }
         let community = crate::db::community::load_community(&CommunityId(
@@ -1327,9 +1493,9 @@ impl VectorCore {
         let config = SendConfig { self_send: false, ..SendConfig::gui() };
         let callback: Arc<dyn SendCallback> = Arc::new(NoOpSendCallback);
 
-        let result = send_rumor_dm(invitee_npub, &pending_id, rumor, &config, callback)
-            .await
-            .map_err(VectorError::Other)?;
+        let result = send_rumor_dm(invitee_npub, &pending_id, rumor, &config, callback).await;
+        *published = Some(matches!(&result, Ok(r) if r.event_id.is_some()));
+        let result = result.map_err(VectorError::Other)?;
 
         Ok(serde_json::json!({
             "community_id": community_id,
@@ -1396,7 +1562,8 @@ impl VectorCore {
             let reply = match replied_to.filter(|r| !r.is_empty()) {
                 Some(parent_id) => {
                     let author_hex = {
-                        let st = state::STATE.lock().await;
+                        let ctx = context::AppContext::current();
+                        let st = ctx.state.lock().await;
                         st.find_message(parent_id)
                             .and_then(|(_, m)| m.npub.as_deref().and_then(|n| nostr_sdk::prelude::PublicKey::parse(n).ok()))
                             .map(|pk| pk.to_hex())
@@ -1447,7 +1614,8 @@ impl VectorCore {
             return Ok(message_id);
         }
         let echoed = {
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             inbound::process_incoming(&mut st, &outer, &channel, &author_pk)
         };
         if let Some(inbound::IncomingEvent::NewMessage(msg)) = echoed {
@@ -1567,7 +1735,8 @@ impl VectorCore {
             .await.map_err(VectorError::Other)?;
         // Local echo so get_messages reflects the send.
         let echoed = {
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             inbound::process_incoming(&mut st, &outer, &channel, &author_pk)
         };
         if let Some(inbound::IncomingEvent::NewMessage(m)) = echoed {
@@ -1623,7 +1792,8 @@ impl VectorCore {
             // store, so this almost always resolves locally); the channel-page fetch
             // is the last resort for a target this device never saw.
             let held = {
-                let st = state::STATE.lock().await;
+                let ctx = context::AppContext::current();
+                let st = ctx.state.lock().await;
                 st.find_message(message_id)
                     .and_then(|(_, m)| m.npub.as_deref().and_then(|n| nostr_sdk::prelude::PublicKey::parse(n).ok()))
             };
@@ -1688,7 +1858,8 @@ impl VectorCore {
     /// [`Self::delete_community_message_in`] with the channel id instead.
     pub async fn delete_community_message(&self, message_id: &str) -> Result<()> {
         let channel_id = {
-            let st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let st = ctx.state.lock().await;
             match st.find_message(message_id) {
                 Some((chat, _)) => chat.id.clone(),
                 None => return Err(VectorError::Other("message not found (already deleted?)".into())),
@@ -1708,7 +1879,8 @@ impl VectorCore {
         // Attachment URLs come from local state when held (a headless v2 consumer
         // has none — blob cleanup is then the receiving peers' concern, not ours).
         let attachment_urls: Vec<String> = {
-            let st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let st = ctx.state.lock().await;
             st.find_message(message_id)
                 .map(|(_, msg)| msg.attachments.iter().filter(|a| !a.url.is_empty()).map(|a| a.url.clone()).collect())
                 .unwrap_or_default()
@@ -1750,7 +1922,8 @@ impl VectorCore {
             return Ok(());
         }
         let removed_chat = {
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             st.remove_message(message_id).map(|(cid, _)| cid)
         };
         let _ = crate::db::events::delete_event(message_id).await;
@@ -1793,7 +1966,8 @@ impl VectorCore {
             return Ok(());
         }
         let outcome = {
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             inbound::process_incoming(&mut st, &outer, &channel, &author_pk)
         };
         if let Some(inbound::IncomingEvent::Updated { target_id, message, edit_event }) = outcome {
@@ -1891,7 +2065,8 @@ impl VectorCore {
             .await
             .map_err(VectorError::Other)?;
         let outcomes = {
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             inbound::process_channel_batch(&mut st, &events, &channel, &my_pk)
         };
         let mut new = 0usize;
@@ -1997,26 +2172,26 @@ impl VectorCore {
                     }
                 }
             }
-            let state = crate::state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let state = ctx.state.lock().await;
             for pk in members {
                 let Ok(npub) = pk.to_bech32();
                 if state.get_profile(&npub).map(|p| p.flags.is_bot()).unwrap_or(false) {
                     bots.push(pk);
                 }
             }
-        } else if chat_id.starts_with("npub1") {
-            if let Ok(pk) = nostr_sdk::prelude::PublicKey::parse(chat_id) {
-                let is_bot = {
-                    let state = crate::state::STATE.lock().await;
-                    state.get_profile(chat_id).map(|p| p.flags.is_bot()).unwrap_or(false)
-                };
-                if is_bot {
-                    bots.push(pk);
-                    // The counterpart published its manifest to its own login
-                    // relays/indexers — our connected pool is the read set.
-                    if let Some(client) = crate::state::nostr_client() {
-                        relays = client.relays().await.keys().map(|u| u.to_string()).collect();
-                    }
+        } else if let Ok(crate::ids::ChatId::Dm(user)) = crate::ids::ChatId::parse(chat_id) {
+            let is_bot = {
+                let ctx = context::AppContext::current();
+                let state = ctx.state.lock().await;
+                state.get_profile(&user.npub()).map(|p| p.flags.is_bot()).unwrap_or(false)
+            };
+            if is_bot {
+                bots.push(user.pubkey());
+                // The counterpart published its manifest to its own login
+                // relays/indexers — our connected pool is the read set.
+                if let Some(client) = crate::state::nostr_client() {
+                    relays = client.relays().await.keys().map(|u| u.to_string()).collect();
                 }
             }
         }
@@ -2222,7 +2397,8 @@ impl VectorCore {
                 continue;
             }
             let outcome = {
-                let mut st = state::STATE.lock().await;
+                let ctx = context::AppContext::current();
+                let mut st = ctx.state.lock().await;
                 apply_chat_to_state(&mut st, &f.event, channel_id, &my_pk)
             };
             if let Some(outcome) = outcome {
@@ -2589,7 +2765,8 @@ impl VectorCore {
             if !session.is_valid() {
                 return Err(VectorError::Other("account changed during leave".into()));
             }
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             st.chats.retain(|c| !channel_ids.contains(&c.id));
             return Ok(());
         }
@@ -2608,7 +2785,8 @@ impl VectorCore {
         // voluntary leave is a self-removal → retain the held epoch keys for later self-scrub.
         crate::db::community::delete_community_retain_keys(community_id).map_err(VectorError::Other)?;
         {
-            let mut st = state::STATE.lock().await;
+            let ctx = context::AppContext::current();
+            let mut st = ctx.state.lock().await;
             st.chats.retain(|c| !channel_ids.contains(&c.id));
         }
         Ok(())
@@ -2674,23 +2852,12 @@ impl VectorCore {
         let all_items = db::wrappers::load_negentropy_items().unwrap_or_default();
 
         // Filter items to time window (or use all for full sync)
-        let (items, filter) = if let Some(days) = since_days {
-            let since_ts = Timestamp::now().as_secs().saturating_sub(days * 24 * 3600);
-            let items: Vec<(EventId, Timestamp)> = all_items.iter()
-                .filter(|(_, ts)| ts.as_secs() >= since_ts)
-                .cloned()
-                .collect();
-            let filter = Filter::new()
-                .pubkey(my_pk)
-                .kind(Kind::GiftWrap)
-                .since(Timestamp::from_secs(since_ts));
-            (items, filter)
-        } else {
-            let filter = Filter::new()
-                .pubkey(my_pk)
-                .kind(Kind::GiftWrap);
-            (all_items, filter)
+        // Windowed by wrapper time, widened for NIP-59 backdating (see `gift_wrap_window`).
+        let window = match since_days {
+            Some(days) => negentropy::SyncWindow::Recent(days * 24 * 3600),
+            None => negentropy::SyncWindow::Full,
         };
+        let (items, filter) = window.plan(my_pk, &all_items);
 
         log_info!("[SyncDMs] {} negentropy items, since_days={:?}", items.len(), since_days);
 
@@ -3078,6 +3245,10 @@ impl VectorCore {
         if let Some(client) = state::take_nostr_client() {
             let _ = client.shutdown().await;
         }
+        // A bunker session's own relay pool, and the signer discriminator with it
+        if let Some(bunker) = signer::drain_bunker_state() {
+            let _ = bunker.shutdown().await;
+        }
         db::close_database();
 
         // Key vaults + transient secrets.
@@ -3111,7 +3282,18 @@ impl VectorCore {
         // In-flight wrap confirmations carry the prior account's chat and
         // message ids — a late OK must not "rescue" into the new session.
         crate::sending::clear_wrap_confirms();
+        crate::sending::clear_parked_uploads();
         crate::emoji_packs::clear_nip65_cache();
+        crate::nostr_uri::clear_cache();
+        crate::zaps::clear_session();
+        crate::starred::clear_session();
+        crate::compression::clear_session();
+        crate::relay_health::clear_session();
+        crate::consent::clear_session();
+        crate::contact_verification::clear_session();
+        crate::crypto::content_cache::clear_session();
+        crate::typing::clear_session();
+        crate::location::clear_session();
         // Chat/user row-id caches are PER-ACCOUNT (row ids belong to the prior account's DB). Not clearing
         // them here let a swapped-in account resolve a channel/npub to the WRONG (prior-account) row id →
         // saves FK-failed silently + reads hit the wrong row (e.g. a community member vanished post-swap).
@@ -3133,6 +3315,87 @@ impl VectorCore {
     }
 }
 
+/// Make `npub` the current account for a bunker login its signer just approved: open its database,
+/// unlock it, and store the pairing when it's a new one. Returns whether the account is encrypted.
+async fn open_bunker_account(
+    bunker_uri: &str,
+    remote_hex: &str,
+    npub: &str,
+    pairing: Option<&db::BunkerPairing>,
+    password: Option<&str>,
+    password_key: Option<[u8; 32]>,
+    client_keys: &nostr_sdk::Keys,
+) -> Result<bool> {
+    use nostr_sdk::prelude::*;
+
+    if pairing.is_some_and(|p| p.npub != npub) {
+        return Err(VectorError::Nostr("The signer answered for a different identity".to_string()));
+    }
+    db::set_current_account(npub.to_string())?;
+    db::init_database(npub)?;
+
+    let has_encryption = state::resolve_encryption_enabled_from_db();
+    let stored_pkey = db::get_pkey()?;
+    if has_encryption {
+        let key = match password_key {
+            Some(key) => key,
+            None => {
+                let pwd = password.ok_or_else(|| VectorError::Crypto("This account is encrypted — a password is required".to_string()))?;
+                crate::crypto::hash_pass(pwd).await
+            }
+        };
+        // A new pairing is stored under this key: make sure it's the account's
+        if let Some(stored) = stored_pkey.as_deref().filter(|p| !p.starts_with("nsec1")) {
+            crate::crypto::decrypt_with_key(stored, &key)
+                .map_err(|_| VectorError::Crypto("Incorrect password".to_string()))?;
+        }
+        state::ENCRYPTION_KEY.set(key, &[&state::MY_SECRET_KEY]);
+    }
+    state::init_encryption_enabled();
+
+    if pairing.is_none() {
+        let signer_type = db::get_signer_type()?;
+        if signer_type == "nip55" || (signer_type != "bunker" && stored_pkey.is_some()) {
+            return Err(VectorError::Other("This account signs another way — log in with its own key or signer".to_string()));
+        }
+        let nsec = client_keys.secret_key().to_bech32()
+            .map_err(|e| VectorError::Nostr(format!("Failed to encode nsec: {}", e)))?;
+        let pkey = crate::crypto::maybe_encrypt(nsec).await;
+        let url = crate::crypto::maybe_encrypt(bunker_uri.to_string()).await;
+        let security_type = db::get_sql_setting("security_type".to_string())?;
+        db::commit_bunker_account_setup(&pkey, has_encryption, security_type.as_deref(), &url, remote_hex)?;
+    }
+    Ok(has_encryption)
+}
+
+/// Fetch a blob (usually attacker-addressed, off an inbound message), streamed with a size cap so
+/// a hostile or oversized blob can't OOM the process.
+async fn fetch_capped(url: &str, max_bytes: usize) -> Result<Vec<u8>> {
+    use futures_util::StreamExt;
+    // SSRF guard: build_http_client only validates redirect HOPS, not the initial request — so
+    // validate it here (matches the native download path). With Tor off this is the only egress guard.
+    crate::net::validate_url_not_private(url)
+        .map_err(|e| VectorError::Other(e.to_string()))?;
+    let client = crate::net::build_http_client(std::time::Duration::from_secs(120)).map_err(VectorError::Other)?;
+    let resp = client.get(url).send().await
+        .map_err(|e| VectorError::Other(format!("download: {e}")))?;
+    if !resp.status().is_success() {
+        return Err(VectorError::Other(format!("download failed: HTTP {}", resp.status())));
+    }
+    let mut data: Vec<u8> = Vec::with_capacity(
+        resp.content_length().map(|l| (l as usize).min(max_bytes)).unwrap_or(64 * 1024),
+    );
+    let mut stream = resp.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| VectorError::Other(format!("read body: {e}")))?;
+        if data.len() + chunk.len() > max_bytes {
+            return Err(VectorError::Other(format!("attachment exceeds {} MiB cap", max_bytes / (1024 * 1024))));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
 #[cfg(test)]
 mod facade_tests {
     use super::*;
@@ -0,0 +1,180 @@
+//! Link previews: fetched once by the sender, carried in the message.
+//!
+//! Fetching a link's OpenGraph / Twitter-card metadata tells the linked site who is reading the
+//! chat. So the sender fetches it (through the configured proxy, see [`crate::net`]) while
+//! composing and ships the result on the rumor as a `["preview", <url>, <json>]` tag; recipients
+//! render that instead of fetching the URL themselves. Fetched previews are cached here by URL
+//! for [`CACHE_TTL_SECS`]. The `web_previews` setting (on by default) turns fetching off entirely.
+//!
+//! An incoming preview is the sender's claim: it's only used for a URL that's actually in the
+//! message, and its domain and click target are pinned to that URL.
+
+use nostr_sdk::prelude::*;
+use rusqlite::{params, OptionalExtension};
+
+use crate::clock::now_secs;
+use crate::types::SiteMetadata;
+
+/// Account setting (synced): `"false"` stops every link preview fetch.
+pub const SETTING_KEY: &str = "web_previews";
+
+/// Tag carrying a preview on a message rumor.
+pub const TAG_PREVIEW: &str = "preview";
+
+/// How long a fetched preview is reused (and kept; see [`crate::db::pruning`]).
+pub const CACHE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Largest preview JSON sent or accepted on a tag.
+const MAX_TAG_BYTES: usize = 4096;
+
+/// Longest title / description kept from an incoming preview (chars).
+const MAX_TEXT_CHARS: usize = 500;
+
+/// Whether link previews may be fetched.
+pub fn enabled() -> bool {
+    crate::db::settings::get_sql_setting(SETTING_KEY.to_string())
+        .ok()
+        .flatten()
+        .is_none_or(|v| v != "false")
+}
+
+/// The cached preview for `url`, unless it's older than [`CACHE_TTL_SECS`].
+pub fn cached(url: &str) -> Option<SiteMetadata> {
+    let conn = crate::db::get_db_connection_guard_static().ok()?;
+    let json: String = conn.query_row(
+        "SELECT metadata FROM link_previews WHERE url = ?1 AND fetched_at >= ?2",
+        params![url, now_secs().saturating_sub(CACHE_TTL_SECS) as i64],
+        |r| r.get(0),
+    ).optional().ok()??;
+    serde_json::from_str(&json).ok()
+}
+
+/// Cache a freshly fetched preview for `url`.
+pub fn store(url: &str, metadata: &SiteMetadata) -> Result<(), String> {
+    let json = serde_json::to_string(metadata).map_err(|e| e.to_string())?;
+    let conn = crate::db::get_write_connection_guard_static()?;
+    conn.execute(
+        "INSERT INTO link_previews (url, metadata, fetched_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(url) DO UPDATE SET metadata = excluded.metadata, fetched_at = excluded.fetched_at",
+        params![url, json, now_secs() as i64],
+    ).map_err(|e| format!("Failed to cache link preview: {}", e))?;
+    Ok(())
+}
+
+/// Where `url` first appears in `text` as a whole link: followed by whitespace, the end, or only
+/// trailing punctuation up to them (so `https://a.io` doesn't match inside `https://a.io/page`).
+fn find_link(text: &str, url: &str) -> Option<usize> {
+    text.match_indices(url).map(|(at, _)| at).find(|&at| {
+        text[at + url.len()..]
+            .split(char::is_whitespace)
+            .next()
+            .is_none_or(|rest| rest.chars().all(|c| ".,:;!?)]>\"'".contains(c)))
+    })
+}
+
+/// The preview to attach to an outgoing message: the cached one for the first link in `content`
+/// that has one. Code spans and markdown link labels don't count as links.
+pub fn outbound(content: &str) -> Option<(String, SiteMetadata)> {
+    if !enabled() || !content.contains("https://") {
+        return None;
+    }
+    let prose = crate::net::strip_md_link_claims(&crate::code_blocks::strip_code(content));
+    let candidates: Vec<(String, String)> = {
+        let conn = crate::db::get_db_connection_guard_static().ok()?;
+        let mut stmt = conn.prepare(
+            "SELECT url, metadata FROM link_previews WHERE instr(?1, url) > 0 AND fetched_at >= ?2",
+        ).ok()?;
+        let rows = stmt.query_map(
+            params![prose, now_secs().saturating_sub(CACHE_TTL_SECS) as i64],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        ).ok()?;
+        let found = rows.filter_map(Result::ok).collect();
+        found
+    };
+    candidates.into_iter()
+        .filter_map(|(url, json)| Some((find_link(&prose, &url)?, url, json)))
+        .min_by_key(|(at, _, _)| *at)
+        .and_then(|(_, url, json)| Some((url, serde_json::from_str(&json).ok()?)))
+}
+
+/// The `preview` tag carrying `metadata` for `url`; `None` when it's too large to send.
+pub fn tag(url: &str, metadata: &SiteMetadata) -> Option<Tag> {
+    let json = serde_json::to_string(metadata).ok()?;
+    (json.len() <= MAX_TAG_BYTES).then(|| Tag::custom(TagKind::custom(TAG_PREVIEW), [url.to_string(), json]))
+}
+
+/// An incoming message's preview, from its `preview` tag. Dropped unless the URL is a link in
+/// `content`; the domain and click target are rebuilt from that URL, and only https images count.
+pub fn from_tags<'a, I: IntoIterator<Item = &'a Tag>>(tags: I, content: &str) -> Option<SiteMetadata> {
+    let tag = tags.into_iter().find(|t| t.as_slice().first().map(|k| k.as_str()) == Some(TAG_PREVIEW))?;
+    let (url, json) = (tag.as_slice().get(1)?, tag.as_slice().get(2)?);
+    if json.len() > MAX_TAG_BYTES || find_link(content, url).is_none() {
+        return None;
+    }
+    let parsed = url::Url::parse(url).ok().filter(|u| u.scheme() == "https")?;
+    let mut meta: SiteMetadata = serde_json::from_str(json).ok()?;
+
+    meta.domain = format!("https://{}/", parsed.host_str()?);
+    meta.og_url = Some(url.clone());
+    let https = |v: Option<String>| v.filter(|s| s.starts_with("https://"));
+    meta.og_image = https(meta.og_image);
+    meta.favicon = https(meta.favicon);
+    let clip = |v: Option<String>| v.map(|s| s.chars().take(MAX_TEXT_CHARS).collect::<String>());
+    meta.og_title = clip(meta.og_title);
+    meta.og_description = clip(meta.og_description);
+    meta.og_type = clip(meta.og_type);
+    meta.title = clip(meta.title);
+    meta.description = clip(meta.description);
+    Some(meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(title: &str) -> SiteMetadata {
+        SiteMetadata {
+            domain: "https://evil.example/".into(),
+            og_title: Some(title.into()),
+            og_description: None,
+            og_image: Some("http://tracker.example/pixel.png".into()),
+            og_url: Some("https://evil.example/".into()),
+            og_type: None,
+            title: None,
+            description: None,
+            favicon: None,
+        }
+    }
+
+    #[test]
+    fn cached_preview_rides_the_message_and_is_pinned_to_its_link() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        crate::db::clear_id_caches();
+        let tmp = tempfile::tempdir().unwrap();
+        let account = Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+        let clock = crate::clock::freeze(1_700_000_000_000);
+
+        store("https://a.example", &meta("A")).unwrap();
+        store("https://a.example/page", &meta("Page")).unwrap();
+        assert_eq!(outbound("see `https://a.example` and https://a.example/page.").unwrap().1.og_title.as_deref(), Some("Page"));
+        assert!(outbound("nothing cached: https://b.example").is_none());
+
+        let (url, m) = outbound("look https://a.example!").unwrap();
+        let wire = tag(&url, &m).unwrap();
+        let got = from_tags([&wire], "look https://a.example!").unwrap();
+        assert_eq!(got.domain, "https://a.example/");
+        assert_eq!(got.og_url.as_deref(), Some("https://a.example"));
+        assert_eq!(got.og_image, None, "plain-http images are dropped");
+        assert!(from_tags([&wire], "a different link https://a.example.evil").is_none());
+
+        clock.advance_secs(CACHE_TTL_SECS + 1);
+        assert!(cached("https://a.example").is_none(), "stale previews are refetched");
+        crate::db::settings::set_sql_setting(SETTING_KEY.to_string(), "false".to_string()).unwrap();
+        assert!(!enabled());
+    }
+}
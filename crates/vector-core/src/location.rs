@@ -0,0 +1,313 @@
+//! Location messages: a point on the map, optionally kept live for a while.
+//!
+//! A location goes out as an ordinary text DM whose content is a `geo:` URI, so other clients
+//! still show something useful, plus a `["location", lat, lon, accuracy, live_until]` tag that
+//! Vector renders as a map card. A live share runs until `live_until` (at most
+//! [`MAX_LIVE_SECS`]). Meanwhile the host feeds the device's position to [`report_position`], and
+//! [`run_live_loop`] sends the latest as a `location-update` rumor pointing at the original
+//! message, at most once per [`LIVE_INTERVAL`]. Stopping early sends one last update whose
+//! `live_until` is now; a share that simply runs out needs none, since receivers honour the time.
+//!
+//! Updates are only applied by their author's own client to their author's own message, and can
+//! shorten a share but never extend it.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::clock::now_secs;
+use crate::sending::{SendCallback, SendConfig, SendResult};
+
+/// Tag carrying a location on a message or an update.
+pub const TAG_LOCATION: &str = "location";
+
+/// `d` tag of a live share's position update.
+pub const UPDATE_IDENTIFIER: &str = "location-update";
+
+/// How often a live share sends its latest position (only when it moved).
+pub const LIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Longest a live share may run.
+pub const MAX_LIVE_SECS: u64 = 8 * 60 * 60;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Location {
+    pub lat: f64,
+    pub lon: f64,
+    /// Radius of uncertainty in metres; 0 = unknown.
+    pub accuracy: f64,
+    /// Unix secs a live share runs until; 0 = a one-off location.
+    #[serde(default)]
+    pub live_until: u64,
+}
+
+impl Location {
+    /// A one-off location, checked to be a real coordinate.
+    pub fn new(lat: f64, lon: f64, accuracy: f64) -> Result<Self, String> {
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return Err("Location is out of range".to_string());
+        }
+        if !accuracy.is_finite() || accuracy < 0.0 {
+            return Err("Location accuracy is invalid".to_string());
+        }
+        Ok(Self { lat, lon, accuracy, live_until: 0 })
+    }
+
+    /// Whether this is a live share that hasn't ended by `now` (secs).
+    pub fn is_live_at(&self, now: u64) -> bool {
+        self.live_until > now
+    }
+
+    /// The RFC 5870 `geo:` URI, sent as the message text.
+    pub fn geo_uri(&self) -> String {
+        if self.accuracy > 0.0 {
+            format!("geo:{:.6},{:.6};u={}", self.lat, self.lon, self.accuracy.round() as u64)
+        } else {
+            format!("geo:{:.6},{:.6}", self.lat, self.lon)
+        }
+    }
+
+    fn tag_values(&self) -> [String; 5] {
+        [
+            TAG_LOCATION.to_string(),
+            format!("{:.6}", self.lat),
+            format!("{:.6}", self.lon),
+            format!("{:.0}", self.accuracy),
+            self.live_until.to_string(),
+        ]
+    }
+
+    /// The `location` tag.
+    pub fn tag(&self) -> Tag {
+        let [_, rest @ ..] = self.tag_values();
+        Tag::custom(TagKind::custom(TAG_LOCATION), rest)
+    }
+
+    /// The `location` tag as stored with a message.
+    pub fn stored_tag(&self) -> Vec<String> {
+        self.tag_values().to_vec()
+    }
+
+    /// Parse a `location` tag's values (the tag name first).
+    pub fn from_tag_values<S: AsRef<str>>(values: &[S]) -> Option<Self> {
+        if values.first()?.as_ref() != TAG_LOCATION {
+            return None;
+        }
+        let num = |i: usize| values.get(i).and_then(|v| v.as_ref().parse::<f64>().ok());
+        let mut location = Self::new(num(1)?, num(2)?, num(3).unwrap_or(0.0)).ok()?;
+        location.live_until = values.get(4).and_then(|v| v.as_ref().parse().ok()).unwrap_or(0);
+        Some(location)
+    }
+}
+
+/// A rumor's location, from its `location` tag.
+pub fn from_tags<'a, I: IntoIterator<Item = &'a Tag>>(tags: I) -> Option<Location> {
+    tags.into_iter().find_map(|t| Location::from_tag_values(t.as_slice()))
+}
+
+/// A stored message's location.
+pub fn from_stored_tags(tags: &[Vec<String>]) -> Option<Location> {
+    tags.iter().find_map(|t| Location::from_tag_values(t.as_slice()))
+}
+
+/// Whether a rumor is a live share's position update.
+pub fn is_update(tags: &Tags) -> bool {
+    tags.find(TagKind::d()).and_then(|t| t.content()) == Some(UPDATE_IDENTIFIER)
+}
+
+/// A running live share of ours.
+struct LiveShare {
+    message_id: String,
+    until: u64,
+    /// The device's latest position.
+    latest: Location,
+    /// The position last sent.
+    sent: Location,
+}
+
+/// Our live shares, by chat.
+static LIVE: Mutex<Option<HashMap<String, LiveShare>>> = Mutex::new(None);
+
+fn with_live<T>(f: impl FnOnce(&mut HashMap<String, LiveShare>) -> T) -> T {
+    let mut guard = LIVE.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(HashMap::new))
+}
+
+/// Share `location` into the DM `chat_id`. With `live_for_secs` it becomes a live share for that
+/// long (capped at [`MAX_LIVE_SECS`]), replacing any live share already running there.
+pub async fn share(
+    chat_id: &str,
+    mut location: Location,
+    live_for_secs: Option<u64>,
+    config: &SendConfig,
+    callback: Arc<dyn SendCallback>,
+) -> Result<SendResult, String> {
+    if !crate::ids::is_dm_chat_id(chat_id) {
+        return Err("Locations can only be shared in direct messages".to_string());
+    }
+    location.live_until = match live_for_secs {
+        Some(secs) if secs > 0 => now_secs() + secs.min(MAX_LIVE_SECS),
+        _ => 0,
+    };
+    if location.live_until > 0 {
+        stop(chat_id).await?;
+    }
+
+    let result = crate::sending::send_location_dm(chat_id, &location, config, callback).await?;
+    if let (true, Some(message_id)) = (location.live_until > 0, result.event_id.clone()) {
+        with_live(|live| live.insert(chat_id.to_string(), LiveShare {
+            message_id,
+            until: location.live_until,
+            latest: location,
+            sent: location,
+        }));
+    }
+    Ok(result)
+}
+
+/// The device's current position, for every running live share. Returns whether any is running
+/// (the host can stop watching the position once none is).
+pub fn report_position(location: Location) -> bool {
+    with_live(|live| {
+        for share in live.values_mut() {
+            share.latest = Location { live_until: share.until, ..location };
+        }
+        !live.is_empty()
+    })
+}
+
+/// The chats with a live share of ours running, and when each ends.
+pub fn live_shares() -> Vec<(String, u64)> {
+    with_live(|live| live.iter().map(|(chat, s)| (chat.clone(), s.until)).collect())
+}
+
+/// End our live share in `chat_id` now, if one is running.
+pub async fn stop(chat_id: &str) -> Result<(), String> {
+    let Some(share) = with_live(|live| live.remove(chat_id)) else { return Ok(()) };
+    let ended = Location { live_until: now_secs(), ..share.latest };
+    send_update(chat_id, &share.message_id, ended).await
+}
+
+/// Send a position update for our message `message_id`, and apply it to our own copy.
+async fn send_update(chat_id: &str, message_id: &str, location: Location) -> Result<(), String> {
+    let my_pk = crate::state::my_public_key().ok_or("Public key not set")?;
+    let event_id = EventId::from_hex(message_id).map_err(|e| format!("Invalid message id: {}", e))?;
+    let rumor = EventBuilder::new(Kind::ApplicationSpecificData, UPDATE_IDENTIFIER)
+        .tag(Tag::identifier(UPDATE_IDENTIFIER))
+        .tag(Tag::event(event_id))
+        .tag(location.tag())
+        .build(my_pk);
+    let pending_id = format!("pending-location-{}", crate::clock::now_ms());
+    let config = SendConfig { self_send: true, ..SendConfig::headless() };
+    crate::sending::send_rumor_dm(chat_id, &pending_id, rumor, &config, Arc::new(crate::sending::NoOpSendCallback)).await?;
+    apply_update(chat_id, message_id, location, true).await;
+    Ok(())
+}
+
+/// Apply a live share's position update to message `message_id` in `chat_id`. `mine` says who
+/// sent the update; it must match who sent the message. Ignored for a message that isn't a live
+/// share, or one that would run longer than it did.
+pub async fn apply_update(chat_id: &str, message_id: &str, location: Location, mine: bool) {
+    let session = crate::state::SessionGuard::capture();
+    let updated = {
+        let mut state = crate::state::STATE.lock().await;
+        let Some(current) = state.get_chat(chat_id)
+            .and_then(|c| c.get_compact_message(message_id))
+            .filter(|m| m.is_mine() == mine)
+            .and_then(|m| m.location.as_deref().copied())
+        else {
+            return;
+        };
+        if current.live_until == 0 || location.live_until > current.live_until {
+            return;
+        }
+        state.update_message_in_chat(chat_id, message_id, |msg| {
+            msg.location = Some(Box::new(location));
+        })
+    };
+    let Some(message) = updated else { return };
+    if !session.is_valid() {
+        return;
+    }
+    let _ = crate::db::events::save_message(chat_id, &message).await;
+    crate::traits::emit_event("message_update", &serde_json::json!({
+        "old_id": message_id,
+        "message": message,
+        "chat_id": chat_id,
+    }));
+}
+
+/// Send what the live shares need: the latest position of each that moved, and drop those that
+/// ran out.
+async fn tick() {
+    let now = now_secs();
+    let due: Vec<(String, String, Location)> = with_live(|live| {
+        live.retain(|_, s| s.until > now);
+        live.iter_mut()
+            .filter(|(_, s)| s.latest != s.sent)
+            .map(|(chat, s)| {
+                s.sent = s.latest;
+                (chat.clone(), s.message_id.clone(), s.latest)
+            })
+            .collect()
+    });
+    for (chat_id, message_id, location) in due {
+        if let Err(e) = send_update(&chat_id, &message_id, location).await {
+            crate::log_warn!("[location] live update to {} failed: {}", chat_id, e);
+        }
+    }
+}
+
+/// The live share loop. Hosts with their own async runtime (e.g. Tauri) should spawn this directly.
+pub async fn run_live_loop() {
+    loop {
+        tokio::time::sleep(LIVE_INTERVAL).await;
+        tick().await;
+    }
+}
+
+/// Account swap: the previous account's live shares end here.
+pub fn clear_session() {
+    with_live(|live| live.clear());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_survives_the_wire_and_storage() {
+        let mut here = Location::new(52.370216, 4.895168, 12.4).unwrap();
+        here.live_until = 1_700_000_900;
+        assert_eq!(here.geo_uri(), "geo:52.370216,4.895168;u=12");
+
+        let wire = here.tag();
+        let parsed = from_tags([&wire]).unwrap();
+        assert_eq!((parsed.lat, parsed.lon, parsed.accuracy, parsed.live_until), (52.370216, 4.895168, 12.0, 1_700_000_900));
+        assert_eq!(from_stored_tags(&[vec!["ms".into(), "5".into()], here.stored_tag()]), Some(parsed));
+        assert!(parsed.is_live_at(1_700_000_899));
+        assert!(!parsed.is_live_at(1_700_000_900));
+
+        assert!(Location::new(91.0, 0.0, 0.0).is_err());
+        assert!(Location::from_tag_values(&["location", "NaN", "4.9"]).is_none());
+        assert!(Location::from_tag_values(&["location", "1.0", "2.0"]).is_some_and(|l| l.accuracy == 0.0 && l.live_until == 0));
+    }
+
+    #[test]
+    fn reported_positions_go_out_only_when_they_move() {
+        clear_session();
+        let start = Location { live_until: 100, ..Location::new(1.0, 2.0, 5.0).unwrap() };
+        with_live(|live| live.insert("npub1a".into(), LiveShare { message_id: "m".into(), until: 100, latest: start, sent: start }));
+
+        assert!(report_position(Location::new(1.5, 2.0, 5.0).unwrap()));
+        let share = with_live(|live| live.get("npub1a").map(|s| s.latest)).unwrap();
+        assert_eq!((share.lat, share.live_until), (1.5, 100), "the share's end is kept");
+        assert_eq!(live_shares(), vec![("npub1a".to_string(), 100)]);
+
+        clear_session();
+        assert!(!report_position(start), "nothing left to feed");
+    }
+}
@@ -0,0 +1,148 @@
+//! Managed media cache: a size cap on downloaded attachments with least-recently-used eviction.
+//!
+//! Downloads used to pile up until the user cleared storage by hand. With a limit set, every
+//! completed download (and every limit change) trims the cache back under it, oldest-used file
+//! first. A file is "used" when it's downloaded and when its chat is opened
+//! (`db::attachments::touch_chat_media`); files never touched since migration 93 count from their
+//! message's age. Eviction deletes the file and resets its attachments to not-downloaded, so they
+//! show as a preview again and re-download from their URL on demand.
+//!
+//! Only files inside the download dir are ever deleted: archived files (`archive_attachments`)
+//! and anything a stale path points elsewhere are out of the cache's hands. Files of starred
+//! messages (`starred`) are never evicted either, though they still count toward the limit.
+
+use std::path::{Path, PathBuf};
+
+const LIMIT_KEY: &str = "media_cache_limit";
+
+/// The smallest cap accepted; below this the cache would churn on every download.
+pub const MIN_LIMIT_BYTES: u64 = 100 * 1024 * 1024;
+
+/// How full the cache is, for the Storage settings.
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct CacheStats {
+    /// 0 when unlimited.
+    pub limit_bytes: u64,
+    pub used_bytes: u64,
+    pub files: usize,
+}
+
+/// What a trim freed, and the messages whose attachments it reset (for STATE and the UI).
+#[derive(Clone, Debug, Default)]
+pub struct Eviction {
+    pub files: usize,
+    pub bytes: u64,
+    pub affected: Vec<String>,
+}
+
+/// The cache cap in bytes; 0 (the default) is unlimited.
+pub fn load_limit() -> u64 {
+    crate::db::settings::get_sql_setting(LIMIT_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0)
+}
+
+pub fn save_limit(limit_bytes: u64) -> Result<(), String> {
+    if limit_bytes != 0 && limit_bytes < MIN_LIMIT_BYTES {
+        return Err(format!("The cache limit must be at least {} MB", MIN_LIMIT_BYTES / (1024 * 1024)));
+    }
+    crate::db::settings::set_sql_setting(LIMIT_KEY.to_string(), limit_bytes.to_string())
+}
+
+/// Cached files that live in the download dir, least recently used first, with their sizes.
+fn cached_files(download_dir: &Path) -> Result<Vec<(String, PathBuf, u64)>, String> {
+    Ok(crate::db::attachments::cached_files_lru()?
+        .into_iter()
+        .filter_map(|(path, _)| {
+            let real = Path::new(&path).canonicalize().ok()?;
+            if !real.starts_with(download_dir) {
+                return None;
+            }
+            let size = std::fs::metadata(&real).ok()?.len();
+            Some((path, real, size))
+        })
+        .collect())
+}
+
+/// How many of the LRU-ordered `sizes` to evict so the rest fit in `limit`. The most recently used
+/// file always stays, even alone over the limit: it's the download that just landed.
+fn eviction_count(sizes: &[u64], limit: u64) -> usize {
+    let mut total: u64 = sizes.iter().sum();
+    sizes.iter()
+        .take(sizes.len().saturating_sub(1))
+        .take_while(|&&size| {
+            let over = total > limit;
+            total -= size;
+            over
+        })
+        .count()
+}
+
+pub fn stats() -> Result<CacheStats, String> {
+    let files = match crate::db::get_download_dir().canonicalize() {
+        Ok(download_dir) => cached_files(&download_dir)?,
+        Err(_) => Vec::new(),
+    };
+    Ok(CacheStats {
+        limit_bytes: load_limit(),
+        used_bytes: files.iter().map(|(_, _, size)| size).sum(),
+        files: files.len(),
+    })
+}
+
+/// Evict least-recently-used files until the cache fits its limit. Blocking (file deletes and DB
+/// writes); a no-op when unlimited.
+pub fn enforce() -> Result<Eviction, String> {
+    let limit = load_limit();
+    if limit == 0 {
+        return Ok(Eviction::default());
+    }
+    let Ok(download_dir) = crate::db::get_download_dir().canonicalize() else {
+        return Ok(Eviction::default());
+    };
+    let kept = crate::db::attachments::starred_paths()?;
+    let (kept_files, files): (Vec<_>, Vec<_>) = cached_files(&download_dir)?
+        .into_iter()
+        .partition(|(path, _, _)| kept.contains(path));
+    let kept_bytes: u64 = kept_files.iter().map(|(_, _, size)| size).sum();
+    let sizes: Vec<u64> = files.iter().map(|(_, _, size)| *size).collect();
+    let count = eviction_count(&sizes, limit.saturating_sub(kept_bytes));
+
+    let mut eviction = Eviction::default();
+    for (path, real, size) in files.into_iter().take(count) {
+        if std::fs::remove_file(&real).is_err() {
+            continue;
+        }
+        match crate::db::attachments::mark_evicted(&path) {
+            Ok(ids) => eviction.affected.extend(ids),
+            Err(e) => {
+                // The file's gone either way; the integrity sweep resets what this couldn't
+                crate::log_warn!("[MediaCache] Failed to record eviction of {}: {}", path, e);
+            }
+        }
+        eviction.files += 1;
+        eviction.bytes += size;
+    }
+    if eviction.files > 0 {
+        crate::log_info!("[MediaCache] Evicted {} file(s), {} bytes", eviction.files, eviction.bytes);
+    }
+    Ok(eviction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_oldest_until_under_the_limit() {
+        assert_eq!(eviction_count(&[40, 30, 20, 10], 100), 0, "fits");
+        assert_eq!(eviction_count(&[40, 30, 20, 10], 60), 2, "100 → 60 needs the two oldest gone");
+        assert_eq!(eviction_count(&[40, 30, 20, 10], 59), 2);
+        assert_eq!(eviction_count(&[40, 30, 20, 10], 29), 3);
+        assert_eq!(eviction_count(&[500], 100), 0, "the newest file stays even when it alone is over");
+        assert_eq!(eviction_count(&[10, 500], 100), 1);
+        assert_eq!(eviction_count(&[], 100), 0);
+    }
+}
@@ -0,0 +1,363 @@
+//! Block list sync — NIP-51 mute list (kind 10000), so a block made in Vector holds in every other
+//! Nostr client, and the other way round.
+//!
+//! Blocks are profile flags (`profile::sync::block_user`); between Vector installs they already ride
+//! the settings snapshot (`settings_sync`). This module mirrors them onto the standard mute list:
+//! blocks Vector adds are written as PRIVATE entries (NIP-44 to ourselves, in `content`), so the
+//! public list doesn't advertise who the user blocked. Public entries and non-`p` tags (muted
+//! words, hashtags, threads) other clients put there are kept as they are.
+//!
+//! The list is a whole-list replace, so what changed on the relay side is read against the last list
+//! this device saw (`mute_list_npubs`): an npub that appeared was blocked elsewhere, one that
+//! disappeared was unblocked elsewhere. Each is adopted unless the local block/unblock is newer (the
+//! `blocked:<npub>` stamps `settings_sync` keeps), in which case the next publish carries it.
+//!
+//! Nothing is published unless the relays actually answered the fetch
+//! ([`crate::settings_sync::fetch_confirmed`]): a timed-out fetch looks like no list at all, and
+//! publishing over it would drop every entry other clients keep there.
+
+use std::collections::HashSet;
+
+use nostr_sdk::prelude::*;
+
+/// JSON array of the npubs on the last mute list this device saw or published.
+const LAST_SEEN_KEY: &str = "mute_list_npubs";
+const FETCH_TIMEOUT_SECS: u64 = 15;
+
+/// The newest kind 10000 on the relays, parsed.
+struct RemoteList {
+    created_at: u64,
+    /// Every tag, `p` or not, as published.
+    public: Vec<Tag>,
+    /// The decrypted private tags.
+    private: Vec<Vec<String>>,
+}
+
+impl RemoteList {
+    fn npubs(&self) -> HashSet<String> {
+        let public = self.public.iter().map(|t| t.as_slice().to_vec());
+        public.chain(self.private.iter().cloned()).filter_map(|t| p_tag_npub(&t)).collect()
+    }
+}
+
+/// The npub a `["p", <hex>, ...]` tag names.
+fn p_tag_npub(tag: &[String]) -> Option<String> {
+    if tag.first().map(String::as_str) != Some("p") {
+        return None;
+    }
+    PublicKey::parse(tag.get(1)?).ok()?.to_bech32().ok()
+}
+
+/// What reconciling a relay list with the local blocks does.
+#[derive(Default, Debug, PartialEq)]
+struct Plan {
+    /// Blocked from another client: block here.
+    block: Vec<String>,
+    /// Unblocked from another client: unblock here.
+    unblock: Vec<String>,
+    /// Blocked here, missing from the list.
+    add: Vec<String>,
+    /// Unblocked here, still on the list.
+    remove: Vec<String>,
+}
+
+/// Reconcile `listed` (a list published at `list_ms`) with the local `blocked` set. `last` is the list
+/// this device saw before; `stamp` is when an npub was last blocked or unblocked here (0 = never).
+fn plan(
+    listed: &HashSet<String>,
+    last: &HashSet<String>,
+    blocked: &HashSet<String>,
+    list_ms: u64,
+    stamp: impl Fn(&str) -> u64,
+) -> Plan {
+    let mut p = Plan::default();
+    for npub in listed.difference(blocked) {
+        if stamp(npub) > list_ms {
+            p.remove.push(npub.clone());
+        } else {
+            p.block.push(npub.clone());
+        }
+    }
+    for npub in blocked.difference(listed) {
+        if last.contains(npub) && stamp(npub) <= list_ms {
+            p.unblock.push(npub.clone());
+        } else {
+            p.add.push(npub.clone());
+        }
+    }
+    for v in [&mut p.block, &mut p.unblock, &mut p.add, &mut p.remove] {
+        v.sort();
+    }
+    p
+}
+
+fn load_last_seen() -> HashSet<String> {
+    crate::db::settings::get_sql_setting(LAST_SEEN_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_last_seen(npubs: &HashSet<String>) {
+    let mut sorted: Vec<&String> = npubs.iter().collect();
+    sorted.sort();
+    if let Ok(json) = serde_json::to_string(&sorted) {
+        let _ = crate::db::settings::set_sql_setting(LAST_SEEN_KEY.to_string(), json);
+    }
+}
+
+async fn blocked_npubs() -> HashSet<String> {
+    crate::profile::sync::get_blocked_users().await.into_iter().map(|p| p.id).collect()
+}
+
+// ============================================================================
+// Transport (kind 10000, replaceable)
+// ============================================================================
+
+/// Parse `event`, decrypting its private half. `Err` when the private half exists but can't be read:
+/// publishing over it would erase entries we never saw.
+async fn parse_list(client: &Client, my_pk: &PublicKey, event: &Event) -> Result<RemoteList, String> {
+    let private = if event.content.is_empty() {
+        Vec::new()
+    } else {
+        let signer = client.signer().await.map_err(|e| format!("Signer unavailable: {}", e))?;
+        // Older clients encrypted with NIP-04 (`?iv=` suffix); NIP-51 now says NIP-44
+        let plaintext = if event.content.contains("?iv=") {
+            signer.nip04_decrypt(my_pk, &event.content).await.map_err(|e| format!("nip04 decrypt mute list: {}", e))?
+        } else {
+            signer.nip44_decrypt(my_pk, &event.content).await.map_err(|e| format!("nip44 decrypt mute list: {}", e))?
+        };
+        serde_json::from_str(&plaintext).map_err(|e| format!("mute list private tags: {}", e))?
+    };
+    Ok(RemoteList { created_at: event.created_at.as_secs(), public: event.tags.iter().cloned().collect(), private })
+}
+
+/// The newest kind 10000 on the relays. `Ok(None)` only when every relay answered that there's none.
+async fn fetch_remote(client: &Client, my_pk: PublicKey) -> Result<Option<RemoteList>, String> {
+    let filter = Filter::new().author(my_pk).kind(Kind::MuteList).limit(1);
+    let events = crate::settings_sync::fetch_confirmed(client, filter, FETCH_TIMEOUT_SECS)
+        .await
+        .map_err(|e| format!("fetch mute list (kind 10000): {}", e))?;
+    match events.into_iter().max_by_key(|e| e.created_at) {
+        Some(ev) => parse_list(client, &my_pk, &ev).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// `remote`'s public tags and private entries with `plan`'s adds (as private entries) and removals
+/// applied. Everything else — other `p` entries, words, hashtags, threads — is carried as it was.
+fn rebuild(remote: Option<RemoteList>, plan: &Plan) -> (Vec<Tag>, Vec<Vec<String>>) {
+    let (mut public, mut private) = remote.map(|r| (r.public, r.private)).unwrap_or_default();
+    public.retain(|t| p_tag_npub(t.as_slice()).is_none_or(|n| !plan.remove.contains(&n)));
+    private.retain(|t| p_tag_npub(t).is_none_or(|n| !plan.remove.contains(&n)));
+    for npub in &plan.add {
+        let Ok(pk) = PublicKey::parse(npub) else { continue };
+        private.push(vec!["p".to_string(), pk.to_hex()]);
+    }
+    (public, private)
+}
+
+/// Publish `remote` with `plan` applied. `remote` is `None` only when the relays confirmed there's
+/// no list yet.
+async fn publish(client: &Client, my_pk: PublicKey, remote: Option<RemoteList>, plan: &Plan) -> Result<(), String> {
+    let (public, private) = rebuild(remote, plan);
+    let signer = client.signer().await.map_err(|e| format!("Signer unavailable: {}", e))?;
+    let json = serde_json::to_string(&private).map_err(|e| e.to_string())?;
+    let content = signer
+        .nip44_encrypt(&my_pk, &json)
+        .await
+        .map_err(|e| format!("nip44 encrypt mute list: {}", e))?;
+    client
+        .send_event_builder(EventBuilder::new(Kind::MuteList, content).tags(public))
+        .await
+        .map_err(|e| format!("Failed to publish mute list (kind 10000): {}", e))?;
+    Ok(())
+}
+
+/// Block and unblock what another client did. Returns the list as it now stands.
+async fn apply(
+    remote: Option<&RemoteList>,
+    handler: &dyn crate::profile::sync::ProfileSyncHandler,
+) -> (Plan, HashSet<String>) {
+    let listed = remote.map(|r| r.npubs()).unwrap_or_default();
+    let list_ms = remote.map(|r| r.created_at * 1000).unwrap_or(0);
+    let plan = plan(&listed, &load_last_seen(), &blocked_npubs().await, list_ms, |npub| {
+        crate::settings_sync::local_stamp(&crate::settings_sync::blocked_key(npub))
+    });
+    for npub in &plan.block {
+        crate::profile::sync::block_user(npub.clone(), handler).await;
+    }
+    for npub in &plan.unblock {
+        crate::profile::sync::unblock_user(npub.clone(), handler).await;
+    }
+    if !plan.block.is_empty() || !plan.unblock.is_empty() {
+        crate::log_info!("[MuteList] Adopted {} block(s), {} unblock(s)", plan.block.len(), plan.unblock.len());
+    }
+    (plan, listed)
+}
+
+/// Full round: fetch the relay list, adopt what other clients changed, and publish the blocks and
+/// unblocks made here.
+pub async fn sync_now(handler: &dyn crate::profile::sync::ProfileSyncHandler) -> Result<(), String> {
+    let session = crate::state::SessionGuard::capture();
+    let client = crate::state::nostr_client().ok_or("Nostr client not initialized")?;
+    let my_pk = crate::state::my_public_key().ok_or("Not logged in")?;
+
+    let remote = fetch_remote(&client, my_pk).await?;
+    if !session.is_valid() {
+        return Ok(());
+    }
+    let (plan, mut listed) = apply(remote.as_ref(), handler).await;
+    if (!plan.add.is_empty() || !plan.remove.is_empty()) && session.is_valid() {
+        publish(&client, my_pk, remote, &plan).await?;
+        crate::log_info!("[MuteList] Published {} block(s), {} unblock(s)", plan.add.len(), plan.remove.len());
+        listed.extend(plan.add.iter().cloned());
+        listed.retain(|n| !plan.remove.contains(n));
+    }
+    save_last_seen(&listed);
+    Ok(())
+}
+
+/// Live path: adopt a mute list another client published. Never republishes (the relay echoes our
+/// own publishes back on the same subscription).
+pub async fn ingest_remote_event(event: &Event, handler: &dyn crate::profile::sync::ProfileSyncHandler) {
+    let session = crate::state::SessionGuard::capture();
+    let (Some(client), Some(my_pk)) = (crate::state::nostr_client(), crate::state::my_public_key()) else { return };
+    if event.pubkey != my_pk || event.kind != Kind::MuteList {
+        return;
+    }
+    let remote = match parse_list(&client, &my_pk, event).await {
+        Ok(r) => r,
+        Err(e) => {
+            crate::log_warn!("[MuteList] Ignoring unreadable mute list: {}", e);
+            return;
+        }
+    };
+    if !session.is_valid() {
+        return;
+    }
+    let (_, listed) = apply(Some(&remote), handler).await;
+    save_last_seen(&listed);
+}
+
+static REPUBLISH_GEN: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// After a local block or unblock: coalesce a burst into one publish. Read-merge-write against the
+/// relay's current list, so entries other clients added are kept.
+pub fn republish_debounced() {
+    use std::sync::atomic::Ordering;
+    let gen = REPUBLISH_GEN.fetch_add(1, Ordering::SeqCst) + 1;
+    let session = crate::state::SessionGuard::capture();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        if REPUBLISH_GEN.load(Ordering::SeqCst) != gen || !session.is_valid() {
+            return;
+        }
+        let (Some(client), Some(my_pk)) = (crate::state::nostr_client(), crate::state::my_public_key()) else { return };
+        let remote = match fetch_remote(&client, my_pk).await {
+            Ok(r) => r,
+            Err(e) => {
+                crate::log_warn!("[MuteList] Republish fetch failed: {}", e);
+                return;
+            }
+        };
+        if !session.is_valid() {
+            return;
+        }
+        // Publish only: adopting the relay's side is the sync paths' job, so a block made here a
+        // moment ago can't be undone by a list that predates it
+        let listed = remote.as_ref().map(|r| r.npubs()).unwrap_or_default();
+        let list_ms = remote.as_ref().map(|r| r.created_at * 1000).unwrap_or(0);
+        let mut plan = plan(&listed, &load_last_seen(), &blocked_npubs().await, list_ms, |npub| {
+            crate::settings_sync::local_stamp(&crate::settings_sync::blocked_key(npub))
+        });
+        plan.block.clear();
+        plan.unblock.clear();
+        if plan.add.is_empty() && plan.remove.is_empty() {
+            return;
+        }
+        match publish(&client, my_pk, remote, &plan).await {
+            Ok(()) => {
+                let mut now_listed = listed;
+                now_listed.extend(plan.add.iter().cloned());
+                now_listed.retain(|n| !plan.remove.contains(n));
+                save_last_seen(&now_listed);
+            }
+            Err(e) => crate::log_warn!("[MuteList] Republish failed: {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(npubs: &[&str]) -> HashSet<String> {
+        npubs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn plan_reads_list_changes_against_the_last_seen_list() {
+        let listed = set(&["npub1kept", "npub1newelsewhere", "npub1unblockedhere"]);
+        let last = set(&["npub1kept", "npub1unblockedhere", "npub1droppedelsewhere", "npub1reblocked"]);
+        let blocked = set(&["npub1kept", "npub1droppedelsewhere", "npub1reblocked", "npub1blockedhere"]);
+        // Local changes after the list (ms 5_000) win; older ones yield to it
+        let stamp = |npub: &str| match npub {
+            "npub1unblockedhere" | "npub1reblocked" => 9_000,
+            "npub1droppedelsewhere" => 1_000,
+            _ => 0,
+        };
+        let p = plan(&listed, &last, &blocked, 5_000, stamp);
+        assert_eq!(p.block, vec!["npub1newelsewhere"]);
+        assert_eq!(p.unblock, vec!["npub1droppedelsewhere"]);
+        assert_eq!(p.add, vec!["npub1blockedhere", "npub1reblocked"]);
+        assert_eq!(p.remove, vec!["npub1unblockedhere"]);
+
+        // First sync: nothing seen before, so nothing is unblocked for being absent
+        let first = plan(&set(&["npub1a"]), &HashSet::new(), &set(&["npub1b"]), 5_000, |_| 0);
+        assert_eq!((first.block, first.unblock, first.add), (vec!["npub1a".to_string()], vec![], vec!["npub1b".to_string()]));
+    }
+
+    #[test]
+    fn only_valid_p_tags_name_npubs() {
+        let pk = Keys::generate().public_key();
+        let npub = pk.to_bech32().unwrap();
+        assert_eq!(p_tag_npub(&["p".into(), pk.to_hex()]), Some(npub));
+        assert_eq!(p_tag_npub(&["t".into(), pk.to_hex()]), None);
+        assert_eq!(p_tag_npub(&["p".into(), "nope".into()]), None);
+        assert_eq!(p_tag_npub(&["p".into()]), None);
+    }
+
+    #[test]
+    fn rebuild_keeps_what_vector_doesnt_manage() {
+        let (public_pk, unblocked, blocked) = (Keys::generate().public_key(), Keys::generate().public_key(), Keys::generate().public_key());
+        let remote = RemoteList {
+            created_at: 1,
+            public: vec![
+                Tag::public_key(public_pk),
+                Tag::public_key(unblocked),
+                Tag::custom(TagKind::custom("word"), ["spoiler"]),
+                Tag::hashtag("nsfw"),
+            ],
+            private: vec![vec!["word".into(), "secret".into()], vec!["e".into(), "ab".repeat(32)]],
+        };
+        let plan = Plan {
+            add: vec![blocked.to_bech32().unwrap()],
+            remove: vec![unblocked.to_bech32().unwrap()],
+            ..Default::default()
+        };
+        let (public, private) = rebuild(Some(remote), &plan);
+        let public: Vec<Vec<String>> = public.iter().map(|t| t.as_slice().to_vec()).collect();
+        assert_eq!(public, vec![
+            vec!["p".to_string(), public_pk.to_hex()],
+            vec!["word".to_string(), "spoiler".to_string()],
+            vec!["t".to_string(), "nsfw".to_string()],
+        ]);
+        assert_eq!(private, vec![
+            vec!["word".to_string(), "secret".to_string()],
+            vec!["e".to_string(), "ab".repeat(32)],
+            vec!["p".to_string(), blocked.to_hex()],
+        ]);
+    }
+}
@@ -78,3 +78,182 @@ pub async fn reconcile_missing(
 
     Ok(missing)
 }
+
+/// NIP-59 backdates every gift-wrap's `created_at` by a random amount up to this (nostr-sdk's
+/// `RANGE_RANDOM_TIMESTAMP_TWEAK`, which our own wraps use too). A wrapper's time therefore says
+/// only that the message was sent at or after it, up to two days later.
+pub const GIFT_WRAP_MAX_FUZZ_SECS: u64 = 2 * 24 * 3600;
+
+/// A DM reconcile window meant to cover everything *sent* in the last `span_secs`, keyed on
+/// wrapper time as relays require: the relay filter plus the fingerprint items inside it.
+///
+/// `since` is pulled back by [`GIFT_WRAP_MAX_FUZZ_SECS`] so a message sent just inside the span
+/// isn't missed because its wrapper claims an earlier time. The items use the same cut-off —
+/// negentropy compares like with like, and a mismatched edge would report every wrapper between
+/// the two cut-offs as missing. Ordering never uses these times; messages sort by rumor
+/// `created_at` once unwrapped.
+pub fn gift_wrap_window(
+    my_public_key: PublicKey,
+    items: &[(EventId, Timestamp)],
+    now: u64,
+    span_secs: u64,
+) -> (Vec<(EventId, Timestamp)>, Filter) {
+    let since = now.saturating_sub(span_secs.saturating_add(GIFT_WRAP_MAX_FUZZ_SECS));
+    let items = items.iter().filter(|(_, ts)| ts.as_secs() >= since).cloned().collect();
+    let filter = Filter::new()
+        .pubkey(my_public_key)
+        .kind(Kind::GiftWrap)
+        .since(Timestamp::from_secs(since));
+    (items, filter)
+}
+
+const DAY_SECS: u64 = 24 * 3600;
+
+/// Which slice of the DM history one sync pass reconciles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncWindow {
+    /// Messages sent in the last this-many secs ([`gift_wrap_window`]).
+    Recent(u64),
+    /// All of it: the archive pass.
+    Full,
+}
+
+/// A single relay reconnecting: just what it may have missed while down.
+pub const RECONNECT_WINDOW: SyncWindow = SyncWindow::Recent(2 * DAY_SECS);
+/// Boot's quick pass, so recent chats fill in near-instantly before the archive pass.
+pub const QUICK_WINDOW: SyncWindow = SyncWindow::Recent(7 * DAY_SECS);
+
+impl SyncWindow {
+    /// The window as of [`crate::clock::now_secs`]: the fingerprint items inside it and the relay
+    /// filter that matches them.
+    pub fn plan(self, my_public_key: PublicKey, items: &[(EventId, Timestamp)]) -> (Vec<(EventId, Timestamp)>, Filter) {
+        match self {
+            Self::Recent(span_secs) => gift_wrap_window(my_public_key, items, crate::clock::now_secs(), span_secs),
+            Self::Full => (items.to_vec(), Filter::new().pubkey(my_public_key).kind(Kind::GiftWrap)),
+        }
+    }
+}
+
+/// Archive backlogs larger than this wait for the user's go-ahead (`history_sync_consent`).
+pub const HISTORY_CONSENT_THRESHOLD: usize = 5_000;
+
+/// What the archive pass does with the wrappers its reconcile found missing.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ArchiveStep {
+    /// Nothing missing: the sync is complete.
+    Done,
+    /// Fetch these now.
+    Fetch(Vec<EventId>),
+    /// Too many to fetch unasked: park them until the user consents (or skips).
+    AwaitConsent(Vec<EventId>),
+}
+
+/// Decide the archive pass's next step. `consent_remembered` is the user's standing "don't ask
+/// again" for history sync.
+pub fn archive_step(missing: HashSet<EventId>, consent_remembered: bool) -> ArchiveStep {
+    if missing.is_empty() {
+        return ArchiveStep::Done;
+    }
+    let ids: Vec<EventId> = missing.into_iter().collect();
+    if ids.len() > HISTORY_CONSENT_THRESHOLD && !consent_remembered {
+        ArchiveStep::AwaitConsent(ids)
+    } else {
+        ArchiveStep::Fetch(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: u64 = 3600;
+    const NOW: u64 = 1_750_000_000;
+
+    /// A ledger entry for a wrapper sent `sent_ago` seconds before NOW and backdated by `fuzz`.
+    fn fuzzed(n: u8, sent_ago: u64, fuzz: u64) -> (EventId, Timestamp) {
+        (EventId::from_byte_array([n; 32]), Timestamp::from_secs(NOW - sent_ago - fuzz))
+    }
+
+    fn since_of(filter: &Filter) -> u64 {
+        filter.since.expect("window sets since").as_secs()
+    }
+
+    #[test]
+    fn window_covers_maximally_backdated_sends() {
+        let me = Keys::generate().public_key();
+        let ledger = vec![
+            // Sent now, fuzzed to the limit
+            fuzzed(1, 0, GIFT_WRAP_MAX_FUZZ_SECS),
+            // Sent just inside a 2-day span, fuzzed to the limit and not at all
+            fuzzed(2, 2 * 24 * HOUR - 1, GIFT_WRAP_MAX_FUZZ_SECS),
+            fuzzed(3, 2 * 24 * HOUR - 1, 0),
+            // Sent well outside the span
+            fuzzed(4, 5 * 24 * HOUR, 0),
+        ];
+
+        let (items, filter) = gift_wrap_window(me, &ledger, NOW, 2 * 24 * HOUR);
+        assert_eq!(since_of(&filter), NOW - 4 * 24 * HOUR);
+        let ids: Vec<u8> = items.iter().map(|(id, _)| id.as_bytes()[0]).collect();
+        assert_eq!(ids, vec![1, 2, 3], "every send inside the span is fingerprinted, however fuzzed");
+
+        // The relay-side view: a wrapper passes the filter exactly when it's in the item set
+        for (id, ts) in &ledger {
+            let in_filter = ts.as_secs() >= since_of(&filter);
+            assert_eq!(in_filter, items.iter().any(|(i, _)| i == id), "items and filter share one edge");
+        }
+    }
+
+    #[test]
+    fn unwidened_window_would_miss_fuzzed_wrappers() {
+        // Regression: a 2-day window keyed straight on wrapper time dropped a message sent an hour
+        // ago whose wrapper was backdated 47.5 hours.
+        let entry = fuzzed(1, HOUR, 47 * HOUR + HOUR / 2);
+        assert!(entry.1.as_secs() < NOW - 2 * 24 * HOUR, "outside the naive window");
+        let (items, _) = gift_wrap_window(Keys::generate().public_key(), &[entry], NOW, 2 * 24 * HOUR);
+        assert_eq!(items.len(), 1);
+    }
+
+    #[test]
+    fn sync_passes_step_through_windows_as_time_moves() {
+        let me = Keys::generate().public_key();
+        let clock = crate::clock::freeze(NOW * 1000);
+        // Sent a day ago (wrapper on time), 3 and 6 days ago (wrappers backdated a day), and last month
+        let ledger = vec![
+            fuzzed(1, 24 * HOUR, 0),
+            fuzzed(2, 3 * 24 * HOUR, 24 * HOUR),
+            fuzzed(3, 6 * 24 * HOUR, 24 * HOUR),
+            fuzzed(4, 30 * 24 * HOUR, 0),
+        ];
+        let ids = |items: &[(EventId, Timestamp)]| items.iter().map(|(id, _)| id.as_bytes()[0]).collect::<Vec<u8>>();
+
+        // Reconnect ⊂ quick ⊂ archive
+        assert_eq!(ids(&RECONNECT_WINDOW.plan(me, &ledger).0), vec![1, 2]);
+        assert_eq!(ids(&QUICK_WINDOW.plan(me, &ledger).0), vec![1, 2, 3]);
+        let (archive, filter) = SyncWindow::Full.plan(me, &ledger);
+        assert_eq!(archive.len(), 4);
+        assert!(filter.since.is_none(), "the archive pass reconciles everything");
+
+        // Three days on, the 6-day-old send is 9 days old: out of the quick pass, only the archive has it
+        clock.advance_secs(3 * 24 * HOUR);
+        assert_eq!(ids(&RECONNECT_WINDOW.plan(me, &ledger).0), vec![1]);
+        assert_eq!(ids(&QUICK_WINDOW.plan(me, &ledger).0), vec![1, 2]);
+        assert_eq!(since_of(&QUICK_WINDOW.plan(me, &ledger).1), NOW + 3 * 24 * HOUR - 9 * 24 * HOUR);
+
+        // The archive pass: done, fetch, or park for consent unless the user said not to ask
+        let missing = |n: usize| (0..n as u32).map(|i| {
+            let mut b = [0u8; 32];
+            b[..4].copy_from_slice(&i.to_be_bytes());
+            EventId::from_byte_array(b)
+        }).collect::<HashSet<_>>();
+        assert_eq!(archive_step(missing(0), false), ArchiveStep::Done);
+        assert!(matches!(archive_step(missing(HISTORY_CONSENT_THRESHOLD), false), ArchiveStep::Fetch(v) if v.len() == HISTORY_CONSENT_THRESHOLD));
+        assert!(matches!(archive_step(missing(HISTORY_CONSENT_THRESHOLD + 1), false), ArchiveStep::AwaitConsent(_)));
+        assert!(matches!(archive_step(missing(HISTORY_CONSENT_THRESHOLD + 1), true), ArchiveStep::Fetch(_)));
+    }
+
+    #[test]
+    fn window_saturates_near_epoch() {
+        let (_, filter) = gift_wrap_window(Keys::generate().public_key(), &[], 100, 7 * 24 * HOUR);
+        assert_eq!(since_of(&filter), 0);
+    }
+}
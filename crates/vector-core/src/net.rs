@@ -53,8 +53,9 @@ fn is_ipv6_private(ip: &std::net::Ipv6Addr) -> bool {
 /// goes through Tor — period. If Tor is enabled but not currently running
 /// (bootstrap in flight, mid-restart, service crashed), the returned client
 /// is wired to a blackhole SOCKS proxy so requests fail at the TCP layer
-/// without any chance of leaking clearnet traffic. Direct connections are
-/// only ever issued when the user has explicitly disabled Tor.
+/// without any chance of leaking clearnet traffic. With Tor off, the user's
+/// own SOCKS5 proxy applies the same way, blackholing while it's down if its
+/// kill switch is on (see [`crate::proxy`]).
 ///
 /// Callers should use this rather than `reqwest::Client::builder()` directly
 /// so the failsafe automatically covers their traffic. The `disallowed_methods`
@@ -99,28 +100,25 @@ pub fn build_http_client_with_options(
         }));
     }
 
-    #[cfg(feature = "tor")]
-    {
-        match crate::tor::transport_state() {
-            crate::tor::TorTransportState::Active(addr) => {
-                // Use the addr from the variant directly — re-querying via
-                // proxy_url() races against TorService::stop() and can panic.
-                let url = format!("socks5h://{addr}");
-                let proxy = reqwest::Proxy::all(&url)
-                    .map_err(|e| format!("Tor proxy URL ({url}) invalid: {e}"))?;
-                builder = builder.proxy(proxy);
-            }
-            crate::tor::TorTransportState::RequiredButInactive => {
-                // Tor failsafe: route to a blackhole so connections fail safe
-                // instead of leaking direct.
-                let url = format!("socks5h://{}", crate::tor::blackhole_proxy_addr());
-                let proxy = reqwest::Proxy::all(&url)
-                    .map_err(|e| format!("blackhole proxy invalid: {e}"))?;
-                builder = builder.proxy(proxy);
-            }
-            crate::tor::TorTransportState::Disabled => {
-                // No proxy — user has Tor off.
-            }
+    match crate::proxy::transport() {
+        crate::proxy::Transport::Proxy(addr) => {
+            // Use the addr from the variant directly — re-querying via
+            // tor::proxy_url() races against TorService::stop() and can panic.
+            let url = format!("socks5h://{addr}");
+            let proxy = reqwest::Proxy::all(&url)
+                .map_err(|e| format!("SOCKS proxy URL ({url}) invalid: {e}"))?;
+            builder = builder.proxy(proxy);
+        }
+        crate::proxy::Transport::Blocked => {
+            // Failsafe: route to a blackhole so connections fail safe
+            // instead of leaking direct.
+            let url = format!("socks5h://{}", crate::proxy::blackhole_addr());
+            let proxy = reqwest::Proxy::all(&url)
+                .map_err(|e| format!("blackhole proxy invalid: {e}"))?;
+            builder = builder.proxy(proxy);
+        }
+        crate::proxy::Transport::Direct => {
+            // No proxy — Tor is off and no user proxy applies.
         }
     }
 
@@ -139,7 +137,7 @@ pub fn build_http_client_with_options(
 // a Tor toggle should affect future requests immediately — but the static is
 // frozen at first init. Instead, we hold an `Arc<Client>` behind a `RwLock`
 // and rebuild it via `rebuild_shared_http_client()` whenever the Tor state
-// changes. In-flight requests finish on the old Arc; new requests pick up
+// or proxy settings change. In-flight requests finish on the old Arc; new requests pick up
 // the new one.
 
 use std::sync::{Arc, OnceLock, RwLock};
@@ -0,0 +1,321 @@
+//! `nostr:` references in message text (NIP-21) — `nprofile`, `nevent`, `note` and `naddr`.
+//!
+//! [`resolve`] decodes a reference and fetches what it points at from our own relay pool only:
+//! relay hints inside the URI are sender-chosen and never dialled. Results are cached per URI
+//! for the session (up to [`MAX_CACHED`], oldest evicted first), so a message re-rendered later
+//! carries its preview straight away via [`cached_previews`]. Bare `npub` references are mentions
+//! and handled elsewhere.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use nostr_sdk::prelude::*;
+
+/// How long a relay fetch may take before the reference is treated as unresolvable.
+const FETCH_TIMEOUT_SECS: u64 = 8;
+
+/// Failed lookups are retried after this long; successful ones live for the session.
+const ERROR_TTL_SECS: u64 = 60;
+
+/// Longest preview text (note content, profile about, article summary), in characters.
+pub const MAX_PREVIEW_CHARS: usize = 280;
+
+/// Most cached lookups; past it the oldest is evicted.
+const MAX_CACHED: usize = 512;
+
+/// Most references resolved per received message, so one message can't queue a fetch storm.
+const MAX_BACKGROUND_REFS: usize = 8;
+
+/// Prefixes of the references we resolve.
+const PREFIXES: [&str; 4] = ["nprofile1", "nevent1", "note1", "naddr1"];
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NostrRefKind {
+    Profile,
+    Event,
+    Address,
+}
+
+/// A resolved reference, as shown under the message.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct NostrPreview {
+    /// The reference as written, lowercased and with its `nostr:` prefix.
+    pub uri: String,
+    pub kind: NostrRefKind,
+    /// The profile itself, or the event's author (npub).
+    pub author: String,
+    /// Profile name, or an addressable event's `title` tag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Note content, profile about, or an addressable event's `summary` (clipped).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// The referenced event's kind (events and addresses).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_kind: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+}
+
+struct Cached {
+    result: Result<NostrPreview, String>,
+    at: Instant,
+}
+
+static CACHE: LazyLock<Mutex<HashMap<String, Cached>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn cache_insert(key: String, result: Result<NostrPreview, String>) {
+    let Ok(mut cache) = CACHE.lock() else { return };
+    if cache.len() >= MAX_CACHED && !cache.contains_key(&key) {
+        if let Some(oldest) = cache.iter().min_by_key(|(_, c)| c.at).map(|(k, _)| k.clone()) {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(key, Cached { result, at: Instant::now() });
+}
+
+/// Drop every cached preview — called on account swap.
+pub fn clear_cache() {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.clear();
+    }
+}
+
+/// Every distinct `nostr:` reference we resolve in `content`, in order, skipping code.
+pub fn find_uris(content: &str) -> Vec<String> {
+    if !content.contains("nostr:") {
+        return Vec::new();
+    }
+    let code = crate::code_blocks::code_ranges(content);
+    let mut out: Vec<String> = Vec::new();
+    for (at, _) in content.match_indices("nostr:") {
+        if crate::code_blocks::in_code(&code, at) {
+            continue;
+        }
+        let rest = &content[at + "nostr:".len()..];
+        let len = rest.bytes().take_while(|b| b.is_ascii_alphanumeric()).count();
+        let bech = rest[..len].to_ascii_lowercase();
+        if !PREFIXES.iter().any(|p| bech.starts_with(p)) {
+            continue;
+        }
+        let uri = format!("nostr:{}", bech);
+        if !out.contains(&uri) {
+            out.push(uri);
+        }
+    }
+    out
+}
+
+/// Already-resolved previews for the references in `content`. Never touches the network.
+pub fn cached_previews(content: &str) -> Vec<NostrPreview> {
+    let uris = find_uris(content);
+    if uris.is_empty() {
+        return Vec::new();
+    }
+    let Ok(cache) = CACHE.lock() else { return Vec::new() };
+    uris.iter()
+        .filter_map(|u| cache.get(u).and_then(|c| c.result.as_ref().ok().cloned()))
+        .collect()
+}
+
+/// Resolve the references in a just-received message, then re-emit it (`message_update`) with
+/// its previews attached. No-op when the text has none or the chat's remote-content policy
+/// doesn't auto-fetch.
+pub fn resolve_in_background(chat_id: &str, message_id: &str, content: &str) {
+    let mut uris = find_uris(content);
+    if uris.is_empty() || crate::remote_content::gate(chat_id, false).is_err() {
+        return;
+    }
+    uris.truncate(MAX_BACKGROUND_REFS);
+    let session = crate::state::SessionGuard::capture();
+    let (chat_id, message_id) = (chat_id.to_string(), message_id.to_string());
+    tokio::spawn(async move {
+        let mut any = false;
+        for uri in &uris {
+            if !session.is_valid() {
+                return;
+            }
+            any |= resolve(uri).await.is_ok();
+        }
+        if !any || !session.is_valid() {
+            return;
+        }
+        let msg = {
+            let state = crate::state::STATE.lock().await;
+            if !session.is_valid() {
+                return;
+            }
+            state.find_message(&message_id).map(|(_, m)| m)
+        };
+        if let Some(msg) = msg {
+            crate::traits::emit_event("message_update", &serde_json::json!({
+                "old_id": &message_id,
+                "message": &msg,
+                "chat_id": &chat_id
+            }));
+        }
+    });
+}
+
+fn clip(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(MAX_PREVIEW_CHARS) {
+        Some((cut, _)) => format!("{}…", text[..cut].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| clip(text))
+}
+
+/// Resolve one `nostr:` reference (the prefix is optional), from cache when possible.
+pub async fn resolve(uri: &str) -> Result<NostrPreview, String> {
+    let bech = uri.trim().trim_start_matches("nostr:").to_ascii_lowercase();
+    let key = format!("nostr:{}", bech);
+
+    if let Ok(cache) = CACHE.lock() {
+        if let Some(hit) = cache.get(&key) {
+            if hit.result.is_ok() || hit.at.elapsed() < Duration::from_secs(ERROR_TTL_SECS) {
+                return hit.result.clone();
+            }
+        }
+    }
+
+    let session = crate::state::SessionGuard::capture();
+    let result = fetch(&key, &bech).await;
+    // A swap mid-fetch cleared the cache; don't refill it from the old account's relays
+    if session.is_valid() {
+        cache_insert(key, result.clone());
+    }
+    result
+}
+
+async fn fetch(key: &str, bech: &str) -> Result<NostrPreview, String> {
+    let decoded = Nip19::from_bech32(bech).map_err(|e| format!("Invalid nostr reference: {}", e))?;
+    match decoded {
+        Nip19::Profile(profile) => resolve_profile(key, profile.public_key).await,
+        Nip19::Pubkey(pubkey) => resolve_profile(key, pubkey).await,
+        Nip19::EventId(id) => resolve_event(key, Filter::new().id(id)).await,
+        Nip19::Event(event) => resolve_event(key, Filter::new().id(event.event_id)).await,
+        Nip19::Coordinate(coord) => {
+            let c = coord.coordinate;
+            let filter = Filter::new().author(c.public_key).kind(c.kind).identifier(&c.identifier);
+            resolve_event(key, filter).await
+        }
+        _ => Err("Unsupported nostr reference".to_string()),
+    }
+}
+
+async fn fetch_latest(filter: Filter) -> Result<Event, String> {
+    let client = crate::state::nostr_client().ok_or("Not connected")?;
+    let events = client
+        .fetch_events(filter.limit(1), Duration::from_secs(FETCH_TIMEOUT_SECS))
+        .await
+        .map_err(|e| e.to_string())?;
+    events.into_iter().max_by_key(|e| e.created_at).ok_or_else(|| "Not found on your relays".to_string())
+}
+
+async fn resolve_profile(key: &str, pubkey: PublicKey) -> Result<NostrPreview, String> {
+    let npub = pubkey.to_bech32().map_err(|e| e.to_string())?;
+
+    // A profile we already know needs no fetch
+    let known = {
+        let state = crate::state::STATE.lock().await;
+        state.get_profile(&npub).map(|p| {
+            let name = if p.display_name.is_empty() { &p.name } else { &p.display_name };
+            (non_empty(name), non_empty(&p.about))
+        })
+    };
+    let (title, content) = match known {
+        Some(known) if known.0.is_some() => known,
+        _ => {
+            let event = fetch_latest(Filter::new().author(pubkey).kind(Kind::Metadata)).await?;
+            let meta = Metadata::from_json(&event.content).map_err(|e| e.to_string())?;
+            let name = meta.display_name.filter(|n| !n.trim().is_empty()).or(meta.name);
+            (name.as_deref().and_then(non_empty), meta.about.as_deref().and_then(non_empty))
+        }
+    };
+
+    Ok(NostrPreview {
+        uri: key.to_string(),
+        kind: NostrRefKind::Profile,
+        author: npub,
+        title,
+        content,
+        event_kind: None,
+        created_at: None,
+    })
+}
+
+async fn resolve_event(key: &str, filter: Filter) -> Result<NostrPreview, String> {
+    let event = fetch_latest(filter).await?;
+    let addressable = event.kind.is_addressable();
+    let tag = |name: &str| {
+        event.tags.iter()
+            .map(|t| t.as_slice())
+            .find(|s| s.first().map(String::as_str) == Some(name))
+            .and_then(|s| s.get(1))
+            .and_then(|v| non_empty(v))
+    };
+    let (title, content) = if addressable {
+        (tag("title").or_else(|| tag("name")), tag("summary").or_else(|| non_empty(&event.content)))
+    } else {
+        (None, non_empty(&event.content))
+    };
+
+    Ok(NostrPreview {
+        uri: key.to_string(),
+        kind: if addressable { NostrRefKind::Address } else { NostrRefKind::Event },
+        author: event.pubkey.to_bech32().map_err(|e| e.to_string())?,
+        title,
+        content,
+        event_kind: Some(event.kind.as_u16()),
+        created_at: Some(event.created_at.as_u64()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_references_outside_code() {
+        let (nprofile, note) = ("nprofile1qqsexample", "note1example");
+        let text = format!(
+            "see nostr:{nprofile}, and NOSTR:x nostr:{note} twice nostr:{note}\n`nostr:{note}`\nnostr:npub1abc"
+        );
+        assert_eq!(find_uris(&text), vec![format!("nostr:{nprofile}"), format!("nostr:{note}")]);
+        assert!(find_uris("no refs here").is_empty());
+    }
+
+    #[test]
+    fn cached_previews_only_returns_successes() {
+        let uri = "nostr:note1cached".to_string();
+        let preview = NostrPreview {
+            uri: uri.clone(),
+            kind: NostrRefKind::Event,
+            author: Keys::generate().public_key().to_bech32().unwrap(),
+            title: None,
+            content: Some("hello".into()),
+            event_kind: Some(1),
+            created_at: Some(1),
+        };
+        CACHE.lock().unwrap().insert(uri.clone(), Cached { result: Ok(preview.clone()), at: Instant::now() });
+        assert_eq!(cached_previews(&format!("look {uri}")), vec![preview]);
+
+        CACHE.lock().unwrap().insert(uri.clone(), Cached { result: Err("Not found".into()), at: Instant::now() });
+        assert!(cached_previews(&format!("look {uri}")).is_empty());
+        CACHE.lock().unwrap().remove(&uri);
+    }
+
+    #[test]
+    fn clip_caps_long_text() {
+        let long = "a".repeat(MAX_PREVIEW_CHARS + 10);
+        assert_eq!(clip(&long).chars().count(), MAX_PREVIEW_CHARS + 1);
+        assert_eq!(non_empty("  "), None);
+    }
+}
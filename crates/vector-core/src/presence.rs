@@ -0,0 +1,136 @@
+//! Live presence: an opt-in "online" signal between contacts.
+//!
+//! [`Profile`](crate::profile::Profile)'s `last_active` is a public approximation read from
+//! profile events. Presence is the live counterpart, and it's private. While the account shares
+//! it, every [`INTERVAL`] each contact gets a gift-wrapped `presence` rumor with a NIP-40 expiry
+//! of [`TTL_SECS`], published to the trusted relays like a typing indicator. A contact counts as
+//! online until their latest presence expires. Who sees ours is set by [`PresenceSharing`]; it's
+//! off by default.
+//!
+//! Presence is live state: received pings update the profile in RAM (`last_seen`, `online`) and
+//! are never persisted or ledgered. Expired wraps are purged by relays rather than re-synced.
+
+use std::time::Duration;
+
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Account setting: who our presence is shared with.
+pub const SETTING_KEY: &str = "presence_sharing";
+
+/// How often presence goes out while shared.
+pub const INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How long one presence ping keeps its sender online; two intervals, so one lost ping doesn't
+/// flicker them offline.
+pub const TTL_SECS: u64 = 2 * 5 * 60;
+
+/// Who receives our presence.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceSharing {
+    #[default]
+    Nobody,
+    /// Everyone on our contact list.
+    Contacts,
+}
+
+/// The account's presence sharing.
+pub fn sharing() -> PresenceSharing {
+    match crate::db::settings::get_sql_setting(SETTING_KEY.to_string()).ok().flatten().as_deref() {
+        Some("contacts") => PresenceSharing::Contacts,
+        _ => PresenceSharing::Nobody,
+    }
+}
+
+/// Persist who our presence is shared with. Turning it on announces us straight away.
+pub async fn set_sharing(sharing: PresenceSharing) -> Result<(), String> {
+    let value = match sharing {
+        PresenceSharing::Nobody => "nobody",
+        PresenceSharing::Contacts => "contacts",
+    };
+    crate::db::settings::set_sql_setting(SETTING_KEY.to_string(), value.to_string())?;
+    if sharing == PresenceSharing::Contacts {
+        publish().await;
+    }
+    Ok(())
+}
+
+/// Whether a rumor is a presence ping (content `presence`, `d` = `vector`).
+pub fn is_presence(content: &str, tags: &Tags) -> bool {
+    content == "presence" && tags.find(TagKind::d()).and_then(|t| t.content()) == Some("vector")
+}
+
+/// A presence ping's expiry when it's live at `now` and no further out than [`TTL_SECS`];
+/// `None` for a stale or over-long one.
+pub fn valid_until(tags: &Tags, now: u64) -> Option<u64> {
+    let until = tags.find(TagKind::Expiration)?.content()?.parse::<u64>().ok()?;
+    (until > now && until <= now + TTL_SECS).then_some(until)
+}
+
+/// Send one presence ping to every contact, if the account shares presence. Returns how many
+/// went out.
+pub async fn publish() -> usize {
+    if sharing() != PresenceSharing::Contacts {
+        return 0;
+    }
+    let session = crate::state::SessionGuard::capture();
+    let (Some(client), Some(me)) = (crate::state::nostr_client(), crate::state::my_public_key()) else {
+        return 0;
+    };
+    let contacts = crate::contacts::list().unwrap_or_default();
+    let relays = crate::state::active_trusted_relays().await;
+    if relays.is_empty() {
+        return 0;
+    }
+    let expiry = Timestamp::from_secs(crate::clock::now_secs() + TTL_SECS);
+    let mut sent = 0;
+    for contact in contacts {
+        if !session.is_valid() {
+            break;
+        }
+        let Ok(pubkey) = PublicKey::from_bech32(&contact.npub) else { continue };
+        let rumor = EventBuilder::new(Kind::ApplicationSpecificData, "presence")
+            .tag(Tag::custom(TagKind::d(), vec!["vector"]))
+            .tag(Tag::expiration(expiry))
+            .build(me);
+        if client.gift_wrap_to(relays.clone(), &pubkey, rumor, [Tag::expiration(expiry)]).await.is_ok() {
+            sent += 1;
+        }
+    }
+    sent
+}
+
+/// The presence loop. Hosts with their own async runtime (e.g. Tauri) should spawn this directly.
+pub async fn run_loop() {
+    loop {
+        publish().await;
+        tokio::time::sleep(INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presence_is_live_only_within_its_ttl() {
+        let me = Keys::generate().public_key();
+        let ping = |until: u64| EventBuilder::new(Kind::ApplicationSpecificData, "presence")
+            .tag(Tag::custom(TagKind::d(), vec!["vector"]))
+            .tag(Tag::expiration(Timestamp::from_secs(until)))
+            .build(me);
+        let now = 1_700_000_000;
+
+        let live = ping(now + TTL_SECS);
+        assert!(is_presence(&live.content, &live.tags));
+        assert_eq!(valid_until(&live.tags, now), Some(now + TTL_SECS));
+        assert_eq!(valid_until(&ping(now).tags, now), None, "already expired");
+        assert_eq!(valid_until(&ping(now + TTL_SECS + 1).tags, now), None, "longer than a ping may claim");
+
+        let typing = EventBuilder::new(Kind::ApplicationSpecificData, "typing")
+            .tag(Tag::custom(TagKind::d(), vec!["vector"]))
+            .build(me);
+        assert!(!is_presence(&typing.content, &typing.tags));
+    }
+}
@@ -26,14 +26,18 @@ impl ProfileFlags {
     const MINE:    u8 = 0b001;
     const BLOCKED: u8 = 0b010;
     const BOT:     u8 = 0b100;
+    /// Safety number compared in person (see [`crate::contact_verification`]).
+    const CONTACT_VERIFIED: u8 = 0b1000;
 
     #[inline] pub fn is_mine(self) -> bool    { self.0 & Self::MINE != 0 }
     #[inline] pub fn is_blocked(self) -> bool  { self.0 & Self::BLOCKED != 0 }
     #[inline] pub fn is_bot(self) -> bool      { self.0 & Self::BOT != 0 }
+    #[inline] pub fn is_contact_verified(self) -> bool { self.0 & Self::CONTACT_VERIFIED != 0 }
 
     #[inline] pub fn set_mine(&mut self, v: bool)    { if v { self.0 |= Self::MINE } else { self.0 &= !Self::MINE } }
     #[inline] pub fn set_blocked(&mut self, v: bool)  { if v { self.0 |= Self::BLOCKED } else { self.0 &= !Self::BLOCKED } }
     #[inline] pub fn set_bot(&mut self, v: bool)      { if v { self.0 |= Self::BOT } else { self.0 &= !Self::BOT } }
+    #[inline] pub fn set_contact_verified(&mut self, v: bool) { if v { self.0 |= Self::CONTACT_VERIFIED } else { self.0 &= !Self::CONTACT_VERIFIED } }
 }
 
 // ============================================================================
@@ -51,6 +55,9 @@ pub struct Profile {
     pub avatar: Box<str>,
     pub about: Box<str>,
     pub last_updated: u32,
+    /// Approximate last-active time (compact secs): the newest of their public kind-0/10002/30315
+    /// events. 0 = unknown, or the owner disabled the feature.
+    pub last_active: u32,
     pub flags: ProfileFlags,
     pub avatar_cached: Box<str>,
     pub banner_cached: Box<str>,
@@ -73,6 +80,13 @@ pub struct ProfileExtras {
     pub status_title: Box<str>,
     pub status_purpose: Box<str>,
     pub status_url: Box<str>,
+    pub status_emoji: Box<str>,
+    /// NIP-40 expiry of the live status (unix secs); 0 = never expires.
+    pub status_expires_at: u64,
+    /// When their latest presence ping arrived (unix secs); 0 = never. RAM only.
+    pub last_seen: u64,
+    /// Their latest presence ping's expiry (unix secs), see [`crate::presence`]. RAM only.
+    pub online_until: u64,
 }
 
 impl Default for Profile {
@@ -91,6 +105,7 @@ impl Profile {
             avatar: Box::<str>::default(),
             about: Box::<str>::default(),
             last_updated: 0,
+            last_active: 0,
             flags: ProfileFlags::default(),
             avatar_cached: Box::<str>::default(),
             banner_cached: Box::<str>::default(),
@@ -107,6 +122,10 @@ impl Profile {
     #[inline] pub fn status_title(&self) -> &str { self.extras.as_ref().map_or("", |e| &e.status_title) }
     #[inline] pub fn status_purpose(&self) -> &str { self.extras.as_ref().map_or("", |e| &e.status_purpose) }
     #[inline] pub fn status_url(&self) -> &str { self.extras.as_ref().map_or("", |e| &e.status_url) }
+    #[inline] pub fn status_emoji(&self) -> &str { self.extras.as_ref().map_or("", |e| &e.status_emoji) }
+    #[inline] pub fn status_expires_at(&self) -> u64 { self.extras.as_ref().map_or(0, |e| e.status_expires_at) }
+    #[inline] pub fn last_seen(&self) -> u64 { self.extras.as_ref().map_or(0, |e| e.last_seen) }
+    #[inline] pub fn online_until(&self) -> u64 { self.extras.as_ref().map_or(0, |e| e.online_until) }
 
     /// Materialize the extras box for writing a cold field (allocates on first set).
     #[inline]
@@ -191,18 +210,45 @@ pub struct SlimProfile {
     pub nip05: String,
     pub status: Status,
     pub last_updated: u64,
+    /// Unix secs; see [`Profile::last_active`].
+    #[serde(default)]
+    pub last_active: u64,
+    /// Unix secs of their latest presence ping (see [`crate::presence`]); 0 = never. Never persisted.
+    #[serde(default)]
+    pub last_seen: u64,
+    /// A live presence ping covers now. Derived at serialization time, never persisted.
+    #[serde(default)]
+    pub online: bool,
     pub mine: bool,
     pub bot: bool,
     pub is_blocked: bool,
+    /// The user compared safety numbers with this contact (see [`crate::contact_verification`]).
+    #[serde(default)]
+    pub contact_verified: bool,
     pub avatar_cached: String,
     pub banner_cached: String,
+    /// Registry entry when this is a verified official/bot/service account (see [`crate::verified`]).
+    /// Derived at serialization time, never persisted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified: Option<crate::verified::VerifiedAccount>,
+    /// Zaps between us and this user (see [`crate::zaps`]). Derived at serialization time, never
+    /// persisted.
+    #[serde(default, skip_serializing_if = "crate::zaps::ZapTotals::is_empty")]
+    pub zaps: crate::zaps::ZapTotals,
+    /// Set on a [`SlimProfile::light`] copy: the heavy fields were left out and are fetched on
+    /// demand. Never persisted.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub details_deferred: bool,
 }
 
 impl SlimProfile {
     /// Convert from internal Profile, resolving interner handle to npub.
     pub fn from_profile(profile: &Profile, interner: &crate::compact::NpubInterner) -> Self {
+        let id = interner.resolve(profile.id).unwrap_or("").to_string();
         Self {
-            id: interner.resolve(profile.id).unwrap_or("").to_string(),
+            verified: crate::verified::verified_account(&id),
+            zaps: crate::zaps::profile_totals(&id),
+            id,
             name: profile.name.to_string(),
             display_name: profile.display_name.to_string(),
             nickname: profile.nickname().to_string(),
@@ -217,23 +263,46 @@ impl SlimProfile {
                 title: profile.status_title().to_string(),
                 purpose: profile.status_purpose().to_string(),
                 url: profile.status_url().to_string(),
+                emoji: profile.status_emoji().to_string(),
+                expires_at: profile.status_expires_at(),
             },
             last_updated: crate::compact::secs_from_compact(profile.last_updated),
+            last_active: crate::compact::secs_from_compact(profile.last_active),
+            last_seen: profile.last_seen(),
+            online: profile.online_until() > crate::clock::now_secs(),
             mine: profile.flags.is_mine(),
             bot: profile.flags.is_bot(),
             is_blocked: profile.flags.is_blocked(),
+            contact_verified: profile.flags.is_contact_verified(),
             avatar_cached: profile.avatar_cached.to_string(),
             banner_cached: profile.banner_cached.to_string(),
+            details_deferred: false,
         }
     }
 
+    /// The boot-payload form: names, avatar, status and flags, but not the heavy display-only
+    /// fields (about, banner, website), which the profile views load when opened. Our own
+    /// profile always goes out whole.
+    pub fn light(profile: &Profile, interner: &crate::compact::NpubInterner) -> Self {
+        let mut slim = Self::from_profile(profile, interner);
+        if !slim.mine {
+            slim.about = String::new();
+            slim.banner = String::new();
+            slim.banner_cached = String::new();
+            slim.website = String::new();
+            slim.details_deferred = true;
+        }
+        slim
+    }
+
     /// Convert to internal Profile (for loading from DB).
     pub fn to_profile(&self) -> Profile {
         // Only allocate the extras box when a cold field is actually set — the whole
         // point of the split is that most profiles skip it.
         let extras = (!self.nickname.is_empty() || !self.lud06.is_empty() || !self.lud16.is_empty()
             || !self.nip05.is_empty() || !self.website.is_empty()
-            || !self.status.title.is_empty() || !self.status.purpose.is_empty() || !self.status.url.is_empty())
+            || !self.status.title.is_empty() || !self.status.purpose.is_empty() || !self.status.url.is_empty()
+            || !self.status.emoji.is_empty())
         .then(|| Box::new(ProfileExtras {
             nickname: self.nickname.clone().into_boxed_str(),
             lud06: self.lud06.clone().into_boxed_str(),
@@ -243,6 +312,9 @@ impl SlimProfile {
             status_title: self.status.title.clone().into_boxed_str(),
             status_purpose: self.status.purpose.clone().into_boxed_str(),
             status_url: self.status.url.clone().into_boxed_str(),
+            status_emoji: self.status.emoji.clone().into_boxed_str(),
+            status_expires_at: self.status.expires_at,
+            ..Default::default()
         }));
         Profile {
             id: NO_NPUB,
@@ -252,11 +324,13 @@ impl SlimProfile {
             avatar: self.avatar.clone().into_boxed_str(),
             about: self.about.clone().into_boxed_str(),
             last_updated: crate::compact::secs_to_compact(self.last_updated),
+            last_active: crate::compact::secs_to_compact(self.last_active),
             flags: {
                 let mut f = ProfileFlags::default();
                 f.set_mine(self.mine);
                 f.set_bot(self.bot);
                 f.set_blocked(self.is_blocked);
+                f.set_contact_verified(self.contact_verified);
                 f
             },
             avatar_cached: self.avatar_cached.clone().into_boxed_str(),
@@ -271,11 +345,47 @@ pub struct Status {
     pub title: String,
     pub purpose: String,
     pub url: String,
+    /// Leading emoji of the status ("🎧"), split out of the NIP-38 content.
+    #[serde(default)]
+    pub emoji: String,
+    /// NIP-40 expiry (unix secs); 0 = never. The status is cleared locally once it passes.
+    #[serde(default)]
+    pub expires_at: u64,
 }
 
 impl Status {
     pub fn new() -> Self {
-        Self { title: String::new(), purpose: String::new(), url: String::new() }
+        Self { title: String::new(), purpose: String::new(), url: String::new(), emoji: String::new(), expires_at: 0 }
+    }
+
+    /// Split a NIP-38 status content into `(emoji, text)`. Vector publishes `"<emoji> <text>"` so
+    /// other clients still render the emoji inline; a leading token counts as the emoji only when
+    /// it is short and has no alphanumeric characters.
+    pub fn split_emoji(content: &str) -> (String, String) {
+        let trimmed = content.trim_start();
+        let (head, rest) = trimmed.split_once(char::is_whitespace).unwrap_or((trimmed, ""));
+        let is_emoji = !head.is_empty()
+            && head.chars().count() <= 8
+            && head.chars().all(|c| !c.is_ascii() && !c.is_alphanumeric());
+        if is_emoji {
+            (head.to_string(), rest.trim_start().to_string())
+        } else {
+            (String::new(), content.to_string())
+        }
+    }
+
+    /// Inverse of [`split_emoji`](Self::split_emoji): the content string to publish.
+    pub fn join_emoji(emoji: &str, text: &str) -> String {
+        match (emoji.trim(), text.trim()) {
+            ("", t) => t.to_string(),
+            (e, "") => e.to_string(),
+            (e, t) => format!("{e} {t}"),
+        }
+    }
+
+    /// Whether a status with this expiry has lapsed at `now` (unix secs).
+    pub fn is_expired_at(expires_at: u64, now: u64) -> bool {
+        expires_at != 0 && expires_at <= now
     }
 }
 
@@ -307,6 +417,18 @@ mod size_tests {
         assert_eq!(q.nip05(), "bob@example.com");
         assert_eq!(q.lud16(), "", "unset cold fields still read as empty");
     }
+
+    #[test]
+    fn status_emoji_round_trips_and_ignores_plain_words() {
+        assert_eq!(Status::split_emoji("🎧 deep work"), ("🎧".into(), "deep work".into()));
+        assert_eq!(Status::split_emoji("🏖️"), ("🏖️".into(), String::new()));
+        assert_eq!(Status::split_emoji("away until 5"), (String::new(), "away until 5".into()));
+        assert_eq!(Status::split_emoji("Über busy"), (String::new(), "Über busy".into()), "accented words aren't emoji");
+        assert_eq!(Status::join_emoji("🎧", "deep work"), "🎧 deep work");
+        assert_eq!(Status::join_emoji("", " lunch "), "lunch");
+        assert!(Status::is_expired_at(100, 100));
+        assert!(!Status::is_expired_at(0, u64::MAX), "0 never expires");
+    }
 }
 
 impl Default for Status {
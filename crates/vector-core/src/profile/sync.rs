@@ -8,16 +8,18 @@
 //! `ProfileSyncHandler` trait — src-tauri provides `TauriProfileSyncHandler`,
 //! CLI provides a no-op or logging implementation.
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::{Arc, Mutex, LazyLock};
 use std::time::{Duration, Instant};
 
 use nostr_sdk::prelude::*;
 
 use crate::compact::secs_to_compact;
-use crate::profile::Profile;
-use crate::state::{nostr_client, my_public_key, STATE};
+use crate::profile::{Profile, Status};
+use crate::context::lock_state;
+use crate::state::{nostr_client, my_public_key};
 use crate::traits::emit_event;
+use crate::clock::now_secs;
 
 // ============================================================================
 // SyncPriority
@@ -257,51 +259,66 @@ pub async fn load_profile(npub: String, handler: &dyn ProfileSyncHandler) -> boo
     };
 
     // Grab old status (or create profile if missing)
-    let (old_status_title, old_status_purpose, old_status_url): (String, String, String);
+    let old_status: Status;
     {
-        let mut state = STATE.lock().await;
+        let mut state = lock_state().await;
         match state.get_profile(&npub) {
             Some(p) => {
-                old_status_title = p.status_title().to_string();
-                old_status_purpose = p.status_purpose().to_string();
-                old_status_url = p.status_url().to_string();
+                old_status = Status {
+                    title: p.status_title().to_string(),
+                    purpose: p.status_purpose().to_string(),
+                    url: p.status_url().to_string(),
+                    emoji: p.status_emoji().to_string(),
+                    expires_at: p.status_expires_at(),
+                };
             }
             None => {
                 state.insert_or_replace_profile(&npub, Profile::new());
-                old_status_title = String::new();
-                old_status_purpose = String::new();
-                old_status_url = String::new();
+                old_status = Status::new();
             }
         }
     }
 
-    // Fetch status (kind 30315) from relays
-    let status_filter = Filter::new()
-        .author(profile_pubkey)
-        .kind(Kind::from_u16(30315))
-        .limit(1);
+    // Fetch status (kind 30315) and NIP-65 relay list (kind 10002) from relays. With "last active"
+    // on, the same round-trip also pulls their newest kind-0 so the approximation costs no extra
+    // fetch.
+    let track_last_active = last_active_enabled();
+    let status_kind = Kind::from_u16(30315);
+    let status_filter = if track_last_active {
+        Filter::new()
+            .author(profile_pubkey)
+            .kinds([status_kind, Kind::Metadata, Kind::RelayList])
+            .limit(6)
+    } else {
+        Filter::new()
+            .author(profile_pubkey)
+            .kinds([status_kind, Kind::RelayList])
+            .limit(4)
+    };
 
-    let (status_title, status_purpose, status_url) = match client
+    let (status, last_active, relay_list) = match client
         .fetch_events(status_filter, Duration::from_secs(15))
         .await
     {
         Ok(res) => {
-            if !res.is_empty() {
-                let status_event = res.first().unwrap();
-                (
-                    status_event.content.clone(),
-                    status_event.tags.first()
-                        .and_then(|t| t.content())
-                        .unwrap_or_default()
-                        .to_string(),
-                    String::new(),
-                )
-            } else {
-                (old_status_title, old_status_purpose, old_status_url)
-            }
+            let last_active = res.iter().map(|e| e.created_at.as_u64()).max().unwrap_or(0);
+            let newest_status = res.iter()
+                .filter(|e| e.kind == status_kind)
+                .max_by_key(|e| e.created_at);
+            let status = match newest_status {
+                Some(status_event) => parse_status_event(status_event, now_secs()),
+                None => old_status,
+            };
+            // Replaceable: only the newest revision is their current list.
+            let relay_list = res.iter()
+                .filter(|e| e.kind == Kind::RelayList)
+                .max_by_key(|e| e.created_at)
+                .map(|e| (crate::inbox_relays::extract_read_relays(e), e.created_at.as_u64()));
+            (status, last_active, relay_list)
         }
-        Err(_) => (old_status_title, old_status_purpose, old_status_url),
+        Err(_) => (old_status, 0, None),
     };
+    let status_expires_at = status.expires_at;
 
     // Fetch metadata from relays
     let fetch_result = client
@@ -311,11 +328,18 @@ pub async fn load_profile(npub: String, handler: &dyn ProfileSyncHandler) -> boo
     // Abandon the fetch result if a swap happened during the await.
     if !session.is_valid() { return false; }
 
+    // Their read relays let gift wraps reach them when they have no 10050 inbox list.
+    if let Some((read_relays, created_at)) = relay_list {
+        if let Err(e) = crate::db::profile_relays::set_read_relays(&npub, &read_relays, created_at) {
+            crate::log_warn!("[Profile] Failed to store relay list for {}: {}", npub, e);
+        }
+    }
+
     match fetch_result {
         Ok(meta) => {
             if meta.is_some() {
                 let save_data = {
-                    let mut state = STATE.lock().await;
+                    let mut state = lock_state().await;
                     let id = match state.interner.lookup(&npub) {
                         Some(id) => id,
                         None => return false,
@@ -328,21 +352,35 @@ pub async fn load_profile(npub: String, handler: &dyn ProfileSyncHandler) -> boo
                         profile.flags.set_mine(my_public_key == profile_pubkey);
 
                         // Update status
-                        let status_changed = profile.status_title() != status_title.as_str()
-                            || profile.status_purpose() != status_purpose.as_str()
-                            || profile.status_url() != status_url.as_str();
+                        let status_changed = profile.status_title() != status.title.as_str()
+                            || profile.status_purpose() != status.purpose.as_str()
+                            || profile.status_url() != status.url.as_str()
+                            || profile.status_emoji() != status.emoji.as_str()
+                            || profile.status_expires_at() != status.expires_at;
                         // Only touch the extras box when there's a real status to store or one
                         // already exists to clear — never materialize an empty box on the common
                         // status-less profile (that would make it larger than before the split).
-                        let has_status = !status_title.is_empty()
-                            || !status_purpose.is_empty() || !status_url.is_empty();
+                        let has_status = !status.title.is_empty() || !status.purpose.is_empty()
+                            || !status.url.is_empty() || !status.emoji.is_empty();
                         if profile.extras.is_some() || has_status {
                             let ex = profile.extras_mut();
-                            ex.status_title = status_title.into_boxed_str();
-                            ex.status_purpose = status_purpose.into_boxed_str();
-                            ex.status_url = status_url.into_boxed_str();
+                            ex.status_title = status.title.into_boxed_str();
+                            ex.status_purpose = status.purpose.into_boxed_str();
+                            ex.status_url = status.url.into_boxed_str();
+                            ex.status_emoji = status.emoji.into_boxed_str();
+                            ex.status_expires_at = status.expires_at;
                         }
 
+                        // Last active only moves forward (a lagging relay can't rewind it); turning
+                        // the feature off wipes it on the next sync.
+                        let new_last_active = if track_last_active {
+                            profile.last_active.max(secs_to_compact(last_active))
+                        } else {
+                            0
+                        };
+                        let last_active_changed = profile.last_active != new_last_active;
+                        profile.last_active = new_last_active;
+
                         // Update metadata
                         let metadata_changed = profile.from_metadata(meta.unwrap());
 
@@ -354,7 +392,7 @@ pub async fn load_profile(npub: String, handler: &dyn ProfileSyncHandler) -> boo
                                 .as_secs()
                         );
 
-                        (status_changed || metadata_changed,
+                        (status_changed || metadata_changed || last_active_changed,
                          profile.avatar.to_string(),
                          profile.banner.to_string())
                     };
@@ -367,16 +405,18 @@ pub async fn load_profile(npub: String, handler: &dyn ProfileSyncHandler) -> boo
                     }
                 };
 
+                schedule_status_expiry(npub.clone(), status_expires_at);
                 if let Some((slim, avatar_url, banner_url)) = save_data {
                     // Notify UI via EventEmitter
                     emit_event("profile_update", &slim);
                     // Platform-specific: DB persist + image caching
                     handler.on_profile_fetched(&slim, &avatar_url, &banner_url);
+                    crate::contact_verification::check_lookalike(&slim).await;
                 }
                 true
             } else {
                 // No metadata on relays — update timestamp so we don't keep retrying
-                let mut state = STATE.lock().await;
+                let mut state = lock_state().await;
                 if let Some(profile) = state.get_profile_mut(&npub) {
                     profile.last_updated = secs_to_compact(
                         std::time::SystemTime::now()
@@ -433,7 +473,7 @@ async fn update_profile_inner(
 
     // Build metadata from current profile, then drop the lock before network I/O
     let meta = {
-        let state = STATE.lock().await;
+        let state = lock_state().await;
         let npub = match my_public_key.to_bech32() {
             Ok(n) => n,
             Err(_) => return false,
@@ -524,7 +564,7 @@ async fn update_profile_inner(
                 Err(_) => return false,
             };
             let save_data = {
-                let mut state = STATE.lock().await;
+                let mut state = lock_state().await;
                 // Apply the published metadata to our own profile, creating the entry if this
                 // identity had none yet (a freshly-created account is interned here on first set).
                 let mut profile = state.get_profile(&npub).cloned().unwrap_or_default();
@@ -554,11 +594,206 @@ async fn update_profile_inner(
 // update_status — publish status to relays
 // ============================================================================
 
+/// Parse a kind-30315 live status into a [`Status`]. The leading emoji is split from the text, and
+/// a NIP-40 `expiration` that has already passed parses as no status at all.
+pub fn parse_status_event(event: &Event, now: u64) -> Status {
+    let expires_at = event.tags.iter()
+        .find(|t| t.kind() == TagKind::Expiration)
+        .and_then(|t| t.content())
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    if Status::is_expired_at(expires_at, now) {
+        return Status::new();
+    }
+    let (emoji, title) = Status::split_emoji(&event.content);
+    Status {
+        title,
+        purpose: event.tags.first()
+            .and_then(|t| t.content())
+            .unwrap_or_default()
+            .to_string(),
+        url: String::new(),
+        emoji,
+        expires_at,
+    }
+}
+
+/// Settings key for the "last active" approximation; absent = enabled.
+pub const LAST_ACTIVE_SETTING: &str = "show_last_active";
+
+/// Whether to derive contacts' approximate last-active time. Privacy-conscious users can turn it off,
+/// which stops the extra kinds being fetched and clears cached values as profiles re-sync.
+pub fn last_active_enabled() -> bool {
+    crate::db::get_sql_setting(LAST_ACTIVE_SETTING.to_string())
+        .ok()
+        .flatten()
+        .map_or(true, |v| v != "false")
+}
+
+/// Persist the "last active" preference. Disabling wipes every cached value right away (RAM + DB)
+/// rather than waiting for each contact to re-sync.
+pub async fn set_last_active_enabled(enabled: bool) -> Result<(), String> {
+    let session = crate::state::SessionGuard::capture();
+    crate::db::set_sql_setting(LAST_ACTIVE_SETTING.to_string(), enabled.to_string())?;
+    if enabled {
+        return Ok(());
+    }
+    {
+        let mut state = lock_state().await;
+        // An account swap while waiting on the lock: the profiles (and the DB below) are the next account's
+        if !session.is_valid() {
+            return Ok(());
+        }
+        for profile in state.profiles.iter_mut() {
+            profile.last_active = 0;
+        }
+    }
+    let conn = crate::db::get_write_connection_guard_static()?;
+    conn.execute("UPDATE profiles SET last_active = 0 WHERE last_active != 0", [])
+        .map_err(|e| format!("clear last_active: {}", e))?;
+    Ok(())
+}
+
+/// Statuses waiting to clear, soonest first, with the session that scheduled each. At most one per
+/// profile; a single task serves them all and runs only while any are pending.
+#[derive(Default)]
+struct StatusExpiries {
+    pending: BTreeMap<(u64, String), crate::state::SessionGuard>,
+    running: bool,
+}
+
+static STATUS_EXPIRIES: LazyLock<Mutex<StatusExpiries>> = LazyLock::new(Mutex::default);
+
+/// Wakes the expiry task when a sooner expiry is scheduled.
+static STATUS_EXPIRY_WAKE: LazyLock<tokio::sync::Notify> = LazyLock::new(tokio::sync::Notify::new);
+
+/// Clear `npub`'s status in STATE once `expires_at` passes (no-op for 0). The clear only applies if
+/// the profile still carries THAT expiry — a newer status set in the meantime is left alone — and
+/// the session is re-checked so a swap can't clear the next account's profile.
+pub fn schedule_status_expiry(npub: String, expires_at: u64) {
+    if expires_at == 0 {
+        return;
+    }
+    let session = crate::state::SessionGuard::capture();
+    let start = {
+        let mut expiries = STATUS_EXPIRIES.lock().unwrap();
+        expiries.pending.retain(|(_, pending), _| *pending != npub);
+        expiries.pending.insert((expires_at, npub), session);
+        !std::mem::replace(&mut expiries.running, true)
+    };
+    if start {
+        tokio::spawn(run_status_expiries());
+    } else {
+        STATUS_EXPIRY_WAKE.notify_one();
+    }
+}
+
+/// Sleep until the soonest pending expiry, clear every status that's due, repeat; exit once
+/// nothing is pending.
+async fn run_status_expiries() {
+    loop {
+        let wake = STATUS_EXPIRY_WAKE.notified();
+        let next = {
+            let mut expiries = STATUS_EXPIRIES.lock().unwrap();
+            match expiries.pending.keys().next() {
+                Some((at, _)) => *at,
+                None => {
+                    expiries.running = false;
+                    return;
+                }
+            }
+        };
+        let now = now_secs();
+        if next > now {
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(next - now)) => {}
+                _ = wake => {}
+            }
+            continue;
+        }
+        let due = {
+            let mut expiries = STATUS_EXPIRIES.lock().unwrap();
+            let later = expiries.pending.split_off(&(now + 1, String::new()));
+            std::mem::replace(&mut expiries.pending, later)
+        };
+        for ((expires_at, npub), session) in due {
+            clear_expired_status(&npub, expires_at, session).await;
+        }
+    }
+}
+
+async fn clear_expired_status(npub: &str, expires_at: u64, session: crate::state::SessionGuard) {
+    if !session.is_valid() {
+        return;
+    }
+    let mut state = lock_state().await;
+    let Some(id) = state.interner.lookup(npub) else { return };
+    {
+        let Some(profile) = state.get_profile_mut_by_id(id) else { return };
+        if profile.status_expires_at() != expires_at {
+            return;
+        }
+        let ex = profile.extras_mut();
+        ex.status_title = Box::default();
+        ex.status_purpose = Box::default();
+        ex.status_url = Box::default();
+        ex.status_emoji = Box::default();
+        ex.status_expires_at = 0;
+    }
+    if let Some(slim) = state.serialize_profile(id) {
+        emit_event("profile_update", &slim);
+    }
+}
+
+/// Record a presence ping from `npub`, live until `until` (see [`crate::presence`]). Only profiles
+/// already known are touched; a ping never creates one. The profile is re-emitted when the ping
+/// arrives and again when it lapses, unless a newer ping replaced it in the meantime.
+pub async fn apply_presence(npub: &str, until: u64) {
+    {
+        let mut state = lock_state().await;
+        let Some(id) = state.interner.lookup(npub) else { return };
+        {
+            let Some(profile) = state.get_profile_mut_by_id(id) else { return };
+            if profile.online_until() >= until {
+                return;
+            }
+            let ex = profile.extras_mut();
+            ex.last_seen = now_secs();
+            ex.online_until = until;
+        }
+        if let Some(slim) = state.serialize_profile(id) {
+            emit_event("profile_update", &slim);
+        }
+    }
+
+    let npub = npub.to_string();
+    let session = crate::state::SessionGuard::capture();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(until.saturating_sub(now_secs()))).await;
+        if !session.is_valid() {
+            return;
+        }
+        let state = lock_state().await;
+        let Some(id) = state.interner.lookup(&npub) else { return };
+        if state.get_profile_by_id(id).is_none_or(|p| p.online_until() != until) {
+            return;
+        }
+        if let Some(slim) = state.serialize_profile(id) {
+            emit_event("profile_update", &slim);
+        }
+    });
+}
+
 /// Update the current user's status (kind 30315) and broadcast to relays.
 ///
-/// Status is ephemeral — updated in STATE + frontend but not persisted to DB.
-/// (Re-fetched from relays on next `load_profile` call.)
-pub async fn update_status(status: String) -> bool {
+/// `emoji` is published as the content's leading token (NIP-38 has no emoji field, so other
+/// clients still show it inline). `expires_at` (unix secs) adds a NIP-40 `expiration` tag: relays
+/// drop the event after it, and the local expiry scheduler clears the status at the same moment.
+///
+/// The status is saved with our profile, so an expiry still applies after a restart (boot
+/// re-schedules saved expiries); `load_profile` later replaces it with the relays' copy.
+pub async fn update_status(status: String, emoji: Option<String>, expires_at: Option<u64>) -> bool {
+    let session = crate::state::SessionGuard::capture();
     let client = match nostr_client() {
         Some(c) => c,
         None => return false,
@@ -569,9 +804,21 @@ pub async fn update_status(status: String) -> bool {
         None => return false,
     };
 
+    // No explicit emoji: honour one typed at the front of the text, so the local copy matches
+    // what `parse_status_event` will read back from relays.
+    let (emoji, status) = match emoji {
+        Some(e) => (e.trim().to_string(), status),
+        None => Status::split_emoji(&status),
+    };
+    let expires_at = expires_at.filter(|&t| t > now_secs()).unwrap_or(0);
+    let content = Status::join_emoji(&emoji, &status);
+
     // Build and sign kind 30315 status event
-    let status_builder = EventBuilder::new(Kind::from_u16(30315), status.as_str())
+    let mut status_builder = EventBuilder::new(Kind::from_u16(30315), content.as_str())
         .tag(Tag::custom(TagKind::d(), vec!["general"]));
+    if expires_at != 0 {
+        status_builder = status_builder.tag(Tag::expiration(Timestamp::from_secs(expires_at)));
+    }
 
     let Ok(event) = client.sign_event_builder(status_builder).await else {
         return false;
@@ -579,7 +826,11 @@ pub async fn update_status(status: String) -> bool {
 
     match crate::inbox_relays::send_event_pool_first_ok(&client, &event).await {
         Ok(_) => {
-            let mut state = STATE.lock().await;
+            let mut state = lock_state().await;
+            // The profile (and the DB it's saved to) would be the next account's
+            if !session.is_valid() {
+                return false;
+            }
             let npub = match my_public_key.to_bech32() {
                 Ok(n) => n,
                 Err(_) => return false,
@@ -595,11 +846,18 @@ pub async fn update_status(status: String) -> bool {
                 };
                 let ex = profile.extras_mut();
                 ex.status_purpose = "general".into();
-                ex.status_title = status.into_boxed_str();
+                ex.status_title = status.trim().into();
+                ex.status_emoji = emoji.into_boxed_str();
+                ex.status_expires_at = expires_at;
             }
 
             let slim = state.serialize_profile(id).unwrap();
             emit_event("profile_update", &slim);
+            drop(state);
+            if let Err(e) = crate::db::profiles::set_profile(&slim) {
+                crate::log_warn!("[Status] Failed to save status: {}", e);
+            }
+            schedule_status_expiry(npub, expires_at);
             true
         }
         Err(_) => false,
@@ -610,8 +868,9 @@ pub async fn update_status(status: String) -> bool {
 // block / unblock / nickname / blocked list
 // ============================================================================
 
-/// Block a user by npub. DM events from blocked users are dropped after decryption.
-/// Group messages are stored but filtered in the UI.
+/// Block a user by npub. DM events from blocked users are dropped as soon as the gift wrap is
+/// opened, before the rumor is parsed. Group messages are stored but filtered in the UI. Synced to
+/// the user's other Vector installs (`settings_sync`) and to other clients (`mute_list`).
 ///
 /// Returns `false` if trying to block yourself or if the profile can't be found.
 pub async fn block_user(npub: String, handler: &dyn ProfileSyncHandler) -> bool {
@@ -622,7 +881,7 @@ pub async fn block_user(npub: String, handler: &dyn ProfileSyncHandler) -> bool
         }
     }
 
-    let mut state = STATE.lock().await;
+    let mut state = lock_state().await;
 
     // Create profile if it doesn't exist (can block someone with no prior contact)
     if state.interner.lookup(&npub).is_none() {
@@ -641,6 +900,10 @@ pub async fn block_user(npub: String, handler: &dyn ProfileSyncHandler) -> bool
         drop(state);
         emit_event("profile_update", &slim);
         handler.on_profile_fetched(&slim, "", "");
+        crate::settings_sync::note_local_change(&crate::settings_sync::blocked_key(&npub));
+        if tokio::runtime::Handle::try_current().is_ok() {
+            crate::mute_list::republish_debounced();
+        }
         true
     } else {
         false
@@ -649,7 +912,7 @@ pub async fn block_user(npub: String, handler: &dyn ProfileSyncHandler) -> bool
 
 /// Unblock a user by npub.
 pub async fn unblock_user(npub: String, handler: &dyn ProfileSyncHandler) -> bool {
-    let mut state = STATE.lock().await;
+    let mut state = lock_state().await;
 
     if let Some(id) = state.interner.lookup(&npub) {
         {
@@ -663,6 +926,10 @@ pub async fn unblock_user(npub: String, handler: &dyn ProfileSyncHandler) -> boo
         drop(state);
         emit_event("profile_update", &slim);
         handler.on_profile_fetched(&slim, "", "");
+        crate::settings_sync::note_local_change(&crate::settings_sync::blocked_key(&npub));
+        if tokio::runtime::Handle::try_current().is_ok() {
+            crate::mute_list::republish_debounced();
+        }
         true
     } else {
         false
@@ -671,16 +938,16 @@ pub async fn unblock_user(npub: String, handler: &dyn ProfileSyncHandler) -> boo
 
 /// Get all blocked profiles.
 pub async fn get_blocked_users() -> Vec<crate::SlimProfile> {
-    let state = STATE.lock().await;
+    let state = lock_state().await;
     state.profiles.iter()
         .filter(|p| p.flags.is_blocked())
         .filter_map(|p| state.serialize_profile(p.id))
         .collect()
 }
 
-/// Set a nickname for a profile.
+/// Set a nickname for a profile. Synced to the user's other Vector installs (`chat_state_sync`).
 pub async fn set_nickname(npub: String, nickname: String, handler: &dyn ProfileSyncHandler) -> bool {
-    let mut state = STATE.lock().await;
+    let mut state = lock_state().await;
 
     if let Some(id) = state.interner.lookup(&npub) {
         {
@@ -697,6 +964,7 @@ pub async fn set_nickname(npub: String, nickname: String, handler: &dyn ProfileS
             "value": &slim.nickname
         }));
         handler.on_profile_fetched(&slim, "", "");
+        crate::chat_state_sync::note_local_change(&crate::chat_state_sync::nickname_key(&npub));
         true
     } else {
         false
@@ -718,7 +986,7 @@ pub async fn start_profile_sync_processor(handler: Arc<dyn ProfileSyncHandler>)
     loop {
         // Periodically queue our own profile to detect changes from other Nostr apps
         if last_own_profile_sync.elapsed() >= own_profile_sync_interval {
-            let state = STATE.lock().await;
+            let state = lock_state().await;
             if let Some(own_profile) = state.profiles.iter().find(|p| p.flags.is_mine()) {
                 let npub = state.interner.resolve(own_profile.id).unwrap_or("").to_string();
                 drop(state);
@@ -800,7 +1068,7 @@ pub fn queue_profile_sync(npub: String, priority: SyncPriority, force_refresh: b
 
 /// Queue all profiles for a chat.
 pub async fn queue_chat_profiles(chat_id: String, is_opening: bool) {
-    let state = STATE.lock().await;
+    let state = lock_state().await;
 
     let chat = match state.get_chat(&chat_id) {
         Some(c) => c,
@@ -846,6 +1114,28 @@ pub async fn queue_chat_profiles(chat_id: String, is_opening: bool) {
     }
 }
 
+/// Queue profiles we know nothing about (never fetched, no name or avatar) at medium priority, for
+/// lists that otherwise show bare npubs until each profile is opened. Each resolved kind-0 is
+/// emitted as `profile_update`, so the list fills in as they land.
+pub async fn queue_unknown_profiles(npubs: &[String]) {
+    let unknown: Vec<String> = {
+        let state = lock_state().await;
+        npubs.iter()
+            .filter(|npub| state.get_profile(npub).is_none_or(|p| {
+                p.name.is_empty() && p.display_name.is_empty() && p.avatar.is_empty() && p.last_updated == 0
+            }))
+            .cloned()
+            .collect()
+    };
+    if unknown.is_empty() {
+        return;
+    }
+    let mut queue = PROFILE_SYNC_QUEUE.lock().unwrap();
+    for npub in unknown {
+        queue.add(npub, SyncPriority::Medium, false);
+    }
+}
+
 /// Force immediate refresh of a profile (for user clicks).
 pub fn refresh_profile_now(npub: String) {
     let mut queue = PROFILE_SYNC_QUEUE.lock().unwrap();
@@ -854,7 +1144,7 @@ pub fn refresh_profile_now(npub: String) {
 
 /// Sync all profiles in the system.
 pub async fn sync_all_profiles() {
-    let state = STATE.lock().await;
+    let state = lock_state().await;
 
     let mut profiles_to_queue = Vec::new();
 
@@ -0,0 +1,192 @@
+//! User-configured SOCKS5 proxy, and the transport every outgoing TCP connection should use.
+//!
+//! The user can point Vector at their own SOCKS5 proxy (a system Tor daemon on `127.0.0.1:9050`,
+//! an SSH tunnel, a proxychains endpoint). It covers everything [`transport`] covers: the relay
+//! pool (via `nostr_client_options` / `tor_aware_relay_options`) and every HTTP client built by
+//! [`crate::net`], which includes Blossom uploads and downloads.
+//!
+//! The embedded Tor service wins when it's enabled: its failsafe guarantee stays absolute. With
+//! Tor off and a proxy set, traffic goes through the proxy while it's reachable. When it isn't,
+//! the kill switch decides: on, connections are blackholed until the proxy comes back; off, they
+//! fall back to direct. Reachability is tracked by [`probe`], which the app calls periodically.
+//!
+//! Settings live in the per-account DB (`proxy_address`, `proxy_kill_switch`) and are cached here
+//! by [`init_from_conn`] so the hot path never touches SQLite.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Setting key: the proxy's `host:port` (absent/empty = no proxy).
+pub const PROXY_ADDRESS_SETTING: &str = "proxy_address";
+/// Setting key: `"1"` blocks all traffic while the proxy is unreachable.
+pub const PROXY_KILL_SWITCH_SETTING: &str = "proxy_kill_switch";
+
+/// How long a reachability probe waits for the proxy to accept a connection.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The user's proxy settings.
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProxyConfig {
+    pub address: SocketAddr,
+    pub kill_switch: bool,
+}
+
+/// What transport a new TCP connection should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// Route through the SOCKS5 proxy at this address (embedded Tor or the user's proxy).
+    Proxy(SocketAddr),
+    /// A proxy is required but not available. Callers MUST route to [`blackhole_addr`] so
+    /// nothing goes out direct.
+    Blocked,
+    /// Connect directly.
+    Direct,
+}
+
+static CONFIG: Mutex<Option<ProxyConfig>> = Mutex::new(None);
+
+/// Whether the last probe reached the proxy. Starts true so a freshly loaded proxy is used
+/// (and fails closed) until a probe says otherwise.
+static REACHABLE: AtomicBool = AtomicBool::new(true);
+
+/// Parse a user-entered proxy address. Accepts `host:port` with an optional `socks5://` or
+/// `socks5h://` prefix; the host must be an IP or `localhost`, since the relay pool takes a
+/// socket address rather than a hostname.
+pub fn parse_proxy_address(input: &str) -> Result<SocketAddr, String> {
+    let trimmed = input.trim().trim_end_matches('/');
+    let addr = ["socks5h://", "socks5://"]
+        .iter()
+        .find_map(|p| trimmed.strip_prefix(p))
+        .unwrap_or(trimmed);
+    if addr.contains("://") {
+        return Err("Only SOCKS5 proxies are supported".to_string());
+    }
+    if let Ok(sock) = addr.parse::<SocketAddr>() {
+        return Ok(sock);
+    }
+    let (host, port) = addr.rsplit_once(':').ok_or("Proxy address needs a port, e.g. 127.0.0.1:9050")?;
+    let port: u16 = port.parse().map_err(|_| format!("Invalid proxy port: {}", port))?;
+    if port == 0 {
+        return Err("Invalid proxy port: 0".to_string());
+    }
+    if host.eq_ignore_ascii_case("localhost") {
+        return Ok(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port));
+    }
+    Err(format!("Proxy host must be an IP address or localhost, not {}", host))
+}
+
+/// The configured proxy, if any.
+pub fn config() -> Option<ProxyConfig> {
+    *CONFIG.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Replace the cached settings (after writing them to the DB, or on account load).
+pub fn set_config(config: Option<ProxyConfig>) {
+    *CONFIG.lock().unwrap_or_else(|e| e.into_inner()) = config;
+    REACHABLE.store(true, Ordering::Release);
+}
+
+/// Hydrate the cache from an account's DB (called by `init_database`, before the account is
+/// current). A saved address that no longer parses is ignored with a warning.
+pub fn init_from_conn(conn: &rusqlite::Connection) {
+    let read = |key: &str| {
+        conn.query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| row.get::<_, String>(0))
+            .ok()
+    };
+    let config = read(PROXY_ADDRESS_SETTING)
+        .filter(|a| !a.trim().is_empty())
+        .and_then(|a| match parse_proxy_address(&a) {
+            Ok(address) => Some(address),
+            Err(e) => {
+                crate::log_warn!("[Proxy] Ignoring saved proxy address: {}", e);
+                None
+            }
+        })
+        .map(|address| ProxyConfig {
+            address,
+            kill_switch: matches!(read(PROXY_KILL_SWITCH_SETTING).as_deref(), Some("1") | Some("true")),
+        });
+    set_config(config);
+}
+
+/// Whether the last probe reached the proxy.
+pub fn is_reachable() -> bool {
+    REACHABLE.load(Ordering::Acquire)
+}
+
+/// Check that the configured proxy accepts connections. Returns true when reachability changed,
+/// so the caller knows to cycle existing connections onto the new transport.
+pub async fn probe() -> bool {
+    let Some(cfg) = config() else { return false };
+    let ok = matches!(
+        tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect(cfg.address)).await,
+        Ok(Ok(_))
+    );
+    // A config change mid-probe resets reachability; don't overwrite it with a stale answer
+    if config() != Some(cfg) {
+        return false;
+    }
+    let was = REACHABLE.swap(ok, Ordering::AcqRel);
+    if was != ok {
+        crate::log_info!("[Proxy] {} is {}", cfg.address, if ok { "reachable again" } else { "unreachable" });
+    }
+    was != ok
+}
+
+/// The user-proxy half of [`transport`], split out so it's testable without the Tor service.
+fn user_transport(config: Option<ProxyConfig>, reachable: bool) -> Transport {
+    match config {
+        Some(cfg) if reachable => Transport::Proxy(cfg.address),
+        Some(cfg) if cfg.kill_switch => Transport::Blocked,
+        _ => Transport::Direct,
+    }
+}
+
+/// The transport every TCP-bearing client (HTTP, nostr) should honor right now.
+pub fn transport() -> Transport {
+    #[cfg(feature = "tor")]
+    match crate::tor::transport_state() {
+        crate::tor::TorTransportState::Active(addr) => return Transport::Proxy(addr),
+        crate::tor::TorTransportState::RequiredButInactive => return Transport::Blocked,
+        crate::tor::TorTransportState::Disabled => {}
+    }
+    user_transport(config(), is_reachable())
+}
+
+/// Sentinel address for [`Transport::Blocked`]: port 1 on loopback has no listener on any sane
+/// OS, so connections through it fail at the TCP layer instantly.
+pub fn blackhole_addr() -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proxy_addresses() {
+        let tor = SocketAddr::from(([127, 0, 0, 1], 9050));
+        assert_eq!(parse_proxy_address("127.0.0.1:9050"), Ok(tor));
+        assert_eq!(parse_proxy_address(" socks5h://127.0.0.1:9050/ "), Ok(tor));
+        assert_eq!(parse_proxy_address("socks5://localhost:9050"), Ok(tor));
+        assert_eq!(parse_proxy_address("[::1]:1080"), Ok("[::1]:1080".parse().unwrap()));
+        assert!(parse_proxy_address("http://127.0.0.1:8080").is_err());
+        assert!(parse_proxy_address("127.0.0.1").is_err());
+        assert!(parse_proxy_address("localhost:0").is_err());
+        assert!(parse_proxy_address("proxy.example.com:1080").is_err());
+    }
+
+    #[test]
+    fn kill_switch_blocks_only_when_unreachable() {
+        let address = SocketAddr::from(([127, 0, 0, 1], 9050));
+        let open = Some(ProxyConfig { address, kill_switch: false });
+        let strict = Some(ProxyConfig { address, kill_switch: true });
+        assert_eq!(user_transport(None, false), Transport::Direct);
+        assert_eq!(user_transport(open, true), Transport::Proxy(address));
+        assert_eq!(user_transport(open, false), Transport::Direct);
+        assert_eq!(user_transport(strict, true), Transport::Proxy(address));
+        assert_eq!(user_transport(strict, false), Transport::Blocked);
+    }
+}
@@ -0,0 +1,287 @@
+//! Passive relay spam scoring and auto-demotion.
+//!
+//! Every live event a relay hands us is scored on what it turned out to be: accepted, a duplicate
+//! of something we already had, a gift wrap that wouldn't unwrap, or a wrap that unwrapped to
+//! something malformed. A relay that keeps sending garbage (or nothing but stale copies) is
+//! demoted: its WRITE flag is dropped so pool-wide publishes skip it, but we keep reading from it.
+//! The counters are halved every [`WINDOW`] events, so a relay that cleans up recovers on its own.
+//!
+//! An event only counts against the relay that delivered it first; copies of the same event from
+//! other relays aren't scored. Junk wraps are cheap to fan out to every relay we read, so the last
+//! relay still taking writes is never demoted automatically.
+//!
+//! The user can override the decision per relay (always trust, always demote); the override is
+//! kept in the account settings KV. [`stats`] reports all of it.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+
+use nostr_sdk::prelude::*;
+
+use crate::inbox_relays::normalize_relay_url;
+
+const OVERRIDE_PREFIX: &str = "relay_demotion:";
+
+/// Events seen before a relay can be judged.
+const MIN_EVENTS: u64 = 40;
+
+/// Counters are halved once they cover this many events.
+const WINDOW: u64 = 500;
+
+/// Share of undecryptable or malformed events that demotes a relay.
+const GARBAGE_RATIO: f64 = 0.5;
+
+/// Event ids remembered to score each event once.
+const SCORED_CAP: usize = 4096;
+
+/// Share of duplicates that demotes a relay. Replays after a reconnect are normal, so only a relay
+/// that sends almost nothing new is demoted for it.
+const STALE_RATIO: f64 = 0.95;
+
+/// What a relay's event turned out to be.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Accepted,
+    Duplicate,
+    UnwrapFailed,
+    Malformed,
+}
+
+/// Manual override of the automatic decision.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DemotionOverride {
+    /// Never demote.
+    Trust,
+    /// Always demote.
+    Demote,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Counters {
+    accepted: u64,
+    duplicates: u64,
+    unwrap_failures: u64,
+    malformed: u64,
+}
+
+impl Counters {
+    fn total(&self) -> u64 {
+        self.accepted + self.duplicates + self.unwrap_failures + self.malformed
+    }
+
+    fn add(&mut self, verdict: Verdict) {
+        match verdict {
+            Verdict::Accepted => self.accepted += 1,
+            Verdict::Duplicate => self.duplicates += 1,
+            Verdict::UnwrapFailed => self.unwrap_failures += 1,
+            Verdict::Malformed => self.malformed += 1,
+        }
+        if self.total() >= WINDOW {
+            self.accepted /= 2;
+            self.duplicates /= 2;
+            self.unwrap_failures /= 2;
+            self.malformed /= 2;
+        }
+    }
+
+    /// Why these counters demote a relay, if they do.
+    fn demotion_reason(&self) -> Option<&'static str> {
+        let total = self.total();
+        if total < MIN_EVENTS {
+            return None;
+        }
+        if (self.unwrap_failures + self.malformed) as f64 >= total as f64 * GARBAGE_RATIO {
+            Some("garbage")
+        } else if self.duplicates as f64 >= total as f64 * STALE_RATIO {
+            Some("stale")
+        } else {
+            None
+        }
+    }
+}
+
+/// One relay's scores and standing.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RelayStats {
+    pub url: String,
+    pub accepted: u64,
+    pub duplicates: u64,
+    pub unwrap_failures: u64,
+    pub malformed: u64,
+    /// Whether publishes currently skip this relay.
+    pub demoted: bool,
+    /// "garbage" or "stale" when the scores call for demotion, whatever the override says.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+    #[serde(rename = "override", skip_serializing_if = "Option::is_none")]
+    pub override_: Option<DemotionOverride>,
+}
+
+#[derive(Default)]
+struct Registry {
+    counters: HashMap<String, Counters>,
+    /// Relays whose WRITE flag we removed, so restoring never grants WRITE to a read-only relay.
+    stripped: HashSet<String>,
+    /// Recently scored events, so a copy from a second relay isn't scored again.
+    scored: HashSet<EventId>,
+}
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(|| Mutex::new(Registry::default()));
+
+fn override_for(url: &str) -> Option<DemotionOverride> {
+    match crate::db::settings::get_sql_setting(format!("{OVERRIDE_PREFIX}{url}")).ok().flatten()?.as_str() {
+        "trust" => Some(DemotionOverride::Trust),
+        "demote" => Some(DemotionOverride::Demote),
+        _ => None,
+    }
+}
+
+fn decide(counters: &Counters, override_: Option<DemotionOverride>) -> bool {
+    match override_ {
+        Some(DemotionOverride::Trust) => false,
+        Some(DemotionOverride::Demote) => true,
+        None => counters.demotion_reason().is_some(),
+    }
+}
+
+/// Whether publishes should skip `relay_url`.
+pub fn is_demoted(relay_url: &str) -> bool {
+    let url = normalize_relay_url(relay_url);
+    let counters = REGISTRY.lock().unwrap().counters.get(&url).copied().unwrap_or_default();
+    decide(&counters, override_for(&url))
+}
+
+/// Score one event from `relay_url`, unless another relay delivered it first. A change in the
+/// relay's standing is applied to the pool in the background.
+pub fn record(relay_url: &str, event_id: &EventId, verdict: Verdict) {
+    let url = normalize_relay_url(relay_url);
+    let (before, after) = {
+        let mut registry = REGISTRY.lock().unwrap();
+        if registry.scored.len() >= SCORED_CAP {
+            registry.scored.clear();
+        }
+        if !registry.scored.insert(*event_id) {
+            return;
+        }
+        let counters = registry.counters.entry(url.clone()).or_default();
+        let before = counters.demotion_reason().is_some();
+        counters.add(verdict);
+        (before, counters.demotion_reason().is_some())
+    };
+    if before != after && override_for(&url).is_none() {
+        if after {
+            crate::log_warn!("[RelayHealth] Demoting {} (stops publishes, keeps reads)", url);
+        } else {
+            crate::log_info!("[RelayHealth] Restoring {}", url);
+        }
+        tokio::spawn(async move { apply(&url).await });
+    }
+}
+
+/// Set (or clear, with `None`) the manual override for a relay, and apply the result.
+pub async fn set_override(relay_url: &str, override_: Option<DemotionOverride>) -> Result<(), String> {
+    let url = normalize_relay_url(relay_url);
+    let key = format!("{OVERRIDE_PREFIX}{url}");
+    match override_ {
+        Some(DemotionOverride::Trust) => crate::db::settings::set_sql_setting(key, "trust".to_string())?,
+        Some(DemotionOverride::Demote) => crate::db::settings::set_sql_setting(key, "demote".to_string())?,
+        None => crate::db::settings::remove_setting(&key)?,
+    }
+    apply(&url).await;
+    Ok(())
+}
+
+/// Bring the pool relay's WRITE flag in line with its standing.
+async fn apply(url: &str) {
+    let Some(client) = crate::state::nostr_client() else { return };
+    let relays = client.pool().all_relays().await;
+    let Some(relay) = relays.iter().find(|(u, _)| normalize_relay_url(u.as_str()) == url).map(|(_, r)| r) else {
+        return;
+    };
+    if is_demoted(url) {
+        // A manual demotion is the user's call; an automatic one never silences the last writer
+        let last_writer = relays.iter()
+            .filter(|(u, r)| normalize_relay_url(u.as_str()) != url && r.flags().has_write())
+            .count() == 0;
+        if relay.flags().has_write() && (!last_writer || override_for(url) == Some(DemotionOverride::Demote)) {
+            relay.flags().remove(RelayServiceFlags::WRITE);
+            REGISTRY.lock().unwrap().stripped.insert(url.to_string());
+        }
+    } else if REGISTRY.lock().unwrap().stripped.remove(url) {
+        relay.flags().add(RelayServiceFlags::WRITE);
+    }
+}
+
+/// Scores and standing of every relay in the pool, plus any we've scored that has since left it.
+pub async fn stats() -> Vec<RelayStats> {
+    let mut urls: Vec<String> = match crate::state::nostr_client() {
+        Some(client) => client.pool().relays().await.keys().map(|u| normalize_relay_url(u.as_str())).collect(),
+        None => Vec::new(),
+    };
+    let (counters, stripped) = {
+        let registry = REGISTRY.lock().unwrap();
+        (registry.counters.clone(), registry.stripped.clone())
+    };
+    urls.extend(counters.keys().cloned());
+    urls.sort();
+    urls.dedup();
+    urls.into_iter()
+        .map(|url| {
+            let c = counters.get(&url).copied().unwrap_or_default();
+            let override_ = override_for(&url);
+            RelayStats {
+                demoted: stripped.contains(&url),
+                reason: c.demotion_reason(),
+                override_,
+                accepted: c.accepted,
+                duplicates: c.duplicates,
+                unwrap_failures: c.unwrap_failures,
+                malformed: c.malformed,
+                url,
+            }
+        })
+        .collect()
+}
+
+/// Forget the scores (account swap).
+pub fn clear_session() {
+    let mut registry = REGISTRY.lock().unwrap();
+    registry.counters.clear();
+    registry.stripped.clear();
+    registry.scored.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(counters: &mut Counters, verdict: Verdict, n: u64) {
+        (0..n).for_each(|_| counters.add(verdict));
+    }
+
+    #[test]
+    fn garbage_and_stale_relays_are_demoted() {
+        let mut c = Counters::default();
+        fill(&mut c, Verdict::UnwrapFailed, MIN_EVENTS - 1);
+        assert_eq!(c.demotion_reason(), None, "not judged on a small sample");
+        fill(&mut c, Verdict::Malformed, 1);
+        assert_eq!(c.demotion_reason(), Some("garbage"));
+        assert!(!decide(&c, Some(DemotionOverride::Trust)));
+
+        // Enough good events bring it back
+        fill(&mut c, Verdict::Accepted, MIN_EVENTS + 1);
+        assert_eq!(c.demotion_reason(), None);
+
+        let mut stale = Counters::default();
+        fill(&mut stale, Verdict::Duplicate, 100);
+        assert_eq!(stale.demotion_reason(), Some("stale"));
+        fill(&mut stale, Verdict::Accepted, 10);
+        assert_eq!(stale.demotion_reason(), None, "reconnect replays alone don't demote");
+        assert!(decide(&stale, Some(DemotionOverride::Demote)));
+
+        // The window keeps counters bounded
+        fill(&mut stale, Verdict::Accepted, WINDOW * 2);
+        assert!(stale.total() < WINDOW);
+    }
+}
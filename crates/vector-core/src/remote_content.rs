@@ -0,0 +1,136 @@
+//! Per-chat remote-content policy — whether a chat's messages may make us fetch things from the
+//! open web (link previews, inline images, avatars) or from relays (`nostr:` references).
+//!
+//! Every such fetch reveals our IP (or Tor exit) and timing to whoever controls the URL, so in a
+//! chat with untrusted members the user can set it to ask first, or to never fetch. The policy is
+//! stored in the account settings KV like the self-destruct timer; absent means allow.
+//!
+//! Enforcement is [`gate`], called by each chat-originated fetch before it touches the network,
+//! and [`allows_profile_images`] for the avatars and banners of the people in those chats.
+
+use std::collections::HashMap;
+
+const KEY_PREFIX: &str = "remote_content:";
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteContentPolicy {
+    /// Fetch automatically.
+    #[default]
+    Allow,
+    /// Only fetch when the user asked for that one item.
+    Ask,
+    /// Never fetch.
+    Block,
+}
+
+impl RemoteContentPolicy {
+    fn as_str(self) -> &'static str {
+        match self {
+            RemoteContentPolicy::Allow => "allow",
+            RemoteContentPolicy::Ask => "ask",
+            RemoteContentPolicy::Block => "block",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "allow" => Some(RemoteContentPolicy::Allow),
+            "ask" => Some(RemoteContentPolicy::Ask),
+            "block" => Some(RemoteContentPolicy::Block),
+            _ => None,
+        }
+    }
+
+    /// Whether a fetch may go ahead; `user_initiated` = the user tapped to load this item.
+    pub fn permits(self, user_initiated: bool) -> bool {
+        match self {
+            RemoteContentPolicy::Allow => true,
+            RemoteContentPolicy::Ask => user_initiated,
+            RemoteContentPolicy::Block => false,
+        }
+    }
+}
+
+/// The policy for a chat (`Allow` when unset).
+pub fn chat_policy(chat_id: &str) -> RemoteContentPolicy {
+    crate::db::settings::get_sql_setting(format!("{KEY_PREFIX}{chat_id}"))
+        .ok()
+        .flatten()
+        .and_then(|v| RemoteContentPolicy::parse(&v))
+        .unwrap_or_default()
+}
+
+/// Every chat whose policy isn't `Allow`, keyed by chat id. One query, so a caller can take it
+/// before locking STATE.
+pub fn chat_policies() -> HashMap<String, RemoteContentPolicy> {
+    let Ok(conn) = crate::db::get_db_connection_guard_static() else { return HashMap::new() };
+    let Ok(mut stmt) = conn.prepare("SELECT key, value FROM settings WHERE substr(key, 1, length(?1)) = ?1") else {
+        return HashMap::new();
+    };
+    let rows = stmt.query_map([KEY_PREFIX], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)));
+    let Ok(rows) = rows else { return HashMap::new() };
+    rows.flatten()
+        .filter_map(|(key, value)| {
+            let policy = RemoteContentPolicy::parse(&value)?;
+            Some((key.strip_prefix(KEY_PREFIX)?.to_string(), policy))
+        })
+        .collect()
+}
+
+/// Set a chat's policy; `Allow` clears the key back to the default.
+pub fn set_chat_policy(chat_id: &str, policy: RemoteContentPolicy) -> Result<(), String> {
+    let key = format!("{KEY_PREFIX}{chat_id}");
+    match policy {
+        RemoteContentPolicy::Allow => crate::db::settings::remove_setting(&key),
+        _ => crate::db::settings::set_sql_setting(key, policy.as_str().to_string()),
+    }
+}
+
+/// Refuse a fetch originating from `chat_id`'s messages when its policy doesn't permit it.
+pub fn gate(chat_id: &str, user_initiated: bool) -> Result<(), String> {
+    match chat_policy(chat_id) {
+        policy if policy.permits(user_initiated) => Ok(()),
+        RemoteContentPolicy::Ask => Err("Remote content in this chat loads on request".to_string()),
+        _ => Err("Remote content is blocked in this chat".to_string()),
+    }
+}
+
+/// Whether to auto-fetch `npub`'s avatar and banner: yes for us, for anyone who shares an `Allow`
+/// chat with us, and for anyone we don't share a chat with at all; no for someone we only meet in
+/// chats that ask first or block. `policies` is [`chat_policies`].
+pub fn allows_profile_images(
+    state: &crate::state::ChatState,
+    policies: &HashMap<String, RemoteContentPolicy>,
+    npub: &str,
+) -> bool {
+    if crate::state::my_public_key().is_some_and(|pk| crate::ids::UserId::from(pk).npub() == npub) {
+        return true;
+    }
+    let mut in_any_chat = false;
+    for chat in state.chats.iter().filter(|c| c.has_participant(npub, &state.interner)) {
+        if policies.get(&chat.id).copied().unwrap_or_default() == RemoteContentPolicy::Allow {
+            return true;
+        }
+        in_any_chat = true;
+    }
+    !in_any_chat
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_gate_fetches() {
+        assert!(RemoteContentPolicy::Allow.permits(false));
+        assert!(!RemoteContentPolicy::Ask.permits(false));
+        assert!(RemoteContentPolicy::Ask.permits(true));
+        assert!(!RemoteContentPolicy::Block.permits(true));
+
+        for policy in [RemoteContentPolicy::Allow, RemoteContentPolicy::Ask, RemoteContentPolicy::Block] {
+            assert_eq!(RemoteContentPolicy::parse(policy.as_str()), Some(policy));
+            assert_eq!(serde_json::to_value(policy).unwrap(), policy.as_str());
+        }
+    }
+}
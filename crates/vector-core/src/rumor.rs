@@ -97,6 +97,16 @@ pub enum RumorProcessingResult {
         profile_id: String,
         until: u64,
     },
+    /// A contact's presence ping: online until `until` (see [`crate::presence`])
+    Presence {
+        profile_id: String,
+        until: u64,
+    },
+    /// A live location share's new position for message `message_id` (see [`crate::location`])
+    LocationUpdate {
+        message_id: String,
+        location: crate::location::Location,
+    },
     /// A leave request from a group member (admin should auto-remove them)
     LeaveRequest {
         /// The event ID of the leave request (for deduplication)
@@ -135,6 +145,19 @@ pub enum RumorProcessingResult {
         /// The stored event for persistence
         event: StoredEvent,
     },
+    /// A Cashu ecash token sent in chat (see `crate::cashu`)
+    EcashToken {
+        /// The encoded token (`cashuB…`)
+        token: String,
+        /// Amount in sats, as claimed by the sender (the token itself is authoritative)
+        amount_sat: u64,
+        /// The issuing mint's URL
+        mint: String,
+        /// The message ID for this token event
+        message_id: String,
+        /// The stored event for persistence
+        event: StoredEvent,
+    },
     /// A per-DM wallpaper change. The encrypted Blossom file is referenced
     /// by URL + decryption key in the tags; the caller is responsible for
     /// the timestamp comparison (latest-write-wins against
@@ -161,6 +184,15 @@ pub enum RumorProcessingResult {
         /// The rumor ID, used as the system-event row id.
         event_id: String,
     },
+    /// A social-backup message (a seed share, a recovery request or a share release). Never shown
+    /// in chat; the caller hands it to [`crate::social_backup::handle_incoming`].
+    SocialBackup {
+        /// The `d` tag, one of the `crate::social_backup::D_*` constants.
+        d_tag: String,
+        content: String,
+        sender_npub: String,
+        created_at: u64,
+    },
     /// Event was ignored (invalid, expired, or should not be stored)
     Ignored,
     /// A NIP-09 deletion request — sender asks live clients to drop a
@@ -203,10 +235,13 @@ pub enum RumorProcessingResult {
 /// A `RumorProcessingResult` indicating what type of event was processed,
 /// or an error if processing failed.
 pub fn process_rumor(
-    rumor: RumorEvent,
+    mut rumor: RumorEvent,
     context: RumorContext,
     download_dir: &Path,
 ) -> Result<RumorProcessingResult, String> {
+    // Compressed content (see `compression`) is restored before any parser reads it
+    crate::compression::decode_content(&mut rumor.content, &rumor.tags)?;
+
     match rumor.kind {
         // Text messages — Kind 14 (NIP-17 DM chat message).
         Kind::PrivateDirectMessage => {
@@ -299,6 +334,10 @@ fn process_text_message(
 
     // Create the message
     let expiration = extract_nip40_expiration(&rumor);
+    let code_languages = crate::code_blocks::code_languages(&rumor.content);
+    // The sender's preview, so we needn't fetch the link ourselves
+    let preview_metadata = crate::link_preview::from_tags(rumor.tags.iter(), &rumor.content);
+    let location = crate::location::from_tags(rumor.tags.iter());
     let msg = Message {
         expiration,
         id: rumor.id.to_hex(),
@@ -308,7 +347,8 @@ fn process_text_message(
         replied_to_npub: None,
         replied_to_has_attachment: None,
         replied_to_attachment_extension: None,
-        preview_metadata: None,
+        preview_metadata,
+        location,
         at: ms_timestamp,
         attachments: Vec::new(),
         reactions: Vec::new(),
@@ -321,6 +361,10 @@ fn process_text_message(
         edit_history: None,
         emoji_tags,
         addressed_bots,
+        code_languages,
+        nostr_previews: Vec::new(),
+        zap_msat: 0,
+        starred: false,
     };
 
     Ok(RumorProcessingResult::TextMessage(msg))
@@ -490,6 +534,25 @@ fn process_file_attachment(
         .filter(|t| t.len() == 52 && t.bytes().all(|b| b.is_ascii_uppercase() || (b'2'..=b'7').contains(&b)))
         .map(|s| s.to_string());
 
+    // View-once media (see `view_once`): a flat tag here, a field in each gallery imeta
+    let view_once = rumor.tags
+        .find(TagKind::Custom(Cow::Borrowed("view-once")))
+        .and_then(|tag| tag.content())
+        == Some("1");
+
+    // A recorded video note, as opposed to a shared video file
+    let video_note = rumor.tags
+        .find(TagKind::Custom(Cow::Borrowed("video-note")))
+        .and_then(|tag| tag.content())
+        == Some("1");
+
+    // NIP-94 `fallback`: mirrors of the blob on other servers
+    let fallback_kind = TagKind::Custom(Cow::Borrowed("fallback"));
+    let mirrors = crate::blossom::mirror_urls(
+        rumor.tags.iter().filter(|t| t.kind() == fallback_kind).filter_map(|t| t.content()),
+        &content_url,
+    );
+
     // Create the attachment
     let attachment = Attachment {
         id: file_hash.clone(),
@@ -506,7 +569,23 @@ fn process_file_attachment(
         webxdc_topic,
         group_id: None,       // Kind 15 attachments use explicit key/nonce
         original_hash: original_file_hash, // ox tag value (original file hash)
+        mirrors,
+        view_once,
+        video_note,
+        viewed: false,
+        transcript: None,
     };
+    // Gallery messages carry one NIP-92 `imeta` per file; the flat tags above
+    // mirror the first file so older clients still show something.
+    let gallery = crate::community::attachments::attachments_from_tags(rumor.tags.iter(), download_dir);
+    let attachments = if gallery.len() >= 2 { gallery } else { vec![attachment] };
+
+    // Kind 15 content is the file URL; any accompanying text rides a `caption` tag.
+    let caption = rumor.tags
+        .find(TagKind::Custom(Cow::Borrowed("caption")))
+        .and_then(|tag| tag.content())
+        .unwrap_or_default()
+        .to_string();
 
     let emoji_tags = crate::types::EmojiTag::extract_from_tags(rumor.tags.iter());
     // DM → None (1:1, implied by chat); Community → the real author.
@@ -514,18 +593,20 @@ fn process_file_attachment(
 
     // Create the message with attachment
     let expiration = extract_nip40_expiration(&rumor);
+    let code_languages = crate::code_blocks::code_languages(&caption);
     let msg = Message {
         expiration,
         id: rumor.id.to_hex(),
-        content: String::new(),
+        content: caption,
         replied_to,
         replied_to_content: None, // Populated by get_message_views
         replied_to_npub: None,
         replied_to_has_attachment: None,
         replied_to_attachment_extension: None,
         preview_metadata: None,
+        location: None,
         at: ms_timestamp,
-        attachments: vec![attachment],
+        attachments,
         reactions: Vec::new(),
         mine: context.is_mine,
         pending: false,
@@ -536,6 +617,10 @@ fn process_file_attachment(
         edit_history: None,
         emoji_tags,
         addressed_bots: crate::bot_interface::addressed_bots(rumor.tags.iter()),
+        code_languages,
+        nostr_previews: Vec::new(),
+        zap_msat: 0,
+        starred: false,
     };
 
     Ok(RumorProcessingResult::FileAttachment(msg))
@@ -729,6 +814,27 @@ fn process_app_specific(
         });
     }
 
+    if crate::presence::is_presence(&rumor.content, &rumor.tags) {
+        let Some(until) = crate::presence::valid_until(&rumor.tags, crate::clock::now_secs()) else {
+            return Ok(RumorProcessingResult::Ignored);
+        };
+        let profile_id = rumor.pubkey.to_bech32()
+            .map_err(|e| format!("Failed to convert pubkey to bech32: {}", e))?;
+        return Ok(RumorProcessingResult::Presence { profile_id, until });
+    }
+
+    if crate::location::is_update(&rumor.tags) {
+        let message_id = rumor.tags
+            .find(TagKind::e())
+            .and_then(|tag| tag.content())
+            .ok_or("Location update missing e tag")?
+            .to_string();
+        let Some(location) = crate::location::from_tags(rumor.tags.iter()) else {
+            return Ok(RumorProcessingResult::Ignored);
+        };
+        return Ok(RumorProcessingResult::LocationUpdate { message_id, location });
+    }
+
     // Check if this is a leave request
     if is_leave_request(&rumor) {
         let member_pubkey = rumor.pubkey.to_bech32()
@@ -785,6 +891,52 @@ fn process_app_specific(
         });
     }
 
+    // Check if this is an ecash token
+    if is_ecash_token(&rumor) {
+        let token = rumor.tags
+            .find(TagKind::Custom(Cow::Borrowed("token")))
+            .and_then(|tag| tag.content())
+            .ok_or("Ecash token event missing token tag")?
+            .to_string();
+
+        let amount_sat = rumor.tags
+            .find(TagKind::Custom(Cow::Borrowed("amount")))
+            .and_then(|tag| tag.content())
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        let mint = rumor.tags
+            .find(TagKind::Custom(Cow::Borrowed("mint")))
+            .and_then(|tag| tag.content())
+            .unwrap_or_default()
+            .to_string();
+
+        let message_id = rumor.id.to_hex();
+
+        let tags: Vec<Vec<String>> = rumor.tags.iter()
+            .map(|tag| tag.as_slice().iter().map(|s| s.to_string()).collect())
+            .collect();
+
+        let event = StoredEventBuilder::new()
+            .id(&message_id)
+            .kind(event_kind::APPLICATION_SPECIFIC)
+            .chat_id(0) // Will be set by caller
+            .content(&rumor.content)
+            .tags(tags)
+            .created_at(rumor.created_at.as_secs())
+            .mine(context.is_mine)
+            .npub(Some(rumor.pubkey.to_bech32().unwrap_or_default()))
+            .build();
+
+        return Ok(RumorProcessingResult::EcashToken {
+            token,
+            amount_sat,
+            mint,
+            message_id,
+            event,
+        });
+    }
+
     // Check if this is a wallpaper change. Tags carry the encrypted file
     // ref; the caller decides whether this beats the chat's current
     // `wallpaper_ts` and runs the download+decrypt step.
@@ -899,6 +1051,19 @@ fn process_app_specific(
         });
     }
 
+    // Check if this is a social-backup share / recovery request (our own copies carry nothing to do)
+    if let Some(d_tag) = social_backup_tag(&rumor) {
+        if context.is_mine {
+            return Ok(RumorProcessingResult::Ignored);
+        }
+        return Ok(RumorProcessingResult::SocialBackup {
+            d_tag: d_tag.to_string(),
+            content: rumor.content,
+            sender_npub: rumor.pubkey.to_bech32().unwrap_or_default(),
+            created_at: rumor.created_at.as_secs(),
+        });
+    }
+
     // Unknown application-specific data
     Ok(RumorProcessingResult::Ignored)
 }
@@ -926,6 +1091,16 @@ fn is_pivx_payment(rumor: &RumorEvent) -> bool {
         && rumor.tags.find(TagKind::Custom(Cow::Borrowed("gift-code"))).is_some()
 }
 
+/// Check if a rumor carries a Cashu ecash token
+fn is_ecash_token(rumor: &RumorEvent) -> bool {
+    rumor.tags
+        .find(TagKind::d())
+        .and_then(|tag| tag.content())
+        .map(|content| content == crate::cashu::D_TAG)
+        .unwrap_or(false)
+        && rumor.tags.find(TagKind::Custom(Cow::Borrowed("token"))).is_some()
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -1011,6 +1186,15 @@ fn is_wallpaper_change(rumor: &RumorEvent) -> bool {
         .unwrap_or(false)
 }
 
+/// The social-backup `d` tag a guardian acts on, if this rumor carries one. Releases are only ever
+/// addressed to a recovering device's throwaway key, which reads them outside this pipeline.
+fn social_backup_tag(rumor: &RumorEvent) -> Option<&'static str> {
+    let d = rumor.tags.find(TagKind::d()).and_then(|tag| tag.content())?;
+    [crate::social_backup::D_SHARE, crate::social_backup::D_REQUEST]
+        .into_iter()
+        .find(|tag| *tag == d)
+}
+
 /// Check if rumor is a leave request
 fn is_leave_request(rumor: &RumorEvent) -> bool {
     let has_vector_tag = rumor.tags
@@ -1129,6 +1313,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_text_message_compressed() {
+        let keys = test_keypair();
+        let text = "A long message that repeats itself. ".repeat(64);
+        let encoded = crate::compression::compress(&text).unwrap();
+        let rumor = make_rumor(
+            &keys,
+            Kind::PrivateDirectMessage,
+            &encoded,
+            tags(vec![custom_tag(crate::compression::ENCODING_TAG, &[crate::compression::ZSTD])]),
+        );
+
+        match process_rumor(rumor, dm_context(&keys), &temp_dir()).unwrap() {
+            RumorProcessingResult::TextMessage(msg) => assert_eq!(msg.content, text),
+            _ => panic!("Expected TextMessage"),
+        }
+    }
+
     #[test]
     fn test_text_message_mine() {
         let keys = test_keypair();
@@ -1347,6 +1549,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ecash_token() {
+        let keys = test_keypair();
+        let t = tags(vec![
+            Tag::identifier("cashu-token"),
+            custom_tag("token", &["cashuBtest"]),
+            custom_tag("amount", &["21"]),
+            custom_tag("mint", &["https://mint.example.com"]),
+        ]);
+        let rumor = make_rumor(&keys, Kind::ApplicationSpecificData, "{\"amount_sat\":21}", t);
+        let ctx = dm_context(&keys);
+        let result = process_rumor(rumor, ctx, &temp_dir()).unwrap();
+
+        match result {
+            RumorProcessingResult::EcashToken { token, amount_sat, mint, .. } => {
+                assert_eq!(token, "cashuBtest");
+                assert_eq!(amount_sat, 21);
+                assert_eq!(mint, "https://mint.example.com");
+            }
+            _ => panic!("Expected EcashToken"),
+        }
+    }
+
     // ========================================================================
     // WebXDC Tests
     // ========================================================================
@@ -1456,6 +1681,41 @@ mod tests {
                 assert_eq!(att.name, "photo.jpg");
                 assert_eq!(att.size, 12345);
                 assert!(!att.downloaded);
+                assert!(msg.content.is_empty(), "no caption tag → no text");
+            }
+            _ => panic!("Expected FileAttachment"),
+        }
+    }
+
+    #[test]
+    fn test_file_attachment_gallery_imeta_and_caption() {
+        let keys = test_keypair();
+        let first = Attachment {
+            key: "aa".repeat(32), nonce: "11".repeat(16), extension: "jpg".into(), name: "one.jpg".into(),
+            url: "https://blossom.example/one.jpg".into(), size: 10, original_hash: Some("a".repeat(64)),
+            ..Default::default()
+        };
+        let second = Attachment {
+            key: "bb".repeat(32), nonce: "22".repeat(16), extension: "png".into(), name: "two.png".into(),
+            url: "https://blossom.example/two.png".into(), size: 20, original_hash: Some("b".repeat(64)),
+            ..Default::default()
+        };
+        let t = tags(vec![
+            custom_tag("decryption-key", &[first.key.as_str()]),
+            custom_tag("decryption-nonce", &[first.nonce.as_str()]),
+            custom_tag("file-type", &["image/jpeg"]),
+            crate::community::attachments::attachment_to_imeta(&first),
+            crate::community::attachments::attachment_to_imeta(&second),
+            custom_tag("caption", &["two shots"]),
+        ]);
+        let rumor = make_rumor(&keys, Kind::from_u16(15), &first.url, t);
+        match process_rumor(rumor, dm_context(&keys), &temp_dir()).unwrap() {
+            RumorProcessingResult::FileAttachment(msg) => {
+                let names: Vec<_> = msg.attachments.iter().map(|a| a.name.as_str()).collect();
+                assert_eq!(names, vec!["one.jpg", "two.png"]);
+                assert_eq!(msg.content, "two shots");
+                assert_eq!(msg.attachments[1].key, second.key);
+                assert_eq!(msg.attachments[1].extension, "png");
             }
             _ => panic!("Expected FileAttachment"),
         }
@@ -81,6 +81,9 @@ pub async fn sweep_expired() -> Option<u64> {
         Err(_) => return None,
     };
 
+    // Starred messages are kept past their expiry (see `starred`).
+    let starred = crate::starred::starred_ids();
+
     // Pass 1 — scan under the lock: collect the expired, and track the soonest
     // still-pending expiry so the loop can time the next sweep to the second.
     let mut soonest: Option<u64> = None;
@@ -90,7 +93,7 @@ pub async fn sweep_expired() -> Option<u64> {
         for chat in &state.chats {
             for msg in chat.messages.iter() {
                 let exp = msg.expiration_secs;
-                if exp == 0 {
+                if exp == 0 || (!starred.is_empty() && starred.contains(&msg.id_hex())) {
                     continue;
                 }
                 let exp = exp as u64;
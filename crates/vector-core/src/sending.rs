@@ -8,7 +8,8 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use nostr_sdk::prelude::*;
 
-use crate::state::{nostr_client, my_public_key, STATE};
+use crate::context::lock_state;
+use crate::state::{nostr_client, my_public_key};
 use crate::types::{Message, Attachment};
 use crate::crypto;
 
@@ -61,6 +62,7 @@ impl SendCallback for NoOpSendCallback {}
 // ============================================================================
 
 /// Configuration for a send operation.
+#[derive(Clone)]
 pub struct SendConfig {
     /// Max gift-wrap send attempts (default: 1).
     pub max_send_attempts: u32,
@@ -78,6 +80,16 @@ pub struct SendConfig {
     /// and mirror onto the outer wrap. None = permanent. Resolved per-chat by
     /// the caller (the "Self-Destruct Timer" setting).
     pub expiration: Option<u64>,
+    /// Send file attachments as view-once media (see `view_once`).
+    pub view_once: bool,
+    /// Send a single file as a video note (`Attachment::video_note`).
+    pub video_note: bool,
+    /// Preview metadata for a single file that isn't an image itself (a video note's poster
+    /// frame). Images generate their own.
+    pub poster: Option<crate::types::ImageMetadata>,
+    /// A device drop to our own npub (see `device_drop`): the message lives in the device-drop
+    /// chat rather than Notes.
+    pub device_drop: bool,
 }
 
 impl Default for SendConfig {
@@ -90,6 +102,10 @@ impl Default for SendConfig {
             upload_retries: 3,
             upload_retry_delay: std::time::Duration::from_secs(2),
             expiration: None,
+            view_once: false,
+            video_note: false,
+            poster: None,
+            device_drop: false,
         }
     }
 }
@@ -227,7 +243,7 @@ async fn rescue_failed_as_sent(entry: &WrapConfirm) {
         return;
     }
     let finalized = {
-        let mut state = STATE.lock().await;
+        let mut state = lock_state().await;
         state.update_message(&entry.pending_id, |msg| {
             msg.set_failed(false);
         });
@@ -480,7 +496,7 @@ async fn retry_send_gift_wrap(
         crate::inbox_relays::teardown_gift_wrap_targets(client, t).await;
     }
     let failed_msg = {
-        let mut state = STATE.lock().await;
+        let mut state = lock_state().await;
         state.update_message(pending_id, |msg| {
             msg.set_failed(true);
             msg.set_pending(false);
@@ -533,7 +549,7 @@ async fn finalize_gift_wrap_sent(
     crate::inbox_relays::teardown_gift_wrap_targets(client, targets).await;
 
     let finalized = {
-        let mut state = STATE.lock().await;
+        let mut state = lock_state().await;
         state.finalize_pending_message(receiver_npub, pending_id, event_id)
     };
     if let Some((_old_id, ref finalized_msg)) = finalized {
@@ -562,14 +578,108 @@ async fn finalize_gift_wrap_sent(
 // send_dm — Text DMs
 // ============================================================================
 
+/// Largest text (in bytes) sent as a single message. NIP-44 caps each encryption layer at
+/// 64KB of plaintext and the seal + gift wrap roughly double a rumor's size, so anything
+/// near that limit is silently rejected. Longer texts go out as parts via [`split_message`].
+pub const MAX_MESSAGE_BYTES: usize = 32 * 1024;
+
+/// Most parts a long text is split into; beyond this it's sent as a `.txt` file instead.
+pub const MAX_MESSAGE_PARTS: usize = 4;
+
+/// Split `content` into parts of at most `max_bytes` each (marker included), preferring to
+/// break at paragraphs, then lines, then words. Every part is prefixed "(Part i/n)".
+/// Content that already fits is returned unchanged as the only part.
+pub fn split_message(content: &str, max_bytes: usize) -> Vec<String> {
+    if content.len() <= max_bytes {
+        return vec![content.to_string()];
+    }
+    // Room for the "(Part i/n)\n" marker
+    const MARKER_RESERVE: usize = 24;
+    let budget = max_bytes.saturating_sub(MARKER_RESERVE).max(4);
+
+    let mut chunks: Vec<&str> = Vec::new();
+    let mut rest = content;
+    while rest.len() > budget {
+        let mut cut = budget;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        // Only take a natural break from the back half, so parts stay roughly even
+        let window = &rest[..cut];
+        let min = cut / 2;
+        let at = window.rfind("\n\n").filter(|&i| i >= min).map(|i| i + 2)
+            .or_else(|| window.rfind('\n').filter(|&i| i >= min).map(|i| i + 1))
+            .or_else(|| window.rfind(' ').filter(|&i| i >= min).map(|i| i + 1))
+            .unwrap_or(cut);
+        chunks.push(&rest[..at]);
+        rest = &rest[at..];
+    }
+    if !rest.is_empty() {
+        chunks.push(rest);
+    }
+
+    let n = chunks.len();
+    chunks.iter().enumerate()
+        .map(|(i, c)| format!("(Part {}/{})\n{}", i + 1, n, c.trim_end()))
+        .collect()
+}
+
 /// Send a NIP-17 gift-wrapped text DM.
 ///
+/// Texts over [`MAX_MESSAGE_BYTES`] go out as consecutive parts, each replying to the one
+/// before it; past [`MAX_MESSAGE_PARTS`] the text is sent as a `message.txt` file instead.
+/// The returned result is the last part's.
+pub async fn send_dm(
+    receiver_npub: &str,
+    content: &str,
+    reply_to: Option<&str>,
+    config: &SendConfig,
+    callback: Arc<dyn SendCallback>,
+) -> Result<SendResult, String> {
+    if content.len() <= MAX_MESSAGE_BYTES {
+        return send_single_dm(receiver_npub, content, reply_to, None, config, callback).await;
+    }
+
+    let parts = split_message(content, MAX_MESSAGE_BYTES);
+    if parts.len() > MAX_MESSAGE_PARTS {
+        let bytes = Arc::new(content.as_bytes().to_vec());
+        return send_file_dm(receiver_npub, bytes, "message.txt", "txt", None, config, callback).await;
+    }
+
+    let mut reply = reply_to.map(str::to_string);
+    let mut last = None;
+    for part in &parts {
+        let result = send_single_dm(receiver_npub, part, reply.as_deref(), None, config, callback.clone()).await?;
+        // A failed part is already marked failed in the UI; don't send the rest out of order
+        let Some(event_id) = result.event_id.clone() else {
+            return Ok(result);
+        };
+        reply = Some(event_id);
+        last = Some(result);
+    }
+    last.ok_or_else(|| "Nothing to send".to_string())
+}
+
+/// Send a location DM: its `geo:` URI as the text, the location itself as a tag (see
+/// [`crate::location`]).
+pub async fn send_location_dm(
+    receiver_npub: &str,
+    location: &crate::location::Location,
+    config: &SendConfig,
+    callback: Arc<dyn SendCallback>,
+) -> Result<SendResult, String> {
+    send_single_dm(receiver_npub, &location.geo_uri(), None, Some(location), config, callback).await
+}
+
+/// Send one text DM that fits in a single event.
+///
 /// Flow: pending msg → callback.on_pending → build Kind 14 rumor →
 /// gift-wrap with retry → finalize → callback.on_sent → optional self-send.
-pub async fn send_dm(
+async fn send_single_dm(
     receiver_npub: &str,
     content: &str,
     reply_to: Option<&str>,
+    location: Option<&crate::location::Location>,
     config: &SendConfig,
     callback: Arc<dyn SendCallback>,
 ) -> Result<SendResult, String> {
@@ -588,6 +698,8 @@ pub async fn send_dm(
     // Recipients without the pack subscribed still render correctly, and
     // our own-view echo populates `emoji_tags` for the renderer.
     let emoji_tags = crate::emoji_packs::resolve_outbound_emoji_tags(content);
+    // A preview fetched while composing goes along, so the recipient needn't fetch the link
+    let preview = crate::link_preview::outbound(content);
 
     // Build pending message and add to state
     let msg = Message {
@@ -600,11 +712,13 @@ pub async fn send_dm(
         npub: my_pk.to_bech32().ok(),
         emoji_tags: emoji_tags.clone(),
         expiration: config.expiration,
+        preview_metadata: preview.as_ref().map(|(_, m)| m.clone()),
+        location: location.copied(),
         ..Default::default()
     };
 
     {
-        let mut state = STATE.lock().await;
+        let mut state = lock_state().await;
         state.add_message_to_participant(receiver_npub, &msg);
     }
 
@@ -612,7 +726,15 @@ pub async fn send_dm(
 
     // Build the rumor
     let milliseconds = now.as_millis() % 1000;
-    let mut rumor = EventBuilder::private_msg_rumor(receiver, content);
+    let (wire_content, encoding_tag) = crate::compression::encode_for(receiver_npub, content);
+    let mut rumor = EventBuilder::private_msg_rumor(receiver, wire_content)
+        .tag(crate::compression::accept_encoding_tag());
+    if let Some(tag) = crate::compression::device_tag(receiver_npub) {
+        rumor = rumor.tag(tag);
+    }
+    if let Some(tag) = encoding_tag {
+        rumor = rumor.tag(tag);
+    }
 
     if let Some(reply_id) = reply_to {
         if !reply_id.is_empty() {
@@ -630,6 +752,12 @@ pub async fn send_dm(
             [et.shortcode.clone(), et.url.clone()],
         ));
     }
+    if let Some(tag) = preview.and_then(|(url, m)| crate::link_preview::tag(&url, &m)) {
+        rumor = rumor.tag(tag);
+    }
+    if let Some(location) = location {
+        rumor = rumor.tag(location.tag());
+    }
     // NIP-40 self-destruct: stamp the rumor so compliant receivers honor the
     // expiry; retry_send_gift_wrap mirrors it onto the outer wrap for relays.
     if let Some(exp) = config.expiration {
@@ -709,7 +837,7 @@ pub async fn resend_failed_dm(
     // Flip the red row back to "sending"; the retry loop's own fail/finalize
     // path returns it to red or promotes it to sent.
     let repending = {
-        let mut state = STATE.lock().await;
+        let mut state = lock_state().await;
         state.update_message(failed_msg_id, |msg| {
             msg.set_failed(false);
             msg.set_pending(true);
@@ -743,35 +871,26 @@ pub async fn resend_failed_dm(
 // send_file_dm — File Attachment DMs
 // ============================================================================
 
-/// Send a NIP-17 gift-wrapped file attachment DM.
-///
-/// Flow: hash → save locally → encrypt → upload → build Kind 15 rumor → gift-wrap + send.
-pub async fn send_file_dm(
-    receiver_npub: &str,
-    file_bytes: Arc<Vec<u8>>,
-    filename: &str,
-    extension: &str,
-    content: Option<&str>,
-    config: &SendConfig,
-    callback: Arc<dyn SendCallback>,
-) -> Result<SendResult, String> {
-    let client = nostr_client().ok_or("Not logged in")?;
-    let my_pk = my_public_key().ok_or("Public key not set")?;
-    // Sign the Blossom auth event via the active client signer so bunker
-    // accounts route through NostrConnect (the user's identity key lives on
-    // the remote signer; MY_SECRET_KEY only holds the NIP-46 client key).
-    let signer = client.signer().await
-        .map_err(|e| format!("Signer unavailable: {}", e))?;
-
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH).unwrap();
-    let pending_id = format!("pending-{}", now.as_nanos());
-    let milliseconds = now.as_millis() % 1000;
-
-    let receiver = PublicKey::from_bech32(receiver_npub)
-        .map_err(|e| format!("Invalid npub: {}", e))?;
+/// An outgoing file saved locally and encrypted, ready to upload.
+struct StagedFile {
+    /// The pending attachment (no URL yet), pointing at the local copy.
+    attachment: Attachment,
+    encrypted: Vec<u8>,
+    mime_type: &'static str,
+    /// SHA-256 of the plaintext (the `ox` tag).
+    file_hash: String,
+    /// Upload as resumable chunks (see [`upload_staged`]).
+    chunked: bool,
+}
 
-    let file_hash = crypto::sha256_hex(&file_bytes);
+/// Hash, save locally (so the attachment is viewable at once) and encrypt one outgoing file.
+///
+/// A large file headed to a client that reassembles chunked uploads is `chunked`; its encryption
+/// params are kept per file hash, so a retry reproduces the ciphertext and resumes the upload.
+fn stage_file(file_bytes: &[u8], filename: &str, extension: &str, my_pk: &PublicKey, receiver_npub: &str) -> Result<StagedFile, String> {
+    let file_hash = crypto::sha256_hex(file_bytes);
+    let chunked = file_bytes.len() >= crate::blossom::CHUNKED_UPLOAD_MIN_BYTES
+        && crate::compression::accepts_chunks(receiver_npub);
     let mime_type = crypto::mime_from_extension(extension);
 
     // WebXDC Mini Apps: mint the realtime-channel topic at send time and carry
@@ -800,104 +919,151 @@ pub async fn send_file_dm(
     let local_path = crypto::resolve_unique_filename(&download_dir, &local_name);
     // Atomic write: temp file then rename
     let tmp = download_dir.join(format!(".{}.tmp", &file_hash));
-    let _ = std::fs::write(&tmp, &*file_bytes);
+    let _ = std::fs::write(&tmp, file_bytes);
     let _ = std::fs::rename(&tmp, &local_path);
     let local_path_str = local_path.to_string_lossy().to_string();
 
     // === Generate image metadata (thumbhash + dimensions) for image files ===
-    let img_meta = crypto::generate_image_metadata(&file_bytes);
-
-    // === Encrypt → upload → build rumor → send ===
-    let params = crypto::generate_encryption_params();
-    let encrypted = crypto::encrypt_data(&file_bytes, &params)?;
+    let img_meta = crypto::generate_image_metadata(file_bytes);
+
+    // === Encrypt (the caller uploads) ===
+    // A voice message is sealed in records when the receiver can open them, so it plays as it
+    // downloads without anything unauthenticated reaching the decoder
+    let voice = filename.is_empty() && matches!(extension, "wav" | "mp3" | "flac");
+    let fresh = if voice && crate::compression::accepts_sealed_chunks(receiver_npub) {
+        crypto::stream::generate_params()
+    } else {
+        crypto::generate_encryption_params()
+    };
+    let params = if chunked {
+        crate::db::uploads::session_params(&file_hash, fresh)?
+    } else {
+        fresh
+    };
+    let encrypted = crypto::encrypt_data(file_bytes, &params)?;
     let encrypted_size = encrypted.len() as u64;
 
     let attachment = Attachment {
-        id: file_hash.clone(), key: params.key.clone(), nonce: params.nonce.clone(),
+        id: file_hash.clone(), key: params.key, nonce: params.nonce,
         extension: extension.to_string(), name: filename.to_string(),
-        url: String::new(), path: local_path_str.clone(), size: encrypted_size,
-        img_meta: img_meta.clone(), downloading: false, downloaded: true,
-        webxdc_topic: webxdc_topic.clone(),
-        ..Default::default()
-    };
-    let msg = Message {
-        id: pending_id.clone(), content: content.unwrap_or("").to_string(),
-        at: now.as_millis() as u64, pending: true, mine: true,
-        npub: my_pk.to_bech32().ok(), attachments: vec![attachment],
-        expiration: config.expiration,
+        url: String::new(), path: local_path_str, size: encrypted_size,
+        img_meta, downloading: false, downloaded: true,
+        webxdc_topic,
         ..Default::default()
     };
-    {
-        let mut state = STATE.lock().await;
-        state.add_message_to_participant(receiver_npub, &msg);
-    }
-    callback.on_pending(receiver_npub, &msg);
+    Ok(StagedFile { attachment, encrypted, mime_type, file_hash, chunked })
+}
 
-    // Upload to Blossom — bridge SendCallback.on_upload_progress to Blossom ProgressCallback
-    let servers = crate::state::get_blossom_servers();
-    let cb_for_progress = callback.clone();
-    let pid_for_progress = pending_id.clone();
-    let progress_cb: crate::blossom::ProgressCallback = Arc::new(move |percentage, bytes| {
-        cb_for_progress.on_upload_progress(
-            &pid_for_progress,
-            percentage.unwrap_or(0),
-            bytes.unwrap_or(0),
-        )
-    });
+/// Upload rounds (each one pass over every enabled server) before a file send gives up.
+const UPLOAD_ROUNDS: u32 = 3;
 
-    // Send the original MIME even though bytes are ciphertext: many
-    // Blossom servers reject `application/octet-stream` but accept the
-    // same bytes under their original type.
-    let upload_url = match crate::blossom::upload_blob_with_progress_and_failover(
-        signer.clone(), servers, Arc::new(encrypted), Some(mime_type),
-        /* is_encrypted */ true,
-        progress_cb, Some(config.upload_retries), Some(config.upload_retry_delay),
-        config.cancel_token.clone(),
-    ).await {
-        Ok(url) => url,
-        Err(e) => {
-            let failed_msg = {
-                let mut state = STATE.lock().await;
-                state.update_message(&pending_id, |msg| {
-                    msg.set_failed(true);
-                    msg.set_pending(false);
-                })
-            };
-            if let Some((_chat_id, ref msg)) = failed_msg {
-                callback.on_failed(receiver_npub, &pending_id, msg);
-                callback.on_persist(receiver_npub, msg);
-            }
-            return Err(format!("Upload failed: {}", e));
-        }
-    };
+/// Pause before the second round; doubles each round after, up to [`UPLOAD_BACKOFF_MAX`].
+const UPLOAD_BACKOFF: std::time::Duration = std::time::Duration::from_secs(3);
+const UPLOAD_BACKOFF_MAX: std::time::Duration = std::time::Duration::from_secs(30);
 
-    {
-        let mut state = STATE.lock().await;
-        state.update_message(&pending_id, |msg| {
-            if let Some(att) = msg.attachments.last_mut() {
-                att.url = upload_url.clone().into_boxed_str();
+/// Retry rounds in flight at once, across every send: an outage with a queue of files behind it
+/// retries them a couple at a time rather than all at once.
+static UPLOAD_RETRY_SLOTS: std::sync::LazyLock<tokio::sync::Semaphore> =
+    std::sync::LazyLock::new(|| tokio::sync::Semaphore::new(2));
+
+/// The pause before upload round `round` (1-based retries).
+fn upload_backoff(round: u32) -> std::time::Duration {
+    UPLOAD_BACKOFF.saturating_mul(1 << round.saturating_sub(1).min(16)).min(UPLOAD_BACKOFF_MAX)
+}
+
+/// Upload a staged file's ciphertext to Blossom, chunked or as one blob.
+///
+/// Send the original MIME even though bytes are ciphertext: many Blossom servers reject
+/// `application/octet-stream` but accept the same bytes under their original type.
+///
+/// A round tries every enabled server in `rank_servers` order. When all of them fail, later
+/// rounds back off exponentially and start one server further along that ranked list, so the
+/// server that just 5xx'd isn't always tried first; a chunked upload resumes from the chunks already stored.
+/// Stops early on cancel, or once every server has refused the blob for good (type or size).
+async fn upload_staged<T: NostrSigner + Clone>(
+    signer: T,
+    encrypted: Arc<Vec<u8>>,
+    mime_type: &str,
+    file_hash: &str,
+    chunked: bool,
+    progress_cb: crate::blossom::ProgressCallback,
+    config: &SendConfig,
+) -> Result<String, String> {
+    // What a single PUT carries, for the "does anyone still take this" check
+    let put_size = if chunked { encrypted.len().min(crate::blossom::CHUNK_SIZE) } else { encrypted.len() } as u64;
+    let mut last_error = String::from("No servers available");
+    for round in 0..UPLOAD_ROUNDS {
+        let _slot = if round > 0 {
+            tokio::time::sleep(upload_backoff(round)).await;
+            Some(UPLOAD_RETRY_SLOTS.acquire().await.map_err(|e| e.to_string())?)
+        } else {
+            None
+        };
+        if config.cancel_token.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed)) {
+            return Err("Upload cancelled".to_string());
+        }
+        let servers = crate::state::get_blossom_servers();
+        let result = if chunked {
+            crate::blossom::upload_chunked_with_failover(
+                signer.clone(), servers.clone(), file_hash, encrypted.clone(), Some(mime_type),
+                progress_cb.clone(), Some(config.upload_retries), Some(config.upload_retry_delay),
+                config.cancel_token.clone(), round as usize,
+            ).await
+        } else {
+            crate::blossom::upload_blob_ranked_from(
+                signer.clone(), servers.clone(), encrypted.clone(), Some(mime_type),
+                /* is_encrypted */ true,
+                progress_cb.clone(), Some(config.upload_retries), Some(config.upload_retry_delay),
+                config.cancel_token.clone(), round as usize,
+            ).await
+        };
+        match result {
+            Ok(url) => return Ok(url),
+            Err(e) if e.contains("Upload cancelled") => return Err(e),
+            Err(e) => {
+                if !crate::blossom_capabilities::any_server_likely_accepts(&servers, mime_type, true, put_size) {
+                    return Err(e);
+                }
+                crate::log_warn!("[Send] Upload round {}/{} failed: {}", round + 1, UPLOAD_ROUNDS, e);
+                last_error = e;
             }
-        });
+        }
     }
-    callback.on_upload_complete(receiver_npub, &pending_id, &file_hash, &upload_url);
+    Err(last_error)
+}
 
-    // Build Kind 15
-    let mut file_rumor = EventBuilder::new(Kind::from_u16(15), &upload_url)
+/// The Kind 15 rumor for one uploaded attachment (`att.url` set), minus `ms`/expiration.
+///
+/// Kind 15's `content` is the file URL, so an accompanying text rides a `caption` tag.
+fn file_rumor_builder(receiver: PublicKey, att: &Attachment, mime_type: &str, file_hash: &str, caption: Option<&str>) -> EventBuilder {
+    let mut file_rumor = EventBuilder::new(Kind::from_u16(15), &att.url)
         .tag(Tag::public_key(receiver))
         .tag(Tag::custom(TagKind::custom("file-type"), [mime_type]))
-        .tag(Tag::custom(TagKind::custom("size"), [encrypted_size.to_string()]))
-        .tag(Tag::custom(TagKind::custom("encryption-algorithm"), ["aes-gcm"]))
-        .tag(Tag::custom(TagKind::custom("decryption-key"), [params.key.as_str()]))
-        .tag(Tag::custom(TagKind::custom("decryption-nonce"), [params.nonce.as_str()]))
-        .tag(Tag::custom(TagKind::custom("ox"), [file_hash.clone()]));
-    if !filename.is_empty() {
-        file_rumor = file_rumor.tag(Tag::custom(TagKind::custom("name"), [filename]));
-    }
-    if let Some(ref topic) = webxdc_topic {
+        .tag(Tag::custom(TagKind::custom("size"), [att.size.to_string()]))
+        .tag(Tag::custom(TagKind::custom("encryption-algorithm"), [
+            if crypto::stream::is_sealed_nonce(&att.nonce) { "aes-gcm-sealed-chunks" } else { "aes-gcm" },
+        ]))
+        .tag(Tag::custom(TagKind::custom("decryption-key"), [att.key.as_str()]))
+        .tag(Tag::custom(TagKind::custom("decryption-nonce"), [att.nonce.as_str()]))
+        .tag(Tag::custom(TagKind::custom("ox"), [file_hash]));
+    if !att.name.is_empty() {
+        file_rumor = file_rumor.tag(Tag::custom(TagKind::custom("name"), [att.name.as_str()]));
+    }
+    if let Some(ref topic) = att.webxdc_topic {
         file_rumor = file_rumor.tag(Tag::custom(TagKind::custom("webxdc-topic"), [topic.as_str()]));
     }
+    if att.view_once {
+        file_rumor = file_rumor.tag(Tag::custom(TagKind::custom("view-once"), ["1"]));
+    }
+    if att.video_note {
+        file_rumor = file_rumor.tag(Tag::custom(TagKind::custom("video-note"), ["1"]));
+    }
+    // NIP-94 `fallback`: the blob's mirrors, for when the server in `content` is gone
+    for mirror in &att.mirrors {
+        file_rumor = file_rumor.tag(Tag::custom(TagKind::custom("fallback"), [mirror.as_str()]));
+    }
     // Include image preview metadata for compatible rendering across all clients
-    if let Some(ref meta) = img_meta {
+    if let Some(ref meta) = att.img_meta {
         if !meta.thumbhash.is_empty() {
             // `thumbhash` names the value accurately; receivers read `thumb` too
             // (legacy), so this stays backward-compatible in both directions.
@@ -905,20 +1071,396 @@ pub async fn send_file_dm(
         }
         file_rumor = file_rumor.tag(Tag::custom(TagKind::custom("dim"), [format!("{}x{}", meta.width, meta.height)]));
     }
-    file_rumor = file_rumor.tag(Tag::custom(TagKind::custom("ms"), [milliseconds.to_string()]));
-    if let Some(exp) = config.expiration {
+    if let Some(caption) = caption.filter(|c| !c.trim().is_empty()) {
+        file_rumor = file_rumor.tag(Tag::custom(TagKind::custom("caption"), [caption]));
+    }
+    file_rumor
+}
+
+/// A file DM from its pending row to the wire: the staged files (each with its URL once
+/// uploaded) and everything needed to build and wrap the rumor. Parked whole when its uploads
+/// give up, so a retry picks the same message back up.
+struct FileJob {
+    receiver_npub: String,
+    chat_id: String,
+    pending_id: String,
+    content: Option<String>,
+    milliseconds: u128,
+    files: Vec<JobFile>,
+    config: SendConfig,
+}
+
+struct JobFile {
+    attachment: Attachment,
+    encrypted: Arc<Vec<u8>>,
+    mime_type: &'static str,
+    file_hash: String,
+    chunked: bool,
+}
+
+impl From<StagedFile> for JobFile {
+    fn from(f: StagedFile) -> Self {
+        JobFile {
+            attachment: f.attachment,
+            encrypted: Arc::new(f.encrypted),
+            mime_type: f.mime_type,
+            file_hash: f.file_hash,
+            chunked: f.chunked,
+        }
+    }
+}
+
+/// Upload a job's remaining files one by one (progress across the whole batch, against the
+/// pending row), then send one Kind 15. One file gets the plain flat tags; a gallery describes
+/// the first file in flat tags so older clients still render it, plus one NIP-92 `imeta` per file.
+async fn run_file_job(mut job: FileJob, callback: Arc<dyn SendCallback>) -> Result<SendResult, String> {
+    let client = nostr_client().ok_or("Not logged in")?;
+    let my_pk = my_public_key().ok_or("Public key not set")?;
+    // Sign the Blossom auth event via the active client signer so bunker
+    // accounts route through NostrConnect (the user's identity key lives on
+    // the remote signer; MY_SECRET_KEY only holds the NIP-46 client key).
+    let signer = client.signer().await
+        .map_err(|e| format!("Signer unavailable: {}", e))?;
+    let receiver = PublicKey::from_bech32(&job.receiver_npub)
+        .map_err(|e| format!("Invalid npub: {}", e))?;
+
+    let total_bytes: u64 = job.files.iter().map(|f| f.attachment.size).sum::<u64>().max(1);
+    let mut done_bytes: u64 = job.files.iter()
+        .filter(|f| !f.attachment.url.is_empty())
+        .map(|f| f.attachment.size)
+        .sum();
+    for index in 0..job.files.len() {
+        if !job.files[index].attachment.url.is_empty() {
+            continue;
+        }
+        let file = &job.files[index];
+        let file_size = file.attachment.size;
+
+        // Bridge SendCallback.on_upload_progress to the Blossom ProgressCallback
+        let cb_for_progress = callback.clone();
+        let pid_for_progress = job.pending_id.clone();
+        let base = done_bytes;
+        let progress_cb: crate::blossom::ProgressCallback = Arc::new(move |percentage, bytes| {
+            let file_sent = bytes.unwrap_or_else(|| file_size * percentage.unwrap_or(0) as u64 / 100);
+            let sent = base + file_sent.min(file_size);
+            cb_for_progress.on_upload_progress(
+                &pid_for_progress,
+                (sent * 100 / total_bytes).min(100) as u8,
+                sent,
+            )
+        });
+
+        let upload_url = match upload_staged(
+            signer.clone(), file.encrypted.clone(), file.mime_type, &file.file_hash, file.chunked,
+            progress_cb, &job.config,
+        ).await {
+            Ok(url) => url,
+            Err(e) => {
+                let failed_msg = {
+                    let mut state = lock_state().await;
+                    state.update_message(&job.pending_id, |msg| {
+                        msg.set_failed(true);
+                        msg.set_pending(false);
+                    })
+                };
+                if let Some((_chat_id, ref msg)) = failed_msg {
+                    callback.on_failed(&job.chat_id, &job.pending_id, msg);
+                    callback.on_persist(&job.chat_id, msg);
+                }
+                let error = if job.files.len() == 1 {
+                    format!("Upload failed: {}", e)
+                } else {
+                    format!("Upload of {} failed: {}", file.attachment.name, e)
+                };
+                if !e.contains("Upload cancelled") {
+                    park_failed_upload(job, &error);
+                }
+                return Err(error);
+            }
+        };
+        done_bytes += file_size;
+
+        let file = &mut job.files[index];
+        if !file.chunked {
+            file.attachment.mirrors = crate::blossom::mirror_blob(
+                signer.clone(), crate::state::get_blossom_servers(), &upload_url,
+                file.encrypted.clone(), Some(file.mime_type), crate::blossom_servers::load_mirror_count(),
+            ).await;
+        }
+        {
+            let mut state = lock_state().await;
+            state.update_message(&job.pending_id, |msg| {
+                if let Some(att) = msg.attachments.get_mut(index) {
+                    att.url = upload_url.clone().into_boxed_str();
+                    att.set_mirrors(&file.attachment.mirrors);
+                }
+            });
+        }
+        callback.on_upload_complete(&job.chat_id, &job.pending_id, &file.file_hash, &upload_url);
+        file.attachment.url = upload_url;
+        file.attachment.original_hash = Some(file.file_hash.clone());
+    }
+
+    let first = &job.files[0];
+    let mut file_rumor = file_rumor_builder(
+        receiver, &first.attachment, first.mime_type, &first.file_hash, job.content.as_deref(),
+    );
+    if job.files.len() > 1 {
+        for file in &job.files {
+            file_rumor = file_rumor.tag(crate::community::attachments::attachment_to_imeta(&file.attachment));
+        }
+    }
+    file_rumor = file_rumor.tag(Tag::custom(TagKind::custom("ms"), [job.milliseconds.to_string()]));
+    if let Some(exp) = job.config.expiration {
         file_rumor = file_rumor.tag(Tag::expiration(Timestamp::from_secs(exp)));
     }
+    if job.config.device_drop {
+        file_rumor = file_rumor.tag(crate::device_drop::device_tag()?);
+    }
 
     let built_rumor = file_rumor.build(my_pk);
     let event_id = built_rumor.id.ok_or("Rumor has no id")?.to_hex();
 
     retry_send_gift_wrap(
-        &client, &receiver, receiver_npub, &pending_id,
-        built_rumor, &event_id, config, callback, None,
+        &client, &receiver, &job.chat_id, &job.pending_id,
+        built_rumor, &event_id, &job.config, callback, None,
     ).await
 }
 
+/// Send a NIP-17 gift-wrapped file attachment DM.
+///
+/// Flow: hash → save locally → encrypt → upload → build Kind 15 rumor → gift-wrap + send.
+pub async fn send_file_dm(
+    receiver_npub: &str,
+    file_bytes: Arc<Vec<u8>>,
+    filename: &str,
+    extension: &str,
+    content: Option<&str>,
+    config: &SendConfig,
+    callback: Arc<dyn SendCallback>,
+) -> Result<SendResult, String> {
+    let my_pk = my_public_key().ok_or("Public key not set")?;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH).unwrap();
+    let pending_id = format!("pending-{}", now.as_nanos());
+
+    PublicKey::from_bech32(receiver_npub)
+        .map_err(|e| format!("Invalid npub: {}", e))?;
+    let chat_id = if config.device_drop { crate::device_drop::CHAT_ID } else { receiver_npub };
+
+    let mut staged = stage_file(&file_bytes, filename, extension, &my_pk, receiver_npub)?;
+    staged.attachment.view_once = config.view_once;
+    staged.attachment.video_note = config.video_note;
+    if staged.attachment.img_meta.is_none() {
+        staged.attachment.img_meta = config.poster.clone();
+    }
+    let msg = Message {
+        id: pending_id.clone(), content: content.unwrap_or("").to_string(),
+        at: now.as_millis() as u64, pending: true, mine: true,
+        npub: my_pk.to_bech32().ok(), attachments: vec![staged.attachment.clone()],
+        expiration: config.expiration,
+        ..Default::default()
+    };
+    {
+        let mut state = lock_state().await;
+        state.add_message_to_participant(chat_id, &msg);
+    }
+    callback.on_pending(chat_id, &msg);
+
+    let job = FileJob {
+        receiver_npub: receiver_npub.to_string(),
+        chat_id: chat_id.to_string(),
+        pending_id,
+        content: content.map(str::to_string),
+        milliseconds: now.as_millis() % 1000,
+        files: vec![staged.into()],
+        config: config.clone(),
+    };
+    run_file_job(job, callback).await
+}
+
+/// Most files one gallery message may carry.
+pub const MAX_FILES_PER_MESSAGE: usize = 10;
+
+/// One file of a multi-attachment send.
+pub struct OutgoingFile {
+    pub bytes: Arc<Vec<u8>>,
+    pub filename: String,
+    pub extension: String,
+}
+
+/// Send several files as ONE gallery-style DM.
+///
+/// Every file is staged and uploaded in turn (progress is reported once, across the whole
+/// batch, against the pending message), then a single Kind 15 rumor goes out: the flat tags
+/// describe the first file so older clients still render it, and one NIP-92 `imeta` per file
+/// lets current clients show them all. A single file takes the plain [`send_file_dm`] path.
+pub async fn send_files_dm(
+    receiver_npub: &str,
+    files: Vec<OutgoingFile>,
+    content: Option<&str>,
+    config: &SendConfig,
+    callback: Arc<dyn SendCallback>,
+) -> Result<SendResult, String> {
+    if files.is_empty() {
+        return Err("No files to send".into());
+    }
+    if files.len() > MAX_FILES_PER_MESSAGE {
+        return Err(format!("A message can carry at most {} files", MAX_FILES_PER_MESSAGE));
+    }
+    if files.len() == 1 {
+        let file = files.into_iter().next().expect("one file");
+        return send_file_dm(
+            receiver_npub, file.bytes, &file.filename, &file.extension, content, config, callback,
+        ).await;
+    }
+
+    let my_pk = my_public_key().ok_or("Public key not set")?;
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH).unwrap();
+    let pending_id = format!("pending-{}", now.as_nanos());
+
+    PublicKey::from_bech32(receiver_npub)
+        .map_err(|e| format!("Invalid npub: {}", e))?;
+    let chat_id = if config.device_drop { crate::device_drop::CHAT_ID } else { receiver_npub };
+
+    let mut staged = files.iter()
+        .map(|f| stage_file(&f.bytes, &f.filename, &f.extension, &my_pk, receiver_npub))
+        .collect::<Result<Vec<_>, String>>()?;
+    for file in &mut staged {
+        file.attachment.view_once = config.view_once;
+    }
+    drop(files);
+
+    let msg = Message {
+        id: pending_id.clone(), content: content.unwrap_or("").to_string(),
+        at: now.as_millis() as u64, pending: true, mine: true,
+        npub: my_pk.to_bech32().ok(),
+        attachments: staged.iter().map(|f| f.attachment.clone()).collect(),
+        expiration: config.expiration,
+        ..Default::default()
+    };
+    {
+        let mut state = lock_state().await;
+        state.add_message_to_participant(chat_id, &msg);
+    }
+    callback.on_pending(chat_id, &msg);
+
+    let job = FileJob {
+        receiver_npub: receiver_npub.to_string(),
+        chat_id: chat_id.to_string(),
+        pending_id,
+        content: content.map(str::to_string),
+        milliseconds: now.as_millis() % 1000,
+        files: staged.into_iter().map(JobFile::from).collect(),
+        config: config.clone(),
+    };
+    run_file_job(job, callback).await
+}
+
+// ============================================================================
+// Failed-upload outbox
+// ============================================================================
+//
+// A file send whose uploads gave up is parked here, ciphertext and all, under a retry token that
+// the `upload_failed` event carries. Retrying the token picks the SAME message back up: files that
+// made it aren't uploaded again, a chunked file resumes, and the keys already on the pending row
+// stay valid. RAM-only and capped by size; after a restart (or for a job too big to keep) the
+// client falls back to a fresh send from the local copy.
+
+/// Most ciphertext held by parked uploads at once (the oldest are dropped past this).
+const MAX_PARKED_BYTES: usize = 256 * 1024 * 1024;
+
+struct ParkedUpload {
+    job: FileJob,
+    parked_at: std::time::Instant,
+}
+
+impl ParkedUpload {
+    fn bytes(&self) -> usize {
+        job_bytes(&self.job)
+    }
+}
+
+fn job_bytes(job: &FileJob) -> usize {
+    job.files.iter().map(|f| f.encrypted.len()).sum()
+}
+
+static PARKED_UPLOADS: std::sync::LazyLock<std::sync::Mutex<std::collections::HashMap<String, ParkedUpload>>> =
+    std::sync::LazyLock::new(|| std::sync::Mutex::new(std::collections::HashMap::new()));
+
+/// Payload of the `upload_failed` event.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct UploadFailed {
+    pub chat_id: String,
+    pub message_id: String,
+    /// Hand back to [`retry_failed_upload`] to try again.
+    pub retry_token: String,
+    pub error: String,
+}
+
+fn park_failed_upload(job: FileJob, error: &str) {
+    let size = job_bytes(&job);
+    if size > MAX_PARKED_BYTES {
+        return;
+    }
+    let mut token_bytes = [0u8; 16];
+    rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut token_bytes);
+    let token = crate::simd::hex::bytes_to_hex_string(&token_bytes);
+    let event = UploadFailed {
+        chat_id: job.chat_id.clone(),
+        message_id: job.pending_id.clone(),
+        retry_token: token.clone(),
+        error: error.to_string(),
+    };
+    {
+        let mut parked = PARKED_UPLOADS.lock().unwrap();
+        parked.retain(|_, p| p.job.pending_id != job.pending_id);
+        let mut held: usize = parked.values().map(ParkedUpload::bytes).sum();
+        while held + size > MAX_PARKED_BYTES {
+            let Some(oldest) = parked.iter().min_by_key(|(_, p)| p.parked_at).map(|(k, _)| k.clone()) else { break };
+            if let Some(dropped) = parked.remove(&oldest) {
+                held -= dropped.bytes();
+            }
+        }
+        parked.insert(token, ParkedUpload { job, parked_at: std::time::Instant::now() });
+    }
+    crate::traits::emit_event("upload_failed", &event);
+}
+
+/// Pick a parked failed upload back up: the row goes back to pending, the files still missing
+/// are uploaded (with the usual rounds and server rotation), and the message is sent.
+pub async fn retry_failed_upload(token: &str, callback: Arc<dyn SendCallback>) -> Result<SendResult, String> {
+    let job = PARKED_UPLOADS.lock().unwrap()
+        .remove(token)
+        .map(|p| p.job)
+        .ok_or("Nothing to retry for that token")?;
+    let repending = {
+        let mut state = lock_state().await;
+        state.update_message(&job.pending_id, |msg| {
+            msg.set_failed(false);
+            msg.set_pending(true);
+        })
+    };
+    let Some((_chat_id, msg)) = repending else {
+        return Err("The failed message is gone".to_string());
+    };
+    callback.on_pending(&job.chat_id, &msg);
+    run_file_job(job, callback).await
+}
+
+/// Drop the parked upload of a failed message the user deleted.
+pub fn discard_failed_upload(message_id: &str) {
+    PARKED_UPLOADS.lock().unwrap().retain(|_, p| p.job.pending_id != message_id);
+}
+
+/// Clear on session swap — parked jobs carry the prior account's messages.
+pub fn clear_parked_uploads() {
+    PARKED_UPLOADS.lock().unwrap().clear();
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -1257,4 +1799,35 @@ mod tests {
         token.store(true, std::sync::atomic::Ordering::Relaxed);
         assert!(c.cancel_token.as_ref().unwrap().load(std::sync::atomic::Ordering::Relaxed));
     }
+
+    #[test]
+    fn split_message_marks_parts_and_respects_limit() {
+        assert_eq!(split_message("short", 100), vec!["short"]);
+
+        let para = "word ".repeat(30);
+        let text = format!("{para}\n\n{para}\n\n{para}");
+        let parts = split_message(&text, 200);
+        assert!(parts.len() > 1);
+        for (i, part) in parts.iter().enumerate() {
+            assert!(part.len() <= 200, "part {} is {} bytes", i, part.len());
+            assert!(part.starts_with(&format!("(Part {}/{})\n", i + 1, parts.len())));
+        }
+        // Nothing is lost apart from the trailing whitespace at each break
+        let rejoined: String = parts.iter().map(|p| p.split_once('\n').unwrap().1).collect::<Vec<_>>().join(" ");
+        assert_eq!(rejoined.split_whitespace().count(), text.split_whitespace().count());
+
+        // No break to be found: cut mid-run, never inside a multi-byte char
+        let emoji = "🙂".repeat(100);
+        for part in split_message(&emoji, 64) {
+            assert!(part.len() <= 64);
+            assert!(part.split_once('\n').unwrap().1.chars().all(|c| c == '🙂'));
+        }
+    }
+
+    #[test]
+    fn upload_backoff_doubles_up_to_the_cap() {
+        assert_eq!(upload_backoff(1), UPLOAD_BACKOFF);
+        assert_eq!(upload_backoff(2), UPLOAD_BACKOFF * 2);
+        assert_eq!(upload_backoff(40), UPLOAD_BACKOFF_MAX);
+    }
 }
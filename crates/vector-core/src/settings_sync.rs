@@ -0,0 +1,334 @@
+//! Cross-device settings sync — an encrypted snapshot of non-sensitive preferences.
+//!
+//! A fresh install should feel familiar immediately, so the preferences in [`SYNCED_KEYS`] (theme,
+//! accessibility, notification and privacy toggles) plus the blocked list ride a NIP-78 parameterized-replaceable
+//! event (kind 30078, `d=vector/settings`) NIP-44-encrypted to ourselves — the same transport as the
+//! Community List. Keys, seeds, relay credentials and device-specific values (sound files, download
+//! limits, Whisper models) are never synced.
+//!
+//! Conflict resolution is last-writer-wins PER KEY: every local write stamps its key (ms), and a merge
+//! keeps the newer stamp of each entry, so editing the theme on one device and a notification toggle on
+//! another both survive. Blocked entries are keyed `blocked:<npub>` with `"true"`/`"false"` so an unblock
+//! is an explicit, stamped entry rather than an absence.
+//!
+//! Per-chat state (read markers, mutes, nicknames) rides the same transport under its own `d`-tag; see
+//! [`crate::chat_state_sync`]. Both are a [`crate::sync_store::StampedStore`].
+
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::state::SessionGuard;
+use crate::stored_event::event_kind;
+use crate::sync_store::{StampedStore, StoreEntries};
+
+pub const SETTINGS_D_TAG: &str = "vector/settings";
+/// Per-key local write stamps (JSON `{key: ms}`).
+const STAMPS_KEY: &str = "settings_sync_stamps";
+const BLOCKED_PREFIX: &str = "blocked:";
+const FETCH_TIMEOUT_SECS: u64 = 15;
+
+/// The per-account settings keys that follow the user across devices.
+pub const SYNCED_KEYS: &[&str] = &[
+    "theme",
+    "notif_global_mute",
+    "notif_mute_everyone",
+    "notif_content_privacy",
+    "notif_short_body",
+    "web_previews",
+    "strip_tracking",
+    "send_typing_indicators",
+    "show_last_active",
+    "announcements_enabled",
+    "display_image_types",
+    "emoticon_suggestions",
+    "autocorrect",
+    "chat_bg_enabled",
+    "a11y_font_scale",
+    "a11y_reduced_motion",
+    "a11y_high_contrast",
+    "reaction_quickpicks",
+];
+
+pub fn is_synced_key(key: &str) -> bool {
+    SYNCED_KEYS.contains(&key)
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct SyncedValue {
+    pub value: String,
+    /// When the value was written, in ms. Zero = never stamped (loses to any real write).
+    pub updated_at: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
+pub struct SettingsSnapshot {
+    #[serde(default)]
+    pub entries: BTreeMap<String, SyncedValue>,
+}
+
+impl SettingsSnapshot {
+    /// Lenient parse: a malformed payload is an empty snapshot (never aborts a sync).
+    pub fn from_json(s: &str) -> Self {
+        serde_json::from_str(s).unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Last-writer-wins per key; an exact stamp tie breaks on the value so every device converges on
+    /// identical bytes regardless of merge order.
+    pub fn merge(&self, other: &SettingsSnapshot) -> SettingsSnapshot {
+        let mut entries = self.entries.clone();
+        for (key, theirs) in &other.entries {
+            let take = match entries.get(key) {
+                Some(ours) => (theirs.updated_at, &theirs.value) > (ours.updated_at, &ours.value),
+                None => true,
+            };
+            if take {
+                entries.insert(key.clone(), theirs.clone());
+            }
+        }
+        SettingsSnapshot { entries }
+    }
+
+    /// Entries in `self` that beat (or are missing from) `other` — what a publish would add.
+    pub fn newer_than(&self, other: &SettingsSnapshot) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|(key, ours)| other.entries.get(*key).is_none_or(|theirs| ours.updated_at > theirs.updated_at))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+}
+
+// ============================================================================
+// Local stamps + snapshot
+// ============================================================================
+
+static STORE: StampedStore = StampedStore::new(SETTINGS_D_TAG, STAMPS_KEY, "[SettingsSync]", 2, &Settings);
+
+/// Record a local change to a synced key (a [`SYNCED_KEYS`] setting or `blocked:<npub>`) and push it out
+/// shortly. Called from the write paths themselves, so every UI edit funnels through here.
+pub fn note_local_change(key: &str) {
+    STORE.note_local_change(key);
+}
+
+/// When `key` was last written on this device (or adopted from another), in ms. Zero = never.
+pub fn local_stamp(key: &str) -> u64 {
+    STORE.local_stamp(key)
+}
+
+pub fn blocked_key(npub: &str) -> String {
+    format!("{BLOCKED_PREFIX}{npub}")
+}
+
+/// This device's view: every synced setting that has a value, plus every blocked profile and every
+/// stamped unblock.
+pub async fn local_snapshot() -> SettingsSnapshot {
+    STORE.local_snapshot().await
+}
+
+struct Settings;
+
+#[async_trait::async_trait]
+impl StoreEntries for Settings {
+    async fn local_snapshot(&self, stamps: &HashMap<String, u64>) -> SettingsSnapshot {
+        let at = |key: &str| stamps.get(key).copied().unwrap_or(0);
+        let mut entries = BTreeMap::new();
+        for key in SYNCED_KEYS {
+            if let Ok(Some(value)) = crate::db::settings::get_sql_setting(key.to_string()) {
+                entries.insert(key.to_string(), SyncedValue { value, updated_at: at(key) });
+            }
+        }
+        for key in stamps.keys().filter(|k| k.starts_with(BLOCKED_PREFIX)) {
+            entries.insert(key.clone(), SyncedValue { value: "false".into(), updated_at: at(key) });
+        }
+        for profile in crate::profile::sync::get_blocked_users().await {
+            let key = blocked_key(&profile.id);
+            let updated_at = at(&key);
+            entries.insert(key, SyncedValue { value: "true".into(), updated_at });
+        }
+        SettingsSnapshot { entries }
+    }
+
+    async fn apply(
+        &self,
+        _session: &SessionGuard,
+        key: &str,
+        entry: &SyncedValue,
+        already: bool,
+        handler: &dyn crate::profile::sync::ProfileSyncHandler,
+    ) -> bool {
+        if let Some(npub) = key.strip_prefix(BLOCKED_PREFIX) {
+            if PublicKey::parse(npub).is_err() {
+                return false;
+            }
+            if !already {
+                if entry.value == "true" {
+                    crate::profile::sync::block_user(npub.to_string(), handler).await;
+                } else {
+                    crate::profile::sync::unblock_user(npub.to_string(), handler).await;
+                }
+            }
+            true
+        } else if is_synced_key(key) {
+            crate::db::settings::write_setting_unsynced(key, &entry.value).is_ok()
+        } else {
+            // A key from a newer client this build doesn't know — ignore it.
+            false
+        }
+    }
+}
+
+// ============================================================================
+// Transport (NIP-44-self-encrypted kind 30078, parameterized-replaceable)
+// ============================================================================
+
+pub(crate) async fn decrypt_snapshot(client: &Client, my_pk: &PublicKey, event: &Event) -> SettingsSnapshot {
+    try_decrypt_snapshot(client, my_pk, event).await.unwrap_or_else(|e| {
+        crate::log_warn!("[SettingsSync] {}", e);
+        SettingsSnapshot::default()
+    })
+}
+
+/// [`decrypt_snapshot`], but an unreadable snapshot is an error: publishing over it would replace
+/// entries we never saw.
+async fn try_decrypt_snapshot(client: &Client, my_pk: &PublicKey, event: &Event) -> Result<SettingsSnapshot, String> {
+    if event.content.is_empty() {
+        return Ok(SettingsSnapshot::default());
+    }
+    let signer = client.signer().await.map_err(|e| format!("Signer unavailable: {}", e))?;
+    let plaintext = signer.nip44_decrypt(my_pk, &event.content).await.map_err(|e| format!("decrypt failed: {}", e))?;
+    Ok(SettingsSnapshot::from_json(&plaintext))
+}
+
+/// Fetch `filter` from every connected relay, trusting only relays that answered to EOSE. nostr-sdk
+/// returns whatever it collected on a timeout, so an empty result can't tell "none published" from
+/// "no relay answered" — and for a replaceable list the first reading lets a local copy replace the
+/// real one. Here events count from any relay that answered, but an empty answer only when every
+/// relay gave it; anything less is an error.
+pub(crate) async fn fetch_confirmed(client: &Client, filter: Filter, timeout_secs: u64) -> Result<Vec<Event>, String> {
+    let timeout = std::time::Duration::from_secs(timeout_secs);
+    let urls: Vec<String> = client.relays().await
+        .iter()
+        .filter(|(_, relay)| relay.is_connected())
+        .map(|(url, _)| url.to_string())
+        .collect();
+    if urls.is_empty() {
+        return Err("No relays connected".to_string());
+    }
+    let answers = futures_util::future::join_all(urls.iter().map(|url| {
+        crate::community::transport::fetch_relay_eose(client, url, filter.clone(), timeout)
+    })).await;
+    let answered = answers.iter().filter(|a| a.is_ok()).count();
+    let events: Vec<Event> = answers.into_iter().flatten().flatten().collect();
+    if answered == 0 || (events.is_empty() && answered < urls.len()) {
+        return Err(format!("only {} of {} relays answered", answered, urls.len()));
+    }
+    Ok(events)
+}
+
+/// Fetch and decrypt our snapshot under `d_tag` (an empty snapshot when the relays confirm there's none
+/// yet). A fetch no relay answered, or a snapshot we can't read, is an error, so callers never publish a
+/// local snapshot over a relay copy they didn't see.
+pub(crate) async fn fetch_remote(client: &Client, my_pk: PublicKey, d_tag: &str) -> Result<SettingsSnapshot, String> {
+    let filter = Filter::new()
+        .author(my_pk)
+        .kind(Kind::Custom(event_kind::APPLICATION_SPECIFIC))
+        .identifier(d_tag)
+        .limit(1);
+    let events = fetch_confirmed(client, filter, FETCH_TIMEOUT_SECS)
+        .await
+        .map_err(|e| format!("fetch {} (kind 30078): {}", d_tag, e))?;
+    match events.into_iter().max_by_key(|e| e.created_at) {
+        Some(ev) => try_decrypt_snapshot(client, &my_pk, &ev).await.map_err(|e| format!("{}: {}", d_tag, e)),
+        None => Ok(SettingsSnapshot::default()),
+    }
+}
+
+/// Encrypt `snapshot` to ourselves and publish it under `d_tag`, replacing the previous copy.
+pub(crate) async fn publish(client: &Client, my_pk: PublicKey, d_tag: &str, snapshot: &SettingsSnapshot) -> Result<(), String> {
+    let signer = client.signer().await.map_err(|e| format!("Signer unavailable: {}", e))?;
+    let content = signer
+        .nip44_encrypt(&my_pk, &snapshot.to_json())
+        .await
+        .map_err(|e| format!("nip44 encrypt {}: {}", d_tag, e))?;
+    let builder = EventBuilder::new(Kind::Custom(event_kind::APPLICATION_SPECIFIC), content)
+        .tag(Tag::identifier(d_tag));
+    client
+        .send_event_builder(builder)
+        .await
+        .map_err(|e| format!("Failed to publish {} (kind 30078): {}", d_tag, e))?;
+    Ok(())
+}
+
+/// Outcome of a [`sync_now`] round.
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct SettingsSyncReport {
+    /// Keys this device adopted from another one.
+    pub applied: Vec<String>,
+    /// Whether our own newer entries were published.
+    pub published: bool,
+}
+
+/// Full round: fetch the relay snapshot, adopt its newer entries, and publish the merge when this device
+/// holds anything newer. Emits `settings_synced` with the adopted keys so the UI can re-read them.
+pub async fn sync_now(handler: &dyn crate::profile::sync::ProfileSyncHandler) -> Result<SettingsSyncReport, String> {
+    let report = STORE.sync_now(handler).await?;
+    if !report.applied.is_empty() {
+        crate::traits::emit_event("settings_synced", &report.applied);
+    }
+    Ok(report)
+}
+
+/// Live cross-device path: adopt a snapshot another device published. Never republishes (the relay
+/// echoes our own publishes back on the same subscription).
+pub async fn ingest_remote_event(event: &Event, handler: &dyn crate::profile::sync::ProfileSyncHandler) {
+    let applied = STORE.ingest_remote_event(event, handler).await;
+    if !applied.is_empty() {
+        crate::traits::emit_event("settings_synced", &applied);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snap(entries: &[(&str, &str, u64)]) -> SettingsSnapshot {
+        SettingsSnapshot {
+            entries: entries
+                .iter()
+                .map(|(k, v, at)| (k.to_string(), SyncedValue { value: v.to_string(), updated_at: *at }))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn merge_is_last_writer_wins_per_key() {
+        let a = snap(&[("theme", "pivx", 10), ("web_previews", "false", 50), ("blocked:npub1x", "true", 5)]);
+        let b = snap(&[("theme", "satoshi", 20), ("web_previews", "true", 40), ("blocked:npub1x", "false", 9)]);
+
+        let merged = a.merge(&b);
+        assert_eq!(merged.entries["theme"].value, "satoshi");
+        assert_eq!(merged.entries["web_previews"].value, "false");
+        assert_eq!(merged.entries["blocked:npub1x"].value, "false", "a later unblock beats an earlier block");
+
+        // Order-independent, including exact stamp ties.
+        assert_eq!(merged, b.merge(&a));
+        let tie_a = snap(&[("theme", "pivx", 7)]);
+        let tie_b = snap(&[("theme", "monero", 7)]);
+        assert_eq!(tie_a.merge(&tie_b), tie_b.merge(&tie_a));
+
+        assert_eq!(a.newer_than(&b), vec!["web_previews".to_string()]);
+        assert!(merged.newer_than(&merged).is_empty());
+    }
+
+    #[test]
+    fn malformed_payload_is_empty() {
+        assert_eq!(SettingsSnapshot::from_json("not json"), SettingsSnapshot::default());
+        let s = snap(&[("theme", "vector", 1)]);
+        assert_eq!(SettingsSnapshot::from_json(&s.to_json()), s);
+    }
+}
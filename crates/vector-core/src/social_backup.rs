@@ -0,0 +1,587 @@
+//! Social backup — the seed phrase split into Shamir shares held by trusted contacts.
+//!
+//! **Opt-in and dangerous by design.** Whoever gathers `threshold` shares can rebuild the seed and
+//! take the account, so the UI must spell out: pick guardians who don't know each other, never fewer
+//! than two, and re-run the backup (new shares, new backup id) if a guardian is no longer trusted.
+//!
+//! - **Split**: the mnemonic is framed into a fixed [`FRAME_LEN`]-byte block (so shares don't leak
+//!   the phrase length) and split byte-wise with Shamir's scheme over GF(256). Each share is encoded
+//!   as text with a checksum (see [`Share::encode`]) and gift-wrapped to one guardian.
+//! - **Hold**: a guardian's app stores the share (encrypted at rest, latest backup per owner).
+//! - **Recover**: a device with no account generates a throwaway key, asks each guardian for its
+//!   share (at their NIP-17 inbox relays, advertising Vector's trusted relays as the throwaway
+//!   key's own inbox for the reply) and shows a verification code. A guardian releases only after explicitly approving — and
+//!   should confirm the code with the owner out-of-band. Once `threshold` consistent shares arrive
+//!   the seed is rebuilt, checked against the digest every share carries, and only handed back if
+//!   it derives the account being recovered.
+//!
+//! Wire: gift-wrapped kind-30078 rumors, `d` = [`D_SHARE`] (owner → guardian), [`D_REQUEST`]
+//! (recovering device → guardian) or [`D_RELEASE`] (guardian → recovering device).
+
+use nostr_sdk::prelude::*;
+use rand::{Rng, RngCore};
+use sha2::{Digest, Sha256};
+use std::sync::LazyLock;
+use zeroize::Zeroizing;
+
+pub const D_SHARE: &str = "vector-backup-share";
+pub const D_REQUEST: &str = "vector-backup-request";
+pub const D_RELEASE: &str = "vector-backup-release";
+const SHARE_PREFIX: &str = "vshare1";
+/// Framed secret size: `[len][secret][zero padding]`.
+const FRAME_LEN: usize = 256;
+pub const MIN_THRESHOLD: u8 = 2;
+pub const MAX_GUARDIANS: usize = 10;
+/// Owner-side record of the last backup (who holds shares, the threshold).
+const BACKUP_META_KEY: &str = "social_backup";
+
+// ============================================================================
+// GF(256) Shamir
+// ============================================================================
+
+/// Multiply in GF(2^8) with the AES polynomial (x^8 + x^4 + x^3 + x + 1).
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// Multiplicative inverse (a^254). Only ever called with a non-zero `a`.
+fn gf_inv(a: u8) -> u8 {
+    let (mut result, mut base, mut exp) = (1u8, a, 254u8);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = gf_mul(result, base);
+        }
+        base = gf_mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+fn digest4(secret: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(secret);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// One guardian's share of a backup.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Share {
+    pub threshold: u8,
+    /// The x-coordinate, 1..=255.
+    pub index: u8,
+    /// Random id shared by every share of one backup — mixing backups is refused.
+    pub backup_id: [u8; 8],
+    /// First 4 bytes of SHA-256(secret): confirms a reconstruction is right.
+    pub check: [u8; 4],
+    pub data: Vec<u8>,
+}
+
+fn text_checksum(body: &str) -> String {
+    crate::simd::hex::bytes_to_hex_string(&Sha256::digest(body.as_bytes())[..4])
+}
+
+impl Share {
+    pub fn backup_id_hex(&self) -> String {
+        crate::simd::hex::bytes_to_hex_string(&self.backup_id)
+    }
+
+    /// `vshare1:<threshold>:<index>:<backup id>:<check>:<data>:<checksum>`, all hex. The trailing
+    /// checksum covers everything before it, so a share copied by hand with a typo is rejected rather
+    /// than silently corrupting the recovered seed.
+    pub fn encode(&self) -> String {
+        let body = format!(
+            "{SHARE_PREFIX}:{}:{}:{}:{}:{}",
+            self.threshold,
+            self.index,
+            self.backup_id_hex(),
+            crate::simd::hex::bytes_to_hex_string(&self.check),
+            crate::simd::hex::bytes_to_hex_string(&self.data),
+        );
+        let checksum = text_checksum(&body);
+        format!("{body}:{checksum}")
+    }
+
+    pub fn decode(s: &str) -> Result<Share, String> {
+        let s = s.trim();
+        let (body, checksum) = s.rsplit_once(':').ok_or("Not a Vector backup share")?;
+        let parts: Vec<&str> = body.split(':').collect();
+        if parts.len() != 6 || parts[0] != SHARE_PREFIX {
+            return Err("Not a Vector backup share".into());
+        }
+        if text_checksum(body) != checksum {
+            return Err("Share checksum mismatch (mistyped or corrupted)".into());
+        }
+        let threshold: u8 = parts[1].parse().map_err(|_| "Invalid share threshold")?;
+        let index: u8 = parts[2].parse().map_err(|_| "Invalid share index")?;
+        if threshold < MIN_THRESHOLD || index == 0 {
+            return Err("Invalid share parameters".into());
+        }
+        let fixed = |hex: &str, len: usize| {
+            crate::simd::hex::hex_string_to_bytes_checked(hex).filter(|b| b.len() == len)
+        };
+        let backup_id = fixed(parts[3], 8).ok_or("Invalid backup id")?;
+        let check = fixed(parts[4], 4).ok_or("Invalid share digest")?;
+        let data = fixed(parts[5], FRAME_LEN).ok_or("Invalid share data")?;
+        Ok(Share {
+            threshold,
+            index,
+            backup_id: backup_id.try_into().map_err(|_| "Invalid backup id")?,
+            check: check.try_into().map_err(|_| "Invalid share digest")?,
+            data,
+        })
+    }
+}
+
+/// Split `secret` into `count` shares, any `threshold` of which rebuild it.
+pub fn split(secret: &[u8], threshold: u8, count: u8) -> Result<Vec<Share>, String> {
+    if threshold < MIN_THRESHOLD || threshold > count {
+        return Err(format!("Threshold must be between {} and the number of guardians", MIN_THRESHOLD));
+    }
+    if secret.is_empty() || secret.len() >= FRAME_LEN {
+        return Err("Secret has an unsupported length".into());
+    }
+    let mut frame = Zeroizing::new(vec![0u8; FRAME_LEN]);
+    frame[0] = secret.len() as u8;
+    frame[1..=secret.len()].copy_from_slice(secret);
+
+    let mut rng = rand::thread_rng();
+    let mut backup_id = [0u8; 8];
+    rng.fill_bytes(&mut backup_id);
+    let check = digest4(secret);
+
+    let mut shares: Vec<Share> = (1..=count)
+        .map(|index| Share { threshold, index, backup_id, check, data: Vec::with_capacity(FRAME_LEN) })
+        .collect();
+    let mut coeffs = Zeroizing::new(vec![0u8; threshold as usize]);
+    for &byte in frame.iter() {
+        coeffs[0] = byte;
+        rng.fill_bytes(&mut coeffs[1..]);
+        for share in &mut shares {
+            // Horner's rule at x = index.
+            let y = coeffs.iter().rev().fold(0u8, |acc, &c| gf_mul(acc, share.index) ^ c);
+            share.data.push(y);
+        }
+    }
+    Ok(shares)
+}
+
+/// Rebuild the secret from at least `threshold` shares of ONE backup, verifying it against the digest.
+pub fn combine(shares: &[Share]) -> Result<Zeroizing<Vec<u8>>, String> {
+    let first = shares.first().ok_or("No shares")?;
+    if shares.iter().any(|s| s.backup_id != first.backup_id || s.threshold != first.threshold || s.check != first.check) {
+        return Err("Shares come from different backups".into());
+    }
+    let mut unique: Vec<&Share> = Vec::new();
+    for share in shares {
+        if !unique.iter().any(|u| u.index == share.index) {
+            unique.push(share);
+        }
+    }
+    if unique.len() < first.threshold as usize {
+        return Err(format!("Need {} shares, have {}", first.threshold, unique.len()));
+    }
+    let used = &unique[..first.threshold as usize];
+
+    // Lagrange basis at x = 0 (addition and subtraction are both XOR in GF(256)).
+    let basis: Vec<u8> = used.iter().map(|si| {
+        used.iter().filter(|sj| sj.index != si.index).fold(1u8, |acc, sj| {
+            gf_mul(acc, gf_mul(sj.index, gf_inv(sj.index ^ si.index)))
+        })
+    }).collect();
+    let mut frame = Zeroizing::new(vec![0u8; FRAME_LEN]);
+    for (pos, out) in frame.iter_mut().enumerate() {
+        *out = used.iter().zip(&basis).fold(0u8, |acc, (s, &l)| acc ^ gf_mul(s.data[pos], l));
+    }
+
+    let len = frame[0] as usize;
+    if len == 0 || len >= FRAME_LEN {
+        return Err("Shares don't reconstruct a valid backup".into());
+    }
+    let secret = Zeroizing::new(frame[1..=len].to_vec());
+    if digest4(&secret) != first.check {
+        return Err("Shares don't reconstruct a valid backup".into());
+    }
+    Ok(secret)
+}
+
+// ============================================================================
+// Owner side: create + distribute
+// ============================================================================
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct GuardianStatus {
+    pub npub: String,
+    pub delivered: bool,
+}
+
+/// What the owner keeps about their latest backup — never the shares themselves.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct BackupInfo {
+    pub backup_id: String,
+    pub threshold: u8,
+    pub guardians: Vec<GuardianStatus>,
+    pub created_at: u64,
+}
+
+pub fn backup_info() -> Option<BackupInfo> {
+    crate::db::get_sql_setting(BACKUP_META_KEY.to_string())
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+}
+
+/// Split `seed` and gift-wrap one share to each guardian. The seed must be a valid BIP-39 phrase.
+/// Shares are never stored locally; a guardian that couldn't be reached shows `delivered: false`
+/// and the backup should be re-run.
+pub async fn create_backup(seed: &str, guardians: &[String], threshold: u8) -> Result<BackupInfo, String> {
+    let seed = Zeroizing::new(seed.trim().to_string());
+    bip39::Mnemonic::parse(seed.as_str()).map_err(|_| "Invalid seed phrase")?;
+    if guardians.is_empty() || guardians.len() > MAX_GUARDIANS {
+        return Err(format!("Choose between {} and {} guardians", MIN_THRESHOLD, MAX_GUARDIANS));
+    }
+    let client = crate::state::nostr_client().ok_or("Nostr client not initialized")?;
+    let my_pk = crate::state::my_public_key().ok_or("Not logged in")?;
+    let mut recipients = Vec::with_capacity(guardians.len());
+    for npub in guardians {
+        let pk = PublicKey::parse(npub).map_err(|_| format!("Invalid guardian: {}", npub))?;
+        if pk == my_pk {
+            return Err("You can't be your own guardian".into());
+        }
+        if recipients.contains(&pk) {
+            return Err("Each guardian can only be chosen once".into());
+        }
+        recipients.push(pk);
+    }
+
+    let shares = split(seed.as_bytes(), threshold, recipients.len() as u8)?;
+    let backup_id = shares[0].backup_id_hex();
+    let mut statuses = Vec::with_capacity(recipients.len());
+    for (share, pk) in shares.iter().zip(&recipients) {
+        let encoded = Zeroizing::new(share.encode());
+        let rumor = EventBuilder::new(Kind::ApplicationSpecificData, encoded.as_str())
+            .tag(Tag::identifier(D_SHARE))
+            .build(my_pk);
+        let delivered = crate::inbox_relays::send_gift_wrap(&client, pk, rumor, []).await.is_ok();
+        statuses.push(GuardianStatus { npub: pk.to_bech32().unwrap_or_default(), delivered });
+    }
+
+    let info = BackupInfo {
+        backup_id,
+        threshold,
+        guardians: statuses,
+        created_at: Timestamp::now().as_secs(),
+    };
+    if let Ok(json) = serde_json::to_string(&info) {
+        crate::db::set_sql_setting(BACKUP_META_KEY.to_string(), json)?;
+    }
+    Ok(info)
+}
+
+// ============================================================================
+// Guardian side: hold shares, approve releases
+// ============================================================================
+
+#[derive(serde::Deserialize)]
+struct RequestBody {
+    owner: String,
+    code: String,
+}
+
+/// Route an inbound social-backup rumor (already unwrapped; `sender_npub` is the seal author).
+pub async fn handle_incoming(d_tag: &str, content: &str, sender_npub: &str, created_at: u64) {
+    match d_tag {
+        D_SHARE => {
+            // The owner hands out their own shares, so the sender IS the owner.
+            let Ok(share) = Share::decode(content) else { return };
+            if crate::db::social_backup::save_held_share(sender_npub, &share.backup_id_hex(), content.trim(), created_at).await.is_ok() {
+                crate::traits::emit_event("social_backup_share_received", &serde_json::json!({ "owner": sender_npub }));
+            }
+        }
+        D_REQUEST => {
+            let Ok(body) = serde_json::from_str::<RequestBody>(content) else { return };
+            let Some(owner) = PublicKey::parse(&body.owner).ok().and_then(|pk| pk.to_bech32().ok()) else { return };
+            if body.code.len() != 6 || !body.code.bytes().all(|b| b.is_ascii_digit()) {
+                return;
+            }
+            // Only surface requests for owners we actually hold a share for.
+            if !crate::db::social_backup::list_held_shares()
+                .map(|held| held.iter().any(|h| h.owner_npub == owner))
+                .unwrap_or(false)
+            {
+                return;
+            }
+            let req = crate::db::social_backup::RecoveryRequest {
+                requester_npub: sender_npub.to_string(),
+                owner_npub: owner,
+                code: body.code,
+                requested_at: created_at,
+            };
+            if crate::db::social_backup::save_request(&req).is_ok() {
+                crate::traits::emit_event("social_backup_request", &req);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Release the share held for a request's owner to the requesting device, then drop the request.
+pub async fn approve_request(requester_npub: &str) -> Result<(), String> {
+    let req = crate::db::social_backup::get_request(requester_npub)?.ok_or("Request not found")?;
+    let share = Zeroizing::new(
+        crate::db::social_backup::get_held_share(&req.owner_npub).await?.ok_or("No share held for this contact")?,
+    );
+    let client = crate::state::nostr_client().ok_or("Nostr client not initialized")?;
+    let my_pk = crate::state::my_public_key().ok_or("Not logged in")?;
+    let requester = PublicKey::parse(requester_npub).map_err(|e| e.to_string())?;
+    let rumor = EventBuilder::new(Kind::ApplicationSpecificData, share.as_str())
+        .tag(Tag::identifier(D_RELEASE))
+        .build(my_pk);
+    crate::inbox_relays::send_gift_wrap(&client, &requester, rumor, []).await?;
+    crate::db::social_backup::remove_request(requester_npub)
+}
+
+pub fn deny_request(requester_npub: &str) -> Result<(), String> {
+    crate::db::social_backup::remove_request(requester_npub)
+}
+
+// ============================================================================
+// Recovering device (no account yet)
+// ============================================================================
+
+struct Recovery {
+    client: Client,
+    /// The account being recovered; the rebuilt seed must derive it.
+    owner: PublicKey,
+    guardians: Vec<PublicKey>,
+    shares: Vec<Share>,
+}
+
+static RECOVERY: LazyLock<tokio::sync::Mutex<Option<Recovery>>> =
+    LazyLock::new(|| tokio::sync::Mutex::new(None));
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct RecoveryStatus {
+    pub active: bool,
+    pub collected: usize,
+    /// Known once the first share arrives (0 until then).
+    pub threshold: u8,
+}
+
+fn recovery_status_locked(recovery: &Option<Recovery>) -> RecoveryStatus {
+    match recovery {
+        Some(r) => RecoveryStatus {
+            active: true,
+            collected: r.shares.len(),
+            threshold: r.shares.first().map_or(0, |s| s.threshold),
+        },
+        None => RecoveryStatus::default(),
+    }
+}
+
+pub async fn recovery_status() -> RecoveryStatus {
+    recovery_status_locked(&RECOVERY.lock().await)
+}
+
+/// Start asking `guardians` for the shares of `owner_npub`'s backup from a throwaway key. Returns the
+/// 6-digit code the owner reads to each guardian so they can tell this request apart from a forgery.
+pub async fn start_recovery(owner_npub: &str, guardians: &[String]) -> Result<String, String> {
+    let owner = PublicKey::parse(owner_npub).map_err(|_| "Invalid account npub")?;
+    let guardians: Vec<PublicKey> = guardians
+        .iter()
+        .map(|g| PublicKey::parse(g).map_err(|_| format!("Invalid guardian: {}", g)))
+        .collect::<Result<_, _>>()?;
+    if guardians.len() < MIN_THRESHOLD as usize {
+        return Err(format!("Ask at least {} guardians", MIN_THRESHOLD));
+    }
+    cancel_recovery().await;
+
+    let keys = Keys::generate();
+    let client = Client::builder().signer(keys.clone()).build();
+    for url in crate::state::TRUSTED_RELAYS {
+        let _ = client.add_relay(*url).await;
+    }
+    // Targeted-only, for finding the guardians' inbox lists and publishing ours
+    for url in crate::state::discovery_relay_iter() {
+        let _ = client.pool().add_relay(url, crate::discovery_relay_options()).await;
+    }
+    client.connect().await;
+
+    // Our inbox is where we listen, so the guardians' releases come back here
+    let inbox = crate::state::TRUSTED_RELAYS.iter().fold(EventBuilder::new(Kind::Custom(10050), ""), |b, url| {
+        b.tag(Tag::custom(TagKind::custom("relay"), vec![*url]))
+    });
+    if let Ok(event) = client.sign_event_builder(inbox).await {
+        let _ = client.send_event(&event).await;
+        let discovery: Vec<RelayUrl> = crate::state::DISCOVERY_RELAYS.iter().filter_map(|s| RelayUrl::parse(s).ok()).collect();
+        let _ = client.send_event_to(discovery, &event).await;
+    }
+
+    let code = format!("{:06}", rand::thread_rng().gen_range(0..1_000_000u32));
+    let body = serde_json::json!({ "owner": owner.to_bech32().unwrap_or_default(), "code": code }).to_string();
+    let mut sent = 0;
+    for guardian in &guardians {
+        let rumor = EventBuilder::new(Kind::ApplicationSpecificData, body.clone())
+            .tag(Tag::identifier(D_REQUEST))
+            .build(keys.public_key());
+        if crate::inbox_relays::send_gift_wrap(&client, guardian, rumor, []).await.is_ok() {
+            sent += 1;
+        }
+    }
+    if sent == 0 {
+        client.shutdown().await;
+        return Err("Couldn't reach any guardian".into());
+    }
+
+    let filter = Filter::new().kind(Kind::GiftWrap).pubkey(keys.public_key());
+    client.subscribe(filter, None).await.map_err(|e| e.to_string())?;
+    let mut notifications = client.notifications();
+    let listener = client.clone();
+    *RECOVERY.lock().await = Some(Recovery { client, owner, guardians, shares: Vec::new() });
+
+    tokio::spawn(async move {
+        while let Ok(notification) = notifications.recv().await {
+            let RelayPoolNotification::Event { event, .. } = notification else { continue };
+            let Ok(unwrapped) = listener.unwrap_gift_wrap(&event).await else { continue };
+            let mut guard = RECOVERY.lock().await;
+            let Some(recovery) = guard.as_mut() else { return };
+            if !recovery.guardians.contains(&unwrapped.sender)
+                || unwrapped.rumor.tags.identifier() != Some(D_RELEASE)
+            {
+                continue;
+            }
+            let Ok(share) = Share::decode(&unwrapped.rumor.content) else { continue };
+            // Stick to the first backup seen; ignore duplicates of an index.
+            if recovery.shares.first().is_some_and(|s| s.backup_id != share.backup_id)
+                || recovery.shares.iter().any(|s| s.index == share.index)
+            {
+                continue;
+            }
+            recovery.shares.push(share);
+            let status = recovery_status_locked(&guard);
+            crate::traits::emit_event("social_recovery_progress", &status);
+        }
+    });
+    Ok(code)
+}
+
+/// Rebuild the seed once enough shares arrived, ending the recovery session on success. Shares don't
+/// name their owner, so a guardian holding someone else's backup could hand over that seed instead:
+/// one that doesn't derive the account asked for (with `passphrase`, the BIP-39 "25th word" the
+/// backup doesn't carry) is refused and the session stays open for another try.
+pub async fn finish_recovery(passphrase: Option<&str>) -> Result<String, String> {
+    let mut guard = RECOVERY.lock().await;
+    let recovery = guard.as_ref().ok_or("No recovery in progress")?;
+    let secret = combine(&recovery.shares)?;
+    let seed = String::from_utf8(secret.to_vec()).map_err(|_| "Recovered data is not a seed phrase")?;
+    if seed_owner(&seed, passphrase)? != recovery.owner {
+        return Err(match passphrase {
+            Some(_) => "The recovered seed phrase and this passphrase belong to a different account",
+            None => "The recovered seed phrase belongs to a different account",
+        }.into());
+    }
+    if let Some(recovery) = guard.take() {
+        recovery.client.shutdown().await;
+    }
+    Ok(seed)
+}
+
+/// The account `seed` (with its optional `passphrase`) derives.
+fn seed_owner(seed: &str, passphrase: Option<&str>) -> Result<PublicKey, String> {
+    let passphrase = passphrase.filter(|p| !p.is_empty()).map(str::to_string);
+    Keys::from_mnemonic(seed.to_string(), passphrase)
+        .map(|keys| keys.public_key())
+        .map_err(|_| "Recovered data is not a seed phrase".to_string())
+}
+
+pub async fn cancel_recovery() {
+    if let Some(recovery) = RECOVERY.lock().await.take() {
+        recovery.client.shutdown().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn a_passphrase_derives_another_account() {
+        let plain = seed_owner(SEED, None).unwrap();
+        assert_eq!(seed_owner(SEED, Some("")).unwrap(), plain, "an empty passphrase is no passphrase");
+        assert_ne!(seed_owner(SEED, Some("25th word")).unwrap(), plain);
+        assert!(seed_owner("not a seed phrase", None).is_err());
+    }
+
+    #[test]
+    fn gf256_inverse_roundtrips() {
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1, "inverse of {a}");
+        }
+    }
+
+    #[test]
+    fn any_threshold_subset_rebuilds_the_seed() {
+        let shares = split(SEED.as_bytes(), 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert!(shares.iter().all(|s| s.data.len() == FRAME_LEN), "fixed size hides the phrase length");
+
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1], [1, 3, 4]] {
+            let picked: Vec<Share> = subset.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(combine(&picked).unwrap().as_slice(), SEED.as_bytes());
+        }
+
+        // Below threshold is refused outright; a duplicated index doesn't count twice.
+        assert!(combine(&shares[..2]).is_err());
+        assert!(combine(&[shares[0].clone(), shares[0].clone(), shares[1].clone()]).is_err());
+    }
+
+    #[test]
+    fn shares_from_different_backups_never_mix() {
+        let a = split(SEED.as_bytes(), 2, 3).unwrap();
+        let b = split(SEED.as_bytes(), 2, 3).unwrap();
+        assert!(combine(&[a[0].clone(), b[1].clone()]).is_err());
+
+        // A forged share with a matching header still fails the digest check.
+        let mut forged = a[1].clone();
+        forged.data[0] ^= 0x5a;
+        assert!(combine(&[a[0].clone(), forged]).is_err());
+    }
+
+    #[test]
+    fn share_encoding_roundtrips_and_catches_typos() {
+        let share = split(SEED.as_bytes(), 2, 2).unwrap().remove(0);
+        let encoded = share.encode();
+        assert!(encoded.starts_with("vshare1:2:1:"));
+        assert_eq!(Share::decode(&encoded).unwrap(), share);
+        assert_eq!(Share::decode(&format!("  {}\n", encoded)).unwrap(), share);
+
+        // Flip one data character: the checksum must reject it.
+        let pos = encoded.len() - 20;
+        let mut typo = encoded.clone().into_bytes();
+        typo[pos] = if typo[pos] == b'0' { b'1' } else { b'0' };
+        assert!(Share::decode(std::str::from_utf8(&typo).unwrap()).is_err());
+
+        assert!(Share::decode("vshare1:1:1:00:00:00:00").is_err());
+        assert!(Share::decode("nsec1notashare").is_err());
+    }
+
+    #[test]
+    fn split_rejects_bad_parameters() {
+        assert!(split(SEED.as_bytes(), 1, 3).is_err(), "threshold 1 would hand the seed to one guardian");
+        assert!(split(SEED.as_bytes(), 4, 3).is_err());
+        assert!(split(&[], 2, 3).is_err());
+        assert!(split(&[b'a'; FRAME_LEN], 2, 3).is_err());
+    }
+}
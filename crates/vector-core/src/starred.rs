@@ -0,0 +1,111 @@
+//! Starred messages — a local "save to keep" mark, gathered across chats as Saved Messages.
+//!
+//! A star never leaves this device: nothing is published and the sender isn't told. A starred
+//! message outlives its chat's Self-Destruct Timer (the sweep skips it) and its attachments are
+//! kept out of media-cache eviction. Unstarring hands it back to both, so a message already past
+//! its expiry goes on the next sweep. Deleting the message drops its star with it.
+
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::types::Message;
+
+/// Most starred messages returned at once.
+const MAX_LIST: usize = 500;
+
+/// A starred message and where it lives, for the Saved Messages view.
+#[derive(Serialize, Clone, Debug)]
+pub struct StarredMessage {
+    pub chat_id: String,
+    /// Secs.
+    pub starred_at: u64,
+    pub message: Message,
+}
+
+#[derive(Default)]
+struct Starred {
+    loaded: bool,
+    ids: HashSet<String>,
+}
+
+impl Starred {
+    /// Read the starred ids, once per session. Stays unloaded (and retries) until the account's
+    /// DB is open.
+    fn ensure_loaded(&mut self) {
+        if self.loaded {
+            return;
+        }
+        let Ok(conn) = crate::db::get_db_connection_guard_static() else { return };
+        let Ok(mut stmt) = conn.prepare("SELECT message_id FROM starred_messages") else { return };
+        let Ok(rows) = stmt.query_map([], |r| r.get::<_, String>(0)) else { return };
+        self.ids = rows.flatten().collect();
+        self.loaded = true;
+    }
+}
+
+static STARRED: LazyLock<Mutex<Starred>> = LazyLock::new(|| Mutex::new(Starred::default()));
+
+pub fn is_starred(message_id: &str) -> bool {
+    let Ok(mut starred) = STARRED.lock() else { return false };
+    starred.ensure_loaded();
+    starred.ids.contains(message_id)
+}
+
+/// Every starred message id.
+pub fn starred_ids() -> HashSet<String> {
+    let Ok(mut starred) = STARRED.lock() else { return HashSet::new() };
+    starred.ensure_loaded();
+    starred.ids.clone()
+}
+
+/// Star or unstar a stored message.
+pub fn set_starred(message_id: &str, star: bool) -> Result<(), String> {
+    {
+        let conn = crate::db::get_write_connection_guard_static()?;
+        if star {
+            conn.execute(
+                "INSERT OR IGNORE INTO starred_messages (message_id, starred_at)
+                 SELECT id, ?2 FROM events WHERE id = ?1",
+                params![message_id, crate::clock::now_secs() as i64],
+            ).map_err(|e| format!("Failed to star message: {}", e))?;
+            let stored: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM starred_messages WHERE message_id = ?1)",
+                params![message_id],
+                |r| r.get(0),
+            ).map_err(|e| format!("Failed to star message: {}", e))?;
+            if !stored {
+                return Err("Message not found".to_string());
+            }
+        } else {
+            conn.execute("DELETE FROM starred_messages WHERE message_id = ?1", params![message_id])
+                .map_err(|e| format!("Failed to unstar message: {}", e))?;
+        }
+    }
+    if let Ok(mut starred) = STARRED.lock() {
+        if star {
+            starred.ids.insert(message_id.to_string());
+        } else {
+            starred.ids.remove(message_id);
+        }
+    }
+    Ok(())
+}
+
+/// Starred messages across every chat, most recently starred first.
+pub async fn starred_messages(limit: Option<usize>) -> Result<Vec<StarredMessage>, String> {
+    let limit = limit.unwrap_or(MAX_LIST).min(MAX_LIST);
+    Ok(crate::db::events::get_starred_message_views(limit).await?
+        .into_iter()
+        .map(|(chat_id, starred_at, message)| StarredMessage { chat_id, starred_at, message })
+        .collect())
+}
+
+/// Forget the loaded stars — called on account swap.
+pub fn clear_session() {
+    if let Ok(mut starred) = STARRED.lock() {
+        *starred = Starred::default();
+    }
+}
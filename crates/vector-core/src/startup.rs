@@ -0,0 +1,93 @@
+//! Startup phase timing — how long each boot step took, kept for diagnostics.
+//!
+//! The boot path calls [`begin`] once per login, then [`mark`] as each phase completes and
+//! [`mark_first_emit`] when the chat list first reaches the UI. [`summary`] renders the last
+//! boot as a single line for the copied log bundle.
+
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct StartupPhase {
+    pub name: &'static str,
+    pub ms: u64,
+}
+
+#[derive(serde::Serialize, Clone, Debug, Default, PartialEq)]
+pub struct StartupTimings {
+    /// Phases in the order they finished (parallel phases overlap).
+    pub phases: Vec<StartupPhase>,
+    /// Milliseconds from [`begin`] until the chat list was first sent to the UI.
+    pub first_emit_ms: Option<u64>,
+    /// Whether that first emit came from the chat-list snapshot rather than a full load.
+    pub fast_path: bool,
+}
+
+static TIMINGS: LazyLock<Mutex<(Option<Instant>, StartupTimings)>> =
+    LazyLock::new(|| Mutex::new((None, StartupTimings::default())));
+
+/// Start timing a new boot, discarding the previous one.
+pub fn begin() {
+    let mut t = TIMINGS.lock().unwrap_or_else(|e| e.into_inner());
+    *t = (Some(Instant::now()), StartupTimings::default());
+}
+
+/// Record that `name` took `took`.
+pub fn mark(name: &'static str, took: Duration) {
+    let mut t = TIMINGS.lock().unwrap_or_else(|e| e.into_inner());
+    t.1.phases.push(StartupPhase { name, ms: took.as_millis() as u64 });
+}
+
+/// Record the first chat-list emit. Only the first call per boot counts.
+pub fn mark_first_emit(fast_path: bool) {
+    let mut t = TIMINGS.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(started) = t.0 else { return };
+    if t.1.first_emit_ms.is_none() {
+        t.1.first_emit_ms = Some(started.elapsed().as_millis() as u64);
+        t.1.fast_path = fast_path;
+    }
+}
+
+pub fn timings() -> StartupTimings {
+    TIMINGS.lock().unwrap_or_else(|e| e.into_inner()).1.clone()
+}
+
+/// One line describing the last boot, or `None` if nothing has been recorded yet.
+pub fn summary() -> Option<String> {
+    let t = timings();
+    if t.phases.is_empty() && t.first_emit_ms.is_none() {
+        return None;
+    }
+    let phases: Vec<String> = t.phases.iter().map(|p| format!("{}={}ms", p.name, p.ms)).collect();
+    let first = match t.first_emit_ms {
+        Some(ms) => format!("{}ms ({})", ms, if t.fast_path { "snapshot" } else { "full load" }),
+        None => "pending".to_string(),
+    };
+    Some(format!("[Startup] first emit {} | {}", first, phases.join(" ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_phases_and_only_the_first_emit() {
+        begin();
+        mark("db_open", Duration::from_millis(12));
+        mark_first_emit(true);
+        mark("chat_load", Duration::from_millis(40));
+        mark_first_emit(false);
+
+        let t = timings();
+        assert_eq!(t.phases.len(), 2);
+        assert_eq!(t.phases[1], StartupPhase { name: "chat_load", ms: 40 });
+        assert!(t.first_emit_ms.is_some());
+        assert!(t.fast_path, "a later full-load emit doesn't overwrite the first");
+
+        let line = summary().unwrap();
+        assert!(line.contains("snapshot") && line.contains("db_open=12ms"));
+
+        begin();
+        assert_eq!(summary(), None);
+    }
+}
@@ -12,6 +12,7 @@ use tokio::sync::Mutex;
 
 use crate::chat::{Chat, ChatType};
 use crate::compact::{CompactMessage, CompactAttachment, NpubInterner, NO_NPUB};
+use crate::db::events::ChatUnread;
 use crate::profile::{Profile, SlimProfile};
 use crate::types::{Message, Reaction};
 use crate::traits::emit_event;
@@ -281,20 +282,32 @@ pub static MY_PUBLIC_KEY: LazyLock<RwLock<Option<PublicKey>>> =
 /// Get a clone of the active Nostr client. The clone is cheap — `Client`
 /// is internally `Arc`-counted, so all clones share connections, signers,
 /// and subscription state. Returns `None` when no session is active.
+/// Inside an [`AppContext::scope`](crate::context::AppContext::scope), the
+/// scoped client.
 #[inline]
 pub fn nostr_client() -> Option<Client> {
+    if let Some((client, _)) = crate::context::scoped_session() {
+        return client;
+    }
     NOSTR_CLIENT.read().unwrap().as_ref().cloned()
 }
 
 /// Returns `true` when there is an active session (client + pubkey set).
 #[inline]
 pub fn has_active_session() -> bool {
+    if let Some((client, _)) = crate::context::scoped_session() {
+        return client.is_some();
+    }
     NOSTR_CLIENT.read().unwrap().is_some()
 }
 
 /// Get the active user's public key. `PublicKey` is `Copy`, so this is by-value.
+/// Scoped like [`nostr_client`].
 #[inline]
 pub fn my_public_key() -> Option<PublicKey> {
+    if let Some((_, pk)) = crate::context::scoped_session() {
+        return pk;
+    }
     *MY_PUBLIC_KEY.read().unwrap()
 }
 
@@ -626,6 +639,11 @@ pub fn open_processing_gate() { PROCESSING_GATE.store(true, Ordering::Release);
 // ChatState
 // ============================================================================
 
+/// Most messages one chat keeps in RAM; older history pages in from SQL on demand.
+pub const MAX_CHAT_MESSAGES_IN_RAM: usize = 1_000;
+/// Newest messages a chat always keeps in RAM, however far back the reader pages.
+pub const PINNED_TAIL_MESSAGES: usize = 100;
+
 #[derive(Clone, Debug)]
 pub struct ChatState {
     pub profiles: Vec<Profile>,
@@ -633,12 +651,13 @@ pub struct ChatState {
     pub interner: NpubInterner,
     pub is_syncing: bool,
     pub db_loaded: bool,
-    /// Authoritative per-chat RAW unread counts (chat_identifier → count), so the badge recount is an
-    /// in-RAM fold instead of a whole-DB scan. Seeded once per account from `db::unread_counts` (see
-    /// `unread_seeded`), then kept current per-chat: cleared on read, reconciled from the DB
-    /// (`db::unread_count_for_chat`) on inbound / delete / mark-unread. RAW = pre muted/blocked
-    /// filter; the sum applies those, matching `sum_unread_from`. Cleared on account reset.
-    pub unread_cache: std::collections::HashMap<String, u32>,
+    /// Authoritative per-chat RAW unread (chat_identifier → count, mentions, oldest unread), so the
+    /// badge recount and the chat list's badges are in-RAM reads instead of whole-DB scans. Seeded once
+    /// per account from `db::unread_summaries` (see `unread_seeded`), then kept current per-chat:
+    /// cleared on read, reconciled from the DB (`db::unread_summary_for_chat`) on inbound / delete /
+    /// mark-unread. RAW = pre muted/blocked filter; the sum applies those, matching `sum_unread_from`.
+    /// Cleared on account reset.
+    pub unread_cache: std::collections::HashMap<String, ChatUnread>,
     /// False until `unread_cache` has been seeded from the DB for this account. Guards the one-time
     /// seed so the full-scan query runs once per login, never per message.
     pub unread_seeded: bool,
@@ -668,7 +687,7 @@ impl ChatState {
     pub fn merge_db_profiles(&mut self, slim_profiles: Vec<SlimProfile>, my_npub: &str) {
         for slim in slim_profiles {
             let mut full_profile = slim.to_profile();
-            full_profile.flags.set_mine(slim.id == my_npub);
+            full_profile.flags.set_mine(crate::ids::same_user(&slim.id, my_npub));
             self.insert_or_replace_profile(&slim.id, full_profile);
         }
     }
@@ -791,7 +810,7 @@ impl ChatState {
             let added = self.chats[idx].add_compact_message(compact);
             (added, idx)
         } else {
-            let mut chat = if chat_id.starts_with("npub1") {
+            let mut chat = if crate::ids::is_dm_chat_id(chat_id) {
                 Chat::new_dm(chat_id.to_string(), &mut self.interner)
             } else {
                 Chat::new(chat_id.to_string(), ChatType::Community, vec![])
@@ -824,7 +843,7 @@ impl ChatState {
         let chat_idx = if let Some(idx) = self.chats.iter().position(|c| c.id == chat_id) {
             idx
         } else {
-            let chat = if chat_id.starts_with("npub1") {
+            let chat = if crate::ids::is_dm_chat_id(chat_id) {
                 Chat::new_dm(chat_id.to_string(), &mut self.interner)
             } else {
                 Chat::new(chat_id.to_string(), ChatType::Community, vec![])
@@ -849,10 +868,31 @@ impl ChatState {
         added
     }
 
+    /// Keep a chat's in-RAM history bounded after an older page came in (`db::events::get_messages_before`):
+    /// past [`MAX_CHAT_MESSAGES_IN_RAM`], the middle goes. The oldest messages (where the reader is paging)
+    /// and the newest [`PINNED_TAIL_MESSAGES`] (the live tail: chat list preview, replies and reactions
+    /// to recent messages) stay. Dropped messages are still in the DB. Returns how many were dropped.
+    pub fn bound_chat_messages(&mut self, chat_id: &str) -> usize {
+        let Some(chat) = self.chats.iter_mut().find(|c| c.id == chat_id) else { return 0 };
+        let total = chat.message_count();
+        if total <= MAX_CHAT_MESSAGES_IN_RAM {
+            return 0;
+        }
+        let excess = total - MAX_CHAT_MESSAGES_IN_RAM;
+        let middle_end = total - PINNED_TAIL_MESSAGES;
+        chat.messages.drain(middle_end - excess..middle_end);
+        chat.messages.rebuild_index();
+        excess
+    }
+
     /// Add a message to a participant's DM chat. Creates profile if missing.
     ///
     /// Unlike the src-tauri version, emitting `profile_update` is the caller's responsibility.
     pub fn add_message_to_participant(&mut self, their_npub: &str, message: &Message) -> bool {
+        // The device-drop chat has no other party to give a profile
+        if their_npub == crate::device_drop::CHAT_ID {
+            return self.add_message_to_chat(their_npub, message);
+        }
         let id = self.interner.intern(their_npub);
         if self.get_profile_by_id(id).is_none() {
             let profile = Profile::new();
@@ -1030,9 +1070,13 @@ impl ChatState {
     /// rather than walking in-memory messages — so it's correct even when only the last message per
     /// chat is in RAM (the boot state). Muted chats and blocked-DM contacts contribute 0.
     pub fn sum_unread_from(&self, counts: &std::collections::HashMap<String, u32>) -> u32 {
+        self.sum_unread_by(|id| counts.get(id).copied().unwrap_or(0))
+    }
+
+    fn sum_unread_by(&self, count: impl Fn(&str) -> u32) -> u32 {
         let mut total = 0u32;
         for chat in &self.chats {
-            if chat.muted {
+            if chat.muted || chat.id == crate::device_drop::CHAT_ID {
                 continue;
             }
             if !chat.is_community() {
@@ -1047,7 +1091,7 @@ impl ChatState {
                 // its unreads can't be seen or cleared, so they must not badge.
                 continue;
             }
-            total += counts.get(&chat.id).copied().unwrap_or(0);
+            total += count(&chat.id);
         }
         total
     }
@@ -1057,10 +1101,10 @@ impl ChatState {
     // never re-scans the DB. See the `unread_cache` field doc.
     // ------------------------------------------------------------------------
 
-    /// Seed the whole cache from a DB `unread_counts()` result and mark it seeded. Idempotent per
-    /// login; a later call replaces the map wholesale (used only if a reseed is ever forced).
-    pub fn unread_seed(&mut self, counts: std::collections::HashMap<String, u32>) {
-        self.unread_cache = counts;
+    /// Seed the whole cache from a DB `unread_summaries()` result and mark it seeded. Idempotent per
+    /// login; a later call replaces the map wholesale (the chat list's refresh uses it to heal drift).
+    pub fn unread_seed(&mut self, unread: std::collections::HashMap<String, ChatUnread>) {
+        self.unread_cache = unread;
         self.unread_seeded = true;
     }
 
@@ -1069,31 +1113,46 @@ impl ChatState {
         self.unread_cache.remove(chat_id);
     }
 
-    /// Reconcile a chat to an exact DB-computed count (delete / retreat / backfill). A zero drops the
-    /// entry so the map stays small and `unwrap_or(0)` reads it as caught-up.
-    pub fn unread_set(&mut self, chat_id: &str, count: u32) {
-        if count == 0 {
+    /// Reconcile a chat to an exact DB-computed summary (inbound / delete / retreat / backfill). A zero
+    /// count drops the entry so the map stays small and a missing entry reads as caught-up.
+    pub fn unread_set(&mut self, chat_id: &str, unread: ChatUnread) {
+        if unread.count == 0 {
             self.unread_cache.remove(chat_id);
         } else {
-            self.unread_cache.insert(chat_id.to_string(), count);
+            self.unread_cache.insert(chat_id.to_string(), unread);
         }
     }
 
+    /// A chat's raw unread count.
+    pub fn unread_count(&self, chat_id: &str) -> u32 {
+        self.unread_cache.get(chat_id).map_or(0, |u| u.count)
+    }
+
     /// Total unread for the badge, from the cache, applying the same muted/blocked filters as
     /// [`sum_unread_from`].
     pub fn sum_unread(&self) -> u32 {
-        self.sum_unread_from(&self.unread_cache)
+        self.sum_unread_by(|id| self.unread_count(id))
+    }
+
+    /// Unread across a chat folder's chats, from the cache, with the badge's muted/blocked filters.
+    pub fn folder_unread(&self, chat_ids: &[String]) -> u32 {
+        self.sum_unread_by(|id| if chat_ids.iter().any(|c| c == id) { self.unread_count(id) } else { 0 })
     }
 
     /// A snapshot of the raw per-chat counts, for the frontend's boot badges.
     pub fn unread_snapshot(&self) -> std::collections::HashMap<String, u32> {
+        self.unread_cache.iter().map(|(id, u)| (id.clone(), u.count)).collect()
+    }
+
+    /// A snapshot of every chat's unread summary (raw, like the per-chat badges), for the chat list.
+    pub fn unread_summary(&self) -> std::collections::HashMap<String, ChatUnread> {
         self.unread_cache.clone()
     }
 
     pub fn count_unread_messages(&self) -> u32 {
         let mut total_unread = 0;
         for chat in &self.chats {
-            if chat.muted { continue; }
+            if chat.muted || chat.id == crate::device_drop::CHAT_ID { continue; }
             let is_group = chat.is_community();
             if !is_group {
                 if let Some(id) = self.interner.lookup(&chat.id) {
@@ -1205,11 +1264,18 @@ mod tests {
             nip05: String::new(),
             status: Status::new(),
             last_updated: 0,
+            last_active: 0,
+            last_seen: 0,
+            online: false,
             mine: false,
             bot: false,
             is_blocked: false,
+            contact_verified: false,
             avatar_cached: String::new(),
             banner_cached: String::new(),
+            verified: None,
+            zaps: Default::default(),
+            details_deferred: false,
         }
     }
 
@@ -1305,6 +1371,23 @@ mod tests {
         assert!(!other.flags.is_mine(), "other profile should not have mine flag");
     }
 
+    #[test]
+    fn merge_db_profiles_detects_mine_from_hex() {
+        let mut state = ChatState::new();
+        let me = Keys::generate().public_key();
+        let my_npub = me.to_bech32().unwrap();
+        let their_npub = Keys::generate().public_key().to_bech32().unwrap();
+
+        // The caller only had my key in hex form
+        state.merge_db_profiles(
+            vec![make_slim_profile(&my_npub, "Me"), make_slim_profile(&their_npub, "Other")],
+            &me.to_hex(),
+        );
+
+        assert!(state.get_profile(&my_npub).unwrap().flags.is_mine());
+        assert!(!state.get_profile(&their_npub).unwrap().flags.is_mine());
+    }
+
     #[test]
     fn serialize_profile_roundtrip() {
         let mut state = ChatState::new();
@@ -1520,6 +1603,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn paging_far_back_keeps_the_chat_bounded() {
+        let mut state = ChatState::new();
+        let msgs: Vec<Message> = (0..MAX_CHAT_MESSAGES_IN_RAM + 50)
+            .map(|i| Message { id: format!("{:064x}", i + 1), at: 1700000000000 + i as u64 * 1000, ..Default::default() })
+            .collect();
+        state.add_messages_to_chat_batch("npub1peer", msgs);
+
+        assert_eq!(state.bound_chat_messages("npub1peer"), 50);
+        let chat = state.get_chat("npub1peer").unwrap();
+        assert_eq!(chat.message_count(), MAX_CHAT_MESSAGES_IN_RAM);
+        let kept = |i: usize| chat.messages.contains_hex_id(&format!("{:064x}", i + 1));
+        assert!(kept(0), "the page just loaded stays");
+        assert!(kept(MAX_CHAT_MESSAGES_IN_RAM + 49), "the live tail stays");
+        assert!(!kept(MAX_CHAT_MESSAGES_IN_RAM + 50 - PINNED_TAIL_MESSAGES - 1), "the middle goes");
+        assert_eq!(state.bound_chat_messages("npub1peer"), 0);
+    }
+
     #[test]
     fn add_messages_to_chat_batch_dedup() {
         let mut state = ChatState::new();
@@ -1896,25 +1997,26 @@ mod tests {
         assert_eq!(state.count_unread_messages(), 0, "blocked user DM should not count");
     }
 
+    fn unread_of(counts: &[(&str, u32)]) -> std::collections::HashMap<String, ChatUnread> {
+        counts.iter().map(|&(id, count)| (id.to_string(), ChatUnread { count, ..Default::default() })).collect()
+    }
+
     #[test]
     fn unread_cache_seed_clear_set_and_sum() {
         let mut state = ChatState::new();
         state.create_dm_chat("npub1a");
         state.create_dm_chat("npub1b");
 
-        let mut seed = std::collections::HashMap::new();
-        seed.insert("npub1a".to_string(), 3u32);
-        seed.insert("npub1b".to_string(), 2u32);
-        state.unread_seed(seed);
+        state.unread_seed(unread_of(&[("npub1a", 3), ("npub1b", 2)]));
         assert!(state.unread_seeded);
         assert_eq!(state.sum_unread(), 5);
 
         state.unread_clear("npub1a");
         assert_eq!(state.sum_unread(), 2, "clear drops a's 3");
 
-        state.unread_set("npub1b", 4);
+        state.unread_set("npub1b", ChatUnread { count: 4, ..Default::default() });
         assert_eq!(state.sum_unread(), 4, "reconcile b to exact 4");
-        state.unread_set("npub1b", 0);
+        state.unread_set("npub1b", ChatUnread::default());
         assert_eq!(state.sum_unread(), 0);
         assert!(!state.unread_cache.contains_key("npub1b"), "a zero count drops the entry");
     }
@@ -1930,16 +2032,27 @@ mod tests {
         state.get_chat_mut("npub1mut").unwrap().muted = true;
         state.create_dm_chat("npub1ok");
 
-        let mut seed = std::collections::HashMap::new();
-        seed.insert("npub1blk".to_string(), 5u32);
-        seed.insert("npub1mut".to_string(), 7u32);
-        seed.insert("npub1ok".to_string(), 2u32);
-        state.unread_seed(seed);
+        state.unread_seed(unread_of(&[("npub1blk", 5), ("npub1mut", 7), ("npub1ok", 2)]));
 
         // Blocked + muted chats are filtered exactly like sum_unread_from; only the normal chat counts.
         assert_eq!(state.sum_unread(), 2);
     }
 
+    #[test]
+    fn folder_unread_counts_only_its_chats() {
+        let mut state = ChatState::new();
+        state.create_dm_chat("npub1a");
+        state.create_dm_chat("npub1b");
+        state.create_dm_chat("npub1mut");
+        state.get_chat_mut("npub1mut").unwrap().muted = true;
+
+        state.unread_seed(unread_of(&[("npub1a", 3), ("npub1b", 4), ("npub1mut", 9)]));
+
+        let folder = vec!["npub1a".to_string(), "npub1mut".to_string(), "npub1gone".to_string()];
+        assert_eq!(state.folder_unread(&folder), 3, "muted and unknown chats add nothing");
+        assert_eq!(state.folder_unread(&[]), 0);
+    }
+
     #[test]
     fn count_unread_own_messages_break_count() {
         let mut state = ChatState::new();
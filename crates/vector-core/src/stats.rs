@@ -7,7 +7,7 @@
 
 use std::time::Duration;
 
-use crate::types::{Message, Attachment, Reaction, EditEntry, ImageMetadata, SiteMetadata};
+use crate::types::{Message, Attachment, Reaction, EditEntry, ImageMetadata, SiteMetadata, Transcript, TranscriptSection};
 use crate::compact::{CompactMessage, CompactMessageVec, CompactReaction, CompactAttachment, MessageFlags, NpubInterner};
 use crate::profile::{Profile, ProfileFlags};
 use crate::chat::Chat;
@@ -156,6 +156,16 @@ impl DeepSize for Attachment {
             + self.webxdc_topic.as_ref().map(|s| s.capacity()).unwrap_or(0)
             + self.group_id.as_ref().map(|s| s.capacity()).unwrap_or(0)
             + self.original_hash.as_ref().map(|s| s.capacity()).unwrap_or(0)
+            + self.mirrors.iter().map(|s| s.capacity()).sum::<usize>()
+            + self.transcript.as_ref().map(|t| t.deep_size()).unwrap_or(0)
+    }
+}
+
+impl DeepSize for Transcript {
+    fn deep_size(&self) -> usize {
+        std::mem::size_of::<Transcript>()
+            + self.lang.capacity()
+            + self.sections.iter().map(|s| std::mem::size_of::<TranscriptSection>() + s.text.capacity()).sum::<usize>()
     }
 }
 
@@ -243,6 +253,8 @@ impl DeepSize for CompactAttachment {
             + self.group_id.as_ref().map(|_| 32).unwrap_or(0)
             + self.original_hash.as_ref().map(|_| 32).unwrap_or(0)
             + self.webxdc_topic.as_ref().map(|s| s.len()).unwrap_or(0)
+            + self.mirrors.as_ref().map(|m| m.iter().map(|s| s.len() + 16).sum::<usize>()).unwrap_or(0)
+            + self.transcript.as_ref().map(|t| t.deep_size()).unwrap_or(0)
     }
 }
 
@@ -252,6 +264,7 @@ impl DeepSize for CompactMessage {
             + self.content.len()
             + self.replied_to_content.as_ref().map(|s| s.len()).unwrap_or(0)
             + self.preview_metadata.as_ref().map(|m| m.deep_size()).unwrap_or(0)
+            + self.location.as_ref().map(|_| std::mem::size_of::<crate::location::Location>()).unwrap_or(0)
             + self.attachments.iter().map(|a| a.deep_size()).sum::<usize>()
             + self.reactions.iter().map(|r| r.deep_size()).sum::<usize>()
             + self.edit_history.as_ref().map(|h| h.iter().map(|e| e.deep_size()).sum::<usize>()).unwrap_or(0)
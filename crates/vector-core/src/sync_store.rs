@@ -0,0 +1,207 @@
+//! The stamped-snapshot store behind [`crate::settings_sync`] and [`crate::chat_state_sync`].
+//!
+//! Each store is a set of keyed entries this device stamps (ms) on every local write, published as one
+//! NIP-44-self-encrypted kind 30078 snapshot under its own `d`-tag and merged last-writer-wins per key. The
+//! store owns the stamp map, the full sync round, the live ingest path and the debounced republish; a
+//! [`StoreEntries`] impl says what its entries are and how a remote one is applied here.
+
+use nostr_sdk::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::clock::now_ms;
+use crate::profile::sync::ProfileSyncHandler;
+use crate::settings_sync::{decrypt_snapshot, fetch_remote, publish, SettingsSnapshot, SettingsSyncReport, SyncedValue};
+use crate::state::SessionGuard;
+
+/// What a [`StampedStore`] holds: how this device's entries are read and how another device's are adopted.
+#[async_trait::async_trait]
+pub(crate) trait StoreEntries: Sync {
+    /// This device's view of every entry, stamped from `stamps`.
+    async fn local_snapshot(&self, stamps: &HashMap<String, u64>) -> SettingsSnapshot;
+
+    /// Adopt `entry` for `key` from another device. `already` when the local value matches, so only the
+    /// stamp needs taking. Returns whether the entry took; one that didn't stays unstamped so a later
+    /// round offers it again.
+    async fn apply(
+        &self,
+        session: &SessionGuard,
+        key: &str,
+        entry: &SyncedValue,
+        already: bool,
+        handler: &dyn ProfileSyncHandler,
+    ) -> bool;
+
+    /// Trim the stamp map before it's saved.
+    fn prune_stamps(&self, _stamps: &mut HashMap<String, u64>) {}
+
+    /// Trim a merged snapshot before it's published.
+    fn prune_published(&self, _snapshot: &mut SettingsSnapshot) {}
+}
+
+pub(crate) struct StampedStore {
+    /// `d`-tag of the store's kind 30078 event.
+    d_tag: &'static str,
+    /// Settings row holding the per-key local write stamps (JSON `{key: ms}`).
+    stamps_key: &'static str,
+    log_tag: &'static str,
+    republish_debounce_secs: u64,
+    entries: &'static dyn StoreEntries,
+    /// Serializes the stamp map's read-modify-write.
+    stamps_lock: std::sync::Mutex<()>,
+    republish_gen: AtomicU64,
+}
+
+impl StampedStore {
+    pub(crate) const fn new(
+        d_tag: &'static str,
+        stamps_key: &'static str,
+        log_tag: &'static str,
+        republish_debounce_secs: u64,
+        entries: &'static dyn StoreEntries,
+    ) -> Self {
+        Self {
+            d_tag,
+            stamps_key,
+            log_tag,
+            republish_debounce_secs,
+            entries,
+            stamps_lock: std::sync::Mutex::new(()),
+            republish_gen: AtomicU64::new(0),
+        }
+    }
+
+    fn load_stamps(&self) -> HashMap<String, u64> {
+        crate::db::settings::get_sql_setting(self.stamps_key.to_string())
+            .ok()
+            .flatten()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn stamp(&self, key: &str, at: u64) {
+        let _guard = self.stamps_lock.lock();
+        let mut stamps = self.load_stamps();
+        stamps.insert(key.to_string(), at);
+        self.entries.prune_stamps(&mut stamps);
+        if let Ok(json) = serde_json::to_string(&stamps) {
+            let _ = crate::db::settings::set_sql_setting(self.stamps_key.to_string(), json);
+        }
+    }
+
+    /// Record a local change to `key` and push it out shortly.
+    pub(crate) fn note_local_change(&'static self, key: &str) {
+        self.stamp(key, now_ms());
+        if tokio::runtime::Handle::try_current().is_ok() {
+            self.republish_debounced();
+        }
+    }
+
+    /// When `key` was last written on this device (or adopted from another), in ms. Zero = never.
+    pub(crate) fn local_stamp(&self, key: &str) -> u64 {
+        self.load_stamps().get(key).copied().unwrap_or(0)
+    }
+
+    pub(crate) async fn local_snapshot(&self) -> SettingsSnapshot {
+        self.entries.local_snapshot(&self.load_stamps()).await
+    }
+
+    /// `local` merged with `remote`, trimmed for publishing.
+    fn merged(&self, local: &SettingsSnapshot, remote: &SettingsSnapshot) -> SettingsSnapshot {
+        let mut merged = local.merge(remote);
+        self.entries.prune_published(&mut merged);
+        merged
+    }
+
+    /// Apply every entry in `remote` that beats `local`, keeping the remote stamp. Returns the changed keys.
+    async fn apply_remote(
+        &self,
+        session: &SessionGuard,
+        local: &SettingsSnapshot,
+        remote: &SettingsSnapshot,
+        handler: &dyn ProfileSyncHandler,
+    ) -> Vec<String> {
+        let mut changed = Vec::new();
+        for key in remote.newer_than(local) {
+            let entry = &remote.entries[&key];
+            let already = local.entries.get(&key).is_some_and(|e| e.value == entry.value);
+            if !self.entries.apply(session, &key, entry, already, handler).await || !session.is_valid() {
+                continue;
+            }
+            // After the entry's write path, so the remote stamp (not "now") is what we keep.
+            self.stamp(&key, entry.updated_at);
+            changed.push(key);
+        }
+        changed
+    }
+
+    /// Full round: fetch the relay snapshot, adopt its newer entries, and publish the merge when this
+    /// device holds anything newer.
+    pub(crate) async fn sync_now(&self, handler: &dyn ProfileSyncHandler) -> Result<SettingsSyncReport, String> {
+        let session = SessionGuard::capture();
+        let client = crate::state::nostr_client().ok_or("Nostr client not initialized")?;
+        let my_pk = crate::state::my_public_key().ok_or("Not logged in")?;
+
+        let remote = fetch_remote(&client, my_pk, self.d_tag).await?;
+        if !session.is_valid() {
+            return Ok(SettingsSyncReport::default());
+        }
+        let local = self.local_snapshot().await;
+        let applied = self.apply_remote(&session, &local, &remote, handler).await;
+
+        let merged = self.merged(&local, &remote);
+        let published = !merged.newer_than(&remote).is_empty();
+        if published && session.is_valid() {
+            publish(&client, my_pk, self.d_tag, &merged).await?;
+            crate::log_info!("{} Published {} entries", self.log_tag, merged.entries.len());
+        }
+        Ok(SettingsSyncReport { applied, published })
+    }
+
+    /// Live cross-device path: adopt a snapshot another device published. Returns the adopted keys. Never
+    /// republishes (the relay echoes our own publishes back on the same subscription).
+    pub(crate) async fn ingest_remote_event(&self, event: &Event, handler: &dyn ProfileSyncHandler) -> Vec<String> {
+        let session = SessionGuard::capture();
+        let Some(client) = crate::state::nostr_client() else { return Vec::new() };
+        let Some(my_pk) = crate::state::my_public_key() else { return Vec::new() };
+        if event.pubkey != my_pk {
+            return Vec::new();
+        }
+        let remote = decrypt_snapshot(&client, &my_pk, event).await;
+        if !session.is_valid() {
+            return Vec::new();
+        }
+        self.apply_remote(&session, &self.local_snapshot().await, &remote, handler).await
+    }
+
+    /// Coalesce a burst of changes into one publish (read-merge-write, so a sibling device's newer entries
+    /// are never overwritten).
+    fn republish_debounced(&'static self) {
+        let gen = self.republish_gen.fetch_add(1, Ordering::SeqCst) + 1;
+        let session = SessionGuard::capture();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_secs(self.republish_debounce_secs)).await;
+            if self.republish_gen.load(Ordering::SeqCst) != gen || !session.is_valid() {
+                return;
+            }
+            let (Some(client), Some(my_pk)) = (crate::state::nostr_client(), crate::state::my_public_key()) else { return };
+            let remote = match fetch_remote(&client, my_pk, self.d_tag).await {
+                Ok(r) => r,
+                Err(e) => {
+                    crate::log_warn!("{} Republish fetch failed: {}", self.log_tag, e);
+                    return;
+                }
+            };
+            if !session.is_valid() {
+                return;
+            }
+            let merged = self.merged(&self.local_snapshot().await, &remote);
+            if merged.newer_than(&remote).is_empty() {
+                return;
+            }
+            if let Err(e) = publish(&client, my_pk, self.d_tag, &merged).await {
+                crate::log_warn!("{} Republish failed: {}", self.log_tag, e);
+            }
+        });
+    }
+}
@@ -662,8 +662,7 @@ pub fn transport_state() -> TorTransportState {
 /// but not active — connection attempts to it fail at the TCP layer
 /// instantly, with no possibility of clearnet leak.
 pub fn blackhole_proxy_addr() -> SocketAddr {
-    // 127.0.0.1 port 1: reserved/no-listener on every sane OS.
-    SocketAddr::from(([127, 0, 0, 1], 1))
+    crate::proxy::blackhole_addr()
 }
 
 /// The current isolation token applied to every SOCKS connection through
@@ -80,6 +80,33 @@ pub fn refresh_subscriptions() {
     }
 }
 
+/// The platform's credential store (macOS Keychain, Windows Credential Manager,
+/// Secret Service, Android Keystore), used when the user keeps the private key
+/// out of the database.
+///
+/// Secrets are keyed by account (the npub), so several accounts can each keep
+/// their key in the store. `load` returns `Ok(None)` for an account with no
+/// entry; `delete` succeeds when there was nothing to delete.
+pub trait SecretStore: Send + Sync + 'static {
+    fn store(&self, account: &str, secret: &str) -> Result<(), String>;
+    fn load(&self, account: &str) -> Result<Option<String>, String>;
+    fn delete(&self, account: &str) -> Result<(), String>;
+}
+
+static SECRET_STORE: OnceLock<Box<dyn SecretStore>> = OnceLock::new();
+
+/// Register the platform credential store. Call once during app startup.
+pub fn set_secret_store(store: Box<dyn SecretStore>) {
+    let _ = SECRET_STORE.set(store);
+}
+
+/// The registered credential store, or an error on hosts without one (CLI, tests).
+pub fn secret_store() -> Result<&'static dyn SecretStore, String> {
+    SECRET_STORE.get()
+        .map(|store| store.as_ref())
+        .ok_or_else(|| "No system keystore is available on this platform".to_string())
+}
+
 /// Trait for reporting download/upload progress.
 pub trait ProgressReporter: Send + Sync {
     fn report_progress(&self, percentage: Option<u8>, bytes: Option<u64>, bytes_per_sec: Option<f64>) -> Result<(), &'static str>;
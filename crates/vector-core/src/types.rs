@@ -26,6 +26,9 @@ pub struct Message {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub replied_to_attachment_extension: Option<String>,
     pub preview_metadata: Option<SiteMetadata>,
+    /// A shared location (see [`crate::location`]); `content` then holds its `geo:` URI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<crate::location::Location>,
     pub attachments: Vec<Attachment>,
     pub reactions: Vec<Reaction>,
     pub at: u64,
@@ -55,6 +58,25 @@ pub struct Message {
     /// — commands are actioned at delivery, never replayed from history).
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub addressed_bots: Vec<String>,
+    /// Language hint of each fenced code block in `content`, in order ("" for a bare fence).
+    /// Derived from the text, never persisted — rebuilt wherever a Message is assembled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub code_languages: Vec<String>,
+    /// Previews of the `nostr:` references in `content` that have already been resolved this
+    /// session. Filled from the resolver's cache wherever a Message is assembled; not persisted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nostr_previews: Vec<crate::nostr_uri::NostrPreview>,
+    /// Total zapped on this message, in msat (see [`crate::zaps`]). Filled from the zap totals
+    /// wherever a Message is assembled; not persisted.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub zap_msat: u64,
+    /// Starred on this device (see [`crate::starred`]). Filled wherever a Message is assembled.
+    #[serde(default)]
+    pub starred: bool,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
@@ -74,6 +96,7 @@ impl Default for Message {
             replied_to_has_attachment: None,
             replied_to_attachment_extension: None,
             preview_metadata: None,
+            location: None,
             attachments: Vec::new(),
             reactions: Vec::new(),
             at: 0,
@@ -87,6 +110,10 @@ impl Default for Message {
             edit_history: None,
             emoji_tags: Vec::new(),
             addressed_bots: Vec::new(),
+            code_languages: Vec::new(),
+            nostr_previews: Vec::new(),
+            zap_msat: 0,
+            starred: false,
         }
     }
 }
@@ -203,21 +230,22 @@ impl Message {
     // Mention / Tag API
     // ========================================================================
 
-    /// Check if this message mentions a specific npub (e.g. `@npub1abc...`).
+    /// Check if this message mentions a specific npub, in any shape [`mentions_npub`] accepts.
+    /// Text inside code doesn't count.
     pub fn mentions(&self, npub: &str) -> bool {
-        self.content.contains(&format!("@{}", npub))
+        mentions_npub(&self.content, npub)
     }
 
     /// Check if this message mentions the current user.
     pub fn mentions_me(&self) -> bool {
         crate::state::my_public_key()
             .and_then(|pk| nostr_sdk::prelude::ToBech32::to_bech32(&pk).ok())
-            .map_or(false, |my_npub| self.content.contains(&format!("@{}", my_npub)))
+            .map_or(false, |my_npub| self.mentions(&my_npub))
     }
 
-    /// Check if this message contains an `@everyone` ping.
+    /// Check if this message contains an `@everyone` ping (outside code).
     pub fn mentions_everyone(&self) -> bool {
-        self.content.contains("@everyone")
+        crate::code_blocks::strip_code(&self.content).contains("@everyone")
     }
 
     /// Extract all mentioned npubs from the message content.
@@ -248,12 +276,18 @@ pub fn mention(npub: &str) -> String {
 /// the render layer, which pills all three shapes); one glued into a longer
 /// alphanumeric token is not.
 ///
-/// Returns npub strings without any prefix. Validates bech32 characters.
+/// Returns npub strings without any prefix. Validates bech32 characters. An npub inside code
+/// (a fenced block or inline span) is text, not a mention.
 pub fn extract_mentions(content: &str) -> Vec<&str> {
     let bytes = content.as_bytes();
     let len = bytes.len();
     let mut mentions = Vec::new();
     let mut i = 0;
+    let code = if content.contains('`') || content.contains("~~~") {
+        crate::code_blocks::code_ranges(content)
+    } else {
+        Vec::new()
+    };
 
     // npub = "npub1" (5) + 58 bech32 chars = 63 bytes
     while i + 63 <= len {
@@ -266,7 +300,7 @@ pub fn extract_mentions(content: &str) -> Vec<&str> {
                 && bytes[i + 5..npub_end]
                     .iter()
                     .all(|b| BECH32_CHARS.contains(&b.to_ascii_lowercase()));
-            if valid {
+            if valid && !crate::code_blocks::in_code(&code, i) {
                 mentions.push(&content[i..npub_end]);
                 i = npub_end;
                 continue;
@@ -278,6 +312,23 @@ pub fn extract_mentions(content: &str) -> Vec<&str> {
     mentions
 }
 
+/// Whether `content` mentions `npub`: any shape [`extract_mentions`] accepts, or a NIP-21
+/// `nostr:nprofile1…` reference, which is how other Nostr clients write a mention. Code doesn't
+/// count.
+pub fn mentions_npub(content: &str, npub: &str) -> bool {
+    use nostr_sdk::nips::nip19::{FromBech32, Nip19Profile};
+    if extract_mentions(content).contains(&npub) {
+        return true;
+    }
+    let Ok(pubkey) = nostr_sdk::PublicKey::parse(npub) else { return false };
+    crate::nostr_uri::find_uris(content).iter().any(|uri| {
+        uri.strip_prefix("nostr:")
+            .filter(|bech| bech.starts_with("nprofile1"))
+            .and_then(|bech| Nip19Profile::from_bech32(bech).ok())
+            .is_some_and(|profile| profile.public_key == pubkey)
+    })
+}
+
 // ============================================================================
 // Attachment
 // ============================================================================
@@ -302,6 +353,24 @@ pub struct Attachment {
     pub group_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub original_hash: Option<String>,
+    /// Copies of the blob on other Blossom servers, tried in order when `url` fails (see
+    /// `blossom::mirror_blob`). On the wire they are NIP-94 `fallback` URLs.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<String>,
+    /// "View once": the receiver's copy is deleted after the first open (see `view_once`).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub view_once: bool,
+    /// A video note from the composer's recorder (see `video.rs` in the app), rather than a shared
+    /// video file. On the wire it's a flat `video-note` tag.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub video_note: bool,
+    /// A view-once attachment that has been opened: its file is gone and it won't download again.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub viewed: bool,
+    /// What was said in a voice message, once transcribed on this device. Local only: it lives in
+    /// the `attachments` table and never goes on the wire.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<Transcript>,
 }
 
 impl Default for Attachment {
@@ -321,6 +390,11 @@ impl Default for Attachment {
             webxdc_topic: None,
             group_id: None,
             original_hash: None,
+            mirrors: Vec::new(),
+            view_once: false,
+            video_note: false,
+            viewed: false,
+            transcript: None,
         }
     }
 }
@@ -337,6 +411,30 @@ pub struct ImageMetadata {
     pub height: u32,
 }
 
+/// A voice message transcript: timed sections (so a tap can seek the audio) and the spoken
+/// language whisper detected.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Transcript {
+    pub lang: String,
+    pub sections: Vec<TranscriptSection>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct TranscriptSection {
+    pub text: String,
+    /// Offset into the audio, in milliseconds.
+    pub at: i64,
+}
+
+impl Transcript {
+    /// The whole transcript as one line of text.
+    pub fn text(&self) -> String {
+        self.sections.iter().map(|s| s.text.trim()).filter(|t| !t.is_empty()).collect::<Vec<_>>().join(" ")
+    }
+}
+
 /// Pre-upload file data.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct AttachmentFile {
@@ -614,6 +712,7 @@ mod tests {
             replied_to_has_attachment: Some(true),
             replied_to_attachment_extension: Some("png".to_string()),
             preview_metadata: None,
+            location: None,
             attachments: vec![Attachment::default()],
             reactions: vec![Reaction {
                 id: "r1".to_string(),
@@ -635,6 +734,10 @@ mod tests {
             }]),
             emoji_tags: Vec::new(),
             addressed_bots: Vec::new(),
+            code_languages: Vec::new(),
+            nostr_previews: Vec::new(),
+            zap_msat: 0,
+            starred: false,
         };
 
         let json = serde_json::to_string(&msg).expect("serialize should succeed");
@@ -740,6 +843,14 @@ mod tests {
             webxdc_topic: Some("game".to_string()),
             group_id: Some("g1".to_string()),
             original_hash: Some("sha256hash".to_string()),
+            mirrors: vec!["https://mirror.example/abc".to_string()],
+            view_once: true,
+            video_note: false,
+            viewed: true,
+            transcript: Some(Transcript {
+                lang: "en".to_string(),
+                sections: vec![TranscriptSection { text: "hello".to_string(), at: 0 }],
+            }),
         };
 
         let json = serde_json::to_string(&att).expect("serialize should succeed");
@@ -764,6 +875,7 @@ mod tests {
         assert!(!json.contains("webxdc_topic"), "None webxdc_topic should be omitted");
         assert!(!json.contains("group_id"), "None group_id should be omitted");
         assert!(!json.contains("original_hash"), "None original_hash should be omitted");
+        assert!(!json.contains("view_once"), "false view_once should be omitted");
     }
 
     // ========================================================================
@@ -810,6 +922,24 @@ mod tests {
         assert!(!msg.mentions("npub1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqdmf9lg"));
     }
 
+    #[test]
+    fn mentions_any_shape_including_nprofile() {
+        use nostr_sdk::nips::nip19::Nip19Profile;
+        let me = nostr_sdk::Keys::generate().public_key();
+        let npub = me.to_bech32().unwrap();
+        let nprofile = Nip19Profile::new(me, Vec::<nostr_sdk::RelayUrl>::new()).to_bech32().unwrap();
+        let other = Nip19Profile::new(nostr_sdk::Keys::generate().public_key(), Vec::<nostr_sdk::RelayUrl>::new())
+            .to_bech32()
+            .unwrap();
+
+        for content in [format!("hi nostr:{}", npub), format!("{} look", npub), format!("hey nostr:{}!", nprofile)] {
+            let msg = Message { content: content.clone(), ..Default::default() };
+            assert!(msg.mentions(&npub), "shape: {}", content);
+        }
+        assert!(!super::mentions_npub(&format!("hey nostr:{}", other), &npub));
+        assert!(!super::mentions_npub(&format!("`nostr:{}`", nprofile), &npub), "code isn't a mention");
+    }
+
     #[test]
     fn mentions_everyone_detected() {
         let msg = Message { content: "hey @everyone look at this".into(), ..Default::default() };
@@ -0,0 +1,112 @@
+//! Outgoing typing indicators: whether one may go out, and how often.
+//!
+//! An indicator is sent only when the account's `send_typing_indicators` setting (on by default)
+//! and the chat's own switch both allow it, the chat isn't muted, and the DM contact isn't
+//! blocked. Past those, each chat gets at most one per [`MIN_INTERVAL_MS`] however fast the
+//! keystroke bursts come, so a chatty composer can't spam the relays.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use rusqlite::params;
+
+/// Account setting (synced): `"false"` stops every typing indicator.
+pub const SETTING_KEY: &str = "send_typing_indicators";
+
+/// Minimum gap between two indicators to one chat.
+pub const MIN_INTERVAL_MS: u64 = 5_000;
+
+/// When each chat last had an indicator sent (ms).
+static LAST_SENT: Mutex<Option<HashMap<String, u64>>> = Mutex::new(None);
+
+/// Whether the account sends typing indicators at all.
+pub fn enabled() -> bool {
+    crate::db::settings::get_sql_setting(SETTING_KEY.to_string())
+        .ok()
+        .flatten()
+        .is_none_or(|v| v == "true" || v == "1")
+}
+
+/// Whether `chat_identifier` sends typing indicators (on unless switched off for it).
+pub fn chat_enabled(chat_identifier: &str) -> bool {
+    let Ok(conn) = crate::db::get_db_connection_guard_static() else { return true };
+    conn.query_row(
+        "SELECT 1 FROM chat_typing_off WHERE chat_identifier = ?1",
+        params![chat_identifier],
+        |_| Ok(()),
+    ).is_err()
+}
+
+/// Switch typing indicators on or off for `chat_identifier`.
+pub fn set_chat_enabled(chat_identifier: &str, on: bool) -> Result<(), String> {
+    let conn = crate::db::get_write_connection_guard_static()?;
+    let sql = if on {
+        "DELETE FROM chat_typing_off WHERE chat_identifier = ?1"
+    } else {
+        "INSERT OR IGNORE INTO chat_typing_off (chat_identifier) VALUES (?1)"
+    };
+    conn.execute(sql, params![chat_identifier])
+        .map_err(|e| format!("Failed to save typing preference: {}", e))?;
+    Ok(())
+}
+
+/// Claim `chat_identifier`'s send slot at `now_ms`: true (and recorded) when the last indicator
+/// to it is at least [`MIN_INTERVAL_MS`] old.
+pub fn take_slot(chat_identifier: &str, now_ms: u64) -> bool {
+    let Ok(mut guard) = LAST_SENT.lock() else { return false };
+    let sent = guard.get_or_insert_with(HashMap::new);
+    if sent.get(chat_identifier).is_some_and(|&at| now_ms.saturating_sub(at) < MIN_INTERVAL_MS) {
+        return false;
+    }
+    // Entries older than the interval no longer limit anything
+    sent.retain(|_, at| now_ms.saturating_sub(*at) < MIN_INTERVAL_MS);
+    sent.insert(chat_identifier.to_string(), now_ms);
+    true
+}
+
+/// Whether an indicator to `chat_identifier` (a DM npub or a Community channel id) may be sent
+/// now. Claims the chat's rate-limit slot when it may.
+pub async fn may_send(chat_identifier: &str) -> bool {
+    if !enabled() || !chat_enabled(chat_identifier) {
+        return false;
+    }
+    {
+        let state = crate::state::STATE.lock().await;
+        if state.get_chat(chat_identifier).is_some_and(|c| c.muted()) {
+            return false;
+        }
+        if state.get_profile(chat_identifier).is_some_and(|p| p.flags.is_blocked()) {
+            return false;
+        }
+    }
+    take_slot(chat_identifier, crate::clock::now_ms())
+}
+
+/// Account swap: forget the previous account's send times.
+pub fn clear_session() {
+    if let Ok(mut guard) = LAST_SENT.lock() {
+        *guard = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_indicator_per_chat_per_interval() {
+        clear_session();
+        let clock = crate::clock::freeze(1_700_000_000_000);
+        let now = crate::clock::now_ms;
+
+        assert!(take_slot("npub1a", now()));
+        assert!(!take_slot("npub1a", now()), "a second burst straight after is dropped");
+        assert!(take_slot("npub1b", now()), "other chats have their own slot");
+
+        clock.advance(MIN_INTERVAL_MS - 1);
+        assert!(!take_slot("npub1a", now()));
+        clock.advance(1);
+        assert!(take_slot("npub1a", now()));
+        clear_session();
+    }
+}
@@ -0,0 +1,183 @@
+//! Verified accounts — a small signed registry of official, bot and service npubs.
+//!
+//! The registry is one kind-30078 `d=vector/verified` event published by the project key. Trust
+//! comes from its signature, not from npub strings shipped in the binary: an entry only counts
+//! while it sits in a registry event that verifies AND is authored by [`REGISTRY_PUBLISHER`]. The
+//! last good event is cached (raw JSON, re-verified on load) so badges survive offline boots.
+
+use nostr_sdk::prelude::*;
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+/// The Vector project key — the same one that signs official Mini App listings.
+pub const REGISTRY_PUBLISHER: &str = "npub16ye7evyevwnl0fc9hujsxf9zym72e063awn0pvde0huvpyec5nyq4dg4wn";
+const REGISTRY_D_TAG: &str = "vector/verified";
+/// Per-account settings key holding the last verified registry event (raw JSON).
+const REGISTRY_CACHE_KEY: &str = "verified_registry";
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifiedRole {
+    /// The project's own accounts (announcements, support).
+    Official,
+    /// Automated accounts run by or for the project.
+    Bot,
+    /// Third-party services vetted by the project.
+    Service,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct VerifiedAccount {
+    pub npub: String,
+    pub label: String,
+    pub role: VerifiedRole,
+}
+
+#[derive(serde::Deserialize)]
+struct RegistryBody {
+    accounts: Vec<VerifiedAccount>,
+}
+
+/// `(registry created_at, npub → entry)`. Only ever replaced by a strictly newer event, so a relay
+/// replaying an old registry can't resurrect a revoked entry.
+static REGISTRY: LazyLock<RwLock<(u64, HashMap<String, VerifiedAccount>)>> =
+    LazyLock::new(|| RwLock::new((0, HashMap::new())));
+
+/// Validate a registry event against `publisher` and return its entries. Rejects a wrong author,
+/// a bad signature, the wrong kind/`d` tag, or a malformed body; entries with an unparseable npub
+/// are dropped and the rest normalized to bech32.
+pub fn parse_registry(event: &Event, publisher: &PublicKey) -> Result<Vec<VerifiedAccount>, String> {
+    if event.pubkey != *publisher {
+        return Err("registry not authored by the publisher".into());
+    }
+    event.verify().map_err(|e| format!("registry signature: {e}"))?;
+    if event.kind != Kind::ApplicationSpecificData || event.tags.identifier() != Some(REGISTRY_D_TAG) {
+        return Err("not a verified-accounts registry".into());
+    }
+    let body: RegistryBody = serde_json::from_str(&event.content)
+        .map_err(|e| format!("registry body: {e}"))?;
+    Ok(body.accounts.into_iter().filter_map(|mut a| {
+        let pk = PublicKey::parse(&a.npub).ok()?;
+        a.npub = pk.to_bech32().ok()?;
+        Some(a)
+    }).collect())
+}
+
+/// Swap in a newer registry. Returns `false` (and changes nothing) for an older or equal one.
+fn install(created_at: u64, accounts: Vec<VerifiedAccount>) -> bool {
+    let Ok(mut reg) = REGISTRY.write() else { return false };
+    if created_at <= reg.0 {
+        return false;
+    }
+    *reg = (created_at, accounts.into_iter().map(|a| (a.npub.clone(), a)).collect());
+    true
+}
+
+fn publisher() -> Option<PublicKey> {
+    PublicKey::from_bech32(REGISTRY_PUBLISHER).ok()
+}
+
+/// The registry entry for `npub`, if it is a verified account.
+pub fn verified_account(npub: &str) -> Option<VerifiedAccount> {
+    REGISTRY.read().ok()?.1.get(npub).cloned()
+}
+
+/// Cheap synchronous check, safe to call while holding STATE (the lookalike scan does).
+pub fn is_verified(npub: &str) -> bool {
+    REGISTRY.read().map(|r| r.1.contains_key(npub)).unwrap_or(false)
+}
+
+/// Restore the cached registry at login, re-verifying it (a tampered DB row is ignored).
+pub fn load_cached_registry() {
+    let Some(publisher) = publisher() else { return };
+    let Some(json) = crate::db::get_sql_setting(REGISTRY_CACHE_KEY.to_string()).ok().flatten() else { return };
+    let Ok(event) = Event::from_json(&json) else { return };
+    if let Ok(accounts) = parse_registry(&event, &publisher) {
+        install(event.created_at.as_secs(), accounts);
+    }
+}
+
+/// Fetch the newest registry from relays and cache it. Returns whether it changed. Invalid copies
+/// (forged author, bad signature) are skipped, never installed.
+pub async fn refresh_registry() -> Result<bool, String> {
+    let session = crate::state::SessionGuard::capture();
+    let client = crate::state::nostr_client().ok_or("Nostr client not initialized")?;
+    let publisher = publisher().ok_or("invalid registry publisher")?;
+    let filter = Filter::new()
+        .author(publisher)
+        .kind(Kind::ApplicationSpecificData)
+        .identifier(REGISTRY_D_TAG)
+        .limit(3);
+    let events = client
+        .fetch_events(filter, std::time::Duration::from_secs(10))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let newest = events.into_iter()
+        .filter_map(|e| parse_registry(&e, &publisher).ok().map(|accounts| (e, accounts)))
+        .max_by_key(|(e, _)| e.created_at);
+    let Some((event, accounts)) = newest else { return Ok(false) };
+    if !install(event.created_at.as_secs(), accounts) {
+        return Ok(false);
+    }
+    if !session.is_valid() {
+        return Ok(true);
+    }
+    let _ = crate::db::set_sql_setting(REGISTRY_CACHE_KEY.to_string(), event.as_json());
+    crate::log_info!("[Verified] registry updated ({} accounts)", REGISTRY.read().map(|r| r.1.len()).unwrap_or(0));
+
+    // Re-emit every cached profile the registry names so badges appear without a re-sync.
+    let state = crate::state::STATE.lock().await;
+    let listed: Vec<String> = REGISTRY.read().map(|r| r.1.keys().cloned().collect()).unwrap_or_default();
+    for npub in listed {
+        if let Some(slim) = state.interner.lookup(&npub).and_then(|id| state.serialize_profile(id)) {
+            crate::traits::emit_event("profile_update", &slim);
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry_event(keys: &Keys, body: &str) -> Event {
+        EventBuilder::new(Kind::ApplicationSpecificData, body)
+            .tag(Tag::identifier(REGISTRY_D_TAG))
+            .sign_with_keys(keys)
+            .unwrap()
+    }
+
+    #[test]
+    fn registry_trusts_signature_and_author_only() {
+        let publisher = Keys::generate();
+        let bot = Keys::generate().public_key();
+        let body = format!(
+            r#"{{"accounts":[{{"npub":"{}","label":"Vector Bot","role":"bot"}},{{"npub":"junk","label":"x","role":"official"}}]}}"#,
+            bot.to_hex()
+        );
+
+        let good = registry_event(&publisher, &body);
+        let accounts = parse_registry(&good, &publisher.public_key()).unwrap();
+        assert_eq!(accounts.len(), 1, "unparseable npub dropped");
+        assert_eq!(accounts[0].npub, bot.to_bech32().unwrap(), "hex normalized to bech32");
+        assert_eq!(accounts[0].role, VerifiedRole::Bot);
+
+        // Same body signed by anyone else is worthless.
+        let forged = registry_event(&Keys::generate(), &body);
+        assert!(parse_registry(&forged, &publisher.public_key()).is_err());
+
+        // Tampering with the content after signing breaks the signature.
+        let mut tampered = good.clone();
+        tampered.content = body.replace("Vector Bot", "Evil Bot");
+        assert!(parse_registry(&tampered, &publisher.public_key()).is_err());
+    }
+
+    #[test]
+    fn older_registry_never_replaces_newer() {
+        let entry = |npub: &str| VerifiedAccount { npub: npub.into(), label: String::new(), role: VerifiedRole::Official };
+        assert!(install(u64::MAX - 1, vec![entry("npub1new")]));
+        assert!(!install(u64::MAX - 2, vec![entry("npub1old")]));
+        assert!(is_verified("npub1new") && !is_verified("npub1old"));
+    }
+}
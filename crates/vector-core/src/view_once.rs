@@ -0,0 +1,104 @@
+//! View-once media — attachments the sender flags to be opened a single time.
+//!
+//! The flag travels in the attachment's `imeta` (`view-once 1`) and, for a single-file DM, a flat
+//! `["view-once", "1"]` tag. The receiver downloads it like any attachment; once the UI has shown
+//! it, [`mark_viewed`] deletes the decrypted file, marks the attachment viewed, and the download
+//! path refuses it from then on.
+//!
+//! This is best-effort and only as strong as the receiving client: the blob stays on the Blossom
+//! server until it expires there, a modified client can keep the file, and nothing stops a
+//! screenshot. The sender's own copy is left alone.
+
+use crate::types::Message;
+
+/// Record that the receiver opened a view-once attachment: delete its file (unless another
+/// message shares the same download), mark it viewed in STATE and the DB, and emit
+/// `message_update`. Idempotent; a no-op for our own sends.
+pub async fn mark_viewed(chat_id: &str, msg_id: &str, attachment_id: &str) -> Result<(), String> {
+    let session = crate::state::SessionGuard::capture();
+    let updated: Option<Message> = {
+        let mut state = crate::state::STATE.lock().await;
+        let chat = state.get_chat(chat_id).ok_or("Chat not found")?;
+        let message = chat.get_compact_message(msg_id).ok_or("Message not found")?;
+        let attachment = message.attachments.iter()
+            .find(|a| a.id_eq(attachment_id))
+            .ok_or("Attachment not found")?;
+        if !attachment.view_once() {
+            return Err("Not a view-once attachment".to_string());
+        }
+        if message.is_mine() || attachment.viewed() {
+            None
+        } else {
+            state.update_message_in_chat(chat_id, msg_id, |msg| {
+                if let Some(att) = msg.attachments.iter_mut().find(|a| a.id_eq(attachment_id)) {
+                    att.flags.set_viewed(true);
+                    att.set_downloaded(false);
+                    att.path = "".into();
+                    att.transcript = None;
+                }
+            })
+        }
+    };
+    let Some(message) = updated else { return Ok(()) };
+    if !session.is_valid() {
+        return Ok(());
+    }
+
+    if let Some((path, shared)) = crate::db::attachments::mark_attachment_viewed(msg_id, attachment_id)? {
+        if !shared && !path.is_empty() {
+            let file = crate::types::Attachment { path, ..Default::default() };
+            crate::deletion::delete_cached_attachment_files_pub(std::slice::from_ref(&file));
+        }
+    }
+
+    crate::traits::emit_event("message_update", &serde_json::json!({
+        "old_id": msg_id,
+        "message": message,
+        "chat_id": chat_id,
+    }));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{Attachment, Message};
+
+    #[tokio::test]
+    async fn viewed_attachment_stays_gone() {
+        let _guard = crate::db::DB_TEST_GUARD.lock().unwrap_or_else(|e| e.into_inner());
+        crate::db::close_database();
+        crate::db::clear_id_caches();
+        use nostr_sdk::prelude::ToBech32;
+        let tmp = tempfile::tempdir().unwrap();
+        let account = nostr_sdk::prelude::Keys::generate().public_key().to_bech32().unwrap();
+        std::fs::create_dir_all(tmp.path().join(&account)).unwrap();
+        crate::db::set_app_data_dir(tmp.path().to_path_buf());
+        crate::db::set_current_account(account.clone()).unwrap();
+        crate::db::init_database(&account).unwrap();
+
+        let attachment = Attachment {
+            id: "ab".repeat(32),
+            path: "/downloads/photo.jpg".into(),
+            downloaded: true,
+            view_once: true,
+            video_note: false,
+            ..Default::default()
+        };
+        let msg = Message { id: "vo_msg".into(), attachments: vec![attachment.clone()], ..Default::default() };
+        crate::db::events::save_message("npub1contactvo", &msg).await.unwrap();
+
+        let (path, shared) = crate::db::attachments::mark_attachment_viewed("vo_msg", &attachment.id)
+            .unwrap()
+            .expect("view-once row found");
+        assert_eq!(path, "/downloads/photo.jpg");
+        assert!(!shared);
+
+        // A relay re-delivery, or a finished download racing the open, can't bring it back
+        crate::db::events::save_message("npub1contactvo", &msg).await.unwrap();
+        crate::db::attachments::set_attachment_downloaded("vo_msg", &attachment.id, true, "/downloads/photo.jpg").unwrap();
+        let stored = crate::db::attachments::get_attachments_for_event("vo_msg").unwrap();
+        assert!(stored[0].view_once && stored[0].viewed);
+        assert!(!stored[0].downloaded);
+        assert!(stored[0].path.is_empty());
+    }
+}
@@ -3,6 +3,10 @@
 //! Wallpapers are static images attached to a 1:1 DM conversation. Either
 //! party may set one; latest-write-wins by rumor `created_at`.
 //!
+//! Any chat can also carry our own wallpaper override (see "Wallpaper
+//! overrides" below): same picker and Blossom upload, but nothing is sent
+//! into the chat — it syncs to our other devices only.
+//!
 //! On the wire: a NIP-17 gift-wrapped rumor with kind 30078 and the d tag
 //! `vector-wallpaper`. The wallpaper bytes themselves are AES-256-GCM
 //! encrypted onto Blossom — same crypto path Vector uses for normal file
@@ -225,6 +229,8 @@ fn encode_wallpaper_still(img: &::image::DynamicImage) -> Result<(Vec<u8>, Strin
 enum FileKind {
     Preview,
     Active,
+    /// Our own wallpaper for the chat ([`set_wallpaper_override`]).
+    Override,
 }
 
 /// Remove every wallpaper artifact for `chat_npub` of the given kind. When
@@ -242,6 +248,7 @@ fn clean_chat_files(
     };
 
     let preview_prefix = format!("{}.preview.", chat_npub);
+    let override_prefix = format!("{}.override.", chat_npub);
     let active_prefix = format!("{}.", chat_npub);
 
     for entry in entries.flatten() {
@@ -252,8 +259,11 @@ fn clean_chat_files(
         };
         let matches = match kind {
             FileKind::Preview => name.starts_with(&preview_prefix),
-            // Active = chat-prefixed but NOT preview-prefixed.
-            FileKind::Active => name.starts_with(&active_prefix) && !name.starts_with(&preview_prefix),
+            // Active = chat-prefixed but neither preview- nor override-prefixed.
+            FileKind::Active => name.starts_with(&active_prefix)
+                && !name.starts_with(&preview_prefix)
+                && !name.starts_with(&override_prefix),
+            FileKind::Override => name.starts_with(&override_prefix),
         };
         if !matches {
             continue;
@@ -269,6 +279,118 @@ fn clean_chat_files(
     Ok(())
 }
 
+/// The chat's staged preview file (we don't know its extension ahead of time).
+fn preview_file(chat_npub: &str) -> Result<PathBuf, String> {
+    let dir = wallpapers_dir()?;
+    let prefix = format!("{}.preview.", chat_npub);
+    for entry in std::fs::read_dir(&dir)
+        .map_err(|e| format!("Wallpapers dir: {}", e))?
+        .flatten()
+    {
+        let p = entry.path();
+        let n = p
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string();
+        if n.starts_with(&prefix) {
+            return Ok(p);
+        }
+    }
+    Err("No wallpaper preview to publish. Pick an image first.".to_string())
+}
+
+/// Upload an encrypted wallpaper blob, reporting progress as `wallpaper_upload_progress` so the
+/// Set Wallpaper button can render a real ring instead of an opaque disabled state.
+async fn upload_wallpaper<T>(
+    signer: T,
+    chat_npub: &str,
+    encrypted: Vec<u8>,
+    mime: &str,
+) -> Result<String, String>
+where
+    T: NostrSigner + Clone,
+{
+    let servers = crate::state::get_blossom_servers();
+    let chat_npub_for_progress = chat_npub.to_string();
+    let progress_cb: crate::blossom::ProgressCallback = Arc::new(move |percentage, bytes| {
+        crate::traits::emit_event(
+            "wallpaper_upload_progress",
+            &serde_json::json!({
+                "chat_id": chat_npub_for_progress,
+                "progress": percentage.unwrap_or(0),
+                "bytes": bytes.unwrap_or(0),
+            }),
+        );
+        Ok(())
+    });
+
+    crate::blossom::upload_blob_with_progress_and_failover(
+        signer,
+        servers,
+        Arc::new(encrypted),
+        Some(mime),
+        /* is_encrypted */ true,
+        progress_cb,
+        None, // default retry count
+        None, // default retry spacing
+        None, // no cancel flag (the picker flow doesn't expose cancel mid-upload)
+    )
+    .await
+    .map_err(|e| format!("Wallpaper upload failed: {}", e))
+}
+
+/// Download, decrypt and (given the plaintext hash) verify a wallpaper blob.
+async fn download_wallpaper(
+    url: &str,
+    key: &str,
+    nonce: &str,
+    plaintext_hash: Option<&str>,
+) -> Result<Vec<u8>, String> {
+    // SSRF guard: the URL is attacker-controlled (it arrives in a rumor),
+    // and this fetch is zero-interaction. Block private/internal targets.
+    crate::net::validate_url_not_private(url)?;
+
+    let http = crate::net::build_http_client(Duration::from_secs(30))?;
+    let resp = http
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Wallpaper download failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("Wallpaper HTTP {}", resp.status()));
+    }
+    // Reject by advertised length first (cheap), then enforce a hard cap
+    // while streaming so a server that omits Content-Length can't OOM us.
+    if let Some(len) = resp.content_length() {
+        if len > MAX_WALLPAPER_DOWNLOAD_BYTES {
+            return Err("Wallpaper too large".to_string());
+        }
+    }
+    let mut resp = resp;
+    let mut bytes: Vec<u8> = Vec::new();
+    while let Some(chunk) = resp
+        .chunk()
+        .await
+        .map_err(|e| format!("Read body: {}", e))?
+    {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() as u64 > MAX_WALLPAPER_DOWNLOAD_BYTES {
+            return Err("Wallpaper too large".to_string());
+        }
+    }
+
+    let plaintext = crypto::decrypt_data(&bytes, key, nonce)?;
+
+    if let Some(want_hash) = plaintext_hash {
+        let got_hash = crypto::sha256_hex(&plaintext);
+        if !got_hash.eq_ignore_ascii_case(want_hash) {
+            return Err("Wallpaper integrity check failed".to_string());
+        }
+    }
+    Ok(plaintext)
+}
+
 /// Publish the current preview file as the chat's wallpaper. Encrypts +
 /// uploads to Blossom, builds the kind-30078 rumor, sends to the
 /// counterparty (the gift-wrap helper fans out to self for cross-device
@@ -288,27 +410,7 @@ pub async fn publish_wallpaper(chat_npub: &str, blur: u8, dim: u8) -> Result<(),
 
     let blur = blur.min(30);
     let dim = dim.min(100);
-    // Find the preview file (we don't know its extension ahead of time).
-    let dir = wallpapers_dir()?;
-    let prefix = format!("{}.preview.", chat_npub);
-    let mut preview_path: Option<PathBuf> = None;
-    for entry in std::fs::read_dir(&dir)
-        .map_err(|e| format!("Wallpapers dir: {}", e))?
-        .flatten()
-    {
-        let p = entry.path();
-        let n = p
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
-        if n.starts_with(&prefix) {
-            preview_path = Some(p);
-            break;
-        }
-    }
-    let preview = preview_path
-        .ok_or_else(|| "No wallpaper preview to publish. Pick an image first.".to_string())?;
+    let preview = preview_file(chat_npub)?;
     let bytes = std::fs::read(&preview)
         .map_err(|e| format!("Failed to read preview file: {}", e))?;
 
@@ -336,36 +438,7 @@ pub async fn publish_wallpaper(chat_npub: &str, blur: u8, dim: u8) -> Result<(),
     let recipient_pk = PublicKey::from_bech32(chat_npub)
         .map_err(|e| format!("Invalid chat npub: {}", e))?;
 
-    let servers = crate::state::get_blossom_servers();
-
-    // Bridge Blossom upload progress to the frontend so the Set Wallpaper
-    // button can render a real ring instead of an opaque disabled state.
-    let chat_npub_for_progress = chat_npub.to_string();
-    let progress_cb: crate::blossom::ProgressCallback = Arc::new(move |percentage, bytes| {
-        crate::traits::emit_event(
-            "wallpaper_upload_progress",
-            &serde_json::json!({
-                "chat_id": chat_npub_for_progress,
-                "progress": percentage.unwrap_or(0),
-                "bytes": bytes.unwrap_or(0),
-            }),
-        );
-        Ok(())
-    });
-
-    let upload_url = crate::blossom::upload_blob_with_progress_and_failover(
-        signer.clone(),
-        servers,
-        Arc::new(encrypted.clone()),
-        Some(&mime),
-        /* is_encrypted */ true,
-        progress_cb,
-        None, // default retry count
-        None, // default retry spacing
-        None, // no cancel flag (the picker flow doesn't expose cancel mid-upload)
-    )
-    .await
-    .map_err(|e| format!("Wallpaper upload failed: {}", e))?;
+    let upload_url = upload_wallpaper(signer.clone(), chat_npub, encrypted.clone(), &mime).await?;
 
     let created_at = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -617,47 +690,7 @@ pub async fn apply_received_wallpaper(
     let mime_str = mime.unwrap_or("image/png").to_string();
     let extension = crypto::extension_from_mime(&mime_str);
 
-    // SSRF guard: the URL is attacker-controlled (it arrives in a rumor),
-    // and this fetch is zero-interaction. Block private/internal targets.
-    crate::net::validate_url_not_private(url)?;
-
-    let http = crate::net::build_http_client(Duration::from_secs(30))?;
-    let resp = http
-        .get(url)
-        .send()
-        .await
-        .map_err(|e| format!("Wallpaper download failed: {}", e))?;
-    if !resp.status().is_success() {
-        return Err(format!("Wallpaper HTTP {}", resp.status()));
-    }
-    // Reject by advertised length first (cheap), then enforce a hard cap
-    // while streaming so a server that omits Content-Length can't OOM us.
-    if let Some(len) = resp.content_length() {
-        if len > MAX_WALLPAPER_DOWNLOAD_BYTES {
-            return Err("Wallpaper too large".to_string());
-        }
-    }
-    let mut resp = resp;
-    let mut bytes: Vec<u8> = Vec::new();
-    while let Some(chunk) = resp
-        .chunk()
-        .await
-        .map_err(|e| format!("Read body: {}", e))?
-    {
-        bytes.extend_from_slice(&chunk);
-        if bytes.len() as u64 > MAX_WALLPAPER_DOWNLOAD_BYTES {
-            return Err("Wallpaper too large".to_string());
-        }
-    }
-
-    let plaintext = crypto::decrypt_data(&bytes, decryption_key, decryption_nonce)?;
-
-    if let Some(want_hash) = plaintext_hash {
-        let got_hash = crypto::sha256_hex(&plaintext);
-        if !got_hash.eq_ignore_ascii_case(want_hash) {
-            return Err("Wallpaper integrity check failed".to_string());
-        }
-    }
+    let plaintext = download_wallpaper(url, decryption_key, decryption_nonce, plaintext_hash).await?;
 
     // Account swap during the download invalidates everything below — the
     // chat npub, the DB pool, and the per-account wallpapers dir all belong
@@ -940,6 +973,150 @@ pub async fn remove_wallpaper(chat_npub: &str) -> Result<(), String> {
     Ok(())
 }
 
+// ============================================================================
+// Wallpaper overrides
+// ============================================================================
+
+/// Set the staged preview as our own wallpaper for `chat_id`, which can be any chat. Unlike
+/// [`publish_wallpaper`] nothing is sent into the chat: the override is only ours, shown instead
+/// of a DM's shared wallpaper, and follows the account to our other devices through
+/// [`crate::chat_state_sync`]. The blob is still encrypted onto Blossom so they can fetch it. The
+/// result lands as `chat_appearance_changed`.
+pub async fn set_wallpaper_override(chat_id: &str, blur: u8, dim: u8) -> Result<(), String> {
+    let session = crate::state::SessionGuard::capture();
+
+    let previous = crate::appearance::wallpaper_override(chat_id);
+    if previous.is_none()
+        && crate::appearance::wallpaper_overrides()?.len() >= crate::appearance::MAX_WALLPAPER_OVERRIDES
+    {
+        return Err(format!(
+            "You can set your own wallpaper on up to {} chats. Remove one first.",
+            crate::appearance::MAX_WALLPAPER_OVERRIDES
+        ));
+    }
+
+    let preview = preview_file(chat_id)?;
+    let bytes = std::fs::read(&preview)
+        .map_err(|e| format!("Failed to read preview file: {}", e))?;
+    let extension = preview
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("png")
+        .to_string();
+    let mime = crypto::mime_from_extension(&extension).to_string();
+    let plaintext_hash = crypto::sha256_hex(&bytes);
+    let params = crypto::generate_encryption_params();
+    let encrypted = crypto::encrypt_data(&bytes, &params)?;
+
+    let client = crate::state::nostr_client().ok_or("Not logged in")?;
+    let signer = client
+        .signer()
+        .await
+        .map_err(|e| format!("Signer: {}", e))?;
+    let url = upload_wallpaper(signer, chat_id, encrypted, &mime).await?;
+
+    // Account swapped during the upload: the commit below would land in the new account's storage
+    if !session.is_valid() {
+        return Ok(());
+    }
+
+    let spec = crate::appearance::WallpaperOverride {
+        url,
+        key: params.key,
+        nonce: params.nonce,
+        x: plaintext_hash,
+        mime,
+        blur: blur.min(30),
+        dim: dim.min(100),
+    };
+    let path = wallpapers_dir()?.join(format!("{}.override.{}", chat_id, extension));
+    clean_chat_files(chat_id, FileKind::Override, None)?;
+    std::fs::rename(&preview, &path)
+        .map_err(|e| format!("Failed to promote preview: {}", e))?;
+    let path_str = path.to_string_lossy().to_string();
+    commit_override(chat_id, Some((&spec, path_str.as_str())))?;
+    crate::chat_state_sync::note_local_change(&crate::chat_state_sync::wallpaper_key(chat_id));
+
+    if let Some(previous) = previous {
+        delete_own_override_blob(&previous.spec.url).await;
+    }
+    Ok(())
+}
+
+/// Drop our wallpaper override for `chat_id`, here and on our other devices.
+pub async fn clear_wallpaper_override(chat_id: &str) -> Result<(), String> {
+    let Some(previous) = crate::appearance::wallpaper_override(chat_id) else { return Ok(()) };
+    clean_chat_files(chat_id, FileKind::Override, None)?;
+    commit_override(chat_id, None)?;
+    crate::chat_state_sync::note_local_change(&crate::chat_state_sync::wallpaper_key(chat_id));
+    delete_own_override_blob(&previous.spec.url).await;
+    Ok(())
+}
+
+/// Adopt an override another of our devices synced (`value` is its spec, or empty when it was
+/// cleared): fetch and cache the blob, then store it. Returns whether it was applied.
+pub(crate) async fn apply_synced_override(
+    session: &crate::state::SessionGuard,
+    chat_id: &str,
+    value: &str,
+) -> bool {
+    if value.is_empty() {
+        if !session.is_valid() {
+            return false;
+        }
+        let _ = clean_chat_files(chat_id, FileKind::Override, None);
+        return commit_override(chat_id, None).is_ok();
+    }
+    let Ok(spec) = serde_json::from_str::<crate::appearance::WallpaperOverride>(value) else { return false };
+    let plaintext = match download_wallpaper(&spec.url, &spec.key, &spec.nonce, Some(&spec.x)).await {
+        Ok(plaintext) => plaintext,
+        Err(e) => {
+            log_warn!("[Wallpaper] synced override for {} failed: {}", chat_id, e);
+            return false;
+        }
+    };
+    if !session.is_valid() {
+        return false;
+    }
+    let extension = crypto::extension_from_mime(&spec.mime);
+    let Ok(dir) = wallpapers_dir() else { return false };
+    let path = dir.join(format!("{}.override.{}", chat_id, extension));
+    let tmp = dir.join(format!("{}.override.{}.tmp", chat_id, extension));
+    if clean_chat_files(chat_id, FileKind::Override, None).is_err()
+        || std::fs::write(&tmp, &plaintext).is_err()
+        || std::fs::rename(&tmp, &path).is_err()
+    {
+        return false;
+    }
+    let path_str = path.to_string_lossy().to_string();
+    commit_override(chat_id, Some((&spec, path_str.as_str()))).is_ok()
+}
+
+/// Store (or with `None`, drop) a chat's override and tell the frontend.
+fn commit_override(
+    chat_id: &str,
+    stored: Option<(&crate::appearance::WallpaperOverride, &str)>,
+) -> Result<(), String> {
+    crate::appearance::write_wallpaper_override(chat_id, stored)?;
+    let wallpaper = crate::appearance::wallpaper_override(chat_id).map(|o| o.view());
+    crate::traits::emit_event(
+        "chat_appearance_changed",
+        &serde_json::json!({
+            "chat_id": chat_id,
+            "wallpaper": wallpaper,
+        }),
+    );
+    Ok(())
+}
+
+/// Fire-and-forget DELETE of a replaced override's blob (always uploaded by us).
+async fn delete_own_override_blob(url: &str) {
+    let me_npub = crate::state::my_public_key()
+        .and_then(|pk| pk.to_bech32().ok())
+        .unwrap_or_default();
+    delete_prior_blob_if_ours(url, &me_npub).await;
+}
+
 #[cfg(test)]
 mod wallpaper_strip_tests {
     use super::*;
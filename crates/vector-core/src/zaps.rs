@@ -0,0 +1,638 @@
+//! Zaps (NIP-57) — Lightning tips for contacts and their messages.
+//!
+//! Sending: the recipient's Lightning address (`lud16`, or a `lud06` LNURL) resolves to an
+//! LNURL-pay endpoint. When that endpoint takes zaps (`allowsNostr`), we sign a zap request
+//! (kind 9734) naming the recipient, the amount and optionally the zapped message, and trade it at
+//! the endpoint's callback for a BOLT11 invoice. Vector holds no funds: the invoice is paid from
+//! the user's own Lightning wallet, after which the recipient's wallet server publishes a zap
+//! receipt (kind 9735) to the relays the request listed.
+//!
+//! Receiving: receipts naming us as recipient (`p`) or sender (`P`) are stored in the `zaps`
+//! table and summed per message and per contact. Anyone can publish a receipt, so only one signed
+//! by the recipient's zap server counts: for zaps we sent, the server whose `nostrPubkey` we paid
+//! (kept in `zap_requests`); for zaps to us, the server behind our own Lightning address. [`message_total`] and [`profile_totals`] read
+//! those sums wherever a message or profile is assembled.
+//!
+//! Zaps are public by design: the wallet server and every relay the receipt reaches learn who
+//! tipped whom, how much, and the zapped message's id (never its content).
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use nostr_sdk::prelude::*;
+use rusqlite::params;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// NIP-57 zap request, signed by the sender and handed to the recipient's LNURL server.
+pub const KIND_ZAP_REQUEST: u16 = 9734;
+/// NIP-57 zap receipt, published by the recipient's LNURL server once the invoice is paid.
+pub const KIND_ZAP_RECEIPT: u16 = 9735;
+
+/// Longest zap comment kept from a receipt, in characters.
+const MAX_COMMENT_CHARS: usize = 280;
+
+/// Largest LNURL response read; pay parameters and an invoice are well under a KiB.
+const MAX_RESPONSE_BYTES: usize = 64 * 1024;
+
+/// Zaps between us and one contact.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ZapTotals {
+    /// What we zapped them (msat).
+    pub sent_msat: u64,
+    /// What they zapped us (msat).
+    pub received_msat: u64,
+}
+
+impl ZapTotals {
+    pub fn is_empty(&self) -> bool {
+        self.sent_msat == 0 && self.received_msat == 0
+    }
+}
+
+/// A validated zap receipt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZapReceipt {
+    /// Receipt event id (hex).
+    pub id: String,
+    pub sender: String,
+    pub recipient: String,
+    /// The zapped event (hex), when the zap was on a message.
+    pub event_id: Option<String>,
+    pub amount_msat: u64,
+    pub comment: String,
+    pub created_at: u64,
+    /// The embedded zap request's id (hex).
+    pub request_id: String,
+    /// Who signed the receipt (hex): must be the recipient's zap server.
+    pub provider: String,
+}
+
+/// An invoice for a zap, ready for the user's wallet.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ZapInvoice {
+    /// BOLT11 invoice.
+    pub invoice: String,
+    pub amount_msat: u64,
+    /// npub being zapped.
+    pub recipient: String,
+}
+
+/// LNURL-pay parameters (LUD-06), with the NIP-57 additions.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PayEndpoint {
+    #[serde(default)]
+    tag: String,
+    callback: String,
+    min_sendable: u64,
+    max_sendable: u64,
+    #[serde(default)]
+    allows_nostr: bool,
+    #[serde(default)]
+    nostr_pubkey: Option<String>,
+}
+
+// ============================================================================
+// Sending
+// ============================================================================
+
+/// Ask `target`'s Lightning wallet for a zap invoice. `target` is an npub (zap the user) or a
+/// message id (zap its author, tagging the message).
+pub async fn request_invoice(
+    client: &Client,
+    target: &str,
+    amount_sats: u64,
+    comment: &str,
+) -> Result<ZapInvoice, String> {
+    let session = crate::state::SessionGuard::capture();
+    let (recipient, event_id) = resolve_target(target).await?;
+    let (lud16, lud06) = {
+        let state = crate::state::STATE.lock().await;
+        let profile = state.get_profile(&recipient);
+        (
+            profile.map(|p| p.lud16().to_string()).unwrap_or_default(),
+            profile.map(|p| p.lud06().to_string()).unwrap_or_default(),
+        )
+    };
+    let pay_url = lnurl_pay_url(&lud16, &lud06)?;
+    let amount_msat = amount_sats.checked_mul(1000).filter(|&a| a > 0).ok_or("Invalid amount")?;
+
+    let endpoint = fetch_endpoint(&pay_url).await?;
+    let provider = zap_provider(&endpoint).ok_or("Their Lightning wallet doesn't support zaps")?;
+    if amount_msat < endpoint.min_sendable || amount_msat > endpoint.max_sendable {
+        return Err(format!(
+            "Their wallet accepts between {} and {} sats",
+            endpoint.min_sendable.div_ceil(1000),
+            endpoint.max_sendable / 1000,
+        ));
+    }
+
+    // The receipt is published to the relays we list here, so list ours
+    let relays: Vec<String> = client.relays().await.keys().map(|u| u.to_string()).collect();
+    if relays.is_empty() {
+        return Err("No relays connected".to_string());
+    }
+    let lnurl = encode_lnurl(&pay_url)?;
+    let comment: String = comment.trim().chars().take(MAX_COMMENT_CHARS).collect();
+    let request = zap_request(&recipient, event_id.as_deref(), amount_msat, &comment, relays, &lnurl)?;
+    let request = client.sign_event_builder(request).await.map_err(|e| e.to_string())?;
+
+    let mut callback = Url::parse(&endpoint.callback).map_err(|_| "Their Lightning wallet gave an invalid callback".to_string())?;
+    if callback.scheme() != "https" {
+        return Err("Their Lightning wallet gave an invalid callback".to_string());
+    }
+    callback.query_pairs_mut()
+        .append_pair("amount", &amount_msat.to_string())
+        .append_pair("nostr", &request.as_json())
+        .append_pair("lnurl", &lnurl);
+    let response: serde_json::Value = get_json(&callback, "Their Lightning wallet returned an invalid response").await?;
+    let Some(invoice) = response.get("pr").and_then(|v| v.as_str()) else {
+        let reason = response.get("reason").and_then(|v| v.as_str()).unwrap_or("no invoice returned");
+        return Err(format!("Their Lightning wallet refused the zap: {}", reason));
+    };
+    // Never hand the user an invoice for something other than what they chose
+    if bolt11_amount_msat(invoice) != Some(amount_msat) {
+        return Err("Their Lightning wallet returned an invoice for the wrong amount".to_string());
+    }
+    if !session.is_valid() {
+        return Err("The account changed during the zap".to_string());
+    }
+    remember_request(&request.id.to_hex(), &provider.to_hex())?;
+    Ok(ZapInvoice { invoice: invoice.to_string(), amount_msat, recipient })
+}
+
+/// The LNURL-pay parameters at `pay_url`.
+async fn fetch_endpoint(pay_url: &Url) -> Result<PayEndpoint, String> {
+    let endpoint: PayEndpoint = get_json(pay_url, "Their Lightning address returned an invalid response").await?;
+    if endpoint.tag != "payRequest" {
+        return Err("Their Lightning address returned an invalid response".to_string());
+    }
+    Ok(endpoint)
+}
+
+/// GET `url` and decode its JSON, reading at most [`MAX_RESPONSE_BYTES`]. Both the pay URL and its
+/// callback come from someone else's profile, so each is held to a public host (redirects are
+/// checked by the client). `invalid` is the error for a body that isn't the expected JSON.
+async fn get_json<T: DeserializeOwned>(url: &Url, invalid: &str) -> Result<T, String> {
+    use futures_util::StreamExt;
+    crate::net::validate_url_not_private(url.as_str())
+        .map_err(|e| format!("Their Lightning wallet can't be used: {}", e))?;
+    let response = crate::net::shared_http_client().get(url.as_str()).send().await
+        .map_err(|e| format!("Couldn't reach their Lightning wallet: {}", e))?;
+    if response.content_length().is_some_and(|len| len > MAX_RESPONSE_BYTES as u64) {
+        return Err(invalid.to_string());
+    }
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Couldn't reach their Lightning wallet: {}", e))?;
+        if body.len() + chunk.len() > MAX_RESPONSE_BYTES {
+            return Err(invalid.to_string());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    serde_json::from_slice(&body).map_err(|_| invalid.to_string())
+}
+
+/// The key an endpoint signs its zap receipts with, or `None` when it doesn't take zaps.
+fn zap_provider(endpoint: &PayEndpoint) -> Option<PublicKey> {
+    if !endpoint.allows_nostr {
+        return None;
+    }
+    endpoint.nostr_pubkey.as_deref().and_then(|k| PublicKey::parse(k).ok())
+}
+
+/// Keep which zap server a request was sent to, so only its receipt is trusted.
+fn remember_request(request_id: &str, provider: &str) -> Result<(), String> {
+    let conn = crate::db::get_write_connection_guard_static()?;
+    conn.execute(
+        "INSERT OR REPLACE INTO zap_requests (request_id, provider, created_at) VALUES (?1, ?2, ?3)",
+        params![request_id, provider, crate::clock::now_secs() as i64],
+    ).map_err(|e| format!("Failed to save zap request: {}", e))?;
+    Ok(())
+}
+
+/// The npub a zap on `target` goes to, plus the zapped message id for message zaps.
+async fn resolve_target(target: &str) -> Result<(String, Option<String>), String> {
+    // Message ids are hex too, so only a bech32 target is a user
+    if crate::ids::is_npub(target) || target.starts_with("nostr:") {
+        return Ok((crate::ids::UserId::parse(target)?.npub(), None));
+    }
+    let state = crate::state::STATE.lock().await;
+    let (chat, msg) = state.find_message(target).ok_or("Message not found")?;
+    if msg.mine {
+        return Err("You can't zap your own message".to_string());
+    }
+    let author = msg.npub.clone()
+        .or_else(|| crate::ids::is_dm_chat_id(chat.id()).then(|| chat.id().clone()))
+        .ok_or("Unknown message author")?;
+    Ok((author, Some(msg.id)))
+}
+
+/// The unsigned zap request (kind 9734) for `amount_msat` to `recipient`.
+fn zap_request(
+    recipient: &str,
+    event_id: Option<&str>,
+    amount_msat: u64,
+    comment: &str,
+    relays: Vec<String>,
+    lnurl: &str,
+) -> Result<EventBuilder, String> {
+    let recipient = PublicKey::parse(recipient).map_err(|e| e.to_string())?;
+    let mut tags = vec![
+        Tag::custom(TagKind::custom("relays"), relays),
+        Tag::custom(TagKind::custom("amount"), [amount_msat.to_string()]),
+        Tag::custom(TagKind::custom("lnurl"), [lnurl.to_string()]),
+        Tag::public_key(recipient),
+    ];
+    if let Some(id) = event_id {
+        tags.push(Tag::event(EventId::from_hex(id).map_err(|e| e.to_string())?));
+    }
+    Ok(EventBuilder::new(Kind::Custom(KIND_ZAP_REQUEST), comment).tags(tags))
+}
+
+/// The LNURL-pay URL behind a profile's Lightning address: `lud16` (`name@domain`, LUD-16)
+/// preferred, else the bech32 `lud06` LNURL.
+pub fn lnurl_pay_url(lud16: &str, lud06: &str) -> Result<Url, String> {
+    let lud16 = lud16.trim().to_ascii_lowercase();
+    if let Some((name, domain)) = lud16.split_once('@') {
+        let name_ok = !name.is_empty()
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.+".contains(c));
+        if name_ok && !domain.is_empty() && !domain.contains(['/', '@', '?', '#']) {
+            return Url::parse(&format!("https://{}/.well-known/lnurlp/{}", domain, name))
+                .map_err(|_| "Invalid Lightning address".to_string());
+        }
+        return Err("Invalid Lightning address".to_string());
+    }
+    let lud06 = lud06.trim().to_ascii_lowercase();
+    let lud06 = lud06.strip_prefix("lightning:").unwrap_or(&lud06);
+    if lud06.is_empty() {
+        return Err("This user hasn't set up a Lightning address".to_string());
+    }
+    let (hrp, data) = bech32::decode(lud06).map_err(|_| "Invalid LNURL".to_string())?;
+    if hrp.to_lowercase() != "lnurl" {
+        return Err("Invalid LNURL".to_string());
+    }
+    let url = String::from_utf8(data).ok()
+        .and_then(|s| Url::parse(&s).ok())
+        .filter(|u| u.scheme() == "https")
+        .ok_or("Invalid LNURL")?;
+    Ok(url)
+}
+
+/// `url` as a bech32 LNURL, for the zap request's `lnurl` tag.
+fn encode_lnurl(url: &Url) -> Result<String, String> {
+    let hrp = bech32::Hrp::parse("lnurl").map_err(|e| e.to_string())?;
+    bech32::encode::<bech32::Bech32>(hrp, url.as_str().as_bytes()).map_err(|e| e.to_string())
+}
+
+/// The amount a BOLT11 invoice asks for, in msat. `None` for amountless or malformed invoices.
+pub fn bolt11_amount_msat(invoice: &str) -> Option<u64> {
+    let invoice = invoice.trim().to_ascii_lowercase();
+    let invoice = invoice.strip_prefix("lightning:").unwrap_or(&invoice);
+    // The human-readable part ends at the last '1' (bech32's data charset has none)
+    let hrp = invoice.get(..invoice.rfind('1')?)?.strip_prefix("ln")?;
+    let amount = &hrp[hrp.find(|c: char| c.is_ascii_digit())?..];
+    let (digits, multiplier) = match amount.as_bytes().last()? {
+        m @ (b'm' | b'u' | b'n' | b'p') => (&amount[..amount.len() - 1], Some(*m)),
+        _ => (amount, None),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let n: u64 = digits.parse().ok()?;
+    match multiplier {
+        None => n.checked_mul(100_000_000_000),
+        Some(b'm') => n.checked_mul(100_000_000),
+        Some(b'u') => n.checked_mul(100_000),
+        Some(b'n') => n.checked_mul(100),
+        // Pico-BTC: only whole msat are valid
+        _ => (n % 10 == 0).then_some(n / 10),
+    }
+}
+
+// ============================================================================
+// Receiving
+// ============================================================================
+
+/// Receipts that involve us: zaps to us (`p`) and zaps we sent (`P`).
+pub fn subscription_filters(my_public_key: PublicKey) -> Vec<Filter> {
+    let kind = Kind::Custom(KIND_ZAP_RECEIPT);
+    vec![
+        Filter::new().kind(kind).pubkey(my_public_key),
+        Filter::new().kind(kind).custom_tags(SingleLetterTag::uppercase(Alphabet::P), vec![my_public_key.to_hex()]),
+    ]
+}
+
+/// Validate a zap receipt: it must embed a correctly signed zap request for the same recipient
+/// and message, and the paid invoice must match the amount requested. Who signed the receipt is
+/// checked by [`ingest`], against the recipient's zap server.
+pub fn parse_receipt(event: &Event) -> Option<ZapReceipt> {
+    if event.kind.as_u16() != KIND_ZAP_RECEIPT {
+        return None;
+    }
+    let amount_msat = bolt11_amount_msat(tag_value(&event.tags, "bolt11")?)?;
+    let request = Event::from_json(tag_value(&event.tags, "description")?).ok()?;
+    if request.kind.as_u16() != KIND_ZAP_REQUEST || request.verify().is_err() {
+        return None;
+    }
+    let recipient = tag_value(&request.tags, "p")?;
+    if tag_value(&event.tags, "p") != Some(recipient) || tag_value(&event.tags, "e") != tag_value(&request.tags, "e") {
+        return None;
+    }
+    if tag_value(&event.tags, "P").is_some_and(|sender| sender != request.pubkey.to_hex()) {
+        return None;
+    }
+    if let Some(requested) = tag_value(&request.tags, "amount") {
+        if requested.parse::<u64>().ok() != Some(amount_msat) {
+            return None;
+        }
+    }
+    let recipient = PublicKey::from_hex(recipient).ok()?;
+    let event_id = tag_value(&request.tags, "e")
+        .and_then(|id| EventId::from_hex(id).ok())
+        .map(|id| id.to_hex());
+    Some(ZapReceipt {
+        id: event.id.to_hex(),
+        sender: request.pubkey.to_bech32().ok()?,
+        recipient: recipient.to_bech32().ok()?,
+        event_id,
+        amount_msat,
+        comment: request.content.trim().chars().take(MAX_COMMENT_CHARS).collect(),
+        created_at: event.created_at.as_secs(),
+        request_id: request.id.to_hex(),
+        provider: event.pubkey.to_hex(),
+    })
+}
+
+/// Our own zap server's key, resolved from our profile's Lightning address and cached per address.
+static OWN_PROVIDER: LazyLock<Mutex<Option<(Url, PublicKey)>>> = LazyLock::new(|| Mutex::new(None));
+
+async fn own_provider(me: &str) -> Option<PublicKey> {
+    let pay_url = {
+        let state = crate::state::STATE.lock().await;
+        let profile = state.get_profile(me)?;
+        lnurl_pay_url(profile.lud16(), profile.lud06()).ok()?
+    };
+    let cached = OWN_PROVIDER.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some((url, provider)) = cached.filter(|(url, _)| *url == pay_url) {
+        return Some(provider);
+    }
+    let provider = zap_provider(&fetch_endpoint(&pay_url).await.ok()?)?;
+    *OWN_PROVIDER.lock().unwrap_or_else(|e| e.into_inner()) = Some((pay_url, provider));
+    Some(provider)
+}
+
+/// Whether the receipt comes from the recipient's zap server: the one we paid for zaps we sent,
+/// the one behind our Lightning address for zaps to us.
+async fn signed_by_provider(receipt: &ZapReceipt, me: &str) -> bool {
+    if receipt.sender == me {
+        let Ok(conn) = crate::db::get_db_connection_guard_static() else { return false };
+        let provider: Option<String> = conn.query_row(
+            "SELECT provider FROM zap_requests WHERE request_id = ?1",
+            params![receipt.request_id],
+            |r| r.get(0),
+        ).ok();
+        return provider.as_deref() == Some(receipt.provider.as_str());
+    }
+    own_provider(me).await.is_some_and(|pk| pk.to_hex() == receipt.provider)
+}
+
+fn tag_value<'a>(tags: &'a Tags, name: &str) -> Option<&'a str> {
+    tags.iter()
+        .find(|t| t.as_slice().first().map(|k| k.as_str()) == Some(name))
+        .and_then(|t| t.as_slice().get(1))
+        .map(|v| v.as_str())
+}
+
+/// Store a receipt and fold it into the totals. Returns false when it was already known.
+pub fn record(receipt: &ZapReceipt) -> Result<bool, String> {
+    let conn = crate::db::get_write_connection_guard_static()?;
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO zaps (receipt_id, sender, recipient, event_id, amount_msat, comment, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            receipt.id, receipt.sender, receipt.recipient, receipt.event_id,
+            receipt.amount_msat as i64, receipt.comment, receipt.created_at as i64,
+        ],
+    ).map_err(|e| format!("Failed to save zap: {}", e))?;
+    drop(conn);
+    if inserted == 0 {
+        return Ok(false);
+    }
+    if let Ok(mut totals) = TOTALS.lock() {
+        if totals.loaded {
+            let me = my_npub();
+            totals.add(receipt.event_id.as_deref(), &receipt.sender, &receipt.recipient, receipt.amount_msat, me.as_deref());
+        }
+    }
+    Ok(true)
+}
+
+/// Store an arriving receipt and push the new totals to the UI: `message_update` for a zapped
+/// message we hold, `profile_update` for the other party. Returns the receipt when it was new.
+pub async fn ingest(event: &Event) -> Option<ZapReceipt> {
+    let receipt = parse_receipt(event)?;
+    let me = my_npub()?;
+    if receipt.sender != me && receipt.recipient != me {
+        return None;
+    }
+    if !signed_by_provider(&receipt, &me).await {
+        return None;
+    }
+    if !record(&receipt).ok()? {
+        return None;
+    }
+
+    let state = crate::state::STATE.lock().await;
+    if let Some((chat, msg)) = receipt.event_id.as_deref().and_then(|id| state.find_message(id)) {
+        crate::traits::emit_event("message_update", &serde_json::json!({
+            "old_id": &msg.id,
+            "message": &msg,
+            "chat_id": chat.id(),
+        }));
+    }
+    let other = if receipt.sender == me { &receipt.recipient } else { &receipt.sender };
+    if let Some(slim) = state.interner.lookup(other).and_then(|id| state.serialize_profile(id)) {
+        crate::traits::emit_event("profile_update", &slim);
+    }
+    drop(state);
+    Some(receipt)
+}
+
+// ============================================================================
+// Totals
+// ============================================================================
+
+#[derive(Default)]
+struct Totals {
+    loaded: bool,
+    /// Zapped event id (hex) → msat.
+    by_event: HashMap<String, u64>,
+    /// Contact npub → zaps between us.
+    by_contact: HashMap<String, ZapTotals>,
+}
+
+impl Totals {
+    fn add(&mut self, event_id: Option<&str>, sender: &str, recipient: &str, amount_msat: u64, me: Option<&str>) {
+        if let Some(id) = event_id {
+            *self.by_event.entry(id.to_string()).or_default() += amount_msat;
+        }
+        if me == Some(sender) && sender != recipient {
+            self.by_contact.entry(recipient.to_string()).or_default().sent_msat += amount_msat;
+        } else if me == Some(recipient) {
+            self.by_contact.entry(sender.to_string()).or_default().received_msat += amount_msat;
+        }
+    }
+
+    /// Sum the stored receipts, once per session. Stays unloaded (and retries) until the
+    /// account's DB is open.
+    fn ensure_loaded(&mut self) {
+        if self.loaded {
+            return;
+        }
+        let Ok(conn) = crate::db::get_db_connection_guard_static() else { return };
+        let Ok(mut stmt) = conn.prepare("SELECT event_id, sender, recipient, amount_msat FROM zaps") else { return };
+        let Ok(rows) = stmt.query_map([], |r| {
+            Ok((r.get::<_, Option<String>>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?, r.get::<_, i64>(3)?))
+        }) else { return };
+        let me = my_npub();
+        for (event_id, sender, recipient, amount) in rows.flatten() {
+            self.add(event_id.as_deref(), &sender, &recipient, amount.max(0) as u64, me.as_deref());
+        }
+        self.loaded = true;
+    }
+}
+
+static TOTALS: LazyLock<Mutex<Totals>> = LazyLock::new(|| Mutex::new(Totals::default()));
+
+fn my_npub() -> Option<String> {
+    crate::state::my_public_key().and_then(|pk| pk.to_bech32().ok())
+}
+
+/// Total zapped on a message (msat).
+pub fn message_total(message_id: &str) -> u64 {
+    let Ok(mut totals) = TOTALS.lock() else { return 0 };
+    totals.ensure_loaded();
+    totals.by_event.get(message_id).copied().unwrap_or(0)
+}
+
+/// Zaps between us and `npub`.
+pub fn profile_totals(npub: &str) -> ZapTotals {
+    let Ok(mut totals) = TOTALS.lock() else { return ZapTotals::default() };
+    totals.ensure_loaded();
+    totals.by_contact.get(npub).copied().unwrap_or_default()
+}
+
+/// Forget the loaded totals — called on account swap.
+pub fn clear_session() {
+    if let Ok(mut totals) = TOTALS.lock() {
+        *totals = Totals::default();
+    }
+    *OWN_PROVIDER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bolt11_amounts_follow_the_multiplier() {
+        // BOLT11's own examples
+        assert_eq!(bolt11_amount_msat("lnbc2500u1pvjluezpp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfq"), Some(250_000_000));
+        assert_eq!(bolt11_amount_msat("LNBC20M1PVJLUEZPP5QQQSYQCYQ5RQWZQFQ"), Some(2_000_000_000));
+        assert_eq!(bolt11_amount_msat("lightning:lnbc9678785340p1pwmna7lpp5gc3xfm08u9qy06djf8dfflhugl6p7lgza6dsjxq454gxhj9t7a0s"), Some(967_878_534));
+        assert_eq!(bolt11_amount_msat("lntb10n1pqqqqq"), Some(1_000));
+        // Amountless, sub-msat and garbage
+        assert_eq!(bolt11_amount_msat("lnbc1pvjluezpp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfq"), None);
+        assert_eq!(bolt11_amount_msat("lnbc1p1pqqqqq"), None);
+        assert_eq!(bolt11_amount_msat("not an invoice"), None);
+    }
+
+    #[test]
+    fn lightning_addresses_resolve_to_lnurlp() {
+        let url = lnurl_pay_url("Satoshi@Example.com", "").unwrap();
+        assert_eq!(url.as_str(), "https://example.com/.well-known/lnurlp/satoshi");
+        assert!(lnurl_pay_url("bad name@example.com", "").is_err());
+        assert!(lnurl_pay_url("", "").is_err());
+
+        // lud06 round-trips through our own encoder
+        let lnurl = encode_lnurl(&url).unwrap();
+        assert!(lnurl.starts_with("lnurl1"));
+        assert_eq!(lnurl_pay_url("", &lnurl.to_uppercase()).unwrap(), url);
+        assert!(lnurl_pay_url("", "lnurl1qqqqqq").is_err());
+    }
+
+    #[tokio::test]
+    async fn private_lnurl_hosts_are_never_fetched() {
+        for address in ["alice@127.0.0.1", "alice@192.168.1.5", "alice@localhost"] {
+            let url = lnurl_pay_url(address, "").unwrap();
+            let err = fetch_endpoint(&url).await.unwrap_err();
+            assert!(err.contains("can't be used"), "{address}: {err}");
+        }
+    }
+
+    fn receipt(wallet: &Keys, request: &Event, recipient: PublicKey, bolt11: &str) -> Event {
+        EventBuilder::new(Kind::Custom(KIND_ZAP_RECEIPT), "")
+            .tags([
+                Tag::public_key(recipient),
+                Tag::custom(TagKind::custom("bolt11"), [bolt11.to_string()]),
+                Tag::custom(TagKind::custom("description"), [request.as_json()]),
+            ])
+            .sign_with_keys(wallet)
+            .unwrap()
+    }
+
+    #[test]
+    fn receipts_need_a_signed_request_for_the_paid_amount() {
+        let (sender, recipient, wallet) = (Keys::generate(), Keys::generate(), Keys::generate());
+        let message_id = "ab".repeat(32);
+        let request = zap_request(
+            &recipient.public_key().to_hex(), Some(&message_id), 2_500_000, " Great point ",
+            vec!["wss://relay.example".into()], "lnurl1test",
+        ).unwrap().sign_with_keys(&sender).unwrap();
+
+        let parsed = parse_receipt(&receipt(&wallet, &request, recipient.public_key(), "lnbc25u1pqqqqq")).unwrap();
+        assert_eq!(parsed.amount_msat, 2_500_000);
+        assert_eq!(parsed.sender, sender.public_key().to_bech32().unwrap());
+        assert_eq!(parsed.recipient, recipient.public_key().to_bech32().unwrap());
+        assert_eq!(parsed.event_id, Some(message_id));
+        assert_eq!(parsed.comment, "Great point");
+        assert_eq!(parsed.request_id, request.id.to_hex());
+        assert_eq!(parsed.provider, wallet.public_key().to_hex());
+
+        // Paid amount differs from the request, receipt names someone else, or the request was tampered with
+        assert!(parse_receipt(&receipt(&wallet, &request, recipient.public_key(), "lnbc1u1pqqqqq")).is_none());
+        assert!(parse_receipt(&receipt(&wallet, &request, Keys::generate().public_key(), "lnbc25u1pqqqqq")).is_none());
+        let mut tampered = request.clone();
+        tampered.content = "Send me your nsec".into();
+        assert!(parse_receipt(&receipt(&wallet, &tampered, recipient.public_key(), "lnbc25u1pqqqqq")).is_none());
+
+        // A receipt pinning the zap on another message
+        let elsewhere = EventBuilder::new(Kind::Custom(KIND_ZAP_RECEIPT), "")
+            .tags([
+                Tag::public_key(recipient.public_key()),
+                Tag::event(EventId::from_hex(&"cd".repeat(32)).unwrap()),
+                Tag::custom(TagKind::custom("bolt11"), ["lnbc25u1pqqqqq".to_string()]),
+                Tag::custom(TagKind::custom("description"), [request.as_json()]),
+            ])
+            .sign_with_keys(&wallet)
+            .unwrap();
+        assert!(parse_receipt(&elsewhere).is_none());
+    }
+
+    #[test]
+    fn totals_split_sent_and_received_per_contact() {
+        let mut totals = Totals::default();
+        totals.add(Some("m1"), "npub_me", "npub_alice", 1_000, Some("npub_me"));
+        totals.add(Some("m2"), "npub_alice", "npub_me", 5_000, Some("npub_me"));
+        totals.add(Some("m2"), "npub_bob", "npub_me", 2_000, Some("npub_me"));
+        totals.add(None, "npub_alice", "npub_me", 3_000, Some("npub_me"));
+
+        assert_eq!(totals.by_event["m1"], 1_000);
+        assert_eq!(totals.by_event["m2"], 7_000);
+        assert_eq!(totals.by_contact["npub_alice"], ZapTotals { sent_msat: 1_000, received_msat: 8_000 });
+        assert_eq!(totals.by_contact["npub_bob"], ZapTotals { sent_msat: 0, received_msat: 2_000 });
+    }
+}
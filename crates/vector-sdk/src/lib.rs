@@ -557,7 +557,8 @@ impl VectorBot {
     // ---- attachments ----
 
     /// Download a received attachment and decrypt it to plaintext bytes (fetches the encrypted blob
-    /// from its Blossom URL, then AES-decrypts with the attachment's embedded key + nonce). Find
+    /// from its Blossom URL or one of its mirrors, then AES-decrypts with the attachment's embedded
+    /// key + nonce). Find
     /// attachments on `msg.message.attachments`.
     pub async fn download_attachment(&self, attachment: &Attachment) -> Result<Vec<u8>> {
         self.core.download_attachment(attachment).await
@@ -2,6 +2,9 @@ package io.vectorapp
 
 import android.content.Intent
 import android.content.pm.PackageManager
+import android.net.ConnectivityManager
+import android.net.LinkProperties
+import android.net.Network
 import android.net.Uri
 import android.os.Build
 import android.hardware.Sensor
@@ -34,10 +37,16 @@ class MainActivity : TauriActivity() {
         external fun nativeOnNotificationTap(chatId: String)
         @JvmStatic
         external fun nativeOnShareReceived(uris: Array<String>, text: String)
+        @JvmStatic
+        external fun nativeOnNetworkChanged()
     }
 
     private var managedWebView: WebView? = null
 
+    // Default-network changes (Wi-Fi ↔ cellular, VPN up/down) → native, which reconnects the relays
+    // right away instead of waiting for the health check to find the dead sockets.
+    private var networkCallback: ConnectivityManager.NetworkCallback? = null
+
     // ===== Device-tilt for the badge card =====
     // Started/stopped from JS (window.__vectorGyroBridge) only while the card is open, so the sensor
     // costs no battery otherwise. Uses the GRAVITY vector (preferred) or the raw ACCELEROMETER (present
@@ -131,6 +140,9 @@ class MainActivity : TauriActivity() {
         // Ensure hardware acceleration is enabled
         window.addFlags(android.view.WindowManager.LayoutParams.FLAG_HARDWARE_ACCELERATED)
 
+        // Screen security (FLAG_SECURE) before the first frame, so not even the PIN screen leaks
+        VectorScreenSecurity.apply(this)
+
         // Request notification permission (Android 13+)
         requestNotificationPermission()
 
@@ -144,12 +156,22 @@ class MainActivity : TauriActivity() {
             }
         }
 
+        registerNetworkCallback()
+
         // Handle notification tap that launched the app
         handleNotificationIntent(intent)
         // Handle a file/text share that launched the app
         handleSendIntent(intent)
     }
 
+    override fun onDestroy() {
+        networkCallback?.let { callback ->
+            try { getSystemService(ConnectivityManager::class.java)?.unregisterNetworkCallback(callback) } catch (_: Exception) {}
+        }
+        networkCallback = null
+        super.onDestroy()
+    }
+
     override fun onNewIntent(intent: Intent) {
         super.onNewIntent(intent)
         // Handle notification tap when app is already running
@@ -223,6 +245,25 @@ class MainActivity : TauriActivity() {
         else
             intent.getParcelableArrayListExtra(Intent.EXTRA_STREAM)
 
+    private fun registerNetworkCallback() {
+        val cm = getSystemService(ConnectivityManager::class.java) ?: return
+        val callback = object : ConnectivityManager.NetworkCallback() {
+            override fun onAvailable(network: Network) = changed()
+            override fun onLost(network: Network) = changed()
+            override fun onLinkPropertiesChanged(network: Network, linkProperties: LinkProperties) = changed()
+
+            private fun changed() {
+                try { nativeOnNetworkChanged() } catch (_: Exception) {}
+            }
+        }
+        try {
+            cm.registerDefaultNetworkCallback(callback)
+            networkCallback = callback
+        } catch (e: Exception) {
+            android.util.Log.w("MainActivity", "Network callback unavailable: ${e.message}")
+        }
+    }
+
     private fun requestNotificationPermission() {
         if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU) {
             if (ContextCompat.checkSelfPermission(
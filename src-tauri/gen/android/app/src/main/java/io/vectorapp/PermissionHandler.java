@@ -8,19 +8,31 @@ public class PermissionHandler {
         System.loadLibrary("vector_lib");
     }
 
+    // Request codes owned by the native side (android/permissions.rs)
+    private static final int AUDIO_PERMISSION_REQUEST_CODE = 9876;
+    private static final int NOTIFICATION_PERMISSION_REQUEST_CODE = 9877;
+    private static final int MEDIA_PERMISSION_REQUEST_CODE = 9878;
+    private static final int LOCATION_PERMISSION_REQUEST_CODE = 9879;
+
     // Native callback method
     private static native void onPermissionResult(int requestCode, boolean granted);
 
     // Forwarded from MainActivity.onRequestPermissionsResult — wakes the native
-    // blocking waiter for the RECORD_AUDIO request (voice recording).
+    // blocking waiter for a microphone, notification, media or location request.
     public static void handlePermissionResult(
         int requestCode,
         @NonNull int[] grantResults
     ) {
-        if (requestCode == 9876) { // AUDIO_PERMISSION_REQUEST_CODE
-            boolean granted = grantResults.length > 0 &&
-                            grantResults[0] == PackageManager.PERMISSION_GRANTED;
-            onPermissionResult(requestCode, granted);
+        if (requestCode != AUDIO_PERMISSION_REQUEST_CODE
+            && requestCode != NOTIFICATION_PERMISSION_REQUEST_CODE
+            && requestCode != MEDIA_PERMISSION_REQUEST_CODE
+            && requestCode != LOCATION_PERMISSION_REQUEST_CODE) {
+            return;
+        }
+        boolean granted = grantResults.length > 0;
+        for (int result : grantResults) {
+            if (result != PackageManager.PERMISSION_GRANTED) granted = false;
         }
+        onPermissionResult(requestCode, granted);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,94 @@
+package io.vectorapp
+
+import android.content.Context
+import android.security.keystore.KeyGenParameterSpec
+import android.security.keystore.KeyProperties
+import android.util.Base64
+import java.security.KeyStore
+import javax.crypto.Cipher
+import javax.crypto.KeyGenerator
+import javax.crypto.SecretKey
+import javax.crypto.spec.GCMParameterSpec
+
+/**
+ * Secrets (the account private key, when the user keeps it out of the database)
+ * sealed with an AES-GCM key held by the Android Keystore. The key material never
+ * enters app memory, so the sealed blobs in SharedPreferences are useless when
+ * copied off the device. Called from Rust (keystore.rs) over JNI, keyed by npub.
+ */
+object VectorKeystore {
+
+    private const val TAG = "VectorKeystore"
+    private const val PREFS_NAME = "vector_keystore"
+    private const val KEY_ALIAS = "vector_secret_store"
+    private const val ANDROID_KEYSTORE = "AndroidKeyStore"
+    private const val TRANSFORMATION = "AES/GCM/NoPadding"
+    private const val TAG_BITS = 128
+
+    /**
+     * An entry exists but can't be unsealed: the Keystore key behind it is gone
+     * (a lock-screen reset, a restore onto a new device) or the blob is damaged.
+     */
+    class UnsealException(cause: Throwable) : Exception("Keystore entry can't be unsealed", cause)
+
+    @JvmStatic
+    fun store(context: Context, account: String, secret: String): Boolean {
+        return try {
+            val cipher = Cipher.getInstance(TRANSFORMATION)
+            cipher.init(Cipher.ENCRYPT_MODE, secretKey())
+            val sealed = cipher.doFinal(secret.toByteArray(Charsets.UTF_8))
+            val blob = Base64.encodeToString(cipher.iv, Base64.NO_WRAP) + ":" +
+                Base64.encodeToString(sealed, Base64.NO_WRAP)
+            prefs(context).edit().putString(account, blob).commit()
+        } catch (e: Exception) {
+            android.util.Log.e(TAG, "store failed", e)
+            false
+        }
+    }
+
+    /**
+     * The secret for [account], or null when there's none. Throws [UnsealException]
+     * when there is one but it can't be unsealed, so the caller can tell a lost
+     * secret from an absent one.
+     */
+    @JvmStatic
+    fun load(context: Context, account: String): String? {
+        val blob = prefs(context).getString(account, null) ?: return null
+        return try {
+            val parts = blob.split(":", limit = 2)
+            val iv = Base64.decode(parts[0], Base64.NO_WRAP)
+            val sealed = Base64.decode(parts[1], Base64.NO_WRAP)
+            val cipher = Cipher.getInstance(TRANSFORMATION)
+            cipher.init(Cipher.DECRYPT_MODE, secretKey(), GCMParameterSpec(TAG_BITS, iv))
+            String(cipher.doFinal(sealed), Charsets.UTF_8)
+        } catch (e: Exception) {
+            android.util.Log.e(TAG, "load failed", e)
+            throw UnsealException(e)
+        }
+    }
+
+    @JvmStatic
+    fun delete(context: Context, account: String): Boolean {
+        return prefs(context).edit().remove(account).commit()
+    }
+
+    private fun prefs(context: Context) =
+        context.getSharedPreferences(PREFS_NAME, Context.MODE_PRIVATE)
+
+    private fun secretKey(): SecretKey {
+        val keyStore = KeyStore.getInstance(ANDROID_KEYSTORE).apply { load(null) }
+        (keyStore.getKey(KEY_ALIAS, null) as? SecretKey)?.let { return it }
+        val generator = KeyGenerator.getInstance(KeyProperties.KEY_ALGORITHM_AES, ANDROID_KEYSTORE)
+        generator.init(
+            KeyGenParameterSpec.Builder(
+                KEY_ALIAS,
+                KeyProperties.PURPOSE_ENCRYPT or KeyProperties.PURPOSE_DECRYPT
+            )
+                .setBlockModes(KeyProperties.BLOCK_MODE_GCM)
+                .setEncryptionPaddings(KeyProperties.ENCRYPTION_PADDING_NONE)
+                .setKeySize(256)
+                .build()
+        )
+        return generator.generateKey()
+    }
+}
@@ -23,6 +23,9 @@ class VectorNotificationService : Service() {
         const val SERVICE_CHANNEL_ID = "vector_service"
         const val MESSAGES_CHANNEL_ID = "vector_messages_v2"
         const val SERVICE_NOTIFICATION_ID = 1
+        /** Single shared notification for the "message count only" privacy mode. */
+        const val COUNT_ONLY_NOTIFICATION_ID = 2
+        private const val COUNT_ONLY_HISTORY_KEY = "__count_only__"
 
         /** Incrementing counter for request codes + non-chat notification IDs. */
         private val notificationCounter = AtomicInteger(100)
@@ -51,6 +54,10 @@ class VectorNotificationService : Service() {
          * - Conversation title = group name
          *
          * For DMs (groupName empty), uses a simple notification with sender avatar.
+         *
+         * `visibility` is a NotificationCompat.VISIBILITY_* constant chosen by the user's
+         * lockscreen preference. `countOnly` collapses every chat into one running
+         * "N new messages" notification whose body replaces, rather than appends to, history.
          */
         @JvmStatic
         fun showMessageNotification(
@@ -62,15 +69,20 @@ class VectorNotificationService : Service() {
             senderName: String,
             groupName: String,
             groupAvatarPath: String,
+            visibility: Int,
+            countOnly: Boolean,
+            silent: Boolean,
         ) {
             val manager = context.getSystemService(Context.NOTIFICATION_SERVICE) as NotificationManager
             val isGroup = groupName.isNotEmpty()
-            val historyKey = chatId.ifEmpty { title }
+            val historyKey = if (countOnly) COUNT_ONLY_HISTORY_KEY else chatId.ifEmpty { title }
 
             // Deterministic per-chat notification ID so messages stack AND any later cancel (in-app
             // read or resume) can recompute it without depending on mutable map state that a racing
             // post/cancel could have already cleared.
-            val notificationId = if (chatId.isNotEmpty()) {
+            val notificationId = if (countOnly) {
+                COUNT_ONLY_NOTIFICATION_ID
+            } else if (chatId.isNotEmpty()) {
                 chatId.hashCode()
             } else {
                 notificationCounter.getAndIncrement()
@@ -103,6 +115,7 @@ class VectorNotificationService : Service() {
             // corruption if two notifications arrive simultaneously for the same chat)
             val history = synchronized(chatMessageHistory) {
                 val h = chatMessageHistory.getOrPut(historyKey) { mutableListOf() }
+                if (countOnly) h.clear()
                 h.add(ChatMessage(
                     if (isGroup) senderName.ifEmpty { "Someone" } else senderName.ifEmpty { title },
                     avatarPath, body, System.currentTimeMillis()
@@ -143,11 +156,26 @@ class VectorNotificationService : Service() {
                 .setPriority(NotificationCompat.PRIORITY_HIGH)
                 .setContentIntent(pendingIntent)
                 .setDeleteIntent(deletePendingIntent)
+                .setVisibility(visibility)
+                // Per-chat "sound off": still shown, just without the channel's sound/vibration.
+                .setSilent(silent)
+
+            // Redacted lockscreen stand-in; without one Android shows only the app name.
+            if (visibility == NotificationCompat.VISIBILITY_PRIVATE) {
+                builder.setPublicVersion(
+                    NotificationCompat.Builder(context, MESSAGES_CHANNEL_ID)
+                        .setSmallIcon(R.drawable.ic_notification)
+                        .setContentTitle("Vector")
+                        .setContentText("New message")
+                        .build()
+                )
+            }
 
             // Only add Mark Read / Reply actions when we have a valid chatId.
             // Encrypted account notifications pass empty chatId (can't decrypt),
-            // so these actions would be non-functional.
-            if (chatId.isNotEmpty()) {
+            // so these actions would be non-functional. Count-only notifications aggregate
+            // every chat, so a per-chat action would target an arbitrary one.
+            if (chatId.isNotEmpty() && !countOnly) {
                 val markReadIntent = Intent(context, NotificationActionReceiver::class.java).apply {
                     action = NotificationActionReceiver.ACTION_MARK_READ
                     putExtra("chat_id", chatId)
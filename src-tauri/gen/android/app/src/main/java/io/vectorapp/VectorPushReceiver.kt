@@ -0,0 +1,95 @@
+package io.vectorapp
+
+import android.content.Context
+import android.os.PowerManager
+import org.unifiedpush.android.connector.MessagingReceiver
+import org.unifiedpush.android.connector.UnifiedPush
+
+/**
+ * UnifiedPush wake-ups. A distributor app (ntfy, NextPush, ...) holds the one
+ * long-lived connection and delivers a push when the user's push gateway sees a
+ * gift wrap for them; Rust then catches up against a single relay. The endpoint
+ * is kept in SharedPreferences so Rust can hand it to the gateway whenever the
+ * app is running, even if the distributor issued it while Vector was closed.
+ */
+class VectorPushReceiver : MessagingReceiver() {
+
+    companion object {
+        private const val PREFS_NAME = "vector_prefs"
+        private const val KEY_PUSH_ENDPOINT = "unifiedpush_endpoint"
+        /** Long enough for a cold start, one relay connect, and a reconcile. */
+        private const val WAKE_LOCK_MS = 30_000L
+
+        init {
+            System.loadLibrary("vector_lib")
+        }
+
+        @JvmStatic
+        external fun nativeOnPushEndpoint(endpoint: String)
+        @JvmStatic
+        external fun nativeOnPushMessage(dataDir: String, message: String, context: Context)
+
+        /**
+         * Register with the user's distributor (the first one installed, unless
+         * they picked one before). Returns false when no distributor is installed.
+         */
+        @JvmStatic
+        fun enable(context: Context): Boolean {
+            if (UnifiedPush.getAckDistributor(context) == null) {
+                val distributors = UnifiedPush.getDistributors(context)
+                if (distributors.isEmpty()) return false
+                UnifiedPush.saveDistributor(context, distributors[0])
+            }
+            UnifiedPush.registerApp(context)
+            return true
+        }
+
+        @JvmStatic
+        fun disable(context: Context) {
+            UnifiedPush.unregisterApp(context)
+            setEndpoint(context, "")
+        }
+
+        /** The distributor we're registered with, or "" when push is off. */
+        @JvmStatic
+        fun getDistributor(context: Context): String {
+            return UnifiedPush.getAckDistributor(context) ?: ""
+        }
+
+        /** The endpoint the distributor issued, or "" before it has. */
+        @JvmStatic
+        fun getEndpoint(context: Context): String {
+            val prefs = context.getSharedPreferences(PREFS_NAME, Context.MODE_PRIVATE)
+            return prefs.getString(KEY_PUSH_ENDPOINT, "") ?: ""
+        }
+
+        private fun setEndpoint(context: Context, endpoint: String) {
+            val prefs = context.getSharedPreferences(PREFS_NAME, Context.MODE_PRIVATE)
+            prefs.edit().putString(KEY_PUSH_ENDPOINT, endpoint).commit()
+        }
+    }
+
+    override fun onNewEndpoint(context: Context, endpoint: String, instance: String) {
+        android.util.Log.d("VectorPush", "New UnifiedPush endpoint")
+        setEndpoint(context, endpoint)
+        nativeOnPushEndpoint(endpoint)
+    }
+
+    override fun onMessage(context: Context, message: ByteArray, instance: String) {
+        // The catch-up runs on a Rust thread after we return; keep the CPU up until it's done
+        val pm = context.getSystemService(Context.POWER_SERVICE) as PowerManager
+        pm.newWakeLock(PowerManager.PARTIAL_WAKE_LOCK, "Vector:PushWake").acquire(WAKE_LOCK_MS)
+        val dataDir = context.applicationContext.dataDir.absolutePath
+        nativeOnPushMessage(dataDir, String(message, Charsets.UTF_8), context.applicationContext)
+    }
+
+    override fun onRegistrationFailed(context: Context, instance: String) {
+        android.util.Log.w("VectorPush", "UnifiedPush registration failed")
+    }
+
+    override fun onUnregistered(context: Context, instance: String) {
+        android.util.Log.d("VectorPush", "Unregistered from UnifiedPush")
+        setEndpoint(context, "")
+        nativeOnPushEndpoint("")
+    }
+}
@@ -0,0 +1,43 @@
+package io.vectorapp
+
+import android.app.Activity
+import android.content.Context
+import android.view.WindowManager
+
+/**
+ * Screen security: FLAG_SECURE on the main window, so chat content can't be screenshotted,
+ * screen-recorded or shown in the recent-apps switcher. Device-wide rather than per account and
+ * kept in SharedPreferences, so MainActivity applies it in onCreate before anything (the PIN
+ * screen included) is drawn. Toggled from Rust (android/screen_security.rs) over JNI.
+ */
+object VectorScreenSecurity {
+
+    private const val PREFS_NAME = "vector_screen_security"
+    private const val KEY_ENABLED = "enabled"
+
+    @JvmStatic
+    fun isEnabled(context: Context): Boolean {
+        return prefs(context).getBoolean(KEY_ENABLED, false)
+    }
+
+    /** Save the preference and apply it to [activity]'s window right away. */
+    @JvmStatic
+    fun setEnabled(activity: Activity, enabled: Boolean): Boolean {
+        val saved = prefs(activity).edit().putBoolean(KEY_ENABLED, enabled).commit()
+        activity.runOnUiThread { apply(activity) }
+        return saved
+    }
+
+    /** Apply the saved preference to [activity]'s window. Must run on the UI thread. */
+    @JvmStatic
+    fun apply(activity: Activity) {
+        if (isEnabled(activity)) {
+            activity.window.addFlags(WindowManager.LayoutParams.FLAG_SECURE)
+        } else {
+            activity.window.clearFlags(WindowManager.LayoutParams.FLAG_SECURE)
+        }
+    }
+
+    private fun prefs(context: Context) =
+        context.getSharedPreferences(PREFS_NAME, Context.MODE_PRIVATE)
+}
@@ -0,0 +1,90 @@
+package io.vectorapp
+
+import android.content.Context
+import android.content.Intent
+import android.content.IntentFilter
+import android.os.BatteryManager
+import android.os.PowerManager
+import androidx.work.Constraints
+import androidx.work.ExistingPeriodicWorkPolicy
+import androidx.work.NetworkType
+import androidx.work.PeriodicWorkRequestBuilder
+import androidx.work.WorkManager
+import androidx.work.Worker
+import androidx.work.WorkerParameters
+import java.util.concurrent.TimeUnit
+
+/**
+ * Periodic background sync for users who don't keep the background service
+ * running. WorkManager wakes us every few minutes (the user's choice, 15 at
+ * the least) and Rust runs one short catch-up before we return. The schedule
+ * survives reboots on its own, so nothing needs re-enqueueing at boot.
+ */
+class VectorSyncWorker(context: Context, params: WorkerParameters) : Worker(context, params) {
+
+    companion object {
+        private const val PREFS_NAME = "vector_prefs"
+        private const val KEY_INTERVAL_MINUTES = "periodic_sync_minutes"
+        private const val WORK_NAME = "vector_periodic_sync"
+        /** WorkManager's floor for periodic work. */
+        private const val MIN_INTERVAL_MINUTES = 15
+
+        init {
+            System.loadLibrary("vector_lib")
+        }
+
+        /** Blocks until the catch-up finishes. */
+        @JvmStatic
+        external fun nativeRunPeriodicSync(dataDir: String, context: Context)
+
+        /** Minutes between syncs, 0 when periodic sync is off. */
+        @JvmStatic
+        fun getIntervalMinutes(context: Context): Int {
+            val prefs = context.getSharedPreferences(PREFS_NAME, Context.MODE_PRIVATE)
+            return prefs.getInt(KEY_INTERVAL_MINUTES, 0)
+        }
+
+        /** Save the interval and (re)schedule, or cancel with 0. */
+        @JvmStatic
+        fun setIntervalMinutes(context: Context, minutes: Int) {
+            val interval = if (minutes <= 0) 0 else maxOf(minutes, MIN_INTERVAL_MINUTES)
+            val prefs = context.getSharedPreferences(PREFS_NAME, Context.MODE_PRIVATE)
+            prefs.edit().putInt(KEY_INTERVAL_MINUTES, interval).commit()
+
+            val workManager = WorkManager.getInstance(context)
+            if (interval == 0) {
+                workManager.cancelUniqueWork(WORK_NAME)
+                return
+            }
+            val constraints = Constraints.Builder()
+                .setRequiredNetworkType(NetworkType.CONNECTED)
+                .setRequiresBatteryNotLow(true)
+                .build()
+            val request = PeriodicWorkRequestBuilder<VectorSyncWorker>(interval.toLong(), TimeUnit.MINUTES)
+                .setConstraints(constraints)
+                .build()
+            workManager.enqueueUniquePeriodicWork(WORK_NAME, ExistingPeriodicWorkPolicy.UPDATE, request)
+        }
+    }
+
+    override fun doWork(): Result {
+        val context = applicationContext
+        // The background service already holds a live connection
+        if (VectorBatteryHelper.getBackgroundServiceEnabled(context)) return Result.success()
+        // Battery Saver on and unplugged: skip this round, the next one may be luckier
+        if (isPowerSaving(context)) {
+            android.util.Log.d("VectorSyncWorker", "Battery Saver on, skipping periodic sync")
+            return Result.success()
+        }
+        nativeRunPeriodicSync(context.dataDir.absolutePath, context)
+        return Result.success()
+    }
+
+    private fun isPowerSaving(context: Context): Boolean {
+        val pm = context.getSystemService(Context.POWER_SERVICE) as PowerManager
+        if (!pm.isPowerSaveMode) return false
+        val battery = context.registerReceiver(null, IntentFilter(Intent.ACTION_BATTERY_CHANGED))
+        val plugged = battery?.getIntExtra(BatteryManager.EXTRA_PLUGGED, 0) ?: 0
+        return plugged == 0
+    }
+}
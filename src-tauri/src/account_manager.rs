@@ -82,7 +82,7 @@ pub fn get_profile_directory<R: Runtime>(
         .map_err(|e| format!("Failed to get app data dir: {}", e))?;
 
     // Validate npub format
-    if !npub.starts_with("npub1") {
+    if !vector_core::ids::is_npub(npub) {
         return Err(format!("Invalid npub format: {}", npub));
     }
 
@@ -132,7 +132,7 @@ pub fn list_accounts<R: Runtime>(handle: &AppHandle<R>) -> Result<Vec<String>, S
             let Ok(ft) = entry.file_type() else { continue; };
             if !ft.is_dir() || ft.is_symlink() { continue; }
             let Some(name) = entry.file_name().to_str().map(|s| s.to_string()) else { continue; };
-            if !name.starts_with("npub1") { continue; }
+            if !vector_core::ids::is_npub(&name) { continue; }
             if let Ok(true) = account_is_valid(handle, &name) {
                 accounts.push(name);
             }
@@ -161,7 +161,7 @@ pub fn prune_invalid_accounts<R: Runtime>(handle: &AppHandle<R>) -> Result<Vec<S
             let Ok(ft) = entry.file_type() else { continue; };
             if !ft.is_dir() || ft.is_symlink() { continue; }
             let Some(name) = entry.file_name().to_str().map(|s| s.to_string()) else { continue; };
-            if !name.starts_with("npub1") { continue; }
+            if !vector_core::ids::is_npub(&name) { continue; }
 
             if matches!(account_is_valid(handle, &name), Ok(false)) {
                 let dir = entry.path();
@@ -673,6 +673,7 @@ pub async fn reset_session() {
     // Active-chat marker is an npub; a shared contact across accounts would
     // otherwise let account A's open chat auto-mark account B's messages.
     vector_core::state::set_active_chat(None);
+    crate::services::read_tracker::reset();
 
     // Profile sync queue (long-lived processor loop services this queue
     // forever, so we drain it instead of cancelling the task).
@@ -745,11 +746,27 @@ pub async fn reset_session() {
     // account-agnostic, but holds privacy-adjacent metadata about every
     // contact account A messaged. Drop on swap.
     vector_core::inbox_relays::clear_inbox_relay_cache();
+    vector_core::compression::clear_session();
+    vector_core::relay_health::clear_session();
+    vector_core::blossom_health::clear_session();
+    vector_core::consent::clear_session();
+    vector_core::contact_verification::clear_session();
+    vector_core::crypto::content_cache::clear_session();
+    vector_core::typing::clear_session();
+    vector_core::location::clear_session();
     // In-flight wrap confirmations carry the prior account's chat and
     // message ids — a late OK must not "rescue" into the new session.
     vector_core::sending::clear_wrap_confirms();
+    // Parked failed uploads belong to the prior account's messages.
+    vector_core::sending::clear_parked_uploads();
     // Pack-author NIP-65 cache — same privacy parity as the inbox cache.
     vector_core::emoji_packs::clear_nip65_cache();
+    // Resolved nostr: reference previews — fetched on account A's behalf.
+    vector_core::nostr_uri::clear_cache();
+    // Zap totals — summed from account A's receipts.
+    vector_core::zaps::clear_session();
+    // Starred message ids — account A's.
+    vector_core::starred::clear_session();
 
     // PIVX address→balance cache — addresses derive from user keys, so
     // a cached entry from account A is meaningless (and slightly
@@ -841,6 +858,15 @@ pub async fn delete_account<R: Runtime>(
         }
     }
 
+    // A key or seed kept in the system keystore outlives the directory; drop them too.
+    if let Ok(store) = vector_core::traits::secret_store() {
+        for entry in [npub.clone(), vector_core::db::seed_entry(&npub)] {
+            if let Err(e) = store.delete(&entry) {
+                eprintln!("[delete_account] Failed to remove keystore entry: {}", e);
+            }
+        }
+    }
+
     // LAST-ACCOUNT CASCADE: when this delete leaves zero accounts on disk,
     // wipe shared caches no remaining account needs — the downloads dir
     // (shared across every Vector account on the device) and the legacy
@@ -22,7 +22,7 @@ use crate::commands::relays::DEFAULT_RELAYS;
 use crate::services::event_handler::handle_event_with_context;
 
 /// Log to Android logcat via NDK. println!/eprintln! go to /dev/null on Android.
-fn logcat(msg: &str) {
+pub(crate) fn logcat(msg: &str) {
     use std::ffi::CString;
     extern "C" {
         fn __android_log_write(prio: i32, tag: *const std::ffi::c_char, text: *const std::ffi::c_char) -> i32;
@@ -61,6 +61,10 @@ static STOP_NOTIFY: std::sync::LazyLock<tokio::sync::Notify> = std::sync::LazyLo
 /// Whether the standalone sync thread is currently running
 static STANDALONE_SYNC_RUNNING: AtomicBool = AtomicBool::new(false);
 
+/// Set while a one-shot catch-up (push wake-up or periodic sync) runs, so overlapping triggers
+/// cost one sync.
+pub(crate) static CATCH_UP_RUNNING: AtomicBool = AtomicBool::new(false);
+
 /// Stored JavaVM for cross-thread JNI calls (set from JNI entry points)
 pub static BG_JAVA_VM: OnceLock<JavaVM> = OnceLock::new();
 
@@ -113,6 +117,15 @@ pub extern "C" fn Java_io_vectorapp_MainActivity_nativeOnResume(
     if let Some(chat_id) = vector_core::state::get_active_chat() {
         cancel_notification_jni(&chat_id);
     }
+
+    // Auto-lock: lock on return if the user chose to lock on leaving, or the time away ran out
+    // the idle timeout. Runs after the client restore above so the teardown takes the real client.
+    if let Some(handle) = crate::TAURI_APP.get() {
+        let handle = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            crate::services::auto_lock::on_resume(&handle).await;
+        });
+    }
 }
 
 /// Called from MainActivity.onPause via JNI
@@ -123,6 +136,9 @@ pub extern "C" fn Java_io_vectorapp_MainActivity_nativeOnPause(
 ) {
     ACTIVITY_IN_FOREGROUND.store(false, Ordering::Release);
     logcat("Activity paused (background)");
+    if let Some(handle) = crate::TAURI_APP.get() {
+        crate::services::auto_lock::on_suspend(handle);
+    }
 
     // Start standalone sync if the foreground service is active but standalone sync isn't running.
     // This handles the case where the app was open (standalone sync was skipped), and the user
@@ -232,23 +248,7 @@ pub extern "C" fn Java_io_vectorapp_VectorNotificationService_nativeStartBackgro
     BACKGROUND_SYNC_ACTIVE.store(true, Ordering::SeqCst);
     STOP_STANDALONE_SYNC.store(false, Ordering::SeqCst);
 
-    // Store the JavaVM and application context for cross-thread JNI calls.
-    if BG_JAVA_VM.get().is_none() {
-        match env.get_java_vm() {
-            Ok(vm) => { let _ = BG_JAVA_VM.set(vm); }
-            Err(e) => logcat(&format!("Failed to get JavaVM: {:?}", e)),
-        }
-    }
-    if BG_APP_CONTEXT.get().is_none() {
-        match env.new_global_ref(&context) {
-            Ok(global_ref) => { let _ = BG_APP_CONTEXT.set(global_ref); }
-            Err(e) => logcat(&format!("Failed to create context GlobalRef: {:?}", e)),
-        }
-    }
-
-    // Register the NIP-55 signer backend for service-only mode (no Activity, so
-    // lib.rs's setup hook never ran). Idempotent — a no-op in full-app mode.
-    crate::android::external_signer::register();
+    store_jni_refs(&mut env, &context);
 
     let data_dir_str: String = match env.get_string(&data_dir) {
         Ok(s) => s.into(),
@@ -290,6 +290,25 @@ pub extern "C" fn Java_io_vectorapp_VectorNotificationService_nativeStartBackgro
     });
 }
 
+/// Store the JavaVM and application context for cross-thread JNI calls, and register the NIP-55
+/// signer backend for service-only mode (no Activity, so lib.rs's setup hook never ran). Both are
+/// idempotent — a no-op in full-app mode.
+pub(crate) fn store_jni_refs(env: &mut JNIEnv, context: &JObject) {
+    if BG_JAVA_VM.get().is_none() {
+        match env.get_java_vm() {
+            Ok(vm) => { let _ = BG_JAVA_VM.set(vm); }
+            Err(e) => logcat(&format!("Failed to get JavaVM: {:?}", e)),
+        }
+    }
+    if BG_APP_CONTEXT.get().is_none() {
+        match env.new_global_ref(context) {
+            Ok(global_ref) => { let _ = BG_APP_CONTEXT.set(global_ref); }
+            Err(e) => logcat(&format!("Failed to create context GlobalRef: {:?}", e)),
+        }
+    }
+    crate::android::external_signer::register();
+}
+
 /// Called when transitioning back to foreground or when service is destroyed.
 /// Signals the standalone sync thread to stop.
 #[no_mangle]
@@ -494,7 +513,7 @@ fn run_standalone_sync_loop(data_dir: &str) {
                         ).await;
                     } else {
                         // Encrypted account — can't decrypt, but we know something arrived
-                        post_notification_jni("Vector", "You have a new message", None, None, None, None, None);
+                        post_notification_jni("Vector", "You have a new message", None, None, None, None, None, false);
                     }
 
                     // Cap the seen set to prevent unbounded memory growth
@@ -528,6 +547,113 @@ fn run_standalone_sync_loop(data_dir: &str) {
     });
 }
 
+/// One-shot catch-up for a push wake-up or periodic sync (see `android::push` and
+/// `android::periodic_sync`) in a process with no live relay connection: bootstrap like the
+/// standalone sync, reconcile against a single relay, notify for what was missed, and disconnect
+/// again.
+pub(crate) fn run_wake_fetch(data_dir: &str, relay_hint: Option<String>) {
+    install_bg_panic_logger();
+
+    let rt = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(rt) => rt,
+        Err(e) => {
+            logcat(&format!("Failed to create tokio runtime: {:?}", e));
+            return;
+        }
+    };
+
+    if let Err(e) = bootstrap_pipeline(data_dir) {
+        logcat(&format!("Failed to bootstrap pipeline: {}", e));
+        return;
+    }
+    // A stop left over from an earlier standalone sync would abort the relay connect below
+    STOP_STANDALONE_SYNC.store(false, Ordering::SeqCst);
+
+    rt.block_on(async {
+        if let Err(e) = crate::commands::tor::sync_to_active_account().await {
+            logcat(&format!("Tor bootstrap for catch-up failed: {} (relays will blackhole)", e));
+        }
+        let (client, my_public_key, can_decrypt, keys) = match bootstrap_client(data_dir).await {
+            Ok(result) => result,
+            Err(e) => {
+                logcat(&format!("Failed to bootstrap client: {}", e));
+                return;
+            }
+        };
+        drop(keys);
+        if can_decrypt {
+            preload_profiles_into_state().await;
+        }
+
+        wake_fetch(&client, my_public_key, relay_hint.as_deref(), can_decrypt).await;
+
+        client.disconnect().await;
+        crate::commands::tor::stop_and_join_if_running().await;
+    });
+}
+
+/// Reconcile our recent gift wraps against one relay and handle whatever it has that we don't,
+/// as new (notifying) events. Prefers `relay_hint` — where the push gateway saw the wrap — when
+/// it's one of the client's relays, else whichever relay is connected, else the first one.
+pub(crate) async fn wake_fetch(client: &Client, my_public_key: PublicKey, relay_hint: Option<&str>, can_decrypt: bool) {
+    let url = {
+        let relays = client.relays().await;
+        relay_hint
+            .filter(|hint| relays.keys().any(|u| u.to_string() == *hint))
+            .map(str::to_string)
+            .or_else(|| relays.iter()
+                .find(|(_, r)| matches!(r.status(), RelayStatus::Connected))
+                .map(|(u, _)| u.to_string()))
+            // Every socket dropped while backgrounded: reconnect below and use the first relay
+            .or_else(|| relays.keys().next().map(|u| u.to_string()))
+    };
+    let Some(url) = url else {
+        logcat("Catch-up: no relay to sync with");
+        return;
+    };
+
+    // A backgrounded app's sockets may have been dropped; give the relay a moment to come back
+    client.connect().await;
+    for _ in 0..20 {
+        let connected = client.relays().await.iter()
+            .any(|(u, r)| u.to_string() == url && matches!(r.status(), RelayStatus::Connected));
+        if connected {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    let missing = match crate::commands::sync::reconcile_relay(client, my_public_key, &url).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            logcat(&format!("Catch-up: {} reconcile failed: {}", url, e));
+            return;
+        }
+    };
+    logcat(&format!("Catch-up: {} missing from {}", missing.len(), url));
+    if missing.is_empty() {
+        return;
+    }
+    if !can_decrypt {
+        // Encrypted account — can't decrypt, but we know something arrived
+        post_notification_jni("Vector", "You have a new message", None, None, None, None, None, false);
+        return;
+    }
+
+    let filter = Filter::new().ids(missing).kind(Kind::GiftWrap);
+    match client.fetch_events_from(vec![url.clone()], filter, std::time::Duration::from_secs(20)).await {
+        Ok(events) => {
+            for event in events {
+                handle_event_with_context(event, true, client, my_public_key).await;
+            }
+        }
+        Err(e) => logcat(&format!("Catch-up: fetch from {} failed: {:?}", url, e)),
+    }
+}
+
 /// Connect the background client to a SINGLE relay for battery efficiency.
 /// Tries each candidate relay in order until one connects successfully.
 ///
@@ -554,7 +680,7 @@ async fn bg_connect_single_relay(client: &Client, data_dir: &str) -> Result<(),
                 entries.flatten()
                     .filter(|e| matches!(e.file_type(), Ok(ft) if ft.is_dir() && !ft.is_symlink()))
                     .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
-                    .find(|n| n.starts_with("npub1"))
+                    .find(|n| vector_core::ids::is_npub(n))
             })
         })
         .map(|npub| data_path.join(npub));
@@ -679,7 +805,7 @@ async fn bootstrap_client(data_dir: &str) -> Result<(Client, PublicKey, bool, Op
                 let Ok(ft) = entry.file_type() else { continue; };
                 if !ft.is_dir() || ft.is_symlink() { continue; }
                 if let Some(n) = entry.file_name().to_str() {
-                    if n.starts_with("npub1") {
+                    if vector_core::ids::is_npub(n) {
                         logcat(&format!("No marker; falling back to first npub dir: {}", n));
                         name = n.to_string();
                         dir = Some(entry.path());
@@ -807,7 +933,7 @@ async fn bootstrap_client(data_dir: &str) -> Result<(Client, PublicKey, bool, Op
         }
 
         // Normal (local) account — full signer client
-        let pkey: String = conn
+        let mut pkey: String = conn
             .query_row(
                 "SELECT value FROM settings WHERE key = 'pkey'",
                 [],
@@ -817,6 +943,12 @@ async fn bootstrap_client(data_dir: &str) -> Result<(Client, PublicKey, bool, Op
 
         drop(conn);
 
+        // Key kept in the Android Keystore: the row is only a pointer to it
+        if pkey == vector_core::db::PKEY_IN_KEYSTORE {
+            pkey = crate::keystore::load(&npub_name)?
+                .ok_or("Private key missing from the Android Keystore")?;
+        }
+
         let keys = Keys::parse(&pkey)
             .map_err(|e| format!("Failed to parse stored key: {:?}", e))?;
 
@@ -870,7 +1002,7 @@ fn bootstrap_pipeline(data_dir: &str) -> Result<String, String> {
         let mut found = None;
         for entry in entries.flatten() {
             if let Some(name) = entry.file_name().to_str() {
-                if name.starts_with("npub1") {
+                if vector_core::ids::is_npub(name) {
                     found = Some(name.to_string());
                     break;
                 }
@@ -931,37 +1063,38 @@ pub fn post_notification_jni(
     sender_name: Option<&str>,
     group_name: Option<&str>,
     group_avatar_path: Option<&str>,
+    silent: bool,
 ) {
     // Don't post notifications when the user is actively using the app
     if is_activity_in_foreground() {
         return;
     }
 
-    // Apply the user's content-privacy preference. This is the single chokepoint
-    // for every Android notification: the foreground path (show_notification_generic)
+    // Apply the user's shaping preferences. This is the single chokepoint for
+    // every Android notification: the foreground path (show_notification_generic)
     // and the background-sync service both land here. chat_id is untouched so
     // tap-to-open still works (it isn't displayed).
-    let (title, body, avatar_path, sender_name, group_name, group_avatar_path):
-        (String, String, Option<String>, Option<String>, Option<String>, Option<String>) =
-        match crate::services::notif_content_privacy() {
-            crate::services::NotifContentPrivacy::Full => (
-                title.to_string(), body.to_string(),
-                avatar_path.map(str::to_string), sender_name.map(str::to_string),
-                group_name.map(str::to_string), group_avatar_path.map(str::to_string),
-            ),
-            crate::services::NotifContentPrivacy::HideContent => {
-                let b = if group_name.is_some() { "Sent a message" } else { "Sent you a message" };
-                (
-                    title.to_string(), b.to_string(),
-                    avatar_path.map(str::to_string), sender_name.map(str::to_string),
-                    group_name.map(str::to_string), group_avatar_path.map(str::to_string),
-                )
-            }
-            crate::services::NotifContentPrivacy::HideAll => (
-                "Vector".to_string(), "You received a message".to_string(),
-                None, None, None, None,
-            ),
-        };
+    let shaping = crate::services::notif_shaping();
+    let mut data = crate::services::NotificationData {
+        notification_type: if group_name.is_some() {
+            crate::services::NotificationType::CommunityMessage
+        } else {
+            crate::services::NotificationType::DirectMessage
+        },
+        title: title.to_string(),
+        body: body.to_string(),
+        group_name: group_name.map(str::to_string),
+        sender_name: sender_name.map(str::to_string),
+        avatar_path: avatar_path.map(str::to_string),
+        group_avatar_path: group_avatar_path.map(str::to_string),
+        chat_id: chat_id.map(str::to_string),
+        ping: false,
+    };
+    data.apply_shaping(&shaping, crate::services::pending_unread_total());
+    let count_only = shaping.privacy == crate::services::NotifContentPrivacy::CountOnly;
+    let crate::services::NotificationData {
+        title, body, avatar_path, sender_name, group_name, group_avatar_path, ..
+    } = data;
 
     let vm = match BG_JAVA_VM.get() {
         Some(vm) => vm,
@@ -1034,8 +1167,14 @@ pub fn post_notification_jni(
         env.call_static_method(
             &service_jclass,
             "showMessageNotification",
-            "(Landroid/content/Context;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;)V",
-            &[context.into(), (&jtitle).into(), (&jbody).into(), (&javatar).into(), (&jchat_id).into(), (&jsender_name).into(), (&jgroup_name).into(), (&jgroup_avatar).into()],
+            "(Landroid/content/Context;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;IZZ)V",
+            &[
+                context.into(), (&jtitle).into(), (&jbody).into(), (&javatar).into(), (&jchat_id).into(),
+                (&jsender_name).into(), (&jgroup_name).into(), (&jgroup_avatar).into(),
+                jni::objects::JValue::Int(shaping.lockscreen.android_flag()),
+                jni::objects::JValue::Bool(count_only as u8),
+                jni::objects::JValue::Bool(silent as u8),
+            ],
         )
         .map_err(|e| format!("Failed to call showMessageNotification: {:?}", e))?;
 
@@ -1204,6 +1343,7 @@ pub extern "C" fn Java_io_vectorapp_NotificationActionReceiver_nativeSendReply(
                         Some(&sender_label),
                         None,
                         None,
+                        false,
                     );
                 }
                 Err(e) => {
@@ -1217,6 +1357,7 @@ pub extern "C" fn Java_io_vectorapp_NotificationActionReceiver_nativeSendReply(
                         None,
                         None,
                         None,
+                        false,
                     );
                 }
             }
@@ -1224,8 +1365,12 @@ pub extern "C" fn Java_io_vectorapp_NotificationActionReceiver_nativeSendReply(
     });
 }
 
+/// Check if the standalone sync thread (the backgrounded or service-only relay connection) is running
+pub fn is_standalone_sync_running() -> bool {
+    STANDALONE_SYNC_RUNNING.load(Ordering::SeqCst)
+}
+
 /// Check if background sync is currently active (foreground service running)
-#[allow(dead_code)]
 pub fn is_background_sync_active() -> bool {
     BACKGROUND_SYNC_ACTIVE.load(Ordering::SeqCst)
 }
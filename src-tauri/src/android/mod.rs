@@ -5,7 +5,10 @@ pub mod filesystem;
 pub mod media_server;
 pub mod miniapp;
 pub mod miniapp_jni;
+pub mod periodic_sync;
 pub mod permissions;
+pub mod push;
+pub mod screen_security;
 pub mod storage;
 pub mod updates;
 pub mod utils;
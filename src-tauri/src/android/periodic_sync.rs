@@ -0,0 +1,94 @@
+//! Periodic background sync.
+//!
+//! For users who keep the background service off, `VectorSyncWorker.kt` has WorkManager run a
+//! short catch-up every few minutes (15 at the least; the user picks the interval, and the worker
+//! skips rounds on low battery or Battery Saver). Each round reconciles recent gift wraps against
+//! one relay and notifies for what was missed. When the app's process is still alive in the
+//! background, it also re-syncs Communities with the app's own client.
+
+use jni::objects::{JClass, JObject, JString};
+use jni::JNIEnv;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use super::background_sync::{self, logcat};
+
+/// Cap on the Community re-sync, so one slow relay can't hold the worker (and the wake lock
+/// WorkManager keeps for it) open.
+const COMMUNITY_SYNC_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Called from VectorSyncWorker.doWork on WorkManager's thread; returns once the round is done.
+#[no_mangle]
+pub extern "C" fn Java_io_vectorapp_VectorSyncWorker_nativeRunPeriodicSync(
+    mut env: JNIEnv,
+    _class: JClass,
+    data_dir: JString<'_>,
+    context: JObject<'_>,
+) {
+    // A live connection (the open app, or the standalone sync) already delivers
+    if background_sync::is_activity_in_foreground() || background_sync::is_standalone_sync_running() {
+        return;
+    }
+    let data_dir: String = match env.get_string(&data_dir) {
+        Ok(s) => s.into(),
+        Err(_) => return,
+    };
+    background_sync::store_jni_refs(&mut env, &context);
+
+    if background_sync::CATCH_UP_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    logcat("Periodic sync starting");
+
+    // The app's process is still alive (backgrounded): sync with its own client
+    let app_client = crate::TAURI_APP.get()
+        .and_then(|_| crate::nostr_client().zip(crate::my_public_key()));
+    match app_client {
+        Some((client, my_public_key)) => {
+            tauri::async_runtime::block_on(async move {
+                background_sync::wake_fetch(&client, my_public_key, None, true).await;
+                let communities = crate::commands::community::sync_communities_boot();
+                if tokio::time::timeout(COMMUNITY_SYNC_TIMEOUT, communities).await.is_err() {
+                    logcat("Periodic sync: Community sync timed out");
+                }
+            });
+        }
+        None => background_sync::run_wake_fetch(&data_dir, None),
+    }
+
+    background_sync::CATCH_UP_RUNNING.store(false, Ordering::SeqCst);
+    logcat("Periodic sync finished");
+}
+
+/// Minutes between periodic syncs, 0 when off.
+pub fn interval_minutes() -> Result<u32, String> {
+    super::utils::with_android_context(|env, context| {
+        let worker_class = load_worker_class(env, context)?;
+        let minutes = env.call_static_method(&worker_class, "getIntervalMinutes",
+            "(Landroid/content/Context;)I", &[context.into()])
+            .map_err(|e| format!("{:?}", e))?.i().map_err(|e| format!("{:?}", e))?;
+        Ok(minutes.max(0) as u32)
+    })
+}
+
+/// Save the interval and reschedule the worker; 0 cancels it.
+pub fn set_interval_minutes(minutes: u32) -> Result<(), String> {
+    super::utils::with_android_context(|env, context| {
+        let worker_class = load_worker_class(env, context)?;
+        env.call_static_method(&worker_class, "setIntervalMinutes", "(Landroid/content/Context;I)V",
+            &[context.into(), jni::objects::JValue::Int(minutes.min(i32::MAX as u32) as i32)])
+            .map_err(|e| format!("{:?}", e))?;
+        Ok(())
+    })
+}
+
+fn load_worker_class<'a>(env: &mut JNIEnv<'a>, context: &JObject<'a>) -> Result<JClass<'a>, String> {
+    let class_loader = env.call_method(context, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])
+        .map_err(|e| format!("{:?}", e))?.l().map_err(|e| format!("{:?}", e))?;
+    let class_name = env.new_string("io.vectorapp.VectorSyncWorker")
+        .map_err(|e| format!("{:?}", e))?;
+    let worker_class = env.call_method(&class_loader, "loadClass", "(Ljava/lang/String;)Ljava/lang/Class;",
+        &[jni::objects::JValue::Object(&class_name)])
+        .map_err(|e| format!("{:?}", e))?.l().map_err(|e| format!("{:?}", e))?;
+    Ok(JClass::from(worker_class))
+}
@@ -5,53 +5,138 @@ use jni::objects::{JClass, JObject, JValue};
 use jni::sys::{jboolean, jint, JNI_TRUE};
 use jni::JNIEnv;
 
-use super::utils::{with_android_activity, with_android_context};
+use super::utils::{get_system_service, with_android_activity, with_android_context};
+
+const RECORD_AUDIO: &str = "android.permission.RECORD_AUDIO";
+const POST_NOTIFICATIONS: &str = "android.permission.POST_NOTIFICATIONS";
+const READ_MEDIA_IMAGES: &str = "android.permission.READ_MEDIA_IMAGES";
+const READ_MEDIA_VIDEO: &str = "android.permission.READ_MEDIA_VIDEO";
+const READ_MEDIA_VISUAL_USER_SELECTED: &str = "android.permission.READ_MEDIA_VISUAL_USER_SELECTED";
+const ACCESS_FINE_LOCATION: &str = "android.permission.ACCESS_FINE_LOCATION";
+const ACCESS_COARSE_LOCATION: &str = "android.permission.ACCESS_COARSE_LOCATION";
+
+// Must match PermissionHandler.java, which forwards only these codes.
+const AUDIO_PERMISSION_REQUEST_CODE: i32 = 9876;
+const NOTIFICATION_PERMISSION_REQUEST_CODE: i32 = 9877;
+const MEDIA_PERMISSION_REQUEST_CODE: i32 = 9878;
+const LOCATION_PERMISSION_REQUEST_CODE: i32 = 9879;
+
+/// Android 13: notifications and media reads became runtime permissions.
+const TIRAMISU: i32 = 33;
+/// Android 14: the photo picker's partial "selected photos" grant.
+const UPSIDE_DOWN_CAKE: i32 = 34;
+
+/// What the device's answer amounts to for a capability.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grant {
+    Granted,
+    /// Only user-picked photos/videos (Android 14+), or only an approximate location.
+    Limited,
+    Denied,
+}
+
+fn sdk_int(env: &mut JNIEnv) -> Result<i32, String> {
+    env.get_static_field("android/os/Build$VERSION", "SDK_INT", "I")
+        .map_err(|e| format!("Failed to read SDK_INT: {:?}", e))?
+        .i()
+        .map_err(|e| format!("Failed to convert SDK_INT: {:?}", e))
+}
+
+fn has_permission(env: &mut JNIEnv, context: &JObject, permission: &str) -> Result<bool, String> {
+    let permission_str = env.new_string(permission)
+        .map_err(|e| format!("Failed to create permission string: {:?}", e))?;
+
+    let permission_status = env.call_method(
+        context,
+        "checkSelfPermission",
+        "(Ljava/lang/String;)I",
+        &[(&permission_str).into()]
+    ).map_err(|e| format!("Failed to check permission: {:?}", e))?
+        .i()
+        .map_err(|e| format!("Failed to convert permission status: {:?}", e))?;
+
+    // PackageManager.PERMISSION_GRANTED = 0
+    Ok(permission_status == 0)
+}
 
 pub fn check_audio_permission() -> Result<bool, String> {
-    with_android_context(|env, activity| {
-        // Check permission
-        let permission_str = env.new_string("android.permission.RECORD_AUDIO")
-            .map_err(|e| format!("Failed to create permission string: {:?}", e))?;
-        
-        let permission_status = env.call_method(
-            activity,
-            "checkSelfPermission",
-            "(Ljava/lang/String;)I",
-            &[(&permission_str).into()]
-        ).map_err(|e| format!("Failed to check permission: {:?}", e))?
-            .i()
-            .map_err(|e| format!("Failed to convert permission status: {:?}", e))?;
-        
-        // PackageManager.PERMISSION_GRANTED = 0
-        Ok(permission_status == 0)
+    with_android_context(|env, activity| has_permission(env, activity, RECORD_AUDIO))
+}
+
+/// Whether notifications can show. Covers both the Android 13 runtime permission and the
+/// per-app switch in system settings (which older versions had all along).
+pub fn check_notification_permission() -> Result<bool, String> {
+    with_android_context(|env, context| {
+        let manager = get_system_service(env, context, "notification")?;
+        env.call_method(&manager, "areNotificationsEnabled", "()Z", &[])
+            .map_err(|e| format!("Failed to check notifications: {:?}", e))?
+            .z()
+            .map_err(|e| format!("Failed to convert notification status: {:?}", e))
+    })
+}
+
+/// Access to shared photos and videos. Before Android 13 reads went through the system picker
+/// and needed no permission.
+pub fn check_media_permission() -> Result<Grant, String> {
+    with_android_context(|env, context| {
+        let sdk = sdk_int(env)?;
+        if sdk < TIRAMISU {
+            return Ok(Grant::Granted);
+        }
+        if has_permission(env, context, READ_MEDIA_IMAGES)? && has_permission(env, context, READ_MEDIA_VIDEO)? {
+            return Ok(Grant::Granted);
+        }
+        if sdk >= UPSIDE_DOWN_CAKE && has_permission(env, context, READ_MEDIA_VISUAL_USER_SELECTED)? {
+            return Ok(Grant::Limited);
+        }
+        Ok(Grant::Denied)
+    })
+}
+
+/// Access to the device's location, for sharing it into a chat. Android 12+ lets the user
+/// grant only an approximate one.
+pub fn check_location_permission() -> Result<Grant, String> {
+    with_android_context(|env, context| {
+        if has_permission(env, context, ACCESS_FINE_LOCATION)? {
+            return Ok(Grant::Granted);
+        }
+        if has_permission(env, context, ACCESS_COARSE_LOCATION)? {
+            return Ok(Grant::Limited);
+        }
+        Ok(Grant::Denied)
     })
 }
 
 // Replaceable (not OnceLock): a denied request must be retryable — each call
 // installs its own fresh waiter, so the JNI result always reaches the request
-// currently in flight rather than a stale one.
-static PERMISSION_CALLBACK: Mutex<Option<Arc<(Mutex<Option<bool>>, Condvar)>>> = Mutex::new(None);
+// currently in flight rather than a stale one. Keyed by request code so a
+// late answer to an earlier prompt can't resolve a different one.
+type PermissionWaiter = Arc<(Mutex<Option<bool>>, Condvar)>;
+static PERMISSION_CALLBACK: Mutex<Option<(i32, PermissionWaiter)>> = Mutex::new(None);
 
+/// Show the system prompt for `permissions` and block until the user answers. `Ok(true)` when
+/// all were granted; callers re-check for partial grants.
 #[cfg(target_os = "android")]
-pub fn request_audio_permission_blocking() -> Result<bool, String> {
-    const AUDIO_PERMISSION_REQUEST_CODE: i32 = 9876;
-
+fn request_permissions_blocking(permissions: &[&str], request_code: i32) -> Result<bool, String> {
     // Initialize the callback state
     let callback_state = Arc::new((Mutex::new(None), Condvar::new()));
-    *PERMISSION_CALLBACK.lock().unwrap() = Some(callback_state.clone());
-    
+    *PERMISSION_CALLBACK.lock().unwrap() = Some((request_code, callback_state.clone()));
+
     // Must run against the ACTIVITY: requestPermissions is Activity-only and
     // throws NoSuchMethodError on the background service's Application context.
     with_android_activity(|env, activity| {
         // Create permission array
-        let permission_str = env.new_string("android.permission.RECORD_AUDIO")
-            .map_err(|e| format!("Failed to create permission string: {:?}", e))?;
-
         let permission_array = env.new_object_array(
-            1,
+            permissions.len() as i32,
             "java/lang/String",
-            JObject::from(permission_str),
+            JObject::null(),
         ).map_err(|e| format!("Failed to create permission array: {:?}", e))?;
+        for (i, permission) in permissions.iter().enumerate() {
+            let permission_str = env.new_string(permission)
+                .map_err(|e| format!("Failed to create permission string: {:?}", e))?;
+            env.set_object_array_element(&permission_array, i as i32, permission_str)
+                .map_err(|e| format!("Failed to fill permission array: {:?}", e))?;
+        }
 
         // Request permissions
         env.call_method(
@@ -60,19 +145,19 @@ pub fn request_audio_permission_blocking() -> Result<bool, String> {
             "([Ljava/lang/String;I)V",
             &[
                 JValue::from(&JObject::from(permission_array)),
-                JValue::from(AUDIO_PERMISSION_REQUEST_CODE),
+                JValue::from(request_code),
             ],
         ).map_err(|e| format!("Failed to request permissions: {:?}", e))?;
-        
+
         Ok(())
     })?;
-    
+
     // Wait for the callback
     let (lock, cvar) = &*callback_state;
-    
+
     // Wait up to 60 seconds for permission response
     let timeout = Duration::from_secs(60);
-    
+
     let result = {
         let guard = lock.lock().unwrap();
         let wait_result = cvar.wait_timeout_while(
@@ -80,15 +165,15 @@ pub fn request_audio_permission_blocking() -> Result<bool, String> {
             timeout,
             |result| result.is_none()
         ).unwrap();
-        
+
         if wait_result.1.timed_out() {
             return Err("Permission request timed out".to_string());
         }
-        
+
         // Extract the value while we still have the lock
         *wait_result.0
     };
-    
+
     match result {
         Some(granted) => {
             // Add a small delay to ensure permission propagates
@@ -99,6 +184,84 @@ pub fn request_audio_permission_blocking() -> Result<bool, String> {
     }
 }
 
+#[cfg(target_os = "android")]
+pub fn request_audio_permission_blocking() -> Result<bool, String> {
+    request_permissions_blocking(&[RECORD_AUDIO], AUDIO_PERMISSION_REQUEST_CODE)
+}
+
+/// Prompt for notifications where Android has a prompt for them (13+). When the user switched
+/// them off in system settings there's nothing to ask; the answer stays "denied".
+#[cfg(target_os = "android")]
+pub fn request_notification_permission_blocking() -> Result<bool, String> {
+    let sdk = with_android_context(|env, _| sdk_int(env))?;
+    if sdk >= TIRAMISU && !check_notification_permission()? {
+        request_permissions_blocking(&[POST_NOTIFICATIONS], NOTIFICATION_PERMISSION_REQUEST_CODE)?;
+    }
+    check_notification_permission()
+}
+
+#[cfg(target_os = "android")]
+pub fn request_media_permission_blocking() -> Result<Grant, String> {
+    let sdk = with_android_context(|env, _| sdk_int(env))?;
+    if sdk < TIRAMISU {
+        return Ok(Grant::Granted);
+    }
+    let mut permissions = vec![READ_MEDIA_IMAGES, READ_MEDIA_VIDEO];
+    if sdk >= UPSIDE_DOWN_CAKE {
+        permissions.push(READ_MEDIA_VISUAL_USER_SELECTED);
+    }
+    request_permissions_blocking(&permissions, MEDIA_PERMISSION_REQUEST_CODE)?;
+    check_media_permission()
+}
+
+/// Both location permissions go in one prompt; Android then offers "precise" or "approximate".
+#[cfg(target_os = "android")]
+pub fn request_location_permission_blocking() -> Result<Grant, String> {
+    request_permissions_blocking(&[ACCESS_FINE_LOCATION, ACCESS_COARSE_LOCATION], LOCATION_PERMISSION_REQUEST_CODE)?;
+    check_location_permission()
+}
+
+/// Open this app's page in system settings — the only way back once a permission was denied
+/// for good (Android stops showing the prompt after the second "Don't allow").
+pub fn open_app_settings() -> Result<(), String> {
+    with_android_context(|env, context| {
+        let package = env.call_method(context, "getPackageName", "()Ljava/lang/String;", &[])
+            .map_err(|e| format!("Failed to get package name: {:?}", e))?
+            .l()
+            .map_err(|e| format!("Failed to convert package name: {:?}", e))?;
+        let package = jni::objects::JString::from(package);
+        let package: String = env.get_string(&package)
+            .map_err(|e| format!("Failed to read package name: {:?}", e))?
+            .into();
+
+        let uri_str = env.new_string(format!("package:{}", package))
+            .map_err(|e| format!("Failed to create URI string: {:?}", e))?;
+        let uri = env.call_static_method(
+            "android/net/Uri",
+            "parse",
+            "(Ljava/lang/String;)Landroid/net/Uri;",
+            &[(&uri_str).into()],
+        ).map_err(|e| format!("Failed to parse URI: {:?}", e))?
+            .l()
+            .map_err(|e| format!("Failed to convert URI: {:?}", e))?;
+
+        let action = env.new_string("android.settings.APPLICATION_DETAILS_SETTINGS")
+            .map_err(|e| format!("Failed to create action string: {:?}", e))?;
+        let intent = env.new_object(
+            "android/content/Intent",
+            "(Ljava/lang/String;Landroid/net/Uri;)V",
+            &[(&action).into(), (&uri).into()],
+        ).map_err(|e| format!("Failed to create intent: {:?}", e))?;
+
+        // FLAG_ACTIVITY_NEW_TASK: the context may be the service's Application context
+        env.call_method(&intent, "addFlags", "(I)Landroid/content/Intent;", &[JValue::from(0x1000_0000)])
+            .map_err(|e| format!("Failed to set intent flags: {:?}", e))?;
+        env.call_method(context, "startActivity", "(Landroid/content/Intent;)V", &[(&intent).into()])
+            .map_err(|e| format!("Failed to open settings: {:?}", e))?;
+        Ok(())
+    })
+}
+
 // This function will be called from Java when permission result is received
 #[no_mangle]
 #[allow(non_snake_case)]
@@ -108,15 +271,12 @@ pub extern "system" fn Java_io_vectorapp_PermissionHandler_onPermissionResult(
     request_code: jint,
     granted: jboolean,
 ) {
-    const AUDIO_PERMISSION_REQUEST_CODE: i32 = 9876;
-    
-    if request_code == AUDIO_PERMISSION_REQUEST_CODE {
-        let callback_state = PERMISSION_CALLBACK.lock().unwrap().clone();
-        if let Some(callback_state) = callback_state {
-            let (lock, cvar) = &*callback_state;
-            let mut result = lock.lock().unwrap();
-            *result = Some(granted == JNI_TRUE);
-            cvar.notify_all();
-        }
-    }
+    let callback_state = match PERMISSION_CALLBACK.lock().unwrap().clone() {
+        Some((code, state)) if code == request_code => state,
+        _ => return,
+    };
+    let (lock, cvar) = &*callback_state;
+    let mut result = lock.lock().unwrap();
+    *result = Some(granted == JNI_TRUE);
+    cvar.notify_all();
 }
@@ -0,0 +1,233 @@
+//! UnifiedPush wake-ups.
+//!
+//! Without the foreground service, Android drops Vector's relay sockets soon after it's
+//! backgrounded and nothing arrives until the app is reopened. UnifiedPush lets a distributor app
+//! the user already runs (ntfy, NextPush, ...) hold the one long-lived connection instead:
+//! `VectorPushReceiver.kt` registers for an endpoint URL, which we hand to the user's push gateway.
+//! The gateway watches their relays for gift wraps addressed to them and POSTs to the endpoint when
+//! one lands. The push is only a wake signal — its body is at most the URL of the relay the wrap
+//! was seen on — and the device then reconciles against that single relay itself
+//! (`background_sync::wake_fetch`), so no message content passes through the gateway.
+//!
+//! Gateway protocol: `POST <gateway>` with `{"endpoint", "pubkey", "relays"}` registers, `DELETE`
+//! with the same body unregisters; both carry a NIP-98 HTTP Auth header. The gateway learns which
+//! pubkey owns the endpoint, so it should be one the user trusts (or runs).
+
+use jni::objects::{JClass, JObject, JString, JValue};
+use jni::JNIEnv;
+use nostr_sdk::hashes::{sha256::Hash as Sha256Hash, Hash};
+use nostr_sdk::prelude::*;
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+
+use super::background_sync::{self, logcat};
+
+/// DB setting holding the push gateway URL.
+pub const GATEWAY_SETTING: &str = "push_gateway";
+
+/// What the settings screen shows for UnifiedPush.
+#[derive(Serialize)]
+pub struct PushStatus {
+    /// Package of the distributor we're registered with, empty when push is off.
+    pub distributor: String,
+    /// Whether the distributor has issued an endpoint yet.
+    pub has_endpoint: bool,
+    pub gateway: String,
+}
+
+/// Called from VectorPushReceiver when the distributor issues (or, with "", revokes) our endpoint.
+/// Registers it with the gateway right away when the app is running; otherwise that waits for
+/// `refresh_registration` on the next launch.
+#[no_mangle]
+pub extern "C" fn Java_io_vectorapp_VectorPushReceiver_nativeOnPushEndpoint(
+    mut env: JNIEnv,
+    _class: JClass,
+    endpoint: JString<'_>,
+) {
+    let endpoint: String = match env.get_string(&endpoint) {
+        Ok(s) => s.into(),
+        Err(_) => return,
+    };
+    if endpoint.is_empty() || crate::TAURI_APP.get().is_none() || crate::nostr_client().is_none() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = register_with_gateway(&endpoint, "POST").await {
+            logcat(&format!("Push gateway registration failed: {}", e));
+        }
+    });
+}
+
+/// Called from VectorPushReceiver when a push arrives. Catches up against one relay unless a
+/// live connection (the open app, or the background service) already delivers.
+#[no_mangle]
+pub extern "C" fn Java_io_vectorapp_VectorPushReceiver_nativeOnPushMessage(
+    mut env: JNIEnv,
+    _class: JClass,
+    data_dir: JString<'_>,
+    message: JString<'_>,
+    context: JObject<'_>,
+) {
+    if background_sync::is_activity_in_foreground() || background_sync::is_standalone_sync_running() {
+        return;
+    }
+    let data_dir: String = match env.get_string(&data_dir) {
+        Ok(s) => s.into(),
+        Err(_) => return,
+    };
+    let message: String = env.get_string(&message).map(Into::into).unwrap_or_default();
+    let relay_hint = Some(message.trim().to_string())
+        .filter(|m| m.starts_with("wss://") || m.starts_with("ws://"));
+
+    // Notifications from a cold-started process need the JNI refs the service would have stored
+    background_sync::store_jni_refs(&mut env, &context);
+
+    if background_sync::CATCH_UP_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    logcat("Push wake-up received");
+
+    // The app's process is still alive (backgrounded): sync with its own client
+    if crate::TAURI_APP.get().is_some() {
+        if let (Some(client), Some(my_public_key)) = (crate::nostr_client(), crate::my_public_key()) {
+            tauri::async_runtime::spawn(async move {
+                background_sync::wake_fetch(&client, my_public_key, relay_hint.as_deref(), true).await;
+                background_sync::CATCH_UP_RUNNING.store(false, Ordering::SeqCst);
+            });
+            return;
+        }
+    }
+
+    std::thread::spawn(move || {
+        background_sync::run_wake_fetch(&data_dir, relay_hint);
+        background_sync::CATCH_UP_RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+/// Register with the user's distributor and remember `gateway`. Returns false when no
+/// distributor app is installed.
+pub async fn enable(gateway: &str) -> Result<bool, String> {
+    let gateway = gateway.trim();
+    let url = Url::parse(gateway).map_err(|_| "Invalid gateway URL".to_string())?;
+    if url.scheme() != "https" {
+        return Err("The push gateway must use https".to_string());
+    }
+    crate::db::set_sql_setting(GATEWAY_SETTING.to_string(), gateway.to_string())?;
+    if !call_push_helper_bool("enable")? {
+        return Ok(false);
+    }
+    // Re-enabling keeps the endpoint the distributor already issued; register it now
+    refresh_registration().await?;
+    Ok(true)
+}
+
+/// Unregister from the gateway and the distributor.
+pub async fn disable() -> Result<(), String> {
+    let endpoint = call_push_helper_string("getEndpoint")?;
+    if !endpoint.is_empty() {
+        if let Err(e) = register_with_gateway(&endpoint, "DELETE").await {
+            logcat(&format!("Push gateway unregistration failed: {}", e));
+        }
+    }
+    call_push_helper_void("disable")
+}
+
+pub fn status() -> Result<PushStatus, String> {
+    Ok(PushStatus {
+        distributor: call_push_helper_string("getDistributor")?,
+        has_endpoint: !call_push_helper_string("getEndpoint")?.is_empty(),
+        gateway: crate::db::get_sql_setting(GATEWAY_SETTING.to_string())?.unwrap_or_default(),
+    })
+}
+
+/// Hand the current endpoint to the gateway, e.g. after it was issued while the app was closed,
+/// or the relay list changed. No-op while push is off.
+pub async fn refresh_registration() -> Result<(), String> {
+    let endpoint = call_push_helper_string("getEndpoint")?;
+    if endpoint.is_empty() {
+        return Ok(());
+    }
+    register_with_gateway(&endpoint, "POST").await
+}
+
+/// Send `{endpoint, pubkey, relays}` to the gateway with a NIP-98 HTTP Auth header.
+async fn register_with_gateway(endpoint: &str, method: &str) -> Result<(), String> {
+    let gateway = crate::db::get_sql_setting(GATEWAY_SETTING.to_string())?
+        .filter(|g| !g.is_empty())
+        .ok_or("No push gateway set")?;
+    let client = crate::nostr_client().ok_or("No active session")?;
+    let my_public_key = crate::my_public_key().ok_or("No public key")?;
+    let relays: Vec<String> = client.relays().await.keys().map(|u| u.to_string()).collect();
+
+    let body = serde_json::to_vec(&serde_json::json!({
+        "endpoint": endpoint,
+        "pubkey": my_public_key.to_hex(),
+        "relays": relays,
+    })).map_err(|e| e.to_string())?;
+
+    // NIP-98 HTTP Auth
+    let auth = EventBuilder::new(Kind::Custom(27235), "").tags([
+        Tag::custom(TagKind::custom("u"), [gateway.clone()]),
+        Tag::custom(TagKind::custom("method"), [method.to_string()]),
+        Tag::custom(TagKind::custom("payload"), [Sha256Hash::hash(&body).to_string()]),
+    ]);
+    let auth = client.sign_event_builder(auth).await.map_err(|e| e.to_string())?;
+    let header = format!("Nostr {}", base64_simd::STANDARD.encode_to_string(auth.as_json()));
+
+    let http = vector_core::net::build_http_client(std::time::Duration::from_secs(20))
+        .map_err(|e| e.to_string())?;
+    let method = reqwest::Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+    let res = http.request(method, &gateway)
+        .header("Authorization", header)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send().await
+        .map_err(|e| format!("Gateway unreachable: {}", e))?;
+    if !res.status().is_success() {
+        return Err(format!("Gateway returned HTTP {}", res.status().as_u16()));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// JNI helpers for VectorPushReceiver's static methods
+// ============================================================================
+
+fn call_push_helper<F, R>(method: &str, sig: &str, convert: F) -> Result<R, String>
+where
+    F: for<'a> FnOnce(&mut JNIEnv<'a>, jni::objects::JValueOwned<'a>) -> Result<R, String>,
+{
+    super::utils::with_android_context(|env, context| {
+        let class_loader = env.call_method(context, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])
+            .map_err(|e| format!("{:?}", e))?.l().map_err(|e| format!("{:?}", e))?;
+
+        let class_name = env.new_string("io.vectorapp.VectorPushReceiver")
+            .map_err(|e| format!("{:?}", e))?;
+        let push_class = env.call_method(&class_loader, "loadClass", "(Ljava/lang/String;)Ljava/lang/Class;",
+            &[JValue::Object(&class_name)])
+            .map_err(|e| format!("{:?}", e))?.l().map_err(|e| format!("{:?}", e))?;
+
+        let push_jclass = JClass::from(push_class);
+        let val = env.call_static_method(&push_jclass, method, sig, &[context.into()])
+            .map_err(|e| format!("{:?}", e))?;
+        convert(env, val)
+    })
+}
+
+fn call_push_helper_bool(method: &str) -> Result<bool, String> {
+    call_push_helper(method, "(Landroid/content/Context;)Z", |_, val| {
+        val.z().map_err(|e| format!("{:?}", e))
+    })
+}
+
+fn call_push_helper_string(method: &str) -> Result<String, String> {
+    call_push_helper(method, "(Landroid/content/Context;)Ljava/lang/String;", |env, val| {
+        let obj = val.l().map_err(|e| format!("{:?}", e))?;
+        let s: String = env.get_string(&JString::from(obj)).map_err(|e| format!("{:?}", e))?.into();
+        Ok(s)
+    })
+}
+
+fn call_push_helper_void(method: &str) -> Result<(), String> {
+    call_push_helper(method, "(Landroid/content/Context;)V", |_, _| Ok(()))
+}
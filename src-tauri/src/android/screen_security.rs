@@ -0,0 +1,39 @@
+//! Screen security: FLAG_SECURE on the main window via `io.vectorapp.VectorScreenSecurity`, which
+//! keeps the preference device-wide so it applies from the first frame on the next launch.
+
+use jni::objects::{JClass, JObject, JValue};
+use jni::JNIEnv;
+
+use super::utils::{with_android_activity, with_android_context};
+
+const CLASS: &str = "io.vectorapp.VectorScreenSecurity";
+
+fn load_class<'a>(env: &mut JNIEnv<'a>, ctx: &JObject<'a>) -> Result<JClass<'a>, String> {
+    let class_loader = env.call_method(ctx, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])
+        .map_err(|e| format!("{:?}", e))?.l().map_err(|e| format!("{:?}", e))?;
+    let class_name = env.new_string(CLASS).map_err(|e| format!("{:?}", e))?;
+    let class = env.call_method(&class_loader, "loadClass", "(Ljava/lang/String;)Ljava/lang/Class;",
+        &[JValue::Object(&class_name)])
+        .map_err(|e| format!("{:?}", e))?.l().map_err(|e| format!("{:?}", e))?;
+    Ok(JClass::from(class))
+}
+
+pub fn is_enabled() -> Result<bool, String> {
+    with_android_context(|env, ctx| {
+        let class = load_class(env, ctx)?;
+        env.call_static_method(&class, "isEnabled", "(Landroid/content/Context;)Z", &[JValue::Object(ctx)])
+            .map_err(|e| format!("{:?}", e))?.z().map_err(|e| format!("{:?}", e))
+    })
+}
+
+/// Save the preference and set or clear FLAG_SECURE on the live window. Needs the Activity (the
+/// window is the Activity's), so only call from a foreground, user-driven path.
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    let saved = with_android_activity(|env, activity| {
+        let class = load_class(env, activity)?;
+        env.call_static_method(&class, "setEnabled", "(Landroid/app/Activity;Z)Z",
+            &[JValue::Object(activity), JValue::Bool(enabled as u8)])
+            .map_err(|e| format!("{:?}", e))?.z().map_err(|e| format!("{:?}", e))
+    })?;
+    if saved { Ok(()) } else { Err("Failed to save the screen security setting".to_string()) }
+}
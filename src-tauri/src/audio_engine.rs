@@ -103,6 +103,40 @@ pub struct AudioLoadResult {
     pub bins: u8,
 }
 
+/// Bytes handed over piece by piece from another thread, read as one stream by
+/// [`AudioEngine::load_from_reader`]. Reads block until the next piece arrives; the stream ends
+/// when the sender is dropped.
+pub struct ChannelReader {
+    rx: std::sync::Mutex<mpsc::Receiver<Vec<u8>>>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl ChannelReader {
+    pub fn new(rx: mpsc::Receiver<Vec<u8>>) -> Self {
+        Self { rx: std::sync::Mutex::new(rx), buf: Vec::new(), pos: 0 }
+    }
+}
+
+impl std::io::Read for ChannelReader {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos == self.buf.len() {
+            let rx = self.rx.get_mut().map_err(|_| std::io::Error::other("Lock poisoned"))?;
+            match rx.recv() {
+                Ok(piece) => {
+                    self.buf = piece;
+                    self.pos = 0;
+                }
+                Err(_) => return Ok(0),
+            }
+        }
+        let n = out.len().min(self.buf.len() - self.pos);
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
 /// Event payload emitted when a source finishes playing
 #[derive(Serialize, Clone)]
 struct AudioEndedPayload {
@@ -264,6 +298,80 @@ impl AudioEngine {
             return Err("Invalid sample rate".to_string());
         }
 
+        let (id, duration_ms) = self.insert_decoding_source(sample_rate, est_frames)?;
+
+        // Spawn background decode thread
+        let shared = Arc::clone(&self.shared);
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_string();
+        std::thread::Builder::new()
+            .name("audio-decode".into())
+            .spawn(move || {
+                stream_decode_worker(id, file_bytes, &ext, channels, sample_rate, &shared);
+            })
+            .map_err(|e| format!("Failed to spawn decode thread: {}", e))?;
+
+        Ok(AudioLoadResult {
+            id,
+            duration_ms,
+            waveform_fps: WAVEFORM_FPS as u8,
+            bins: WAVEFORM_BINS as u8,
+        })
+    }
+
+    /// Load audio that is still arriving (a voice message streaming in, see
+    /// `commands::attachments::stream_attachment`). Blocks until the header has arrived, then
+    /// decodes in the background as `reader` yields more; playback that catches up waits in silence.
+    pub fn load_from_reader<R: std::io::Read + Send + Sync + 'static>(&self, reader: R, ext: &str) -> Result<AudioLoadResult, String> {
+        use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+        use symphonia::core::formats::FormatOptions;
+        use symphonia::core::io::{MediaSourceStream, ReadOnlySource};
+        use symphonia::core::meta::MetadataOptions;
+        use symphonia::core::probe::Hint;
+
+        let t0 = std::time::Instant::now();
+        let media_source = MediaSourceStream::new(Box::new(ReadOnlySource::new(reader)), Default::default());
+        let mut hint = Hint::new();
+        if !ext.is_empty() {
+            hint.with_extension(ext);
+        }
+        let probed = symphonia::default::get_probe()
+            .format(&hint, media_source, &FormatOptions::default(), &MetadataOptions::default())
+            .map_err(|e| format!("Failed to probe audio stream: {}", e))?;
+        let format = probed.format;
+        let track = format.tracks().iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or("No supported audio tracks found")?;
+        let sample_rate = track.codec_params.sample_rate.ok_or("Unknown sample rate")?;
+        if self.shared.device_sample_rate == 0 {
+            return Err("Invalid sample rate".to_string());
+        }
+        let channels = track.codec_params.channels.map(|c| c.count()).unwrap_or(2);
+        // n_frames is in the header for WAV/FLAC; otherwise the duration arrives with the last packet
+        let est_frames = track.codec_params.n_frames.unwrap_or(0);
+        let track_id = track.id;
+        let decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .map_err(|e| format!("Decoder creation failed: {}", e))?;
+
+        let (id, duration_ms) = self.insert_decoding_source(sample_rate, est_frames)?;
+        let shared = Arc::clone(&self.shared);
+        std::thread::Builder::new()
+            .name("audio-decode".into())
+            .spawn(move || {
+                decode_packets(id, format, decoder, track_id, channels, sample_rate, &shared, t0);
+            })
+            .map_err(|e| format!("Failed to spawn decode thread: {}", e))?;
+
+        Ok(AudioLoadResult {
+            id,
+            duration_ms,
+            waveform_fps: WAVEFORM_FPS as u8,
+            bins: WAVEFORM_BINS as u8,
+        })
+    }
+
+    /// Register an empty source for a background decode to fill. Returns its id and estimated duration.
+    fn insert_decoding_source(&self, sample_rate: u32, est_frames: u64) -> Result<(u32, u64), String> {
         let id = self.shared.next_id.fetch_add(1, Ordering::Relaxed);
         let rate_ratio = sample_rate as f64 / self.shared.device_sample_rate as f64;
         let duration_ms = if sample_rate > 0 { est_frames * 1000 / sample_rate as u64 } else { 0 };
@@ -290,22 +398,7 @@ impl AudioEngine {
             .map_err(|_| "Lock poisoned")?
             .insert(id, source);
 
-        // Spawn background decode thread
-        let shared = Arc::clone(&self.shared);
-        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_string();
-        std::thread::Builder::new()
-            .name("audio-decode".into())
-            .spawn(move || {
-                stream_decode_worker(id, file_bytes, &ext, channels, sample_rate, &shared);
-            })
-            .map_err(|e| format!("Failed to spawn decode thread: {}", e))?;
-
-        Ok(AudioLoadResult {
-            id,
-            duration_ms,
-            waveform_fps: WAVEFORM_FPS as u8,
-            bins: WAVEFORM_BINS as u8,
-        })
+        Ok((id, duration_ms))
     }
 
     /// Load audio from pre-decoded f32 samples (already mono, at native sample rate).
@@ -736,9 +829,7 @@ fn stream_decode_worker(
     sample_rate: u32,
     shared: &SharedState,
 ) {
-    use symphonia::core::audio::SampleBuffer;
     use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
-    use symphonia::core::errors::Error as SymphoniaError;
     use symphonia::core::formats::FormatOptions;
     use symphonia::core::io::MediaSourceStream;
     use symphonia::core::meta::MetadataOptions;
@@ -765,7 +856,7 @@ fn stream_decode_worker(
         }
     };
 
-    let mut format = probed.format;
+    let format = probed.format;
     let track = match format.tracks().iter()
         .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
     {
@@ -778,7 +869,7 @@ fn stream_decode_worker(
     };
 
     let track_id = track.id;
-    let mut decoder = match symphonia::default::get_codecs()
+    let decoder = match symphonia::default::get_codecs()
         .make(&track.codec_params, &DecoderOptions::default())
     {
         Ok(d) => d,
@@ -789,6 +880,24 @@ fn stream_decode_worker(
         }
     };
 
+    decode_packets(id, format, decoder, track_id, channels, sample_rate, shared, t0);
+}
+
+/// Decode every packet of `track_id` into source `id`, then publish its waveform and duration.
+#[allow(clippy::too_many_arguments)]
+fn decode_packets(
+    id: u32,
+    mut format: Box<dyn symphonia::core::formats::FormatReader>,
+    mut decoder: Box<dyn symphonia::core::codecs::Decoder>,
+    track_id: u32,
+    channels: usize,
+    sample_rate: u32,
+    shared: &SharedState,
+    t0: std::time::Instant,
+) {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::errors::Error as SymphoniaError;
+
     let mut sample_buf: Option<SampleBuffer<f32>> = None;
     let mut batch = Vec::with_capacity(DECODE_BATCH_SIZE);
     // Thread-local accumulator for FFT: keeps all decoded samples so we can
@@ -1,4 +1,5 @@
 use vector_core::compact::encode_message_id;
+use vector_core::db::notification_prefs::ChatNotificationPrefs;
 
 pub use vector_core::{ChatType, SerializableChat};
 // ============================================================================
@@ -27,6 +28,7 @@ pub async fn mark_as_read_headless(chat_id: &str) -> bool {
         (crate::db::chats::SlimChatDB::from_chat(&state.chats[idx], &state.interner), lr)
     };
     let _ = crate::db::chats::save_slim_chat(slim).await;
+    vector_core::chat_state_sync::note_local_change(&vector_core::chat_state_sync::read_key(chat_id));
 
     // Read clears any lingering OS notification for this chat (in-app open / another device).
     crate::services::notification_service::cancel_chat_notification(chat_id);
@@ -43,6 +45,22 @@ pub async fn mark_as_read_headless(chat_id: &str) -> bool {
     true
 }
 
+/// Follow-up for chat state adopted from another device (`chat_state_sync`): a chat read elsewhere
+/// drops its OS notification here, and the badge is recounted.
+pub async fn on_chat_state_synced(applied: &[String]) {
+    if applied.is_empty() {
+        return;
+    }
+    for key in applied {
+        if let Some(chat_id) = key.strip_prefix(vector_core::chat_state_sync::READ_PREFIX) {
+            crate::services::notification_service::cancel_chat_notification(chat_id);
+        }
+    }
+    if let Some(handle) = crate::TAURI_APP.get() {
+        let _ = crate::commands::messaging::update_unread_counter(handle.clone()).await;
+    }
+}
+
 /// Marks a specific message as read for a chat.
 #[tauri::command]
 pub async fn mark_as_read(chat_id: String, message_id: Option<String>) -> bool {
@@ -83,6 +101,7 @@ pub async fn mark_as_read(chat_id: String, message_id: Option<String>) -> bool {
             if let Some(slim) = slim {
                 let _ = crate::db::chats::save_slim_chat(slim).await;
             }
+            vector_core::chat_state_sync::note_local_change(&vector_core::chat_state_sync::read_key(&chat_id));
         }
 
         // Reconcile from the DB: a message-scoped mark ("read up to here") can leave a non-zero
@@ -127,6 +146,7 @@ pub async fn mark_as_unread(chat_id: String) -> Option<String> {
         crate::db::chats::SlimChatDB::from_chat(&state.chats[idx], &state.interner)
     };
     let _ = crate::db::chats::save_slim_chat(slim).await;
+    vector_core::chat_state_sync::note_local_change(&vector_core::chat_state_sync::read_key(&chat_id));
 
     // The retreat surfaces the newest contact message again, so reconcile the exact remainder.
     crate::commands::messaging::reconcile_chat_unread(&chat_id).await;
@@ -137,7 +157,7 @@ pub async fn mark_as_unread(chat_id: String) -> Option<String> {
     Some(last_read_hex)
 }
 
-/// Toggles the muted status of a chat (DM or group).
+/// Toggles the muted status of a chat (DM or group). Synced to the user's other Vector installs.
 #[tauri::command]
 pub async fn toggle_chat_mute(chat_id: String) -> bool {
     let handle = crate::TAURI_APP.get().unwrap();
@@ -154,6 +174,7 @@ pub async fn toggle_chat_mute(chat_id: String) -> bool {
     };
 
     let _ = crate::db::chats::save_slim_chat(slim).await;
+    vector_core::chat_state_sync::note_local_change(&vector_core::chat_state_sync::muted_key(&chat_id));
 
     use tauri::Emitter;
     handle.emit("chat_muted", serde_json::json!({
@@ -164,3 +185,115 @@ pub async fn toggle_chat_mute(chat_id: String) -> bool {
     let _ = crate::commands::messaging::update_unread_counter(handle.clone()).await;
     muted
 }
+
+/// A chat's notification preferences beyond mute: mentions-only, a timed mute, sound.
+#[tauri::command]
+pub async fn get_chat_notification_prefs(chat_id: String) -> ChatNotificationPrefs {
+    vector_core::db::notification_prefs::get(&chat_id)
+}
+
+/// Update a chat's notification preferences. `mute_for_secs` mutes notifications for that long from
+/// now (0 ends a timed mute); left out, the current one stands. Returns the stored preferences.
+#[tauri::command]
+pub async fn set_chat_notification_prefs(
+    chat_id: String,
+    mentions_only: bool,
+    sound: bool,
+    mute_for_secs: Option<u64>,
+) -> Result<ChatNotificationPrefs, String> {
+    let mut prefs = vector_core::db::notification_prefs::get(&chat_id);
+    prefs.mentions_only = mentions_only;
+    prefs.sound = sound;
+    if let Some(secs) = mute_for_secs {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        prefs.muted_until = if secs == 0 { 0 } else { now.saturating_add(secs) };
+    }
+    vector_core::db::notification_prefs::set(&chat_id, &prefs)?;
+    Ok(prefs)
+}
+
+/// Whether typing indicators are sent in this chat (the account-wide setting aside).
+#[tauri::command]
+pub async fn get_chat_typing_enabled(chat_id: String) -> bool {
+    vector_core::typing::chat_enabled(&chat_id)
+}
+
+/// Switch typing indicators on or off for one chat.
+#[tauri::command]
+pub async fn set_chat_typing_enabled(chat_id: String, enabled: bool) -> Result<(), String> {
+    vector_core::typing::set_chat_enabled(&chat_id, enabled)
+}
+
+/// Soft-delete a DM: it leaves the chat list but keeps its history, restorable from
+/// `list_recently_deleted` for 30 days before the retention sweep removes it for good. A new
+/// message from the contact brings the chat (and its history) back.
+#[tauri::command]
+pub async fn delete_chat(chat_id: String) -> Result<(), String> {
+    let handle = crate::TAURI_APP.get().ok_or("App handle not initialized")?;
+    if !matches!(vector_core::ids::ChatId::parse(&chat_id), Ok(vector_core::ids::ChatId::Dm(_))) {
+        // Communities are left (and their keys dropped) via `leave_community`
+        return Err("Only direct messages can be deleted".to_string());
+    }
+    let session = vector_core::state::SessionGuard::capture();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    if !crate::db::chats::soft_delete_chat(&chat_id, now).await? {
+        return Err("Chat not found".to_string());
+    }
+    {
+        let mut state = crate::STATE.lock().await;
+        if !session.is_valid() { return Ok(()); }
+        state.chats.retain(|c| c.id != chat_id);
+        state.unread_clear(&chat_id);
+    }
+    crate::services::notification_service::cancel_chat_notification(&chat_id);
+    let _ = crate::commands::messaging::update_unread_counter(handle.clone()).await;
+    Ok(())
+}
+
+/// Bring a soft-deleted chat back into the chat list, with its newest message for the preview
+/// (older history pages in as usual).
+#[tauri::command]
+pub async fn restore_chat(chat_id: String) -> Result<SerializableChat, String> {
+    let session = vector_core::state::SessionGuard::capture();
+    let slim = crate::db::chats::restore_chat(&chat_id).await?
+        .ok_or("This chat can no longer be restored")?;
+    let last = crate::db::get_chat_messages_paginated(&chat_id, 1, 0).await.unwrap_or_default();
+
+    let chat = {
+        let mut state = crate::STATE.lock().await;
+        if !session.is_valid() { return Err("Account changed".to_string()); }
+        if state.get_chat(&chat_id).is_none() {
+            let mut chat = slim.to_chat(&mut state.interner);
+            for npub in &slim.participants {
+                if state.get_profile(npub).is_none() {
+                    state.insert_or_replace_profile(npub, vector_core::Profile::new());
+                }
+            }
+            for message in last {
+                chat.internal_add_message(message, &mut state.interner);
+            }
+            state.chats.push(chat);
+            state.chats.sort_by(|a, b| b.last_message_time().cmp(&a.last_message_time()));
+        }
+        let chat = state.get_chat(&chat_id).ok_or("Chat not found")?;
+        chat.to_serializable(&state.interner)
+    };
+
+    crate::commands::messaging::reconcile_chat_unread(&chat_id).await;
+    if let Some(handle) = crate::TAURI_APP.get() {
+        let _ = crate::commands::messaging::update_unread_counter(handle.clone()).await;
+    }
+    Ok(chat)
+}
+
+/// Soft-deleted chats still inside their restore window, most recently deleted first.
+#[tauri::command]
+pub async fn list_recently_deleted() -> Result<Vec<vector_core::db::chats::DeletedChat>, String> {
+    vector_core::db::chats::list_recently_deleted()
+}
@@ -5,6 +5,8 @@
 //! - Logout (data cleanup and restart)
 //! - Account creation (new keypair generation)
 //! - Key export (nsec and seed phrase retrieval)
+//! - Account bundles (the whole account as one encrypted file, for moving devices)
+//! - Key-usage audit log
 //! - PIN encrypt/decrypt for account security
 
 use nostr_sdk::prelude::*;
@@ -91,7 +93,7 @@ pub async fn debug_hot_reload_sync() -> Result<serde_json::Value, String> {
         .map(|c| c.to_serializable(&state.interner))
         .collect();
     let slim_profiles: Vec<db::SlimProfile> = state.profiles.iter()
-        .map(|p| db::SlimProfile::from_profile(p, &state.interner))
+        .map(|p| db::SlimProfile::light(p, &state.interner))
         .collect();
     Ok(serde_json::json!({
         "success": true,
@@ -110,6 +112,7 @@ pub async fn debug_hot_reload_sync() -> Result<serde_json::Value, String> {
 pub async fn login<R: Runtime>(
     handle: AppHandle<R>,
     mut import_key: String,
+    passphrase: Option<String>,
 ) -> Result<LoginResult, String> {
     let keys: Keys;
 
@@ -150,7 +153,9 @@ pub async fn login<R: Runtime>(
         // from_mnemonic takes ownership, so clone for zeroize
         let mnemonic_copy = import_key.clone();
         import_key.zeroize();
-        match Keys::from_mnemonic(mnemonic_copy, Some(String::new())) {
+        // The optional BIP-39 passphrase ("25th word") is consumed here and never stored; a wrong
+        // one still derives a valid key, just for a different (usually empty) account.
+        match Keys::from_mnemonic(mnemonic_copy, Some(passphrase.unwrap_or_default())) {
             Ok(parsed) => keys = parsed,
             Err(_) => return Err(String::from("Invalid Seed Phrase")),
         };
@@ -1243,18 +1248,43 @@ pub async fn logout<R: Runtime>(handle: AppHandle<R>) -> Result<(), String> {
     result.map(|_| ())
 }
 
+/// Lock the account now: the session is torn down (keys scrubbed, chats dropped from memory) and
+/// the frontend reloads into the PIN/password screen. Needs Local Encryption.
+#[tauri::command]
+pub async fn lock_now<R: Runtime>(handle: AppHandle<R>) -> Result<(), String> {
+    crate::services::auto_lock::lock(&handle).await
+}
+
+/// Report user activity (throttled by the frontend), restarting the auto-lock idle clock.
+#[tauri::command]
+pub fn report_activity() {
+    crate::services::auto_lock::note_activity();
+}
+
+/// Report the app leaving (`false`) or returning to (`true`) the foreground. Returning may lock,
+/// when the user chose to lock on leaving or the time away ran out the auto-lock timeout.
+#[tauri::command]
+pub async fn report_app_visibility<R: Runtime>(handle: AppHandle<R>, visible: bool) {
+    if visible {
+        crate::services::auto_lock::on_resume(&handle).await;
+    } else {
+        crate::services::auto_lock::on_suspend(&handle);
+    }
+}
+
 /// Creates a new Nostr keypair derived from a BIP39 Seed Phrase
 ///
 /// The private key is stored in PENDING_NSEC for setup_encryption/skip_encryption
 /// to consume — it is never returned over IPC.
 #[tauri::command]
-pub async fn create_account() -> Result<LoginResult, String> {
+pub async fn create_account(passphrase: Option<String>) -> Result<LoginResult, String> {
     // Generate a BIP39 Mnemonic Seed Phrase
     let mnemonic = bip39::Mnemonic::generate(12).map_err(|e| e.to_string())?;
     let mnemonic_string = mnemonic.to_string();
 
-    // Derive our nsec from our Mnemonic
-    let keys = Keys::from_mnemonic(mnemonic_string.clone(), None).map_err(|e| e.to_string())?;
+    // Derive our nsec from our Mnemonic (and the optional BIP-39 passphrase, which is never stored)
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    let keys = Keys::from_mnemonic(mnemonic_string.clone(), passphrase).map_err(|e| e.to_string())?;
 
     // Store nsec in PENDING_NSEC for setup_encryption/skip_encryption (never sent over IPC)
     {
@@ -1342,15 +1372,86 @@ pub async fn export_keys() -> Result<serde_json::Value, String> {
         }
     };
 
+    // A seed that doesn't derive our key on its own was created with a BIP-39 passphrase: the
+    // phrase alone won't restore this account, and the export must say so.
+    let seed_passphrase_protected = seed_phrase.as_ref().is_some_and(|seed| {
+        let derived = Keys::from_mnemonic(seed.clone(), None).map(|k| k.public_key);
+        derived.ok() != crate::my_public_key()
+    });
+
+    vector_core::key_audit::record_caller(
+        vector_core::key_audit::KeyOp::ExportKeys,
+        Some(if seed_phrase.is_some() { "nsec + seed phrase" } else { "nsec" }.to_string()),
+    );
+
     // Create response object
     let response = serde_json::json!({
         "nsec": nsec,
-        "seed_phrase": seed_phrase
+        "seed_phrase": seed_phrase,
+        "seed_passphrase_protected": seed_passphrase_protected
     });
 
     Ok(response)
 }
 
+/// One page (0 = most recent) of the key-usage audit log: coalesced records of what signed,
+/// encrypted or decrypted with this account's keys, and from where.
+#[tauri::command]
+pub async fn get_key_audit_log(page: u32) -> Result<Vec<vector_core::key_audit::KeyAuditEntry>, String> {
+    tokio::task::spawn_blocking(move || vector_core::key_audit::get_key_audit_log(page))
+        .await
+        .map_err(|e| format!("join error: {:?}", e))?
+}
+
+/// Write the current account (database, settings, group state and a manifest of downloaded
+/// files) to one password-encrypted bundle at `path`, for moving to another device.
+#[tauri::command]
+pub async fn export_account_bundle(password: String, path: String) -> Result<vector_core::account_bundle::BundleSummary, String> {
+    account_manager::refuse_if_migration_in_progress("export the account")?;
+    vector_core::account_bundle::export_account_bundle(&password, std::path::Path::new(&path)).await
+}
+
+/// Write a metadata-only activity report (messages per chat per day, logins, this device, relays)
+/// for `range` to `path`, as CSV or JSON. No message content is included.
+#[tauri::command]
+pub async fn export_activity_report<R: Runtime>(
+    handle: AppHandle<R>,
+    range: vector_core::activity_report::ReportRange,
+    format: vector_core::activity_report::ReportFormat,
+    path: String,
+) -> Result<vector_core::activity_report::ReportSummary, String> {
+    let relays = crate::commands::relays::get_relays(handle).await.unwrap_or_default()
+        .into_iter()
+        .map(|r| vector_core::activity_report::RelayUse { url: r.url, enabled: r.enabled, mode: r.mode })
+        .collect();
+    tokio::task::spawn_blocking(move || {
+        vector_core::activity_report::export(range, format, relays, std::path::Path::new(&path))
+    })
+    .await
+    .map_err(|e| format!("join error: {:?}", e))?
+}
+
+/// Restore an account bundle as a new local account. The frontend then switches to it, which
+/// lands on its usual unlock screen.
+#[tauri::command]
+pub async fn import_account_bundle(path: String, password: String) -> Result<vector_core::account_bundle::BundleSummary, String> {
+    vector_core::account_bundle::import_account_bundle(std::path::Path::new(&path), &password).await
+}
+
+/// Back up the current account as a bundle in the download folder's `Backups` directory; the
+/// summary carries the file's path.
+#[tauri::command]
+pub async fn create_backup(password: String) -> Result<vector_core::account_bundle::BundleSummary, String> {
+    account_manager::refuse_if_migration_in_progress("back up the account")?;
+    vector_core::account_bundle::create_backup(&password).await
+}
+
+/// Restore a backup made by `create_backup` (or any account bundle) on this install.
+#[tauri::command]
+pub async fn restore_backup(path: String, password: String) -> Result<vector_core::account_bundle::BundleSummary, String> {
+    vector_core::account_bundle::import_account_bundle(std::path::Path::new(&path), &password).await
+}
+
 // ============================================================================
 // PIN Encryption Commands
 // ============================================================================
@@ -2091,3 +2192,4 @@ fn broadcast_pending_invite_if_any() {
 // - export_keys
 // - encrypt
 // - decrypt
+// - export_activity_report
@@ -0,0 +1,50 @@
+//! Per-chat appearance Tauri commands — thin shims over `vector_core::appearance`.
+//!
+//! The account theme stays on `get_theme`; shared DM wallpapers keep their own commands (`wallpaper`),
+//! and a wallpaper override starts from their staged preview. Accent and override changes made on our
+//! other devices arrive as `chat_appearance_changed`.
+
+use std::collections::HashMap;
+
+use vector_core::appearance::{self, ChatAppearance, ChatWallpaper};
+
+/// A chat's accent color and wallpaper.
+#[tauri::command]
+pub async fn get_chat_appearance(chat_id: String) -> Result<ChatAppearance, String> {
+    Ok(appearance::chat_appearance(&chat_id).await)
+}
+
+/// Every chat's accent color, keyed by chat id.
+#[tauri::command]
+pub async fn get_chat_accent_colors() -> Result<HashMap<String, String>, String> {
+    appearance::accent_colors()
+}
+
+/// Set a chat's accent color (`#rrggbb`), or reset it to the theme's with `color = null`. Returns the
+/// stored color.
+#[tauri::command]
+pub async fn set_chat_accent_color(chat_id: String, color: Option<String>) -> Result<Option<String>, String> {
+    appearance::set_accent_color(&chat_id, color.as_deref())
+}
+
+/// Every chat's wallpaper override, keyed by chat id.
+#[tauri::command]
+pub async fn get_chat_wallpaper_overrides() -> Result<HashMap<String, ChatWallpaper>, String> {
+    Ok(appearance::wallpaper_overrides()?
+        .into_iter()
+        .map(|(chat_id, stored)| (chat_id, stored.view()))
+        .collect())
+}
+
+/// Set the staged wallpaper preview as our own wallpaper for the chat (any chat). Lands as
+/// `chat_appearance_changed`.
+#[tauri::command]
+pub async fn set_chat_wallpaper_override(chat_id: String, blur: u8, dim: u8) -> Result<(), String> {
+    vector_core::wallpaper::set_wallpaper_override(&chat_id, blur, dim).await
+}
+
+/// Drop our wallpaper override for the chat.
+#[tauri::command]
+pub async fn clear_chat_wallpaper_override(chat_id: String) -> Result<(), String> {
+    vector_core::wallpaper::clear_wallpaper_override(&chat_id).await
+}
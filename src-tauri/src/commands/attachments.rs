@@ -3,6 +3,7 @@
 //! This module handles attachment operations:
 //! - ThumbHash preview generation and decoding
 //! - Attachment download, decryption, and saving
+//! - Archiving old downloads to a user-chosen folder, and restoring them
 
 use std::collections::HashSet;
 use std::sync::LazyLock;
@@ -52,6 +53,33 @@ impl Drop for ActiveDownloadGuard {
     }
 }
 
+/// Bytes of streamed voice-message blobs kept for the download that follows playback.
+const STREAMED_BLOBS_BUDGET: usize = 16 * 1024 * 1024;
+
+/// Encrypted voice-message blobs fetched (and verified) by `stream_attachment`, keyed by
+/// attachment ID, so persisting the message afterwards doesn't fetch it a second time. Oldest
+/// first, within [`STREAMED_BLOBS_BUDGET`].
+static STREAMED_BLOBS: LazyLock<Mutex<Vec<(String, Vec<u8>)>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+async fn stash_streamed_blob(attachment_id: String, blob: Vec<u8>) {
+    if blob.len() > STREAMED_BLOBS_BUDGET {
+        return;
+    }
+    let mut blobs = STREAMED_BLOBS.lock().await;
+    blobs.retain(|(id, _)| *id != attachment_id);
+    let mut held: usize = blobs.iter().map(|(_, b)| b.len()).sum();
+    while held + blob.len() > STREAMED_BLOBS_BUDGET && !blobs.is_empty() {
+        held -= blobs.remove(0).1.len();
+    }
+    blobs.push((attachment_id, blob));
+}
+
+async fn take_streamed_blob(attachment_id: &str) -> Option<Vec<u8>> {
+    let mut blobs = STREAMED_BLOBS.lock().await;
+    let idx = blobs.iter().position(|(id, _)| id == attachment_id)?;
+    Some(blobs.remove(idx).1)
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -232,6 +260,19 @@ pub async fn set_gallery_hidden(hidden: bool) -> Result<(), String> {
     }
 }
 
+/// A chat's shared media of one kind (images/videos/voice/files/links), newest first,
+/// for the "shared media" tab. Reads the indexed attachments table — no history walk.
+#[tauri::command]
+pub async fn get_chat_media(
+    chat_id: String,
+    kind: vector_core::db::attachments::MediaKind,
+    page: usize,
+) -> Result<vector_core::db::attachments::ChatMediaPage, String> {
+    const PAGE_SIZE: usize = 60;
+    let chat_int_id = db::get_chat_id_by_identifier(&chat_id)?;
+    vector_core::db::attachments::get_chat_media(chat_int_id, kind, page, PAGE_SIZE)
+}
+
 /// Reject any path that doesn't resolve to a real file inside Vector's download
 /// dir. Hardening: the open/share intents hand a content:// URI to other apps
 /// via the FileProvider (which is scoped to all external storage), so a
@@ -286,6 +327,11 @@ pub async fn download_attachment(npub: String, msg_id: String, attachment_id: St
                             return false;
                         }
 
+                        // An opened view-once attachment is gone for good
+                        if attachment.viewed() {
+                            return false;
+                        }
+
                         // Check if file already exists on disk (downloaded but flag was wrong).
                         // Use the same canonical dir the write path uses
                         // (vector-core download dir) so dedup looks where files
@@ -401,7 +447,31 @@ pub async fn download_attachment(npub: String, msg_id: String, attachment_id: St
     })).unwrap();
 
     // Download the file - no timeout, allow large downloads to complete
-    let encrypted_data = match net::download(&*attachment.url, handle, &attachment_hex_id, None).await {
+    // A chunked upload's URL is a manifest; fetch and join the chunks it lists.
+    // When the sender mirrored the blob, each mirror is tried in turn after the primary.
+    let sources: Vec<&str> = std::iter::once(&*attachment.url)
+        .chain(attachment.mirrors.as_deref().unwrap_or_default().iter().map(|m| &**m))
+        .collect();
+    // A voice message that was played while streaming already has its verified blob in hand.
+    let mut downloaded = take_streamed_blob(&attachment_hex_id).await.ok_or("No download URL");
+    for source in &sources {
+        if matches!(&downloaded, Ok(data) if data.len() >= 16) {
+            break;
+        }
+        downloaded = match net::download(source, handle, &attachment_hex_id, None).await {
+            Ok(data) => match vector_core::blossom::parse_chunk_manifest(&data) {
+                Some(manifest) => net::download_chunked(&manifest, handle, &attachment_hex_id).await,
+                None => Ok(data),
+            },
+            Err(error) => Err(error),
+        };
+        match &downloaded {
+            Ok(data) if data.len() >= 16 => break,
+            Ok(_) => vector_core::log_warn!("[AttachmentDownload] {} returned a truncated blob", source),
+            Err(error) => vector_core::log_warn!("[AttachmentDownload] {} failed: {}", source, error),
+        }
+    }
+    let encrypted_data = match downloaded {
         Ok(data) => data,
         Err(error) => {
             vector_core::log_warn!(
@@ -579,6 +649,20 @@ pub async fn download_attachment(npub: String, msg_id: String, attachment_id: St
 
                     let _ = db::save_message(&npub, &updated_message).await;
 
+                    // A received voice message: transcribe it now if the user opted in
+                    #[cfg(feature = "whisper")]
+                    {
+                        let is_voice = updated_message.attachments.iter().any(|a| {
+                            a.id == file_hash && a.name.is_empty() && !a.view_once
+                                && matches!(a.extension.as_str(), "wav" | "mp3" | "flac")
+                        });
+                        if !updated_message.mine && is_voice {
+                            tokio::spawn(crate::commands::media::auto_transcribe(
+                                npub.clone(), msg_id.clone(), file_hash.clone(), path_str.clone(),
+                            ));
+                        }
+                    }
+
                     // Backfill other messages with the same attachment hash
                     let file_hash_clone = file_hash.clone();
                     let path_str_clone = path_str.clone();
@@ -592,11 +676,120 @@ pub async fn download_attachment(npub: String, msg_id: String, attachment_id: St
                 }
             }
 
+            // Keep the media cache under its limit (evicting older files, never this one)
+            tokio::spawn(async {
+                if let Err(e) = crate::commands::system::trim_media_cache().await {
+                    eprintln!("[MediaCache] Trim failed: {}", e);
+                }
+            });
+
             true
         }
     }
 }
 
+/// Start playing a voice message while it downloads, instead of waiting for the whole file.
+///
+/// Only sealed-chunk blobs are streamed (see `vector_core::crypto::stream`): each record is
+/// authenticated as it arrives, and only authenticated audio reaches the audio engine. A record
+/// that fails stops the fetch and its source at once, and emits `audio_stream_failed`. The blob
+/// comes from its URL, then each mirror in turn, picking up where the last source broke off; a
+/// verified one is kept for the `download_attachment` that saves the message after playback.
+/// Errors (a plain AES-GCM blob, a chunked upload) mean the caller should download it the usual
+/// way instead.
+#[tauri::command]
+pub async fn stream_attachment(npub: String, msg_id: String, attachment_id: String) -> Result<crate::audio_engine::AudioLoadResult, String> {
+    use crate::audio_engine::{AudioEngine, ChannelReader};
+
+    let attachment = {
+        let state = STATE.lock().await;
+        let (chat, message) = state.find_message(&msg_id).ok_or("Message not found")?;
+        if *chat.id() != npub {
+            return Err("Message not found".to_string());
+        }
+        message.attachments.into_iter()
+            .find(|a| a.id == attachment_id)
+            .ok_or("Attachment not found")?
+    };
+    if attachment.view_once {
+        return Err("View-once attachments are not streamed".to_string());
+    }
+    let voice = attachment.name.is_empty() && matches!(attachment.extension.as_str(), "wav" | "mp3" | "flac");
+    if !voice || !vector_core::crypto::stream::is_sealed_nonce(&attachment.nonce) {
+        return Err("Only sealed voice messages are streamed".to_string());
+    }
+    let mut opener = vector_core::crypto::stream::SealedStream::new(&attachment.key, &attachment.nonce)?;
+    let sources: Vec<String> = std::iter::once(attachment.url.clone())
+        .chain(attachment.mirrors.iter().cloned())
+        .collect();
+
+    let (tx, rx) = std::sync::mpsc::channel::<Vec<u8>>();
+    let (id_tx, id_rx) = tokio::sync::oneshot::channel::<u32>();
+    tokio::spawn(async move {
+        // Blob bytes handed to the opener so far, and whether a record failed authentication
+        let mut consumed = 0usize;
+        let mut tampered = false;
+        let mut fetched = None;
+        for source in &sources {
+            // Every source serves the same blob: skip what an earlier one already delivered
+            let mut skip = consumed;
+            let result = net::stream_blob(source, |piece| {
+                let fresh = &piece[skip.min(piece.len())..];
+                skip = skip.saturating_sub(piece.len());
+                consumed += fresh.len();
+                match opener.feed(fresh) {
+                    Ok(plain) => {
+                        let _ = tx.send(plain);
+                        true
+                    }
+                    Err(_) => {
+                        tampered = true;
+                        false
+                    }
+                }
+            }).await;
+            match result {
+                Ok(blob) => {
+                    fetched = Some(blob);
+                    break;
+                }
+                Err(_) if tampered => break,
+                Err(error) => vector_core::log_warn!("[AttachmentStream] {} failed: {}", source, error),
+            }
+        }
+        let verified = fetched.filter(|_| !tampered).and_then(|blob| {
+            let tail = opener.finish().ok()?;
+            let _ = tx.send(tail);
+            Some(blob)
+        });
+        // Ends the decoder's input, whatever happened
+        drop(tx);
+
+        match verified {
+            Some(blob) => stash_streamed_blob(attachment.id, blob).await,
+            None => {
+                let Ok(id) = id_rx.await else { return };
+                vector_core::log_warn!("[AttachmentStream] {} failed verification, dropping playback", attachment.id);
+                if let Ok(engine) = AudioEngine::get() {
+                    let _ = engine.stop(id);
+                }
+                if let Some(handle) = TAURI_APP.get() {
+                    let _ = handle.emit("audio_stream_failed", serde_json::json!({ "id": id }));
+                }
+            }
+        }
+    });
+
+    let ext = attachment.extension.clone();
+    let loaded = tokio::task::spawn_blocking(move || {
+        AudioEngine::get()?.load_from_reader(ChannelReader::new(rx), &ext)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))??;
+    let _ = id_tx.send(loaded.id);
+    Ok(loaded)
+}
+
 /// Reconcile in-memory STATE against the boot integrity check. Boot preloads messages into STATE (and
 /// ships them to the frontend) BEFORE the integrity check runs, so a file that went missing while
 /// Vector was closed leaves the preloaded message (e.g. the latest one) painting a broken image — the
@@ -653,7 +846,235 @@ pub(crate) async fn reconcile_missing_attachments_in_state(affected: &[String])
     }
 }
 
+/// Outcome of an archive or restore run.
+#[derive(serde::Serialize, Default)]
+pub struct ArchiveReport {
+    /// Files moved.
+    pub moved: u32,
+    /// Their total size, in bytes.
+    pub bytes: u64,
+    /// Files that couldn't be moved (left where they were, still referenced there).
+    pub failed: u32,
+}
+
+/// Move a file, falling back to copy + delete when `to` is on another filesystem (an external
+/// drive or NAS mount, where a rename can't cross).
+fn move_file(from: &std::path::Path, to: &std::path::Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)
+}
+
+/// `dir/name`, or `dir/stem (n).ext` when that name is already taken.
+fn free_path_in(dir: &std::path::Path, name: &std::ffi::OsStr) -> std::path::PathBuf {
+    let first = dir.join(name);
+    if !first.exists() {
+        return first;
+    }
+    let name = std::path::Path::new(name);
+    let stem = name.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = name.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, ext)))
+        .find(|p| !p.exists())
+        .expect("unbounded")
+}
+
+/// Point in-memory attachments at their new paths after an archive/restore (the DB is already
+/// updated) and emit `message_update` for each affected message. Skipped once the account swapped:
+/// the moves belong to the old one.
+async fn repoint_attachments_in_state(
+    session: &vector_core::state::SessionGuard,
+    moves: &std::collections::HashMap<String, String>,
+    affected: &[String],
+) {
+    if affected.is_empty() {
+        return;
+    }
+    let affected: HashSet<&str> = affected.iter().map(|s| s.as_str()).collect();
+    let mut state = STATE.lock().await;
+    if !session.is_valid() {
+        return;
+    }
+    for chat_idx in 0..state.chats.len() {
+        let mut updated_ids: Vec<String> = Vec::new();
+        for msg in state.chats[chat_idx].messages.iter_mut() {
+            if msg.attachments.is_empty() {
+                continue;
+            }
+            let hex = msg.id_hex();
+            if !affected.contains(hex.as_str()) {
+                continue;
+            }
+            let mut changed = false;
+            for att in msg.attachments.iter_mut() {
+                if let Some(new_path) = moves.get(&*att.path) {
+                    att.path = new_path.clone().into_boxed_str();
+                    changed = true;
+                }
+            }
+            if changed {
+                updated_ids.push(hex);
+            }
+        }
+        let chat_id = state.chats[chat_idx].id().to_string();
+        for hex in &updated_ids {
+            if let Some(m) = state.chats[chat_idx].messages.find_by_hex_id(hex) {
+                let message = m.to_message(&state.interner);
+                vector_core::emit_event("message_update", &serde_json::json!({
+                    "old_id": &message.id,
+                    "message": &message,
+                    "chat_id": &chat_id,
+                }));
+            }
+        }
+    }
+}
+
+/// Let the webview display files from archive folders (the asset protocol is otherwise scoped to
+/// the download dir). Run at boot for existing archives and after each archive run.
+pub(crate) fn allow_archive_dirs<R: Runtime>(handle: &AppHandle<R>) {
+    use tauri::Manager;
+    let dirs: HashSet<std::path::PathBuf> = vector_core::db::attachments::archived_paths(None)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(path, _)| std::path::Path::new(&path).parent().map(|p| p.to_path_buf()))
+        .collect();
+    for dir in dirs {
+        let _ = handle.asset_protocol_scope().allow_directory(&dir, true);
+    }
+}
+
+/// Move downloaded files from messages older than `older_than_days` into `target_dir` (e.g. a
+/// NAS mount), repointing their attachments there. Files a newer message shares stay put. They
+/// keep displaying while the folder is reachable; [`restore_archived_attachments`] brings them back.
+#[tauri::command]
+pub async fn archive_attachments<R: Runtime>(
+    handle: AppHandle<R>,
+    older_than_days: u64,
+    target_dir: String,
+) -> Result<ArchiveReport, String> {
+    if cfg!(target_os = "android") {
+        return Err("Archiving attachments is only available on desktop".to_string());
+    }
+    let target = std::path::PathBuf::from(&target_dir);
+    if !target.is_dir() {
+        return Err("The archive folder doesn't exist".to_string());
+    }
+    let download_dir = vector_core::db::get_download_dir();
+    if std::fs::canonicalize(&target).ok() == std::fs::canonicalize(&download_dir).ok() {
+        return Err("Choose a folder other than Vector's download folder".to_string());
+    }
+    let cutoff = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+        .saturating_sub(older_than_days.saturating_mul(24 * 60 * 60));
+
+    let session = vector_core::state::SessionGuard::capture();
+    let (report, moves, affected) = tokio::task::spawn_blocking(move || {
+        let mut report = ArchiveReport::default();
+        let mut moves = std::collections::HashMap::new();
+        let mut affected = Vec::new();
+        for path in vector_core::db::attachments::archivable_paths(cutoff)? {
+            if !session.is_valid() {
+                break;
+            }
+            let from = std::path::Path::new(&path);
+            let (Some(name), Ok(meta)) = (from.file_name(), std::fs::metadata(from)) else {
+                // Missing already — the integrity check handles it
+                continue;
+            };
+            let to = free_path_in(&target, name);
+            if move_file(from, &to).is_err() {
+                report.failed += 1;
+                continue;
+            }
+            let to = to.to_string_lossy().to_string();
+            match vector_core::db::attachments::mark_archived(&path, &to) {
+                Ok(ids) => affected.extend(ids),
+                Err(_) => {
+                    // Keep the DB truthful: put the file back where it's still recorded
+                    let _ = move_file(std::path::Path::new(&to), from);
+                    report.failed += 1;
+                    continue;
+                }
+            }
+            report.moved += 1;
+            report.bytes += meta.len();
+            moves.insert(path, to);
+        }
+        Ok::<_, String>((report, moves, affected))
+    })
+    .await
+    .map_err(|e| format!("join error: {:?}", e))??;
+
+    allow_archive_dirs(&handle);
+    repoint_attachments_in_state(&session, &moves, &affected).await;
+    Ok(report)
+}
+
+/// Move archived files back to their original download locations — every archived file, or
+/// just `message_id`'s. A file whose archive folder is unreachable counts as failed and stays
+/// archived.
+#[tauri::command]
+pub async fn restore_archived_attachments(message_id: Option<String>) -> Result<ArchiveReport, String> {
+    let session = vector_core::state::SessionGuard::capture();
+    let (report, moves, affected) = tokio::task::spawn_blocking(move || {
+        let mut report = ArchiveReport::default();
+        let mut moves = std::collections::HashMap::new();
+        let mut affected = Vec::new();
+        for (path, original) in vector_core::db::attachments::archived_paths(message_id.as_deref())? {
+            if !session.is_valid() {
+                break;
+            }
+            let (from, to) = (std::path::Path::new(&path), std::path::Path::new(&original));
+            let Ok(meta) = std::fs::metadata(from) else {
+                report.failed += 1;
+                continue;
+            };
+            if let Some(parent) = to.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            if move_file(from, to).is_err() {
+                report.failed += 1;
+                continue;
+            }
+            match vector_core::db::attachments::mark_restored(&path) {
+                Ok(ids) => affected.extend(ids),
+                Err(_) => {
+                    let _ = move_file(to, from);
+                    report.failed += 1;
+                    continue;
+                }
+            }
+            report.moved += 1;
+            report.bytes += meta.len();
+            moves.insert(path, original);
+        }
+        Ok::<_, String>((report, moves, affected))
+    })
+    .await
+    .map_err(|e| format!("join error: {:?}", e))??;
+
+    repoint_attachments_in_state(&session, &moves, &affected).await;
+    Ok(report)
+}
+
+/// The receiver has opened a view-once attachment: delete the local file and refuse any
+/// re-download.
+#[tauri::command]
+pub async fn mark_media_viewed(chat_id: String, msg_id: String, attachment_id: String) -> Result<(), String> {
+    vector_core::view_once::mark_viewed(&chat_id, &msg_id, &attachment_id).await
+}
+
 // Handler list for this module (for reference):
 // - generate_thumbhash_preview
 // - decode_thumbhash
 // - download_attachment
+// - stream_attachment
+// - archive_attachments
+// - restore_archived_attachments
+// - mark_media_viewed
@@ -73,7 +73,7 @@ pub fn audio_set_volume(id: u32, volume: f32) -> Result<(), String> {
 /// Send a pending voice recording without passing audio data over IPC.
 /// Encodes WAV from stashed i16 samples and sends via existing voice_message path.
 #[tauri::command]
-pub async fn send_recording(receiver: String, replied_to: String) -> Result<crate::message::MessageSendResult, String> {
+pub async fn send_recording(receiver: String, replied_to: String, caption: Option<String>) -> Result<crate::message::MessageSendResult, String> {
     use crate::voice::AudioRecorder;
 
     let pending = AudioRecorder::global()
@@ -87,7 +87,7 @@ pub async fn send_recording(receiver: String, replied_to: String) -> Result<crat
     let wav_bytes = pending.encode_wav()?;
 
     // Send via existing voice_message path
-    crate::message::voice_message(receiver, replied_to, wav_bytes).await
+    crate::message::voice_message(receiver, replied_to, wav_bytes, caption).await
 }
 
 /// Metadata extracted from an audio file's tags (ID3, Vorbis, MP4 atoms).
@@ -0,0 +1,41 @@
+//! Chat folder Tauri commands — thin shims over `vector_core::db::chat_folders`.
+//!
+//! Folders are purely local; the chat list filters on `get_folders` membership and shows each
+//! folder's unread count on its chip.
+
+use vector_core::db::chat_folders::{self, ChatFolder};
+
+use crate::STATE;
+
+/// Every folder with its chats and unread count (the badge's muted/blocked filters), ordered by name.
+#[tauri::command]
+pub async fn get_folders() -> Result<Vec<ChatFolder>, String> {
+    let mut folders = chat_folders::list_folders()?;
+    crate::commands::messaging::ensure_unread_seeded().await;
+    let state = STATE.lock().await;
+    for folder in &mut folders {
+        folder.unread = state.folder_unread(&folder.chats);
+    }
+    Ok(folders)
+}
+
+#[tauri::command]
+pub async fn create_folder(name: String) -> Result<ChatFolder, String> {
+    chat_folders::create_folder(&name)
+}
+
+#[tauri::command]
+pub async fn rename_folder(id: i64, name: String) -> Result<(), String> {
+    chat_folders::rename_folder(id, &name)
+}
+
+#[tauri::command]
+pub async fn delete_folder(id: i64) -> Result<(), String> {
+    chat_folders::delete_folder(id)
+}
+
+/// Move a chat into a folder, or out of its folder with `folder_id = null`.
+#[tauri::command]
+pub async fn assign_chat_to_folder(chat_id: String, folder_id: Option<i64>) -> Result<(), String> {
+    chat_folders::assign_chat(&chat_id, folder_id)
+}
@@ -185,26 +185,30 @@ pub struct CommunityMember {
 }
 
 /// Observed participants of a Community: distinct authors seen across its channels, newest
-/// activity first. The frontend resolves each npub's profile (name/avatar) for display.
+/// activity first. The frontend resolves each npub's profile (name/avatar) for display; members
+/// we've never fetched are queued here, so their names arrive as `profile_update` events.
 #[tauri::command]
 pub async fn get_community_members(community_id: String) -> Result<Vec<CommunityMember>, String> {
-    if is_v2_community(&community_id) {
+    let members: Vec<CommunityMember> = if is_v2_community(&community_id) {
         // v2 memberlist = the persisted guestbook fold ∪ observed authors ∪ granted
         // roster − banlist, all LOCAL (seeded post-join, cursor-caught-up at boot,
         // live-ingested) — the facade read is instant.
-        return Ok(vector_core::VectorCore
+        vector_core::VectorCore
             .get_community_members(&community_id)
             .await
             .into_iter()
             .filter_map(|m| m.get("npub").and_then(|n| n.as_str()).map(String::from))
             .map(|npub| CommunityMember { npub, last_active: 0 })
-            .collect());
-    }
-    let activity = vector_core::db::community::community_member_activity(&community_id)?;
-    Ok(activity
-        .into_iter()
-        .map(|(npub, last_active)| CommunityMember { npub, last_active })
-        .collect())
+            .collect()
+    } else {
+        vector_core::db::community::community_member_activity(&community_id)?
+            .into_iter()
+            .map(|(npub, last_active)| CommunityMember { npub, last_active })
+            .collect()
+    };
+    let npubs: Vec<String> = members.iter().map(|m| m.npub.clone()).collect();
+    vector_core::profile::sync::queue_unknown_profiles(&npubs).await;
+    Ok(members)
 }
 
 /// The `/` picker snapshot for a chat: its bot members' last-known command
@@ -400,6 +404,13 @@ pub fn can_manage_community_roles(community_id: String) -> Result<bool, String>
     Ok(vector_core::community::service::caller_can_manage_roles(&community))
 }
 
+/// A diagnostics snapshot of a community (epochs, held epoch keys and when they arrived, folded
+/// control heads, sync cursors, storage counts) for "stopped decrypting" reports. No key material.
+#[tauri::command]
+pub fn get_community_debug_info(community_id: String) -> Result<vector_core::community::debug::CommunityDebugInfo, String> {
+    vector_core::community::debug::debug_info(&community_id)
+}
+
 /// The local user's effective management capabilities in a community (role engine — owner is just
 /// position 0, NOTHING is owner-hardcoded). Drives which management affordances the UI shows: an admin
 /// whose role carries a permission gets the same buttons as the owner. `manage_admin_role` is the crown's
@@ -747,6 +758,33 @@ pub async fn send_community_message(
     replied_to: Option<String>,
     bot: Option<String>,
 ) -> Result<(), String> {
+    use vector_core::sending::{split_message, MAX_MESSAGE_BYTES, MAX_MESSAGE_PARTS};
+    if content.len() <= MAX_MESSAGE_BYTES {
+        return post_community_text(channel_id, content, replied_to, bot).await.map(|_| ());
+    }
+    // Too long for one event: consecutive "(Part i/n)" messages, each replying to the one
+    // before it — or, past MAX_MESSAGE_PARTS, a `message.txt` file (parity with DMs)
+    let parts = split_message(&content, MAX_MESSAGE_BYTES);
+    if parts.len() > MAX_MESSAGE_PARTS {
+        let session = vector_core::state::SessionGuard::capture();
+        let prepared = process_outbound_community_attachment_bytes(content.into_bytes(), "message.txt", false, false).await?;
+        return dispatch_community_attachment_message(channel_id, String::new(), replied_to, session, vec![prepared]).await.map(|_| ());
+    }
+    let mut reply = replied_to;
+    for part in parts {
+        reply = Some(post_community_text(channel_id.clone(), part, reply, bot.clone()).await?);
+    }
+    Ok(())
+}
+
+/// Post one text message that fits in a single event to a Community channel. Returns the id
+/// it was sent under.
+async fn post_community_text(
+    channel_id: String,
+    content: String,
+    replied_to: Option<String>,
+    bot: Option<String>,
+) -> Result<String, String> {
     use vector_core::sending::SendCallback;
     use vector_core::Message;
     let reply = replied_to.filter(|r| !r.is_empty());
@@ -758,8 +796,13 @@ pub async fn send_community_message(
         .as_deref()
         .filter(|b| !b.is_empty())
         .and_then(|b| nostr_sdk::prelude::PublicKey::parse(b).ok());
-    let bot_tags: Vec<nostr_sdk::prelude::Tag> =
+    let mut inner_tags: Vec<nostr_sdk::prelude::Tag> =
         bot_pk.map(|pk| vec![vector_core::bot_interface::bot_tag(&pk)]).unwrap_or_default();
+    // A preview fetched while composing rides the inner too, so members needn't fetch the link.
+    let preview = vector_core::link_preview::outbound(&content);
+    if let Some(tag) = preview.as_ref().and_then(|(url, m)| vector_core::link_preview::tag(url, m)) {
+        inner_tags.push(tag);
+    }
     let addressed_bots: Vec<String> = bot_pk
         .and_then(|pk| nostr_sdk::prelude::ToBech32::to_bech32(&pk).ok())
         .into_iter()
@@ -813,7 +856,7 @@ pub async fn send_community_message(
         // set) so relays drop the wrap and every member's client purges on
         // schedule. Resolved ONCE and passed to both the precompute and the send
         // so the pure-function rumor id can't fork.
-        let mut extra_tags = bot_tags;
+        let mut extra_tags = inner_tags;
         // Resolve the Self-Destruct expiry ONCE: it stamps both the outgoing
         // rumor (so recipients + relays honour NIP-40) AND the optimistic message
         // below, so the sender's own echo self-destructs like everyone else's.
@@ -837,6 +880,7 @@ pub async fn send_community_message(
             replied_to: reply.clone().unwrap_or_default(),
             emoji_tags: emoji_tags.clone(),
             addressed_bots: addressed_bots.clone(),
+            preview_metadata: preview.as_ref().map(|(_, m)| m.clone()),
             expiration: expiry,
             ..Default::default()
         };
@@ -869,7 +913,7 @@ pub async fn send_community_message(
                         callback.on_sent(&channel_id, &message_id, msg);
                         callback.on_persist(&channel_id, msg);
                     }
-                    return Ok(());
+                    return Ok(sent_id);
                 }
                 // 3a. Sent — clear the pending flag (the echo deduped onto this row).
                 let sent_row = {
@@ -880,7 +924,7 @@ pub async fn send_community_message(
                     callback.on_sent(&channel_id, &message_id, msg);
                     callback.on_persist(&channel_id, msg);
                 }
-                Ok(())
+                Ok(message_id)
             }
             Ok(_) => Err("account changed during send".to_string()),
             Err(e) => {
@@ -935,7 +979,7 @@ pub async fn send_community_message(
         ms,
         reply.as_deref(),
         &emoji_tags,
-        &bot_tags,
+        &inner_tags,
     );
     let message_id = unsigned.id.ok_or("inner event has no id")?.to_hex();
 
@@ -950,6 +994,7 @@ pub async fn send_community_message(
         replied_to: reply.clone().unwrap_or_default(),
         emoji_tags: emoji_tags.clone(),
         addressed_bots: addressed_bots.clone(),
+        preview_metadata: preview.as_ref().map(|(_, m)| m.clone()),
         ..Default::default()
     };
     {
@@ -990,7 +1035,7 @@ pub async fn send_community_message(
                 // did publish; just don't strand a phantom pending bubble.
                 vector_core::log_warn!("[community] sent message {} not in STATE to finalize", message_id);
             }
-            Ok(())
+            Ok(message_id)
         }
         Err(e) => {
             // 3b. Failed — mark the optimistic message failed (offers retry in the UI).
@@ -1132,6 +1177,11 @@ async fn process_outbound_community_attachment_bytes(
         webxdc_topic,
         group_id: None,
         original_hash: Some(plaintext_hash),
+        mirrors: Vec::new(),
+        view_once: false,
+        video_note: false,
+        viewed: false,
+        transcript: None,
     };
     Ok(PreparedCommunityAttachment { attachment, encrypted, mime })
 }
@@ -1189,12 +1239,13 @@ pub async fn send_community_file_bytes(
 pub(crate) async fn send_community_voice_bytes(
     channel_id: String,
     bytes: Vec<u8>,
+    caption: String,
     replied_to: Option<String>,
 ) -> Result<(), String> {
     let session = vector_core::state::SessionGuard::capture();
     let mut prepared = process_outbound_community_attachment_bytes(bytes, "voice-message.wav", false, false).await?;
     prepared.attachment.name = String::new();
-    dispatch_community_attachment_message(channel_id, String::new(), replied_to, session, vec![prepared]).await.map(|_| ())
+    dispatch_community_attachment_message(channel_id, caption, replied_to, session, vec![prepared]).await.map(|_| ())
 }
 
 /// Send the JS-cached paste bytes (populated by `cache_file_bytes` on clipboard paste,
@@ -1358,10 +1409,11 @@ async fn dispatch_community_attachment_message(
                 )
             });
 
+        let encrypted = std::sync::Arc::new(encrypted);
         let upload_url = match vector_core::blossom::upload_blob_with_progress_and_failover(
             signer.clone(),
             servers.clone(),
-            std::sync::Arc::new(encrypted),
+            encrypted.clone(),
             Some(mime.as_str()),
             /* is_encrypted */ true,
             progress_cb,
@@ -1379,11 +1431,21 @@ async fn dispatch_community_attachment_message(
         };
 
         attachment.url = upload_url.clone();
+        attachment.mirrors = vector_core::blossom::mirror_blob(
+            signer.clone(),
+            servers.clone(),
+            &upload_url,
+            encrypted,
+            Some(mime.as_str()),
+            vector_core::blossom_servers::load_mirror_count(),
+        )
+        .await;
         // Reflect the uploaded URL on the optimistic bubble's attachment.
         {
             let mut state = vector_core::state::STATE.lock().await;
             state.update_attachment(&channel_id, &pending_id, &attachment.id, |a| {
                 a.url = upload_url.clone().into_boxed_str();
+                a.set_mirrors(&attachment.mirrors);
             });
         }
         callback.on_upload_complete(&channel_id, &pending_id, &attachment.id, &upload_url);
@@ -3208,9 +3270,34 @@ pub async fn invite_to_community(community_id: String, invitee_npub: String) ->
     let config = SendConfig { self_send: false, ..SendConfig::gui() };
     let callback: Arc<dyn SendCallback> = Arc::new(NoOpSendCallback);
 
-    send_rumor_dm(&invitee_npub, &pending_id, rumor, &config, callback)
-        .await
-        .map(|_| ())
+    let result = send_rumor_dm(&invitee_npub, &pending_id, rumor, &config, callback).await;
+    vector_core::community::invite_delivery::record_attempt(
+        &community_id,
+        &invitee_npub,
+        matches!(&result, Ok(r) if r.event_id.is_some()),
+    );
+    result.map(|_| ())
+}
+
+/// Delivery state of every invite this account sent for a Community: pending (no relay accepted it
+/// yet, a re-send is scheduled), delivered, joined, or failed after every retry.
+#[tauri::command]
+pub async fn get_community_invite_status(
+    community_id: String,
+) -> Result<Vec<vector_core::community::invite_delivery::InviteDelivery>, String> {
+    vector_core::community::invite_delivery::invite_status(&community_id).await
+}
+
+/// Every invite this account has sent, across all Communities, newest first.
+#[tauri::command]
+pub async fn list_outgoing_invites() -> Result<Vec<vector_core::community::invite_delivery::InviteDelivery>, String> {
+    vector_core::community::invite_delivery::list_outgoing_invites().await
+}
+
+/// Withdraw an invite the invitee hasn't joined from: stops its re-sends and drops it from the list.
+#[tauri::command]
+pub async fn revoke_outgoing_invite(community_id: String, invitee_npub: String) -> Result<(), String> {
+    vector_core::community::invite_delivery::revoke_invite(&community_id, &invitee_npub).await
 }
 
 /// List invites awaiting the user's accept/decline decision.
@@ -3409,6 +3496,91 @@ pub async fn decline_community_invite(community_id: String) -> Result<(), String
     Ok(())
 }
 
+/// How far back the invite preview's activity estimate looks.
+const INVITE_ACTIVITY_WINDOW_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// What a parked invite card can show before Accept: who owns it, the logo, and how busy it is.
+#[derive(serde::Serialize)]
+pub struct CommunityInvitePreview {
+    pub community_id: String,
+    pub name: String,
+    pub inviter_npub: String,
+    /// The proven owner (v1: verified attestation; v2: the bundle's self-certified owner).
+    pub owner_npub: Option<String>,
+    pub channels: usize,
+    /// Decrypted + cached logo, when the bundle carries one.
+    pub icon_path: Option<String>,
+    /// Messages in the primary channel's newest page sent within the last week; `None` when the
+    /// page couldn't be fetched (or the protocol has no pre-join warm-up).
+    pub recent_messages: Option<usize>,
+    pub last_active_at: Option<u64>,
+}
+
+/// Enrich a parked invite for an informed accept/decline: verify the owner, queue the owner's and
+/// inviter's profiles (arriving as `profile_update`), resolve the logo, and estimate activity from
+/// the warmed first page (re-warming it if the receive-time preload has expired). Read-only: nothing
+/// is persisted for a community the user hasn't joined.
+#[tauri::command]
+pub async fn preview_community_invite(community_id: String) -> Result<CommunityInvitePreview, String> {
+    let pending = vector_core::db::community::list_pending_invites()?
+        .into_iter()
+        .find(|inv| inv.community_id == community_id)
+        .ok_or("No pending invite for that Community")?;
+
+    let v1_invite;
+    let (name, owner_npub, channels, icon) =
+        match vector_core::community::v2::invite::CommunityInvite::from_bundle_json(&pending.bundle_json) {
+            Ok(bundle) => {
+                v1_invite = None;
+                let owner = nostr_sdk::PublicKey::from_hex(&bundle.owner).ok().and_then(|pk| pk.to_bech32().ok());
+                let icon = bundle.icon.as_ref().map(|i| i.to_community_image());
+                (bundle.name, owner, bundle.channels.len(), icon)
+            }
+            Err(_) => {
+                let invite = CommunityInvite::from_json(&pending.bundle_json)?;
+                let owner = invite
+                    .owner_attestation
+                    .as_ref()
+                    .and_then(|att| vector_core::community::owner::verify_owner_attestation(att, &invite.community_id))
+                    .and_then(|pk| pk.to_bech32().ok());
+                let (name, channels, icon) = (invite.name.clone(), invite.channels.len(), invite.icon.clone());
+                v1_invite = Some(invite);
+                (name, owner, channels, icon)
+            }
+        };
+
+    let npubs: Vec<String> = owner_npub.iter().chain(std::iter::once(&pending.inviter_npub)).cloned().collect();
+    vector_core::profile::sync::queue_unknown_profiles(&npubs).await;
+
+    let icon_path = match icon {
+        Some(image) => {
+            let handle = crate::TAURI_APP.get().ok_or("App handle not initialized")?.clone();
+            download_decrypt_cache_image(&handle, &image).await.ok()
+        }
+        None => None,
+    };
+
+    let since = nostr_sdk::Timestamp::now().as_secs().saturating_sub(INVITE_ACTIVITY_WINDOW_SECS);
+    let mut activity = vector_core::community::cache::peek_preload_activity(&community_id, since);
+    if activity.is_none() {
+        if let Some(invite) = &v1_invite {
+            service::preload_community(invite).await;
+            activity = vector_core::community::cache::peek_preload_activity(&community_id, since);
+        }
+    }
+
+    Ok(CommunityInvitePreview {
+        community_id,
+        name,
+        inviter_npub: pending.inviter_npub,
+        owner_npub,
+        channels,
+        icon_path,
+        recent_messages: activity.map(|(recent, _)| recent),
+        last_active_at: activity.and_then(|(_, newest)| newest),
+    })
+}
+
 // ============================================================================
 // Community display metadata (name / description / logo / banner)
 // ============================================================================
@@ -3756,10 +3928,13 @@ pub async fn create_public_invite(
     expires_in_secs: Option<u64>,
     label: Option<String>,
 ) -> Result<String, String> {
-    // v2 mints a naddr#fragment link (expiry/label wiring is a follow-up).
+    // v2 mints a naddr#fragment link.
     if is_v2_community(&community_id) {
-        let _ = (expires_in_secs, label);
-        return vector_core::VectorCore.create_public_invite(&community_id).await.map_err(|e| e.to_string());
+        let expires_at = expires_in_secs.map(|secs| now_secs().saturating_add(secs));
+        return vector_core::VectorCore
+            .create_public_invite_with(&community_id, expires_at, label)
+            .await
+            .map_err(|e| e.to_string());
     }
     let session = vector_core::state::SessionGuard::capture();
     let id_bytes = hex_to_id32(&community_id)?;
@@ -0,0 +1,33 @@
+//! Contact group Tauri commands — thin shims over `vector_core::db::contact_groups`.
+//!
+//! Groups are purely local labels for organizing DM contacts; the chat list filters on
+//! `get_contact_groups` membership.
+
+use vector_core::db::contact_groups::{self, ContactGroup};
+
+/// Every contact group with its members, ordered by name.
+#[tauri::command]
+pub async fn get_contact_groups() -> Result<Vec<ContactGroup>, String> {
+    contact_groups::list_groups()
+}
+
+#[tauri::command]
+pub async fn create_contact_group(name: String) -> Result<ContactGroup, String> {
+    contact_groups::create_group(&name)
+}
+
+#[tauri::command]
+pub async fn rename_contact_group(id: i64, name: String) -> Result<(), String> {
+    contact_groups::rename_group(id, &name)
+}
+
+#[tauri::command]
+pub async fn delete_contact_group(id: i64) -> Result<(), String> {
+    contact_groups::delete_group(id)
+}
+
+/// Add (`member = true`) or remove `npub` from a group.
+#[tauri::command]
+pub async fn set_contact_group_member(id: i64, npub: String, member: bool) -> Result<(), String> {
+    contact_groups::set_member(id, &npub, member)
+}
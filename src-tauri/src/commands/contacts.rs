@@ -0,0 +1,22 @@
+//! Contact list Tauri commands — thin shims over `vector_core::contacts` (NIP-02, synced with
+//! other Nostr clients through kind 3).
+
+use vector_core::contacts::{self, Contact};
+
+/// The contact list, sorted by npub.
+#[tauri::command]
+pub async fn get_contacts() -> Result<Vec<Contact>, String> {
+    contacts::list()
+}
+
+/// Add `npub` to the contact list, or change its petname.
+#[tauri::command]
+pub async fn add_contact(npub: String, petname: Option<String>) -> Result<Contact, String> {
+    contacts::add(&npub, petname.as_deref())
+}
+
+#[tauri::command]
+pub async fn remove_contact(npub: String) -> Result<(), String> {
+    contacts::remove(&npub)
+}
+
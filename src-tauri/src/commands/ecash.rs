@@ -0,0 +1,169 @@
+//! Ecash Tauri commands — the Cashu wallet (`vector_core::cashu`) and in-chat tokens.
+
+use std::borrow::Cow;
+
+use nostr_sdk::prelude::*;
+use vector_core::cashu;
+
+/// Send `amount` sats of ecash to a DM. The token rides in a gift-wrapped rumor like a PIVX
+/// payment; the recipient's client claims it on receipt. Returns the message id.
+#[tauri::command]
+pub async fn send_ecash(chat_id: String, amount: u64, memo: Option<String>) -> Result<String, String> {
+    let Ok(vector_core::ids::ChatId::Dm(receiver)) = vector_core::ids::ChatId::parse(&chat_id) else {
+        return Err("Ecash can only be sent in direct messages".to_string());
+    };
+    let receiver_pubkey = receiver.pubkey();
+    let client = crate::nostr_client().ok_or("Nostr client not initialized")?;
+    let my_public_key = crate::my_public_key().ok_or("Public key not initialized")?;
+
+    let sent = cashu::create_token(amount, memo.as_deref()).await?;
+    let content = serde_json::json!({
+        "amount_sat": amount,
+        "memo": sent.token.memo,
+    }).to_string();
+    let tags = vec![
+        vec!["d".to_string(), cashu::D_TAG.to_string()],
+        vec!["token".to_string(), sent.encoded.clone()],
+        vec!["amount".to_string(), amount.to_string()],
+        vec!["mint".to_string(), sent.token.mint.clone()],
+    ];
+
+    let rumor = EventBuilder::new(Kind::ApplicationSpecificData, &content)
+        .tag(Tag::custom(TagKind::d(), vec![cashu::D_TAG]))
+        .tag(Tag::custom(TagKind::Custom(Cow::Borrowed("token")), vec![&sent.encoded]))
+        .tag(Tag::custom(TagKind::Custom(Cow::Borrowed("amount")), vec![&amount.to_string()]))
+        .tag(Tag::custom(TagKind::Custom(Cow::Borrowed("mint")), vec![&sent.token.mint]))
+        .tag(Tag::public_key(receiver_pubkey))
+        .build(my_public_key);
+    let event_id = rumor.id.ok_or("Failed to get event ID")?.to_hex();
+
+    if let Err(e) = crate::inbox_relays::send_gift_wrap(&client, &receiver_pubkey, rumor.clone(), []).await {
+        // Nothing left the device, so the token is still ours to take back
+        return match cashu::receive_token(&sent.encoded, None, None).await {
+            Ok(_) => Err(format!("Failed to send ecash: {}", e)),
+            Err(reclaim) => Err(format!(
+                "Failed to send ecash: {} (the token is kept in your wallet history: {})", e, reclaim
+            )),
+        };
+    }
+
+    // Self-copy for recovery (in-scope client clone + SessionGuard).
+    let self_wrap_client = client.clone();
+    let self_wrap_session = vector_core::state::SessionGuard::capture();
+    tokio::spawn(async move {
+        if !self_wrap_session.is_valid() { return; }
+        let _ = self_wrap_client.gift_wrap(&my_public_key, rumor, []).await;
+    });
+
+    let _ = cashu::link_sent_token(sent.history_id, &chat_id, &event_id);
+    let stored_event = crate::stored_event::StoredEventBuilder::new()
+        .id(&event_id)
+        .kind(crate::stored_event::event_kind::APPLICATION_SPECIFIC)
+        .content(&content)
+        .tags(tags)
+        .created_at(vector_core::clock::now_secs())
+        .mine(true)
+        .npub(Some(my_public_key.to_bech32().unwrap_or_default()))
+        .build();
+    let event_timestamp = stored_event.created_at;
+    let _ = crate::db::save_pivx_payment_event(&chat_id, stored_event).await;
+
+    vector_core::emit_event("ecash_received", &serde_json::json!({
+        "conversation_id": chat_id,
+        "amount_sat": amount,
+        "mint": sent.token.mint,
+        "memo": sent.token.memo,
+        "message_id": event_id,
+        "sender": my_public_key.to_bech32().unwrap_or_default(),
+        "is_mine": true,
+        "at": event_timestamp * 1000,
+    }));
+
+    Ok(event_id)
+}
+
+/// All ecash tokens sent in a chat (for loading history), with what each brought in once claimed.
+#[tauri::command]
+pub async fn get_chat_ecash(conversation_id: String) -> Result<Vec<serde_json::Value>, String> {
+    let events = crate::db::get_ecash_tokens_for_chat(&conversation_id).await?;
+    Ok(events.iter().map(|event| {
+        let tag = |name: &str| event.tags.iter()
+            .find(|tag| tag.len() >= 2 && tag[0] == name)
+            .map(|tag| tag[1].clone());
+        let memo = serde_json::from_str::<serde_json::Value>(&event.content)
+            .ok()
+            .and_then(|v| v.get("memo").and_then(|m| m.as_str().map(String::from)));
+        serde_json::json!({
+            "message_id": event.id,
+            "amount_sat": tag("amount").and_then(|a| a.parse::<u64>().ok()).unwrap_or(0),
+            "mint": tag("mint").unwrap_or_default(),
+            "memo": memo,
+            "redeemed": cashu::redeemed_amount(&event.id),
+            "sender": event.npub,
+            "is_mine": event.mine,
+            "at": event.created_at * 1000,
+        })
+    }).collect())
+}
+
+/// Claim a chat's ecash token by hand: one the chat's remote-content policy held back or from a
+/// mint the wallet didn't use yet, or one we sent that was never claimed (taking it back).
+/// Returns the sats received.
+#[tauri::command]
+pub async fn claim_chat_ecash(conversation_id: String, message_id: String) -> Result<u64, String> {
+    let event = crate::db::get_ecash_tokens_for_chat(&conversation_id).await?
+        .into_iter()
+        .find(|event| event.id == message_id)
+        .ok_or("Ecash token not found")?;
+    let token = event.tags.iter()
+        .find(|tag| tag.len() >= 2 && tag[0] == "token")
+        .map(|tag| tag[1].clone())
+        .ok_or("Ecash token not found")?;
+    if !event.mine {
+        vector_core::remote_content::gate(&conversation_id, true)?;
+    }
+    let amount = cashu::receive_token(&token, Some(&conversation_id), Some(&message_id)).await?;
+    vector_core::emit_event("ecash_redeemed", &serde_json::json!({
+        "conversation_id": conversation_id, "message_id": message_id, "amount": amount,
+    }));
+    Ok(amount)
+}
+
+/// Redeem a pasted `cashuA…`/`cashuB…` token. Returns the sats received.
+#[tauri::command]
+pub async fn redeem_ecash(token: String) -> Result<u64, String> {
+    cashu::receive_token(&token, None, None).await
+}
+
+#[tauri::command]
+pub async fn get_ecash_balance() -> Result<cashu::WalletBalance, String> {
+    cashu::balance()
+}
+
+#[tauri::command]
+pub async fn get_ecash_history(limit: Option<u32>) -> Result<Vec<cashu::HistoryEntry>, String> {
+    cashu::history(limit.unwrap_or(50).min(500))
+}
+
+/// Start a deposit: returns the Lightning invoice to pay.
+#[tauri::command]
+pub async fn ecash_deposit(mint_url: String, amount: u64) -> Result<cashu::Deposit, String> {
+    cashu::deposit(&mint_url, amount).await
+}
+
+#[tauri::command]
+pub async fn get_ecash_deposits() -> Result<Vec<cashu::Deposit>, String> {
+    cashu::pending_deposits()
+}
+
+/// Collect a deposit once its invoice is paid. Returns the sats minted.
+#[tauri::command]
+pub async fn ecash_complete_deposit(quote: String) -> Result<u64, String> {
+    cashu::complete_deposit(&quote).await
+}
+
+/// Pay a Lightning invoice from the wallet.
+#[tauri::command]
+pub async fn ecash_withdraw(invoice: String) -> Result<cashu::Withdrawal, String> {
+    cashu::withdraw(&invoice).await
+}
@@ -70,6 +70,22 @@ pub async fn get_emoji_usage(limit: Option<usize>) -> Result<Vec<vector_core::em
     Ok(vector_core::emoji_usage::ranked(limit))
 }
 
+/// The long-press reaction bar's emojis: pinned ones first, then the most-used,
+/// topped up with the defaults (which is all a new account gets).
+#[tauri::command]
+pub async fn get_reaction_quickpicks() -> Result<Vec<String>, String> {
+    if crate::account_manager::get_current_account().is_err() {
+        return Ok(vector_core::emoji_usage::DEFAULT_QUICKPICKS.map(String::from).to_vec());
+    }
+    Ok(vector_core::emoji_usage::quickpicks())
+}
+
+/// Pin emojis to the front of the reaction bar (an empty list unpins them all).
+#[tauri::command]
+pub async fn set_reaction_quickpicks(overrides: Vec<String>) -> Result<(), String> {
+    vector_core::emoji_usage::set_quickpicks(overrides)
+}
+
 /// Resolve the active theme's pinned pack cache-first: returns the persisted
 /// copy instantly (refreshing in the background) or fetches + persists on a
 /// cache miss. Returns null if uncached and not found on relays.
@@ -153,12 +169,13 @@ pub struct EmojiPackCreateInput {
 fn generate_pack_identifier() -> String {
     // 12 url-safe chars from the system PRNG — fits comfortably in a
     // NIP-19 naddr and is short enough to scan in logs.
-    use rand::{Rng, thread_rng, distributions::Alphanumeric};
-    thread_rng()
-        .sample_iter(&Alphanumeric)
-        .take(12)
-        .map(char::from)
-        .collect()
+    use rand::{Rng, distributions::Alphanumeric};
+    vector_core::clock::with_rng(|rng| {
+        rng.sample_iter(&Alphanumeric)
+            .take(12)
+            .map(char::from)
+            .collect()
+    })
 }
 
 /// Publish (or replace) one of the user's own packs as a kind 30030
@@ -5,6 +5,10 @@
 //! - Bulk decryption migration (disable encryption)
 //! - Bulk encryption migration (enable encryption)
 //! - Event queue management during migration
+//! - Migration progress (messages, chats, bytes), the verification pass, and cancellation
+//!
+//! A migration is one SQLite transaction, so it can't be paused part-way: a cancel or a crash
+//! rolls it back entirely, and running it again starts from the top.
 
 use tauri::{command, AppHandle, Emitter, Runtime};
 use zeroize::Zeroize;
@@ -25,6 +29,7 @@ struct MigrationGuard;
 impl MigrationGuard {
     fn enter() -> Self {
         MIGRATION_IN_PROGRESS.store(true, std::sync::atomic::Ordering::Release);
+        CANCEL_REQUESTED.store(false, std::sync::atomic::Ordering::Release);
         Self
     }
 }
@@ -34,12 +39,145 @@ impl Drop for MigrationGuard {
     }
 }
 
+/// Set by `cancel_encryption_migration`; the message pass checks it per event and rolls back.
+static CANCEL_REQUESTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// The error a cancelled migration returns (the frontend matches on it).
+const MIGRATION_CANCELLED: &str = "Migration cancelled";
+
 /// Progress update for encryption migration
 #[derive(serde::Serialize, Clone)]
 pub struct MigrationProgress {
     pub total: usize,
     pub completed: usize,
     pub phase: String,
+    /// Chats whose messages are all done, of `chats_total`.
+    pub chats_completed: usize,
+    pub chats_total: usize,
+    /// Stored message content read so far.
+    pub bytes_processed: u64,
+}
+
+/// What a finished migration did, sent with `encryption_migration_complete` once the
+/// verification pass has confirmed the result.
+#[derive(serde::Serialize, Clone)]
+pub struct MigrationReport {
+    /// Messages transformed.
+    pub messages: usize,
+    /// Messages left as they were (looked encrypted but weren't, or didn't decrypt).
+    pub skipped: usize,
+    pub chats: usize,
+    pub bytes_processed: u64,
+}
+
+/// Running totals for a migration's message pass. The IDs arrive sorted by chat, so a chat is
+/// done when the next one starts. Emits `encryption_migration_progress` every 5%.
+struct ProgressTracker<'a, R: Runtime> {
+    handle: &'a AppHandle<R>,
+    progress: MigrationProgress,
+    current_chat: Option<i64>,
+    last_emitted_percent: i32,
+    skipped: usize,
+}
+
+impl<'a, R: Runtime> ProgressTracker<'a, R> {
+    fn new(handle: &'a AppHandle<R>, phase: &str, ids: &[(String, i64)]) -> Self {
+        let chats_total = if ids.is_empty() { 0 } else { 1 + ids.windows(2).filter(|w| w[0].1 != w[1].1).count() };
+        Self {
+            handle,
+            progress: MigrationProgress {
+                total: ids.len(),
+                completed: 0,
+                phase: phase.to_string(),
+                chats_completed: 0,
+                chats_total,
+                bytes_processed: 0,
+            },
+            current_chat: None,
+            last_emitted_percent: -1,
+            skipped: 0,
+        }
+    }
+
+    /// Whether the user asked to stop; the caller returns [`MIGRATION_CANCELLED`] so the
+    /// transaction rolls back.
+    fn cancelled(&self) -> bool {
+        CANCEL_REQUESTED.load(std::sync::atomic::Ordering::Acquire)
+    }
+
+    /// Count one event of `chat`, `bytes` of stored content.
+    fn step(&mut self, chat: i64, bytes: usize) {
+        if self.current_chat.is_some_and(|c| c != chat) {
+            self.progress.chats_completed += 1;
+        }
+        self.current_chat = Some(chat);
+        self.progress.completed += 1;
+        self.progress.bytes_processed += bytes as u64;
+
+        let total = self.progress.total;
+        let current_percent = if total > 0 {
+            ((self.progress.completed as f64 / total as f64) * 100.0) as i32
+        } else {
+            100
+        };
+        if current_percent >= self.last_emitted_percent + 5 {
+            self.last_emitted_percent = current_percent;
+            let _ = self.handle.emit("encryption_migration_progress", self.progress.clone());
+        }
+    }
+
+    /// Move on to a later phase (`finalizing`, `verifying`): the message pass is complete.
+    fn enter_phase(&mut self, phase: &str) {
+        self.progress.phase = phase.to_string();
+        self.progress.chats_completed = self.progress.chats_total;
+        let _ = self.handle.emit("encryption_migration_progress", self.progress.clone());
+    }
+
+    fn report(&self) -> MigrationReport {
+        MigrationReport {
+            messages: self.progress.completed - self.skipped,
+            skipped: self.skipped,
+            chats: self.progress.chats_total,
+            bytes_processed: self.progress.bytes_processed,
+        }
+    }
+}
+
+/// Collect the IDs of the message events a migration pass transforms (`filter` picks plaintext
+/// or ciphertext content), with their chat, sorted by chat. Just the ID strings: content is
+/// read one event at a time.
+fn collect_event_ids(tx: &rusqlite::Transaction, filter: &str) -> Result<Vec<(String, i64)>, String> {
+    let mut stmt = tx.prepare(&format!(
+        "SELECT id, chat_id FROM events WHERE kind IN (?1, ?2, ?3) AND {} ORDER BY chat_id",
+        filter
+    )).map_err(|e| format!("Failed to prepare ID query: {}", e))?;
+
+    let rows = stmt.query_map(
+        rusqlite::params![
+            event_kind::CHAT_MESSAGE as i32,
+            event_kind::PRIVATE_DIRECT_MESSAGE as i32,
+            event_kind::MESSAGE_EDIT as i32,
+        ],
+        |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+    ).map_err(|e| format!("Failed to query IDs: {}", e))?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Content filters for [`collect_event_ids`]: hex of at least nonce + tag length is ciphertext.
+const ENCRYPTED_CONTENT: &str = "length(content) >= 56 AND content NOT GLOB '*[^0-9a-f]*'";
+const PLAINTEXT_CONTENT: &str = "(length(content) < 56 OR content GLOB '*[^0-9a-f]*')";
+
+/// Ask the running encryption migration (enable, disable or credential change) to stop. The
+/// database rolls back to exactly where it started. Only the message pass can be cancelled; past
+/// it, the migration finishes. Returns whether a migration was running.
+#[command]
+pub fn cancel_encryption_migration() -> bool {
+    let running = MIGRATION_IN_PROGRESS.load(std::sync::atomic::Ordering::Acquire);
+    if running {
+        CANCEL_REQUESTED.store(true, std::sync::atomic::Ordering::Release);
+    }
+    running
 }
 
 /// Get current encryption status
@@ -221,6 +359,7 @@ pub fn get_encryption_and_key<R: Runtime>(handle: AppHandle<R>) -> Result<BootEn
 pub async fn disable_encryption<R: Runtime>(handle: AppHandle<R>) -> Result<(), String> {
     // Mark migration in flight so reset_session() refuses to fire mid-tx.
     let _guard = MigrationGuard::enter();
+    let key_in_keystore = pkey_in_keystore();
 
     // Close the processing gate — events are queued until we reopen
     close_processing_gate();
@@ -233,13 +372,16 @@ pub async fn disable_encryption<R: Runtime>(handle: AppHandle<R>) -> Result<(),
     drain_pending_events(&handle).await;
 
     match result {
-        Ok(()) => {
+        Ok(report) => {
             // Drop the NIP-55 PIN canary — the account is plaintext now, so boot
             // won't derive a key to check it against, and a re-enable rewrites it.
             if vector_core::signer_kind() == vector_core::SignerKind::Nip55 {
                 let _ = vector_core::db::remove_setting("nip55_pin_check");
             }
-            let _ = handle.emit("encryption_migration_complete", ());
+            if key_in_keystore {
+                return_pkey_to_keystore();
+            }
+            let _ = handle.emit("encryption_migration_complete", report);
             Ok(())
         }
         Err(e) => Err(e),
@@ -248,7 +390,7 @@ pub async fn disable_encryption<R: Runtime>(handle: AppHandle<R>) -> Result<(),
 
 /// Inner work for disable_encryption — separated so the outer function
 /// can guarantee the processing gate is always reopened.
-fn disable_encryption_work<R: Runtime>(handle: &AppHandle<R>) -> Result<(), String> {
+fn disable_encryption_work<R: Runtime>(handle: &AppHandle<R>) -> Result<MigrationReport, String> {
     // Read the encryption key from the guarded vault
     let mut key: [u8; 32] = crate::ENCRYPTION_KEY.get()
         .ok_or("No encryption key available".to_string())?;
@@ -288,6 +430,7 @@ pub async fn enable_encryption<R: Runtime>(
     security_type: String,
 ) -> Result<(), String> {
     let _guard = MigrationGuard::enter();
+    let key_in_keystore = pkey_in_keystore();
     // Derive key from credential (this is the slow Argon2 step)
     let key = crate::crypto::hash_pass(credential).await;
     crate::ENCRYPTION_KEY.set(key, &[&crate::MY_SECRET_KEY]);
@@ -302,7 +445,7 @@ pub async fn enable_encryption<R: Runtime>(
     drain_pending_events(&handle).await;
 
     match result {
-        Ok(()) => {
+        Ok(report) => {
             // NIP-55 keyless account: no pkey whose failed decrypt would reject a
             // wrong PIN at boot, so persist the verification canary (same as
             // setup_encryption). `set_encryption_enabled(true)` already ran inside
@@ -315,7 +458,10 @@ pub async fn enable_encryption<R: Runtime>(
                 ).await;
                 let _ = vector_core::db::set_sql_setting("nip55_pin_check".to_string(), canary);
             }
-            let _ = handle.emit("encryption_migration_complete", ());
+            if key_in_keystore {
+                return_pkey_to_keystore();
+            }
+            let _ = handle.emit("encryption_migration_complete", report);
             Ok(())
         }
         Err(e) => {
@@ -333,7 +479,7 @@ pub async fn enable_encryption<R: Runtime>(
 fn enable_encryption_work<R: Runtime>(
     handle: &AppHandle<R>,
     security_type: &str,
-) -> Result<(), String> {
+) -> Result<MigrationReport, String> {
     // Read the encryption key from the guarded vault
     let mut key: [u8; 32] = crate::ENCRYPTION_KEY.get()
         .ok_or("No encryption key available".to_string())?;
@@ -387,7 +533,7 @@ async fn drain_pending_events<R: Runtime>(_handle: &AppHandle<R>) {
 fn disable_encryption_transactional<R: Runtime>(
     handle: &AppHandle<R>,
     key: &[u8; 32],
-) -> Result<(), String> {
+) -> Result<MigrationReport, String> {
     let mut conn = crate::account_manager::get_write_connection_guard(handle)?;
     let tx = conn.transaction()
         .map_err(|e| format!("Failed to begin transaction: {}", e))?;
@@ -399,38 +545,20 @@ fn disable_encryption_transactional<R: Runtime>(
     ).map_err(|e| format!("Failed to set migration_state: {}", e))?;
 
     // 1. Collect encrypted event IDs (memory-efficient: just ID strings)
-    let all_ids: Vec<String> = {
-        let mut stmt = tx.prepare(
-            r#"SELECT id FROM events
-               WHERE kind IN (?1, ?2, ?3)
-               AND length(content) >= 56
-               AND content NOT GLOB '*[^0-9a-f]*'"#,
-        ).map_err(|e| format!("Failed to prepare ID query: {}", e))?;
-
-        let rows = stmt.query_map(
-            rusqlite::params![
-                event_kind::CHAT_MESSAGE as i32,
-                event_kind::PRIVATE_DIRECT_MESSAGE as i32,
-                event_kind::MESSAGE_EDIT as i32,
-            ],
-            |row| row.get::<_, String>(0),
-        ).map_err(|e| format!("Failed to query IDs: {}", e))?;
-
-        rows.filter_map(|r| r.ok()).collect()
-    };
-
-    let total = all_ids.len();
-    let mut completed = 0;
-    let mut last_emitted_percent: i32 = -1;
-    let mut skipped = 0;
+    let all_ids = collect_event_ids(&tx, ENCRYPTED_CONTENT)?;
+    let mut progress = ProgressTracker::new(handle, "decrypting", &all_ids);
 
     // 2. Decrypt each event within the transaction
-    for id in &all_ids {
+    for (id, chat) in &all_ids {
+        if progress.cancelled() {
+            return Err(MIGRATION_CANCELLED.to_string());
+        }
         let content: Option<String> = tx.query_row(
             "SELECT content FROM events WHERE id = ?1",
             rusqlite::params![id],
             |row| row.get(0),
         ).ok();
+        let bytes = content.as_ref().map_or(0, |c| c.len());
 
         if let Some(content) = content {
             match decrypt_with_key(&content, key) {
@@ -443,47 +571,26 @@ fn disable_encryption_transactional<R: Runtime>(
                 Err(_) => {
                     // Content looks encrypted (hex) but isn't — skip it
                     println!("[Encryption] Skipping event {} - looks encrypted but isn't", id);
-                    skipped += 1;
+                    progress.skipped += 1;
                 }
             }
         }
 
-        completed += 1;
-        let current_percent = if total > 0 {
-            ((completed as f64 / total as f64) * 100.0) as i32
-        } else {
-            100
-        };
-
-        if current_percent >= last_emitted_percent + 5 {
-            last_emitted_percent = current_percent;
-            let _ = handle.emit(
-                "encryption_migration_progress",
-                MigrationProgress {
-                    total,
-                    completed,
-                    phase: "decrypting".to_string(),
-                },
-            );
-        }
+        progress.step(*chat, bytes);
     }
 
     // 3. Decrypt settings and PIVX keys within the same transaction
-    let _ = handle.emit(
-        "encryption_migration_progress",
-        MigrationProgress {
-            total,
-            completed,
-            phase: "finalizing".to_string(),
-        },
-    );
+    progress.enter_phase("finalizing");
 
+    inline_keystore_secrets_in_tx(&tx)?;
     decrypt_setting_in_tx(&tx, "seed", key, |v| v.contains(' '))?;
     decrypt_setting_in_tx(&tx, "pkey", key, |v| v.starts_with("nsec"))?;
     decrypt_pivx_in_tx(&tx, key)?;
     decrypt_community_in_tx(&tx, key)?;
+    migrate_cashu_in_tx(&tx, None, Some(key))?;
 
     // 4. Verify plaintext state within the transaction (before committing)
+    progress.enter_phase("verifying");
     verify_plaintext_state_in_tx(&tx)?;
 
     // 5. Update flags within the transaction
@@ -503,12 +610,13 @@ fn disable_encryption_transactional<R: Runtime>(
     // Update cached flag now that transaction committed successfully
     set_encryption_enabled(false);
 
-    if skipped > 0 {
-        println!("[Encryption] Skipped {} false-positive hex events", skipped);
+    let report = progress.report();
+    if report.skipped > 0 {
+        println!("[Encryption] Skipped {} false-positive hex events", report.skipped);
     }
-    println!("[Encryption] Disable complete: decrypted {} events", total - skipped);
+    println!("[Encryption] Disable complete: decrypted {} events", report.messages);
 
-    Ok(())
+    Ok(report)
 }
 
 /// Enable encryption inside a single SQLite transaction.
@@ -523,7 +631,7 @@ fn enable_encryption_transactional<R: Runtime>(
     handle: &AppHandle<R>,
     key: &[u8; 32],
     security_type: &str,
-) -> Result<(), String> {
+) -> Result<MigrationReport, String> {
     let mut conn = crate::account_manager::get_write_connection_guard(handle)?;
     let tx = conn.transaction()
         .map_err(|e| format!("Failed to begin transaction: {}", e))?;
@@ -535,36 +643,20 @@ fn enable_encryption_transactional<R: Runtime>(
     ).map_err(|e| format!("Failed to set migration_state: {}", e))?;
 
     // 1. Collect plaintext event IDs (memory-efficient: just ID strings)
-    let all_ids: Vec<String> = {
-        let mut stmt = tx.prepare(
-            r#"SELECT id FROM events
-               WHERE kind IN (?1, ?2, ?3)
-               AND (length(content) < 56 OR content GLOB '*[^0-9a-f]*')"#,
-        ).map_err(|e| format!("Failed to prepare ID query: {}", e))?;
-
-        let rows = stmt.query_map(
-            rusqlite::params![
-                event_kind::CHAT_MESSAGE as i32,
-                event_kind::PRIVATE_DIRECT_MESSAGE as i32,
-                event_kind::MESSAGE_EDIT as i32,
-            ],
-            |row| row.get::<_, String>(0),
-        ).map_err(|e| format!("Failed to query IDs: {}", e))?;
-
-        rows.filter_map(|r| r.ok()).collect()
-    };
-
-    let total = all_ids.len();
-    let mut completed = 0;
-    let mut last_emitted_percent: i32 = -1;
+    let all_ids = collect_event_ids(&tx, PLAINTEXT_CONTENT)?;
+    let mut progress = ProgressTracker::new(handle, "encrypting", &all_ids);
 
     // 2. Encrypt each event within the transaction
-    for id in &all_ids {
+    for (id, chat) in &all_ids {
+        if progress.cancelled() {
+            return Err(MIGRATION_CANCELLED.to_string());
+        }
         let content: Option<String> = tx.query_row(
             "SELECT content FROM events WHERE id = ?1",
             rusqlite::params![id],
             |row| row.get(0),
         ).ok();
+        let bytes = content.as_ref().map_or(0, |c| c.len());
 
         if let Some(content) = content {
             let encrypted = encrypt_with_key(&content, key);
@@ -574,42 +666,21 @@ fn enable_encryption_transactional<R: Runtime>(
             ).map_err(|e| format!("Failed to update event: {}", e))?;
         }
 
-        completed += 1;
-        let current_percent = if total > 0 {
-            ((completed as f64 / total as f64) * 100.0) as i32
-        } else {
-            100
-        };
-
-        if current_percent >= last_emitted_percent + 5 {
-            last_emitted_percent = current_percent;
-            let _ = handle.emit(
-                "encryption_migration_progress",
-                MigrationProgress {
-                    total,
-                    completed,
-                    phase: "encrypting".to_string(),
-                },
-            );
-        }
+        progress.step(*chat, bytes);
     }
 
     // 3. Encrypt settings and PIVX keys within the same transaction
-    let _ = handle.emit(
-        "encryption_migration_progress",
-        MigrationProgress {
-            total,
-            completed,
-            phase: "finalizing".to_string(),
-        },
-    );
+    progress.enter_phase("finalizing");
 
+    inline_keystore_secrets_in_tx(&tx)?;
     encrypt_setting_in_tx(&tx, "seed", key, |v| v.contains(' '))?;
     encrypt_setting_in_tx(&tx, "pkey", key, |v| v.starts_with("nsec"))?;
     encrypt_pivx_in_tx(&tx, key)?;
     encrypt_community_in_tx(&tx, key)?;
+    migrate_cashu_in_tx(&tx, Some(key), None)?;
 
     // 4. Verify encrypted state within the transaction (before committing)
+    progress.enter_phase("verifying");
     verify_encrypted_state_in_tx(&tx, key)?;
 
     // 5. Update flags within the transaction
@@ -634,15 +705,56 @@ fn enable_encryption_transactional<R: Runtime>(
     // Update cached flag now that transaction committed successfully
     set_encryption_enabled(true);
 
-    println!("[Encryption] Enable complete: encrypted {} events", total);
+    let report = progress.report();
+    println!("[Encryption] Enable complete: encrypted {} events", report.messages);
 
-    Ok(())
+    Ok(report)
 }
 
 // ============================================================================
 // In-Transaction Helpers
 // ============================================================================
 
+/// Whether the private key is currently kept in the system keystore.
+fn pkey_in_keystore() -> bool {
+    matches!(vector_core::db::key_storage(), Ok(vector_core::db::KeyStorage::Keystore))
+}
+
+/// Copy a keystore-held private key and seed phrase into their rows, so the flow
+/// transforms and verifies them with everything else inside the transaction. The
+/// keystore entries are left untouched until after COMMIT: on rollback the rows
+/// are the sentinel again and still point at the originals.
+fn inline_keystore_secrets_in_tx(tx: &rusqlite::Transaction) -> Result<(), String> {
+    for key in ["pkey", "seed"] {
+        let row: Option<String> = tx.query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            rusqlite::params![key],
+            |row| row.get(0),
+        ).ok();
+        if row.as_deref() != Some(vector_core::db::PKEY_IN_KEYSTORE) {
+            continue;
+        }
+        let secret = match key {
+            "pkey" => vector_core::db::load_keystore_pkey()?,
+            _ => vector_core::db::load_keystore_seed()?,
+        };
+        tx.execute(
+            "UPDATE settings SET value = ?1 WHERE key = ?2",
+            rusqlite::params![secret, key],
+        ).map_err(|e| format!("Failed to inline {}: {}", key, e))?;
+    }
+    Ok(())
+}
+
+/// Move the private key and seed back to the keystore after a committed flow
+/// inlined them. Best-effort: on failure they stay in the database, which is
+/// then where `get_key_storage` reports them.
+fn return_pkey_to_keystore() {
+    if let Err(e) = vector_core::db::set_key_storage(vector_core::db::KeyStorage::Keystore) {
+        eprintln!("[Encryption] Key left in the database, keystore write failed: {}", e);
+    }
+}
+
 /// Decrypt a setting value within a transaction.
 fn decrypt_setting_in_tx(
     tx: &rusqlite::Transaction,
@@ -1044,6 +1156,43 @@ fn rekey_community_in_tx(tx: &rusqlite::Transaction, old_key: &[u8; 32], new_key
     migrate_community_in_tx(tx, Some(new_key), Some(old_key))
 }
 
+/// Wrap / unwrap / re-wrap the ecash wallet (`vector_core::cashu`): held proofs and the tokens kept
+/// from sends are bearer money. Same `enc`/`dec` convention as [`migrate_community_in_tx`].
+fn migrate_cashu_in_tx(
+    tx: &rusqlite::Transaction,
+    enc: Option<&[u8; 32]>,
+    dec: Option<&[u8; 32]>,
+) -> Result<(), String> {
+    let proofs: Vec<(String, String)> = {
+        let mut stmt = tx.prepare("SELECT y, proof FROM cashu_proofs")
+            .map_err(|e| format!("prepare cashu_proofs: {e}"))?;
+        let mapped = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))
+            .map_err(|e| format!("query cashu_proofs: {e}"))?;
+        mapped.filter_map(|r| r.ok()).collect()
+    };
+    for (y, proof) in proofs {
+        tx.execute(
+            "UPDATE cashu_proofs SET proof=?1 WHERE y=?2",
+            rusqlite::params![xform_text(&proof, enc, dec)?, y],
+        ).map_err(|e| format!("update cashu_proofs: {e}"))?;
+    }
+
+    let tokens: Vec<(i64, String)> = {
+        let mut stmt = tx.prepare("SELECT id, token FROM cashu_history WHERE token IS NOT NULL")
+            .map_err(|e| format!("prepare cashu_history: {e}"))?;
+        let mapped = stmt.query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?)))
+            .map_err(|e| format!("query cashu_history: {e}"))?;
+        mapped.filter_map(|r| r.ok()).collect()
+    };
+    for (id, token) in tokens {
+        tx.execute(
+            "UPDATE cashu_history SET token=?1 WHERE id=?2",
+            rusqlite::params![xform_text(&token, enc, dec)?, id],
+        ).map_err(|e| format!("update cashu_history: {e}"))?;
+    }
+    Ok(())
+}
+
 /// One-time backfill for an account that already had Local Encryption ON *before* Concord at-rest
 /// encryption shipped: its community rows are still plaintext. Wrap them once, gated by a per-account
 /// settings flag. Idempotent (the field discriminators skip already-wrapped rows), so a crash mid-pass
@@ -1207,6 +1356,17 @@ fn verify_encrypted_state_in_tx(
     Ok(())
 }
 
+// ============================================================================
+// Decrypted Content Cache
+// ============================================================================
+
+/// Keep recently decrypted message content in RAM for faster re-reads, or decrypt every read on
+/// demand (the default). Per account; only matters while encryption is on.
+#[command]
+pub fn set_message_plaintext_cache(enabled: bool) -> Result<(), String> {
+    vector_core::crypto::content_cache::set_enabled(enabled)
+}
+
 // ============================================================================
 // Credential Verification (no secrets cross IPC)
 // ============================================================================
@@ -1218,15 +1378,11 @@ fn verify_encrypted_state_in_tx(
 /// credential is correct, Err otherwise. The private key never leaves Rust.
 #[command]
 pub async fn verify_credential<R: Runtime>(
-    handle: AppHandle<R>,
+    _handle: AppHandle<R>,
     credential: String,
 ) -> Result<(), String> {
     let key = crate::crypto::hash_pass(credential).await;
-
-    let conn = crate::account_manager::get_db_connection_guard(&handle)?;
-    let pkey: Option<String> = conn
-        .query_row("SELECT value FROM settings WHERE key = 'pkey'", [], |row| row.get(0))
-        .ok();
+    let pkey = vector_core::db::get_pkey()?;
 
     if let Some(ref encrypted_pkey) = pkey {
         match decrypt_with_key(encrypted_pkey, &key) {
@@ -1262,14 +1418,12 @@ pub async fn rekey_encryption<R: Runtime>(
     security_type: String,
 ) -> Result<(), String> {
     let _guard = MigrationGuard::enter();
+    let key_in_keystore = pkey_in_keystore();
 
     // 1. Derive old key and verify it by test-decrypting pkey
     let old_key = crate::crypto::hash_pass(old_credential).await;
     {
-        let conn = crate::account_manager::get_db_connection_guard(&handle)?;
-        let pkey: Option<String> = conn
-            .query_row("SELECT value FROM settings WHERE key = 'pkey'", [], |row| row.get(0))
-            .ok();
+        let pkey = vector_core::db::get_pkey()?;
 
         if let Some(ref encrypted_pkey) = pkey {
             match decrypt_with_key(encrypted_pkey, &old_key) {
@@ -1304,8 +1458,11 @@ pub async fn rekey_encryption<R: Runtime>(
     drain_pending_events(&handle).await;
 
     match result {
-        Ok(()) => {
-            let _ = handle.emit("encryption_migration_complete", ());
+        Ok(report) => {
+            if key_in_keystore {
+                return_pkey_to_keystore();
+            }
+            let _ = handle.emit("encryption_migration_complete", report);
             println!("[Rekey] Re-keying complete");
             Ok(())
         }
@@ -1327,7 +1484,7 @@ fn rekey_encryption_transactional<R: Runtime>(
     old_key: &[u8; 32],
     new_key: &[u8; 32],
     security_type: &str,
-) -> Result<(), String> {
+) -> Result<MigrationReport, String> {
     use crate::crypto::{encrypt_with_key, decrypt_with_key};
 
     let mut conn = crate::account_manager::get_write_connection_guard(handle)?;
@@ -1343,37 +1500,20 @@ fn rekey_encryption_transactional<R: Runtime>(
     ).map_err(|e| format!("Failed to set migration_state: {}", e))?;
 
     // 1. Collect all encrypted event IDs (memory-efficient: just ID strings)
-    let all_ids: Vec<String> = {
-        let mut stmt = tx.prepare(
-            r#"SELECT id FROM events
-               WHERE kind IN (?1, ?2, ?3)
-               AND length(content) >= 56
-               AND content NOT GLOB '*[^0-9a-f]*'"#,
-        ).map_err(|e| format!("Failed to prepare ID query: {}", e))?;
-
-        let rows = stmt.query_map(
-            rusqlite::params![
-                event_kind::CHAT_MESSAGE as i32,
-                event_kind::PRIVATE_DIRECT_MESSAGE as i32,
-                event_kind::MESSAGE_EDIT as i32,
-            ],
-            |row| row.get::<_, String>(0),
-        ).map_err(|e| format!("Failed to query IDs: {}", e))?;
-
-        rows.filter_map(|r| r.ok()).collect()
-    };
-
-    let total = all_ids.len();
-    let mut completed = 0;
-    let mut last_emitted_percent: i32 = -1;
+    let all_ids = collect_event_ids(&tx, ENCRYPTED_CONTENT)?;
+    let mut progress = ProgressTracker::new(handle, "rekeying", &all_ids);
 
     // 2. Re-key each event (one at a time, memory-efficient)
-    for id in &all_ids {
+    for (id, chat) in &all_ids {
+        if progress.cancelled() {
+            return Err(MIGRATION_CANCELLED.to_string());
+        }
         let content: Option<String> = tx.query_row(
             "SELECT content FROM events WHERE id = ?1",
             rusqlite::params![id],
             |row| row.get(0),
         ).ok();
+        let bytes = content.as_ref().map_or(0, |c| c.len());
 
         if let Some(content) = content {
             match decrypt_with_key(&content, old_key) {
@@ -1387,46 +1527,26 @@ fn rekey_encryption_transactional<R: Runtime>(
                 Err(_) => {
                     // Content looks encrypted but can't be decrypted — skip
                     println!("[Rekey] Skipping event {} - decrypt failed", id);
+                    progress.skipped += 1;
                 }
             }
         }
 
-        completed += 1;
-        let current_percent = if total > 0 {
-            ((completed as f64 / total as f64) * 100.0) as i32
-        } else {
-            100
-        };
-
-        if current_percent >= last_emitted_percent + 5 {
-            last_emitted_percent = current_percent;
-            let _ = handle.emit(
-                "encryption_migration_progress",
-                MigrationProgress {
-                    total,
-                    completed,
-                    phase: "rekeying".to_string(),
-                },
-            );
-        }
+        progress.step(*chat, bytes);
     }
 
     // 3. Re-key settings within the same transaction
-    let _ = handle.emit(
-        "encryption_migration_progress",
-        MigrationProgress {
-            total,
-            completed,
-            phase: "finalizing".to_string(),
-        },
-    );
+    progress.enter_phase("finalizing");
 
+    inline_keystore_secrets_in_tx(&tx)?;
     rekey_setting_in_tx(&tx, "pkey", old_key, new_key)?;
     rekey_setting_in_tx(&tx, "seed", old_key, new_key)?;
     rekey_pivx_in_tx(&tx, old_key, new_key)?;
     rekey_community_in_tx(&tx, old_key, new_key)?;
+    migrate_cashu_in_tx(&tx, Some(new_key), Some(old_key))?;
 
     // 4. Verify re-keyed state within the transaction (before committing)
+    progress.enter_phase("verifying");
     verify_encrypted_state_in_tx(&tx, new_key)?;
 
     // 5. Update metadata within the same transaction
@@ -1443,7 +1563,7 @@ fn rekey_encryption_transactional<R: Runtime>(
     // 6. COMMIT — the atomic point. Everything succeeds or nothing does.
     tx.commit().map_err(|e| format!("Failed to commit re-key transaction: {}", e))?;
 
-    Ok(())
+    Ok(progress.report())
 }
 
 /// Re-key a single settings value within a transaction.
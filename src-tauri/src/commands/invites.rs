@@ -8,7 +8,7 @@
 use std::borrow::Cow;
 
 use nostr_sdk::prelude::*;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use rand::distributions::Alphanumeric;
 
 use crate::{nostr_client, active_trusted_relays, PendingInviteAcceptance};
@@ -20,12 +20,13 @@ use crate::db;
 
 /// Generate a random alphanumeric invite code
 fn generate_invite_code() -> String {
-    thread_rng()
-        .sample_iter(&Alphanumeric)
-        .take(8)
-        .map(char::from)
-        .collect::<String>()
-        .to_uppercase()
+    vector_core::clock::with_rng(|rng| {
+        rng.sample_iter(&Alphanumeric)
+            .take(8)
+            .map(char::from)
+            .collect::<String>()
+            .to_uppercase()
+    })
 }
 
 // ============================================================================
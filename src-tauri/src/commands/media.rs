@@ -2,6 +2,7 @@
 //!
 //! This module handles:
 //! - Voice recording (start/stop)
+//! - Video notes (capability check, send)
 //! - Audio transcription via Whisper (platform-specific)
 
 use tauri::{AppHandle, Runtime};
@@ -44,6 +45,36 @@ pub async fn stop_recording() -> Result<crate::audio_engine::AudioLoadResult, St
     AudioRecorder::global().stop()
 }
 
+// ============================================================================
+// Video Note Commands
+// ============================================================================
+
+/// Whether this platform can record video notes, and the recorder's limits
+#[tauri::command]
+pub fn get_video_note_support() -> crate::video::VideoNoteSupport {
+    crate::video::support()
+}
+
+/// Send a recorded video note. Raw-bytes IPC: the clip is the binary body; `receiver`,
+/// `mime`, `width` and `height` ride headers, plus an optional base64 JPEG `poster` frame and a
+/// base64 `caption`.
+#[tauri::command]
+pub async fn send_video_note(request: tauri::ipc::Request<'_>) -> Result<crate::message::MessageSendResult, String> {
+    use crate::shared::ipc::{header, header_b64, raw_body};
+
+    let bytes = raw_body(&request)?;
+    let receiver = header(&request, "receiver").ok_or("Missing receiver")?;
+    let mime = header(&request, "mime").ok_or("Missing mime")?;
+    let dim = |name: &str| header(&request, name).and_then(|v| v.parse::<u32>().ok()).unwrap_or(0);
+    let (width, height) = (dim("width"), dim("height"));
+    let poster = header(&request, "poster")
+        .and_then(|b64| base64_simd::STANDARD.decode_to_vec(b64).ok())
+        .and_then(|jpeg| crate::util::poster_metadata(&jpeg, width, height));
+    let caption = header_b64(&request, "caption");
+
+    crate::video::send(receiver, bytes, &mime, poster, caption).await
+}
+
 // ============================================================================
 // Transcription Commands (Whisper)
 // ============================================================================
@@ -61,6 +92,13 @@ pub async fn transcribe<R: Runtime>(
     if !path.exists() {
         return Err("File not found".to_string());
     }
+    // Transcribing fetches a missing model on the fly; only let it when the user has waved
+    // model downloads through, otherwise it goes through `download_whisper_model`'s prompt.
+    if !whisper::is_model_downloaded(&handle, &model_name)
+        && !vector_core::consent::is_remembered(vector_core::consent::ExpensiveOp::ModelDownload)
+    {
+        return Err("Model not downloaded".to_string());
+    }
 
     // Decode to mono 16kHz for Whisper (fast resample — whisper doesn't need audiophile quality)
     let t0 = std::time::Instant::now();
@@ -85,13 +123,117 @@ pub async fn transcribe<R: Runtime>(
     Err("Whisper transcription is not supported on this platform".to_string())
 }
 
-/// Download a Whisper model for transcription
+/// Transcribe a received voice message once it has downloaded, if the user turned on automatic
+/// transcription. Only runs with the chosen model already on disk (never starts a download), and
+/// never for view-once media. The transcript is stored with the attachment and announced via
+/// `transcription_ready`, unless the account changed while Whisper ran.
+#[cfg(feature = "whisper")]
+pub async fn auto_transcribe(chat_id: String, msg_id: String, attachment_id: String, path: String) {
+    use tauri::Emitter;
+
+    let setting = |key: &str| crate::db::get_sql_setting(key.to_string()).ok().flatten();
+    if setting("whisper_auto_transcribe").as_deref() != Some("true") {
+        return;
+    }
+    let model_name = setting("whisper_model_name")
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| "small".to_string());
+    let translate = setting("whisper_auto_translate").as_deref() == Some("true");
+    let Some(handle) = crate::TAURI_APP.get() else { return };
+    if !whisper::is_model_downloaded(handle, &model_name) {
+        return;
+    }
+    let session = vector_core::state::SessionGuard::capture();
+
+    let audio_data = match audio::decode_for_whisper(std::path::Path::new(&path)) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("[Whisper] Auto-transcribe decode failed: {}", e);
+            return;
+        }
+    };
+    let result = match whisper::transcribe(handle, &model_name, translate, audio_data).await {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("[Whisper] Auto-transcribe failed: {}", e);
+            return;
+        }
+    };
+    let transcript = vector_core::types::Transcript {
+        lang: result.lang,
+        sections: result.sections.into_iter()
+            .map(|s| vector_core::types::TranscriptSection { text: s.text, at: s.at })
+            .collect(),
+    };
+    if transcript.text().is_empty() || !session.is_valid() {
+        return;
+    }
+
+    if let Err(e) = vector_core::db::attachments::set_attachment_transcript(&msg_id, &attachment_id, &transcript) {
+        eprintln!("[Whisper] Failed to store transcript: {}", e);
+        return;
+    }
+    let mut state = crate::STATE.lock().await;
+    if !session.is_valid() {
+        return;
+    }
+    state.update_attachment(&chat_id, &msg_id, &attachment_id, |att| {
+        if !att.view_once() {
+            att.transcript = Some(Box::new(transcript.clone()));
+        }
+    });
+    drop(state);
+    let _ = handle.emit("transcription_ready", serde_json::json!({
+        "chat_id": chat_id,
+        "msg_id": msg_id,
+        "attachment_id": attachment_id,
+        "transcript": transcript,
+    }));
+}
+
+/// What downloading a Whisper model costs, for the confirmation prompt; `None` when it's
+/// already on disk.
+#[cfg(feature = "whisper")]
+#[tauri::command]
+pub async fn estimate_whisper_download<R: Runtime>(
+    handle: AppHandle<R>,
+    model_name: String,
+) -> Result<Option<vector_core::consent::Estimate>, String> {
+    let model = whisper::MODELS.iter().find(|m| m.name == model_name)
+        .ok_or_else(|| format!("Unknown model: {}", model_name))?;
+    if whisper::is_model_downloaded(&handle, &model_name) {
+        return Ok(None);
+    }
+    let bytes = model.size as u64 * 1024 * 1024;
+    Ok(Some(vector_core::consent::estimate(
+        vector_core::consent::ExpensiveOp::ModelDownload, &model_name, None, Some(bytes),
+    )))
+}
+
+/// Estimate a Whisper model download (stub for unsupported platforms)
+#[cfg(not(feature = "whisper"))]
+#[tauri::command]
+pub async fn estimate_whisper_download<R: Runtime>(
+    _handle: AppHandle<R>,
+    _model_name: String,
+) -> Result<Option<vector_core::consent::Estimate>, String> {
+    Err("Whisper model download is not supported on this platform".to_string())
+}
+
+/// Download a Whisper model for transcription. A model not yet on disk needs the token from
+/// `estimate_whisper_download` (unless the user said not to ask again).
 #[cfg(feature = "whisper")]
 #[tauri::command]
 pub async fn download_whisper_model<R: Runtime>(
     handle: AppHandle<R>,
     model_name: String,
+    consent_token: Option<String>,
 ) -> Result<String, String> {
+    if !whisper::is_model_downloaded(&handle, &model_name) {
+        vector_core::consent::redeem(
+            vector_core::consent::ExpensiveOp::ModelDownload, &model_name, consent_token.as_deref(),
+        )?;
+    }
     // Download (or simply return the cached path of) a Whisper Model
     match whisper::download_whisper_model(&handle, &model_name).await {
         Ok(path) => Ok(path),
@@ -105,6 +247,7 @@ pub async fn download_whisper_model<R: Runtime>(
 pub async fn download_whisper_model<R: Runtime>(
     _handle: AppHandle<R>,
     _model_name: String,
+    _consent_token: Option<String>,
 ) -> Result<String, String> {
     Err("Whisper model download is not supported on this platform".to_string())
 }
@@ -112,5 +255,8 @@ pub async fn download_whisper_model<R: Runtime>(
 // Handler list for this module (for reference):
 // - start_recording
 // - stop_recording
+// - get_video_note_support
+// - send_video_note
 // - transcribe (platform-specific)
+// - estimate_whisper_download (platform-specific)
 // - download_whisper_model (platform-specific)
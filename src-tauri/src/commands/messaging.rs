@@ -1,7 +1,7 @@
 //! Message fetching and caching Tauri commands.
 //!
 //! This module handles:
-//! - Paginated message retrieval from database
+//! - Paginated message retrieval from database (offset, anchored and keyset)
 //! - Message view composition from events
 //! - System events (member joined/left)
 //! - Backend cache synchronization
@@ -182,6 +182,51 @@ pub async fn get_messages_around<R: Runtime>(
     Ok(messages_for_return)
 }
 
+/// Page older history from SQL: up to `limit` messages strictly older than `before_id` (the oldest
+/// message the frontend holds), oldest first; empty at the start of history. Keyset-paged, so
+/// messages arriving meanwhile don't shift pages the way the offset pager's do. The page is mirrored
+/// into the backend state like the other loaders, which then drops the middle of the chat's history
+/// so a long scroll-up can't grow RAM without bound.
+#[tauri::command]
+pub async fn load_older_messages<R: Runtime>(
+    _handle: AppHandle<R>,
+    chat_id: String,
+    before_id: String,
+    limit: usize,
+) -> Result<Vec<Message>, String> {
+    let session = vector_core::state::SessionGuard::capture();
+    // Same clamp as `get_messages_around`: each row is decrypted, composed and cloned into STATE.
+    let limit = limit.min(512);
+    let messages = db::get_messages_before(&chat_id, &before_id, limit).await?;
+
+    let messages_for_return = messages.clone();
+
+    if !messages.is_empty() && session.is_valid() {
+        let mut state = STATE.lock().await;
+        state.add_messages_to_chat_batch(&chat_id, messages);
+        state.bound_chat_messages(&chat_id);
+    }
+
+    Ok(messages_for_return)
+}
+
+/// Search a single conversation: ordered match positions (message id + UTF-16
+/// ranges), newest first, with older/newer cursors for match navigation. The
+/// frontend jumps to a match via `get_messages_around`.
+#[tauri::command]
+pub async fn search_in_chat<R: Runtime>(
+    _handle: AppHandle<R>,
+    chat_id: String,
+    query: String,
+    cursor: Option<String>,
+    direction: Option<vector_core::db::events::SearchDirection>,
+    limit: Option<usize>,
+) -> Result<vector_core::db::events::ChatSearchPage, String> {
+    // Each scanned row is decrypted; cap the page so a hostile frontend can't pin a full-history scan.
+    let limit = limit.unwrap_or(50).min(200);
+    db::search_in_chat(&chat_id, &query, cursor.as_deref(), direction.unwrap_or_default(), limit).await
+}
+
 // ============================================================================
 // System Events Commands
 // ============================================================================
@@ -246,38 +291,38 @@ pub async fn evict_chat_messages(chat_id: String, keep_count: usize) -> Result<(
 // Unread Count Commands
 // ============================================================================
 
-/// Seed the in-RAM unread cache from the DB once per login. The full-scan `unread_counts` query runs
-/// here and nowhere on the per-message badge path — after seeding, the badge is a pure RAM fold.
-async fn ensure_unread_seeded() {
+/// Seed the in-RAM unread cache from the DB once per login. The full-scan `unread_summaries` query
+/// runs here and nowhere on the per-message badge path — after seeding, the badge is a pure RAM fold.
+pub(crate) async fn ensure_unread_seeded() {
     if STATE.lock().await.unread_seeded {
         return;
     }
     // The DB read straddles an await; a swap could land, so guard the seed against writing account
     // A's counts into account B's freshly-swapped state.
     let session = vector_core::state::SessionGuard::capture();
-    let counts = crate::db::unread_counts().await.unwrap_or_default();
+    let unread = crate::db::unread_summaries().await.unwrap_or_default();
     let mut state = STATE.lock().await;
     if !session.is_valid() {
         return;
     }
     if !state.unread_seeded {
-        state.unread_seed(counts);
+        state.unread_seed(unread);
     }
 }
 
-/// Recompute ONE chat's unread straight from the DB (indexed single-chat query) and store it in the
-/// cache. Used where an O(1) delta would be unsafe: a delete, a mark-unread retreat, or a batch
+/// Recompute ONE chat's unread summary straight from the DB (indexed single-chat queries) and store it
+/// in the cache. Used where an O(1) delta would be unsafe: a delete, a mark-unread retreat, or a batch
 /// backfill that adds messages outside the live inbound path.
 pub async fn reconcile_chat_unread(chat_id: &str) {
     let session = vector_core::state::SessionGuard::capture();
-    let count = vector_core::db::events::unread_count_for_chat(chat_id).await.unwrap_or(0);
+    let unread = vector_core::db::events::unread_summary_for_chat(chat_id).await.unwrap_or_default();
     let mut state = STATE.lock().await;
     if !session.is_valid() {
         return;
     }
     // Before the seed the whole map is (re)built from the DB anyway, so only patch a live cache.
     if state.unread_seeded {
-        state.unread_set(chat_id, count);
+        state.unread_set(chat_id, unread);
     }
 }
 
@@ -293,6 +338,9 @@ pub async fn update_unread_counter<R: Runtime>(handle: AppHandle<R>) -> u32 {
         state.sum_unread()
     };
 
+    #[cfg(desktop)]
+    crate::tray::set_unread(&handle, unread_count);
+
     // Get the main window (only used on desktop for badge handling)
     #[allow(unused_variables)]
     if let Some(window) = handle.get_webview_window("main") {
@@ -338,23 +386,84 @@ pub async fn get_unread_counts() -> std::collections::HashMap<String, u32> {
     // DB here so this call also HEALS any incremental drift; the per-message badge path stays a pure
     // in-RAM fold between these refreshes.
     let session = vector_core::state::SessionGuard::capture();
-    let counts = crate::db::unread_counts().await.unwrap_or_default();
+    let unread = crate::db::unread_summaries().await.unwrap_or_default();
     let mut state = STATE.lock().await;
     if session.is_valid() {
-        state.unread_seed(counts);
+        state.unread_seed(unread);
     }
     state.unread_snapshot()
 }
 
+/// Every chat's unread — count, unread mentions and oldest unread message id — keyed by
+/// `chat_identifier`, for the chat list's badges. Served from the in-RAM cache (seeded once, then
+/// reconciled a chat at a time), so no messages are loaded and nothing is rescanned. Chats with 0
+/// unread are omitted. Raw, like [`get_unread_counts`]: muted/blocked filtering is the badge's.
+#[tauri::command]
+pub async fn get_unread_summary() -> std::collections::HashMap<String, vector_core::db::events::ChatUnread> {
+    ensure_unread_seeded().await;
+    STATE.lock().await.unread_summary()
+}
+
+/// Messages mentioning me across every chat, newest first (`page` is 0-based). Pre-index history
+/// is indexed on first use, so the list is complete after an upgrade.
+#[tauri::command]
+pub async fn get_my_mentions(page: Option<usize>) -> Result<Vec<vector_core::db::mentions::MentionEntry>, String> {
+    const PAGE_SIZE: usize = 50;
+    vector_core::db::mentions::backfill_if_needed().await?;
+    vector_core::db::mentions::get_my_mentions(page.unwrap_or(0), PAGE_SIZE)
+}
+
+/// Per-chat unread mention counts (`chat_identifier` → count), fetched alongside
+/// [`get_unread_counts`] so the chatlist can flag rows that ping us. Chats with none are omitted.
+#[tauri::command]
+pub async fn get_unread_mention_counts() -> std::collections::HashMap<String, u32> {
+    let _ = vector_core::db::mentions::backfill_if_needed().await;
+    vector_core::db::mentions::unread_mention_counts().unwrap_or_default()
+}
+
+/// Star a message: kept in Saved Messages, past its chat's Self-Destruct Timer and out of
+/// media-cache eviction. Local only.
+#[tauri::command]
+pub fn star_message(message_id: String) -> Result<(), String> {
+    vector_core::starred::set_starred(&message_id, true)
+}
+
+#[tauri::command]
+pub fn unstar_message(message_id: String) -> Result<(), String> {
+    vector_core::starred::set_starred(&message_id, false)
+}
+
+/// Starred messages across every chat, most recently starred first.
+#[tauri::command]
+pub async fn get_starred_messages(limit: Option<usize>) -> Result<Vec<vector_core::starred::StarredMessage>, String> {
+    vector_core::starred::starred_messages(limit).await
+}
+
 /// Tell the backend which chat the user is actively watching, so inbound
 /// messages in that chat auto-mark as read on arrival (no dock-badge bump,
 /// no race with the FE's own `markAsRead`). Frontend sends `chat_id=None`
 /// when the chat closes, the window blurs, or the user scrolls off-bottom.
+/// Opening a chat also counts its recent media as used, so the media cache evicts it last.
 #[tauri::command]
 pub fn set_active_chat(chat_id: Option<String>) {
+    if let Some(id) = chat_id.clone() {
+        tauri::async_runtime::spawn_blocking(move || {
+            if let Ok(chat) = vector_core::db::id_cache::get_chat_id_by_identifier(&id) {
+                let _ = vector_core::db::attachments::touch_chat_media(chat);
+            }
+        });
+    }
     vector_core::state::set_active_chat(chat_id);
 }
 
+/// Report the chat in view for dwell-based read tracking — sent when it changes and as a
+/// heartbeat every second while it holds (`None` when nothing is in view). The chat is marked
+/// read once it has stayed in view for the `read_dwell_secs` setting. No-op without a dwell.
+#[tauri::command]
+pub async fn report_chat_view(chat_id: Option<String>) {
+    crate::services::read_tracker::report_view(chat_id).await;
+}
+
 // Handler list for this module (for reference):
 // - get_chat_messages_paginated
 // - get_chat_message_count
@@ -364,3 +473,4 @@ pub fn set_active_chat(chat_id: Option<String>) {
 // - evict_chat_messages
 // - update_unread_counter
 // - set_active_chat
+// - report_chat_view
@@ -23,12 +23,21 @@ pub mod invites;
 pub mod media;
 pub mod messaging;
 pub mod self_destruct;
+pub mod remote_content;
 pub mod realtime;
 pub mod encryption;
 pub mod audio;
 pub mod tor;
+pub mod proxy;
 pub mod emoji_packs;
 pub mod wallpaper;
+pub mod appearance;
+pub mod social_backup;
+pub mod contact_groups;
+pub mod chat_folders;
+pub mod contacts;
 pub mod community;
 pub mod clipboard;
 pub mod updates;
+pub mod zaps;
+pub mod ecash;
@@ -0,0 +1,175 @@
+//! SOCKS5 proxy Tauri commands.
+//!
+//! Wires `vector_core::proxy` (the user's own SOCKS5 proxy + kill switch) to the Privacy
+//! settings, and owns the relay-pool transport switch shared with the Tor toggle:
+//!
+//!   - `proxy_get_state`: the saved proxy, whether it's reachable, and what transport is live
+//!   - `proxy_set`: persist the proxy settings and move every connection onto them
+//!
+//! A background monitor re-probes the proxy while one is set, so a proxy going down (or coming
+//! back) cycles the relays onto the right transport without the user touching anything.
+
+use serde::Serialize;
+use tauri::Emitter;
+use vector_core::proxy::{self, ProxyConfig, Transport};
+
+/// How often the monitor re-probes the configured proxy.
+const MONITOR_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// State surfaced to the frontend for the proxy settings UI.
+#[derive(Debug, Serialize, Clone)]
+pub struct ProxyState {
+    /// Saved proxy address, `None` when no proxy is set.
+    pub address: Option<String>,
+    pub kill_switch: bool,
+    /// Whether the last probe reached the proxy (true when none is set).
+    pub reachable: bool,
+    /// Embedded Tor is enabled, so it carries traffic instead of this proxy.
+    pub tor_overrides: bool,
+    /// `socks5h://…` that traffic is routed through right now (Tor or this proxy), if any.
+    /// Lets JS-initiated plugin requests (updater) follow the same route.
+    pub active_proxy: Option<String>,
+    /// A proxy is required but down: everything is blackholed.
+    pub blocked: bool,
+}
+
+fn current_state() -> ProxyState {
+    let config = proxy::config();
+    let transport = proxy::transport();
+    ProxyState {
+        address: config.map(|c| c.address.to_string()),
+        kill_switch: config.is_some_and(|c| c.kill_switch),
+        reachable: config.is_none() || proxy::is_reachable(),
+        tor_overrides: {
+            #[cfg(feature = "tor")]
+            { !matches!(vector_core::tor::transport_state(), vector_core::tor::TorTransportState::Disabled) }
+            #[cfg(not(feature = "tor"))]
+            { false }
+        },
+        active_proxy: match transport {
+            Transport::Proxy(addr) => Some(format!("socks5h://{}", addr)),
+            _ => None,
+        },
+        blocked: transport == Transport::Blocked,
+    }
+}
+
+#[tauri::command]
+pub fn proxy_get_state() -> ProxyState {
+    current_state()
+}
+
+/// Save the proxy settings (`address: None` or empty removes the proxy), probe the new proxy,
+/// and move the shared HTTP client and every relay socket onto the resulting transport.
+#[tauri::command]
+pub async fn proxy_set(address: Option<String>, kill_switch: bool) -> Result<ProxyState, String> {
+    let address = match address.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+        Some(a) => Some(proxy::parse_proxy_address(a)?),
+        None => None,
+    };
+    vector_core::db::settings::set_sql_setting(
+        proxy::PROXY_ADDRESS_SETTING.to_string(),
+        address.map(|a| a.to_string()).unwrap_or_default(),
+    )?;
+    vector_core::db::settings::set_sql_setting(
+        proxy::PROXY_KILL_SWITCH_SETTING.to_string(),
+        if kill_switch { "1" } else { "0" }.to_string(),
+    )?;
+    proxy::set_config(address.map(|address| ProxyConfig { address, kill_switch }));
+    proxy::probe().await;
+
+    // Same deferred-error ordering as the Tor toggle: never early-return with relays still on
+    // the old transport.
+    let rebuild = vector_core::net::rebuild_shared_http_client();
+    switch_relay_transport().await?;
+    rebuild?;
+    Ok(current_state())
+}
+
+/// Start the reachability monitor (once per process). While a proxy is set it's probed every
+/// [`MONITOR_INTERVAL`]; on a change, HTTP and relay traffic are moved onto the new transport
+/// (blackholed under the kill switch, direct without it, or back onto the proxy) and a
+/// `proxy_state` event tells the UI.
+pub fn start_monitor<R: tauri::Runtime>(handle: tauri::AppHandle<R>) {
+    static STARTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+    if STARTED.swap(true, std::sync::atomic::Ordering::AcqRel) {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(MONITOR_INTERVAL).await;
+            if !proxy::probe().await {
+                continue;
+            }
+            if let Err(e) = vector_core::net::rebuild_shared_http_client() {
+                log_warn!("[Proxy] HTTP client rebuild failed: {}", e);
+            }
+            if let Err(e) = switch_relay_transport().await {
+                log_warn!("[Proxy] relay transport switch failed: {}", e);
+            }
+            let _ = handle.emit("proxy_state", current_state());
+        }
+    });
+}
+
+/// Tear down every Nostr relay in the active client's pool and re-add it with
+/// its `connection_mode` set to match the current transport (see
+/// `vector_core::proxy::transport`). Preserves all other RelayOptions
+/// (read/write/both, reconnect flags, retry config) so the pool's behavior is
+/// otherwise unchanged.
+///
+/// Why we don't just rebuild the Client: NOSTR_CLIENT is a OnceLock that's
+/// read from hundreds of places — replacing it would force a wide refactor.
+/// Per-relay re-add achieves the same end (every socket is a fresh connection
+/// through the new transport) without touching the Client's identity.
+pub(crate) async fn switch_relay_transport() -> Result<(), String> {
+    // ConnectionMode is re-exported from nostr-relay-pool via nostr-sdk::pool.
+    use nostr_sdk::pool::ConnectionMode;
+    use nostr_sdk::RelayUrl;
+
+    let client = match crate::nostr_client() {
+        Some(c) => c,
+        None => return Ok(()), // Client hasn't been built yet (e.g. pre-login). No-op.
+    };
+
+    let new_mode = match proxy::transport() {
+        Transport::Proxy(addr) => ConnectionMode::proxy(addr),
+        // Failsafe: a required proxy that isn't up routes to the blackhole
+        // rather than direct. A relay that fails to connect is recoverable;
+        // a relay that leaks the user's IP is not.
+        Transport::Blocked => ConnectionMode::proxy(proxy::blackhole_addr()),
+        Transport::Direct => ConnectionMode::Direct,
+    };
+
+    // Snapshot the current pool — URL + its existing RelayOptions clone — then
+    // mutate. We don't iterate-and-mutate concurrently because remove/add take
+    // the pool's internal lock.
+    let relays = client.relays().await;
+    let snapshots: Vec<(RelayUrl, nostr_sdk::RelayOptions)> = relays
+        .iter()
+        .map(|(url, relay)| (url.clone(), relay.opts().clone()))
+        .collect();
+
+    log_info!("[Transport] cycling {} relay connection(s) onto new transport...", snapshots.len());
+
+    for (url, opts) in snapshots {
+        // Take down the existing socket + drop the relay registration.
+        if let Err(e) = client.remove_relay(url.clone()).await {
+            log_warn!("[Transport] remove_relay({}) failed: {} — continuing", url, e);
+        }
+        // Re-add with the same options modulo connection_mode.
+        let new_opts = opts.connection_mode(new_mode.clone());
+        if let Err(e) = client.pool().add_relay(url.clone(), new_opts).await {
+            log_warn!("[Transport] re-add_relay({}) failed: {}", url, e);
+        }
+    }
+
+    // Reconnect any relays the pool didn't auto-connect.
+    client.connect().await;
+    log_info!("[Transport] relay transport switch complete");
+    Ok(())
+}
+
+// Handler list for this module (for reference):
+// - proxy_get_state
+// - proxy_set
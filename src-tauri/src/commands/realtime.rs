@@ -20,6 +20,10 @@ pub async fn start_typing(receiver: String) -> bool {
     if crate::my_public_key().is_none() {
         return false;
     }
+    // The account and chat switches, mute/block, and the per-chat rate limit
+    if !vector_core::typing::may_send(&receiver).await {
+        return false;
+    }
 
     match PublicKey::from_bech32(receiver.as_str()) {
         // A DM — vector-core owns the NIP-17 typing pipeline (30s expiry).
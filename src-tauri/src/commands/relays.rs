@@ -176,23 +176,14 @@ where
     Ok(())
 }
 
-/// True when the user has Tor enabled but the service hasn't finished
-/// bootstrapping yet. While in this state every TCP connection blackholes
-/// (failsafe), so a `connect_relay` would always fail. Better to skip it —
-/// `switch_relay_transport` cycles every relay onto the live proxy as soon
-/// as bootstrap completes, picking up anything we deferred here.
+/// True when a proxy is required but not up: Tor enabled and still
+/// bootstrapping, or the user's kill-switched proxy unreachable. While in this
+/// state every TCP connection blackholes (failsafe), so a `connect_relay`
+/// would always fail. Better to skip it — `switch_relay_transport` cycles
+/// every relay onto the live proxy as soon as it's up, picking up anything we
+/// deferred here.
 fn defer_connect_for_bootstrap() -> bool {
-    #[cfg(feature = "tor")]
-    {
-        matches!(
-            vector_core::tor::transport_state(),
-            vector_core::tor::TorTransportState::RequiredButInactive
-        )
-    }
-    #[cfg(not(feature = "tor"))]
-    {
-        false
-    }
+    matches!(vector_core::proxy::transport(), vector_core::proxy::Transport::Blocked)
 }
 
 /// Add a log entry for a relay
@@ -249,11 +240,12 @@ pub fn relay_options_for_mode(mode: &str) -> RelayOptions {
 /// connect. Returns (url, mode) pairs.
 async fn desired_enabled_relays<R: Runtime>(handle: &AppHandle<R>) -> Vec<(String, String)> {
     let disabled = get_disabled_default_relays(handle).await.unwrap_or_default();
+    let default_modes = load_default_relay_modes(handle).await.unwrap_or_default();
     let customs = load_custom_relays(handle).await.unwrap_or_default();
     let mut out: Vec<(String, String)> = Vec::new();
     for d in DEFAULT_RELAYS {
         if disabled.iter().any(|x| x.eq_ignore_ascii_case(d)) { continue; }
-        out.push(((*d).to_string(), "both".to_string()));
+        out.push(((*d).to_string(), default_relay_mode(&default_modes, d)));
     }
     for c in customs {
         if c.enabled {
@@ -357,6 +349,57 @@ async fn save_disabled_default_relays<R: Runtime>(handle: &AppHandle<R>, relays:
     Ok(())
 }
 
+/// Get the read/write mode overrides for default relays (url -> mode). Default relays without
+/// an entry run as "both".
+async fn load_default_relay_modes<R: Runtime>(handle: &AppHandle<R>) -> Result<HashMap<String, String>, String> {
+    if crate::account_manager::get_current_account().is_err() {
+        return Ok(HashMap::new());
+    }
+
+    let conn = crate::account_manager::get_db_connection_guard(handle)?;
+
+    let result: Option<String> = conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        rusqlite::params!["default_relay_modes"],
+        |row| row.get(0)
+    ).ok();
+
+    match result {
+        Some(json_str) => {
+            serde_json::from_str(&json_str)
+                .map_err(|e| format!("Failed to parse default relay modes: {}", e))
+        }
+        None => Ok(HashMap::new())
+    }
+}
+
+/// Save the read/write mode overrides for default relays
+async fn save_default_relay_modes<R: Runtime>(handle: &AppHandle<R>, modes: &HashMap<String, String>) -> Result<(), String> {
+    if crate::account_manager::get_current_account().is_err() {
+        return Err("No account selected".to_string());
+    }
+
+    let json_str = serde_json::to_string(modes)
+        .map_err(|e| format!("Failed to serialize default relay modes: {}", e))?;
+
+    let conn = crate::account_manager::get_write_connection_guard(handle)?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        rusqlite::params!["default_relay_modes", json_str],
+    ).map_err(|e| format!("Failed to save default relay modes: {}", e))?;
+
+    Ok(())
+}
+
+/// A default relay's mode from the saved overrides ("both" when unset)
+fn default_relay_mode(modes: &HashMap<String, String>, url: &str) -> String {
+    modes.iter()
+        .find(|(u, _)| u.eq_ignore_ascii_case(url))
+        .map(|(_, m)| m.clone())
+        .unwrap_or_else(|| "both".to_string())
+}
+
 // ============================================================================
 // Tauri Commands
 // ============================================================================
@@ -385,6 +428,23 @@ pub async fn get_relay_logs(url: String) -> Result<Vec<RelayLog>, String> {
     Ok(logs)
 }
 
+/// Spam scores and demotion standing of every relay (see `vector_core::relay_health`).
+#[tauri::command]
+pub async fn get_relay_stats() -> Vec<vector_core::relay_health::RelayStats> {
+    vector_core::relay_health::stats().await
+}
+
+/// Override a relay's automatic demotion: "trust" (never demote), "demote" (always), or null to
+/// go back to the automatic decision.
+#[tauri::command]
+pub async fn set_relay_demotion_override(
+    url: String,
+    value: Option<vector_core::relay_health::DemotionOverride>,
+) -> Result<(), String> {
+    let url = validate_relay_url(&url)?;
+    vector_core::relay_health::set_override(&url, value).await
+}
+
 /// Get all relays with their current status
 #[tauri::command]
 pub async fn get_relays<R: Runtime>(handle: AppHandle<R>) -> Result<Vec<RelayInfo>, String> {
@@ -392,6 +452,7 @@ pub async fn get_relays<R: Runtime>(handle: AppHandle<R>) -> Result<Vec<RelayInf
 
     let custom_relays = get_custom_relays(handle.clone()).await.unwrap_or_default();
     let disabled_defaults = get_disabled_default_relays(&handle).await.unwrap_or_default();
+    let default_modes = load_default_relay_modes(&handle).await.unwrap_or_default();
 
     let pool_relays = client.relays().await;
 
@@ -401,8 +462,9 @@ pub async fn get_relays<R: Runtime>(handle: AppHandle<R>) -> Result<Vec<RelayInf
     for default_url in DEFAULT_RELAYS {
         let url_str = *default_url;
         let is_disabled = disabled_defaults.iter().any(|d| d.eq_ignore_ascii_case(url_str));
+        let mode = default_relay_mode(&default_modes, url_str);
 
-        let (status, mode) = if let Some((_, relay)) = pool_relays.iter().find(|(u, _)| u.as_str().eq_ignore_ascii_case(url_str)) {
+        let status = if let Some((_, relay)) = pool_relays.iter().find(|(u, _)| u.as_str().eq_ignore_ascii_case(url_str)) {
             let status = match relay.status() {
                 RelayStatus::Initialized => "initialized",
                 RelayStatus::Pending => "pending",
@@ -413,9 +475,9 @@ pub async fn get_relays<R: Runtime>(handle: AppHandle<R>) -> Result<Vec<RelayInf
                 RelayStatus::Banned => "banned",
                 RelayStatus::Sleeping => "sleeping",
             };
-            (status.to_string(), "both".to_string())
+            status.to_string()
         } else {
-            ("disabled".to_string(), "both".to_string())
+            "disabled".to_string()
         };
 
         relay_infos.push(RelayInfo {
@@ -544,6 +606,7 @@ pub async fn remove_custom_blossom_server(url: String) -> Result<bool, String> {
     vector_core::blossom_servers::save_custom_blossom_servers(&customs)?;
     // Clean slate on re-add.
     let _ = vector_core::blossom_capabilities::purge_server(&url);
+    vector_core::blossom_health::forget(&url);
     vector_core::blossom_servers::refresh_cache();
     vector_core::blossom_servers::republish_blossom_servers_debounced();
     Ok(true)
@@ -603,6 +666,65 @@ pub async fn toggle_default_blossom_server(url: String, enabled: bool) -> Result
     Ok(true)
 }
 
+/// Set the server order (every listed URL must be a configured server; unlisted ones trail).
+#[tauri::command]
+pub async fn reorder_blossom_servers(urls: Vec<String>) -> Result<(), String> {
+    let session = require_active_blossom_session()?;
+    let known: Vec<String> = vector_core::blossom_servers::list_all_servers()
+        .into_iter()
+        .map(|s| s.url.trim_end_matches('/').to_lowercase())
+        .collect();
+    let mut order: Vec<String> = Vec::with_capacity(urls.len());
+    for url in urls {
+        let key = url.trim().trim_end_matches('/').to_lowercase();
+        if !known.contains(&key) {
+            return Err(format!("Unknown server: {}", url));
+        }
+        if !order.contains(&key) {
+            order.push(key);
+        }
+    }
+    if !session.is_valid() { return Err("Session changed".to_string()); }
+    vector_core::blossom_servers::save_server_order(&order)?;
+    vector_core::blossom_servers::refresh_cache();
+    vector_core::blossom_servers::republish_blossom_servers_debounced();
+    Ok(())
+}
+
+/// Health of every configured server, plus the upload preferences: whether the fastest server
+/// goes first, and how many other servers each attachment is mirrored to.
+#[derive(serde::Serialize)]
+pub struct BlossomHealthReport {
+    pub prefer_fastest: bool,
+    pub mirror_count: usize,
+    pub max_mirrors: usize,
+    pub servers: Vec<vector_core::blossom_health::BlossomServerHealth>,
+}
+
+#[tauri::command]
+pub async fn get_blossom_server_health() -> BlossomHealthReport {
+    let urls: Vec<String> = vector_core::blossom_servers::list_all_servers().into_iter().map(|s| s.url).collect();
+    BlossomHealthReport {
+        prefer_fastest: vector_core::blossom_health::prefer_fastest(),
+        mirror_count: vector_core::blossom_servers::load_mirror_count(),
+        max_mirrors: vector_core::blossom_servers::MAX_MIRRORS,
+        servers: vector_core::blossom_health::stats(&urls),
+    }
+}
+
+#[tauri::command]
+pub async fn set_blossom_prefer_fastest(enabled: bool) -> Result<(), String> {
+    require_active_blossom_session()?;
+    vector_core::blossom_health::set_prefer_fastest(enabled)
+}
+
+/// Mirror each new attachment to `count` more servers (0 turns mirroring off).
+#[tauri::command]
+pub async fn set_blossom_mirror_count(count: usize) -> Result<(), String> {
+    require_active_blossom_session()?;
+    vector_core::blossom_servers::save_mirror_count(count)
+}
+
 #[tauri::command]
 pub async fn get_blossom_server_capabilities(url: String) -> Result<Vec<vector_core::blossom_capabilities::CapabilityEntry>, String> {
     vector_core::blossom_capabilities::list_for_server(&url)
@@ -651,12 +773,12 @@ pub async fn toggle_default_relay<R: Runtime>(handle: AppHandle<R>, url: String,
 
     if let Some(client) = nostr_client() {
         if enabled {
-            // Wrap with tor_aware_relay_options so a re-enabled default relay
-            // doesn't come up Direct when Tor is on (or pre-bootstrap). The
-            // failsafe helper handles the boot-completes-mid-call race.
-            match add_relay_failsafe(&client, &normalized_url, || {
-                vector_core::tor_aware_relay_options(RelayOptions::new().reconnect(false))
-            }).await {
+            // relay_options_for_mode is Tor-aware, so a re-enabled default
+            // relay doesn't come up Direct when Tor is on (or pre-bootstrap).
+            // The failsafe helper handles the boot-completes-mid-call race.
+            let modes = load_default_relay_modes(&handle).await.unwrap_or_default();
+            let mode = default_relay_mode(&modes, &normalized_url);
+            match add_relay_failsafe(&client, &normalized_url, || relay_options_for_mode(&mode)).await {
                 Ok(_) => {
                     if defer_connect_for_bootstrap() {
                         println!("[Relay] Enabled default relay (deferred connect, Tor bootstrapping): {}", normalized_url);
@@ -801,32 +923,46 @@ pub async fn toggle_custom_relay<R: Runtime>(handle: AppHandle<R>, url: String,
     Ok(true)
 }
 
-/// Update a custom relay's mode (read/write/both)
+/// Update a relay's mode (read/write/both). Works for default relays too;
+/// their modes are kept apart from the custom relay list.
 #[tauri::command]
 pub async fn update_relay_mode<R: Runtime>(handle: AppHandle<R>, url: String, mode: String) -> Result<bool, String> {
     if !["read", "write", "both"].contains(&mode.as_str()) {
         return Err("Invalid mode. Must be 'read', 'write', or 'both'".to_string());
     }
 
-    let mut relays = load_custom_relays(&handle).await?;
+    let is_enabled = if is_default_relay(&url) {
+        let normalized_url = url.trim().trim_end_matches('/').to_string();
+        let mut modes = load_default_relay_modes(&handle).await?;
+        modes.retain(|u, _| !u.eq_ignore_ascii_case(&normalized_url));
+        if mode != "both" {
+            modes.insert(normalized_url.clone(), mode.clone());
+        }
+        save_default_relay_modes(&handle, &modes).await?;
+        let disabled = get_disabled_default_relays(&handle).await?;
+        !disabled.iter().any(|d| d.eq_ignore_ascii_case(&normalized_url))
+    } else {
+        let mut relays = load_custom_relays(&handle).await?;
 
-    let mut found = false;
-    let mut is_enabled = false;
+        let mut found = false;
+        let mut is_enabled = false;
 
-    for relay in relays.iter_mut() {
-        if relay.url.eq_ignore_ascii_case(&url) {
-            relay.mode = mode.clone();
-            is_enabled = relay.enabled;
-            found = true;
-            break;
+        for relay in relays.iter_mut() {
+            if relay.url.eq_ignore_ascii_case(&url) {
+                relay.mode = mode.clone();
+                is_enabled = relay.enabled;
+                found = true;
+                break;
+            }
         }
-    }
 
-    if !found {
-        return Err("Relay not found".to_string());
-    }
+        if !found {
+            return Err("Relay not found".to_string());
+        }
 
-    save_custom_relays(&handle, &relays).await?;
+        save_custom_relays(&handle, &relays).await?;
+        is_enabled
+    };
 
     if is_enabled {
         if let Some(client) = nostr_client() {
@@ -908,6 +1044,22 @@ pub async fn reconcile_dm_relay_list<R: Runtime>(handle: AppHandle<R>) {
     {
         eprintln!("[Relay] Failed to publish inbox relays: {}", e);
     }
+
+    // With the list settled, make sure every relay senders may ask actually holds it
+    if !session.is_valid() {
+        return;
+    }
+    match vector_core::inbox_relays::check_own_list_health(&client).await {
+        Ok(health) => {
+            if health.status != "healthy" {
+                println!("[Relay] DM relay list health: {} ({} rebroadcast)", health.status, health.healed.len());
+            }
+            if session.is_valid() {
+                let _ = handle.emit("inbox_list_health", &health);
+            }
+        }
+        Err(e) => eprintln!("[Relay] DM relay list health check skipped: {}", e),
+    }
 }
 
 /// (enabled, disabled) relay urls from the same per-account stores the
@@ -1314,12 +1466,14 @@ pub async fn connect<R: Runtime>(handle: AppHandle<R>) -> bool {
         return false;
     }
 
-    // Get disabled default relays and custom relays concurrently
-    let (disabled_defaults, custom_relays_result) = tokio::join!(
+    // Get disabled default relays, default relay modes and custom relays concurrently
+    let (disabled_defaults, default_modes, custom_relays_result) = tokio::join!(
         get_disabled_default_relays(&handle),
+        load_default_relay_modes(&handle),
         get_custom_relays(handle.clone())
     );
     let disabled_defaults = disabled_defaults.unwrap_or_default();
+    let default_modes = default_modes.unwrap_or_default();
 
     // Collect all relays to add (URL, options, is_default, mode_info)
     let mut relays_to_add: Vec<(String, RelayOptions, bool, String)> = Vec::new();
@@ -1328,11 +1482,12 @@ pub async fn connect<R: Runtime>(handle: AppHandle<R>) -> bool {
     for default_url in DEFAULT_RELAYS {
         let is_disabled = disabled_defaults.iter().any(|d| d.eq_ignore_ascii_case(default_url));
         if !is_disabled {
+            let mode = default_relay_mode(&default_modes, default_url);
             relays_to_add.push((
                 default_url.to_string(),
-                vector_core::tor_aware_relay_options(RelayOptions::new().reconnect(false)),
+                relay_options_for_mode(&mode),
                 true,
-                "both".to_string(),
+                mode,
             ));
         } else {
             println!("[Relay] Skipping disabled default relay: {}", default_url);
@@ -1432,5 +1587,7 @@ pub async fn connect<R: Runtime>(handle: AppHandle<R>) -> bool {
 // - validate_relay_url_cmd
 // - get_relay_metrics
 // - get_relay_logs
+// - get_relay_stats
+// - set_relay_demotion_override
 // - monitor_relay_connections
 // - connect
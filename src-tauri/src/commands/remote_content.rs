@@ -0,0 +1,33 @@
+//! Remote-content policy commands — per chat, whether its messages may auto-fetch link previews,
+//! inline images, avatars and `nostr:` references.
+
+use std::collections::HashMap;
+
+use vector_core::remote_content::RemoteContentPolicy;
+use vector_core::state::SessionGuard;
+
+/// The chat's policy: "allow" (default), "ask" or "block".
+#[tauri::command]
+pub async fn get_remote_content_policy(chat_id: String) -> Result<RemoteContentPolicy, String> {
+    Ok(vector_core::remote_content::chat_policy(&chat_id))
+}
+
+/// Every chat that doesn't auto-fetch, keyed by chat id: what the frontend needs to offer
+/// tap-to-load in those chats.
+#[tauri::command]
+pub async fn get_remote_content_policies() -> Result<HashMap<String, RemoteContentPolicy>, String> {
+    Ok(vector_core::remote_content::chat_policies())
+}
+
+/// Set the chat's policy.
+#[tauri::command]
+pub async fn set_remote_content_policy(chat_id: String, policy: RemoteContentPolicy) -> Result<(), String> {
+    // Per-account KV write — guard against a mid-call account swap.
+    let session = SessionGuard::capture();
+    if !session.is_valid() {
+        return Err("Account changed".into());
+    }
+    vector_core::remote_content::set_chat_policy(&chat_id, policy)
+}
+
+// Handlers: get_remote_content_policy, get_remote_content_policies, set_remote_content_policy
@@ -0,0 +1,67 @@
+//! Social backup Tauri commands — thin shims over `vector_core::social_backup`.
+
+use vector_core::db::social_backup::{HeldShare, RecoveryRequest};
+use vector_core::social_backup::{self, BackupInfo, RecoveryStatus};
+
+use crate::{db, MNEMONIC_SEED};
+
+/// Split this account's seed phrase between `guardians`, any `threshold` of whom can restore it.
+/// Only accounts with a seed phrase (created in Vector) can be backed up this way.
+#[tauri::command]
+pub async fn create_social_backup(guardians: Vec<String>, threshold: u8) -> Result<BackupInfo, String> {
+    let seed_from_mem = MNEMONIC_SEED.lock().unwrap().clone();
+    let seed = match seed_from_mem {
+        Some(seed) => seed,
+        None => db::get_seed().await?.ok_or("This account has no seed phrase to back up")?,
+    };
+    let seed = zeroize::Zeroizing::new(seed);
+    social_backup::create_backup(&seed, &guardians, threshold).await
+}
+
+/// The last social backup this account made (guardians + threshold), if any.
+#[tauri::command]
+pub async fn get_social_backup() -> Option<BackupInfo> {
+    social_backup::backup_info()
+}
+
+/// Shares this account holds for contacts, and recovery requests awaiting approval.
+#[tauri::command]
+pub async fn get_backup_guardianship() -> Result<(Vec<HeldShare>, Vec<RecoveryRequest>), String> {
+    Ok((
+        vector_core::db::social_backup::list_held_shares()?,
+        vector_core::db::social_backup::list_requests()?,
+    ))
+}
+
+#[tauri::command]
+pub async fn approve_backup_request(requester: String) -> Result<(), String> {
+    social_backup::approve_request(&requester).await
+}
+
+#[tauri::command]
+pub async fn deny_backup_request(requester: String) -> Result<(), String> {
+    social_backup::deny_request(&requester)
+}
+
+/// Pre-login: ask `guardians` for their shares of `npub`'s backup. Returns the verification code.
+#[tauri::command]
+pub async fn start_social_recovery(npub: String, guardians: Vec<String>) -> Result<String, String> {
+    social_backup::start_recovery(&npub, &guardians).await
+}
+
+#[tauri::command]
+pub async fn get_social_recovery_status() -> RecoveryStatus {
+    social_backup::recovery_status().await
+}
+
+/// Rebuild the seed phrase from the collected shares, refusing one that doesn't derive the account
+/// being recovered (with `passphrase`, if its seed phrase has one); the frontend logs in with it.
+#[tauri::command]
+pub async fn finish_social_recovery(passphrase: Option<String>) -> Result<String, String> {
+    social_backup::finish_recovery(passphrase.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn cancel_social_recovery() {
+    social_backup::cancel_recovery().await
+}
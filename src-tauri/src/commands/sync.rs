@@ -10,6 +10,7 @@ use nostr_sdk::prelude::*;
 use tauri::{AppHandle, Emitter, Manager, Runtime};
 
 use vector_core::PreparedEvent;
+use vector_core::negentropy::ArchiveStep;
 
 use crate::{
     db, profile, profile_sync,
@@ -62,6 +63,12 @@ pub async fn sync_all_profiles() -> Result<(), String> {
     Ok(())
 }
 
+/// Sync cross-device settings now: adopt newer values from other devices, publish ours.
+#[tauri::command]
+pub async fn sync_settings_now() -> Result<vector_core::settings_sync::SettingsSyncReport, String> {
+    vector_core::settings_sync::sync_now(&crate::profile_sync::TauriProfileSyncHandler).await
+}
+
 /// Check if a sync/scan operation is currently in progress
 #[tauri::command]
 pub async fn is_scanning() -> bool {
@@ -73,6 +80,34 @@ pub async fn is_scanning() -> bool {
 // Message Sync Commands
 // ============================================================================
 
+/// Reconcile the reconnection window of our gift wraps against one relay in the pool (NIP-77),
+/// returning the IDs it holds that we haven't processed.
+pub(crate) async fn reconcile_relay(client: &Client, my_public_key: PublicKey, url: &str) -> Result<Vec<EventId>, String> {
+    // Look up the Relay object for this URL
+    let relay = client.relays().await.into_iter()
+        .find(|(u, _)| u.to_string() == url)
+        .map(|(_, r)| r)
+        .ok_or("relay not found in pool")?;
+
+    // Load negentropy items — messages sent in the last 2 days, for fast reconnection sync
+    // (the window reaches further back by wrapper time to cover NIP-59 backdating)
+    let all_items = db::load_negentropy_items().unwrap_or_default();
+    let (items, filter) = vector_core::negentropy::RECONNECT_WINDOW.plan(my_public_key, &all_items);
+    let sync_opts = nostr_sdk::SyncOptions::new()
+        .direction(nostr_sdk::SyncDirection::Down)
+        .initial_timeout(std::time::Duration::from_secs(3))
+        .dry_run();
+
+    match tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        relay.sync_with_items(filter, items, &sync_opts),
+    ).await {
+        Ok(Ok(recon)) => Ok(recon.remote.into_iter().collect()),
+        Ok(Err(e)) => Err(format!("negentropy failed: {}", e)),
+        Err(_) => Err("negentropy timed out (10s)".to_string()),
+    }
+}
+
 /// Fetch messages from relays and sync to local state
 ///
 /// Uses NIP-77 negentropy set reconciliation:
@@ -111,56 +146,18 @@ pub async fn fetch_messages<R: Runtime>(
     if let Some(url) = relay_url {
         let recon_start = std::time::Instant::now();
 
-        // Look up the Relay object for this URL
-        let relay_map = client.relays().await;
-        let relay = relay_map.iter()
-            .find(|(u, _)| u.to_string() == url)
-            .map(|(_, r)| r.clone());
-        drop(relay_map);
-
-        let Some(relay) = relay else {
-            eprintln!("[Sync] Single-relay sync: relay {} not found in pool", url);
-            return;
-        };
-
         // Pin to the session whose items/pubkey drive this reconcile — captured BEFORE the
         // reconcile so a swap during it invalidates the whole fetch+commit pipeline.
         let recon_session = vector_core::state::SessionGuard::capture();
 
-        // Load negentropy items — use 2-day window for fast reconnection sync
-        let all_items = db::load_negentropy_items().unwrap_or_default();
-        let quick_since = Timestamp::now().as_secs().saturating_sub(2 * 24 * 3600);
-        let items: Vec<(EventId, Timestamp)> = all_items.iter()
-            .filter(|(_, ts)| ts.as_secs() >= quick_since)
-            .cloned()
-            .collect();
-        let filter = Filter::new()
-            .pubkey(my_public_key)
-            .kind(Kind::GiftWrap)
-            .since(Timestamp::from_secs(quick_since));
-        let sync_opts = nostr_sdk::SyncOptions::new()
-            .direction(nostr_sdk::SyncDirection::Down)
-            .initial_timeout(std::time::Duration::from_secs(3))
-            .dry_run();
-
-        let recon_result = tokio::time::timeout(
-            std::time::Duration::from_secs(10),
-            relay.sync_with_items(filter, items, &sync_opts),
-        ).await;
-
-        let missing_ids: Vec<EventId> = match recon_result {
-            Ok(Ok(recon)) => {
-                let ids: Vec<EventId> = recon.remote.into_iter().collect();
+        let missing_ids = match reconcile_relay(&client, my_public_key, &url).await {
+            Ok(ids) => {
                 println!("[Sync] Single-relay {} reconciled in {:?}: {} missing",
                     url, recon_start.elapsed(), ids.len());
                 ids
             }
-            Ok(Err(e)) => {
-                eprintln!("[Sync] Single-relay {} negentropy failed: {}", url, e);
-                return;
-            }
-            Err(_) => {
-                eprintln!("[Sync] Single-relay {} negentropy timed out (10s)", url);
+            Err(e) => {
+                eprintln!("[Sync] Single-relay {}: {}", url, e);
                 return;
             }
         };
@@ -215,8 +212,10 @@ pub async fn fetch_messages<R: Runtime>(
 
     {
         let boot_start = std::time::Instant::now();
+        vector_core::startup::begin();
         let mut state = STATE.lock().await;
         println!("[Boot] STATE.lock acquired in {:?}", boot_start.elapsed());
+        vector_core::startup::mark("state_lock", boot_start.elapsed());
 
         {
             // Set current account for SQL mode if profile database exists
@@ -227,14 +226,37 @@ pub async fn fetch_messages<R: Runtime>(
             if let Some(data_dir) = app_data {
                 let profile_db = data_dir.join(&npub).join("vector.db");
                 if profile_db.exists() {
+                    let db_open = std::time::Instant::now();
                     let _ = crate::account_manager::set_current_account(npub.clone());
                     let _ = vector_core::db::init_database(&npub);
+                    vector_core::startup::mark("db_open", db_open.elapsed());
                     println!("[Startup] Set current account for SQL mode: {}", npub);
                 }
             }
 
             // Load our DB (if we haven't already)
             if !state.db_loaded {
+                // Fast path: paint the chat list from the last boot's snapshot while the full load
+                // below runs. The full result follows as `init_hydrated` and replaces it.
+                let snapshot_start = std::time::Instant::now();
+                let mut previewed = false;
+                if let Some(snapshot) = vector_core::db::chat_list_snapshot::load().await {
+                    match serde_json::from_str::<serde_json::Value>(&snapshot) {
+                        Ok(serde_json::Value::Object(mut payload)) => {
+                            payload.insert("preview".into(), serde_json::Value::Bool(true));
+                            // The snapshot's copy may predate a settings change; send the current one
+                            payload.insert("accessibility".into(), serde_json::json!(vector_core::accessibility::load()));
+                            previewed = handle.emit("init_finished", &payload).is_ok();
+                        }
+                        _ => eprintln!("[Boot] Ignoring unreadable chat list snapshot"),
+                    }
+                }
+                if previewed {
+                    vector_core::startup::mark("snapshot_emit", snapshot_start.elapsed());
+                    vector_core::startup::mark_first_emit(true);
+                    println!("[Boot] Snapshot chat list emitted in {:?}", boot_start.elapsed());
+                }
+
                 // Load profiles, chats, and last messages in parallel (all are independent reads)
                 let db_start = std::time::Instant::now();
                 let (profiles_result, slim_chats_result, last_messages_result) = tokio::join!(
@@ -242,18 +264,21 @@ pub async fn fetch_messages<R: Runtime>(
                         let t = std::time::Instant::now();
                         let r = db::get_all_profiles().await;
                         println!("[Boot]   get_all_profiles: {:?}", t.elapsed());
+                        vector_core::startup::mark("profile_load", t.elapsed());
                         r
                     },
                     async {
                         let t = std::time::Instant::now();
                         let r = db::get_all_chats().await;
                         println!("[Boot]   get_all_chats: {:?}", t.elapsed());
+                        vector_core::startup::mark("chat_load", t.elapsed());
                         r
                     },
                     async {
                         let t = std::time::Instant::now();
                         let r = db::get_all_chats_last_messages().await;
                         println!("[Boot]   get_all_chats_last_messages: {:?}", t.elapsed());
+                        vector_core::startup::mark("last_messages", t.elapsed());
                         r
                     }
                 );
@@ -262,10 +287,21 @@ pub async fn fetch_messages<R: Runtime>(
                 // Process profiles
                 let merge_start = std::time::Instant::now();
                 if let Ok(profiles) = profiles_result {
+                    // A status saved with an expiry still has to clear itself this session
+                    for p in profiles.iter().filter(|p| p.status.expires_at > 0) {
+                        vector_core::profile::sync::schedule_status_expiry(p.id.clone(), p.status.expires_at);
+                    }
                     state.merge_db_profiles(profiles, &npub);
                 }
                 println!("[Boot] Profile merge in {:?}", merge_start.elapsed());
 
+                // Store ecash a mint signed for but an interrupted operation never saved
+                let ecash_session = vector_core::state::SessionGuard::capture();
+                tokio::spawn(async move {
+                    if !ecash_session.is_valid() { return; }
+                    vector_core::cashu::recover_pending().await;
+                });
+
                 // Spawn background task to cache profile images for offline support
                 let img_session = vector_core::state::SessionGuard::capture();
                 tokio::spawn(async move {
@@ -388,6 +424,8 @@ pub async fn fetch_messages<R: Runtime>(
                 // reconcile any missing files against in-memory STATE + the frontend — boot preloads
                 // messages before this runs, so a missing file on a preloaded message would otherwise
                 // stay a broken image until a full reload.
+                // Files archived to an external folder need the asset protocol allowed there
+                crate::commands::attachments::allow_archive_dirs(&handle);
                 let integrity_session = vector_core::state::SessionGuard::capture();
                 tokio::spawn(async move {
                     if !integrity_session.is_valid() { return; }
@@ -414,26 +452,45 @@ pub async fn fetch_messages<R: Runtime>(
                     .map(|c| c.to_serializable(&state.interner))
                     .collect();
                 let slim_profiles: Vec<db::SlimProfile> = state.profiles.iter()
-                    .map(|p| db::SlimProfile::from_profile(p, &state.interner))
+                    .map(|p| db::SlimProfile::light(p, &state.interner))
                     .collect();
                 println!("[Boot] Serialization in {:?}", serialize_start.elapsed());
+                vector_core::startup::mark("serialize", serialize_start.elapsed());
 
                 #[derive(serde::Serialize)]
                 struct InitPayload<'a> {
                     profiles: &'a [db::SlimProfile],
                     chats: &'a [crate::chat::SerializableChat],
+                    accessibility: vector_core::accessibility::AccessibilitySettings,
                 }
 
                 let emit_start = std::time::Instant::now();
-                // A failed emit must not panic the boot task — the sync below still has to run.
-                if let Err(e) = handle.emit("init_finished", &InitPayload {
+                let payload = InitPayload {
                     profiles: &slim_profiles,
                     chats: &serializable_chats,
-                }) {
-                    eprintln!("[Boot] init_finished emit failed: {e}");
+                    accessibility: vector_core::accessibility::load(),
+                };
+                // After a snapshot paint the UI is already up; hand it the real list to swap in.
+                let event = if previewed { "init_hydrated" } else { "init_finished" };
+                // A failed emit must not panic the boot task — the sync below still has to run.
+                if let Err(e) = handle.emit(event, &payload) {
+                    eprintln!("[Boot] {event} emit failed: {e}");
                 }
+                vector_core::startup::mark_first_emit(false);
+                vector_core::startup::mark("full_emit", emit_start.elapsed());
                 println!("[Boot] Event emit in {:?}", emit_start.elapsed());
                 println!("[Boot] Total init time: {:?}", boot_start.elapsed());
+
+                // Refresh the snapshot for the next cold start
+                if let Ok(json) = serde_json::to_string(&payload) {
+                    let snapshot_session = vector_core::state::SessionGuard::capture();
+                    tokio::spawn(async move {
+                        if !snapshot_session.is_valid() { return; }
+                        if let Err(e) = vector_core::db::chat_list_snapshot::save(json).await {
+                            eprintln!("[Boot] {e}");
+                        }
+                    });
+                }
             }
 
             // Preload marketplace cache from SQLite → MARKETPLACE_STATE (non-blocking)
@@ -497,17 +554,10 @@ pub async fn fetch_messages<R: Runtime>(
     println!("[Sync] Loaded {} negentropy items ({} with valid timestamps)",
         negentropy_items.len(), valid_ts_count);
 
-    // Quick phase: last 7 days — small item set for near-instant reconciliation.
-    // Shows recent offline messages within ~1s. Full archive sync runs in background after.
-    let quick_since = Timestamp::now().as_secs().saturating_sub(7 * 24 * 3600);
-    let quick_items: Vec<(EventId, Timestamp)> = negentropy_items.iter()
-        .filter(|(_, ts)| ts.as_secs() >= quick_since)
-        .cloned()
-        .collect();
-    let filter = Filter::new()
-        .pubkey(my_public_key)
-        .kind(Kind::GiftWrap)
-        .since(Timestamp::from_secs(quick_since));
+    // Quick phase: messages sent in the last 7 days — small item set for near-instant
+    // reconciliation. Shows recent offline messages within ~1s. Full archive sync runs in
+    // background after. Keyed on wrapper time, so the window is widened for NIP-59 backdating.
+    let (quick_items, filter) = vector_core::negentropy::QUICK_WINDOW.plan(my_public_key, &negentropy_items);
     println!("[Sync] Quick phase: {} items (last 7d), full: {}", quick_items.len(), negentropy_items.len());
 
     // Dry-run negentropy reconciliation — exchange fingerprints only
@@ -734,7 +784,7 @@ pub async fn fetch_messages<R: Runtime>(
                     PreparedEvent::ErrorSkip { .. } => {
                         error_skips += 1;
                     }
-                    PreparedEvent::CommunityInvite { .. } | PreparedEvent::CommunityInviteV2 { .. } => {}
+                    PreparedEvent::CommunityInvite { .. } | PreparedEvent::CommunityInviteV2 { .. } | PreparedEvent::BlockedSkip { .. } | PreparedEvent::GroupSkip { .. } => {}
                 }
                 let t = std::time::Instant::now();
                 if crate::services::tauri_commit_prepared_event_with(prepared, false, &batcher).await {
@@ -779,6 +829,8 @@ pub async fn fetch_messages<R: Runtime>(
 
     // Deferred bootstrap: merge own kind 10063, then probe unknown servers.
     // Runs after Quick Sync so it can't contend for boot-window bandwidth.
+    // Health checks start here too and repeat for the session.
+    vector_core::blossom_health::spawn_health_checks();
     {
         let bg_client = client.clone();
         let session = vector_core::state::SessionGuard::capture();
@@ -829,12 +881,9 @@ pub async fn fetch_messages<R: Runtime>(
             let mut archive_new = 0u32;
 
             // Reload items (includes anything saved during quick phase)
-            let items = db::load_negentropy_items().unwrap_or_default();
+            let all_items = db::load_negentropy_items().unwrap_or_default();
+            let (items, filter) = vector_core::negentropy::SyncWindow::Full.plan(my_public_key, &all_items);
             println!("[Sync] Archive: negentropy with {} items", items.len());
-
-            let filter = Filter::new()
-                .pubkey(my_public_key)
-                .kind(Kind::GiftWrap);
             let opts = nostr_sdk::SyncOptions::new()
                 .direction(nostr_sdk::SyncDirection::Down)
                 .initial_timeout(std::time::Duration::from_secs(45))
@@ -875,53 +924,26 @@ pub async fn fetch_messages<R: Runtime>(
                 }
             }
 
-            if !all_missing.is_empty() {
-                let missing_total = all_missing.len() as u32;
-                println!("[Sync] Archive: fetching {} events", missing_total);
-                let ids: Vec<EventId> = all_missing.into_iter().collect();
-                let relay_strs: Vec<String> = bg_client.relays().await.keys()
-                    .map(|u| u.to_string()).collect();
-                let archive_inner = crate::services::event_handler::TauriEventHandler;
-                let archive_batcher = vector_core::event_handler::BatchingPersist::new(&archive_inner);
-                const BATCH: usize = 500;
-                let mut processed = 0u32;
-                for batch in ids.chunks(BATCH) {
-                    let f = Filter::new().ids(batch.to_vec()).kind(Kind::GiftWrap);
-                    match bg_client.stream_events_from(
-                        relay_strs.clone(), f,
-                        std::time::Duration::from_secs(30),
-                    ).await {
-                        Ok(stream) => {
-                            tokio::pin!(stream);
-                            while let Some(event) = stream.next().await {
-                                if !archive_session.is_valid() { return; }
-                                let prepared = vector_core::event_handler::prepare_event(
-                                    event, &bg_client, my_public_key,
-                                ).await;
-                                processed += 1;
-                                if processed % 250 == 0 {
-                                    let _ = handle_bg.emit("sync_progress", serde_json::json!({
-                                        "mode": "Syncing",
-                                        "current": processed,
-                                        "total": missing_total,
-                                        "new_messages": archive_new,
-                                    }));
-                                }
-                                if crate::services::tauri_commit_prepared_event_with(prepared, false, &archive_batcher).await {
-                                    archive_new += 1;
-                                }
-                                if archive_batcher.buffered() >= PERSIST_BATCH {
-                                    archive_batcher.flush(&archive_session).await;
-                                }
-                            }
-                        }
-                        Err(e) => eprintln!("[Sync] Archive: batch fetch error: {}", e),
+            let remembered = vector_core::consent::is_remembered(vector_core::consent::ExpensiveOp::HistorySync);
+            match vector_core::negentropy::archive_step(all_missing, remembered) {
+                ArchiveStep::AwaitConsent(ids) => {
+                    let events = ids.len() as u64;
+                    println!("[Sync] Archive: {} events missing, waiting for consent", events);
+                    *PARKED_HISTORY.lock().unwrap() = Some((archive_session, ids));
+                    let estimate = vector_core::consent::estimate(
+                        vector_core::consent::ExpensiveOp::HistorySync, "",
+                        Some(events), Some(events * vector_core::consent::AVG_WRAP_BYTES),
+                    );
+                    let _ = handle_bg.emit("history_sync_consent", &estimate);
+                }
+                ArchiveStep::Fetch(ids) => {
+                    println!("[Sync] Archive: fetching {} events", ids.len());
+                    match fetch_archive_events(&bg_client, my_public_key, ids, &handle_bg, archive_session).await {
+                        Some(n) => archive_new += n,
+                        None => return,
                     }
-                    if !archive_session.is_valid() { return; }
                 }
-                archive_batcher.flush(&archive_session).await;
-            } else {
-                println!("[Sync] Archive: no missing events");
+                ArchiveStep::Done => println!("[Sync] Archive: no missing events"),
             }
 
             // ════════════════════════════════════════════
@@ -959,9 +981,38 @@ pub async fn fetch_messages<R: Runtime>(
             {
                 let badge_handle = handle_bg.clone();
                 let badge_session = vector_core::state::SessionGuard::capture();
+                // Verified-account badges: the cached (re-verified) registry applies now; a fresh
+                // copy is fetched alongside the badge sweep once the pool is quiet.
+                vector_core::verified::load_cached_registry();
                 tokio::spawn(async move {
                     tokio::time::sleep(std::time::Duration::from_secs(30)).await;
                     if !badge_session.is_valid() { return; }
+                    if let Err(e) = vector_core::verified::refresh_registry().await {
+                        vector_core::log_warn!("[Verified] registry refresh failed: {}", e);
+                    }
+                    // Settings sync: the live self-sync sub already adopted the relay copy; this round
+                    // publishes anything this device changed while offline.
+                    if !badge_session.is_valid() { return; }
+                    if let Err(e) = vector_core::settings_sync::sync_now(&crate::profile_sync::TauriProfileSyncHandler).await {
+                        vector_core::log_warn!("[SettingsSync] boot sync failed: {}", e);
+                    }
+                    // Chat state: read markers, mutes and nicknames changed here while offline
+                    if !badge_session.is_valid() { return; }
+                    match vector_core::chat_state_sync::sync_now(&crate::profile_sync::TauriProfileSyncHandler).await {
+                        Ok(report) => crate::chat::on_chat_state_synced(&report.applied).await,
+                        Err(e) => vector_core::log_warn!("[ChatStateSync] boot sync failed: {}", e),
+                    }
+                    // Contact list: publishes local edits made offline (the live sub adopted the relay copy)
+                    if !badge_session.is_valid() { return; }
+                    if let Err(e) = vector_core::contacts::sync_now().await {
+                        vector_core::log_warn!("[Contacts] boot sync failed: {}", e);
+                    }
+                    // Block list: after settings, so blocks from other Vector installs are in before the merge
+                    if !badge_session.is_valid() { return; }
+                    if let Err(e) = vector_core::mute_list::sync_now(&crate::profile_sync::TauriProfileSyncHandler).await {
+                        vector_core::log_warn!("[MuteList] boot sync failed: {}", e);
+                    }
+                    if !badge_session.is_valid() { return; }
                     vector_core::badges::refresh_own_badges().await;
                     if !badge_session.is_valid() { return; }
                     vector_core::badges::refresh_own_bug_hunter().await;
@@ -974,9 +1025,28 @@ pub async fn fetch_messages<R: Runtime>(
                 });
             }
 
-            // Post-sync: weekly vacuum + daily planner-stats refresh.
+            // Post-sync: deleted-chat retention, daily stale-cache pruning, weekly vacuum + daily
+            // planner-stats refresh.
             tokio::time::sleep(std::time::Duration::from_secs(5)).await;
             if !archive_session.is_valid() { return; }
+            let cutoff = vector_core::clock::now_secs()
+                .saturating_sub(vector_core::db::chats::DELETED_CHAT_RETENTION_SECS);
+            match vector_core::db::chats::purge_deleted_chats(cutoff) {
+                Ok(0) => {}
+                Ok(n) => println!("[Maintenance] Purged {} chat(s) deleted over 30 days ago", n),
+                Err(e) => eprintln!("[Maintenance] Deleted-chat purge failed: {}", e),
+            }
+            match vector_core::db::pruning::prune_if_due() {
+                Ok(Some(stats)) => {
+                    let pruned: u64 = stats.policies.iter().map(|p| p.pruned_last).sum();
+                    if pruned > 0 {
+                        println!("[Maintenance] Pruned {} stale cache row(s)", pruned);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("[Maintenance] Stale-data prune failed: {}", e),
+            }
+            if !archive_session.is_valid() { return; }
             if let Err(e) = db::check_and_vacuum_if_needed().await {
                 eprintln!("[Maintenance] Weekly VACUUM check failed: {}", e);
             }
@@ -988,10 +1058,102 @@ pub async fn fetch_messages<R: Runtime>(
     }
 }
 
+/// A backlog waiting for consent, with the session it was found in.
+static PARKED_HISTORY: std::sync::Mutex<Option<(vector_core::state::SessionGuard, Vec<EventId>)>> =
+    std::sync::Mutex::new(None);
+
+/// Fetch and commit archive gift wraps by id, reporting `sync_progress`. Returns how many were
+/// new messages, or `None` when the session ended mid-way.
+async fn fetch_archive_events<R: Runtime>(
+    client: &Client,
+    my_public_key: PublicKey,
+    ids: Vec<EventId>,
+    handle: &AppHandle<R>,
+    session: vector_core::state::SessionGuard,
+) -> Option<u32> {
+    let missing_total = ids.len() as u32;
+    let relay_strs: Vec<String> = client.relays().await.keys()
+        .map(|u| u.to_string()).collect();
+    let archive_inner = crate::services::event_handler::TauriEventHandler;
+    let archive_batcher = vector_core::event_handler::BatchingPersist::new(&archive_inner);
+    const BATCH: usize = 500;
+    let mut processed = 0u32;
+    let mut archive_new = 0u32;
+    for batch in ids.chunks(BATCH) {
+        let f = Filter::new().ids(batch.to_vec()).kind(Kind::GiftWrap);
+        match client.stream_events_from(
+            relay_strs.clone(), f,
+            std::time::Duration::from_secs(30),
+        ).await {
+            Ok(stream) => {
+                tokio::pin!(stream);
+                while let Some(event) = stream.next().await {
+                    if !session.is_valid() { return None; }
+                    let prepared = vector_core::event_handler::prepare_event(
+                        event, client, my_public_key,
+                    ).await;
+                    processed += 1;
+                    if processed % 250 == 0 {
+                        let _ = handle.emit("sync_progress", serde_json::json!({
+                            "mode": "Syncing",
+                            "current": processed,
+                            "total": missing_total,
+                            "new_messages": archive_new,
+                        }));
+                    }
+                    if crate::services::tauri_commit_prepared_event_with(prepared, false, &archive_batcher).await {
+                        archive_new += 1;
+                    }
+                    if archive_batcher.buffered() >= PERSIST_BATCH {
+                        archive_batcher.flush(&session).await;
+                    }
+                }
+            }
+            Err(e) => eprintln!("[Sync] Archive: batch fetch error: {}", e),
+        }
+        if !session.is_valid() { return None; }
+    }
+    archive_batcher.flush(&session).await;
+    Some(archive_new)
+}
+
+/// Fetch the history backlog that was waiting for consent. `token` comes from the
+/// `history_sync_consent` estimate (none needed once the user said not to ask again).
+#[tauri::command]
+pub async fn resume_history_sync<R: Runtime>(handle: AppHandle<R>, token: Option<String>) -> Result<(), String> {
+    vector_core::consent::redeem(vector_core::consent::ExpensiveOp::HistorySync, "", token.as_deref())?;
+    let (session, ids) = PARKED_HISTORY.lock().unwrap().take()
+        .filter(|(session, _)| session.is_valid())
+        .ok_or("No history waiting to sync")?;
+    let client = nostr_client().ok_or("Nostr client not initialized")?;
+    let my_public_key = crate::my_public_key().ok_or("Public key not initialized")?;
+
+    STATE.lock().await.is_syncing = true;
+    tokio::spawn(async move {
+        println!("[Sync] Archive: fetching {} events (confirmed)", ids.len());
+        let archive_new = fetch_archive_events(&client, my_public_key, ids, &handle, session).await;
+        if !session.is_valid() { return; }
+        println!("[Sync] Archive: {} new messages from confirmed history", archive_new.unwrap_or(0));
+        STATE.lock().await.is_syncing = false;
+        let _ = handle.emit("sync_finished", ());
+    });
+    Ok(())
+}
+
+/// Drop the backlog waiting for consent (the user declined).
+#[tauri::command]
+pub async fn skip_history_sync() -> Result<(), String> {
+    PARKED_HISTORY.lock().unwrap().take();
+    Ok(())
+}
+
 // Handler list for this module (for reference):
 // - queue_profile_sync
 // - queue_chat_profiles_sync
 // - refresh_profile_now
 // - sync_all_profiles
+// - sync_settings_now
 // - is_scanning
 // - fetch_messages
+// - resume_history_sync
+// - skip_history_sync
@@ -4,6 +4,8 @@
 //! - Platform feature detection
 //! - Storage management (info and cleanup)
 //! - Periodic maintenance tasks
+//! - Runtime path diagnostics
+//! - Linux graphics diagnostics and renderer override
 
 use tauri::{AppHandle, Emitter, Manager, Runtime};
 
@@ -34,6 +36,42 @@ pub struct PlatformFeatures {
     pub media_url: Option<String>,
 }
 
+/// A capability gated behind a runtime permission.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionKind {
+    Microphone,
+    Notifications,
+    /// Shared photos and videos.
+    Media,
+    /// The device's location, for sharing it into a chat.
+    Location,
+}
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionState {
+    Granted,
+    /// Only some of it (Android 14's "selected photos", an approximate location).
+    Limited,
+    Denied,
+    /// The check itself failed (no Activity, JNI error).
+    Unknown,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct PermissionStatus {
+    pub kind: PermissionKind,
+    pub state: PermissionState,
+}
+
+const PERMISSION_KINDS: [PermissionKind; 4] = [
+    PermissionKind::Microphone,
+    PermissionKind::Notifications,
+    PermissionKind::Media,
+    PermissionKind::Location,
+];
+
 // ============================================================================
 // Tauri Commands
 // ============================================================================
@@ -191,6 +229,37 @@ pub async fn get_storage_info() -> Result<serde_json::Value, String> {
     }))
 }
 
+/// Database size, row counts for the largest tables and for the caches the stale-data sweep
+/// keeps in check, and what that sweep last removed.
+#[tauri::command]
+pub async fn get_database_stats() -> Result<serde_json::Value, String> {
+    let (size_bytes, mut tables) = {
+        let conn = crate::account_manager::get_db_connection_guard_static()?;
+        let pragma = |name: &str| {
+            conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get::<_, i64>(0)).unwrap_or(0)
+        };
+        let size_bytes = (pragma("page_count") * pragma("page_size")).max(0) as u64;
+        let mut tables = serde_json::Map::new();
+        for table in ["events", "chats", "profiles", "attachments", "processed_wrappers"] {
+            let rows: i64 = conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                .unwrap_or(0);
+            tables.insert(table.to_string(), rows.into());
+        }
+        (size_bytes, tables)
+    };
+    for (name, rows) in vector_core::db::pruning::table_rows()? {
+        tables.insert(name.to_string(), rows.into());
+    }
+
+    Ok(serde_json::json!({
+        "size_bytes": size_bytes,
+        "size_formatted": format_bytes(size_bytes),
+        "tables": tables,
+        "pruning": vector_core::db::pruning::load_stats(),
+    }))
+}
+
 /// Delete downloaded attachment files and reset their metadata, optionally
 /// restricted to a set of lowercase file extensions (None = every attachment).
 /// Returns the number of chats that had messages updated.
@@ -463,10 +532,133 @@ pub async fn clear_storage_category<R: Runtime>(
     }))
 }
 
+/// One media cache trim at a time; downloads finishing together would otherwise race to evict.
+static CACHE_TRIMMING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Trim downloaded media back under the cache limit (`vector_core::media_cache`) and reset the
+/// evicted attachments in STATE and the UI. Run after each download and on a limit change.
+pub(crate) async fn trim_media_cache() -> Result<vector_core::media_cache::Eviction, String> {
+    use std::sync::atomic::Ordering;
+    if CACHE_TRIMMING.swap(true, Ordering::SeqCst) {
+        return Ok(Default::default());
+    }
+    let session = vector_core::state::SessionGuard::capture();
+    let result = tokio::task::spawn_blocking(vector_core::media_cache::enforce)
+        .await
+        .map_err(|e| format!("join error: {:?}", e));
+    CACHE_TRIMMING.store(false, Ordering::SeqCst);
+    let eviction = result??;
+    if session.is_valid() {
+        crate::commands::attachments::reconcile_missing_attachments_in_state(&eviction.affected).await;
+    }
+    Ok(eviction)
+}
+
+/// How much downloaded media is cached, against the limit
+#[tauri::command]
+pub async fn get_cache_stats() -> Result<vector_core::media_cache::CacheStats, String> {
+    tokio::task::spawn_blocking(vector_core::media_cache::stats)
+        .await
+        .map_err(|e| format!("join error: {:?}", e))?
+}
+
+/// Set the media cache limit in bytes (0 = unlimited) and trim to it right away
+#[tauri::command]
+pub async fn set_cache_limit(limit_bytes: u64) -> Result<vector_core::media_cache::CacheStats, String> {
+    vector_core::media_cache::save_limit(limit_bytes)?;
+    trim_media_cache().await?;
+    get_cache_stats().await
+}
+
 // ============================================================================
 // Battery Optimization & Background Service Commands
 // ============================================================================
 
+#[cfg(target_os = "android")]
+fn grant_state(granted: Result<bool, String>) -> PermissionState {
+    match granted {
+        Ok(true) => PermissionState::Granted,
+        Ok(false) => PermissionState::Denied,
+        Err(_) => PermissionState::Unknown,
+    }
+}
+
+#[cfg(target_os = "android")]
+fn level_state(grant: Result<crate::android::permissions::Grant, String>) -> PermissionState {
+    use crate::android::permissions::Grant;
+    match grant {
+        Ok(Grant::Granted) => PermissionState::Granted,
+        Ok(Grant::Limited) => PermissionState::Limited,
+        Ok(Grant::Denied) => PermissionState::Denied,
+        Err(_) => PermissionState::Unknown,
+    }
+}
+
+fn permission_state(kind: PermissionKind) -> PermissionState {
+    #[cfg(target_os = "android")]
+    {
+        use crate::android::permissions;
+        match kind {
+            PermissionKind::Microphone => grant_state(permissions::check_audio_permission()),
+            PermissionKind::Notifications => grant_state(permissions::check_notification_permission()),
+            PermissionKind::Media => level_state(permissions::check_media_permission()),
+            PermissionKind::Location => level_state(permissions::check_location_permission()),
+        }
+    }
+    // Elsewhere the OS asks inline on first use (or doesn't gate at all)
+    #[cfg(not(target_os = "android"))]
+    {
+        let _ = kind;
+        PermissionState::Granted
+    }
+}
+
+/// Where each runtime permission stands, so the UI can explain a feature that won't work
+/// instead of failing silently. Always granted off Android.
+#[tauri::command]
+pub async fn check_permissions() -> Vec<PermissionStatus> {
+    PERMISSION_KINDS.iter()
+        .map(|&kind| PermissionStatus { kind, state: permission_state(kind) })
+        .collect()
+}
+
+/// Show the system prompt for `kind` and return where it stands after. Once the user has denied
+/// for good Android answers at once without a prompt; follow up with `open_permission_settings`.
+#[tauri::command]
+pub async fn request_permission(kind: PermissionKind) -> Result<PermissionState, String> {
+    #[cfg(target_os = "android")]
+    {
+        use crate::android::permissions;
+        // The request blocks on the user's answer
+        tokio::task::spawn_blocking(move || match kind {
+            PermissionKind::Microphone => permissions::request_audio_permission_blocking().map(|g| grant_state(Ok(g))),
+            PermissionKind::Notifications => permissions::request_notification_permission_blocking().map(|g| grant_state(Ok(g))),
+            PermissionKind::Media => permissions::request_media_permission_blocking().map(|g| level_state(Ok(g))),
+            PermissionKind::Location => permissions::request_location_permission_blocking().map(|g| level_state(Ok(g))),
+        })
+        .await
+        .map_err(|e| format!("Permission request failed: {}", e))?
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        Ok(permission_state(kind))
+    }
+}
+
+/// Open Vector's page in the system settings, where a denied permission can be turned back on
+/// (Android only).
+#[tauri::command]
+pub async fn open_permission_settings() -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        crate::android::permissions::open_app_settings()
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        Err("Not supported on this platform".to_string())
+    }
+}
+
 /// Check whether the app is exempt from battery optimizations (Android only).
 /// Returns `true` on non-Android platforms (no optimization needed).
 #[tauri::command]
@@ -517,6 +709,34 @@ pub async fn set_background_service_enabled(enabled: bool) {
     }
 }
 
+/// Whether screen security (no screenshots, screen recording or app-switcher previews) is on.
+/// Android only; `false` elsewhere.
+#[tauri::command]
+pub async fn get_screen_security() -> bool {
+    #[cfg(target_os = "android")]
+    {
+        crate::android::screen_security::is_enabled().unwrap_or(false)
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        false
+    }
+}
+
+/// Turn screen security on or off (Android only), applying it to the window right away.
+#[tauri::command]
+pub async fn set_screen_security(enabled: bool) -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        crate::android::screen_security::set_enabled(enabled)
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        let _ = enabled;
+        Err("Screen security is only available on Android".to_string())
+    }
+}
+
 /// Check whether the user has been prompted for background service setup.
 /// Returns `true` on non-Android platforms (no prompt needed).
 #[tauri::command]
@@ -540,6 +760,132 @@ pub async fn set_background_service_prompted() {
     }
 }
 
+/// Minutes between periodic background syncs (0 = off). Returns 0 on non-Android platforms.
+#[tauri::command]
+pub async fn get_background_sync_interval() -> u32 {
+    #[cfg(target_os = "android")]
+    {
+        crate::android::periodic_sync::interval_minutes().unwrap_or(0)
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        0
+    }
+}
+
+/// Set how often the periodic background sync runs, in minutes (0 = off; the OS floor is 15).
+#[tauri::command]
+pub async fn set_background_sync_interval(minutes: u32) -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        crate::android::periodic_sync::set_interval_minutes(minutes)
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        let _ = minutes;
+        Err("Periodic background sync is only available on Android".to_string())
+    }
+}
+
+/// Whether closing the window hides Vector to the tray. Always false on mobile.
+#[tauri::command]
+pub fn get_minimize_to_tray() -> bool {
+    #[cfg(desktop)]
+    {
+        crate::tray::minimize_to_tray()
+    }
+    #[cfg(not(desktop))]
+    {
+        false
+    }
+}
+
+/// Set whether closing the window hides Vector to the tray instead of quitting (desktop only).
+#[tauri::command]
+pub fn set_minimize_to_tray(enabled: bool) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        crate::tray::set_minimize_to_tray(enabled)
+    }
+    #[cfg(not(desktop))]
+    {
+        let _ = enabled;
+        Err("Minimize to tray is only available on desktop".to_string())
+    }
+}
+
+/// UnifiedPush wake-up state: the distributor in use, whether it issued an endpoint, and the
+/// push gateway. Android only.
+#[tauri::command]
+pub async fn get_push_status() -> Result<serde_json::Value, String> {
+    #[cfg(target_os = "android")]
+    {
+        serde_json::to_value(crate::android::push::status()?).map_err(|e| e.to_string())
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        Err("UnifiedPush is only available on Android".to_string())
+    }
+}
+
+/// Turn UnifiedPush wake-ups on (through `gateway`) or off. Returns false when turning on finds
+/// no distributor app installed.
+#[tauri::command]
+pub async fn set_push_enabled(enabled: bool, gateway: Option<String>) -> Result<bool, String> {
+    #[cfg(target_os = "android")]
+    {
+        if enabled {
+            crate::android::push::enable(gateway.as_deref().unwrap_or_default()).await
+        } else {
+            crate::android::push::disable().await.map(|_| false)
+        }
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        let _ = (enabled, gateway);
+        Err("UnifiedPush is only available on Android".to_string())
+    }
+}
+
+/// Re-send the UnifiedPush endpoint to the gateway (after login: it may have been issued while
+/// Vector was closed). No-op when push is off or on other platforms.
+#[tauri::command]
+pub async fn refresh_push_registration() -> Result<(), String> {
+    #[cfg(target_os = "android")]
+    {
+        crate::android::push::refresh_registration().await
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        Ok(())
+    }
+}
+
+/// Opt in or out of official announcements; (re)subscribes or drops the live subscription at once.
+#[tauri::command]
+pub async fn set_announcements_enabled(enabled: bool) -> Result<(), String> {
+    vector_core::announcements::set_enabled(enabled)?;
+    crate::services::subscribe_announcements().await;
+    Ok(())
+}
+
+/// Message font scale, reduced motion and high contrast.
+#[tauri::command]
+pub fn get_accessibility_settings() -> vector_core::accessibility::AccessibilitySettings {
+    vector_core::accessibility::load()
+}
+
+#[tauri::command]
+pub fn set_accessibility_settings(settings: vector_core::accessibility::AccessibilitySettings) -> Result<(), String> {
+    vector_core::accessibility::save(&settings)
+}
+
+/// Remember (or forget) "don't ask again" for an expensive operation's confirmation.
+#[tauri::command]
+pub fn set_consent_remembered(op: vector_core::consent::ExpensiveOp, remembered: bool) -> Result<(), String> {
+    vector_core::consent::set_remembered(op, remembered)
+}
+
 // ============================================================================
 // Android JNI helpers for VectorBatteryHelper
 // Uses ndk_context (Tauri's Activity context) — always available when Tauri
@@ -719,28 +1065,80 @@ fn get_total_memory() -> u64 {
     0
 }
 
-/// Read the log file (errors + panics, written by log_error! and panic hook).
-/// Returns the log contents, or an empty string if no log file exists.
+/// Read the log file (errors + panics, written by log_error! and panic hook), followed by
+/// this session's startup phase timings. Empty if there's neither.
 #[tauri::command]
 pub async fn get_logs(handle: AppHandle) -> String {
-    let log_path = match handle.path().app_data_dir() {
-        Ok(dir) => dir.join("vector.log"),
-        Err(_) => return String::new(),
+    let mut logs = match handle.path().app_data_dir() {
+        Ok(dir) => std::fs::read_to_string(dir.join("vector.log")).unwrap_or_default(),
+        Err(_) => String::new(),
     };
-    std::fs::read_to_string(log_path).unwrap_or_default()
+    if let Some(startup) = vector_core::startup::summary() {
+        if !logs.is_empty() && !logs.ends_with('\n') {
+            logs.push('\n');
+        }
+        logs.push_str(&startup);
+        logs.push('\n');
+    }
+    logs
+}
+
+/// Every directory Vector uses, freshly probed for read/write access — lets a sandboxed user
+/// see which paths to whitelist.
+#[tauri::command]
+pub async fn get_runtime_paths<R: Runtime>(handle: AppHandle<R>) -> Vec<crate::services::runtime_paths::RuntimePath> {
+    tokio::task::spawn_blocking(move || crate::services::runtime_paths::check(&handle))
+        .await
+        .unwrap_or_else(|_| crate::services::runtime_paths::last_report())
+}
+
+/// The Linux graphics environment detected at startup and the renderer it chose. `None` on
+/// other platforms.
+#[tauri::command]
+pub fn get_graphics_env() -> Option<crate::services::graphics_env::GraphicsEnv> {
+    crate::services::graphics_env::report()
+}
+
+/// Override the WebKitGTK renderer (`auto` restores per-environment selection). Takes effect on
+/// the next launch.
+#[tauri::command]
+pub fn set_renderer_mode(mode: crate::services::graphics_env::RendererMode) -> Result<(), String> {
+    if !cfg!(target_os = "linux") {
+        return Err("Renderer modes only apply on Linux".to_string());
+    }
+    crate::services::graphics_env::set_override(mode)
 }
 
 // Handler list for this module (for reference):
 // - get_platform_features
 // - run_maintenance
 // - get_storage_info
+// - get_database_stats
 // - clear_storage
 // - clear_storage_category
+// - get_cache_stats
+// - set_cache_limit
 // - get_device_memory
 // - get_crash_log
+// - check_permissions
+// - request_permission
+// - open_permission_settings
 // - check_battery_optimized
 // - request_battery_optimization
 // - get_background_service_enabled
 // - set_background_service_enabled
 // - get_background_service_prompted
 // - set_background_service_prompted
+// - get_background_sync_interval
+// - set_background_sync_interval
+// - get_minimize_to_tray
+// - set_minimize_to_tray
+// - get_push_status
+// - set_push_enabled
+// - refresh_push_registration
+// - set_announcements_enabled
+// - get_accessibility_settings
+// - set_accessibility_settings
+// - set_consent_remembered
+// - get_graphics_env
+// - set_renderer_mode
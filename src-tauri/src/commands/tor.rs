@@ -127,7 +127,8 @@ pub async fn stop_and_join_if_running() {
 /// account's data dir if its preference is on. Called from `switch_account`
 /// after the new account's DB pool is up so `tor_data_dirs()` resolves
 /// correctly. Failures fall through to a "Tor off" state — better than
-/// crashing the switch.
+/// crashing the switch. The shared HTTP client is rebuilt for the account
+/// even in builds without Tor.
 pub async fn sync_to_active_account() -> Result<(), String> {
     #[cfg(feature = "tor")]
     {
@@ -167,8 +168,9 @@ pub async fn sync_to_active_account() -> Result<(), String> {
                 }
             }
         }
-        vector_core::net::rebuild_shared_http_client()?;
     }
+    // Rebuilt with or without Tor: the shared client also carries the account's SOCKS5 proxy
+    vector_core::net::rebuild_shared_http_client()?;
     Ok(())
 }
 
@@ -322,7 +324,7 @@ pub async fn tor_set_bridges(enabled: bool, lines: String) -> Result<BridgesStat
             let bridges = read_saved_bridges();
             svc.reconfigure_bridges(state_dir, cache_dir, &bridges).await?;
             vector_core::net::rebuild_shared_http_client()?;
-            switch_relay_transport(true).await?;
+            super::proxy::switch_relay_transport().await?;
         }
     }
 
@@ -357,7 +359,7 @@ pub async fn tor_get_circuits(force_new: Option<bool>) -> Result<Vec<CircuitHopO
             // Force every existing relay socket to reconnect. New sockets
             // pick up the rotated isolation token via the SOCKS handler and
             // land on the freshly-built circuit.
-            switch_relay_transport(true).await?;
+            super::proxy::switch_relay_transport().await?;
         }
         Ok(hops
             .into_iter()
@@ -439,7 +441,7 @@ pub async fn tor_set_enabled(enabled: bool) -> Result<TorState, String> {
             // result is deferred so a (near-impossible) builder failure can't
             // early-return with relays still flowing direct.
             let rebuild = vector_core::net::rebuild_shared_http_client();
-            switch_relay_transport(true).await?;
+            super::proxy::switch_relay_transport().await?;
             rebuild?;
             // Iroh (mini-app realtime) is deliberately left running: it's
             // QUIC/UDP (can't ride Tor) but relay-only, and the user consents
@@ -473,7 +475,8 @@ pub async fn tor_set_enabled(enabled: bool) -> Result<TorState, String> {
         } else {
             // OFF: stop the service first so transport_state stays in
             // RequiredButInactive (blackhole) during the transition, THEN
-            // flip the preference to release into Disabled (direct).
+            // flip the preference to release into Disabled (direct, or the
+            // user's own proxy if one is set).
             if let Some(svc) = vector_core::tor::current() {
                 svc.stop();
             }
@@ -483,7 +486,7 @@ pub async fn tor_set_enabled(enabled: bool) -> Result<TorState, String> {
             write_setting_enabled(false)?;
         }
         vector_core::net::rebuild_shared_http_client()?;
-        switch_relay_transport(enabled).await?;
+        super::proxy::switch_relay_transport().await?;
     }
 
     #[cfg(not(feature = "tor"))]
@@ -496,65 +499,3 @@ pub async fn tor_set_enabled(enabled: bool) -> Result<TorState, String> {
 
     Ok(tor_get_state())
 }
-
-/// Tear down every Nostr relay in the active client's pool and re-add it with
-/// its `connection_mode` set to match the desired Tor state. Preserves all
-/// other RelayOptions (read/write/both, reconnect flags, retry config) so the
-/// pool's behavior is otherwise unchanged.
-///
-/// Why we don't just rebuild the Client: NOSTR_CLIENT is a OnceLock that's
-/// read from hundreds of places — replacing it would force a wide refactor.
-/// Per-relay re-add achieves the same end (every socket is a fresh circuit
-/// through the new transport) without touching the Client's identity.
-#[cfg(feature = "tor")]
-async fn switch_relay_transport(tor_enabled: bool) -> Result<(), String> {
-    // ConnectionMode is re-exported from nostr-relay-pool via nostr-sdk::pool.
-    use nostr_sdk::pool::ConnectionMode;
-    use nostr_sdk::RelayUrl;
-
-    let client = match crate::nostr_client() {
-        Some(c) => c,
-        None => return Ok(()), // Client hasn't been built yet (e.g. pre-login). No-op.
-    };
-
-    let new_mode = if tor_enabled {
-        match vector_core::tor::socks_addr() {
-            Some(addr) => ConnectionMode::proxy(addr),
-            // Failsafe: if the user wanted Tor but the service is somehow
-            // not up at this exact moment, route to the blackhole rather
-            // than direct. A relay that fails to connect is recoverable;
-            // a relay that leaks the user's IP is not.
-            None => ConnectionMode::proxy(vector_core::tor::blackhole_proxy_addr()),
-        }
-    } else {
-        ConnectionMode::Direct
-    };
-
-    // Snapshot the current pool — URL + its existing RelayOptions clone — then
-    // mutate. We don't iterate-and-mutate concurrently because remove/add take
-    // the pool's internal lock.
-    let relays = client.relays().await;
-    let snapshots: Vec<(RelayUrl, nostr_sdk::RelayOptions)> = relays
-        .iter()
-        .map(|(url, relay)| (url.clone(), relay.opts().clone()))
-        .collect();
-
-    log_info!("[Tor] cycling {} relay connection(s) onto new transport...", snapshots.len());
-
-    for (url, opts) in snapshots {
-        // Take down the existing socket + drop the relay registration.
-        if let Err(e) = client.remove_relay(url.clone()).await {
-            log_warn!("[Tor] remove_relay({}) failed: {} — continuing", url, e);
-        }
-        // Re-add with the same options modulo connection_mode.
-        let new_opts = opts.connection_mode(new_mode.clone());
-        if let Err(e) = client.pool().add_relay(url.clone(), new_opts).await {
-            log_warn!("[Tor] re-add_relay({}) failed: {}", url, e);
-        }
-    }
-
-    // Reconnect any relays the pool didn't auto-connect.
-    client.connect().await;
-    log_info!("[Tor] relay transport switch complete");
-    Ok(())
-}
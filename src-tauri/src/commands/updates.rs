@@ -27,7 +27,7 @@ const MANIFEST_MAX_BYTES: usize = 1024 * 1024;
 /// segments read as 0, so garbage never announces an update; a legitimately
 /// larger version does. Pre-release/build suffixes (after `-`/`+`) are
 /// ignored, comparing on the numeric core.
-fn version_is_newer(latest: &str, current: &str) -> bool {
+pub(crate) fn version_is_newer(latest: &str, current: &str) -> bool {
     fn parts(v: &str) -> [u64; 3] {
         let mut out = [0u64; 3];
         for (i, seg) in v
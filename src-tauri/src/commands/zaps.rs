@@ -0,0 +1,31 @@
+//! Zap Tauri commands — thin shims over `vector_core::zaps` (NIP-57).
+
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_opener::OpenerExt;
+
+/// A zap invoice, and whether a Lightning wallet took it.
+#[derive(Serialize)]
+pub struct ZapPayment {
+    #[serde(flatten)]
+    pub invoice: vector_core::zaps::ZapInvoice,
+    /// False when no app handles `lightning:` links; the UI then offers the invoice to copy.
+    pub opened_wallet: bool,
+}
+
+/// Zap `target` (an npub, or a message id to zap its author) and hand the invoice to the user's
+/// Lightning wallet.
+#[tauri::command]
+pub async fn send_zap<R: Runtime>(
+    app: AppHandle<R>,
+    target: String,
+    amount_sats: u64,
+    comment: String,
+) -> Result<ZapPayment, String> {
+    let client = crate::nostr_client().ok_or("No active session")?;
+    let invoice = vector_core::zaps::request_invoice(&client, &target, amount_sats, &comment).await?;
+    let opened_wallet = app.opener()
+        .open_url(format!("lightning:{}", invoice.invoice), None::<&str>)
+        .is_ok();
+    Ok(ZapPayment { invoice, opened_wallet })
+}
@@ -132,9 +132,15 @@ pub async fn check_downloaded_attachments_integrity(
     let mut total_checked = 0;
     let mut missing_count = 0;
     let mut affected_ids: Vec<String> = Vec::new();
-    for (event_id, hash, path) in downloaded {
+    for (event_id, hash, path, archived) in downloaded {
         total_checked += 1;
-        if !std::path::Path::new(&path).exists() {
+        let path_ref = std::path::Path::new(&path);
+        // An archived file whose folder is gone sits on an unmounted drive or offline NAS — it
+        // comes back with the volume, so it isn't missing.
+        if archived && !path_ref.parent().is_some_and(|dir| dir.exists()) {
+            continue;
+        }
+        if !path_ref.exists() {
             vector_core::db::attachments::clear_attachment_download(&event_id, &hash).ok();
             missing_count += 1;
             if !affected_ids.contains(&event_id) {
@@ -9,3 +9,11 @@ pub async fn get_all_chats() -> Result<Vec<SlimChatDB>, String> {
 pub async fn save_slim_chat(slim_chat: SlimChatDB) -> Result<(), String> {
     vector_core::db::chats::save_slim_chat(&slim_chat)
 }
+
+pub async fn soft_delete_chat(chat_identifier: &str, at: u64) -> Result<bool, String> {
+    vector_core::db::chats::soft_delete_chat(chat_identifier, at)
+}
+
+pub async fn restore_chat(chat_identifier: &str) -> Result<Option<SlimChatDB>, String> {
+    vector_core::db::chats::restore_chat(chat_identifier)
+}
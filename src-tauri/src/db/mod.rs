@@ -54,6 +54,22 @@ pub async fn get_messages_around(chat_id: &str, anchor_id: &str, before: usize,
     let chat_int_id = vector_core::db::id_cache::get_chat_id_by_identifier(chat_id)?;
     vector_core::db::events::get_messages_around(chat_int_id, anchor_id, before, after).await
 }
+/// Keyset page of messages older than an event id (string-identifier wrapper).
+pub async fn get_messages_before(chat_id: &str, before_id: &str, limit: usize) -> Result<Vec<vector_core::Message>, String> {
+    let chat_int_id = vector_core::db::id_cache::get_chat_id_by_identifier(chat_id)?;
+    vector_core::db::events::get_messages_before(chat_int_id, before_id, limit).await
+}
+/// In-chat search page (string-identifier wrapper).
+pub async fn search_in_chat(
+    chat_id: &str,
+    query: &str,
+    cursor: Option<&str>,
+    direction: vector_core::db::events::SearchDirection,
+    limit: usize,
+) -> Result<vector_core::db::events::ChatSearchPage, String> {
+    let chat_int_id = vector_core::db::id_cache::get_chat_id_by_identifier(chat_id)?;
+    vector_core::db::events::search_in_chat(chat_int_id, query, cursor, direction, limit).await
+}
 // Wrapper tracking — sync functions re-exported directly
 pub use vector_core::db::wrappers::{
     save_processed_wrapper, load_processed_wrappers, load_negentropy_items,
@@ -72,12 +88,15 @@ pub use events::{
     save_event, save_pivx_payment_event,
     event_exists, delete_event,
     get_message_views, get_all_chats_last_messages,
-    unread_counts,
+    unread_summaries,
 };
 // Async wrappers for sync vector-core read functions
 pub async fn get_pivx_payments_for_chat(id: &str) -> Result<Vec<vector_core::StoredEvent>, String> {
     vector_core::db::events::get_pivx_payments_for_chat(id)
 }
+pub async fn get_ecash_tokens_for_chat(id: &str) -> Result<Vec<vector_core::StoredEvent>, String> {
+    vector_core::db::events::get_ecash_tokens_for_chat(id)
+}
 pub async fn get_system_events_for_chat(id: &str) -> Result<Vec<vector_core::StoredEvent>, String> {
     vector_core::db::events::get_system_events_for_chat(id)
 }
@@ -2,7 +2,7 @@
 //!
 //! This module handles:
 //! - Theme preferences
-//! - Private key (pkey) storage
+//! - Private key (pkey) storage, in the database or the system keystore
 //! - Seed phrase storage (encrypted)
 //! - Generic SQL settings key-value store
 
@@ -13,7 +13,7 @@ use crate::crypto::{maybe_encrypt, maybe_decrypt};
 #[command]
 pub fn get_theme() -> Result<Option<String>, String> {
     if let Ok(_npub) = crate::account_manager::get_current_account() {
-        return get_sql_setting("theme".to_string());
+        return vector_core::appearance::theme();
     }
     Ok(None)
 }
@@ -53,47 +53,41 @@ pub async fn set_pkey<R: tauri::Runtime>(handle: tauri::AppHandle<R>, pkey: Stri
         return Ok(());
     }
 
-    let conn = crate::account_manager::get_write_connection_guard_static()?;
-    conn.execute(
-        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
-        rusqlite::params!["pkey", pkey],
-    ).map_err(|e| format!("Failed to insert pkey: {}", e))?;
-
-    Ok(())
+    // Existing account: write through to wherever the key is kept
+    vector_core::db::set_pkey(&pkey)
 }
 
 #[command]
 pub fn get_pkey() -> Result<Option<String>, String> {
-    let conn = crate::account_manager::get_db_connection_guard_static()?;
-    let result: Option<String> = conn.query_row(
-        "SELECT value FROM settings WHERE key = ?1",
-        rusqlite::params!["pkey"],
-        |row| row.get(0)
-    ).ok();
-    Ok(result)
+    vector_core::db::get_pkey()
+}
+
+/// Where the private key is kept: `database` or `keystore`.
+#[command]
+pub fn get_key_storage() -> Result<vector_core::db::KeyStorage, String> {
+    vector_core::db::key_storage()
+}
+
+/// Move the private key and seed phrase between the database and the system keystore.
+///
+/// The values move as-is (still PIN-encrypted when local encryption is on), so
+/// no credential is needed. Refused mid-migration: the encryption flows rewrite
+/// the key in place.
+#[command]
+pub fn set_key_storage(storage: vector_core::db::KeyStorage) -> Result<(), String> {
+    crate::account_manager::refuse_if_migration_in_progress("move your key")?;
+    vector_core::db::set_key_storage(storage)
 }
 
 #[command]
 pub async fn set_seed(seed: String) -> Result<(), String> {
     let stored_seed = maybe_encrypt(seed).await;
-    let conn = crate::account_manager::get_write_connection_guard_static()?;
-    conn.execute(
-        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
-        rusqlite::params!["seed", stored_seed],
-    ).map_err(|e| format!("Failed to insert seed: {}", e))?;
-    Ok(())
+    vector_core::db::set_seed(&stored_seed)
 }
 
 #[command]
 pub async fn get_seed() -> Result<Option<String>, String> {
-    let conn = crate::account_manager::get_db_connection_guard_static()?;
-    let stored_seed: Option<String> = conn.query_row(
-        "SELECT value FROM settings WHERE key = ?1",
-        rusqlite::params!["seed"],
-        |row| row.get(0)
-    ).ok();
-
-    if let Some(seed_value) = stored_seed {
+    if let Some(seed_value) = vector_core::db::get_seed()? {
         match maybe_decrypt(seed_value).await {
             Ok(decrypted) => return Ok(Some(decrypted)),
             Err(_) => return Err("Failed to decrypt seed phrase".to_string()),
@@ -111,6 +105,12 @@ pub fn set_sql_setting(key: String, value: String) -> Result<(), String> {
             "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
             rusqlite::params![&key, &value],
         ).map_err(|e| format!("Failed to set setting: {}", e))?;
+        // Cross-device keys: stamp + debounce a publish (on the async runtime — this command is sync).
+        if vector_core::settings_sync::is_synced_key(&key) {
+            tauri::async_runtime::spawn(async move {
+                vector_core::settings_sync::note_local_change(&key);
+            });
+        }
         return Ok(());
     }
     Ok(())
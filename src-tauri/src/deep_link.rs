@@ -3,10 +3,14 @@
 //! This module handles parsing and processing of deep link URLs for Vector.
 //! Supported URL formats:
 //! - `vector://profile/<npub>` - Opens a user's profile
+//! - `vector://chat/<npub | channel id>` - Opens (or starts) a chat
+//! - `nostr:<npub | nprofile>` - NIP-21 URI: opens (or starts) a DM with that user
 //! - `vector://emojis/pack/<naddr>` - Opens the Pack Details modal
+//! - `vector://verify/<npub>/<digits>` - Checks a scanned contact verification code
 //! - `https://vectorapp.io/profile/<npub>` - Web URL for mobile app links
 //! - `https://vectorapp.io/emojis/pack/<naddr>` - Web URL for pack share links
 
+use nostr_sdk::prelude::*;
 use serde::Serialize;
 use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Runtime};
@@ -17,7 +21,7 @@ static PENDING_DEEP_LINK: Mutex<Option<DeepLinkAction>> = Mutex::new(None);
 /// Represents a parsed deep link action to be sent to the frontend
 #[derive(Debug, Clone, Serialize)]
 pub struct DeepLinkAction {
-    /// The type of action: "profile"
+    /// The type of action: "profile", "chat", "emoji_pack", "verify", "community_invite"
     pub action_type: String,
     /// The target identifier (npub)
     pub target: String,
@@ -48,6 +52,11 @@ pub fn parse_deep_link(url_str: &str) -> Option<DeepLinkAction> {
         }
     }
 
+    // Handle nostr: URIs (NIP-21)
+    if let Some(entity) = url_str.strip_prefix("nostr:") {
+        return parse_nostr_uri(entity);
+    }
+
     // Handle vector:// scheme
     if url_str.starts_with("vector://") {
         return parse_vector_scheme(url_str);
@@ -68,6 +77,31 @@ fn parse_vector_scheme(url_str: &str) -> Option<DeepLinkAction> {
     parse_path_segments(path)
 }
 
+/// Parse a NIP-21 `nostr:` URI. A user (`npub`, `nprofile`) opens a DM with them.
+fn parse_nostr_uri(entity: &str) -> Option<DeepLinkAction> {
+    // Some launchers hand over `nostr://npub…`, or a trailing slash / query
+    let entity = entity.trim_start_matches("//");
+    let entity = entity.split(['?', '#']).next().unwrap_or(entity).trim_end_matches('/');
+    let public_key = if entity.starts_with("npub1") {
+        PublicKey::from_bech32(entity).ok()
+    } else if entity.starts_with("nprofile1") {
+        Nip19Profile::from_bech32(entity).ok().map(|profile| profile.public_key)
+    } else {
+        println!("[DeepLink] Unsupported nostr: entity: {}", entity);
+        return None;
+    };
+    match public_key.and_then(|pk| pk.to_bech32().ok()) {
+        Some(npub) => Some(DeepLinkAction {
+            action_type: "chat".to_string(),
+            target: npub,
+        }),
+        None => {
+            println!("[DeepLink] Invalid nostr: entity: {}", entity);
+            None
+        }
+    }
+}
+
 /// Parse a web URL (https://vectorapp.io/...)
 fn parse_web_url(url_str: &str) -> Option<DeepLinkAction> {
     // Extract the path from the URL using simple string manipulation
@@ -104,6 +138,17 @@ fn parse_path_segments(path: &str) -> Option<DeepLinkAction> {
                 None
             }
         }
+        // Open (or start) a chat: a DM by npub, or a Community channel by its hex id
+        "chat" if segments.len() >= 2 => match vector_core::ids::ChatId::parse(segments[1]) {
+            Ok(chat_id) => Some(DeepLinkAction {
+                action_type: "chat".to_string(),
+                target: chat_id.as_identifier(),
+            }),
+            Err(_) => {
+                println!("[DeepLink] Invalid chat id: {}", segments[1]);
+                None
+            }
+        },
         // Pack share links — `emojis/pack/<naddr>` matches the website
         // route + the Vector app's `_sharePackToClipboard` output. Naddr
         // decoding happens on the frontend; we only sanity-check the
@@ -120,6 +165,20 @@ fn parse_path_segments(path: &str) -> Option<DeepLinkAction> {
                 None
             }
         }
+        // Contact verification QR — the sender's npub and their safety number with us, checked by
+        // the frontend's `verify_contact_qr` call and recorded only once the user confirms.
+        "verify" if segments.len() >= 3 => {
+            let (npub, digits) = (segments[1], segments[2]);
+            if validate_npub(npub) && digits.len() == 60 && digits.bytes().all(|b| b.is_ascii_digit()) {
+                Some(DeepLinkAction {
+                    action_type: "verify".to_string(),
+                    target: format!("vector://verify/{}/{}", npub, digits),
+                })
+            } else {
+                println!("[DeepLink] Invalid verification code");
+                None
+            }
+        }
         _ => {
             println!("[DeepLink] Unknown action: {}", segments[0]);
             None
@@ -129,8 +188,7 @@ fn parse_path_segments(path: &str) -> Option<DeepLinkAction> {
 
 /// Validate that a string is a valid npub (Nostr public key in bech32 format)
 fn validate_npub(npub: &str) -> bool {
-    // npub1 prefix + 58 characters of bech32 data = 63 total characters
-    npub.starts_with("npub1") && npub.len() == 63
+    vector_core::ids::is_npub(npub)
 }
 
 /// Lightweight naddr sanity check. Real bech32 + TLV decoding happens
@@ -288,4 +346,47 @@ mod tests {
         assert_eq!(action.action_type, "profile");
         assert_eq!(action.target, npub);
     }
-}
\ No newline at end of file
+
+    // NIP-19's example key, as an npub and as an nprofile with relay hints
+    const SPEC_NPUB: &str = "npub180cvv07tjdrrgpa0j7j7tmnyl2yr6yr7l8j4s3evf6u64th6gkwsyjh6w6";
+    const SPEC_NPROFILE: &str = "nprofile1qqsrhuxx8l9ex335q7he0f09aej04zpazpl0ne2cgukyawd24mayt8gpp4mhxue69uhhytnc9e3k7mgpz4mhxue69uhkg6nzv9ejuumpv34kytnrdaksjlyr9p";
+
+    #[test]
+    fn nostr_uris_open_a_dm_with_the_user() {
+        for url in [
+            format!("nostr:{SPEC_NPUB}"),
+            format!("nostr://{SPEC_NPUB}/"),
+            format!("nostr:{SPEC_NPROFILE}"),
+        ] {
+            let action = parse_deep_link(&url).unwrap_or_else(|| panic!("{url}"));
+            assert_eq!(action.action_type, "chat");
+            assert_eq!(action.target, SPEC_NPUB);
+        }
+        assert!(parse_deep_link("nostr:npub1invalid").is_none());
+        assert!(parse_deep_link("nostr:note1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq").is_none());
+    }
+
+    #[test]
+    fn chat_links_take_an_npub_or_a_channel_id() {
+        let action = parse_deep_link(&format!("vector://chat/{SPEC_NPUB}")).unwrap();
+        assert_eq!((action.action_type.as_str(), action.target.as_str()), ("chat", SPEC_NPUB));
+
+        let channel = "AB".repeat(32);
+        let action = parse_deep_link(&format!("vector://chat/{channel}")).unwrap();
+        assert_eq!(action.target, channel.to_ascii_lowercase());
+
+        assert!(parse_deep_link("vector://chat/not-a-chat").is_none());
+        assert!(parse_deep_link("vector://chat").is_none());
+    }
+
+    #[test]
+    fn verification_code_needs_an_npub_and_sixty_digits() {
+        let npub = format!("npub1{}", "q".repeat(58));
+        let url = format!("vector://verify/{npub}/{}", "0123456789".repeat(6));
+        let action = parse_deep_link(&url).unwrap();
+        assert_eq!(action.action_type, "verify");
+        assert_eq!(action.target, url);
+        assert!(parse_deep_link(&format!("vector://verify/{npub}/12345")).is_none());
+        assert!(parse_deep_link(&format!("vector://verify/notanpub/{}", "0".repeat(60))).is_none());
+    }
+}
@@ -648,10 +648,15 @@ pub async fn cleanup_stale_downloads() {
 ///
 /// Security: Only HTTPS URLs from non-private hosts are allowed.
 /// URL format validation is done in JS to avoid IPC overhead for invalid URLs.
+///
+/// With `chat_id`, a download (not a cache hit) honours the chat's remote-content policy
+/// (`user_initiated` = the user tapped to load it).
 #[tauri::command]
 pub async fn cache_url_image<R: Runtime>(
     handle: AppHandle<R>,
     url: String,
+    chat_id: Option<String>,
+    user_initiated: Option<bool>,
 ) -> Result<Option<String>, String> {
     // Security: Only allow HTTPS URLs
     if !is_https_url(&url) {
@@ -672,6 +677,10 @@ pub async fn cache_url_image<R: Runtime>(
         return Ok(Some(path));
     }
 
+    if let Some(chat_id) = chat_id.as_deref() {
+        vector_core::remote_content::gate(chat_id, user_initiated.unwrap_or(false))?;
+    }
+
     // Check if this URL is already being downloaded (e.g., message re-rendered from Pending to Sent)
     // If so, return None - the frontend will receive the cached path via the inline_image_cached event
     {
@@ -0,0 +1,146 @@
+//! The platform credential store behind `vector_core::SecretStore`, for accounts
+//! that keep their private key out of the database.
+//!
+//! - Desktop: the `keyring` crate — macOS Keychain, Windows Credential Manager,
+//!   and the Secret Service (GNOME Keyring, KWallet) on Linux.
+//! - Android: `io.vectorapp.VectorKeystore`, which seals the value with an AES
+//!   key that never leaves the Android Keystore.
+//!
+//! Entries are keyed by npub under one service name, so each account has its own.
+
+/// Keychain service name shared by every account's entry.
+#[cfg(not(target_os = "android"))]
+const SERVICE: &str = "io.vectorapp";
+
+pub struct PlatformSecretStore;
+
+/// Register the platform store with vector-core. Call once during app startup.
+pub fn register() {
+    vector_core::set_secret_store(Box::new(PlatformSecretStore));
+}
+
+#[cfg(not(target_os = "android"))]
+impl vector_core::SecretStore for PlatformSecretStore {
+    fn store(&self, account: &str, secret: &str) -> Result<(), String> {
+        entry(account)?
+            .set_password(secret)
+            .map_err(|e| format!("Failed to write to the system keystore: {}", e))
+    }
+
+    fn load(&self, account: &str) -> Result<Option<String>, String> {
+        match entry(account)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Failed to read from the system keystore: {}", e)),
+        }
+    }
+
+    fn delete(&self, account: &str) -> Result<(), String> {
+        match entry(account)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to delete from the system keystore: {}", e)),
+        }
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+fn entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, account)
+        .map_err(|e| format!("System keystore unavailable: {}", e))
+}
+
+#[cfg(target_os = "android")]
+impl vector_core::SecretStore for PlatformSecretStore {
+    fn store(&self, account: &str, secret: &str) -> Result<(), String> {
+        if jni_bridge::call_bool("store", account, Some(secret))? {
+            Ok(())
+        } else {
+            Err("Failed to write to the Android Keystore".to_string())
+        }
+    }
+
+    fn load(&self, account: &str) -> Result<Option<String>, String> {
+        jni_bridge::load(account)
+    }
+
+    fn delete(&self, account: &str) -> Result<(), String> {
+        if jni_bridge::call_bool("delete", account, None)? {
+            Ok(())
+        } else {
+            Err("Failed to delete from the Android Keystore".to_string())
+        }
+    }
+}
+
+/// A keystore entry, for callers that run before (or without) the registered
+/// store — the Activity-less background sync service.
+#[cfg(target_os = "android")]
+pub fn load(account: &str) -> Result<Option<String>, String> {
+    jni_bridge::load(account)
+}
+
+#[cfg(target_os = "android")]
+mod jni_bridge {
+    use jni::objects::{JClass, JObject, JString, JValue};
+
+    const CLASS: &str = "io.vectorapp.VectorKeystore";
+
+    fn jni_err<E: std::fmt::Debug>(e: E) -> String {
+        format!("{:?}", e)
+    }
+
+    fn load_class<'a>(env: &mut jni::JNIEnv<'a>, ctx: &JObject<'a>) -> Result<JClass<'a>, String> {
+        let class_loader = env.call_method(ctx, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])
+            .map_err(jni_err)?.l().map_err(jni_err)?;
+        let class_name = env.new_string(CLASS).map_err(jni_err)?;
+        let class = env.call_method(&class_loader, "loadClass", "(Ljava/lang/String;)Ljava/lang/Class;",
+            &[JValue::Object(&class_name)])
+            .map_err(jni_err)?.l().map_err(jni_err)?;
+        Ok(JClass::from(class))
+    }
+
+    /// `store(context, account, secret)` / `delete(context, account)`.
+    pub fn call_bool(method: &str, account: &str, secret: Option<&str>) -> Result<bool, String> {
+        crate::android::utils::with_android_context(|env, ctx| {
+            let class = load_class(env, ctx)?;
+            let account: JObject = env.new_string(account).map_err(jni_err)?.into();
+            let result = match secret {
+                Some(secret) => {
+                    let secret: JObject = env.new_string(secret).map_err(jni_err)?.into();
+                    env.call_static_method(&class, method,
+                        "(Landroid/content/Context;Ljava/lang/String;Ljava/lang/String;)Z",
+                        &[JValue::Object(ctx), JValue::Object(&account), JValue::Object(&secret)])
+                }
+                None => env.call_static_method(&class, method,
+                    "(Landroid/content/Context;Ljava/lang/String;)Z",
+                    &[JValue::Object(ctx), JValue::Object(&account)]),
+            };
+            result.map_err(jni_err)?.z().map_err(jni_err)
+        })
+    }
+
+    /// `load(context, account)`. A thrown `UnsealException` (the only thing `load` throws) is
+    /// cleared and reported as [`vector_core::db::KEYSTORE_UNSEALABLE`].
+    pub fn load(account: &str) -> Result<Option<String>, String> {
+        crate::android::utils::with_android_context(|env, ctx| {
+            let class = load_class(env, ctx)?;
+            let account: JObject = env.new_string(account).map_err(jni_err)?.into();
+            let secret = match env.call_static_method(&class, "load",
+                "(Landroid/content/Context;Ljava/lang/String;)Ljava/lang/String;",
+                &[JValue::Object(ctx), JValue::Object(&account)])
+            {
+                Ok(secret) => secret.l().map_err(jni_err)?,
+                Err(jni::errors::Error::JavaException) => {
+                    let _ = env.exception_clear();
+                    return Err(vector_core::db::KEYSTORE_UNSEALABLE.to_string());
+                }
+                Err(e) => return Err(jni_err(e)),
+            };
+            if secret.is_null() {
+                return Ok(None);
+            }
+            let secret: String = env.get_string(&JString::from(secret)).map_err(jni_err)?.into();
+            Ok(Some(secret))
+        })
+    }
+}
@@ -14,6 +14,7 @@ mod db;
 mod account_manager;
 
 mod voice;
+mod video;
 
 mod net;
 
@@ -61,6 +62,14 @@ pub use vector_core::{StoredEvent, StoredEventBuilder};
 mod deep_link;
 mod share;
 
+// OS keychain / Android Keystore backend for the private key
+#[cfg(any(target_os = "macos", windows, target_os = "linux", target_os = "android"))]
+mod keystore;
+
+// Desktop system tray (unread count, quick actions, minimize-to-tray)
+#[cfg(desktop)]
+mod tray;
+
 // Mini Apps (WebXDC-compatible) support
 mod miniapps;
 
@@ -112,6 +121,25 @@ mod services;
 // Re-export notification types for backwards compatibility
 pub(crate) use services::{NotificationData, show_notification_generic};
 
+/// Save the window state and cleanly shut down our Nostr client, ahead of the app exiting (the
+/// window closing, or Quit from the tray).
+pub(crate) fn prepare_exit<R: tauri::Runtime>(handle: &tauri::AppHandle<R>) {
+    // Save window state (position, size, maximized, etc.)
+    #[cfg(desktop)]
+    {
+        use tauri_plugin_window_state::{AppHandleExt, StateFlags};
+        let _ = handle.save_window_state(StateFlags::all());
+    }
+    #[cfg(not(desktop))]
+    let _ = handle;
+
+    if let Some(nostr_client) = nostr_client() {
+        tauri::async_runtime::block_on(async {
+            nostr_client.shutdown().await;
+        });
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Install a panic hook that logs the crash before the process dies.
@@ -239,12 +267,10 @@ pub fn run() {
     // 'ring' and 'aws-lc-rs' features are pulled by different transitive deps)
     let _ = rustls::crypto::ring::default_provider().install_default();
 
+    // WebKitGTK can be quite funky cross-platform: detect the display server and GPU driver, and
+    // pick the most compatible renderer for them (or the user's override) before any webview exists.
     #[cfg(target_os = "linux")]
-    {
-        // WebKitGTK can be quite funky cross-platform: as a result, we'll fallback to a more compatible renderer
-        // In theory, this will make Vector run more consistently across a wider range of Linux Desktop distros.
-        std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
-    }
+    services::graphics_env::apply();
 
     #[cfg(target_os = "windows")]
     {
@@ -290,14 +316,15 @@ pub fn run() {
         builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
             // Handle deep links from single-instance (Windows/Linux)
             let urls: Vec<String> = args.iter()
-                .filter(|arg| arg.starts_with("vector://") || arg.contains("vectorapp.io"))
+                .filter(|arg| arg.starts_with("vector://") || arg.starts_with("nostr:") || arg.contains("vectorapp.io"))
                 .cloned()
                 .collect();
             if !urls.is_empty() {
                 deep_link::handle_deep_link(app, urls);
             }
-            // Focus the existing window
+            // Focus the existing window (bringing it back if it was hidden to the tray)
             if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
                 let _ = window.set_focus();
             }
         }));
@@ -315,8 +342,7 @@ pub fn run() {
             let window = app.get_webview_window("main").unwrap();
 
             // Setup a graceful shutdown for our Nostr subscriptions
-            #[cfg(desktop)]
-            let handle_for_window_state = handle.clone();
+            let handle_for_close = handle.clone();
             window.on_window_event(move |event| {
                 match event {
                     // This catches when the window is being closed
@@ -327,20 +353,15 @@ pub fn run() {
                             return;
                         }
 
-                        // Save window state (position, size, maximized, etc.) before closing
+                        // Minimize-to-tray: keep running (and notifying) with the window hidden
                         #[cfg(desktop)]
-                        {
-                            use tauri_plugin_window_state::{AppHandleExt, StateFlags};
-                            let _ = handle_for_window_state.save_window_state(StateFlags::all());
+                        if tray::minimize_to_tray() {
+                            api.prevent_close();
+                            tray::hide_main_window(&handle_for_close);
+                            return;
                         }
 
-                        // Cleanly shutdown our Nostr client
-                        if let Some(nostr_client) = nostr_client() {
-                            tauri::async_runtime::block_on(async {
-                                // Shutdown the Nostr client
-                                nostr_client.shutdown().await;
-                            });
-                        }
+                        prepare_exit(&handle_for_close);
                     }
                     _ => {}
                 }
@@ -392,6 +413,10 @@ pub fn run() {
                 }
             }
 
+            // Probe every directory we need before touching any of them, so a sandbox that blocks
+            // one is reported (and shown to the user) rather than surfacing as a downstream failure.
+            services::runtime_paths::check(&handle);
+
             // Boot account selection: honors active_account marker file, falls
             // back to single-account, otherwise leaves CURRENT_ACCOUNT unset so
             // the frontend shows the multi-account picker.
@@ -404,9 +429,23 @@ pub fn run() {
             // Set as our accessible static app handle
             TAURI_APP.set(handle.clone()).unwrap();
 
+            // Mark as Read / Reply on desktop message notifications
+            #[cfg(any(target_os = "linux", target_os = "macos"))]
+            services::notification_actions::init();
+
+            // Desktop tray icon (after the app data dir is set: it holds the minimize-to-tray flag)
+            #[cfg(desktop)]
+            if let Err(e) = tray::init(&handle) {
+                println!("[Tray] Failed to create tray icon: {e}");
+            }
+
             // Bridge vector-core's EventEmitter to Tauri's emit system
             vector_core::set_event_emitter(Box::new(TauriEventEmitter));
 
+            // Let accounts keep their private key in the OS keychain / Android Keystore
+            #[cfg(any(target_os = "macos", windows, target_os = "linux", target_os = "android"))]
+            keystore::register();
+
             // Register the Android NIP-55 (Amber) signer backend so keyless
             // offline accounts can sign/encrypt/decrypt over local IPC.
             #[cfg(target_os = "android")]
@@ -462,6 +501,32 @@ pub fn run() {
                 vector_core::self_destruct::run_sweeper_loop().await;
             });
 
+            // Re-send Community invites that no relay accepted.
+            tauri::async_runtime::spawn(async {
+                vector_core::community::invite_delivery::run_retry_loop().await;
+            });
+
+            // Keep our presence fresh with contacts while the account shares it.
+            tauri::async_runtime::spawn(async {
+                vector_core::presence::run_loop().await;
+            });
+
+            // Send live location shares' latest positions until they run out.
+            tauri::async_runtime::spawn(async {
+                vector_core::location::run_live_loop().await;
+            });
+
+            // Watch the user's SOCKS proxy so a kill-switched proxy going down (or back up)
+            // moves every connection onto the right transport.
+            commands::proxy::start_monitor(handle.clone());
+
+            // Reconnect + catch up as soon as the OS reports a network switch (Wi-Fi ↔ cellular,
+            // VPN up/down) instead of waiting for the health check to find the dead sockets.
+            services::network_watch::start(handle.clone());
+
+            // Lock encrypted accounts after the user's chosen stretch of inactivity.
+            services::auto_lock::start(handle.clone());
+
             
             // Setup deep link listener for macOS/iOS/Android
             // On these platforms, deep links are received as events rather than CLI args
@@ -508,6 +573,8 @@ pub fn run() {
             db::settings::get_theme,
             db::settings::get_pkey,
             db::settings::set_pkey,
+            db::settings::get_key_storage,
+            db::settings::set_key_storage,
             db::settings::get_seed,
             db::settings::set_seed,
             db::settings::get_sql_setting,
@@ -516,17 +583,37 @@ pub fn run() {
             profile::load_profile,
             profile::update_profile,
             profile::update_status,
+            profile::set_last_active_enabled,
+            profile::get_presence_sharing,
+            profile::set_presence_sharing,
             profile::upload_avatar,
             chat::mark_as_read,
             chat::mark_as_unread,
             chat::toggle_chat_mute,
+            chat::get_chat_notification_prefs,
+            chat::set_chat_notification_prefs,
+            chat::get_chat_typing_enabled,
+            chat::set_chat_typing_enabled,
+            chat::delete_chat,
+            chat::restore_chat,
+            chat::list_recently_deleted,
             profile::set_nickname,
+            profile::get_verification_code,
+            profile::mark_contact_verified,
+            profile::verify_contact_qr,
+            profile::get_contact_relays,
+            profile::set_contact_relays,
             profile::block_user,
             profile::unblock_user,
             profile::get_blocked_users,
+            profile::get_profile_details,
             message::message,
+            message::send_location,
+            message::report_location,
+            message::stop_live_location,
             message::delete_failed_message,
             message::retry_failed_dm,
+            message::retry_failed_upload,
             message::delete_own_message,
             message::is_message_deletable,
             message::get_message_delete_options,
@@ -534,6 +621,7 @@ pub fn run() {
             message::cancel_upload,
             message::paste_message,
             message::file_message,
+            message::send_files,
             message::forward_attachment,
             message::get_file_info,
             message::cache_android_file,
@@ -552,27 +640,41 @@ pub fn run() {
             message::get_image_preview_base64,
             message::start_image_precompression,
             message::get_compression_status,
+            message::prepare_attachment,
             message::clear_compression_cache,
+            message::get_media_settings,
+            message::set_media_settings,
             message::send_cached_compressed_file,
             message::is_directory,
             message::zip_directory,
             message::cleanup_zip,
             message::react_to_message,
+            message::get_message_reactions,
+            message::copy_code_block,
+            message::resolve_nostr_uri,
             message::edit_message,
             message::fetch_msg_metadata,
+            message::prefetch_link_preview,
             // Sync commands (commands/sync.rs)
             commands::sync::fetch_messages,
             commands::sync::is_scanning,
+            commands::sync::resume_history_sync,
+            commands::sync::skip_history_sync,
             // Messaging commands (commands/messaging.rs)
             commands::messaging::get_chat_messages_paginated,
             commands::messaging::get_message_views,
             commands::messaging::get_messages_around_id,
             commands::messaging::get_messages_around,
+            commands::messaging::load_older_messages,
+            commands::messaging::search_in_chat,
             commands::messaging::get_system_events,
             commands::messaging::get_chat_message_count,
             commands::messaging::evict_chat_messages,
             commands::self_destruct::get_self_destruct_timer,
             commands::self_destruct::set_self_destruct_timer,
+            commands::remote_content::get_remote_content_policy,
+            commands::remote_content::get_remote_content_policies,
+            commands::remote_content::set_remote_content_policy,
             // Realtime signaling commands (commands/realtime.rs)
             commands::realtime::notifs,
             commands::realtime::start_typing,
@@ -584,11 +686,21 @@ pub fn run() {
             // Media commands (commands/media.rs)
             commands::media::start_recording,
             commands::media::stop_recording,
+            commands::media::get_video_note_support,
+            commands::media::send_video_note,
             commands::media::transcribe,
             commands::media::download_whisper_model,
+            commands::media::estimate_whisper_download,
             commands::messaging::update_unread_counter,
             commands::messaging::get_unread_counts,
+            commands::messaging::get_unread_summary,
+            commands::messaging::get_my_mentions,
+            commands::messaging::get_unread_mention_counts,
+            commands::messaging::star_message,
+            commands::messaging::unstar_message,
+            commands::messaging::get_starred_messages,
             commands::messaging::set_active_chat,
+            commands::messaging::report_chat_view,
             commands::system::get_platform_features,
             commands::system::get_device_memory,
             // Invite and badge commands (commands/invites.rs)
@@ -600,14 +712,33 @@ pub fn run() {
             commands::invites::get_bug_hunter_tier,
             commands::invites::get_max_account_tier,
             commands::system::get_storage_info,
+            commands::system::get_database_stats,
             commands::system::clear_storage,
             commands::system::clear_storage_category,
+            commands::system::get_cache_stats,
+            commands::system::set_cache_limit,
+            commands::system::check_permissions,
+            commands::system::request_permission,
+            commands::system::open_permission_settings,
             commands::system::check_battery_optimized,
             commands::system::request_battery_optimization,
             commands::system::get_background_service_enabled,
             commands::system::set_background_service_enabled,
+            commands::system::get_screen_security,
+            commands::system::set_screen_security,
             commands::system::get_background_service_prompted,
             commands::system::set_background_service_prompted,
+            commands::system::get_background_sync_interval,
+            commands::system::set_background_sync_interval,
+            commands::system::get_minimize_to_tray,
+            commands::system::set_minimize_to_tray,
+            commands::system::get_push_status,
+            commands::system::set_push_enabled,
+            commands::system::refresh_push_registration,
+            commands::system::set_announcements_enabled,
+            commands::system::get_accessibility_settings,
+            commands::system::set_accessibility_settings,
+            commands::system::set_consent_remembered,
             commands::updates::check_app_update,
             commands::updates::get_install_source,
             commands::updates::open_update_source,
@@ -711,6 +842,9 @@ pub fn run() {
             commands::tor::tor_get_bridges,
             commands::tor::tor_set_bridges,
             commands::tor::tor_check_obfs4_proxy,
+            // SOCKS5 proxy commands
+            commands::proxy::proxy_get_state,
+            commands::proxy::proxy_set,
             // Notification sound commands (desktop only)
             #[cfg(desktop)]
             audio::get_notification_settings,
@@ -751,6 +885,8 @@ pub fn run() {
             commands::emoji_packs::bump_emoji_usage,
             commands::emoji_packs::bump_emoji_usage_batch,
             commands::emoji_packs::get_emoji_usage,
+            commands::emoji_packs::get_reaction_quickpicks,
+            commands::emoji_packs::set_reaction_quickpicks,
             commands::emoji_packs::set_theme_emoji_pack,
             commands::emoji_packs::decode_animated_emoji,
             commands::emoji_packs::emoji_pack_create,
@@ -764,13 +900,61 @@ pub fn run() {
             commands::wallpaper::publish_wallpaper,
             commands::wallpaper::cancel_wallpaper_preview,
             commands::wallpaper::remove_wallpaper,
+            commands::social_backup::create_social_backup,
+            commands::social_backup::get_social_backup,
+            commands::social_backup::get_backup_guardianship,
+            commands::social_backup::approve_backup_request,
+            commands::social_backup::deny_backup_request,
+            commands::social_backup::start_social_recovery,
+            commands::social_backup::get_social_recovery_status,
+            commands::social_backup::finish_social_recovery,
+            commands::social_backup::cancel_social_recovery,
+            commands::contact_groups::get_contact_groups,
+            commands::contact_groups::create_contact_group,
+            commands::contact_groups::rename_contact_group,
+            commands::contact_groups::delete_contact_group,
+            commands::contact_groups::set_contact_group_member,
+            commands::chat_folders::get_folders,
+            commands::chat_folders::create_folder,
+            commands::chat_folders::rename_folder,
+            commands::chat_folders::delete_folder,
+            commands::chat_folders::assign_chat_to_folder,
+            commands::appearance::get_chat_appearance,
+            commands::appearance::get_chat_accent_colors,
+            commands::appearance::set_chat_accent_color,
+            commands::appearance::get_chat_wallpaper_overrides,
+            commands::appearance::set_chat_wallpaper_override,
+            commands::appearance::clear_chat_wallpaper_override,
+            commands::contacts::get_contacts,
+            commands::contacts::add_contact,
+            commands::contacts::remove_contact,
+            commands::zaps::send_zap,
+            commands::ecash::send_ecash,
+            commands::ecash::get_chat_ecash,
+            commands::ecash::claim_chat_ecash,
+            commands::ecash::redeem_ecash,
+            commands::ecash::get_ecash_balance,
+            commands::ecash::get_ecash_history,
+            commands::ecash::ecash_deposit,
+            commands::ecash::get_ecash_deposits,
+            commands::ecash::ecash_complete_deposit,
+            commands::ecash::ecash_withdraw,
             commands::clipboard::read_clipboard_files,
             commands::clipboard::write_clipboard_files,
             #[cfg(debug_assertions)]
             commands::account::debug_hot_reload_sync,
             commands::account::logout,
+            commands::account::lock_now,
+            commands::account::report_activity,
+            commands::account::report_app_visibility,
             commands::account::create_account,
             commands::account::export_keys,
+            commands::account::export_account_bundle,
+            commands::account::export_activity_report,
+            commands::account::import_account_bundle,
+            commands::account::create_backup,
+            commands::account::restore_backup,
+            commands::account::get_key_audit_log,
             // Relay commands (commands/relays.rs)
             commands::relays::get_relays,
             commands::relays::get_media_servers,
@@ -780,6 +964,10 @@ pub fn run() {
             commands::relays::toggle_custom_blossom_server,
             commands::relays::toggle_default_blossom_server,
             commands::relays::get_blossom_server_capabilities,
+            commands::relays::reorder_blossom_servers,
+            commands::relays::get_blossom_server_health,
+            commands::relays::set_blossom_prefer_fastest,
+            commands::relays::set_blossom_mirror_count,
             commands::relays::blossom_can_likely_upload,
             commands::relays::get_custom_relays,
             commands::relays::add_custom_relay,
@@ -789,16 +977,23 @@ pub fn run() {
             commands::relays::update_relay_mode,
             commands::relays::validate_relay_url_cmd,
             commands::relays::get_relay_metrics,
+            commands::relays::get_relay_stats,
+            commands::relays::set_relay_demotion_override,
             commands::relays::get_relay_logs,
             commands::relays::monitor_relay_connections,
             // Attachment commands (commands/attachments.rs)
             commands::attachments::generate_thumbhash_preview,
             commands::attachments::decode_thumbhash,
             commands::attachments::download_attachment,
+            commands::attachments::stream_attachment,
+            commands::attachments::archive_attachments,
+            commands::attachments::restore_archived_attachments,
+            commands::attachments::mark_media_viewed,
             commands::attachments::open_attachment,
             commands::attachments::share_attachment,
             commands::attachments::get_gallery_hidden,
             commands::attachments::set_gallery_hidden,
+            commands::attachments::get_chat_media,
             // Community commands (commands/community.rs)
             commands::community::list_communities,
             commands::community::get_community,
@@ -831,9 +1026,13 @@ pub fn run() {
             commands::community::react_to_community_message,
             commands::community::edit_community_message,
             commands::community::invite_to_community,
+            commands::community::get_community_invite_status,
+            commands::community::list_outgoing_invites,
+            commands::community::revoke_outgoing_invite,
             commands::community::list_community_invites,
             commands::community::accept_community_invite,
             commands::community::decline_community_invite,
+            commands::community::preview_community_invite,
             commands::community::create_public_invite,
             commands::community::preview_public_invite,
             commands::community::accept_public_invite,
@@ -849,15 +1048,20 @@ pub fn run() {
             commands::community::get_community_admins,
             commands::community::can_manage_community_roles,
             commands::community::get_community_capabilities,
+            commands::community::get_community_debug_info,
             commands::community::get_community_invite_summary,
             // Sync commands (commands/sync.rs)
             commands::sync::queue_profile_sync,
             commands::sync::queue_chat_profiles_sync,
             commands::sync::refresh_profile_now,
             commands::sync::sync_all_profiles,
+            commands::sync::sync_settings_now,
             // System commands (commands/system.rs)
             commands::system::run_maintenance,
             commands::system::get_logs,
+            commands::system::get_runtime_paths,
+            commands::system::get_graphics_env,
+            commands::system::set_renderer_mode,
             // Encryption toggle commands (commands/encryption.rs)
             commands::encryption::get_encryption_status,
             commands::encryption::get_encryption_and_key,
@@ -865,6 +1069,8 @@ pub fn run() {
             commands::encryption::enable_encryption,
             commands::encryption::rekey_encryption,
             commands::encryption::verify_credential,
+            commands::encryption::set_message_plaintext_cache,
+            commands::encryption::cancel_encryption_migration,
             #[cfg(feature = "whisper")]
             whisper::delete_whisper_model,
             #[cfg(feature = "whisper")]
@@ -1,13 +1,15 @@
 //! Image compression functions.
 //!
 //! This module handles:
-//! - Image compression with resize to max 1920px
+//! - Image compression with resize to the configured quality's max dimension
 //! - GIF preservation (skip compression to keep animation)
-//! - PNG for transparent images, JPEG for opaque
+//! - PNG for transparent images, JPEG (or WebP, if chosen) for opaque
 //! - ThumbHash generation for previews
 
 use std::sync::Arc;
 
+use crate::shared::image::EncodedImage;
+
 use super::types::{CachedCompressedImage, ImageMetadata};
 
 #[cfg(target_os = "android")]
@@ -15,10 +17,149 @@ use super::types::ANDROID_FILE_CACHE;
 #[cfg(target_os = "android")]
 use crate::android::filesystem;
 
+const QUALITY_KEY: &str = "media_quality";
+const FORMAT_KEY: &str = "media_format";
+const COMPRESS_DEFAULT_KEY: &str = "media_compress_default";
+
+/// How hard outbound photos are compressed.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaQuality {
+    /// 1280px, for slow or metered connections.
+    Small,
+    /// 1920px, the long-standing default.
+    #[default]
+    Standard,
+    /// 2560px at near-lossless quality.
+    High,
+}
+
+impl MediaQuality {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "small" => Some(Self::Small),
+            "standard" => Some(Self::Standard),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Small => "small",
+            Self::Standard => "standard",
+            Self::High => "high",
+        }
+    }
+
+    /// Longest side of a compressed image.
+    pub fn max_dimension(self) -> u32 {
+        match self {
+            Self::Small => 1280,
+            Self::Standard => crate::shared::image::MAX_DIMENSION,
+            Self::High => 2560,
+        }
+    }
+
+    /// Encoder quality (0-100), used for both JPEG and WebP.
+    pub fn encoder_quality(self) -> u8 {
+        match self {
+            Self::Small => crate::shared::image::JPEG_QUALITY_COMPRESSED,
+            Self::Standard => crate::shared::image::JPEG_QUALITY_STANDARD,
+            Self::High => 92,
+        }
+    }
+}
+
+/// Container for compressed opaque photos. Transparent and tiny images stay PNG either way.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaFormat {
+    #[default]
+    Jpeg,
+    /// Smaller at the same quality, but older clients on other apps may not preview it.
+    Webp,
+}
+
+/// The user's outbound media settings (Settings > Storage). Per device, not synced: what's
+/// worth uploading depends on the connection this device is on.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MediaSettings {
+    pub quality: MediaQuality,
+    pub format: MediaFormat,
+    /// Whether sends without a preview (drag-and-drop, multi-file) compress images. The
+    /// preview's Compress toggle starts from this too; switching it off sends the original.
+    pub compress_by_default: bool,
+}
+
+impl Default for MediaSettings {
+    fn default() -> Self {
+        Self { quality: MediaQuality::Standard, format: MediaFormat::Jpeg, compress_by_default: true }
+    }
+}
+
+fn setting(key: &str) -> Option<String> {
+    crate::db::get_sql_setting(key.to_string()).ok().flatten()
+}
+
+/// The stored media settings (defaults for anything unset, or with no account open).
+pub fn load_media_settings() -> MediaSettings {
+    let defaults = MediaSettings::default();
+    MediaSettings {
+        quality: setting(QUALITY_KEY).and_then(|v| MediaQuality::parse(&v)).unwrap_or(defaults.quality),
+        format: match setting(FORMAT_KEY).as_deref() {
+            Some("webp") => MediaFormat::Webp,
+            Some("jpeg") => MediaFormat::Jpeg,
+            _ => defaults.format,
+        },
+        compress_by_default: setting(COMPRESS_DEFAULT_KEY).map(|v| v != "false").unwrap_or(defaults.compress_by_default),
+    }
+}
+
+pub fn save_media_settings(settings: &MediaSettings) -> Result<(), String> {
+    crate::db::set_sql_setting(QUALITY_KEY.to_string(), settings.quality.as_str().to_string())?;
+    let format = match settings.format {
+        MediaFormat::Jpeg => "jpeg",
+        MediaFormat::Webp => "webp",
+    };
+    crate::db::set_sql_setting(FORMAT_KEY.to_string(), format.to_string())?;
+    crate::db::set_sql_setting(COMPRESS_DEFAULT_KEY.to_string(), settings.compress_by_default.to_string())
+}
+
+/// Encode a resized image the way `settings` asks: PNG for alpha and tiny images (as
+/// `encode_rgba_auto` decides), otherwise JPEG or lossy WebP at the preset's quality.
+fn encode_compressed(pixels: &[u8], width: u32, height: u32, settings: &MediaSettings) -> Result<EncodedImage, String> {
+    let quality = settings.quality.encoder_quality();
+    let encoded = crate::shared::image::encode_rgba_auto(pixels, width, height, quality)?;
+    if settings.format == MediaFormat::Webp && encoded.extension == "jpg" {
+        let webp = webp::Encoder::from_rgba(pixels, width, height).encode(quality as f32);
+        return Ok(EncodedImage { bytes: webp.to_vec(), extension: "webp" });
+    }
+    Ok(encoded)
+}
+
+/// Resize to the preset's max dimension and encode; returns the bytes plus preview metadata.
+fn compress_decoded(img: ::image::DynamicImage, settings: &MediaSettings) -> Result<(EncodedImage, Option<ImageMetadata>), String> {
+    use crate::shared::image::calculate_resize_dimensions;
+    let (width, height) = (img.width(), img.height());
+    let (new_width, new_height) = calculate_resize_dimensions(width, height, settings.quality.max_dimension());
+    let resized = if new_width != width || new_height != height {
+        img.resize(new_width, new_height, ::image::imageops::FilterType::Lanczos3)
+    } else {
+        img
+    };
+    let (actual_width, actual_height) = (resized.width(), resized.height());
+    let img_meta = crate::util::generate_thumbhash_from_image(&resized)
+        .map(|thumbhash| ImageMetadata { thumbhash, width: actual_width, height: actual_height });
+    let rgba = resized.to_rgba8();
+    let encoded = encode_compressed(rgba.as_raw(), actual_width, actual_height, settings)?;
+    Ok((encoded, img_meta))
+}
+
 /// Prepare an image for sending, honouring the compress + keep-metadata choices.
 ///
 /// The 2x2 of behaviours:
-/// - compress + strip  -> resize to MAX_DIMENSION and re-encode (metadata dropped)
+/// - compress + strip  -> resize and re-encode per [`MediaSettings`] (metadata dropped)
 /// - compress + keep   -> resize + re-encode, then re-attach the original EXIF
 ///                        (orientation normalised, since pixels are baked upright)
 /// - full-res + strip  -> re-encode at full resolution (metadata dropped, orientation baked)
@@ -31,10 +172,7 @@ pub(crate) fn prepare_outbound_image(
     compress: bool,
     keep_metadata: bool,
 ) -> Result<CachedCompressedImage, String> {
-    use crate::shared::image::{
-        calculate_resize_dimensions, encode_rgba_auto, reattach_exif_jpeg,
-        MAX_DIMENSION, JPEG_QUALITY_STANDARD, JPEG_QUALITY_HIGH,
-    };
+    use crate::shared::image::{encode_rgba_auto, reattach_exif_jpeg, JPEG_QUALITY_HIGH};
 
     let original_size = bytes.len() as u64;
 
@@ -91,29 +229,23 @@ pub(crate) fn prepare_outbound_image(
 
     // Re-encode paths. decode_image_bounded bakes EXIF orientation into pixels.
     let img = vector_core::crypto::decode_image_bounded(&bytes)?;
-    let (w, h) = (img.width(), img.height());
-    let (nw, nh) = if compress {
-        calculate_resize_dimensions(w, h, MAX_DIMENSION)
+    let (img_meta, out_bytes, out_ext) = if compress {
+        // Compression follows the user's quality preset and format
+        let (encoded, img_meta) = compress_decoded(img, &load_media_settings())?;
+        (img_meta, encoded.bytes, encoded.extension)
     } else {
-        (w, h)
-    };
-    let resized = if nw != w || nh != h {
-        img.resize(nw, nh, ::image::imageops::FilterType::Lanczos3)
-    } else {
-        img
-    };
-    let (aw, ah) = (resized.width(), resized.height());
-    let img_meta = meta_from(&resized);
-    let rgba = resized.to_rgba8();
-    // Full-resolution sends (compression declined) use higher quality, and keep
-    // a PNG source lossless rather than re-encoding a screenshot to JPEG just to
-    // strip its metadata. Compression still picks the smaller format by content.
-    let (out_bytes, out_ext): (Vec<u8>, &'static str) = if !compress && extension.eq_ignore_ascii_case("png") {
-        (crate::shared::image::encode_png(rgba.as_raw(), aw, ah)?, "png")
-    } else {
-        let quality = if compress { JPEG_QUALITY_STANDARD } else { JPEG_QUALITY_HIGH };
-        let encoded = encode_rgba_auto(rgba.as_raw(), aw, ah, quality)?;
-        (encoded.bytes, encoded.extension)
+        let (w, h) = (img.width(), img.height());
+        let img_meta = meta_from(&img);
+        let rgba = img.to_rgba8();
+        // Full-resolution sends (compression declined) use higher quality, and keep
+        // a PNG source lossless rather than re-encoding a screenshot to JPEG just to
+        // strip its metadata.
+        if extension.eq_ignore_ascii_case("png") {
+            (img_meta, crate::shared::image::encode_png(rgba.as_raw(), w, h)?, "png")
+        } else {
+            let encoded = encode_rgba_auto(rgba.as_raw(), w, h, JPEG_QUALITY_HIGH)?;
+            (img_meta, encoded.bytes, encoded.extension)
+        }
     };
     let mut out_bytes = out_bytes;
 
@@ -170,37 +302,13 @@ pub(super) fn compress_bytes_internal(
     // Load and decode the image (EXIF orientation baked into pixels)
     let img = vector_core::crypto::decode_image_bounded(&bytes)?;
 
-    // Determine target dimensions (max 1920px on longest side)
-    use crate::shared::image::{calculate_resize_dimensions, MAX_DIMENSION};
-    let (width, height) = (img.width(), img.height());
-    let (new_width, new_height) = calculate_resize_dimensions(width, height, MAX_DIMENSION);
-
-    // Resize if needed
-    let resized_img = if new_width != width || new_height != height {
-        img.resize(new_width, new_height, ::image::imageops::FilterType::Lanczos3)
-    } else {
-        img
-    };
-
-    let actual_width = resized_img.width();
-    let actual_height = resized_img.height();
-
-    // Generate metadata from final image only (avoid redundant thumbhash generation)
-    let final_meta = crate::util::generate_thumbhash_from_image(&resized_img)
-        .map(|thumbhash| ImageMetadata {
-            thumbhash,
-            width: actual_width,
-            height: actual_height,
-        });
+    // Resize + encode per the user's quality preset and format. Metadata comes
+    // from the final image only (avoids redundant thumbhash generation).
+    let (encoded, final_meta) = compress_decoded(img, &load_media_settings())?;
 
     // Keep reference to original metadata for fallback path
     let img_meta = final_meta.clone();
 
-    let rgba_img = resized_img.to_rgba8();
-
-    // Encode as PNG (alpha/small) or JPEG (standard)
-    use crate::shared::image::{encode_rgba_auto, JPEG_QUALITY_STANDARD};
-    let encoded = encode_rgba_auto(rgba_img.as_raw(), actual_width, actual_height, JPEG_QUALITY_STANDARD)?;
     let compressed_bytes = encoded.bytes;
     let new_extension = encoded.extension;
 
@@ -302,30 +410,8 @@ pub(super) fn compress_image_internal(file_path: &str) -> Result<CachedCompresse
         // Try to load and decode the image (EXIF orientation baked into pixels)
         let img = vector_core::crypto::decode_image_bounded(&file_data)?;
 
-        // Determine target dimensions (max 1920px on longest side)
-        use crate::shared::image::{calculate_resize_dimensions, MAX_DIMENSION, encode_rgba_auto, JPEG_QUALITY_STANDARD};
-        let (width, height) = (img.width(), img.height());
-        let (new_width, new_height) = calculate_resize_dimensions(width, height, MAX_DIMENSION);
-
-        // Resize if needed
-        let resized_img = if new_width != width || new_height != height {
-            img.resize(new_width, new_height, ::image::imageops::FilterType::Lanczos3)
-        } else {
-            img
-        };
-
-        let actual_width = resized_img.width();
-        let actual_height = resized_img.height();
-
-        let img_meta = crate::util::generate_thumbhash_from_image(&resized_img)
-            .map(|thumbhash| ImageMetadata {
-                thumbhash,
-                width: actual_width,
-                height: actual_height,
-            });
-
-        let rgba_img = resized_img.to_rgba8();
-        let encoded = encode_rgba_auto(rgba_img.as_raw(), actual_width, actual_height, JPEG_QUALITY_STANDARD)?;
+        // Resize + encode per the user's quality preset and format
+        let (encoded, img_meta) = compress_decoded(img, &load_media_settings())?;
         let compressed_bytes = encoded.bytes;
         let extension = encoded.extension;
 
@@ -381,30 +467,8 @@ pub(super) fn compress_image_internal(file_path: &str) -> Result<CachedCompresse
         // Try to load and decode the image (EXIF orientation baked into pixels)
         let img = vector_core::crypto::decode_image_bounded(&bytes)?;
 
-        // Determine target dimensions (max 1920px on longest side)
-        use crate::shared::image::{calculate_resize_dimensions, MAX_DIMENSION, encode_rgba_auto, JPEG_QUALITY_STANDARD};
-        let (width, height) = (img.width(), img.height());
-        let (new_width, new_height) = calculate_resize_dimensions(width, height, MAX_DIMENSION);
-
-        // Resize if needed
-        let resized_img = if new_width != width || new_height != height {
-            img.resize(new_width, new_height, ::image::imageops::FilterType::Lanczos3)
-        } else {
-            img
-        };
-
-        let actual_width = resized_img.width();
-        let actual_height = resized_img.height();
-
-        let img_meta = crate::util::generate_thumbhash_from_image(&resized_img)
-            .map(|thumbhash| ImageMetadata {
-                thumbhash,
-                width: actual_width,
-                height: actual_height,
-            });
-
-        let rgba_img = resized_img.to_rgba8();
-        let encoded = encode_rgba_auto(rgba_img.as_raw(), actual_width, actual_height, JPEG_QUALITY_STANDARD)?;
+        // Resize + encode per the user's quality preset and format
+        let (encoded, img_meta) = compress_decoded(img, &load_media_settings())?;
         let compressed_bytes = encoded.bytes;
         let extension = encoded.extension;
 
@@ -418,4 +482,31 @@ pub(super) fn compress_image_internal(file_path: &str) -> Result<CachedCompresse
             compressed_size,
         })
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_photos_follow_the_chosen_format() {
+        // An opaque gradient big enough to skip the small-image PNG path
+        let (w, h) = (320u32, 240u32);
+        let pixels: Vec<u8> = (0..w * h).flat_map(|i| [(i % w) as u8, (i / w) as u8, 128, 255]).collect();
+
+        let jpeg = encode_compressed(&pixels, w, h, &MediaSettings::default()).unwrap();
+        assert_eq!(jpeg.extension, "jpg");
+
+        let settings = MediaSettings { format: MediaFormat::Webp, ..MediaSettings::default() };
+        let webp = encode_compressed(&pixels, w, h, &settings).unwrap();
+        assert_eq!(webp.extension, "webp");
+        assert_eq!(&webp.bytes[8..12], b"WEBP");
+
+        // Tiny images stay lossless whatever the format
+        let tiny = encode_compressed(&pixels[..16 * 16 * 4], 16, 16, &settings).unwrap();
+        assert_eq!(tiny.extension, "png");
+
+        for quality in [MediaQuality::Small, MediaQuality::Standard, MediaQuality::High] {
+            assert_eq!(MediaQuality::parse(quality.as_str()), Some(quality));
+        }
+    }
+}
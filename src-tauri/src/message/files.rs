@@ -16,7 +16,8 @@ use crate::shared::image::read_file_checked;
 
 use super::types::{CachedCompressedImage, AttachmentFile, COMPRESSION_CACHE, ANDROID_FILE_CACHE};
 use super::compression::{compress_bytes_internal, compress_image_internal};
-use super::sending::{message, MessageSendResult};
+use super::sending::{message, MessageSendResult, TauriSendCallback};
+use vector_core::sending::{OutgoingFile, SendCallback, SendConfig, MAX_FILES_PER_MESSAGE};
 
 #[cfg(target_os = "android")]
 use crate::android::filesystem;
@@ -209,7 +210,7 @@ pub async fn get_cached_bytes_compression_status() -> Result<Option<CompressionE
 
 /// Send cached file (with optional compression and metadata retention)
 #[tauri::command]
-pub async fn send_cached_file(receiver: String, replied_to: String, use_compression: bool, keep_metadata: bool, name_override: String) -> Result<MessageSendResult, String> {
+pub async fn send_cached_file(receiver: String, replied_to: String, use_compression: bool, keep_metadata: bool, name_override: String, caption: Option<String>) -> Result<MessageSendResult, String> {
     use super::compression::process_image_for_send;
 
     // Take the background pre-compression result (stripped + resized), if ready.
@@ -245,7 +246,7 @@ pub async fn send_cached_file(receiver: String, replied_to: String, use_compress
         if !sanitized.is_empty() { attachment_file.name = sanitized; }
     }
 
-    message(receiver, String::new(), replied_to, Some(attachment_file)).await
+    message(receiver, caption.unwrap_or_default(), replied_to, Some(attachment_file)).await
 }
 
 /// Clear cached file bytes
@@ -284,7 +285,8 @@ pub async fn send_file_bytes(
     file_name: String,
     use_compression: bool,
     keep_metadata: bool,
-    name_override: String
+    name_override: String,
+    caption: Option<String>,
 ) -> Result<MessageSendResult, String> {
     use super::compression::process_image_for_send;
 
@@ -325,11 +327,13 @@ pub async fn send_file_bytes(
         if !sanitized.is_empty() { attachment_file.name = sanitized; }
     }
 
-    message(receiver, String::new(), replied_to, Some(attachment_file)).await
+    message(receiver, caption.unwrap_or_default(), replied_to, Some(attachment_file)).await
 }
 
-#[tauri::command]
-pub async fn file_message(receiver: String, replied_to: String, file_path: String, keep_metadata: bool, name_override: String) -> Result<MessageSendResult, String> {
+/// Load a picked file for sending: read it (Android: from the pick-time cache or the content
+/// URI), and for images bake orientation, strip metadata unless `keep_metadata`, and downscale +
+/// recompress when `use_compression`.
+fn load_file_for_send(file_path: &str, use_compression: bool, keep_metadata: bool) -> Result<AttachmentFile, String> {
     // Extract filename from the path
     let file_name = std::path::Path::new(&file_path)
         .file_name()
@@ -341,7 +345,7 @@ pub async fn file_message(receiver: String, replied_to: String, file_path: Strin
     let mut attachment_file = {
         #[cfg(not(target_os = "android"))]
         {
-            let file_bytes = read_file_checked(&file_path)?;
+            let file_bytes = read_file_checked(file_path)?;
 
             let extension = file_path
                 .rsplit('.')
@@ -361,7 +365,7 @@ pub async fn file_message(receiver: String, replied_to: String, file_path: Strin
             // First check if we have cached bytes for this URI
             // Take ownership from cache to avoid clone - bytes already Arc
             let mut cache = ANDROID_FILE_CACHE.lock().unwrap();
-            if let Some((bytes, extension, cached_name, _)) = cache.remove(&file_path) {
+            if let Some((bytes, extension, cached_name, _)) = cache.remove(file_path) {
                 drop(cache);
                 AttachmentFile {
                     bytes,
@@ -374,10 +378,10 @@ pub async fn file_message(receiver: String, replied_to: String, file_path: Strin
                 // Check if this is a content:// URI or a regular file path
                 if file_path.starts_with("content://") {
                     // Content URI - use Android ContentResolver
-                    filesystem::read_android_uri(file_path)?
+                    filesystem::read_android_uri(file_path.to_string())?
                 } else {
                     // Regular file path (e.g., marketplace apps) - use standard file I/O
-                    let file_bytes = read_file_checked(&file_path)?;
+                    let file_bytes = read_file_checked(file_path)?;
 
                     let extension = file_path
                         .rsplit('.')
@@ -396,18 +400,27 @@ pub async fn file_message(receiver: String, replied_to: String, file_path: Strin
         }
     };
 
-    // Images (no compression here): strip metadata (default) or keep the
-    // original bytes untouched. Either way orientation is baked and preview
+    // Images: compress per the media settings if asked, and strip metadata
+    // (default) or keep it. Either way orientation is baked and preview
     // metadata is generated.
     if matches!(attachment_file.extension.as_str(), "png" | "jpg" | "jpeg" | "gif" | "webp" | "tiff" | "tif" | "ico") {
         let processed = super::compression::process_image_for_send(
             attachment_file.bytes.clone(), &attachment_file.extension,
-            /* use_compression */ false, keep_metadata, None,
+            use_compression, keep_metadata, None,
         )?;
         attachment_file.bytes = processed.bytes;
         attachment_file.extension = processed.extension;
         attachment_file.img_meta = processed.img_meta;
     }
+    Ok(attachment_file)
+}
+
+/// Send one file from disk. Images go out as picked unless `use_compression` asks for the
+/// settings' downscale + recompress (callers pass the user's default; the preview passes its
+/// toggle, and retries of already-compressed files leave it off).
+#[tauri::command]
+pub async fn file_message(receiver: String, replied_to: String, file_path: String, use_compression: Option<bool>, keep_metadata: bool, name_override: String, caption: Option<String>) -> Result<MessageSendResult, String> {
+    let mut attachment_file = load_file_for_send(&file_path, use_compression.unwrap_or(false), keep_metadata)?;
 
     // Apply user-edited name override (if any)
     if !name_override.is_empty() {
@@ -416,7 +429,111 @@ pub async fn file_message(receiver: String, replied_to: String, file_path: Strin
     }
 
     // Message the file to the intended user
-    message(receiver, String::new(), replied_to, Some(attachment_file)).await
+    message(receiver, caption.unwrap_or_default(), replied_to, Some(attachment_file)).await
+}
+
+/// Check a batch of file paths before anything uploads: drop duplicates, enforce the per-message
+/// cap, and require each entry to be a non-empty regular file that some configured media server
+/// will likely accept. The first offending file fails the whole batch, naming the file.
+fn validate_batch_paths(file_paths: Vec<String>) -> Result<Vec<String>, String> {
+    let mut paths: Vec<String> = Vec::with_capacity(file_paths.len());
+    for path in file_paths {
+        if !path.is_empty() && !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    if paths.is_empty() {
+        return Err("No files to send".to_string());
+    }
+    if paths.len() > MAX_FILES_PER_MESSAGE {
+        return Err(format!("A message can carry at most {} files", MAX_FILES_PER_MESSAGE));
+    }
+
+    let servers = vector_core::state::get_blossom_servers();
+    for path in &paths {
+        // Android content:// URIs have no filesystem metadata; the loader validates them.
+        if path.starts_with("content://") {
+            continue;
+        }
+        let name = crate::commands::attachments::sanitize_filename(
+            std::path::Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path),
+        );
+        let metadata = std::fs::metadata(path).map_err(|_| format!("{} could not be read", name))?;
+        if !metadata.is_file() {
+            return Err(format!("{} is not a file", name));
+        }
+        if metadata.len() == 0 {
+            return Err(format!("{} is empty", name));
+        }
+        let extension = path.rsplit('.').next().unwrap_or("bin").to_lowercase();
+        let mime = vector_core::crypto::mime_from_extension(&extension);
+        if !vector_core::blossom_capabilities::any_server_likely_accepts(&servers, mime, true, metadata.len()) {
+            return Err(format!("{} is too large for your media servers", name));
+        }
+    }
+    Ok(paths)
+}
+
+/// Send a batch of picked, dropped or pasted files to any chat as ONE message: a DM gallery
+/// (see `vector_core::sending::send_files_dm`) or a Community multi-attachment post.
+///
+/// The batch is validated up front, uploads one file at a time under a single pending message
+/// with one aggregated `attachment_upload_progress` stream, and is cancelled as a whole with
+/// `cancel_upload(pending_id)`. `view_once` (DMs only) sends the files as view-once media.
+#[tauri::command]
+pub async fn send_files(
+    chat_id: String,
+    file_paths: Vec<String>,
+    caption: Option<String>,
+    keep_metadata: bool,
+    replied_to: Option<String>,
+    view_once: Option<bool>,
+) -> Result<MessageSendResult, String> {
+    let file_paths = validate_batch_paths(file_paths)?;
+    let view_once = view_once.unwrap_or(false);
+    let use_compression = use_compression.unwrap_or(false);
+    let caption = caption.map(|c| c.trim().to_string()).unwrap_or_default();
+
+    let is_community = {
+        let state = crate::STATE.lock().await;
+        match state.get_chat(&chat_id) {
+            Some(chat) => chat.is_community(),
+            None => !vector_core::ids::is_dm_chat_id(&chat_id),
+        }
+    };
+    if is_community {
+        if view_once {
+            return Err("View-once media is only supported in direct messages".to_string());
+        }
+        let replied_to = replied_to.filter(|r| !r.is_empty());
+        crate::commands::community::send_community_files(
+            chat_id, caption, file_paths, Vec::new(), use_compression, keep_metadata, replied_to,
+        ).await?;
+        // The Community path drives its own pending→sent lifecycle (no id to finalize).
+        return Ok(MessageSendResult { pending_id: String::new(), event_id: None });
+    }
+
+    let files = file_paths.iter()
+        .map(|path| load_file_for_send(path, use_compression, keep_metadata).map(|file| OutgoingFile {
+            bytes: file.bytes,
+            filename: file.name,
+            extension: file.extension,
+        }))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let config = SendConfig {
+        expiration: vector_core::self_destruct::resolve_send_expiry(&chat_id),
+        view_once,
+        ..SendConfig::gui()
+    };
+    let callback: Arc<dyn SendCallback> = Arc::new(TauriSendCallback);
+    let caption = if caption.is_empty() { None } else { Some(caption.as_str()) };
+    let result = if chat_id == vector_core::device_drop::CHAT_ID {
+        vector_core::device_drop::send_files(files, caption, config, callback).await?
+    } else {
+        vector_core::sending::send_files_dm(&chat_id, files, caption, &config, callback).await?
+    };
+    Ok(MessageSendResult { pending_id: result.pending_id, event_id: result.event_id })
 }
 
 /// File info structure for the frontend
@@ -674,6 +791,96 @@ pub async fn start_image_precompression(file_path: String) -> Result<(), String>
     Ok(())
 }
 
+/// The send-time processing choices offered by the file preview.
+#[derive(serde::Deserialize, Clone, Copy, Default)]
+pub struct AttachmentPolicy {
+    #[serde(default)]
+    pub compress: bool,
+    #[serde(default)]
+    pub keep_metadata: bool,
+}
+
+/// What a file will look like on the wire under a given [`AttachmentPolicy`].
+#[derive(serde::Serialize)]
+pub struct AttachmentPreparation {
+    pub original_size: u64,
+    /// Size of the processed file that would be uploaded.
+    pub projected_size: u64,
+    /// Output extension; re-encoding can change it (e.g. png → jpg).
+    pub extension: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Small data-URI thumbnail of the processed output (images only).
+    pub preview: Option<String>,
+}
+
+/// Dry-run the send pipeline for `file_path`: process it exactly as sending would under
+/// `policy` and report the result, without caching or uploading anything. Lets the preview
+/// say "will send as 1.2 MB JPG" and offer the original instead.
+#[tauri::command]
+pub async fn prepare_attachment(file_path: String, policy: AttachmentPolicy) -> Result<AttachmentPreparation, String> {
+    tokio::task::spawn_blocking(move || prepare_attachment_blocking(&file_path, policy))
+        .await
+        .map_err(|e| format!("Preparation task failed: {}", e))?
+}
+
+fn prepare_attachment_blocking(file_path: &str, policy: AttachmentPolicy) -> Result<AttachmentPreparation, String> {
+    use crate::shared::image::{encode_rgba_auto, JPEG_QUALITY_PREVIEW};
+
+    #[cfg(not(target_os = "android"))]
+    let (bytes, extension) = (
+        Arc::new(read_file_checked(file_path)?),
+        file_path.rsplit('.').next().unwrap_or("bin").to_lowercase(),
+    );
+    #[cfg(target_os = "android")]
+    let (bytes, extension) = {
+        let cached = ANDROID_FILE_CACHE.lock().unwrap().get(file_path).map(|(b, e, _, _)| (b.clone(), e.clone()));
+        match cached {
+            Some(entry) => entry,
+            None => {
+                let file = filesystem::read_android_uri(file_path.to_string())?;
+                (file.bytes, file.extension)
+            }
+        }
+    };
+    let original_size = bytes.len() as u64;
+
+    if !matches!(extension.as_str(), "png" | "jpg" | "jpeg" | "gif" | "webp" | "tiff" | "tif" | "ico") {
+        return Ok(AttachmentPreparation {
+            original_size, projected_size: original_size, extension,
+            width: None, height: None, preview: None,
+        });
+    }
+
+    let processed = super::compression::prepare_outbound_image(bytes, &extension, policy.compress, policy.keep_metadata)?;
+    let preview = vector_core::crypto::decode_image_bounded(&processed.bytes).ok().and_then(|img| {
+        let thumb = img.thumbnail(320, 320);
+        encode_rgba_auto(thumb.to_rgba8().as_raw(), thumb.width(), thumb.height(), JPEG_QUALITY_PREVIEW)
+            .ok()
+            .map(|encoded| encoded.to_data_uri())
+    });
+    Ok(AttachmentPreparation {
+        original_size,
+        projected_size: processed.compressed_size,
+        extension: processed.extension,
+        width: processed.img_meta.as_ref().map(|m| m.width),
+        height: processed.img_meta.as_ref().map(|m| m.height),
+        preview,
+    })
+}
+
+/// Outbound image quality, format and compress-by-default preference (Settings > Storage).
+#[tauri::command]
+pub fn get_media_settings() -> super::compression::MediaSettings {
+    super::compression::load_media_settings()
+}
+
+#[tauri::command]
+pub fn set_media_settings(settings: super::compression::MediaSettings) -> Result<(), String> {
+    crate::account_manager::get_current_account()?;
+    super::compression::save_media_settings(&settings)
+}
+
 /// Get the compression status/result for a file
 #[tauri::command]
 pub async fn get_compression_status(file_path: String) -> Result<Option<CompressionEstimate>, String> {
@@ -1016,7 +1223,7 @@ pub fn cleanup_zip() -> Result<(), String> {
 
 /// Send a file using the cached compressed version if available
 #[tauri::command]
-pub async fn send_cached_compressed_file(receiver: String, replied_to: String, file_path: String, keep_metadata: bool, name_override: String) -> Result<MessageSendResult, String> {
+pub async fn send_cached_compressed_file(receiver: String, replied_to: String, file_path: String, keep_metadata: bool, name_override: String, caption: Option<String>) -> Result<MessageSendResult, String> {
     use super::compression::process_image_for_send;
 
     let file_name = std::path::Path::new(&file_path)
@@ -1069,5 +1276,5 @@ pub async fn send_cached_compressed_file(receiver: String, replied_to: String, f
         let sanitized = crate::commands::attachments::sanitize_filename(&name_override);
         if !sanitized.is_empty() { attachment_file.name = sanitized; }
     }
-    message(receiver, String::new(), replied_to, Some(attachment_file)).await
+    message(receiver, caption.unwrap_or_default(), replied_to, Some(attachment_file)).await
 }
@@ -83,32 +83,75 @@ pub async fn react_to_message(
     }
 }
 
+/// Copy a message's `index`th fenced code block (0-based) to the clipboard and return its
+/// exact text. Read from the held message, or the database when it isn't loaded in memory.
+/// Android has no native text clipboard here, so the frontend writes the returned text.
 #[tauri::command]
-pub async fn fetch_msg_metadata(chat_id: String, msg_id: String) -> bool {
-    // Find the message we're extracting metadata from
-    let text = {
+pub async fn copy_code_block<R: tauri::Runtime>(handle: tauri::AppHandle<R>, message_id: String, index: usize) -> Result<String, String> {
+    let held = {
         let state = STATE.lock().await;
-        let chat_idx = state.chats.iter().position(|c| c.id == chat_id);
-        if let Some(idx) = chat_idx {
-            state.chats[idx].messages.find_by_hex_id(&msg_id)
-                .map(|m| m.content.clone())
-        } else { None }
+        state.find_message(&message_id).map(|(_, m)| m.content)
     };
-
-    // Message might not be in backend state (e.g., loaded via get_messages_around_id)
-    let text = match text {
-        Some(t) => t,
-        None => return false,
+    let content = match held {
+        Some(content) => content,
+        None => vector_core::db::events::get_reply_contexts(std::slice::from_ref(&message_id)).await?
+            .remove(&message_id)
+            .map(|ctx| ctx.content)
+            .ok_or("Message not found")?,
     };
+    let code = vector_core::code_blocks::code_blocks(&content)
+        .into_iter()
+        .nth(index)
+        .map(|block| block.code)
+        .ok_or("Code block not found")?;
+
+    #[cfg(not(target_os = "android"))]
+    {
+        use tauri_plugin_clipboard_manager::ClipboardExt;
+        handle.clipboard().write_text(code.clone())
+            .map_err(|e| format!("Failed to write clipboard: {:?}", e))?;
+    }
+    #[cfg(target_os = "android")]
+    let _ = &handle;
+
+    Ok(code)
+}
+
+/// Resolve a `nostr:` reference (`nprofile`, `nevent`, `note`, `naddr`) into a preview card.
+/// Fetched from our own relays only and cached for the session. With `chat_id`, the chat's
+/// remote-content policy applies (`user_initiated` = the user tapped to load it).
+#[tauri::command]
+pub async fn resolve_nostr_uri(
+    uri: String,
+    chat_id: Option<String>,
+    user_initiated: Option<bool>,
+) -> Result<vector_core::nostr_uri::NostrPreview, String> {
+    if let Some(chat_id) = chat_id {
+        vector_core::remote_content::gate(&chat_id, user_initiated.unwrap_or(false))?;
+    }
+    vector_core::nostr_uri::resolve(&uri).await
+}
+
+/// Who reacted with what on one message, for the "tap to see who reacted" sheet: per-emoji
+/// tallies plus one entry per (reactor, emoji) resolved to cached profiles, relay duplicates folded.
+#[tauri::command]
+pub async fn get_message_reactions(message_id: String) -> Result<vector_core::db::events::MessageReactions, String> {
+    vector_core::db::events::get_message_reactions(&message_id).await
+}
+
+/// The preview for the first previewable URL in `text` (of at most a few tried), from the link
+/// preview cache or fetched through the proxy and cached. `None` when previews are switched off.
+async fn link_preview_for(text: &str) -> Option<vector_core::SiteMetadata> {
+    if !vector_core::link_preview::enabled() {
+        return None;
+    }
 
     // Extract URLs from the message. Markdown links contribute their DESTINATION
     // only: [https://trusted.com](https://evil.io) must never preview the claimed
-    // site while the click goes elsewhere.
+    // site while the click goes elsewhere. URLs inside code are literal text.
     const MAX_URLS_TO_TRY: usize = 3;
-    let urls = util::extract_https_urls(&vector_core::net::strip_md_link_claims(&text));
-    if urls.is_empty() {
-        return false;
-    }
+    let prose = vector_core::code_blocks::strip_code(text);
+    let urls = util::extract_https_urls(&vector_core::net::strip_md_link_claims(&prose));
 
     // Only try the first few URLs
     for url in urls.into_iter().take(MAX_URLS_TO_TRY) {
@@ -119,42 +162,77 @@ pub async fn fetch_msg_metadata(chat_id: String, msg_id: String) -> bool {
         {
             continue;
         }
-        match net::fetch_site_metadata(&url).await {
-            Ok(metadata) => {
-                let has_content = metadata.og_title.is_some()
-                    || metadata.og_description.is_some()
-                    || metadata.og_image.is_some()
-                    || metadata.title.is_some()
-                    || metadata.description.is_some();
-
-                // Extracted metadata!
-                if has_content {
-                    // Update message with metadata
-                    let msg_for_save = {
-                        let mut state = STATE.lock().await;
-                        state.update_message_in_chat(&chat_id, &msg_id, |msg| {
-                            msg.preview_metadata = Some(Box::new(metadata));
-                        })
-                    };
-
-                    if let Some(msg) = msg_for_save {
-                        let handle = TAURI_APP.get().unwrap();
-                        handle.emit("message_update", serde_json::json!({
-                            "old_id": &msg_id,
-                            "message": &msg,
-                            "chat_id": &chat_id
-                        })).unwrap();
-                        let _ = crate::db::save_message(&chat_id, &msg).await;
-                        return true;
-                    }
-                }
-            }
-            Err(_) => continue,
+        if let Some(metadata) = vector_core::link_preview::cached(&url) {
+            return Some(metadata);
         }
+        let Ok(metadata) = net::fetch_site_metadata(&url).await else { continue };
+        let has_content = metadata.og_title.is_some()
+            || metadata.og_description.is_some()
+            || metadata.og_image.is_some()
+            || metadata.title.is_some()
+            || metadata.description.is_some();
+        if has_content {
+            let _ = vector_core::link_preview::store(&url, &metadata);
+            return Some(metadata);
+        }
+    }
+    None
+}
+
+/// Fetch a link preview for a message's first previewable URL, subject to the chat's
+/// remote-content policy (`user_initiated` = the user tapped to load it).
+#[tauri::command]
+pub async fn fetch_msg_metadata(chat_id: String, msg_id: String, user_initiated: Option<bool>) -> bool {
+    if vector_core::remote_content::gate(&chat_id, user_initiated.unwrap_or(false)).is_err() {
+        return false;
+    }
+
+    // Find the message we're extracting metadata from
+    let text = {
+        let state = STATE.lock().await;
+        let chat_idx = state.chats.iter().position(|c| c.id == chat_id);
+        if let Some(idx) = chat_idx {
+            state.chats[idx].messages.find_by_hex_id(&msg_id)
+                .map(|m| m.content.clone())
+        } else { None }
+    };
+
+    // Message might not be in backend state (e.g., loaded via get_messages_around_id)
+    let text = match text {
+        Some(t) => t,
+        None => return false,
+    };
+
+    let Some(metadata) = link_preview_for(&text).await else { return false };
+
+    // Update message with metadata
+    let msg_for_save = {
+        let mut state = STATE.lock().await;
+        state.update_message_in_chat(&chat_id, &msg_id, |msg| {
+            msg.preview_metadata = Some(Box::new(metadata));
+        })
+    };
+
+    if let Some(msg) = msg_for_save {
+        let handle = TAURI_APP.get().unwrap();
+        handle.emit("message_update", serde_json::json!({
+            "old_id": &msg_id,
+            "message": &msg,
+            "chat_id": &chat_id
+        })).unwrap();
+        let _ = crate::db::save_message(&chat_id, &msg).await;
+        return true;
     }
     false
 }
 
+/// Fetch and cache the preview for a link in the composer, so it can go out with the message
+/// and recipients needn't fetch the link themselves. True when a preview is ready.
+#[tauri::command]
+pub async fn prefetch_link_preview(text: String) -> bool {
+    link_preview_for(&text).await.is_some()
+}
+
 /// Forward an attachment from one message to a different chat
 /// This is used for "Play & Invite" functionality in Mini Apps
 /// Returns the new message ID if successful
@@ -187,7 +265,7 @@ pub async fn forward_attachment(
     
     // Send the file to the target chat using the existing file_message function
     // The hash-based reuse will automatically avoid re-uploading
-    file_message(target_chat_id, String::new(), attachment_path, false, String::new()).await?;
+    file_message(target_chat_id, String::new(), attachment_path, None, false, String::new(), None).await?;
     
     // Return success - the new message ID will be emitted via the normal message flow
     Ok("forwarded".to_string())
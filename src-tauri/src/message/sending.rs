@@ -467,6 +467,7 @@ pub async fn delete_failed_message(message_id: String) -> Result<(), String> {
     };
 
     if let Some((chat_id, msg)) = removed {
+        vector_core::sending::discard_failed_upload(&message_id);
         // Best-effort: drop the staged preview copy. Canonicalize both sides
         // so a stale or symlinked `att.path` can't follow out of download_dir.
         if let Ok(canonical_dl_dir) = std::fs::canonicalize(vector_core::db::get_download_dir()) {
@@ -537,6 +538,16 @@ pub async fn retry_failed_dm(receiver: String, message_id: String) -> Result<boo
     vector_core::sending::resend_failed_dm(&receiver, &message_id, &config, callback).await
 }
 
+/// Retry a file DM whose uploads gave up, by the token of its `upload_failed` event: the same
+/// message is picked back up and only the files still missing are uploaded. Errs when the token
+/// is unknown (e.g. after a restart), in which case the caller falls back to a fresh send.
+#[tauri::command]
+pub async fn retry_failed_upload(token: String) -> Result<MessageSendResult, String> {
+    let callback: Arc<dyn SendCallback> = Arc::new(TauriSendCallback);
+    let result = vector_core::sending::retry_failed_upload(&token, callback).await?;
+    Ok(MessageSendResult { pending_id: result.pending_id, event_id: result.event_id })
+}
+
 /// Cancel an in-progress file upload by setting its cancel flag.
 /// Removes the pending message from state and emits `message_removed`.
 #[tauri::command]
@@ -638,7 +649,7 @@ pub async fn cancel_upload(pending_id: String) -> Result<(), String> {
 }
 
 /// Headless text-only reply: sends a DM.
-/// Used by Android notification inline-reply (JNI).
+/// Used by notification inline-reply (Android via JNI, macOS via `notification_actions`).
 #[allow(dead_code)]
 pub async fn send_text_reply_headless(chat_id: &str, content: &str) -> Result<String, String> {
     let config = SendConfig {
@@ -663,7 +674,7 @@ pub async fn message(receiver: String, content: String, replied_to: String, file
         if let Some(chat) = state.get_chat(&receiver) {
             chat.is_community()
         } else {
-            !receiver.starts_with("npub1")
+            !vector_core::ids::is_dm_chat_id(&receiver)
         }
     };
 
@@ -700,6 +711,34 @@ pub async fn message(receiver: String, content: String, replied_to: String, file
     Err("Group chats are no longer supported".to_string())
 }
 
+/// Share a location into a DM. `live` keeps it updating from `report_location` for
+/// `live_secs` (default one hour, capped by vector-core).
+#[tauri::command]
+pub async fn send_location(chat_id: String, lat: f64, lon: f64, accuracy: f64, live: bool, live_secs: Option<u64>) -> Result<MessageSendResult, String> {
+    let location = vector_core::location::Location::new(lat, lon, accuracy)?;
+    let live_for = live.then(|| live_secs.unwrap_or(60 * 60));
+    let config = SendConfig {
+        expiration: vector_core::self_destruct::resolve_send_expiry(&chat_id),
+        ..SendConfig::gui()
+    };
+    let callback: Arc<dyn SendCallback> = Arc::new(TauriSendCallback);
+    let result = vector_core::location::share(&chat_id, location, live_for, &config, callback).await?;
+    Ok(MessageSendResult { pending_id: result.pending_id, event_id: result.event_id })
+}
+
+/// The device's current position, for the running live location shares. Returns whether any
+/// is still running, so the frontend can stop watching the position once none is.
+#[tauri::command]
+pub async fn report_location(lat: f64, lon: f64, accuracy: f64) -> Result<bool, String> {
+    Ok(vector_core::location::report_position(vector_core::location::Location::new(lat, lon, accuracy)?))
+}
+
+/// End our live location share in `chat_id`.
+#[tauri::command]
+pub async fn stop_live_location(chat_id: String) -> Result<(), String> {
+    vector_core::location::stop(&chat_id).await
+}
+
 #[tauri::command]
 pub async fn paste_message<R: Runtime>(handle: AppHandle<R>, receiver: String, replied_to: String, transparent: bool) -> Result<MessageSendResult, String> {
     // Platform-specific clipboard reading
@@ -772,20 +811,20 @@ pub async fn paste_message<R: Runtime>(handle: AppHandle<R>, receiver: String, r
     message(receiver, String::new(), replied_to, Some(attachment_file)).await
 }
 
-pub async fn voice_message(receiver: String, replied_to: String, bytes: Vec<u8>) -> Result<MessageSendResult, String> {
+pub async fn voice_message(receiver: String, replied_to: String, bytes: Vec<u8>, caption: Option<String>) -> Result<MessageSendResult, String> {
     // Community channels route through the Concord file-bytes envelope; the DM `message`
     // command rejects channel ids. Mirrors how text/file sends fan out by chat type.
     let is_community = {
         let state = STATE.lock().await;
         match state.get_chat(&receiver) {
             Some(chat) => chat.is_community(),
-            None => !receiver.starts_with("npub1"),
+            None => !vector_core::ids::is_dm_chat_id(&receiver),
         }
     };
     if is_community {
         let reply = if replied_to.is_empty() { None } else { Some(replied_to) };
         // Empty-name attachment → renderer shows the voice player + transcription, not a file row.
-        crate::commands::community::send_community_voice_bytes(receiver, bytes, reply).await?;
+        crate::commands::community::send_community_voice_bytes(receiver, bytes, caption.unwrap_or_default(), reply).await?;
         // The Community path drives its own pending→sent lifecycle (no id to finalize).
         return Ok(MessageSendResult { pending_id: String::new(), event_id: None });
     }
@@ -799,5 +838,5 @@ pub async fn voice_message(receiver: String, replied_to: String, bytes: Vec<u8>)
     };
 
     // Message the file to the intended user
-    message(receiver, String::new(), replied_to, Some(attachment_file)).await
+    message(receiver, caption.unwrap_or_default(), replied_to, Some(attachment_file)).await
 }
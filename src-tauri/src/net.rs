@@ -104,6 +104,57 @@ pub async fn download<R: tauri::Runtime>(
     download_with_reporter(content_url, &reporter, timeout).await
 }
 
+/// Reports one chunk's progress as progress through the whole chunked blob.
+struct ChunkProgressReporter<'a, P: ProgressReporter> {
+    inner: &'a P,
+    /// Bytes of the blob fetched before this chunk.
+    base: u64,
+    chunk_size: u64,
+    total: u64,
+}
+
+impl<'a, P: ProgressReporter> ProgressReporter for ChunkProgressReporter<'a, P> {
+    fn report_progress(&self, _percentage: Option<u8>, bytes_downloaded: Option<u64>, bytes_per_sec: Option<f64>) -> Result<(), &'static str> {
+        let done = self.base + bytes_downloaded.unwrap_or(0).min(self.chunk_size);
+        let percentage = (done * 100 / self.total.max(1)).min(100) as u8;
+        self.inner.report_progress(Some(percentage), Some(done), bytes_per_sec)
+    }
+
+    fn report_complete(&self) -> Result<(), &'static str> {
+        // Only the last chunk completes the blob; the caller reports it
+        Ok(())
+    }
+}
+
+/// Fetch and reassemble a chunked upload (`vector_core::blossom::ChunkManifest`), verifying every
+/// chunk against the hash and size the manifest lists.
+pub async fn download_chunked<R: tauri::Runtime>(
+    manifest: &vector_core::blossom::ChunkManifest,
+    handle: &AppHandle<R>,
+    attachment_id: &str,
+) -> Result<Vec<u8>, &'static str> {
+    if manifest.size > MAX_DOWNLOAD_BYTES {
+        return Err("File too large");
+    }
+    let reporter = TauriProgressReporter::new(handle, attachment_id);
+    let mut data = Vec::with_capacity(min(manifest.size, MAX_PREALLOC_BYTES) as usize);
+    for chunk in &manifest.chunks {
+        let chunk_reporter = ChunkProgressReporter {
+            inner: &reporter,
+            base: data.len() as u64,
+            chunk_size: chunk.size,
+            total: manifest.size,
+        };
+        let bytes = download_with_reporter(&chunk.url, &chunk_reporter, None).await?;
+        if bytes.len() as u64 != chunk.size || vector_core::crypto::sha256_hex(&bytes) != chunk.sha256 {
+            return Err("Chunk does not match its manifest");
+        }
+        data.extend_from_slice(&bytes);
+    }
+    reporter.report_complete()?;
+    Ok(data)
+}
+
 /// Determine a remote file's size without downloading it.
 /// Tries HTTP HEAD first, falls back to a 2-byte Range request.
 /// Returns None if size cannot be determined.
@@ -198,6 +249,77 @@ pub async fn download_with_reporter(
     }
 }
 
+/// Bytes fetched per range request while streaming a blob.
+const STREAM_RANGE_BYTES: u64 = 64 * 1024;
+
+/// Fetch a blob front to back in small ranges, handing each piece to `on_piece` as it lands, so
+/// media can start playing before the download finishes. A server that ignores `Range` has its
+/// whole-body reply streamed the same way. `on_piece` returning false stops the fetch. Returns the
+/// complete blob.
+pub async fn stream_blob(url: &str, mut on_piece: impl FnMut(&[u8]) -> bool) -> Result<Vec<u8>, &'static str> {
+    validate_url_not_private(url)?;
+    let client = vector_core::net::build_http_client(std::time::Duration::from_secs(300))
+        .map_err(|_| "Failed to create HTTP client")?;
+    let total = get_remote_file_size(url).await;
+    if matches!(total, Some(size) if size > MAX_DOWNLOAD_BYTES) {
+        return Err("File exceeds the maximum download size");
+    }
+
+    let mut data = Vec::with_capacity(total.unwrap_or(0).min(MAX_PREALLOC_BYTES) as usize);
+    loop {
+        let from = data.len() as u64;
+        if matches!(total, Some(size) if from >= size) {
+            break;
+        }
+        let to = from + STREAM_RANGE_BYTES - 1;
+        let res = client.get(url).header("Range", format!("bytes={}-{}", from, to)).send().await
+            .map_err(|e| {
+                vector_core::log_warn!("[AttachmentStream] range request failed for {}: {}", url, e);
+                "Failed to download chunk"
+            })?;
+        match res.status().as_u16() {
+            206 => {
+                let piece = res.bytes().await.map_err(|_| "Failed to read chunk bytes")?;
+                if piece.is_empty() || piece.len() as u64 > STREAM_RANGE_BYTES {
+                    return Err("Server sent a malformed range");
+                }
+                if !on_piece(&piece) {
+                    return Err("Stream stopped");
+                }
+                data.extend_from_slice(&piece);
+                // Without a known size, a short range is the last one
+                if total.is_none() && (piece.len() as u64) < STREAM_RANGE_BYTES {
+                    break;
+                }
+            }
+            200 if from == 0 => {
+                let mut body = res.bytes_stream();
+                while let Some(item) = body.next().await {
+                    let piece = item.map_err(|_| "Failed to read chunk bytes")?;
+                    if !on_piece(&piece) {
+                        return Err("Stream stopped");
+                    }
+                    data.extend_from_slice(&piece);
+                    if data.len() as u64 > MAX_DOWNLOAD_BYTES {
+                        return Err("File exceeds the maximum download size");
+                    }
+                }
+                break;
+            }
+            // Past the end of a blob whose size we couldn't learn up front
+            416 if total.is_none() && from > 0 => break,
+            status => {
+                vector_core::log_debug!("[AttachmentStream] HTTP {} (expected 206) for {}", status, url);
+                return Err("Server did not honor range request");
+            }
+        }
+        if data.len() as u64 > MAX_DOWNLOAD_BYTES {
+            return Err("File exceeds the maximum download size");
+        }
+    }
+    Ok(data)
+}
+
 /// Checks if the server supports range requests
 async fn supports_range(url: &str, client: &Client) -> bool {
     if let Ok(res) = client.head(url).send().await {
@@ -216,7 +338,85 @@ async fn supports_range(url: &str, client: &Client) -> bool {
     false
 }
 
-/// Downloads using HTTP range requests with adaptive chunk sizing and speed tracking
+/// Files at least this large are fetched as parallel range segments.
+const PARALLEL_MIN_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Concurrent segments of a parallel download.
+const PARALLEL_SEGMENTS: u64 = 4;
+
+/// Files at least this large keep their partial bytes on disk, so a dropped download (or a
+/// restart) resumes where it stopped instead of starting over.
+const RESUMABLE_MIN_BYTES: u64 = 1024 * 1024;
+
+/// Tries per range request before the download gives up; what arrived stays on disk.
+const RANGE_ATTEMPTS: u32 = 3;
+
+/// Partial files untouched this long are abandoned.
+const PARTIAL_TTL: std::time::Duration = std::time::Duration::from_secs(7 * 24 * 3600);
+
+/// Where partial downloads live (`<app data>/cache/partial_downloads`), with stale ones pruned.
+/// `None` leaves the download in memory only.
+fn partial_download_dir() -> Option<std::path::PathBuf> {
+    let dir = vector_core::db::get_app_data_dir().ok()?.join("cache").join("partial_downloads");
+    std::fs::create_dir_all(&dir).ok()?;
+    if let Ok(entries) = std::fs::read_dir(&dir) {
+        for entry in entries.flatten() {
+            let stale = entry.metadata()
+                .and_then(|m| m.modified())
+                .is_ok_and(|t| t.elapsed().is_ok_and(|age| age > PARTIAL_TTL));
+            if stale {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+    Some(dir)
+}
+
+/// Progress across every segment of one download, reported as a single figure.
+struct SegmentedProgress<'a, P: ProgressReporter> {
+    reporter: &'a P,
+    total: u64,
+    downloaded: std::sync::atomic::AtomicU64,
+    /// Bytes fetched by this run (resumed bytes excluded), for the speed figure.
+    fetched: std::sync::atomic::AtomicU64,
+    started: std::time::Instant,
+    last_percentage: std::sync::atomic::AtomicU8,
+}
+
+impl<'a, P: ProgressReporter> SegmentedProgress<'a, P> {
+    fn new(reporter: &'a P, total: u64) -> Self {
+        Self {
+            reporter,
+            total,
+            downloaded: Default::default(),
+            fetched: Default::default(),
+            started: std::time::Instant::now(),
+            last_percentage: Default::default(),
+        }
+    }
+
+    /// Count bytes a previous attempt already saved.
+    fn resumed(&self, bytes: u64) {
+        self.downloaded.fetch_add(bytes, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Count freshly downloaded bytes, emitting when the percentage moves.
+    fn fetched(&self, bytes: u64) -> Result<(), &'static str> {
+        use std::sync::atomic::Ordering;
+        let downloaded = self.downloaded.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let fetched = self.fetched.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let percentage = (downloaded * 100 / self.total.max(1)).min(100) as u8;
+        if self.last_percentage.fetch_max(percentage, Ordering::Relaxed) < percentage {
+            let elapsed = self.started.elapsed().as_secs_f64();
+            let speed = (elapsed > 0.0).then(|| fetched as f64 / elapsed);
+            self.reporter.report_progress(Some(percentage), Some(downloaded), speed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Downloads using HTTP range requests: in parallel segments for large files, resuming from
+/// partial files an earlier attempt left behind.
 async fn download_with_ranges(
     client: &Client,
     url: &str,
@@ -226,32 +426,93 @@ async fn download_with_ranges(
     if total_size > MAX_DOWNLOAD_BYTES {
         return Err("File exceeds the maximum download size");
     }
+    let segments = if total_size >= PARALLEL_MIN_BYTES { PARALLEL_SEGMENTS } else { 1 };
+    let segment_len = total_size.div_ceil(segments);
+    // Keyed by URL and size, so a different blob at a reused URL never resumes onto old bytes
+    let partial_dir = (total_size >= RESUMABLE_MIN_BYTES).then(partial_download_dir).flatten();
+    let key = &vector_core::crypto::sha256_hex(format!("{}#{}", url, total_size).as_bytes())[..32];
+    let partial_paths: Vec<Option<std::path::PathBuf>> = (0..segments)
+        .map(|i| partial_dir.as_ref().map(|dir| dir.join(format!("{}.{}.part", key, i))))
+        .collect();
+
+    let progress = SegmentedProgress::new(reporter, total_size);
+    let parts = futures_util::future::try_join_all((0..segments).map(|i| {
+        let start = i * segment_len;
+        let end = min(start + segment_len, total_size) - 1;
+        download_segment(client, url, start, end, partial_paths[i as usize].as_deref(), &progress, segments == 1)
+    })).await?;
+
     let mut result = Vec::with_capacity(total_size.min(MAX_PREALLOC_BYTES) as usize);
-    let mut downloaded: u64 = 0;
-    let mut last_emitted_percentage: u8 = 0;
+    for part in parts {
+        result.extend_from_slice(&part);
+    }
+    for path in partial_paths.into_iter().flatten() {
+        let _ = std::fs::remove_file(path);
+    }
+    reporter.report_complete()?;
+    Ok(result)
+}
+
+/// Fetch bytes `start..=end` with adaptive chunk sizing, appending each chunk to `partial` (when
+/// given) and resuming from what it already holds. A server that answers the first range of a
+/// single-segment download with the whole file (`allow_full_body`) is taken at its word.
+async fn download_segment<P: ProgressReporter>(
+    client: &Client,
+    url: &str,
+    start: u64,
+    end: u64,
+    partial: Option<&std::path::Path>,
+    progress: &SegmentedProgress<'_, P>,
+    allow_full_body: bool,
+) -> Result<Vec<u8>, &'static str> {
+    use std::io::Write;
+
+    let len = end - start + 1;
+    let mut data = partial.and_then(|p| std::fs::read(p).ok()).unwrap_or_default();
+    if data.len() as u64 > len {
+        data.clear();
+    }
+    let mut file = partial.and_then(|p| {
+        std::fs::OpenOptions::new().create(true).write(true).truncate(data.is_empty()).open(p)
+            .and_then(|mut f| std::io::Seek::seek(&mut f, std::io::SeekFrom::Start(data.len() as u64)).map(|_| f))
+            .map_err(|e| vector_core::log_warn!("[AttachmentDownload] can't keep partial {}: {}", p.display(), e))
+            .ok()
+    });
+    if !data.is_empty() {
+        vector_core::log_info!("[AttachmentDownload] resuming {} at {} of {} bytes", url, data.len(), len);
+        progress.resumed(data.len() as u64);
+    }
+    data.reserve((len - data.len() as u64).min(MAX_PREALLOC_BYTES) as usize);
 
-    // Adaptive chunk sizing: start at 128KB, adjust based on throughput
+    // Adaptive chunk sizing, adjusted based on throughput
     const MIN_CHUNK: u64 = 32_000;      // 32KB floor (considerate to extreme conditions)
     const MAX_CHUNK: u64 = 2_000_000;   // 2MB ceiling
     const TARGET_CHUNK_SECS: f64 = 1.0; // Target ~1 second per chunk
-    let mut chunk_size: u64 = 32_000;   // Start at 32KB (floor) for fast first-chunk response
+    let mut chunk_size: u64 = MIN_CHUNK; // Start at the floor for fast first-chunk response
 
     // Speed tracking: rolling window of last 10 chunk measurements
     let mut speed_samples: Vec<f64> = Vec::with_capacity(10);
 
-    while downloaded < total_size {
-        let end = min(downloaded + chunk_size - 1, total_size - 1);
+    while (data.len() as u64) < len {
+        let from = start + data.len() as u64;
+        let to = min(from + chunk_size - 1, end);
         let chunk_start = std::time::Instant::now();
 
-        let chunk_res = client
-            .get(url)
-            .header("Range", format!("bytes={}-{}", downloaded, end))
-            .send()
-            .await
-            .map_err(|e| {
-                vector_core::log_warn!("[AttachmentDownload] range request failed for {}: {}", url, e);
-                "Failed to download chunk"
-            })?;
+        let mut attempt = 0;
+        let chunk_res = loop {
+            attempt += 1;
+            match client.get(url).header("Range", format!("bytes={}-{}", from, to)).send().await {
+                Ok(res) => break res,
+                Err(e) if attempt < RANGE_ATTEMPTS => {
+                    vector_core::log_debug!("[AttachmentDownload] range request failed for {} (retrying): {}", url, e);
+                    tokio::time::sleep(std::time::Duration::from_secs(attempt as u64)).await;
+                }
+                Err(e) => {
+                    vector_core::log_warn!("[AttachmentDownload] range request failed for {}: {}", url, e);
+                    return Err("Failed to download chunk");
+                }
+            }
+        };
 
         let status = chunk_res.status().as_u16();
         if status == 200 {
@@ -259,7 +520,7 @@ async fn download_with_ranges(
             // (common with media servers that advertise range support but
             // don't honor it). The body IS the complete attachment — consume
             // it and finish rather than failing the download.
-            if downloaded > 0 {
+            if !allow_full_body || from > 0 {
                 vector_core::log_warn!("[AttachmentDownload] unexpected HTTP 200 mid-range for {}", url);
                 return Err("Server did not honor range request");
             }
@@ -269,6 +530,7 @@ async fn download_with_ranges(
             // Stream-capped, NOT .bytes(): a chunked 200 carries no
             // Content-Length, so .bytes() would buffer an endless body —
             // the exact unbounded read the byte cap exists to stop.
+            let mut result = Vec::new();
             let mut stream = chunk_res.bytes_stream();
             while let Some(item) = stream.next().await {
                 let chunk = item.map_err(|e| {
@@ -280,7 +542,10 @@ async fn download_with_ranges(
                     return Err("File exceeds the maximum download size");
                 }
             }
-            let _ = reporter.report_complete();
+            drop(file);
+            if let Some(p) = partial {
+                let _ = std::fs::remove_file(p);
+            }
             return Ok(result);
         }
         if status != 206 {
@@ -295,43 +560,33 @@ async fn download_with_ranges(
                 vector_core::log_warn!("[AttachmentDownload] range read failed for {}: {}", url, e);
                 "Failed to read chunk bytes"
             })?;
+        if chunk.is_empty() || chunk.len() as u64 > to - from + 1 {
+            return Err("Server sent a malformed range");
+        }
 
         let elapsed = chunk_start.elapsed().as_secs_f64();
-        result.extend_from_slice(&chunk);
-        downloaded += chunk.len() as u64;
-        if downloaded > MAX_DOWNLOAD_BYTES {
-            return Err("File exceeds the maximum download size");
+        data.extend_from_slice(&chunk);
+        if let Some(f) = file.as_mut() {
+            if let Err(e) = f.write_all(&chunk) {
+                vector_core::log_warn!("[AttachmentDownload] partial write failed: {}", e);
+                file = None;
+            }
         }
 
-        // Track speed and adapt chunk size
+        // Track speed and adapt chunk size: target ~1 second per chunk
         if elapsed > 0.0 {
-            let bps = chunk.len() as f64 / elapsed;
-            speed_samples.push(bps);
+            speed_samples.push(chunk.len() as f64 / elapsed);
             if speed_samples.len() > 10 {
                 speed_samples.remove(0);
             }
-
-            // Adapt chunk size: target ~1 second per chunk
             let avg_bps = speed_samples.iter().sum::<f64>() / speed_samples.len() as f64;
             chunk_size = ((avg_bps * TARGET_CHUNK_SECS) as u64).clamp(MIN_CHUNK, MAX_CHUNK);
         }
 
-        // Report progress with speed
-        let progress = (downloaded as f64 / total_size as f64) * 100.0;
-        let current_percentage = progress as u8;
-        if current_percentage > last_emitted_percentage {
-            let avg_speed = if !speed_samples.is_empty() {
-                Some(speed_samples.iter().sum::<f64>() / speed_samples.len() as f64)
-            } else {
-                None
-            };
-            reporter.report_progress(Some(current_percentage), Some(downloaded), avg_speed)?;
-            last_emitted_percentage = current_percentage;
-        }
+        progress.fetched(chunk.len() as u64)?;
     }
 
-    reporter.report_complete()?;
-    Ok(result)
+    Ok(data)
 }
 
 /// Downloads using a streaming approach with progress reporting
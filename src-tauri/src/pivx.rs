@@ -1241,8 +1241,8 @@ pub async fn pivx_send_payment<R: Runtime>(
         "amount_piv": amount_piv,
     }).to_string();
 
-    let event_id = if receiver.starts_with("npub1") {
-        let receiver_pubkey = PublicKey::from_bech32(&receiver).map_err(|e| e.to_string())?;
+    let event_id = if let vector_core::ids::ChatId::Dm(receiver_user) = vector_core::ids::ChatId::parse(&receiver)? {
+        let receiver_pubkey = receiver_user.pubkey();
 
         let rumor = EventBuilder::new(Kind::ApplicationSpecificData, &content)
             .tag(Tag::custom(TagKind::d(), vec!["pivx-payment"]))
@@ -1354,9 +1354,9 @@ pub async fn pivx_send_existing_promo<R: Runtime>(
     let amount_sats = (amount_piv * 100_000_000.0) as u64;
 
     // Build and send the PIVX payment rumor
-    let event_id = if receiver.starts_with("npub1") {
+    let event_id = if let vector_core::ids::ChatId::Dm(receiver_user) = vector_core::ids::ChatId::parse(&receiver)? {
         // Direct message - send via gift wrap
-        let receiver_pubkey = PublicKey::from_bech32(&receiver).map_err(|e| e.to_string())?;
+        let receiver_pubkey = receiver_user.pubkey();
 
         // Build the PIVX payment rumor with p tag for recipient (needed for DM routing)
         // Include address tag so recipients can check balance without deriving keys
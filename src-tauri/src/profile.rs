@@ -25,6 +25,12 @@ pub async fn cache_profile_images(npub: &str, avatar_url: &str, banner_url: &str
         None => return,
     };
 
+    // Someone only seen in chats that don't auto-fetch remote content keeps their placeholder
+    let policies = vector_core::remote_content::chat_policies();
+    if !vector_core::remote_content::allows_profile_images(&*STATE.lock().await, &policies, npub) {
+        return;
+    }
+
     let mut avatar_cached = String::new();
     let mut banner_cached = String::new();
 
@@ -91,6 +97,7 @@ pub async fn cache_all_profile_images() {
     };
 
     // Get all profiles that need caching (resolve npub from interner)
+    let policies = vector_core::remote_content::chat_policies();
     let profiles_to_cache: Vec<(String, String, String)> = {
         let state = STATE.lock().await;
         state.profiles.iter()
@@ -100,6 +107,7 @@ pub async fn cache_all_profile_images() {
             })
             .filter_map(|p| {
                 state.interner.resolve(p.id)
+                    .filter(|npub| vector_core::remote_content::allows_profile_images(&state, &policies, npub))
                     .map(|npub| (npub.to_string(), p.avatar.to_string(), p.banner.to_string()))
             })
             .collect()
@@ -197,9 +205,29 @@ pub async fn update_profile(name: String, avatar: String, banner: String, about:
 
 /// Update the current user's status and broadcast to relays.
 /// Delegates to vector-core (no handler needed — status is ephemeral).
+/// `expires_at` (unix secs) auto-clears the status; `emoji` defaults to one typed at the front.
+#[tauri::command]
+pub async fn update_status(status: String, emoji: Option<String>, expires_at: Option<u64>) -> bool {
+    vector_core::profile::sync::update_status(status, emoji, expires_at).await
+}
+
+/// Toggle the approximate "last active" shown on contacts. Turning it off clears every cached
+/// value and stops deriving new ones.
 #[tauri::command]
-pub async fn update_status(status: String) -> bool {
-    vector_core::profile::sync::update_status(status).await
+pub async fn set_last_active_enabled(enabled: bool) -> Result<(), String> {
+    vector_core::profile::sync::set_last_active_enabled(enabled).await
+}
+
+/// Who sees our live presence ("online"): `nobody` (default) or `contacts`.
+#[tauri::command]
+pub fn get_presence_sharing() -> vector_core::presence::PresenceSharing {
+    vector_core::presence::sharing()
+}
+
+/// Change who sees our live presence; sharing with contacts announces us right away.
+#[tauri::command]
+pub async fn set_presence_sharing(sharing: vector_core::presence::PresenceSharing) -> Result<(), String> {
+    vector_core::presence::set_sharing(sharing).await
 }
 
 /// Uploads an avatar or banner image with progress reporting
@@ -329,12 +357,59 @@ pub async fn unblock_user(npub: String) -> bool {
     vector_core::profile::sync::unblock_user(npub, &crate::profile_sync::TauriProfileSyncHandler).await
 }
 
+/// The full profile (about, banner, website included) for a contact whose boot copy was a
+/// [`SlimProfile::light`](vector_core::profile::SlimProfile::light) one.
+#[tauri::command]
+pub async fn get_profile_details(npub: String) -> Option<crate::db::SlimProfile> {
+    let state = crate::STATE.lock().await;
+    let profile = state.get_profile(&npub)?;
+    Some(crate::db::SlimProfile::from_profile(profile, &state.interner))
+}
+
 /// Returns all blocked profiles.
 #[tauri::command]
 pub async fn get_blocked_users() -> Vec<crate::db::SlimProfile> {
     vector_core::profile::sync::get_blocked_users().await
 }
 
+/// Our safety number with a contact, and the QR payload for them to scan.
+#[tauri::command]
+pub async fn get_verification_code(npub: String) -> Result<vector_core::contact_verification::VerificationCode, String> {
+    vector_core::contact_verification::code_for(&npub).await
+}
+
+/// Mark a contact verified (after comparing safety numbers), or clear it.
+#[tauri::command]
+pub async fn mark_contact_verified(npub: String, verified: bool) -> Result<(), String> {
+    vector_core::contact_verification::set_verified(&npub, verified, &crate::profile_sync::TauriProfileSyncHandler).await
+}
+
+/// Check a scanned `vector://verify/...` code against our safety number with its sender. Marks
+/// nothing: the user confirms a match, then `mark_contact_verified` records it.
+#[tauri::command]
+pub async fn verify_contact_qr(payload: String) -> Result<vector_core::contact_verification::ScannedCode, String> {
+    vector_core::contact_verification::check_scanned(&payload).await
+}
+
+/// Where gift wraps to a contact go: `{ mode: "auto" }`, `{ mode: "manual", relays: [...] }` or
+/// `{ mode: "trusted" }`.
+#[tauri::command]
+pub async fn get_contact_relays(npub: String) -> vector_core::db::profile_relays::ContactRelays {
+    vector_core::db::profile_relays::get_delivery(&npub)
+}
+
+/// Choose where gift wraps (messages, typing) to a contact are published. Returns the stored
+/// preference (a manual list deduplicated and normalised).
+#[tauri::command]
+pub async fn set_contact_relays(
+    npub: String,
+    relays: vector_core::db::profile_relays::ContactRelays,
+) -> Result<vector_core::db::profile_relays::ContactRelays, String> {
+    use nostr_sdk::prelude::{FromBech32, PublicKey};
+    PublicKey::from_bech32(&npub).map_err(|_| "Invalid npub".to_string())?;
+    vector_core::db::profile_relays::set_delivery(&npub, &relays)
+}
+
 /// Set a nickname for a profile.
 #[tauri::command]
 pub async fn set_nickname(npub: String, nickname: String) -> bool {
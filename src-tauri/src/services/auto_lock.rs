@@ -0,0 +1,141 @@
+//! Auto-lock: lock an encrypted account after a stretch of inactivity.
+//!
+//! Locking tears the session down exactly like an account swap (`reset_session`): the client and
+//! database close, `ENCRYPTION_KEY` and the secret key are scrubbed from their vaults, and chats,
+//! profiles and caches are dropped from memory. The frontend then reloads into the PIN/password
+//! screen. Only accounts with Local Encryption can lock — without it there's nothing to unlock with.
+//!
+//! The frontend reports user activity (throttled) and a ticker locks once the idle timeout passes.
+//! A suspended mobile app can't tick, so suspend/resume are reported too and the time away is
+//! checked on resume. The "when leaving the app" setting (`0`) locks on suspend, so nothing is left
+//! unlocked in the background. The exception is Android while its background sync holds the client
+//! (it takes it over on pause): nothing is torn down underneath it, and the lock lands on resume.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Setting key: idle minutes before locking (absent/empty = never, `0` = when leaving the app).
+pub const AUTO_LOCK_SETTING: &str = "auto_lock_minutes";
+
+/// Longest accepted timeout (a day).
+const MAX_MINUTES: u64 = 24 * 60;
+
+/// Ticker cadence while auto-lock is off, so a newly chosen timeout is picked up.
+const IDLE_TICK: Duration = Duration::from_secs(60);
+
+/// Shortest sleep between checks while a timeout is armed.
+const MIN_TICK: Duration = Duration::from_secs(5);
+
+/// Unix seconds of the last reported user activity. Wall-clock, so time spent with the device
+/// asleep counts as idle.
+static LAST_ACTIVITY: AtomicU64 = AtomicU64::new(0);
+
+/// Unix seconds the app was suspended (backgrounded/hidden) at, or 0 while in the foreground.
+static SUSPENDED_AT: AtomicU64 = AtomicU64::new(0);
+
+/// The configured timeout: `None` = never lock, `Some(ZERO)` = lock when leaving the app.
+pub fn timeout() -> Option<Duration> {
+    let minutes = crate::db::get_sql_setting(AUTO_LOCK_SETTING.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<u64>().ok())?;
+    Some(Duration::from_secs(minutes.min(MAX_MINUTES) * 60))
+}
+
+/// Record user activity, restarting the idle clock.
+pub fn note_activity() {
+    LAST_ACTIVITY.store(vector_core::clock::now_secs(), Ordering::Relaxed);
+}
+
+fn idle() -> Duration {
+    let last = LAST_ACTIVITY.load(Ordering::Relaxed);
+    Duration::from_secs(vector_core::clock::now_secs().saturating_sub(last))
+}
+
+/// Whether there's an unlocked, encrypted session to lock.
+fn can_lock() -> bool {
+    vector_core::state::is_encryption_enabled_fast() && crate::nostr_client().is_some()
+}
+
+/// Lock now: tear the session down and send the frontend back to the unlock screen.
+pub async fn lock<R: Runtime>(handle: &AppHandle<R>) -> Result<(), String> {
+    if !can_lock() {
+        return Err("Locking needs Local Encryption to be enabled".to_string());
+    }
+    crate::account_manager::refuse_if_migration_in_progress("lock")?;
+    crate::account_manager::reset_session().await;
+    let _ = handle.emit("session_reload", ());
+    Ok(())
+}
+
+/// Lock if the idle timeout has passed.
+async fn check<R: Runtime>(handle: &AppHandle<R>) {
+    if !can_lock() {
+        // Locked or logged out: the idle clock only runs while unlocked
+        note_activity();
+        return;
+    }
+    // Backgrounded Android may hand the client to the background sync; resume decides then
+    if SUSPENDED_AT.load(Ordering::Relaxed) != 0 && sync_holds_client() {
+        return;
+    }
+    if let Some(timeout) = timeout().filter(|t| !t.is_zero()) {
+        if idle() >= timeout {
+            let _ = lock(handle).await;
+        }
+    }
+}
+
+/// Whether the Android background sync has (or is about to take) the client.
+fn sync_holds_client() -> bool {
+    #[cfg(target_os = "android")]
+    {
+        use crate::android::background_sync;
+        background_sync::is_background_sync_active() || background_sync::is_standalone_sync_running()
+    }
+    #[cfg(not(target_os = "android"))]
+    {
+        false
+    }
+}
+
+/// The app left the foreground: lock right away if the user chose to lock on leaving.
+pub fn on_suspend<R: Runtime>(handle: &AppHandle<R>) {
+    let _ = SUSPENDED_AT.compare_exchange(0, vector_core::clock::now_secs(), Ordering::Relaxed, Ordering::Relaxed);
+    if can_lock() && !sync_holds_client() && timeout().is_some_and(|t| t.is_zero()) {
+        let handle = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = lock(&handle).await;
+        });
+    }
+}
+
+/// The app came back to the foreground: lock if the user chose to lock on leaving (and it didn't
+/// already happen on suspend), or if the time away ran out the idle timeout.
+pub async fn on_resume<R: Runtime>(handle: &AppHandle<R>) {
+    if SUSPENDED_AT.swap(0, Ordering::Relaxed) == 0 || !can_lock() {
+        return;
+    }
+    if let Some(timeout) = timeout() {
+        if timeout.is_zero() || idle() >= timeout {
+            let _ = lock(handle).await;
+        }
+    }
+}
+
+/// Start the idle ticker. Call once during app startup.
+pub fn start<R: Runtime>(handle: AppHandle<R>) {
+    note_activity();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let wait = match timeout().filter(|t| !t.is_zero()) {
+                Some(timeout) => timeout.saturating_sub(idle()).max(MIN_TICK),
+                None => IDLE_TICK,
+            };
+            tokio::time::sleep(wait).await;
+            check(&handle).await;
+        }
+    });
+}
@@ -15,7 +15,7 @@ use crate::{
     db, miniapps, commands,
     NotificationData, show_notification_generic,
     STATE, TAURI_APP, nostr_client, WRAPPER_ID_CACHE,
-    util::get_file_type_description,
+    util::file_notification_body,
     state::{is_processing_allowed, PENDING_EVENTS},
 };
 
@@ -30,6 +30,11 @@ use crate::{
 async fn auto_mark_if_active(chat_id: &str, msg_id: &str) -> bool {
     let active = vector_core::state::get_active_chat();
     if active.as_deref() != Some(chat_id) { return false; }
+    // With a read dwell set, the arrival gets its own reading time before the tracker marks it.
+    if crate::services::read_tracker::read_dwell().is_some() {
+        crate::services::read_tracker::restart_dwell(chat_id);
+        return false;
+    }
     // A swap can land while awaiting the STATE lock; re-check inside so we never write account A's
     // last_read into account B's freshly-swapped chat list/DB.
     let session = vector_core::state::SessionGuard::capture();
@@ -49,6 +54,7 @@ async fn auto_mark_if_active(chat_id: &str, msg_id: &str) -> bool {
     let marked = slim.is_some();
     if let Some(slim) = slim {
         let _ = vector_core::db::chats::save_slim_chat(&slim);
+        vector_core::chat_state_sync::note_local_change(&vector_core::chat_state_sync::read_key(chat_id));
     }
 
     if let Some(app) = TAURI_APP.get() {
@@ -149,6 +155,7 @@ impl vector_core::InboundEventHandler for TauriEventHandler {
         let extension = msg.attachments.first()
             .map(|att| att.extension.clone())
             .unwrap_or_else(|| String::from("file"));
+        let caption = crate::services::strip_content_for_preview(&msg.content);
         let msg_id = msg.id.clone();
         let session = vector_core::state::SessionGuard::capture();
         tokio::spawn(async move {
@@ -163,7 +170,7 @@ impl vector_core::InboundEventHandler for TauriEventHandler {
             if !is_muted {
                 let display_info = {
                     let state = STATE.lock().await;
-                    get_file_notification_info(&state, &chat_id, &extension)
+                    get_file_notification_info(&state, &chat_id, &extension, &caption)
                 };
                 if let Some((name, body, avatar)) = display_info {
                     let notification = NotificationData::direct_message(name, body, avatar, chat_id.clone());
@@ -371,6 +378,7 @@ fn get_file_notification_info(
     state: &crate::state::ChatState,
     contact: &str,
     extension: &str,
+    caption: &str,
 ) -> Option<(String, String, Option<String>)> {
     let (name, avatar) = match state.get_profile(contact) {
         Some(profile) => {
@@ -390,7 +398,7 @@ fn get_file_notification_info(
         }
         None => (String::from("New Message"), None),
     };
-    let body = "Sent a ".to_string() + &get_file_type_description(extension);
+    let body = file_notification_body(extension, caption);
     Some((name, body, avatar))
 }
 
@@ -409,6 +417,20 @@ pub(crate) async fn handle_event(event: Event, is_new: bool) -> bool {
     handle_event_with_context(event, is_new, &client, my_public_key).await
 }
 
+/// `handle_event` for a live event from `relay_url`, also scoring the relay on what it delivered
+/// (see `vector_core::relay_health`).
+pub(crate) async fn handle_relay_event(relay_url: &RelayUrl, event: Event) -> bool {
+    let Some(client) = nostr_client() else { return false; };
+    let Some(my_public_key) = crate::my_public_key() else { return false; };
+    if !is_processing_allowed() {
+        return handle_event_with_context(event, true, &client, my_public_key).await;
+    }
+    let event_id = event.id;
+    let prepared = core_handler::prepare_event(event, &client, my_public_key).await;
+    vector_core::relay_health::record(relay_url.as_str(), &event_id, prepared.relay_verdict());
+    tauri_commit_prepared_event(prepared, true).await
+}
+
 /// Full event processing — accepts dependencies as parameters.
 /// Enables headless (background service) callers to provide their own client/key.
 pub(crate) async fn handle_event_with_context(
@@ -0,0 +1,274 @@
+//! Linux graphics environment detection and WebKitGTK renderer selection.
+//!
+//! WebKitGTK's GPU paths break differently per setup: the DMA-BUF renderer shows a blank or
+//! flickering window on NVIDIA's proprietary driver, and accelerated compositing crawls or
+//! crashes without a real GPU (VMs, X forwarding). Before any webview exists, [`apply`] detects
+//! the display server, desktop/compositor and GPU driver, picks the safest renderer for that
+//! combination (or the user's [`RendererMode`] override), exports the matching environment
+//! variables, and logs the result. The report is kept for `get_graphics_env`.
+//!
+//! The override lives in a `renderer_mode` file in the app data root rather than a per-account
+//! setting: it has to be read before Tauri (and any account) is up, and it takes effect on the
+//! next launch. Variables the user already exported are left alone.
+
+#![cfg_attr(not(target_os = "linux"), allow(dead_code))]
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// File (in the app data root) holding the renderer override.
+const RENDERER_MODE_FILE: &str = "renderer_mode";
+
+/// WebKitGTK variables this module may set.
+const DISABLE_DMABUF: &str = "WEBKIT_DISABLE_DMABUF_RENDERER";
+const DISABLE_COMPOSITING: &str = "WEBKIT_DISABLE_COMPOSITING_MODE";
+
+/// GPU drivers that mean "no real GPU": virtual adapters and firmware framebuffers.
+const SOFTWARE_DRIVERS: &[&str] = &["virtio-pci", "virtio_gpu", "vmwgfx", "qxl", "bochs-drm", "bochs", "cirrus", "vboxvideo", "simpledrm", "efifb", "hyperv_drm"];
+
+/// How WebKitGTK should render.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RendererMode {
+    /// Pick per environment (the default).
+    #[default]
+    Auto,
+    /// Full GPU path, DMA-BUF renderer included. Fastest where the driver handles it.
+    Accelerated,
+    /// GPU compositing without the DMA-BUF renderer.
+    Compatible,
+    /// No GPU compositing at all. Slowest, but renders anywhere.
+    Software,
+}
+
+impl RendererMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            RendererMode::Auto => "auto",
+            RendererMode::Accelerated => "accelerated",
+            RendererMode::Compatible => "compatible",
+            RendererMode::Software => "software",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "auto" => Some(RendererMode::Auto),
+            "accelerated" => Some(RendererMode::Accelerated),
+            "compatible" => Some(RendererMode::Compatible),
+            "software" => Some(RendererMode::Software),
+            _ => None,
+        }
+    }
+}
+
+/// What was detected, and what it was rendered with.
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct GraphicsEnv {
+    /// `wayland`, `xwayland` (Wayland session, X11 backend forced), `x11`, or `unknown`.
+    pub display_server: String,
+    /// `XDG_CURRENT_DESKTOP`, e.g. `GNOME` or `KDE`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desktop: Option<String>,
+    /// Kernel driver of each DRM card, e.g. `amdgpu`, `i915`, `nvidia`.
+    pub gpu_drivers: Vec<String>,
+    /// `ssh` for a forwarded X display, `flatpak` / `snap` / `appimage` for packaged runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// The saved override (`auto` when none).
+    pub requested: RendererMode,
+    /// What this run renders with.
+    pub mode: RendererMode,
+    /// Why that mode.
+    pub reason: String,
+    /// Renderer variables in effect, as `NAME=value`.
+    pub env: Vec<String>,
+}
+
+static REPORT: Mutex<Option<GraphicsEnv>> = Mutex::new(None);
+
+/// The app data root, resolved by hand: this runs before Tauri's path resolver exists. Mirrors
+/// Tauri's Linux layout (`$XDG_DATA_HOME/<identifier>`, else `~/.local/share/<identifier>`).
+fn data_root() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))?;
+    Some(base.join("io.vectorapp"))
+}
+
+fn read_override() -> RendererMode {
+    data_root()
+        .and_then(|d| std::fs::read_to_string(d.join(RENDERER_MODE_FILE)).ok())
+        .and_then(|s| RendererMode::parse(&s))
+        .unwrap_or(RendererMode::Auto)
+}
+
+fn env_nonempty(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// Kernel driver behind each `/sys/class/drm/cardN` (connectors like `card0-DP-1` skipped).
+fn gpu_drivers() -> Vec<String> {
+    let mut drivers: Vec<String> = std::fs::read_dir("/sys/class/drm")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            name.strip_prefix("card").is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()))
+        })
+        .filter_map(|e| std::fs::read_link(e.path().join("device/driver")).ok())
+        .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .collect();
+    // The proprietary NVIDIA driver may not register a DRM card without `nvidia-drm.modeset=1`
+    if !drivers.iter().any(|d| d == "nvidia") && std::path::Path::new("/proc/driver/nvidia/version").exists() {
+        drivers.push("nvidia".to_string());
+    }
+    drivers.sort();
+    drivers.dedup();
+    drivers
+}
+
+/// Detect the environment from the process's variables and the DRM sysfs tree.
+fn detect() -> GraphicsEnv {
+    let wayland = env_nonempty("WAYLAND_DISPLAY").is_some()
+        || env_nonempty("XDG_SESSION_TYPE").is_some_and(|t| t == "wayland");
+    let x_display = env_nonempty("DISPLAY");
+    let forced_x11 = env_nonempty("GDK_BACKEND").is_some_and(|b| b.starts_with("x11"));
+    let display_server = match (wayland, forced_x11, x_display.is_some()) {
+        (true, true, _) => "xwayland",
+        (true, false, _) => "wayland",
+        (false, _, true) => "x11",
+        _ => "unknown",
+    };
+    // A hostname before the colon (`localhost:10.0`) is a forwarded display
+    let context = if x_display.as_deref().is_some_and(|d| !d.starts_with(':')) && !wayland {
+        Some("ssh")
+    } else if env_nonempty("FLATPAK_ID").is_some() {
+        Some("flatpak")
+    } else if env_nonempty("SNAP").is_some() {
+        Some("snap")
+    } else if env_nonempty("APPIMAGE").is_some() {
+        Some("appimage")
+    } else {
+        None
+    };
+    GraphicsEnv {
+        display_server: display_server.to_string(),
+        desktop: env_nonempty("XDG_CURRENT_DESKTOP"),
+        gpu_drivers: gpu_drivers(),
+        context: context.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+/// The safest mode for a detected environment, and why.
+fn auto_mode(env: &GraphicsEnv) -> (RendererMode, &'static str) {
+    if env.context.as_deref() == Some("ssh") {
+        return (RendererMode::Software, "forwarded X display, no local GPU");
+    }
+    if env.gpu_drivers.is_empty() {
+        return (RendererMode::Software, "no GPU found");
+    }
+    if env.gpu_drivers.iter().all(|d| SOFTWARE_DRIVERS.contains(&d.as_str())) {
+        return (RendererMode::Software, "virtual or framebuffer-only GPU");
+    }
+    if env.gpu_drivers.iter().any(|d| d == "nvidia") {
+        return (RendererMode::Compatible, "NVIDIA proprietary driver breaks the DMA-BUF renderer");
+    }
+    // Mesa drivers mostly cope with DMA-BUF, but not across every distro and compositor; a stray
+    // blank window costs more than the extra copy.
+    (RendererMode::Compatible, "DMA-BUF renderer is unreliable across distros")
+}
+
+/// Renderer variables for a mode.
+fn env_for(mode: RendererMode) -> &'static [(&'static str, &'static str)] {
+    match mode {
+        RendererMode::Auto | RendererMode::Accelerated => &[],
+        RendererMode::Compatible => &[(DISABLE_DMABUF, "1")],
+        RendererMode::Software => &[(DISABLE_DMABUF, "1"), (DISABLE_COMPOSITING, "1")],
+    }
+}
+
+/// Detect the environment, export the chosen renderer's variables, and log it. Must run before
+/// the first webview is created, while the process is still single-threaded.
+pub fn apply() {
+    let mut env = detect();
+    let requested = read_override();
+    let (mode, reason) = match requested {
+        RendererMode::Auto => auto_mode(&env),
+        chosen => (chosen, "set in Settings"),
+    };
+    for (key, value) in env_for(mode) {
+        if std::env::var_os(key).is_none() {
+            std::env::set_var(key, value);
+        }
+    }
+    env.requested = requested;
+    env.mode = mode;
+    env.reason = reason.to_string();
+    env.env = [DISABLE_DMABUF, DISABLE_COMPOSITING, "GDK_BACKEND", "LIBGL_ALWAYS_SOFTWARE"]
+        .iter()
+        .filter_map(|k| std::env::var(k).ok().map(|v| format!("{}={}", k, v)))
+        .collect();
+    eprintln!(
+        "[Graphics] {} session{}, GPU: {}, renderer: {} ({}){}",
+        env.display_server,
+        env.desktop.as_deref().map(|d| format!(" on {}", d)).unwrap_or_default(),
+        if env.gpu_drivers.is_empty() { "none".to_string() } else { env.gpu_drivers.join(", ") },
+        mode.as_str(),
+        reason,
+        if env.env.is_empty() { String::new() } else { format!(" [{}]", env.env.join(" ")) },
+    );
+    *REPORT.lock().unwrap_or_else(|e| e.into_inner()) = Some(env);
+}
+
+/// The startup report, if [`apply`] ran (Linux only).
+pub fn report() -> Option<GraphicsEnv> {
+    REPORT.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Save a renderer override for the next launch (`Auto` clears it).
+pub fn set_override(mode: RendererMode) -> Result<(), String> {
+    let root = data_root().ok_or("Couldn't locate the app data folder")?;
+    let path = root.join(RENDERER_MODE_FILE);
+    if mode == RendererMode::Auto {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to clear renderer mode: {}", e)),
+            _ => Ok(()),
+        };
+    }
+    std::fs::create_dir_all(&root).map_err(|e| format!("Failed to create app data folder: {}", e))?;
+    std::fs::write(&path, mode.as_str()).map_err(|e| format!("Failed to save renderer mode: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(drivers: &[&str], context: Option<&str>) -> GraphicsEnv {
+        GraphicsEnv {
+            gpu_drivers: drivers.iter().map(|d| d.to_string()).collect(),
+            context: context.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn auto_picks_the_safest_mode() {
+        assert_eq!(auto_mode(&env(&[], None)).0, RendererMode::Software);
+        assert_eq!(auto_mode(&env(&["virtio-pci"], None)).0, RendererMode::Software);
+        assert_eq!(auto_mode(&env(&["amdgpu"], Some("ssh"))).0, RendererMode::Software);
+        assert_eq!(auto_mode(&env(&["i915", "nvidia"], None)).0, RendererMode::Compatible);
+        assert_eq!(auto_mode(&env(&["amdgpu"], Some("flatpak"))).0, RendererMode::Compatible);
+    }
+
+    #[test]
+    fn modes_round_trip() {
+        for mode in [RendererMode::Auto, RendererMode::Accelerated, RendererMode::Compatible, RendererMode::Software] {
+            assert_eq!(RendererMode::parse(mode.as_str()), Some(mode));
+        }
+        assert_eq!(RendererMode::parse("turbo"), None);
+    }
+}
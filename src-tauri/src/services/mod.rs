@@ -4,17 +4,30 @@
 //! - `event_handler`: Main event dispatcher for handling incoming Nostr events
 //! - `subscription_handler`: Live subscription handling for real-time events
 //! - `notification_service`: OS notification handling
+//! - `notification_actions`: Mark as Read / Reply on desktop message notifications (Linux, macOS)
+//! - `read_tracker`: dwell-based marking of the chat in view as read
+//! - `auto_lock`: locking an encrypted account after inactivity or on leaving the app
+//! - `runtime_paths`: startup access check of every directory Vector uses
+//! - `graphics_env`: Linux display/GPU detection and WebKitGTK renderer selection
+//! - `network_watch`: OS network-change listeners that reconnect relays on an interface switch
 //!
 //! Services are used by command handlers and can be unit tested independently.
 
 pub mod event_handler;
 pub mod subscription_handler;
 pub mod notification_service;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub mod notification_actions;
+pub mod read_tracker;
+pub mod auto_lock;
+pub mod runtime_paths;
+pub mod graphics_env;
+pub mod network_watch;
 
-pub(crate) use event_handler::handle_event;
+pub(crate) use event_handler::{handle_event, handle_relay_event};
 pub(crate) use event_handler::tauri_commit_prepared_event_with;
-pub(crate) use subscription_handler::start_subscriptions;
+pub(crate) use subscription_handler::{start_subscriptions, subscribe_announcements};
 pub(crate) use notification_service::{NotificationData, show_notification_generic, resolve_mention_display_names, strip_content_for_preview};
 // Used by the Android background-sync notification chokepoint (post_notification_jni).
 #[cfg(target_os = "android")]
-pub(crate) use notification_service::{NotifContentPrivacy, notif_content_privacy};
+pub(crate) use notification_service::{NotifContentPrivacy, NotificationType, notif_shaping, pending_unread_total};
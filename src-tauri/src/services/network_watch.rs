@@ -0,0 +1,236 @@
+//! Network change detection.
+//!
+//! Moving from Wi-Fi to cellular, or bringing a VPN up, leaves every relay websocket bound to the
+//! old route. The sockets look connected but are dead, and the health check only notices a minute
+//! or more later. Each platform's own change notification wakes this service instead:
+//!
+//!   - Linux: a `NETLINK_ROUTE` socket subscribed to link and address changes
+//!   - macOS / iOS: a `PF_ROUTE` routing socket
+//!   - Windows: `NotifyAddrChange` from iphlpapi
+//!   - Android: a `ConnectivityManager` default-network callback in `MainActivity`
+//!
+//! Notifications arrive in bursts (and include noise like IPv6 privacy address rotation), so
+//! they're debounced and then checked against the route fingerprint: the local addresses the OS
+//! would use to reach the internet. Only a real change reconnects every relay, which re-runs the
+//! per-relay catch-up sync in the relay monitor, and emits `network_changed`.
+
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// How long a burst of OS notifications is given to settle before the route is checked.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// How long each relay gets to reconnect after a change.
+const RECONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+static WAKE: LazyLock<tokio::sync::Notify> = LazyLock::new(tokio::sync::Notify::new);
+
+/// Route fingerprint from the last check.
+static LAST_ROUTE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Signal that the OS reported a network change. Safe to call from any thread, and before
+/// [`start`] (the wake is kept until the watcher picks it up).
+pub(crate) fn notify() {
+    WAKE.notify_one();
+}
+
+/// The local IPv4/IPv6 addresses the OS would route internet traffic from, or `None` with no
+/// route at all. A "connected" UDP socket only resolves the route; nothing is sent.
+fn route_fingerprint() -> Option<String> {
+    let local = |bind: &str, target: &str| {
+        let socket = std::net::UdpSocket::bind(bind).ok()?;
+        socket.connect(target).ok()?;
+        socket.local_addr().ok().map(|a| a.ip().to_string())
+    };
+    let v4 = local("0.0.0.0:0", "1.1.1.1:53");
+    let v6 = local("[::]:0", "[2606:4700:4700::1111]:53");
+    if v4.is_none() && v6.is_none() {
+        return None;
+    }
+    Some(format!("{}|{}", v4.unwrap_or_default(), v6.unwrap_or_default()))
+}
+
+/// Whether going from route `prev` to `now` is a change worth acting on, and if so whether we're
+/// online afterwards.
+fn route_change(prev: &Option<String>, now: &Option<String>) -> Option<bool> {
+    (prev != now).then_some(now.is_some())
+}
+
+/// Start the platform listener and the debounced reaction to it (once per process).
+pub fn start<R: Runtime>(handle: AppHandle<R>) {
+    static STARTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+    if STARTED.swap(true, std::sync::atomic::Ordering::AcqRel) {
+        return;
+    }
+    *LAST_ROUTE.lock().unwrap_or_else(|e| e.into_inner()) = route_fingerprint();
+
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "ios", windows))]
+    {
+        let spawned = std::thread::Builder::new()
+            .name("network-watch".into())
+            .spawn(listen_os);
+        if let Err(e) = spawned {
+            log_warn!("[Network] Couldn't start the change listener: {}", e);
+        }
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            WAKE.notified().await;
+            tokio::time::sleep(DEBOUNCE).await;
+            let now = tokio::task::spawn_blocking(route_fingerprint).await.unwrap_or(None);
+            let online = {
+                let mut last = LAST_ROUTE.lock().unwrap_or_else(|e| e.into_inner());
+                let change = route_change(&last, &now);
+                *last = now;
+                change
+            };
+            if let Some(online) = online {
+                on_network_changed(&handle, online).await;
+            }
+        }
+    });
+}
+
+/// React to a real route change: move HTTP and relay traffic onto fresh connections, then tell
+/// the UI.
+async fn on_network_changed<R: Runtime>(handle: &AppHandle<R>, online: bool) {
+    log_info!("[Network] Network changed ({})", if online { "online" } else { "offline" });
+    if online {
+        // A proxy that was unreachable may be reachable over the new route (or the reverse).
+        // When its state flips, the transport switch already cycles every relay.
+        let transport_switched = vector_core::proxy::probe().await
+            && crate::commands::proxy::switch_relay_transport().await.is_ok();
+        if let Err(e) = vector_core::net::rebuild_shared_http_client() {
+            log_warn!("[Network] HTTP client rebuild failed: {}", e);
+        }
+        if !transport_switched {
+            reconnect_relays().await;
+        }
+    }
+    let _ = handle.emit("network_changed", serde_json::json!({ "online": online }));
+}
+
+/// Drop and re-open every relay socket. Each relay coming back up triggers the relay monitor's
+/// reconnection sync, which catches up on anything missed while the old route was dead.
+async fn reconnect_relays() {
+    let Some(client) = crate::nostr_client() else { return };
+    let relays = client.relays().await;
+    log_info!("[Network] Reconnecting {} relay(s)", relays.len());
+    let reconnects = relays.into_values().map(|relay| async move {
+        let _ = relay.disconnect();
+        let _ = relay.try_connect(RECONNECT_TIMEOUT).await;
+    });
+    futures_util::future::join_all(reconnects).await;
+}
+
+/// Block on a `NETLINK_ROUTE` socket, waking the watcher on every link/address/route message.
+#[cfg(target_os = "linux")]
+fn listen_os() {
+    // SAFETY: plain socket syscalls on a descriptor owned by this thread.
+    unsafe {
+        let fd = libc::socket(libc::AF_NETLINK, libc::SOCK_RAW | libc::SOCK_CLOEXEC, libc::NETLINK_ROUTE);
+        if fd < 0 {
+            log_warn!("[Network] netlink socket failed: {}", std::io::Error::last_os_error());
+            return;
+        }
+        let mut addr: libc::sockaddr_nl = std::mem::zeroed();
+        addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        addr.nl_groups = (libc::RTMGRP_LINK
+            | libc::RTMGRP_IPV4_IFADDR
+            | libc::RTMGRP_IPV6_IFADDR
+            | libc::RTMGRP_IPV4_ROUTE) as u32;
+        let bound = libc::bind(
+            fd,
+            &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        );
+        if bound < 0 {
+            log_warn!("[Network] netlink bind failed: {}", std::io::Error::last_os_error());
+            libc::close(fd);
+            return;
+        }
+        recv_loop(fd);
+        libc::close(fd);
+    }
+}
+
+/// Block on a `PF_ROUTE` routing socket, waking the watcher on every routing message.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn listen_os() {
+    // SAFETY: plain socket syscalls on a descriptor owned by this thread.
+    unsafe {
+        let fd = libc::socket(libc::PF_ROUTE, libc::SOCK_RAW, libc::AF_UNSPEC);
+        if fd < 0 {
+            log_warn!("[Network] routing socket failed: {}", std::io::Error::last_os_error());
+            return;
+        }
+        recv_loop(fd);
+        libc::close(fd);
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "ios"))]
+unsafe fn recv_loop(fd: libc::c_int) {
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = libc::recv(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0);
+        if n < 0 {
+            let err = std::io::Error::last_os_error();
+            // ENOBUFS: the kernel dropped messages in a burst, which is itself a change
+            if err.kind() == std::io::ErrorKind::Interrupted || err.raw_os_error() == Some(libc::ENOBUFS) {
+                notify();
+                continue;
+            }
+            log_warn!("[Network] change listener stopped: {}", err);
+            return;
+        }
+        notify();
+    }
+}
+
+/// Block on `NotifyAddrChange`, which returns each time an interface address changes.
+#[cfg(windows)]
+fn listen_os() {
+    #[link(name = "iphlpapi")]
+    extern "system" {
+        fn NotifyAddrChange(handle: *mut *mut std::ffi::c_void, overlapped: *mut std::ffi::c_void) -> u32;
+    }
+    loop {
+        // SAFETY: null handle + overlapped selects the synchronous form, which takes no pointers.
+        let status = unsafe { NotifyAddrChange(std::ptr::null_mut(), std::ptr::null_mut()) };
+        if status != 0 {
+            log_warn!("[Network] NotifyAddrChange failed: {}", status);
+            return;
+        }
+        notify();
+    }
+}
+
+/// Called from MainActivity's default-network callback via JNI.
+#[cfg(target_os = "android")]
+#[no_mangle]
+pub extern "C" fn Java_io_vectorapp_MainActivity_nativeOnNetworkChanged(
+    _env: jni::JNIEnv,
+    _class: jni::objects::JClass,
+) {
+    notify();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_a_different_route_is_a_change() {
+        let wifi = Some("192.168.1.20|".to_string());
+        let cellular = Some("10.45.0.7|2a00::7".to_string());
+        assert_eq!(route_change(&wifi, &wifi), None);
+        assert_eq!(route_change(&wifi, &cellular), Some(true));
+        assert_eq!(route_change(&cellular, &None), Some(false));
+        assert_eq!(route_change(&None, &wifi), Some(true));
+        assert_eq!(route_change(&None, &None), None);
+    }
+}
@@ -0,0 +1,143 @@
+//! Message notifications with actions on desktop.
+//!
+//! Tauri's notification plugin can't report clicks or actions on desktop, so on Linux and macOS
+//! message notifications go straight to the OS library it wraps, and a thread waits for the
+//! user's response. The buttons:
+//! - **Mark as Read** marks the chat read without opening Vector.
+//! - **Reply** replies inline on macOS, sending without opening Vector. Linux notification
+//!   servers have no text field, so there it opens the chat with the composer focused.
+//!
+//! Clicking the notification opens the chat. Reply is only offered for DMs, which can be sent
+//! headlessly. Android's equivalents live in `NotificationActionReceiver.kt` and
+//! `background_sync.rs`.
+
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use super::NotificationData;
+
+/// What the user did with a notification.
+#[derive(Debug)]
+pub enum NotificationAction {
+    /// Clicked it.
+    Open,
+    MarkRead,
+    /// Replied inline (`Some`), or asked to reply where the OS can't take text (`None`).
+    Reply(Option<String>),
+}
+
+/// Register what the OS needs before the first actionable notification. Call once from `setup`.
+pub fn init() {
+    // Responses are routed to the app with this bundle id (the plugin may already have set it)
+    #[cfg(target_os = "macos")]
+    let _ = mac_notification_sys::set_application("io.vectorapp");
+}
+
+/// Show a message notification with actions.
+pub fn show(data: &NotificationData, chat_id: &str) {
+    let can_reply = vector_core::ids::is_dm_chat_id(chat_id);
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut notification = notify_rust::Notification::new();
+        notification
+            .appname("Vector")
+            .summary(&data.title)
+            .body(&data.body)
+            .auto_icon()
+            .action("default", "Open")
+            .action("mark_read", "Mark as Read");
+        if can_reply {
+            notification.action("reply", "Reply");
+        }
+        let notification_handle = match notification.show() {
+            Ok(h) => h,
+            Err(e) => {
+                eprintln!("Failed to send notification: {}", e);
+                return;
+            }
+        };
+        let chat_id = chat_id.to_string();
+        std::thread::spawn(move || {
+            notification_handle.wait_for_action(|action| {
+                let action = match action {
+                    "default" => NotificationAction::Open,
+                    "mark_read" => NotificationAction::MarkRead,
+                    "reply" => NotificationAction::Reply(None),
+                    // "__closed": dismissed or expired
+                    _ => return,
+                };
+                handle_action(chat_id, action);
+            });
+        });
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use mac_notification_sys::{MainButton, Notification, NotificationResponse};
+
+        let (title, body) = (data.title.clone(), data.body.clone());
+        let chat_id = chat_id.to_string();
+        std::thread::spawn(move || {
+            let mut notification = Notification::new();
+            notification.title(&title).message(&body).close_button("Mark as Read");
+            if can_reply {
+                notification.main_button(MainButton::Response("Reply"));
+            }
+            let action = match notification.send() {
+                Ok(NotificationResponse::Click) => NotificationAction::Open,
+                Ok(NotificationResponse::CloseButton(_)) => NotificationAction::MarkRead,
+                Ok(NotificationResponse::Reply(text)) if !text.trim().is_empty() => {
+                    NotificationAction::Reply(Some(text))
+                }
+                Ok(_) => return,
+                Err(e) => {
+                    eprintln!("Failed to send notification: {}", e);
+                    return;
+                }
+            };
+            handle_action(chat_id, action);
+        });
+    }
+}
+
+/// Carry out a notification action for `chat_id`.
+pub fn handle_action(chat_id: String, action: NotificationAction) {
+    match action {
+        NotificationAction::Open => open_chat(&chat_id, "chat"),
+        NotificationAction::Reply(None) => open_chat(&chat_id, "chat_reply"),
+        NotificationAction::MarkRead => {
+            tauri::async_runtime::spawn(async move {
+                crate::chat::mark_as_read_headless(&chat_id).await;
+            });
+        }
+        NotificationAction::Reply(Some(content)) => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::message::send_text_reply_headless(&chat_id, &content).await {
+                    eprintln!("[Notification] Inline reply failed: {}", e);
+                    if let Some(handle) = crate::TAURI_APP.get() {
+                        let _ = handle.notification().builder()
+                            .title("Vector")
+                            .body("Reply failed to send")
+                            .show();
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Bring the window up on `chat_id` (`chat_reply` also focuses the composer).
+fn open_chat(chat_id: &str, action_type: &str) {
+    let Some(handle) = crate::TAURI_APP.get() else { return };
+    if let Some(window) = handle.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+    let action = crate::deep_link::DeepLinkAction {
+        action_type: action_type.to_string(),
+        target: chat_id.to_string(),
+    };
+    let _ = handle.emit("deep_link_action", &action);
+}
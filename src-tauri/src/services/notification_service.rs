@@ -36,6 +36,8 @@ pub enum NotifContentPrivacy {
     HideContent,
     /// Generic "you received a message" — no sender, avatar, or group.
     HideAll,
+    /// Only the running unread total ("3 new messages"), nothing per-message.
+    CountOnly,
 }
 
 /// Read the account's notification content-privacy preference. Defaults to
@@ -48,10 +50,103 @@ pub fn notif_content_privacy() -> NotifContentPrivacy {
     {
         Some("hide_content") => NotifContentPrivacy::HideContent,
         Some("hide_all") => NotifContentPrivacy::HideAll,
+        Some("count_only") => NotifContentPrivacy::CountOnly,
         _ => NotifContentPrivacy::Full,
     }
 }
 
+/// What the OS may show while the device is locked. Per-account setting
+/// (`notif_lockscreen`). Only Android distinguishes these; desktop ignores it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LockscreenVisibility {
+    /// Full notification on the lockscreen.
+    Public,
+    /// Redacted public version on the lockscreen (default — the Android default).
+    Private,
+    /// Nothing on the lockscreen at all.
+    Secret,
+}
+
+impl LockscreenVisibility {
+    /// The matching `NotificationCompat.VISIBILITY_*` constant.
+    pub fn android_flag(self) -> i32 {
+        match self {
+            LockscreenVisibility::Public => 1,
+            LockscreenVisibility::Private => 0,
+            LockscreenVisibility::Secret => -1,
+        }
+    }
+}
+
+/// Body length cap for `notif_short_body`. Sized for a watch face: paired
+/// wearables mirror the phone notification verbatim.
+const SHORT_BODY_CHARS: usize = 60;
+
+/// Per-account notification payload shaping, resolved once per notification.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NotifShaping {
+    pub privacy: NotifContentPrivacy,
+    pub lockscreen: LockscreenVisibility,
+    /// Truncate bodies to [`SHORT_BODY_CHARS`] for wearables / compact banners.
+    pub short_body: bool,
+}
+
+impl Default for NotifShaping {
+    fn default() -> Self {
+        Self {
+            privacy: NotifContentPrivacy::Full,
+            lockscreen: LockscreenVisibility::Private,
+            short_body: false,
+        }
+    }
+}
+
+/// Read every shaping preference for the active account. Unset or unreadable
+/// keys fall back to [`NotifShaping::default`].
+pub fn notif_shaping() -> NotifShaping {
+    let setting = |key: &str| crate::db::get_sql_setting(key.to_string()).ok().flatten();
+    NotifShaping {
+        privacy: notif_content_privacy(),
+        lockscreen: match setting("notif_lockscreen").as_deref() {
+            Some("public") => LockscreenVisibility::Public,
+            Some("secret") => LockscreenVisibility::Secret,
+            _ => LockscreenVisibility::Private,
+        },
+        short_body: setting("notif_short_body").as_deref() == Some("true"),
+    }
+}
+
+/// Badge-equivalent unread total for `CountOnly` bodies. Never blocks the notify
+/// path: a contended STATE lock falls back to 1 (there is at least this message).
+pub fn pending_unread_total() -> u32 {
+    crate::STATE
+        .try_lock()
+        .map(|s| s.sum_unread())
+        .unwrap_or(0)
+        .max(1)
+}
+
+/// Do Not Disturb, toggled from the desktop tray: while on, no OS notifications (or sounds) are
+/// shown. Lasts until turned off or Vector restarts.
+static DO_NOT_DISTURB: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+pub fn do_not_disturb() -> bool {
+    DO_NOT_DISTURB.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+#[cfg(desktop)]
+pub fn set_do_not_disturb(enabled: bool) {
+    DO_NOT_DISTURB.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Truncate `body` to at most `max` chars on a char boundary, marking the cut with `…`.
+fn shorten_body(body: &str, max: usize) -> String {
+    match body.char_indices().nth(max) {
+        Some((cut, _)) => format!("{}…", body[..cut].trim_end()),
+        None => body.to_string(),
+    }
+}
+
 /// Generic notification data structure
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -69,6 +164,9 @@ pub struct NotificationData {
     pub group_avatar_path: Option<String>,
     /// Chat identifier for notification tap navigation (npub for DMs, group_id for groups)
     pub chat_id: Option<String>,
+    /// Addressed to us (a DM, @mention, reply to us or authorized @everyone): passes a chat's
+    /// mentions-only preference
+    pub ping: bool,
 }
 
 impl NotificationData {
@@ -83,6 +181,7 @@ impl NotificationData {
             avatar_path,
             group_avatar_path: None,
             chat_id: Some(chat_id),
+            ping: true,
         }
     }
 
@@ -105,13 +204,29 @@ impl NotificationData {
             avatar_path,
             group_avatar_path: community_avatar_path,
             chat_id: Some(chat_id),
+            ping: false,
+        }
+    }
+
+    /// Mark whether the message is addressed to us (see [`Self::ping`]).
+    pub fn with_ping(mut self, ping: bool) -> Self {
+        self.ping = ping;
+        self
+    }
+
+    /// Apply every shaping preference: content privacy first, then the body
+    /// length cap. `unread_total` only feeds `CountOnly`. Idempotent.
+    pub fn apply_shaping(&mut self, shaping: &NotifShaping, unread_total: u32) {
+        self.apply_content_privacy(shaping.privacy, unread_total);
+        if shaping.short_body {
+            self.body = shorten_body(&self.body, SHORT_BODY_CHARS);
         }
     }
 
     /// Rewrite the notification's visible fields per the content-privacy
     /// preference. `chat_id` is left intact so tap-to-open still works (it is
     /// not shown). Idempotent.
-    pub fn apply_content_privacy(&mut self, privacy: NotifContentPrivacy) {
+    pub fn apply_content_privacy(&mut self, privacy: NotifContentPrivacy, unread_total: u32) {
         match privacy {
             NotifContentPrivacy::Full => {}
             NotifContentPrivacy::HideContent => {
@@ -130,6 +245,18 @@ impl NotificationData {
                 self.group_name = None;
                 self.group_avatar_path = None;
             }
+            NotifContentPrivacy::CountOnly => {
+                self.title = "Vector".to_string();
+                self.body = if unread_total == 1 {
+                    "1 new message".to_string()
+                } else {
+                    format!("{} new messages", unread_total)
+                };
+                self.sender_name = None;
+                self.avatar_path = None;
+                self.group_name = None;
+                self.group_avatar_path = None;
+            }
         }
     }
 }
@@ -345,6 +472,54 @@ mod mention_tests {
     }
 }
 
+#[cfg(test)]
+mod shaping_tests {
+    use super::*;
+
+    fn dm(body: &str) -> NotificationData {
+        NotificationData::direct_message("Alice".into(), body.into(), Some("/a.png".into()), "npub1x".into())
+    }
+
+    #[test]
+    fn count_only_reveals_nothing_but_the_total() {
+        let shaping = NotifShaping { privacy: NotifContentPrivacy::CountOnly, ..Default::default() };
+        let mut n = dm("secret plans");
+        n.apply_shaping(&shaping, 3);
+        assert_eq!((n.title.as_str(), n.body.as_str()), ("Vector", "3 new messages"));
+        assert!(n.sender_name.is_none() && n.avatar_path.is_none());
+        assert_eq!(n.chat_id.as_deref(), Some("npub1x"));
+
+        let mut one = dm("hi");
+        one.apply_shaping(&shaping, 1);
+        assert_eq!(one.body, "1 new message");
+    }
+
+    #[test]
+    fn short_body_truncates_on_char_boundary_and_is_idempotent() {
+        let shaping = NotifShaping { short_body: true, ..Default::default() };
+        let long = "é".repeat(SHORT_BODY_CHARS + 10);
+        let mut n = dm(&long);
+        n.apply_shaping(&shaping, 1);
+        assert_eq!(n.body.chars().count(), SHORT_BODY_CHARS + 1);
+        assert!(n.body.ends_with('…'));
+
+        let once = n.body.clone();
+        n.apply_shaping(&shaping, 1);
+        assert_eq!(n.body, once);
+
+        let mut short = dm("hello");
+        short.apply_shaping(&shaping, 1);
+        assert_eq!(short.body, "hello");
+    }
+
+    #[test]
+    fn lockscreen_maps_to_android_visibility_constants() {
+        assert_eq!(LockscreenVisibility::Public.android_flag(), 1);
+        assert_eq!(LockscreenVisibility::Private.android_flag(), 0);
+        assert_eq!(LockscreenVisibility::Secret.android_flag(), -1);
+    }
+}
+
 /// Revoke the OS notification for a chat once it's been read (opened in-app) or answered on
 /// another device. Android: cancels the per-chat notification via JNI (no-op if none is showing).
 /// Desktop: no-op (desktop notifications aren't persistent or handle-tracked).
@@ -358,10 +533,30 @@ pub fn cancel_chat_notification(chat_id: &str) {
 
 /// Show an OS notification with generic notification data
 pub fn show_notification_generic(mut data: NotificationData) {
-    // Apply the user's content-privacy preference up front so every platform
-    // path inherits it. Android's background-sync service posts straight to
-    // post_notification_jni, which re-applies it (the transform is idempotent).
-    data.apply_content_privacy(notif_content_privacy());
+    if do_not_disturb() {
+        return;
+    }
+
+    // The chat's own preferences first: mentions-only or a timed mute drop it, and it may be silent.
+    let sound = match data.chat_id.as_deref() {
+        Some(chat_id) => {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            match vector_core::db::notification_prefs::get(chat_id).decide(data.ping, now) {
+                Some(sound) => sound,
+                None => return,
+            }
+        }
+        None => true,
+    };
+
+    // Apply the user's shaping preferences up front so every platform path
+    // inherits them. Android's background-sync service posts straight to
+    // post_notification_jni, which re-applies them (the transform is idempotent).
+    let shaping = notif_shaping();
+    data.apply_shaping(&shaping, pending_unread_total());
 
     // On Android, always use our native JNI notification path.
     // Tauri's notification plugin is unreliable on Android (requires Activity).
@@ -377,6 +572,7 @@ pub fn show_notification_generic(mut data: NotificationData) {
             data.sender_name.as_deref(),
             data.group_name.as_deref(),
             data.group_avatar_path.as_deref(),
+            !sound,
         );
         return;
     }
@@ -400,9 +596,9 @@ pub fn show_notification_generic(mut data: NotificationData) {
             return;
         }
 
-        // Play notification sound (non-blocking)
+        // Play notification sound (non-blocking), unless the chat is set silent
         #[cfg(desktop)]
-        {
+        if sound {
             let handle_clone = handle.clone();
             std::thread::spawn(move || {
                 if let Err(e) = audio::play_notification_if_enabled(&handle_clone) {
@@ -410,6 +606,15 @@ pub fn show_notification_generic(mut data: NotificationData) {
                 }
             });
         }
+        #[cfg(not(desktop))]
+        let _ = sound;
+
+        // Message notifications carry Mark as Read / Reply where the OS reports actions back
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        if let Some(chat_id) = data.chat_id.as_deref() {
+            super::notification_actions::show(&data, chat_id);
+            return;
+        }
 
         handle
             .notification()
@@ -0,0 +1,127 @@
+//! Dwell-based read tracking.
+//!
+//! With a dwell set (`read_dwell_secs`), the frontend stops marking chats read itself on open,
+//! live arrival and window refocus. It reports the chat in view (open, pinned to the bottom, window
+//! active) with a heartbeat instead, and the chat is marked read here once it has stayed in view
+//! for the dwell. A message arriving mid-view restarts the dwell, so it gets its own reading time.
+//! No dwell (the default) keeps the instant frontend marking and the arrival auto-mark.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Setting key: seconds a chat must stay in view before it's marked read (absent/0 = instantly).
+pub const READ_DWELL_SETTING: &str = "read_dwell_secs";
+
+/// Longest accepted dwell.
+const MAX_DWELL_SECS: u64 = 60;
+
+/// The frontend heartbeats every second while a chat is in view; a longer silence than this
+/// means the view was lost without a report (sleep, reload) and the dwell starts over.
+const HEARTBEAT_STALE: Duration = Duration::from_secs(5);
+
+struct Viewing {
+    chat_id: String,
+    since: Instant,
+    last_seen: Instant,
+    dwell: Duration,
+}
+
+static VIEWING: Mutex<Option<Viewing>> = Mutex::new(None);
+
+/// The configured dwell, or `None` when chats are marked read instantly.
+pub fn read_dwell() -> Option<Duration> {
+    let secs = crate::db::get_sql_setting(READ_DWELL_SETTING.to_string())
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(0)
+        .min(MAX_DWELL_SECS);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Record a view report (`None` = nothing in view) at `now`; returns the chat whose dwell has
+/// elapsed, if any.
+fn observe(slot: &mut Option<Viewing>, chat_id: Option<&str>, now: Instant, dwell: Duration) -> Option<String> {
+    let Some(chat_id) = chat_id else {
+        *slot = None;
+        return None;
+    };
+    match slot {
+        Some(v) if v.chat_id == chat_id && now.duration_since(v.last_seen) <= HEARTBEAT_STALE => {
+            v.last_seen = now;
+        }
+        _ => {
+            *slot = Some(Viewing { chat_id: chat_id.to_string(), since: now, last_seen: now, dwell });
+        }
+    }
+    let v = slot.as_ref()?;
+    (now.duration_since(v.since) >= v.dwell).then(|| v.chat_id.clone())
+}
+
+/// Restart the dwell for `chat_id` if it's the chat in view (a new message just landed in it).
+pub fn restart_dwell(chat_id: &str) {
+    let mut slot = VIEWING.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(v) = slot.as_mut().filter(|v| v.chat_id == chat_id) {
+        v.since = Instant::now();
+    }
+}
+
+/// Forget the chat in view (account swap).
+pub fn reset() {
+    *VIEWING.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Handle a view report from the frontend, marking the chat read once its dwell has elapsed and
+/// it holds something unread.
+pub async fn report_view(chat_id: Option<String>) {
+    let Some(dwell) = read_dwell() else {
+        reset();
+        return;
+    };
+    let due = {
+        let mut slot = VIEWING.lock().unwrap_or_else(|e| e.into_inner());
+        observe(&mut slot, chat_id.as_deref(), Instant::now(), dwell)
+    };
+    let Some(chat_id) = due else { return };
+
+    let has_unread = {
+        let state = crate::STATE.lock().await;
+        state.get_chat(&chat_id).is_some_and(|chat| {
+            chat.messages.iter().rev()
+                .find(|m| !m.flags.is_mine())
+                .is_some_and(|m| m.id != chat.last_read)
+        })
+    };
+    if has_unread && crate::chat::mark_as_read_headless(&chat_id).await {
+        if let Some(handle) = crate::TAURI_APP.get() {
+            crate::commands::messaging::update_unread_counter(handle.clone()).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_after_dwell_and_restarts_on_gaps() {
+        let dwell = Duration::from_secs(3);
+        let t0 = Instant::now();
+        let at = |s: u64| t0 + Duration::from_secs(s);
+        let mut slot = None;
+
+        assert_eq!(observe(&mut slot, Some("a"), at(0), dwell), None);
+        assert_eq!(observe(&mut slot, Some("a"), at(2), dwell), None);
+        assert_eq!(observe(&mut slot, Some("a"), at(3), dwell).as_deref(), Some("a"));
+
+        // Switching chats starts a fresh dwell
+        assert_eq!(observe(&mut slot, Some("b"), at(4), dwell), None);
+        assert_eq!(observe(&mut slot, Some("b"), at(7), dwell).as_deref(), Some("b"));
+
+        // Leaving and coming back starts over, as does a heartbeat gap
+        assert_eq!(observe(&mut slot, None, at(8), dwell), None);
+        assert_eq!(observe(&mut slot, Some("b"), at(9), dwell), None);
+        assert_eq!(observe(&mut slot, Some("b"), at(20), dwell), None, "stale heartbeat restarts the dwell");
+        assert_eq!(observe(&mut slot, Some("b"), at(23), dwell).as_deref(), Some("b"));
+    }
+}
@@ -0,0 +1,96 @@
+//! Runtime path self-check.
+//!
+//! Lists every directory Vector reads or writes and probes each one for access. Sandboxes
+//! (Firejail, Flatpak overrides, AppArmor profiles) often block some of them. The check runs
+//! once at startup, logs what's inaccessible, and keeps the report for `get_runtime_paths`, so
+//! the UI can tell the user exactly which paths to whitelist instead of failing somewhere
+//! downstream.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+/// One directory Vector needs, and whether it's usable.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RuntimePath {
+    /// Short id, e.g. `app_data`.
+    pub name: &'static str,
+    /// What Vector keeps there.
+    pub purpose: &'static str,
+    /// Empty when the OS couldn't resolve the location at all.
+    pub path: String,
+    pub exists: bool,
+    pub readable: bool,
+    pub writable: bool,
+    /// Why it isn't usable, if it isn't.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RuntimePath {
+    pub fn ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+static LAST_REPORT: Mutex<Vec<RuntimePath>> = Mutex::new(Vec::new());
+
+/// Create `dir` if needed, then confirm it can be listed and written (a probe file is written
+/// and removed).
+fn probe(name: &'static str, purpose: &'static str, dir: Result<PathBuf, String>) -> RuntimePath {
+    let mut entry = RuntimePath { name, purpose, path: String::new(), exists: false, readable: false, writable: false, error: None };
+    let dir = match dir {
+        Ok(dir) => dir,
+        Err(e) => {
+            entry.error = Some(format!("Couldn't resolve the location: {}", e));
+            return entry;
+        }
+    };
+    entry.path = dir.to_string_lossy().to_string();
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        entry.error = Some(format!("Couldn't create it: {}", e));
+        return entry;
+    }
+    entry.exists = true;
+    entry.readable = std::fs::read_dir(&dir).is_ok();
+    let write = probe_write(&dir);
+    entry.writable = write.is_ok();
+    entry.error = match (entry.readable, write) {
+        (true, Ok(())) => None,
+        (false, _) => Some("Can't be read".to_string()),
+        (true, Err(e)) => Some(format!("Can't be written: {}", e)),
+    };
+    entry
+}
+
+fn probe_write(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!(".vector-access-check-{}", std::process::id()));
+    std::fs::write(&probe, b"ok")?;
+    std::fs::remove_file(&probe)
+}
+
+/// Check every directory Vector uses. The temp dir is included since attachment encoding and
+/// picked wallpapers stage files there.
+pub fn check<R: Runtime>(handle: &AppHandle<R>) -> Vec<RuntimePath> {
+    let path = handle.path();
+    let app_data = path.app_data_dir().map_err(|e| e.to_string());
+    let report = vec![
+        probe("app_data", "Accounts, databases, logs and the image cache", app_data.clone()),
+        probe("app_config", "App configuration", path.app_config_dir().map_err(|e| e.to_string())),
+        probe("app_local_data", "Voice-transcription models and web view data", path.app_local_data_dir().map_err(|e| e.to_string())),
+        probe("downloads", "Downloaded and sent attachments", Ok(vector_core::db::get_download_dir())),
+        probe("mini_apps", "Installed Mini Apps", app_data.map(|d| d.join("miniapps"))),
+        probe("temp", "Scratch files while sending media", Ok(std::env::temp_dir())),
+    ];
+    for p in report.iter().filter(|p| !p.ok()) {
+        eprintln!("[Paths] {} ({}) is not usable: {}", p.name, p.path, p.error.as_deref().unwrap_or_default());
+    }
+    *LAST_REPORT.lock().unwrap_or_else(|e| e.into_inner()) = report.clone();
+    report
+}
+
+/// The report from the last check, if one has run.
+pub fn last_report() -> Vec<RuntimePath> {
+    LAST_REPORT.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
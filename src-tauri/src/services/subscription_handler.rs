@@ -3,6 +3,8 @@
 //! This module handles:
 //! - GiftWrap subscription (DMs, files)
 //! - Community (kind-3300) message subscription
+//! - Official announcements (project-signed, trusted relays only)
+//! - Zap receipts (NIP-57) to and from us
 
 use nostr_sdk::prelude::*;
 
@@ -29,6 +31,14 @@ pub(crate) static SELFSYNC_SUB_IDS: LazyLock<Mutex<Vec<SubscriptionId>>> =
 static SELFSYNC_LAST_EVENT: LazyLock<Mutex<HashMap<String, EventId>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
+/// The open announcements subscription (trusted relays only), if the account hasn't opted out.
+static ANNOUNCEMENT_SUB_ID: LazyLock<Mutex<Option<SubscriptionId>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// The open zap-receipt subscription.
+static ZAP_SUB_ID: LazyLock<Mutex<Option<SubscriptionId>>> =
+    LazyLock::new(|| Mutex::new(None));
+
 // `rebuild_community_routes` + `refresh_community_subscription` route state now lives in
 // `vector_core::community::realtime`; `refresh_community_subscription` below stays as a thin wrapper
 // for the call sites that trigger a resubscribe (join/leave/ban/etc.).
@@ -54,14 +64,17 @@ pub(crate) async fn subscribe_self_sync() {
     // unsubscribe whatever it displaced — so two concurrent calls (start racing a swap re-entry) can't leak
     // an orphaned subscription or leave the routing set momentarily empty.
     let mut new_ids = Vec::new();
-    // Community List + Invite List — both parameterized-replaceable kind-30078, d-tag scoped so they never
-    // alias a wallpaper/badge 30078. One filter (both d-tags) keeps the live sub as wire-efficient as boot.
+    // Community List + Invite List + synced settings + chat state — all parameterized-replaceable kind-30078, d-tag scoped
+    // so they never alias a wallpaper/badge 30078. One filter (all d-tags) keeps the live sub as wire-efficient
+    // as boot.
     let self_lists_filter = Filter::new()
         .author(my_pk)
         .kind(Kind::Custom(vector_core::stored_event::event_kind::APPLICATION_SPECIFIC))
         .identifiers([
             vector_core::community::list::COMMUNITY_LIST_D_TAG.to_string(),
             vector_core::community::invite_list::INVITE_LIST_D_TAG.to_string(),
+            vector_core::settings_sync::SETTINGS_D_TAG.to_string(),
+            vector_core::chat_state_sync::CHAT_STATE_D_TAG.to_string(),
         ]);
     match client.subscribe(self_lists_filter, None).await {
         Ok(out) => new_ids.push(out.val),
@@ -73,6 +86,18 @@ pub(crate) async fn subscribe_self_sync() {
         Ok(out) => new_ids.push(out.val),
         Err(e) => eprintln!("[self-sync] emoji-list subscribe failed: {:?}", e),
     }
+    // NIP-02 contact list (replaceable kind 3), shared with other Nostr clients.
+    let contacts_filter = Filter::new().author(my_pk).kind(Kind::ContactList);
+    match client.subscribe(contacts_filter, None).await {
+        Ok(out) => new_ids.push(out.val),
+        Err(e) => eprintln!("[self-sync] contact-list subscribe failed: {:?}", e),
+    }
+    // NIP-51 mute list (replaceable kind 10000) — the block list, shared with other Nostr clients.
+    let mute_filter = Filter::new().author(my_pk).kind(Kind::MuteList);
+    match client.subscribe(mute_filter, None).await {
+        Ok(out) => new_ids.push(out.val),
+        Err(e) => eprintln!("[self-sync] mute-list subscribe failed: {:?}", e),
+    }
 
     let displaced = {
         let mut ids = SELFSYNC_SUB_IDS.lock().await;
@@ -83,9 +108,137 @@ pub(crate) async fn subscribe_self_sync() {
     }
 }
 
+/// (Re)subscribe to official announcements on the trusted relays, or drop the subscription when the
+/// account has opted out. Open sub from the stored cursor: the relay replays anything missed while
+/// offline, then streams new notices live. Idempotent (account swap / toggle).
+pub(crate) async fn subscribe_announcements() {
+    let Some(client) = nostr_client() else { return };
+    let mut new_id = None;
+    if vector_core::announcements::enabled() {
+        let relays = vector_core::state::active_trusted_relays().await;
+        if let Some(filter) = vector_core::announcements::subscription_filter() {
+            if !relays.is_empty() {
+                match client.subscribe_to(relays, filter, None).await {
+                    Ok(out) => new_id = Some(out.val),
+                    Err(e) => eprintln!("[Announcements] subscribe failed: {:?}", e),
+                }
+            }
+        }
+    }
+    let displaced = std::mem::replace(&mut *ANNOUNCEMENT_SUB_ID.lock().await, new_id);
+    if let Some(id) = displaced {
+        client.unsubscribe(&id).await;
+    }
+}
+
+/// (Re)subscribe to zap receipts naming us as recipient or sender. Open sub: the relay replays stored
+/// receipts on connect (already-known ones dedup by id), then streams new ones. Idempotent.
+pub(crate) async fn subscribe_zaps() {
+    let Some(client) = nostr_client() else { return };
+    let Some(my_pk) = vector_core::my_public_key() else { return };
+    let new_id = match client.subscribe(vector_core::zaps::subscription_filters(my_pk), None).await {
+        Ok(out) => Some(out.val),
+        Err(e) => {
+            eprintln!("[Zaps] subscribe failed: {:?}", e);
+            None
+        }
+    };
+    let displaced = std::mem::replace(&mut *ZAP_SUB_ID.lock().await, new_id);
+    if let Some(id) = displaced {
+        client.unsubscribe(&id).await;
+    }
+}
+
+/// Store an arriving zap receipt and, for a fresh zap to us, notify. A zap notifies like a DM from
+/// its sender: silent when their chat is muted or they're blocked.
+async fn handle_zap_receipt(session: &vector_core::state::SessionGuard, event: Event) {
+    if !session.is_valid() {
+        return;
+    }
+    let Some(receipt) = vector_core::zaps::ingest(&event).await else { return };
+    let mine = vector_core::my_public_key().and_then(|pk| pk.to_bech32().ok());
+    let fresh = Timestamp::now().as_secs().saturating_sub(receipt.created_at) < 24 * 60 * 60;
+    if mine.as_deref() != Some(receipt.recipient.as_str()) || receipt.sender == receipt.recipient || !fresh {
+        return;
+    }
+
+    let (sender_name, avatar, silenced) = {
+        let state = crate::STATE.lock().await;
+        let profile = state.get_profile(&receipt.sender);
+        let name = profile.map(|p| {
+            if !p.nickname().is_empty() { p.nickname().to_string() }
+            else if !p.name.is_empty() { p.name.to_string() }
+            else { "Someone".to_string() }
+        }).unwrap_or_else(|| "Someone".to_string());
+        let avatar = profile.filter(|p| !p.avatar_cached.is_empty()).map(|p| p.avatar_cached.to_string());
+        let blocked = profile.is_some_and(|p| p.flags.is_blocked());
+        let muted = state.get_chat(&receipt.sender).is_some_and(|c| c.muted);
+        (name, avatar, blocked || muted)
+    };
+    if silenced {
+        return;
+    }
+    let sats = receipt.amount_msat / 1000;
+    let mut body = format!("⚡ Zapped you {} sat{}", sats, if sats == 1 { "" } else { "s" });
+    if !receipt.comment.is_empty() {
+        body = format!("{}: {}", body, crate::services::strip_content_for_preview(&receipt.comment));
+    }
+    let notification = crate::services::NotificationData::direct_message(
+        sender_name, body, avatar, receipt.sender.clone(),
+    ).with_ping(false);
+    crate::services::show_notification_generic(notification);
+}
+
+/// Store an arriving announcement in the system chat and notify. Version notices only apply to builds
+/// without the updater plugin (desktop updates itself) and only when newer than what's running. The
+/// notification respects the global and the system chat's mute, and backfilled (older than a day)
+/// notices stay silent.
+async fn handle_announcement_event(session: &vector_core::state::SessionGuard, event: Event) {
+    if !session.is_valid() || !vector_core::announcements::enabled() {
+        return;
+    }
+    let Ok(announcement) = vector_core::announcements::parse_official(&event, std::env::consts::OS) else { return };
+    if let Some(version) = &announcement.version {
+        if cfg!(desktop) {
+            return;
+        }
+        let Some(handle) = crate::TAURI_APP.get() else { return };
+        let current = handle.package_info().version.to_string();
+        if !crate::commands::updates::version_is_newer(version, &current) {
+            return;
+        }
+    }
+    let created_at = announcement.created_at;
+    let Ok(Some(msg)) = vector_core::announcements::store(announcement).await else { return };
+    if !session.is_valid() {
+        return;
+    }
+
+    let chat_id = vector_core::announcements::system_chat_id();
+    let fresh = Timestamp::now().as_secs().saturating_sub(created_at) < 24 * 60 * 60;
+    let globally_muted = vector_core::db::settings::get_sql_setting("notif_global_mute".to_string())
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true");
+    let muted = globally_muted || {
+        let state = crate::STATE.lock().await;
+        state.get_chat(chat_id).map_or(false, |c| c.muted)
+    };
+    if fresh && !muted {
+        let body = crate::services::strip_content_for_preview(&msg.content);
+        let notification = crate::services::NotificationData::direct_message(
+            "Vector Announcements".to_string(), body, None, chat_id.to_string(),
+        ).with_ping(false);
+        crate::services::show_notification_generic(notification);
+    }
+    if let Some(handle) = crate::TAURI_APP.get() {
+        let _ = crate::commands::messaging::update_unread_counter(handle.clone()).await;
+    }
+}
+
 /// Route an arriving self-sync list event (our own replaceable settings): a Community List update folds +
-/// rehydrates (so a join on another device appears live); an emoji-list update refreshes the pack set.
-/// Spawned off the notification loop — both run several relay fetches and must not head-of-line-block it.
+/// rehydrates (so a join on another device appears live); an emoji-list update refreshes the pack set; a
+/// contact list (kind 3) or mute list (kind 10000), maybe from another client, merges into ours. Spawned off the notification loop — both run several relay fetches and must not head-of-line-block it.
 async fn handle_self_sync_event(session: &vector_core::state::SessionGuard, event: Event) {
     if !session.is_valid() {
         return;
@@ -106,11 +259,18 @@ async fn handle_self_sync_event(session: &vector_core::state::SessionGuard, even
     }
     match event.kind.as_u16() {
         k if k == vector_core::stored_event::event_kind::APPLICATION_SPECIFIC => {
-            // Both lists are kind 30078 — route by `d`-tag.
-            let is_invite = event.tags.identifier()
-                == Some(vector_core::community::invite_list::INVITE_LIST_D_TAG);
+            // All four lists are kind 30078 — route by `d`-tag.
+            let d_tag = event.tags.identifier().map(str::to_string);
+            let is_invite = d_tag.as_deref() == Some(vector_core::community::invite_list::INVITE_LIST_D_TAG);
+            let is_settings = d_tag.as_deref() == Some(vector_core::settings_sync::SETTINGS_D_TAG);
+            let is_chat_state = d_tag.as_deref() == Some(vector_core::chat_state_sync::CHAT_STATE_D_TAG);
             tokio::spawn(async move {
-                if is_invite {
+                if is_settings {
+                    vector_core::settings_sync::ingest_remote_event(&event, &crate::profile_sync::TauriProfileSyncHandler).await;
+                } else if is_chat_state {
+                    let applied = vector_core::chat_state_sync::ingest_remote_event(&event, &crate::profile_sync::TauriProfileSyncHandler).await;
+                    crate::chat::on_chat_state_synced(&applied).await;
+                } else if is_invite {
                     crate::commands::community::ingest_invite_list_update(event).await;
                 } else {
                     crate::commands::community::ingest_community_list_update(event).await;
@@ -122,6 +282,16 @@ async fn handle_self_sync_event(session: &vector_core::state::SessionGuard, even
                 let _ = vector_core::emoji_packs::refresh_subscribed_packs().await;
             });
         }
+        3 => {
+            tokio::spawn(async move {
+                vector_core::contacts::ingest_remote_event(&event).await;
+            });
+        }
+        10000 => {
+            tokio::spawn(async move {
+                vector_core::mute_list::ingest_remote_event(&event, &crate::profile_sync::TauriProfileSyncHandler).await;
+            });
+        }
         _ => {}
     }
 }
@@ -203,6 +373,9 @@ pub(crate) async fn show_community_notification(chat_id: &str, msg: &vector_core
     let reply_ping = !msg.replied_to.is_empty()
         && vector_core::db::events::is_own_event(&msg.replied_to);
 
+    // Addressed to us: also what passes the channel's mentions-only preference.
+    let ping = msg.mentions_me() || reply_ping || everyone_ping;
+
     let should_notify = {
         let state = crate::STATE.lock().await;
         // Only a community's surfaced (primary) row notifies — sibling-channel rows
@@ -210,12 +383,11 @@ pub(crate) async fn show_community_notification(chat_id: &str, msg: &vector_core
         let registered = state
             .get_chat(chat_id)
             .is_some_and(|c| c.metadata.custom_fields.contains_key("community_id"));
-        let mentions_me = msg.mentions_me();
         let sender_blocked = state.get_profile(sender_npub).map_or(false, |p| p.flags.is_blocked());
         let sender_dm_muted = state.get_chat(sender_npub).map_or(false, |c| c.muted);
         if !registered || sender_blocked {
             false
-        } else if mentions_me || reply_ping || everyone_ping {
+        } else if ping {
             // Pings bypass a muted CHANNEL, but never a muted/blocked sender.
             !sender_dm_muted
         } else {
@@ -239,7 +411,10 @@ pub(crate) async fn show_community_notification(chat_id: &str, msg: &vector_core
             .unwrap_or_else(|| "Community".to_string());
         let content = if is_file {
             let ext = msg.attachments.first().map(|a| a.extension.clone()).unwrap_or_else(|| "file".into());
-            "Sent a ".to_string() + &crate::util::get_file_type_description(&ext)
+            let caption = crate::services::strip_content_for_preview(
+                &crate::services::resolve_mention_display_names(&msg.content, &state)
+            );
+            crate::util::file_notification_body(&ext, &caption)
         } else {
             crate::services::strip_content_for_preview(
                 &crate::services::resolve_mention_display_names(&msg.content, &state)
@@ -264,7 +439,7 @@ pub(crate) async fn show_community_notification(chat_id: &str, msg: &vector_core
 
     let notification = crate::services::NotificationData::community_message(
         sender_name, community_name, content, avatar, community_avatar, chat_id.to_string(),
-    );
+    ).with_ping(ping);
     crate::services::show_notification_generic(notification);
 }
 
@@ -346,6 +521,12 @@ pub(crate) async fn start_subscriptions() -> Result<bool, String> {
     // boot, reconnect, AND instant cross-device in one open subscription.
     subscribe_self_sync().await;
 
+    // Official announcements — trusted relays only, opt-out per account.
+    subscribe_announcements().await;
+
+    // Zap receipts to and from us (NIP-57).
+    subscribe_zaps().await;
+
     // v2 reconnect catch-up: a `limit(0)` sub never replays what a relay missed
     // while down, so each Connected transition enqueues a refold + re-tracks the
     // subs at the current epochs (debounced across a reconnect burst). v1 leans
@@ -384,11 +565,12 @@ pub(crate) async fn start_subscriptions() -> Result<bool, String> {
             // notification loop. Returning Ok(true) tells nostr-sdk to break.
             if !session.is_valid() { return Ok(true); }
             match notification {
-                RelayPoolNotification::Event { event, subscription_id, .. } => {
+                RelayPoolNotification::Event { relay_url, event, subscription_id } => {
                     let k = event.kind.as_u16();
                     if subscription_id == gift_sub_id {
-                        // DMs/files/reactions/edits (via tauri_commit_prepared_event)
-                        super::handle_event(*event, true).await;
+                        // DMs/files/reactions/edits (via tauri_commit_prepared_event), scoring
+                        // the delivering relay as we go
+                        super::handle_relay_event(&relay_url, *event).await;
                     } else if (3300..=3311).contains(&k) {
                         // Route Community events by KIND, not by subscription id: an event can arrive on the
                         // live community sub OR on a fetch/sync/reconcile sub, so matching only the live sub
@@ -403,6 +585,13 @@ pub(crate) async fn start_subscriptions() -> Result<bool, String> {
                         handle_community_v2_event(&session, *event).await;
                     } else if SELFSYNC_SUB_IDS.lock().await.contains(&subscription_id) {
                         handle_self_sync_event(&session, *event).await;
+                    } else if k == vector_core::announcements::KIND_ANNOUNCEMENT {
+                        // Verified against the project key inside; anything else is dropped.
+                        let session = session;
+                        tokio::spawn(async move { handle_announcement_event(&session, *event).await });
+                    } else if k == vector_core::zaps::KIND_ZAP_RECEIPT {
+                        let session = session;
+                        tokio::spawn(async move { handle_zap_receipt(&session, *event).await });
                     }
                 }
                 RelayPoolNotification::Message { message, .. } => {
@@ -0,0 +1,113 @@
+//! Desktop system tray.
+//!
+//! The tray icon shows the unread count (tooltip, plus the text beside the icon where the OS has
+//! one) and a menu with quick actions: open Vector, toggle Do Not Disturb, quit. With
+//! minimize-to-tray on, closing the window hides it instead of quitting; a left click on the icon
+//! brings it back.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::services::notification_service;
+
+const TRAY_ID: &str = "main";
+
+/// Marker file in the app data dir: present = closing the window hides it to the tray. A file
+/// rather than an account setting, since the window can be closed before any account is unlocked.
+const MINIMIZE_TO_TRAY_FILE: &str = "minimize_to_tray";
+
+static MINIMIZE_TO_TRAY: AtomicBool = AtomicBool::new(false);
+
+/// Create the tray icon. Call once from `setup`, after the app data dir is known.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    MINIMIZE_TO_TRAY.store(marker_path().is_some_and(|p| p.exists()), Ordering::Relaxed);
+
+    let open = MenuItem::with_id(app, "open", "Open Vector", true, None::<&str>)?;
+    let dnd = CheckMenuItem::with_id(
+        app, "dnd", "Do Not Disturb", true, notification_service::do_not_disturb(), None::<&str>,
+    )?;
+    let quit = MenuItem::with_id(app, "quit", "Quit Vector", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&open, &dnd, &PredefinedMenuItem::separator(app)?, &quit])?;
+
+    let dnd_item = dnd.clone();
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Vector")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(move |app, event| match event.id().as_ref() {
+            "open" => show_main_window(app),
+            // The check mark flips itself on click; follow it
+            "dnd" => notification_service::set_do_not_disturb(dnd_item.is_checked().unwrap_or(false)),
+            "quit" => {
+                crate::prepare_exit(app);
+                app.exit(0);
+            }
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// Reflect the unread total in the tray.
+pub fn set_unread<R: Runtime>(app: &AppHandle<R>, count: u32) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+    let tooltip = match count {
+        0 => "Vector".to_string(),
+        1 => "Vector — 1 unread message".to_string(),
+        n => format!("Vector — {} unread messages", n),
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+    // Text beside the icon: the menu bar on macOS, the indicator label on Linux (none on Windows)
+    let _ = tray.set_title((count > 0).then(|| count.to_string()));
+}
+
+/// Whether closing the window should hide it to the tray instead of quitting.
+pub fn minimize_to_tray() -> bool {
+    MINIMIZE_TO_TRAY.load(Ordering::Relaxed)
+}
+
+pub fn set_minimize_to_tray(enabled: bool) -> Result<(), String> {
+    let path = marker_path().ok_or("Couldn't locate the app data folder")?;
+    if enabled {
+        std::fs::write(&path, b"").map_err(|e| format!("Failed to save setting: {}", e))?;
+    } else {
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!("Failed to save setting: {}", e));
+            }
+            _ => {}
+        }
+    }
+    MINIMIZE_TO_TRAY.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Hide the main window to the tray.
+pub fn hide_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+}
+
+fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn marker_path() -> Option<std::path::PathBuf> {
+    crate::account_manager::get_app_data_dir().ok().map(|d| d.join(MINIMIZE_TO_TRAY_FILE))
+}
@@ -330,6 +330,17 @@ pub fn get_file_type_description(extension: &str) -> String {
         .to_string()
 }
 
+/// Notification body for an attachment: "Sent a Picture", or "Picture: check this out"
+/// when it came with a caption (already stripped to plain text by the caller).
+pub fn file_notification_body(extension: &str, caption: &str) -> String {
+    let description = get_file_type_description(extension);
+    if caption.trim().is_empty() {
+        format!("Sent a {}", description)
+    } else {
+        format!("{}: {}", description, caption.trim())
+    }
+}
+
 /// Calculate SHA-256 hash of file data
 pub fn calculate_file_hash(data: &[u8]) -> String {
     vector_core::crypto::sha256_hex(data)
@@ -410,6 +421,17 @@ pub fn generate_thumbhash_from_image(img: &image::DynamicImage) -> Option<String
     Some(base91_encode(&hash))
 }
 
+/// Preview metadata for a video from its poster frame: the frame's thumbhash, with the video's
+/// own dimensions (the poster may be scaled down).
+pub fn poster_metadata(poster: &[u8], width: u32, height: u32) -> Option<vector_core::types::ImageMetadata> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let img = vector_core::crypto::decode_image_bounded(poster).ok()?;
+    let thumbhash = generate_thumbhash_from_image(&img)?;
+    Some(vector_core::types::ImageMetadata { thumbhash, width, height })
+}
+
 /// Decode a thumbhash string to a Base64-encoded PNG data URL
 /// Returns a data URL string that can be used directly in an <img> src attribute
 pub fn decode_thumbhash_to_base64(thumbhash: &str) -> String {
@@ -0,0 +1,110 @@
+//! Video notes — short camera clips recorded in the composer, the video counterpart of voice
+//! messages (`voice.rs`).
+//!
+//! The webview owns the camera: it captures with `getUserMedia` and encodes with `MediaRecorder`
+//! (H.264 in MP4 where the platform has it, VP8/VP9 in WebM otherwise), so no encoder ships with
+//! the app. The backend takes the finished clip plus a small poster frame, derives the preview
+//! thumbhash and dimensions from it, and sends the clip as a nameless video marked as a video note
+//! (`Attachment::video_note`, a `video-note` tag on the wire), which both ends file under
+//! `MediaKind::VideoNote`.
+
+use std::sync::Arc;
+
+use vector_core::sending::{SendCallback, SendConfig};
+use vector_core::types::ImageMetadata;
+
+use crate::message::{MessageSendResult, TauriSendCallback};
+use crate::STATE;
+
+/// Longest clip the recorder takes, in seconds.
+pub const MAX_DURATION_SECS: u32 = 60;
+
+/// Largest clip we send.
+pub const MAX_BYTES: usize = 32 * 1024 * 1024;
+
+/// What the composer needs to know before offering the camera.
+#[derive(serde::Serialize)]
+pub struct VideoNoteSupport {
+    pub supported: bool,
+    pub max_duration_secs: u32,
+    pub max_bytes: usize,
+}
+
+pub fn support() -> VideoNoteSupport {
+    VideoNoteSupport {
+        // WebKitGTK can neither capture nor play inline video reliably, and Android's webview
+        // isn't granted the camera.
+        supported: cfg!(any(target_os = "macos", target_os = "windows", target_os = "ios")),
+        max_duration_secs: MAX_DURATION_SECS,
+        max_bytes: MAX_BYTES,
+    }
+}
+
+/// The attachment extension for a recorder MIME type (`video/webm;codecs=vp9` → `webm`).
+pub fn extension_for_mime(mime: &str) -> Option<&'static str> {
+    match mime.split(';').next()?.trim() {
+        "video/mp4" => Some("mp4"),
+        "video/webm" => Some("webm"),
+        _ => None,
+    }
+}
+
+/// Send a recorded clip to a DM, through the regular encrypted Blossom file path.
+pub async fn send(
+    receiver: String,
+    bytes: Vec<u8>,
+    mime: &str,
+    poster: Option<ImageMetadata>,
+    caption: Option<String>,
+) -> Result<MessageSendResult, String> {
+    if bytes.is_empty() {
+        return Err("Empty recording".to_string());
+    }
+    if bytes.len() > MAX_BYTES {
+        return Err(format!(
+            "Video note is too large ({}, max {})",
+            crate::util::format_bytes(bytes.len() as u64),
+            crate::util::format_bytes(MAX_BYTES as u64),
+        ));
+    }
+    let extension = extension_for_mime(mime)
+        .ok_or_else(|| format!("Unsupported video format: {}", mime))?;
+
+    let is_community = {
+        let state = STATE.lock().await;
+        match state.get_chat(&receiver) {
+            Some(chat) => chat.is_community(),
+            None => !vector_core::ids::is_dm_chat_id(&receiver),
+        }
+    };
+    if is_community {
+        return Err("Video notes are only supported in direct messages".to_string());
+    }
+
+    let config = SendConfig {
+        expiration: vector_core::self_destruct::resolve_send_expiry(&receiver),
+        poster,
+        video_note: true,
+        ..SendConfig::gui()
+    };
+    let callback: Arc<dyn SendCallback> = Arc::new(TauriSendCallback);
+    let caption = caption.filter(|c| !c.trim().is_empty());
+    let result = vector_core::sending::send_file_dm(
+        &receiver, Arc::new(bytes), "", extension, caption.as_deref(), &config, callback,
+    ).await?;
+    Ok(MessageSendResult { pending_id: result.pending_id, event_id: result.event_id })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorder_formats_map_to_extensions() {
+        assert_eq!(extension_for_mime("video/mp4"), Some("mp4"));
+        assert_eq!(extension_for_mime("video/mp4;codecs=avc1.42E01E"), Some("mp4"));
+        assert_eq!(extension_for_mime("video/webm; codecs=vp9,opus"), Some("webm"));
+        assert_eq!(extension_for_mime("video/x-matroska"), None);
+        assert_eq!(extension_for_mime("audio/webm"), None);
+    }
+}
@@ -0,0 +1,106 @@
+/**
+ * Per-chat appearance: a chat can swap the theme's accent for its own, and
+ * carry our own wallpaper override (any chat, shown instead of a DM's shared
+ * wallpaper).
+ *
+ * The backend stores both (`appearance`) and carries them to our other
+ * devices; changes made there arrive as `chat_appearance_changed`. The
+ * accent is applied by overriding the theme's color variables on `#chat`,
+ * so it only tints the open chat.
+ */
+
+/** @type {Object<string, string>} chat id → `#rrggbb` */
+let objChatAccents = {};
+
+/** @type {Object<string, {path: string, blur: number, dim: number, updated_at: number}>} chat id → our wallpaper override */
+let objChatWallpaperOverrides = {};
+
+/** The picker's presets, tuned to read well on the dark themes. */
+const CHAT_ACCENT_PRESETS = [
+    { name: 'Green', color: '#59fcb3' },
+    { name: 'Blue', color: '#4da3ff' },
+    { name: 'Purple', color: '#b084ff' },
+    { name: 'Pink', color: '#ff5fa2' },
+    { name: 'Red', color: '#ff5c5c' },
+    { name: 'Orange', color: '#ffa24d' },
+    { name: 'Yellow', color: '#ffd84d' },
+];
+
+/** The theme variables an accent overrides. */
+const CHAT_ACCENT_VARS = ['--icon-color-primary', '--primary-color', '--accent-color', '--reply-highlight-border'];
+
+/** Load every chat's accent from the backend, then re-tint the open chat. */
+async function loadChatAccents() {
+    try {
+        objChatAccents = await invoke('get_chat_accent_colors');
+    } catch (e) {
+        console.warn('[ChatAccent] load failed:', e);
+        objChatAccents = {};
+    }
+    applyChatAccent();
+}
+
+/** Load every chat's wallpaper override from the backend, then refresh the open chat's wallpaper. */
+async function loadChatWallpaperOverrides() {
+    try {
+        objChatWallpaperOverrides = await invoke('get_chat_wallpaper_overrides');
+    } catch (e) {
+        console.warn('[ChatAppearance] wallpaper overrides load failed:', e);
+        objChatWallpaperOverrides = {};
+    }
+    if (strOpenChat) refreshChatWallpaper();
+}
+
+/** The wallpaper to show in a chat: our override, else a DM's shared one. */
+function chatWallpaper(chatId) {
+    const own = objChatWallpaperOverrides[chatId];
+    if (own) return { path: own.path, blur: own.blur, dim: own.dim, ts: own.updated_at, override: true };
+    const chat = getChat(chatId);
+    return {
+        path: chat?.wallpaper_path || '',
+        blur: chat?.wallpaper_blur,
+        dim: chat?.wallpaper_dim,
+        ts: chat?.wallpaper_ts,
+        override: false,
+    };
+}
+
+/** Tint `#chat` with the open chat's accent, or hand it back to the theme. */
+function applyChatAccent() {
+    const domChat = document.getElementById('chat');
+    if (!domChat) return;
+    const color = strOpenChat ? objChatAccents[strOpenChat] : null;
+    for (const prop of CHAT_ACCENT_VARS) {
+        if (color) domChat.style.setProperty(prop, color);
+        else domChat.style.removeProperty(prop);
+    }
+}
+
+async function setChatAccent(chatId, color) {
+    try {
+        const stored = await invoke('set_chat_accent_color', { chatId, color });
+        if (stored) objChatAccents[chatId] = stored;
+        else delete objChatAccents[chatId];
+    } catch (e) {
+        return showToast(String(e));
+    }
+    if (chatId === strOpenChat) applyChatAccent();
+}
+
+/** The chat menu's accent picker, anchored to a rect: the presets (current one ticked) and the theme's own. */
+function openChatAccentPicker(chatId, anchor) {
+    const rect = anchor || { right: window.innerWidth / 2, bottom: window.innerHeight / 2 };
+    const current = objChatAccents[chatId] || null;
+    const items = CHAT_ACCENT_PRESETS.map(({ name, color }) => ({
+        label: name,
+        hint: color === current ? '✓' : undefined,
+        onClick: () => setChatAccent(chatId, color),
+    }));
+    items.push({ divider: true });
+    items.push({
+        label: 'Theme Default',
+        hint: current ? undefined : '✓',
+        onClick: () => setChatAccent(chatId, null),
+    });
+    showContextMenu({ x: rect.right, y: rect.bottom + 4, items });
+}
@@ -394,11 +394,10 @@ function _windowReseatAnchorsFromDom() {
  * Two sources, in order:
  *  1. In-memory rows above the window (a prior extend dropped them but chat.messages
  *     still holds them) — prepend the slice directly, no backend.
- *  2. Window top IS the slice start (post-seek the slice is bounded): anchored DB
- *     load `get_messages_around(windowTopId, before=WINDOW_STEP+1, after=0)`. The
- *     newest returned row IS windowTopId (the `<= anchor` include) — addEvents dedups
- *     it, so only genuinely-older rows enter chat.messages. For Community channels
- *     whose DB has no older rows yet, fill the DB from the network FIRST, then re-read.
+ *  2. Window top IS the slice start (post-seek the slice is bounded): keyset DB
+ *     page `load_older_messages(windowTopId, limit=WINDOW_STEP)` — rows strictly older
+ *     than the window top, so an empty page means the DB's history start. For Community
+ *     channels whose DB has no older rows yet, fill the DB from the network FIRST, then re-read.
  */
 async function windowExtendOlder() {
     if (!CHAT_WINDOW_ENABLED) return false;
@@ -464,24 +463,24 @@ async function windowExtendOlder() {
             ? UNREAD_SEEK_FETCH_STEP : WINDOW_STEP;
         let older = [];
         try {
-            older = await invoke('get_messages_around', { chatId, anchorId, before: seekFetch + 1, after: 0 }) || [];
-        } catch (e) { console.warn('[unread-jump] extend-older anchored load failed:', e); }
+            older = await invoke('load_older_messages', { chatId, beforeId: anchorId, limit: seekFetch }) || [];
+        } catch (e) { console.warn('[unread-jump] extend-older keyset load failed:', e); }
         if (strOpenChat !== chatId) return true;
 
-        // ≤1 row back = only the anchor (the `<= anchor` include) → DB history start.
+        // Nothing older → DB history start.
         // For a Community, try the network to grow the DB first, THEN re-read.
-        if (older.length <= 1 && chat.chat_type === 'Community') {
+        if (!older.length && chat.chat_type === 'Community') {
             await maybeLoadCommunityOlderFromNetwork(chatId);
             if (strOpenChat !== chatId) return true;
             try {
-                older = await invoke('get_messages_around', { chatId, anchorId, before: seekFetch + 1, after: 0 }) || [];
+                older = await invoke('load_older_messages', { chatId, beforeId: anchorId, limit: seekFetch }) || [];
             } catch (e) { console.warn('[unread-jump] extend-older re-read failed:', e); }
             if (strOpenChat !== chatId) return true;
         }
-        if (older.length <= 1) return true;   // history start reached; handled, don't loop
+        if (!older.length) return true;   // history start reached; handled, don't loop
 
-        // addEvents(prepend) dedups the anchor row, so only the genuinely-older rows
-        // enter chat.messages. Re-derive the slice from the cache so indices stay valid.
+        // The page is strictly older than the window top; addEvents(prepend) still dedups any
+        // row already cached. Re-derive the slice from the cache so indices stay valid.
         eventCache.addEvents(chatId, older, true);
         chat.messages = eventCache.getEventsRef(chatId) || chat.messages;
         // Older messages just dropped the reveal bound — surface any buffered "X joined/left" system
@@ -13,6 +13,9 @@
  *     { label: 'Remove', icon: 'x', danger: true, onClick: () => {...} },
  *   ],
  * });
+ *
+ * A `{ reactions: ['👍', ...], onPick: (emoji) => {...} }` item renders as a
+ * row of emoji buttons (the message menu's quick-react bar).
  */
 
 let _ctxMenuEl = null;
@@ -63,6 +66,25 @@ function showContextMenu({ x, y, items }) {
             el.appendChild(div);
             continue;
         }
+        if (item.reactions) {
+            const bar = document.createElement('div');
+            bar.className = 'context-menu-reactions';
+            for (const emoji of item.reactions) {
+                const btn = document.createElement('span');
+                btn.className = 'context-menu-reaction';
+                btn.setAttribute('role', 'menuitem');
+                btn.textContent = emoji;
+                btn.addEventListener('click', (e) => {
+                    e.stopPropagation();
+                    hideContextMenu();
+                    try { item.onPick && item.onPick(emoji); }
+                    catch (err) { console.warn('[context-menu] reaction handler failed:', err); }
+                });
+                bar.appendChild(btn);
+            }
+            el.appendChild(bar);
+            continue;
+        }
         const row = document.createElement('div');
         row.className = 'context-menu-item';
         if (item.danger) row.classList.add('is-danger');
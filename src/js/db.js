@@ -161,6 +161,16 @@ async function saveSendTypingIndicators(bool) {
     return await invoke('set_sql_setting', { key: 'send_typing_indicators', value: bool ? 'true' : 'false' });
 }
 
+/**
+ * Load the user's Show Last Active setting
+ * @returns {Promise<boolean>}
+ */
+async function loadShowLastActive() {
+    const value = await invoke('get_sql_setting', { key: 'show_last_active' });
+    if (value === null || value === undefined) return true; // Default to enabled
+    return value === 'true' || value === '1';
+}
+
 // ============================================================================
 // Display Settings
 // ============================================================================
@@ -305,7 +315,7 @@ async function toggleDefaultRelay(url, enabled) {
 }
 
 /**
- * Update a custom relay's mode
+ * Update a relay's mode (default or custom)
  * @param {string} url - The relay URL
  * @param {string} mode - The new mode: 'read', 'write', or 'both'
  * @returns {Promise<boolean>} - True if successful
@@ -347,6 +357,25 @@ async function toggleDefaultBlossomServer(url, enabled) {
     return await invoke('toggle_default_blossom_server', { url, enabled });
 }
 
+/** @param {string[]} urls - Every server URL, in the new order */
+async function reorderBlossomServers(urls) {
+    return await invoke('reorder_blossom_servers', { urls });
+}
+
+/** @returns {Promise<{prefer_fastest: boolean, mirror_count: number, max_mirrors: number, servers: Array<{url: string, latency_ms: ?number, checked_at: number, uploads_ok: number, uploads_failed: number, healthy: boolean, reason?: string}>}>} */
+async function getBlossomServerHealth() {
+    return await invoke('get_blossom_server_health');
+}
+
+async function setBlossomPreferFastest(enabled) {
+    return await invoke('set_blossom_prefer_fastest', { enabled });
+}
+
+/** @param {number} count - Extra servers each attachment is copied to (0 = off) */
+async function setBlossomMirrorCount(count) {
+    return await invoke('set_blossom_mirror_count', { count });
+}
+
 /**
  * @typedef {Object} BlossomCapability
  * @property {string} mime_type
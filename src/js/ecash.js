@@ -0,0 +1,280 @@
+/**
+ * Ecash: a Cashu wallet in the composer, and tokens sent as chat messages.
+ *
+ * Sats come in from a Lightning invoice paid to a mint, go out as tokens in a
+ * DM (or to a Lightning invoice), and tokens that arrive are claimed by the
+ * backend on receipt — unless the chat's remote-content policy holds them
+ * back or they're from a mint the wallet doesn't use yet, in which case the
+ * bubble offers to claim by hand.
+ */
+
+/** The mint last deposited to, offered as the default next time. */
+const ECASH_MINT_KEY = 'ecash_last_mint';
+
+/**
+ * Show the Ecash entry in the attachment panel: the balance, then sending,
+ * depositing, withdrawing and redeeming.
+ */
+function initEcashButton() {
+    const btn = document.getElementById('attachment-panel-ecash');
+    if (!btn) return;
+    btn.onclick = async () => {
+        const rect = btn.getBoundingClientRect();
+        const chatId = strOpenChat;
+        closeAttachmentPanel();
+        const [balance, deposits] = await Promise.all([
+            invoke('get_ecash_balance').catch(() => ({ total: 0, mints: [] })),
+            invoke('get_ecash_deposits').catch(() => []),
+        ]);
+        const items = [
+            { label: `${balance.total.toLocaleString()} sats`, hint: 'History', onClick: showEcashHistory },
+            { divider: true },
+        ];
+        if (balance.total > 0) items.push({ label: 'Send Ecash', onClick: () => sendEcashFlow(chatId) });
+        items.push({ label: 'Deposit via Lightning', onClick: depositEcashFlow });
+        if (deposits.length) items.push({ label: 'Collect Deposits', hint: String(deposits.length), onClick: () => collectEcashDeposits(deposits) });
+        if (balance.total > 0) items.push({ label: 'Withdraw to Lightning', onClick: withdrawEcashFlow });
+        items.push({ label: 'Redeem a Token', onClick: redeemEcashFlow });
+        showContextMenu({ x: rect.left, y: rect.top, items });
+    };
+}
+
+/** Whether the attachment panel should offer Ecash for `chatId` (DMs only). */
+function canUseEcash(chatId) {
+    return typeof chatId === 'string' && chatId.startsWith('npub1');
+}
+
+/** Ask for an amount in sats; null when cancelled or invalid (after saying so). */
+async function promptEcashSats(strTitle, strSubtext) {
+    const amount = await popupConfirm(strTitle, strSubtext, false, 'Amount (sats)');
+    if (amount === false) return null;
+    const sats = parseInt(String(amount).replace(/[^0-9]/g, ''), 10);
+    if (!Number.isFinite(sats) || sats <= 0) {
+        await popupConfirm(strTitle, 'Please enter a whole number of sats.', true, '', 'vector_warning.svg');
+        return null;
+    }
+    return sats;
+}
+
+/** Send ecash into the DM `chatId`. */
+async function sendEcashFlow(chatId) {
+    const profile = getProfile(chatId);
+    const name = profile ? (profile.nickname || profile.name || 'this user') : 'this user';
+    const sats = await promptEcashSats(`Send Ecash to ${escapeHtml(name)}`,
+        'The token goes in this chat; their app claims it from the mint as soon as it arrives.');
+    if (sats === null) return;
+    const memo = await popupConfirm('Add a Note', 'Optional, and only visible in this chat.', false, 'Note');
+    if (memo === false) return;
+    try {
+        await invoke('send_ecash', { chatId, amount: sats, memo: memo || null });
+    } catch (e) {
+        popupConfirm('Ecash Not Sent', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+}
+
+/** Top up the wallet: ask a mint for an invoice, show it, then collect once it's paid. */
+async function depositEcashFlow() {
+    const lastMint = localStorage.getItem(ECASH_MINT_KEY);
+    const mintInput = await popupConfirm('Choose a Mint',
+        'Ecash is held by a mint you trust with these sats.' + (lastMint ? `<br><br>Leave empty to use <b>${escapeHtml(lastMint)}</b>.` : ''),
+        false, 'https://mint.example.com');
+    if (mintInput === false) return;
+    const mintUrl = String(mintInput).trim() || lastMint;
+    if (!mintUrl) return;
+    const sats = await promptEcashSats('Deposit', 'How many sats to deposit?');
+    if (sats === null) return;
+
+    let deposit;
+    try {
+        deposit = await invoke('ecash_deposit', { mintUrl, amount: sats });
+    } catch (e) {
+        return popupConfirm('Deposit Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+    localStorage.setItem(ECASH_MINT_KEY, deposit.mint);
+    openUrl(`lightning:${deposit.invoice}`).catch(() => navigator.clipboard.writeText(deposit.invoice));
+    const html = `<div id="ecash-invoice-qr" style="width: 180px; height: 180px; margin: 0 auto 12px; background: white; padding: 8px; border-radius: 8px;"></div>
+        Pay this invoice for ${sats.toLocaleString()} sats from any Lightning wallet, then collect your ecash.`;
+    const popup = popupConfirm('Pay Invoice', html, false, '', '', '', 'Collect');
+    renderQrInto(document.getElementById('ecash-invoice-qr'), `lightning:${deposit.invoice}`.toUpperCase());
+    if (!await popup) return;
+    await collectEcashDeposits([deposit]);
+}
+
+/** Mint the ecash for paid deposits; unpaid ones stay pending. */
+async function collectEcashDeposits(deposits) {
+    let collected = 0;
+    let lastError = null;
+    for (const deposit of deposits) {
+        try {
+            collected += await invoke('ecash_complete_deposit', { quote: deposit.quote });
+        } catch (e) {
+            lastError = e;
+        }
+    }
+    if (collected > 0) showToast(`Deposited ${collected.toLocaleString()} sats`);
+    else if (lastError) popupConfirm('Deposit Not Collected', escapeHtml(String(lastError)), true, '', 'vector_warning.svg');
+}
+
+/** Pay a Lightning invoice from the wallet. */
+async function withdrawEcashFlow() {
+    const invoice = await popupConfirm('Withdraw', 'Paste a Lightning invoice for the amount to withdraw.', false, 'lnbc…');
+    if (invoice === false || !String(invoice).trim()) return;
+    try {
+        const result = await invoke('ecash_withdraw', { invoice: String(invoice).trim() });
+        showToast(`Sent ${result.amount.toLocaleString()} sats (fee ${result.fee.toLocaleString()})`);
+    } catch (e) {
+        popupConfirm('Withdrawal Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+}
+
+/** Claim a token pasted from elsewhere. */
+async function redeemEcashFlow() {
+    const token = await popupConfirm('Redeem a Token', 'Paste a Cashu token.', false, 'cashuB…');
+    if (token === false || !String(token).trim()) return;
+    try {
+        const amount = await invoke('redeem_ecash', { token: String(token).trim() });
+        showToast(`Received ${amount.toLocaleString()} sats`);
+    } catch (e) {
+        popupConfirm('Token Not Redeemed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+}
+
+/** The wallet's recent activity. */
+async function showEcashHistory() {
+    const entries = await invoke('get_ecash_history', { limit: 25 }).catch(() => []);
+    const verbs = { deposit: 'Deposited', withdraw: 'Withdrew', send: 'Sent', receive: 'Received' };
+    const rows = entries.map(entry => {
+        const sign = entry.kind === 'deposit' || entry.kind === 'receive' ? '+' : '−';
+        const fee = entry.fee ? ` <span style="opacity: 0.6;">(fee ${entry.fee.toLocaleString()})</span>` : '';
+        const when = new Date(entry.created_at * 1000).toLocaleDateString();
+        return `${verbs[entry.kind] || escapeHtml(entry.kind)}: <b>${sign}${entry.amount.toLocaleString()} sats</b>${fee} · ${when}`;
+    });
+    await popupConfirm('Ecash History', rows.length ? rows.join('<br>') : 'Nothing yet.', true);
+}
+
+/**
+ * The bubble an ecash message renders as: the amount, the note, and whether
+ * it's been claimed. An unclaimed incoming token claims on tap; one we sent
+ * can be taken back while the recipient hasn't claimed it.
+ * @param {object} msg - A message with `ecash`
+ * @returns {HTMLElement}
+ */
+function renderEcashBubble(msg) {
+    const ecash = msg.ecash;
+    const bubble = document.createElement('div');
+    bubble.classList.add('msg-ecash');
+
+    const amount = document.createElement('span');
+    amount.classList.add('msg-ecash-amount');
+    amount.textContent = `${(ecash.amount_sat || 0).toLocaleString()} sats`;
+    bubble.appendChild(amount);
+
+    if (ecash.memo) {
+        const memo = document.createElement('span');
+        memo.classList.add('msg-ecash-memo');
+        memo.textContent = ecash.memo;
+        bubble.appendChild(memo);
+    }
+
+    const hint = document.createElement('span');
+    hint.classList.add('msg-ecash-hint');
+    let host = '';
+    try { host = new URL(ecash.mint).host; } catch (_) {}
+    if (ecash.redeemed != null) {
+        hint.textContent = msg.mine ? 'Taken back' : `Claimed ${ecash.redeemed.toLocaleString()} sats`;
+        bubble.classList.add('claimed');
+    } else {
+        hint.textContent = msg.mine ? `Ecash · ${host}` : 'Tap to claim';
+    }
+    bubble.appendChild(hint);
+
+    bubble.addEventListener('click', async () => {
+        if (bubble.classList.contains('claimed') || bubble.classList.contains('syncing')) return;
+        if (msg.mine && !await popupConfirm('Take Back Ecash?', 'If they haven\'t claimed it yet, the sats return to your wallet and the token stops working for them.', false, '', '', '', 'Take Back')) return;
+        bubble.classList.add('syncing');
+        try {
+            await invoke('claim_chat_ecash', { conversationId: strOpenChat, messageId: msg.id });
+        } catch (e) {
+            popupConfirm('Not Claimed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+        }
+        bubble.classList.remove('syncing');
+    });
+    return bubble;
+}
+
+/** A token arrived (or we sent one): add it to its chat like a message. */
+function handleEcashReceived(evt) {
+    const { conversation_id, amount_sat, mint, memo, message_id, sender, is_mine } = evt.payload;
+    const chat = arrChats.find(c => c.id === conversation_id);
+    if (!chat || chat.messages?.find(m => m.id === message_id)) return;
+
+    const ecashMsg = {
+        id: message_id,
+        at: evt.payload.at || Date.now(),
+        content: '',
+        mine: is_mine,
+        attachments: [],
+        npub: sender,
+        ecash: { amount_sat, mint, memo, redeemed: null },
+    };
+    if (!chat.messages) chat.messages = [];
+    eventCache.addEvent(conversation_id, ecashMsg);
+
+    const isNewest = chat.messages.length === 0 || ecashMsg.at >= chat.messages[chat.messages.length - 1].at;
+    if (isNewest) {
+        chat.messages.push(ecashMsg);
+        if (strOpenChat === conversation_id) {
+            domChatMessages.appendChild(renderMessage(ecashMsg, getProfile(conversation_id)));
+            softChatScroll();
+        }
+    } else {
+        // Historical (resync): slot it in order; the DOM picks it up on scroll/reopen
+        let insertIdx = 0;
+        for (let i = chat.messages.length - 1; i >= 0; i--) {
+            if (chat.messages[i].at <= ecashMsg.at) {
+                insertIdx = i + 1;
+                break;
+            }
+        }
+        chat.messages.splice(insertIdx, 0, ecashMsg);
+    }
+    renderChatlist();
+}
+
+/** A token was claimed: mark its bubble. */
+function handleEcashRedeemed(evt) {
+    const { conversation_id, message_id, amount } = evt.payload;
+    const msg = arrChats.find(c => c.id === conversation_id)?.messages?.find(m => m.id === message_id);
+    if (!msg?.ecash) return;
+    msg.ecash.redeemed = amount;
+    const row = document.getElementById(message_id);
+    const bubble = row?.querySelector('.msg-ecash');
+    if (bubble) bubble.replaceWith(renderEcashBubble(msg));
+    if (!msg.mine) showToast(`Received ${amount.toLocaleString()} sats`);
+}
+
+/**
+ * Add a chat's ecash messages (stored apart from regular messages) to its
+ * initially-loaded history.
+ */
+async function mergeEcashIntoChat(contact, initialMessages) {
+    try {
+        const tokens = await invoke('get_chat_ecash', { conversationId: contact });
+        if (!tokens?.length) return;
+        for (const token of tokens) {
+            if (initialMessages.find(m => m.id === token.message_id)) continue;
+            eventCache.addEvent(contact, {
+                id: token.message_id,
+                at: token.at,
+                content: '',
+                mine: token.is_mine,
+                attachments: [],
+                npub: token.sender,
+                ecash: { amount_sat: token.amount_sat, mint: token.mint, memo: token.memo, redeemed: token.redeemed },
+            });
+        }
+        initialMessages.sort((a, b) => a.at - b.at);
+    } catch (e) {
+        console.warn('Failed to load ecash tokens:', e);
+    }
+}
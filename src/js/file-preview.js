@@ -13,7 +13,7 @@ function filePreviewOptionsHTML(showCompress, showMetadata) {
                     <div class="file-preview-option-label">Compress Image</div>
                     <div class="file-preview-option-sublabel" id="file-preview-compress-info">Compressing...</div>
                 </div>
-                <input type="checkbox" id="file-preview-compress" checked>
+                <input type="checkbox" id="file-preview-compress" ${mediaSettings.compress_by_default ? 'checked' : ''}>
                 <span class="neon-toggle"></span>
             </label>` : '';
     // Rendered hidden; revealMetadataOptionIfPresent() shows it only when the
@@ -61,6 +61,7 @@ let zipInProgress = false; // For folder zip: compression in progress
 let pendingZipUnlisten = null; // For folder zip: unlisten function for zip_progress events
 let filePreviewGeneration = 0; // Guards against setTimeout race on rapid close+reopen
 let pendingSpoiler = false; // Spoiler mode: prepends SPOILER_ to filename on send
+let sendEstimateSeq = 0; // Latest prepare_attachment request; older replies are dropped
 
 // Image extensions supported by the image crate
 const SUPPORTED_IMAGE_EXTENSIONS = ['png', 'jpg', 'jpeg', 'gif', 'webp', 'tiff', 'tif', 'ico'];
@@ -460,9 +461,11 @@ function createFilePreviewOverlay() {
                     </div>
                     <div class="file-preview-details">
                         <span class="file-preview-detail" id="file-preview-size"></span>
+                        <span class="file-preview-detail" id="file-preview-estimate"></span>
                     </div>
                 </div>
                 <div class="file-preview-options" id="file-preview-options"></div>
+                <input type="text" class="file-preview-caption" id="file-preview-caption" placeholder="Add a caption..." maxlength="2000" autocomplete="off">
             </div>
             <div class="file-preview-buttons">
                 <button class="file-preview-btn file-preview-btn-publish" id="file-preview-publish" style="display: none;">
@@ -490,6 +493,9 @@ function createFilePreviewOverlay() {
             const warn = document.getElementById('file-preview-metadata-warning');
             if (warn) warn.style.display = e.target.checked ? 'inline-block' : 'none';
         }
+        if (e.target && (e.target.id === 'file-preview-metadata' || e.target.id === 'file-preview-compress')) {
+            updateSendEstimate();
+        }
     });
     
     // Close on background click
@@ -739,6 +745,7 @@ async function openFilePreview(filepath, receiver, replyRef = '') {
     } else {
         optionsArea.innerHTML = '';
     }
+    updateSendEstimate();
     
     // Store Mini App info for potential publishing
     pendingMiniAppInfo = miniAppInfo;
@@ -781,6 +788,35 @@ async function checkAndShowPublishButton(publishBtn) {
     }
 }
 
+/**
+ * Show what the previewed image will actually send as under the current toggles
+ * ("Sends as 1.2 MB JPG · 1920×1080"), via a dry run of the send pipeline.
+ * Path-based previews only; the estimate clears when unavailable.
+ */
+async function updateSendEstimate() {
+    const el = document.getElementById('file-preview-estimate');
+    if (!el) return;
+    el.textContent = '';
+    const filePath = pendingFile;
+    if (!filePath || !SUPPORTED_IMAGE_EXTENSIONS.includes(pendingFileExt)) return;
+    const compressCheckbox = document.getElementById('file-preview-compress');
+    const metadataCheckbox = document.getElementById('file-preview-metadata');
+    const policy = {
+        compress: !!(compressCheckbox && compressCheckbox.checked && pendingFileExt !== 'gif'),
+        keep_metadata: !!(metadataCheckbox && metadataCheckbox.checked),
+    };
+    const seq = ++sendEstimateSeq;
+    try {
+        const prep = await invoke('prepare_attachment', { filePath, policy });
+        // A newer toggle (or a different preview) superseded this estimate
+        if (seq !== sendEstimateSeq || pendingFile !== filePath) return;
+        const dims = prep.width && prep.height ? ` · ${prep.width}×${prep.height}` : '';
+        el.textContent = `Sends as ${formatFileSize(prep.projected_size)} ${prep.extension.toUpperCase()}${dims}`;
+    } catch (e) {
+        console.warn('[FilePreview] send estimate failed:', e);
+    }
+}
+
 /**
  * Start pre-compression and poll for status
  * @param {string} filepath - Path to the image file
@@ -1404,6 +1440,11 @@ function closeFilePreview() {
         pendingZipUnlisten = null;
     }
 
+    const captionInput = document.getElementById('file-preview-caption');
+    if (captionInput) captionInput.value = '';
+    const estimateEl = document.getElementById('file-preview-estimate');
+    if (estimateEl) estimateEl.textContent = '';
+
     // Clear state immediately (not in setTimeout) to prevent race with rapid reopen
     const closeGeneration = ++filePreviewGeneration;
     pendingFile = null;
@@ -1454,6 +1495,9 @@ async function sendPreviewedFile() {
     const ext = pendingFileExt;
     const editedStem = pendingEditedName;
     const isSpoiler = pendingSpoiler;
+    const captionInput = document.getElementById('file-preview-caption');
+    const caption = captionInput ? captionInput.value.trim() : '';
+    if (captionInput) captionInput.value = '';
     // Build nameOverride: if spoiler, always ensure SPOILER_ prefix (requires a name)
     let nameOverride;
     if (isSpoiler) {
@@ -1541,15 +1585,15 @@ async function sendPreviewedFile() {
             if (filePath) {
                 // On-disk source (file picker, drag-drop, voice). nameOverride carries
                 // spoiler/rename (empty = derive from the path) — parity with DM file sends.
-                await invoke('send_community_files', { channelId: chatId, content: '', filePaths: [filePath], nameOverrides: [nameOverride || ''], useCompression: shouldCompress, keepMetadata, repliedTo: replyRef });
+                await invoke('send_community_files', { channelId: chatId, content: caption, filePaths: [filePath], nameOverrides: [nameOverride || ''], useCompression: shouldCompress, keepMetadata, repliedTo: replyRef });
             } else if (fileObject) {
                 // Android File object — read the real bytes; sendName already folds in nameOverride.
                 const bytes = Array.from(new Uint8Array(await fileObject.arrayBuffer()));
-                await invoke('send_community_file_bytes', { channelId: chatId, content: '', fileBytes: bytes, fileName: sendName, useCompression: shouldCompress, keepMetadata, repliedTo: replyRef });
+                await invoke('send_community_file_bytes', { channelId: chatId, content: caption, fileBytes: bytes, fileName: sendName, useCompression: shouldCompress, keepMetadata, repliedTo: replyRef });
             } else if (usingBytes) {
                 // Clipboard paste: the bytes live Rust-side (JS only holds a flag), so send
                 // from the cache. nameOverride applies the spoiler/rename to the cached name.
-                await invoke('send_community_cached_file', { channelId: chatId, content: '', nameOverride: nameOverride || null, useCompression: shouldCompress, keepMetadata, repliedTo: replyRef });
+                await invoke('send_community_cached_file', { channelId: chatId, content: caption, nameOverride: nameOverride || null, useCompression: shouldCompress, keepMetadata, repliedTo: replyRef });
             } else {
                 popupConfirm('Send failed', 'Could not read the attachment to send.', true, '', 'vector_warning.svg');
             }
@@ -1576,7 +1620,8 @@ async function sendPreviewedFile() {
                     repliedTo: replyRef,
                     useCompression: shouldCompress,
                     keepMetadata,
-                    nameOverride
+                    nameOverride,
+                    caption
                 });
             } else {
                 // No compression needed and bytes weren't cached, read directly
@@ -1589,7 +1634,8 @@ async function sendPreviewedFile() {
                     fileName: fileObject.name,
                     useCompression: false,
                     keepMetadata,
-                    nameOverride
+                    nameOverride,
+                    caption
                 });
             }
         } else if (usingBytes) {
@@ -1599,7 +1645,8 @@ async function sendPreviewedFile() {
                 repliedTo: replyRef,
                 useCompression: shouldCompress,
                 keepMetadata,
-                nameOverride
+                nameOverride,
+                caption
             });
         } else if (shouldCompress) {
             // Desktop: use cached compressed file (will wait if still compressing)
@@ -1608,7 +1655,8 @@ async function sendPreviewedFile() {
                 repliedTo: replyRef,
                 filePath: filePath,
                 keepMetadata,
-                nameOverride
+                nameOverride,
+                caption
             });
         } else {
             // Desktop: send without compression, but clear the cache first
@@ -1618,7 +1666,8 @@ async function sendPreviewedFile() {
                 repliedTo: replyRef,
                 filePath: filePath,
                 keepMetadata,
-                nameOverride
+                nameOverride,
+                caption
             });
         }
 
@@ -0,0 +1,190 @@
+/**
+ * Location sharing from the composer: a one-off pin, or a live location that
+ * keeps updating for an hour (or until stopped).
+ *
+ * The webview reads the position (navigator.geolocation); the backend sends it
+ * as a `geo:` text with a `location` tag and, for a live share, sends the
+ * latest position we report every so often until the share runs out. Nothing
+ * here fetches map tiles: a card shows the coordinates and opens a map only
+ * when tapped.
+ */
+
+/** How long a live share runs unless stopped. */
+const LIVE_LOCATION_SECS = 60 * 60;
+
+/** The watchPosition id feeding running live shares, or null. */
+let nLiveLocationWatch = null;
+
+/** Chat id → when our live share there ends (unix secs). */
+const mapLiveLocationShares = new Map();
+
+/**
+ * Show the Location entry in the attachment panel for DMs, where the device
+ * can report a position.
+ */
+function initLocationButton() {
+    const btn = document.getElementById('attachment-panel-location');
+    if (!btn || !navigator.geolocation) return;
+    btn.onclick = () => {
+        const rect = btn.getBoundingClientRect();
+        const chatId = strOpenChat;
+        closeAttachmentPanel();
+        const items = [
+            { label: 'Send Current Location', onClick: () => shareLocation(chatId, false) },
+            { label: 'Share Live Location (1 hour)', onClick: () => shareLocation(chatId, true) },
+        ];
+        if (isSharingLiveLocation(chatId)) {
+            items.push({ divider: true });
+            items.push({ label: 'Stop Sharing Live Location', danger: true, onClick: () => stopLiveLocation(chatId) });
+        }
+        showContextMenu({ x: rect.left, y: rect.top, items });
+    };
+}
+
+/** Whether the attachment panel should offer Location for `chatId`. */
+function canShareLocation(chatId) {
+    return !!navigator.geolocation && typeof chatId === 'string' && chatId.startsWith('npub1');
+}
+
+/** Whether we're sharing our live location into `chatId`. */
+function isSharingLiveLocation(chatId) {
+    const until = mapLiveLocationShares.get(chatId);
+    return !!until && until > Date.now() / 1000;
+}
+
+/**
+ * Make sure the device will hand out its position: on Android that's a
+ * runtime permission, elsewhere the webview asks on first use.
+ * @returns {Promise<boolean>}
+ */
+async function ensureLocationPermission() {
+    if (platformFeatures?.os !== 'android') return true;
+    const state = await invoke('request_permission', { kind: 'location' }).catch(() => 'unknown');
+    if (state === 'granted' || state === 'limited') return true;
+    if (state === 'denied') {
+        await offerPermissionSettings('Location Blocked',
+            'Android is no longer showing the prompt. Allow location for Vector in system settings to share where you are.');
+    }
+    return false;
+}
+
+/** The device's current position. */
+function readPosition() {
+    return new Promise((resolve, reject) => {
+        navigator.geolocation.getCurrentPosition(resolve, reject, {
+            enableHighAccuracy: true,
+            timeout: 20000,
+            maximumAge: 30000,
+        });
+    });
+}
+
+/**
+ * Send our location to `chatId`, once or as a live share.
+ * @param {string} chatId - The DM to share into
+ * @param {boolean} fLive - Keep it updating for LIVE_LOCATION_SECS
+ */
+async function shareLocation(chatId, fLive) {
+    if (!await ensureLocationPermission()) return;
+    let pos;
+    try {
+        pos = await readPosition();
+    } catch (err) {
+        await popupConfirm('Location Unavailable', escapeHtml(String(err?.message || err)), true, '', 'vector_warning.svg');
+        return;
+    }
+    const { latitude, longitude, accuracy } = pos.coords;
+    try {
+        await invoke('send_location', {
+            chatId,
+            lat: latitude,
+            lon: longitude,
+            accuracy: accuracy || 0,
+            live: fLive,
+            liveSecs: fLive ? LIVE_LOCATION_SECS : null,
+        });
+    } catch (err) {
+        await popupConfirm('Location Not Sent', escapeHtml(String(err)), true, '', 'vector_warning.svg');
+        return;
+    }
+    if (fLive) {
+        mapLiveLocationShares.set(chatId, Math.floor(Date.now() / 1000) + LIVE_LOCATION_SECS);
+        watchLiveLocation();
+    }
+}
+
+/** Feed position changes to the backend while any live share runs. */
+function watchLiveLocation() {
+    if (nLiveLocationWatch !== null) return;
+    nLiveLocationWatch = navigator.geolocation.watchPosition(async (pos) => {
+        const { latitude, longitude, accuracy } = pos.coords;
+        const fRunning = await invoke('report_location', { lat: latitude, lon: longitude, accuracy: accuracy || 0 })
+            .catch(() => true);
+        if (!fRunning) unwatchLiveLocation();
+    }, () => {}, { enableHighAccuracy: true, maximumAge: 15000 });
+}
+
+function unwatchLiveLocation() {
+    if (nLiveLocationWatch === null) return;
+    navigator.geolocation.clearWatch(nLiveLocationWatch);
+    nLiveLocationWatch = null;
+    mapLiveLocationShares.clear();
+}
+
+/** End our live share in `chatId` now. */
+async function stopLiveLocation(chatId) {
+    mapLiveLocationShares.delete(chatId);
+    await invoke('stop_live_location', { chatId }).catch(() => {});
+    if (![...mapLiveLocationShares.keys()].some(isSharingLiveLocation)) unwatchLiveLocation();
+}
+
+/**
+ * The card a location message renders as: coordinates, accuracy and, for a
+ * live share, how long it runs. Tapping it opens the spot on OpenStreetMap.
+ * @param {object} msg - A message with `location`
+ * @returns {HTMLElement}
+ */
+function renderLocationCard(msg) {
+    const loc = msg.location;
+    const card = document.createElement('div');
+    card.classList.add('msg-location');
+
+    const title = document.createElement('span');
+    title.classList.add('msg-location-title');
+    const nowSecs = Date.now() / 1000;
+    const fLive = loc.live_until > nowSecs;
+    if (fLive) title.textContent = 'Live Location';
+    else if (loc.live_until) title.textContent = 'Live Location Ended';
+    else title.textContent = 'Location';
+    card.appendChild(title);
+
+    const coords = document.createElement('span');
+    coords.classList.add('msg-location-coords');
+    coords.textContent = `${loc.lat.toFixed(5)}, ${loc.lon.toFixed(5)}`
+        + (loc.accuracy ? ` (±${Math.round(loc.accuracy)} m)` : '');
+    card.appendChild(coords);
+
+    if (fLive) {
+        const until = document.createElement('span');
+        until.classList.add('msg-location-until');
+        const end = new Date(loc.live_until * 1000);
+        until.textContent = `Until ${end.toLocaleTimeString([], { hour: '2-digit', minute: '2-digit' })}`;
+        card.appendChild(until);
+    }
+
+    card.addEventListener('click', () => {
+        openUrl(`https://www.openstreetmap.org/?mlat=${loc.lat}&mlon=${loc.lon}#map=16/${loc.lat}/${loc.lon}`);
+    });
+
+    if (fLive && msg.mine) {
+        const stop = document.createElement('button');
+        stop.classList.add('msg-location-stop');
+        stop.textContent = 'Stop Sharing';
+        stop.addEventListener('click', (e) => {
+            e.stopPropagation();
+            stopLiveLocation(strOpenChat);
+        });
+        card.appendChild(stop);
+    }
+    return card;
+}
@@ -632,19 +632,14 @@ function stripMarkdownToPlain(content) {
  * Set up event delegation for code block copy buttons
  * Uses event delegation on document to handle dynamically added code blocks
  */
-document.addEventListener('click', (e) => {
+document.addEventListener('click', async (e) => {
     const copyBtn = e.target.closest('.code-copy-btn');
     if (!copyBtn) return;
 
     const code = copyBtn.getAttribute('data-code');
     if (!code) return;
 
-    // Decode HTML entities back to original text
-    const textarea = document.createElement('textarea');
-    textarea.innerHTML = code;
-    const decodedCode = textarea.value;
-
-    navigator.clipboard.writeText(decodedCode).then(() => {
+    const showCopied = () => {
         // Show checkmark feedback
         const originalHTML = copyBtn.innerHTML;
         copyBtn.innerHTML = '<span class="icon icon-check"></span>';
@@ -654,7 +649,29 @@ document.addEventListener('click', (e) => {
             copyBtn.innerHTML = originalHTML;
             copyBtn.classList.remove('copied');
         }, 2000);
-    }).catch(err => {
+    };
+
+    // Inside a message, copy the block's exact text from the backend (the attribute has been
+    // through HTML encoding). On desktop the backend writes the clipboard itself: WebKit drops
+    // writeText once the click has passed through an await.
+    const wrapper = copyBtn.closest('.code-block-wrapper');
+    const row = copyBtn.closest('.dmsg');
+    const text = copyBtn.closest('.dmsg-text');
+    if (wrapper && row?.id && text) {
+        const index = Array.from(text.querySelectorAll('.code-block-wrapper')).indexOf(wrapper);
+        const copied = await invoke('copy_code_block', { messageId: row.id, index }).catch(() => null);
+        if (copied !== null) {
+            if (platformFeatures.os === 'android') await navigator.clipboard.writeText(copied).catch(() => {});
+            return showCopied();
+        }
+    }
+
+    // Decode HTML entities back to original text
+    const textarea = document.createElement('textarea');
+    textarea.innerHTML = code;
+    const decodedCode = textarea.value;
+
+    navigator.clipboard.writeText(decodedCode).then(showCopied).catch(err => {
         console.error('Failed to copy code:', err);
     });
 });
@@ -184,6 +184,45 @@ async function popupConfirm(strTitle, strSubtext, fNotice = false, strInputPlace
     });
 }
 
+/**
+ * `popupConfirm` with a masked input, for passwords. Resolves to the entered text, or false.
+ */
+async function popupPassword(strTitle, strSubtext, strInputPlaceholder = 'Password', strIcon = 'key.svg') {
+    domPopupInput.type = 'password';
+    try {
+        return await popupConfirm(strTitle, strSubtext, false, strInputPlaceholder, strIcon);
+    } finally {
+        domPopupInput.type = 'text';
+    }
+}
+
+/**
+ * Ask before an expensive operation, showing what it will cost (an estimate from the backend,
+ * see `consent.rs`), with a "Don't ask again" option.
+ * @param {Object} estimate - `{ op, events?, bytes?, minutes, token }`
+ * @param {string} strTitle - e.g. 'Download Model?'
+ * @param {string} strWhat - What the operation does, as HTML
+ * @returns {Promise<string|null|false>} The token to proceed with (null when remembered), or false if declined.
+ */
+async function popupConsent(estimate, strTitle, strWhat) {
+    if (!estimate.token) return null;
+    const cost = [];
+    if (estimate.events) cost.push(`<b>${estimate.events.toLocaleString()}</b> messages`);
+    if (estimate.bytes) cost.push(`about <b>${formatBytes(estimate.bytes, 1)}</b>`);
+    cost.push(`roughly <b>${estimate.minutes} min</b>`);
+    const subtext = `${strWhat}<br><br>${cost.join(', ')}.
+        <label class="file-preview-option" style="margin-top: 10px;">
+            <div class="file-preview-option-label">Don't ask again</div>
+            <input type="checkbox" id="popup-consent-remember">
+            <span class="neon-toggle"></span>
+        </label>`;
+    if (!await popupConfirm(strTitle, subtext, false, '', 'vector_warning.svg', '', 'Continue')) return false;
+    if (document.getElementById('popup-consent-remember')?.checked) {
+        await invoke('set_consent_remembered', { op: estimate.op, remembered: true }).catch(() => {});
+    }
+    return estimate.token;
+}
+
 /** Helper function to determine if a date is today */
 function isToday(date) {
     const today = new Date();
@@ -1258,6 +1297,42 @@ function setupInlineImageListeners() {
     });
 }
 
+/** @type {Object<string, string>} chat id → 'ask' | 'block', for chats that don't auto-fetch remote content */
+let objRemoteContentPolicies = {};
+
+/** Load which chats don't auto-fetch remote content (link previews, inline images, `nostr:` refs). */
+async function loadRemoteContentPolicies() {
+    try {
+        objRemoteContentPolicies = await invoke('get_remote_content_policies');
+    } catch (e) {
+        console.warn('[RemoteContent] load failed:', e);
+        objRemoteContentPolicies = {};
+    }
+}
+
+/** A chat's remote-content policy: 'allow', 'ask' or 'block'. */
+function remoteContentPolicy(chatId) {
+    return (chatId && objRemoteContentPolicies[chatId]) || 'allow';
+}
+
+/**
+ * Stand-in for remote content an 'ask' chat didn't load: a button whose tap is the
+ * go-ahead for that one item — it runs `load` and removes itself.
+ * @param {string} label
+ * @param {Function} load
+ */
+function createRemoteContentPrompt(label, load) {
+    const btn = document.createElement('span');
+    btn.className = 'remote-content-prompt btn';
+    btn.textContent = label;
+    btn.addEventListener('click', (e) => {
+        e.stopPropagation();
+        btn.remove();
+        load();
+    });
+    return btn;
+}
+
 /**
  * Bind a REMOTE image URL to an <img> through the backend cache pipeline.
  *
@@ -1268,14 +1343,16 @@ function setupInlineImageListeners() {
  * fallbacks (hide favicon, remove preview) behave as before.
  * @param {HTMLImageElement} img
  * @param {string} url - remote https URL (attacker-controlled is fine)
+ * @param {string} [chatId] - the chat it came from, whose remote-content policy applies
+ * @param {boolean} [userInitiated] - the user tapped to load it
  */
-function bindBackendCachedImg(img, url) {
+function bindBackendCachedImg(img, url, chatId = null, userInitiated = false) {
     if (!url || typeof url !== 'string') {
         queueMicrotask(() => img.dispatchEvent(new Event('error')));
         return;
     }
     img.dataset.pendingCacheUrl = url;
-    invoke('cache_url_image', { url }).then(path => {
+    invoke('cache_url_image', { url, chatId, userInitiated }).then(path => {
         if (path) {
             delete img.dataset.pendingCacheUrl;
             img.src = convertFileSrc(path);
@@ -1323,6 +1400,9 @@ function isImageUrl(text) {
 async function processInlineImages(element) {
     // Skip if web previews (including inline images) are disabled
     if (!fWebPreviewsEnabled) return;
+    const chatId = strOpenChat || null;
+    const policy = remoteContentPolicy(chatId);
+    if (policy === 'block') return;
 
     // Find all linkified URLs that point to images
     const links = element.querySelectorAll('a.linkified-url');
@@ -1337,28 +1417,43 @@ async function processInlineImages(element) {
         if (link.dataset.inlineImageProcessed) continue;
         link.dataset.inlineImageProcessed = 'true';
 
-        // Add loading indicator after the link with data-url for event-based updates
-        const loadingIndicator = document.createElement('span');
-        loadingIndicator.className = 'inline-image-loading';
-        loadingIndicator.dataset.url = url;
-        link.after(loadingIndicator);
+        if (policy === 'ask') {
+            link.after(createRemoteContentPrompt('Load image', () => loadInlineImage(link, url, chatId, true)));
+            continue;
+        }
+        await loadInlineImage(link, url, chatId, false);
+    }
+}
+
+/**
+ * Cache one inline image and swap it in after its link
+ * @param {HTMLElement} link - The linkified image URL
+ * @param {string} url
+ * @param {string|null} chatId - The chat it came from, whose remote-content policy applies
+ * @param {boolean} userInitiated - The user tapped to load it
+ */
+async function loadInlineImage(link, url, chatId, userInitiated) {
+    // Add loading indicator after the link with data-url for event-based updates
+    const loadingIndicator = document.createElement('span');
+    loadingIndicator.className = 'inline-image-loading';
+    loadingIndicator.dataset.url = url;
+    link.after(loadingIndicator);
 
-        try {
-            // Call Rust backend to cache the image (emits progress events)
-            const cachedPath = await invoke('cache_url_image', { url });
+    try {
+        // Call Rust backend to cache the image (emits progress events)
+        const cachedPath = await invoke('cache_url_image', { url, chatId, userInitiated });
 
-            if (cachedPath) {
-                // Image was cached immediately (already in cache or just downloaded)
-                // Use the shared helper to replace indicator with image
-                replaceInlineImageIndicator(loadingIndicator, cachedPath);
-            }
-            // If cachedPath is null, another download is in progress.
-            // The inline_image_cached event will update ALL indicators when complete.
-        } catch (e) {
-            // If caching fails, remove indicator and leave the link as-is
-            loadingIndicator.remove();
-            console.warn('[InlineImages] Failed to cache image:', url, e);
+        if (cachedPath) {
+            // Image was cached immediately (already in cache or just downloaded)
+            // Use the shared helper to replace indicator with image
+            replaceInlineImageIndicator(loadingIndicator, cachedPath);
         }
+        // If cachedPath is null, another download is in progress.
+        // The inline_image_cached event will update ALL indicators when complete.
+    } catch (e) {
+        // If caching fails, remove indicator and leave the link as-is
+        loadingIndicator.remove();
+        console.warn('[InlineImages] Failed to cache image:', url, e);
     }
 }
 
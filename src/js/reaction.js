@@ -4,6 +4,7 @@
  */
 
 let reactionDetailsPopup = null;
+let reactionDetailsRequest = 0; // bumps on every open/hide so a slow lookup can't pop a stale sheet
 let reactionLongPressTimer = null;
 let reactionLongPressed = false;
 
@@ -108,7 +109,7 @@ function hideReactionHoverTip() {
  * Show a popup listing who reacted with a specific emoji
  * @param {HTMLElement} reactionEl - The .reaction element that was long-pressed
  */
-function showReactionDetails(reactionEl) {
+async function showReactionDetails(reactionEl) {
     hideReactionDetails();
     // Right-click / long-press supersedes the lightweight hover tip.
     hideReactionHoverTip();
@@ -116,17 +117,23 @@ function showReactionDetails(reactionEl) {
     const emoji = reactionEl.getAttribute('data-emoji');
     const msgId = reactionEl.getAttribute('data-msg-id');
     if (!emoji || !msgId) return;
-
-    // Find the message across all chats
-    let msg = null;
-    for (const chat of arrChats) {
-        msg = chat.messages.find(m => m.id === msgId);
-        if (msg) break;
+    const request = ++reactionDetailsRequest;
+
+    // The DB is authoritative (relay duplicates folded, works for windowed-out messages); the
+    // in-memory aggregate is the fallback if the lookup fails.
+    let matchingReactions = [];
+    try {
+        const details = await invoke('get_message_reactions', { messageId: msgId });
+        matchingReactions = details.reactors.filter(r => r.emoji === emoji);
+    } catch (_) { /* fall through to the RAM copy */ }
+    if (matchingReactions.length === 0) {
+        for (const chat of arrChats) {
+            const msg = chat.messages.find(m => m.id === msgId);
+            if (msg) { matchingReactions = msg.reactions.filter(r => r.emoji === emoji); break; }
+        }
     }
-    if (!msg) return;
-
-    // Filter reactions for this emoji
-    const matchingReactions = msg.reactions.filter(r => r.emoji === emoji);
+    // A newer open (or a dismiss) landed while we awaited.
+    if (request !== reactionDetailsRequest || !reactionEl.isConnected) return;
     if (matchingReactions.length === 0) return;
 
     // Build popup
@@ -173,7 +180,7 @@ function showReactionDetails(reactionEl) {
 
         const name = document.createElement('span');
         name.className = 'reaction-detail-name';
-        name.textContent = profile?.name || profile?.display_name || reaction.author_id.slice(0, 12) + '...';
+        name.textContent = profile?.name || profile?.display_name || reaction.name || reaction.author_id.slice(0, 12) + '...';
         row.appendChild(name);
 
         body.appendChild(row);
@@ -205,6 +212,7 @@ function showReactionDetails(reactionEl) {
  * Hide the reaction details popup
  */
 function hideReactionDetails() {
+    reactionDetailsRequest++;
     if (reactionDetailsPopup) {
         reactionDetailsPopup.remove();
         reactionDetailsPopup = null;
@@ -13,12 +13,18 @@
 // Reset implicitly on chat switch via openChat clearing the chat-messages tree;
 // the set is allowed to grow across chats (an msg id is unique to its event).
 const _dmsgPreviewFetchedIds = new Set();
+// Messages whose preview the user tapped to load (in an 'ask' chat), so its images may load too.
+const _dmsgPreviewTappedIds = new Set();
 
 // Unique-emoji ceiling for a message's reaction row. At this count the "+"
 // add-reaction shortcut is dropped (no more can be shown), and the reaction
 // picker's shift multi-react auto-closes.
 const MAX_DISPLAYED_REACTIONS = 8;
 
+// Longest text rendered up front. Pathologically long messages stall the
+// markdown / mention / emoji passes, so the rest waits behind "Show full message".
+const MAX_RENDERED_TEXT_CHARS = 16000;
+
 /**
  * Build a complete `.dmsg` row DOM element for a Message.
  *
@@ -104,6 +110,16 @@ function renderMessage(msg, sender, editID = '', contextElement = null) {
         return row;
     }
 
+    // ---- Ecash token short-circuit ------------------------------------------
+    if (msg.ecash) {
+        const body = document.createElement('div');
+        body.classList.add('dmsg-body');
+        body.appendChild(renderEcashBubble(msg));
+        row.appendChild(_dmsgBuildGutter(authorFullId, _dmsgResolveProfile(authorFullId, sender, msg), msg));
+        row.appendChild(body);
+        return row;
+    }
+
     // ---- System event (centered timestamp-style line) -----------------------
     if (msg.system_event) {
         const el = insertSystemEvent(msg.content, null, msg.system_event.member_npub, msg.system_event.event_type);
@@ -229,7 +245,8 @@ function renderMessage(msg, sender, editID = '', contextElement = null) {
         && graphemeCount <= 6
         && remainderIsEmojiOnly;
 
-    const textSpan = _dmsgBuildText(msg, displayContent, fEmojiOnly, isGroupChat, currentChat, isRevealedBlockedMsg);
+    // A location's text is only its `geo:` URI; the card below stands in for it
+    const textSpan = msg.location ? null : _dmsgBuildText(msg, displayContent, fEmojiOnly, isGroupChat, currentChat, isRevealedBlockedMsg);
     if (textSpan && (textSpan.textContent || textSpan.querySelector('img,video,hr'))) {
         twemojify(textSpan);
         content.appendChild(textSpan);
@@ -249,6 +266,11 @@ function renderMessage(msg, sender, editID = '', contextElement = null) {
         content.appendChild(attachmentsDiv);
     }
 
+    // ---- Shared location -----------------------------------------------------
+    if (msg.location) {
+        content.appendChild(renderLocationCard(msg));
+    }
+
     // ---- Crypto address shortcut --------------------------------------------
     const cAddress = detectCryptoAddress(msg.content);
     if (cAddress) {
@@ -260,6 +282,11 @@ function renderMessage(msg, sender, editID = '', contextElement = null) {
         renderEmojiPackPreviews(content, msg.content);
     }
 
+    // ---- nostr: reference cards (nprofile / nevent / note / naddr) ----------
+    if (msg.content && typeof renderNostrRefPreviews === 'function') {
+        renderNostrRefPreviews(content, msg);
+    }
+
     // ---- Community invite card (vectorapp.io/invite share links) -----------
     if (msg.content && typeof renderCommunityInvitePreviews === 'function') {
         renderCommunityInvitePreviews(content, msg.content);
@@ -286,10 +313,18 @@ function renderMessage(msg, sender, editID = '', contextElement = null) {
     }
 
     // ---- Self-Destruct Timer glyph (per-message NIP-40 expiry) --------------
-    if (msg.expiration) {
+    // A starred message is kept past its expiry, so it shows the star instead.
+    if (msg.starred) {
+        content.appendChild(_dmsgBuildStarred());
+    } else if (msg.expiration) {
         content.appendChild(_dmsgBuildSelfDestruct(msg));
     }
 
+    // ---- Zap total (NIP-57 receipts seen for this message) ------------------
+    if (msg.zap_msat) {
+        content.appendChild(_dmsgBuildZaps(msg));
+    }
+
     body.appendChild(content);
 
     // ---- Reactions row ------------------------------------------------------
@@ -681,6 +716,13 @@ function _dmsgBuildText(msg, displayContent, fEmojiOnly, isGroupChat, currentCha
     if (typeof stripCommunityInviteUrls === 'function') {
         textBody = stripCommunityInviteUrls(textBody);
     }
+    const fClipped = textBody.length > MAX_RENDERED_TEXT_CHARS && !msg._showFullText;
+    if (fClipped) {
+        // Don't split a surrogate pair
+        let cut = MAX_RENDERED_TEXT_CHARS;
+        if (/[\uD800-\uDBFF]/.test(textBody[cut - 1])) cut--;
+        textBody = textBody.slice(0, cut) + '…';
+    }
     // Defensive: displayContent can be null/undefined for attachment-only messages.
     span.innerHTML = parseMarkdown(textBody);
     linkifyUrls(span);
@@ -700,6 +742,21 @@ function _dmsgBuildText(msg, displayContent, fEmojiOnly, isGroupChat, currentCha
         renderCustomEmojiShortcodes(span, msg.emoji_tags);
     }
 
+    if (fClipped) {
+        const btnFull = document.createElement('button');
+        btnFull.type = 'button';
+        btnFull.className = 'dmsg-show-full';
+        btnFull.textContent = 'Show full message';
+        btnFull.onclick = (e) => {
+            e.stopPropagation();
+            msg._showFullText = true;
+            const full = _dmsgBuildText(msg, displayContent, fEmojiOnly, isGroupChat, currentChat, isRevealedBlockedMsg);
+            twemojify(full);
+            span.replaceWith(full);
+        };
+        span.appendChild(btnFull);
+    }
+
     return span;
 }
 
@@ -718,6 +775,9 @@ function _dmsgBuildAttachments(target, msg, sender, isGroupChat, isRevealedBlock
                 _dmsgRenderImageAttachment(target, msg, sender, isGroupChat, cAttachment, assetUrl);
             } else if (['wav', 'mp3', 'flac', 'aac', 'm4a', 'ogg'].includes(cAttachment.extension)) {
                 handleAudioAttachment(cAttachment, target, msg);
+            } else if (platformFeatures.os !== 'linux' && cAttachment.video_note && ['mp4', 'webm'].includes(cAttachment.extension)) {
+                // A video note from the recorder (video-note.js)
+                _dmsgRenderVideoNote(target, cAttachment);
             } else if (platformFeatures.os !== 'linux' && ['mp4', 'webm', 'mov'].includes(cAttachment.extension)) {
                 _dmsgRenderVideoAttachment(target, cAttachment);
             } else {
@@ -900,6 +960,34 @@ function _dmsgRenderVideoAttachment(target, cAttachment) {
     target.appendChild(vidPreview);
 }
 
+/**
+ * A video note: a round player that loops silently in place; tapping restarts
+ * it with sound, tapping again pauses.
+ */
+function _dmsgRenderVideoNote(target, cAttachment) {
+    const vidNote = document.createElement('video');
+    vidNote.classList.add('dmsg-video-note');
+    vidNote.preload = 'metadata';
+    vidNote.playsInline = true;
+    vidNote.loop = true;
+    vidNote.muted = true;
+    vidNote.src = mediaUrl(cAttachment.path);
+    vidNote.addEventListener('loadedmetadata', () => {
+        if (!vidNote.isConnected) return;
+        compensateChatScrollForResize();
+    }, { once: true });
+    vidNote.addEventListener('click', () => {
+        if (vidNote.muted || vidNote.paused) {
+            vidNote.muted = false;
+            vidNote.currentTime = 0;
+            vidNote.play().catch(() => {});
+        } else {
+            vidNote.pause();
+        }
+    });
+    target.appendChild(vidNote);
+}
+
 function _dmsgRenderFileAttachment(target, msg, cAttachment) {
     const { fileDiv, isMiniApp } = createFileBox(cAttachment, 'downloaded');
     fileDiv.addEventListener('click', async (e) => {
@@ -1088,6 +1176,13 @@ function _dmsgRenderUndownloadedAttachment(target, msg, sender, isGroupChat, cAt
     const willAutoDownload = AUTO_DOWNLOAD_ENABLED && !isRevealedBlockedMsg && cAttachment.size > 0
         && cAttachment.size <= MAX_AUTO_DOWNLOAD_BYTES && !cAttachment.download_failed;
 
+    // A voice message that isn't fetched up front still plays on tap, streaming in as it goes
+    if (!willAutoDownload && !cAttachment.name && !cAttachment.download_failed
+        && ['wav', 'mp3', 'flac', 'aac', 'm4a', 'ogg'].includes(cAttachment.extension)) {
+        handleAudioAttachment(cAttachment, target, msg, isGroupChat ? strOpenChat : (sender?.id || strOpenChat));
+        return;
+    }
+
     if (['png', 'jpeg', 'jpg', 'gif', 'webp', 'tiff', 'tif', 'ico'].includes(cAttachment.extension)) {
         const thumbhashNpub = isGroupChat ? strOpenChat : (sender?.id || strOpenChat);
         invoke('generate_thumbhash_preview', { npub: thumbhashNpub, msgId: msg.id })
@@ -1216,9 +1311,18 @@ function _dmsgBuildLinkPreview(msg) {
             if (contentForPreview.includes('https') && !isImageUrl(msg.content)) {
                 // Dedupe — every re-render (e.g., reactions update) of a
                 // metadata-less message would otherwise re-fire this invoke.
-                if (!_dmsgPreviewFetchedIds.has(msg.id)) {
+                const chatId = strOpenChat;
+                const policy = remoteContentPolicy(chatId);
+                if (policy === 'ask' && !_dmsgPreviewFetchedIds.has(msg.id)) {
+                    return createRemoteContentPrompt('Load link preview', () => {
+                        _dmsgPreviewFetchedIds.add(msg.id);
+                        _dmsgPreviewTappedIds.add(msg.id);
+                        invoke('fetch_msg_metadata', { chatId, msgId: msg.id, userInitiated: true });
+                    });
+                }
+                if (policy === 'allow' && !_dmsgPreviewFetchedIds.has(msg.id)) {
                     _dmsgPreviewFetchedIds.add(msg.id);
-                    invoke('fetch_msg_metadata', { chatId: strOpenChat, msgId: msg.id });
+                    invoke('fetch_msg_metadata', { chatId, msgId: msg.id });
                 }
             }
         }
@@ -1243,7 +1347,7 @@ function _dmsgBuildLinkPreview(msg) {
     imgFavicon.addEventListener('error', () => imgFavicon.style.display = 'none', { once: true });
     // Backend-cached: the favicon URL points at the linked (attacker-chosen)
     // host — a raw img.src would be a clearnet fetch that bypasses Tor.
-    bindBackendCachedImg(imgFavicon, msg.preview_metadata.favicon);
+    bindBackendCachedImg(imgFavicon, msg.preview_metadata.favicon, strOpenChat, _dmsgPreviewTappedIds.has(msg.id));
 
     const spanPreviewTitle = document.createElement('span');
     spanPreviewTitle.appendChild(imgFavicon);
@@ -1279,7 +1383,7 @@ function _dmsgBuildLinkPreview(msg) {
         }, { once: true });
         // Backend-cached: og:image is served by the attacker-controlled
         // linked page — never fetch it from the WebView (Tor bypass).
-        bindBackendCachedImg(imgPreview, msg.preview_metadata.og_image);
+        bindBackendCachedImg(imgPreview, msg.preview_metadata.og_image, strOpenChat, _dmsgPreviewTappedIds.has(msg.id));
         divPrev.appendChild(imgPreview);
     }
 
@@ -1298,6 +1402,23 @@ function _dmsgBuildEditedIndicator(msg) {
     return span;
 }
 
+/** Build the star glyph on a starred message (inline SVG, like the self-destruct clock). */
+function _dmsgBuildStarred() {
+    const el = document.createElement('span');
+    el.className = 'dmsg-starred';
+    el.title = 'Starred';
+    el.innerHTML = '<svg viewBox="0 0 24 24" width="11" height="11" fill="currentColor"><path d="M12 2.5l2.9 6.1 6.6.8-4.9 4.6 1.3 6.6L12 17.3l-5.9 3.3 1.3-6.6-4.9-4.6 6.6-.8z"/></svg>';
+    return el;
+}
+
+function _dmsgBuildZaps(msg) {
+    const span = document.createElement('span');
+    span.classList.add('dmsg-zaps');
+    span.textContent = `⚡ ${formatSats(msg.zap_msat)}`;
+    span.title = `Zapped ${formatSats(msg.zap_msat)} sats`;
+    return span;
+}
+
 function _dmsgBuildStatus(msg) {
     const statusEl = document.createElement('span');
     statusEl.classList.add('dmsg-status');
@@ -5,7 +5,7 @@
  * Repositioned via getBoundingClientRect on row hover. Saves ~25k DOM nodes on a
  * 5k-message chat compared to per-row button columns.
  *
- * Buttons: 😀 react, ↩ reply, ✎ edit (mine + text only), 📁 reveal-file (mine + downloaded attachment), 🗑 delete (mine).
+ * Buttons: 😀 react, ↩ reply, ⚡ zap (others', when the author has a Lightning address), ✎ edit (mine + text only), 📁 reveal-file (mine + downloaded attachment), 🗑 delete (mine).
  *
  * Wiring:
  *   - mouseover/mouseleave delegated on .chat-messages
@@ -129,6 +129,7 @@ function initMessageToolbar() {
         _dmsgToolbarEl.innerHTML = `
             <button class="dmsg-toolbar-btn btn" data-action="react" aria-label="Add reaction" title="Add reaction"><span class="icon icon-smile-face"></span></button>
             <button class="dmsg-toolbar-btn btn" data-action="reply" aria-label="Reply" title="Reply"><span class="icon icon-reply"></span></button>
+            <button class="dmsg-toolbar-btn btn" data-action="zap" aria-label="Zap" title="Zap" hidden><span class="icon icon-zap"></span></button>
             <button class="dmsg-toolbar-btn btn" data-action="edit" aria-label="Edit" title="Edit" hidden><span class="icon icon-edit"></span></button>
             <button class="dmsg-toolbar-btn btn" data-action="reveal-file" aria-label="Reveal in folder" title="Reveal in folder" hidden><span class="icon icon-file-search"></span></button>
             <button class="dmsg-toolbar-btn btn" data-action="copy-file" aria-label="Copy" title="Copy" hidden><span class="icon icon-copy"></span></button>
@@ -243,6 +244,7 @@ function showMessageToolbar(rowEl) {
     _dmsgToolbarEl.dataset.target = rowEl.id;
     const reactBtn = _dmsgToolbarEl.querySelector('[data-action="react"]');
     const replyBtn = _dmsgToolbarEl.querySelector('[data-action="reply"]');
+    const zapBtn = _dmsgToolbarEl.querySelector('[data-action="zap"]');
     const editBtn = _dmsgToolbarEl.querySelector('[data-action="edit"]');
     const revealBtn = _dmsgToolbarEl.querySelector('[data-action="reveal-file"]');
     const copyFileBtn = _dmsgToolbarEl.querySelector('[data-action="copy-file"]');
@@ -256,6 +258,7 @@ function showMessageToolbar(rowEl) {
     if (status === 'failed') {
         reactBtn.hidden = true;
         replyBtn.hidden = true;
+        zapBtn.hidden = true;
         editBtn.hidden = true;
         revealBtn.hidden = true;
         copyFileBtn.hidden = true;
@@ -286,6 +289,10 @@ function showMessageToolbar(rowEl) {
     reactBtn.hidden = dissolved || uniqueEmojiCount >= 8;
     replyBtn.hidden = dissolved;
 
+    // Zap: others' messages, when the author has a Lightning address.
+    const zapRecipient = !mine && msg ? _dmsgZapRecipient(msg) : null;
+    zapBtn.hidden = dissolved || !zapRecipient;
+
     // Edit: own text-only messages (parity with legacy edit gate). A command
     // invocation is a passive render line, not editable text.
     editBtn.hidden = dissolved || !!_dmsgCommandInfo(msg) || !(mine && hasContent && !hasAttachments);
@@ -408,6 +415,13 @@ function _dmsgPositionToolbar(rowEl) {
     _dmsgToolbarEl.style.left = `${left}px`;
 }
 
+/** The npub a zap on `msg` goes to: its author, if they have a Lightning address. */
+function _dmsgZapRecipient(msg) {
+    const author = msg.npub || (strOpenChat && strOpenChat.startsWith('npub1') ? strOpenChat : null);
+    const profile = author ? getProfile(author) : null;
+    return profile && (profile.lud16 || profile.lud06) ? author : null;
+}
+
 function _dmsgHandleToolbarClick(e) {
     const btn = e.target.closest('.dmsg-toolbar-btn');
     if (!btn) return;
@@ -427,6 +441,13 @@ function _dmsgHandleToolbarClick(e) {
         case 'reply':
             _dmsgSelectReply(targetId);
             break;
+        case 'zap': {
+            const row = document.getElementById(targetId);
+            const msg = row ? _dmsgLookupMessage(row) : null;
+            const recipient = msg ? _dmsgZapRecipient(msg) : null;
+            if (recipient) zapFlow(msg.id, recipient);
+            break;
+        }
         case 'edit': {
             const row = document.getElementById(targetId);
             const msg = row ? _dmsgLookupMessage(row) : null;
@@ -564,6 +585,22 @@ function _dmsgOpenReactionPicker(targetMsgId) {
     openEmojiPanel({ target: fakeBtn });
 }
 
+/**
+ * React from the message menu's quick-pick bar: same decoy chip + routed send
+ * as the picker, skipping an emoji we've already reacted with.
+ */
+function _dmsgQuickReact(targetMsgId, emoji) {
+    for (const cChat of arrChats) {
+        const cMsg = cChat.messages.find(m => m.id === targetMsgId);
+        if (!cMsg) continue;
+        if (cMsg.reactions && cMsg.reactions.some(r => r.emoji === emoji && r.author_id === strPubkey)) return;
+        const spanReaction = _dmsgBuildReactionChip(emoji, { count: 1, mine: true, url: null }, targetMsgId);
+        _dmsgInjectReaction(document.getElementById(targetMsgId), spanReaction);
+        reactToMessageRouted(targetMsgId, cChat.id, emoji);
+        return;
+    }
+}
+
 /**
  * Mark the row as the active reply target and show the reply bar above the
  * composer (accent name + one-line snippet, Discord-style). Replaces the
@@ -666,6 +703,22 @@ function _dmsgClearLongTimer() {
     if (_gLongTimer) { clearTimeout(_gLongTimer); _gLongTimer = null; }
 }
 
+/**
+ * Star or unstar a message (a local mark: kept in Saved Messages, past the chat's
+ * Self-Destruct Timer and media-cache eviction), then redraw its row.
+ */
+async function _dmsgToggleStar(targetId, msg) {
+    const star = !msg.starred;
+    try {
+        await invoke(star ? 'star_message' : 'unstar_message', { messageId: targetId });
+    } catch (e) {
+        return showToast(String(e));
+    }
+    msg.starred = star;
+    document.getElementById(targetId)?.replaceWith(renderMessage(msg, getProfile(strOpenChat), targetId));
+    showToast(star ? 'Saved to Starred Messages' : 'Removed from Starred Messages');
+}
+
 function _dmsgCopyText(text) {
     if (!text) return;
     navigator.clipboard.writeText(text)
@@ -898,9 +951,18 @@ async function _dmsgOpenMessageMenu(rowEl, x, y) {
 
     if (!dissolved) {
         if (uniqueEmojiCount < 8) {
+            const quickpicks = await invoke('get_reaction_quickpicks').catch(() => []);
+            if (quickpicks.length) {
+                items.push({ reactions: quickpicks, onPick: (emoji) => _dmsgQuickReact(targetId, emoji) });
+                items.push({ divider: true });
+            }
             items.push({ label: 'React', icon: 'smile-face', onClick: () => _dmsgOpenReactionPicker(targetId) });
         }
         items.push({ label: 'Reply', icon: 'reply', onClick: () => _dmsgSelectReply(targetId) });
+        const zapRecipient = !mine && msg ? _dmsgZapRecipient(msg) : null;
+        if (zapRecipient) {
+            items.push({ label: 'Zap', icon: 'zap', onClick: () => zapFlow(targetId, zapRecipient) });
+        }
         if (mine && hasContent && !hasAttachments && !_dmsgCommandInfo(msg)) {
             items.push({ label: 'Edit', icon: 'edit', onClick: () => { if (msg) startEditMessage(targetId, msg.content); } });
         }
@@ -931,6 +993,10 @@ async function _dmsgOpenMessageMenu(rowEl, x, y) {
             }
         }
     }
+    // Star — local-only, so offered in dissolved communities too.
+    if (msg) {
+        items.push({ label: msg.starred ? 'Unstar' : 'Star', icon: 'star', onClick: () => _dmsgToggleStar(targetId, msg) });
+    }
     // Copy — text selection is off on mobile. If the message carries markdown,
     // offer both flavours (plain vs as-sent); if it's provably plaintext
     // (stripping is a no-op), a single plain Copy is all that's needed.
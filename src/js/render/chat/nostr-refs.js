@@ -0,0 +1,144 @@
+/**
+ * `nostr:` reference cards: a `nostr:nprofile…`, `nostr:nevent…`, `nostr:note…` or
+ * `nostr:naddr…` in a message renders as a small card under the text.
+ *
+ * - The backend resolves each reference from our own relays (`resolve_nostr_uri`) and caches it;
+ *   messages assembled after that carry the result in `msg.nostr_previews`, so re-renders fill
+ *   instantly instead of skeleton-loading.
+ * - Emoji-pack naddrs (kind 30030) are left to `renderEmojiPackPreviews`.
+ * - The chat's remote-content policy applies: an `ask` chat shows a tap-to-load card, a `block`
+ *   chat only shows references that were already resolved.
+ */
+
+const NOSTR_REF_REGEX = /nostr:((?:nprofile|nevent|note|naddr)1[ac-hj-np-z02-9]{20,})/gi;
+// Each distinct card costs a relay fetch — cap per message.
+const NOSTR_REF_CARDS_PER_MSG = 3;
+
+/** In-flight resolves, keyed by lowercase URI, so a burst of renders shares one fetch. */
+const _nostrRefPending = new Map();
+
+/** Append a card under `target` for each distinct reference in the message's text. */
+function renderNostrRefPreviews(target, msg) {
+    if (!msg.content || !msg.content.includes('nostr:')) return;
+    const chatId = strOpenChat;
+    const blocked = remoteContentPolicy(chatId) === 'block';
+    const known = new Map((msg.nostr_previews || []).map(p => [p.uri, p]));
+    const seen = new Set();
+    NOSTR_REF_REGEX.lastIndex = 0;
+    let match;
+    while ((match = NOSTR_REF_REGEX.exec(msg.content)) !== null) {
+        const bech = match[1].toLowerCase();
+        const uri = 'nostr:' + bech;
+        if (seen.has(uri)) continue;
+        if (bech.startsWith('naddr1') && typeof _naddrKind === 'function' && _naddrKind(bech) === KIND_EMOJI_SET) continue;
+        if (blocked && !known.has(uri)) continue;
+        if (seen.size >= NOSTR_REF_CARDS_PER_MSG) break;
+        seen.add(uri);
+        target.appendChild(_buildNostrRefCard(uri, known.get(uri), chatId));
+    }
+}
+
+/** Resolve a reference under `chatId`'s policy; `userInitiated` = the user tapped to load it. */
+function _resolveNostrRef(uri, chatId, userInitiated) {
+    if (userInitiated) {
+        return invoke('resolve_nostr_uri', { uri, chatId, userInitiated }).catch(() => null);
+    }
+    let pending = _nostrRefPending.get(uri);
+    if (!pending) {
+        pending = invoke('resolve_nostr_uri', { uri, chatId })
+            .catch(() => null)
+            .finally(() => _nostrRefPending.delete(uri));
+        _nostrRefPending.set(uri, pending);
+    }
+    return pending;
+}
+
+function _buildNostrRefCard(uri, preview, chatId) {
+    const card = document.createElement('div');
+    card.className = 'nostr-ref-card';
+
+    const eyebrow = document.createElement('div');
+    eyebrow.className = 'cic-eyebrow';
+    card.appendChild(eyebrow);
+
+    const title = document.createElement('div');
+    title.className = 'nostr-ref-title';
+    card.appendChild(title);
+
+    const body = document.createElement('div');
+    body.className = 'nostr-ref-body';
+    card.appendChild(body);
+
+    if (preview) {
+        _fillNostrRefCard(card, preview);
+        return card;
+    }
+
+    eyebrow.textContent = uri.startsWith('nostr:nprofile') ? 'Profile' : 'Nostr Event';
+    if (remoteContentPolicy(chatId) === 'ask') {
+        // This chat loads references on request: the tap is the go-ahead
+        title.textContent = 'Tap to load';
+        body.textContent = uri.slice(0, 24) + '…';
+        card.classList.add('is-clickable');
+        card.onclick = (e) => {
+            e.stopPropagation();
+            card.onclick = null;
+            card.classList.remove('is-clickable');
+            _loadNostrRefCard(card, uri, chatId, true);
+        };
+        return card;
+    }
+    _loadNostrRefCard(card, uri, chatId, false);
+    return card;
+}
+
+/** Skeleton the card, then fill it with the resolved reference (or a not-found note). */
+function _loadNostrRefCard(card, uri, chatId, userInitiated) {
+    const [, title, body] = card.children;
+    card.classList.add('is-loading');
+    title.innerHTML = '<span class="pack-skel cic-skel-name"></span>';
+    body.innerHTML = '<span class="pack-skel cic-skel-desc"></span>';
+    _resolveNostrRef(uri, chatId, userInitiated).then(result => {
+        card.classList.remove('is-loading');
+        if (result) {
+            _fillNostrRefCard(card, result);
+        } else {
+            card.classList.add('is-invalid');
+            title.textContent = 'Not found on your relays';
+            body.textContent = uri.slice(0, 24) + '…';
+        }
+    });
+}
+
+/** Eyebrow label for an event kind. */
+function _nostrRefKindLabel(preview) {
+    if (preview.kind === 'profile') return 'Profile';
+    switch (preview.event_kind) {
+        case 1: return 'Note';
+        case 30023: return 'Article';
+        default: return preview.kind === 'address' ? 'Nostr Post' : 'Nostr Event';
+    }
+}
+
+function _fillNostrRefCard(card, preview) {
+    const [eyebrow, title, body] = card.children;
+    eyebrow.textContent = _nostrRefKindLabel(preview);
+
+    // A profile we know locally shows our own name for it (nickname first)
+    const authorName = getProfile(preview.author) ? getName(preview.author) : null;
+    if (preview.kind === 'profile') {
+        title.textContent = authorName || preview.title || getName(preview.author);
+    } else {
+        const by = authorName || getName(preview.author);
+        const when = preview.created_at ? ' · ' + timeAgo(preview.created_at * 1000) : '';
+        title.textContent = preview.title ? `${preview.title} — ${by}` : by + when;
+    }
+    body.textContent = preview.content || '';
+    body.style.display = preview.content ? '' : 'none';
+
+    card.classList.add('is-clickable');
+    card.onclick = (e) => {
+        e.stopPropagation();
+        showMiniProfile(preview.author, card);
+    };
+}
@@ -0,0 +1,139 @@
+/**
+ * Chat folders: user-defined folders ("Work", "Family") that hold any chat, DMs and
+ * communities alike. A chat sits in at most one folder.
+ *
+ * - `arrChatFolders` — every folder with its chats and unread count (backend is the source of truth).
+ * - `nChatlistFolderFilter` — the folder id the chat list is narrowed to, or null for all chats.
+ * - `buildChatFolderChip` — a folder's chip in the chat list's filter row (see groups.js).
+ * - `moveChatToFolder` — the chat row menu's "Move to Folder" picker.
+ *
+ * Purely local and organizational.
+ */
+
+/** @type {Array<{id: number, name: string, chats: string[], unread: number}>} */
+let arrChatFolders = [];
+
+/** @type {number|null} */
+let nChatlistFolderFilter = null;
+
+/** Reload the folders (and their unread counts) from the backend and re-render the chat list. */
+async function loadChatFolders() {
+    try {
+        arrChatFolders = await invoke('get_folders');
+    } catch (e) {
+        console.warn('[ChatFolders] load failed:', e);
+        arrChatFolders = [];
+    }
+    if (!arrChatFolders.some(f => f.id === nChatlistFolderFilter)) nChatlistFolderFilter = null;
+    renderChatlist();
+}
+
+/** Whether `chat` passes the active folder filter. */
+function chatMatchesFolderFilter(chat) {
+    if (nChatlistFolderFilter === null) return true;
+    const folder = arrChatFolders.find(f => f.id === nChatlistFolderFilter);
+    return !!folder && folder.chats.includes(chat.id);
+}
+
+/** A folder's filter chip, with its unread count; right-click / long-press to manage it. */
+function buildChatFolderChip(folder) {
+    const chip = document.createElement('button');
+    chip.type = 'button';
+    chip.className = 'chatlist-group-chip chatlist-folder-chip';
+    if (folder.id === nChatlistFolderFilter) chip.classList.add('is-active');
+    chip.textContent = folder.name;
+    if (folder.unread) {
+        const count = document.createElement('span');
+        count.className = 'chatlist-folder-unread';
+        count.textContent = folder.unread > 99 ? '99+' : String(folder.unread);
+        chip.appendChild(count);
+    }
+    chip.onclick = () => {
+        nChatlistFolderFilter = folder.id;
+        nChatlistGroupFilter = null;
+        renderChatlist();
+    };
+    chip.oncontextmenu = (e) => {
+        e.preventDefault();
+        showChatFolderMenu(folder.id, e.clientX, e.clientY);
+    };
+    return chip;
+}
+
+function showChatFolderMenu(id, x, y) {
+    const folder = arrChatFolders.find(f => f.id === id);
+    if (!folder) return;
+    showContextMenu({ x, y, items: [
+        {
+            label: 'Rename',
+            icon: 'edit',
+            onClick: async () => {
+                const name = await popupConfirm('Rename Folder', `Enter a new name for "${escapeHtml(folder.name)}".`, false, folder.name);
+                if (!name) return;
+                try {
+                    await invoke('rename_folder', { id, name });
+                } catch (e) {
+                    return popupConfirm('Rename Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+                }
+                loadChatFolders();
+            },
+        },
+        { divider: true },
+        {
+            label: 'Delete',
+            icon: 'trash',
+            danger: true,
+            onClick: async () => {
+                const fConfirm = await popupConfirm('Delete Folder', `Delete "${escapeHtml(folder.name)}"? Its chats stay in your chat list.`, false, '', 'vector_warning.svg');
+                if (!fConfirm) return;
+                await invoke('delete_folder', { id }).catch(() => {});
+                loadChatFolders();
+            },
+        },
+    ] });
+}
+
+/** Ask for a folder name and create it, returning the new folder (or null). */
+async function createChatFolder() {
+    const name = await popupConfirm('New Folder', 'Name the folder (e.g. Work). Folders only organize your chat list.', false, 'Work');
+    if (!name) return null;
+    try {
+        const folder = await invoke('create_folder', { name });
+        arrChatFolders.push(folder);
+        return folder;
+    } catch (e) {
+        popupConfirm('New Folder', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+        return null;
+    }
+}
+
+/** The chat row menu's folder picker: every folder (current one ticked), a new one, or none. */
+function moveChatToFolder(chatId, x, y) {
+    const current = arrChatFolders.find(f => f.chats.includes(chatId));
+    const assign = async (folderId) => {
+        try {
+            await invoke('assign_chat_to_folder', { chatId, folderId });
+        } catch (e) {
+            return showToast(String(e));
+        }
+        loadChatFolders();
+    };
+    const items = arrChatFolders.map(folder => ({
+        label: folder.name,
+        hint: folder === current ? '✓' : undefined,
+        onClick: () => assign(folder.id),
+    }));
+    if (items.length) items.push({ divider: true });
+    items.push({
+        label: 'New Folder',
+        icon: 'plus',
+        onClick: async () => {
+            const folder = await createChatFolder();
+            if (folder) assign(folder.id);
+        },
+    });
+    if (current) {
+        items.push({ label: 'Remove from Folder', icon: 'x', onClick: () => assign(null) });
+    }
+    showContextMenu({ x, y, items });
+}
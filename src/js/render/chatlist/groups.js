@@ -0,0 +1,138 @@
+/**
+ * Contact groups: user-defined labels ("Family", "Work") that filter the chat list.
+ *
+ * - `arrContactGroups` — every group with its member npubs (backend is the source of truth).
+ * - `nChatlistGroupFilter` — the group id the chat list is narrowed to, or null for all chats.
+ * - `buildContactGroupFilterBar` — the chip row rendered above the chats when groups or chat
+ *   folders (folders.js) exist.
+ * - `editContactGroupsFor` — the DM row menu's "Contact Groups" editor.
+ *
+ * Purely local and organizational; unrelated to group chats.
+ */
+
+/** @type {Array<{id: number, name: string, members: string[]}>} */
+let arrContactGroups = [];
+
+/** @type {number|null} */
+let nChatlistGroupFilter = null;
+
+/** Reload the groups from the backend and re-render the chat list. */
+async function loadContactGroups() {
+    try {
+        arrContactGroups = await invoke('get_contact_groups');
+    } catch (e) {
+        console.warn('[ContactGroups] load failed:', e);
+        arrContactGroups = [];
+    }
+    if (!arrContactGroups.some(g => g.id === nChatlistGroupFilter)) nChatlistGroupFilter = null;
+    renderChatlist();
+}
+
+/** Whether `chat` passes the active group filter (group chats never do while one is set). */
+function chatMatchesGroupFilter(chat) {
+    if (nChatlistGroupFilter === null) return true;
+    if (chatIsGroup(chat)) return false;
+    const group = arrContactGroups.find(g => g.id === nChatlistGroupFilter);
+    return !!group && group.members.includes(chat.id);
+}
+
+/**
+ * The chip row: "All", one chip per chat folder, then one per group; right-click / long-press a
+ * chip to manage it. A folder and a group filter never apply together.
+ */
+function buildContactGroupFilterBar() {
+    const bar = document.createElement('div');
+    bar.className = 'chatlist-group-filter';
+    const addChip = (label, id) => {
+        const chip = document.createElement('button');
+        chip.type = 'button';
+        chip.className = 'chatlist-group-chip';
+        if (id === nChatlistGroupFilter) chip.classList.add('is-active');
+        if (id === null && nChatlistFolderFilter !== null) chip.classList.remove('is-active');
+        chip.textContent = label;
+        chip.onclick = () => {
+            nChatlistGroupFilter = id;
+            nChatlistFolderFilter = null;
+            renderChatlist();
+        };
+        if (id !== null) {
+            chip.oncontextmenu = (e) => {
+                e.preventDefault();
+                showContactGroupMenu(id, e.clientX, e.clientY);
+            };
+        }
+        bar.appendChild(chip);
+    };
+    addChip('All', null);
+    for (const folder of arrChatFolders) bar.appendChild(buildChatFolderChip(folder));
+    for (const group of arrContactGroups) addChip(group.name, group.id);
+    return bar;
+}
+
+function showContactGroupMenu(id, x, y) {
+    const group = arrContactGroups.find(g => g.id === id);
+    if (!group) return;
+    showContextMenu({ x, y, items: [
+        {
+            label: 'Rename',
+            icon: 'edit',
+            onClick: async () => {
+                const name = await popupConfirm('Rename Group', `Enter a new name for "${escapeHtml(group.name)}".`, false, group.name);
+                if (!name) return;
+                try {
+                    await invoke('rename_contact_group', { id, name });
+                } catch (e) {
+                    return popupConfirm('Rename Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+                }
+                loadContactGroups();
+            },
+        },
+        { divider: true },
+        {
+            label: 'Delete',
+            icon: 'trash',
+            danger: true,
+            onClick: async () => {
+                const fConfirm = await popupConfirm('Delete Group', `Delete "${escapeHtml(group.name)}"? Your chats and contacts are not affected.`, false, '', 'vector_warning.svg');
+                if (!fConfirm) return;
+                await invoke('delete_contact_group', { id }).catch(() => {});
+                loadContactGroups();
+            },
+        },
+    ] });
+}
+
+/**
+ * Edit which groups `npub` belongs to: a comma-separated list, pre-filled with the current ones.
+ * Unknown names create new groups.
+ */
+async function editContactGroupsFor(npub) {
+    const current = arrContactGroups.filter(g => g.members.includes(npub)).map(g => g.name);
+    const promise = popupConfirm('Contact Groups', 'Groups for this contact, separated by commas (e.g. Family, Work). Groups only organize your chat list.', false, 'Family, Work');
+    domPopupInput.value = current.join(', ');
+    const strNames = await promise;
+    if (strNames === false) return;
+
+    const wanted = [...new Set(strNames.split(',').map(n => n.trim()).filter(Boolean))];
+    try {
+        for (const name of wanted) {
+            let group = arrContactGroups.find(g => g.name.toLowerCase() === name.toLowerCase());
+            if (!group) {
+                group = await invoke('create_contact_group', { name });
+                arrContactGroups.push(group);
+            }
+            if (!group.members.includes(npub)) {
+                await invoke('set_contact_group_member', { id: group.id, npub, member: true });
+            }
+        }
+        for (const group of arrContactGroups) {
+            const keep = wanted.some(n => n.toLowerCase() === group.name.toLowerCase());
+            if (!keep && group.members.includes(npub)) {
+                await invoke('set_contact_group_member', { id: group.id, npub, member: false });
+            }
+        }
+    } catch (e) {
+        popupConfirm('Contact Groups', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+    loadContactGroups();
+}
@@ -23,6 +23,18 @@ function generateChatlistStateHash() {
     // Build a simple array of state values (faster than creating objects)
     const states = [];
 
+    // Contact-group filter chips (names + membership drive both the bar and the filter)
+    states.push(nChatlistGroupFilter);
+    for (const group of arrContactGroups) {
+        states.push(group.id, group.name, group.members.length);
+    }
+
+    // Chat-folder chips carry an unread count too
+    states.push(nChatlistFolderFilter);
+    for (const folder of arrChatFolders) {
+        states.push(folder.id, folder.name, folder.chats.length, folder.unread);
+    }
+
     // Add pending Community invite ids
     for (const inv of arrCommunityInvites) {
         states.push(inv.community_id, inv.name);
@@ -41,6 +53,7 @@ function generateChatlistStateHash() {
         states.push(
             chat.id,
             nUnread,
+            chat.unread_mentions,
             activeTypers.length,
             // Message count so a REMOVAL re-renders even when the raw last array
             // element is unchanged — a self-destruct purges the preview message
@@ -86,9 +99,15 @@ function renderChatlist() {
     // Prep a fragment to re-render the full list in one sweep
     const fragment = document.createDocumentFragment();
 
-    // Render invites first (at the top of the chat list)
-    for (const invite of arrCommunityInvites) {
-        fragment.appendChild(renderCommunityInviteItem(invite));
+    // Filter chips lead the list once the user has made a contact group or chat folder
+    if (arrContactGroups.length || arrChatFolders.length) fragment.appendChild(buildContactGroupFilterBar());
+
+    // Render invites first (at the top of the chat list); a group or folder filter hides them
+    const fFiltered = nChatlistGroupFilter !== null || nChatlistFolderFilter !== null;
+    if (!fFiltered) {
+        for (const invite of arrCommunityInvites) {
+            fragment.appendChild(renderCommunityInviteItem(invite));
+        }
     }
 
     // Then render regular chats
@@ -109,6 +128,9 @@ function renderChatlist() {
         // Do not render our own profile: it is accessible via the Bookmarks/Notes section
         if (chat.id === strPubkey) continue;
 
+        // Narrowed to one contact group or chat folder
+        if (!chatMatchesGroupFilter(chat) || !chatMatchesFolderFilter(chat)) continue;
+
         // Hide DM chats with blocked users from the chat list
         if (!chatIsGroup(chat)) {
             const chatProfile = getProfile(chat.id);
@@ -127,7 +149,7 @@ function renderChatlist() {
     // message and groups the user has joined; if the fragment came out
     // empty AND there are no pending invites, surface a friendly nudge
     // so the user understands what to do next.
-    if (!fragment.firstElementChild && arrCommunityInvites.length === 0) {
+    if (!fragment.querySelector(':scope > :not(.chatlist-group-filter)') && arrCommunityInvites.length === 0 && !fFiltered) {
         fragment.appendChild(buildChatlistEmptyState());
     }
 
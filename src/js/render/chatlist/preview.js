@@ -139,13 +139,19 @@ function generateChatPreviewText(chat) {
         senderPrefix = `${senderName}: `;
     }
 
-    // Attachment message
-    if (!cLastMsg.content && cLastMsg.attachments?.length) {
-        return {
-            text: senderPrefix + 'Sent a ' + getFileTypeInfo(cLastMsg.attachments[0].extension).description,
-            isTyping: false,
-            needsTwemoji: false
-        };
+    // Attachment message ("Sent a Picture", or "Picture: caption" when captioned)
+    if (cLastMsg.attachments?.length) {
+        const description = getFileTypeInfo(cLastMsg.attachments[0].extension).description;
+        if (!cLastMsg.content) {
+            return { text: senderPrefix + 'Sent a ' + description, isTyping: false, needsTwemoji: false };
+        }
+        return { text: escapeHtml(`${senderPrefix}${description}: `) + contentToPreviewHtml(resolveMentionText(cLastMsg.content)), isTyping: false, needsTwemoji: true, isHtml: true, emojiTags: cLastMsg.emoji_tags };
+    }
+
+    // Shared location (its text is only a `geo:` URI)
+    if (cLastMsg.location) {
+        const strKind = cLastMsg.location.live_until ? 'Live Location' : 'Location';
+        return { text: senderPrefix + 'Shared a ' + strKind, isTyping: false, needsTwemoji: false };
     }
 
     // PIVX payment message
@@ -153,6 +159,11 @@ function generateChatPreviewText(chat) {
         return { text: senderPrefix + 'Sent a PIVX Payment', isTyping: false, needsTwemoji: false };
     }
 
+    // Ecash token message
+    if (cLastMsg.ecash) {
+        return { text: senderPrefix + 'Sent ' + (cLastMsg.ecash.amount_sat || 0).toLocaleString() + ' sats in Ecash', isTyping: false, needsTwemoji: false };
+    }
+
     // System event (member joined/left, etc.)
     if (cLastMsg.system_event) {
         return { text: cLastMsg.content, isTyping: false, needsTwemoji: false };
@@ -189,6 +189,10 @@ function renderChat(chat, primaryColor) {
         spanCount.textContent = nUnread > 99
             ? '99+'
             : (chat.chat_type === 'Community' && nUnread >= COMMUNITY_UNREAD_PLUS_THRESHOLD ? `${nUnread}+` : String(nUnread));
+        // Someone pinged us in the unread stretch: lead with "@" so mentions stand out in busy groups.
+        if (chat.unread_mentions > 0) {
+            spanCount.textContent = '@ ' + spanCount.textContent;
+        }
         divContact.appendChild(spanCount);
     }
 
@@ -199,11 +203,56 @@ function renderChat(chat, primaryColor) {
     return divContact;
 }
 
+/**
+ * Notification preferences submenu: mentions-only (groups), a timed mute that
+ * lapses on its own, and sound on/off. Opened from the row menu at the same spot.
+ */
+async function _showChatNotificationMenu(chat, isGroup, x, y) {
+    let prefs;
+    try { prefs = await invoke('get_chat_notification_prefs', { chatId: chat.id }); }
+    catch (_) { return; }
+    const save = async (change) => {
+        const next = { mentionsOnly: prefs.mentions_only, sound: prefs.sound, muteForSecs: null, ...change };
+        try { await invoke('set_chat_notification_prefs', { chatId: chat.id, ...next }); }
+        catch (e) { showToast(String(e)); }
+    };
+    const items = [];
+    const mutedUntil = prefs.muted_until * 1000;
+    if (mutedUntil > Date.now()) {
+        items.push({
+            label: 'End timed mute',
+            hint: `(until ${new Date(mutedUntil).toLocaleString([], { dateStyle: 'short', timeStyle: 'short' })})`,
+            icon: 'volume-max',
+            onClick: () => save({ muteForSecs: 0 }),
+        });
+    }
+    for (const [label, secs] of [['Mute for 1 hour', 3600], ['Mute for 8 hours', 8 * 3600], ['Mute for 1 week', 7 * 86400]]) {
+        items.push({ label, icon: 'clock', onClick: () => save({ muteForSecs: secs }) });
+    }
+    items.push({ divider: true });
+    if (isGroup) {
+        items.push({
+            label: 'Mentions only',
+            hint: prefs.mentions_only ? '(on)' : '(off)',
+            icon: 'message',
+            onClick: () => save({ mentionsOnly: !prefs.mentions_only }),
+        });
+    }
+    items.push({
+        label: 'Sound',
+        hint: prefs.sound ? '(on)' : '(off)',
+        icon: prefs.sound ? 'volume-max' : 'volume-mute',
+        onClick: () => save({ sound: !prefs.sound }),
+    });
+    showContextMenu({ x, y, items });
+}
+
 /**
  * Row context menu (right-click / long-press): Mark as Read (when unread) or
- * Mark as Unread (when caught up), a Mute/Unmute toggle, and — for DMs only —
- * Block. Actions reuse the same backend commands as the profile/group panels
- * and repaint the list.
+ * Mark as Unread (when caught up), a Mute/Unmute toggle, the notification
+ * preferences submenu, a per-chat typing indicator toggle, and — for DMs only —
+ * Delete and Block. Actions reuse the same backend commands as the profile/group
+ * panels and repaint the list.
  */
 function _showChatRowContextMenu(chat, isGroup, nUnread, x, y) {
     if (chat._joining) return; // nothing actionable until the join finalises
@@ -234,8 +283,42 @@ function _showChatRowContextMenu(chat, isGroup, nUnread, x, y) {
             renderChatlist();
         },
     });
+    items.push({
+        label: 'Notifications',
+        icon: 'settings',
+        onClick: () => _showChatNotificationMenu(chat, isGroup, x, y),
+    });
+    items.push({
+        label: 'Typing Indicators',
+        icon: 'edit',
+        onClick: async () => {
+            try {
+                const enabled = !await invoke('get_chat_typing_enabled', { chatId: chat.id });
+                await invoke('set_chat_typing_enabled', { chatId: chat.id, enabled });
+                showToast(enabled ? 'Typing Indicators On' : 'Typing Indicators Off');
+            } catch (e) {
+                showToast(String(e));
+            }
+        },
+    });
+    items.push({
+        label: 'Move to Folder',
+        icon: 'folder',
+        onClick: () => moveChatToFolder(chat.id, x, y),
+    });
     if (!isGroup) {
+        items.push({
+            label: 'Contact Groups',
+            icon: 'edit',
+            onClick: () => editContactGroupsFor(chat.id),
+        });
         items.push({ divider: true });
+        items.push({
+            label: 'Delete Chat',
+            icon: 'trash',
+            danger: true,
+            onClick: () => deleteChatFromList(chat),
+        });
         items.push({
             label: 'Block',
             icon: 'x-user',
@@ -263,6 +346,28 @@ function _showChatRowContextMenu(chat, isGroup, nUnread, x, y) {
 }
 
 
+/**
+ * The invite row's subtitle: who invited (and whether that's the owner), then an activity hint.
+ * @param {{inviter_npub: string, owner_npub: ?string, recent_messages: ?number}} preview
+ * @returns {string}
+ */
+function describeCommunityInvite(preview) {
+    const parts = [];
+    const inviter = getName(preview.inviter_npub);
+    if (preview.owner_npub && preview.owner_npub === preview.inviter_npub) {
+        parts.push(`Invited by ${inviter} (owner)`);
+    } else {
+        parts.push(`Invited by ${inviter}`);
+        if (preview.owner_npub) parts.push(`Owner ${getName(preview.owner_npub)}`);
+    }
+    const recent = preview.recent_messages;
+    if (recent >= 50) parts.push('Very active');
+    else if (recent >= 10) parts.push('Active');
+    else if (recent > 0) parts.push('A few messages this week');
+    else if (recent === 0) parts.push('Quiet lately');
+    return parts.join(' · ');
+}
+
 /**
  * Render a pending Community invite row (npub gift-wrap) — same look as an MLS invite
  * slot, pinned at the top of the chat list, with Accept / Decline actions.
@@ -308,6 +413,15 @@ function renderCommunityInviteItem(invite) {
     pSub.classList.add('cutoff');
     pSub.textContent = 'Community invite';
     divPreviewContainer.appendChild(pSub);
+    // Who's behind it and how busy it is, once the preview resolves. Names come from getName, so
+    // the profile_update re-render fills in a freshly fetched owner/inviter.
+    getCommunityInvitePreview(invite.community_id).then(preview => {
+        if (!preview || !pSub.isConnected) return;
+        pSub.textContent = describeCommunityInvite(preview);
+        if (preview.icon_path && placeholder.isConnected) {
+            placeholder.replaceWith(createAvatarImg(convertFileSrc(preview.icon_path), 50, true));
+        }
+    });
     divInvite.appendChild(divPreviewContainer);
 
     const divActions = document.createElement('div');
@@ -90,6 +90,11 @@ function showMiniProfile(npub, anchorEl) {
         _positionMiniProfile(popup, anchorEl);
     }
 
+    // Bio and banner were left out of the boot payload — pull them in for the popup
+    if (profile?.details_deferred && typeof ensureProfileDetails === 'function') {
+        ensureProfileDetails(npub).then(() => refreshMiniProfileIfMatches(npub));
+    }
+
     // Kick off a fresh fetch in case data is stale or missing — same priority
     // queue renderMessage uses for missing author profiles.
     if (typeof invoke === 'function') {
@@ -197,7 +202,7 @@ function _populateMiniProfile(popup, npub, profile, opts) {
     // the banner area, to the right of the avatar (CSS handles placement). The
     // `.title` is the user-visible text — bare profile.status is the {title,
     // purpose, url} object and would stringify to "[object Object]".
-    const statusText = (profile?.status?.title || '').toString().trim();
+    const statusText = getStatusText(profile);
     if (statusText) {
         const status = document.createElement('div');
         status.className = 'mini-profile-status';
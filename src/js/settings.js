@@ -270,6 +270,84 @@ async function loadTorCircuits(forceRefresh = false, forceNewCircuit = false) {
     }
 }
 
+/**
+ * Render the SOCKS5 proxy row from a ProxyState (from `proxy_get_state` / `proxy_set`
+ * or a `proxy_state` event).
+ */
+function applyProxyState(state) {
+    const statusEl = document.getElementById('proxy-status');
+    const killRow = document.getElementById('proxy-kill-switch-row');
+    const killToggle = document.getElementById('proxy-kill-switch-toggle');
+    const btn = document.getElementById('proxy-configure-btn');
+    if (!statusEl || !killRow || !killToggle || !btn) return;
+    btn.textContent = state.address ? 'Change' : 'Set Up';
+    killRow.style.display = state.address ? '' : 'none';
+    killToggle.checked = !!state.kill_switch;
+    statusEl.style.display = state.address ? '' : 'none';
+    statusEl.classList.toggle('is-down', !state.reachable);
+    if (!state.address) return;
+    if (state.tor_overrides) {
+        statusEl.textContent = `${state.address} · Tor is on, so Tor carries traffic instead`;
+    } else if (!state.reachable) {
+        statusEl.textContent = state.blocked
+            ? `${state.address} isn't responding · all connections blocked`
+            : `${state.address} isn't responding · connecting directly`;
+    } else {
+        statusEl.textContent = `Routing through ${state.address}`;
+    }
+}
+
+/**
+ * Wire the SOCKS5 proxy row: Set Up/Change prompts for the address (empty removes the proxy),
+ * the Kill Switch toggle re-saves with the current address.
+ */
+async function initProxySettingsUI() {
+    const btn = document.getElementById('proxy-configure-btn');
+    const killToggle = document.getElementById('proxy-kill-switch-toggle');
+    if (!btn || !killToggle) return;
+
+    let state = null;
+    try {
+        state = await invoke('proxy_get_state');
+        applyProxyState(state);
+    } catch (e) {
+        console.warn('[Proxy] proxy_get_state failed:', e);
+    }
+
+    const save = async (address, killSwitch) => {
+        btn.disabled = killToggle.disabled = true;
+        try {
+            state = await invoke('proxy_set', { address, killSwitch });
+            applyProxyState(state);
+        } catch (err) {
+            await popupConfirm('Proxy Not Saved', escapeHtml(String(err)), true, '', 'vector_warning.svg');
+            if (state) applyProxyState(state);
+        } finally {
+            btn.disabled = killToggle.disabled = false;
+        }
+    };
+
+    btn.addEventListener('click', async () => {
+        const entered = await popupConfirm(
+            'SOCKS5 Proxy',
+            'Enter your proxy\'s address, e.g. <b>127.0.0.1:9050</b> for a local Tor daemon.'
+            + (state?.address ? `<br>Currently <b>${escapeHtml(state.address)}</b>. Leave empty to stop using it.` : ''),
+            false,
+            state?.address || '127.0.0.1:9050'
+        );
+        if (entered === false) return;
+        await save(entered.trim() || null, !!state?.kill_switch);
+    });
+
+    killToggle.addEventListener('change', () => save(state?.address || null, killToggle.checked));
+
+    // The backend's monitor reports the proxy going down or coming back
+    await window.__TAURI__.event.listen('proxy_state', (event) => {
+        state = event.payload;
+        applyProxyState(state);
+    });
+}
+
 /**
  * Wire the Bridges section under Tor → Advanced. Toggle reveals the textarea;
  * Apply persists via tor_set_bridges (which also restarts Tor if it's
@@ -854,7 +932,21 @@ class VoiceSettings {
 
         async downloadModel(modelName) {
         const model = this.models.find(m => m.model.name === modelName);
-        if (!model || model.downloaded) return;
+        if (!model || model.downloaded) return true;
+
+        // Large models are worth a second look before pulling them over a metered connection
+        let consentToken = null;
+        try {
+            const estimate = await invoke('estimate_whisper_download', { modelName });
+            if (estimate) {
+                consentToken = await popupConsent(estimate, 'Download AI Model?',
+                    `Transcription needs the <b>${escapeHtml(model.model.display_name)}</b> model on this device.`);
+                if (consentToken === false) return false;
+            }
+        } catch (error) {
+            console.error('Model estimate failed:', error);
+            return false;
+        }
 
         const modelStatus = document.getElementById('model-status');
 
@@ -886,7 +978,7 @@ class VoiceSettings {
                 }
             );
 
-            await invoke('download_whisper_model', { modelName });
+            await invoke('download_whisper_model', { modelName, consentToken });
             unlisten();
 
             model.downloaded = true;
@@ -895,6 +987,7 @@ class VoiceSettings {
             modelStatus.innerHTML = `<div class="alert alert-success">Vector AI is ready</div>`;
             await this.loadWhisperModels();
             this.updateModelStatus();
+            return true;
 
         } catch (error) {
             model.downloading = false;
@@ -904,6 +997,7 @@ class VoiceSettings {
                 : `<div class="alert alert-warning">Download failed: ${escapeHtml(String(error))}</div>`;
             if (!isCancelled) console.error('Download failed:', error);
             this.updateModelStatus();
+            return false;
         } finally {
             if (cancelBtn) cancelBtn.style.display = 'none';
             document.getElementById('whisper-model').disabled = false;
@@ -1280,32 +1374,53 @@ async function askForBanner() {
     }
 }
 
+/**
+ * Parse a short duration ("30m", "4h", "2d") into seconds; null for blank or unparseable input.
+ * @param {string} str
+ * @returns {number|null}
+ */
+function parseStatusDuration(str) {
+    const m = /^(\d+)\s*([mhd])$/i.exec((str || '').trim());
+    if (!m) return null;
+    const unit = { m: 60, h: 3600, d: 86400 }[m[2].toLowerCase()];
+    return parseInt(m[1], 10) * unit;
+}
+
 /**
  * A GUI wrapper to ask the user for a status, and apply it both
- * in-app and on the Nostr network.
+ * in-app and on the Nostr network. A leading emoji ("🎧 deep work") is
+ * split out by the backend; a non-empty status can optionally auto-clear.
  */
 async function askForStatus() {
     const strStatus = await popupConfirm('Status', 'Set a public status for everyone to see', false, 'Custom Status');
     if (strStatus === false) return;
 
+    let expiresAt = null;
+    if (strStatus.trim()) {
+        const strExpiry = await popupConfirm('Clear After', 'Auto-clear in e.g. <b>30m</b>, <b>4h</b> or <b>1d</b> — leave blank to keep it', false, 'Never');
+        if (strExpiry === false) return;
+        const secs = parseStatusDuration(strExpiry);
+        if (secs) expiresAt = Math.floor(Date.now() / 1000) + secs;
+    }
+
     // Display the change immediately
     const cProfile = arrProfiles.find(a => a.mine);
-    const oldStatus = cProfile.status.title;
-    cProfile.status.title = strStatus;
+    const oldStatus = { ...cProfile.status };
+    Object.assign(cProfile.status, { title: strStatus, emoji: '', expires_at: expiresAt || 0 });
     renderCurrentProfile(cProfile);
     if (domProfile.style.display === '') renderProfileTab(cProfile);
 
     // Send out the status update
     try {
-        const success = await invoke("update_status", { status: strStatus });
+        const success = await invoke("update_status", { status: strStatus, expiresAt });
         if (!success) {
-            cProfile.status.title = oldStatus;
+            cProfile.status = oldStatus;
             renderCurrentProfile(cProfile);
             if (domProfile.style.display === '') renderProfileTab(cProfile);
             await popupConfirm('Status Update Failed!', 'Failed to broadcast status update to the network.', true, '', 'vector_warning.svg');
         }
     } catch (e) {
-        cProfile.status.title = oldStatus;
+        cProfile.status = oldStatus;
         renderCurrentProfile(cProfile);
         if (domProfile.style.display === '') renderProfileTab(cProfile);
         await popupConfirm('Status Update Failed!', escapeHtml(String(e)), true, '', 'vector_warning.svg');
@@ -1324,6 +1439,19 @@ async function selectFile() {
     return file || "";
 }
 
+/**
+ * A GUI wrapper to ask the user for one or more file paths.
+ * @returns {Promise<string[]>}
+ */
+async function selectFiles() {
+    const files = await open({
+        multiple: true,
+        directory: false
+    });
+    if (!files) return [];
+    return Array.isArray(files) ? files : [files];
+}
+
 /**
  * A GUI wrapper to ask the user for a folder path (desktop only).
  */
@@ -1408,11 +1536,12 @@ domSettingsExport.onclick = async (evt) => {
         if (keys.seed_phrase) {
         exportContent += `
             <div style="text-align: left; padding: 0 8px; margin-bottom: 12px;">
-            <p style="font-weight: bold; margin: 0 0 4px 0; text-align: center;">Seed Phrase</p>
+            <p style="font-weight: bold; margin: 0 0 4px 0; text-align: center;">Seed Phrase${keys.seed_passphrase_protected ? ' + Passphrase' : ''}</p>
             <div style="display: flex; align-items: center; gap: 6px; min-width: 0;">
                 <p id="export-seed-value" style="overflow-x: auto; overflow-y: hidden; white-space: nowrap; background: #1a1a1a; padding: 8px 10px; border-radius: 5px; font-family: monospace; font-size: 12px; flex: 1; min-width: 0; margin: 0;">${safeSeed}</p>
                 <button id="export-seed-copy" style="flex-shrink: 0; padding: 6px 10px; border-radius: 5px; cursor: pointer;">Copy</button>
             </div>
+            ${keys.seed_passphrase_protected ? '<p style="color: var(--danger-pink); font-size: 12px; margin: 6px 0 0 0; text-align: center;">This seed phrase was created with a passphrase. You need both to restore your account — Vector does not store the passphrase.</p>' : ''}
             </div>
         `;
         }
@@ -1447,10 +1576,146 @@ domSettingsExport.onclick = async (evt) => {
     }
 };
 
+// Listen for Move to Another Device clicks: pick a destination and password, then write the bundle
+domSettingsExportBundle.onclick = async () => {
+    const path = await window.__TAURI__.dialog.save({ defaultPath: 'vector-account.vbundle', filters: [{ name: 'Vector Account Bundle', extensions: ['vbundle'] }] });
+    if (!path) return;
+    const password = await popupPassword('Bundle Password', 'Choose a password to lock the bundle. You\'ll need it to restore the account; it can\'t be recovered.');
+    if (!password) return;
+    if (password !== await popupPassword('Confirm Password', 'Enter the password again.')) {
+        return popupConfirm('Export Failed', 'The passwords didn\'t match.', true, '', 'vector_warning.svg');
+    }
+
+    const strPrevText = domSettingsExportBundle.textContent;
+    try {
+        domSettingsExportBundle.disabled = true;
+        domSettingsExportBundle.textContent = 'Exporting...';
+        const summary = await invoke('export_account_bundle', { password, path });
+        await popupConfirm('Bundle Saved', `Saved ${formatBytes(summary.bytes, 1)} to:<br><br><code>${escapeHtml(path)}</code><br><br>Anyone with this file and its password can read your messages and use your account. Delete it once you've restored it.`, true);
+    } catch (error) {
+        await popupConfirm('Export Failed', escapeHtml(String(error)), true, '', 'vector_warning.svg');
+    } finally {
+        domSettingsExportBundle.textContent = strPrevText;
+        domSettingsExportBundle.disabled = false;
+    }
+};
+
+const KEY_AUDIT_OP_LABELS = {
+    sign_event: 'Signed',
+    encrypt: 'Encrypted',
+    decrypt: 'Decrypted',
+    key_access: 'Key read',
+    export_keys: 'Keys exported',
+    key_loaded: 'Key loaded',
+};
+
+// Listen for Key Activity clicks: page through the key-usage audit log, newest first
+domSettingsKeyAudit.onclick = async () => {
+    for (let page = 0; ; page++) {
+        let entries;
+        try {
+            entries = await invoke('get_key_audit_log', { page });
+        } catch (error) {
+            return popupConfirm('Key Activity', escapeHtml(String(error)), true, '', 'vector_warning.svg');
+        }
+        if (!entries.length) {
+            return popupConfirm('Key Activity', page ? 'No older activity.' : 'No key activity recorded yet.', true, '', 'key.svg');
+        }
+        const rows = entries.map(e => {
+            const what = (KEY_AUDIT_OP_LABELS[e.op] || e.op) + (e.detail ? ` (${escapeHtml(e.detail)})` : '');
+            const count = e.count > 1 ? ` · ${e.count}×` : '';
+            return `<div style="padding: 4px 0; border-bottom: 1px solid rgba(255, 255, 255, 0.08);">
+                <b>${what}</b>${count} · ${timeAgo(e.last_at * 1000)}
+                <div style="opacity: 0.6; font-family: monospace; font-size: 11px;">${escapeHtml(e.subsystem)}</div>
+            </div>`;
+        }).join('');
+        const fOlder = await popupConfirm('Key Activity', `<div style="text-align: left; max-height: 50vh; overflow-y: auto; font-size: 13px;">${rows}</div>`, false, '', 'key.svg', '', 'Older');
+        if (!fOlder) return;
+    }
+};
+
+// Listen for Activity Report clicks: pick a window and destination, then write the metadata-only report
+domSettingsActivityReport.onclick = async () => {
+    const strDays = await popupConfirm('Activity Report', 'How many days back should the report cover? Leave empty for all time.', false, 'All time');
+    if (strDays === false) return;
+    const days = parseInt(strDays, 10);
+    const range = days > 0 ? { from: Math.floor(Date.now() / 1000) - days * 86400, to: null } : { from: null, to: null };
+
+    const path = await window.__TAURI__.dialog.save({
+        defaultPath: 'vector-activity.csv',
+        filters: [{ name: 'Spreadsheet', extensions: ['csv'] }, { name: 'JSON', extensions: ['json'] }],
+    });
+    if (!path) return;
+    const format = path.toLowerCase().endsWith('.json') ? 'json' : 'csv';
+
+    try {
+        const summary = await invoke('export_activity_report', { range, format, path });
+        await popupConfirm('Report Saved', `${summary.messages.toLocaleString()} messages across ${summary.chats} chats, and ${summary.logins} logins, saved to:<br><br><code>${escapeHtml(path)}</code>`, true);
+    } catch (error) {
+        await popupConfirm('Export Failed', escapeHtml(String(error)), true, '', 'vector_warning.svg');
+    }
+};
+
+// Listen for Social Backup clicks: warn, pick guardians + threshold, then distribute the shares
+domSettingsSocialBackup.onclick = async () => {
+    const existing = await invoke('get_social_backup').catch(() => null);
+    const intro = existing
+        ? `You have a backup with <b>${existing.guardians.length}</b> guardians (any <b>${existing.threshold}</b> can restore). Creating a new one replaces it: ask your old guardians to ignore their old share.<br><br>`
+        : '';
+    const fAccept = await popupConfirm('Social Backup',
+        intro + '<b style="color: var(--danger-pink);">Your guardians will hold pieces of your seed phrase.</b> If enough of them team up — or are hacked together — they can take your account.<br><br>Only continue if you trust each guardian, and pick people who don\'t know each other.',
+        false, '', 'vector_warning.svg', '', 'I Understand');
+    if (!fAccept) return;
+
+    const strGuardians = await popupConfirm('Choose Guardians', 'Enter the npubs of 2 to 10 trusted contacts, separated by commas or spaces.', false, 'npub1..., npub1...');
+    if (!strGuardians) return;
+    const guardians = strGuardians.split(/[\s,]+/).filter(Boolean);
+
+    const strThreshold = await popupConfirm('Recovery Threshold', `How many of your ${guardians.length} guardians must help to restore your account? (2 to ${guardians.length})`, false, String(Math.min(guardians.length, Math.max(2, Math.ceil(guardians.length / 2)))));
+    if (!strThreshold) return;
+    const threshold = parseInt(strThreshold, 10);
+
+    try {
+        const info = await invoke('create_social_backup', { guardians, threshold });
+        const failed = info.guardians.filter(g => !g.delivered);
+        const note = failed.length
+            ? `<br><br><span style="color: var(--danger-pink);">${failed.length} share(s) could not be delivered. Try again later, or the backup may not be recoverable.</span>`
+            : '';
+        await popupConfirm('Backup Created', `Your seed phrase was split between ${info.guardians.length} guardians. Any ${info.threshold} of them can help you recover your account.${note}`, true);
+    } catch (e) {
+        await popupConfirm('Backup Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+};
+
+/**
+ * A contact (from a new device) asks for the backup share we hold for them. Releasing it helps
+ * whoever holds the requesting device, so the code must be confirmed with the owner out-of-band.
+ * @param {{requester_npub: string, owner_npub: string, code: string}} req
+ */
+async function reviewBackupRequest(req) {
+    if (!req || !req.requester_npub) return;
+    const profile = getProfile(req.owner_npub);
+    const name = escapeHtml(profile?.nickname || profile?.name || req.owner_npub.slice(0, 16) + '…');
+    const fApprove = await popupConfirm('Account Recovery Request',
+        `Someone is recovering <b>${name}</b>'s account and asked for the backup share you hold.<br><br>Verification code: <b style="font-family: monospace; font-size: 18px;">${escapeHtml(req.code)}</b><br><br><b style="color: var(--danger-pink);">Only approve if ${name} told you this exact code by phone or in person.</b> Anyone can send this request.`,
+        false, '', 'vector_warning.svg', '', 'Release Share');
+    try {
+        if (fApprove) {
+            await invoke('approve_backup_request', { requester: req.requester_npub });
+            showToast('Backup share released');
+        } else {
+            await invoke('deny_backup_request', { requester: req.requester_npub });
+        }
+    } catch (e) {
+        await popupConfirm('Recovery Request Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+}
+
 // Privacy Settings - Simple global variables
 let fWebPreviewsEnabled = true;
 let fStripTrackingEnabled = true;
 let fSendTypingIndicators = true;
+let fShowLastActive = true;
 
 // Display Settings - Simple global variables
 let fDisplayImageTypes = false;
@@ -1469,12 +1734,67 @@ function applyAutocorrectSetting() {
     domChatMessageInput.setAttribute('autocorrect', fAutocorrectEnabled ? 'on' : 'off');
 }
 
+/** Accessibility preferences, as stored by the backend (see `get_accessibility_settings`). */
+let accessibilitySettings = { font_scale: 100, reduced_motion: false, high_contrast: false };
+
+/**
+ * Apply accessibility preferences to the document. Called with the boot payload's copy before
+ * the first paint, and again whenever they change here or on another device.
+ * @param {{font_scale: number, reduced_motion: boolean, high_contrast: boolean}} settings
+ */
+function applyAccessibilitySettings(settings) {
+    if (!settings) return;
+    accessibilitySettings = settings;
+    document.documentElement.style.setProperty('--msg-font-scale', String(settings.font_scale / 100));
+    document.body.classList.toggle('reduced-motion', settings.reduced_motion);
+    document.body.classList.toggle('high-contrast', settings.high_contrast);
+}
+
+/** Reflect the current accessibility preferences onto the Display controls. */
+function renderAccessibilityControls() {
+    const scaleSelect = document.getElementById('a11y-font-scale-select');
+    if (!scaleSelect) return;
+    scaleSelect.value = String(accessibilitySettings.font_scale);
+    document.getElementById('a11y-reduced-motion-toggle').checked = accessibilitySettings.reduced_motion;
+    document.getElementById('a11y-high-contrast-toggle').checked = accessibilitySettings.high_contrast;
+}
+
+/** Wire the accessibility controls in Settings > Display. */
+async function initAccessibilitySettings() {
+    try {
+        applyAccessibilitySettings(await invoke('get_accessibility_settings'));
+    } catch (_) {}
+    renderAccessibilityControls();
+    const save = async (changes) => {
+        const previous = accessibilitySettings;
+        applyAccessibilitySettings({ ...accessibilitySettings, ...changes });
+        try {
+            await invoke('set_accessibility_settings', { settings: accessibilitySettings });
+        } catch (err) {
+            applyAccessibilitySettings(previous);
+            renderAccessibilityControls();
+            await popupConfirm('Settings Not Saved', escapeHtml(String(err)), true, '', 'vector_warning.svg');
+        }
+    };
+    document.getElementById('a11y-font-scale-select').addEventListener('change', (e) => {
+        save({ font_scale: Number(e.target.value) });
+    });
+    document.getElementById('a11y-reduced-motion-toggle').addEventListener('change', (e) => {
+        save({ reduced_motion: e.target.checked });
+    });
+    document.getElementById('a11y-high-contrast-toggle').addEventListener('change', (e) => {
+        save({ high_contrast: e.target.checked });
+    });
+}
+
 // Security Settings - Encryption state
 let fEncryptionEnabled = true;
 let fSecurityType = 'pin';
 let fMigrationInProgress = false;
 let fMigrationEncrypting = false;
 let fMigrationRekeying = false;
+/** The error a cancelled encryption migration rejects with (matches the backend's). */
+const MIGRATION_CANCELLED = 'Migration cancelled';
 let unlistenMigrationProgress = null;
 let unlistenMigrationComplete = null;
 
@@ -1528,6 +1848,68 @@ async function clearStorage() {
     }
 }
 
+/**
+ * Move downloaded files older than the chosen age into a folder the user picks (e.g. a NAS or
+ * external drive). They keep opening from there while it's reachable. Desktop only.
+ */
+async function archiveOldFiles() {
+    const archiveBtn = document.getElementById('archive-storage-btn');
+    if (archiveBtn.disabled) return;
+    const ageSelect = document.getElementById('archive-storage-age');
+    const days = parseInt(ageSelect.value, 10);
+
+    const folder = await selectFolder();
+    if (!folder) return;
+    const confirmArchive = await popupConfirm(
+        'Archive Old Files?',
+        `Files from messages older than <b>${escapeHtml(ageSelect.selectedOptions[0].textContent)}</b> will be moved to:<br><br><code>${escapeHtml(folder)}</code><br><br>They stay viewable in Vector while that folder is available.`,
+        false,
+        '',
+        'vector_warning.svg'
+    );
+    if (!confirmArchive) return;
+
+    const strPrevText = archiveBtn.textContent;
+    try {
+        archiveBtn.disabled = true;
+        archiveBtn.textContent = 'Archiving...';
+        const report = await invoke('archive_attachments', { olderThanDays: days, targetDir: folder });
+        const failed = report.failed ? `<br><br>${report.failed} file(s) could not be moved and were left in place.` : '';
+        await popupConfirm('Archive Complete', `Moved ${report.moved} file(s), ${formatBytes(report.bytes, 1)}.${failed}`, true);
+        return true;
+    } catch (error) {
+        await popupConfirm('Archive Failed', `Could not archive files: ${escapeHtml(String(error))}`, true, '', 'vector_warning.svg');
+        return false;
+    } finally {
+        archiveBtn.textContent = strPrevText;
+        archiveBtn.disabled = false;
+    }
+}
+
+/**
+ * Move every archived file back into Vector's download folder.
+ */
+async function restoreArchivedFiles() {
+    const restoreBtn = document.getElementById('restore-archive-btn');
+    if (restoreBtn.disabled) return;
+
+    const strPrevText = restoreBtn.textContent;
+    try {
+        restoreBtn.disabled = true;
+        restoreBtn.textContent = 'Restoring...';
+        const report = await invoke('restore_archived_attachments', { messageId: null });
+        const failed = report.failed ? `<br><br>${report.failed} file(s) could not be found - is the archive folder connected?` : '';
+        await popupConfirm('Restore Complete', `Restored ${report.moved} file(s), ${formatBytes(report.bytes, 1)}.${failed}`, true);
+        return true;
+    } catch (error) {
+        await popupConfirm('Restore Failed', `Could not restore files: ${escapeHtml(String(error))}`, true, '', 'vector_warning.svg');
+        return false;
+    } finally {
+        restoreBtn.textContent = strPrevText;
+        restoreBtn.disabled = false;
+    }
+}
+
 /**
  * Load the auto-download settings into their globals, migrating pre-split accounts.
  *
@@ -1564,6 +1946,45 @@ async function initAutoDownloadSettings() {
     await saveMaxAutoDownloadBytes(MAX_AUTO_DOWNLOAD_BYTES);
 }
 
+/** Outbound photo compression (see `get_media_settings`): quality preset, JPEG/WebP, and
+ *  whether sends compress unless the user picks the original. */
+let mediaSettings = { quality: 'standard', format: 'jpeg', compress_by_default: true };
+
+/** Reflect the media settings into Settings > Storage and wire the controls. */
+function initMediaSettingsControls() {
+    const compressToggle = document.getElementById('media-compress-toggle');
+    const qualitySelect = document.getElementById('media-quality-select');
+    const formatSelect = document.getElementById('media-format-select');
+    if (!compressToggle || !qualitySelect || !formatSelect) return;
+    const render = () => {
+        compressToggle.checked = mediaSettings.compress_by_default;
+        qualitySelect.value = mediaSettings.quality;
+        formatSelect.value = mediaSettings.format;
+    };
+    const save = async (changes) => {
+        const previous = mediaSettings;
+        mediaSettings = { ...mediaSettings, ...changes };
+        try {
+            await invoke('set_media_settings', { settings: mediaSettings });
+        } catch (err) {
+            mediaSettings = previous;
+            render();
+            await popupConfirm('Settings Not Saved', escapeHtml(String(err)), true, '', 'vector_warning.svg');
+        }
+    };
+    render();
+    // onchange (not addEventListener): initStorageSection re-runs and must not stack listeners
+    compressToggle.onchange = () => save({ compress_by_default: compressToggle.checked });
+    qualitySelect.onchange = () => save({ quality: qualitySelect.value });
+    formatSelect.onchange = () => save({ format: formatSelect.value });
+
+    const info = document.getElementById('media-compress-info');
+    if (info) info.onclick = (e) => {
+        e.preventDefault(); e.stopPropagation();
+        popupConfirm('Compress Photos', 'Downscales and recompresses photos before they upload, so they send faster and use less data.<br><br>The file preview lets you send the original of any single photo instead. GIFs are never compressed.', true);
+    };
+}
+
 /**
  * Initialize the Storage section in settings
  */
@@ -1610,6 +2031,30 @@ async function initStorageSection() {
     }
     applyAutoDownloadState();
 
+    // Media cache limit: downloads beyond it evict the least recently used files
+    const cacheLimit = document.getElementById('media-cache-limit');
+    const cacheUsage = document.getElementById('media-cache-usage');
+    const showCacheStats = (stats) => {
+        if (cacheLimit) cacheLimit.value = String(stats.limit_bytes);
+        if (cacheUsage) cacheUsage.textContent = stats.files ? `(${formatBytes(stats.used_bytes, 1)} used)` : '';
+    };
+    if (cacheLimit) {
+        try {
+            showCacheStats(await invoke('get_cache_stats'));
+        } catch (_) {}
+        cacheLimit.onchange = async () => {
+            try {
+                await invoke('set_cache_limit', { limitBytes: parseInt(cacheLimit.value, 10) });
+                // Refresh the chart too: a lower limit may have just freed space
+                await initStorageSection();
+            } catch (err) {
+                popupConfirm('Media Cache', escapeHtml(String(err)), true, '', 'vector_warning.svg');
+            }
+        };
+    }
+
+    initMediaSettingsControls();
+
     // Explainer (i) icons. preventDefault so the toggle-row icon doesn't flip the switch.
     const adInfo = document.getElementById('auto-download-info');
     if (adInfo) adInfo.onclick = (e) => {
@@ -1621,6 +2066,18 @@ async function initStorageSection() {
         e.preventDefault(); e.stopPropagation();
         popupConfirm('Auto-Download Limit', 'The largest attachment size Vector will fetch automatically.<br><br>Anything above this waits for you to tap Download. Only applies while Auto-Download Media is on.', true);
     };
+    const cacheLimitInfo = document.getElementById('media-cache-limit-info');
+    if (cacheLimitInfo) cacheLimitInfo.onclick = (e) => {
+        e.preventDefault(); e.stopPropagation();
+        popupConfirm('Media Cache Limit', 'The most space downloaded attachments may take up on this device.<br><br>When a new download goes over it, Vector removes the files you opened least recently. They stay in your chats as previews and can be downloaded again while their sender\'s server still has them.', true);
+    };
+    const archiveInfo = document.getElementById('archive-storage-info');
+    if (archiveInfo) archiveInfo.onclick = (e) => {
+        e.preventDefault(); e.stopPropagation();
+        popupConfirm('Archive Old Files', 'Moves files from older messages to a folder you choose, such as an external drive or network share, to free up space here.<br><br>Archived files still open in Vector while that folder is available. Restore moves them all back.', true);
+    };
+    const archiveGroup = document.getElementById('archive-storage-group');
+    if (archiveGroup) archiveGroup.style.display = platformFeatures.is_mobile ? 'none' : '';
     const clearInfo = document.getElementById('clear-storage-info');
     if (clearInfo) clearInfo.onclick = (e) => {
         e.preventDefault(); e.stopPropagation();
@@ -1965,14 +2422,14 @@ async function initMuteEveryoneSetting(useDirect = false) {
 /**
  * Initialize the notification Content Privacy dropdown (all platforms). Reads
  * and writes the per-account `notif_content_privacy` setting directly; the
- * backend reads the same key at notify time. Values: full | hide_content | hide_all.
+ * backend reads the same key at notify time. Values: full | hide_content | hide_all | count_only.
  */
 async function initNotifContentPrivacy() {
     const select = document.getElementById('notif-privacy-select');
     if (!select) return;
     try {
         const val = await invoke('get_sql_setting', { key: 'notif_content_privacy' });
-        select.value = (val === 'hide_content' || val === 'hide_all') ? val : 'full';
+        select.value = ['hide_content', 'hide_all', 'count_only'].includes(val) ? val : 'full';
     } catch (_) {
         select.value = 'full';
     }
@@ -1981,6 +2438,276 @@ async function initNotifContentPrivacy() {
     });
 }
 
+/**
+ * Initialize the "Mark Chats Read" dropdown: instantly (the default) or after the chat has stayed
+ * in view for a few seconds. Writes `read_dwell_secs`, which the backend's read tracker reads.
+ */
+async function initReadDwellSetting() {
+    const select = document.getElementById('read-dwell-select');
+    try {
+        nReadDwellSecs = parseInt(await invoke('get_sql_setting', { key: 'read_dwell_secs' })) || 0;
+    } catch (_) {
+        nReadDwellSecs = 0;
+    }
+    if (!select) return;
+    select.value = ['2', '5', '10'].includes(String(nReadDwellSecs)) ? String(nReadDwellSecs) : '0';
+    select.addEventListener('change', async (e) => {
+        await invoke('set_sql_setting', { key: 'read_dwell_secs', value: e.target.value });
+        nReadDwellSecs = parseInt(e.target.value) || 0;
+        // Re-report the chat in view under the new mode
+        _lastReportedActiveChat = '__init__';
+        syncBackendActiveChat();
+    });
+}
+
+/**
+ * Initialize the screen security toggle. Android: screen security (FLAG_SECURE), a device-wide
+ * preference kept by the OS side. Elsewhere: blur the UI while Vector isn't the active window,
+ * stored per account as `blur_on_background`. iOS has no native overlay or capture guard behind
+ * the blur, so the info popup says what it doesn't cover there.
+ */
+async function initScreenSecuritySetting() {
+    const toggle = document.getElementById('privacy-screen-security-toggle');
+    if (!toggle) return;
+    const fAndroid = platformFeatures.os === 'android';
+    const fIOS = platformFeatures.os === 'ios';
+    if (fAndroid) {
+        document.getElementById('privacy-screen-security-label').textContent = 'Screen Security';
+        toggle.checked = await invoke('get_screen_security').catch(() => false);
+    } else {
+        fBlurOnBackground = (await invoke('get_sql_setting', { key: 'blur_on_background' }).catch(() => null)) === 'true';
+        toggle.checked = fBlurOnBackground;
+    }
+    toggle.addEventListener('change', async (e) => {
+        try {
+            if (fAndroid) {
+                await invoke('set_screen_security', { enabled: e.target.checked });
+            } else {
+                await invoke('set_sql_setting', { key: 'blur_on_background', value: String(e.target.checked) });
+                fBlurOnBackground = e.target.checked;
+            }
+        } catch (err) {
+            e.target.checked = !e.target.checked;
+            showToast('Failed to save setting');
+        }
+    });
+    document.getElementById('privacy-screen-security-info').addEventListener('click', (e) => {
+        e.stopPropagation();
+        e.preventDefault();
+        popupConfirm(
+            fAndroid ? 'Screen Security' : 'Blur When in Background',
+            fAndroid
+                ? 'Blocks screenshots and screen recording of Vector, and hides its preview in the recent apps screen.<br><br>' +
+                  'Applies to every account on this device, including Vector\'s unlock screen.'
+                : fIOS
+                ? 'Blurs Vector whenever you leave it, so your chats stay hidden from anyone looking over your shoulder.<br><br>' +
+                  '<b>Not covered on iOS:</b> the app switcher preview may be captured before the blur applies, and ' +
+                  'screenshots and screen recording aren\'t blocked.'
+                : 'Blurs Vector whenever you switch to another window, so your chats stay hidden from anyone ' +
+                  'looking over your shoulder, and from screen shares and the app switcher.',
+            true,
+            '',
+            'vector-check.svg'
+        );
+    });
+}
+
+/**
+ * Desktop only: whether closing the window hides Vector to the system tray (still receiving and
+ * notifying) instead of quitting.
+ */
+async function initMinimizeToTraySetting() {
+    const group = document.getElementById('minimize-to-tray-group');
+    const toggle = document.getElementById('minimize-to-tray-toggle');
+    const info = document.getElementById('minimize-to-tray-info');
+    if (!group || !toggle || platformFeatures.is_mobile) return;
+    group.style.display = '';
+    toggle.checked = await invoke('get_minimize_to_tray');
+
+    if (info) info.onclick = (e) => {
+        e.preventDefault(); e.stopPropagation();
+        popupConfirm('Minimize to Tray', 'When enabled, closing the window keeps Vector <b>running in the system tray</b>, so you keep receiving messages and notifications.<br><br>Click the tray icon to bring the window back, or use <b>Quit Vector</b> from its menu to exit.', true);
+    };
+
+    toggle.addEventListener('change', async (e) => {
+        try {
+            await invoke('set_minimize_to_tray', { enabled: e.target.checked });
+        } catch (err) {
+            e.target.checked = !e.target.checked;
+            showToast('Failed to save setting');
+        }
+    });
+}
+
+/**
+ * Linux only: the WebKitGTK renderer picker. The backend detects the display server and GPU at
+ * startup and picks a renderer; the override saved here applies on the next launch.
+ */
+async function initRendererModeSetting() {
+    const group = document.getElementById('renderer-mode-group');
+    const select = document.getElementById('renderer-mode-select');
+    const info = document.getElementById('renderer-mode-info');
+    if (!group || !select) return;
+    let env = null;
+    try {
+        env = await invoke('get_graphics_env');
+    } catch (_) { /* older backend */ }
+    if (!env) return;
+    group.style.display = '';
+    select.value = env.requested;
+
+    if (info) info.onclick = (e) => {
+        e.preventDefault(); e.stopPropagation();
+        const gpu = env.gpu_drivers.length ? env.gpu_drivers.join(', ') : 'none found';
+        popupConfirm('Renderer',
+            'How Vector draws its window. Automatic picks the most reliable option for your system; try another if the window is blank, flickers or feels slow.<br><br>'
+            + `<b>Display:</b> ${escapeHtml(env.display_server)}${env.desktop ? ' (' + escapeHtml(env.desktop) + ')' : ''}<br>`
+            + `<b>GPU driver:</b> ${escapeHtml(gpu)}<br>`
+            + `<b>Running:</b> ${escapeHtml(env.mode)}, ${escapeHtml(env.reason)}`,
+            true);
+    };
+
+    select.addEventListener('change', async (e) => {
+        try {
+            await invoke('set_renderer_mode', { mode: e.target.value });
+            await popupConfirm('Renderer Changed', 'Restart Vector to apply the new renderer.', true);
+        } catch (err) {
+            select.value = env.requested;
+            await popupConfirm('Renderer Not Changed', escapeHtml(String(err)), true, '', 'vector_warning.svg');
+            return;
+        }
+        env.requested = e.target.value;
+    });
+}
+
+/**
+ * Re-read settings another device changed (the backend's `settings_synced`
+ * event) so the new values apply live without a restart.
+ * @param {string[]} keys - The adopted setting keys
+ */
+async function applySyncedSettings(keys) {
+    if (keys.includes('theme')) {
+        const theme = await invoke('get_sql_setting', { key: 'theme' });
+        if (theme) {
+            applyTheme(theme);
+            refreshEmojiPacksForTheme();
+        }
+    }
+    fWebPreviewsEnabled = await loadWebPreviews();
+    fStripTrackingEnabled = await loadStripTracking();
+    fSendTypingIndicators = await loadSendTypingIndicators();
+    fShowLastActive = await loadShowLastActive();
+    document.getElementById('privacy-web-previews-toggle').checked = fWebPreviewsEnabled;
+    document.getElementById('privacy-strip-tracking-toggle').checked = fStripTrackingEnabled;
+    document.getElementById('privacy-send-typing-toggle').checked = fSendTypingIndicators;
+    document.getElementById('privacy-last-active-toggle').checked = fShowLastActive;
+    if (keys.some(k => k.startsWith('a11y_'))) {
+        applyAccessibilitySettings(await invoke('get_accessibility_settings'));
+        renderAccessibilityControls();
+    }
+    if (keys.includes('announcements_enabled')) {
+        const enabled = (await invoke('get_sql_setting', { key: 'announcements_enabled' })) !== 'false';
+        document.getElementById('notif-announcements-toggle').checked = enabled;
+        await invoke('set_announcements_enabled', { enabled });
+    }
+}
+
+/**
+ * Initialize the Vector Announcements opt-out (all platforms). Unset reads as
+ * enabled; the backend (re)subscribes or unsubscribes as soon as it changes.
+ */
+async function initAnnouncementsSetting() {
+    const toggle = document.getElementById('notif-announcements-toggle');
+    if (!toggle) return;
+    try {
+        toggle.checked = (await invoke('get_sql_setting', { key: 'announcements_enabled' })) !== 'false';
+    } catch (_) {
+        toggle.checked = true;
+    }
+    toggle.addEventListener('change', async (e) => {
+        await invoke('set_announcements_enabled', { enabled: e.target.checked });
+    });
+}
+
+/**
+ * Initialize the notification payload-shaping controls: lock screen visibility
+ * (`notif_lockscreen`, Android only) and short wearable-friendly previews
+ * (`notif_short_body`, all platforms). Read by the backend at notify time.
+ */
+async function initNotifShaping() {
+    const lockGroup = document.getElementById('notif-lockscreen-group');
+    const lockSelect = document.getElementById('notif-lockscreen-select');
+    if (lockGroup && lockSelect && platformFeatures.os === 'android') {
+        lockGroup.style.display = '';
+        try {
+            const val = await invoke('get_sql_setting', { key: 'notif_lockscreen' });
+            lockSelect.value = (val === 'public' || val === 'secret') ? val : 'private';
+        } catch (_) {
+            lockSelect.value = 'private';
+        }
+        lockSelect.addEventListener('change', async (e) => {
+            await invoke('set_sql_setting', { key: 'notif_lockscreen', value: e.target.value });
+        });
+    }
+
+    const shortToggle = document.getElementById('notif-short-body-toggle');
+    if (!shortToggle) return;
+    try {
+        shortToggle.checked = (await invoke('get_sql_setting', { key: 'notif_short_body' })) === 'true';
+    } catch (_) {
+        shortToggle.checked = false;
+    }
+    shortToggle.addEventListener('change', async (e) => {
+        await invoke('set_sql_setting', { key: 'notif_short_body', value: e.target.checked ? 'true' : 'false' });
+    });
+}
+
+/**
+ * Explain a permission the user denied for good and offer the system settings
+ * page, the only place Android lets it be turned back on.
+ * @param {string} strTitle - e.g. 'Microphone Blocked'
+ * @param {string} strWhy - What stops working without it
+ */
+async function offerPermissionSettings(strTitle, strWhy) {
+    if (platformFeatures?.os !== 'android') return;
+    if (await popupConfirm(strTitle, strWhy, false, '', 'vector_warning.svg', '', 'Open Settings')) {
+        await invoke('open_permission_settings').catch(() => {});
+    }
+}
+
+/**
+ * Warn in the Notifications section when Android is blocking them (the
+ * permission was denied or they're switched off in system settings), with a
+ * button to ask again. Re-checked whenever the app comes back to the front.
+ */
+async function initNotifPermissionBanner() {
+    const group = document.getElementById('notif-permission-group');
+    const btn = document.getElementById('notif-permission-btn');
+    if (!group || !btn || platformFeatures.os !== 'android') return;
+
+    const refresh = async () => {
+        try {
+            const statuses = await invoke('check_permissions');
+            const notifications = statuses.find(s => s.kind === 'notifications');
+            group.style.display = notifications?.state === 'denied' ? '' : 'none';
+        } catch (_) {
+            group.style.display = 'none';
+        }
+    };
+    btn.addEventListener('click', async () => {
+        const state = await invoke('request_permission', { kind: 'notifications' }).catch(() => 'unknown');
+        if (state === 'denied') {
+            await offerPermissionSettings('Notifications Blocked',
+                'Android is no longer showing the prompt. Turn notifications on for Vector in system settings.');
+        }
+        await refresh();
+    });
+    document.addEventListener('visibilitychange', () => {
+        if (document.visibilityState === 'visible') refresh();
+    });
+    await refresh();
+}
+
 /**
  * Initialize notification sound settings UI
  */
@@ -2174,6 +2901,62 @@ async function loadBlockedUsersList() {
     }
 }
 
+/**
+ * Load and render the Recently Deleted chats in Privacy settings, each restorable until its purge
+ */
+async function loadRecentlyDeletedList() {
+    const listContainer = document.getElementById('settings-deleted-list');
+    const emptyMsg = document.getElementById('settings-deleted-empty');
+    listContainer.innerHTML = '';
+
+    try {
+        const deleted = await invoke('list_recently_deleted');
+        emptyMsg.style.display = deleted.length ? 'none' : '';
+
+        for (const entry of deleted) {
+            const profile = getProfile(entry.id) || { id: entry.id };
+            const row = document.createElement('div');
+            row.style.cssText = 'display: flex; align-items: center; justify-content: space-between; padding: 8px 10px;';
+
+            const left = document.createElement('div');
+            left.style.cssText = 'display: flex; align-items: center; gap: 10px; min-width: 0; flex: 1; -webkit-user-select: none; user-select: none;';
+
+            const avatar = createAvatarImg(getProfileAvatarSrc(profile), 30, false);
+            avatar.style.flexShrink = '0';
+            const name = document.createElement('span');
+            name.style.cssText = 'color: #ddd; font-size: 14px; flex: 1; min-width: 0; overflow: hidden; text-overflow: ellipsis; white-space: nowrap; text-align: left;';
+            name.textContent = getName(entry.id) + ' ';
+            const daysLeft = Math.max(0, Math.ceil((entry.purge_at * 1000 - Date.now()) / 86_400_000));
+            const hint = document.createElement('span');
+            hint.style.cssText = 'opacity: 0.4; font-size: 12px;';
+            hint.textContent = `(${daysLeft} day${daysLeft === 1 ? '' : 's'} left)`;
+            name.appendChild(hint);
+
+            left.appendChild(avatar);
+            left.appendChild(name);
+
+            const restoreBtn = document.createElement('span');
+            restoreBtn.textContent = 'Restore';
+            restoreBtn.classList.add('unblock-btn');
+            restoreBtn.onclick = async () => {
+                try {
+                    await restoreDeletedChat(entry.id);
+                    showToast('Chat restored');
+                } catch (e) {
+                    await popupConfirm('Failed to Restore', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+                }
+                await loadRecentlyDeletedList();
+            };
+
+            row.appendChild(left);
+            row.appendChild(restoreBtn);
+            listContainer.appendChild(row);
+        }
+    } catch (e) {
+        console.warn('Failed to load deleted chats:', e);
+    }
+}
+
 /**
  * Initialize settings on app start
  */
@@ -2182,11 +2965,15 @@ async function initSettings() {
     fWebPreviewsEnabled = await loadWebPreviews();
     fStripTrackingEnabled = await loadStripTracking();
     fSendTypingIndicators = await loadSendTypingIndicators();
+    fShowLastActive = await loadShowLastActive();
 
     // Auto-download toggle + limit (migrates pre-split accounts). At boot so the
     // gate in message-row.js is correct before Settings is opened.
     await initAutoDownloadSettings();
 
+    // Outbound photo compression, read by every send path (not just the preview)
+    try { mediaSettings = await invoke('get_media_settings'); } catch (_) {}
+
     // Set initial toggle states
     const webPreviewsToggle = document.getElementById('privacy-web-previews-toggle');
     const stripTrackingToggle = document.getElementById('privacy-strip-tracking-toggle');
@@ -2210,6 +2997,22 @@ async function initSettings() {
         await saveSendTypingIndicators(e.target.checked);
     });
 
+    const lastActiveToggle = document.getElementById('privacy-last-active-toggle');
+    lastActiveToggle.checked = fShowLastActive;
+    lastActiveToggle.addEventListener('change', async (e) => {
+        fShowLastActive = e.target.checked;
+        await invoke('set_last_active_enabled', { enabled: e.target.checked });
+        // The backend wiped its cache; mirror that so nothing stale lingers on screen.
+        if (!fShowLastActive) for (const p of arrProfiles) p.last_active = 0;
+    });
+
+    // Online status goes to contacts only, and only while this is on (off by default).
+    const presenceToggle = document.getElementById('privacy-presence-toggle');
+    invoke('get_presence_sharing').then(sharing => { presenceToggle.checked = sharing === 'contacts'; });
+    presenceToggle.addEventListener('change', async (e) => {
+        await invoke('set_presence_sharing', { sharing: e.target.checked ? 'contacts' : 'nobody' });
+    });
+
     // Tor toggle — reads current state from the backend (which knows whether
     // the build was compiled with `--features tor`), then attaches a change
     // handler that persists the preference and starts/stops the embedded Tor
@@ -2306,6 +3109,9 @@ async function initSettings() {
         await initTorBridgesUI();
     }
 
+    // Custom SOCKS5 proxy + kill switch
+    await initProxySettingsUI();
+
     // Load blocked users list + toggle
     await loadBlockedUsersList();
     const blockedToggle = document.getElementById('settings-blocked-toggle');
@@ -2323,6 +3129,23 @@ async function initSettings() {
         }
     };
 
+    // Recently deleted chats + toggle (re-fetched on open: deletes happen from the chat list)
+    const deletedToggle = document.getElementById('settings-deleted-toggle');
+    const deletedContent = document.getElementById('settings-deleted-content');
+    const deletedChevron = deletedToggle.querySelector('.icon');
+    deletedToggle.onclick = async () => {
+        const isOpen = deletedContent.style.display !== 'none';
+        if (isOpen) {
+            deletedContent.style.display = 'none';
+            deletedChevron.style.transform = '';
+        } else {
+            await loadRecentlyDeletedList();
+            deletedContent.style.display = '';
+            deletedContent.style.animation = 'blockedFadeIn 0.2s ease';
+            deletedChevron.style.transform = 'rotate(180deg)';
+        }
+    };
+
     // Load and initialize display settings
     fDisplayImageTypes = await loadDisplayImageTypes();
     const displayImageTypesToggle = document.getElementById('display-image-types-toggle');
@@ -2395,6 +3218,14 @@ async function initSettings() {
 
     // Content Privacy dropdown is cross-platform (direct DB read/write).
     await initNotifContentPrivacy();
+    await initReadDwellSetting();
+    await initScreenSecuritySetting();
+    await initAccessibilitySettings();
+    await initMinimizeToTraySetting();
+    await initRendererModeSetting();
+    await initNotifShaping();
+    await initNotifPermissionBanner();
+    await initAnnouncementsSetting();
 
     // Set up clear storage button
     const clearStorageBtn = document.getElementById('clear-storage-btn');
@@ -2402,6 +3233,12 @@ async function initSettings() {
         const success = await clearStorage();
         if (success) initStorageSection();
     });
+    document.getElementById('archive-storage-btn').addEventListener('click', async () => {
+        if (await archiveOldFiles()) initStorageSection();
+    });
+    document.getElementById('restore-archive-btn').addEventListener('click', async () => {
+        if (await restoreArchivedFiles()) initStorageSection();
+    });
 
     // Pre-fetch logs so clipboard.writeText runs synchronously on click (user gesture required)
     window._cachedLogs = '';
@@ -2477,17 +3314,129 @@ async function initEncryptionSettings() {
         changeCredentialBtn.addEventListener('click', handleChangeCredential);
     }
 
+    // Decrypted-content cache (per account; off = decrypt every read on demand)
+    const plaintextCacheToggle = document.getElementById('security-plaintext-cache-toggle');
+    plaintextCacheToggle.checked = (await invoke('get_sql_setting', { key: 'message_plaintext_cache' })) === 'true';
+    plaintextCacheToggle.addEventListener('change', async (e) => {
+        try {
+            await invoke('set_message_plaintext_cache', { enabled: e.target.checked });
+        } catch (err) {
+            e.target.checked = !e.target.checked;
+            showToast('Failed to save setting');
+        }
+    });
+    document.getElementById('security-plaintext-cache-info').addEventListener('click', (e) => {
+        e.stopPropagation();
+        e.preventDefault();
+        popupConfirm(
+            'Cache Decrypted Messages',
+            'Your messages stay encrypted on disk either way.<br><br>' +
+            'When on, recently read messages are kept decrypted in memory so scrolling and search are faster. ' +
+            'When off, every message is decrypted each time it is read, and nothing decrypted lingers in memory.',
+            true,
+            '',
+            'vector-check.svg'
+        );
+    });
+
+    await initKeyStorageSetting();
+    await initAutoLockSetting();
+
     // Set up Tauri event listeners for migration progress
     setupMigrationEventListeners();
 
 }
 
 /**
- * Update change credential button visibility and text
+ * The "Keep Key in System Keychain" toggle: moves the private key and seed
+ * phrase between the database and the OS keychain / Android Keystore. Hidden
+ * for NIP-55 accounts, which keep no key on this device.
+ */
+async function initKeyStorageSetting() {
+    const container = document.getElementById('key-storage-container');
+    const toggle = document.getElementById('security-key-storage-toggle');
+    if (window.__activeSignerType === 'nip55') return;
+    try {
+        toggle.checked = (await invoke('get_key_storage')) === 'keystore';
+    } catch (e) {
+        return;
+    }
+    container.style.display = '';
+    toggle.addEventListener('change', async (e) => {
+        const storage = e.target.checked ? 'keystore' : 'database';
+        try {
+            await invoke('set_key_storage', { storage });
+            showToast(storage === 'keystore' ? 'Key moved to the system keychain' : 'Key moved back to Vector');
+        } catch (err) {
+            e.target.checked = !e.target.checked;
+            popupConfirm('Key Not Moved', escapeHtml(String(err)), true, '', 'vector_warning.svg');
+        }
+    });
+    document.getElementById('security-key-storage-info').addEventListener('click', (e) => {
+        e.stopPropagation();
+        e.preventDefault();
+        popupConfirm(
+            'Keep Key in System Keychain',
+            'Stores your private key and seed phrase in your device\'s keychain (Keychain, Credential Manager, Secret Service or Android Keystore) instead of Vector\'s database.<br><br>' +
+            'A copy of the database alone then doesn\'t carry your account\'s secrets. Your messages and other data stay in the database, protected by Local Encryption as before. ' +
+            'Local Encryption still applies to the key and seed phrase either way.',
+            true,
+            '',
+            'vector-check.svg'
+        );
+    });
+}
+
+/**
+ * The "Auto-Lock" dropdown and "Lock Now" button. Writes `auto_lock_minutes` (empty = never,
+ * 0 = when leaving the app), which the backend's auto-lock reads. Shown only with encryption on,
+ * since a lock needs a PIN or password to come back from.
+ */
+async function initAutoLockSetting() {
+    const select = document.getElementById('auto-lock-select');
+    if (!select) return;
+    const strMinutes = (await invoke('get_sql_setting', { key: 'auto_lock_minutes' }).catch(() => null)) ?? '';
+    select.value = [...select.options].some(o => o.value === strMinutes) ? strMinutes : '';
+    select.addEventListener('change', async (e) => {
+        try {
+            await invoke('set_sql_setting', { key: 'auto_lock_minutes', value: e.target.value });
+            invoke('report_activity').catch(() => {});
+        } catch (err) {
+            showToast('Failed to save setting');
+        }
+    });
+    document.getElementById('auto-lock-info').addEventListener('click', (e) => {
+        e.stopPropagation();
+        e.preventDefault();
+        popupConfirm(
+            'Auto-Lock',
+            'Locks Vector after you stop using it, so your chats need your ' +
+            (fSecurityType === 'password' ? 'password' : 'PIN') + ' again.<br><br>' +
+            'A lock clears your keys and decrypted messages from memory until you unlock.',
+            true,
+            '',
+            'vector-check.svg'
+        );
+    });
+    document.getElementById('lock-now-btn').addEventListener('click', async () => {
+        try {
+            await invoke('lock_now');
+        } catch (err) {
+            popupConfirm('Not Locked', escapeHtml(String(err)), true, '', 'vector_warning.svg');
+        }
+    });
+}
+
+/**
+ * Update change credential button visibility and text, and the decrypted-cache, auto-lock and
+ * lock controls that only apply while encryption is on
  */
 function updateChangeCredentialButton() {
     const container = document.getElementById('change-pin-container');
     if (!container) return;
+    document.getElementById('plaintext-cache-container').style.display = fEncryptionEnabled ? '' : 'none';
+    document.getElementById('auto-lock-container').style.display = fEncryptionEnabled ? '' : 'none';
+    document.getElementById('lock-now-container').style.display = fEncryptionEnabled ? '' : 'none';
     if (fEncryptionEnabled) {
         container.style.display = '';
         domSettingsChangePinLabel.textContent = fSecurityType === 'password' ? 'Change Password' : 'Change PIN';
@@ -2553,6 +3502,11 @@ async function handleEnableEncryption(toggle) {
         updateChangeCredentialButton();
     } catch (e) {
         hideMigrationModal();
+        if (String(e) === MIGRATION_CANCELLED) {
+            showToast('Cancelled: encryption is still off');
+            toggle.checked = false;
+            return;
+        }
         await popupConfirm(
             'Encryption Failed',
             `Failed to enable encryption: ${escapeHtml(String(e))}`,
@@ -2852,6 +3806,10 @@ async function handleChangeCredential() {
     } catch (e) {
         hideMigrationModal();
         fMigrationRekeying = false;
+        if (String(e) === MIGRATION_CANCELLED) {
+            showToast('Cancelled: your credential is unchanged');
+            return;
+        }
         await popupConfirm(
             'Re-keying Failed',
             `Failed to change credential: ${escapeHtml(String(e))}`,
@@ -2892,6 +3850,11 @@ async function handleDisableEncryption(toggle) {
         // Success - migration complete event will hide modal
     } catch (e) {
         hideMigrationModal();
+        if (String(e) === MIGRATION_CANCELLED) {
+            showToast('Cancelled: encryption is still on');
+            toggle.checked = true;
+            return;
+        }
         await popupConfirm(
             'Decryption Failed',
             `Failed to disable encryption: ${escapeHtml(String(e))}`,
@@ -2915,12 +3878,11 @@ async function setupMigrationEventListeners() {
 
     // Listen for migration progress updates
     unlistenMigrationProgress = await listen('encryption_migration_progress', (event) => {
-        const { total, completed, phase } = event.payload;
-        updateMigrationProgress(total, completed, phase);
+        updateMigrationProgress(event.payload);
     });
 
-    // Listen for migration completion
-    unlistenMigrationComplete = await listen('encryption_migration_complete', () => {
+    // Listen for migration completion (the payload reports what the verified migration did)
+    unlistenMigrationComplete = await listen('encryption_migration_complete', (event) => {
         const wasEncrypting = fMigrationEncrypting;
         const wasRekeying = fMigrationRekeying;
         hideMigrationModal();
@@ -2928,7 +3890,9 @@ async function setupMigrationEventListeners() {
         // Update local state
         fEncryptionEnabled = document.getElementById('security-encryption-toggle').checked;
         updateChangeCredentialButton();
-        showToast(wasRekeying ? 'Credential changed' : wasEncrypting ? 'Encryption enabled' : 'Encryption disabled');
+        const done = wasRekeying ? 'Credential changed' : wasEncrypting ? 'Encryption enabled' : 'Encryption disabled';
+        const messages = event.payload?.messages || 0;
+        showToast(messages ? `${done} · ${messages.toLocaleString()} messages verified` : done);
     });
 }
 
@@ -2951,6 +3915,16 @@ function showMigrationModal(encrypting) {
     phase.textContent = 'Preparing...';
     progressFill.style.width = '0%';
     progressText.textContent = '0%';
+    const cancelBtn = document.getElementById('encryption-migration-cancel');
+    cancelBtn.disabled = false;
+    cancelBtn.textContent = 'Cancel';
+    cancelBtn.style.display = '';
+    cancelBtn.onclick = async () => {
+        // Rolls the whole migration back; the command's error path closes the modal
+        cancelBtn.disabled = true;
+        cancelBtn.textContent = 'Cancelling...';
+        await invoke('cancel_encryption_migration');
+    };
 
     // Show the overlay
     overlay.classList.add('active');
@@ -3035,6 +4009,84 @@ async function initBatterySettings() {
             warning.style.display = 'none';
         }
     });
+
+    if (platformFeatures.os === 'android') {
+        await initPeriodicSyncSetting();
+        await initUnifiedPushSetting();
+    }
+}
+
+/**
+ * Periodic background sync (Android): how often WorkManager wakes Vector for a short catch-up
+ * while the background service is off.
+ */
+async function initPeriodicSyncSetting() {
+    const row = document.getElementById('battery-periodic-sync');
+    const select = document.getElementById('battery-periodic-sync-select');
+    if (!row || !select) return;
+
+    let current = String(await invoke('get_background_sync_interval'));
+    select.value = current;
+    row.style.display = '';
+
+    select.addEventListener('change', async () => {
+        try {
+            await invoke('set_background_sync_interval', { minutes: Number(select.value) });
+            current = select.value;
+        } catch (e) {
+            select.value = current;
+            showToast('Failed to save setting');
+        }
+    });
+}
+
+/**
+ * UnifiedPush wake-ups (Android): a distributor app relays a wake signal from the user's push
+ * gateway, so messages arrive without the background service running.
+ */
+async function initUnifiedPushSetting() {
+    const row = document.getElementById('battery-unifiedpush');
+    const toggle = document.getElementById('battery-unifiedpush-toggle');
+    if (!row || !toggle) return;
+
+    const status = await invoke('get_push_status');
+    toggle.checked = !!status.distributor;
+    row.style.display = '';
+
+    toggle.addEventListener('change', async () => {
+        if (!toggle.checked) {
+            try {
+                await invoke('set_push_enabled', { enabled: false, gateway: null });
+            } catch (e) {
+                toggle.checked = true;
+                showToast('Failed to turn off UnifiedPush');
+            }
+            return;
+        }
+
+        const current = (await invoke('get_push_status')).gateway;
+        const gateway = await popupConfirm(
+            'Push Gateway',
+            'Enter the URL of the push gateway that should wake Vector when a message arrives for you.' +
+            (current ? '<br><br>Leave empty to keep <b>' + escapeHtml(current) + '</b>.' : ''),
+            false, 'https://...', 'vector_warning.svg'
+        );
+        if (gateway === false || (!gateway.trim() && !current)) {
+            toggle.checked = false;
+            return;
+        }
+
+        try {
+            const registered = await invoke('set_push_enabled', { enabled: true, gateway: gateway.trim() || current });
+            if (!registered) {
+                toggle.checked = false;
+                popupConfirm('No Distributor Found', 'UnifiedPush needs a <b>distributor app</b> (such as ntfy) to deliver wake-ups.<br><br>Install one, then try again.', true, '', 'vector_warning.svg');
+            }
+        } catch (e) {
+            toggle.checked = false;
+            popupConfirm('UnifiedPush Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+        }
+    });
 }
 
 /**
@@ -3097,8 +4149,9 @@ function waitForVisibility() {
     });
 }
 
-function updateMigrationProgress(total, completed, phase) {
+function updateMigrationProgress({ total, completed, phase, chats_completed, chats_total, bytes_processed }) {
     const phaseEl = document.getElementById('encryption-migration-phase');
+    const detailEl = document.getElementById('encryption-migration-detail');
     const progressFill = document.getElementById('encryption-migration-progress-fill');
     const progressText = document.getElementById('encryption-migration-progress-text');
 
@@ -3115,7 +4168,17 @@ function updateMigrationProgress(total, completed, phase) {
         phaseText = `Re-encrypting messages... ${completed.toLocaleString()} / ${total.toLocaleString()}`;
     } else if (phase === 'finalizing') {
         phaseText = 'Finalizing...';
+    } else if (phase === 'verifying') {
+        phaseText = 'Verifying...';
+    }
+
+    // Past the messages there's nothing left to cancel (and no more to count)
+    if (phase === 'finalizing' || phase === 'verifying') {
+        document.getElementById('encryption-migration-cancel').style.display = 'none';
     }
+    detailEl.textContent = chats_total
+        ? `${chats_completed.toLocaleString()} / ${chats_total.toLocaleString()} chats · ${formatBytes(bytes_processed, 1)}`
+        : '';
 
     phaseEl.textContent = phaseText;
     progressFill.style.width = `${percentage}%`;
@@ -0,0 +1,49 @@
+/**
+ * Starred Messages: messages the user starred, gathered from every chat.
+ *
+ * Stars are local — nothing is published. The backend keeps a starred message
+ * past its chat's Self-Destruct Timer and keeps its files out of media-cache
+ * eviction; this list is just the way back to them.
+ */
+
+/** How many starred messages the list shows. */
+const STARRED_LIST_LIMIT = 50;
+
+/** A short label for the chat a starred message lives in. */
+function starredChatName(chatId) {
+    if (chatId === strPubkey) return 'Notes';
+    const chat = getChat(chatId);
+    if (chat && chat.chat_type !== 'DirectMessage') {
+        return chat.metadata?.custom_fields?.name || 'Community';
+    }
+    return getName(chatId);
+}
+
+/** A one-line preview of a starred message. */
+function starredPreview(msg) {
+    const text = stripMarkdownToPlain(msg.content || '').replace(/\s+/g, ' ').trim();
+    if (text) return text.length > 48 ? text.slice(0, 47) + '…' : text;
+    if (msg.attachments && msg.attachments.length) return 'Attachment';
+    return 'Message';
+}
+
+/**
+ * Show the starred messages, most recently starred first, anchored to a rect.
+ * Picking one opens its chat at that message.
+ */
+async function openStarredMessages(anchor) {
+    const starred = await invoke('get_starred_messages', { limit: STARRED_LIST_LIMIT }).catch(() => []);
+    const rect = anchor || { right: window.innerWidth / 2, bottom: window.innerHeight / 2 };
+    if (!starred.length) {
+        return popupConfirm('Starred Messages', 'Nothing starred yet.<br>Open a message\'s menu and choose <b>Star</b> to keep it here.', true, '', 'vector-check.svg');
+    }
+    const items = starred.map(({ chat_id, message }) => ({
+        label: starredPreview(message),
+        hint: starredChatName(chat_id),
+        onClick: async () => {
+            if (strOpenChat !== chat_id) await openChat(chat_id);
+            jumpToMessage(message.id);
+        },
+    }));
+    showContextMenu({ x: rect.right, y: rect.bottom + 4, items });
+}
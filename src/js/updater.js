@@ -238,13 +238,19 @@ function updateUI(state, message = '', progress = 0) {
 }
 
 // Resolve the proxy the updater's own HTTP client must use. The updater
-// plugin bypasses the backend's Tor-aware client entirely, so with Tor on
-// we either hand it the SOCKS proxy or refuse to touch the network at all.
+// plugin bypasses the backend's Tor-aware client entirely, so with Tor (or a
+// SOCKS5 proxy) on we either hand it the proxy or refuse to touch the
+// network at all.
 // Returns: { allowed: boolean, proxy?: string }
 async function resolveUpdateTransport() {
     try {
         const tor = await window.__TAURI__.core.invoke('tor_get_state');
-        if (!tor?.enabled) return { allowed: true };
+        if (!tor?.enabled) {
+            // The user's own proxy: same route as the backend, blocked under its kill switch
+            const proxy = await window.__TAURI__.core.invoke('proxy_get_state');
+            if (proxy?.blocked) return { allowed: false };
+            return proxy?.active_proxy ? { allowed: true, proxy: proxy.active_proxy } : { allowed: true };
+        }
         if (tor.running && tor.socks_proxy) return { allowed: true, proxy: tor.socks_proxy };
         // Tor wanted but not up (bootstrapping/failed): fail closed.
         return { allowed: false };
@@ -0,0 +1,172 @@
+/**
+ * Video notes: short camera clips recorded from the composer, the video
+ * counterpart of voice messages (voice.js).
+ *
+ * The webview captures (getUserMedia) and encodes (MediaRecorder, H.264/MP4
+ * where available, WebM otherwise). The backend turns a small poster frame
+ * into the preview thumbhash and sends the clip as a nameless video, which is
+ * what renders it as a video note on both ends.
+ */
+
+/** Backend limits and platform support (see `get_video_note_support`). */
+let videoNoteSupport = null;
+
+/**
+ * Show the Video Note entry in the attachment panel when this platform can
+ * record one.
+ */
+async function initVideoNoteButton() {
+    const btn = document.getElementById('attachment-panel-video-note');
+    if (!btn) return;
+    try {
+        videoNoteSupport = await invoke('get_video_note_support');
+    } catch (_) {
+        return;
+    }
+    if (!videoNoteSupport.supported || !navigator.mediaDevices?.getUserMedia || typeof MediaRecorder === 'undefined') return;
+    btn.style.display = '';
+    btn.onclick = () => {
+        closeAttachmentPanel();
+        openVideoNoteRecorder(strOpenChat);
+    };
+}
+
+/** The best container/codec this webview can record. */
+function pickVideoNoteMime() {
+    const candidates = [
+        'video/mp4;codecs=avc1.42E01E,mp4a.40.2',
+        'video/mp4',
+        'video/webm;codecs=vp9,opus',
+        'video/webm;codecs=vp8,opus',
+        'video/webm',
+    ];
+    return candidates.find(type => MediaRecorder.isTypeSupported(type)) || '';
+}
+
+/**
+ * Open the camera overlay for `chatId`: tap the shutter to start, again to
+ * stop and send. Recording stops on its own at the backend's length limit.
+ * @param {string} chatId - The chat to send the note to
+ */
+async function openVideoNoteRecorder(chatId) {
+    const overlay = document.getElementById('video-note-recorder');
+    const preview = document.getElementById('video-note-preview');
+    const timer = document.getElementById('video-note-timer');
+    const recordBtn = document.getElementById('video-note-record');
+    const cancelBtn = document.getElementById('video-note-cancel');
+
+    let stream;
+    try {
+        stream = await navigator.mediaDevices.getUserMedia({
+            video: { width: { ideal: 480 }, height: { ideal: 480 }, facingMode: 'user' },
+            audio: true,
+        });
+    } catch (err) {
+        await popupConfirm('Camera Unavailable', escapeHtml(String(err?.message || err)), true, '', 'vector_warning.svg');
+        return;
+    }
+
+    preview.muted = true;
+    preview.srcObject = stream;
+    preview.play().catch(() => {});
+    overlay.style.display = '';
+
+    let recorder = null;
+    let chunks = [];
+    let cancelled = false;
+    let tick = null;
+    let limit = null;
+
+    const close = () => {
+        clearInterval(tick);
+        clearTimeout(limit);
+        stream.getTracks().forEach(track => track.stop());
+        preview.srcObject = null;
+        overlay.style.display = 'none';
+        recordBtn.classList.remove('recording');
+        timer.textContent = '0:00';
+        recordBtn.onclick = null;
+        cancelBtn.onclick = null;
+    };
+
+    cancelBtn.onclick = () => {
+        cancelled = true;
+        if (recorder?.state === 'recording') recorder.stop();
+        else close();
+    };
+
+    recordBtn.onclick = () => {
+        if (recorder) {
+            if (recorder.state === 'recording') recorder.stop();
+            return;
+        }
+        const mimeType = pickVideoNoteMime();
+        recorder = new MediaRecorder(stream, mimeType ? { mimeType, videoBitsPerSecond: 1000000 } : undefined);
+        recorder.ondataavailable = (e) => {
+            if (e.data.size) chunks.push(e.data);
+        };
+        recorder.onstop = async () => {
+            // Grab the poster while the camera is still live
+            const poster = captureVideoNotePoster(preview);
+            close();
+            if (cancelled || !chunks.length) return;
+            const blob = new Blob(chunks, { type: recorder.mimeType || mimeType });
+            chunks = [];
+            await sendVideoNote(chatId, blob, poster);
+        };
+        recorder.start(1000);
+
+        const startedAt = Date.now();
+        recordBtn.classList.add('recording');
+        tick = setInterval(() => {
+            const secs = Math.floor((Date.now() - startedAt) / 1000);
+            timer.textContent = `${Math.floor(secs / 60)}:${String(secs % 60).padStart(2, '0')}`;
+        }, 250);
+        limit = setTimeout(() => {
+            if (recorder.state === 'recording') recorder.stop();
+        }, videoNoteSupport.max_duration_secs * 1000);
+    };
+}
+
+/**
+ * A small JPEG of the current camera frame (thumbhash only needs 100px) plus
+ * the video's real dimensions.
+ * @param {HTMLVideoElement} video
+ * @returns {{jpeg: string, width: number, height: number} | null}
+ */
+function captureVideoNotePoster(video) {
+    const width = video.videoWidth;
+    const height = video.videoHeight;
+    if (!width || !height) return null;
+    const scale = Math.min(1, 100 / Math.max(width, height));
+    const canvas = document.createElement('canvas');
+    canvas.width = Math.max(1, Math.round(width * scale));
+    canvas.height = Math.max(1, Math.round(height * scale));
+    canvas.getContext('2d').drawImage(video, 0, 0, canvas.width, canvas.height);
+    return { jpeg: canvas.toDataURL('image/jpeg', 0.8).split(',')[1], width, height };
+}
+
+/**
+ * Hand the finished clip to the backend over raw-bytes IPC.
+ * @param {string} chatId
+ * @param {Blob} blob - The recorded clip
+ * @param {{jpeg: string, width: number, height: number} | null} poster
+ */
+async function sendVideoNote(chatId, blob, poster) {
+    if (blob.size > videoNoteSupport.max_bytes) {
+        await popupConfirm('Video Note Too Large', 'Try a shorter recording.', true, '', 'vector_warning.svg');
+        return;
+    }
+    const headers = { receiver: chatId, mime: blob.type };
+    if (poster) {
+        headers.poster = poster.jpeg;
+        headers.width = String(poster.width);
+        headers.height = String(poster.height);
+    }
+    try {
+        const bytes = new Uint8Array(await blob.arrayBuffer());
+        await invoke('send_video_note', bytes, { headers });
+    } catch (err) {
+        await popupConfirm('Video Note Not Sent', escapeHtml(String(err)), true, '', 'vector_warning.svg');
+    }
+}
@@ -366,7 +366,12 @@ class VoiceRecorder {
             this._startTimer();
         } catch (err) {
             console.error('Recording start failed:', err);
-            await popupConfirm('Recording Error', err, true, '', 'vector_warning.svg');
+            if (String(err).includes('permission denied')) {
+                await offerPermissionSettings('Microphone Blocked',
+                    'Vector needs microphone access to record voice messages. You can allow it in system settings.');
+            } else {
+                await popupConfirm('Recording Error', err, true, '', 'vector_warning.svg');
+            }
             this._setState(RecordingState.IDLE);
         }
     }
@@ -982,8 +987,7 @@ class VoiceTranscriptionUI {
         if (!transcribeBtn) {
             this.isSettingUp = true;
             try {
-                await window.voiceSettings?.downloadModel(selectedModel);
-                return true;
+                return !!await window.voiceSettings?.downloadModel(selectedModel);
             } catch {
                 return false;
             } finally {
@@ -1049,8 +1053,9 @@ class VoiceTranscriptionUI {
                 }
             );
 
-            await window.voiceSettings.downloadModel(selectedModel);
+            const ready = await window.voiceSettings.downloadModel(selectedModel);
             unlisten();
+            if (!ready) throw new Error('Model not downloaded');
 
             progressText.textContent = 'Ready!';
             setTimeout(() => {
@@ -1351,8 +1356,10 @@ function createTranscriptionUI(transcriptionData, seekTarget) {
  * @param {Object} cAttachment - Attachment data
  * @param {HTMLElement} pMessage - Message container element
  * @param {Object} msg - Message data
+ * @param {string} [streamFrom] - For a voice message not downloaded yet: the npub to fetch it
+ *   through. Playback then streams it (`stream_attachment`) and saves it once played through.
  */
-function handleAudioAttachment(cAttachment, pMessage, msg) {
+function handleAudioAttachment(cAttachment, pMessage, msg, streamFrom) {
     const audioContainer = document.createElement('div');
     audioContainer.classList.add('audio-message-container', 'custom-audio-player');
 
@@ -1433,8 +1440,9 @@ function handleAudioAttachment(cAttachment, pMessage, msg) {
     let barOffsetY = -9;      // shared translateY for all bars (centered when idle)
     let windDownId = null;    // rAF ID for wind-down animation (cancelled on play)
 
-    // Probe duration immediately (header-only, no decode) — skip for pending uploads
-    if (!isPending) {
+    // Probe duration immediately (header-only, no decode) — skip for pending uploads and
+    // streamed messages, which have no file yet
+    if (!isPending && !streamFrom) {
         invoke('audio_probe', { path: cAttachment.path }).then(ms => {
             if (ms > 0 && durationMs === 0) {
                 durationMs = ms;
@@ -1448,6 +1456,7 @@ function handleAudioAttachment(cAttachment, pMessage, msg) {
     let audioEndedUnlisten = null;
     let audioWaveformUnlisten = null;
     let audioDurationUnlisten = null;
+    let audioStreamFailedUnlisten = null;
     const cachedGlowColor = getComputedStyle(document.documentElement).getPropertyValue('--voice-frequency-glow').trim();
     const currentTimeEl = timeDisplay.querySelector('.current-time');
     const durationEl = timeDisplay.querySelector('.duration');
@@ -1636,7 +1645,19 @@ function handleAudioAttachment(cAttachment, pMessage, msg) {
                     }
                 });
 
-                const result = await invoke('audio_load', { path: cAttachment.path });
+                if (streamFrom) {
+                    // A record that fails authentication (or every source failing) cuts playback there
+                    audioStreamFailedUnlisten = await window.__TAURI__.event.listen('audio_stream_failed', (event) => {
+                        if (event.payload.id === sourceId) {
+                            sourceId = null;
+                            onEnded();
+                        }
+                    });
+                }
+
+                const result = streamFrom
+                    ? await invoke('stream_attachment', { npub: streamFrom, msgId: msg.id, attachmentId: cAttachment.id })
+                    : await invoke('audio_load', { path: cAttachment.path });
                 sourceId = result.id;
                 if (result.duration_ms > 0) {
                     durationMs = result.duration_ms;
@@ -1649,6 +1670,8 @@ function handleAudioAttachment(cAttachment, pMessage, msg) {
                 playBtn.classList.remove('loading');
                 playBtn.innerHTML = '<span class="icon icon-play"></span>';
                 isLoading = false;
+                // Can't be streamed (e.g. an older upload): download it the usual way
+                if (streamFrom) saveStreamed();
                 return;
             }
             playBtn.classList.remove('loading');
@@ -1823,6 +1846,15 @@ function handleAudioAttachment(cAttachment, pMessage, msg) {
         document.removeEventListener('mouseup', stopWaveformDrag);
     }
 
+    // Save a streamed voice message, reusing the blob playback already fetched. The
+    // download result re-renders the message as a regular (downloaded) player.
+    function saveStreamed() {
+        if (downloadingAttachmentIds.has(cAttachment.id)) return;
+        downloadingAttachmentIds.add(cAttachment.id);
+        invoke('download_attachment', { npub: streamFrom, msgId: msg.id, attachmentId: cAttachment.id })
+            .catch(() => downloadingAttachmentIds.delete(cAttachment.id));
+    }
+
     // Reset on end
     function onEnded() {
         playBtn.innerHTML = '<span class="icon icon-play"></span>';
@@ -1867,10 +1899,11 @@ function handleAudioAttachment(cAttachment, pMessage, msg) {
         };
         windDownReset();
 
+        if (streamFrom) saveStreamed();
     }
 
     // Only add transcription UI for voice messages with supported formats
-    if (isVoiceMessage && platformFeatures.transcription && ['wav', 'mp3', 'flac'].includes(cAttachment.extension)) {
+    if (isVoiceMessage && !streamFrom && platformFeatures.transcription && ['wav', 'mp3', 'flac'].includes(cAttachment.extension)) {
         // Display the Transcribe button
         customPlayer.appendChild(transcribeBtn);
 
@@ -1963,17 +1996,28 @@ function handleAudioAttachment(cAttachment, pMessage, msg) {
         audioContainer.appendChild(transcribeContainer);
         audioContainer.appendChild(transcriptionResult);
 
-        // Auto-transcribe if enabled and this is a recent received message
-        if (window.voiceSettings?.autoTranscribe &&
-            !msg.mine &&
-            msg.at > (Date.now() - 60)) {
-
-            const selectedModel = window.voiceSettings?.selectedModel || 'small';
-            const currentModel = window.voiceSettings.models?.find(m => m.model.name === selectedModel);
-            if (currentModel?.downloaded) {
-                transcribeBtn.click();
+        // Show a stored transcript (auto-transcribed after download) without another run
+        const showTranscript = (transcript, fAnimate) => {
+            transcriptionResult.innerHTML = '';
+            const transcriptionUI = createTranscriptionUI(transcript, seekTarget);
+            transcriptionResult.appendChild(transcriptionUI);
+            transcribeIcon.classList.replace('icon-file-plus', 'icon-file-minus');
+            if (fAnimate) {
+                slideTranscription(transcriptionResult, true, () => {
+                    animateTranscriptionIn(transcriptionUI);
+                });
+            } else {
+                transcriptionResult.classList.remove('hidden');
             }
+        };
+        if (cAttachment.transcript) {
+            showTranscript(cAttachment.transcript, false);
         }
+        // Lets the `transcription_ready` listener fill in this player when a transcript lands
+        audioContainer.dataset.attachmentId = cAttachment.id;
+        audioContainer._showTranscript = (transcript) => {
+            if (!transcriptionResult.textContent.trim()) showTranscript(transcript, true);
+        };
     }
 
     pMessage.appendChild(audioContainer);
@@ -1989,6 +2033,7 @@ function handleAudioAttachment(cAttachment, pMessage, msg) {
                     if (audioEndedUnlisten) audioEndedUnlisten();
                     if (audioWaveformUnlisten) audioWaveformUnlisten();
                     if (audioDurationUnlisten) audioDurationUnlisten();
+                    if (audioStreamFailedUnlisten) audioStreamFailedUnlisten();
                     resizeObserver.disconnect();
                     cleanupObserver.disconnect();
                     return;
@@ -22,6 +22,14 @@ function getName(profileOrId) {
     return p?.nickname || p?.name || p?.display_name || (id ? id.substring(0, 12) + '…' : 'Someone');
 }
 
+/** A profile's live status as one line ("🎧 deep work"), or '' when unset or already expired
+ *  (the backend clears lapsed statuses too, this just covers the gap until it fires). */
+function getStatusText(profile) {
+    const s = profile?.status;
+    if (!s || (s.expires_at && s.expires_at * 1000 <= Date.now())) return '';
+    return [s.emoji, s.title].filter(Boolean).join(' ').trim();
+}
+
 /** Resolve a system-event actor's display name from cached profiles; npub-prefix fallback. */
 function systemEventName(npub) {
     return getName(npub);
@@ -666,8 +674,13 @@ const domTheme = document.getElementById('theme');
 
 const domLoginStart = document.getElementById('login-start');
 const domLoginAccountCreationBtn = document.getElementById('start-account-creation-btn');
+const domLoginAccountPassphraseBtn = document.getElementById('start-account-passphrase-btn');
+const domLoginPassphraseBtn = document.getElementById('login-passphrase-btn');
+const domLoginPassphraseLabel = document.getElementById('login-passphrase-label');
 const domLoginAccountBtn = document.getElementById('start-login-btn');
 const domLoginBunkerStartBtn = document.getElementById('start-bunker-btn');
+const domLoginSocialRecoveryBtn = document.getElementById('start-social-recovery-btn');
+const domLoginBundleRestoreBtn = document.getElementById('start-bundle-restore-btn');
 const domLoginNip55StartBtn = document.getElementById('start-nip55-btn');
 const domLogin = document.getElementById('login-form');
 const domLoginImport = document.getElementById('login-import');
@@ -825,6 +838,108 @@ async function startBunkerSession() {
     }
 }
 
+/** Login screen: restore an account bundle exported on another device, then switch to it. */
+async function startBundleRestore() {
+    const path = await window.__TAURI__.dialog.open({ multiple: false, directory: false, filters: [{ name: 'Vector Account Bundle', extensions: ['vbundle'] }] });
+    if (!path) return;
+    const password = await popupPassword('Restore Account', 'Enter the password this bundle was exported with.');
+    if (!password) return;
+
+    let summary;
+    try {
+        summary = await invoke('import_account_bundle', { path, password });
+    } catch (e) {
+        return popupConfirm('Restore Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+    const missing = summary.downloads - summary.downloads_relinked;
+    const downloads = missing > 0
+        ? `<br><br>${missing} downloaded file(s) weren't found in this device's Vector downloads folder; you can download them again from their chats.`
+        : '';
+    await popupConfirm('Account Restored', `Your account was restored. Unlock it with its usual PIN or password.${downloads}`, true);
+    try {
+        await multiAccount.setActiveAndSwap(summary.npub);
+    } catch (e) {
+        await popupConfirm('Restore Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+}
+
+/**
+ * Recover an account from its social backup: ask the guardians for their shares from a throwaway
+ * key, show the code they must confirm with us, and once enough shares arrived log in with the
+ * rebuilt seed phrase — which the backend only hands back if it derives the npub entered here.
+ */
+async function startSocialRecovery() {
+    const npub = await popupConfirm('Recover with Guardians', 'Enter the npub of the account you want to recover.', false, 'npub1...');
+    if (!npub) return;
+    const strGuardians = await popupConfirm('Your Guardians', 'Enter the npubs of the guardians you chose when creating the backup, separated by commas or spaces.', false, 'npub1..., npub1...');
+    if (!strGuardians) return;
+
+    let code;
+    try {
+        code = await invoke('start_social_recovery', { npub: npub.trim(), guardians: strGuardians.split(/[\s,]+/).filter(Boolean) });
+    } catch (e) {
+        return popupConfirm('Recovery Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+
+    // Wait for the guardians to approve; each Check re-reads the progress.
+    while (true) {
+        const status = await invoke('get_social_recovery_status');
+        if (status.threshold && status.collected >= status.threshold) break;
+        const progress = status.threshold
+            ? `${status.collected} of ${status.threshold} shares received.`
+            : 'No shares received yet.';
+        const fWait = await popupConfirm('Waiting for Guardians',
+            `Contact each guardian by phone or in person and tell them this code:<br><br><b style="font-family: monospace; font-size: 22px;">${code}</b><br><br>They will only release their share if the code matches. ${progress}`,
+            false, '', 'key.svg', '', 'Check');
+        if (!fWait) {
+            await invoke('cancel_social_recovery');
+            return;
+        }
+    }
+
+    // A seed phrase created with a passphrase derives a different account without it, so a
+    // mismatch asks for one; the backend keeps the shares until it matches or the user gives up.
+    let passphrase = null;
+    while (true) {
+        try {
+            domLoginInput.value = await invoke('finish_social_recovery', { passphrase });
+            break;
+        } catch (e) {
+            const fMismatch = String(e).includes('different account');
+            const strRetry = fMismatch && await popupSecret('Seed Passphrase',
+                'The recovered seed phrase doesn\'t open this account' + (passphrase ? ' with that passphrase' : ' on its own') + '. If it was created with a passphrase ("25th word"), enter it to continue.',
+                'Passphrase...');
+            if (!strRetry) {
+                await invoke('cancel_social_recovery');
+                return popupConfirm('Recovery Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+            }
+            passphrase = strRetry;
+        }
+    }
+    // Login derives the key the same way, so it opens the account that was just checked.
+    strLoginPassphrase = passphrase || '';
+    domLoginBtn.click();
+}
+
+/**
+ * Optional BIP-39 passphrase for the seed phrase being imported. Held only until login; the
+ * backend consumes it to derive the key and never stores it.
+ */
+let strLoginPassphrase = '';
+
+/**
+ * Prompt for a secret with the popup's input masked.
+ * @returns {Promise<string|false>}
+ */
+async function popupSecret(strTitle, strSubtext, strPlaceholder) {
+    domPopupInput.type = 'password';
+    try {
+        return await popupConfirm(strTitle, strSubtext, false, strPlaceholder);
+    } finally {
+        domPopupInput.type = 'text';
+    }
+}
+
 /** Tracks which main-app panel was visible when the bunker form took over
  *  (reauth from Settings, etc.) so the back button can restore it. Null
  *  when entering from the login screen. */
@@ -928,6 +1043,7 @@ let domProfileBanner = document.getElementById('profile-banner');
 let domProfileAvatar = document.getElementById('profile-avatar');
 const domProfileNameSecondary = document.getElementById('profile-secondary-name');
 const domProfileStatusSecondary = document.getElementById('profile-secondary-status');
+const domProfileLastActive = document.getElementById('profile-last-active');
 const domProfileBadgeInvite = document.getElementById('profile-badge-invites');
 const domProfileBadgeFawkes = document.getElementById('profile-badge-fawkes');
 const domProfileBadgeBugHunter = document.getElementById('profile-badge-bughunter');
@@ -940,6 +1056,11 @@ const domProfileOptionShare = document.getElementById('profile-option-share');
 const domProfileOptionMore = document.getElementById('profile-option-more');
 const domProfileMoreDropdown = document.getElementById('profile-more-dropdown');
 const domProfileOptionNickname = document.getElementById('profile-option-nickname');
+const domProfileOptionContact = document.getElementById('profile-option-contact');
+const domProfileOptionVerify = document.getElementById('profile-option-verify');
+const domProfileOptionRelays = document.getElementById('profile-option-relays');
+const domProfileOptionZap = document.getElementById('profile-option-zap');
+const domProfileZaps = document.getElementById('profile-zaps');
 const domProfileOptionBlock = document.getElementById('profile-option-block');
 const domProfileId = document.getElementById('profile-id');
 
@@ -1130,11 +1251,14 @@ const domSettingsWhisperAutoTranscribeInfo = document.getElementById('whisper-au
 const domSettingsPrivacyWebPreviewsInfo = document.getElementById('privacy-web-previews-info');
 const domSettingsPrivacyStripTrackingInfo = document.getElementById('privacy-strip-tracking-info');
 const domSettingsPrivacySendTypingInfo = document.getElementById('privacy-send-typing-info');
+const domSettingsPrivacyLastActiveInfo = document.getElementById('privacy-last-active-info');
+const domSettingsPrivacyPresenceInfo = document.getElementById('privacy-presence-info');
 const domSettingsPrivacyTorInfo = document.getElementById('privacy-tor-info');
 const domSettingsDisplayImageTypesInfo = document.getElementById('display-image-types-info');
 const domSettingsChatBgInfo = document.getElementById('chat-bg-info');
 const domSettingsNotifMuteInfo = document.getElementById('notif-mute-info');
 const domSettingsNotifMuteEveryoneInfo = document.getElementById('notif-mute-everyone-info');
+const domSettingsNotifAnnouncementsInfo = document.getElementById('notif-announcements-info');
 const domSettingsNotifPrivacyInfo = document.getElementById('notif-privacy-info');
 const domSettingsStorageGalleryInfo = document.getElementById('storage-gallery-info');
 const domSettingsExportAccountInfo = document.getElementById('export-account-info');
@@ -1143,6 +1267,14 @@ const domSettingsChangePinLabel = document.getElementById('change-pin-label');
 const domSettingsLogoutInfo = document.getElementById('logout-info');
 const domSettingsLogout = document.getElementById('logout-btn');
 const domSettingsExport = document.getElementById('export-account-btn');
+const domSettingsSocialBackupInfo = document.getElementById('social-backup-info');
+const domSettingsSocialBackup = document.getElementById('social-backup-btn');
+const domSettingsExportBundleInfo = document.getElementById('export-bundle-info');
+const domSettingsKeyAuditInfo = document.getElementById('key-audit-info');
+const domSettingsKeyAudit = document.getElementById('key-audit-btn');
+const domSettingsActivityReportInfo = document.getElementById('activity-report-info');
+const domSettingsActivityReport = document.getElementById('activity-report-btn');
+const domSettingsExportBundle = document.getElementById('export-bundle-btn');
 const domRemoteSignerReauthBtn = document.getElementById('remote-signer-reauth-btn');
 
 const domApp = document.getElementById('popup-container');
@@ -1253,6 +1385,18 @@ function toggleAttachmentPanel() {
             }
         }
 
+        // Location: DMs only
+        const domAttachmentPanelLocation = document.getElementById('attachment-panel-location');
+        if (domAttachmentPanelLocation) {
+            domAttachmentPanelLocation.style.display = canShareLocation(strOpenChat) ? '' : 'none';
+        }
+
+        // Ecash: DMs only
+        const domAttachmentPanelEcash = document.getElementById('attachment-panel-ecash');
+        if (domAttachmentPanelEcash) {
+            domAttachmentPanelEcash.style.display = canUseEcash(strOpenChat) ? '' : 'none';
+        }
+
         // Animate items when panel opens
         animateAttachmentPanelItems(domAttachmentPanelMain);
     } else {
@@ -1562,6 +1706,25 @@ let arrChats = [];
  */
 let arrCommunityInvites = [];
 
+/**
+ * Enriched invite previews (owner, logo, activity) by community id, fetched once per
+ * invite load so chatlist re-renders don't re-warm the community's relays.
+ * @type {Map<string, Promise<object|null>>}
+ */
+const communityInvitePreviews = new Map();
+
+/**
+ * The enriched preview for a pending invite (see `preview_community_invite`).
+ * @param {string} communityId
+ * @returns {Promise<object|null>} null if it couldn't be built
+ */
+function getCommunityInvitePreview(communityId) {
+    if (!communityInvitePreviews.has(communityId)) {
+        communityInvitePreviews.set(communityId, invoke('preview_community_invite', { communityId }).catch(() => null));
+    }
+    return communityInvitePreviews.get(communityId);
+}
+
 /**
  * The current open chat (by npub)
  */
@@ -1865,6 +2028,25 @@ function getProfile(npub) {
     return arrProfiles.find(p => p.id === npub);
 }
 
+/**
+ * Fill in the heavy fields (about, banner, website) of a profile that arrived in its light boot
+ * form. A no-op for profiles that are already complete.
+ * @param {string} npub - The user's npub
+ * @returns {Promise<Profile|undefined>} - The (now complete) profile if it exists
+ */
+async function ensureProfileDetails(npub) {
+    const profile = getProfile(npub);
+    if (!profile?.details_deferred) return profile;
+    const full = await invoke('get_profile_details', { npub }).catch(() => null);
+    // A profile_update may have replaced the entry with a full one while we waited
+    const current = getProfile(npub);
+    if (full && current?.details_deferred) {
+        Object.assign(current, full);
+        delete current.details_deferred;
+    }
+    return current;
+}
+
 /**
  * Get the avatar src for a profile: the backend-cached local file, or null
  * (placeholder) while the cache is empty.
@@ -2042,6 +2224,10 @@ async function loadCommunityInvites() {
             } catch (_) {}
             return { community_id: inv.community_id, name, inviter_npub: inv.inviter_npub, channels, icon };
         });
+        // Drop previews of invites that are gone; the rest stay cached
+        for (const id of communityInvitePreviews.keys()) {
+            if (!arrCommunityInvites.some(i => i.community_id === id)) communityInvitePreviews.delete(id);
+        }
         updateChatBackNotification();
     } catch (e) {
         console.error('Failed to load community invites:', e);
@@ -2613,6 +2799,21 @@ async function refreshCommunityMemberCount(communityId, force = false) {
     }
 }
 
+let _communityOverviewRepaintTimer = null;
+
+/**
+ * Re-render the open Community overview once member profiles stop arriving: names backfill in a
+ * burst right after the member list opens, and each `profile_update` would otherwise repaint it.
+ */
+function scheduleCommunityOverviewRepaint(communityId) {
+    clearTimeout(_communityOverviewRepaintTimer);
+    _communityOverviewRepaintTimer = setTimeout(() => {
+        if (domGroupOverview.style.display === 'none' || domGroupOverview.getAttribute('data-group-id') !== communityId) return;
+        const chat = arrChats.find(c => c.metadata?.custom_fields?.community_id === communityId);
+        if (chat) renderCommunityOverview(chat, true);
+    }, 500);
+}
+
 function updateChatHeaderSubtext(chat) {
     if (!chat) return;
 
@@ -2649,7 +2850,7 @@ function updateChatHeaderSubtext(chat) {
     } else {
         // DM - not typing, show profile status
         const profile = getProfile(chat.id);
-        newStatusText = profile?.status?.title || '';
+        newStatusText = getStatusText(profile);
         shouldAddGradient = false;
     }
     
@@ -2833,28 +3034,68 @@ async function leaveCommunityFromList(chat) {
 }
 
 /**
- * Per-chat unread badges are sourced from the DB (`chat.unread`), not by walking in-memory
+ * Soft-delete a DM from the chat list. The chat stays restorable from Settings → Privacy →
+ * Recently Deleted for 30 days (see `restoreDeletedChat`).
+ */
+async function deleteChatFromList(chat) {
+    const confirmed = await popupConfirm('Delete Chat', `Delete your chat with <b>${escapeHtml(getName(chat.id))}</b>? You can restore it from Settings for 30 days.`, false, '', 'vector_warning.svg');
+    if (!confirmed) return;
+    try {
+        await invoke('delete_chat', { chatId: chat.id });
+        if (chat.id === strOpenChat) await closeChat();
+        arrChats = arrChats.filter(c => c.id !== chat.id);
+        eventCache.clearConversation(chat.id);
+        renderChatlist();
+        showToast('Chat deleted');
+    } catch (e) {
+        await popupConfirm('Failed to Delete', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+}
+
+/**
+ * Undo a chat deletion: the backend re-lists it and hands back the chat (with its newest message)
+ * for the chat list.
+ * @param {string} chatId
+ */
+async function restoreDeletedChat(chatId) {
+    const chat = await invoke('restore_chat', { chatId });
+    arrChats = arrChats.filter(c => c.id !== chat.id);
+    arrChats.push(chat);
+    await refreshUnreadCounts();
+    renderChatlist();
+}
+
+/**
+ * Per-chat unread badges are sourced from the backend (`chat.unread`), not by walking in-memory
  * messages — so they're correct even after a restart, when only the last message per chat is in
- * RAM. This fetches the authoritative counts and updates every chat. Awaited at boot; elsewhere use
- * the debounced `scheduleUnreadRefresh` so a burst of arrivals coalesces into one query.
+ * RAM. This fetches every chat's unread summary (count, unread mentions, oldest unread message)
+ * from the backend's in-RAM cache and updates every chat. Awaited at boot; elsewhere use the
+ * debounced `scheduleUnreadRefresh` so a burst of arrivals coalesces into one call.
  */
 async function refreshUnreadCounts() {
-    let counts;
+    let summary;
     try {
-        counts = await invoke('get_unread_counts');
+        summary = await invoke('get_unread_summary');
     } catch (e) {
         return; // keep prior chat.unread on failure
     }
     let changed = false;
     for (const chat of arrChats) {
-        const n = counts[chat.id] || 0;
+        const entry = summary[chat.id];
+        const n = entry?.count || 0;
         if (chat.unread !== n) { chat.unread = n; changed = true; }
+        // Unread pings of us (by npub or @everyone) — flags the row's count pill with an "@".
+        const m = entry?.mentions || 0;
+        if (chat.unread_mentions !== m) { chat.unread_mentions = m; changed = true; }
+        chat.oldest_unread = entry?.oldest_unread || null;
     }
     if (changed) {
         // A chat is open → the chatlist is hidden, so refresh the in-chat back-chevron unread dot
         // (it reads the chat.unread we just updated); otherwise refresh the visible rows.
         if (strOpenChat) updateChatBackNotification();
         else renderChatlist();
+        // Folder chips carry their own unread counts
+        if (arrChatFolders.length) loadChatFolders();
     }
 }
 
@@ -2911,6 +3152,34 @@ function reactToMessageRouted(referenceId, chatId, emoji, emojiUrl) {
     return invoke('react_to_message', args);
 }
 
+/**
+ * Send several files as one gallery message (confirmed + optionally captioned first — there's no per-file preview)
+ * @param {string} pubkey - The user's pubkey or Community channel id
+ * @param {string?} replied_to - The reference of the message, if any (Communities only)
+ * @param {string[]} filepaths - The absolute file paths
+ */
+async function sendFiles(pubkey, replied_to, filepaths) {
+    const MAX_FILES = 10;
+    if (filepaths.length > MAX_FILES) {
+        return popupConfirm('Too Many Files', `You can send up to ${MAX_FILES} files in one message.`, true, '', 'vector_warning.svg');
+    }
+    const names = filepaths.map(p => escapeHtml(getFileName(p))).join('<br>');
+    const caption = await popupConfirm(`Send ${filepaths.length} Files?`, names, false, 'Add a caption...', '', '', 'Send');
+    if (caption === false) return;
+    try {
+        // One batch: validated up front, one pending message, one progress bar, one cancel.
+        const result = await invoke('send_files', { chatId: pubkey, filePaths: filepaths, caption, useCompression: mediaSettings.compress_by_default, keepMetadata: false, repliedTo: replied_to || null });
+        if (result && result.event_id) {
+            finalizePendingMessage(pubkey, result.pending_id, result.event_id);
+        }
+    } catch (e) {
+        if (e && e.toString().includes('Upload cancelled')) { nLastTypingIndicator = 0; return; }
+        const { title, body } = humanizeUploadError(String(e));
+        popupConfirm(title, body, true, '', 'vector_warning.svg');
+    }
+    nLastTypingIndicator = 0;
+}
+
 /**
  * Send a file via NIP-96 server to a Nostr user or group
  * @param {string} pubkey - The user's pubkey or group_id
@@ -2924,10 +3193,10 @@ async function sendFile(pubkey, replied_to, filepath) {
         // no pending id to finalize here (mirrors send_community_message).
         const chat = arrChats.find(c => c.id === pubkey);
         if (chat && chat.chat_type === 'Community') {
-            await invoke('send_community_files', { channelId: pubkey, content: '', filePaths: [filepath], nameOverrides: [''], useCompression: false, keepMetadata: false, repliedTo: replied_to || '' });
+            await invoke('send_community_files', { channelId: pubkey, content: '', filePaths: [filepath], nameOverrides: [''], useCompression: mediaSettings.compress_by_default, keepMetadata: false, repliedTo: replied_to || '' });
         } else {
             // DMs use the protocol-agnostic file_message command.
-            const result = await invoke("file_message", { receiver: pubkey, repliedTo: replied_to, filePath: filepath, keepMetadata: false, nameOverride: '' });
+            const result = await invoke("file_message", { receiver: pubkey, repliedTo: replied_to, filePath: filepath, useCompression: mediaSettings.compress_by_default, keepMetadata: false, nameOverride: '' });
             if (result && result.event_id) {
                 finalizePendingMessage(pubkey, result.pending_id, result.event_id);
             }
@@ -3029,6 +3298,16 @@ async function setupRustListeners() {
         await removeCommunityFromUI(communityId);
     });
 
+    // Another device changed synced settings (theme, privacy, notifications, blocks);
+    // the backend already persisted them — re-read so they apply live.
+    _on('settings_synced', (evt) => applySyncedSettings(evt.payload || []));
+
+    // A contact is trying to recover their account and asked for the backup share we hold.
+    _on('social_backup_request', (evt) => reviewBackupRequest(evt.payload));
+    invoke('get_backup_guardianship').then(async ([, requests]) => {
+        for (const req of requests) await reviewBackupRequest(req);
+    }).catch(() => {});
+
     // A pack's health verdict changed (revoked / missing / revived) or a pack
     // was deleted. Reload the local mirror so the picker greys out or revives
     // the section live, instead of waiting for the next panel open.
@@ -3038,6 +3317,22 @@ async function setupRustListeners() {
     // panel so an already-open list reflects them without a reopen.
     _on('relay_list_updated', () => renderRelayList());
 
+    // The boot check found our DM relay list missing or unusable where senders look for it:
+    // DMs and Community invites may not reach us until it's fixed
+    _on('inbox_list_health', (evt) => {
+        const { status, relays } = evt.payload;
+        if (status === 'missing' || status === 'unusable') {
+            popupConfirm('You May Be Unreachable',
+                'Other users look up your <b>DM Relay List</b> to message or invite you, and ' +
+                (status === 'missing' ? 'none of your relays have it.' : 'yours lists no usable relays.') +
+                '<br><br>Open <b>Settings → Network</b> and make sure at least one relay is enabled; Vector republishes the list automatically.',
+                true, '', 'vector_warning.svg');
+        } else if (status === 'degraded') {
+            const behind = relays.filter(r => r.state === 'missing' || r.state === 'stale').length;
+            showToast(`${behind} relay${behind === 1 ? '' : 's'} couldn't store your DM Relay List`);
+        }
+    });
+
     // A control change (banlist / roles / metadata / invite-mode) landed in REALTIME (via the 3308
     // control-plane subscription). Re-read this community's summary into the chat list + re-render the
     // overview if it's open, so online members see name/role/mode changes live, not just on next open.
@@ -3231,6 +3526,29 @@ async function setupRustListeners() {
         }, 300);
     });
 
+    // A large message backlog waits for a go-ahead before it's fetched
+    _on('history_sync_consent', async (evt) => {
+        const token = await popupConsent(evt.payload, 'Sync Message History?',
+            'Your relays hold older messages this device has never downloaded.');
+        try {
+            if (token === false) await invoke('skip_history_sync');
+            else await invoke('resume_history_sync', { token });
+        } catch (e) {
+            console.warn('History sync:', e);
+        }
+    });
+
+    // A profile is using a verified contact's name or NIP-05 under a different key
+    _on('verification_warning', (evt) => {
+        const { npub, verified_npub, matched } = evt.payload;
+        const verified = arrProfiles.find(p => p.id === verified_npub);
+        const name = verified ? (verified.nickname || verified.name || verified_npub) : verified_npub;
+        const what = { name: 'name', display_name: 'display name', nip05: 'NIP-05 address' }[matched] || matched;
+        popupConfirm('Possible Impersonation',
+            `<b>${escapeHtml(npub.slice(0, 16))}…</b> uses the same ${what} as your verified contact <b>${escapeHtml(name)}</b>, but a different key.<br><br>If your contact says they changed accounts, verify the new one with them before trusting it.`,
+            true, '', 'vector_warning.svg');
+    });
+
     // Listen for Synchronisation Progress updates
     _on('sync_progress', (evt) => {
         if (fInit) return;
@@ -3511,6 +3829,17 @@ async function setupRustListeners() {
         }
     });
 
+    // A downloaded voice message was auto-transcribed: keep the transcript and show it in its player
+    _on('transcription_ready', (evt) => {
+        const { chat_id, msg_id, attachment_id, transcript } = evt.payload;
+        const cMsg = getChat(chat_id)?.messages.find(m => m.id === msg_id);
+        const cAttachment = cMsg?.attachments.find(a => a.id === attachment_id);
+        if (cAttachment) cAttachment.transcript = transcript;
+        if (strOpenChat !== chat_id) return;
+        const player = document.querySelector(`.audio-message-container[data-attachment-id="${CSS.escape(attachment_id)}"]`);
+        player?._showTranscript?.(transcript);
+    });
+
     // Listen for profile updates
     _on('profile_update', (evt) => {
         // Check if the frontend is already aware
@@ -3648,6 +3977,13 @@ async function setupRustListeners() {
             activeInviteModalRerender();
         }
 
+        // A member of the open Community overview resolved: swap their npub stub for the name
+        const overviewCommunityId = domGroupOverview.getAttribute('data-group-id');
+        if (overviewCommunityId && domGroupOverview.style.display !== 'none' &&
+            (communityMembersCache.get(overviewCommunityId) || []).some(m => m.npub === evt.payload.id)) {
+            scheduleCommunityOverviewRepaint(overviewCommunityId);
+        }
+
         // Upgrade any message-less community whose preview shows THIS npub's join from the npub stub
         // to the resolved name. The group row's state hash doesn't track the join actor, so renderChatlist
         // alone wouldn't repaint it — patch the row directly.
@@ -3700,6 +4036,10 @@ async function setupRustListeners() {
     // PIVX payment events — handler in pivx.js
     _on('pivx_payment_received', handlePivxPaymentReceived);
 
+    // Ecash token events — handlers in ecash.js
+    _on('ecash_received', handleEcashReceived);
+    _on('ecash_redeemed', handleEcashRedeemed);
+
     // Listen for typing indicator updates (both DMs and Groups)
     _on('typing-update', (evt) => {
         const { conversation_id, typers } = evt.payload;
@@ -3869,7 +4209,7 @@ async function setupRustListeners() {
             // the chat is open and pinned. Only when the row actually rendered at
             // the tail (the user saw it) — never on a data-only/frozen path.
             if (!newMessage.mine && rendered && tailAppend && chatPinnedToBottom && isWindowActive()) {
-                markAsRead(chat, newMessage);
+                if (readsInstantly()) markAsRead(chat, newMessage);
                 clearUnreadDivider();
             }
             // Own-send catches up to the latest non-mine message AT OR BEFORE this send
@@ -3897,6 +4237,11 @@ async function setupRustListeners() {
         updateChatBackNotification();
     });
 
+    // A DM file send whose uploads gave up: keep its token so Retry resumes the same message
+    _on('upload_failed', (evt) => {
+        failedUploadTokens.set(evt.payload.message_id, evt.payload.retry_token);
+    });
+
     // Listen for existing message updates (works for both DMs and MLS groups)
     _on('message_update', (evt) => {
         // Drop any buffered upload progress + speed tracker for this pending id (the upload finished
@@ -4156,10 +4501,31 @@ async function setupRustListeners() {
             // is mid-decision and we already swapped the layer to the
             // staged file. The next openChat will hydrate from chat.* fields.
             if (wallpaperPreviewState && wallpaperPreviewState.chatId === chat_id) return;
+            // Our own override keeps showing over the shared wallpaper
+            if (objChatWallpaperOverrides[chat_id]) return;
             applyChatWallpaper(chat_id, path || '', blur, dim, ts);
         }
     });
 
+    // An accent color (`accent_color`) or wallpaper override (`wallpaper`) changed: set here, or on
+    // another of our devices. Only the keys present changed.
+    _on('chat_appearance_changed', (evt) => {
+        const payload = evt.payload || {};
+        const { chat_id } = payload;
+        if (!chat_id) return;
+        if ('accent_color' in payload) {
+            if (payload.accent_color) objChatAccents[chat_id] = payload.accent_color;
+            else delete objChatAccents[chat_id];
+            if (strOpenChat === chat_id) applyChatAccent();
+        }
+        if ('wallpaper' in payload) {
+            if (payload.wallpaper) objChatWallpaperOverrides[chat_id] = payload.wallpaper;
+            else delete objChatWallpaperOverrides[chat_id];
+            const previewing = wallpaperPreviewState && wallpaperPreviewState.chatId === chat_id;
+            if (strOpenChat === chat_id && !previewing) refreshChatWallpaper();
+        }
+    });
+
     _on('attachment_update', (evt) => {
         const { chat_id, message_id, attachment_id, url } = evt.payload;
         const cChat = getChat(chat_id);
@@ -4210,6 +4576,10 @@ async function setupRustListeners() {
         if (typeof renderRelayList === 'function') renderRelayList();
     });
 
+    _on('blossom_health_updated', () => {
+        if (typeof renderRelayList === 'function') renderRelayList();
+    });
+
     _on('blossom_capabilities_updated', () => {
         if (currentBlossomInfo) {
             renderBlossomCapabilities(currentBlossomInfo.url, ++_blossomCapsToken);
@@ -4308,8 +4678,17 @@ async function executeDeepLinkAction(payload) {
         // Open the profile view
         await openProfile(profile);
     } else if (action_type === 'chat') {
-        // Open a specific chat (triggered by tapping a notification)
+        // Open a specific chat (a notification tap, a vector://chat link, or a nostr: profile URI).
+        // DMs open even when new; a channel link only works for Communities we're in.
+        if (!target.startsWith('npub1') && !getChat(target)) {
+            await popupConfirm('Chat Not Found', 'This link points to a Community channel you aren\'t a member of.', true, '', 'vector_warning.svg');
+            return;
+        }
         await openChat(target);
+    } else if (action_type === 'chat_reply') {
+        // "Reply" on a desktop notification whose OS can't take text: open the chat, ready to type
+        await openChat(target);
+        domChatMessageInput.focus();
     } else if (action_type === 'emoji_pack') {
         // Open the Pack Details modal for the given naddr. The modal
         // owns the fetch, render, and subscribe/unsubscribe flow; we
@@ -4317,6 +4696,35 @@ async function executeDeepLinkAction(payload) {
         if (typeof openPackDetailsModal === 'function') {
             await openPackDetailsModal(target);
         }
+    } else if (action_type === 'verify') {
+        // A scanned contact verification QR. A link can come from anywhere, so a match is only
+        // shown: the user compares the numbers with their contact and confirms before it's recorded.
+        let scan;
+        try {
+            scan = await invoke('verify_contact_qr', { payload: target });
+        } catch (e) {
+            return popupConfirm('Verify Contact', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+        }
+        if (!scan.matches) {
+            return popupConfirm('Numbers Don\'t Match', 'This code doesn\'t match your safety number with its sender. You may be talking to someone else: check the npub with them through another channel.', true, '', 'vector_warning.svg');
+        }
+        const profile = arrProfiles.find(p => p.id === scan.npub);
+        const name = profile ? (profile.nickname || profile.name || scan.npub) : scan.npub;
+        if (scan.verified) {
+            return popupConfirm('Contact Verified', `You've already verified <b>${escapeHtml(name)}</b>.`, true, '', 'check-circle.svg');
+        }
+        const groups = scan.safety_number.split(' ');
+        const digits = [0, 4, 8].map(i => groups.slice(i, i + 4).join(' ')).join('<br>');
+        const html = `The code matches your safety number with <b>${escapeHtml(name)}</b>:<br><br>
+            <code style="font-size: 16px; letter-spacing: 1px;">${digits}</code><br><br>
+            Check that your contact's screen shows these same numbers before marking them verified.`;
+        if (!await popupConfirm('Verify Contact', html, false, '', 'check-circle.svg', '', 'Mark as Verified')) return;
+        try {
+            await invoke('mark_contact_verified', { npub: scan.npub, verified: true });
+            showToast('Contact Verified');
+        } catch (e) {
+            popupConfirm('Verify Contact', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+        }
     } else if (action_type === 'community_invite') {
         // Invite link (vector://invite#… or vectorapp.io/invite#…) — `target` is the full URL;
         // the join flow re-parses its fragment, previews, and accepts on confirm.
@@ -4324,6 +4732,107 @@ async function executeDeepLinkAction(payload) {
     }
 }
 
+/** Whole sats from msat, grouped for display (e.g. "2,100"). */
+function formatSats(msat) {
+    return Math.floor((msat || 0) / 1000).toLocaleString();
+}
+
+/**
+ * Zap a user or a message (NIP-57): ask for an amount and an optional comment, then hand the
+ * invoice to the user's Lightning wallet, or copy it when no wallet app takes the link.
+ * @param {string} target - An npub, or a message id to zap its author
+ * @param {string} recipientNpub - Who receives the zap (for the prompt)
+ */
+async function zapFlow(target, recipientNpub) {
+    const profile = getProfile(recipientNpub);
+    const name = profile ? (profile.nickname || profile.name || 'this user') : 'this user';
+    const amount = await popupConfirm(`Zap ${name}`, 'How many sats? Zaps are public: anyone can see who zapped whom, and how much.', false, 'Amount (sats)');
+    if (amount === false) return;
+    const sats = parseInt(String(amount).replace(/[^0-9]/g, ''), 10);
+    if (!Number.isFinite(sats) || sats <= 0) {
+        return popupConfirm('Zap', 'Please enter a whole number of sats.', true, '', 'vector_warning.svg');
+    }
+    const comment = await popupConfirm('Add a Comment', 'Optional, and public like the zap itself.', false, 'Comment');
+    if (comment === false) return;
+    try {
+        const payment = await invoke('send_zap', { target, amountSats: sats, comment });
+        if (!payment.opened_wallet) {
+            await navigator.clipboard.writeText(payment.invoice);
+            await popupConfirm('Invoice Copied', 'No Lightning wallet app opened the zap, so its invoice was copied: paste it into your wallet to pay.', true);
+        }
+    } catch (e) {
+        popupConfirm('Zap Failed', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+}
+
+/**
+ * Show our safety number with a contact (digits + QR for them to scan) and let the user mark them
+ * verified, or clear an earlier verification.
+ * @param {string} npub - The contact's npub
+ */
+async function openContactVerification(npub) {
+    let code;
+    try {
+        code = await invoke('get_verification_code', { npub });
+    } catch (e) {
+        return popupConfirm('Verify Contact', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+    const groups = code.safety_number.split(' ');
+    const digits = [0, 4, 8].map(i => groups.slice(i, i + 4).join(' ')).join('<br>');
+    const status = code.verified ? '<br><br><b>You\'ve verified this contact.</b>' : '';
+    const html = `<div id="verify-qr" style="width: 160px; height: 160px; margin: 0 auto 12px; background: white; padding: 8px; border-radius: 8px;"></div>
+        <code style="font-size: 16px; letter-spacing: 1px;">${digits}</code><br><br>
+        Compare these numbers with your contact's screen, or have them scan the code with their camera. If they match, your chat is end-to-end with them and nobody else.${status}`;
+    const popup = popupConfirm('Verify Contact', html, false, '', 'check-circle.svg', '', code.verified ? 'Clear Verification' : 'Mark as Verified');
+    renderQrInto(document.getElementById('verify-qr'), code.qr_payload);
+    if (!await popup) return;
+    try {
+        await invoke('mark_contact_verified', { npub: code.npub, verified: !code.verified });
+        showToast(code.verified ? 'Verification Cleared' : 'Contact Verified');
+    } catch (e) {
+        popupConfirm('Verify Contact', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+    }
+}
+
+/**
+ * Choose where messages (and typing indicators) to a contact are published: automatically from
+ * their advertised relays, Vector's trusted relays only, or a list of the user's own.
+ * @param {string} npub - The contact's npub
+ * @param {number} x - Menu position
+ * @param {number} y - Menu position
+ */
+async function openContactRelaysMenu(npub, x, y) {
+    let current;
+    try { current = await invoke('get_contact_relays', { npub }); }
+    catch (_) { return; }
+    const save = async (relays) => {
+        try {
+            await invoke('set_contact_relays', { npub, relays });
+            showToast('Message Relays Updated');
+        } catch (e) {
+            popupConfirm('Message Relays', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+        }
+    };
+    const mark = (mode) => current.mode === mode ? '(current)' : '';
+    showContextMenu({ x, y, items: [
+        { label: 'Automatic', hint: mark('auto'), icon: 'refresh', onClick: () => save({ mode: 'auto' }) },
+        { label: 'Trusted relays only', hint: mark('trusted'), icon: 'locked', onClick: () => save({ mode: 'trusted' }) },
+        {
+            label: 'Custom relays…',
+            hint: mark('manual'),
+            icon: 'edit',
+            onClick: async () => {
+                const existing = current.mode === 'manual' ? current.relays.join(', ') : '';
+                const input = await popupConfirm('Custom Relays', `Messages to this contact will go to these relays only, whatever they advertise. Separate relays with commas.${existing ? `<br><br>Current: <code>${escapeHtml(existing)}</code>` : ''}`, false, 'wss://relay.example.com');
+                if (input === false) return;
+                const relays = input.split(/[\s,]+/).filter(Boolean);
+                if (relays.length === 0) return;
+                await save({ mode: 'manual', relays });
+            },
+        },
+    ] });
+}
+
 /**
  * A flag that indicates when the initial sync is complete
  * This is separate from fInit because sync continues after UI init
@@ -4465,7 +4974,11 @@ async function renderRelayList() {
             networkList.appendChild(relayItem);
         });
         
-        const blossomServers = await invoke('get_blossom_servers_config');
+        const [blossomServers, blossomHealth] = await Promise.all([
+            invoke('get_blossom_servers_config'),
+            getBlossomServerHealth(),
+        ]);
+        const healthByUrl = new Map(blossomHealth.servers.map(h => [h.url, h]));
 
         const mediaTitleContainer = document.createElement('div');
         mediaTitleContainer.className = 'relay-section-header';
@@ -4517,7 +5030,64 @@ async function renderRelayList() {
         mediaTitleContainer.appendChild(addMediaBtn);
         networkList.appendChild(mediaTitleContainer);
 
-        blossomServers.forEach(server => {
+        // Upload routing: fastest healthy server first, or strictly the list order below
+        const fastestItem = document.createElement('div');
+        fastestItem.className = 'relay-item';
+        const fastestLabel = document.createElement('div');
+        fastestLabel.className = 'relay-item-content';
+        const fastestText = document.createElement('span');
+        fastestText.className = 'relay-url';
+        fastestText.textContent = 'Upload to the fastest server first';
+        fastestLabel.appendChild(fastestText);
+        const fastestToggle = document.createElement('input');
+        fastestToggle.type = 'checkbox';
+        fastestToggle.className = 'relay-toggle';
+        fastestToggle.checked = blossomHealth.prefer_fastest;
+        fastestToggle.onchange = async (e) => {
+            try {
+                await setBlossomPreferFastest(e.target.checked);
+            } catch (err) {
+                console.error('Failed to set media server preference:', err);
+                e.target.checked = !e.target.checked;
+            }
+        };
+        fastestItem.appendChild(fastestLabel);
+        fastestItem.appendChild(fastestToggle);
+        networkList.appendChild(fastestItem);
+
+        // Mirroring: keep extra copies of each attachment on other servers
+        const mirrorItem = document.createElement('div');
+        mirrorItem.className = 'relay-item';
+        const mirrorLabel = document.createElement('div');
+        mirrorLabel.className = 'relay-item-content';
+        const mirrorText = document.createElement('span');
+        mirrorText.className = 'relay-url';
+        mirrorText.textContent = 'Mirror attachments to other servers';
+        mirrorLabel.appendChild(mirrorText);
+        const mirrorSelect = document.createElement('select');
+        mirrorSelect.className = 'relay-form-select';
+        mirrorSelect.style.width = 'auto';
+        mirrorSelect.style.marginBottom = '0';
+        for (let count = 0; count <= blossomHealth.max_mirrors; count++) {
+            const option = document.createElement('option');
+            option.value = String(count);
+            option.textContent = count === 0 ? 'Off' : `${count} ${count === 1 ? 'copy' : 'copies'}`;
+            mirrorSelect.appendChild(option);
+        }
+        mirrorSelect.value = String(blossomHealth.mirror_count);
+        mirrorSelect.onchange = async (e) => {
+            try {
+                await setBlossomMirrorCount(Number(e.target.value));
+            } catch (err) {
+                console.error('Failed to set media mirroring:', err);
+                e.target.value = String(blossomHealth.mirror_count);
+            }
+        };
+        mirrorItem.appendChild(mirrorLabel);
+        mirrorItem.appendChild(mirrorSelect);
+        networkList.appendChild(mirrorItem);
+
+        blossomServers.forEach((server, index) => {
             const serverItem = document.createElement('div');
             serverItem.className = 'relay-item media-server-item' + (server.enabled ? '' : ' disabled');
             serverItem.setAttribute('data-server-url', server.url);
@@ -4538,12 +5108,48 @@ async function renderRelayList() {
             serverUrlSpan.textContent = server.url.replace(/^https?:\/\//, '');
             serverContent.appendChild(serverUrlSpan);
 
+            // Health: down/failing servers are tried last; otherwise show the last check's latency
+            const health = healthByUrl.get(server.url);
             const statusBadge = document.createElement('span');
-            statusBadge.className = `relay-status ${server.enabled ? 'connected' : 'disabled'}`;
-            statusBadge.textContent = server.enabled ? 'active' : 'disabled';
+            if (!server.enabled) {
+                statusBadge.className = 'relay-status disabled';
+                statusBadge.textContent = 'disabled';
+            } else if (health && !health.healthy) {
+                statusBadge.className = 'relay-status disconnected';
+                statusBadge.textContent = health.reason === 'unreachable' ? 'down' : 'failing';
+            } else {
+                statusBadge.className = 'relay-status connected';
+                statusBadge.textContent = health?.latency_ms != null ? `${health.latency_ms} ms` : 'active';
+            }
+            if (health && (health.uploads_ok || health.uploads_failed)) {
+                statusBadge.title = `${health.uploads_ok} of ${health.uploads_ok + health.uploads_failed} recent uploads succeeded`;
+            }
+
+            const actionsContainer = document.createElement('div');
+            actionsContainer.className = 'relay-item-actions';
+            if (index > 0) {
+                const moveUp = document.createElement('span');
+                moveUp.className = 'icon icon-arrow-up btn';
+                moveUp.title = 'Move Up';
+                moveUp.style.width = '14px';
+                moveUp.style.height = '14px';
+                moveUp.onclick = async (e) => {
+                    e.stopPropagation();
+                    const urls = blossomServers.map(s => s.url);
+                    [urls[index - 1], urls[index]] = [urls[index], urls[index - 1]];
+                    try {
+                        await reorderBlossomServers(urls);
+                        renderRelayList();
+                    } catch (err) {
+                        popupConfirm('Could not reorder servers', escapeHtml(String(err)), true, '', 'vector_warning.svg');
+                    }
+                };
+                actionsContainer.appendChild(moveUp);
+            }
+            actionsContainer.appendChild(statusBadge);
 
             serverItem.appendChild(serverContent);
-            serverItem.appendChild(statusBadge);
+            serverItem.appendChild(actionsContainer);
             networkList.appendChild(serverItem);
         });
     } catch (error) {
@@ -4721,9 +5327,8 @@ async function openRelayInfoDialog(relay) {
     // Set static info (URL doesn't change)
     urlEl.textContent = relay.url.replace(/^wss?:\/\//, '');
 
-    // Set mode (only editable for custom relays)
+    // Set mode
     modeSelect.value = relay.mode || 'both';
-    modeSelect.disabled = relay.is_default;
 
     // Initial data load
     await refreshRelayInfoDialog();
@@ -4754,7 +5359,7 @@ function closeRelayInfoDialog() {
  * Handles mode change from the info dialog
  */
 async function handleRelayModeChange() {
-    if (!currentRelayInfo || currentRelayInfo.is_default) return;
+    if (!currentRelayInfo) return;
 
     const modeSelect = document.getElementById('relay-info-mode');
     const newMode = modeSelect.value;
@@ -5143,11 +5748,33 @@ async function login(skipAnimations = false) {
         });
 
 
+        // A cold start may paint from the backend's chat-list snapshot (`init_finished` with
+        // `preview: true`); the full load then lands here and replaces it.
+        const _initHydratedP = listen('init_hydrated', (evt) => {
+            applyAccessibilitySettings(evt.payload.accessibility);
+            arrProfiles = evt.payload.profiles || [];
+            const prior = new Map(arrChats.map(c => [c.id, c]));
+            arrChats = (evt.payload.chats || []).map(chat => {
+                // Keep any history the user already paged in while the snapshot was showing
+                const old = prior.get(chat.id);
+                if (old && old.messages.length > chat.messages.length) chat.messages = old.messages;
+                return chat;
+            });
+            refreshUnreadCounts();
+            resolveCommunityAvatars();
+            if (!fInit) {
+                renderCurrentProfile(arrProfiles.find(p => p.mine));
+                renderChatlist();
+            }
+        });
+
         // Setup a Rust Listener for the backend's init finish
         // (helper hoisted above this block — see runWithTorBootstrapStatus)
         const _initFinishedP = listen('init_finished', async (evt) => {
             console.timeEnd('[Boot] login() total');
             console.time('[Boot] init_finished handler');
+            // Before anything renders, so the first paint already has the user's text size
+            applyAccessibilitySettings(evt.payload.accessibility);
             // The backend now sends both profiles (without messages) and chats (with messages)
             arrProfiles = evt.payload.profiles || [];
             arrChats = evt.payload.chats || [];
@@ -5168,6 +5795,12 @@ async function login(skipAnimations = false) {
             // registers the theme with the send resolver and makes the first picker
             // open cheap (data + DOM already composed). Read-only/local + guarded.
             loadEmojiPacks();
+            // Contact groups and chat folders drive the chat list's filter chips.
+            loadContactGroups();
+            loadChatFolders();
+            loadChatAccents();
+            loadChatWallpaperOverrides();
+            loadRemoteContentPolicies();
             // Warm frecency too, so `:` autocomplete + the picker reflect ranked/recent use from the
             // first interaction, not only after the panel's first open (which is where it loaded before).
             loadEmojiUsage();
@@ -5253,6 +5886,8 @@ async function login(skipAnimations = false) {
                         } catch (e) {
                             console.error('[Battery] prompt error:', e);
                         }
+                        // Hand the gateway any UnifiedPush endpoint issued while Vector was closed
+                        if (platformFeatures.os === 'android') invoke('refresh_push_registration').catch(() => {});
                     }, 1500);
                 }
             };
@@ -5366,7 +6001,7 @@ async function login(skipAnimations = false) {
         });
 
         // Wait for connect + all listener registrations to complete
-        await Promise.all([_connectP, _listenersP, _progressP, _initFinishedP]);
+        await Promise.all([_connectP, _listenersP, _progressP, _initFinishedP, _initHydratedP]);
         console.timeEnd('[Boot] connect + listeners');
 
         // Load and Decrypt our database; fetching the full chat state from disk for immediate bootup
@@ -5398,12 +6033,26 @@ function renderCurrentProfile(cProfile) {
     if (cProfile?.nickname || cProfile?.name) twemojify(domAccountName);
 
     // Render our status
-    domAccountStatus.textContent = cProfile?.status?.title || 'Set a Status';
+    domAccountStatus.textContent = getStatusText(cProfile) || 'Set a Status';
     domAccountStatus.onclick = askForStatus;
     twemojify(domAccountStatus);
 
 }
 
+/**
+ * The verified marker shown beside a name — only ever set by the backend from
+ * the signed project registry.
+ * @param {{label: string}} verified - The profile's registry entry
+ */
+function createVerifiedIcon(verified) {
+    const verifiedIcon = document.createElement('span');
+    verifiedIcon.className = 'icon icon-check-circle profile-name-bot-icon';
+    const label = verified.label || 'Verified';
+    verifiedIcon.addEventListener('mouseenter', () => showGlobalTooltip(`Verified: ${label}`, verifiedIcon));
+    verifiedIcon.addEventListener('mouseleave', hideGlobalTooltip);
+    return verifiedIcon;
+}
+
 /**
  * Render the Profile tab based on a given profile
  * @param {Profile} cProfile 
@@ -5434,8 +6083,8 @@ function renderProfileTab(cProfile) {
     // Status
     const strStatusPlaceholder = cProfile.mine ? 'Set a Status' : '';
     // textContent: status is attacker-controlled NIP-38 data, never HTML.
-    domProfileStatus.textContent = cProfile?.status?.title || strStatusPlaceholder;
-    if (cProfile?.status?.title) twemojify(domProfileStatus);
+    domProfileStatus.textContent = getStatusText(cProfile) || strStatusPlaceholder;
+    if (getStatusText(cProfile)) twemojify(domProfileStatus);
 
     // Adjust our Profile Name class to manage space according to Status visibility
     domProfileName.classList.toggle('chat-contact', !domProfileStatus.textContent);
@@ -5510,11 +6159,25 @@ function renderProfileTab(cProfile) {
         botIcon.addEventListener('mouseleave', hideGlobalTooltip);
         domProfileNameSecondary.appendChild(botIcon);
     }
+    if (cProfile?.verified) domProfileNameSecondary.appendChild(createVerifiedIcon(cProfile.verified));
 
     // Secondary Status (innerHTML copy is safe: source was built from a text
     // node, so serialization is escaped; only twemoji markup carries over)
     domProfileStatusSecondary.innerHTML = domProfileStatus.innerHTML;
 
+    // Live presence (only from contacts who share it) beats the approximate "last active"
+    // (derived from their public events, never our chats).
+    const lastActiveMs = (cProfile.last_active || 0) * 1000;
+    const lastSeenMs = (cProfile.last_seen || 0) * 1000;
+    const showLastActive = !cProfile.mine && (cProfile.online || lastSeenMs > 0 || (fShowLastActive && lastActiveMs > 0));
+    domProfileLastActive.style.display = showLastActive ? '' : 'none';
+    if (cProfile.online && !cProfile.mine) {
+        domProfileLastActive.textContent = 'Online';
+    } else if (showLastActive) {
+        const ago = timeAgo(lastSeenMs || lastActiveMs);
+        domProfileLastActive.textContent = ago === 'Now' ? 'Active recently' : `${lastSeenMs ? 'Seen' : 'Active'} ${ago} ago`;
+    }
+
     // Badges
     domProfileBadgeInvite.style.display = 'none';
     invoke("get_invited_users", { npub: cProfile.id }).then(count => {
@@ -5568,6 +6231,13 @@ function renderProfileTab(cProfile) {
         renderMentions(domProfileDescription, false, { allowBare: true, queueSync: true });
     }
 
+    // Zaps between us (from the NIP-57 receipts we've seen)
+    const zapParts = [];
+    if (cProfile.zaps?.sent_msat) zapParts.push(`You zapped ${formatSats(cProfile.zaps.sent_msat)} sats`);
+    if (cProfile.zaps?.received_msat) zapParts.push(`Zapped you ${formatSats(cProfile.zaps.received_msat)} sats`);
+    domProfileZaps.textContent = zapParts.length ? '⚡ ' + zapParts.join(' · ') : '';
+    domProfileZaps.style.display = zapParts.length ? '' : 'none';
+
     // npub
     domProfileId.textContent = cProfile.id;
 
@@ -5707,6 +6377,57 @@ function renderProfileTab(cProfile) {
             }
         };
 
+        // Setup Contact option (inside More dropdown): the NIP-02 contact list, shared with other Nostr clients
+        const contactLabel = domProfileOptionContact.querySelector('span:first-child');
+        const contactIcon = domProfileOptionContact.querySelector('.icon');
+        let isContact = false;
+        const renderContactOption = () => {
+            contactLabel.textContent = isContact ? 'Remove Contact' : 'Add Contact';
+            contactIcon.classList.toggle('icon-add-user', !isContact);
+            contactIcon.classList.toggle('icon-x-user', isContact);
+        };
+        renderContactOption();
+        invoke('get_contacts').then(contacts => {
+            isContact = contacts.some(c => c.npub === cProfile.id);
+            renderContactOption();
+        }).catch(() => {});
+        domProfileOptionContact.onclick = async () => {
+            domProfileMoreDropdown.style.display = 'none';
+            try {
+                if (isContact) {
+                    await invoke('remove_contact', { npub: cProfile.id });
+                    showToast('Contact Removed');
+                } else {
+                    await invoke('add_contact', { npub: cProfile.id, petname: null });
+                    showToast('Contact Added');
+                }
+                isContact = !isContact;
+                renderContactOption();
+            } catch (e) {
+                popupConfirm('Contacts', escapeHtml(String(e)), true, '', 'vector_warning.svg');
+            }
+        };
+
+        // Setup Verify option (inside More dropdown): compare safety numbers in person or by scanning
+        domProfileOptionVerify.querySelector('span:first-child').textContent = cProfile.contact_verified ? 'Verified' : 'Verify';
+        domProfileOptionVerify.onclick = async () => {
+            domProfileMoreDropdown.style.display = 'none';
+            await openContactVerification(cProfile.id);
+        };
+
+        // Setup Message Relays option (inside More dropdown): where our messages to them are published
+        domProfileOptionRelays.onclick = async (e) => {
+            domProfileMoreDropdown.style.display = 'none';
+            await openContactRelaysMenu(cProfile.id, e.clientX, e.clientY);
+        };
+
+        // Setup Zap option (inside More dropdown): only for users with a Lightning address
+        domProfileOptionZap.style.display = (cProfile.lud16 || cProfile.lud06) ? '' : 'none';
+        domProfileOptionZap.onclick = async () => {
+            domProfileMoreDropdown.style.display = 'none';
+            await zapFlow(cProfile.id, cProfile.id);
+        };
+
         // Setup Nickname option (inside More dropdown)
         domProfileOptionNickname.onclick = async () => {
             domProfileMoreDropdown.style.display = 'none';
@@ -6035,9 +6756,10 @@ function openEncryptionFlow(fUnlock = false, securityType = 'pin') {
                         // PIN was already validated by internal_decrypt, so a
                         // post-decrypt failure (signer unreachable) shouldn't
                         // be presented as "Incorrect PIN".
-                        const handled = typeof window.handleBunkerLoginError === 'function'
-                            ? await window.handleBunkerLoginError(e)
-                            : false;
+                        const handled = (typeof window.handleKeystoreLoginError === 'function'
+                                && await window.handleKeystoreLoginError(e))
+                            || (typeof window.handleBunkerLoginError === 'function'
+                                && await window.handleBunkerLoginError(e));
                         if (handled) { pinProcessing = false; return; }
                         updateStatusMessage(INCORRECT_PIN_MSG);
                         resetPinDisplay(true, false);
@@ -6207,9 +6929,10 @@ function openEncryptionFlow(fUnlock = false, securityType = 'pin') {
                 } catch (e) {
                     // Bunker-unreachable case: pass through to re-auth flow
                     // instead of telling the user their password is wrong.
-                    const handled = typeof window.handleBunkerLoginError === 'function'
-                        ? await window.handleBunkerLoginError(e)
-                        : false;
+                    const handled = (typeof window.handleKeystoreLoginError === 'function'
+                            && await window.handleKeystoreLoginError(e))
+                        || (typeof window.handleBunkerLoginError === 'function'
+                            && await window.handleBunkerLoginError(e));
                     if (handled) { passwordProcessing = false; return; }
                     updateStatusMessage(INCORRECT_MSG);
                     newInput.value = '';
@@ -6538,6 +7261,38 @@ function buildChatMenuItems(chat) {
             });
         }
     }
+    if (chat) {
+        items.push({
+            label: 'My Wallpaper',
+            icon: 'image',
+            onClick: () => startWallpaperChange(strOpenChat, true),
+        });
+        if (objChatWallpaperOverrides[chat.id]) {
+            items.push({
+                label: 'Remove My Wallpaper',
+                icon: 'trash',
+                onClick: () => removeWallpaperOverride(strOpenChat),
+            });
+        }
+    }
+    items.push({
+        label: 'Accent Color',
+        icon: 'bulb',
+        onClick: () => {
+            const btn = document.getElementById('chat-menu-btn');
+            const rect = btn ? btn.getBoundingClientRect() : null;
+            requestAnimationFrame(() => openChatAccentPicker(strOpenChat, rect));
+        },
+    });
+    items.push({
+        label: 'Starred Messages',
+        icon: 'star',
+        onClick: () => {
+            const btn = document.getElementById('chat-menu-btn');
+            const rect = btn ? btn.getBoundingClientRect() : null;
+            requestAnimationFrame(() => openStarredMessages(rect));
+        },
+    });
     return items;
 }
 
@@ -6579,6 +7334,7 @@ function setChatHeader(chat, profile, isGroup, fNotes) {
     } else {
         domChatContact.textContent = getName(profile);
         if (profile?.nickname || profile?.name) twemojify(domChatContact);
+        if (profile?.verified) domChatContact.appendChild(createVerifiedIcon(profile.verified));
         domChatContact.onclick = () => {
             previousChatBeforeProfile = strOpenChat;
             openProfile(profile);
@@ -6900,6 +7656,7 @@ async function updateChat(chat, arrMessages = [], profile = null, fClicked = fal
             updateChatHeaderSubtext(chat);
         } else {
             domChatContact.textContent = getName(profile);
+            if (profile?.verified) domChatContact.appendChild(createVerifiedIcon(profile.verified));
             domChatContact.onclick = null;
             domChatContact.classList.remove('btn');
             domChatContactStatus.textContent = '';
@@ -7113,14 +7870,8 @@ function applyChatWallpaper(chatId, path, blur, dim, ts) {
 
 /** Refresh the wallpaper layer from the open chat's persisted state. */
 function refreshChatWallpaper() {
-    const chat = getChat(strOpenChat);
-    applyChatWallpaper(
-        strOpenChat,
-        chat?.wallpaper_path || '',
-        chat?.wallpaper_blur,
-        chat?.wallpaper_dim,
-        chat?.wallpaper_ts,
-    );
+    const { path, blur, dim, ts } = chatWallpaper(strOpenChat);
+    applyChatWallpaper(strOpenChat, path, blur, dim, ts);
 }
 
 /** Show or hide the wallpaper edit UI: the bottom slider/trash bar plus the
@@ -7220,10 +7971,11 @@ function hideProcessingOverlay() {
     document.getElementById('processing-overlay')?.classList.remove('visible');
 }
 
-async function startWallpaperChange(chatId) {
+async function startWallpaperChange(chatId, fOverride = false) {
     if (!chatId) return;
     const chat = getChat(chatId);
-    if (!chat || chat.chat_type !== 'DirectMessage') return;
+    // Only DMs share a wallpaper; our own override works in any chat
+    if (!chat || (!fOverride && chat.chat_type !== 'DirectMessage')) return;
 
     try {
         // Native picker on both platforms. Desktop returns a filesystem path,
@@ -7245,14 +7997,15 @@ async function startWallpaperChange(chatId) {
         } finally {
             hideProcessingOverlay();
         }
-        await applyWallpaperPreview(chatId, previewResult);
+        await applyWallpaperPreview(chatId, previewResult, fOverride);
     } catch (err) {
         popupConfirm('Couldn’t use that image', String(err), true);
     }
 }
 
-/** Apply the staged preview file to the chat + open the slider bar. */
-async function applyWallpaperPreview(chatId, previewResult) {
+/** Apply the staged preview file to the chat + open the slider bar. `fOverride` stages our own
+ *  wallpaper override instead of the DM's shared one. */
+async function applyWallpaperPreview(chatId, previewResult, fOverride = false) {
     if (!previewResult?.path) return;
     if (previewResult.was_animated) {
         await popupConfirm(
@@ -7266,16 +8019,18 @@ async function applyWallpaperPreview(chatId, previewResult) {
     // picks, fall back to the backend's per-image suggested brightness so
     // photos that are very bright don't ship with text-killing contrast.
     const chat = getChat(chatId);
-    const hadWallpaper = !!chat?.wallpaper_path;
-    const blur = hadWallpaper ? (chat.wallpaper_blur ?? WALLPAPER_DEFAULT_BLUR) : WALLPAPER_DEFAULT_BLUR;
-    const dim = hadWallpaper
-        ? (chat.wallpaper_dim ?? WALLPAPER_DEFAULT_DIM)
+    const current = fOverride
+        ? objChatWallpaperOverrides[chatId]
+        : (chat?.wallpaper_path ? { blur: chat.wallpaper_blur, dim: chat.wallpaper_dim } : null);
+    const blur = current ? (current.blur ?? WALLPAPER_DEFAULT_BLUR) : WALLPAPER_DEFAULT_BLUR;
+    const dim = current
+        ? (current.dim ?? WALLPAPER_DEFAULT_DIM)
         : (previewResult.recommended_dim ?? WALLPAPER_DEFAULT_DIM);
     writeWallpaperSliders(blur, dim);
     // Use Date.now() as the cache key so picking a second image with the
     // same extension (same on-disk preview path) forces a refetch.
     const previewTs = Date.now();
-    wallpaperPreviewState = { chatId, previewPath: previewResult.path, blur, dim, ts: previewTs };
+    wallpaperPreviewState = { chatId, previewPath: previewResult.path, blur, dim, ts: previewTs, override: fOverride };
     clearWallpaperUploadProgress();
     setWallpaperEditBusy(false);
     // Flag previewing FIRST so applyChatWallpaper renders the staged image
@@ -7300,10 +8055,18 @@ function onWallpaperSliderInput() {
 /** Publish the preview as the chat's wallpaper (with current slider values). */
 async function confirmWallpaperChange() {
     if (!wallpaperPreviewState) return;
-    const { chatId, blur, dim } = wallpaperPreviewState;
+    const { chatId, blur, dim, override } = wallpaperPreviewState;
     setWallpaperEditBusy(true);
     setWallpaperUploadProgress(0);
     try {
+        if (override) {
+            // Lands as chat_appearance_changed with the cached path
+            await invoke('set_chat_wallpaper_override', { chatId, blur, dim });
+            wallpaperPreviewState = null;
+            setWallpaperPreviewBarVisible(false);
+            refreshChatWallpaper();
+            return;
+        }
         await invoke('publish_wallpaper', { chatId, blur, dim });
         wallpaperPreviewState = null;
         setWallpaperPreviewBarVisible(false);
@@ -7354,7 +8117,17 @@ async function removeWallpaper(chatId) {
     if (!ok) return;
     try {
         await invoke('remove_wallpaper', { chatId });
-        applyChatWallpaper(chatId, '', 0, 50, Date.now());
+        if (!objChatWallpaperOverrides[chatId]) applyChatWallpaper(chatId, '', 0, 50, Date.now());
+    } catch (err) {
+        popupConfirm('Wallpaper not removed', String(err), true, '', 'vector_warning.svg');
+    }
+}
+
+/** Drop our own wallpaper override for the chat, here and on our other devices. */
+async function removeWallpaperOverride(chatId) {
+    if (!chatId) return;
+    try {
+        await invoke('clear_chat_wallpaper_override', { chatId });
     } catch (err) {
         popupConfirm('Wallpaper not removed', String(err), true, '', 'vector_warning.svg');
     }
@@ -7746,6 +8519,12 @@ function startAttachmentDownload(cAttachment, msg, isGroupChat, strOpenChat, sen
     }
 }
 
+/**
+ * Retry tokens of DM file sends whose uploads gave up, by message id (from `upload_failed`).
+ * @type {Map<string, string>}
+ */
+const failedUploadTokens = new Map();
+
 async function retryFailedMessage(msg) {
     const chatId = strOpenChat;
     if (!chatId) return;
@@ -7778,6 +8557,23 @@ async function retryFailedMessage(msg) {
             }
         };
         setSendState(false, true);
+        // An upload that gave up is parked backend-side: pick the same message back up so
+        // the files that made it aren't uploaded again. An unknown token (e.g. after a
+        // restart) falls through; any other error means the retry ran and failed again,
+        // and the backend has already turned the row red.
+        const uploadToken = failedUploadTokens.get(msg.id);
+        if (uploadToken) {
+            failedUploadTokens.delete(msg.id);
+            try {
+                await invoke('retry_failed_upload', { token: uploadToken });
+                return;
+            } catch (e) {
+                if (!String(e).includes('Nothing to retry')) {
+                    console.error('Upload retry failed:', e);
+                    return;
+                }
+            }
+        }
         try {
             const resent = await invoke('retry_failed_dm', { receiver: chatId, messageId: msg.id });
             if (resent) return;
@@ -8291,14 +9087,16 @@ async function openChat(contact) {
     // chat.last_read so the OS badge clears immediately on entering the chat.
     const lastReadOnOpen = chat?.last_read || '';
     const unreadOnOpen = chat?.unread || 0;   // snapshot before the open-time markAsRead zeroes it
-    if (chat?.messages?.length) {
+    if (chat?.messages?.length && readsInstantly()) {
         const latestNonMine = findLatestContactMessage(chat.messages);
         if (latestNonMine) markAsRead(chat, latestNonMine);
     }
 
     // Apply the chat's wallpaper to the layer before any messages render,
     // so the first paint already shows the bg + filter.
-    applyChatWallpaper(contact, chat?.wallpaper_path || '', chat?.wallpaper_blur, chat?.wallpaper_dim, chat?.wallpaper_ts);
+    const wallpaper = chatWallpaper(contact);
+    applyChatWallpaper(contact, wallpaper.path, wallpaper.blur, wallpaper.dim, wallpaper.ts);
+    applyChatAccent();
 
     // Render the header SYNCHRONOUSLY using whatever in-memory data we have,
     // so the user sees the contact name + avatar the instant the chat panel
@@ -8360,6 +9158,8 @@ async function openChat(contact) {
 
     // Merge any historical PIVX payments — helper in pivx.js
     await mergePivxPaymentsIntoChat(contact, initialMessages);
+    // ...and ecash tokens — helper in ecash.js
+    await mergeEcashIntoChat(contact, initialMessages);
 
     // No on-open Community sync: NIP-17-parity means catch-up happens at boot (sync_communities_boot)
     // and on relay reconnect, with realtime delivering everything in between. Opening a channel reads
@@ -8679,6 +9479,7 @@ async function closeChat() {
     domChat.style.display = 'none';
     strOpenChat = "";
     previousChatBeforeProfile = ""; // Clear when closing chat
+    applyChatAccent();
     nLastTypingIndicator = 0;
     syncBackendActiveChat();
     
@@ -8768,6 +9569,14 @@ async function openProfile(cProfile) {
 
     renderProfileTab(cProfile);
 
+    // Bio and banner were left out of the boot payload; fetch them now and re-render in place
+    if (cProfile?.details_deferred) {
+        const npub = cProfile.id;
+        ensureProfileDetails(npub).then(full => {
+            if (full && domProfile.style.display === '' && domProfileId.textContent === npub) renderProfileTab(full);
+        });
+    }
+
     if (domProfile.style.display !== '') {
         // Run a subtle fade-in animation
         domProfile.classList.add('fadein-subtle-anim');
@@ -9603,6 +10412,13 @@ async function openCommunityInvitePanel(chat) {
             const lbl = (link.label || '').trim();
             url.append(lbl || `Invite · ${(link.token || link.url).slice(-8)}`);
             url.title = link.url;
+            if (link.expires_at) {
+                const expired = link.expires_at * 1000 <= Date.now();
+                const exp = document.createElement('span');
+                exp.className = 'cmt-link-expiry' + (expired ? ' cmt-link-expired' : '');
+                exp.textContent = expired ? ' · expired' : ` · until ${new Date(link.expires_at * 1000).toLocaleDateString()}`;
+                url.append(exp);
+            }
             // Join counter: distinct members who joined via this link.
             const joins = link.join_count || 0;
             const count = document.createElement('span');
@@ -9692,9 +10508,14 @@ async function openCommunityInvitePanel(chat) {
         const labelInput = await popupConfirm('Label this link', 'Optional. A label lets you see which link people join through (e.g. "Reddit", "Twitter"). Leave blank to skip.', false, 'Label (optional)', '', '', 'Create link');
         if (labelInput === false) return; // cancelled
         const label = (typeof labelInput === 'string' && labelInput.trim()) ? labelInput.trim() : null;
+        // Optional expiry in days — joiners refuse the link past it (it stays listed until revoked).
+        const daysInput = await popupConfirm('Link expiry', 'Optional. After this many days the link stops letting people in. Leave blank to keep it open until revoked.', false, 'Days (optional)', '', '', 'Create link');
+        if (daysInput === false) return; // cancelled
+        const days = parseInt(typeof daysInput === 'string' ? daysInput.trim() : '', 10);
+        const expiresInSecs = days > 0 ? days * 86400 : null;
         setBusy(true); // lock the panel — the FIRST link flips public + the publish is a critical op
         btn.innerHTML = '<span class="icon icon-loading spin"></span>Creating…'; setStatus('Creating link...');
-        try { await invoke('create_public_invite', { communityId, expiresInSecs: null, label }); setStatus(''); }
+        try { await invoke('create_public_invite', { communityId, expiresInSecs, label }); setStatus(''); }
         catch (err) { setStatus(String(err), true); }
         finally { setBusy(false); btn.innerHTML = '<span class="icon icon-plus"></span>Create invite link'; await renderLinks(); }
     };
@@ -9892,6 +10713,7 @@ function applyRemoteSignerDot(state) {
 async function refreshRemoteSignerCard() {
     const card = document.getElementById('settings-remote-signer');
     const exportRow = document.getElementById('export-account-row');
+    const socialBackupRow = document.getElementById('social-backup-row');
     if (!card) return;
     const labelEl = document.getElementById('remote-signer-label');
     const hintEl = document.getElementById('remote-signer-hint');
@@ -9913,6 +10735,7 @@ async function refreshRemoteSignerCard() {
             }
             card.style.display = '';
             if (exportRow) exportRow.style.display = 'none';
+            if (socialBackupRow) socialBackupRow.style.display = 'none';
             return;
         }
         // Not a bunker account — an on-device NIP-55 offline signer (Amber)
@@ -9938,15 +10761,18 @@ async function refreshRemoteSignerCard() {
             applyRemoteSignerDot(nip55.installed ? 'online' : 'offline');
             card.style.display = '';
             if (exportRow) exportRow.style.display = 'none';
+            if (socialBackupRow) socialBackupRow.style.display = 'none';
             return;
         }
         // Local-key account — no external signer card.
         card.style.display = 'none';
         if (exportRow) exportRow.style.display = '';
+        if (socialBackupRow) socialBackupRow.style.display = '';
     } catch (e) {
         console.warn('[settings] remote signer status failed:', e);
         card.style.display = 'none';
         if (exportRow) exportRow.style.display = '';
+        if (socialBackupRow) socialBackupRow.style.display = '';
     }
 }
 
@@ -10052,7 +10878,7 @@ function updateProfileEditLabel() {
     if (!label) return;
 
     const nameChanged = nameInput?.value.trim() !== (objProfileEditSnapshot.name || '');
-    const statusChanged = statusInput?.value.trim() !== (objProfileEditSnapshot.status?.title ?? objProfileEditSnapshot.status ?? '');
+    const statusChanged = statusInput?.value.trim() !== getStatusText(objProfileEditSnapshot);
     const bioChanged = bioInput?.value.trim() !== (objProfileEditSnapshot.about || '');
     const avatarChanged = strPendingProfileAvatarPath !== null;
     const bannerChanged = strPendingProfileBannerPath !== null;
@@ -10071,7 +10897,7 @@ function enterProfileEditMode() {
     if (!cProfile) return;
     objProfileEditSnapshot = {
         name: cProfile.name || '',
-        status: cProfile.status || '',
+        status: { ...(cProfile.status || {}) },
         about: cProfile.about || '',
         avatar: getProfileAvatarSrc(cProfile) || null,
         banner: getProfileBannerSrc(cProfile) || null
@@ -10125,7 +10951,7 @@ function enterProfileEditMode() {
     editName.innerHTML = `<input type="text" maxlength="50" style="background: none; border: none; outline: none; color: inherit; font-size: 16px; width: 100%;">`;
     editName.querySelector('input').value = cProfile.name || '';
     editStatus.innerHTML = `<input type="text" style="background: none; border: none; outline: none; color: inherit; font-size: 16px; width: 100%;">`;
-    editStatus.querySelector('input').value = cProfile.status?.title || '';
+    editStatus.querySelector('input').value = getStatusText(cProfile);
     editBio.innerHTML = `<textarea style="background: none; border: none; outline: none; color: inherit; font-size: 16px; width: 100%; resize: none; min-height: 60px;"></textarea>`;
     const bioTextarea = editBio.querySelector('textarea');
     bioTextarea.value = typeof cProfile.about === 'string' ? cProfile.about : '';
@@ -10266,19 +11092,21 @@ function exitProfileEditMode(fCancel = false) {
             const statusInput = document.querySelector('#profile-edit-status input');
             const bioInput = document.querySelector('#profile-edit-bio textarea');
             const newName = nameInput ? nameInput.value.trim() : cProfile.name;
-            const newStatus = statusInput ? statusInput.value.trim() : (cProfile.status?.title ?? '');
+            const newStatus = statusInput ? statusInput.value.trim() : getStatusText(cProfile);
             const newAbout = bioInput ? bioInput.value.trim() : (cProfile.about ?? '');
             const prevName = objProfileEditSnapshot.name || '';
-            const prevStatus = objProfileEditSnapshot.status?.title ?? objProfileEditSnapshot.status ?? '';
+            const prevStatus = getStatusText(objProfileEditSnapshot);
             const prevAbout = objProfileEditSnapshot.about || '';
 
-            cProfile.name = newName;
-            if (cProfile.status) cProfile.status.title = newStatus;
-            cProfile.about = newAbout;
-
             const nameChanged = newName !== prevName;
             const aboutChanged = newAbout !== prevAbout;
             const statusChanged = newStatus !== prevStatus;
+
+            cProfile.name = newName;
+            // Backend splits a leading emoji back out; keep the edit's one-line form until it echoes.
+            // An untouched status keeps its emoji and its auto-clear.
+            if (statusChanged && cProfile.status) Object.assign(cProfile.status, { title: newStatus, emoji: '', expires_at: 0 });
+            cProfile.about = newAbout;
             if (nameChanged || aboutChanged) {
                 invoke('update_profile', {
                     name: nameChanged ? newName : '',
@@ -10452,6 +11280,9 @@ let strPubkey;
  */
 let nLastTypingIndicator = 0;
 
+/** Debounce for fetching a link preview while composing (see `prefetch_link_preview`). */
+let nLinkPreviewPrefetchTimer = null;
+
 const strOriginalInputPlaceholder = domChatMessageInput.getAttribute('placeholder');
 
 /**
@@ -10545,6 +11376,15 @@ window.addEventListener("DOMContentLoaded", async () => {
         popupConfirm('Loading Error', evt.payload, true, '', 'vector_warning.svg');
     });
 
+    // Directories a sandbox (Firejail, Flatpak, AppArmor) blocks would otherwise fail somewhere
+    // downstream; list them so the user knows exactly what to whitelist.
+    invoke('get_runtime_paths').then((paths) => {
+        const blocked = (paths || []).filter(p => p.error);
+        if (!blocked.length) return;
+        const rows = blocked.map(p => `<b>${escapeHtml(p.purpose)}</b><br><code>${escapeHtml(p.path || '(unresolved)')}</code><br><span style="opacity: 0.7;">${escapeHtml(p.error)}</span>`).join('<br><br>');
+        popupConfirm('Folder Access Blocked', `Vector can't use some folders it needs, so parts of the app may not work. If you run Vector in a sandbox, allow access to:<br><br><div style="text-align: left;">${rows}</div>`, true, '', 'vector_warning.svg');
+    }).catch(() => {});
+
     // Bunker session events — must be registered EARLY (alongside
     // loading_error / session_reload), not inside setupRustListeners, because
     // the re-auth flow fires these while the user is still on the login
@@ -10762,6 +11602,32 @@ window.addEventListener("DOMContentLoaded", async () => {
     });
 
     // Module-scope: callable from the boot-time login_from_stored_key catch.
+    // The account's key is kept in the system keystore but can't be read back
+    // (missing, or the Android Keystore can no longer unseal it — e.g. after a
+    // lock-screen reset). Retrying the PIN can't help, so say so and send the
+    // user to the start screen to restore from their seed phrase or nsec.
+    window.handleKeystoreLoginError = async function handleKeystoreLoginError(e) {
+        const msg = String(e || '');
+        if (!msg.includes('missing from the system keystore')
+            && !msg.includes('can no longer unseal')) return false;
+        await popupConfirm(
+            'Key Unavailable',
+            'This account\'s key was kept in your device\'s keychain, and it can no longer be read. This can happen after a lock-screen reset or restoring onto a new device.<br><br>'
+                + 'Restore the account with your seed phrase or nsec to keep using it.',
+            true,
+            '',
+            'vector_warning.svg'
+        );
+        domLoginEncrypt.style.display = 'none';
+        domLoginStart.style.display = '';
+        if (typeof loginPicker !== 'undefined'
+            && loginPicker.accounts
+            && loginPicker.accounts.length >= 2) {
+            loginPicker.show(loginPicker.activeNpub);
+        }
+        return true;
+    };
+
     window.handleBunkerLoginError = async function handleBunkerLoginError(e) {
         const msg = String(e || '');
         const looksLikeBunkerOffline = msg.includes('Remote signer unreachable')
@@ -10836,6 +11702,11 @@ window.addEventListener("DOMContentLoaded", async () => {
                 // and a stale first panel open).
                 loadEmojiPacks();
                 loadEmojiUsage();
+                loadContactGroups();
+                loadChatFolders();
+                loadChatAccents();
+                loadChatWallpaperOverrides();
+                loadRemoteContentPolicies();
 
                 // Hide login UI and show main UI
                 domLogin.style.display = 'none';
@@ -11017,6 +11888,8 @@ window.addEventListener("DOMContentLoaded", async () => {
                     // instead of bouncing the user to the start screen.
                     // The account stays intact, only the pairing needs
                     // refreshing in the signer app.
+                    if (typeof window.handleKeystoreLoginError === 'function'
+                        && await window.handleKeystoreLoginError(e)) return;
                     const handled = typeof window.handleBunkerLoginError === 'function'
                         ? await window.handleBunkerLoginError(e)
                         : false;
@@ -11049,14 +11922,14 @@ window.addEventListener("DOMContentLoaded", async () => {
     domProfileBtn.onclick = () => openProfile();
     domChatlistBtn.onclick = openChatlist;
     domSettingsBtn.onclick = openSettings;
-    domLoginAccountCreationBtn.onclick = async () => {
+    domLoginAccountCreationBtn.onclick = async (passphrase = null) => {
         try {
             // Add Profile commit point: tear down the existing session
             // before generating a new keypair, otherwise create_account's
             // lock-and-check guard would silently reuse the old client.
             if (addAccountFlow.active) await addAccountFlow.commit();
 
-            const { public: pubKey } = await invoke("create_account");
+            const { public: pubKey } = await invoke("create_account", { passphrase: typeof passphrase === 'string' ? passphrase : null });
             strPubkey = pubKey;
 
             // Connect to Nostr network
@@ -11091,6 +11964,32 @@ window.addEventListener("DOMContentLoaded", async () => {
         // remote signer.
         domLoginBunkerStartBtn.onclick = showBunkerForm;
     }
+    if (domLoginSocialRecoveryBtn) domLoginSocialRecoveryBtn.onclick = startSocialRecovery;
+    if (domLoginBundleRestoreBtn) domLoginBundleRestoreBtn.onclick = startBundleRestore;
+
+    // BIP-39 passphrase ("25th word"): the same seed phrase with a different passphrase is a
+    // different account, and forgetting it means losing the account — say so before accepting one.
+    domLoginAccountPassphraseBtn.onclick = async () => {
+        const fAccept = await popupConfirm('Seed Passphrase',
+            'A passphrase adds a secret "25th word" to your seed phrase. Anyone who finds your seed phrase also needs it to access your account.<br><br><b style="color: var(--danger-pink);">Vector never stores your passphrase. If you forget it, your seed phrase alone cannot restore this account.</b>',
+            false, '', 'vector_warning.svg', '', 'I Understand');
+        if (!fAccept) return;
+        const passphrase = await popupSecret('Choose a Passphrase', 'Pick something you will remember exactly — it is case-sensitive.', 'Passphrase...');
+        if (!passphrase) return;
+        const repeat = await popupSecret('Confirm Passphrase', 'Type your passphrase again.', 'Passphrase...');
+        if (repeat !== passphrase) {
+            return popupConfirm('Passphrases Don\'t Match', 'Nothing was created. Please try again.', true, '', 'vector_warning.svg');
+        }
+        domLoginAccountCreationBtn.onclick(passphrase);
+    };
+    domLoginPassphraseBtn.onclick = async () => {
+        const passphrase = await popupSecret('Seed Passphrase',
+            'Only needed if your seed phrase was created with a passphrase ("25th word"). A wrong passphrase opens a different, empty account.',
+            'Passphrase...');
+        if (passphrase === false) return;
+        strLoginPassphrase = passphrase;
+        domLoginPassphraseLabel.textContent = passphrase ? 'Seed Passphrase Set' : 'Seed Passphrase (25th Word)';
+    };
 
     // NIP-55 offline signer (Amber): Android-only, and only when a signer app
     // is actually installed — otherwise the button is a dead end. The reveal
@@ -11237,7 +12136,10 @@ window.addEventListener("DOMContentLoaded", async () => {
             // before importing the new key.
             if (addAccountFlow.active) await addAccountFlow.commit();
 
-            const { public: pubKey, existing } = await invoke("login", { importKey: domLoginInput.value.trim() });
+            const passphrase = strLoginPassphrase || null;
+            strLoginPassphrase = '';
+            domLoginPassphraseLabel.textContent = 'Seed Passphrase (25th Word)';
+            const { public: pubKey, existing } = await invoke("login", { importKey: domLoginInput.value.trim(), passphrase });
             strPubkey = pubKey;
 
             // Pasted key matches an account already on disk; the backend has
@@ -11313,9 +12215,9 @@ window.addEventListener("DOMContentLoaded", async () => {
     };
     domChatNewBackBtn.onclick = closeChat;
 
-    // Chat-header overflow menu — dropdown of chat-scoped actions. Currently
-    // hosts "Change Wallpaper" for DM chats. Group chats don't get wallpapers
-    // by design, so the option only renders when the open chat is a DM.
+    // Chat-header overflow menu — dropdown of chat-scoped actions, plus the
+    // cross-chat Starred Messages list. Only DMs share a wallpaper, so "Change
+    // Wallpaper" only renders there; "My Wallpaper" (ours alone) works in any chat.
     const domChatMenuBtn = document.getElementById('chat-menu-btn');
     if (domChatMenuBtn) {
         domChatMenuBtn.addEventListener('click', (e) => {
@@ -11467,13 +12369,17 @@ window.addEventListener("DOMContentLoaded", async () => {
         // Handle File button in attachment panel (Desktop - use Tauri dialog)
         domAttachmentPanelFile.onclick = async () => {
             closeAttachmentPanel();
-            let filepath = await selectFile();
-            if (filepath) {
+            const filepaths = await selectFiles();
+            if (filepaths.length) {
                 // Reset reply selection while passing a copy of the reference to the backend
                 const strReplyRef = strCurrentReplyReference;
                 cancelReply();
-                // Show file preview instead of sending directly
-                await openFilePreview(filepath, strOpenChat, strReplyRef);
+                if (filepaths.length > 1) {
+                    await sendFiles(strOpenChat, strReplyRef, filepaths);
+                } else {
+                    // Show file preview instead of sending directly
+                    await openFilePreview(filepaths[0], strOpenChat, strReplyRef);
+                }
             }
         };
 
@@ -11490,7 +12396,11 @@ window.addEventListener("DOMContentLoaded", async () => {
                 }
             };
         }
+
+        initVideoNoteButton();
     }
+    initLocationButton();
+    initEcashButton();
 
     // Commands button — bot-chats only. Drops a `/` into the composer and opens
     // the command list. Grayed (with a tooltip) while a draft is present.
@@ -11594,9 +12504,14 @@ window.addEventListener("DOMContentLoaded", async () => {
                 const filePaths = await invoke('read_clipboard_files');
                 if (Array.isArray(filePaths) && filePaths.length) {
                     restoreInput();
-                    const droppedPath = filePaths[0]; // mirror drag-drop: first item
                     const strReplyRef = strCurrentReplyReference;
                     cancelReply();
+                    if (filePaths.length > 1) {
+                        // Several copied files: one batch, like a multi-file drop
+                        await sendFiles(strOpenChat, strReplyRef, filePaths);
+                        return;
+                    }
+                    const droppedPath = filePaths[0];
                     const isDir = await invoke('is_directory', { path: droppedPath }).catch(() => false);
                     if (isDir) {
                         await openFolderZipPreview(droppedPath, strOpenChat, strReplyRef);
@@ -12032,6 +12947,15 @@ domChatMessageInput.oninput = async (e) => {
         }
     }
 
+    // Fetch a typed link's preview ahead of sending: it goes out with the message, so the
+    // recipients never have to fetch the link themselves.
+    if (fWebPreviewsEnabled && domChatMessageInput.value.includes('https://')) {
+        clearTimeout(nLinkPreviewPrefetchTimer);
+        nLinkPreviewPrefetchTimer = setTimeout(() => {
+            invoke('prefetch_link_preview', { text: domChatMessageInput.value }).catch(() => {});
+        }, 800);
+    }
+
     // Send a Typing Indicator only when content actually changes and setting is enabled.
     // Don't send while editing (not a new message), while the draft is a `/` command
     // (an instruction to a bot, not conversation), or on a DELETION — removing text,
@@ -12111,7 +13035,14 @@ domChatMessageInput.oninput = async (e) => {
                     // Check if dropped path is a directory or file
                     const droppedPath = event.payload.paths[0];
                     const isDir = await invoke('is_directory', { path: droppedPath });
-                    if (isDir) {
+                    const droppedFiles = [];
+                    for (const path of event.payload.paths) {
+                        if (!await invoke('is_directory', { path })) droppedFiles.push(path);
+                    }
+                    if (droppedFiles.length > 1) {
+                        // Several files: one gallery message (folders in the drop are skipped)
+                        await sendFiles(strOpenChat, strReplyRef, droppedFiles);
+                    } else if (isDir) {
                         await openFolderZipPreview(droppedPath, strOpenChat, strReplyRef);
                     } else {
                         await openFilePreview(droppedPath, strOpenChat, strReplyRef);
@@ -12127,7 +13058,7 @@ domChatMessageInput.oninput = async (e) => {
         // read and clears its divider, but only when pinned — scrolled-up
         // users haven't seen the new messages just because they refocused.
         const onWindowResumed = () => {
-            if (!strOpenChat || !chatPinnedToBottom) return;
+            if (!strOpenChat || !chatPinnedToBottom || !readsInstantly()) return;
             const currentChat = getChat(strOpenChat);
             if (!currentChat?.messages?.length) return;
             const latestNonMine = findLatestContactMessage(currentChat.messages);
@@ -12140,6 +13071,7 @@ domChatMessageInput.oninput = async (e) => {
             windowFocused = !!event.payload;
             if (!wasActive && isWindowActive()) { onWindowResumed(); if (!fInit) consumePendingShare(); }
             syncBackendActiveChat();
+            applyPrivacyBlur();
         });
 
         document.addEventListener('visibilitychange', () => {
@@ -12152,6 +13084,23 @@ domChatMessageInput.oninput = async (e) => {
         });
     }
 
+    // Auto-lock: feed the backend's idle clock (input, at most every 30s — timeouts are minutes)
+    // and tell it when the app leaves and returns, which may lock on the way back.
+    let nLastActivityReport = 0;
+    const reportActivity = () => {
+        const now = Date.now();
+        if (now - nLastActivityReport < 30000) return;
+        nLastActivityReport = now;
+        invoke('report_activity').catch(() => {});
+    };
+    for (const strEvent of ['pointerdown', 'keydown', 'wheel', 'touchstart']) {
+        document.addEventListener(strEvent, reportActivity, { capture: true, passive: true });
+    }
+    document.addEventListener('visibilitychange', () => {
+        invoke('report_app_visibility', { visible: !document.hidden }).catch(() => {});
+        applyPrivacyBlur();
+    });
+
     // Hook up our voice message recorder with Telegram-like UX
     const recorder = new VoiceRecorder(domChatMessageInputVoice, domChatInputContainer);
     
@@ -12214,9 +13163,10 @@ domChatMessageInput.oninput = async (e) => {
             const torState = await invoke('tor_get_state');
             torEnabled = !!(torState && torState.enabled);
         } catch (_) { /* fall through to default warning */ }
-        const message = torEnabled
+        const shared = '<br><br>Previews of links you send are fetched once by you and sent along with the message, so your contacts don\'t have to visit the link. Disabling this stops every preview fetch.';
+        const message = (torEnabled
             ? 'When enabled, Vector will <b>automatically fetch and display previews</b> for links shared in messages.<br><br>You have <b>Tor enabled</b>, so preview fetches route through the Tor network. Your IP address stays hidden from the linked sites.'
-            : 'When enabled, Vector will <b>automatically fetch and display previews</b> for links shared in messages.<br><br>This may expose your IP address to the linked sites. <b>Use Tor</b> (Privacy, Route traffic through Tor) <b>or a VPN</b> if that\'s a concern.';
+            : 'When enabled, Vector will <b>automatically fetch and display previews</b> for links shared in messages.<br><br>This may expose your IP address to the linked sites. <b>Use Tor</b> (Privacy, Route traffic through Tor) <b>or a VPN</b> if that\'s a concern.') + shared;
         popupConfirm('Web Previews', message, true);
     };
     domSettingsPrivacyStripTrackingInfo.onclick = (e) => {
@@ -12227,7 +13177,17 @@ domChatMessageInput.oninput = async (e) => {
     domSettingsPrivacySendTypingInfo.onclick = (e) => {
         e.preventDefault();
         e.stopPropagation();
-        popupConfirm('Send Typing Indicators', 'When enabled, Vector will <b>notify your contacts when you are typing</b> a message to them.<br><br>Disable this if you prefer to type without others knowing you are composing a message.', true);
+        popupConfirm('Send Typing Indicators', 'When enabled, Vector will <b>notify your contacts when you are typing</b> a message to them.<br><br>Disable this if you prefer to type without others knowing you are composing a message. You can also switch them off for a single chat from its menu in the chat list; muted chats and blocked contacts never get them.', true);
+    };
+    domSettingsPrivacyLastActiveInfo.onclick = (e) => {
+        e.preventDefault();
+        e.stopPropagation();
+        popupConfirm('Show Last Active', 'When enabled, Vector <b>estimates when a contact was last active</b> from their most recent public profile, relay-list and status updates.<br><br>It is only an approximation and never uses your private messages. Disable this to stop Vector computing or showing it.', true);
+    };
+    domSettingsPrivacyPresenceInfo.onclick = (e) => {
+        e.preventDefault();
+        e.stopPropagation();
+        popupConfirm('Share Online Status', 'When enabled, Vector privately tells <b>your contacts</b> that you are online, with a small encrypted ping every few minutes that expires on its own.<br><br>Nobody else can see it, and nothing is sent while this is off.', true);
     };
     if (domSettingsPrivacyTorInfo) {
         domSettingsPrivacyTorInfo.onclick = (e) => {
@@ -12253,6 +13213,31 @@ domChatMessageInput.oninput = async (e) => {
             openUrl('https://torproject.org');
         };
     }
+    const domProxyInfo = document.getElementById('proxy-info');
+    if (domProxyInfo) {
+        domProxyInfo.onclick = (e) => {
+            e.preventDefault();
+            e.stopPropagation();
+            popupConfirm(
+                'SOCKS5 Proxy',
+                'Routes Nostr relays, Blossom uploads and downloads, and other web requests through your own SOCKS5 proxy, such as a system Tor daemon (<b>127.0.0.1:9050</b>) or an SSH tunnel.<br><br>'
+                + 'If Tor is turned on above, Tor carries the traffic instead.',
+                true
+            );
+        };
+    }
+    const domProxyKillSwitchInfo = document.getElementById('proxy-kill-switch-info');
+    if (domProxyKillSwitchInfo) {
+        domProxyKillSwitchInfo.onclick = (e) => {
+            e.preventDefault();
+            e.stopPropagation();
+            popupConfirm(
+                'Kill Switch',
+                'When your proxy stops responding, Vector blocks all connections until it\'s back, instead of falling back to a direct connection that reveals your IP address.',
+                true
+            );
+        };
+    }
     domSettingsDisplayImageTypesInfo.onclick = (e) => {
         e.preventDefault();
         e.stopPropagation();
@@ -12287,6 +13272,22 @@ domChatMessageInput.oninput = async (e) => {
             popupConfirm('Run in Background', 'When enabled, Vector runs a <b>background service</b> to keep your connection alive and deliver <b>instant notifications</b>.<br><br>This requires disabling Android\'s battery optimization for Vector, otherwise the system may kill the service and delay or prevent notifications.', true);
         };
     }
+    const domSettingsBatteryPeriodicSyncInfo = document.getElementById('battery-periodic-sync-info');
+    if (domSettingsBatteryPeriodicSyncInfo) {
+        domSettingsBatteryPeriodicSyncInfo.onclick = (e) => {
+            e.preventDefault();
+            e.stopPropagation();
+            popupConfirm('Periodic Sync', 'When <b>Run in Background</b> is off, Vector can still <b>check for new messages</b> every so often and notify you about them.<br><br>Checks are skipped while your battery is low or Battery Saver is on, and Android may delay them further to save power, so notifications can arrive late.', true);
+        };
+    }
+    const domSettingsBatteryUnifiedPushInfo = document.getElementById('battery-unifiedpush-info');
+    if (domSettingsBatteryUnifiedPushInfo) {
+        domSettingsBatteryUnifiedPushInfo.onclick = (e) => {
+            e.preventDefault();
+            e.stopPropagation();
+            popupConfirm('UnifiedPush Wake-Ups', 'An alternative to running in the background: a <b>UnifiedPush distributor</b> app you already use (such as ntfy) wakes Vector when your <b>push gateway</b> sees a message for you, and Vector then fetches it from your relay itself.<br><br>No message content passes through the gateway, but it does learn your public key and when you receive messages, so only use a gateway you trust or run yourself.', true);
+        };
+    }
     domSettingsNotifMuteInfo.onclick = (e) => {
         e.preventDefault();
         e.stopPropagation();
@@ -12297,10 +13298,15 @@ domChatMessageInput.oninput = async (e) => {
         e.stopPropagation();
         popupConfirm('Mute @everyone Pings', 'When enabled, <b>@everyone</b> mentions from group admins will <b>not bypass</b> your group mute setting.<br><br>By default, @everyone pings from admins will notify you even if the group is muted.', true);
     };
+    domSettingsNotifAnnouncementsInfo.onclick = (e) => {
+        e.preventDefault();
+        e.stopPropagation();
+        popupConfirm('Vector Announcements', 'When enabled, <b>official announcements signed by the Vector team</b> (including new-version notices on platforms that can\'t update themselves) arrive in a dedicated chat.<br><br>Only notices carrying the project\'s signature are shown. Mute the chat to keep them silent, or disable this to stop receiving them.', true);
+    };
     if (domSettingsNotifPrivacyInfo) domSettingsNotifPrivacyInfo.onclick = (e) => {
         e.preventDefault();
         e.stopPropagation();
-        popupConfirm('Notification Content Privacy', 'Controls how much of a message shows in OS notifications (lock screen, banners).<br><br><b>Show sender and message</b>: full preview.<br><b>Hide message</b>: shows who messaged you, not what.<br><b>Hide sender and message</b>: a generic "You received a message", revealing nothing.', true);
+        popupConfirm('Notification Content Privacy', 'Controls how much of a message shows in OS notifications (lock screen, banners).<br><br><b>Show sender and message</b>: full preview.<br><b>Hide message</b>: shows who messaged you, not what.<br><b>Hide sender and message</b>: a generic "You received a message", revealing nothing.<br><b>Message count only</b>: a single running "N new messages" notification.', true);
     };
     if (domSettingsStorageGalleryInfo) domSettingsStorageGalleryInfo.onclick = (e) => {
         e.preventDefault();
@@ -12314,6 +13320,30 @@ domChatMessageInput.oninput = async (e) => {
         popupConfirm('Export Account', 'Export Account will display a backup of your encryption keys. Keep it safe to restore your account later.', true);
     };
 
+    domSettingsExportBundleInfo.onclick = (e) => {
+        e.preventDefault();
+        e.stopPropagation();
+        popupConfirm('Move to Another Device', 'Saves this whole account — keys, messages, settings and group chats — as one file locked with a password you choose. On the new device, pick <b>Restore from an Account Bundle</b> on the login screen.<br><br>Downloaded files aren\'t included: copy your Vector downloads folder across too, and they\'ll be picked back up.', true);
+    };
+
+    domSettingsKeyAuditInfo.onclick = (e) => {
+        e.preventDefault();
+        e.stopPropagation();
+        popupConfirm('Key Activity', 'A log of everything on this device that used your private key — signing, encrypting, decrypting, exporting — and which part of Vector did it.<br><br>Repeated uses are grouped, so a sync that opens hundreds of messages shows as one line with a count.', true);
+    };
+
+    domSettingsActivityReportInfo.onclick = (e) => {
+        e.preventDefault();
+        e.stopPropagation();
+        popupConfirm('Activity Report', 'Saves a record of your activity as a spreadsheet (CSV) or JSON file: how many messages you sent and received in each chat per day, when you logged in, this device, and your relays.<br><br>No message content is included, so you can share it — for example to document harassment — without handing over your conversations.', true);
+    };
+
+    domSettingsSocialBackupInfo.onclick = (e) => {
+        e.preventDefault();
+        e.stopPropagation();
+        popupConfirm('Social Backup', 'Splits your seed phrase into pieces ("shares") sent privately to contacts you trust ("guardians"). Any chosen number of them together can restore your account if you lose your device; fewer learn nothing.<br><br><b>Warning:</b> guardians who work together can take over your account. Choose people who don\'t know each other, and set up a new backup if you stop trusting one.', true);
+    };
+
     if (domSettingsChangePinInfo) {
         domSettingsChangePinInfo.onclick = (e) => {
             e.preventDefault();
@@ -12710,16 +13740,42 @@ let windowFocused = true;
 let documentVisible = typeof document !== 'undefined' ? !document.hidden : true;
 function isWindowActive() { return windowFocused && documentVisible; }
 
+/** Blur the whole UI while Vector isn't the active window (the `blur_on_background` setting), so
+ *  chats don't show over a shoulder, in screen shares or in the app switcher. Android blocks
+ *  capture outright with screen security (FLAG_SECURE) instead. */
+let fBlurOnBackground = false;
+function applyPrivacyBlur() {
+    document.body.classList.toggle('privacy-blur', fBlurOnBackground && (!isWindowActive() || document.hidden));
+}
+
+/** Seconds a chat must stay in view before it's marked read (the `read_dwell_secs` setting).
+ *  0 = instantly, by the frontend's own open/arrival/refocus marks; otherwise the backend's read
+ *  tracker owns it, fed by the view heartbeat below. */
+let nReadDwellSecs = 0;
+function readsInstantly() { return !nReadDwellSecs; }
+
 /** Tell the backend which chat the user is actively watching, so inbound
  *  messages in that chat auto-mark as read on arrival. Bumps badge counts
  *  in lock-step with our FE markAsRead — without this the on_dm_received
  *  task can race ahead and tick the dock badge before markAsRead lands. */
 let _lastReportedActiveChat = '__init__';
+let _chatViewHeartbeat = null;
 function syncBackendActiveChat() {
     const id = (strOpenChat && chatPinnedToBottom && isWindowActive()) ? strOpenChat : null;
     if (id === _lastReportedActiveChat) return;
     _lastReportedActiveChat = id;
     invoke('set_active_chat', { chatId: id }).catch(() => { /* best-effort */ });
+
+    // Dwell-based reads: report the chat in view now and every second while it stays in view
+    clearInterval(_chatViewHeartbeat);
+    _chatViewHeartbeat = null;
+    if (readsInstantly()) return;
+    invoke('report_chat_view', { chatId: id }).catch(() => {});
+    if (id) {
+        _chatViewHeartbeat = setInterval(() => {
+            invoke('report_chat_view', { chatId: id }).catch(() => {});
+        }, 1000);
+    }
 }
 
 const PIN_THRESHOLD_PX = 80;